@@ -0,0 +1,182 @@
+//! Tool exporting sealed L1 batches into portable archives and importing them back, used for disaster recovery
+//! of the main node database. See [`zksync_core::batch_archive`] for the description of the archive format
+//! and the recovery procedure.
+//!
+//! Export only reads from Postgres and the object store with L1 batch proofs, so it can be run against a replica.
+//! Import writes to the master database and must be run against a database with the genesis state.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use zksync_config::{
+    configs::ObservabilityConfig, ContractsConfig, ObjectStoreConfig, PostgresConfig,
+};
+use zksync_core::batch_archive::L1BatchArchive;
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::L1BatchNumber;
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "L1 batch archive tool", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exports sealed L1 batches into archives, one JSON file per batch.
+    Export {
+        /// First L1 batch to export.
+        #[arg(long)]
+        from_l1_batch: u32,
+        /// Last L1 batch (inclusive) to export. If not specified, exports all L1 batches up to the last sealed one.
+        #[arg(long)]
+        to_l1_batch: Option<u32>,
+        /// Directory to write archives to. Archives are named `l1_batch_{number}.json`.
+        #[arg(long)]
+        output_dir: PathBuf,
+        /// Supplement archives with L1 batch proofs from the object store.
+        #[arg(long)]
+        with_proofs: bool,
+    },
+    /// Imports archives into the database. Archives are sorted by the L1 batch number in their file names
+    /// and are read and imported one at a time.
+    Import {
+        /// Paths to archive files named `l1_batch_{number}.json`.
+        #[arg(required = true)]
+        archives: Vec<PathBuf>,
+        /// Store L1 batch proofs contained in archives to the object store.
+        #[arg(long)]
+        with_proofs: bool,
+    },
+}
+
+fn archive_path(output_dir: &Path, number: L1BatchNumber) -> PathBuf {
+    output_dir.join(format!("l1_batch_{}.json", number.0))
+}
+
+/// Parses the L1 batch number from the archive file name, as written by [`archive_path()`].
+fn archive_number(path: &Path) -> anyhow::Result<L1BatchNumber> {
+    let number = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix("l1_batch_")?.strip_suffix(".json"))
+        .and_then(|number| number.parse().ok())
+        .with_context(|| format!("archive {path:?} is not named `l1_batch_{{number}}.json`"))?;
+    Ok(L1BatchNumber(number))
+}
+
+fn read_archive(path: &Path) -> anyhow::Result<L1BatchArchive> {
+    let archive = std::fs::read(path).with_context(|| format!("failed reading {path:?}"))?;
+    serde_json::from_slice(&archive).with_context(|| format!("failed parsing archive {path:?}"))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let object_store_config =
+        ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+    let blob_store = ObjectStoreFactory::new(object_store_config)
+        .create_store()
+        .await;
+
+    match cli.command {
+        Command::Export {
+            from_l1_batch,
+            to_l1_batch,
+            output_dir,
+            with_proofs,
+        } => {
+            let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            let mut storage = pool.access_storage().await?;
+            let to_l1_batch = match to_l1_batch {
+                Some(number) => L1BatchNumber(number),
+                None => storage
+                    .blocks_dal()
+                    .get_sealed_l1_batch_number()
+                    .await?
+                    .context("storage contains no L1 batches")?,
+            };
+            std::fs::create_dir_all(&output_dir)
+                .with_context(|| format!("failed creating output directory {output_dir:?}"))?;
+
+            for number in from_l1_batch..=to_l1_batch.0 {
+                let number = L1BatchNumber(number);
+                let mut archive = L1BatchArchive::export(&mut storage, number)
+                    .await?
+                    .with_context(|| format!("L1 batch #{number} is not sealed"))?;
+                if with_proofs {
+                    archive.load_proof(&*blob_store).await?;
+                    if archive.proof.is_none() {
+                        tracing::warn!("No proof for L1 batch #{number} in the object store");
+                    }
+                }
+                let path = archive_path(&output_dir, number);
+                let archive = serde_json::to_vec(&archive)?;
+                std::fs::write(&path, archive)
+                    .with_context(|| format!("failed writing archive to {path:?}"))?;
+                tracing::info!("Exported L1 batch #{number} to {path:?}");
+            }
+        }
+
+        Command::Import {
+            archives,
+            with_proofs,
+        } => {
+            let contracts_config =
+                ContractsConfig::from_env().context("ContractsConfig::from_env()")?;
+            let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            let mut storage = pool.access_storage().await?;
+
+            // Archives may be large, so only a single archive is held in memory at a time.
+            let mut archives = archives
+                .into_iter()
+                .map(|path| Ok((archive_number(&path)?, path)))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            archives.sort_unstable_by_key(|(number, _)| *number);
+            for (number, path) in archives {
+                let archive = read_archive(&path)?;
+                anyhow::ensure!(
+                    archive.number() == number,
+                    "archive {path:?} contains L1 batch #{}, expected #{number}",
+                    archive.number()
+                );
+                archive
+                    .import(&mut storage, contracts_config.l2_erc20_bridge_addr)
+                    .await
+                    .with_context(|| format!("failed importing archive {path:?}"))?;
+                if with_proofs {
+                    archive.store_proof(&*blob_store).await?;
+                }
+                tracing::info!("Imported L1 batch #{number} from {path:?}");
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,15 +1,19 @@
 use anyhow::Context as _;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use tokio::io::{self, AsyncReadExt};
 use zksync_config::{
     configs::ObservabilityConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig,
-    PostgresConfig,
+    ObjectStoreConfig, PostgresConfig,
 };
-use zksync_core::block_reverter::{
-    BlockReverter, BlockReverterEthConfig, BlockReverterFlags, L1ExecutedBatchesRevert,
+use zksync_core::{
+    basic_witness_input_producer::WitnessInputCache,
+    block_reverter::{
+        BlockReverter, BlockReverterEthConfig, BlockReverterFlags, L1ExecutedBatchesRevert,
+    },
 };
 use zksync_dal::ConnectionPool;
 use zksync_env_config::FromEnv;
+use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{L1BatchNumber, U256};
 
 #[derive(Debug, Parser)]
@@ -34,6 +38,8 @@ enum Command {
         /// L1 batch number used to rollback to.
         #[arg(long)]
         l1_batch_number: u32,
+        #[command(flatten)]
+        confirmation: ConfirmationArgs,
         /// Priority fee used for rollback Ethereum transaction.
         // We operate only by priority fee because we want to use base fee from Ethereum
         // and send transaction as soon as possible without any resend logic
@@ -62,6 +68,8 @@ enum Command {
         /// Flag that allows to revert already executed blocks, it's ultra dangerous and required only for fixing external nodes
         #[arg(long)]
         allow_executed_block_reversion: bool,
+        #[command(flatten)]
+        confirmation: ConfirmationArgs,
     },
 
     /// Clears failed L1 transactions.
@@ -69,6 +77,56 @@ enum Command {
     ClearFailedL1Transactions,
 }
 
+/// Two-step confirmation of a revert. The revert must first be run with `--dry-run`, which outputs a report
+/// with affected data and safety checks without modifying anything. The report contains a confirmation token,
+/// which must be supplied with `--confirm` to actually perform the revert with the same params.
+#[derive(Debug, Args)]
+struct ConfirmationArgs {
+    /// Outputs the revert report without modifying any data.
+    #[arg(long, conflicts_with = "confirm")]
+    dry_run: bool,
+    /// Confirmation token from the dry run report.
+    #[arg(long, required_unless_present = "dry_run")]
+    confirm: Option<String>,
+}
+
+impl ConfirmationArgs {
+    /// Returns `Ok(false)` if the revert should not be performed (i.e., on dry run).
+    async fn check(
+        &self,
+        block_reverter: &BlockReverter,
+        last_l1_batch_to_keep: L1BatchNumber,
+        flags: BlockReverterFlags,
+    ) -> anyhow::Result<bool> {
+        if self.dry_run {
+            let report = block_reverter
+                .plan_rollback(last_l1_batch_to_keep, flags)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if report.failed_checks.is_empty() {
+                println!(
+                    "Review the report above and re-run the command with `--confirm {}` to perform the revert",
+                    report.confirmation_token
+                );
+            } else {
+                println!("Revert cannot be performed because of failed safety checks:");
+                for failed_check in &report.failed_checks {
+                    println!("- {failed_check}");
+                }
+            }
+            return Ok(false);
+        }
+
+        let token = self.confirm.as_deref().context(
+            "confirmation token is not provided; run the command with `--dry-run` to get it",
+        )?;
+        block_reverter
+            .confirm_rollback(last_l1_batch_to_keep, flags, token)
+            .await?;
+        Ok(true)
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let observability_config =
@@ -110,6 +168,9 @@ async fn main() -> anyhow::Result<()> {
         connection_pool,
         L1ExecutedBatchesRevert::Disallowed,
     );
+    if let Some(api_state_cache_path) = db_config.api_state_cache_path {
+        block_reverter.set_api_state_cache_path(api_state_cache_path);
+    }
 
     match Cli::parse().command {
         Command::Display { json } => {
@@ -122,17 +183,23 @@ async fn main() -> anyhow::Result<()> {
         }
         Command::SendEthTransaction {
             l1_batch_number,
+            confirmation,
             priority_fee_per_gas,
             nonce,
         } => {
+            let l1_batch_number = L1BatchNumber(l1_batch_number);
+            let flags = BlockReverterFlags::empty();
+            if !confirmation
+                .check(&block_reverter, l1_batch_number, flags)
+                .await?
+            {
+                return Ok(());
+            }
+
             let priority_fee_per_gas =
                 priority_fee_per_gas.map_or(default_priority_fee_per_gas, U256::from);
             block_reverter
-                .send_ethereum_revert_transaction(
-                    L1BatchNumber(l1_batch_number),
-                    priority_fee_per_gas,
-                    nonce,
-                )
+                .send_ethereum_revert_transaction(l1_batch_number, priority_fee_per_gas, nonce)
                 .await
         }
         Command::RollbackDB {
@@ -141,8 +208,9 @@ async fn main() -> anyhow::Result<()> {
             rollback_tree,
             rollback_sk_cache,
             allow_executed_block_reversion,
+            confirmation,
         } => {
-            if !rollback_tree && rollback_postgres {
+            if !rollback_tree && rollback_postgres && !confirmation.dry_run {
                 println!("You want to rollback Postgres DB without rolling back tree.");
                 println!(
                     "If tree is not yet rolled back to this block then the only way \
@@ -158,14 +226,16 @@ async fn main() -> anyhow::Result<()> {
             }
 
             if allow_executed_block_reversion {
-                println!("You want to revert already executed blocks. It's impossible to restore them for the main node");
-                println!("Make sure you are doing it ONLY for external node");
-                println!("Are you sure? Print y/n");
+                if !confirmation.dry_run {
+                    println!("You want to revert already executed blocks. It's impossible to restore them for the main node");
+                    println!("Make sure you are doing it ONLY for external node");
+                    println!("Are you sure? Print y/n");
 
-                let mut input = [0u8];
-                io::stdin().read_exact(&mut input).await.unwrap();
-                if input[0] != b'y' && input[0] != b'Y' {
-                    std::process::exit(0);
+                    let mut input = [0u8];
+                    io::stdin().read_exact(&mut input).await.unwrap();
+                    if input[0] != b'y' && input[0] != b'Y' {
+                        std::process::exit(0);
+                    }
                 }
                 block_reverter.change_rollback_executed_l1_batches_allowance(
                     L1ExecutedBatchesRevert::Allowed,
@@ -175,6 +245,20 @@ async fn main() -> anyhow::Result<()> {
             let mut flags = BlockReverterFlags::empty();
             if rollback_postgres {
                 flags |= BlockReverterFlags::POSTGRES;
+                match ObjectStoreConfig::from_env() {
+                    Ok(object_store_config) => {
+                        let object_store = ObjectStoreFactory::new(object_store_config)
+                            .create_store()
+                            .await;
+                        block_reverter
+                            .set_witness_input_cache(WitnessInputCache::new(object_store));
+                    }
+                    Err(err) => {
+                        println!(
+                            "Object store is not configured ({err:#}); cached witness inputs will not be invalidated"
+                        );
+                    }
+                }
             }
             if rollback_tree {
                 flags |= BlockReverterFlags::TREE;
@@ -182,9 +266,14 @@ async fn main() -> anyhow::Result<()> {
             if rollback_sk_cache {
                 flags |= BlockReverterFlags::SK_CACHE;
             }
-            block_reverter
-                .rollback_db(L1BatchNumber(l1_batch_number), flags)
-                .await
+            let l1_batch_number = L1BatchNumber(l1_batch_number);
+            if !confirmation
+                .check(&block_reverter, l1_batch_number, flags)
+                .await?
+            {
+                return Ok(());
+            }
+            block_reverter.rollback_db(l1_batch_number, flags).await
         }
         Command::ClearFailedL1Transactions => block_reverter.clear_failed_l1_transactions().await,
     }
@@ -0,0 +1,135 @@
+//! Chain data exporter. Exports blocks, transactions, receipts, events and token transfers to CSV or Parquet files
+//! in an object store for consumption by analytics tooling. Intended to run on a schedule, with each run exporting
+//! all newly sealed miniblocks. Alternatively, the exporter can run as a long-living service
+//! (see `ChainExportConfig::export_interval_sec`).
+//!
+//! The exporter only reads from Postgres (preferably from a read replica), so it can be run against
+//! the main node or an external node database. It is assumed that the exporter is run as a singleton process.
+
+use anyhow::Context as _;
+use clap::Parser;
+use prometheus_exporter::PrometheusExporterConfig;
+use tokio::{sync::watch, task::JoinHandle};
+use zksync_chain_export::ChainExporter;
+use zksync_config::{
+    configs::{ObservabilityConfig, PrometheusConfig},
+    ChainExportConfig, PostgresConfig,
+};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::{object_store::ChainExportObjectStoreConfig, FromEnv};
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::MiniblockNumber;
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Chain data exporter", long_about = None)]
+struct Cli {
+    /// First miniblock to export in the backfill mode. In this mode, the exporter exports the specified range
+    /// of miniblocks once, overwriting existing files and not updating the persisted export progress.
+    #[arg(long, requires = "to_miniblock")]
+    from_miniblock: Option<u32>,
+    /// Last miniblock (inclusive) to export in the backfill mode.
+    #[arg(long, requires = "from_miniblock")]
+    to_miniblock: Option<u32>,
+}
+
+async fn maybe_enable_prometheus_metrics(
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<Option<JoinHandle<anyhow::Result<()>>>> {
+    let prometheus_config = PrometheusConfig::from_env().ok();
+    if let Some(prometheus_config) = prometheus_config {
+        let exporter_config = PrometheusExporterConfig::push(
+            prometheus_config.gateway_endpoint(),
+            prometheus_config.push_interval(),
+        );
+
+        tracing::info!("Starting prometheus exporter with config {prometheus_config:?}");
+        let prometheus_exporter_task = tokio::spawn(exporter_config.run(stop_receiver));
+        Ok(Some(prometheus_exporter_task))
+    } else {
+        tracing::info!("Starting without prometheus exporter");
+        Ok(None)
+    }
+}
+
+async fn backfill(exporter: &ChainExporter, from: u32, to: u32, chunk: u32) -> anyhow::Result<()> {
+    anyhow::ensure!(from <= to, "invalid miniblock range: {from}..={to}");
+    let mut start = from;
+    loop {
+        let end = start.saturating_add(chunk - 1).min(to);
+        exporter
+            .export_range(MiniblockNumber(start)..=MiniblockNumber(end))
+            .await?;
+        if end == to {
+            return Ok(());
+        }
+        start = end + 1;
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+
+    let prometheus_exporter_task = maybe_enable_prometheus_metrics(stop_receiver).await?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+    tracing::info!("Starting chain exporter");
+
+    let object_store_config = ChainExportObjectStoreConfig::from_env()
+        .context("ChainExportObjectStoreConfig::from_env()")?;
+    let blob_store = ObjectStoreFactory::new(object_store_config.0)
+        .create_store()
+        .await;
+
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig")?;
+    let export_config = ChainExportConfig::from_env().context("ChainExportConfig::from_env()")?;
+    let replica_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+        .build()
+        .await?;
+
+    let export_interval = export_config.export_interval();
+    let miniblocks_per_file = export_config.miniblocks_per_file.max(1);
+    let exporter = ChainExporter::new(replica_pool, blob_store, export_config);
+    if let (Some(from), Some(to)) = (cli.from_miniblock, cli.to_miniblock) {
+        backfill(&exporter, from, to, miniblocks_per_file).await?;
+    } else if let Some(export_interval) = export_interval {
+        loop {
+            if let Err(err) = exporter.export_new_miniblocks().await {
+                tracing::error!("Failed exporting chain data: {err:#}");
+            }
+            tracing::info!("Next export attempt in {export_interval:?}");
+            tokio::select! {
+                () = tokio::time::sleep(export_interval) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("Stop signal received, chain exporter is shutting down");
+                    break;
+                }
+            }
+        }
+    } else {
+        exporter.export_new_miniblocks().await?;
+    }
+
+    tracing::info!("Finished running chain exporter!");
+    stop_sender.send(true).ok();
+    if let Some(prometheus_exporter_task) = prometheus_exporter_task {
+        prometheus_exporter_task
+            .await?
+            .context("Prometheus did not finish gracefully")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+//! Exporter of artifacts required to prove and execute committed L1 batches in the settlement layer emergency mode
+//! (i.e., after the operator has become inactive). The artifacts contain calldata of prove and execute calls
+//! for the settlement layer contract, so that they can be sent by a third party without running the node,
+//! e.g. from a multisig.
+//!
+//! The exporter only reads from Postgres and the object store with L1 batch proofs, so it can be run against
+//! the database of any node having metadata for the exported L1 batches.
+
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use clap::Parser;
+use zksync_config::{
+    configs::{chain::NetworkConfig, ObservabilityConfig},
+    ObjectStoreConfig, PostgresConfig,
+};
+use zksync_core::eth_sender::EmergencyArtifacts;
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::L1BatchNumber;
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Emergency mode artifacts exporter", long_about = None)]
+struct Cli {
+    /// First L1 batch to prove and execute. Must be the first L1 batch not proven on the settlement layer.
+    #[arg(long)]
+    from_l1_batch: u32,
+    /// Last L1 batch (inclusive) to prove and execute. Must be committed on the settlement layer.
+    #[arg(long)]
+    to_l1_batch: u32,
+    /// Prove L1 batches with an empty proof, which is only accepted by the testnet verifier.
+    #[arg(long)]
+    mock_proofs: bool,
+    /// Path to the output JSON file. If not specified, artifacts are printed to stdout.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let object_store_config =
+        ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+    let blob_store = ObjectStoreFactory::new(object_store_config)
+        .create_store()
+        .await;
+    let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let mut storage = pool.access_storage().await?;
+
+    let l1_batches = L1BatchNumber(cli.from_l1_batch)..=L1BatchNumber(cli.to_l1_batch);
+    tracing::info!("Exporting emergency mode artifacts for L1 batches {l1_batches:?}");
+    let artifacts = EmergencyArtifacts::load(
+        &mut storage,
+        &*blob_store,
+        l1_batches,
+        network_config.zksync_network_id,
+        !cli.mock_proofs,
+    )
+    .await?;
+    let artifacts = serde_json::to_string_pretty(&artifacts)?;
+    if let Some(output) = &cli.output {
+        std::fs::write(output, artifacts)
+            .with_context(|| format!("failed writing artifacts to {output:?}"))?;
+        tracing::info!("Saved emergency mode artifacts to {output:?}");
+    } else {
+        println!("{artifacts}");
+    }
+    Ok(())
+}
@@ -1,18 +1,27 @@
-use std::{env, time::Duration};
+use std::{
+    env,
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    time::Duration,
+};
 
 use anyhow::Context;
 use serde::Deserialize;
 use url::Url;
-use zksync_basic_types::{Address, L1ChainId, L2ChainId};
-use zksync_config::ObjectStoreConfig;
+use zksync_basic_types::{Address, L1ChainId, L2ChainId, H256};
+use zksync_config::{
+    configs::database::{RocksdbCompactionStyle, RocksdbProfile},
+    CdcPublisherConfig, ObjectStoreConfig,
+};
 use zksync_consensus_roles::node;
 use zksync_core::{
     api_server::{
-        tx_sender::TxSenderConfig,
+        tx_sender::{TxLimits, TxSenderConfig},
         web3::{state::InternalApiConfig, Namespace},
     },
     consensus,
+    sync_layer::QuorumPolicy,
 };
+use zksync_dal::connection::{WorkloadClass, WorkloadQuotas};
 use zksync_types::api::BridgeAddresses;
 use zksync_web3_decl::{
     error::ClientRpcContext,
@@ -31,10 +40,14 @@ const BYTES_IN_MEGABYTE: usize = 1_024 * 1_024;
 pub struct RemoteENConfig {
     pub bridgehub_proxy_addr: Option<Address>,
     pub diamond_proxy_addr: Address,
+    /// Address of the validator timelock contract that the main node sends commit transactions to.
+    pub validator_timelock_addr: Option<Address>,
     pub l1_erc20_bridge_proxy_addr: Address,
     pub l2_erc20_bridge_addr: Address,
     pub l1_weth_bridge_proxy_addr: Option<Address>,
     pub l2_weth_bridge_addr: Option<Address>,
+    pub l1_shared_bridge_proxy_addr: Option<Address>,
+    pub l2_shared_bridge_addr: Option<Address>,
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub l2_chain_id: L2ChainId,
     pub l1_chain_id: L1ChainId,
@@ -56,6 +69,12 @@ impl RemoteENConfig {
             .get_main_contract()
             .rpc_context("get_main_contract")
             .await?;
+        // In case EN is connected to the old server version without `get_validator_timelock_contract` method.
+        let validator_timelock_addr = client
+            .get_validator_timelock_contract()
+            .await
+            .ok()
+            .flatten();
         let l2_chain_id = client.chain_id().rpc_context("chain_id").await?;
         let l2_chain_id = L2ChainId::try_from(l2_chain_id.as_u64())
             .map_err(|err| anyhow::anyhow!("invalid chain ID supplied by main node: {err}"))?;
@@ -65,11 +84,14 @@ impl RemoteENConfig {
         Ok(Self {
             bridgehub_proxy_addr,
             diamond_proxy_addr,
+            validator_timelock_addr,
             l2_testnet_paymaster_addr,
             l1_erc20_bridge_proxy_addr: bridges.l1_erc20_default_bridge,
             l2_erc20_bridge_addr: bridges.l2_erc20_default_bridge,
             l1_weth_bridge_proxy_addr: bridges.l1_weth_bridge,
             l2_weth_bridge_addr: bridges.l2_weth_bridge,
+            l1_shared_bridge_proxy_addr: bridges.l1_shared_default_bridge,
+            l2_shared_bridge_addr: bridges.l2_shared_default_bridge,
             l2_chain_id,
             l1_chain_id,
         })
@@ -82,6 +104,36 @@ pub enum BlockFetcher {
     Consensus,
 }
 
+/// Mode of the external node determining how much historical data it retains and serves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeMode {
+    /// Retains all historical data. Pruning cannot be enabled in this mode.
+    Archive,
+    /// Prunes Postgres data and Merkle tree versions according to the configured retention.
+    Full,
+    /// Like [`Self::Full`], but with minimal retention defaults. Historical call traces are not stored,
+    /// and the `debug` namespace is not served.
+    Minimal,
+    /// Read-only API replica. The node doesn't execute transactions; instead, it persists miniblocks
+    /// together with their execution outputs fetched from the main node. The state keeper, the Merkle tree and
+    /// the reorg detector are not run, and call traces are not stored, so the `debug` namespace is not served.
+    /// API caches are larger by default, and the sandbox warm pool is enabled by default.
+    ReadReplica,
+}
+
+impl NodeMode {
+    /// Checks whether call traces are stored (and thus the `debug` namespace can be served) in this mode.
+    fn serves_call_traces(self) -> bool {
+        matches!(self, Self::Archive | Self::Full)
+    }
+
+    /// Checks whether the node executes transactions locally.
+    pub fn executes_transactions(self) -> bool {
+        !matches!(self, Self::ReadReplica)
+    }
+}
+
 /// This part of the external node config is completely optional to provide.
 /// It can tweak limits of the API, delay intervals of certain components, etc.
 /// If any of the fields are not provided, the default values will be used.
@@ -114,6 +166,9 @@ pub struct OptionalENConfig {
     /// Maximum response body size in MiBs. Default is 10 MiB.
     #[serde(default = "OptionalENConfig::default_max_response_body_size_mb")]
     pub max_response_body_size_mb: usize,
+    /// Compute budget for a single `idexo_multicallRead` request in milliseconds. Default is 1,000 ms.
+    #[serde(default = "OptionalENConfig::default_multicall_compute_budget_ms")]
+    pub multicall_compute_budget_ms: u64,
 
     // Other API config settings
     /// Interval between polling DB for pubsub (in ms).
@@ -122,6 +177,14 @@ pub struct OptionalENConfig {
         default = "OptionalENConfig::default_polling_interval"
     )]
     polling_interval: u64,
+    /// Number of queued notification batches for a WebSocket subscription after which the subscriber
+    /// is warned that it lags behind. Default is 128.
+    #[serde(default = "OptionalENConfig::default_pubsub_lag_warning_threshold")]
+    pub pubsub_lag_warning_threshold: usize,
+    /// Number of queued notification batches for a WebSocket subscription after which the subscription
+    /// is terminated. Must not exceed 1,024. Default is 512.
+    #[serde(default = "OptionalENConfig::default_pubsub_lag_close_threshold")]
+    pub pubsub_lag_close_threshold: usize,
     /// Tx nonce: how far ahead from the committed nonce can it be.
     #[serde(default = "OptionalENConfig::default_max_nonce_ahead")]
     pub max_nonce_ahead: u32,
@@ -129,16 +192,14 @@ pub struct OptionalENConfig {
     /// This option can be tweaked down if the API server is running out of memory.
     #[serde(default = "OptionalENConfig::default_vm_concurrency_limit")]
     pub vm_concurrency_limit: usize,
-    /// Smart contract bytecode cache size for the API server. Default value is 128 MiB.
-    #[serde(default = "OptionalENConfig::default_factory_deps_cache_size_mb")]
-    factory_deps_cache_size_mb: usize,
+    /// Smart contract bytecode cache size for the API server. Default value is 128 MiB (512 MiB for read replicas).
+    factory_deps_cache_size_mb: Option<usize>,
     /// Initial writes cache size for the API server. Default value is 32 MiB.
     #[serde(default = "OptionalENConfig::default_initial_writes_cache_size_mb")]
     initial_writes_cache_size_mb: usize,
-    /// Latest values cache size in MiBs. The default value is 128 MiB. If set to 0, the latest
-    /// values cache will be disabled.
-    #[serde(default = "OptionalENConfig::default_latest_values_cache_size_mb")]
-    latest_values_cache_size_mb: usize,
+    /// Latest values cache size in MiBs. The default value is 128 MiB (1 GiB for read replicas). If set to 0,
+    /// the latest values cache will be disabled.
+    latest_values_cache_size_mb: Option<usize>,
     /// Enabled JSON RPC API namespaces.
     api_namespaces: Option<Vec<Namespace>>,
 
@@ -164,6 +225,13 @@ pub struct OptionalENConfig {
     pub gas_price_scale_factor: f64,
 
     // Merkle tree config
+    /// Whether to run the Merkle tree. If disabled, the node keeps no RocksDB tree, and L1 batch root hashes are taken
+    /// from the main node; this requires `sequencer_address`, so that the hashes are authenticated. Storage proofs
+    /// (`zks_getProof`) and the tree lag (`zks_getTreeLag`) are not served, and components requiring tree data
+    /// (the commitment generator, consistency checker, and DA / proof verifiers) are not run. Always disabled
+    /// for read replicas.
+    #[serde(default = "OptionalENConfig::default_merkle_tree_enabled")]
+    merkle_tree_enabled: bool,
     #[serde(default = "OptionalENConfig::default_metadata_calculator_delay")]
     metadata_calculator_delay: u64,
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
@@ -187,12 +255,79 @@ pub struct OptionalENConfig {
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     #[serde(default = "OptionalENConfig::default_merkle_tree_stalled_writes_timeout_sec")]
     merkle_tree_stalled_writes_timeout_sec: u64,
+    /// Number of threads in a dedicated thread pool used to hash Merkle tree levels in parallel. 0 means
+    /// the number of logical CPUs. If not specified, the global thread pool is used.
+    pub merkle_tree_thread_pool_size: Option<usize>,
+    /// Interval between Merkle tree integrity checks re-hashing random subtrees and the latest tree root.
+    /// If not specified, integrity checks are disabled.
+    merkle_tree_integrity_check_interval_sec: Option<u64>,
+    /// Number of random subtrees checked on each Merkle tree integrity check.
+    #[serde(default = "OptionalENConfig::default_merkle_tree_integrity_check_subtree_count")]
+    pub merkle_tree_integrity_check_subtree_count: usize,
+    /// Whether to rebuild Merkle tree versions affected by detected corruption from Postgres data.
+    #[serde(default)]
+    pub merkle_tree_integrity_auto_repair: bool,
+    /// Enables pruning of old Merkle tree versions. If enabled, Merkle proofs (e.g., ones returned by `zks_getProof`)
+    /// can only be served for the retained L1 batches. Implied by the full and minimal node modes.
+    #[serde(default)]
+    merkle_tree_pruning_enabled: bool,
+    /// Number of the latest Merkle tree versions (= L1 batches) retained by pruning. The tree cannot be rolled back
+    /// beyond the retained versions, so this value should exceed the maximum expected reorg depth.
+    /// The default value depends on the node mode.
+    merkle_tree_retained_versions: Option<NonZeroU64>,
+    /// Minimum interval between Merkle tree RocksDB compactions triggered after pruning. 0 disables such compactions,
+    /// so that the space occupied by pruned data is only reclaimed by background RocksDB compactions.
+    #[serde(default = "OptionalENConfig::default_merkle_tree_compaction_interval_sec")]
+    merkle_tree_compaction_interval_sec: u64,
+    /// Enables the async tree mode: the state keeper doesn't wait for the Merkle tree to process the previous L1 batch
+    /// before executing the next one, using the L1 batch root hash from the main node instead, as long as the tree lags
+    /// behind by at most this number of L1 batches. Storage proofs and other tree-derived data are only served
+    /// for L1 batches processed by the local tree; see the `merkleized` block tag and `zks_getTreeLag`.
+    /// If not specified, the state keeper always waits for the tree. Requires `sequencer_address`, so that
+    /// root hashes returned by the main node are authenticated.
+    pub merkle_tree_async_max_lag: Option<u32>,
+    /// Compaction style for the Merkle tree RocksDB: `level` (default) or `universal`. Universal compaction
+    /// has lower write amplification, but can temporarily require up to 2x disk space.
+    #[serde(default)]
+    merkle_tree_compaction_style: RocksdbCompactionStyle,
+    /// Maximum total size of write-ahead log files for the Merkle tree RocksDB in MiB. If not specified,
+    /// the limit is chosen by RocksDB.
+    merkle_tree_max_total_wal_size_mb: Option<u64>,
+    /// If specified, write-ahead log files for the Merkle tree RocksDB are incrementally synced to disk
+    /// every time this number of MiB is written.
+    merkle_tree_wal_bytes_per_sync_mb: Option<u64>,
+
+    // State keeper cache tuning
+    /// Capacity of the block cache for the state keeper RocksDB in MiB. If not specified, the default RocksDB
+    /// options are used.
+    state_keeper_db_block_cache_size_mb: Option<usize>,
+    /// Compaction style for the state keeper RocksDB: `level` (default) or `universal`.
+    #[serde(default)]
+    state_keeper_db_compaction_style: RocksdbCompactionStyle,
+    /// Maximum total size of write-ahead log files for the state keeper RocksDB in MiB.
+    state_keeper_db_max_total_wal_size_mb: Option<u64>,
+    /// If specified, write-ahead log files for the state keeper RocksDB are incrementally synced to disk
+    /// every time this number of MiB is written.
+    state_keeper_db_wal_bytes_per_sync_mb: Option<u64>,
+    /// Path to the RocksDB replica of the state keeper cache used by the API server to serve storage reads
+    /// for VM invocations (`eth_call`, `eth_estimateGas` etc.) at the latest block. If not specified,
+    /// such reads are served from Postgres.
+    pub api_state_cache_path: Option<String>,
 
     // Postgres config (new parameters)
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
     database_long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     database_slow_query_threshold_ms: Option<u64>,
+    /// Maximum number of DB connections concurrently used by the API server. If not set, the API server
+    /// can use all connections in the pool.
+    database_api_max_connections: Option<u32>,
+    /// Maximum number of DB connections concurrently used by the state keeper. If not set, the state keeper
+    /// can use all connections in the pool.
+    database_state_keeper_max_connections: Option<u32>,
+    /// Maximum number of DB connections concurrently used by background tasks (e.g., syncing and pruning).
+    /// If not set, background tasks can use all connections in the pool.
+    database_background_max_connections: Option<u32>,
 
     // Other config settings
     /// Port on which the Prometheus exporter server is listening.
@@ -205,6 +340,156 @@ pub struct OptionalENConfig {
     /// 0 means that sealing is synchronous; this is mostly useful for performance comparison, testing etc.
     #[serde(default = "OptionalENConfig::default_miniblock_seal_queue_capacity")]
     pub miniblock_seal_queue_capacity: usize,
+    /// Maximum number of concurrent requests used to fetch miniblocks from the main node. The actual concurrency
+    /// is adjusted based on the main node latency.
+    #[serde(default = "OptionalENConfig::default_fetcher_max_concurrency")]
+    pub fetcher_max_concurrency: NonZeroUsize,
+    /// Target latency of a batch of concurrent miniblock requests to the main node. If the main node responds slower,
+    /// the fetcher concurrency is reduced.
+    #[serde(default = "OptionalENConfig::default_fetcher_target_latency_ms")]
+    fetcher_target_latency_ms: u64,
+
+    /// Comma-separated URLs of additional main node endpoints (e.g., operated on independent infrastructure) used
+    /// to cross-check miniblocks fetched from the main node before applying them.
+    main_node_witness_urls: Option<Vec<String>>,
+    /// Policy determining how many main node endpoints (the main one and witnesses) must return matching miniblocks
+    /// for a miniblock to be applied. Only used if `main_node_witness_urls` are specified.
+    #[serde(default)]
+    pub main_node_quorum_policy: QuorumPolicy,
+    /// Whether the consistency checker should stop the node once it detects an L1 batch with the locally computed
+    /// commitment diverging from the one committed on L1. By default, divergences are only reported via logs,
+    /// metrics and the health check.
+    #[serde(default)]
+    pub consistency_checker_bail_on_mismatch: bool,
+    /// Maximum number of L1 batches that can be rolled back automatically after a reorg is detected. Deeper rollbacks
+    /// require a manual confirmation via the `--confirm-deep-rollback` command-line arg. If not set, rollbacks
+    /// are not limited.
+    pub max_auto_rollback_l1_batches: Option<u32>,
+
+    // DA verification
+    /// URL of the L1 beacon node API. If set, pubdata published in EIP-4844 blobs is verified against
+    /// blob sidecars retrieved from the beacon node.
+    pub l1_beacon_api_url: Option<String>,
+    /// URL of a Celestia node. If set together with `da_celestia_namespace`, pubdata published on Celestia
+    /// is verified against blobs retrieved from the node.
+    pub da_celestia_node_url: Option<String>,
+    /// Hex-encoded Celestia namespace ID used by the main node.
+    pub da_celestia_namespace: Option<String>,
+    /// URL of the EigenDA disperser API. If set, pubdata published on EigenDA is verified against blobs
+    /// retrieved from the disperser.
+    pub da_eigenda_disperser_url: Option<String>,
+    /// First protocol version for which the main node compresses pubdata dispatched to the DA layer.
+    /// Must match `compression_protocol_version` in the main node DA dispatcher config.
+    pub da_compression_protocol_version: Option<u16>,
+    /// Path to the zstd dictionary used by the main node to compress pubdata.
+    pub da_compression_dictionary_path: Option<String>,
+    /// Number of latest committed L1 batches re-verified by the DA verifier on node start. Default is 10.
+    #[serde(default = "OptionalENConfig::default_da_verifier_max_batches_to_recheck")]
+    pub da_verifier_max_batches_to_recheck: u32,
+
+    // Proof verification
+    /// Enables local verification of L1 batch proofs submitted to L1. Verification results are exposed
+    /// via the `idexo_getL1BatchProofStatus` API method.
+    #[serde(default)]
+    pub proof_verification_enabled: bool,
+
+    // Pruning
+    /// Node mode determining pruning and API defaults. If not specified, the mode is inferred from the pruning flags:
+    /// the node is a full node if any pruning is enabled, and an archive node otherwise.
+    node_mode: Option<NodeMode>,
+    /// Enables pruning of old data (transactions, events, overwritten storage logs etc.) from Postgres.
+    /// Implied by the full and minimal node modes.
+    #[serde(default)]
+    pruning_enabled: bool,
+    /// If set, the pruner only reports data that would be pruned (via logs and metrics) without removing it.
+    #[serde(default)]
+    pub pruning_dry_run: bool,
+    /// If set, the pruner only removes storage logs overwritten within the same miniblock, so that the state after
+    /// each pruned miniblock is retained. Together with `archive_tree_api_url`, this allows serving `eth_call` for
+    /// any pruned miniblock rather than only for the last miniblock in each L1 batch.
+    #[serde(default)]
+    pub pruning_retain_miniblock_state: bool,
+    /// Number of L1 batches pruned at a time.
+    #[serde(default = "OptionalENConfig::default_pruning_chunk_size")]
+    pub pruning_chunk_size: NonZeroU32,
+    /// Minimum age of an L1 batch for its data to be pruned. Only L1 batches executed on L1 are pruned
+    /// regardless of this setting. The default value depends on the node mode.
+    pruning_data_retention_hours: Option<u64>,
+    /// Enables partitioning Postgres tables (events and storage logs) by miniblock ranges and creating partitions
+    /// in advance, which allows the pruner to drop old partitions as a whole. Tables are converted to partitioned ones
+    /// on the first start with partitioning enabled and remain partitioned afterwards. Enabled by default if pruning
+    /// is enabled.
+    database_partitioning_enabled: Option<bool>,
+    /// Number of miniblocks in a single partition of partitioned Postgres tables.
+    #[serde(default = "OptionalENConfig::default_database_partition_size")]
+    pub database_partition_size: NonZeroU32,
+
+    // Health checks
+    /// Time since the last sealed miniblock after which the state keeper is reported as affected by the health check.
+    /// Miniblock timestamps are assigned by the main node, so the lag includes both the sync lag and the time the chain
+    /// was idle. If not set, the lag is only reported in health check details.
+    healthcheck_state_keeper_max_lag_sec: Option<u64>,
+    /// Number of L1 batches the Merkle tree may lag behind synced L1 batches before it is reported as affected
+    /// by the health check. If not set, `merkle_tree_async_max_lag` is used; if neither is set, the lag
+    /// is only reported in health check details.
+    pub healthcheck_tree_max_lag: Option<u32>,
+
+    // Sequencer signatures
+    /// Address of the key used by the main node to sign miniblocks. If set, miniblocks fetched from the main node
+    /// must be signed by this key; otherwise, the fetcher stops with an error. Miniblocks synced via consensus
+    /// are authenticated by validator certificates instead and are not checked. Required for read replicas,
+    /// which additionally verify signatures of miniblock outputs.
+    pub sequencer_address: Option<Address>,
+
+    // Content hashes
+    /// Enables verification of content hashes for miniblocks sealed by the node. Hashes are checked against
+    /// the local data in Postgres (to detect silent data corruption) and against the hashes reported by the main node;
+    /// either mismatch stops the node. If enabled, the API server also verifies content hashes when serving blocks
+    /// and transaction receipts.
+    #[serde(default)]
+    pub content_hash_checks_enabled: bool,
+
+    // Name resolution
+    /// Address of the on-chain name registry used to resolve human-readable names in the API server.
+    /// Should be set to the same value as on the main node.
+    pub name_registry_addr: Option<Address>,
+    /// Enables resolving names in address parameters of RPC methods. Has no effect if `name_registry_addr` is not set.
+    #[serde(default)]
+    pub resolve_names_in_params: bool,
+
+    // Request shadowing
+    /// URL of a secondary JSON-RPC endpoint (e.g., a canary node) that read requests are mirrored to.
+    /// Responses of the secondary endpoint are compared with the local ones; divergences are logged.
+    pub shadow_api_url: Option<String>,
+    /// Percentage of read requests mirrored to `shadow_api_url`. Default is 1%.
+    #[serde(default = "OptionalENConfig::default_shadow_requests_percentage")]
+    pub shadow_requests_percentage: f64,
+
+    // Replay protection
+    /// Accepts legacy transactions without replay protection (i.e., not specifying the chain ID as per EIP-155)
+    /// submitted to the API server. By default, such transactions are rejected.
+    #[serde(default)]
+    pub pre_eip155_txs_allowed: bool,
+    /// Senders of legacy transactions without replay protection that are accepted even if `pre_eip155_txs_allowed`
+    /// is not set (e.g., keyless deployers of deterministic deployment proxies).
+    #[serde(default)]
+    pub pre_eip155_tx_senders: Vec<Address>,
+
+    // Sandbox warm pool
+    /// Enables the warm pool of sandbox environments for VM invocations at the latest block. The pool
+    /// is refreshed on each sealed miniblock, so that VM invocations don't need to resolve the block context
+    /// from Postgres. Enabled by default for read replicas.
+    sandbox_warm_pool_enabled: Option<bool>,
+
+    // Archive calls
+    /// URL of the Merkle tree API (e.g., the one exposed by the main node or an archive node) used to serve
+    /// `eth_call` for pruned blocks. If not set, such calls return an error.
+    pub archive_tree_api_url: Option<String>,
+
+    // Genesis
+    /// Path to the genesis manifest used by the main node. Must be set if the main node used a manifest
+    /// during genesis; otherwise, the genesis state will not match the main node.
+    pub genesis_manifest_path: Option<String>,
 }
 
 impl OptionalENConfig {
@@ -228,6 +513,18 @@ impl OptionalENConfig {
         200
     }
 
+    const fn default_pubsub_lag_warning_threshold() -> usize {
+        128
+    }
+
+    const fn default_pubsub_lag_close_threshold() -> usize {
+        512
+    }
+
+    const fn default_shadow_requests_percentage() -> f64 {
+        1.0
+    }
+
     const fn default_estimate_gas_scale_factor() -> f64 {
         1.2
     }
@@ -263,16 +560,22 @@ impl OptionalENConfig {
         2_048
     }
 
-    const fn default_factory_deps_cache_size_mb() -> usize {
-        128
+    const fn default_factory_deps_cache_size_mb(node_mode: NodeMode) -> usize {
+        match node_mode {
+            NodeMode::ReadReplica => 512,
+            NodeMode::Archive | NodeMode::Full | NodeMode::Minimal => 128,
+        }
     }
 
     const fn default_initial_writes_cache_size_mb() -> usize {
         32
     }
 
-    const fn default_latest_values_cache_size_mb() -> usize {
-        128
+    const fn default_latest_values_cache_size_mb(node_mode: NodeMode) -> usize {
+        match node_mode {
+            NodeMode::ReadReplica => 1_024,
+            NodeMode::Archive | NodeMode::Full | NodeMode::Minimal => 128,
+        }
     }
 
     const fn default_merkle_tree_multi_get_chunk_size() -> usize {
@@ -287,14 +590,34 @@ impl OptionalENConfig {
         256
     }
 
+    const fn default_merkle_tree_compaction_interval_sec() -> u64 {
+        3_600
+    }
+
     const fn default_merkle_tree_stalled_writes_timeout_sec() -> u64 {
         30
     }
 
+    const fn default_merkle_tree_integrity_check_subtree_count() -> usize {
+        16
+    }
+
+    const fn default_merkle_tree_enabled() -> bool {
+        true
+    }
+
     const fn default_fee_history_limit() -> u64 {
         1_024
     }
 
+    const fn default_da_verifier_max_batches_to_recheck() -> u32 {
+        10
+    }
+
+    const fn default_multicall_compute_budget_ms() -> u64 {
+        1_000
+    }
+
     const fn default_max_batch_request_size() -> usize {
         500 // The default limit is chosen to be reasonably permissive.
     }
@@ -311,17 +634,131 @@ impl OptionalENConfig {
         10
     }
 
+    fn default_fetcher_max_concurrency() -> NonZeroUsize {
+        NonZeroUsize::new(100).unwrap()
+    }
+
+    const fn default_fetcher_target_latency_ms() -> u64 {
+        1_000
+    }
+
+    fn default_pruning_chunk_size() -> NonZeroU32 {
+        NonZeroU32::new(10).unwrap()
+    }
+
+    fn default_database_partition_size() -> NonZeroU32 {
+        NonZeroU32::new(100_000).unwrap()
+    }
+
+    /// Auth token for the Celestia node; kept out of the config struct since it's a secret.
+    pub fn da_celestia_auth_token(&self) -> Option<String> {
+        env::var("EN_DA_CELESTIA_AUTH_TOKEN").ok()
+    }
+
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval)
     }
 
+    pub fn main_node_witness_urls(&self) -> &[String] {
+        self.main_node_witness_urls.as_deref().unwrap_or_default()
+    }
+
+    pub fn fetcher_target_latency(&self) -> Duration {
+        Duration::from_millis(self.fetcher_target_latency_ms)
+    }
+
+    /// Returns the node mode, either specified explicitly or inferred from the pruning flags.
+    pub fn node_mode(&self) -> NodeMode {
+        self.node_mode.unwrap_or_else(|| {
+            if self.pruning_enabled || self.merkle_tree_pruning_enabled {
+                NodeMode::Full
+            } else {
+                NodeMode::Archive
+            }
+        })
+    }
+
+    fn validate_node_mode(&self) -> anyhow::Result<()> {
+        if self.node_mode == Some(NodeMode::Archive)
+            && (self.pruning_enabled || self.merkle_tree_pruning_enabled)
+        {
+            anyhow::bail!(
+                "Pruning cannot be enabled for an archive node; either unset `EN_PRUNING_ENABLED` \
+                 and `EN_MERKLE_TREE_PRUNING_ENABLED`, or change `EN_NODE_MODE`"
+            );
+        }
+
+        let has_debug_namespace = self
+            .api_namespaces
+            .as_ref()
+            .map_or(false, |namespaces| namespaces.contains(&Namespace::Debug));
+        let node_mode = self.node_mode();
+        if !node_mode.serves_call_traces() && has_debug_namespace {
+            tracing::warn!(
+                "`debug` namespace is not served in the {node_mode:?} node mode; it will be disabled"
+            );
+        }
+        if node_mode == NodeMode::ReadReplica && self.sequencer_address.is_none() {
+            anyhow::bail!(
+                "Read replica persists miniblocks and their outputs returned by the main node without executing \
+                 them; `EN_SEQUENCER_ADDRESS` must be set so that sequencer signatures can be verified"
+            );
+        }
+        if !self.merkle_tree_enabled() {
+            if self.merkle_tree_async_max_lag.is_some() {
+                tracing::warn!(
+                    "`EN_MERKLE_TREE_ASYNC_MAX_LAG` has no effect if the Merkle tree is not run; it will be ignored"
+                );
+            }
+            if self.sequencer_address.is_none() {
+                anyhow::bail!(
+                    "Without the Merkle tree, L1 batches are executed using root hashes returned by the main node; \
+                     `EN_SEQUENCER_ADDRESS` must be set so that sequencer signatures of root hashes can be verified"
+                );
+            }
+        } else if self.merkle_tree_async_max_lag.is_some() && self.sequencer_address.is_none() {
+            anyhow::bail!(
+                "In the async tree mode, L1 batches are executed using root hashes returned by the main node; \
+                 `EN_SEQUENCER_ADDRESS` must be set so that sequencer signatures of root hashes can be verified"
+            );
+        }
+        Ok(())
+    }
+
+    pub fn pruning_enabled(&self) -> bool {
+        self.pruning_enabled || self.node_mode() != NodeMode::Archive
+    }
+
+    pub fn healthcheck_state_keeper_max_lag(&self) -> Option<Duration> {
+        self.healthcheck_state_keeper_max_lag_sec
+            .map(Duration::from_secs)
+    }
+
+    pub fn pruning_data_retention(&self) -> Duration {
+        let hours = self
+            .pruning_data_retention_hours
+            .unwrap_or(match self.node_mode() {
+                NodeMode::Minimal => 0,
+                NodeMode::Archive | NodeMode::Full | NodeMode::ReadReplica => 7 * 24,
+            });
+        Duration::from_secs(hours * 3_600)
+    }
+
+    pub fn database_partitioning_enabled(&self) -> bool {
+        self.database_partitioning_enabled
+            .unwrap_or_else(|| self.pruning_enabled())
+    }
+
     pub fn metadata_calculator_delay(&self) -> Duration {
         Duration::from_millis(self.metadata_calculator_delay)
     }
 
     /// Returns the size of factory dependencies cache in bytes.
     pub fn factory_deps_cache_size(&self) -> usize {
-        self.factory_deps_cache_size_mb * BYTES_IN_MEGABYTE
+        let size_mb = self
+            .factory_deps_cache_size_mb
+            .unwrap_or_else(|| Self::default_factory_deps_cache_size_mb(self.node_mode()));
+        size_mb * BYTES_IN_MEGABYTE
     }
 
     /// Returns the size of initial writes cache in bytes.
@@ -331,7 +768,15 @@ impl OptionalENConfig {
 
     /// Returns the size of latest values cache in bytes.
     pub fn latest_values_cache_size(&self) -> usize {
-        self.latest_values_cache_size_mb * BYTES_IN_MEGABYTE
+        let size_mb = self
+            .latest_values_cache_size_mb
+            .unwrap_or_else(|| Self::default_latest_values_cache_size_mb(self.node_mode()));
+        size_mb * BYTES_IN_MEGABYTE
+    }
+
+    pub fn sandbox_warm_pool_enabled(&self) -> bool {
+        self.sandbox_warm_pool_enabled
+            .unwrap_or(self.node_mode() == NodeMode::ReadReplica)
     }
 
     /// Returns the size of block cache for Merkle tree in bytes.
@@ -349,6 +794,61 @@ impl OptionalENConfig {
         Duration::from_secs(self.merkle_tree_stalled_writes_timeout_sec)
     }
 
+    /// Checks whether the node runs the Merkle tree.
+    pub fn merkle_tree_enabled(&self) -> bool {
+        self.merkle_tree_enabled && self.node_mode().executes_transactions()
+    }
+
+    pub fn merkle_tree_pruning_enabled(&self) -> bool {
+        self.merkle_tree_pruning_enabled || self.node_mode() != NodeMode::Archive
+    }
+
+    /// Returns the number of the latest Merkle tree versions retained by pruning.
+    pub fn merkle_tree_retained_versions(&self) -> NonZeroU64 {
+        self.merkle_tree_retained_versions.unwrap_or_else(|| {
+            let versions = match self.node_mode() {
+                NodeMode::Minimal => 100,
+                NodeMode::Archive | NodeMode::Full | NodeMode::ReadReplica => 10_000,
+            };
+            NonZeroU64::new(versions).unwrap()
+        })
+    }
+
+    /// Returns the minimum interval between Merkle tree RocksDB compactions triggered after pruning.
+    pub fn merkle_tree_compaction_interval(&self) -> Option<Duration> {
+        (self.merkle_tree_compaction_interval_sec > 0)
+            .then(|| Duration::from_secs(self.merkle_tree_compaction_interval_sec))
+    }
+
+    pub fn merkle_tree_integrity_check_interval(&self) -> Option<Duration> {
+        self.merkle_tree_integrity_check_interval_sec
+            .map(Duration::from_secs)
+    }
+
+    /// Returns the RocksDB tuning profile for the Merkle tree.
+    pub fn merkle_tree_rocksdb_profile(&self) -> RocksdbProfile {
+        RocksdbProfile {
+            compaction_style: self.merkle_tree_compaction_style,
+            max_total_wal_size_mb: self.merkle_tree_max_total_wal_size_mb,
+            wal_bytes_per_sync_mb: self.merkle_tree_wal_bytes_per_sync_mb,
+        }
+    }
+
+    /// Returns the size of block cache for the state keeper RocksDB in bytes.
+    pub fn state_keeper_db_block_cache_size(&self) -> Option<usize> {
+        self.state_keeper_db_block_cache_size_mb
+            .map(|size| size * BYTES_IN_MEGABYTE)
+    }
+
+    /// Returns the RocksDB tuning profile for the state keeper cache.
+    pub fn state_keeper_rocksdb_profile(&self) -> RocksdbProfile {
+        RocksdbProfile {
+            compaction_style: self.state_keeper_db_compaction_style,
+            max_total_wal_size_mb: self.state_keeper_db_max_total_wal_size_mb,
+            wal_bytes_per_sync_mb: self.state_keeper_db_wal_bytes_per_sync_mb,
+        }
+    }
+
     pub fn long_connection_threshold(&self) -> Option<Duration> {
         self.database_long_connection_threshold_ms
             .map(Duration::from_millis)
@@ -359,10 +859,37 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    /// Returns connection quotas for workloads sharing the main connection pool.
+    pub fn database_workload_quotas(&self) -> WorkloadQuotas {
+        let mut quotas = WorkloadQuotas::default();
+        let limits = [
+            (WorkloadClass::Api, self.database_api_max_connections),
+            (
+                WorkloadClass::StateKeeper,
+                self.database_state_keeper_max_connections,
+            ),
+            (
+                WorkloadClass::Background,
+                self.database_background_max_connections,
+            ),
+        ];
+        for (class, limit) in limits {
+            if let Some(limit) = limit {
+                quotas.set_limit(class, limit);
+            }
+        }
+        quotas
+    }
+
     pub fn api_namespaces(&self) -> Vec<Namespace> {
-        self.api_namespaces
+        let mut namespaces = self
+            .api_namespaces
             .clone()
-            .unwrap_or_else(|| Namespace::DEFAULT.to_vec())
+            .unwrap_or_else(|| Namespace::DEFAULT.to_vec());
+        if !self.node_mode().serves_call_traces() {
+            namespaces.retain(|namespace| *namespace != Namespace::Debug);
+        }
+        namespaces
     }
 
     pub fn max_response_body_size(&self) -> usize {
@@ -446,17 +973,64 @@ pub(crate) fn read_consensus_config() -> anyhow::Result<consensus::FetcherConfig
 #[derive(Debug, Clone)]
 pub struct SnapshotsRecoveryConfig {
     pub snapshots_object_store: ObjectStoreConfig,
+    /// Maximum number of storage logs chunks recovered concurrently. Additionally limited by the size
+    /// of the Postgres connection pool.
+    pub max_concurrency: NonZeroUsize,
+    /// Number of attempts to recover from a snapshot before giving up on retryable errors (e.g., network
+    /// errors when accessing the object store).
+    pub retry_count: usize,
+    /// Expected hash of the snapshot manifest published by the main node operator. If set, the manifest
+    /// fetched from the object store is checked against it.
+    pub manifest_hash: Option<H256>,
+}
+
+/// Snapshot recovery parameters loaded from env variables with the `EN_SNAPSHOTS_RECOVERY_` prefix.
+#[derive(Debug, Deserialize)]
+struct SnapshotsRecoveryOptions {
+    #[serde(default = "SnapshotsRecoveryOptions::default_max_concurrency")]
+    max_concurrency: NonZeroUsize,
+    #[serde(default = "SnapshotsRecoveryOptions::default_retry_count")]
+    retry_count: usize,
+    manifest_hash: Option<H256>,
+}
+
+impl SnapshotsRecoveryOptions {
+    fn default_max_concurrency() -> NonZeroUsize {
+        NonZeroUsize::new(10).unwrap()
+    }
+
+    const fn default_retry_count() -> usize {
+        5
+    }
 }
 
 pub(crate) fn read_snapshots_recovery_config() -> anyhow::Result<SnapshotsRecoveryConfig> {
     let snapshots_object_store = envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_")
         .from_env::<ObjectStoreConfig>()
         .context("failed loading snapshot object store config from env variables")?;
+    let options = envy::prefixed("EN_SNAPSHOTS_RECOVERY_")
+        .from_env::<SnapshotsRecoveryOptions>()
+        .context("failed loading snapshot recovery options from env variables")?;
     Ok(SnapshotsRecoveryConfig {
         snapshots_object_store,
+        max_concurrency: options.max_concurrency,
+        retry_count: options.retry_count,
+        manifest_hash: options.manifest_hash,
     })
 }
 
+/// Reads the CDC publisher configuration. Returns `None` if the publisher is not configured,
+/// i.e., `EN_CDC_PUBLISHER_BROKER` is not set.
+pub(crate) fn read_cdc_publisher_config() -> anyhow::Result<Option<CdcPublisherConfig>> {
+    if env::var_os("EN_CDC_PUBLISHER_BROKER").is_none() {
+        return Ok(None);
+    }
+    let config = envy::prefixed("EN_CDC_PUBLISHER_")
+        .from_env::<CdcPublisherConfig>()
+        .context("failed loading CDC publisher config from env variables")?;
+    Ok(Some(config))
+}
+
 /// External Node Config contains all the configuration required for the EN operation.
 /// It is split into three parts: required, optional and remote for easier navigation.
 #[derive(Debug, Clone)]
@@ -466,6 +1040,7 @@ pub struct ExternalNodeConfig {
     pub optional: OptionalENConfig,
     pub remote: RemoteENConfig,
     pub consensus: Option<consensus::FetcherConfig>,
+    pub cdc_publisher: Option<CdcPublisherConfig>,
 }
 
 impl ExternalNodeConfig {
@@ -479,6 +1054,7 @@ impl ExternalNodeConfig {
         let optional = envy::prefixed("EN_")
             .from_env::<OptionalENConfig>()
             .context("could not load external node config")?;
+        optional.validate_node_mode()?;
 
         let client = HttpClientBuilder::default()
             .build(required.main_node_url()?)
@@ -524,6 +1100,7 @@ impl ExternalNodeConfig {
         }
 
         let postgres = PostgresConfig::from_env()?;
+        let cdc_publisher = read_cdc_publisher_config()?;
 
         Ok(Self {
             remote,
@@ -531,6 +1108,7 @@ impl ExternalNodeConfig {
             required,
             optional,
             consensus: None,
+            cdc_publisher,
         })
     }
 }
@@ -561,12 +1139,26 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
                 l2_erc20_default_bridge: config.remote.l2_erc20_bridge_addr,
                 l1_weth_bridge: config.remote.l1_weth_bridge_proxy_addr,
                 l2_weth_bridge: config.remote.l2_weth_bridge_addr,
+                l1_shared_default_bridge: config.remote.l1_shared_bridge_proxy_addr,
+                l2_shared_default_bridge: config.remote.l2_shared_bridge_addr,
             },
             bridgehub_proxy_addr: config.remote.bridgehub_proxy_addr,
             diamond_proxy_addr: config.remote.diamond_proxy_addr,
+            validator_timelock_addr: config.remote.validator_timelock_addr,
             l2_testnet_paymaster_addr: config.remote.l2_testnet_paymaster_addr,
             req_entities_limit: config.optional.req_entities_limit,
             fee_history_limit: config.optional.fee_history_limit,
+            forced_inclusion_deadline: None,
+            verify_content_hashes: config.optional.content_hash_checks_enabled,
+            name_registry_addr: config.optional.name_registry_addr,
+            resolve_names_in_params: config.optional.resolve_names_in_params,
+            multicall_compute_budget: Duration::from_millis(
+                config.optional.multicall_compute_budget_ms,
+            ),
+            shadow_api_url: config.optional.shadow_api_url,
+            shadow_requests_percentage: config.optional.shadow_requests_percentage,
+            pre_eip155_txs_allowed: config.optional.pre_eip155_txs_allowed,
+            pre_eip155_tx_senders: config.optional.pre_eip155_tx_senders,
         }
     }
 }
@@ -590,6 +1182,8 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
             l1_to_l2_transactions_compatibility_mode: config
                 .optional
                 .l1_to_l2_transactions_compatibility_mode,
+            // Limits other than the transaction size are enforced by the main node.
+            tx_limits: TxLimits::new(config.optional.max_tx_size),
         }
     }
 }
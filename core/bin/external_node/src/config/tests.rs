@@ -24,7 +24,34 @@ fn parsing_optional_config_from_empty_env() {
         config.merkle_tree_block_cache_size(),
         128 * BYTES_IN_MEGABYTE
     );
+    assert_eq!(config.node_mode(), NodeMode::Archive);
+    assert!(!config.merkle_tree_pruning_enabled());
+    assert_eq!(config.merkle_tree_retained_versions().get(), 10_000);
+    assert_eq!(
+        config.merkle_tree_compaction_interval(),
+        Some(Duration::from_secs(3_600))
+    );
     assert_eq!(config.max_response_body_size(), 10 * BYTES_IN_MEGABYTE);
+    assert!(config.database_workload_quotas().is_empty());
+    assert_eq!(config.l1_beacon_api_url, None);
+    assert_eq!(config.da_celestia_node_url, None);
+    assert_eq!(config.da_compression_protocol_version, None);
+    assert_eq!(config.da_verifier_max_batches_to_recheck, 10);
+    assert!(config.main_node_witness_urls().is_empty());
+    assert_eq!(config.main_node_quorum_policy, QuorumPolicy::Majority);
+    assert_eq!(config.max_auto_rollback_l1_batches, None);
+    assert!(!config.pruning_enabled());
+    assert!(!config.pruning_dry_run);
+    assert!(!config.pruning_retain_miniblock_state);
+    assert_eq!(config.pruning_chunk_size.get(), 10);
+    assert_eq!(
+        config.pruning_data_retention(),
+        Duration::from_secs(7 * 24 * 3_600)
+    );
+    assert!(!config.database_partitioning_enabled());
+    assert_eq!(config.database_partition_size.get(), 100_000);
+    assert!(!config.pre_eip155_txs_allowed);
+    assert!(config.pre_eip155_tx_senders.is_empty());
 }
 
 #[test]
@@ -43,7 +70,45 @@ fn parsing_optional_config_from_env() {
         ("EN_LATEST_VALUES_CACHE_SIZE_MB", "50"),
         ("EN_MERKLE_TREE_MULTI_GET_CHUNK_SIZE", "1000"),
         ("EN_MERKLE_TREE_BLOCK_CACHE_SIZE_MB", "32"),
+        ("EN_MERKLE_TREE_PRUNING_ENABLED", "true"),
+        ("EN_MERKLE_TREE_RETAINED_VERSIONS", "100"),
+        ("EN_MERKLE_TREE_COMPACTION_INTERVAL_SEC", "0"),
+        ("EN_MERKLE_TREE_THREAD_POOL_SIZE", "4"),
+        ("EN_MERKLE_TREE_INTEGRITY_CHECK_INTERVAL_SEC", "300"),
+        ("EN_MERKLE_TREE_INTEGRITY_AUTO_REPAIR", "true"),
+        ("EN_MERKLE_TREE_ASYNC_MAX_LAG", "3"),
+        ("EN_MERKLE_TREE_COMPACTION_STYLE", "universal"),
+        ("EN_MERKLE_TREE_MAX_TOTAL_WAL_SIZE_MB", "512"),
+        ("EN_STATE_KEEPER_DB_BLOCK_CACHE_SIZE_MB", "64"),
+        ("EN_STATE_KEEPER_DB_WAL_BYTES_PER_SYNC_MB", "1"),
+        ("EN_API_STATE_CACHE_PATH", "/db/api_state_cache"),
         ("EN_MAX_RESPONSE_BODY_SIZE_MB", "1"),
+        ("EN_DATABASE_API_MAX_CONNECTIONS", "30"),
+        ("EN_DATABASE_STATE_KEEPER_MAX_CONNECTIONS", "10"),
+        ("EN_L1_BEACON_API_URL", "http://127.0.0.1:5052"),
+        ("EN_DA_CELESTIA_NODE_URL", "http://127.0.0.1:26658"),
+        ("EN_DA_CELESTIA_NAMESPACE", "1d3c0a"),
+        ("EN_DA_COMPRESSION_PROTOCOL_VERSION", "22"),
+        ("EN_DA_VERIFIER_MAX_BATCHES_TO_RECHECK", "50"),
+        ("EN_PROOF_VERIFICATION_ENABLED", "true"),
+        (
+            "EN_MAIN_NODE_WITNESS_URLS",
+            "http://127.0.0.1:3060,http://127.0.0.1:3070",
+        ),
+        ("EN_MAIN_NODE_QUORUM_POLICY", "all"),
+        ("EN_MAX_AUTO_ROLLBACK_L1_BATCHES", "5"),
+        ("EN_PRUNING_ENABLED", "true"),
+        ("EN_PRUNING_DRY_RUN", "true"),
+        ("EN_PRUNING_RETAIN_MINIBLOCK_STATE", "true"),
+        ("EN_PRUNING_CHUNK_SIZE", "5"),
+        ("EN_PRUNING_DATA_RETENTION_HOURS", "1"),
+        ("EN_DATABASE_PARTITIONING_ENABLED", "false"),
+        ("EN_DATABASE_PARTITION_SIZE", "1000"),
+        ("EN_PRE_EIP155_TXS_ALLOWED", "true"),
+        (
+            "EN_PRE_EIP155_TX_SENDERS",
+            "0x3fab184622dc19b6109349b94811493bf2a45362",
+        ),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -69,5 +134,269 @@ fn parsing_optional_config_from_env() {
         config.merkle_tree_block_cache_size(),
         32 * BYTES_IN_MEGABYTE
     );
+    assert_eq!(config.node_mode(), NodeMode::Full);
+    assert!(config.merkle_tree_pruning_enabled());
+    assert_eq!(config.merkle_tree_retained_versions().get(), 100);
+    assert_eq!(config.merkle_tree_compaction_interval(), None);
+    assert_eq!(config.merkle_tree_thread_pool_size, Some(4));
+    assert_eq!(
+        config.merkle_tree_integrity_check_interval(),
+        Some(Duration::from_secs(300))
+    );
+    assert_eq!(config.merkle_tree_integrity_check_subtree_count, 16);
+    assert!(config.merkle_tree_integrity_auto_repair);
+    assert_eq!(config.merkle_tree_async_max_lag, Some(3));
+    let tree_profile = config.merkle_tree_rocksdb_profile();
+    assert_eq!(
+        tree_profile.compaction_style,
+        RocksdbCompactionStyle::Universal
+    );
+    assert_eq!(tree_profile.max_total_wal_size(), Some(512 << 20));
+    assert_eq!(tree_profile.wal_bytes_per_sync(), None);
+    assert_eq!(
+        config.state_keeper_db_block_cache_size(),
+        Some(64 * BYTES_IN_MEGABYTE)
+    );
+    let state_keeper_profile = config.state_keeper_rocksdb_profile();
+    assert_eq!(
+        state_keeper_profile.compaction_style,
+        RocksdbCompactionStyle::Level
+    );
+    assert_eq!(state_keeper_profile.wal_bytes_per_sync(), Some(1 << 20));
+    assert_eq!(
+        config.api_state_cache_path.as_deref(),
+        Some("/db/api_state_cache")
+    );
     assert_eq!(config.max_response_body_size(), BYTES_IN_MEGABYTE);
+    let quotas = config.database_workload_quotas();
+    assert_eq!(quotas.limit(WorkloadClass::Api), Some(30));
+    assert_eq!(quotas.limit(WorkloadClass::StateKeeper), Some(10));
+    assert_eq!(quotas.limit(WorkloadClass::Background), None);
+    assert_eq!(
+        config.l1_beacon_api_url.as_deref(),
+        Some("http://127.0.0.1:5052")
+    );
+    assert_eq!(
+        config.da_celestia_node_url.as_deref(),
+        Some("http://127.0.0.1:26658")
+    );
+    assert_eq!(config.da_celestia_namespace.as_deref(), Some("1d3c0a"));
+    assert_eq!(config.da_eigenda_disperser_url, None);
+    assert_eq!(config.da_compression_protocol_version, Some(22));
+    assert_eq!(config.da_compression_dictionary_path, None);
+    assert_eq!(config.da_verifier_max_batches_to_recheck, 50);
+    assert!(config.proof_verification_enabled);
+    assert_eq!(
+        config.main_node_witness_urls(),
+        ["http://127.0.0.1:3060", "http://127.0.0.1:3070"]
+    );
+    assert_eq!(config.main_node_quorum_policy, QuorumPolicy::All);
+    assert_eq!(config.max_auto_rollback_l1_batches, Some(5));
+    assert!(config.pruning_enabled());
+    assert!(config.pruning_dry_run);
+    assert!(config.pruning_retain_miniblock_state);
+    assert_eq!(config.pruning_chunk_size.get(), 5);
+    assert_eq!(config.pruning_data_retention(), Duration::from_secs(3_600));
+    assert!(!config.database_partitioning_enabled());
+    assert_eq!(config.database_partition_size.get(), 1_000);
+    assert!(config.pre_eip155_txs_allowed);
+    assert_eq!(
+        config.pre_eip155_tx_senders,
+        ["0x3fab184622dc19b6109349b94811493bf2a45362"
+            .parse::<Address>()
+            .unwrap()]
+    );
+}
+
+#[test]
+fn parsing_node_mode() {
+    let parse = |env_vars: &[(&str, &str)]| -> OptionalENConfig {
+        let env_vars = env_vars
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()));
+        envy::prefixed("EN_").from_iter(env_vars).unwrap()
+    };
+
+    let config = parse(&[("EN_PRUNING_ENABLED", "true")]);
+    assert_eq!(config.node_mode(), NodeMode::Full);
+    assert!(config.pruning_enabled());
+    assert!(config.merkle_tree_pruning_enabled());
+    config.validate_node_mode().unwrap();
+
+    let config = parse(&[("EN_NODE_MODE", "full")]);
+    assert!(config.pruning_enabled());
+    assert!(config.database_partitioning_enabled());
+    assert!(config.merkle_tree_pruning_enabled());
+    assert_eq!(config.merkle_tree_retained_versions().get(), 10_000);
+    assert_eq!(
+        config.pruning_data_retention(),
+        Duration::from_secs(7 * 24 * 3_600)
+    );
+    assert_eq!(config.api_namespaces(), Namespace::DEFAULT);
+
+    let config = parse(&[
+        ("EN_NODE_MODE", "minimal"),
+        ("EN_API_NAMESPACES", "eth,net,web3,debug"),
+    ]);
+    assert!(config.pruning_enabled());
+    assert!(config.merkle_tree_pruning_enabled());
+    assert_eq!(config.merkle_tree_retained_versions().get(), 100);
+    assert_eq!(config.pruning_data_retention(), Duration::ZERO);
+    assert_eq!(
+        config.api_namespaces(),
+        [Namespace::Eth, Namespace::Net, Namespace::Web3]
+    );
+
+    let config = parse(&[
+        ("EN_NODE_MODE", "minimal"),
+        ("EN_MERKLE_TREE_RETAINED_VERSIONS", "500"),
+        ("EN_PRUNING_DATA_RETENTION_HOURS", "2"),
+    ]);
+    assert_eq!(config.merkle_tree_retained_versions().get(), 500);
+    assert_eq!(config.pruning_data_retention(), Duration::from_secs(7_200));
+
+    let config = parse(&[("EN_NODE_MODE", "archive"), ("EN_PRUNING_ENABLED", "true")]);
+    config.validate_node_mode().unwrap_err();
+    let config = parse(&[
+        ("EN_NODE_MODE", "archive"),
+        ("EN_MERKLE_TREE_PRUNING_ENABLED", "true"),
+    ]);
+    config.validate_node_mode().unwrap_err();
+}
+
+#[test]
+fn parsing_read_replica_node_mode() {
+    let parse = |env_vars: &[(&str, &str)]| -> OptionalENConfig {
+        let env_vars = env_vars
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()));
+        envy::prefixed("EN_").from_iter(env_vars).unwrap()
+    };
+
+    let config = parse(&[]);
+    assert!(config.node_mode().executes_transactions());
+    assert_eq!(config.factory_deps_cache_size(), 128 * BYTES_IN_MEGABYTE);
+    assert_eq!(config.latest_values_cache_size(), 128 * BYTES_IN_MEGABYTE);
+    assert!(!config.sandbox_warm_pool_enabled());
+
+    let config = parse(&[
+        ("EN_NODE_MODE", "read_replica"),
+        ("EN_API_NAMESPACES", "eth,net,web3,debug"),
+    ]);
+    assert_eq!(config.node_mode(), NodeMode::ReadReplica);
+    assert!(!config.node_mode().executes_transactions());
+    // Read replicas require the sequencer address.
+    config.validate_node_mode().unwrap_err();
+    let config = parse(&[
+        ("EN_NODE_MODE", "read_replica"),
+        ("EN_API_NAMESPACES", "eth,net,web3,debug"),
+        (
+            "EN_SEQUENCER_ADDRESS",
+            "0x0101010101010101010101010101010101010101",
+        ),
+    ]);
+    config.validate_node_mode().unwrap();
+    assert_eq!(
+        config.api_namespaces(),
+        [Namespace::Eth, Namespace::Net, Namespace::Web3]
+    );
+    assert_eq!(config.factory_deps_cache_size(), 512 * BYTES_IN_MEGABYTE);
+    assert_eq!(config.latest_values_cache_size(), 1_024 * BYTES_IN_MEGABYTE);
+    assert!(config.sandbox_warm_pool_enabled());
+
+    let config = parse(&[
+        ("EN_NODE_MODE", "read_replica"),
+        ("EN_LATEST_VALUES_CACHE_SIZE_MB", "0"),
+        ("EN_SANDBOX_WARM_POOL_ENABLED", "false"),
+    ]);
+    assert_eq!(config.latest_values_cache_size(), 0);
+    assert!(!config.sandbox_warm_pool_enabled());
+}
+
+#[test]
+fn parsing_merkle_tree_enabled() {
+    let parse = |env_vars: &[(&str, &str)]| -> OptionalENConfig {
+        let env_vars = env_vars
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()));
+        envy::prefixed("EN_").from_iter(env_vars).unwrap()
+    };
+
+    let config = parse(&[]);
+    assert!(config.merkle_tree_enabled());
+
+    let config = parse(&[
+        ("EN_MERKLE_TREE_ENABLED", "false"),
+        ("EN_MERKLE_TREE_ASYNC_MAX_LAG", "5"),
+    ]);
+    assert!(!config.merkle_tree_enabled());
+    assert!(config.node_mode().executes_transactions());
+    // Root hashes from the main node must be authenticated.
+    config.validate_node_mode().unwrap_err();
+
+    let config = parse(&[
+        ("EN_MERKLE_TREE_ENABLED", "false"),
+        ("EN_MERKLE_TREE_ASYNC_MAX_LAG", "5"),
+        (
+            "EN_SEQUENCER_ADDRESS",
+            "0x0101010101010101010101010101010101010101",
+        ),
+    ]);
+    assert!(!config.merkle_tree_enabled());
+    config.validate_node_mode().unwrap();
+
+    // Read replicas never run the tree.
+    let config = parse(&[("EN_NODE_MODE", "read_replica")]);
+    assert!(!config.merkle_tree_enabled());
+}
+
+#[test]
+fn parsing_async_tree_mode() {
+    let parse = |env_vars: &[(&str, &str)]| -> OptionalENConfig {
+        let env_vars = env_vars
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()));
+        envy::prefixed("EN_").from_iter(env_vars).unwrap()
+    };
+
+    // Root hashes from the main node must be authenticated.
+    let config = parse(&[("EN_MERKLE_TREE_ASYNC_MAX_LAG", "5")]);
+    config.validate_node_mode().unwrap_err();
+    let config = parse(&[
+        ("EN_MERKLE_TREE_ASYNC_MAX_LAG", "5"),
+        (
+            "EN_SEQUENCER_ADDRESS",
+            "0x0101010101010101010101010101010101010101",
+        ),
+    ]);
+    config.validate_node_mode().unwrap();
+    assert_eq!(config.merkle_tree_async_max_lag, Some(5));
+}
+
+#[test]
+fn parsing_snapshots_recovery_options() {
+    let options: SnapshotsRecoveryOptions = envy::prefixed("EN_SNAPSHOTS_RECOVERY_")
+        .from_iter([])
+        .unwrap();
+    assert_eq!(options.max_concurrency.get(), 10);
+    assert_eq!(options.retry_count, 5);
+    assert_eq!(options.manifest_hash, None);
+
+    let env_vars = [
+        ("EN_SNAPSHOTS_RECOVERY_MAX_CONCURRENCY", "4"),
+        ("EN_SNAPSHOTS_RECOVERY_RETRY_COUNT", "10"),
+        (
+            "EN_SNAPSHOTS_RECOVERY_MANIFEST_HASH",
+            "0x0101010101010101010101010101010101010101010101010101010101010101",
+        ),
+    ];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+    let options: SnapshotsRecoveryOptions = envy::prefixed("EN_SNAPSHOTS_RECOVERY_")
+        .from_iter(env_vars)
+        .unwrap();
+    assert_eq!(options.max_concurrency.get(), 4);
+    assert_eq!(options.retry_count, 10);
+    assert_eq!(options.manifest_hash, Some(H256::repeat_byte(1)));
 }
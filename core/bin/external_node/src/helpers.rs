@@ -1,5 +1,7 @@
 //! Miscellaneous helpers for the EN.
 
+use std::time::Instant;
+
 use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
 use zksync_web3_decl::{jsonrpsee::http_client::HttpClient, namespaces::EthNamespaceClient};
 
@@ -20,13 +22,22 @@ impl CheckHealth for MainNodeHealthCheck {
     }
 
     async fn check_health(&self) -> Health {
-        if let Err(err) = self.0.get_block_number().await {
-            tracing::warn!("Health-check call to main node HTTP RPC failed: {err}");
-            let details = serde_json::json!({
-                "error": err.to_string(),
-            });
-            return Health::from(HealthStatus::NotReady).with_details(details);
+        let started_at = Instant::now();
+        match self.0.get_block_number().await {
+            Ok(block_number) => {
+                let details = serde_json::json!({
+                    "block_number": block_number.as_u64(),
+                    "latency_ms": started_at.elapsed().as_millis() as u64,
+                });
+                Health::from(HealthStatus::Ready).with_details(details)
+            }
+            Err(err) => {
+                tracing::warn!("Health-check call to main node HTTP RPC failed: {err}");
+                let details = serde_json::json!({
+                    "error": err.to_string(),
+                });
+                Health::from(HealthStatus::NotReady).with_details(details)
+            }
         }
-        HealthStatus::Ready.into()
     }
 }
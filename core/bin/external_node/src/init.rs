@@ -2,7 +2,10 @@
 
 use anyhow::Context as _;
 use zksync_basic_types::{L1BatchNumber, L2ChainId};
-use zksync_core::sync_layer::genesis::perform_genesis_if_needed;
+use zksync_core::{
+    genesis::GenesisManifest,
+    sync_layer::{genesis::perform_genesis_if_needed, MainNodeClient},
+};
 use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStoreFactory;
 use zksync_snapshots_applier::{SnapshotsApplierConfig, SnapshotsApplierOutcome};
@@ -18,10 +21,14 @@ enum InitDecision {
     SnapshotRecovery,
 }
 
+/// `genesis_client` is used to fetch the genesis miniblock; unlike `main_node_client` used for snapshot recovery,
+/// it may verify sequencer signatures.
 pub(crate) async fn ensure_storage_initialized(
     pool: &ConnectionPool,
     main_node_client: &HttpClient,
+    genesis_client: &dyn MainNodeClient,
     l2_chain_id: L2ChainId,
+    genesis_manifest: Option<GenesisManifest>,
     consider_snapshot_recovery: bool,
 ) -> anyhow::Result<()> {
     let mut storage = pool.access_storage_tagged("en").await?;
@@ -66,7 +73,7 @@ pub(crate) async fn ensure_storage_initialized(
     match decision {
         InitDecision::Genesis => {
             let mut storage = pool.access_storage_tagged("en").await?;
-            perform_genesis_if_needed(&mut storage, l2_chain_id, main_node_client)
+            perform_genesis_if_needed(&mut storage, l2_chain_id, genesis_client, genesis_manifest)
                 .await
                 .context("performing genesis failed")?;
         }
@@ -83,7 +90,13 @@ pub(crate) async fn ensure_storage_initialized(
             let blob_store = ObjectStoreFactory::new(recovery_config.snapshots_object_store)
                 .create_store()
                 .await;
-            let outcome = SnapshotsApplierConfig::default()
+            let applier_config = SnapshotsApplierConfig {
+                retry_count: recovery_config.retry_count,
+                max_concurrency: recovery_config.max_concurrency,
+                expected_manifest_hash: recovery_config.manifest_hash,
+                ..SnapshotsApplierConfig::default()
+            };
+            let outcome = applier_config
                 .run(pool, main_node_client, &blob_store)
                 .await
                 .context("snapshot recovery failed")?;
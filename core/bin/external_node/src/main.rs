@@ -1,43 +1,65 @@
-use std::{sync::Arc, time::Duration};
+use std::{num::NonZeroU32, path::Path, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use clap::Parser;
 use futures::{future::FusedFuture, FutureExt as _};
 use metrics::EN_METRICS;
 use prometheus_exporter::PrometheusExporterConfig;
-use tokio::{sync::watch, task, time::sleep};
-use zksync_basic_types::{Address, L2ChainId};
+use tokio::{
+    sync::{oneshot, watch},
+    task,
+    time::sleep,
+};
+use zksync_basic_types::{Address, L1BatchNumber, L2ChainId};
 use zksync_concurrency::{ctx, scope};
 use zksync_config::configs::database::MerkleTreeMode;
 use zksync_core::{
     api_server::{
-        execution_sandbox::VmConcurrencyLimiter,
+        execution_sandbox::{ApiStateCache, SandboxWarmPool, VmConcurrencyLimiter},
         healthcheck::HealthCheckHandle,
         tx_sender::{ApiContracts, TxSenderBuilder},
         web3::{ApiBuilder, Namespace},
     },
     block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert},
+    cdc_publisher::{create_broker, CdcPublisher},
     commitment_generator::CommitmentGenerator,
     consensus,
     consistency_checker::ConsistencyChecker,
+    da_dispatcher::{
+        CelestiaClient, DataAvailabilityClient, EigenDAClient, PubdataCompression, ZstdCompressor,
+    },
+    da_verifier::{BeaconClient, DataAvailabilityVerifier},
+    db_partition_manager::{DbPartitionManager, DbPartitionManagerConfig},
+    db_pruner::{DbPruner, DbPrunerConfig},
+    genesis::GenesisManifest,
     l1_gas_price::MainNodeFeeParamsFetcher,
-    metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
+    metadata_calculator::{
+        DisabledTreeHealthCheck, MerkleTreeIntegrityCheckConfig, MerkleTreePruningConfig,
+        MetadataCalculator, MetadataCalculatorConfig, TreeLagHealthCheck,
+    },
+    proof_verifier::ProofVerifier,
     reorg_detector::ReorgDetector,
     setup_sigint_handler,
     state_keeper::{
         seal_criteria::NoopSealer, BatchExecutor, MainBatchExecutor, MiniblockSealer,
-        MiniblockSealerHandle, ZkSyncStateKeeper,
+        MiniblockSealerHandle, StateKeeperHealthCheck, ZkSyncStateKeeper,
     },
     sync_layer::{
         batch_status_updater::BatchStatusUpdater, external_io::ExternalIO,
-        fetcher::MainNodeFetcher, ActionQueue, MainNodeClient, SyncState,
+        fetcher::MainNodeFetcher, replica::ReplicaSyncer, ActionQueue, ContentHashChecker,
+        MainNodeClient, QuorumMainNodeClient, SignatureVerifyingMainNodeClient,
+        SignatureVerifyingOutputsClient, SyncState,
     },
 };
-use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
+use zksync_dal::{
+    connection::WorkloadClass, healthcheck::ConnectionPoolHealthCheck,
+    pruning_dal::StorageLogsPruning, ConnectionPool,
+};
 use zksync_health_check::{CheckHealth, HealthStatus, ReactiveHealthCheck};
 use zksync_state::PostgresStorageCaches;
 use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
+use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
 use crate::{
     config::{observability::observability_config_from_env, ExternalNodeConfig},
@@ -74,7 +96,7 @@ async fn build_state_keeper(
     // We only need call traces on the external node if the `debug_` namespace is enabled.
     let save_call_traces = config.optional.api_namespaces().contains(&Namespace::Debug);
 
-    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(MainBatchExecutor::new(
+    let batch_executor_base = MainBatchExecutor::new(
         state_keeper_db_path,
         connection_pool.clone(),
         max_allowed_l2_tx_gas_limit,
@@ -82,7 +104,12 @@ async fn build_state_keeper(
         false,
         config.optional.enum_index_migration_chunk_size,
         true,
-    ));
+    )
+    .with_state_keeper_db_tuning(
+        config.optional.state_keeper_db_block_cache_size(),
+        &config.optional.state_keeper_rocksdb_profile(),
+    );
+    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(batch_executor_base);
 
     let main_node_url = config.required.main_node_url()?;
     let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
@@ -92,13 +119,24 @@ async fn build_state_keeper(
         connection_pool,
         action_queue,
         sync_state,
-        Box::new(main_node_client),
+        with_signature_verification(config, Box::new(main_node_client)),
         l2_erc20_bridge_addr,
         validation_computational_gas_limit,
         chain_id,
     )
     .await
     .context("Failed initializing I/O for external node state keeper")?;
+    let io = if !config.optional.merkle_tree_enabled() {
+        tracing::info!(
+            "Running state keeper without Merkle tree; L1 batch hashes are taken from main node"
+        );
+        io.without_tree()
+    } else if let Some(max_lag) = config.optional.merkle_tree_async_max_lag {
+        tracing::info!("Running state keeper in async tree mode with max tree lag {max_lag}");
+        io.with_async_tree(max_lag)
+    } else {
+        io
+    };
 
     Ok(ZkSyncStateKeeper::new(
         stop_receiver,
@@ -129,20 +167,50 @@ async fn init_tasks(
         .main_node_url()
         .expect("Main node URL is incorrect");
     let mut healthchecks: Vec<Box<dyn CheckHealth>> = Vec::new();
+    // Components sharing the main connection pool are subject to per-workload connection quotas.
+    let state_keeper_pool = connection_pool.for_workload(WorkloadClass::StateKeeper);
+    let api_pool = connection_pool.for_workload(WorkloadClass::Api);
+    let background_pool = connection_pool.for_workload(WorkloadClass::Background);
+    // Read replicas persist miniblocks fetched from the main node without executing them.
+    let executes_transactions = config.optional.node_mode().executes_transactions();
+    if !executes_transactions {
+        anyhow::ensure!(
+            config.consensus.is_none(),
+            "Consensus cannot be used in the read replica node mode"
+        );
+    }
+    // Nodes without the tree don't compute L1 batch metadata, so components relying on it are not run either.
+    let merkle_tree_enabled = config.optional.merkle_tree_enabled();
+    if !merkle_tree_enabled {
+        tracing::info!(
+            "Merkle tree is disabled; storage proofs and tree lag will not be served by the API"
+        );
+    }
+
     // Create components.
     let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(&main_node_url));
 
     let sync_state = SyncState::default();
     healthchecks.push(Box::new(sync_state.clone()));
+    healthchecks.push(Box::new(StateKeeperHealthCheck::new(
+        connection_pool.clone(),
+        config.optional.healthcheck_state_keeper_max_lag(),
+    )));
     let (action_queue_sender, action_queue) = ActionQueue::new();
 
     let mut task_handles = vec![];
-    let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
-        connection_pool.clone(),
-        config.optional.miniblock_seal_queue_capacity,
-    );
-    task_handles.push(tokio::spawn(miniblock_sealer.run()));
-    let pool = connection_pool.clone();
+    let miniblock_sealer_handle = if executes_transactions {
+        let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
+            state_keeper_pool.clone(),
+            config.optional.miniblock_seal_queue_capacity,
+        );
+        task_handles.push(tokio::spawn(miniblock_sealer.run()));
+        Some(miniblock_sealer_handle)
+    } else {
+        None
+    };
+    let pool = background_pool.clone();
+    let mut version_stop_receiver = stop_receiver.clone();
     task_handles.push(tokio::spawn(async move {
         loop {
             let protocol_version = pool
@@ -156,22 +224,34 @@ async fn init_tasks(
 
             EN_METRICS.version[&(format!("{}", version), protocol_version)].set(1);
 
-            tokio::time::sleep(Duration::from_secs(10)).await;
+            // The node may be restarted in the same process after a rollback, so the task must stop on request.
+            let stop = version_stop_receiver.wait_for(|stop| *stop);
+            if tokio::time::timeout(Duration::from_secs(10), stop)
+                .await
+                .is_ok()
+            {
+                return Ok(());
+            }
         }
     }));
 
-    let state_keeper = build_state_keeper(
-        action_queue,
-        config.required.state_cache_path.clone(),
-        config,
-        connection_pool.clone(),
-        sync_state.clone(),
-        config.remote.l2_erc20_bridge_addr,
-        miniblock_sealer_handle,
-        stop_receiver.clone(),
-        config.remote.l2_chain_id,
-    )
-    .await?;
+    let state_keeper = if let Some(miniblock_sealer_handle) = miniblock_sealer_handle {
+        let state_keeper = build_state_keeper(
+            action_queue,
+            config.required.state_cache_path.clone(),
+            config,
+            state_keeper_pool,
+            sync_state.clone(),
+            config.remote.l2_erc20_bridge_addr,
+            miniblock_sealer_handle,
+            stop_receiver.clone(),
+            config.remote.l2_chain_id,
+        )
+        .await?;
+        Some(state_keeper)
+    } else {
+        None
+    };
 
     let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
         .context("Failed creating JSON-RPC client for main node")?;
@@ -180,8 +260,30 @@ async fn init_tasks(
     )));
     let singleton_pool_builder = ConnectionPool::singleton(&config.postgres.database_url);
 
-    let fetcher_handle = if let Some(cfg) = config.consensus.clone() {
-        let pool = connection_pool.clone();
+    let fetcher_handle = if !executes_transactions {
+        let pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a connection pool for `ReplicaSyncer`")?;
+        let sequencer_address = config
+            .optional
+            .sequencer_address
+            .context("sequencer address is required for read replicas")?;
+        let outputs_client = SignatureVerifyingOutputsClient::new(
+            Box::new(main_node_client.clone()),
+            sequencer_address,
+            config.remote.l2_chain_id,
+        );
+        let replica_syncer = ReplicaSyncer::new(
+            build_fetcher_client(config, main_node_client)?,
+            Box::new(outputs_client),
+            pool,
+            sync_state.clone(),
+            config.remote.l2_erc20_bridge_addr,
+        );
+        tokio::spawn(replica_syncer.run(stop_receiver.clone()))
+    } else if let Some(cfg) = config.consensus.clone() {
+        let pool = background_pool.clone();
         let mut stop_receiver = stop_receiver.clone();
         let sync_state = sync_state.clone();
 
@@ -191,12 +293,14 @@ async fn init_tasks(
             let main_node_client = main_node_client;
             scope::run!(&ctx::root(), |ctx, s| async {
                 s.spawn_bg(async {
-                    let res = cfg.run(ctx, pool, action_queue_sender).await;
+                    let res = cfg
+                        .run(ctx, pool, action_queue_sender, sync_state.clone())
+                        .await;
                     tracing::info!("Consensus actor stopped");
                     res
                 });
-                // TODO: information about the head block of the validators (currently just the main node)
-                //   should also be provided over the gossip network.
+                // The main node head is mostly learned from the blocks received over the gossip network;
+                // polling the main node is only needed to track the lag while the node catches up.
                 s.spawn_bg(async {
                     consensus::run_main_node_state_fetcher(ctx, &main_node_client, &sync_state)
                         .await?;
@@ -216,44 +320,125 @@ async fn init_tasks(
         let mut storage = pool.access_storage_tagged("sync_layer").await?;
         let fetcher = MainNodeFetcher::new(
             &mut storage,
-            Box::new(main_node_client),
+            build_fetcher_client(config, main_node_client)?,
             action_queue_sender,
             sync_state.clone(),
             stop_receiver.clone(),
         )
         .await
-        .context("failed initializing main node fetcher")?;
+        .context("failed initializing main node fetcher")?
+        .with_concurrency(
+            config.optional.fetcher_max_concurrency,
+            config.optional.fetcher_target_latency(),
+        );
         tokio::spawn(fetcher.run())
     };
 
-    let metadata_calculator_config = MetadataCalculatorConfig {
-        db_path: config.required.merkle_tree_path.clone(),
-        mode: MerkleTreeMode::Full,
-        delay_interval: config.optional.metadata_calculator_delay(),
-        max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
-        multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
-        block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
-        memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
-        stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+    let metadata_calculator = if merkle_tree_enabled {
+        let integrity_check =
+            config
+                .optional
+                .merkle_tree_integrity_check_interval()
+                .map(|interval| MerkleTreeIntegrityCheckConfig {
+                    interval,
+                    subtree_count: config.optional.merkle_tree_integrity_check_subtree_count,
+                    auto_repair: config.optional.merkle_tree_integrity_auto_repair,
+                });
+        let metadata_calculator_config = MetadataCalculatorConfig {
+            db_path: config.required.merkle_tree_path.clone(),
+            mode: MerkleTreeMode::Full,
+            delay_interval: config.optional.metadata_calculator_delay(),
+            max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
+            multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
+            block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
+            memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
+            stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+            thread_pool_size: config.optional.merkle_tree_thread_pool_size,
+            rocksdb_profile: config.optional.merkle_tree_rocksdb_profile(),
+            pruning: config.optional.merkle_tree_pruning_enabled().then(|| {
+                MerkleTreePruningConfig {
+                    retained_versions: config.optional.merkle_tree_retained_versions(),
+                    poll_interval: Duration::from_secs(60),
+                    compaction_interval: config.optional.merkle_tree_compaction_interval(),
+                }
+            }),
+            integrity_check,
+        };
+        let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
+            .await
+            .context("failed initializing metadata calculator")?;
+        healthchecks.push(Box::new(metadata_calculator.tree_health_check()));
+        if let Some(integrity_health_check) = metadata_calculator.tree_integrity_health_check() {
+            healthchecks.push(Box::new(integrity_health_check));
+        }
+        let tree_max_lag = config
+            .optional
+            .healthcheck_tree_max_lag
+            .or(config.optional.merkle_tree_async_max_lag);
+        healthchecks.push(Box::new(TreeLagHealthCheck::new(
+            connection_pool.clone(),
+            tree_max_lag,
+        )));
+        Some(metadata_calculator)
+    } else {
+        // Allows to distinguish nodes without the tree from nodes with a stalled tree.
+        healthchecks.push(Box::new(DisabledTreeHealthCheck));
+        None
     };
-    let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
-        .await
-        .context("failed initializing metadata calculator")?;
-    healthchecks.push(Box::new(metadata_calculator.tree_health_check()));
 
-    let consistency_checker = ConsistencyChecker::new(
-        &config
-            .required
-            .eth_client_url()
-            .context("L1 client URL is incorrect")?,
-        10, // TODO (BFT-97): Make it a part of a proper EN config
-        singleton_pool_builder
-            .build()
-            .await
-            .context("failed to build connection pool for ConsistencyChecker")?,
-    );
-    healthchecks.push(Box::new(consistency_checker.health_check().clone()));
-    let consistency_checker_handle = tokio::spawn(consistency_checker.run(stop_receiver.clone()));
+    let consistency_checker_handle = if merkle_tree_enabled {
+        let mut consistency_checker = ConsistencyChecker::new(
+            &config
+                .required
+                .eth_client_url()
+                .context("L1 client URL is incorrect")?,
+            10, // TODO (BFT-97): Make it a part of a proper EN config
+            singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build connection pool for ConsistencyChecker")?,
+        )
+        .with_diamond_proxy_addr(config.remote.diamond_proxy_addr);
+        if let Some(address) = config.remote.validator_timelock_addr {
+            consistency_checker = consistency_checker.with_validator_timelock_addr(address);
+        }
+        if config.optional.consistency_checker_bail_on_mismatch {
+            consistency_checker = consistency_checker.bail_on_l1_data_mismatch();
+        }
+        healthchecks.push(Box::new(consistency_checker.health_check().clone()));
+        Some(tokio::spawn(consistency_checker.run(stop_receiver.clone())))
+    } else {
+        None
+    };
+
+    let da_verifier_handle = if !merkle_tree_enabled {
+        None
+    } else if let Some(da_verifier) = build_da_verifier(config).await? {
+        healthchecks.push(Box::new(da_verifier.health_check().clone()));
+        Some(tokio::spawn(da_verifier.run(stop_receiver.clone())))
+    } else {
+        None
+    };
+
+    let proof_verifier_handle = if merkle_tree_enabled && config.optional.proof_verification_enabled
+    {
+        let proof_verifier = ProofVerifier::new(
+            &config
+                .required
+                .eth_client_url()
+                .context("L1 client URL is incorrect")?,
+            config.remote.diamond_proxy_addr,
+            10,
+            singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build connection pool for ProofVerifier")?,
+        );
+        healthchecks.push(Box::new(proof_verifier.health_check().clone()));
+        Some(tokio::spawn(proof_verifier.run(stop_receiver.clone())))
+    } else {
+        None
+    };
 
     let batch_status_updater = BatchStatusUpdater::new(
         &main_node_url,
@@ -266,33 +451,123 @@ async fn init_tasks(
     healthchecks.push(Box::new(batch_status_updater.health_check()));
 
     // Run the components.
-    let tree_stop_receiver = stop_receiver.clone();
-    let tree_pool = singleton_pool_builder
-        .build()
-        .await
-        .context("failed to build a tree_pool")?;
-    let tree_handle = task::spawn(metadata_calculator.run(tree_pool, tree_stop_receiver));
+    let tree_handle = if let Some(metadata_calculator) = metadata_calculator {
+        let tree_stop_receiver = stop_receiver.clone();
+        let tree_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a tree_pool")?;
+        Some(task::spawn(
+            metadata_calculator.run(tree_pool, tree_stop_receiver),
+        ))
+    } else {
+        None
+    };
 
-    let commitment_generator_pool = singleton_pool_builder
-        .build()
-        .await
-        .context("failed to build a commitment_generator_pool")?;
-    let commitment_generator = CommitmentGenerator::new(commitment_generator_pool);
-    healthchecks.push(Box::new(commitment_generator.health_check()));
-    let commitment_generator_handle = tokio::spawn(commitment_generator.run(stop_receiver.clone()));
+    let commitment_generator_handle = if merkle_tree_enabled {
+        let commitment_generator_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a commitment_generator_pool")?;
+        let commitment_generator = CommitmentGenerator::new(commitment_generator_pool);
+        healthchecks.push(Box::new(commitment_generator.health_check()));
+        Some(tokio::spawn(
+            commitment_generator.run(stop_receiver.clone()),
+        ))
+    } else {
+        None
+    };
+
+    let db_pruner_handle = if config.optional.pruning_enabled() {
+        let pruner_config = DbPrunerConfig {
+            data_retention: config.optional.pruning_data_retention(),
+            chunk_size: config.optional.pruning_chunk_size,
+            interval: Duration::from_secs(60),
+            dry_run: config.optional.pruning_dry_run,
+            storage_logs_pruning: if config.optional.pruning_retain_miniblock_state {
+                StorageLogsPruning::WithinMiniblocks
+            } else {
+                StorageLogsPruning::Overwritten
+            },
+        };
+        let pruner_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a db_pruner_pool")?;
+        let db_pruner = DbPruner::new(pruner_config, pruner_pool);
+        healthchecks.push(Box::new(db_pruner.health_check()));
+        Some(tokio::spawn(db_pruner.run(stop_receiver.clone())))
+    } else {
+        None
+    };
+
+    let db_partition_manager_handle = if config.optional.database_partitioning_enabled() {
+        let partition_manager_config = DbPartitionManagerConfig {
+            partition_size: config.optional.database_partition_size,
+            partitions_ahead: NonZeroU32::new(2).unwrap(),
+            interval: Duration::from_secs(60),
+        };
+        let partition_manager_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a db_partition_manager_pool")?;
+        let partition_manager =
+            DbPartitionManager::new(partition_manager_config, partition_manager_pool);
+        Some(tokio::spawn(partition_manager.run(stop_receiver.clone())))
+    } else {
+        None
+    };
+
+    let cdc_publisher_handle = if let Some(cdc_publisher_config) = config.cdc_publisher.clone() {
+        let broker = create_broker(&cdc_publisher_config)
+            .await
+            .context("failed to create CDC broker client")?;
+        let cdc_publisher_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a cdc_publisher_pool")?;
+        let cdc_publisher = CdcPublisher::new(broker, cdc_publisher_pool, cdc_publisher_config);
+        Some(tokio::spawn(cdc_publisher.run(stop_receiver.clone())))
+    } else {
+        None
+    };
+
+    // Read replicas check content hashes of miniblocks before persisting them.
+    let content_hash_checker_handle =
+        if executes_transactions && config.optional.content_hash_checks_enabled {
+            let client = <dyn MainNodeClient>::json_rpc(&main_node_url)
+                .context("Failed creating JSON-RPC client for main node")?;
+            let content_hash_checker_pool = singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build a content_hash_checker_pool")?;
+            let content_hash_checker = ContentHashChecker::new(
+                with_signature_verification(config, Box::new(client)),
+                content_hash_checker_pool,
+            );
+            Some(tokio::spawn(
+                content_hash_checker.run(stop_receiver.clone()),
+            ))
+        } else {
+            None
+        };
 
     let updater_handle = task::spawn(batch_status_updater.run(stop_receiver.clone()));
-    let fee_address_migration_handle =
-        task::spawn(state_keeper.run_fee_address_migration(connection_pool.clone()));
-    let sk_handle = task::spawn(state_keeper.run());
+    if let Some(state_keeper) = state_keeper {
+        let fee_address_migration_handle =
+            task::spawn(state_keeper.run_fee_address_migration(background_pool));
+        task_handles.extend([
+            fee_address_migration_handle,
+            task::spawn(state_keeper.run()),
+        ]);
+    }
     let fee_params_fetcher_handle =
         tokio::spawn(fee_params_fetcher.clone().run(stop_receiver.clone()));
 
-    let (tx_sender, vm_barrier, cache_update_handle) = {
-        let tx_sender_builder =
-            TxSenderBuilder::new(config.clone().into(), connection_pool.clone())
-                .with_main_connection_pool(connection_pool.clone())
-                .with_tx_proxy(&main_node_url);
+    let (tx_sender, vm_barrier, cache_update_handle, api_state_cache_handle, warm_pool_handle) = {
+        let mut tx_sender_builder = TxSenderBuilder::new(config.clone().into(), api_pool.clone())
+            .with_main_connection_pool(api_pool.clone())
+            .with_tx_proxy(&main_node_url);
 
         if config.optional.transactions_per_sec_limit.is_some() {
             tracing::warn!("`transactions_per_sec_limit` option is deprecated and ignored");
@@ -308,11 +583,40 @@ async fn init_tasks(
         let cache_update_handle = (latest_values_cache_size > 0).then(|| {
             task::spawn_blocking(storage_caches.configure_storage_values_cache(
                 latest_values_cache_size,
-                connection_pool.clone(),
+                api_pool.clone(),
                 tokio::runtime::Handle::current(),
             ))
         });
 
+        let mut api_state_cache = None;
+        let api_state_cache_handle = config
+            .optional
+            .api_state_cache_path
+            .as_ref()
+            .map(|db_path| {
+                let (state_cache, updater) = ApiStateCache::new(
+                    db_path.into(),
+                    config.optional.state_keeper_db_block_cache_size(),
+                    &config.optional.state_keeper_rocksdb_profile(),
+                );
+                api_state_cache = Some(state_cache.clone());
+                tx_sender_builder = tx_sender_builder.with_state_cache(state_cache);
+                task::spawn(updater.run(api_pool.clone(), stop_receiver.clone()))
+            });
+
+        let warm_pool_handle = config.optional.sandbox_warm_pool_enabled().then(|| {
+            let (warm_pool, mut updater) = SandboxWarmPool::new();
+            if let Some(state_cache) = api_state_cache {
+                updater = updater.with_state_cache(state_cache);
+            }
+            tx_sender_builder = tx_sender_builder.with_warm_pool(warm_pool);
+            task::spawn(updater.run(api_pool.clone(), stop_receiver.clone()))
+        });
+
+        if let Some(tree_api_url) = &config.optional.archive_tree_api_url {
+            tx_sender_builder = tx_sender_builder.with_archive_tree_api(tree_api_url);
+        }
+
         let tx_sender = tx_sender_builder
             .build(
                 fee_params_fetcher,
@@ -321,36 +625,47 @@ async fn init_tasks(
                 storage_caches,
             )
             .await;
-        (tx_sender, vm_barrier, cache_update_handle)
+        (
+            tx_sender,
+            vm_barrier,
+            cache_update_handle,
+            api_state_cache_handle,
+            warm_pool_handle,
+        )
     };
 
     let http_server_handles =
-        ApiBuilder::jsonrpsee_backend(config.clone().into(), connection_pool.clone())
+        ApiBuilder::jsonrpsee_backend(config.clone().into(), api_pool.clone())
             .http(config.required.http_port)
             .with_filter_limit(config.optional.filters_limit)
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
             .with_tx_sender(tx_sender.clone(), vm_barrier.clone())
             .with_sync_state(sync_state.clone())
+            .with_merkle_tree_disabled(!merkle_tree_enabled)
             .enable_api_namespaces(config.optional.api_namespaces())
             .build(stop_receiver.clone())
             .await
             .context("Failed initializing HTTP JSON-RPC server")?;
 
-    let ws_server_handles =
-        ApiBuilder::jsonrpsee_backend(config.clone().into(), connection_pool.clone())
-            .ws(config.required.ws_port)
-            .with_filter_limit(config.optional.filters_limit)
-            .with_subscriptions_limit(config.optional.subscriptions_limit)
-            .with_batch_request_size_limit(config.optional.max_batch_request_size)
-            .with_response_body_size_limit(config.optional.max_response_body_size())
-            .with_polling_interval(config.optional.polling_interval())
-            .with_tx_sender(tx_sender, vm_barrier)
-            .with_sync_state(sync_state)
-            .enable_api_namespaces(config.optional.api_namespaces())
-            .build(stop_receiver.clone())
-            .await
-            .context("Failed initializing WS JSON-RPC server")?;
+    let ws_server_handles = ApiBuilder::jsonrpsee_backend(config.clone().into(), api_pool.clone())
+        .ws(config.required.ws_port)
+        .with_filter_limit(config.optional.filters_limit)
+        .with_subscriptions_limit(config.optional.subscriptions_limit)
+        .with_pubsub_lag_thresholds(
+            config.optional.pubsub_lag_warning_threshold,
+            config.optional.pubsub_lag_close_threshold,
+        )
+        .with_batch_request_size_limit(config.optional.max_batch_request_size)
+        .with_response_body_size_limit(config.optional.max_response_body_size())
+        .with_polling_interval(config.optional.polling_interval())
+        .with_tx_sender(tx_sender, vm_barrier)
+        .with_sync_state(sync_state)
+        .with_merkle_tree_disabled(!merkle_tree_enabled)
+        .enable_api_namespaces(config.optional.api_namespaces())
+        .build(stop_receiver.clone())
+        .await
+        .context("Failed initializing WS JSON-RPC server")?;
 
     healthchecks.push(Box::new(ws_server_handles.health_check));
     healthchecks.push(Box::new(http_server_handles.health_check));
@@ -373,20 +688,138 @@ async fn init_tasks(
     task_handles.extend(http_server_handles.tasks);
     task_handles.extend(ws_server_handles.tasks);
     task_handles.extend(cache_update_handle);
-    task_handles.extend([
-        sk_handle,
-        fee_address_migration_handle,
-        fetcher_handle,
-        updater_handle,
-        tree_handle,
-        consistency_checker_handle,
-        fee_params_fetcher_handle,
-        commitment_generator_handle,
-    ]);
+    task_handles.extend(api_state_cache_handle);
+    task_handles.extend(warm_pool_handle);
+    task_handles.extend(da_verifier_handle);
+    task_handles.extend(proof_verifier_handle);
+    task_handles.extend(db_pruner_handle);
+    task_handles.extend(db_partition_manager_handle);
+    task_handles.extend(cdc_publisher_handle);
+    task_handles.extend(content_hash_checker_handle);
+    task_handles.extend(tree_handle);
+    task_handles.extend(consistency_checker_handle);
+    task_handles.extend(commitment_generator_handle);
+    task_handles.extend([fetcher_handle, updater_handle, fee_params_fetcher_handle]);
 
     Ok((task_handles, healthchecks))
 }
 
+/// Wraps the main node client into a client cross-checking miniblocks with witness endpoints if they are configured.
+fn build_fetcher_client(
+    config: &ExternalNodeConfig,
+    main_node_client: HttpClient,
+) -> anyhow::Result<Box<dyn MainNodeClient>> {
+    let client = build_quorum_client(config, main_node_client)?;
+    Ok(with_signature_verification(config, client))
+}
+
+/// Wraps the client into a client verifying sequencer signatures of miniblocks if the sequencer address is configured.
+fn with_signature_verification(
+    config: &ExternalNodeConfig,
+    client: Box<dyn MainNodeClient>,
+) -> Box<dyn MainNodeClient> {
+    let Some(sequencer_address) = config.optional.sequencer_address else {
+        return client;
+    };
+    tracing::info!("Verifying that miniblocks are signed by sequencer {sequencer_address:?}");
+    Box::new(SignatureVerifyingMainNodeClient::new(
+        client,
+        sequencer_address,
+        config.remote.l2_chain_id,
+    ))
+}
+
+fn build_quorum_client(
+    config: &ExternalNodeConfig,
+    main_node_client: HttpClient,
+) -> anyhow::Result<Box<dyn MainNodeClient>> {
+    let witness_urls = config.optional.main_node_witness_urls();
+    if witness_urls.is_empty() {
+        return Ok(Box::new(main_node_client));
+    }
+
+    let witnesses = witness_urls
+        .iter()
+        .map(|url| {
+            let client = <dyn MainNodeClient>::json_rpc(url).with_context(|| {
+                format!("Failed creating JSON-RPC client for main node witness `{url}`")
+            })?;
+            Ok(Box::new(client) as Box<dyn MainNodeClient>)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let policy = config.optional.main_node_quorum_policy;
+    tracing::info!(
+        "Cross-checking miniblocks with {} main node witness(es) using {policy:?} quorum policy",
+        witnesses.len()
+    );
+    Ok(Box::new(QuorumMainNodeClient::new(
+        Box::new(main_node_client),
+        witnesses,
+        policy,
+    )))
+}
+
+/// Creates the DA verifier if a source of pubdata published outside L1 calldata is configured.
+async fn build_da_verifier(
+    config: &ExternalNodeConfig,
+) -> anyhow::Result<Option<DataAvailabilityVerifier>> {
+    let optional = &config.optional;
+    let da_client: Option<Box<dyn DataAvailabilityClient>> = match (
+        &optional.da_celestia_node_url,
+        &optional.da_celestia_namespace,
+        &optional.da_eigenda_disperser_url,
+    ) {
+        (Some(_), Some(_), Some(_)) => {
+            anyhow::bail!("only one of Celestia and EigenDA can be used for DA verification");
+        }
+        (Some(url), Some(namespace), None) => Some(Box::new(
+            CelestiaClient::new(url.clone(), optional.da_celestia_auth_token(), namespace)
+                .context("failed initializing Celestia client")?,
+        )),
+        (Some(_), None, _) | (None, Some(_), _) => {
+            anyhow::bail!("both Celestia node URL and namespace must be set for DA verification");
+        }
+        (None, None, Some(url)) => Some(Box::new(EigenDAClient::new(url.clone()))),
+        (None, None, None) => None,
+    };
+    if da_client.is_none() && optional.l1_beacon_api_url.is_none() {
+        return Ok(None);
+    }
+
+    let mut da_verifier = DataAvailabilityVerifier::new(
+        &config
+            .required
+            .eth_client_url()
+            .context("L1 client URL is incorrect")?,
+        optional.da_verifier_max_batches_to_recheck,
+        ConnectionPool::singleton(&config.postgres.database_url)
+            .build()
+            .await
+            .context("failed to build connection pool for DataAvailabilityVerifier")?,
+    );
+    if let Some(url) = &optional.l1_beacon_api_url {
+        da_verifier =
+            da_verifier.with_blob_sidecar_source(Box::new(BeaconClient::new(url.clone())));
+    }
+    if let Some(da_client) = da_client {
+        da_verifier = da_verifier.with_da_client(da_client);
+    }
+    if let Some(protocol_version) = optional.da_compression_protocol_version {
+        // The compression level only affects compression, so the default one is fine here.
+        let compression = PubdataCompression::zstd(
+            protocol_version,
+            ZstdCompressor::DEFAULT_LEVEL,
+            optional
+                .da_compression_dictionary_path
+                .as_deref()
+                .map(Path::new),
+        )
+        .context("failed initializing pubdata compression")?;
+        da_verifier = da_verifier.with_compression(compression);
+    }
+    Ok(Some(da_verifier))
+}
+
 async fn shutdown_components(
     stop_sender: watch::Sender<bool>,
     healthcheck_handle: HealthCheckHandle,
@@ -400,6 +833,162 @@ async fn shutdown_components(
     healthcheck_handle.stop().await;
 }
 
+/// Outcome of a single [`run_node()`] run.
+#[derive(Debug)]
+struct NodeRunOutcome {
+    /// Last correct L1 batch if a reorg was detected.
+    last_correct_batch: Option<L1BatchNumber>,
+    /// Whether the node was stopped by a signal.
+    stop_requested: bool,
+}
+
+/// Runs all node components until a stop signal is received, one of components exits or a reorg is detected.
+async fn run_node(
+    config: &ExternalNodeConfig,
+    connection_pool: ConnectionPool,
+    sigint_receiver: &mut oneshot::Receiver<()>,
+) -> anyhow::Result<NodeRunOutcome> {
+    let main_node_url = config
+        .required
+        .main_node_url()
+        .context("Main node URL is incorrect")?;
+    let (stop_sender, mut stop_receiver) = watch::channel(false);
+    let (task_handles, mut healthchecks) =
+        init_tasks(config, connection_pool.clone(), stop_receiver.clone())
+            .await
+            .context("init_tasks")?;
+
+    // Read replicas don't run the reorg detector; the replica syncer stops on a divergence from the main node instead.
+    let reorg_detector = config
+        .optional
+        .node_mode()
+        .executes_transactions()
+        .then(|| ReorgDetector::new(&main_node_url, connection_pool));
+    if let Some(reorg_detector) = &reorg_detector {
+        healthchecks.push(Box::new(reorg_detector.health_check().clone()));
+    }
+    let healthcheck_handle = HealthCheckHandle::spawn_server(
+        ([0, 0, 0, 0], config.required.healthcheck_port).into(),
+        healthchecks,
+    );
+    let mut reorg_detector_handle = tokio::spawn(async move {
+        if let Some(reorg_detector) = reorg_detector {
+            reorg_detector.run(stop_receiver).await
+        } else {
+            stop_receiver.wait_for(|stop| *stop).await.ok();
+            Ok(None)
+        }
+    })
+    .fuse();
+    let mut reorg_detector_result = None;
+
+    let particular_crypto_alerts = None;
+    let graceful_shutdown = None::<futures::future::Ready<()>>;
+    let tasks_allowed_to_finish = false;
+    let mut stop_requested = false;
+
+    tokio::select! {
+        _ = wait_for_tasks(task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
+        _ = sigint_receiver => {
+            tracing::info!("Stop signal received, shutting down");
+            stop_requested = true;
+        },
+        result = &mut reorg_detector_handle => {
+            tracing::info!("Reorg detector terminated, shutting down");
+            reorg_detector_result = Some(result);
+        }
+    };
+
+    // Reaching this point means that either some actor exited unexpectedly or we received a stop signal.
+    // Broadcast the stop signal to all actors and exit.
+    shutdown_components(stop_sender, healthcheck_handle).await;
+
+    if !reorg_detector_handle.is_terminated() {
+        reorg_detector_result = Some(reorg_detector_handle.await);
+    }
+    let last_correct_batch = reorg_detector_result.and_then(|result| match result {
+        Ok(Ok(last_correct_batch)) => last_correct_batch,
+        Ok(Err(err)) => {
+            tracing::error!("Reorg detector failed: {err:#}");
+            None
+        }
+        Err(err) => {
+            tracing::error!("Reorg detector panicked: {err}");
+            None
+        }
+    });
+    Ok(NodeRunOutcome {
+        last_correct_batch,
+        // If no reorg is detected, the node must stop regardless of the reason (e.g., because a component has failed).
+        stop_requested: stop_requested || last_correct_batch.is_none(),
+    })
+}
+
+/// Rolls back the node state after a detected reorg. Refuses to roll back more than
+/// `max_auto_rollback_l1_batches` L1 batches unless the rollback is confirmed via the command-line flag.
+async fn roll_back_after_reorg(
+    config: &ExternalNodeConfig,
+    opt: &Cli,
+    connection_pool: ConnectionPool,
+    last_correct_batch: L1BatchNumber,
+) -> anyhow::Result<()> {
+    let mut storage = connection_pool.access_storage().await?;
+    let sealed_l1_batch_number = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .context("Failed getting sealed L1 batch number")?
+        .context("Cannot roll back since there are no L1 batches in Postgres")?;
+    drop(storage);
+
+    let rollback_depth = sealed_l1_batch_number
+        .0
+        .saturating_sub(last_correct_batch.0);
+    if let Some(max_depth) = config.optional.max_auto_rollback_l1_batches {
+        if rollback_depth > max_depth && !opt.confirm_deep_rollback {
+            anyhow::bail!(
+                "Reorg detected: rolling back to L1 batch #{last_correct_batch} requires reverting {rollback_depth} \
+                 L1 batches, which exceeds the limit for automatic rollbacks ({max_depth}). Make sure that \
+                 the main node has indeed reverted its state, and restart the node with `--confirm-deep-rollback` \
+                 to perform the rollback"
+            );
+        }
+    }
+
+    let merkle_tree_enabled = config.optional.merkle_tree_enabled();
+    if merkle_tree_enabled && config.optional.merkle_tree_pruning_enabled() {
+        let retained_versions = config.optional.merkle_tree_retained_versions().get();
+        if u64::from(rollback_depth) >= retained_versions {
+            anyhow::bail!(
+                "Reorg detected: rolling back to L1 batch #{last_correct_batch} requires reverting {rollback_depth} \
+                 L1 batches, but the Merkle tree only retains {retained_versions} latest versions. The tree cannot \
+                 be rolled back automatically and needs to be restored manually"
+            );
+        }
+    }
+
+    tracing::info!(
+        "Performing rollback to L1 batch #{last_correct_batch} ({rollback_depth} L1 batches are reverted)"
+    );
+    let mut reverter = BlockReverter::new(
+        config.required.state_cache_path.clone(),
+        config.required.merkle_tree_path.clone(),
+        None,
+        connection_pool,
+        L1ExecutedBatchesRevert::Allowed,
+    );
+    if let Some(api_state_cache_path) = &config.optional.api_state_cache_path {
+        reverter.set_api_state_cache_path(api_state_cache_path.clone());
+    }
+    let mut flags = BlockReverterFlags::all();
+    if !merkle_tree_enabled {
+        flags.remove(BlockReverterFlags::TREE);
+    }
+    reverter.rollback_db(last_correct_batch, flags).await;
+    EN_METRICS.rollbacks.inc();
+    Ok(())
+}
+
 /// External node for zkSync Era.
 #[derive(Debug, Parser)]
 #[command(author = "Matter Labs", version)]
@@ -418,6 +1007,11 @@ struct Cli {
     /// This is an experimental and incomplete feature; do not use unless you know what you're doing.
     #[arg(long, conflicts_with = "enable_consensus")]
     enable_snapshots_recovery: bool,
+    /// Confirms a rollback after a reorg that exceeds the limit for automatic rollbacks
+    /// (`EN_MAX_AUTO_ROLLBACK_L1_BATCHES`). Should only be used after making sure that the main node
+    /// has indeed reverted its state.
+    #[arg(long)]
+    confirm_deep_rollback: bool,
 }
 
 #[tokio::main]
@@ -480,19 +1074,27 @@ async fn main() -> anyhow::Result<()> {
         &config.postgres.database_url,
         config.postgres.max_connections,
     )
+    .set_workload_quotas(config.optional.database_workload_quotas())
     .build()
     .await
     .context("failed to build a connection_pool")?;
 
     if opt.revert_pending_l1_batch {
         tracing::info!("Rolling pending L1 batch back..");
-        let reverter = BlockReverter::new(
+        let mut flags = BlockReverterFlags::all();
+        if !config.optional.merkle_tree_enabled() {
+            flags.remove(BlockReverterFlags::TREE);
+        }
+        let mut reverter = BlockReverter::new(
             config.required.state_cache_path,
             config.required.merkle_tree_path,
             None,
             connection_pool.clone(),
             L1ExecutedBatchesRevert::Allowed,
         );
+        if let Some(api_state_cache_path) = &config.optional.api_state_cache_path {
+            reverter.set_api_state_cache_path(api_state_cache_path.clone());
+        }
 
         let mut connection = connection_pool.access_storage().await?;
         let sealed_l1_batch_number = connection
@@ -506,93 +1108,51 @@ async fn main() -> anyhow::Result<()> {
         drop(connection);
 
         tracing::info!("Rolling back to l1 batch number {sealed_l1_batch_number}");
-        reverter
-            .rollback_db(sealed_l1_batch_number, BlockReverterFlags::all())
-            .await;
+        reverter.rollback_db(sealed_l1_batch_number, flags).await;
         tracing::info!(
             "Rollback successfully completed, the node has to restart to continue working"
         );
         return Ok(());
     }
 
-    let sigint_receiver = setup_sigint_handler();
+    let mut sigint_receiver = setup_sigint_handler();
     tracing::warn!("The external node is in the alpha phase, and should be used with caution.");
     tracing::info!("Started the external node");
     tracing::info!("Main node URL is: {}", main_node_url);
+    tracing::info!("Node mode is: {:?}", config.optional.node_mode());
 
     // Make sure that the node storage is initialized either via genesis or snapshot recovery.
+    let genesis_manifest = config
+        .optional
+        .genesis_manifest_path
+        .as_ref()
+        .map(|path| GenesisManifest::load(path.as_ref()))
+        .transpose()?;
     ensure_storage_initialized(
         &connection_pool,
         &main_node_client,
+        with_signature_verification(&config, Box::new(main_node_client.clone())).as_ref(),
         config.remote.l2_chain_id,
+        genesis_manifest,
         opt.enable_snapshots_recovery,
     )
     .await?;
 
-    let (stop_sender, stop_receiver) = watch::channel(false);
-    let (task_handles, mut healthchecks) =
-        init_tasks(&config, connection_pool.clone(), stop_receiver.clone())
-            .await
-            .context("init_tasks")?;
-
-    let reorg_detector = ReorgDetector::new(&main_node_url, connection_pool.clone());
-    healthchecks.push(Box::new(reorg_detector.health_check().clone()));
-    let healthcheck_handle = HealthCheckHandle::spawn_server(
-        ([0, 0, 0, 0], config.required.healthcheck_port).into(),
-        healthchecks,
-    );
-    let mut reorg_detector_handle = tokio::spawn(reorg_detector.run(stop_receiver)).fuse();
-    let mut reorg_detector_result = None;
-
-    let particular_crypto_alerts = None;
-    let graceful_shutdown = None::<futures::future::Ready<()>>;
-    let tasks_allowed_to_finish = false;
-
-    tokio::select! {
-        _ = wait_for_tasks(task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
-        _ = sigint_receiver => {
-            tracing::info!("Stop signal received, shutting down");
-        },
-        result = &mut reorg_detector_handle => {
-            tracing::info!("Reorg detector terminated, shutting down");
-            reorg_detector_result = Some(result);
-        }
-    };
-
-    // Reaching this point means that either some actor exited unexpectedly or we received a stop signal.
-    // Broadcast the stop signal to all actors and exit.
-    shutdown_components(stop_sender, healthcheck_handle).await;
-
-    if !reorg_detector_handle.is_terminated() {
-        reorg_detector_result = Some(reorg_detector_handle.await);
-    }
-    let reorg_detector_last_correct_batch = reorg_detector_result.and_then(|result| match result {
-        Ok(Ok(last_correct_batch)) => last_correct_batch,
-        Ok(Err(err)) => {
-            tracing::error!("Reorg detector failed: {err:#}");
-            None
-        }
-        Err(err) => {
-            tracing::error!("Reorg detector panicked: {err}");
-            None
+    // If a reorg is detected, the node state is rolled back, and the node is restarted to resync with the main node.
+    loop {
+        let outcome = run_node(&config, connection_pool.clone(), &mut sigint_receiver).await?;
+        let Some(last_correct_batch) = outcome.last_correct_batch else {
+            break;
+        };
+        roll_back_after_reorg(&config, &opt, connection_pool.clone(), last_correct_batch).await?;
+        if outcome.stop_requested {
+            tracing::info!(
+                "Rollback successfully completed, the node has to restart to continue working"
+            );
+            break;
         }
-    });
-
-    if let Some(last_correct_batch) = reorg_detector_last_correct_batch {
-        tracing::info!("Performing rollback to L1 batch #{last_correct_batch}");
-
-        let reverter = BlockReverter::new(
-            config.required.state_cache_path,
-            config.required.merkle_tree_path,
-            None,
-            connection_pool,
-            L1ExecutedBatchesRevert::Allowed,
-        );
-        reverter
-            .rollback_db(last_correct_batch, BlockReverterFlags::all())
-            .await;
         tracing::info!(
-            "Rollback successfully completed, the node has to restart to continue working"
+            "Rollback successfully completed; restarting the node to resync with the main node"
         );
     }
 
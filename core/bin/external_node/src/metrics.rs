@@ -1,10 +1,12 @@
-use vise::{Gauge, LabeledFamily, Metrics};
+use vise::{Counter, Gauge, LabeledFamily, Metrics};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "external_node")]
 pub(crate) struct EnMetrics {
     #[metrics(labels = ["server_version", "protocol_version"])]
     pub version: LabeledFamily<(String, Option<u16>), Gauge<u64>, 2>,
+    /// Number of automatic rollbacks performed after detected reorgs.
+    pub rollbacks: Counter,
 }
 
 #[vise::register]
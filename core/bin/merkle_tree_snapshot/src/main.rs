@@ -0,0 +1,169 @@
+//! Exporter and importer of portable Merkle tree snapshots. Snapshots allow migrating the tree
+//! between hosts without rebuilding it from Postgres; see `zksync_merkle_tree::snapshot` for details.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use zksync_config::{configs::ObservabilityConfig, DBConfig};
+use zksync_env_config::FromEnv;
+use zksync_merkle_tree::{
+    domain::ZkSyncTree,
+    recovery::MerkleTreeRecovery,
+    snapshot::{SnapshotManifest, MANIFEST_FILE_NAME},
+    Database, RocksDBWrapper,
+};
+use zksync_storage::RocksDB;
+use zksync_types::L1BatchNumber;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Merkle tree snapshot exporter / importer",
+    long_about = None
+)]
+struct Cli {
+    /// Path to the Merkle tree RocksDB instance. If not specified, the path from the database config is used.
+    #[arg(long)]
+    db_path: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exports a snapshot of the tree into a directory.
+    Export {
+        /// Specifies the version of the tree to be exported, expressed as a 0-based L1 batch number
+        /// applied to it last. If not specified, the latest tree version is exported.
+        #[arg(long = "l1-batch")]
+        l1_batch: Option<u32>,
+        /// Maximum number of tree entries in a single snapshot chunk.
+        #[arg(long, default_value_t = 1_000_000)]
+        chunk_size: usize,
+        /// Directory to export the snapshot to. Will be created if it doesn't exist.
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Imports a snapshot from a directory into an empty tree. An interrupted import can be resumed
+    /// by running this command again.
+    Import {
+        /// Directory containing the snapshot.
+        #[arg(long)]
+        input: PathBuf,
+    },
+}
+
+impl Cli {
+    fn run(self, config: &DBConfig) -> anyhow::Result<()> {
+        let db_path = self
+            .db_path
+            .unwrap_or_else(|| PathBuf::from(&config.merkle_tree.path));
+        match self.command {
+            Command::Export {
+                l1_batch,
+                chunk_size,
+                output,
+            } => export_snapshot(&db_path, l1_batch.map(L1BatchNumber), chunk_size, &output),
+            Command::Import { input } => import_snapshot(&db_path, &input),
+        }
+    }
+}
+
+fn export_snapshot(
+    db_path: &Path,
+    l1_batch_number: Option<L1BatchNumber>,
+    chunk_size: usize,
+    output: &Path,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(chunk_size > 0, "chunk size must be positive");
+    let start = Instant::now();
+    let db = RocksDB::new(db_path).context("failed opening Merkle tree RocksDB")?;
+    let tree = ZkSyncTree::new_lightweight(db.into());
+    let l1_batch_number = if let Some(number) = l1_batch_number {
+        number
+    } else {
+        let next_number = tree.next_l1_batch_number();
+        anyhow::ensure!(
+            next_number > L1BatchNumber(0),
+            "Merkle tree at {db_path:?} is empty"
+        );
+        next_number - 1
+    };
+
+    fs::create_dir_all(output).with_context(|| format!("failed creating {output:?}"))?;
+    anyhow::ensure!(
+        !output.join(MANIFEST_FILE_NAME).exists(),
+        "{output:?} already contains a snapshot"
+    );
+    tracing::info!(
+        "Exporting Merkle tree at {db_path:?} for L1 batch #{l1_batch_number} to {output:?}"
+    );
+    let manifest = tree
+        .reader()
+        .export_snapshot(l1_batch_number, output, chunk_size)?;
+    tracing::info!(
+        "Exported snapshot with {} entries and root hash {:?} in {:?}",
+        manifest.leaf_count,
+        manifest.root_hash,
+        start.elapsed()
+    );
+    Ok(())
+}
+
+fn import_snapshot(db_path: &Path, input: &Path) -> anyhow::Result<()> {
+    let start = Instant::now();
+    let manifest = SnapshotManifest::read(input)?;
+    let db = RocksDBWrapper::new(db_path).context("failed opening Merkle tree RocksDB")?;
+    if let Some(tree_manifest) = db.manifest() {
+        match tree_manifest.recovered_version() {
+            Some(version) => anyhow::ensure!(
+                version == manifest.version,
+                "Merkle tree at {db_path:?} is being recovered for version {version}, \
+                 while the snapshot has version {}",
+                manifest.version
+            ),
+            None => anyhow::bail!("Merkle tree at {db_path:?} is already initialized"),
+        }
+    }
+
+    tracing::info!(
+        "Importing snapshot for L1 batch #{} with {} entries from {input:?} to Merkle tree at {db_path:?}",
+        manifest.version,
+        manifest.leaf_count
+    );
+    let mut recovery = MerkleTreeRecovery::new(db, manifest.version);
+    recovery.import_snapshot(input, &manifest)?;
+    recovery.finalize();
+    tracing::info!(
+        "Imported snapshot with root hash {:?} in {:?}",
+        manifest.root_hash,
+        start.elapsed()
+    );
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
+    Cli::parse().run(&db_config)
+}
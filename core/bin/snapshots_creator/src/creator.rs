@@ -1,16 +1,17 @@
 //! [`SnapshotCreator`] and tightly related types.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::Context as _;
 use tokio::sync::Semaphore;
 use zksync_config::SnapshotsCreatorConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_object_store::ObjectStore;
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
 use zksync_types::{
     snapshots::{
-        uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        uniform_hashed_keys_chunk, SnapshotBlobDigest, SnapshotFactoryDependencies,
+        SnapshotFactoryDependency, SnapshotManifest, SnapshotMetadata, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     L1BatchNumber, MiniblockNumber,
 };
@@ -73,6 +74,32 @@ impl SnapshotCreator {
             .await
     }
 
+    /// Serializes and saves an object to the blob store, returning its key and digest.
+    async fn put_with_digest<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+        value: &V,
+    ) -> Result<(String, SnapshotBlobDigest), ObjectStoreError> {
+        let filename = V::encode_key(key);
+        let blob = value.serialize().map_err(ObjectStoreError::Serialization)?;
+        let digest = SnapshotBlobDigest::new(&blob);
+        self.blob_store.put_raw(V::BUCKET, &filename, blob).await?;
+        Ok((filename, digest))
+    }
+
+    /// Computes the digest of an object previously saved to the blob store.
+    async fn fetch_digest<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+    ) -> Result<SnapshotBlobDigest, ObjectStoreError> {
+        let blob = self
+            .blob_store
+            .get_raw(V::BUCKET, &V::encode_key(key))
+            .await?;
+        Ok(SnapshotBlobDigest::new(&blob))
+    }
+
+    /// Returns `Ok(None)` if processing was interrupted in tests.
     async fn process_storage_logs_single_chunk(
         &self,
         semaphore: &Semaphore,
@@ -80,11 +107,11 @@ impl SnapshotCreator {
         l1_batch_number: L1BatchNumber,
         chunk_id: u64,
         chunk_count: u64,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Option<SnapshotBlobDigest>> {
         let _permit = semaphore.acquire().await?;
         #[cfg(test)]
         if self.event_listener.on_chunk_started().should_exit() {
-            return Ok(());
+            return Ok(None);
         }
 
         let hashed_keys_range = uniform_hashed_keys_chunk(chunk_id, chunk_count);
@@ -111,9 +138,8 @@ impl SnapshotCreator {
             l1_batch_number,
             chunk_id,
         };
-        let filename = self
-            .blob_store
-            .put(key, &storage_logs_chunk)
+        let (filename, digest) = self
+            .put_with_digest(key, &storage_logs_chunk)
             .await
             .context("Error storing storage logs chunk in blob store")?;
         let output_filepath_prefix = self
@@ -138,14 +164,14 @@ impl SnapshotCreator {
             "Saved chunk {chunk_id} (overall progress {}/{chunk_count}) in {latency:?} to location: {output_filepath}",
             chunk_count - tasks_left as u64
         );
-        Ok(())
+        Ok(Some(digest))
     }
 
     async fn process_factory_deps(
         &self,
         miniblock_number: MiniblockNumber,
         l1_batch_number: L1BatchNumber,
-    ) -> anyhow::Result<String> {
+    ) -> anyhow::Result<(String, SnapshotBlobDigest)> {
         let mut conn = self.connect_to_replica().await?;
 
         tracing::info!("Loading factory deps from Postgres...");
@@ -169,9 +195,8 @@ impl SnapshotCreator {
             })
             .collect();
         let factory_deps = SnapshotFactoryDependencies { factory_deps };
-        let filename = self
-            .blob_store
-            .put(l1_batch_number, &factory_deps)
+        let (filename, digest) = self
+            .put_with_digest(l1_batch_number, &factory_deps)
             .await
             .context("Error storing factory deps in blob store")?;
         let output_filepath_prefix = self
@@ -184,7 +209,70 @@ impl SnapshotCreator {
             factory_deps.factory_deps.len()
         );
 
-        Ok(output_filepath)
+        Ok((output_filepath, digest))
+    }
+
+    /// Creates the integrity manifest for a complete snapshot. Digests of blobs saved by previous runs
+    /// of the creator are computed by fetching the blobs from the blob store.
+    async fn create_manifest(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+        factory_deps_digest: Option<SnapshotBlobDigest>,
+        mut chunk_digests: HashMap<u64, SnapshotBlobDigest>,
+        chunk_count: u64,
+    ) -> anyhow::Result<SnapshotManifest> {
+        let factory_deps = match factory_deps_digest {
+            Some(digest) => digest,
+            None => self
+                .fetch_digest::<SnapshotFactoryDependencies>(l1_batch_number)
+                .await
+                .context("Error fetching factory deps from blob store")?,
+        };
+
+        let mut storage_logs_chunks = Vec::with_capacity(chunk_count as usize);
+        for chunk_id in 0..chunk_count {
+            let digest = if let Some(digest) = chunk_digests.remove(&chunk_id) {
+                digest
+            } else {
+                let key = SnapshotStorageLogsStorageKey {
+                    l1_batch_number,
+                    chunk_id,
+                };
+                self.fetch_digest::<SnapshotStorageLogsChunk>(key)
+                    .await
+                    .with_context(|| {
+                        format!("Error fetching storage logs chunk {chunk_id} from blob store")
+                    })?
+            };
+            storage_logs_chunks.push(digest);
+        }
+
+        Ok(SnapshotManifest {
+            l1_batch_number,
+            miniblock_number,
+            factory_deps,
+            storage_logs_chunks,
+        })
+    }
+
+    /// Marks the snapshot requested via the admin API (if any) as processed.
+    async fn mark_snapshot_request_as_processed(
+        conn: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let request = conn
+            .snapshots_dal()
+            .mark_snapshot_request_as_processed(l1_batch_number)
+            .await?;
+        if let Some(request) = request {
+            tracing::info!(
+                "Processed snapshot request #{} made at {} with snapshot at L1 batch #{l1_batch_number}",
+                request.id,
+                request.requested_at
+            );
+        }
+        Ok(())
     }
 
     /// Returns `Ok(None)` if the created snapshot would coincide with `latest_snapshot`.
@@ -262,7 +350,7 @@ impl SnapshotCreator {
     }
 
     pub async fn run(
-        self,
+        &self,
         config: SnapshotsCreatorConfig,
         min_chunk_count: u64,
     ) -> anyhow::Result<()> {
@@ -277,6 +365,21 @@ impl SnapshotCreator {
             .await?
         else {
             // No snapshot creation is necessary; a snapshot for the current L1 batch is already created
+            let mut master_conn = self
+                .master_pool
+                .access_storage_tagged("snapshots_creator")
+                .await?;
+            let latest_snapshot = master_conn
+                .snapshots_dal()
+                .get_newest_snapshot_metadata()
+                .await?;
+            if let Some(snapshot) = latest_snapshot {
+                Self::mark_snapshot_request_as_processed(
+                    &mut master_conn,
+                    snapshot.l1_batch_number,
+                )
+                .await?;
+            }
             return Ok(());
         };
 
@@ -295,8 +398,9 @@ impl SnapshotCreator {
             progress.l1_batch_number
         );
 
+        let mut factory_deps_digest = None;
         if progress.is_new_snapshot {
-            let factory_deps_output_file = self
+            let (factory_deps_output_file, digest) = self
                 .process_factory_deps(last_miniblock_number_in_batch, progress.l1_batch_number)
                 .await?;
 
@@ -312,13 +416,14 @@ impl SnapshotCreator {
                     &factory_deps_output_file,
                 )
                 .await?;
+            factory_deps_digest = Some(digest);
         }
 
         METRICS
             .storage_logs_chunks_left_to_process
             .set(progress.remaining_chunk_ids.len());
         let semaphore = Semaphore::new(config.concurrent_queries_count as usize);
-        let tasks = progress.remaining_chunk_ids.into_iter().map(|chunk_id| {
+        let tasks = progress.remaining_chunk_ids.iter().map(|&chunk_id| {
             self.process_storage_logs_single_chunk(
                 &semaphore,
                 last_miniblock_number_in_batch,
@@ -327,7 +432,39 @@ impl SnapshotCreator {
                 progress.chunk_count,
             )
         });
-        futures::future::try_join_all(tasks).await?;
+        let digests = futures::future::try_join_all(tasks).await?;
+        let Some(digests) = digests.into_iter().collect::<Option<Vec<_>>>() else {
+            return Ok(()); // processing was interrupted in tests
+        };
+        let chunk_digests = progress.remaining_chunk_ids.into_iter().zip(digests);
+
+        let manifest = self
+            .create_manifest(
+                progress.l1_batch_number,
+                last_miniblock_number_in_batch,
+                factory_deps_digest,
+                chunk_digests.collect(),
+                progress.chunk_count,
+            )
+            .await?;
+        let (_, manifest_digest) = self
+            .put_with_digest(progress.l1_batch_number, &manifest)
+            .await
+            .context("Error storing snapshot manifest in blob store")?;
+        // Snapshot consumers can only detect a tampered object store if they obtain the manifest hash
+        // from a trusted source, so the hash should be published by the operator.
+        tracing::info!(
+            "Saved manifest for snapshot at L1 batch {} with hash {:?}",
+            progress.l1_batch_number,
+            manifest_digest.hash
+        );
+        let mut master_conn = self
+            .master_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+        Self::mark_snapshot_request_as_processed(&mut master_conn, progress.l1_batch_number)
+            .await?;
+        drop(master_conn);
 
         METRICS
             .snapshot_l1_batch
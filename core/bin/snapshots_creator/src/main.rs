@@ -1,4 +1,10 @@
 //! Snapshot creator utility. Intended to run on a schedule, with each run creating a new snapshot.
+//! Alternatively, the creator can run as a long-living service creating snapshots periodically
+//! (see `SnapshotsCreatorConfig::creation_interval_sec`). In the latter case, a snapshot can also be requested
+//! ahead of schedule via the `admin_requestSnapshot` RPC method.
+//!
+//! Once all snapshot blobs are saved, the creator saves a manifest with digests of all blobs,
+//! which allows snapshot consumers to check snapshot integrity.
 //!
 //! # Assumptions
 //!
@@ -9,6 +15,8 @@
 //! It is assumed that the snapshot creator is run as a singleton process (no more than 1 instance
 //! at a time).
 
+use std::time::Duration;
+
 use anyhow::Context as _;
 use prometheus_exporter::PrometheusExporterConfig;
 use tokio::{sync::watch, task::JoinHandle};
@@ -48,6 +56,41 @@ async fn maybe_enable_prometheus_metrics(
 
 /// Minimum number of storage log chunks to produce.
 const MIN_CHUNK_COUNT: u64 = 10;
+/// Interval between checks for snapshots requested via the admin API.
+const SNAPSHOT_REQUEST_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Waits until `creation_interval` elapses, or a snapshot is requested via the admin API.
+async fn wait_for_next_snapshot(pool: &ConnectionPool, creation_interval: Duration) {
+    let deadline = tokio::time::Instant::now() + creation_interval;
+    loop {
+        let request = match pool.access_storage_tagged("snapshots_creator").await {
+            Ok(mut conn) => conn
+                .snapshots_dal()
+                .get_pending_snapshot_request()
+                .await
+                .map_err(anyhow::Error::from),
+            Err(err) => Err(err),
+        };
+        match request {
+            Ok(Some(request)) => {
+                tracing::info!(
+                    "Snapshot #{} was requested at {}; creating snapshot",
+                    request.id,
+                    request.requested_at
+                );
+                return;
+            }
+            Ok(None) => { /* continue waiting */ }
+            Err(err) => tracing::warn!("Failed checking pending snapshot requests: {err:#}"),
+        }
+
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return;
+        }
+        tokio::time::sleep((deadline - now).min(SNAPSHOT_REQUEST_POLL_INTERVAL)).await;
+    }
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -94,12 +137,28 @@ async fn main() -> anyhow::Result<()> {
 
     let creator = SnapshotCreator {
         blob_store,
-        master_pool,
+        master_pool: master_pool.clone(),
         replica_pool,
         #[cfg(test)]
         event_listener: Box::new(()),
     };
-    creator.run(creator_config, MIN_CHUNK_COUNT).await?;
+    if let Some(creation_interval) = creator_config.creation_interval() {
+        loop {
+            if let Err(err) = creator.run(creator_config.clone(), MIN_CHUNK_COUNT).await {
+                tracing::error!("Failed creating snapshot: {err:#}");
+            }
+            tracing::info!("Next snapshot creation attempt in {creation_interval:?}");
+            tokio::select! {
+                () = wait_for_next_snapshot(&master_pool, creation_interval) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("Stop signal received, snapshot creator is shutting down");
+                    break;
+                }
+            }
+        }
+    } else {
+        creator.run(creator_config, MIN_CHUNK_COUNT).await?;
+    }
 
     tracing::info!("Finished running snapshot creator!");
     stop_sender.send(true).ok();
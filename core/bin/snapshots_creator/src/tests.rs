@@ -11,12 +11,13 @@ use std::{
 
 use rand::{thread_rng, Rng};
 use zksync_dal::StorageProcessor;
-use zksync_object_store::ObjectStore;
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotBlobDigest, SnapshotFactoryDependencies, SnapshotFactoryDependency,
+        SnapshotManifest, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersion, StorageKey,
     StorageLog, H256,
@@ -27,10 +28,12 @@ use super::*;
 const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
+    creation_interval_sec: None,
 };
 const SEQUENTIAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 1,
+    creation_interval_sec: None,
 };
 
 #[derive(Debug)]
@@ -288,6 +291,47 @@ async fn persisting_snapshot_metadata() {
     }
 }
 
+#[tokio::test]
+async fn processing_snapshot_request() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+    let request = conn
+        .snapshots_dal()
+        .request_snapshot()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let creator = SnapshotCreator::for_tests(object_store, pool.clone());
+    creator.run(TEST_CONFIG, MIN_CHUNK_COUNT).await.unwrap();
+    let pending_request = conn
+        .snapshots_dal()
+        .get_pending_snapshot_request()
+        .await
+        .unwrap();
+    assert_eq!(pending_request, None);
+
+    // A request made when the snapshot for the current L1 batch already exists is processed immediately.
+    let new_request = conn
+        .snapshots_dal()
+        .request_snapshot()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_ne!(new_request.id, request.id);
+    creator.run(TEST_CONFIG, MIN_CHUNK_COUNT).await.unwrap();
+    let pending_request = conn
+        .snapshots_dal()
+        .get_pending_snapshot_request()
+        .await
+        .unwrap();
+    assert_eq!(pending_request, None);
+}
+
 #[tokio::test]
 async fn persisting_snapshot_factory_deps() {
     let pool = ConnectionPool::test_pool().await;
@@ -327,6 +371,7 @@ async fn persisting_snapshot_logs() {
 
     let object_store = object_store_factory.create_store().await;
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
+    assert_manifest(&*object_store, snapshot_l1_batch_number).await;
 }
 
 async fn assert_storage_logs(
@@ -346,6 +391,40 @@ async fn assert_storage_logs(
     assert_eq!(actual_logs, expected_outputs.storage_logs);
 }
 
+async fn assert_manifest(object_store: &dyn ObjectStore, snapshot_l1_batch_number: L1BatchNumber) {
+    let manifest: SnapshotManifest = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(manifest.l1_batch_number, snapshot_l1_batch_number);
+    assert_eq!(manifest.miniblock_number, MiniblockNumber(8));
+
+    let factory_deps = object_store
+        .get_raw(
+            SnapshotFactoryDependencies::BUCKET,
+            &SnapshotFactoryDependencies::encode_key(snapshot_l1_batch_number),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        manifest.factory_deps,
+        SnapshotBlobDigest::new(&factory_deps)
+    );
+
+    assert_eq!(manifest.storage_logs_chunks.len() as u64, MIN_CHUNK_COUNT);
+    for (chunk_id, digest) in manifest.storage_logs_chunks.iter().enumerate() {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot_l1_batch_number,
+            chunk_id: chunk_id as u64,
+        };
+        let chunk = object_store
+            .get_raw(
+                SnapshotStorageLogsChunk::BUCKET,
+                &SnapshotStorageLogsChunk::encode_key(key),
+            )
+            .await
+            .unwrap();
+        assert_eq!(*digest, SnapshotBlobDigest::new(&chunk));
+    }
+}
+
 #[tokio::test]
 async fn recovery_workflow() {
     let pool = ConnectionPool::test_pool().await;
@@ -400,9 +479,15 @@ async fn recovery_workflow() {
             .count(),
         2
     );
+    // The manifest must only be created for complete snapshots.
+    let object_store = object_store_factory.create_store().await;
+    let err = object_store
+        .get::<SnapshotManifest>(snapshot_l1_batch_number)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
 
     // Process the remaining chunks.
-    let object_store = object_store_factory.create_store().await;
     SnapshotCreator::for_tests(object_store, pool.clone())
         .run(SEQUENTIAL_TEST_CONFIG, MIN_CHUNK_COUNT)
         .await
@@ -410,6 +495,8 @@ async fn recovery_workflow() {
 
     let object_store = object_store_factory.create_store().await;
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
+    // Digests of chunks created by the previous runs should be correctly restored.
+    assert_manifest(&*object_store, snapshot_l1_batch_number).await;
 }
 
 #[tokio::test]
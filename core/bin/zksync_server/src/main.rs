@@ -14,8 +14,11 @@ use zksync_config::{
         FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, ObservabilityConfig,
         PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
     },
-    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
-    GasAdjusterConfig, ObjectStoreConfig, PostgresConfig,
+    ApiConfig, AuditLogConfig, CdcPublisherConfig, ConfigReloadConfig, ContractsConfig, DBConfig,
+    ETHClientConfig, ETHSenderConfig, ETHWatchConfig, FeeDistributorConfig, FirehoseConfig,
+    GasAdjusterConfig, LeaderElectionConfig, MessageRelayConfig, ObjectStoreConfig, PostgresConfig,
+    ReexecutionWatchdogConfig, RosettaApiConfig, SharedSequencerConfig, StableGasPriceConfig,
+    SupervisorConfig, SupplyCheckerConfig, WebhooksConfig, WithdrawalFinalizerConfig,
 };
 use zksync_core::{
     genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
@@ -50,6 +53,22 @@ struct Cli {
         default_value = "api,tree,eth,state_keeper,housekeeper,basic_witness_input_producer,commitment_generator"
     )]
     components: ComponentsToRun,
+    /// Run a single-process in-memory node without Postgres instead of the full set of components.
+    #[cfg(feature = "in-memory-node")]
+    #[arg(long, conflicts_with_all = ["genesis", "rebuild_tree"])]
+    in_memory: bool,
+    /// Path to the genesis manifest with predeploys and initial balances for the in-memory node.
+    #[cfg(feature = "in-memory-node")]
+    #[arg(long, requires = "in_memory")]
+    in_memory_genesis: Option<std::path::PathBuf>,
+    /// URL of the JSON-RPC API of a remote node to fork the in-memory node from.
+    #[cfg(feature = "in-memory-node")]
+    #[arg(long, requires = "in_memory", conflicts_with = "in_memory_genesis")]
+    in_memory_fork_url: Option<String>,
+    /// Number of the remote block to fork the in-memory node at. If not specified, the latest block is used.
+    #[cfg(feature = "in-memory-node")]
+    #[arg(long, requires = "in_memory_fork_url")]
+    in_memory_fork_block: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -95,6 +114,11 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("No sentry URL was provided");
     }
 
+    #[cfg(feature = "in-memory-node")]
+    if opt.in_memory {
+        return run_in_memory_node(&opt).await;
+    }
+
     // TODO (QIT-22): Only deserialize configs on demand.
     // Right now, we are trying to deserialize all the configs that may be needed by `zksync_core`.
     // "May" is the key word here, since some configs are only used by certain component configuration,
@@ -126,6 +150,21 @@ async fn main() -> anyhow::Result<()> {
         gas_adjuster_config: GasAdjusterConfig::from_env().ok(),
         object_store_config: ObjectStoreConfig::from_env().ok(),
         consensus_config: None,
+        cdc_publisher_config: CdcPublisherConfig::from_env().ok(),
+        withdrawal_finalizer_config: WithdrawalFinalizerConfig::from_env().ok(),
+        message_relay_config: MessageRelayConfig::from_env().ok(),
+        fee_distributor_config: FeeDistributorConfig::from_env().ok(),
+        supply_checker_config: SupplyCheckerConfig::from_env().ok(),
+        reexecution_watchdog_config: ReexecutionWatchdogConfig::from_env().ok(),
+        stable_gas_price_config: StableGasPriceConfig::from_env().ok(),
+        webhooks_config: WebhooksConfig::from_env().ok(),
+        audit_log_config: AuditLogConfig::from_env().ok(),
+        leader_election_config: LeaderElectionConfig::from_env().ok(),
+        shared_sequencer_config: SharedSequencerConfig::from_env().ok(),
+        rosetta_api_config: RosettaApiConfig::from_env().ok(),
+        firehose_config: FirehoseConfig::from_env().ok(),
+        config_reload_config: ConfigReloadConfig::from_env().ok(),
+        supervisor_config: SupervisorConfig::from_env().ok(),
     };
 
     if opt.components.0.contains(&Component::Consensus) {
@@ -197,3 +236,43 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Stopped");
     Ok(())
 }
+
+#[cfg(feature = "in-memory-node")]
+async fn run_in_memory_node(opt: &Cli) -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use zksync_core::{
+        genesis::GenesisManifest,
+        in_memory_node::{ForkSource, InMemoryNode, InMemoryNodeConfig},
+    };
+    use zksync_types::{L2ChainId, MiniblockNumber};
+
+    let mut config = InMemoryNodeConfig::default();
+    if let Some(path) = &opt.in_memory_genesis {
+        let manifest = GenesisManifest::load(path)?;
+        config.chain_id = L2ChainId::try_from(manifest.chain_id)
+            .map_err(|err| anyhow::anyhow!(err))
+            .context("invalid chain ID in genesis manifest")?;
+        config.genesis = Some(manifest);
+    }
+    let node = if let Some(fork_url) = &opt.in_memory_fork_url {
+        let source = <dyn ForkSource>::json_rpc(fork_url).context("failed creating fork client")?;
+        let fork_block = opt.in_memory_fork_block.map(MiniblockNumber);
+        InMemoryNode::fork(config, Arc::new(source), fork_block)
+            .await
+            .context("failed initializing forked in-memory node")?
+    } else {
+        InMemoryNode::new(config).context("failed initializing in-memory node")?
+    };
+
+    let (stop_sender, stop_receiver) = tokio::sync::watch::channel(false);
+    let mut node_task = tokio::spawn(node.run(stop_receiver));
+    tokio::select! {
+        result = &mut node_task => return result.context("in-memory node panicked")?,
+        _ = setup_sigint_handler() => {
+            tracing::info!("Stop signal received, shutting down");
+        },
+    }
+    stop_sender.send(true).ok();
+    node_task.await.context("in-memory node panicked")?
+}
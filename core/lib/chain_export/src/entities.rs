@@ -0,0 +1,208 @@
+//! Exported entities and their conversion to tables.
+
+use vise::{EncodeLabelSet, EncodeLabelValue};
+use zksync_dal::chain_export_dal::{
+    ExportedEvent, ExportedMiniblock, ExportedTransaction, ExportedTransfer,
+};
+use zksync_types::U256;
+
+use crate::table::{ColumnType, Table, Value};
+
+/// Kind of exported chain data. Each kind is exported to separate files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "entity", rename_all = "snake_case")]
+pub enum ExportedEntity {
+    Blocks,
+    Transactions,
+    Receipts,
+    Events,
+    Transfers,
+}
+
+impl ExportedEntity {
+    pub const ALL: [Self; 5] = [
+        Self::Blocks,
+        Self::Transactions,
+        Self::Receipts,
+        Self::Events,
+        Self::Transfers,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Blocks => "blocks",
+            Self::Transactions => "transactions",
+            Self::Receipts => "receipts",
+            Self::Events => "events",
+            Self::Transfers => "transfers",
+        }
+    }
+
+    pub(crate) fn columns(self) -> &'static [(&'static str, ColumnType)] {
+        use ColumnType::{Bool, Int64, Utf8};
+
+        match self {
+            Self::Blocks => &[
+                ("number", Int64),
+                ("hash", Utf8),
+                ("timestamp", Int64),
+                ("l1_batch_number", Int64),
+                ("l1_tx_count", Int64),
+                ("l2_tx_count", Int64),
+                ("base_fee_per_gas", Utf8),
+                ("protocol_version", Int64),
+                ("fee_account_address", Utf8),
+            ],
+            Self::Transactions => &[
+                ("hash", Utf8),
+                ("block_number", Int64),
+                ("index_in_block", Int64),
+                ("from", Utf8),
+                ("to", Utf8),
+                ("nonce", Int64),
+                ("value", Utf8),
+                ("gas_limit", Utf8),
+                ("max_fee_per_gas", Utf8),
+                ("is_priority", Bool),
+                ("tx_format", Int64),
+            ],
+            Self::Receipts => &[
+                ("transaction_hash", Utf8),
+                ("block_number", Int64),
+                ("index_in_block", Int64),
+                ("status", Int64),
+                ("error", Utf8),
+                ("gas_used", Utf8),
+                ("effective_gas_price", Utf8),
+            ],
+            Self::Events => &[
+                ("block_number", Int64),
+                ("transaction_hash", Utf8),
+                ("transaction_index", Int64),
+                ("log_index", Int64),
+                ("log_index_in_transaction", Int64),
+                ("address", Utf8),
+                ("topic0", Utf8),
+                ("topic1", Utf8),
+                ("topic2", Utf8),
+                ("topic3", Utf8),
+                ("data", Utf8),
+            ],
+            Self::Transfers => &[
+                ("block_number", Int64),
+                ("transaction_hash", Utf8),
+                ("log_index", Int64),
+                ("token_address", Utf8),
+                ("from", Utf8),
+                ("to", Utf8),
+                ("value", Utf8),
+            ],
+        }
+    }
+}
+
+fn hex_value(value: impl std::fmt::Debug) -> Value {
+    // `Debug` implementations for hashes and addresses output the full `0x`-prefixed hex.
+    Value::Utf8(format!("{value:?}"))
+}
+
+fn u256_value(value: U256) -> Value {
+    Value::Utf8(value.to_string())
+}
+
+fn bytes_value(bytes: &[u8]) -> Value {
+    Value::Utf8(format!("0x{}", hex::encode(bytes)))
+}
+
+pub(crate) fn blocks_table(miniblocks: &[ExportedMiniblock]) -> Table {
+    let mut table = Table::new(ExportedEntity::Blocks.columns());
+    for block in miniblocks {
+        table.push_row(vec![
+            block.number.0.into(),
+            hex_value(block.hash),
+            block.timestamp.into(),
+            block.l1_batch_number.map(|number| number.0).into(),
+            block.l1_tx_count.into(),
+            block.l2_tx_count.into(),
+            u256_value(block.base_fee_per_gas),
+            block.protocol_version.map(u32::from).into(),
+            hex_value(block.fee_account_address),
+        ]);
+    }
+    table
+}
+
+pub(crate) fn transactions_table(transactions: &[ExportedTransaction]) -> Table {
+    let mut table = Table::new(ExportedEntity::Transactions.columns());
+    for tx in transactions {
+        table.push_row(vec![
+            hex_value(tx.hash),
+            tx.miniblock_number.0.into(),
+            tx.index_in_block.into(),
+            hex_value(tx.initiator_address),
+            tx.contract_address.map_or(Value::Null, hex_value),
+            tx.nonce.into(),
+            u256_value(tx.value),
+            tx.gas_limit.map_or(Value::Null, u256_value),
+            tx.max_fee_per_gas.map_or(Value::Null, u256_value),
+            tx.is_priority.into(),
+            tx.tx_format
+                .map_or(Value::Null, |format| Value::Int64(format.into())),
+        ]);
+    }
+    table
+}
+
+pub(crate) fn receipts_table(transactions: &[ExportedTransaction]) -> Table {
+    let mut table = Table::new(ExportedEntity::Receipts.columns());
+    for tx in transactions {
+        let status = if tx.error.is_some() { 0_u32 } else { 1 };
+        table.push_row(vec![
+            hex_value(tx.hash),
+            tx.miniblock_number.0.into(),
+            tx.index_in_block.into(),
+            status.into(),
+            tx.error.clone().into(),
+            tx.gas_used.map_or(Value::Null, u256_value),
+            tx.effective_gas_price.map_or(Value::Null, u256_value),
+        ]);
+    }
+    table
+}
+
+pub(crate) fn events_table(events: &[ExportedEvent]) -> Table {
+    let mut table = Table::new(ExportedEntity::Events.columns());
+    for event in events {
+        let topic = |i: usize| event.topics.get(i).map_or(Value::Null, hex_value);
+        table.push_row(vec![
+            event.miniblock_number.0.into(),
+            hex_value(event.tx_hash),
+            event.tx_index_in_block.into(),
+            event.event_index_in_block.into(),
+            event.event_index_in_tx.into(),
+            hex_value(event.address),
+            topic(0),
+            topic(1),
+            topic(2),
+            topic(3),
+            bytes_value(&event.data),
+        ]);
+    }
+    table
+}
+
+pub(crate) fn transfers_table(transfers: &[ExportedTransfer]) -> Table {
+    let mut table = Table::new(ExportedEntity::Transfers.columns());
+    for transfer in transfers {
+        table.push_row(vec![
+            transfer.miniblock_number.0.into(),
+            hex_value(transfer.tx_hash),
+            transfer.event_index_in_block.into(),
+            hex_value(transfer.token_address),
+            hex_value(transfer.from),
+            hex_value(transfer.to),
+            u256_value(transfer.value),
+        ]);
+    }
+    table
+}
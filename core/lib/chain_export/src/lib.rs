@@ -0,0 +1,279 @@
+//! Export of chain data (blocks, transactions, receipts, events and token transfers) to CSV or Parquet files
+//! in an object store, so that analytics tooling can consume chain data without querying the node database.
+//!
+//! # File layout
+//!
+//! Data is exported in ranges of [`ChainExportConfig::miniblocks_per_file`] miniblocks; each range produces
+//! one file per [`ExportedEntity`] in the [`Bucket::ChainExport`] bucket, named
+//! `{entity}_miniblocks_{first}_{last}.{parquet|csv}` with zero-padded miniblock numbers (so that
+//! lexicographic order of files matches the chain order). A range is exported only after all its miniblocks
+//! are sealed, so exported files are immutable. Ranges including pruned miniblocks cannot be exported.
+//!
+//! Data for a range is loaded from Postgres in small chunks of miniblocks; each chunk is encoded before the next one
+//! is loaded, so that only encoded files are retained in memory.
+//!
+//! Export progress is stored in the same bucket as a `progress.json` object. The exporter doesn't write
+//! to Postgres, so it can run against a read replica of either the main node or an external node database.
+
+use std::{collections::HashMap, ops, sync::Arc, time::Instant};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_config::{configs::chain_export::ChainExportFormat, ChainExportConfig};
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError};
+use zksync_types::MiniblockNumber;
+
+pub use crate::entities::ExportedEntity;
+use crate::{entities::*, metrics::METRICS, table::TableWriter};
+
+mod entities;
+mod metrics;
+mod table;
+#[cfg(test)]
+mod tests;
+
+const PROGRESS_KEY: &str = "progress.json";
+/// Number of miniblocks loaded from Postgres at once.
+const MINIBLOCKS_PER_CHUNK: u32 = 100;
+
+/// Persisted progress of the exporter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct ExportProgress {
+    /// First miniblock that is not exported yet.
+    next_miniblock: u32,
+}
+
+/// Exporter of chain data. See the crate-level docs for details.
+#[derive(Debug)]
+pub struct ChainExporter {
+    pool: ConnectionPool,
+    blob_store: Arc<dyn ObjectStore>,
+    config: ChainExportConfig,
+    miniblocks_per_chunk: u32,
+}
+
+impl ChainExporter {
+    /// Creates a new exporter. The `pool` should point to a read replica if possible.
+    pub fn new(
+        pool: ConnectionPool,
+        blob_store: Arc<dyn ObjectStore>,
+        config: ChainExportConfig,
+    ) -> Self {
+        Self {
+            pool,
+            blob_store,
+            config,
+            miniblocks_per_chunk: MINIBLOCKS_PER_CHUNK,
+        }
+    }
+
+    /// Returns the object store key of the file with the specified entity and miniblock range.
+    pub fn file_key(
+        entity: ExportedEntity,
+        miniblocks: &ops::RangeInclusive<MiniblockNumber>,
+        format: ChainExportFormat,
+    ) -> String {
+        let extension = match format {
+            ChainExportFormat::Parquet => "parquet",
+            ChainExportFormat::Csv => "csv",
+        };
+        format!(
+            "{entity}_miniblocks_{first:010}_{last:010}.{extension}",
+            entity = entity.name(),
+            first = miniblocks.start().0,
+            last = miniblocks.end().0
+        )
+    }
+
+    /// Exports all complete ranges of sealed miniblocks following the persisted progress. Returns the last
+    /// exported miniblock, or `None` if there was nothing to export.
+    pub async fn export_new_miniblocks(&self) -> anyhow::Result<Option<MiniblockNumber>> {
+        let mut next_miniblock = match self.load_progress().await? {
+            Some(progress) => MiniblockNumber(progress.next_miniblock),
+            None => self.first_available_miniblock().await?,
+        };
+        let mut conn = self.pool.access_storage_tagged("chain_export").await?;
+        let Some(sealed_miniblock) = conn.blocks_dal().get_sealed_miniblock_number().await? else {
+            tracing::info!("Node storage is empty; nothing to export");
+            return Ok(None);
+        };
+        drop(conn);
+
+        let miniblocks_per_file = self.config.miniblocks_per_file.max(1);
+        let mut last_exported_miniblock = None;
+        loop {
+            let range_end = next_miniblock + (miniblocks_per_file - 1);
+            if range_end > sealed_miniblock {
+                break;
+            }
+            self.export_range(next_miniblock..=range_end).await?;
+            next_miniblock = range_end + 1;
+            self.save_progress(ExportProgress {
+                next_miniblock: next_miniblock.0,
+            })
+            .await?;
+            METRICS.last_exported_miniblock.set(range_end.0.into());
+            last_exported_miniblock = Some(range_end);
+        }
+        Ok(last_exported_miniblock)
+    }
+
+    /// Exports the specified range of miniblocks to files, overwriting existing files for the range if necessary.
+    /// Does not update persisted progress, so it can be used for backfills. Returns an error if the range
+    /// includes pruned or unsealed miniblocks.
+    pub async fn export_range(
+        &self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> anyhow::Result<()> {
+        let started_at = Instant::now();
+        self.ensure_range_available(&miniblocks).await?;
+
+        let format = self.config.format;
+        let mut writers = HashMap::new();
+        for entity in ExportedEntity::ALL {
+            writers.insert(entity, TableWriter::new(entity.columns(), format)?);
+        }
+        let mut chunk_start = *miniblocks.start();
+        loop {
+            let chunk_end = chunk_start
+                .0
+                .saturating_add(self.miniblocks_per_chunk.max(1) - 1)
+                .min(miniblocks.end().0);
+            let chunk = chunk_start..=MiniblockNumber(chunk_end);
+            let mut conn = self.pool.access_storage_tagged("chain_export").await?;
+            let mut export_dal = conn.chain_export_dal();
+            let blocks = export_dal.get_miniblocks(chunk.clone()).await?;
+            let transactions = export_dal.get_transactions(chunk.clone()).await?;
+            let events = export_dal.get_events(chunk.clone()).await?;
+            let transfers = export_dal.get_transfers(chunk.clone()).await?;
+            drop(conn);
+
+            let tables = [
+                (ExportedEntity::Blocks, blocks_table(&blocks)),
+                (
+                    ExportedEntity::Transactions,
+                    transactions_table(&transactions),
+                ),
+                (ExportedEntity::Receipts, receipts_table(&transactions)),
+                (ExportedEntity::Events, events_table(&events)),
+                (ExportedEntity::Transfers, transfers_table(&transfers)),
+            ];
+            for (entity, table) in tables {
+                writers
+                    .get_mut(&entity)
+                    .unwrap()
+                    .write(&table)
+                    .with_context(|| format!("failed encoding {entity:?} for {chunk:?}"))?;
+            }
+            if chunk_end == miniblocks.end().0 {
+                break;
+            }
+            chunk_start = MiniblockNumber(chunk_end + 1);
+        }
+
+        let row_count = |entity| writers[&entity].row_count();
+        let (block_count, tx_count, event_count) = (
+            row_count(ExportedEntity::Blocks),
+            row_count(ExportedEntity::Transactions),
+            row_count(ExportedEntity::Events),
+        );
+        for (entity, writer) in writers {
+            self.save_file(entity, &miniblocks, writer).await?;
+        }
+
+        let latency = started_at.elapsed();
+        METRICS.range_export_latency.observe(latency);
+        tracing::info!(
+            "Exported miniblocks {miniblocks:?} ({block_count} blocks, {tx_count} transactions, \
+             {event_count} events) in {latency:?}"
+        );
+        Ok(())
+    }
+
+    async fn ensure_range_available(
+        &self,
+        miniblocks: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            miniblocks.start() <= miniblocks.end(),
+            "invalid miniblock range: {miniblocks:?}"
+        );
+        let first_available_miniblock = self.first_available_miniblock().await?;
+        anyhow::ensure!(
+            *miniblocks.start() >= first_available_miniblock,
+            "cannot export miniblocks {miniblocks:?}: miniblocks before #{first_available_miniblock} are pruned \
+             or were not recovered from a snapshot"
+        );
+        let mut conn = self.pool.access_storage_tagged("chain_export").await?;
+        let sealed_miniblock = conn.blocks_dal().get_sealed_miniblock_number().await?;
+        anyhow::ensure!(
+            sealed_miniblock.is_some_and(|sealed| *miniblocks.end() <= sealed),
+            "cannot export miniblocks {miniblocks:?}: the last sealed miniblock is {sealed_miniblock:?}"
+        );
+        Ok(())
+    }
+
+    async fn save_file(
+        &self,
+        entity: ExportedEntity,
+        miniblocks: &ops::RangeInclusive<MiniblockNumber>,
+        writer: TableWriter,
+    ) -> anyhow::Result<()> {
+        let format = self.config.format;
+        let row_count = writer.row_count();
+        let bytes = writer
+            .finish()
+            .with_context(|| format!("failed serializing {entity:?} for {miniblocks:?}"))?;
+        let key = Self::file_key(entity, miniblocks, format);
+        METRICS.rows[&entity].inc_by(row_count as u64);
+        METRICS.file_size[&entity].observe(bytes.len());
+        self.blob_store
+            .put_raw(Bucket::ChainExport, &key, bytes)
+            .await
+            .with_context(|| format!("failed saving `{key}` to object store"))
+    }
+
+    async fn load_progress(&self) -> anyhow::Result<Option<ExportProgress>> {
+        match self
+            .blob_store
+            .get_raw(Bucket::ChainExport, PROGRESS_KEY)
+            .await
+        {
+            Ok(bytes) => {
+                let progress =
+                    serde_json::from_slice(&bytes).context("failed deserializing progress")?;
+                Ok(Some(progress))
+            }
+            Err(ObjectStoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(anyhow::Error::from(err).context("failed loading progress")),
+        }
+    }
+
+    async fn save_progress(&self, progress: ExportProgress) -> anyhow::Result<()> {
+        let bytes = serde_json::to_vec(&progress).context("failed serializing progress")?;
+        self.blob_store
+            .put_raw(Bucket::ChainExport, PROGRESS_KEY, bytes)
+            .await
+            .context("failed saving progress")
+    }
+
+    /// Returns the first miniblock stored in the node database, taking pruning and snapshot recovery into account.
+    async fn first_available_miniblock(&self) -> anyhow::Result<MiniblockNumber> {
+        let mut conn = self.pool.access_storage_tagged("chain_export").await?;
+        let pruning_info = conn.pruning_dal().get_pruning_info().await?;
+        let snapshot_recovery = conn
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        let first_miniblock = [
+            pruning_info.last_pruned_miniblock,
+            snapshot_recovery.map(|status| status.miniblock_number),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .map_or(MiniblockNumber(0), |number| number + 1);
+        Ok(first_miniblock)
+    }
+}
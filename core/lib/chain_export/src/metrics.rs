@@ -0,0 +1,25 @@
+//! Metrics for the chain exporter.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Family, Gauge, Histogram, Metrics, Unit};
+
+use crate::entities::ExportedEntity;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "chain_export")]
+pub(crate) struct ChainExportMetrics {
+    /// Number of exported rows split by entity.
+    pub rows: Family<ExportedEntity, Counter>,
+    /// Size of exported files split by entity.
+    #[metrics(buckets = Buckets::exponential(1_024.0..=1_073_741_824.0, 4.0), unit = Unit::Bytes)]
+    pub file_size: Family<ExportedEntity, Histogram<usize>>,
+    /// Last miniblock exported to all files.
+    pub last_exported_miniblock: Gauge<u64>,
+    /// Latency of exporting a single range of miniblocks.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub range_export_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(crate) static METRICS: vise::Global<ChainExportMetrics> = vise::Global::new();
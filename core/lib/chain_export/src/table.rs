@@ -0,0 +1,286 @@
+//! Tabular representation of exported data and its serialization to CSV and Parquet.
+
+use std::{fmt, fmt::Write as _, sync::Arc};
+
+use anyhow::Context as _;
+use parquet::{
+    basic::{Compression, LogicalType, Repetition, Type as PhysicalType},
+    column::writer::ColumnWriter,
+    data_type::ByteArray,
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::types::Type as SchemaType,
+};
+use zksync_config::configs::chain_export::ChainExportFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColumnType {
+    Int64,
+    /// UTF-8 string. Hashes, addresses and byte sequences are exported as `0x`-prefixed hex strings,
+    /// and 256-bit integers as decimal strings.
+    Utf8,
+    Bool,
+}
+
+/// Value in a [`Table`] cell. All columns are nullable.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Int64(i64),
+    Utf8(String),
+    Bool(bool),
+}
+
+impl Value {
+    fn has_type(&self, ty: ColumnType) -> bool {
+        matches!(
+            (self, ty),
+            (Self::Null, _)
+                | (Self::Int64(_), ColumnType::Int64)
+                | (Self::Utf8(_), ColumnType::Utf8)
+                | (Self::Bool(_), ColumnType::Bool)
+        )
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Self::Int64(value.into())
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Self::Int64(value as i64)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Utf8(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// Table with a fixed set of columns.
+#[derive(Debug)]
+pub(crate) struct Table {
+    columns: &'static [(&'static str, ColumnType)],
+    rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    pub fn new(columns: &'static [(&'static str, ColumnType)]) -> Self {
+        Self {
+            columns,
+            rows: vec![],
+        }
+    }
+
+    pub fn push_row(&mut self, row: Vec<Value>) {
+        assert_eq!(row.len(), self.columns.len(), "unexpected row length");
+        for (value, &(name, ty)) in row.iter().zip(self.columns) {
+            assert!(
+                value.has_type(ty),
+                "unexpected value for column `{name}`: {value:?}"
+            );
+        }
+        self.rows.push(row);
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn write_csv_rows(&self, csv: &mut String) {
+        for row in &self.rows {
+            for (i, value) in row.iter().enumerate() {
+                if i > 0 {
+                    csv.push(',');
+                }
+                match value {
+                    Value::Null => { /* Nulls are represented by empty fields */ }
+                    Value::Int64(value) => write!(csv, "{value}").unwrap(),
+                    Value::Bool(value) => write!(csv, "{value}").unwrap(),
+                    Value::Utf8(value) => Self::write_csv_string(csv, value),
+                }
+            }
+            csv.push('\n');
+        }
+    }
+
+    fn write_csv_string(csv: &mut String, value: &str) {
+        if value.contains([',', '"', '\n', '\r']) {
+            csv.push('"');
+            csv.push_str(&value.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(value);
+        }
+    }
+
+    fn write_parquet_row_group(
+        &self,
+        writer: &mut SerializedFileWriter<Vec<u8>>,
+    ) -> anyhow::Result<()> {
+        let mut row_group = writer.next_row_group()?;
+        for (i, &(name, _)) in self.columns.iter().enumerate() {
+            let mut column = row_group
+                .next_column()?
+                .with_context(|| format!("missing Parquet column `{name}`"))?;
+            let cells = self.rows.iter().map(|row| &row[i]);
+            let def_levels: Vec<i16> = cells
+                .clone()
+                .map(|value| i16::from(*value != Value::Null))
+                .collect();
+
+            match column.untyped() {
+                ColumnWriter::Int64ColumnWriter(writer) => {
+                    let values: Vec<_> = cells
+                        .filter_map(|value| match value {
+                            Value::Int64(value) => Some(*value),
+                            _ => None,
+                        })
+                        .collect();
+                    writer.write_batch(&values, Some(&def_levels), None)?;
+                }
+                ColumnWriter::ByteArrayColumnWriter(writer) => {
+                    let values: Vec<_> = cells
+                        .filter_map(|value| match value {
+                            Value::Utf8(value) => Some(ByteArray::from(value.as_str())),
+                            _ => None,
+                        })
+                        .collect();
+                    writer.write_batch(&values, Some(&def_levels), None)?;
+                }
+                ColumnWriter::BoolColumnWriter(writer) => {
+                    let values: Vec<_> = cells
+                        .filter_map(|value| match value {
+                            Value::Bool(value) => Some(*value),
+                            _ => None,
+                        })
+                        .collect();
+                    writer.write_batch(&values, Some(&def_levels), None)?;
+                }
+                _ => unreachable!("unexpected Parquet column type"),
+            }
+            column.close()?;
+        }
+        row_group.close()?;
+        Ok(())
+    }
+}
+
+/// Writer of a file that receives table rows in chunks. Each chunk is encoded as soon as it's written
+/// (as a separate row group for Parquet files), so that only the encoded file is retained in memory.
+pub(crate) struct TableWriter {
+    columns: &'static [(&'static str, ColumnType)],
+    output: TableOutput,
+    row_count: usize,
+}
+
+enum TableOutput {
+    Csv(String),
+    Parquet(SerializedFileWriter<Vec<u8>>),
+}
+
+impl fmt::Debug for TableWriter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TableWriter")
+            .field("columns", &self.columns)
+            .field("row_count", &self.row_count)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TableWriter {
+    pub fn new(
+        columns: &'static [(&'static str, ColumnType)],
+        format: ChainExportFormat,
+    ) -> anyhow::Result<Self> {
+        let output = match format {
+            ChainExportFormat::Csv => {
+                let header: Vec<_> = columns.iter().map(|(name, _)| *name).collect();
+                TableOutput::Csv(header.join(",") + "\n")
+            }
+            ChainExportFormat::Parquet => {
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                let writer = SerializedFileWriter::new(
+                    vec![],
+                    Self::parquet_schema(columns)?,
+                    Arc::new(properties),
+                )?;
+                TableOutput::Parquet(writer)
+            }
+        };
+        Ok(Self {
+            columns,
+            output,
+            row_count: 0,
+        })
+    }
+
+    fn parquet_schema(columns: &[(&'static str, ColumnType)]) -> anyhow::Result<Arc<SchemaType>> {
+        let fields = columns.iter().map(|&(name, ty)| {
+            let builder = match ty {
+                ColumnType::Int64 => SchemaType::primitive_type_builder(name, PhysicalType::INT64),
+                ColumnType::Utf8 => {
+                    SchemaType::primitive_type_builder(name, PhysicalType::BYTE_ARRAY)
+                        .with_logical_type(Some(LogicalType::String))
+                }
+                ColumnType::Bool => SchemaType::primitive_type_builder(name, PhysicalType::BOOLEAN),
+            };
+            builder
+                .with_repetition(Repetition::OPTIONAL)
+                .build()
+                .map(Arc::new)
+        });
+        let fields = fields
+            .collect::<Result<_, _>>()
+            .context("failed building Parquet schema")?;
+        let schema = SchemaType::group_type_builder("schema")
+            .with_fields(fields)
+            .build()
+            .context("failed building Parquet schema")?;
+        Ok(Arc::new(schema))
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Encodes a chunk of rows. Empty chunks are skipped.
+    pub fn write(&mut self, chunk: &Table) -> anyhow::Result<()> {
+        assert_eq!(chunk.columns, self.columns, "unexpected table columns");
+        if chunk.rows.is_empty() {
+            return Ok(());
+        }
+        match &mut self.output {
+            TableOutput::Csv(csv) => chunk.write_csv_rows(csv),
+            TableOutput::Parquet(writer) => chunk.write_parquet_row_group(writer)?,
+        }
+        self.row_count += chunk.row_count();
+        Ok(())
+    }
+
+    /// Finalizes the file and returns its contents.
+    pub fn finish(self) -> anyhow::Result<Vec<u8>> {
+        Ok(match self.output {
+            TableOutput::Csv(csv) => csv.into_bytes(),
+            TableOutput::Parquet(writer) => writer.into_inner()?,
+        })
+    }
+}
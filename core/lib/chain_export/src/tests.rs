@@ -0,0 +1,291 @@
+//! Tests for the chain exporter.
+
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use zksync_dal::{pruning_dal::StorageLogsPruning, StorageProcessor};
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::{
+    block::MiniblockHeader, event::TRANSFER_EVENT_SIGNATURE, tx::IncludedTxLocation, Address,
+    L1BatchNumber, ProtocolVersion, VmEvent, H256,
+};
+
+use super::*;
+use crate::table::{ColumnType, Table, Value};
+
+const TOKEN_ADDRESS: Address = Address::repeat_byte(0x10);
+
+fn transfer_event(value: u64) -> VmEvent {
+    let mut value_bytes = [0_u8; 32];
+    value_bytes[24..].copy_from_slice(&value.to_be_bytes());
+    VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: TOKEN_ADDRESS,
+        indexed_topics: vec![
+            *TRANSFER_EVENT_SIGNATURE,
+            H256::from(Address::repeat_byte(1)),
+            H256::from(Address::repeat_byte(2)),
+        ],
+        value: value_bytes.to_vec(),
+    }
+}
+
+async fn create_miniblock(conn: &mut StorageProcessor<'_>, number: u32) {
+    let miniblock_number = MiniblockNumber(number);
+    let header = MiniblockHeader {
+        number: miniblock_number,
+        timestamp: number.into(),
+        hash: H256::from_low_u64_be(number.into()),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        fee_account_address: Address::repeat_byte(1),
+        base_fee_per_gas: 100,
+        gas_per_pubdata_limit: 0,
+        batch_fee_input: Default::default(),
+        base_system_contracts_hashes: Default::default(),
+        protocol_version: Some(Default::default()),
+        virtual_blocks: 0,
+    };
+    conn.blocks_dal().insert_miniblock(&header).await.unwrap();
+
+    let tx_location = IncludedTxLocation {
+        tx_hash: H256::from_low_u64_be(number.into()),
+        tx_index_in_miniblock: 0,
+        tx_initiator_address: Address::repeat_byte(1),
+    };
+    let other_event = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: Address::repeat_byte(0x20),
+        indexed_topics: vec![H256::repeat_byte(0xff)],
+        value: vec![1, 2, 3],
+    };
+    let transfer = transfer_event(number.into());
+    conn.events_dal()
+        .save_events(
+            miniblock_number,
+            &[(tx_location, vec![&other_event, &transfer])],
+        )
+        .await;
+}
+
+async fn prepare_postgres(pool: &ConnectionPool, miniblock_count: u32) {
+    let mut conn = pool.access_storage().await.unwrap();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    for number in 0..miniblock_count {
+        create_miniblock(&mut conn, number).await;
+    }
+}
+
+fn test_config(format: ChainExportFormat) -> ChainExportConfig {
+    ChainExportConfig {
+        format,
+        miniblocks_per_file: 2,
+        export_interval_sec: None,
+    }
+}
+
+async fn get_file(
+    blob_store: &dyn ObjectStore,
+    entity: ExportedEntity,
+    miniblocks: ops::RangeInclusive<u32>,
+    format: ChainExportFormat,
+) -> Vec<u8> {
+    let miniblocks = MiniblockNumber(*miniblocks.start())..=MiniblockNumber(*miniblocks.end());
+    let key = ChainExporter::file_key(entity, &miniblocks, format);
+    blob_store
+        .get_raw(Bucket::ChainExport, &key)
+        .await
+        .unwrap_or_else(|err| panic!("failed getting `{key}`: {err}"))
+}
+
+#[test]
+fn file_keys_are_ordered() {
+    let format = ChainExportFormat::Parquet;
+    let key = ChainExporter::file_key(
+        ExportedEntity::Events,
+        &(MiniblockNumber(0)..=MiniblockNumber(999)),
+        format,
+    );
+    assert_eq!(key, "events_miniblocks_0000000000_0000000999.parquet");
+    let next_key = ChainExporter::file_key(
+        ExportedEntity::Events,
+        &(MiniblockNumber(1_000)..=MiniblockNumber(1_999)),
+        format,
+    );
+    assert!(key < next_key);
+}
+
+#[test]
+fn escaping_csv_values() {
+    static COLUMNS: &[(&str, ColumnType)] = &[
+        ("number", ColumnType::Int64),
+        ("text", ColumnType::Utf8),
+        ("flag", ColumnType::Bool),
+    ];
+
+    let mut table = Table::new(COLUMNS);
+    table.push_row(vec![1_u32.into(), "plain".to_owned().into(), true.into()]);
+    table.push_row(vec![
+        Value::Null,
+        "with \"quotes\", commas".to_owned().into(),
+        Value::Null,
+    ]);
+    let mut writer = TableWriter::new(COLUMNS, ChainExportFormat::Csv).unwrap();
+    writer.write(&table).unwrap();
+    let csv = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(
+        csv,
+        "number,text,flag\n1,plain,true\n,\"with \"\"quotes\"\", commas\",\n"
+    );
+}
+
+#[tokio::test]
+async fn exporting_miniblocks_to_csv() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_postgres(&pool, 5).await;
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let format = ChainExportFormat::Csv;
+    let exporter = ChainExporter::new(pool.clone(), blob_store.clone(), test_config(format));
+
+    let last_exported_miniblock = exporter.export_new_miniblocks().await.unwrap();
+    // Miniblock #4 doesn't fill a complete file, so it must not be exported.
+    assert_eq!(last_exported_miniblock, Some(MiniblockNumber(3)));
+    for entity in ExportedEntity::ALL {
+        for range in [0..=1, 2..=3] {
+            get_file(&*blob_store, entity, range, format).await;
+        }
+    }
+
+    let blocks = get_file(&*blob_store, ExportedEntity::Blocks, 2..=3, format).await;
+    let blocks = String::from_utf8(blocks).unwrap();
+    let lines: Vec<_> = blocks.lines().collect();
+    assert_eq!(lines.len(), 3, "{blocks}");
+    assert!(lines[0].starts_with("number,hash,timestamp,"), "{blocks}");
+    assert!(lines[1].starts_with(&format!("2,{:?},2,", H256::from_low_u64_be(2))));
+
+    let events = get_file(&*blob_store, ExportedEntity::Events, 0..=1, format).await;
+    let events = String::from_utf8(events).unwrap();
+    assert_eq!(events.lines().count(), 5, "{events}");
+    assert!(events.contains(",0x010203\n"), "{events}");
+
+    let transfers = get_file(&*blob_store, ExportedEntity::Transfers, 0..=1, format).await;
+    let transfers = String::from_utf8(transfers).unwrap();
+    let lines: Vec<_> = transfers.lines().collect();
+    assert_eq!(lines.len(), 3, "{transfers}");
+    let expected_transfer = format!(
+        "1,{:?},1,{TOKEN_ADDRESS:?},{:?},{:?},1",
+        H256::from_low_u64_be(1),
+        Address::repeat_byte(1),
+        Address::repeat_byte(2)
+    );
+    assert_eq!(lines[2], expected_transfer);
+
+    // Repeated export must not produce any files.
+    let last_exported_miniblock = exporter.export_new_miniblocks().await.unwrap();
+    assert_eq!(last_exported_miniblock, None);
+
+    let mut conn = pool.access_storage().await.unwrap();
+    create_miniblock(&mut conn, 5).await;
+    drop(conn);
+    let last_exported_miniblock = exporter.export_new_miniblocks().await.unwrap();
+    assert_eq!(last_exported_miniblock, Some(MiniblockNumber(5)));
+    let blocks = get_file(&*blob_store, ExportedEntity::Blocks, 4..=5, format).await;
+    assert_eq!(String::from_utf8(blocks).unwrap().lines().count(), 3);
+}
+
+#[tokio::test]
+async fn exporting_miniblocks_to_parquet() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_postgres(&pool, 2).await;
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let format = ChainExportFormat::Parquet;
+    let exporter = ChainExporter::new(pool, blob_store.clone(), test_config(format));
+
+    let last_exported_miniblock = exporter.export_new_miniblocks().await.unwrap();
+    assert_eq!(last_exported_miniblock, Some(MiniblockNumber(1)));
+
+    let expected_row_counts = [
+        (ExportedEntity::Blocks, 2),
+        (ExportedEntity::Transactions, 0),
+        (ExportedEntity::Receipts, 0),
+        (ExportedEntity::Events, 4),
+        (ExportedEntity::Transfers, 2),
+    ];
+    for (entity, expected_row_count) in expected_row_counts {
+        let file = get_file(&*blob_store, entity, 0..=1, format).await;
+        assert!(file.starts_with(b"PAR1"));
+        let reader = SerializedFileReader::new(bytes::Bytes::from(file)).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), expected_row_count, "{entity:?}");
+        assert_eq!(
+            metadata.schema_descr().num_columns(),
+            entity.columns().len(),
+            "{entity:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn exporting_miniblocks_in_chunks() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_postgres(&pool, 5).await;
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let format = ChainExportFormat::Parquet;
+    let mut exporter = ChainExporter::new(pool, blob_store.clone(), test_config(format));
+    exporter.miniblocks_per_chunk = 2;
+
+    exporter
+        .export_range(MiniblockNumber(0)..=MiniblockNumber(4))
+        .await
+        .unwrap();
+    let file = get_file(&*blob_store, ExportedEntity::Events, 0..=4, format).await;
+    let reader = SerializedFileReader::new(bytes::Bytes::from(file)).unwrap();
+    // Each chunk is written as a separate row group.
+    assert_eq!(reader.metadata().num_row_groups(), 3);
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 10);
+}
+
+#[tokio::test]
+async fn exporting_pruned_miniblocks_is_an_error() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_postgres(&pool, 4).await;
+    let mut conn = pool.access_storage().await.unwrap();
+    conn.pruning_dal()
+        .prune_l1_batches(
+            L1BatchNumber(0),
+            MiniblockNumber(0)..=MiniblockNumber(1),
+            StorageLogsPruning::default(),
+        )
+        .await
+        .unwrap();
+    drop(conn);
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let format = ChainExportFormat::Csv;
+    let exporter = ChainExporter::new(pool, blob_store.clone(), test_config(format));
+
+    let err = exporter
+        .export_range(MiniblockNumber(0)..=MiniblockNumber(3))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("pruned"), "{err}");
+    let err = exporter
+        .export_range(MiniblockNumber(2)..=MiniblockNumber(5))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("sealed"), "{err}");
+    let key = ChainExporter::file_key(
+        ExportedEntity::Blocks,
+        &(MiniblockNumber(0)..=MiniblockNumber(3)),
+        format,
+    );
+    let err = blob_store
+        .get_raw(Bucket::ChainExport, &key)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+
+    // Progress starts from the first non-pruned miniblock.
+    let last_exported_miniblock = exporter.export_new_miniblocks().await.unwrap();
+    assert_eq!(last_exported_miniblock, Some(MiniblockNumber(3)));
+    get_file(&*blob_store, ExportedEntity::Blocks, 2..=3, format).await;
+}
@@ -1,7 +1,7 @@
 use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
 
 use serde::Deserialize;
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, H256};
 
 pub use crate::configs::PrometheusConfig;
 
@@ -61,6 +61,16 @@ pub struct Web3JsonRpcConfig {
     pub l1_to_l2_transactions_compatibility_mode: bool,
     ///  Max possible size of an ABI encoded tx (in bytes).
     pub max_tx_size: usize,
+    /// Max number of factory dependencies in a submitted transaction. Values greater than the number supported
+    /// by the bootloader (32) are capped; this is also the default value.
+    pub max_factory_deps: Option<usize>,
+    /// Max size of calldata of a submitted transaction (in bytes). If not set, calldata is only limited
+    /// by `max_tx_size`.
+    pub max_calldata_size: Option<usize>,
+    /// Fraction of each transaction size limit (`max_tx_size`, `max_factory_deps` and `max_calldata_size`)
+    /// used as a soft limit. Transactions exceeding a soft limit are accepted, but are reported in logs and metrics.
+    /// Default is 0.8.
+    pub soft_tx_limits_ratio: Option<f64>,
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
     pub vm_execution_cache_misses_limit: Option<usize>,
@@ -87,6 +97,69 @@ pub struct Web3JsonRpcConfig {
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
     /// Tree API url, currently used to proxy `getProof` calls to the tree
     pub tree_api_url: Option<String>,
+    /// Max number of L1 batches that the pending mempool backlog executable at the current base fee may occupy
+    /// before new transactions are rejected as the mempool is saturated. If not set, admission control is disabled.
+    pub inclusion_horizon_batches: Option<u32>,
+    /// Enables the `operator` namespace with methods overriding automated node safety mechanisms.
+    /// Should only be enabled on API servers not exposed publicly.
+    #[serde(default)]
+    pub operator_namespace_enabled: bool,
+    /// Bearer token required in the `Authorization` header of HTTP requests calling `operator` or `admin` methods.
+    /// Must be set if any of these namespaces is enabled; the API server refuses to start otherwise.
+    /// These namespaces are not served over WebSocket, since WS messages cannot be authenticated.
+    pub operator_auth_token: Option<String>,
+    /// Enables the `admin` namespace with methods controlling the node operation (pausing transaction intake,
+    /// dropping mempool transactions, sealing L1 batches etc.). Authenticated with `operator_auth_token`.
+    /// Should only be enabled on API servers not exposed publicly.
+    #[serde(default)]
+    pub admin_namespace_enabled: bool,
+    /// Enables verification of miniblock content hashes when serving blocks and transaction receipts.
+    /// If the miniblock data in Postgres doesn't match the hash persisted when sealing the miniblock,
+    /// the request fails with an internal error instead of returning corrupted data.
+    #[serde(default)]
+    pub verify_content_hashes: bool,
+    /// Address of the on-chain name registry used to resolve human-readable names (e.g., in `idexo_resolveName`).
+    /// The registry must implement the ENS resolver interface, i.e. `addr(bytes32 node)`.
+    pub name_registry_addr: Option<Address>,
+    /// Enables resolving names in address parameters of RPC methods (e.g., `eth_getBalance` or `to` in `eth_call`)
+    /// using the name registry. Has no effect if `name_registry_addr` is not set.
+    #[serde(default)]
+    pub resolve_names_in_params: bool,
+    /// Compute budget for a single `idexo_multicallRead` request in milliseconds. Reads not started
+    /// within the budget fail with an error. Default is 1,000 ms.
+    pub multicall_compute_budget_ms: Option<u64>,
+    /// Number of queued notification batches for a WebSocket subscription after which the subscriber
+    /// is warned that it lags behind. Default is 128.
+    pub pubsub_lag_warning_threshold: Option<u32>,
+    /// Number of queued notification batches for a WebSocket subscription after which the subscription
+    /// is terminated. Must not exceed 1,024. Default is 512.
+    pub pubsub_lag_close_threshold: Option<u32>,
+    /// URL of a secondary JSON-RPC endpoint (e.g., a canary node) that read requests are mirrored to.
+    /// Responses of the secondary endpoint are compared with the local ones; divergences are logged.
+    pub shadow_api_url: Option<String>,
+    /// Percentage of read requests mirrored to `shadow_api_url`. Default is 1%.
+    pub shadow_requests_percentage: Option<f64>,
+    /// Enables the warm pool of sandbox environments for VM invocations (`eth_call`, `eth_estimateGas` etc.)
+    /// at the latest block. The pool is refreshed on each sealed miniblock, so that VM invocations don't need
+    /// to resolve the block context from Postgres.
+    #[serde(default)]
+    pub sandbox_warm_pool_enabled: bool,
+    /// JSON-RPC methods (e.g., `debug_traceBlockByNumber`) rejected by the main node API servers.
+    /// Can be changed at runtime via the config reloader.
+    #[serde(default)]
+    pub disabled_methods: Vec<String>,
+    /// Enables `eth_call` for pruned blocks. The VM state for such blocks is read from the Merkle tree
+    /// using the tree API at `tree_api_url`, so this has no effect if the URL is not set.
+    #[serde(default)]
+    pub archive_calls_enabled: bool,
+    /// Accepts legacy transactions without replay protection, i.e. signed without a chain ID (pre-EIP-155).
+    /// Such transactions can be replayed on any chain; by default, they are rejected.
+    #[serde(default)]
+    pub pre_eip155_txs_allowed: bool,
+    /// Senders of legacy transactions without replay protection accepted even if `pre_eip155_txs_allowed`
+    /// is not set (e.g., keyless deployers of deterministic deployment proxies).
+    #[serde(default)]
+    pub pre_eip155_tx_senders: Vec<Address>,
 }
 
 impl Web3JsonRpcConfig {
@@ -111,6 +184,9 @@ impl Web3JsonRpcConfig {
             estimate_gas_acceptable_overestimation: 1000,
             l1_to_l2_transactions_compatibility_mode: true,
             max_tx_size: 1000000,
+            max_factory_deps: None,
+            max_calldata_size: None,
+            soft_tx_limits_ratio: None,
             vm_execution_cache_misses_limit: Default::default(),
             vm_concurrency_limit: Default::default(),
             factory_deps_cache_size_mb: Default::default(),
@@ -121,6 +197,23 @@ impl Web3JsonRpcConfig {
             max_response_body_size_mb: Default::default(),
             websocket_requests_per_minute_limit: Default::default(),
             tree_api_url: None,
+            inclusion_horizon_batches: None,
+            operator_namespace_enabled: false,
+            operator_auth_token: None,
+            admin_namespace_enabled: false,
+            verify_content_hashes: false,
+            name_registry_addr: None,
+            resolve_names_in_params: false,
+            multicall_compute_budget_ms: None,
+            pubsub_lag_warning_threshold: None,
+            pubsub_lag_close_threshold: None,
+            shadow_api_url: None,
+            shadow_requests_percentage: None,
+            sandbox_warm_pool_enabled: false,
+            disabled_methods: vec![],
+            archive_calls_enabled: false,
+            pre_eip155_txs_allowed: false,
+            pre_eip155_tx_senders: vec![],
         }
     }
 
@@ -148,6 +241,14 @@ impl Web3JsonRpcConfig {
         Duration::from_millis(self.pubsub_polling_interval.unwrap_or(200))
     }
 
+    pub fn pubsub_lag_warning_threshold(&self) -> usize {
+        self.pubsub_lag_warning_threshold.unwrap_or(128) as usize
+    }
+
+    pub fn pubsub_lag_close_threshold(&self) -> usize {
+        self.pubsub_lag_close_threshold.unwrap_or(512) as usize
+    }
+
     pub fn request_timeout(&self) -> Duration {
         Duration::from_secs(self.request_timeout.unwrap_or(10))
     }
@@ -197,21 +298,51 @@ impl Web3JsonRpcConfig {
             .unwrap_or(NonZeroU32::new(6000).unwrap())
     }
 
+    pub fn multicall_compute_budget(&self) -> Duration {
+        Duration::from_millis(self.multicall_compute_budget_ms.unwrap_or(1_000))
+    }
+
     pub fn tree_api_url(&self) -> Option<String> {
         self.tree_api_url.clone()
     }
+
+    pub fn shadow_requests_percentage(&self) -> f64 {
+        self.shadow_requests_percentage.unwrap_or(1.0)
+    }
+
+    /// Private key used to sign L2 blocks served to external nodes via the `en` namespace. If not set,
+    /// blocks are served without signatures.
+    // Don't load private key, if it's not required.
+    pub fn sequencer_signing_key(&self) -> Option<H256> {
+        std::env::var("API_WEB3_JSON_RPC_SEQUENCER_SIGNING_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct HealthCheckConfig {
     /// Port to which the REST server is listening.
     pub port: u16,
+    /// Time since the last sealed miniblock after which the state keeper is reported as affected.
+    /// If not set, the lag is only reported in health check details.
+    pub state_keeper_max_lag_sec: Option<u64>,
+    /// Number of L1 batches the Merkle tree may lag behind sealed L1 batches before it is reported as affected.
+    /// If not set, the lag is only reported in health check details.
+    pub tree_max_lag: Option<u32>,
+    /// Number of in-flight L1 transactions after which `eth_sender` is reported as affected.
+    /// If not set, the number of in-flight transactions is only reported in health check details.
+    pub eth_sender_max_inflight_txs: Option<usize>,
 }
 
 impl HealthCheckConfig {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn state_keeper_max_lag(&self) -> Option<Duration> {
+        self.state_keeper_max_lag_sec.map(Duration::from_secs)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
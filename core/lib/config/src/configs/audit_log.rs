@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the exporter copying the audit log of sensitive node actions from Postgres to a file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AuditLogConfig {
+    /// Path to the file audit log entries are appended to as JSON lines. If the file already exists,
+    /// the export continues after the last entry in it.
+    pub file_path: String,
+    /// Interval between checks for new audit log entries.
+    #[serde(default = "AuditLogConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u64,
+    /// Minimum age of exported entries. Entries are recorded within the DB transactions performing the actions,
+    /// so they may become visible out of order; the lag must exceed the duration of such transactions.
+    #[serde(default = "AuditLogConfig::default_export_lag_ms")]
+    pub export_lag_ms: u64,
+}
+
+impl AuditLogConfig {
+    const fn default_polling_interval_ms() -> u64 {
+        1_000
+    }
+
+    const fn default_export_lag_ms() -> u64 {
+        10_000
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+
+    pub fn export_lag(&self) -> Duration {
+        Duration::from_millis(self.export_lag_ms)
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Message broker receiving change data capture (CDC) messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CdcBroker {
+    Kafka,
+    Nats,
+}
+
+/// Encoding of published CDC messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CdcMessageFormat {
+    #[default]
+    Json,
+    Protobuf,
+}
+
+/// Configuration for the change data capture (CDC) publisher, which publishes messages for sealed miniblocks,
+/// transaction receipts and L1 batch status changes to a message broker.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CdcPublisherConfig {
+    pub broker: CdcBroker,
+    /// Broker URLs: bootstrap servers (`host:port`) for Kafka, or server URLs (e.g., `nats://127.0.0.1:4222`)
+    /// for NATS.
+    pub broker_urls: Vec<String>,
+    /// Prefix for topics (Kafka) or subjects (NATS) messages are published to. Messages are published
+    /// to `{prefix}.miniblocks`, `{prefix}.receipts` and `{prefix}.l1_batches`.
+    #[serde(default = "CdcPublisherConfig::default_topic_prefix")]
+    pub topic_prefix: String,
+    #[serde(default)]
+    pub format: CdcMessageFormat,
+    /// Interval between checks for new data to publish.
+    #[serde(default = "CdcPublisherConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u64,
+    /// Maximum number of miniblocks published in a single iteration. The same limit applies to L1 batches
+    /// separately for each L1 batch status.
+    #[serde(default = "CdcPublisherConfig::default_max_miniblocks_per_iteration")]
+    pub max_miniblocks_per_iteration: u32,
+}
+
+impl CdcPublisherConfig {
+    fn default_topic_prefix() -> String {
+        "zksync".to_owned()
+    }
+
+    const fn default_polling_interval_ms() -> u64 {
+        500
+    }
+
+    const fn default_max_miniblocks_per_iteration() -> u32 {
+        100
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+}
@@ -1,7 +1,7 @@
 use std::{str::FromStr, time::Duration};
 
 use serde::Deserialize;
-use zksync_basic_types::{network::Network, Address, L2ChainId};
+use zksync_basic_types::{network::Network, Address, L2ChainId, H256};
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct NetworkConfig {
@@ -33,10 +33,13 @@ impl NetworkConfig {
 ///  - `V2`, the second model that was used in zkSync Era. There the pubdata price might be independent from the L1 gas price. Also,
 ///  The fair L2 gas price is expected to both the proving/computation price for the operator and the costs that come from
 ///  processing the batch on L1.
+///  - `V3`, the `V2` model with congestion pricing. The fair L2 gas price is additionally multiplied by a congestion multiplier
+///  that follows an EIP-1559-style curve based on the fullness of recent L1 batches.
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
 pub enum FeeModelVersion {
     V1,
     V2,
+    V3,
 }
 
 impl Default for FeeModelVersion {
@@ -45,6 +48,23 @@ impl Default for FeeModelVersion {
     }
 }
 
+/// Data availability mode of the chain, i.e., where pubdata of L1 batches is published.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+pub enum DataAvailabilityMode {
+    /// Pubdata is published on the settlement layer in calldata or blobs, depending on the `pubdata_sending_mode`
+    /// of the Ethereum sender.
+    #[default]
+    Rollup,
+    /// Pubdata is not published; the settlement layer only receives state commitments.
+    Validium,
+    /// Pubdata is published to an external DA layer; the settlement layer receives the DA inclusion data.
+    External,
+    /// Pubdata is published to Celestia; the settlement layer receives the reference to the Celestia blob.
+    Celestia,
+    /// Pubdata is published to EigenDA; the settlement layer receives the EigenDA blob certificate.
+    EigenDA,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq, Default)]
 pub struct StateKeeperConfig {
     /// The max number of slots for txs in a block before it should be sealed by the slots sealer.
@@ -102,6 +122,19 @@ pub struct StateKeeperConfig {
 
     /// The version of the fee model to use.
     pub fee_model_version: FeeModelVersion,
+    /// Target fullness of an L1 batch (the ratio of gas used by transactions to `max_gas_per_batch`) for the `V3` fee model.
+    /// If not set, 0.5.
+    pub congestion_target_batch_fullness: Option<f64>,
+    /// Max relative change of the congestion multiplier per L1 batch for the `V3` fee model. If not set, 0.125
+    /// (same as in EIP-1559).
+    pub congestion_max_change_per_batch: Option<f64>,
+    /// Upper bound for the congestion multiplier for the `V3` fee model. If not set, 10.
+    pub congestion_max_multiplier: Option<f64>,
+    /// Number of latest L1 batches used to compute the congestion multiplier for the `V3` fee model. If not set, 32.
+    pub congestion_window_size: Option<u32>,
+    /// The data availability mode of the chain.
+    #[serde(default)]
+    pub data_availability_mode: DataAvailabilityMode,
 
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
@@ -116,6 +149,22 @@ pub struct StateKeeperConfig {
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     pub enum_index_migration_chunk_size: Option<usize>,
+
+    /// Max number of L2 transactions from a single initiator account that can be included into a miniblock.
+    /// Transactions exceeding the cap are retained in the mempool until the next miniblock. If not set,
+    /// the number of transactions per account is not limited.
+    pub max_txs_per_account_in_miniblock: Option<u32>,
+    /// Max number of L2 transactions from a single initiator account that can be included into an L1 batch.
+    /// Transactions exceeding the cap are retained in the mempool until the next batch. If not set,
+    /// the number of transactions per account is not limited.
+    pub max_txs_per_account_in_batch: Option<u32>,
+    /// Max time in ms between a priority operation being picked up from the settlement layer and the execution
+    /// of its L1 batch on the settlement layer. Priority operations bypass the mempool policies (fee filter,
+    /// per-account caps, shared sequencer ordering). L1 batches with priority operations are sealed once
+    /// the oldest operation has waited for half of the deadline; the other half is left for committing,
+    /// proving and executing the batch. The node is reported as unhealthy while any operation is overdue.
+    /// If not set, 5 minutes.
+    pub forced_inclusion_deadline_ms: Option<u64>,
 }
 
 impl StateKeeperConfig {
@@ -144,18 +193,66 @@ impl StateKeeperConfig {
             max_pubdata_per_batch: 100_000,
             minimal_l2_gas_price: 100000000,
             fee_model_version: FeeModelVersion::V2,
+            congestion_target_batch_fullness: None,
+            congestion_max_change_per_batch: None,
+            congestion_max_multiplier: None,
+            congestion_window_size: None,
+            data_availability_mode: DataAvailabilityMode::Rollup,
             validation_computational_gas_limit: 300000,
             save_call_traces: true,
             virtual_blocks_interval: 1,
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: None,
+            max_txs_per_account_in_miniblock: None,
+            max_txs_per_account_in_batch: None,
+            forced_inclusion_deadline_ms: None,
         }
     }
 
     pub fn enum_index_migration_chunk_size(&self) -> usize {
         self.enum_index_migration_chunk_size.unwrap_or(1_000)
     }
+
+    pub fn forced_inclusion_deadline(&self) -> Duration {
+        Duration::from_millis(self.forced_inclusion_deadline_ms.unwrap_or(300_000))
+    }
+
+    pub fn congestion_target_batch_fullness(&self) -> f64 {
+        self.congestion_target_batch_fullness.unwrap_or(0.5)
+    }
+
+    pub fn congestion_max_change_per_batch(&self) -> f64 {
+        self.congestion_max_change_per_batch.unwrap_or(0.125)
+    }
+
+    pub fn congestion_max_multiplier(&self) -> f64 {
+        self.congestion_max_multiplier.unwrap_or(10.0)
+    }
+
+    pub fn congestion_window_size(&self) -> u32 {
+        self.congestion_window_size.unwrap_or(32)
+    }
+
+    /// Checks that the config values lie in their allowed ranges.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(fullness) = self.congestion_target_batch_fullness {
+            anyhow::ensure!(
+                fullness > 0.0 && fullness < 1.0,
+                "congestion_target_batch_fullness must lie in (0, 1), got {fullness}"
+            );
+        }
+        Ok(())
+    }
+
+    /// Private key of the account used to send operator-scheduled L2 transactions. If not set,
+    /// scheduled transactions are not injected by the state keeper.
+    // Don't load private key, if it's not required.
+    pub fn scheduled_txs_private_key(&self) -> Option<H256> {
+        std::env::var("CHAIN_STATE_KEEPER_SCHEDULED_TXS_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
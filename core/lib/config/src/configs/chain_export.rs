@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// File format of exported chain data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainExportFormat {
+    #[default]
+    Parquet,
+    Csv,
+}
+
+/// Configuration for the chain data exporter.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChainExportConfig {
+    #[serde(default)]
+    pub format: ChainExportFormat,
+    /// Number of miniblocks exported to a single file.
+    #[serde(default = "ChainExportConfig::default_miniblocks_per_file")]
+    pub miniblocks_per_file: u32,
+    /// Interval between export runs. If not set, the exporter exports all sealed miniblocks and exits,
+    /// which is suitable for running it as a cron job.
+    #[serde(default)]
+    pub export_interval_sec: Option<u64>,
+}
+
+impl ChainExportConfig {
+    const fn default_miniblocks_per_file() -> u32 {
+        10_000
+    }
+
+    pub fn export_interval(&self) -> Option<Duration> {
+        self.export_interval_sec.map(Duration::from_secs)
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the config reloader, which applies changes of parameters that are safe to change at runtime
+/// (rate limits, mempool caps, the L2 gas price floor, disabled API methods, fee sweep interval) without restarting
+/// the server.
+///
+/// Parameters are read from the same environment variables as on startup (e.g., `CHAIN_MEMPOOL_CAPACITY`).
+/// Since the environment of a running process cannot be changed externally, variables can be overridden
+/// in the overrides file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ConfigReloadConfig {
+    /// Interval between checks for changed parameters.
+    #[serde(default = "ConfigReloadConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u64,
+    /// Path to the file with overridden environment variables in the `NAME=value` format, one variable per line.
+    /// Empty lines and lines starting with `#` are ignored. Overrides take precedence over the process environment.
+    /// A missing file is treated as having no overrides.
+    pub overrides_path: Option<String>,
+}
+
+impl ConfigReloadConfig {
+    const fn default_polling_interval_ms() -> u64 {
+        5_000
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+}
@@ -47,6 +47,19 @@ pub struct ContractsConfig {
     pub state_transition_proxy_addr: Option<Address>,
     pub state_transition_impl_addr: Option<Address>,
     pub transparent_proxy_admin_addr: Option<Address>,
+
+    /// Custom bridge (e.g., the shared bridge) deployed on the settlement layer in addition to the default ones.
+    pub l1_shared_bridge_proxy_addr: Option<Address>,
+    /// Counterpart of `l1_shared_bridge_proxy_addr` deployed on the chain.
+    pub l2_shared_bridge_addr: Option<Address>,
+    /// Expected `keccak256` hash of the code deployed at `l1_shared_bridge_proxy_addr`. Checked at startup if set.
+    pub l1_shared_bridge_code_hash: Option<H256>,
+    /// Expected bytecode hash of the contract deployed at `l2_shared_bridge_addr`. Checked at startup if set.
+    pub l2_shared_bridge_code_hash: Option<H256>,
+
+    /// Path to the JSON manifest customizing the genesis state (extra predeployed contracts, initial balances
+    /// and chain params). Only used during genesis; if not set, the default genesis state is created.
+    pub genesis_manifest_path: Option<String>,
 }
 
 impl ContractsConfig {
@@ -89,6 +102,11 @@ impl ContractsConfig {
             bridgehub_impl_addr: Some(Address::repeat_byte(0x15)),
             state_transition_proxy_addr: Some(Address::repeat_byte(0x16)),
             state_transition_impl_addr: Some(Address::repeat_byte(0x17)),
+            l1_shared_bridge_proxy_addr: None,
+            l2_shared_bridge_addr: None,
+            l1_shared_bridge_code_hash: None,
+            l2_shared_bridge_code_hash: None,
+            genesis_manifest_path: None,
         }
     }
 }
@@ -18,6 +18,48 @@ pub enum MerkleTreeMode {
     Lightweight,
 }
 
+/// Compaction style for a RocksDB instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompactionStyle {
+    /// Leveled compaction. Has the lowest space amplification at the cost of higher write amplification.
+    #[default]
+    Level,
+    /// Universal compaction. Has lower write amplification than leveled compaction, but can temporarily
+    /// require up to 2x disk space during full compactions.
+    Universal,
+}
+
+/// Tuning profile for a RocksDB instance.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct RocksdbProfile {
+    /// Compaction style used for all column families. If not specified, leveled compaction is used.
+    #[serde(default)]
+    pub compaction_style: RocksdbCompactionStyle,
+    /// Maximum total size of write-ahead log (WAL) files in MB. Once exceeded, RocksDB flushes memtables
+    /// backed by the oldest WAL files. If not specified, the limit is chosen by RocksDB based on memtable sizes.
+    #[serde(default)]
+    pub max_total_wal_size_mb: Option<u64>,
+    /// If specified, WAL files are incrementally synced to disk every time this number of MB is written.
+    /// This smooths out disk I/O, but can reduce write throughput.
+    #[serde(default)]
+    pub wal_bytes_per_sync_mb: Option<u64>,
+}
+
+impl RocksdbProfile {
+    /// Returns the maximum total size of WAL files in bytes.
+    pub fn max_total_wal_size(&self) -> Option<u64> {
+        self.max_total_wal_size_mb
+            .map(|size| size * super::BYTES_IN_MEGABYTE as u64)
+    }
+
+    /// Returns the number of bytes after which WAL files are incrementally synced to disk.
+    pub fn wal_bytes_per_sync(&self) -> Option<u64> {
+        self.wal_bytes_per_sync_mb
+            .map(|size| size * super::BYTES_IN_MEGABYTE as u64)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct MerkleTreeConfig {
     /// Path to the RocksDB data directory for Merkle tree.
@@ -44,6 +86,25 @@ pub struct MerkleTreeConfig {
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// Number of threads in a dedicated `rayon` thread pool used to hash tree levels in parallel when updating
+    /// the tree. 0 means the number of logical CPUs. If not specified, the global `rayon` thread pool is used.
+    #[serde(default)]
+    pub thread_pool_size: Option<usize>,
+    /// Interval between Merkle tree integrity checks. Each check re-hashes random subtrees and the root
+    /// of the latest tree version, and compares them with stored hashes. If not specified, integrity checks
+    /// are disabled.
+    #[serde(default)]
+    pub integrity_check_interval_sec: Option<u64>,
+    /// Number of random subtrees checked on each integrity check.
+    #[serde(default = "MerkleTreeConfig::default_integrity_check_subtree_count")]
+    pub integrity_check_subtree_count: usize,
+    /// Whether to rebuild tree versions affected by detected corruption from Postgres data.
+    #[serde(default)]
+    pub integrity_auto_repair: bool,
+    /// RocksDB tuning profile for the Merkle tree.
+    #[serde(skip)]
+    // ^ Filled in separately in `DBConfig::from_env()`.
+    pub rocksdb: RocksdbProfile,
 }
 
 impl Default for MerkleTreeConfig {
@@ -56,6 +117,11 @@ impl Default for MerkleTreeConfig {
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            thread_pool_size: None,
+            integrity_check_interval_sec: None,
+            integrity_check_subtree_count: Self::default_integrity_check_subtree_count(),
+            integrity_auto_repair: false,
+            rocksdb: RocksdbProfile::default(),
         }
     }
 }
@@ -85,6 +151,10 @@ impl MerkleTreeConfig {
         20
     }
 
+    const fn default_integrity_check_subtree_count() -> usize {
+        16
+    }
+
     /// Returns the size of block cache size for Merkle tree in bytes.
     pub fn block_cache_size(&self) -> usize {
         self.block_cache_size_mb * super::BYTES_IN_MEGABYTE
@@ -99,6 +169,11 @@ impl MerkleTreeConfig {
     pub fn stalled_writes_timeout(&self) -> Duration {
         Duration::from_secs(self.stalled_writes_timeout_sec)
     }
+
+    /// Returns the interval between Merkle tree integrity checks, or `None` if checks are disabled.
+    pub fn integrity_check_interval(&self) -> Option<Duration> {
+        self.integrity_check_interval_sec.map(Duration::from_secs)
+    }
 }
 
 /// Database configuration.
@@ -107,6 +182,18 @@ pub struct DBConfig {
     /// Path to the RocksDB data directory that serves state cache.
     #[serde(default = "DBConfig::default_state_keeper_db_path")]
     pub state_keeper_db_path: String,
+    /// Capacity of the block cache for the state keeper RocksDB in MB. If not specified, the default RocksDB
+    /// block cache options are used.
+    #[serde(default)]
+    pub state_keeper_db_block_cache_size_mb: Option<usize>,
+    /// RocksDB tuning profile for the state keeper cache.
+    #[serde(skip)]
+    // ^ Filled in separately in `Self::from_env()`.
+    pub state_keeper_rocksdb: RocksdbProfile,
+    /// Path to the RocksDB replica of the state keeper cache used by the API server to serve storage reads
+    /// for VM invocations at the latest block. If not specified, such reads are served from Postgres.
+    #[serde(default)]
+    pub api_state_cache_path: Option<String>,
     /// Merkle tree configuration.
     #[serde(skip)]
     // ^ Filled in separately in `Self::from_env()`. We cannot use `serde(flatten)` because it
@@ -118,6 +205,12 @@ impl DBConfig {
     fn default_state_keeper_db_path() -> String {
         "./db/state_keeper".to_owned()
     }
+
+    /// Returns the size of block cache for the state keeper RocksDB in bytes.
+    pub fn state_keeper_db_block_cache_size(&self) -> Option<usize> {
+        self.state_keeper_db_block_cache_size_mb
+            .map(|size| size * super::BYTES_IN_MEGABYTE)
+    }
 }
 
 /// Collection of different database URLs and general PostgreSQL options.
@@ -144,6 +237,12 @@ pub struct PostgresConfig {
     pub long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     pub slow_query_threshold_ms: Option<u64>,
+    /// Maximum number of connections concurrently used by the API servers in each of the main and replica pools.
+    /// If not set, the API servers can use all connections in the pools.
+    pub api_max_connections: Option<u32>,
+    /// Maximum number of connections concurrently used by background tasks (e.g., metrics and cache updaters)
+    /// in each of the main and replica pools. If not set, background tasks can use all connections in the pools.
+    pub background_max_connections: Option<u32>,
 }
 
 impl PostgresConfig {
@@ -8,4 +8,7 @@ pub struct ETHClientConfig {
     pub chain_id: u64,
     /// Address of the Ethereum node API.
     pub web3_url: String,
+    /// Address of the Ethereum L1 node API if the settlement layer accessed via `web3_url` is itself an L2 rollup.
+    /// If specified, L1 prices (which determine the pubdata costs on the settlement layer) are tracked as well.
+    pub l1_web3_url: Option<String>,
 }
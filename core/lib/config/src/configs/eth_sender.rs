@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use serde::Deserialize;
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, H256};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -10,6 +10,19 @@ pub struct ETHSenderConfig {
     pub sender: SenderConfig,
     /// Options related to the `GasAdjuster` submodule.
     pub gas_adjuster: GasAdjusterConfig,
+    /// Remote signer for the main operator account. If not set, the operator private key is used.
+    pub operator_signer: Option<OperatorSignerConfig>,
+    /// Monitoring of operator account balances. If not set, balances are not monitored.
+    pub balance_monitor: Option<OperatorBalanceMonitorConfig>,
+    /// Dispatching of L1 batch pubdata to a data availability layer. Required if the chain doesn't use
+    /// the rollup data availability mode.
+    pub da_dispatcher: Option<DADispatcherConfig>,
+    /// Pricing of pubdata based on the actual cost of the data availability layer of the chain. If not set,
+    /// pubdata is priced as if it was published in the settlement layer calldata.
+    pub da_cost_oracle: Option<DACostOracleConfig>,
+    /// Settlement layer emergency mode, in which the node only proves and executes L1 batches committed
+    /// by an inactive operator. If not set, the node operates normally.
+    pub emergency_mode: Option<EmergencyModeConfig>,
 }
 
 impl ETHSenderConfig {
@@ -35,6 +48,15 @@ impl ETHSenderConfig {
                 l1_batch_min_age_before_execute_seconds: None,
                 max_acceptable_priority_fee_in_gwei: 100000000000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
+                pubdata_sending_mode: PubdataSendingMode::Calldata,
+                resubmission_interval_blocks: None,
+                priority_fee_bump_percent: None,
+                max_fee_per_gas_cap: None,
+                max_fee_bumps_before_replacement: None,
+                dry_run: false,
+                target_cost_per_batch_in_gwei: None,
+                settlement_checks_enabled: false,
+                max_settlement_reorg_depth: None,
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 1000000000,
@@ -45,16 +67,216 @@ impl ETHSenderConfig {
                 internal_enforced_l1_gas_price: None,
                 poll_period: 5,
                 max_l1_gas_price: None,
+                num_samples_for_blob_base_fee_estimate: 10,
+                l1_pubdata_price_scalar: 1.0,
             },
+            operator_signer: None,
+            balance_monitor: None,
+            da_dispatcher: None,
+            da_cost_oracle: None,
+            emergency_mode: None,
         }
     }
 }
 
+/// Configuration of a remote signer (KMS, HSM or a web3-signer-compatible service) holding the operator key.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct OperatorSignerConfig {
+    #[serde(flatten)]
+    pub mode: OperatorSignerMode,
+    /// Address of the operator account controlled by the signer. Signatures returned by the signer
+    /// are checked against this address.
+    pub operator_address: Address,
+    /// Timeout for a single request to the signer.
+    #[serde(default = "OperatorSignerConfig::default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+impl OperatorSignerConfig {
+    const fn default_request_timeout_ms() -> u64 {
+        5_000
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+}
+
+/// Remote signing service. Credentials for cloud KMS services are not part of the config; they are obtained
+/// from the standard environment of the corresponding cloud.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(tag = "mode")]
+pub enum OperatorSignerMode {
+    /// Service compatible with the `web3signer` raw signing API (`/api/v1/eth1/sign`).
+    Web3Signer {
+        web3_signer_url: String,
+        /// Identifier of the key in the signer (hex-encoded secp256k1 public key).
+        web3_signer_key_id: String,
+    },
+    /// AWS KMS asymmetric `ECC_SECG_P256K1` key. Credentials are read from the `AWS_ACCESS_KEY_ID`,
+    /// `AWS_SECRET_ACCESS_KEY` and (optionally) `AWS_SESSION_TOKEN` env variables.
+    AwsKms {
+        aws_kms_region: String,
+        aws_kms_key_id: String,
+    },
+    /// GCP Cloud KMS `EC_SIGN_SECP256K1_SHA256` key version. Access tokens are obtained
+    /// from the GCE metadata server.
+    GcpKms {
+        /// Full resource name of the key version, e.g.
+        /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`.
+        gcp_kms_key_version_name: String,
+    },
+}
+
+/// Configuration of the task monitoring balances of the operator accounts on the settlement layer.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct OperatorBalanceMonitorConfig {
+    /// Interval between balance checks.
+    #[serde(default = "OperatorBalanceMonitorConfig::default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Balance of an operator account (in gwei) below which an alert is raised and a top-up is requested.
+    pub low_balance_threshold_gwei: u64,
+    /// URL of a webhook receiving a POST request with a JSON payload for each operator account with a low balance.
+    pub top_up_webhook_url: Option<String>,
+    /// Address of a funding contract with a `topUp(address)` function transferring funds to the specified account.
+    /// The function is called from the funder account; see [`Self::funder_private_key()`].
+    pub funding_contract_address: Option<Address>,
+    /// Minimum interval between top-up requests for the same operator account.
+    #[serde(default = "OperatorBalanceMonitorConfig::default_top_up_cooldown_ms")]
+    pub top_up_cooldown_ms: u64,
+}
+
+impl OperatorBalanceMonitorConfig {
+    const fn default_check_interval_ms() -> u64 {
+        60_000
+    }
+
+    const fn default_top_up_cooldown_ms() -> u64 {
+        600_000
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+
+    pub fn top_up_cooldown(&self) -> Duration {
+        Duration::from_millis(self.top_up_cooldown_ms)
+    }
+
+    /// Private key of the account calling the funding contract. Must be set if `funding_contract_address` is set.
+    pub fn funder_private_key(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_BALANCE_MONITOR_FUNDER_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
+}
+
+/// Configuration of the task dispatching L1 batch pubdata to a data availability layer for validium chains
+/// and chains using an external DA layer.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct DADispatcherConfig {
+    /// Interval between dispatcher iterations. On each iteration, pubdata of new L1 batches is dispatched,
+    /// and inclusion data is polled for the dispatched pubdata.
+    pub polling_interval_ms: u64,
+    /// Maximum number of L1 batches dispatched during a single iteration.
+    #[serde(default = "DADispatcherConfig::default_max_batches_to_dispatch")]
+    pub max_batches_to_dispatch: u32,
+    /// URL of the HTTP API of the external DA layer. Required if the chain uses an external DA layer.
+    pub external_da_url: Option<String>,
+    /// URL of the JSON-RPC API of a Celestia light or bridge node. Required if the chain uses Celestia.
+    pub celestia_node_url: Option<String>,
+    /// Hex-encoded ID (up to 10 bytes) of the version 0 Celestia namespace to post pubdata to.
+    /// Required if the chain uses Celestia.
+    pub celestia_namespace: Option<String>,
+    /// URL of the EigenDA disperser API. Required if the chain uses EigenDA.
+    pub eigenda_disperser_url: Option<String>,
+    /// If set, L1 batches with pubdata not included into the DA layer within this timeout since sealing
+    /// are committed with pubdata published on the settlement layer instead.
+    pub failover_timeout_ms: Option<u64>,
+    /// First protocol version for which pubdata is compressed before dispatching it to the DA layer.
+    /// If not set, pubdata is dispatched uncompressed.
+    pub compression_protocol_version: Option<u16>,
+    /// zstd compression level (1 to 22). The default level favors compression ratio over speed.
+    pub compression_level: Option<u32>,
+    /// Path to a zstd dictionary used to compress pubdata. The same dictionary is required to decompress pubdata,
+    /// e.g. for external nodes verifying data availability.
+    pub compression_dictionary_path: Option<String>,
+}
+
+impl DADispatcherConfig {
+    const fn default_max_batches_to_dispatch() -> u32 {
+        100
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+
+    pub fn failover_timeout(&self) -> Option<Duration> {
+        self.failover_timeout_ms.map(Duration::from_millis)
+    }
+
+    /// Auth token for the JSON-RPC API of the Celestia node. If not set, requests are not authenticated.
+    pub fn celestia_auth_token(&self) -> Option<String> {
+        std::env::var("ETH_SENDER_DA_DISPATCHER_CELESTIA_AUTH_TOKEN").ok()
+    }
+}
+
+/// Configuration of the oracle tracking the cost of publishing pubdata on the data availability layer
+/// of the chain (EIP-4844 blobs for rollups, or an external DA layer). The tracked price is used as
+/// the pubdata price in the fee model. Prices are updated with the `GasAdjuster` poll period.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct DACostOracleConfig {
+    /// Number of price observations from which the median price is taken.
+    pub max_price_samples: usize,
+    /// Price of the smallest unit of the DA layer native token (e.g., utia for Celestia) in wei.
+    /// Required for DA layers charging fees in their native token.
+    pub native_token_price_in_wei: Option<f64>,
+    /// Multiplier applied to the DA layer price, e.g. to account for pubdata compression or the operator margin.
+    #[serde(default = "DACostOracleConfig::default_price_scalar")]
+    pub price_scalar: f64,
+    /// Upper bound for the pubdata price, in wei per byte.
+    pub max_price_per_byte: Option<u64>,
+    /// Address of the EigenDA `PaymentVault` contract on L1, from which the on-demand EigenDA price is read.
+    /// Required if the chain uses EigenDA.
+    pub eigenda_payment_vault_address: Option<Address>,
+}
+
+impl DACostOracleConfig {
+    const fn default_price_scalar() -> f64 {
+        1.0
+    }
+
+    pub fn max_price_per_byte(&self) -> u64 {
+        self.max_price_per_byte.unwrap_or(u64::MAX)
+    }
+}
+
+/// Configuration of the settlement layer emergency mode (aka escape hatch). Once the operator hasn't committed
+/// L1 batches for the configured time, the settlement layer contract allows anyone to prove and execute
+/// already committed L1 batches. In this mode, the node is run by a third party and never commits L1 batches;
+/// it only proves and executes L1 batches committed on the settlement layer after the operator becomes inactive.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EmergencyModeConfig {
+    /// Time since the last L1 batch commitment on the settlement layer after which the operator is considered
+    /// inactive. Must not be lower than the inactivity period enforced by the settlement layer contract.
+    pub inactivity_threshold_hours: u64,
+}
+
+impl EmergencyModeConfig {
+    pub fn inactivity_threshold(&self) -> Duration {
+        Duration::from_secs(self.inactivity_threshold_hours * 3_600)
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
 pub enum ProofSendingMode {
     OnlyRealProofs,
     OnlySampledProofs,
     SkipEveryProof,
+    /// Proofs are loaded in the same way as real ones, but are sent with an empty proof, which is only accepted
+    /// by the testnet verifier. Should be used together with mock proof generation in the proof data handler.
+    OnlyMockProofs,
 }
 
 #[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
@@ -63,6 +285,17 @@ pub enum ProofLoadingMode {
     FriProofFromGcs,
 }
 
+/// Data availability mode used to publish pubdata of committed L1 batches.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum PubdataSendingMode {
+    /// Pubdata is always sent as a part of the commit transaction calldata.
+    #[default]
+    Calldata,
+    /// Pubdata is sent in EIP-4844 blobs if the settlement layer supports them and blobs are cheaper
+    /// than calldata; otherwise, the sender falls back to calldata.
+    Blobs,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct SenderConfig {
     pub aggregated_proof_sizes: Vec<usize>,
@@ -96,6 +329,45 @@ pub struct SenderConfig {
 
     /// The mode in which proofs are loaded, either from DB/GCS for FRI/Old proof.
     pub proof_loading_mode: ProofLoadingMode,
+
+    /// The mode in which pubdata of committed L1 batches is published.
+    #[serde(default)]
+    pub pubdata_sending_mode: PubdataSendingMode,
+
+    /// Minimum number of L1 blocks between resubmissions of a stuck transaction. If not specified,
+    /// stuck transactions may be resubmitted on each new L1 block.
+    pub resubmission_interval_blocks: Option<u32>,
+    /// Percentage by which the priority fee is increased on each resubmission. Must be at least 10%
+    /// to satisfy transaction replacement rules of L1 nodes. If not specified, 20% is used.
+    pub priority_fee_bump_percent: Option<u64>,
+    /// Upper bound for `max_fee_per_gas` (i.e., base fee + priority fee) of sent transactions, in wei.
+    /// Once a stuck transaction reaches the cap, it's not resubmitted until the fees it pays become competitive.
+    pub max_fee_per_gas_cap: Option<u64>,
+    /// Number of consecutive fee bumps after which a stuck transaction is replaced by a transaction
+    /// with doubled fees, regardless of the fees currently suggested by the gas adjuster.
+    /// If not specified, stuck transactions are never replaced this way.
+    pub max_fee_bumps_before_replacement: Option<u32>,
+
+    /// If set, the Ethereum sender doesn't send any transactions. Instead, commit, prove and execute operations
+    /// covering the next pending L1 batches are encoded and simulated using `eth_call` against L1, and the simulation
+    /// results are logged. Publish criteria are ignored, but the number of L1 batches per operation is still limited
+    /// by `max_aggregated_blocks_to_commit`, `aggregated_proof_sizes` and `max_aggregated_blocks_to_execute`.
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Target L1 cost per L1 batch for commit and execute operations, in gwei. If specified, L1 batches are published
+    /// as soon as the estimated cost of the operation split among its batches doesn't exceed the target at current
+    /// gas prices; otherwise, more batches are accumulated. Other aggregation limits and deadlines still apply.
+    pub target_cost_per_batch_in_gwei: Option<u64>,
+
+    /// If set, the Ethereum sender checks the settlement layer state before aggregating operations, and halts
+    /// new commit and execute operations if an anomaly is detected (e.g., the diamond proxy is frozen, or the hash
+    /// of the last committed L1 batch doesn't match the local one). Halts can be overridden by the operator.
+    #[serde(default)]
+    pub settlement_checks_enabled: bool,
+    /// Maximum depth of a settlement layer reorg (in L1 blocks) tolerated by settlement checks. Deeper reorgs
+    /// halt settlement. If not specified, reorgs are not checked.
+    pub max_settlement_reorg_depth: Option<u64>,
 }
 
 impl SenderConfig {
@@ -115,6 +387,44 @@ impl SenderConfig {
             .ok()
             .map(|pk| pk.parse().unwrap())
     }
+
+    /// Private key of the dedicated account sending commit transactions. If not set, commit transactions
+    /// are sent from the main operator account.
+    pub fn commit_private_key(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_SENDER_OPERATOR_COMMIT_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
+
+    /// Private key of the dedicated account sending prove transactions. If not set, prove transactions
+    /// are sent from the main operator account.
+    pub fn prove_private_key(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_SENDER_OPERATOR_PROVE_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
+
+    /// Private key of the dedicated account sending execute transactions. If not set, execute transactions
+    /// are sent from the main operator account.
+    pub fn execute_private_key(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_SENDER_OPERATOR_EXECUTE_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
+
+    /// Comma-separated private keys of operator accounts that were rotated out. These accounts are never used
+    /// for new transactions; they are only used to resend transactions that were in flight during the rotation.
+    pub fn retired_private_keys(&self) -> Vec<H256> {
+        std::env::var("ETH_SENDER_SENDER_RETIRED_OPERATOR_PRIVATE_KEYS")
+            .map(|keys| {
+                keys.split(',')
+                    .map(str::trim)
+                    .filter(|pk| !pk.is_empty())
+                    .map(|pk| pk.parse().unwrap())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
@@ -135,6 +445,13 @@ pub struct GasAdjusterConfig {
     pub poll_period: u64,
     /// Max number of l1 gas price that is allowed to be used in state keeper.
     pub max_l1_gas_price: Option<u64>,
+    /// Number of blocks collected by GasAdjuster from which the blob base fee median is taken
+    #[serde(default = "GasAdjusterConfig::default_num_samples_for_blob_base_fee_estimate")]
+    pub num_samples_for_blob_base_fee_estimate: usize,
+    /// Multiplier applied to the L1 price of publishing a pubdata byte if the settlement layer is an L2 rollup.
+    /// Accounts for the way the settlement layer charges for the data it publishes on L1 (e.g., compression).
+    #[serde(default = "GasAdjusterConfig::default_l1_pubdata_price_scalar")]
+    pub l1_pubdata_price_scalar: f64,
 }
 
 impl GasAdjusterConfig {
@@ -146,4 +463,12 @@ impl GasAdjusterConfig {
     pub fn max_l1_gas_price(&self) -> u64 {
         self.max_l1_gas_price.unwrap_or(u64::MAX)
     }
+
+    pub const fn default_num_samples_for_blob_base_fee_estimate() -> usize {
+        10
+    }
+
+    pub const fn default_l1_pubdata_price_scalar() -> f64 {
+        1.0
+    }
 }
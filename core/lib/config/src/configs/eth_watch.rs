@@ -2,6 +2,21 @@ use std::time::Duration;
 
 use serde::Deserialize;
 
+/// Mode used by the Ethereum watcher to determine settlement layer blocks that can be processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EthWatchFinalityMode {
+    /// A block is processed once it has `confirmations_for_eth_event` confirmations.
+    Confirmations,
+    /// A block is processed once it's `safe` according to the settlement layer node.
+    Safe,
+    /// A block is processed once it's `finalized` according to the settlement layer node.
+    Finalized,
+    /// A block is processed once the L1 batch containing it is executed. Only applicable to settlement layers
+    /// that are zkSync-based chains.
+    BatchExecution,
+}
+
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ETHWatchConfig {
@@ -11,6 +26,13 @@ pub struct ETHWatchConfig {
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// Mode used to determine settlement layer blocks that can be processed. If not specified,
+    /// it's inferred from `confirmations_for_eth_event`.
+    pub finality_mode: Option<EthWatchFinalityMode>,
+    /// Number of already processed settlement layer blocks rescanned on each poll, so that events
+    /// moved to other blocks by reorgs are not missed. Should be set for settlement layers
+    /// with probabilistic finality.
+    pub reorg_rescan_depth: Option<u64>,
 }
 
 impl ETHWatchConfig {
@@ -18,4 +40,14 @@ impl ETHWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Returns the finality mode, falling back to the mode implied by `confirmations_for_eth_event`.
+    pub fn finality_mode(&self) -> EthWatchFinalityMode {
+        self.finality_mode
+            .unwrap_or(if self.confirmations_for_eth_event.is_some() {
+                EthWatchFinalityMode::Confirmations
+            } else {
+                EthWatchFinalityMode::Finalized
+            })
+    }
 }
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_basic_types::{Address, H256};
+
+/// Configuration for the fee distributor, which periodically sweeps fees collected by the fee account
+/// to the configured destinations.
+///
+/// Shares of the destinations are specified in basis points and must add up to 10,000. A destination
+/// with a zero share doesn't receive transfers, and its address may be omitted.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeeDistributorConfig {
+    /// Interval between checks of the statuses of submitted transfers and whether a sweep is due.
+    #[serde(default = "FeeDistributorConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u64,
+    /// Minimum interval between consecutive sweeps.
+    #[serde(default = "FeeDistributorConfig::default_sweep_interval_sec")]
+    pub sweep_interval_sec: u64,
+    /// Balance (in wei) left on the fee account after a sweep, in addition to the fees for the sweep transfers.
+    #[serde(default)]
+    pub min_balance: u64,
+    /// Minimum amount (in wei) distributed by a sweep. If less funds can be distributed, the sweep is postponed.
+    #[serde(default = "FeeDistributorConfig::default_min_sweep_amount")]
+    pub min_sweep_amount: u64,
+    /// Gas limit for each sweep transfer.
+    #[serde(default = "FeeDistributorConfig::default_gas_limit_per_transfer")]
+    pub gas_limit_per_transfer: u64,
+    /// Treasury receiving its share of the fees.
+    pub treasury_addr: Option<Address>,
+    /// Share of the treasury in basis points.
+    #[serde(default)]
+    pub treasury_share_bps: u32,
+    /// Sequencer receiving its share of the fees.
+    pub sequencer_addr: Option<Address>,
+    /// Share of the sequencer in basis points.
+    #[serde(default)]
+    pub sequencer_share_bps: u32,
+    /// Address that the burned share of the fees is sent to. If not set, [`Self::DEFAULT_BURN_ADDR`] is used.
+    pub burn_addr: Option<Address>,
+    /// Share of the burned fees in basis points.
+    #[serde(default)]
+    pub burn_share_bps: u32,
+}
+
+impl FeeDistributorConfig {
+    /// Default address for burned fees (`0x000000000000000000000000000000000000dEaD`).
+    pub const DEFAULT_BURN_ADDR: Address = Address([
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xde, 0xad,
+    ]);
+
+    const fn default_polling_interval_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_sweep_interval_sec() -> u64 {
+        3_600
+    }
+
+    const fn default_min_sweep_amount() -> u64 {
+        1_000_000_000_000_000 // 0.001 ETH
+    }
+
+    const fn default_gas_limit_per_transfer() -> u64 {
+        1_000_000
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+
+    pub fn sweep_interval(&self) -> Duration {
+        Duration::from_secs(self.sweep_interval_sec)
+    }
+
+    pub fn burn_addr(&self) -> Address {
+        self.burn_addr.unwrap_or(Self::DEFAULT_BURN_ADDR)
+    }
+
+    /// Private key of the fee account. Returns an error if the key is set, but is malformed.
+    pub fn private_key(&self) -> anyhow::Result<Option<H256>> {
+        match std::env::var("FEE_DISTRIBUTOR_PRIVATE_KEY") {
+            Ok(private_key) => private_key
+                .parse()
+                .map(Some)
+                .context("FEE_DISTRIBUTOR_PRIVATE_KEY is not a valid 32-byte hex value"),
+            Err(_) => Ok(None),
+        }
+    }
+}
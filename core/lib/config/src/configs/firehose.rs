@@ -0,0 +1,42 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+/// Configuration for the Firehose block stream used by The Graph and Substreams indexers.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FirehoseConfig {
+    /// Port to bind the gRPC server to.
+    #[serde(default = "FirehoseConfig::default_port")]
+    pub port: u16,
+    /// Interval between polls for new miniblocks when streaming the chain head.
+    #[serde(default = "FirehoseConfig::default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Maximum number of concurrent block streams.
+    #[serde(default = "FirehoseConfig::default_max_streams")]
+    pub max_streams: usize,
+}
+
+impl FirehoseConfig {
+    const fn default_port() -> u16 {
+        3092
+    }
+
+    const fn default_poll_interval_ms() -> u64 {
+        500
+    }
+
+    const fn default_max_streams() -> usize {
+        16
+    }
+
+    pub fn bind_address(&self) -> SocketAddr {
+        (Ipv4Addr::UNSPECIFIED, self.port).into()
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+}
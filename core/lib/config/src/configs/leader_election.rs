@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration of leader election between sequencer instances running in active / standby mode.
+/// The configuration must be the same for all instances of a chain. If the configuration is not provided,
+/// singleton writers (the state keeper, the Ethereum sender, the DA dispatcher and components sending settlement layer
+/// transactions) are started unconditionally. Since all singleton writers of a process are gated by a single lock,
+/// processes running different sets of components must use different lock IDs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LeaderElectionConfig {
+    /// ID of the session-level Postgres advisory lock held by the active sequencer.
+    pub lock_id: i64,
+    /// Interval between attempts to acquire the lock by a standby instance, and between checks that
+    /// the lock is still held by the leader.
+    #[serde(default = "LeaderElectionConfig::default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Interval between catching up the state keeper cache with Postgres by a standby instance.
+    #[serde(default = "LeaderElectionConfig::default_standby_catchup_interval_ms")]
+    pub standby_catchup_interval_ms: u64,
+}
+
+impl LeaderElectionConfig {
+    const fn default_check_interval_ms() -> u64 {
+        1_000
+    }
+
+    const fn default_standby_catchup_interval_ms() -> u64 {
+        10_000
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+
+    pub fn standby_catchup_interval(&self) -> Duration {
+        Duration::from_millis(self.standby_catchup_interval_ms)
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use zksync_basic_types::{Address, H256};
+
+/// Configuration for the message relay, which delivers arbitrary messages between the chain
+/// and the settlement layer.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MessageRelayConfig {
+    /// Interval between checks for new messages and delivery transaction statuses.
+    #[serde(default = "MessageRelayConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u64,
+    /// Chain contracts whose messages sent via the L1 messenger are delivered to the settlement layer.
+    #[serde(default)]
+    pub outbound_senders: Vec<Address>,
+    /// Settlement layer contract delivering messages from the chain. If not set, outbound messages are not relayed.
+    pub inbox_addr: Option<Address>,
+    /// Settlement layer contract that messages to the chain are sent to. If not set, inbound messages
+    /// are not relayed.
+    pub outbox_addr: Option<Address>,
+    /// Maximum number of messages delivered in a single settlement layer transaction.
+    #[serde(default = "MessageRelayConfig::default_max_messages_per_tx")]
+    pub max_messages_per_tx: u32,
+    /// Gas limit allocated for delivering a single message.
+    #[serde(default = "MessageRelayConfig::default_gas_limit_per_message")]
+    pub gas_limit_per_message: u64,
+    /// Maximum number of delivery attempts for a message. Messages that fail to be delivered
+    /// after this number of attempts are marked as failed.
+    #[serde(default = "MessageRelayConfig::default_max_attempts")]
+    pub max_attempts: u32,
+    /// Time after which a delivery transaction that is not mined is replaced with a transaction
+    /// with bumped fees.
+    #[serde(default = "MessageRelayConfig::default_tx_resend_interval_sec")]
+    pub tx_resend_interval_sec: u64,
+}
+
+impl MessageRelayConfig {
+    const fn default_polling_interval_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_max_messages_per_tx() -> u32 {
+        10
+    }
+
+    const fn default_gas_limit_per_message() -> u64 {
+        300_000
+    }
+
+    const fn default_max_attempts() -> u32 {
+        3
+    }
+
+    const fn default_tx_resend_interval_sec() -> u64 {
+        300
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+
+    pub fn tx_resend_interval(&self) -> Duration {
+        Duration::from_secs(self.tx_resend_interval_sec)
+    }
+
+    /// Private key of the account sending delivery transactions.
+    pub fn private_key(&self) -> Option<H256> {
+        std::env::var("MESSAGE_RELAY_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
+}
@@ -2,34 +2,56 @@
 pub use self::{
     alerts::AlertsConfig,
     api::ApiConfig,
+    audit_log::AuditLogConfig,
+    cdc_publisher::CdcPublisherConfig,
+    chain_export::ChainExportConfig,
+    config_reload::ConfigReloadConfig,
     contract_verifier::ContractVerifierConfig,
     contracts::ContractsConfig,
     database::{DBConfig, PostgresConfig},
     eth_client::ETHClientConfig,
     eth_sender::{ETHSenderConfig, GasAdjusterConfig},
     eth_watch::ETHWatchConfig,
+    fee_distributor::FeeDistributorConfig,
+    firehose::FirehoseConfig,
     fri_proof_compressor::FriProofCompressorConfig,
     fri_prover::FriProverConfig,
     fri_prover_gateway::FriProverGatewayConfig,
     fri_witness_generator::FriWitnessGeneratorConfig,
     fri_witness_vector_generator::FriWitnessVectorGeneratorConfig,
+    leader_election::LeaderElectionConfig,
+    message_relay::MessageRelayConfig,
     object_store::ObjectStoreConfig,
     observability::ObservabilityConfig,
     proof_data_handler::ProofDataHandlerConfig,
+    reexecution_watchdog::ReexecutionWatchdogConfig,
+    rosetta_api::RosettaApiConfig,
+    shared_sequencer::SharedSequencerConfig,
     snapshots_creator::SnapshotsCreatorConfig,
+    stable_gas_price::StableGasPriceConfig,
+    supervisor::SupervisorConfig,
+    supply_checker::SupplyCheckerConfig,
     utils::PrometheusConfig,
+    webhooks::WebhooksConfig,
+    withdrawal_finalizer::WithdrawalFinalizerConfig,
     witness_generator::WitnessGeneratorConfig,
 };
 
 pub mod alerts;
 pub mod api;
+pub mod audit_log;
+pub mod cdc_publisher;
 pub mod chain;
+pub mod chain_export;
+pub mod config_reload;
 pub mod contract_verifier;
 pub mod contracts;
 pub mod database;
 pub mod eth_client;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod fee_distributor;
+pub mod firehose;
 pub mod fri_proof_compressor;
 pub mod fri_prover;
 pub mod fri_prover_gateway;
@@ -37,11 +59,21 @@ pub mod fri_prover_group;
 pub mod fri_witness_generator;
 pub mod fri_witness_vector_generator;
 pub mod house_keeper;
+pub mod leader_election;
+pub mod message_relay;
 pub mod object_store;
 pub mod observability;
 pub mod proof_data_handler;
+pub mod reexecution_watchdog;
+pub mod rosetta_api;
+pub mod shared_sequencer;
 pub mod snapshots_creator;
+pub mod stable_gas_price;
+pub mod supervisor;
+pub mod supply_checker;
 pub mod utils;
+pub mod webhooks;
+pub mod withdrawal_finalizer;
 pub mod witness_generator;
 
 const BYTES_IN_MEGABYTE: usize = 1_024 * 1_024;
@@ -31,4 +31,19 @@ pub enum ObjectStoreMode {
     FileBacked {
         file_backed_base_path: String,
     },
+    /// AWS S3 or an S3-compatible store. Credentials are loaded from the standard AWS provider chain
+    /// (e.g., `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` env variables).
+    S3 {
+        /// Name of the S3 bucket.
+        bucket_base_url: String,
+        region: Option<String>,
+        /// Custom endpoint URL for S3-compatible stores.
+        endpoint: Option<String>,
+    },
+    /// Azure Blob Storage. If the `AZURE_STORAGE_ACCESS_KEY` env variable is set, it is used as
+    /// the storage account access key; otherwise, the store is accessed anonymously (read-only).
+    AzureBlob {
+        storage_account: String,
+        container: String,
+    },
 }
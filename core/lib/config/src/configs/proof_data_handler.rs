@@ -8,12 +8,26 @@ pub enum ProtocolVersionLoadingMode {
     FromEnvVar,
 }
 
+/// Source of proofs for L1 batches.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ProofGenerationMode {
+    /// Proofs are generated by provers polling the proof data handler.
+    #[default]
+    Prover,
+    /// Proofs are mocked by the proof data handler itself, and no proof generation data is served to provers.
+    /// Mock proofs are only accepted by the testnet verifier; they should be sent
+    /// with the `OnlyMockProofs` proof sending mode.
+    Mock,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ProofDataHandlerConfig {
     pub http_port: u16,
     pub proof_generation_timeout_in_secs: u16,
     pub protocol_version_loading_mode: ProtocolVersionLoadingMode,
     pub fri_protocol_version_id: u16,
+    #[serde(default)]
+    pub proof_generation_mode: ProofGenerationMode,
 }
 
 impl ProofDataHandlerConfig {
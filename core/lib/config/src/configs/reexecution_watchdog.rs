@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the re-execution watchdog, which re-executes a random sample of recent L1 batches
+/// and compares the outcome with the data persisted by the state keeper.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReexecutionWatchdogConfig {
+    /// Interval between consecutive checks. Each check re-executes a single L1 batch.
+    #[serde(default = "ReexecutionWatchdogConfig::default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Number of the latest sealed L1 batches the re-executed batch is sampled from.
+    #[serde(default = "ReexecutionWatchdogConfig::default_sample_window_batches")]
+    pub sample_window_batches: u32,
+}
+
+impl ReexecutionWatchdogConfig {
+    const fn default_check_interval_ms() -> u64 {
+        300_000
+    }
+
+    const fn default_sample_window_batches() -> u32 {
+        100
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+}
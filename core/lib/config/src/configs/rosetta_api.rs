@@ -0,0 +1,50 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use serde::Deserialize;
+
+/// Configuration for the Rosetta (Mesh) API server used by exchanges to integrate with the chain.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RosettaApiConfig {
+    /// Port to bind the API server to.
+    #[serde(default = "RosettaApiConfig::default_port")]
+    pub port: u16,
+    /// Network name reported in network identifiers (e.g., `mainnet` or `testnet`).
+    #[serde(default = "RosettaApiConfig::default_network")]
+    pub network: String,
+    /// Symbol of the base token reported in balances and operation amounts.
+    #[serde(default = "RosettaApiConfig::default_base_token_symbol")]
+    pub base_token_symbol: String,
+    /// Number of decimals of the base token.
+    #[serde(default = "RosettaApiConfig::default_base_token_decimals")]
+    pub base_token_decimals: u32,
+    /// Maximum number of token events loaded by a single query. Operations of transactions in blocks
+    /// with more events are returned via the `/block/transaction` endpoint.
+    #[serde(default = "RosettaApiConfig::default_max_block_events")]
+    pub max_block_events: usize,
+}
+
+impl RosettaApiConfig {
+    const fn default_port() -> u16 {
+        3090
+    }
+
+    fn default_network() -> String {
+        "mainnet".to_owned()
+    }
+
+    fn default_base_token_symbol() -> String {
+        "ETH".to_owned()
+    }
+
+    const fn default_base_token_decimals() -> u32 {
+        18
+    }
+
+    const fn default_max_block_events() -> usize {
+        10_000
+    }
+
+    pub fn bind_address(&self) -> SocketAddr {
+        (Ipv4Addr::UNSPECIFIED, self.port).into()
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration of the shared sequencer integration. If provided, the state keeper executes transactions
+/// in the order received from the shared sequencer instead of taking them from the local mempool.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SharedSequencerConfig {
+    /// URL of the shared sequencer gRPC endpoint.
+    pub url: String,
+    /// Time without a connection to the shared sequencer after which the state keeper falls back
+    /// to the local mempool. The fallback ends as soon as the connection is restored.
+    #[serde(default = "SharedSequencerConfig::default_fallback_timeout_ms")]
+    pub fallback_timeout_ms: u64,
+    /// Interval between attempts to reconnect to the shared sequencer.
+    #[serde(default = "SharedSequencerConfig::default_reconnect_interval_ms")]
+    pub reconnect_interval_ms: u64,
+    /// Interval between acknowledging transactions in sealed miniblocks to the shared sequencer.
+    #[serde(default = "SharedSequencerConfig::default_ack_interval_ms")]
+    pub ack_interval_ms: u64,
+    /// Maximum number of received transactions buffered before they are executed by the state keeper.
+    #[serde(default = "SharedSequencerConfig::default_max_buffered_txs")]
+    pub max_buffered_txs: u32,
+}
+
+impl SharedSequencerConfig {
+    const fn default_fallback_timeout_ms() -> u64 {
+        30_000
+    }
+
+    const fn default_reconnect_interval_ms() -> u64 {
+        1_000
+    }
+
+    const fn default_ack_interval_ms() -> u64 {
+        500
+    }
+
+    const fn default_max_buffered_txs() -> u32 {
+        1_000
+    }
+
+    pub fn fallback_timeout(&self) -> Duration {
+        Duration::from_millis(self.fallback_timeout_ms)
+    }
+
+    pub fn reconnect_interval(&self) -> Duration {
+        Duration::from_millis(self.reconnect_interval_ms)
+    }
+
+    pub fn ack_interval(&self) -> Duration {
+        Duration::from_millis(self.ack_interval_ms)
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -7,6 +9,17 @@ pub struct SnapshotsCreatorConfig {
 
     #[serde(default = "snapshots_creator_concurrent_queries_count")]
     pub concurrent_queries_count: u32,
+
+    /// Interval between snapshot creation attempts. If not set, the creator creates a single snapshot
+    /// and exits, which is suitable for running it as a cron job.
+    #[serde(default)]
+    pub creation_interval_sec: Option<u64>,
+}
+
+impl SnapshotsCreatorConfig {
+    pub fn creation_interval(&self) -> Option<Duration> {
+        self.creation_interval_sec.map(Duration::from_secs)
+    }
 }
 
 fn snapshots_creator_storage_logs_chunk_size_default() -> u64 {
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use zksync_basic_types::Address;
+
+/// Source of the base token price in the fiat currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceFeedSource {
+    /// HTTP oracle returning the price in a JSON document.
+    Http,
+    /// Chainlink-compatible aggregator contract on the settlement layer.
+    OnChain,
+}
+
+/// Configuration for the stable gas pricing mode, in which the L2 gas price is adjusted so that the cost
+/// of a reference transaction stays near the target in a fiat currency.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StableGasPriceConfig {
+    pub source: PriceFeedSource,
+    /// URL of the HTTP oracle. Required for the `http` source.
+    pub feed_url: Option<String>,
+    /// JSON pointer (e.g., `/ethereum/usd`) to the price in the HTTP oracle response. The price may be
+    /// represented either as a number or as a string.
+    #[serde(default = "StableGasPriceConfig::default_feed_json_pointer")]
+    pub feed_json_pointer: String,
+    /// Address of the aggregator contract on the settlement layer. Required for the `on_chain` source.
+    pub feed_contract_addr: Option<Address>,
+    /// Maximum age of the price reported by the aggregator contract. Older prices are ignored.
+    #[serde(default = "StableGasPriceConfig::default_max_price_age_sec")]
+    pub max_price_age_sec: u64,
+    /// Interval between consecutive price feed queries.
+    #[serde(default = "StableGasPriceConfig::default_update_interval_ms")]
+    pub update_interval_ms: u64,
+    /// Target cost of the reference transaction in the fiat currency of the price feed.
+    pub target_tx_cost: f64,
+    /// Gas consumed by the reference transaction, i.e. by an average transaction on the chain.
+    #[serde(default = "StableGasPriceConfig::default_reference_tx_gas")]
+    pub reference_tx_gas: u64,
+    /// Lower bound for the L2 gas price in wei.
+    pub min_l2_gas_price: u64,
+    /// Upper bound for the L2 gas price in wei.
+    pub max_l2_gas_price: u64,
+    /// Weight of the latest target price in the exponential moving average of the L2 gas price. Must be in (0, 1].
+    #[serde(default = "StableGasPriceConfig::default_smoothing_factor")]
+    pub smoothing_factor: f64,
+    /// Maximum relative change of the L2 gas price per update.
+    #[serde(default = "StableGasPriceConfig::default_max_change_per_update")]
+    pub max_change_per_update: f64,
+}
+
+impl StableGasPriceConfig {
+    fn default_feed_json_pointer() -> String {
+        "/price".to_owned()
+    }
+
+    const fn default_max_price_age_sec() -> u64 {
+        3_600
+    }
+
+    const fn default_update_interval_ms() -> u64 {
+        60_000
+    }
+
+    const fn default_reference_tx_gas() -> u64 {
+        300_000
+    }
+
+    const fn default_smoothing_factor() -> f64 {
+        0.2
+    }
+
+    const fn default_max_change_per_update() -> f64 {
+        0.1
+    }
+
+    pub fn max_price_age(&self) -> Duration {
+        Duration::from_secs(self.max_price_age_sec)
+    }
+
+    pub fn update_interval(&self) -> Duration {
+        Duration::from_millis(self.update_interval_ms)
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for supervision of background tasks that are safe to restart (e.g., Ethereum watcher,
+/// pub-sub notifiers and webhooks). If provided (i.e., `max_restarts` is set), such tasks are restarted
+/// with exponential backoff after a panic or an error; otherwise, a failure of any task shuts down the server.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SupervisorConfig {
+    /// Maximum number of restarts of a single task within `restart_window_sec`. Once it's exceeded,
+    /// the failure is escalated, i.e., the server is shut down.
+    pub max_restarts: u32,
+    /// Sliding window for counting restarts. If a task runs longer than this window before failing,
+    /// it's restarted with the initial backoff.
+    #[serde(default = "SupervisorConfig::default_restart_window_sec")]
+    pub restart_window_sec: u64,
+    /// Delay before the first restart of a failed task.
+    #[serde(default = "SupervisorConfig::default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Maximum delay before restarting a failed task. The delay is doubled after each consecutive restart.
+    #[serde(default = "SupervisorConfig::default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+impl SupervisorConfig {
+    const fn default_restart_window_sec() -> u64 {
+        600
+    }
+
+    const fn default_initial_backoff_ms() -> u64 {
+        1_000
+    }
+
+    const fn default_max_backoff_ms() -> u64 {
+        60_000
+    }
+
+    pub fn restart_window(&self) -> Duration {
+        Duration::from_secs(self.restart_window_sec)
+    }
+
+    pub fn initial_backoff(&self) -> Duration {
+        Duration::from_millis(self.initial_backoff_ms)
+    }
+
+    pub fn max_backoff(&self) -> Duration {
+        Duration::from_millis(self.max_backoff_ms)
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use zksync_basic_types::Address;
+
+/// Configuration for the supply checker, which reconciles the total supply of the base token on the chain
+/// with the amount locked on the settlement layer.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SupplyCheckerConfig {
+    /// Interval between consecutive checks.
+    #[serde(default = "SupplyCheckerConfig::default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Settlement layer address of the base token. If not set, the base token is ETH.
+    pub base_token_l1_addr: Option<Address>,
+    /// Settlement layer contract holding the locked base token of this chain only. If not set, the chain balance
+    /// tracked by the shared bridge (`chainBalance`) is used if the bridge is deployed, and the diamond proxy
+    /// balance otherwise.
+    pub locked_funds_addr: Option<Address>,
+    /// Supply of the base token minted on the chain without locking funds on the settlement layer
+    /// (e.g., initial balances in the genesis manifest).
+    #[serde(default)]
+    pub unbacked_supply_gwei: u64,
+    /// Maximum tolerated excess of the chain supply over the locked amount.
+    #[serde(default = "SupplyCheckerConfig::default_epsilon_gwei")]
+    pub epsilon_gwei: u64,
+    /// Maximum tolerated excess of the locked amount over the chain supply. This excess is expected to be non-zero
+    /// since funds of deposits not yet executed on the chain, and of withdrawals not yet tracked by the withdrawal
+    /// finalizer, are locked on the settlement layer. If not set, the excess doesn't raise an alert.
+    pub max_surplus_gwei: Option<u64>,
+    /// Number of consecutive checks with a divergence beyond the threshold required to raise an alert.
+    #[serde(default = "SupplyCheckerConfig::default_alert_after_checks")]
+    pub alert_after_checks: u32,
+}
+
+impl SupplyCheckerConfig {
+    const fn default_check_interval_ms() -> u64 {
+        60_000
+    }
+
+    const fn default_epsilon_gwei() -> u64 {
+        1_000
+    }
+
+    const fn default_alert_after_checks() -> u32 {
+        3
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for webhooks notifying external services (e.g., exchanges and bridges) about L1 batches
+/// being committed, proven and executed on the settlement layer.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebhooksConfig {
+    /// URLs notifications are POSTed to. Each notification is delivered to every URL.
+    pub urls: Vec<String>,
+    /// Interval between checks for L1 batch status changes and pending deliveries.
+    #[serde(default = "WebhooksConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u64,
+    /// Timeout for a single delivery request.
+    #[serde(default = "WebhooksConfig::default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+    /// Maximum number of delivery attempts for a notification. Notifications that fail to be delivered
+    /// after this number of attempts are kept in the delivery log, but are not retried.
+    #[serde(default = "WebhooksConfig::default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry of a failed delivery. The delay is doubled after each failed attempt.
+    #[serde(default = "WebhooksConfig::default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+}
+
+impl WebhooksConfig {
+    const fn default_polling_interval_ms() -> u64 {
+        1_000
+    }
+
+    const fn default_request_timeout_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_max_attempts() -> u32 {
+        10
+    }
+
+    const fn default_retry_backoff_ms() -> u64 {
+        1_000
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+
+    pub fn retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.retry_backoff_ms)
+    }
+
+    /// Secret used to sign notifications with HMAC-SHA256. If not set, notifications are not signed.
+    pub fn signing_secret(&self) -> Option<String> {
+        std::env::var("WEBHOOKS_SIGNING_SECRET").ok()
+    }
+}
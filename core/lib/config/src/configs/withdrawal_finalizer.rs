@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use zksync_basic_types::{Address, H256};
+
+/// Configuration for the withdrawal finalizer, which finalizes withdrawals from the chain on the settlement layer
+/// on behalf of users.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WithdrawalFinalizerConfig {
+    /// Interval between checks for new executed L1 batches and finalization transaction statuses.
+    #[serde(default = "WithdrawalFinalizerConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u64,
+    /// Settlement layer addresses of tokens whose withdrawals are finalized; the base token is denoted by
+    /// the zero address. Withdrawals of other tokens are tracked, but must be finalized by users.
+    #[serde(default)]
+    pub token_allowlist: Vec<Address>,
+    /// Maximum number of withdrawals finalized in a single settlement layer transaction.
+    #[serde(default = "WithdrawalFinalizerConfig::default_max_withdrawals_per_tx")]
+    pub max_withdrawals_per_tx: u32,
+    /// Gas limit allocated for finalizing a single withdrawal.
+    #[serde(default = "WithdrawalFinalizerConfig::default_gas_limit_per_withdrawal")]
+    pub gas_limit_per_withdrawal: u64,
+    /// Maximum gas price on the settlement layer in gwei. If the gas price is higher, finalization is postponed.
+    pub max_gas_price_gwei: Option<u64>,
+    /// Maximum number of finalization attempts for a withdrawal. Withdrawals that fail to be finalized
+    /// after this number of attempts are left to be finalized by users.
+    #[serde(default = "WithdrawalFinalizerConfig::default_max_attempts")]
+    pub max_attempts: u32,
+    /// Time after which a finalization transaction that is not mined is replaced with a transaction
+    /// with bumped fees.
+    #[serde(default = "WithdrawalFinalizerConfig::default_tx_resend_interval_sec")]
+    pub tx_resend_interval_sec: u64,
+}
+
+impl WithdrawalFinalizerConfig {
+    const fn default_polling_interval_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_max_withdrawals_per_tx() -> u32 {
+        10
+    }
+
+    const fn default_gas_limit_per_withdrawal() -> u64 {
+        200_000
+    }
+
+    const fn default_max_attempts() -> u32 {
+        3
+    }
+
+    const fn default_tx_resend_interval_sec() -> u64 {
+        300
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+
+    pub fn tx_resend_interval(&self) -> Duration {
+        Duration::from_secs(self.tx_resend_interval_sec)
+    }
+
+    /// Private key of the account sending finalization transactions.
+    pub fn private_key(&self) -> Option<H256> {
+        std::env::var("WITHDRAWAL_FINALIZER_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
+}
@@ -1,8 +1,12 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
 pub use crate::configs::{
-    ApiConfig, ContractVerifierConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig,
-    ETHWatchConfig, GasAdjusterConfig, ObjectStoreConfig, PostgresConfig, SnapshotsCreatorConfig,
+    ApiConfig, AuditLogConfig, CdcPublisherConfig, ChainExportConfig, ConfigReloadConfig,
+    ContractVerifierConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig,
+    ETHWatchConfig, FeeDistributorConfig, FirehoseConfig, GasAdjusterConfig, LeaderElectionConfig,
+    MessageRelayConfig, ObjectStoreConfig, PostgresConfig, ReexecutionWatchdogConfig,
+    RosettaApiConfig, SharedSequencerConfig, SnapshotsCreatorConfig, StableGasPriceConfig,
+    SupervisorConfig, SupplyCheckerConfig, WebhooksConfig, WithdrawalFinalizerConfig,
 };
 
 pub mod configs;
@@ -147,9 +147,22 @@ impl RandomConfig for Network {
 
 impl RandomConfig for configs::chain::FeeModelVersion {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        match g.rng.gen_range(0..2) {
+        match g.rng.gen_range(0..3) {
             0 => Self::V1,
-            _ => Self::V2,
+            1 => Self::V2,
+            _ => Self::V3,
+        }
+    }
+}
+
+impl RandomConfig for configs::chain::DataAvailabilityMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..5) {
+            0 => Self::Rollup,
+            1 => Self::Validium,
+            2 => Self::External,
+            3 => Self::Celestia,
+            _ => Self::EigenDA,
         }
     }
 }
@@ -193,6 +206,9 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             estimate_gas_acceptable_overestimation: g.gen(),
             l1_to_l2_transactions_compatibility_mode: g.gen(),
             max_tx_size: g.gen(),
+            max_factory_deps: g.gen(),
+            max_calldata_size: g.gen(),
+            soft_tx_limits_ratio: g.gen(),
             vm_execution_cache_misses_limit: g.gen(),
             vm_concurrency_limit: g.gen(),
             factory_deps_cache_size_mb: g.gen(),
@@ -203,13 +219,35 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             max_response_body_size_mb: g.gen(),
             websocket_requests_per_minute_limit: g.gen(),
             tree_api_url: g.gen(),
+            inclusion_horizon_batches: g.gen(),
+            operator_namespace_enabled: g.gen(),
+            operator_auth_token: g.gen(),
+            admin_namespace_enabled: g.gen(),
+            verify_content_hashes: g.gen(),
+            name_registry_addr: g.gen(),
+            resolve_names_in_params: g.gen(),
+            multicall_compute_budget_ms: g.gen(),
+            pubsub_lag_warning_threshold: g.gen(),
+            pubsub_lag_close_threshold: g.gen(),
+            shadow_api_url: g.gen(),
+            shadow_requests_percentage: g.gen(),
+            sandbox_warm_pool_enabled: g.gen(),
+            disabled_methods: g.gen(),
+            archive_calls_enabled: g.gen(),
+            pre_eip155_txs_allowed: g.gen(),
+            pre_eip155_tx_senders: g.gen(),
         }
     }
 }
 
 impl RandomConfig for configs::api::HealthCheckConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        Self { port: g.gen() }
+        Self {
+            port: g.gen(),
+            state_keeper_max_lag_sec: g.gen(),
+            tree_max_lag: g.gen(),
+            eth_sender_max_inflight_txs: g.gen(),
+        }
     }
 }
 
@@ -271,12 +309,21 @@ impl RandomConfig for configs::chain::StateKeeperConfig {
             max_gas_per_batch: g.gen(),
             max_pubdata_per_batch: g.gen(),
             fee_model_version: g.gen(),
+            // Must lie in (0, 1).
+            congestion_target_batch_fullness: g.gen::<Option<f64>>().map(|x| x.max(0.01)),
+            congestion_max_change_per_batch: g.gen(),
+            congestion_max_multiplier: g.gen(),
+            congestion_window_size: g.gen(),
+            data_availability_mode: g.gen(),
             validation_computational_gas_limit: g.gen(),
             save_call_traces: g.gen(),
             virtual_blocks_interval: g.gen(),
             virtual_blocks_per_miniblock: g.gen(),
             upload_witness_inputs_to_gcs: g.gen(),
             enum_index_migration_chunk_size: g.gen(),
+            max_txs_per_account_in_miniblock: g.gen(),
+            max_txs_per_account_in_batch: g.gen(),
+            forced_inclusion_deadline_ms: g.gen(),
         }
     }
 }
@@ -368,6 +415,11 @@ impl RandomConfig for configs::ContractsConfig {
             state_transition_proxy_addr: g.gen(),
             state_transition_impl_addr: g.gen(),
             transparent_proxy_admin_addr: g.gen(),
+            l1_shared_bridge_proxy_addr: g.gen(),
+            l2_shared_bridge_addr: g.gen(),
+            l1_shared_bridge_code_hash: g.gen(),
+            l2_shared_bridge_code_hash: g.gen(),
+            genesis_manifest_path: g.gen(),
         }
     }
 }
@@ -381,6 +433,25 @@ impl RandomConfig for configs::database::MerkleTreeMode {
     }
 }
 
+impl RandomConfig for configs::database::RocksdbCompactionStyle {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Level,
+            _ => Self::Universal,
+        }
+    }
+}
+
+impl RandomConfig for configs::database::RocksdbProfile {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            compaction_style: g.gen(),
+            max_total_wal_size_mb: g.gen(),
+            wal_bytes_per_sync_mb: g.gen(),
+        }
+    }
+}
+
 impl RandomConfig for configs::database::MerkleTreeConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -391,6 +462,11 @@ impl RandomConfig for configs::database::MerkleTreeConfig {
             memtable_capacity_mb: g.gen(),
             stalled_writes_timeout_sec: g.gen(),
             max_l1_batches_per_iter: g.gen(),
+            thread_pool_size: g.gen(),
+            integrity_check_interval_sec: g.gen(),
+            integrity_check_subtree_count: g.gen(),
+            integrity_auto_repair: g.gen(),
+            rocksdb: g.gen(),
         }
     }
 }
@@ -399,6 +475,9 @@ impl RandomConfig for configs::database::DBConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
             state_keeper_db_path: g.gen(),
+            state_keeper_db_block_cache_size_mb: g.gen(),
+            state_keeper_rocksdb: g.gen(),
+            api_state_cache_path: g.gen(),
             merkle_tree: g.gen(),
         }
     }
@@ -415,6 +494,8 @@ impl RandomConfig for configs::database::PostgresConfig {
             statement_timeout_sec: g.gen(),
             long_connection_threshold_ms: g.gen(),
             slow_query_threshold_ms: g.gen(),
+            api_max_connections: g.gen(),
+            background_max_connections: g.gen(),
         }
     }
 }
@@ -424,6 +505,7 @@ impl RandomConfig for configs::ETHClientConfig {
         Self {
             chain_id: g.gen(),
             web3_url: g.gen(),
+            l1_web3_url: g.gen(),
         }
     }
 }
@@ -433,16 +515,99 @@ impl RandomConfig for configs::ETHSenderConfig {
         Self {
             sender: g.gen(),
             gas_adjuster: g.gen(),
+            operator_signer: g.gen(),
+            balance_monitor: g.gen(),
+            da_dispatcher: g.gen(),
+            da_cost_oracle: g.gen(),
+            emergency_mode: g.gen(),
         }
     }
 }
 
-impl RandomConfig for configs::eth_sender::ProofSendingMode {
+impl RandomConfig for configs::eth_sender::OperatorSignerMode {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         match g.rng.gen_range(0..3) {
+            0 => Self::Web3Signer {
+                web3_signer_url: g.gen(),
+                web3_signer_key_id: g.gen(),
+            },
+            1 => Self::AwsKms {
+                aws_kms_region: g.gen(),
+                aws_kms_key_id: g.gen(),
+            },
+            _ => Self::GcpKms {
+                gcp_kms_key_version_name: g.gen(),
+            },
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::OperatorSignerConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            mode: g.gen(),
+            operator_address: g.gen(),
+            request_timeout_ms: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::OperatorBalanceMonitorConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            check_interval_ms: g.gen(),
+            low_balance_threshold_gwei: g.gen(),
+            top_up_webhook_url: g.gen(),
+            funding_contract_address: g.gen(),
+            top_up_cooldown_ms: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::DADispatcherConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            polling_interval_ms: g.gen(),
+            max_batches_to_dispatch: g.gen(),
+            external_da_url: g.gen(),
+            celestia_node_url: g.gen(),
+            celestia_namespace: g.gen(),
+            eigenda_disperser_url: g.gen(),
+            failover_timeout_ms: g.gen(),
+            compression_protocol_version: g.gen(),
+            compression_level: g.gen(),
+            compression_dictionary_path: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::DACostOracleConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            max_price_samples: g.gen(),
+            native_token_price_in_wei: g.gen(),
+            price_scalar: g.gen(),
+            max_price_per_byte: g.gen(),
+            eigenda_payment_vault_address: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::EmergencyModeConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            inactivity_threshold_hours: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::ProofSendingMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..4) {
             0 => Self::OnlyRealProofs,
             1 => Self::OnlySampledProofs,
-            _ => Self::SkipEveryProof,
+            2 => Self::SkipEveryProof,
+            _ => Self::OnlyMockProofs,
         }
     }
 }
@@ -456,6 +621,15 @@ impl RandomConfig for configs::eth_sender::ProofLoadingMode {
     }
 }
 
+impl RandomConfig for configs::eth_sender::PubdataSendingMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Calldata,
+            _ => Self::Blobs,
+        }
+    }
+}
+
 impl RandomConfig for configs::eth_sender::SenderConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -476,6 +650,15 @@ impl RandomConfig for configs::eth_sender::SenderConfig {
             l1_batch_min_age_before_execute_seconds: g.gen(),
             max_acceptable_priority_fee_in_gwei: g.gen(),
             proof_loading_mode: g.gen(),
+            pubdata_sending_mode: g.gen(),
+            resubmission_interval_blocks: g.gen(),
+            priority_fee_bump_percent: g.gen(),
+            max_fee_per_gas_cap: g.gen(),
+            max_fee_bumps_before_replacement: g.gen(),
+            dry_run: g.gen(),
+            target_cost_per_batch_in_gwei: g.gen(),
+            settlement_checks_enabled: g.gen(),
+            max_settlement_reorg_depth: g.gen(),
         }
     }
 }
@@ -491,6 +674,19 @@ impl RandomConfig for configs::eth_sender::GasAdjusterConfig {
             internal_enforced_l1_gas_price: g.gen(),
             poll_period: g.gen(),
             max_l1_gas_price: g.gen(),
+            num_samples_for_blob_base_fee_estimate: g.gen(),
+            l1_pubdata_price_scalar: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_watch::EthWatchFinalityMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..4) {
+            0 => Self::Confirmations,
+            1 => Self::Safe,
+            2 => Self::Finalized,
+            _ => Self::BatchExecution,
         }
     }
 }
@@ -500,6 +696,8 @@ impl RandomConfig for configs::ETHWatchConfig {
         Self {
             confirmations_for_eth_event: g.gen(),
             eth_node_poll_interval: g.gen(),
+            finality_mode: g.gen(),
+            reorg_rescan_depth: g.gen(),
         }
     }
 }
@@ -640,7 +838,7 @@ impl RandomConfig for configs::house_keeper::HouseKeeperConfig {
 
 impl RandomConfig for configs::object_store::ObjectStoreMode {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        match g.rng.gen_range(0..4) {
+        match g.rng.gen_range(0..6) {
             0 => Self::GCS {
                 bucket_base_url: g.gen(),
             },
@@ -651,6 +849,15 @@ impl RandomConfig for configs::object_store::ObjectStoreMode {
             2 => Self::FileBacked {
                 file_backed_base_path: g.gen(),
             },
+            3 => Self::S3 {
+                bucket_base_url: g.gen(),
+                region: g.gen(),
+                endpoint: g.gen(),
+            },
+            4 => Self::AzureBlob {
+                storage_account: g.gen(),
+                container: g.gen(),
+            },
             _ => Self::GCSAnonymousReadOnly {
                 bucket_base_url: g.gen(),
             },
@@ -676,6 +883,15 @@ impl RandomConfig for configs::proof_data_handler::ProtocolVersionLoadingMode {
     }
 }
 
+impl RandomConfig for configs::proof_data_handler::ProofGenerationMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Prover,
+            _ => Self::Mock,
+        }
+    }
+}
+
 impl RandomConfig for configs::ProofDataHandlerConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -683,6 +899,7 @@ impl RandomConfig for configs::ProofDataHandlerConfig {
             proof_generation_timeout_in_secs: g.gen(),
             protocol_version_loading_mode: g.gen(),
             fri_protocol_version_id: g.gen(),
+            proof_generation_mode: g.gen(),
         }
     }
 }
@@ -692,6 +909,241 @@ impl RandomConfig for configs::SnapshotsCreatorConfig {
         Self {
             storage_logs_chunk_size: g.gen(),
             concurrent_queries_count: g.gen(),
+            creation_interval_sec: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::cdc_publisher::CdcBroker {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Kafka,
+            _ => Self::Nats,
+        }
+    }
+}
+
+impl RandomConfig for configs::cdc_publisher::CdcMessageFormat {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Json,
+            _ => Self::Protobuf,
+        }
+    }
+}
+
+impl RandomConfig for configs::CdcPublisherConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            broker: g.gen(),
+            broker_urls: g.gen(),
+            topic_prefix: g.gen(),
+            format: g.gen(),
+            polling_interval_ms: g.gen(),
+            max_miniblocks_per_iteration: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::AuditLogConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            file_path: g.gen(),
+            polling_interval_ms: g.gen(),
+            export_lag_ms: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::LeaderElectionConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            lock_id: g.gen(),
+            check_interval_ms: g.gen(),
+            standby_catchup_interval_ms: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::SharedSequencerConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            url: g.gen(),
+            fallback_timeout_ms: g.gen(),
+            reconnect_interval_ms: g.gen(),
+            ack_interval_ms: g.gen(),
+            max_buffered_txs: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::WebhooksConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            urls: g.gen(),
+            polling_interval_ms: g.gen(),
+            request_timeout_ms: g.gen(),
+            max_attempts: g.gen(),
+            retry_backoff_ms: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::WithdrawalFinalizerConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            polling_interval_ms: g.gen(),
+            token_allowlist: g.gen(),
+            max_withdrawals_per_tx: g.gen(),
+            gas_limit_per_withdrawal: g.gen(),
+            max_gas_price_gwei: g.gen(),
+            max_attempts: g.gen(),
+            tx_resend_interval_sec: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::MessageRelayConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            polling_interval_ms: g.gen(),
+            outbound_senders: g.gen(),
+            inbox_addr: g.gen(),
+            outbox_addr: g.gen(),
+            max_messages_per_tx: g.gen(),
+            gas_limit_per_message: g.gen(),
+            max_attempts: g.gen(),
+            tx_resend_interval_sec: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::FeeDistributorConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            polling_interval_ms: g.gen(),
+            sweep_interval_sec: g.gen(),
+            min_balance: g.gen(),
+            min_sweep_amount: g.gen(),
+            gas_limit_per_transfer: g.gen(),
+            treasury_addr: g.gen(),
+            treasury_share_bps: g.gen(),
+            sequencer_addr: g.gen(),
+            sequencer_share_bps: g.gen(),
+            burn_addr: g.gen(),
+            burn_share_bps: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::SupplyCheckerConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            check_interval_ms: g.gen(),
+            base_token_l1_addr: g.gen(),
+            locked_funds_addr: g.gen(),
+            unbacked_supply_gwei: g.gen(),
+            epsilon_gwei: g.gen(),
+            max_surplus_gwei: g.gen(),
+            alert_after_checks: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::ReexecutionWatchdogConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            check_interval_ms: g.gen(),
+            sample_window_batches: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::ConfigReloadConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            polling_interval_ms: g.gen(),
+            overrides_path: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::SupervisorConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            max_restarts: g.gen(),
+            restart_window_sec: g.gen(),
+            initial_backoff_ms: g.gen(),
+            max_backoff_ms: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::FirehoseConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            port: g.gen(),
+            poll_interval_ms: g.gen(),
+            max_streams: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::RosettaApiConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            port: g.gen(),
+            network: g.gen(),
+            base_token_symbol: g.gen(),
+            base_token_decimals: g.gen(),
+            max_block_events: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::stable_gas_price::PriceFeedSource {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Http,
+            _ => Self::OnChain,
+        }
+    }
+}
+
+impl RandomConfig for configs::StableGasPriceConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            source: g.gen(),
+            feed_url: g.gen(),
+            feed_json_pointer: g.gen(),
+            feed_contract_addr: g.gen(),
+            max_price_age_sec: g.gen(),
+            update_interval_ms: g.gen(),
+            target_tx_cost: g.gen(),
+            reference_tx_gas: g.gen(),
+            min_l2_gas_price: g.gen(),
+            max_l2_gas_price: g.gen(),
+            smoothing_factor: g.gen(),
+            max_change_per_update: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::chain_export::ChainExportFormat {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Parquet,
+            _ => Self::Csv,
+        }
+    }
+}
+
+impl RandomConfig for configs::ChainExportConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            format: g.gen(),
+            miniblocks_per_file: g.gen(),
+            export_interval_sec: g.gen(),
         }
     }
 }
@@ -31,6 +31,10 @@ const ZKSYNC_CONTRACT_FILE: &str =
     "contracts/l1-contracts/artifacts/cache/solpp-generated-contracts/zksync/interfaces/IZkSync.sol/IZkSync.json";
 const MULTICALL3_CONTRACT_FILE: &str =
     "contracts/l1-contracts/artifacts/cache/solpp-generated-contracts/dev-contracts/Multicall3.sol/Multicall3.json";
+const MESSAGE_INBOX_CONTRACT_FILE: &str =
+    "contracts/l1-contracts/artifacts/cache/solpp-generated-contracts/bridge/interfaces/IMessageInbox.sol/IMessageInbox.json";
+const MESSAGE_OUTBOX_CONTRACT_FILE: &str =
+    "contracts/l1-contracts/artifacts/cache/solpp-generated-contracts/bridge/interfaces/IMessageOutbox.sol/IMessageOutbox.json";
 const VERIFIER_CONTRACT_FILE: &str =
     "contracts/l1-contracts/artifacts/cache/solpp-generated-contracts/zksync/Verifier.sol/Verifier.json";
 const L2_BRIDGE_CONTRACT_FILE: &str =
@@ -83,6 +87,16 @@ pub fn multicall_contract() -> Contract {
     load_contract(MULTICALL3_CONTRACT_FILE)
 }
 
+/// Settlement layer contract delivering messages sent by the chain via the L1 messenger.
+pub fn message_inbox_contract() -> Contract {
+    load_contract(MESSAGE_INBOX_CONTRACT_FILE)
+}
+
+/// Settlement layer contract emitting messages to be delivered to the chain.
+pub fn message_outbox_contract() -> Contract {
+    load_contract(MESSAGE_OUTBOX_CONTRACT_FILE)
+}
+
 pub fn l2_bridge_contract() -> Contract {
     load_contract(L2_BRIDGE_CONTRACT_FILE)
 }
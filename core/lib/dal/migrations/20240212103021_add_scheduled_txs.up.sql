@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS scheduled_txs
+(
+    id                   BIGSERIAL PRIMARY KEY,
+    name                 TEXT         NOT NULL UNIQUE,
+    contract_address     BYTEA        NOT NULL,
+    calldata             BYTEA        NOT NULL,
+    value                NUMERIC(80)  NOT NULL,
+    gas_limit            BIGINT       NOT NULL,
+    interval_sec         BIGINT       NOT NULL,
+    is_enabled           BOOLEAN      NOT NULL DEFAULT TRUE,
+    next_run_at          TIMESTAMP    NOT NULL,
+    -- Hash of the last submitted transaction that wasn't processed yet.
+    last_tx_hash         BYTEA,
+    consecutive_failures INT          NOT NULL DEFAULT 0,
+
+    created_at           TIMESTAMP    NOT NULL,
+    updated_at           TIMESTAMP    NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS eth_txs_from_addr_nonce_idx;
+ALTER TABLE eth_txs DROP COLUMN IF EXISTS from_addr;
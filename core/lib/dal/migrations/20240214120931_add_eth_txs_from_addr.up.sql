@@ -0,0 +1,2 @@
+ALTER TABLE eth_txs ADD COLUMN IF NOT EXISTS from_addr BYTEA;
+CREATE INDEX IF NOT EXISTS eth_txs_from_addr_nonce_idx ON eth_txs (from_addr, nonce);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS eth_txs_gas_escalations (
+    id BIGSERIAL PRIMARY KEY,
+    eth_tx_id INT NOT NULL REFERENCES eth_txs (id) ON DELETE CASCADE,
+    attempt INT NOT NULL,
+    action TEXT NOT NULL,
+    skip_reason TEXT,
+    base_fee_per_gas BIGINT NOT NULL,
+    priority_fee_per_gas BIGINT NOT NULL,
+    blob_base_fee_per_gas BIGINT,
+    tx_hash TEXT,
+    l1_block_number INT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS eth_txs_gas_escalations_eth_tx_id_idx ON eth_txs_gas_escalations (eth_tx_id);
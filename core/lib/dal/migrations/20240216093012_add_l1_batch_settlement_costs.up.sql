@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS l1_batch_settlement_costs (
+    l1_batch_number BIGINT NOT NULL REFERENCES l1_batches (number) ON DELETE CASCADE,
+    action_type TEXT NOT NULL,
+    eth_tx_id INT NOT NULL REFERENCES eth_txs (id) ON DELETE CASCADE,
+    gas_used BIGINT NOT NULL,
+    gas_cost NUMERIC(80) NOT NULL,
+    blob_gas_used BIGINT NOT NULL,
+    blob_cost NUMERIC(80) NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, action_type)
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS eth_sender_settlement_halts (
+    id BIGSERIAL PRIMARY KEY,
+    reason TEXT NOT NULL,
+    halted_at TIMESTAMP NOT NULL,
+    resumed_at TIMESTAMP
+);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS data_availability (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    -- Identifier of the pubdata blob in the DA layer.
+    blob_id TEXT NOT NULL,
+    -- Data proving inclusion of the blob into the DA layer; `NULL` until the blob is included.
+    inclusion_data BYTEA,
+    sent_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
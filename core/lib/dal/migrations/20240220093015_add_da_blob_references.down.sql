@@ -0,0 +1,4 @@
+ALTER TABLE data_availability
+    DROP COLUMN IF EXISTS namespace,
+    DROP COLUMN IF EXISTS height,
+    DROP COLUMN IF EXISTS commitment;
@@ -0,0 +1,5 @@
+-- Location of the pubdata blob in DA layers organizing blobs by namespaces, such as Celestia.
+ALTER TABLE data_availability
+    ADD COLUMN IF NOT EXISTS namespace BYTEA,
+    ADD COLUMN IF NOT EXISTS height BIGINT,
+    ADD COLUMN IF NOT EXISTS commitment BYTEA;
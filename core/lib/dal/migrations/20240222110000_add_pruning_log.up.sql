@@ -0,0 +1,7 @@
+-- Log of L1 batch ranges removed from Postgres by the DB pruner.
+CREATE TABLE IF NOT EXISTS pruning_log (
+    pruned_l1_batch BIGINT NOT NULL PRIMARY KEY,
+    pruned_miniblock BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
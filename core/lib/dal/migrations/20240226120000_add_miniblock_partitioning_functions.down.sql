@@ -0,0 +1,15 @@
+-- Converts partitioned tables back into regular tables if they were converted by the node.
+DO $$
+DECLARE
+    table_name TEXT;
+BEGIN
+    FOREACH table_name IN ARRAY ARRAY['events', 'storage_logs'] LOOP
+        IF EXISTS (SELECT 1 FROM pg_partitioned_table WHERE partrelid = table_name::regclass) THEN
+            PERFORM unpartition_by_miniblock_number(table_name);
+        END IF;
+    END LOOP;
+END;
+$$;
+
+DROP FUNCTION unpartition_by_miniblock_number;
+DROP FUNCTION partition_by_miniblock_number;
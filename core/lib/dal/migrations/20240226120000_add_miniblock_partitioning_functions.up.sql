@@ -0,0 +1,97 @@
+-- Functions converting tables to and from partitioning by `miniblock_number` ranges. Tables are not converted by
+-- migrations since partitioning is only used by external nodes with partitioning enabled; the conversion is performed
+-- by the node on startup.
+
+-- Converts the specified table into a table partitioned by `miniblock_number` ranges. Existing data is retained
+-- in the `<table>_legacy` partition covering all miniblocks up to and including the last stored one; new data is
+-- routed to the `<table>_default` partition until range partitions are created by the node.
+--
+-- Indexes and constraints of the table are recreated on the partitioned table, so that the legacy partition attaches
+-- to them without rebuilding indexes. Attaching the partition still requires scanning it to check the partition bounds,
+-- so the conversion may take a while on large databases.
+CREATE FUNCTION partition_by_miniblock_number(table_name TEXT) RETURNS VOID AS $$
+DECLARE
+    legacy_name TEXT := table_name || '_legacy';
+    constraint_defs TEXT[];
+    index_defs TEXT[];
+    index_names TEXT[];
+    def TEXT;
+    index_name TEXT;
+    next_miniblock BIGINT;
+BEGIN
+    SELECT COALESCE(ARRAY_AGG(format('ADD CONSTRAINT %I %s', conname, pg_get_constraintdef(oid))), '{}')
+    INTO constraint_defs
+    FROM pg_constraint
+    WHERE conrelid = table_name::regclass AND contype IN ('p', 'u', 'f');
+
+    SELECT
+        COALESCE(ARRAY_AGG(pg_get_indexdef(idx.indexrelid)) FILTER (WHERE con.oid IS NULL), '{}'),
+        COALESCE(ARRAY_AGG(cls.relname::TEXT), '{}')
+    INTO index_defs, index_names
+    FROM pg_index idx
+    JOIN pg_class cls ON cls.oid = idx.indexrelid
+    LEFT JOIN pg_constraint con ON con.conrelid = idx.indrelid AND con.conindid = idx.indexrelid
+    WHERE idx.indrelid = table_name::regclass;
+
+    EXECUTE format('ALTER TABLE %I RENAME TO %I', table_name, legacy_name);
+    FOREACH index_name IN ARRAY index_names LOOP
+        EXECUTE format('ALTER INDEX %I RENAME TO %I', index_name, index_name || '_legacy');
+    END LOOP;
+
+    EXECUTE format(
+        'CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS INCLUDING CONSTRAINTS INCLUDING STORAGE INCLUDING COMMENTS) '
+        'PARTITION BY RANGE (miniblock_number)',
+        table_name, legacy_name
+    );
+    FOREACH def IN ARRAY constraint_defs LOOP
+        EXECUTE format('ALTER TABLE %I %s', table_name, def);
+    END LOOP;
+    -- Index definitions were obtained before renaming the table, so they refer to the partitioned table.
+    FOREACH def IN ARRAY index_defs LOOP
+        EXECUTE def;
+    END LOOP;
+
+    EXECUTE format('SELECT COALESCE(MAX(miniblock_number) + 1, 0) FROM %I', legacy_name) INTO next_miniblock;
+    EXECUTE format(
+        'ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (MINVALUE) TO (%s)',
+        table_name, legacy_name, next_miniblock
+    );
+    EXECUTE format('CREATE TABLE %I PARTITION OF %I DEFAULT', table_name || '_default', table_name);
+END;
+$$ LANGUAGE plpgsql;
+
+-- Converts the specified partitioned table back into a regular table, copying data from all partitions.
+CREATE FUNCTION unpartition_by_miniblock_number(table_name TEXT) RETURNS VOID AS $$
+DECLARE
+    partitioned_name TEXT := table_name || '_partitioned';
+    constraint_defs TEXT[];
+    index_defs TEXT[];
+    def TEXT;
+BEGIN
+    SELECT COALESCE(ARRAY_AGG(format('ADD CONSTRAINT %I %s', conname, pg_get_constraintdef(oid))), '{}')
+    INTO constraint_defs
+    FROM pg_constraint
+    WHERE conrelid = table_name::regclass AND contype IN ('p', 'u', 'f');
+
+    SELECT COALESCE(ARRAY_AGG(REPLACE(pg_get_indexdef(idx.indexrelid), ' ON ONLY ', ' ON ')), '{}')
+    INTO index_defs
+    FROM pg_index idx
+    LEFT JOIN pg_constraint con ON con.conrelid = idx.indrelid AND con.conindid = idx.indexrelid
+    WHERE idx.indrelid = table_name::regclass AND con.oid IS NULL;
+
+    EXECUTE format('ALTER TABLE %I RENAME TO %I', table_name, partitioned_name);
+    EXECUTE format(
+        'CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS INCLUDING CONSTRAINTS INCLUDING STORAGE INCLUDING COMMENTS)',
+        table_name, partitioned_name
+    );
+    EXECUTE format('INSERT INTO %I SELECT * FROM %I', table_name, partitioned_name);
+    EXECUTE format('DROP TABLE %I', partitioned_name);
+
+    FOREACH def IN ARRAY constraint_defs LOOP
+        EXECUTE format('ALTER TABLE %I %s', table_name, def);
+    END LOOP;
+    FOREACH def IN ARRAY index_defs LOOP
+        EXECUTE def;
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
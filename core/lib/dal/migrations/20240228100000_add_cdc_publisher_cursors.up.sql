@@ -0,0 +1,8 @@
+-- Positions of the change data capture (CDC) publisher in its streams: the next miniblock to publish,
+-- or the next L1 batch to publish for each L1 batch status.
+CREATE TABLE IF NOT EXISTS cdc_publisher_cursors (
+    stream TEXT NOT NULL PRIMARY KEY,
+    next_number BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
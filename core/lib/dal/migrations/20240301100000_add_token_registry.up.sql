@@ -0,0 +1,12 @@
+-- Token metadata curated by the operator for wallets on the chain. Unlike `tokens`, entries are not tied
+-- to on-chain bridging events and are not affected by block reverts.
+CREATE TABLE IF NOT EXISTS token_registry (
+    l2_address BYTEA NOT NULL PRIMARY KEY,
+    l1_address BYTEA NOT NULL,
+    name VARCHAR NOT NULL,
+    symbol VARCHAR NOT NULL,
+    decimals INT NOT NULL,
+    logo_url VARCHAR,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Deposits to the chain observed on the settlement layer, including ones in non-finalized blocks that are not yet
+-- picked up by the Ethereum watcher. Used to track deposits by the hash of the settlement layer transaction.
+CREATE TABLE IF NOT EXISTS bridge_deposits (
+    priority_op_id BIGINT NOT NULL PRIMARY KEY,
+    settlement_tx_hash BYTEA NOT NULL,
+    settlement_block_number BIGINT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS bridge_deposits_settlement_tx_hash_idx ON bridge_deposits (settlement_tx_hash);
+CREATE INDEX IF NOT EXISTS bridge_deposits_settlement_block_number_idx ON bridge_deposits (settlement_block_number);
@@ -0,0 +1,25 @@
+-- Withdrawals from the chain tracked by the withdrawal finalizer. Withdrawals are only added for executed L1 batches,
+-- so they are not affected by block reverts.
+CREATE TABLE IF NOT EXISTS withdrawals (
+    l1_batch_number BIGINT NOT NULL,
+    -- Index of the L2-to-L1 log with the withdrawal message in the L1 batch.
+    l2_message_index INT NOT NULL,
+    tx_number_in_batch INT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    sender BYTEA NOT NULL,
+    l1_token BYTEA NOT NULL,
+    l1_receiver BYTEA NOT NULL,
+    amount NUMERIC(80) NOT NULL,
+    message BYTEA NOT NULL,
+    -- Concatenated hashes of the Merkle path for the withdrawal message.
+    merkle_proof BYTEA NOT NULL,
+    -- One of `pending`, `sent`, `finalized` or `failed`.
+    status VARCHAR NOT NULL,
+    finalization_tx_hash BYTEA,
+    attempts INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, l2_message_index)
+);
+
+CREATE INDEX IF NOT EXISTS withdrawals_status_idx ON withdrawals (status);
@@ -0,0 +1,34 @@
+-- Arbitrary messages relayed between the chain and the settlement layer by the message relay.
+-- Outbound messages are only added for executed L1 batches, and inbound messages for finalized settlement layer
+-- blocks, so they are not affected by reverts.
+CREATE TABLE IF NOT EXISTS relayed_messages (
+    -- Unique message ID used for deduplication.
+    id BYTEA PRIMARY KEY,
+    -- Either `outbound` (from the chain to the settlement layer) or `inbound`.
+    direction VARCHAR NOT NULL,
+    source_tx_hash BYTEA NOT NULL,
+    sender BYTEA NOT NULL,
+    data BYTEA NOT NULL,
+    -- Outbound message fields.
+    l1_batch_number BIGINT,
+    -- Index of the L2-to-L1 log with the message in the L1 batch.
+    l2_message_index INT,
+    tx_number_in_batch INT,
+    -- Concatenated hashes of the Merkle path for the message.
+    merkle_proof BYTEA,
+    -- Inbound message fields.
+    settlement_block_number BIGINT,
+    nonce BIGINT,
+    target BYTEA,
+    -- One of `pending`, `sent`, `delivered` or `failed`.
+    status VARCHAR NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    delivery_tx_hash BYTEA,
+    -- Settlement layer block with the delivery transaction; only set if the message is delivered by the relay.
+    delivery_block_number BIGINT,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS relayed_messages_source_tx_hash_idx ON relayed_messages (source_tx_hash);
+CREATE INDEX IF NOT EXISTS relayed_messages_status_idx ON relayed_messages (status);
@@ -0,0 +1,3 @@
+-- Speeds up listing priority operations that are not included into a sealed L1 batch yet.
+CREATE INDEX IF NOT EXISTS transactions_pending_priority_ops_idx ON transactions (priority_op_id)
+    WHERE is_priority = TRUE AND l1_batch_number IS NULL;
@@ -0,0 +1,10 @@
+-- Results of the verification of L1 batch proofs submitted to the settlement layer; only populated on external nodes.
+CREATE TABLE IF NOT EXISTS l1_batch_proof_verifications (
+    l1_batch_number BIGINT NOT NULL PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    prove_tx_hash BYTEA NOT NULL,
+    -- One of `verified`, `mock_proof` or `invalid`.
+    status VARCHAR NOT NULL,
+    details TEXT,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
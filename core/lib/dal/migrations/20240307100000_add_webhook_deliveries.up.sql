@@ -0,0 +1,27 @@
+-- Positions of the L1 batch status webhook dispatcher: the next L1 batch to notify about for each L1 batch status.
+CREATE TABLE IF NOT EXISTS webhook_cursors (
+    status TEXT NOT NULL PRIMARY KEY,
+    next_l1_batch_number BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+-- Delivery log of L1 batch status notifications, with a row per notification and webhook URL.
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    url TEXT NOT NULL,
+    l1_batch_number BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    last_response_status INT,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP NOT NULL,
+    delivered_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    UNIQUE (url, l1_batch_number, status)
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_pending_idx
+    ON webhook_deliveries (next_attempt_at) WHERE delivered_at IS NULL;
@@ -0,0 +1,21 @@
+-- Append-only audit trail of sensitive actions affecting the node operation.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    action TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    details JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_action_idx ON audit_log (action, id);
+
+-- Entries must never be modified or deleted. `TRUNCATE` is not affected, so that the database can still be reset.
+CREATE OR REPLACE FUNCTION audit_log_reject_modification() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_reject_modification();
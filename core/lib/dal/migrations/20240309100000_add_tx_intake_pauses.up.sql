@@ -0,0 +1,11 @@
+-- Pauses of L2 transaction intake by the API servers, e.g. during incidents.
+CREATE TABLE IF NOT EXISTS tx_intake_pauses (
+    id BIGSERIAL PRIMARY KEY,
+    reason TEXT NOT NULL,
+    paused_at TIMESTAMP NOT NULL,
+    resumed_at TIMESTAMP
+);
+
+-- At most one pause can be active at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS tx_intake_pauses_active_idx ON tx_intake_pauses ((resumed_at IS NULL))
+    WHERE resumed_at IS NULL;
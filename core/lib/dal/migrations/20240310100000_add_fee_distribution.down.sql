@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS fee_distribution_transfers;
+DROP TABLE IF EXISTS fee_distribution_sweeps;
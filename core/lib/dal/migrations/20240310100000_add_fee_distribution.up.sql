@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS fee_distribution_sweeps
+(
+    id               BIGSERIAL PRIMARY KEY,
+    fee_account      BYTEA       NOT NULL,
+    -- Miniblock at which the fee account balance was read.
+    miniblock_number BIGINT      NOT NULL,
+    balance          NUMERIC(80) NOT NULL,
+    created_at       TIMESTAMP   NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS fee_distribution_transfers
+(
+    sweep_id    BIGINT      NOT NULL REFERENCES fee_distribution_sweeps (id) ON DELETE CASCADE,
+    destination TEXT        NOT NULL,
+    recipient   BYTEA       NOT NULL,
+    share_bps   INT         NOT NULL,
+    amount      NUMERIC(80) NOT NULL,
+    -- Not a foreign key: rejected transactions may be purged from the mempool, but the transfer record must stay.
+    tx_hash     BYTEA       NOT NULL,
+    PRIMARY KEY (sweep_id, destination)
+);
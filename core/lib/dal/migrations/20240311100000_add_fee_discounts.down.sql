@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS fee_rebates;
+DROP TABLE IF EXISTS fee_discounts;
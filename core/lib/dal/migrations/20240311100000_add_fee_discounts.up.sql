@@ -0,0 +1,26 @@
+-- Accounts receiving discounted L2 gas pricing, managed by the operator.
+CREATE TABLE IF NOT EXISTS fee_discounts
+(
+    address      BYTEA PRIMARY KEY,
+    -- 10000 basis points correspond to a full fee exemption.
+    discount_bps INT       NOT NULL CHECK (discount_bps > 0 AND discount_bps <= 10000),
+    label        TEXT,
+    created_at   TIMESTAMP NOT NULL,
+    updated_at   TIMESTAMP NOT NULL
+);
+
+-- Rebates of fees paid by transactions of discounted accounts.
+CREATE TABLE IF NOT EXISTS fee_rebates
+(
+    tx_hash          BYTEA PRIMARY KEY,
+    miniblock_number BIGINT      NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    account          BYTEA       NOT NULL,
+    fee_paid         NUMERIC(80) NOT NULL,
+    discount_bps     INT         NOT NULL,
+    amount           NUMERIC(80) NOT NULL,
+    -- Transfer paying out the rebate. Not a foreign key: rejected transfers may be purged from the mempool.
+    transfer_tx_hash BYTEA,
+    created_at       TIMESTAMP   NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS fee_rebates_account_idx ON fee_rebates (account);
@@ -0,0 +1 @@
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS content_hash;
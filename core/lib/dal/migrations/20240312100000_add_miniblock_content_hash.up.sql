@@ -0,0 +1,3 @@
+-- Canonical hash of the miniblock payload (transactions, their outcome and events) used to detect data corruption.
+-- `NULL` for miniblocks sealed before the hash was introduced.
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS content_hash BYTEA;
@@ -0,0 +1,8 @@
+-- Contract ABIs registered by the operator, used to decode event logs. ABIs of verified contracts
+-- are taken from `contracts_verification_info` and do not need to be registered separately.
+CREATE TABLE IF NOT EXISTS contract_abis (
+    address BYTEA NOT NULL PRIMARY KEY,
+    abi JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS withdrawal_limit_usage;
+DROP TABLE IF EXISTS withdrawal_limit_exemptions;
+DROP TABLE IF EXISTS withdrawal_limits;
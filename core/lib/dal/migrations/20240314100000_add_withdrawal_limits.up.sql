@@ -0,0 +1,38 @@
+-- Rate limits on withdrawals initiated on the chain, managed by the operator.
+CREATE TABLE IF NOT EXISTS withdrawal_limits
+(
+    -- Token address on the settlement layer; the zero address corresponds to the base token.
+    l1_token      BYTEA PRIMARY KEY,
+    -- Max amount withdrawn by all accounts within the window; `NULL` if not limited.
+    total_limit   NUMERIC(80),
+    -- Max amount withdrawn by a single account within the window; `NULL` if not limited.
+    account_limit NUMERIC(80),
+    window_sec    BIGINT    NOT NULL CHECK (window_sec > 0),
+    created_at    TIMESTAMP NOT NULL,
+    updated_at    TIMESTAMP NOT NULL,
+    CHECK (total_limit IS NOT NULL OR account_limit IS NOT NULL)
+);
+
+-- Accounts exempt from withdrawal limits.
+CREATE TABLE IF NOT EXISTS withdrawal_limit_exemptions
+(
+    address    BYTEA PRIMARY KEY,
+    label      TEXT,
+    created_at TIMESTAMP NOT NULL
+);
+
+-- Withdrawals initiated by transactions accepted to the mempool. Withdrawals of transactions that were replaced,
+-- dropped or reverted are filtered out by joining with the `transactions` table.
+CREATE TABLE IF NOT EXISTS withdrawal_limit_usage
+(
+    id                BIGSERIAL PRIMARY KEY,
+    tx_hash           BYTEA       NOT NULL,
+    initiator_address BYTEA       NOT NULL,
+    l1_token          BYTEA       NOT NULL,
+    amount            NUMERIC(80) NOT NULL,
+    created_at        TIMESTAMP   NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS withdrawal_limit_usage_token_idx ON withdrawal_limit_usage (l1_token, created_at);
+CREATE INDEX IF NOT EXISTS withdrawal_limit_usage_initiator_idx
+    ON withdrawal_limit_usage (initiator_address, l1_token, created_at);
@@ -0,0 +1,3 @@
+ALTER TABLE transactions
+    DROP COLUMN IF EXISTS mempool_at,
+    DROP COLUMN IF EXISTS executed_at;
@@ -0,0 +1,7 @@
+-- Lifecycle timestamps of transactions used by the transaction timeline API. `NULL` for transactions that haven't
+-- reached the corresponding stage yet, or that were processed before the timestamps were introduced.
+ALTER TABLE transactions
+    -- First time the transaction was picked up by the state keeper mempool.
+    ADD COLUMN IF NOT EXISTS mempool_at TIMESTAMP,
+    -- Time the transaction was executed by the state keeper (as opposed to the time its miniblock was sealed).
+    ADD COLUMN IF NOT EXISTS executed_at TIMESTAMP;
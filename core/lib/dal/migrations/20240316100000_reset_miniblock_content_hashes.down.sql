@@ -0,0 +1 @@
+-- Reset content hashes cannot be restored.
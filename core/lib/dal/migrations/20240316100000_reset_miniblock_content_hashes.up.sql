@@ -0,0 +1,2 @@
+-- Content hashes now also commit to user L2-to-L1 logs and storage writes; previously computed hashes are invalidated.
+UPDATE miniblocks SET content_hash = NULL WHERE content_hash IS NOT NULL;
@@ -0,0 +1,4 @@
+ALTER TABLE withdrawals DROP COLUMN IF EXISTS finalization_tx_id;
+
+DROP TABLE IF EXISTS settlement_tx_attempts;
+DROP TABLE IF EXISTS settlement_txs;
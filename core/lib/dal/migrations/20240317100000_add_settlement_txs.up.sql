@@ -0,0 +1,27 @@
+-- Settlement layer transactions sent by auxiliary components (the withdrawal finalizer and the message relay).
+-- Transactions are persisted before they are sent, so that they are tracked across restarts.
+CREATE TABLE IF NOT EXISTS settlement_txs (
+    id BIGSERIAL PRIMARY KEY,
+    -- Component that has sent the transaction, e.g. `withdrawal_finalizer`.
+    component VARCHAR NOT NULL,
+    nonce BIGINT NOT NULL,
+    contract_address BYTEA NOT NULL,
+    calldata BYTEA NOT NULL,
+    gas_limit BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+-- Signed versions of settlement layer transactions. A transaction that isn't mined in time is replaced with
+-- a version with the same nonce and higher fees; any of the versions may be mined.
+CREATE TABLE IF NOT EXISTS settlement_tx_attempts (
+    tx_hash BYTEA PRIMARY KEY,
+    settlement_tx_id BIGINT NOT NULL REFERENCES settlement_txs (id) ON DELETE CASCADE,
+    raw_tx BYTEA NOT NULL,
+    max_fee_per_gas NUMERIC(80) NOT NULL,
+    max_priority_fee_per_gas NUMERIC(80) NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS settlement_tx_attempts_settlement_tx_id_idx ON settlement_tx_attempts (settlement_tx_id);
+
+ALTER TABLE withdrawals ADD COLUMN IF NOT EXISTS finalization_tx_id BIGINT REFERENCES settlement_txs (id);
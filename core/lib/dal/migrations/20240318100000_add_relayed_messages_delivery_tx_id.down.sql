@@ -0,0 +1 @@
+ALTER TABLE relayed_messages DROP COLUMN IF EXISTS delivery_tx_id;
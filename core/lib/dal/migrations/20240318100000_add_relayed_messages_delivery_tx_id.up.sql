@@ -0,0 +1 @@
+ALTER TABLE relayed_messages ADD COLUMN IF NOT EXISTS delivery_tx_id BIGINT REFERENCES settlement_txs (id);
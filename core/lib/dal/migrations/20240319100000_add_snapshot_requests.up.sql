@@ -0,0 +1,12 @@
+-- Requests to create a snapshot made via the admin API; processed by the snapshot creator.
+CREATE TABLE IF NOT EXISTS snapshot_requests (
+    id BIGSERIAL PRIMARY KEY,
+    requested_at TIMESTAMP NOT NULL,
+    -- L1 batch of the snapshot created for the request; `NULL` if the request is not processed yet.
+    l1_batch_number BIGINT,
+    processed_at TIMESTAMP
+);
+
+-- At most one request can be pending at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS snapshot_requests_pending_idx ON snapshot_requests ((processed_at IS NULL))
+    WHERE processed_at IS NULL;
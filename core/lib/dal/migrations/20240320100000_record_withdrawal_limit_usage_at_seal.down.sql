@@ -0,0 +1 @@
+-- Removed usage records cannot be restored.
@@ -0,0 +1,5 @@
+-- Withdrawal limit usage is now recorded when a transaction is sealed in a miniblock rather than when it's
+-- accepted to the mempool. Usage recorded for transactions that are not executed yet is removed; it will be
+-- recorded again from the actual execution once the transactions are sealed.
+DELETE FROM withdrawal_limit_usage
+WHERE tx_hash IN (SELECT hash FROM transactions WHERE miniblock_number IS NULL);
@@ -0,0 +1,11 @@
+-- Set for L1 batches whose state root hash was taken from the main node rather than computed by the local Merkle tree
+-- (e.g., on external nodes running without a tree). Such hashes cannot be used to detect reorgs.
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS hash_from_main_node BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Hashes from the main node were persisted without other tree data.
+UPDATE l1_batches
+SET
+    hash_from_main_node = TRUE
+WHERE
+    hash IS NOT NULL
+    AND merkle_root_hash IS NULL;
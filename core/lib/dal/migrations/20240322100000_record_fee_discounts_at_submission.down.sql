@@ -0,0 +1,3 @@
+ALTER TABLE fee_rebates ADD COLUMN IF NOT EXISTS miniblock_number BIGINT REFERENCES miniblocks (number) ON DELETE CASCADE;
+ALTER TABLE fee_rebates ADD COLUMN IF NOT EXISTS fee_paid NUMERIC(80);
+ALTER TABLE fee_rebates ADD COLUMN IF NOT EXISTS amount NUMERIC(80);
@@ -0,0 +1,5 @@
+-- Discounts are recorded when transactions are accepted to the mempool; rebates are computed
+-- from the fees paid by executed transactions when they are paid out.
+ALTER TABLE fee_rebates DROP COLUMN IF EXISTS miniblock_number;
+ALTER TABLE fee_rebates DROP COLUMN IF EXISTS fee_paid;
+ALTER TABLE fee_rebates DROP COLUMN IF EXISTS amount;
@@ -0,0 +1,2 @@
+-- Used by the audit log exporter, which pages entries by `(created_at, id)`.
+CREATE INDEX IF NOT EXISTS audit_log_created_at_idx ON audit_log (created_at, id);
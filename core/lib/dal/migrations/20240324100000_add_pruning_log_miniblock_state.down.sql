@@ -0,0 +1,2 @@
+ALTER TABLE pruning_log DROP COLUMN IF EXISTS miniblock_state_retained;
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS fair_pubdata_price;
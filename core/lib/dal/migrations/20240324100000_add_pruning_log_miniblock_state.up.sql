@@ -0,0 +1,5 @@
+-- Whether storage logs were pruned only within miniblocks, so that the state after each pruned miniblock is retained.
+ALTER TABLE pruning_log ADD COLUMN IF NOT EXISTS miniblock_state_retained BOOLEAN NOT NULL DEFAULT FALSE;
+-- Fee inputs of pruned L1 batches, which are copied from miniblock headers before they are pruned.
+-- `l1_gas_price` and `l2_fair_gas_price` columns are reused for this purpose.
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS fair_pubdata_price BIGINT;
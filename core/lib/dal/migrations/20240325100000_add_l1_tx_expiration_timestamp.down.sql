@@ -0,0 +1 @@
+ALTER TABLE transactions DROP COLUMN IF EXISTS l1_tx_expiration_timestamp;
@@ -0,0 +1,3 @@
+-- Deadline for executing a priority operation set by the settlement layer contract, as a UNIX timestamp
+-- in seconds. `NULL` for operations received before the column was added.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS l1_tx_expiration_timestamp BIGINT;
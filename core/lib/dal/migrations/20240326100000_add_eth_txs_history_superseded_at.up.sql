@@ -0,0 +1,3 @@
+-- Time when a sending attempt was superseded because the nonce of its transaction was reassigned. Superseded attempts
+-- are kept for auditing, but are not resent or escalated.
+ALTER TABLE eth_txs_history ADD COLUMN IF NOT EXISTS superseded_at TIMESTAMP;
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS withdrawal_limit_usage_tx_hash_idx;
@@ -0,0 +1,3 @@
+-- Withdrawal limit usage is reserved when a transaction is accepted to the mempool, released if the transaction
+-- is rejected or evicted, and replaced with the actual usage once the transaction is sealed in a miniblock.
+CREATE INDEX IF NOT EXISTS withdrawal_limit_usage_tx_hash_idx ON withdrawal_limit_usage (tx_hash);
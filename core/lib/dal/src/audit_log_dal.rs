@@ -0,0 +1,262 @@
+//! Append-only audit log of sensitive actions affecting the node operation.
+
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use zksync_types::api::idexo::{AuditAction, AuditLogEntry};
+
+use crate::{instrument::InstrumentExt, time_utils::pg_interval_from_duration, StorageProcessor};
+
+#[derive(Debug)]
+struct StorageAuditLogEntry {
+    id: i64,
+    action: String,
+    actor: String,
+    details: serde_json::Value,
+    created_at: NaiveDateTime,
+}
+
+impl TryFrom<StorageAuditLogEntry> for AuditLogEntry {
+    type Error = sqlx::Error;
+
+    fn try_from(row: StorageAuditLogEntry) -> Result<Self, Self::Error> {
+        let action = row
+            .action
+            .parse()
+            .map_err(|err: &str| sqlx::Error::Decode(err.into()))?;
+        Ok(Self {
+            id: row.id as u64,
+            action,
+            actor: row.actor,
+            details: row.details,
+            created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditLogDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl AuditLogDal<'_, '_> {
+    /// Appends an entry to the audit log. If called within a transaction, the entry is only persisted
+    /// if the transaction is committed, i.e., if the action has actually taken place.
+    pub async fn insert_entry(
+        &mut self,
+        action: AuditAction,
+        actor: &str,
+        details: &serde_json::Value,
+    ) -> sqlx::Result<u64> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                audit_log (action, actor, details, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            RETURNING
+                id
+            "#,
+            action.as_str(),
+            actor,
+            details
+        )
+        .instrument("insert_audit_log_entry")
+        .with_arg("action", &action)
+        .with_arg("actor", &actor)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.id as u64)
+    }
+
+    /// Returns audit log entries with ID greater than `after_id`, optionally filtered by the action,
+    /// in the order they were recorded.
+    pub async fn get_entries(
+        &mut self,
+        action: Option<AuditAction>,
+        after_id: u64,
+        limit: usize,
+    ) -> sqlx::Result<Vec<AuditLogEntry>> {
+        let rows = sqlx::query_as!(
+            StorageAuditLogEntry,
+            r#"
+            SELECT
+                id,
+                action,
+                actor,
+                details,
+                created_at
+            FROM
+                audit_log
+            WHERE
+                id > $1
+                AND (
+                    $2::TEXT IS NULL
+                    OR action = $2
+                )
+            ORDER BY
+                id
+            LIMIT
+                $3
+            "#,
+            after_id as i64,
+            action.map(AuditAction::as_str),
+            limit as i64
+        )
+        .instrument("get_audit_log_entries")
+        .with_arg("after_id", &after_id)
+        .with_arg("action", &action)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        rows.into_iter().map(AuditLogEntry::try_from).collect()
+    }
+
+    /// Returns entries recorded after the specified `(created_at, id)` cursor and at least `lag` ago, ordered
+    /// by the cursor. IDs and timestamps are assigned on insertion, so an entry recorded by a long-running
+    /// DB transaction may become visible after entries with greater IDs; the lag gives such transactions time
+    /// to commit, so that paging by the cursor doesn't skip their entries.
+    pub async fn get_entries_for_export(
+        &mut self,
+        after: Option<(DateTime<Utc>, u64)>,
+        lag: Duration,
+        limit: usize,
+    ) -> sqlx::Result<Vec<AuditLogEntry>> {
+        let (after_timestamp, after_id) = after
+            .map_or((NaiveDateTime::default(), 0), |(timestamp, id)| {
+                (timestamp.naive_utc(), id)
+            });
+        let rows = sqlx::query_as!(
+            StorageAuditLogEntry,
+            r#"
+            SELECT
+                id,
+                action,
+                actor,
+                details,
+                created_at
+            FROM
+                audit_log
+            WHERE
+                (created_at, id) > ($1::TIMESTAMP, $2::BIGINT)
+                AND created_at < NOW() - $3::INTERVAL
+            ORDER BY
+                created_at,
+                id
+            LIMIT
+                $4
+            "#,
+            after_timestamp,
+            after_id as i64,
+            pg_interval_from_duration(lag),
+            limit as i64
+        )
+        .instrument("get_audit_log_entries_for_export")
+        .with_arg("after_timestamp", &after_timestamp)
+        .with_arg("after_id", &after_id)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        rows.into_iter().map(AuditLogEntry::try_from).collect()
+    }
+
+    /// Returns the last recorded entry with the specified action.
+    pub async fn get_last_entry(
+        &mut self,
+        action: AuditAction,
+    ) -> sqlx::Result<Option<AuditLogEntry>> {
+        let row = sqlx::query_as!(
+            StorageAuditLogEntry,
+            r#"
+            SELECT
+                id,
+                action,
+                actor,
+                details,
+                created_at
+            FROM
+                audit_log
+            WHERE
+                action = $1
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#,
+            action.as_str()
+        )
+        .instrument("get_last_audit_log_entry")
+        .with_arg("action", &action)
+        .fetch_optional(self.storage)
+        .await?;
+
+        row.map(AuditLogEntry::try_from).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn recording_and_querying_audit_log() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let details = serde_json::json!({ "symbol": "USDC" });
+        let first_id = conn
+            .audit_log_dal()
+            .insert_entry(AuditAction::TokenRegistration, "operator_rpc", &details)
+            .await
+            .unwrap();
+        let second_id = conn
+            .audit_log_dal()
+            .insert_entry(AuditAction::BlockRevert, "block_reverter", &details)
+            .await
+            .unwrap();
+        assert!(second_id > first_id);
+
+        let entries = conn.audit_log_dal().get_entries(None, 0, 10).await.unwrap();
+        let ids: Vec<_> = entries.iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, [first_id, second_id]);
+        assert_eq!(entries[0].action, AuditAction::TokenRegistration);
+        assert_eq!(entries[0].actor, "operator_rpc");
+        assert_eq!(entries[0].details, details);
+
+        let entries = conn
+            .audit_log_dal()
+            .get_entries(Some(AuditAction::BlockRevert), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, second_id);
+        let entries = conn
+            .audit_log_dal()
+            .get_entries(None, second_id, 10)
+            .await
+            .unwrap();
+        assert!(entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn audit_log_is_append_only() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.audit_log_dal()
+            .insert_entry(
+                AuditAction::SettlementResume,
+                "operator_rpc",
+                &serde_json::Value::Null,
+            )
+            .await
+            .unwrap();
+
+        let err = sqlx::query("DELETE FROM audit_log")
+            .execute(conn.conn())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("append-only"), "{err}");
+    }
+}
@@ -0,0 +1,148 @@
+//! Queries for exporting L1 batch archives used for disaster recovery.
+
+use std::collections::HashMap;
+
+use sqlx::types::chrono::{DateTime, Utc};
+use zksync_types::{
+    vm_trace::Call, AccountTreeId, Address, MiniblockNumber, StorageKey, StorageLog, H256,
+};
+
+use crate::{instrument::InstrumentExt, models::storage_transaction::CallTrace, StorageProcessor};
+
+#[derive(Debug)]
+pub struct BatchArchiveDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Persisted execution outcome of a transaction included into a miniblock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredTxExecution {
+    pub hash: H256,
+    /// Error marker; `None` if the transaction has succeeded.
+    pub error: Option<String>,
+    pub refunded_gas: u64,
+    /// Serialized execution metrics of the transaction.
+    pub execution_info: serde_json::Value,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub call_trace: Option<Call>,
+}
+
+impl BatchArchiveDal<'_, '_> {
+    /// Returns execution outcomes for all transactions in the specified miniblock, ordered by their index
+    /// in the miniblock.
+    pub async fn get_tx_executions(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<Vec<StoredTxExecution>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.hash,
+                transactions.error,
+                transactions.refunded_gas,
+                transactions.execution_info,
+                transactions.executed_at,
+                call_traces.call_trace AS "call_trace?"
+            FROM
+                transactions
+                LEFT JOIN call_traces ON call_traces.tx_hash = transactions.hash
+            WHERE
+                transactions.miniblock_number = $1
+            ORDER BY
+                transactions.index_in_block
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_tx_executions_for_archive")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| StoredTxExecution {
+                hash: H256::from_slice(&row.hash),
+                error: row.error,
+                refunded_gas: row.refunded_gas as u64,
+                execution_info: row.execution_info,
+                executed_at: row
+                    .executed_at
+                    .map(|time| DateTime::<Utc>::from_naive_utc_and_offset(time, Utc)),
+                call_trace: row
+                    .call_trace
+                    .map(|call_trace| CallTrace { call_trace }.into()),
+            })
+            .collect())
+    }
+
+    /// Returns storage logs in the specified miniblock grouped by transaction hash, in the same format
+    /// as accepted by [`StorageLogsDal::insert_storage_logs()`](crate::storage_logs_dal::StorageLogsDal::insert_storage_logs()).
+    pub async fn get_storage_logs(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<Vec<(H256, Vec<StorageLog>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                address,
+                key,
+                value,
+                tx_hash
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                operation_number
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_storage_logs_for_archive")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut logs_by_tx: Vec<(H256, Vec<StorageLog>)> = vec![];
+        for row in rows {
+            let tx_hash = H256::from_slice(&row.tx_hash);
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            let log = StorageLog::new_write_log(key, H256::from_slice(&row.value));
+            match logs_by_tx.last_mut() {
+                Some((last_tx_hash, logs)) if *last_tx_hash == tx_hash => logs.push(log),
+                _ => logs_by_tx.push((tx_hash, vec![log])),
+            }
+        }
+        Ok(logs_by_tx)
+    }
+
+    /// Returns factory deps inserted in the specified miniblock.
+    pub async fn get_factory_deps(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<HashMap<H256, Vec<u8>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                bytecode_hash,
+                bytecode
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number = $1
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_factory_deps_for_archive")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
+            .collect())
+    }
+}
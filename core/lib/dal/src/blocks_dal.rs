@@ -27,6 +27,22 @@ pub struct BlocksDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
 }
 
+/// Numbers of rows removed from Postgres tables when rolling back blocks, as returned by
+/// [`BlocksDal::get_rows_to_revert()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowsToRevert {
+    pub l1_batches: u64,
+    pub miniblocks: u64,
+    /// Number of transactions returned to the mempool.
+    pub transactions: u64,
+    pub events: u64,
+    pub l2_to_l1_logs: u64,
+    pub storage_logs: u64,
+    pub initial_writes: u64,
+    pub factory_deps: u64,
+}
+
 impl BlocksDal<'_, '_> {
     pub async fn is_genesis_needed(&mut self) -> sqlx::Result<bool> {
         let count = sqlx::query!(
@@ -53,7 +69,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_sealed_block_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -70,7 +85,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_sealed_miniblock_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -88,7 +102,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_earliest_l1_batch_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -109,7 +122,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_last_block_number_with_metadata")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -135,7 +147,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_next_l1_batch_ready_for_commitment_generation")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -158,7 +169,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_earliest_l1_batch_number_with_metadata")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -312,7 +322,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_initial_bootloader_heap")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -341,7 +350,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_storage_refunds")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -372,7 +380,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_events_queue")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -769,7 +776,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_batch_tree_data")
         .with_arg("number", &number)
-        .report_latency()
         .execute(self.storage)
         .await?;
 
@@ -792,7 +798,6 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("get_matching_batch_hash")
             .with_arg("number", &number)
-            .report_latency()
             .fetch_one(self.storage)
             .await?
             .count;
@@ -808,6 +813,60 @@ impl BlocksDal<'_, '_> {
         Ok(())
     }
 
+    /// Saves the state root hash of an L1 batch obtained from the main node. Used by external nodes running
+    /// without a Merkle tree; other tree data (e.g., the last leaf index) is not set, and the hash is marked
+    /// as obtained from the main node (see [`Self::is_l1_batch_root_hash_from_main_node()`]). Returns `false`
+    /// if the L1 batch is not present in the storage or already has a root hash.
+    pub async fn save_l1_batch_root_hash_from_main_node(
+        &mut self,
+        number: L1BatchNumber,
+        hash: H256,
+    ) -> sqlx::Result<bool> {
+        let update_result = sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                hash = $1,
+                hash_from_main_node = TRUE,
+                updated_at = NOW()
+            WHERE
+                number = $2
+                AND hash IS NULL
+            "#,
+            hash.as_bytes(),
+            number.0 as i64,
+        )
+        .instrument("save_l1_batch_root_hash_from_main_node")
+        .with_arg("number", &number)
+        .execute(self.storage)
+        .await?;
+        Ok(update_result.rows_affected() > 0)
+    }
+
+    /// Checks whether the state root hash of the specified L1 batch was obtained from the main node rather than
+    /// computed by the local Merkle tree. Returns `false` if the L1 batch is not present in the storage.
+    pub async fn is_l1_batch_root_hash_from_main_node(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                hash_from_main_node
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("is_l1_batch_root_hash_from_main_node")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map_or(false, |row| row.hash_from_main_node))
+    }
+
     pub async fn save_l1_batch_commitment_artifacts(
         &mut self,
         number: L1BatchNumber,
@@ -852,7 +911,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_l1_batch_commitment_artifacts")
         .with_arg("number", &number)
-        .report_latency()
         .execute(&mut transaction)
         .await?;
         if update_result.rows_affected() == 0 {
@@ -876,7 +934,6 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("get_matching_batch_commitment")
             .with_arg("number", &number)
-            .report_latency()
             .fetch_one(&mut transaction)
             .await?
             .count;
@@ -905,7 +962,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_batch_aux_commitments")
         .with_arg("number", &number)
-        .report_latency()
         .execute(&mut transaction)
         .await?;
 
@@ -1020,6 +1076,24 @@ impl BlocksDal<'_, '_> {
         Ok(L1BatchNumber(row.number as u32))
     }
 
+    /// Returns the number of the last L1 batch for which an Ethereum execute tx exists in the database.
+    pub async fn get_last_l1_batch_with_execute_tx(&mut self) -> sqlx::Result<L1BatchNumber> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(MAX(number), 0) AS "number!"
+            FROM
+                l1_batches
+            WHERE
+                eth_execute_tx_id IS NOT NULL
+            "#
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+
+        Ok(L1BatchNumber(row.number as u32))
+    }
+
     pub async fn get_eth_commit_tx_id(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -1089,7 +1163,8 @@ impl BlocksDal<'_, '_> {
         .map(|row| L1BatchNumber(row.number as u32)))
     }
 
-    /// This method returns batches that are confirmed on L1. That is, it doesn't wait for the proofs to be generated.
+    /// This method returns batches whose commit tx is confirmed on L1. That is, it doesn't wait for the proofs
+    /// to be generated.
     pub async fn get_ready_for_dummy_proof_l1_batches(
         &mut self,
         limit: usize,
@@ -1132,8 +1207,9 @@ impl BlocksDal<'_, '_> {
             FROM
                 l1_batches
                 LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
+                JOIN eth_txs_history AS commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id)
             WHERE
-                eth_commit_tx_id IS NOT NULL
+                commit_tx.confirmed_at IS NOT NULL
                 AND eth_prove_tx_id IS NULL
             ORDER BY
                 number
@@ -1187,7 +1263,7 @@ impl BlocksDal<'_, '_> {
         Ok(())
     }
 
-    /// This method returns batches that are committed on L1 and witness jobs for them are skipped.
+    /// This method returns batches whose commit tx is confirmed on L1 and witness jobs for them are skipped.
     pub async fn get_skipped_for_proof_l1_batches(
         &mut self,
         limit: usize,
@@ -1243,8 +1319,9 @@ impl BlocksDal<'_, '_> {
                         ) AS ROW_NUMBER
                     FROM
                         l1_batches
+                        JOIN eth_txs_history AS commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id)
                     WHERE
-                        eth_commit_tx_id IS NOT NULL
+                        commit_tx.confirmed_at IS NOT NULL
                         AND l1_batches.skip_proof = TRUE
                         AND l1_batches.number > $1
                     ORDER BY
@@ -1269,6 +1346,8 @@ impl BlocksDal<'_, '_> {
             .context("map_l1_batches()")
     }
 
+    /// Returns batches whose prove tx is confirmed on L1. Commit, prove and execute txs may be sent
+    /// from different operator accounts, so a sent prove tx alone doesn't guarantee its ordering on L1.
     pub async fn get_ready_for_execute_l1_batches(
         &mut self,
         limit: usize,
@@ -1314,8 +1393,9 @@ impl BlocksDal<'_, '_> {
                     FROM
                         l1_batches
                         LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
+                        JOIN eth_txs_history AS prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id)
                     WHERE
-                        eth_prove_tx_id IS NOT NULL
+                        prove_tx.confirmed_at IS NOT NULL
                         AND eth_execute_tx_id IS NULL
                     ORDER BY
                         number
@@ -1358,8 +1438,9 @@ impl BlocksDal<'_, '_> {
                 number
             FROM
                 l1_batches
+                JOIN eth_txs_history AS prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id)
             WHERE
-                eth_prove_tx_id IS NOT NULL
+                prove_tx.confirmed_at IS NOT NULL
                 AND eth_execute_tx_id IS NULL
             ORDER BY
                 number
@@ -1387,9 +1468,10 @@ impl BlocksDal<'_, '_> {
                 l1_batches
                 JOIN eth_txs ON (l1_batches.eth_commit_tx_id = eth_txs.id)
                 JOIN eth_txs_history AS commit_tx ON (eth_txs.confirmed_eth_tx_history_id = commit_tx.id)
+                JOIN eth_txs_history AS prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id)
             WHERE
                 commit_tx.confirmed_at IS NOT NULL
-                AND eth_prove_tx_id IS NOT NULL
+                AND prove_tx.confirmed_at IS NOT NULL
                 AND eth_execute_tx_id IS NULL
                 AND EXTRACT(
                     epoch
@@ -1768,6 +1850,102 @@ impl BlocksDal<'_, '_> {
         .collect())
     }
 
+    /// Counts rows that will be removed or reset when rolling back to the specified L1 batch and miniblock.
+    /// Does not modify any data.
+    pub async fn get_rows_to_revert(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        last_miniblock_to_keep: MiniblockNumber,
+    ) -> sqlx::Result<RowsToRevert> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        l1_batches
+                    WHERE
+                        number > $1
+                ) AS "l1_batches!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        miniblocks
+                    WHERE
+                        number > $2
+                ) AS "miniblocks!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        transactions
+                    WHERE
+                        miniblock_number > $2
+                ) AS "transactions!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        events
+                    WHERE
+                        miniblock_number > $2
+                ) AS "events!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        l2_to_l1_logs
+                    WHERE
+                        miniblock_number > $2
+                ) AS "l2_to_l1_logs!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number > $2
+                ) AS "storage_logs!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        initial_writes
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "initial_writes!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        factory_deps
+                    WHERE
+                        miniblock_number > $2
+                ) AS "factory_deps!"
+            "#,
+            i64::from(last_l1_batch_to_keep.0),
+            i64::from(last_miniblock_to_keep.0)
+        )
+        .instrument("get_rows_to_revert")
+        .with_arg("last_l1_batch_to_keep", &last_l1_batch_to_keep)
+        .with_arg("last_miniblock_to_keep", &last_miniblock_to_keep)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(RowsToRevert {
+            l1_batches: row.l1_batches as u64,
+            miniblocks: row.miniblocks as u64,
+            transactions: row.transactions as u64,
+            events: row.events as u64,
+            l2_to_l1_logs: row.l2_to_l1_logs as u64,
+            storage_logs: row.storage_logs as u64,
+            initial_writes: row.initial_writes as u64,
+            factory_deps: row.factory_deps as u64,
+        })
+    }
+
     pub async fn delete_initial_writes(
         &mut self,
         last_batch_to_keep: L1BatchNumber,
@@ -1872,6 +2050,39 @@ impl BlocksDal<'_, '_> {
             .context("Sum of predicted gas costs should fit into u32")
     }
 
+    /// Returns predicted gas costs for each L1 batch in the given range. L1 batches missing from the storage
+    /// are not included into the returned map.
+    pub async fn get_predicted_gas_per_l1_batch(
+        &mut self,
+        number_range: ops::RangeInclusive<L1BatchNumber>,
+        op_type: AggregatedActionType,
+    ) -> anyhow::Result<HashMap<L1BatchNumber, u32>> {
+        let column_name = match op_type {
+            AggregatedActionType::Commit => "predicted_commit_gas_cost",
+            AggregatedActionType::PublishProofOnchain => "predicted_prove_gas_cost",
+            AggregatedActionType::Execute => "predicted_execute_gas_cost",
+        };
+        let sql_query_str = format!(
+            "SELECT number, {column_name} AS gas FROM l1_batches \
+             WHERE number BETWEEN $1 AND $2"
+        );
+        let rows = sqlx::query(&sql_query_str)
+            .bind(number_range.start().0 as i64)
+            .bind(number_range.end().0 as i64)
+            .fetch_all(self.storage.conn())
+            .await?;
+        rows.into_iter()
+            .map(|row| -> anyhow::Result<_> {
+                let number = L1BatchNumber(row.get::<i64, &str>("number") as u32);
+                let gas = row
+                    .get::<i64, &str>("gas")
+                    .try_into()
+                    .context("Predicted gas cost should fit into u32")?;
+                Ok((number, gas))
+            })
+            .collect()
+    }
+
     pub async fn get_miniblock_range_of_l1_batch(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -1898,6 +2109,95 @@ impl BlocksDal<'_, '_> {
         )))
     }
 
+    /// Returns compressed state diffs published as the pubdata of the specified L1 batch. Returns `None`
+    /// if the batch is not present or its commitment is not generated yet.
+    pub async fn get_compressed_state_diffs(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                compressed_state_diffs
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.and_then(|row| row.compressed_state_diffs))
+    }
+
+    /// Returns hashes of all miniblocks in the specified L1 batch ordered by the miniblock number.
+    pub async fn get_miniblock_hashes_of_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<H256>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash
+            FROM
+                miniblocks
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                number
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect())
+    }
+
+    /// Returns the total gas used by transactions in each of the `count` latest sealed L1 batches,
+    /// ordered by the L1 batch number.
+    pub async fn get_gas_used_in_latest_l1_batches(
+        &mut self,
+        count: u32,
+    ) -> sqlx::Result<Vec<(L1BatchNumber, u64)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                latest_batches.number AS "number!",
+                COALESCE(SUM((transactions.execution_info ->> 'gas_used')::BIGINT), 0)::BIGINT AS "gas_used!"
+            FROM
+                (
+                    SELECT
+                        number
+                    FROM
+                        l1_batches
+                    ORDER BY
+                        number DESC
+                    LIMIT
+                        $1
+                ) AS latest_batches
+                LEFT JOIN transactions ON transactions.l1_batch_number = latest_batches.number
+            GROUP BY
+                latest_batches.number
+            ORDER BY
+                latest_batches.number
+            "#,
+            i64::from(count)
+        )
+        .instrument("get_gas_used_in_latest_l1_batches")
+        .with_arg("count", &count)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (L1BatchNumber(row.number as u32), row.gas_used as u64))
+            .collect())
+    }
+
     /// Returns `true` if there exists a non-sealed batch (i.e. there is one+ stored miniblock that isn't assigned
     /// to any batch yet).
     pub async fn pending_batch_exists(&mut self) -> sqlx::Result<bool> {
@@ -2445,6 +2745,123 @@ mod tests {
                 .await
                 .unwrap();
             assert_eq!(gas, 3 * expected_gas);
+
+            let gas_per_batch = conn
+                .blocks_dal()
+                .get_predicted_gas_per_l1_batch(L1BatchNumber(1)..=L1BatchNumber(3), action_type)
+                .await
+                .unwrap();
+            let expected_gas_per_batch = HashMap::from([
+                (L1BatchNumber(1), expected_gas),
+                (L1BatchNumber(2), 2 * expected_gas),
+            ]);
+            assert_eq!(gas_per_batch, expected_gas_per_batch);
+        }
+    }
+
+    #[tokio::test]
+    async fn counting_rows_to_revert() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2, 3] {
+            let l1_batch = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&l1_batch)
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+                .await
+                .unwrap();
+        }
+
+        let rows = conn
+            .blocks_dal()
+            .get_rows_to_revert(L1BatchNumber(1), MiniblockNumber(1))
+            .await
+            .unwrap();
+        let expected_rows = RowsToRevert {
+            l1_batches: 2,
+            miniblocks: 2,
+            ..RowsToRevert::default()
+        };
+        assert_eq!(rows, expected_rows);
+
+        let rows = conn
+            .blocks_dal()
+            .get_rows_to_revert(L1BatchNumber(3), MiniblockNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(rows, RowsToRevert::default());
+    }
+
+    #[tokio::test]
+    async fn saving_l1_batch_root_hash_from_main_node() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+
+        let tree_data = L1BatchTreeData {
+            hash: H256::repeat_byte(1),
+            rollup_last_leaf_index: 10,
+        };
+        conn.blocks_dal()
+            .save_l1_batch_tree_data(L1BatchNumber(1), &tree_data)
+            .await
+            .unwrap();
+        let saved = conn
+            .blocks_dal()
+            .save_l1_batch_root_hash_from_main_node(L1BatchNumber(2), H256::repeat_byte(2))
+            .await
+            .unwrap();
+        assert!(saved);
+        // The hash is not overwritten.
+        let saved = conn
+            .blocks_dal()
+            .save_l1_batch_root_hash_from_main_node(L1BatchNumber(2), H256::repeat_byte(3))
+            .await
+            .unwrap();
+        assert!(!saved);
+
+        let state_root = conn
+            .blocks_dal()
+            .get_l1_batch_state_root(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(state_root, Some(H256::repeat_byte(2)));
+        for (number, expected) in [(1, false), (2, true), (3, false)] {
+            let is_from_main_node = conn
+                .blocks_dal()
+                .is_l1_batch_root_hash_from_main_node(L1BatchNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(is_from_main_node, expected, "L1 batch #{number}");
         }
     }
 
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bigdecimal::BigDecimal;
 use sqlx::Row;
 use zksync_system_constants::EMPTY_UNCLES_HASH;
@@ -68,7 +70,13 @@ impl BlocksWeb3Dal<'_, '_> {
         );
 
         let query = bind_block_where_sql_params(&block_id, sqlx::query(&query));
-        let rows = query.fetch_all(self.storage.conn()).await?.into_iter();
+        let rows = query
+            .instrument("get_block_by_web3_block_id")
+            .with_arg("block_id", &block_id)
+            .with_arg("include_full_transactions", &include_full_transactions)
+            .fetch_all(self.storage)
+            .await?
+            .into_iter();
 
         let block = rows.fold(None, |prev_block, db_row| {
             let mut block = prev_block.unwrap_or_else(|| {
@@ -133,7 +141,12 @@ impl BlocksWeb3Dal<'_, '_> {
         );
         let query = bind_block_where_sql_params(&block_id, sqlx::query(&query));
 
-        Ok(query.fetch_optional(self.storage.conn()).await?.map(|row| {
+        let row = query
+            .instrument("get_block_tx_count")
+            .with_arg("block_id", &block_id)
+            .fetch_optional(self.storage)
+            .await?;
+        Ok(row.map(|row| {
             let miniblock_number = row.get::<i64, _>("number") as u32;
             let tx_count = row.get::<i32, _>("tx_count") as u32;
             (MiniblockNumber(miniblock_number), tx_count.into())
@@ -163,7 +176,10 @@ impl BlocksWeb3Dal<'_, '_> {
             from_block.0 as i64,
             limit as i32
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_block_hashes_since")
+        .with_arg("from_block", &from_block)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
         .await?;
 
         let last_block_number = rows.last().map(|row| MiniblockNumber(row.number as u32));
@@ -191,7 +207,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             from_block.0 as i64,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_block_headers_after")
+        .with_arg("from_block", &from_block)
+        .fetch_all(self.storage)
         .await?;
 
         let blocks = rows.into_iter().map(|row| BlockHeader {
@@ -241,7 +259,9 @@ impl BlocksWeb3Dal<'_, '_> {
             }
         };
         let row = bind_block_where_sql_params(&block_id, sqlx::query(query_str))
-            .fetch_optional(self.storage.conn())
+            .instrument("resolve_block_id")
+            .with_arg("block_id", &block_id)
+            .fetch_optional(self.storage)
             .await?;
 
         let block_number = row
@@ -274,7 +294,9 @@ impl BlocksWeb3Dal<'_, '_> {
                 "#,
                 i64::from(miniblock_l1_batch.0)
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_expected_l1_batch_timestamp#sealed")
+            .with_arg("l1_batch_number", &miniblock_l1_batch)
+            .fetch_optional(self.storage)
             .await?
             .map(|row| row.timestamp as u64))
         } else {
@@ -315,7 +337,9 @@ impl BlocksWeb3Dal<'_, '_> {
                 "#,
                 i64::from(prev_l1_batch_number.0)
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_expected_l1_batch_timestamp#pending")
+            .with_arg("prev_l1_batch_number", &prev_l1_batch_number)
+            .fetch_optional(self.storage)
             .await?
             .map(|row| row.timestamp as u64))
         }
@@ -336,7 +360,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_hash")
+        .with_arg("block_number", &block_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| H256::from_slice(&row.hash));
         Ok(hash)
@@ -357,7 +383,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l2_to_l1_logs")
+        .with_arg("block_number", &block_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.l2_to_l1_logs)
         .unwrap_or_default();
@@ -383,7 +411,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_number_of_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_optional(self.storage)
         .await?
         .and_then(|row| row.l1_batch_number);
 
@@ -406,7 +436,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_miniblock_range_of_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
         .await?;
 
         Ok(match (row.min, row.max) {
@@ -418,6 +450,36 @@ impl BlocksWeb3Dal<'_, '_> {
         })
     }
 
+    /// Returns the greatest number of L1 transactions in the specified number of last sealed L1 batches.
+    pub async fn get_max_l1_tx_count_in_recent_l1_batches(
+        &mut self,
+        l1_batch_count: u32,
+    ) -> sqlx::Result<u64> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_tx_count) AS "max_count?"
+            FROM
+                (
+                    SELECT
+                        l1_tx_count
+                    FROM
+                        l1_batches
+                    ORDER BY
+                        number DESC
+                    LIMIT
+                        $1
+                ) AS recent_l1_batches
+            "#,
+            i64::from(l1_batch_count)
+        )
+        .instrument("get_max_l1_tx_count_in_recent_l1_batches")
+        .with_arg("l1_batch_count", &l1_batch_count)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.max_count.map_or(0, |count| count as u64))
+    }
+
     pub async fn get_l1_batch_info_for_tx(
         &mut self,
         tx_hash: H256,
@@ -434,7 +496,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             tx_hash.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_info_for_tx")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
         .await?;
 
         let result = row.and_then(|row| match (row.l1_batch_number, row.l1_batch_tx_index) {
@@ -447,6 +511,44 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(result)
     }
 
+    /// Batched version of [`Self::get_l1_batch_info_for_tx()`]. Transactions not included into an L1 batch
+    /// are omitted from the returned map.
+    pub async fn get_l1_batch_info_for_txs(
+        &mut self,
+        tx_hashes: &[H256],
+    ) -> sqlx::Result<HashMap<H256, (L1BatchNumber, u16)>> {
+        let hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                l1_batch_number,
+                l1_batch_tx_index
+            FROM
+                transactions
+            WHERE
+                hash = ANY ($1)
+            "#,
+            &hashes as &[&[u8]]
+        )
+        .instrument("get_l1_batch_info_for_txs")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let l1_batch_number = L1BatchNumber(row.l1_batch_number? as u32);
+                let l1_batch_tx_index = row.l1_batch_tx_index? as u16;
+                Some((
+                    H256::from_slice(&row.hash),
+                    (l1_batch_number, l1_batch_tx_index),
+                ))
+            })
+            .collect())
+    }
+
     /// Returns call traces for all transactions in the specified miniblock in the order of their execution.
     pub async fn get_traces_for_miniblock(
         &mut self,
@@ -467,7 +569,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_traces_for_miniblock")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(Call::from)
@@ -497,7 +601,10 @@ impl BlocksWeb3Dal<'_, '_> {
             newest_block.0 as i64,
             block_count as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_fee_history")
+        .with_arg("newest_block", &newest_block)
+        .with_arg("block_count", &block_count)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| bigdecimal_to_u256(row.base_fee_per_gas))
@@ -562,7 +669,6 @@ impl BlocksWeb3Dal<'_, '_> {
         )
         .instrument("get_block_details")
         .with_arg("block_number", &block_number)
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -637,7 +743,6 @@ impl BlocksWeb3Dal<'_, '_> {
         )
         .instrument("get_l1_batch_details")
         .with_arg("l1_batch_number", &l1_batch_number)
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -648,7 +753,7 @@ impl BlocksWeb3Dal<'_, '_> {
 #[cfg(test)]
 mod tests {
     use zksync_types::{
-        block::{MiniblockHasher, MiniblockHeader},
+        block::{L1BatchHeader, MiniblockHasher, MiniblockHeader},
         fee::TransactionExecutionMetrics,
         Address, MiniblockNumber, ProtocolVersion, ProtocolVersionId,
     };
@@ -831,6 +936,62 @@ mod tests {
         assert_eq!(miniblock_number, Some(MiniblockNumber(43)));
     }
 
+    #[tokio::test]
+    async fn resolving_merkleized_block_id() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for (l1_batch_number, miniblock_numbers) in [(0, 0..=0), (1, 1..=2)] {
+            for miniblock_number in miniblock_numbers {
+                conn.blocks_dal()
+                    .insert_miniblock(&create_miniblock_header(miniblock_number))
+                    .await
+                    .unwrap();
+            }
+            let l1_batch_header = L1BatchHeader::new(
+                L1BatchNumber(l1_batch_number),
+                0,
+                Default::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&l1_batch_header)
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(l1_batch_number))
+                .await
+                .unwrap();
+        }
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(3))
+            .await
+            .unwrap();
+
+        let merkleized_block_id = api::BlockId::Number(api::BlockNumber::Merkleized);
+        conn.blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(0), H256::repeat_byte(1))
+            .await
+            .unwrap();
+        let miniblock_number = conn
+            .blocks_web3_dal()
+            .resolve_block_id(merkleized_block_id)
+            .await;
+        assert_eq!(miniblock_number.unwrap(), Some(MiniblockNumber(0)));
+
+        conn.blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(1), H256::repeat_byte(2))
+            .await
+            .unwrap();
+        let miniblock_number = conn
+            .blocks_web3_dal()
+            .resolve_block_id(merkleized_block_id)
+            .await;
+        assert_eq!(miniblock_number.unwrap(), Some(MiniblockNumber(2)));
+    }
+
     #[tokio::test]
     async fn resolving_block_by_hash() {
         let connection_pool = ConnectionPool::test_pool().await;
@@ -0,0 +1,193 @@
+use zksync_types::{api::idexo::BridgeDeposit, PriorityOpId, H256};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+struct StorageBridgeDeposit {
+    priority_op_id: i64,
+    settlement_tx_hash: Vec<u8>,
+    settlement_block_number: i64,
+    tx_hash: Vec<u8>,
+}
+
+impl From<StorageBridgeDeposit> for BridgeDeposit {
+    fn from(row: StorageBridgeDeposit) -> Self {
+        Self {
+            settlement_tx_hash: H256::from_slice(&row.settlement_tx_hash),
+            settlement_block_number: row.settlement_block_number as u64,
+            priority_op_id: PriorityOpId(row.priority_op_id as u64),
+            tx_hash: H256::from_slice(&row.tx_hash),
+        }
+    }
+}
+
+/// Deposits to the chain observed on the settlement layer by the bridge watcher.
+#[derive(Debug)]
+pub struct BridgeDepositsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl BridgeDepositsDal<'_, '_> {
+    /// Replaces deposits in settlement layer blocks starting from `from_block` with the provided ones.
+    /// Deposits are expected to belong to these blocks; deposits in blocks removed by a reorg are discarded.
+    pub async fn replace_deposits(
+        &mut self,
+        from_block: u64,
+        deposits: &[BridgeDeposit],
+    ) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM bridge_deposits
+            WHERE
+                settlement_block_number >= $1
+            "#,
+            from_block as i64
+        )
+        .instrument("replace_deposits#delete")
+        .with_arg("from_block", &from_block)
+        .execute(&mut transaction)
+        .await?;
+
+        let mut priority_op_ids = Vec::with_capacity(deposits.len());
+        let mut settlement_tx_hashes = Vec::with_capacity(deposits.len());
+        let mut settlement_block_numbers = Vec::with_capacity(deposits.len());
+        let mut tx_hashes = Vec::with_capacity(deposits.len());
+        for deposit in deposits {
+            priority_op_ids.push(deposit.priority_op_id.0 as i64);
+            settlement_tx_hashes.push(deposit.settlement_tx_hash.as_bytes());
+            settlement_block_numbers.push(deposit.settlement_block_number as i64);
+            tx_hashes.push(deposit.tx_hash.as_bytes());
+        }
+        // A deposit may have been moved to another block by a reorg, so deposits are upserted.
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                bridge_deposits (
+                    priority_op_id,
+                    settlement_tx_hash,
+                    settlement_block_number,
+                    tx_hash,
+                    created_at,
+                    updated_at
+                )
+            SELECT
+                u.priority_op_id,
+                u.settlement_tx_hash,
+                u.settlement_block_number,
+                u.tx_hash,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST($1::BIGINT[], $2::BYTEA[], $3::BIGINT[], $4::BYTEA[]) AS u (
+                    priority_op_id,
+                    settlement_tx_hash,
+                    settlement_block_number,
+                    tx_hash
+                )
+            ON CONFLICT (priority_op_id) DO
+            UPDATE
+            SET
+                settlement_tx_hash = excluded.settlement_tx_hash,
+                settlement_block_number = excluded.settlement_block_number,
+                tx_hash = excluded.tx_hash,
+                updated_at = NOW()
+            "#,
+            &priority_op_ids,
+            &settlement_tx_hashes as &[&[u8]],
+            &settlement_block_numbers,
+            &tx_hashes as &[&[u8]]
+        )
+        .instrument("replace_deposits#insert")
+        .with_arg("deposits.len", &deposits.len())
+        .execute(&mut transaction)
+        .await?;
+
+        transaction.commit().await
+    }
+
+    /// Returns the greatest settlement layer block number with an observed deposit.
+    pub async fn get_last_deposit_block(&mut self) -> sqlx::Result<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(settlement_block_number) AS "block_number"
+            FROM
+                bridge_deposits
+            "#
+        )
+        .instrument("get_last_deposit_block")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.block_number.map(|number| number as u64))
+    }
+
+    /// Returns deposits requested by the specified settlement layer transaction ordered by the priority op ID.
+    pub async fn get_deposits(
+        &mut self,
+        settlement_tx_hash: H256,
+    ) -> sqlx::Result<Vec<BridgeDeposit>> {
+        let rows = sqlx::query_as!(
+            StorageBridgeDeposit,
+            r#"
+            SELECT
+                priority_op_id,
+                settlement_tx_hash,
+                settlement_block_number,
+                tx_hash
+            FROM
+                bridge_deposits
+            WHERE
+                settlement_tx_hash = $1
+            ORDER BY
+                priority_op_id
+            "#,
+            settlement_tx_hash.as_bytes()
+        )
+        .instrument("get_deposits")
+        .with_arg("settlement_tx_hash", &settlement_tx_hash)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    fn deposit(priority_op_id: u64, settlement_tx_byte: u8, block_number: u64) -> BridgeDeposit {
+        BridgeDeposit {
+            settlement_tx_hash: H256::repeat_byte(settlement_tx_byte),
+            settlement_block_number: block_number,
+            priority_op_id: PriorityOpId(priority_op_id),
+            tx_hash: H256::from_low_u64_be(priority_op_id),
+        }
+    }
+
+    #[tokio::test]
+    async fn replacing_deposits() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.bridge_deposits_dal();
+        assert_eq!(dal.get_last_deposit_block().await.unwrap(), None);
+
+        let deposits = [deposit(0, 1, 10), deposit(1, 1, 10), deposit(2, 2, 12)];
+        dal.replace_deposits(0, &deposits).await.unwrap();
+        assert_eq!(dal.get_last_deposit_block().await.unwrap(), Some(12));
+        let fetched = dal.get_deposits(H256::repeat_byte(1)).await.unwrap();
+        assert_eq!(fetched, deposits[..2]);
+
+        // Emulate a reorg moving the second deposit to a later block and dropping the third one.
+        let reorged_deposits = [deposit(1, 3, 11)];
+        dal.replace_deposits(11, &reorged_deposits).await.unwrap();
+        assert_eq!(dal.get_last_deposit_block().await.unwrap(), Some(11));
+        let fetched = dal.get_deposits(H256::repeat_byte(1)).await.unwrap();
+        assert_eq!(fetched, deposits[..1]);
+        let fetched = dal.get_deposits(H256::repeat_byte(3)).await.unwrap();
+        assert_eq!(fetched, reorged_deposits);
+        let fetched = dal.get_deposits(H256::repeat_byte(2)).await.unwrap();
+        assert_eq!(fetched, []);
+    }
+}
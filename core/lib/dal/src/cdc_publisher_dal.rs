@@ -0,0 +1,92 @@
+//! Persistence of the change data capture (CDC) publisher progress.
+
+use std::collections::HashMap;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+pub struct CdcPublisherDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl CdcPublisherDal<'_, '_> {
+    /// Returns the next number to publish (a miniblock or an L1 batch number, depending on the stream)
+    /// for all streams with persisted cursors.
+    pub async fn get_cursors(&mut self) -> sqlx::Result<HashMap<String, u32>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                stream,
+                next_number
+            FROM
+                cdc_publisher_cursors
+            "#
+        )
+        .instrument("get_cdc_publisher_cursors")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.stream, row.next_number as u32))
+            .collect())
+    }
+
+    pub async fn set_cursor(&mut self, stream: &str, next_number: u32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                cdc_publisher_cursors (stream, next_number, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (stream) DO
+            UPDATE
+            SET
+                next_number = $2,
+                updated_at = NOW()
+            "#,
+            stream,
+            i64::from(next_number)
+        )
+        .instrument("set_cdc_publisher_cursor")
+        .with_arg("stream", &stream)
+        .with_arg("next_number", &next_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn setting_cdc_publisher_cursors() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let cursors = conn.cdc_publisher_dal().get_cursors().await.unwrap();
+        assert!(cursors.is_empty());
+
+        conn.cdc_publisher_dal()
+            .set_cursor("miniblocks", 10)
+            .await
+            .unwrap();
+        conn.cdc_publisher_dal()
+            .set_cursor("l1_batches_sealed", 2)
+            .await
+            .unwrap();
+        conn.cdc_publisher_dal()
+            .set_cursor("miniblocks", 15)
+            .await
+            .unwrap();
+
+        let cursors = conn.cdc_publisher_dal().get_cursors().await.unwrap();
+        let expected_cursors = HashMap::from([
+            ("miniblocks".to_owned(), 15),
+            ("l1_batches_sealed".to_owned(), 2),
+        ]);
+        assert_eq!(cursors, expected_cursors);
+    }
+}
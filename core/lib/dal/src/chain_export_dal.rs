@@ -0,0 +1,298 @@
+//! Bulk queries for exporting chain data for analytics.
+
+use std::ops;
+
+use zksync_types::{
+    event::TRANSFER_EVENT_SIGNATURE, Address, L1BatchNumber, MiniblockNumber, H256, U256,
+};
+use zksync_utils::bigdecimal_to_u256;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+pub struct ChainExportDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Exported miniblock header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedMiniblock {
+    pub number: MiniblockNumber,
+    pub hash: H256,
+    pub timestamp: u64,
+    pub l1_batch_number: Option<L1BatchNumber>,
+    pub l1_tx_count: u32,
+    pub l2_tx_count: u32,
+    pub base_fee_per_gas: U256,
+    pub protocol_version: Option<u16>,
+    pub fee_account_address: Address,
+}
+
+/// Exported transaction together with its execution results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedTransaction {
+    pub hash: H256,
+    pub miniblock_number: MiniblockNumber,
+    pub index_in_block: u32,
+    pub initiator_address: Address,
+    pub nonce: Option<u32>,
+    /// Called contract; `None` for deployment transactions.
+    pub contract_address: Option<Address>,
+    pub value: U256,
+    pub gas_limit: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub is_priority: bool,
+    pub tx_format: Option<i32>,
+    /// Revert reason; `None` if the transaction has succeeded.
+    pub error: Option<String>,
+    pub effective_gas_price: Option<U256>,
+    pub gas_used: Option<U256>,
+}
+
+/// Exported event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedEvent {
+    pub miniblock_number: MiniblockNumber,
+    pub tx_hash: H256,
+    pub tx_index_in_block: u32,
+    pub event_index_in_block: u32,
+    pub event_index_in_tx: u32,
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+/// Exported token transfer, i.e. a `Transfer(address,address,uint256)` event with the value in event data
+/// (as emitted by ERC-20 tokens and the base token).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedTransfer {
+    pub miniblock_number: MiniblockNumber,
+    pub tx_hash: H256,
+    pub event_index_in_block: u32,
+    pub token_address: Address,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
+impl ChainExportDal<'_, '_> {
+    pub async fn get_miniblocks(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<ExportedMiniblock>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                number,
+                hash,
+                timestamp,
+                l1_batch_number,
+                l1_tx_count,
+                l2_tx_count,
+                base_fee_per_gas,
+                protocol_version,
+                fee_account_address
+            FROM
+                miniblocks
+            WHERE
+                number BETWEEN $1 AND $2
+            ORDER BY
+                number
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
+        )
+        .instrument("get_miniblocks_for_export")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ExportedMiniblock {
+                number: MiniblockNumber(row.number as u32),
+                hash: H256::from_slice(&row.hash),
+                timestamp: row.timestamp as u64,
+                l1_batch_number: row
+                    .l1_batch_number
+                    .map(|number| L1BatchNumber(number as u32)),
+                l1_tx_count: row.l1_tx_count as u32,
+                l2_tx_count: row.l2_tx_count as u32,
+                base_fee_per_gas: bigdecimal_to_u256(row.base_fee_per_gas),
+                protocol_version: row.protocol_version.map(|version| version as u16),
+                fee_account_address: Address::from_slice(&row.fee_account_address),
+            })
+            .collect())
+    }
+
+    pub async fn get_transactions(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<ExportedTransaction>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                miniblock_number AS "miniblock_number!",
+                index_in_block AS "index_in_block!",
+                initiator_address,
+                nonce,
+                data ->> 'contractAddress' AS "contract_address?",
+                value,
+                gas_limit,
+                max_fee_per_gas,
+                is_priority,
+                tx_format,
+                error,
+                effective_gas_price,
+                refunded_gas
+            FROM
+                transactions
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                miniblock_number,
+                index_in_block
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
+        )
+        .instrument("get_transactions_for_export")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let gas_limit = row.gas_limit.map(bigdecimal_to_u256);
+                ExportedTransaction {
+                    hash: H256::from_slice(&row.hash),
+                    miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+                    index_in_block: row.index_in_block as u32,
+                    initiator_address: Address::from_slice(&row.initiator_address),
+                    nonce: row.nonce.map(|nonce| nonce as u32),
+                    contract_address: row
+                        .contract_address
+                        .and_then(|address| address.parse().ok()),
+                    value: bigdecimal_to_u256(row.value),
+                    gas_limit,
+                    max_fee_per_gas: row.max_fee_per_gas.map(bigdecimal_to_u256),
+                    is_priority: row.is_priority,
+                    tx_format: row.tx_format,
+                    error: row.error,
+                    effective_gas_price: row.effective_gas_price.map(bigdecimal_to_u256),
+                    gas_used: gas_limit
+                        .map(|limit| limit.saturating_sub((row.refunded_gas as u64).into())),
+                }
+            })
+            .collect())
+    }
+
+    pub async fn get_events(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<ExportedEvent>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                tx_hash,
+                tx_index_in_block,
+                event_index_in_block,
+                event_index_in_tx,
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4,
+                value
+            FROM
+                events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                miniblock_number,
+                event_index_in_block
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
+        )
+        .instrument("get_events_for_export")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let topics = [row.topic1, row.topic2, row.topic3, row.topic4]
+                    .into_iter()
+                    .filter(|topic| !topic.is_empty())
+                    .map(|topic| H256::from_slice(&topic))
+                    .collect();
+                ExportedEvent {
+                    miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+                    tx_hash: H256::from_slice(&row.tx_hash),
+                    tx_index_in_block: row.tx_index_in_block as u32,
+                    event_index_in_block: row.event_index_in_block as u32,
+                    event_index_in_tx: row.event_index_in_tx as u32,
+                    address: Address::from_slice(&row.address),
+                    topics,
+                    data: row.value,
+                }
+            })
+            .collect())
+    }
+
+    /// Returns token transfers in the specified miniblocks; see [`ExportedTransfer`] for details.
+    pub async fn get_transfers(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<ExportedTransfer>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                tx_hash,
+                event_index_in_block,
+                address,
+                topic2,
+                topic3,
+                value
+            FROM
+                events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND topic1 = $3
+                AND LENGTH(topic2) = 32
+                AND LENGTH(topic3) = 32
+                AND LENGTH(topic4) = 0
+                AND LENGTH(value) = 32
+            ORDER BY
+                miniblock_number,
+                event_index_in_block
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0),
+            TRANSFER_EVENT_SIGNATURE.as_bytes()
+        )
+        .instrument("get_transfers_for_export")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ExportedTransfer {
+                miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+                tx_hash: H256::from_slice(&row.tx_hash),
+                event_index_in_block: row.event_index_in_block as u32,
+                token_address: Address::from_slice(&row.address),
+                from: Address::from_slice(&row.topic2[12..]),
+                to: Address::from_slice(&row.topic3[12..]),
+                value: U256::from_big_endian(&row.value),
+            })
+            .collect())
+    }
+}
@@ -16,12 +16,16 @@ use sqlx::{
     postgres::{PgConnectOptions, PgPool, PgPoolOptions, Postgres},
 };
 
-pub use self::processor::StorageProcessor;
 pub(crate) use self::processor::StorageProcessorTags;
-use self::processor::TracedConnections;
+pub use self::{
+    processor::StorageProcessor,
+    quotas::{WorkloadClass, WorkloadQuotas},
+};
+use self::{processor::TracedConnections, quotas::WorkloadSemaphores};
 use crate::metrics::CONNECTION_METRICS;
 
 mod processor;
+mod quotas;
 
 /// Builder for [`ConnectionPool`]s.
 #[derive(Clone)]
@@ -30,6 +34,7 @@ pub struct ConnectionPoolBuilder {
     max_size: u32,
     acquire_timeout: Duration,
     statement_timeout: Option<Duration>,
+    workload_quotas: WorkloadQuotas,
 }
 
 impl fmt::Debug for ConnectionPoolBuilder {
@@ -40,6 +45,7 @@ impl fmt::Debug for ConnectionPoolBuilder {
             .field("max_size", &self.max_size)
             .field("acquire_timeout", &self.acquire_timeout)
             .field("statement_timeout", &self.statement_timeout)
+            .field("workload_quotas", &self.workload_quotas)
             .finish()
     }
 }
@@ -63,6 +69,16 @@ impl ConnectionPoolBuilder {
         self
     }
 
+    /// Sets connection quotas for workload classes. Quotas only apply to connections acquired via pool handles
+    /// returned by [`ConnectionPool::for_workload()`]. By default, no quotas are set.
+    ///
+    /// Quotas are validated when building the pool: each quota must be positive, and quotas must sum up
+    /// to at most the pool size.
+    pub fn set_workload_quotas(&mut self, quotas: WorkloadQuotas) -> &mut Self {
+        self.workload_quotas = quotas;
+        self
+    }
+
     /// Returns the maximum number of connections that can be allocated by the pool.
     pub fn max_size(&self) -> u32 {
         self.max_size
@@ -70,6 +86,9 @@ impl ConnectionPoolBuilder {
 
     /// Builds a connection pool from this builder.
     pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
+        self.workload_quotas
+            .validate(self.max_size)
+            .context("invalid workload connection quotas")?;
         let options = PgPoolOptions::new()
             .max_connections(self.max_size)
             .acquire_timeout(self.acquire_timeout);
@@ -86,11 +105,15 @@ impl ConnectionPoolBuilder {
             .await
             .context("Failed connecting to database")?;
         tracing::info!("Created DB pool with parameters {self:?}");
+        let workload_semaphores = (!self.workload_quotas.is_empty())
+            .then(|| Arc::new(WorkloadSemaphores::new(&self.workload_quotas)));
         Ok(ConnectionPool {
             database_url: self.database_url.clone(),
             inner: pool,
             max_size: self.max_size,
             traced_connections: None,
+            workload_semaphores,
+            workload: None,
         })
     }
 
@@ -237,6 +260,8 @@ pub struct ConnectionPool {
     database_url: String,
     max_size: u32,
     traced_connections: Option<Arc<TracedConnections>>,
+    workload_semaphores: Option<Arc<WorkloadSemaphores>>,
+    workload: Option<WorkloadClass>,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -246,6 +271,7 @@ impl fmt::Debug for ConnectionPool {
         formatter
             .debug_struct("ConnectionPool")
             .field("max_size", &self.max_size)
+            .field("workload", &self.workload)
             .finish_non_exhaustive()
     }
 }
@@ -294,6 +320,7 @@ impl ConnectionPool {
             max_size: max_pool_size,
             acquire_timeout: Duration::from_secs(30), // Default value used by `sqlx`
             statement_timeout: None,
+            workload_quotas: WorkloadQuotas::default(),
         }
     }
 
@@ -310,6 +337,17 @@ impl ConnectionPool {
         self.max_size
     }
 
+    /// Returns a handle to this pool that acquires connections on behalf of the specified workload class.
+    /// If the pool has a [quota](ConnectionPoolBuilder::set_workload_quotas()) for the class, acquiring
+    /// a connection will wait until the number of connections used by the class is below the quota.
+    /// The returned handle shares connections with this pool.
+    pub fn for_workload(&self, class: WorkloadClass) -> Self {
+        Self {
+            workload: Some(class),
+            ..self.clone()
+        }
+    }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -348,6 +386,10 @@ impl ConnectionPool {
         tags: Option<StorageProcessorTags>,
     ) -> anyhow::Result<StorageProcessor<'_>> {
         let acquire_latency = CONNECTION_METRICS.acquire.start();
+        let quota_permit = match (&self.workload_semaphores, self.workload) {
+            (Some(semaphores), Some(class)) => semaphores.acquire(class).await,
+            _ => None,
+        };
         let conn = self
             .acquire_connection_retried(tags.as_ref())
             .await
@@ -359,6 +401,7 @@ impl ConnectionPool {
         Ok(StorageProcessor::from_pool(
             conn,
             tags,
+            quota_permit,
             self.traced_connections.as_deref(),
         ))
     }
@@ -448,4 +491,30 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[tokio::test]
+    async fn workload_quotas() {
+        let mut builder = TestTemplate::empty().unwrap().create_db(3).await.unwrap();
+        let mut quotas = WorkloadQuotas::default();
+        quotas.set_limit(WorkloadClass::Api, 1);
+        let pool = builder.set_workload_quotas(quotas).build().await.unwrap();
+        let api_pool = pool.for_workload(WorkloadClass::Api);
+        let state_keeper_pool = pool.for_workload(WorkloadClass::StateKeeper);
+
+        let api_connection = api_pool.access_storage().await.unwrap();
+        // The API quota is exhausted, so the next API connection should wait...
+        let api_future = api_pool.access_storage();
+        tokio::pin!(api_future);
+        tokio::time::timeout(Duration::from_millis(100), &mut api_future)
+            .await
+            .unwrap_err();
+        // ...while other workloads should not be affected.
+        let _state_keeper_connection = state_keeper_pool.access_storage().await.unwrap();
+
+        drop(api_connection);
+        tokio::time::timeout(Duration::from_secs(5), api_future)
+            .await
+            .expect("timed out waiting for API connection")
+            .unwrap();
+    }
 }
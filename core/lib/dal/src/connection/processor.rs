@@ -11,6 +11,7 @@ use std::{
 
 use sqlx::{pool::PoolConnection, types::chrono, Connection, PgConnection, Postgres, Transaction};
 
+use super::quotas::QuotaPermit;
 use crate::{metrics::CONNECTION_METRICS, ConnectionPool};
 
 /// Tags that can be associated with a connection.
@@ -95,6 +96,8 @@ struct PooledStorageProcessor<'a> {
     tags: Option<StorageProcessorTags>,
     created_at: Instant,
     traced: Option<(&'a TracedConnections, usize)>,
+    // Released after the connection is returned to the pool (fields are dropped in the declaration order).
+    _quota_permit: Option<QuotaPermit>,
 }
 
 impl fmt::Debug for PooledStorageProcessor<'_> {
@@ -178,6 +181,7 @@ impl<'a> StorageProcessor<'a> {
     pub(super) fn from_pool(
         connection: PoolConnection<Postgres>,
         tags: Option<StorageProcessorTags>,
+        quota_permit: Option<QuotaPermit>,
         traced_connections: Option<&'a TracedConnections>,
     ) -> Self {
         let created_at = Instant::now();
@@ -189,6 +193,7 @@ impl<'a> StorageProcessor<'a> {
                 let id = connections.acquire(tags, created_at);
                 (connections, id)
             }),
+            _quota_permit: quota_permit,
         });
         Self { inner }
     }
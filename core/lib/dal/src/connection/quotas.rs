@@ -0,0 +1,135 @@
+//! Per-workload connection quotas.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Context as _;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use vise::{EncodeLabelSet, EncodeLabelValue};
+
+use crate::metrics::CONNECTION_METRICS;
+
+/// Class of the workload using a [`ConnectionPool`](super::ConnectionPool). Connection pools can limit
+/// the number of connections used by each class (see [`WorkloadQuotas`]), so that a burst of requests
+/// from one class (e.g., the API server) cannot starve other classes (e.g., the state keeper).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "workload", rename_all = "snake_case")]
+pub enum WorkloadClass {
+    /// Read-heavy API workload.
+    Api,
+    /// Write path of the state keeper (including miniblock sealing).
+    StateKeeper,
+    /// Background tasks (e.g., syncing, pruning, metrics reporting).
+    Background,
+}
+
+/// Limits on the number of concurrently used connections for [`WorkloadClass`]es sharing a connection pool.
+/// Classes without a limit can use all connections in the pool.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkloadQuotas {
+    limits: HashMap<WorkloadClass, u32>,
+}
+
+impl WorkloadQuotas {
+    /// Sets the maximum number of concurrently used connections for the specified class.
+    pub fn set_limit(&mut self, class: WorkloadClass, max_connections: u32) -> &mut Self {
+        self.limits.insert(class, max_connections);
+        self
+    }
+
+    /// Returns the connection limit for the specified class, if any.
+    pub fn limit(&self, class: WorkloadClass) -> Option<u32> {
+        self.limits.get(&class).copied()
+    }
+
+    /// Checks whether no limits are set.
+    pub fn is_empty(&self) -> bool {
+        self.limits.is_empty()
+    }
+
+    /// Checks that the limits are positive and can be satisfied simultaneously by a pool of the specified size.
+    /// A zero limit would block the class forever, and with limits exceeding the pool size, the classes
+    /// could still starve each other.
+    pub fn validate(&self, pool_size: u32) -> anyhow::Result<()> {
+        let mut total_limit = 0_u32;
+        for (&class, &limit) in &self.limits {
+            anyhow::ensure!(limit > 0, "connection quota for {class:?} workload is zero");
+            total_limit = total_limit
+                .checked_add(limit)
+                .context("connection quotas overflow")?;
+        }
+        anyhow::ensure!(
+            total_limit <= pool_size,
+            "connection quotas sum up to {total_limit}, which exceeds the pool size {pool_size}"
+        );
+        Ok(())
+    }
+}
+
+/// Semaphores enforcing [`WorkloadQuotas`] for a connection pool.
+#[derive(Debug)]
+pub(super) struct WorkloadSemaphores {
+    semaphores: HashMap<WorkloadClass, Arc<Semaphore>>,
+}
+
+impl WorkloadSemaphores {
+    pub fn new(quotas: &WorkloadQuotas) -> Self {
+        let semaphores = quotas
+            .limits
+            .iter()
+            .map(|(&class, &limit)| (class, Arc::new(Semaphore::new(limit as usize))))
+            .collect();
+        Self { semaphores }
+    }
+
+    /// Waits until a connection can be used by the specified class. Returns `None` if the class is not limited.
+    pub async fn acquire(&self, class: WorkloadClass) -> Option<QuotaPermit> {
+        let semaphore = self.semaphores.get(&class)?;
+        let wait_latency = CONNECTION_METRICS.quota_wait[&class].start();
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("quota semaphores are never closed");
+        wait_latency.observe();
+        CONNECTION_METRICS.quota_in_use[&class].inc_by(1);
+        Some(QuotaPermit {
+            class,
+            _permit: permit,
+        })
+    }
+}
+
+/// Permit to use a connection within a workload quota. The permit is released on drop.
+#[derive(Debug)]
+pub(super) struct QuotaPermit {
+    class: WorkloadClass,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for QuotaPermit {
+    fn drop(&mut self) {
+        CONNECTION_METRICS.quota_in_use[&self.class].dec_by(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validating_quotas() {
+        WorkloadQuotas::default().validate(1).unwrap();
+
+        let mut quotas = WorkloadQuotas::default();
+        quotas
+            .set_limit(WorkloadClass::Api, 6)
+            .set_limit(WorkloadClass::Background, 4);
+        quotas.validate(10).unwrap();
+        let err = quotas.validate(9).unwrap_err().to_string();
+        assert!(err.contains("exceeds the pool size"), "{err}");
+
+        quotas.set_limit(WorkloadClass::StateKeeper, 0);
+        let err = quotas.validate(10).unwrap_err().to_string();
+        assert!(err.contains("is zero"), "{err}");
+    }
+}
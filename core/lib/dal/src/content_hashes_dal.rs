@@ -0,0 +1,226 @@
+//! Content hashes of miniblocks used to detect silent corruption of the miniblock data.
+
+use zksync_types::{
+    block::MiniblockContentHasher, l2_to_l1_log::L2ToL1Log, Address, MiniblockNumber, H256,
+};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Outcome of checking the miniblock data against its persisted content hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentHashCheck {
+    /// The miniblock has no persisted content hash (e.g., it was sealed before content hashes were introduced).
+    Missing,
+    /// The miniblock data matches the persisted hash.
+    Valid(H256),
+    /// The miniblock data doesn't match the persisted hash.
+    Mismatch { persisted: H256, computed: H256 },
+}
+
+#[derive(Debug)]
+pub struct ContentHashesDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ContentHashesDal<'_, '_> {
+    pub async fn set_content_hash(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        content_hash: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE miniblocks
+            SET
+                content_hash = $2
+            WHERE
+                number = $1
+            "#,
+            i64::from(miniblock_number.0),
+            content_hash.as_bytes()
+        )
+        .instrument("set_content_hash")
+        .with_arg("miniblock_number", &miniblock_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the persisted content hash of the miniblock, or `None` if the miniblock doesn't exist or has no hash.
+    pub async fn get_content_hash(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<Option<H256>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                content_hash
+            FROM
+                miniblocks
+            WHERE
+                number = $1
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_content_hash")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row
+            .and_then(|row| row.content_hash)
+            .map(|hash| H256::from_slice(&hash)))
+    }
+
+    /// Computes the content hash of the miniblock from the transactions, events, user L2-to-L1 logs
+    /// and storage writes currently in the storage.
+    pub async fn compute_content_hash(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<H256> {
+        let txs = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                error,
+                refunded_gas
+            FROM
+                transactions
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                index_in_block
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("compute_content_hash#txs")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let events = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4,
+                value
+            FROM
+                events
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                event_index_in_block
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("compute_content_hash#events")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let l2_to_l1_logs = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                shard_id,
+                is_service,
+                sender,
+                key,
+                value
+            FROM
+                l2_to_l1_logs
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                log_index_in_miniblock
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("compute_content_hash#l2_to_l1_logs")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let storage_writes = sqlx::query!(
+            r#"
+            SELECT
+                hashed_key,
+                value
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                operation_number
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("compute_content_hash#storage_logs")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut hasher = MiniblockContentHasher::new(miniblock_number);
+        for tx in txs {
+            hasher.push_tx(
+                H256::from_slice(&tx.hash),
+                tx.error.is_none(),
+                tx.refunded_gas as u64,
+            );
+        }
+        for event in events {
+            // Missing topics are stored as empty byte arrays.
+            let topics: Vec<_> = [event.topic1, event.topic2, event.topic3, event.topic4]
+                .into_iter()
+                .filter(|topic| !topic.is_empty())
+                .map(|topic| H256::from_slice(&topic))
+                .collect();
+            hasher.push_event(
+                H256::from_slice(&event.tx_hash),
+                Address::from_slice(&event.address),
+                &topics,
+                &event.value,
+            );
+        }
+        for log in l2_to_l1_logs {
+            let log_data = L2ToL1Log {
+                shard_id: log.shard_id as u8,
+                is_service: log.is_service,
+                tx_number_in_block: 0, // not hashed
+                sender: Address::from_slice(&log.sender),
+                key: H256::from_slice(&log.key),
+                value: H256::from_slice(&log.value),
+            };
+            hasher.push_l2_to_l1_log(H256::from_slice(&log.tx_hash), &log_data);
+        }
+        for write in storage_writes {
+            hasher.push_storage_write(
+                H256::from_slice(&write.hashed_key),
+                H256::from_slice(&write.value),
+            );
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Checks the miniblock data in the storage against the persisted content hash.
+    pub async fn check_content_hash(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<ContentHashCheck> {
+        let Some(persisted) = self.get_content_hash(miniblock_number).await? else {
+            return Ok(ContentHashCheck::Missing);
+        };
+        let computed = self.compute_content_hash(miniblock_number).await?;
+        Ok(if computed == persisted {
+            ContentHashCheck::Valid(persisted)
+        } else {
+            ContentHashCheck::Mismatch {
+                persisted,
+                computed,
+            }
+        })
+    }
+}
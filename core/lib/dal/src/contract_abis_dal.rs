@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use zksync_types::Address;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// ABIs of contracts used to decode event logs.
+#[derive(Debug)]
+pub struct ContractAbisDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ContractAbisDal<'_, '_> {
+    /// Registers an ABI for the contract, or overwrites the ABI if one is already registered.
+    pub async fn register_abi(
+        &mut self,
+        address: Address,
+        abi: &serde_json::Value,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                contract_abis (address, abi, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (address) DO
+            UPDATE
+            SET
+                abi = $2,
+                updated_at = NOW()
+            "#,
+            address.as_bytes(),
+            abi
+        )
+        .instrument("register_abi")
+        .with_arg("address", &address)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the registered ABI for the contract. Returns `false` if no ABI was registered.
+    /// Does not affect ABIs of verified contracts.
+    pub async fn unregister_abi(&mut self, address: Address) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM contract_abis
+            WHERE
+                address = $1
+            "#,
+            address.as_bytes()
+        )
+        .instrument("unregister_abi")
+        .with_arg("address", &address)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns ABIs for the specified contracts. An ABI registered by the operator takes precedence
+    /// over the ABI from contract verification. Contracts without a known ABI are omitted.
+    pub async fn get_abis(
+        &mut self,
+        addresses: &[Address],
+    ) -> sqlx::Result<HashMap<Address, serde_json::Value>> {
+        let addresses: Vec<_> = addresses.iter().map(Address::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                addresses.address AS "address!",
+                COALESCE(
+                    contract_abis.abi,
+                    contracts_verification_info.verification_info -> 'artifacts' -> 'abi'
+                ) AS abi
+            FROM
+                UNNEST($1::bytea[]) AS addresses (address)
+                LEFT JOIN contract_abis ON contract_abis.address = addresses.address
+                LEFT JOIN contracts_verification_info ON contracts_verification_info.address = addresses.address
+            "#,
+            &addresses as &[&[u8]]
+        )
+        .instrument("get_abis")
+        .with_arg("addresses.len", &addresses.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| Some((Address::from_slice(&row.address), row.abi?)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    fn transfer_abi(indexed_to: bool) -> serde_json::Value {
+        serde_json::json!([{
+            "type": "event",
+            "name": "Transfer",
+            "anonymous": false,
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "to", "type": "address", "indexed": indexed_to },
+                { "name": "value", "type": "uint256", "indexed": false },
+            ],
+        }])
+    }
+
+    #[tokio::test]
+    async fn registering_and_unregistering_abis() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let address = Address::repeat_byte(1);
+        let other_address = Address::repeat_byte(2);
+
+        let abis = conn
+            .contract_abis_dal()
+            .get_abis(&[address, other_address])
+            .await
+            .unwrap();
+        assert!(abis.is_empty());
+
+        conn.contract_abis_dal()
+            .register_abi(address, &transfer_abi(true))
+            .await
+            .unwrap();
+        let abis = conn
+            .contract_abis_dal()
+            .get_abis(&[address, other_address])
+            .await
+            .unwrap();
+        assert_eq!(abis, HashMap::from([(address, transfer_abi(true))]));
+
+        conn.contract_abis_dal()
+            .register_abi(address, &transfer_abi(false))
+            .await
+            .unwrap();
+        let abis = conn.contract_abis_dal().get_abis(&[address]).await.unwrap();
+        assert_eq!(abis, HashMap::from([(address, transfer_abi(false))]));
+
+        let removed = conn
+            .contract_abis_dal()
+            .unregister_abi(address)
+            .await
+            .unwrap();
+        assert!(removed);
+        let removed = conn
+            .contract_abis_dal()
+            .unregister_abi(address)
+            .await
+            .unwrap();
+        assert!(!removed);
+        let abis = conn.contract_abis_dal().get_abis(&[address]).await.unwrap();
+        assert!(abis.is_empty());
+    }
+
+    #[tokio::test]
+    async fn getting_abis_of_verified_contracts() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let verified_address = Address::repeat_byte(1);
+        let verification_info = serde_json::json!({
+            "artifacts": { "bytecode": [], "abi": transfer_abi(true) },
+        });
+        sqlx::query(
+            "INSERT INTO contracts_verification_info (address, verification_info) VALUES ($1, $2)",
+        )
+        .bind(verified_address.as_bytes())
+        .bind(&verification_info)
+        .execute(conn.conn())
+        .await
+        .unwrap();
+
+        let abis = conn
+            .contract_abis_dal()
+            .get_abis(&[verified_address])
+            .await
+            .unwrap();
+        assert_eq!(
+            abis,
+            HashMap::from([(verified_address, transfer_abi(true))])
+        );
+
+        // The registered ABI takes precedence over the verified one.
+        conn.contract_abis_dal()
+            .register_abi(verified_address, &transfer_abi(false))
+            .await
+            .unwrap();
+        let abis = conn
+            .contract_abis_dal()
+            .get_abis(&[verified_address])
+            .await
+            .unwrap();
+        assert_eq!(
+            abis,
+            HashMap::from([(verified_address, transfer_abi(false))])
+        );
+    }
+}
@@ -0,0 +1,453 @@
+use std::ops;
+
+use chrono::{DateTime, Utc};
+use zksync_types::{
+    api::idexo::DaInclusionProof,
+    pubdata_da::{DABlobReference, DataAvailabilityBlob, L1BatchPubdata},
+    L1BatchNumber,
+};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+pub struct DataAvailabilityDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl DataAvailabilityDal<'_, '_> {
+    /// Records that pubdata of the specified L1 batch was dispatched to the DA layer. Does nothing
+    /// if the pubdata was already recorded as dispatched.
+    pub async fn insert_l1_batch_da(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        blob_id: &str,
+        reference: Option<&DABlobReference>,
+        sent_at: DateTime<Utc>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                data_availability (
+                    l1_batch_number,
+                    blob_id,
+                    namespace,
+                    height,
+                    commitment,
+                    sent_at,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+            ON CONFLICT DO NOTHING
+            "#,
+            i64::from(l1_batch_number.0),
+            blob_id,
+            reference.map(|reference| reference.namespace.as_slice()),
+            reference.map(|reference| reference.height as i64),
+            reference.map(|reference| reference.commitment.as_slice()),
+            sent_at.naive_utc()
+        )
+        .instrument("insert_l1_batch_da")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("blob_id", &blob_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Saves the inclusion data for the pubdata of the specified L1 batch. Does nothing if the inclusion
+    /// data is already saved.
+    pub async fn save_l1_batch_inclusion_data(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        inclusion_data: &[u8],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE data_availability
+            SET
+                inclusion_data = $1,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $2
+                AND inclusion_data IS NULL
+            "#,
+            inclusion_data,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("save_l1_batch_inclusion_data")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the record about pubdata of the specified L1 batch dispatched to the DA layer, e.g. because
+    /// the DA layer has rejected the blob. The pubdata will be dispatched again.
+    pub async fn remove_l1_batch_da(&mut self, l1_batch_number: L1BatchNumber) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM data_availability
+            WHERE
+                l1_batch_number = $1
+                AND inclusion_data IS NULL
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("remove_l1_batch_da")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns dispatched blobs awaiting inclusion into the DA layer, ordered by the L1 batch number.
+    /// Blobs for already committed L1 batches (e.g., ones committed with pubdata published on the settlement layer
+    /// after a DA failover) are not returned.
+    pub async fn get_blobs_awaiting_inclusion(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<DataAvailabilityBlob>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                blob_id,
+                inclusion_data,
+                sent_at
+            FROM
+                data_availability
+                JOIN l1_batches ON l1_batches.number = data_availability.l1_batch_number
+            WHERE
+                inclusion_data IS NULL
+                AND eth_commit_tx_id IS NULL
+            ORDER BY
+                l1_batch_number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_blobs_awaiting_inclusion")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DataAvailabilityBlob {
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                blob_id: row.blob_id,
+                inclusion_data: row.inclusion_data,
+                sent_at: DateTime::<Utc>::from_naive_utc_and_offset(row.sent_at, Utc),
+            })
+            .collect())
+    }
+
+    /// Returns pubdata of L1 batches that are not dispatched to the DA layer yet, ordered by the L1 batch number.
+    /// Only L1 batches with stored pubdata are returned.
+    pub async fn get_ready_for_da_dispatch_l1_batches(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<L1BatchPubdata>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                number,
+                protocol_version,
+                pubdata_input
+            FROM
+                l1_batches
+                LEFT JOIN data_availability ON data_availability.l1_batch_number = l1_batches.number
+            WHERE
+                eth_commit_tx_id IS NULL
+                AND number != 0
+                AND data_availability.blob_id IS NULL
+                AND pubdata_input IS NOT NULL
+            ORDER BY
+                number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_ready_for_da_dispatch_l1_batches")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchPubdata {
+                l1_batch_number: L1BatchNumber(row.number as u32),
+                protocol_version: row
+                    .protocol_version
+                    .map(|version| (version as u16).try_into().unwrap()),
+                // `unwrap` is safe due to the check in the query
+                pubdata: row.pubdata_input.unwrap(),
+            })
+            .collect())
+    }
+
+    /// Returns the inclusion data for L1 batches in the specified range, starting from the first batch
+    /// in the range and stopping at the first batch without inclusion data.
+    pub async fn get_inclusion_data_for_range(
+        &mut self,
+        l1_batch_range: ops::RangeInclusive<L1BatchNumber>,
+    ) -> sqlx::Result<Vec<Vec<u8>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                inclusion_data AS "inclusion_data!"
+            FROM
+                data_availability
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND inclusion_data IS NOT NULL
+            ORDER BY
+                l1_batch_number
+            "#,
+            i64::from(l1_batch_range.start().0),
+            i64::from(l1_batch_range.end().0)
+        )
+        .instrument("get_inclusion_data_for_range")
+        .with_arg("l1_batch_range", &l1_batch_range)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut expected_number = i64::from(l1_batch_range.start().0);
+        let mut inclusion_data = Vec::with_capacity(rows.len());
+        for row in rows {
+            if row.l1_batch_number != expected_number {
+                break;
+            }
+            inclusion_data.push(row.inclusion_data);
+            expected_number += 1;
+        }
+        Ok(inclusion_data)
+    }
+
+    /// Returns the proof of the pubdata inclusion into the DA layer for the specified L1 batch, or `None`
+    /// if the pubdata is not dispatched yet.
+    pub async fn get_da_inclusion_proof(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<DaInclusionProof>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                blob_id,
+                namespace,
+                height,
+                commitment,
+                inclusion_data,
+                sent_at
+            FROM
+                data_availability
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_da_inclusion_proof")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| DaInclusionProof {
+            l1_batch_number,
+            blob_id: row.blob_id,
+            namespace: row.namespace.map(Into::into),
+            height: row.height.map(|height| height as u64),
+            commitment: row.commitment.map(Into::into),
+            inclusion_data: row.inclusion_data.map(Into::into),
+            sent_at: DateTime::<Utc>::from_naive_utc_and_offset(row.sent_at, Utc),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    async fn insert_l1_batch(conn: &mut StorageProcessor<'_>, number: u32) {
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        header.pubdata_input = Some(vec![number as u8; 32]);
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn dispatching_l1_batch_pubdata() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..=3 {
+            insert_l1_batch(&mut conn, number).await;
+        }
+
+        let ready_batches = conn
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(10)
+            .await
+            .unwrap();
+        let ready_numbers: Vec<_> = ready_batches
+            .iter()
+            .map(|batch| batch.l1_batch_number)
+            .collect();
+        assert_eq!(
+            ready_numbers,
+            [L1BatchNumber(1), L1BatchNumber(2), L1BatchNumber(3)]
+        );
+        assert_eq!(ready_batches[0].pubdata, [1; 32]);
+        assert_eq!(
+            ready_batches[0].protocol_version,
+            Some(ProtocolVersionId::latest())
+        );
+
+        let sent_at = Utc::now();
+        for number in 1..=3 {
+            conn.data_availability_dal()
+                .insert_l1_batch_da(
+                    L1BatchNumber(number),
+                    &format!("blob{number}"),
+                    None,
+                    sent_at,
+                )
+                .await
+                .unwrap();
+        }
+        let ready_batches = conn
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(10)
+            .await
+            .unwrap();
+        assert!(ready_batches.is_empty());
+
+        let awaiting_blobs = conn
+            .data_availability_dal()
+            .get_blobs_awaiting_inclusion(2)
+            .await
+            .unwrap();
+        assert_eq!(awaiting_blobs.len(), 2);
+        assert_eq!(awaiting_blobs[0].l1_batch_number, L1BatchNumber(1));
+        assert_eq!(awaiting_blobs[0].blob_id, "blob1");
+        assert_eq!(awaiting_blobs[0].inclusion_data, None);
+
+        for number in [1, 3] {
+            conn.data_availability_dal()
+                .save_l1_batch_inclusion_data(L1BatchNumber(number), &[number as u8])
+                .await
+                .unwrap();
+        }
+        let awaiting_blobs = conn
+            .data_availability_dal()
+            .get_blobs_awaiting_inclusion(10)
+            .await
+            .unwrap();
+        assert_eq!(awaiting_blobs.len(), 1);
+        assert_eq!(awaiting_blobs[0].l1_batch_number, L1BatchNumber(2));
+
+        // Removed blobs are dispatched again.
+        conn.data_availability_dal()
+            .remove_l1_batch_da(L1BatchNumber(2))
+            .await
+            .unwrap();
+        let ready_batches = conn
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(10)
+            .await
+            .unwrap();
+        assert_eq!(ready_batches.len(), 1);
+        assert_eq!(ready_batches[0].l1_batch_number, L1BatchNumber(2));
+        conn.data_availability_dal()
+            .insert_l1_batch_da(L1BatchNumber(2), "blob2", None, sent_at)
+            .await
+            .unwrap();
+
+        // Inclusion data is returned up to the first L1 batch without it.
+        let inclusion_data = conn
+            .data_availability_dal()
+            .get_inclusion_data_for_range(L1BatchNumber(1)..=L1BatchNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(inclusion_data, [vec![1]]);
+
+        conn.data_availability_dal()
+            .save_l1_batch_inclusion_data(L1BatchNumber(2), &[2])
+            .await
+            .unwrap();
+        let inclusion_data = conn
+            .data_availability_dal()
+            .get_inclusion_data_for_range(L1BatchNumber(1)..=L1BatchNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(inclusion_data, [vec![1], vec![2], vec![3]]);
+    }
+
+    #[tokio::test]
+    async fn getting_da_inclusion_proof() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..=2 {
+            insert_l1_batch(&mut conn, number).await;
+        }
+
+        let reference = DABlobReference {
+            namespace: vec![0; 29],
+            height: 123,
+            commitment: vec![1; 32],
+        };
+        conn.data_availability_dal()
+            .insert_l1_batch_da(L1BatchNumber(1), "123:0101", Some(&reference), Utc::now())
+            .await
+            .unwrap();
+        let proof = conn
+            .data_availability_dal()
+            .get_da_inclusion_proof(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no inclusion proof");
+        assert_eq!(proof.blob_id, "123:0101");
+        assert_eq!(proof.namespace.unwrap().0, reference.namespace);
+        assert_eq!(proof.height, Some(123));
+        assert_eq!(proof.commitment.unwrap().0, reference.commitment);
+        assert_eq!(proof.inclusion_data, None);
+
+        conn.data_availability_dal()
+            .save_l1_batch_inclusion_data(L1BatchNumber(1), &[1, 2, 3])
+            .await
+            .unwrap();
+        let proof = conn
+            .data_availability_dal()
+            .get_da_inclusion_proof(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no inclusion proof");
+        assert_eq!(proof.inclusion_data.unwrap().0, [1, 2, 3]);
+
+        let proof = conn
+            .data_availability_dal()
+            .get_da_inclusion_proof(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert!(proof.is_none());
+    }
+}
@@ -7,13 +7,16 @@ use sqlx::{
 };
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    eth_sender::{EthTx, TxHistory, TxHistoryToSend},
+    eth_sender::{
+        EthTx, EthTxBlobSidecar, GasEscalationEntry, SettlementHalt, TxHistory, TxHistoryToSend,
+    },
     Address, L1BatchNumber, H256, U256,
 };
 
 use crate::{
     models::storage_eth_tx::{
-        L1BatchEthSenderStats, StorageEthTx, StorageTxHistory, StorageTxHistoryToSend,
+        L1BatchEthSenderStats, StorageEthTx, StorageGasEscalationEntry, StorageSettlementHalt,
+        StorageTxHistory, StorageTxHistoryToSend,
     },
     StorageProcessor,
 };
@@ -24,6 +27,8 @@ pub struct EthSenderDal<'a, 'c> {
 }
 
 impl EthSenderDal<'_, '_> {
+    /// Returns in-flight transactions for all operator accounts ordered by ID. A transaction is considered
+    /// in-flight if it's not confirmed and a transaction with the same or greater ID was sent from the same account.
     pub async fn get_inflight_txs(&mut self) -> sqlx::Result<Vec<EthTx>> {
         let txs = sqlx::query_as!(
             StorageEthTx,
@@ -39,8 +44,11 @@ impl EthSenderDal<'_, '_> {
                         COALESCE(MAX(eth_tx_id), 0)
                     FROM
                         eth_txs_history
+                        JOIN eth_txs AS sent_txs ON sent_txs.id = eth_txs_history.eth_tx_id
                     WHERE
-                        sent_at_block IS NOT NULL
+                        eth_txs_history.sent_at_block IS NOT NULL
+                        AND eth_txs_history.superseded_at IS NULL
+                        AND sent_txs.from_addr IS NOT DISTINCT FROM eth_txs.from_addr
                 )
             ORDER BY
                 id
@@ -113,7 +121,14 @@ impl EthSenderDal<'_, '_> {
         .map(Into::into))
     }
 
-    pub async fn get_new_eth_txs(&mut self, limit: u64) -> sqlx::Result<Vec<EthTx>> {
+    /// Returns transactions that were not sent yet from the specified operator account (`None` corresponds
+    /// to the main operator account).
+    pub async fn get_new_eth_txs(
+        &mut self,
+        limit: u64,
+        from_addr: Option<Address>,
+    ) -> sqlx::Result<Vec<EthTx>> {
+        let from_addr = from_addr.map(|addr| addr.as_bytes().to_vec());
         let txs = sqlx::query_as!(
             StorageEthTx,
             r#"
@@ -122,18 +137,24 @@ impl EthSenderDal<'_, '_> {
             FROM
                 eth_txs
             WHERE
-                id > (
+                from_addr IS NOT DISTINCT FROM $2
+                AND id > (
                     SELECT
                         COALESCE(MAX(eth_tx_id), 0)
                     FROM
                         eth_txs_history
+                        JOIN eth_txs AS sent_txs ON sent_txs.id = eth_txs_history.eth_tx_id
+                    WHERE
+                        eth_txs_history.superseded_at IS NULL
+                        AND sent_txs.from_addr IS NOT DISTINCT FROM $2
                 )
             ORDER BY
                 id
             LIMIT
                 $1
             "#,
-            limit as i64
+            limit as i64,
+            from_addr
         )
         .fetch_all(self.storage.conn())
         .await?;
@@ -157,6 +178,7 @@ impl EthSenderDal<'_, '_> {
                 JOIN eth_txs ON eth_txs.id = eth_txs_history.eth_tx_id
             WHERE
                 eth_txs_history.sent_at_block IS NULL
+                AND eth_txs_history.superseded_at IS NULL
                 AND eth_txs.confirmed_eth_tx_history_id IS NULL
             ORDER BY
                 eth_txs_history.id DESC
@@ -174,8 +196,14 @@ impl EthSenderDal<'_, '_> {
         tx_type: AggregatedActionType,
         contract_address: Address,
         predicted_gas_cost: u32,
+        from_addr: Option<Address>,
+        blob_sidecar: Option<EthTxBlobSidecar>,
     ) -> sqlx::Result<EthTx> {
         let address = format!("{:#x}", contract_address);
+        let from_addr = from_addr.map(|addr| addr.as_bytes().to_vec());
+        let blob_sidecar = blob_sidecar.map(|sidecar| {
+            bincode::serialize(&sidecar).expect("can always bincode serialize EthTxBlobSidecar")
+        });
         let eth_tx = sqlx::query_as!(
             StorageEthTx,
             r#"
@@ -187,10 +215,12 @@ impl EthSenderDal<'_, '_> {
                     contract_address,
                     predicted_gas_cost,
                     created_at,
-                    updated_at
+                    updated_at,
+                    from_addr,
+                    blob_sidecar
                 )
             VALUES
-                ($1, $2, $3, $4, $5, NOW(), NOW())
+                ($1, $2, $3, $4, $5, NOW(), NOW(), $6, $7)
             RETURNING
                 *
             "#,
@@ -198,7 +228,9 @@ impl EthSenderDal<'_, '_> {
             nonce as i64,
             tx_type.to_string(),
             address,
-            predicted_gas_cost as i64
+            predicted_gas_cost as i64,
+            from_addr,
+            blob_sidecar
         )
         .fetch_one(self.storage.conn())
         .await?;
@@ -210,6 +242,7 @@ impl EthSenderDal<'_, '_> {
         eth_tx_id: u32,
         base_fee_per_gas: u64,
         priority_fee_per_gas: u64,
+        blob_base_fee_per_gas: Option<u64>,
         tx_hash: H256,
         raw_signed_tx: &[u8],
     ) -> anyhow::Result<Option<u32>> {
@@ -217,6 +250,10 @@ impl EthSenderDal<'_, '_> {
             i64::try_from(priority_fee_per_gas).context("Can't convert u64 to i64")?;
         let base_fee_per_gas =
             i64::try_from(base_fee_per_gas).context("Can't convert u64 to i64")?;
+        let blob_base_fee_per_gas = blob_base_fee_per_gas
+            .map(i64::try_from)
+            .transpose()
+            .context("Can't convert u64 to i64")?;
         let tx_hash = format!("{:#x}", tx_hash);
 
         Ok(sqlx::query!(
@@ -229,10 +266,11 @@ impl EthSenderDal<'_, '_> {
                     tx_hash,
                     signed_raw_tx,
                     created_at,
-                    updated_at
+                    updated_at,
+                    blob_base_fee_per_gas
                 )
             VALUES
-                ($1, $2, $3, $4, $5, NOW(), NOW())
+                ($1, $2, $3, $4, $5, NOW(), NOW(), $6)
             ON CONFLICT (tx_hash) DO NOTHING
             RETURNING
                 id
@@ -241,7 +279,8 @@ impl EthSenderDal<'_, '_> {
             base_fee_per_gas,
             priority_fee_per_gas,
             tx_hash,
-            raw_signed_tx
+            raw_signed_tx,
+            blob_base_fee_per_gas
         )
         .fetch_optional(self.storage.conn())
         .await?
@@ -449,6 +488,8 @@ impl EthSenderDal<'_, '_> {
         transaction.commit().await.context("commit()")
     }
 
+    /// Returns all sending attempts for the specified transaction, including superseded ones, since they may still
+    /// have been mined.
     pub async fn get_tx_history_to_check(
         &mut self,
         eth_tx_id: u32,
@@ -477,7 +518,7 @@ impl EthSenderDal<'_, '_> {
         eth_tx_id: u32,
     ) -> sqlx::Result<Option<u32>> {
         let sent_at_block = sqlx::query_scalar!(
-            "SELECT sent_at_block FROM eth_txs_history WHERE eth_tx_id = $1 AND sent_at_block IS NOT NULL ORDER BY created_at ASC LIMIT 1",
+            "SELECT sent_at_block FROM eth_txs_history WHERE eth_tx_id = $1 AND sent_at_block IS NOT NULL AND superseded_at IS NULL ORDER BY created_at ASC LIMIT 1",
             eth_tx_id as i32
         )
         .fetch_optional(self.storage.conn())
@@ -498,6 +539,7 @@ impl EthSenderDal<'_, '_> {
                 eth_txs_history
             WHERE
                 eth_tx_id = $1
+                AND superseded_at IS NULL
             ORDER BY
                 created_at DESC
             LIMIT
@@ -510,24 +552,289 @@ impl EthSenderDal<'_, '_> {
         Ok(history_item.map(|tx| tx.into()))
     }
 
-    pub async fn get_next_nonce(&mut self) -> sqlx::Result<Option<u64>> {
+    /// Returns the number of stored sending attempts for the specified transaction, not counting superseded attempts.
+    pub async fn get_number_of_attempts(&mut self, eth_tx_id: u32) -> sqlx::Result<u32> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*)
+            FROM
+                eth_txs_history
+            WHERE
+                eth_tx_id = $1
+                AND superseded_at IS NULL
+            "#,
+            eth_tx_id as i32
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(count.unwrap_or(0) as u32)
+    }
+
+    /// Appends an entry to the gas escalation audit trail.
+    pub async fn insert_gas_escalation(
+        &mut self,
+        entry: &GasEscalationEntry,
+    ) -> anyhow::Result<()> {
+        let base_fee_per_gas =
+            i64::try_from(entry.base_fee_per_gas).context("Can't convert u64 to i64")?;
+        let priority_fee_per_gas =
+            i64::try_from(entry.priority_fee_per_gas).context("Can't convert u64 to i64")?;
+        let blob_base_fee_per_gas = entry
+            .blob_base_fee_per_gas
+            .map(i64::try_from)
+            .transpose()
+            .context("Can't convert u64 to i64")?;
+        let tx_hash = entry.tx_hash.map(|hash| format!("{hash:#x}"));
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_txs_gas_escalations (
+                    eth_tx_id,
+                    attempt,
+                    action,
+                    skip_reason,
+                    base_fee_per_gas,
+                    priority_fee_per_gas,
+                    blob_base_fee_per_gas,
+                    tx_hash,
+                    l1_block_number,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+            "#,
+            entry.eth_tx_id as i32,
+            entry.attempt as i32,
+            entry.action.as_str(),
+            entry.skip_reason,
+            base_fee_per_gas,
+            priority_fee_per_gas,
+            blob_base_fee_per_gas,
+            tx_hash,
+            entry.l1_block_number as i32
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the gas escalation audit trail for the specified transaction, from the oldest entry to the newest one.
+    pub async fn get_gas_escalations(
+        &mut self,
+        eth_tx_id: u32,
+    ) -> sqlx::Result<Vec<GasEscalationEntry>> {
+        let entries = sqlx::query_as!(
+            StorageGasEscalationEntry,
+            r#"
+            SELECT
+                eth_tx_id,
+                attempt,
+                action,
+                skip_reason,
+                base_fee_per_gas,
+                priority_fee_per_gas,
+                blob_base_fee_per_gas,
+                tx_hash,
+                l1_block_number
+            FROM
+                eth_txs_gas_escalations
+            WHERE
+                eth_tx_id = $1
+            ORDER BY
+                id
+            "#,
+            eth_tx_id as i32
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
+
+    /// Halts settlement because of the specified anomaly. Does nothing if settlement is already halted.
+    /// Previously resumed halts are not taken into account, so an anomaly persisting after a resume halts
+    /// settlement again.
+    ///
+    /// Returns `true` if a new halt was recorded.
+    pub async fn halt_settlement(&mut self, reason: &str) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_sender_settlement_halts (reason, halted_at)
+            SELECT
+                $1,
+                NOW()
+            WHERE
+                NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        eth_sender_settlement_halts
+                    WHERE
+                        resumed_at IS NULL
+                )
+            "#,
+            reason
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns the active settlement halt, if any.
+    pub async fn get_active_settlement_halt(&mut self) -> sqlx::Result<Option<SettlementHalt>> {
+        let halt = sqlx::query_as!(
+            StorageSettlementHalt,
+            r#"
+            SELECT
+                id,
+                reason,
+                halted_at,
+                resumed_at
+            FROM
+                eth_sender_settlement_halts
+            WHERE
+                resumed_at IS NULL
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(halt.map(Into::into))
+    }
+
+    /// Resumes settlement after a halt. Returns the resumed halt, or `None` if settlement wasn't halted.
+    pub async fn resume_settlement(&mut self) -> sqlx::Result<Option<SettlementHalt>> {
+        let halt = sqlx::query_as!(
+            StorageSettlementHalt,
+            r#"
+            UPDATE eth_sender_settlement_halts
+            SET
+                resumed_at = NOW()
+            WHERE
+                resumed_at IS NULL
+            RETURNING
+                id,
+                reason,
+                halted_at,
+                resumed_at
+            "#
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(halt.map(Into::into))
+    }
+
+    /// Returns the next nonce for the specified operator account (`None` corresponds to the main operator account)
+    /// based on the stored transactions, i.e., the nonce following the greatest stored nonce. Nonces are not
+    /// necessarily ordered by transaction ID, since they can be reassigned by [`Self::reassign_nonces()`].
+    pub async fn get_next_nonce(
+        &mut self,
+        from_addr: Option<Address>,
+    ) -> sqlx::Result<Option<u64>> {
+        let from_addr = from_addr.map(|addr| addr.as_bytes().to_vec());
         let row = sqlx::query!(
             r#"
             SELECT
                 nonce
             FROM
                 eth_txs
+            WHERE
+                from_addr IS NOT DISTINCT FROM $1
             ORDER BY
-                id DESC
+                nonce DESC
             LIMIT
                 1
-            "#
+            "#,
+            from_addr
         )
         .fetch_optional(self.storage.conn())
         .await?;
         Ok(row.map(|row| row.nonce as u64 + 1))
     }
 
+    /// Returns unconfirmed and not failed transactions sent from the specified account with ID greater than
+    /// or equal to `min_eth_tx_id`, ordered by ID.
+    pub async fn get_unconfirmed_txs(
+        &mut self,
+        from_addr: Option<Address>,
+        min_eth_tx_id: u32,
+    ) -> sqlx::Result<Vec<EthTx>> {
+        let from_addr = from_addr.map(|addr| addr.as_bytes().to_vec());
+        let txs = sqlx::query_as!(
+            StorageEthTx,
+            r#"
+            SELECT
+                *
+            FROM
+                eth_txs
+            WHERE
+                from_addr IS NOT DISTINCT FROM $1
+                AND id >= $2
+                AND confirmed_eth_tx_history_id IS NULL
+                AND NOT has_failed
+            ORDER BY
+                id
+            "#,
+            from_addr,
+            min_eth_tx_id as i32
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        Ok(txs.into_iter().map(Into::into).collect())
+    }
+
+    /// Assigns new nonces to the specified transactions and marks all their sending attempts as superseded,
+    /// so that the transactions are sent anew. Superseded attempts are kept for auditing. `reassignments` contains
+    /// pairs of transaction IDs and new nonces.
+    pub async fn reassign_nonces(&mut self, reassignments: &[(u32, u64)]) -> sqlx::Result<()> {
+        let (eth_tx_ids, nonces): (Vec<_>, Vec<_>) = reassignments
+            .iter()
+            .map(|&(eth_tx_id, nonce)| (eth_tx_id as i32, nonce as i64))
+            .unzip();
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            UPDATE eth_txs_history
+            SET
+                superseded_at = NOW(),
+                updated_at = NOW()
+            WHERE
+                eth_tx_id = ANY ($1)
+                AND superseded_at IS NULL
+            "#,
+            &eth_tx_ids
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE eth_txs
+            SET
+                nonce = data_table.nonce,
+                updated_at = NOW()
+            FROM
+                (
+                    SELECT
+                        UNNEST($1::INT[]) AS id,
+                        UNNEST($2::BIGINT[]) AS nonce
+                ) AS data_table
+            WHERE
+                eth_txs.id = data_table.id
+            "#,
+            &eth_tx_ids,
+            &nonces
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await
+    }
+
     pub async fn mark_failed_transaction(&mut self, eth_tx_id: u32) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
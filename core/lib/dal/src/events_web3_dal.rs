@@ -48,7 +48,6 @@ impl EventsWeb3Dal<'_, '_> {
             query = query.bind(offset as i32);
             let log = query
                 .instrument("get_log_block_number")
-                .report_latency()
                 .with_arg("filter", filter)
                 .with_arg("offset", &offset)
                 .fetch_optional(self.storage)
@@ -101,7 +100,6 @@ impl EventsWeb3Dal<'_, '_> {
 
             let db_logs: Vec<StorageWeb3Log> = query
                 .instrument("get_logs")
-                .report_latency()
                 .with_arg("filter", &filter)
                 .with_arg("limit", &limit)
                 .fetch_all(self.storage)
@@ -130,6 +128,91 @@ impl EventsWeb3Dal<'_, '_> {
         (where_sql, arg_index)
     }
 
+    /// Returns logs emitted in the specified miniblock by one of `addresses` with the first topic in `topics1`,
+    /// ordered by their index in the miniblock. If `tx_hash` is specified, only logs of this transaction
+    /// are returned. Logs can be paginated by setting `after_log_index` to the index of the last returned log.
+    pub async fn get_miniblock_logs(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        tx_hash: Option<H256>,
+        addresses: &[Address],
+        topics1: &[H256],
+        after_log_index: Option<u32>,
+        limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        let addresses: Vec<_> = addresses.iter().map(Address::as_bytes).collect();
+        let topics1: Vec<_> = topics1.iter().map(H256::as_bytes).collect();
+        let db_logs: Vec<StorageWeb3Log> = sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            WITH
+                events_select AS (
+                    SELECT
+                        address,
+                        topic1,
+                        topic2,
+                        topic3,
+                        topic4,
+                        value,
+                        miniblock_number,
+                        tx_hash,
+                        tx_index_in_block,
+                        event_index_in_block,
+                        event_index_in_tx
+                    FROM
+                        events
+                    WHERE
+                        miniblock_number = $1
+                        AND (
+                            $2::BYTEA IS NULL
+                            OR tx_hash = $2
+                        )
+                        AND address = ANY ($3)
+                        AND topic1 = ANY ($4)
+                        AND event_index_in_block > $5
+                    ORDER BY
+                        event_index_in_block ASC
+                    LIMIT
+                        $6
+                )
+            SELECT
+                miniblocks.hash AS "block_hash?",
+                address AS "address!",
+                topic1 AS "topic1!",
+                topic2 AS "topic2!",
+                topic3 AS "topic3!",
+                topic4 AS "topic4!",
+                value AS "value!",
+                miniblock_number AS "miniblock_number!",
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                tx_hash AS "tx_hash!",
+                tx_index_in_block AS "tx_index_in_block!",
+                event_index_in_block AS "event_index_in_block!",
+                event_index_in_tx AS "event_index_in_tx!"
+            FROM
+                events_select
+                INNER JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            ORDER BY
+                event_index_in_block ASC
+            "#,
+            i64::from(miniblock_number.0),
+            tx_hash.as_ref().map(H256::as_bytes),
+            &addresses as &[&[u8]],
+            &topics1 as &[&[u8]],
+            after_log_index.map_or(-1, |index| index as i32),
+            limit as i64
+        )
+        .instrument("get_miniblock_logs")
+        .with_arg("miniblock_number", &miniblock_number)
+        .with_arg("tx_hash", &tx_hash)
+        .with_arg("after_log_index", &after_log_index)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+        let logs = db_logs.into_iter().map(Into::into).collect();
+        Ok(logs)
+    }
+
     pub async fn get_all_logs(
         &mut self,
         from_block: MiniblockNumber,
@@ -183,7 +266,9 @@ impl EventsWeb3Dal<'_, '_> {
                 "#,
                 from_block.0 as i64
             )
-            .fetch_all(self.storage.conn())
+            .instrument("get_all_logs")
+            .with_arg("from_block", &from_block)
+            .fetch_all(self.storage)
             .await?;
             let logs = db_logs.into_iter().map(Into::into).collect();
             Ok(logs)
@@ -1,7 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    ops,
+};
 
 use zksync_contracts::{BaseSystemContracts, SystemContractCode};
-use zksync_types::{MiniblockNumber, H256, U256};
+use zksync_types::{api::idexo::DeployedBytecode, MiniblockNumber, H256, U256};
 use zksync_utils::{bytes_to_be_words, bytes_to_chunks};
 
 use crate::StorageProcessor;
@@ -130,6 +133,68 @@ impl FactoryDepsDal<'_, '_> {
         .collect()
     }
 
+    /// Returns factory deps inserted in the specified miniblock range, ordered by bytecode hash.
+    pub async fn get_factory_deps_in_miniblocks(
+        &mut self,
+        miniblock_range: ops::RangeInclusive<MiniblockNumber>,
+        offset: usize,
+        limit: usize,
+    ) -> sqlx::Result<Vec<DeployedBytecode>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                bytecode_hash,
+                bytecode
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                bytecode_hash
+            OFFSET
+                $3
+            LIMIT
+                $4
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64,
+            offset as i64,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DeployedBytecode {
+                bytecode_hash: H256::from_slice(&row.bytecode_hash),
+                bytecode: row.bytecode.into(),
+            })
+            .collect())
+    }
+
+    /// Returns the number of factory deps inserted in the specified miniblock range.
+    pub async fn count_factory_deps_in_miniblocks(
+        &mut self,
+        miniblock_range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<usize> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.count as usize)
+    }
+
     /// Returns bytecode hashes for factory deps from miniblocks with number strictly greater
     /// than `block_number`.
     pub async fn get_factory_deps_for_revert(
@@ -0,0 +1,582 @@
+//! Registry of accounts with discounted L2 fees, and rebates of fees paid by these accounts.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use zksync_types::{api::idexo::FeeDiscount, Address, MiniblockNumber, H256, U256};
+use zksync_utils::bigdecimal_to_u256;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+struct StorageFeeDiscount {
+    address: Vec<u8>,
+    discount_bps: i32,
+    label: Option<String>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<StorageFeeDiscount> for FeeDiscount {
+    fn from(row: StorageFeeDiscount) -> Self {
+        Self {
+            address: Address::from_slice(&row.address),
+            discount_bps: row.discount_bps as u32,
+            label: row.label,
+            created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+            updated_at: DateTime::<Utc>::from_naive_utc_and_offset(row.updated_at, Utc),
+        }
+    }
+}
+
+/// Total of rebates owed to an account that are not paid out yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnpaidFeeRebate {
+    pub account: Address,
+    pub amount: U256,
+}
+
+#[derive(Debug)]
+pub struct FeeDiscountsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl FeeDiscountsDal<'_, '_> {
+    /// Grants a discount to the account, or overwrites the existing discount.
+    pub async fn set_discount(
+        &mut self,
+        address: Address,
+        discount_bps: u32,
+        label: Option<&str>,
+    ) -> sqlx::Result<FeeDiscount> {
+        let discount = sqlx::query_as!(
+            StorageFeeDiscount,
+            r#"
+            INSERT INTO
+                fee_discounts (address, discount_bps, label, created_at, updated_at)
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (address) DO
+            UPDATE
+            SET
+                discount_bps = $2,
+                label = $3,
+                updated_at = NOW()
+            RETURNING
+                address,
+                discount_bps,
+                label,
+                created_at,
+                updated_at
+            "#,
+            address.as_bytes(),
+            discount_bps as i32,
+            label
+        )
+        .instrument("set_fee_discount")
+        .with_arg("address", &address)
+        .with_arg("discount_bps", &discount_bps)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(discount.into())
+    }
+
+    /// Removes the discount of the account. Returns the removed discount, or `None` if the account
+    /// had no discount.
+    pub async fn remove_discount(&mut self, address: Address) -> sqlx::Result<Option<FeeDiscount>> {
+        let discount = sqlx::query_as!(
+            StorageFeeDiscount,
+            r#"
+            DELETE FROM fee_discounts
+            WHERE
+                address = $1
+            RETURNING
+                address,
+                discount_bps,
+                label,
+                created_at,
+                updated_at
+            "#,
+            address.as_bytes()
+        )
+        .instrument("remove_fee_discount")
+        .with_arg("address", &address)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(discount.map(Into::into))
+    }
+
+    /// Returns all discounts ordered by the account address.
+    pub async fn get_discounts(&mut self) -> sqlx::Result<Vec<FeeDiscount>> {
+        let discounts = sqlx::query_as!(
+            StorageFeeDiscount,
+            r#"
+            SELECT
+                address,
+                discount_bps,
+                label,
+                created_at,
+                updated_at
+            FROM
+                fee_discounts
+            ORDER BY
+                address
+            "#
+        )
+        .instrument("get_fee_discounts")
+        .fetch_all(self.storage)
+        .await?;
+        Ok(discounts.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns discounts in basis points for the specified accounts. Accounts without a discount
+    /// are not present in the returned map.
+    pub async fn get_discounts_for_accounts(
+        &mut self,
+        accounts: &[Address],
+    ) -> sqlx::Result<HashMap<Address, u32>> {
+        let account_bytes: Vec<_> = accounts.iter().map(Address::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                address,
+                discount_bps
+            FROM
+                fee_discounts
+            WHERE
+                address = ANY ($1)
+            "#,
+            &account_bytes as &[&[u8]]
+        )
+        .instrument("get_fee_discounts_for_accounts")
+        .with_arg("accounts.len", &accounts.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (Address::from_slice(&row.address), row.discount_bps as u32))
+            .collect())
+    }
+
+    /// Records the discount of a transaction of a discounted account accepted to the mempool. Should be called
+    /// in the same transaction as inserting the transaction. The rebate is computed from the fee paid by
+    /// the transaction once it's executed.
+    pub async fn insert_discounted_tx(
+        &mut self,
+        tx_hash: H256,
+        account: Address,
+        discount_bps: u32,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                fee_rebates (tx_hash, account, discount_bps, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            tx_hash.as_bytes(),
+            account.as_bytes(),
+            discount_bps as i32
+        )
+        .instrument("insert_discounted_tx")
+        .with_arg("tx_hash", &tx_hash)
+        .with_arg("account", &account)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes discounts recorded for transactions of the account that are no longer stored, e.g. because they were
+    /// replaced in the mempool by a transaction with the same nonce. Should be called in the same transaction
+    /// as replacing a transaction. Returns the number of removed discounts.
+    pub async fn remove_replaced_discounted_txs(&mut self, account: Address) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM fee_rebates
+            WHERE
+                account = $1
+                AND transfer_tx_hash IS NULL
+                AND NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        transactions
+                    WHERE
+                        transactions.hash = fee_rebates.tx_hash
+                )
+            "#,
+            account.as_bytes()
+        )
+        .instrument("remove_replaced_discounted_txs")
+        .with_arg("account", &account)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Returns totals of unpaid rebates for transactions executed in miniblocks up to and including `up_to_miniblock`
+    /// per account, ordered by the account address. The rebate of a transaction is the discounted part of the fee
+    /// paid by it (after refunds), rounded down. A rebate is unpaid if no transfer was submitted for it, or if
+    /// the transfer failed or was dropped from the mempool.
+    pub async fn get_unpaid_rebates(
+        &mut self,
+        up_to_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Vec<UnpaidFeeRebate>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                fee_rebates.account,
+                SUM(
+                    FLOOR(
+                        txs.effective_gas_price * (txs.gas_limit - txs.refunded_gas) * fee_rebates.discount_bps / 10000
+                    )
+                ) AS "amount!"
+            FROM
+                fee_rebates
+                INNER JOIN transactions AS txs ON txs.hash = fee_rebates.tx_hash
+                LEFT JOIN transactions AS transfers ON transfers.hash = fee_rebates.transfer_tx_hash
+            WHERE
+                txs.miniblock_number <= $1
+                AND (
+                    fee_rebates.transfer_tx_hash IS NULL
+                    OR transfers.hash IS NULL
+                    OR transfers.error IS NOT NULL
+                )
+            GROUP BY
+                fee_rebates.account
+            HAVING
+                SUM(
+                    FLOOR(
+                        txs.effective_gas_price * (txs.gas_limit - txs.refunded_gas) * fee_rebates.discount_bps / 10000
+                    )
+                ) > 0
+            ORDER BY
+                fee_rebates.account
+            "#,
+            i64::from(up_to_miniblock.0)
+        )
+        .instrument("get_unpaid_fee_rebates")
+        .with_arg("up_to_miniblock", &up_to_miniblock)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| UnpaidFeeRebate {
+                account: Address::from_slice(&row.account),
+                amount: bigdecimal_to_u256(row.amount),
+            })
+            .collect())
+    }
+
+    /// Checks whether there are rebate transfers waiting in the mempool.
+    pub async fn has_pending_rebate_transfers(&mut self) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        fee_rebates
+                        INNER JOIN transactions ON transactions.hash = fee_rebates.transfer_tx_hash
+                    WHERE
+                        transactions.miniblock_number IS NULL
+                        AND transactions.error IS NULL
+                ) AS "exists!"
+            "#
+        )
+        .instrument("has_pending_fee_rebate_transfers")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.exists)
+    }
+
+    /// Assigns the payout transfer to unpaid rebates of the account for miniblocks up to and including
+    /// `up_to_miniblock`, i.e., rebates returned by [`Self::get_unpaid_rebates()`]. Should be called
+    /// in the same transaction as persisting the transfer. Returns the number of affected rebates.
+    pub async fn assign_rebate_transfer(
+        &mut self,
+        account: Address,
+        up_to_miniblock: MiniblockNumber,
+        transfer_tx_hash: H256,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE fee_rebates
+            SET
+                transfer_tx_hash = $3
+            WHERE
+                account = $1
+                AND EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        transactions
+                    WHERE
+                        transactions.hash = fee_rebates.tx_hash
+                        AND transactions.miniblock_number <= $2
+                )
+                AND (
+                    transfer_tx_hash IS NULL
+                    OR NOT EXISTS (
+                        SELECT
+                            1
+                        FROM
+                            transactions
+                        WHERE
+                            transactions.hash = fee_rebates.transfer_tx_hash
+                            AND transactions.error IS NULL
+                    )
+                )
+            "#,
+            account.as_bytes(),
+            i64::from(up_to_miniblock.0),
+            transfer_tx_hash.as_bytes()
+        )
+        .instrument("assign_fee_rebate_transfer")
+        .with_arg("account", &account)
+        .with_arg("up_to_miniblock", &up_to_miniblock)
+        .with_arg("transfer_tx_hash", &transfer_tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        fee::TransactionExecutionMetrics, tx::TransactionExecutionResult, ProtocolVersion,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        transactions_dal::L2TxSubmissionResult,
+        ConnectionPool,
+    };
+
+    #[tokio::test]
+    async fn managing_fee_discounts() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let partner = Address::repeat_byte(1);
+        let other_partner = Address::repeat_byte(2);
+        assert!(conn
+            .fee_discounts_dal()
+            .get_discounts()
+            .await
+            .unwrap()
+            .is_empty());
+
+        let discount = conn
+            .fee_discounts_dal()
+            .set_discount(other_partner, 10_000, None)
+            .await
+            .unwrap();
+        assert_eq!(discount.address, other_partner);
+        assert_eq!(discount.discount_bps, 10_000);
+        let discount = conn
+            .fee_discounts_dal()
+            .set_discount(partner, 2_500, Some("partner"))
+            .await
+            .unwrap();
+        assert_eq!(discount.label.as_deref(), Some("partner"));
+
+        let updated_discount = conn
+            .fee_discounts_dal()
+            .set_discount(partner, 5_000, Some("partner"))
+            .await
+            .unwrap();
+        assert_eq!(updated_discount.discount_bps, 5_000);
+        assert_eq!(updated_discount.created_at, discount.created_at);
+        let discounts = conn.fee_discounts_dal().get_discounts().await.unwrap();
+        let addresses: Vec<_> = discounts.iter().map(|discount| discount.address).collect();
+        assert_eq!(addresses, [partner, other_partner]);
+
+        let discounts = conn
+            .fee_discounts_dal()
+            .get_discounts_for_accounts(&[partner, Address::repeat_byte(3)])
+            .await
+            .unwrap();
+        assert_eq!(discounts, HashMap::from([(partner, 5_000)]));
+
+        let removed_discount = conn
+            .fee_discounts_dal()
+            .remove_discount(partner)
+            .await
+            .unwrap();
+        assert_eq!(removed_discount, Some(updated_discount));
+        let removed_discount = conn
+            .fee_discounts_dal()
+            .remove_discount(partner)
+            .await
+            .unwrap();
+        assert_eq!(removed_discount, None);
+    }
+
+    #[tokio::test]
+    async fn removing_discounts_of_replaced_txs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let tx = mock_l2_transaction();
+        let partner = tx.initiator_account();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        conn.fee_discounts_dal()
+            .insert_discounted_tx(tx.hash(), partner, 5_000)
+            .await
+            .unwrap();
+        let removed_count = conn
+            .fee_discounts_dal()
+            .remove_replaced_discounted_txs(partner)
+            .await
+            .unwrap();
+        assert_eq!(removed_count, 0);
+
+        let mut replacement_tx = mock_l2_transaction();
+        replacement_tx.common_data.nonce = tx.common_data.nonce;
+        replacement_tx.common_data.initiator_address = partner;
+        let result = conn
+            .transactions_dal()
+            .insert_transaction_l2(replacement_tx, TransactionExecutionMetrics::default())
+            .await;
+        assert_eq!(result, L2TxSubmissionResult::Replaced);
+        let removed_count = conn
+            .fee_discounts_dal()
+            .remove_replaced_discounted_txs(partner)
+            .await
+            .unwrap();
+        assert_eq!(removed_count, 1);
+    }
+
+    #[tokio::test]
+    async fn paying_out_fee_rebates() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let partner = Address::repeat_byte(1);
+        let other_partner = Address::repeat_byte(2);
+        // Each transaction has the gas limit 1_000_000 and pays the base fee of 100 wei per gas.
+        let discounted_txs = [
+            (partner, 5_000, 1, 0),
+            (partner, 5_000, 1, 500_000),
+            (other_partner, 10_000, 1, 0),
+            (partner, 5_000, 2, 0),
+        ];
+        for number in [1, 2] {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+        for (account, discount_bps, miniblock_number, refunded_gas) in discounted_txs {
+            let tx = mock_l2_transaction();
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+            conn.fee_discounts_dal()
+                .insert_discounted_tx(tx.hash(), account, discount_bps)
+                .await
+                .unwrap();
+            let execution_result = TransactionExecutionResult {
+                refunded_gas,
+                ..mock_execution_result(tx)
+            };
+            conn.transactions_dal()
+                .mark_txs_as_executed_in_miniblock(
+                    MiniblockNumber(miniblock_number),
+                    &[execution_result],
+                    100.into(),
+                )
+                .await;
+        }
+        // Rebates are not paid for transactions that are not executed.
+        let pending_tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(pending_tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        conn.fee_discounts_dal()
+            .insert_discounted_tx(pending_tx.hash(), partner, 5_000)
+            .await
+            .unwrap();
+
+        let unpaid_rebates = conn
+            .fee_discounts_dal()
+            .get_unpaid_rebates(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            unpaid_rebates,
+            [
+                UnpaidFeeRebate {
+                    account: partner,
+                    amount: 75_000_000.into(),
+                },
+                UnpaidFeeRebate {
+                    account: other_partner,
+                    amount: 100_000_000.into(),
+                }
+            ]
+        );
+
+        let transfer = mock_l2_transaction();
+        let transfer_hash = transfer.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(transfer, TransactionExecutionMetrics::default())
+            .await;
+        let assigned_count = conn
+            .fee_discounts_dal()
+            .assign_rebate_transfer(partner, MiniblockNumber(1), transfer_hash)
+            .await
+            .unwrap();
+        assert_eq!(assigned_count, 2);
+        assert!(conn
+            .fee_discounts_dal()
+            .has_pending_rebate_transfers()
+            .await
+            .unwrap());
+        let unpaid_rebates = conn
+            .fee_discounts_dal()
+            .get_unpaid_rebates(MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            unpaid_rebates,
+            [
+                UnpaidFeeRebate {
+                    account: partner,
+                    amount: 50_000_000.into(),
+                },
+                UnpaidFeeRebate {
+                    account: other_partner,
+                    amount: 100_000_000.into(),
+                }
+            ]
+        );
+
+        // Rebates become unpaid again if the transfer fails.
+        conn.transactions_dal()
+            .mark_tx_as_rejected(transfer_hash, "rejected: test")
+            .await;
+        assert!(!conn
+            .fee_discounts_dal()
+            .has_pending_rebate_transfers()
+            .await
+            .unwrap());
+        let unpaid_rebates = conn
+            .fee_discounts_dal()
+            .get_unpaid_rebates(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(unpaid_rebates.len(), 2);
+        assert_eq!(unpaid_rebates[0].amount, 75_000_000.into());
+    }
+}
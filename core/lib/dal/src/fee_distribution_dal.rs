@@ -0,0 +1,320 @@
+//! Auditable history of sweeps of collected fees from the fee account.
+
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use zksync_types::{Address, MiniblockNumber, H256, U256};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Destination receiving a share of the swept fees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeeDestination {
+    Treasury,
+    Sequencer,
+    Burn,
+}
+
+impl FeeDestination {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Treasury => "treasury",
+            Self::Sequencer => "sequencer",
+            Self::Burn => "burn",
+        }
+    }
+}
+
+impl FromStr for FeeDestination {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "treasury" => Self::Treasury,
+            "sequencer" => Self::Sequencer,
+            "burn" => Self::Burn,
+            _ => return Err("unknown fee destination"),
+        })
+    }
+}
+
+/// Transfer of a share of the swept fees to a destination.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewFeeTransfer {
+    pub destination: FeeDestination,
+    pub recipient: Address,
+    pub share_bps: u32,
+    pub amount: U256,
+    pub tx_hash: H256,
+}
+
+/// Status of a fee transfer transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeeTransferStatus {
+    /// Transaction is waiting in the mempool.
+    Pending,
+    /// Transaction was included in a miniblock and succeeded.
+    Succeeded(MiniblockNumber),
+    /// Transaction was rejected or reverted.
+    Failed(String),
+    /// Transaction is not present in the storage (e.g., it was purged from the mempool).
+    Dropped,
+}
+
+/// Fee transfer together with the status of its transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeTransfer {
+    pub transfer: NewFeeTransfer,
+    pub status: FeeTransferStatus,
+}
+
+/// Sweep of the collected fees, i.e., a set of transfers from the fee account submitted at the same time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeSweep {
+    pub id: i64,
+    pub fee_account: Address,
+    /// Miniblock at which the fee account balance was read.
+    pub miniblock_number: MiniblockNumber,
+    /// Balance of the fee account before the sweep.
+    pub balance: U256,
+    pub created_at: DateTime<Utc>,
+    pub transfers: Vec<FeeTransfer>,
+}
+
+#[derive(Debug)]
+pub struct FeeDistributionDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl FeeDistributionDal<'_, '_> {
+    /// Records a sweep with the specified transfers. Should be called in the same transaction as persisting
+    /// transfer transactions, so that the history contains all transfers sent from the fee account.
+    pub async fn insert_sweep(
+        &mut self,
+        fee_account: Address,
+        miniblock_number: MiniblockNumber,
+        balance: U256,
+        transfers: &[NewFeeTransfer],
+    ) -> sqlx::Result<i64> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                fee_distribution_sweeps (fee_account, miniblock_number, balance, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            RETURNING
+                id
+            "#,
+            fee_account.as_bytes(),
+            i64::from(miniblock_number.0),
+            u256_to_big_decimal(balance)
+        )
+        .instrument("insert_fee_sweep")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_one(&mut transaction)
+        .await?;
+
+        for transfer in transfers {
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    fee_distribution_transfers (
+                        sweep_id,
+                        destination,
+                        recipient,
+                        share_bps,
+                        amount,
+                        tx_hash
+                    )
+                VALUES
+                    ($1, $2, $3, $4, $5, $6)
+                "#,
+                row.id,
+                transfer.destination.as_str(),
+                transfer.recipient.as_bytes(),
+                transfer.share_bps as i32,
+                u256_to_big_decimal(transfer.amount),
+                transfer.tx_hash.as_bytes()
+            )
+            .instrument("insert_fee_sweep#transfer")
+            .with_arg("sweep_id", &row.id)
+            .with_arg("destination", &transfer.destination.as_str())
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+        Ok(row.id)
+    }
+
+    /// Returns the specified number of the latest sweeps, starting from the most recent one.
+    pub async fn get_latest_sweeps(&mut self, limit: usize) -> sqlx::Result<Vec<FeeSweep>> {
+        let sweep_rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                fee_account,
+                miniblock_number,
+                balance,
+                created_at
+            FROM
+                fee_distribution_sweeps
+            ORDER BY
+                id DESC
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_latest_fee_sweeps")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        let sweep_ids: Vec<_> = sweep_rows.iter().map(|row| row.id).collect();
+        let transfer_rows = sqlx::query!(
+            r#"
+            SELECT
+                fee_distribution_transfers.sweep_id,
+                fee_distribution_transfers.destination,
+                fee_distribution_transfers.recipient,
+                fee_distribution_transfers.share_bps,
+                fee_distribution_transfers.amount,
+                fee_distribution_transfers.tx_hash,
+                transactions.hash AS "tx_found?",
+                transactions.miniblock_number AS "tx_miniblock_number?",
+                transactions.error AS "tx_error?"
+            FROM
+                fee_distribution_transfers
+                LEFT JOIN transactions ON transactions.hash = fee_distribution_transfers.tx_hash
+            WHERE
+                fee_distribution_transfers.sweep_id = ANY ($1)
+            ORDER BY
+                fee_distribution_transfers.sweep_id,
+                fee_distribution_transfers.destination
+            "#,
+            &sweep_ids
+        )
+        .instrument("get_latest_fee_sweeps#transfers")
+        .with_arg("sweep_ids.len", &sweep_ids.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut transfers_by_sweep = HashMap::<_, Vec<_>>::new();
+        for row in transfer_rows {
+            let destination = row
+                .destination
+                .parse()
+                .map_err(|err: &str| sqlx::Error::Decode(err.into()))?;
+            let status = match (row.tx_found, row.tx_miniblock_number, row.tx_error) {
+                (None, _, _) => FeeTransferStatus::Dropped,
+                (Some(_), _, Some(error)) => FeeTransferStatus::Failed(error),
+                (Some(_), Some(number), None) => {
+                    FeeTransferStatus::Succeeded(MiniblockNumber(number as u32))
+                }
+                (Some(_), None, None) => FeeTransferStatus::Pending,
+            };
+            let transfer = NewFeeTransfer {
+                destination,
+                recipient: Address::from_slice(&row.recipient),
+                share_bps: row.share_bps as u32,
+                amount: bigdecimal_to_u256(row.amount),
+                tx_hash: H256::from_slice(&row.tx_hash),
+            };
+            transfers_by_sweep
+                .entry(row.sweep_id)
+                .or_default()
+                .push(FeeTransfer { transfer, status });
+        }
+
+        Ok(sweep_rows
+            .into_iter()
+            .map(|row| FeeSweep {
+                id: row.id,
+                fee_account: Address::from_slice(&row.fee_account),
+                miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+                balance: bigdecimal_to_u256(row.balance),
+                created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+                transfers: transfers_by_sweep.remove(&row.id).unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn recording_fee_sweeps() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        assert!(conn
+            .fee_distribution_dal()
+            .get_latest_sweeps(10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let fee_account = Address::repeat_byte(0xfe);
+        let transfers = [
+            NewFeeTransfer {
+                destination: FeeDestination::Treasury,
+                recipient: Address::repeat_byte(1),
+                share_bps: 7_500,
+                amount: 750.into(),
+                tx_hash: H256::repeat_byte(1),
+            },
+            NewFeeTransfer {
+                destination: FeeDestination::Burn,
+                recipient: Address::repeat_byte(2),
+                share_bps: 2_500,
+                amount: 250.into(),
+                tx_hash: H256::repeat_byte(2),
+            },
+        ];
+        let first_id = conn
+            .fee_distribution_dal()
+            .insert_sweep(fee_account, MiniblockNumber(5), 1_000.into(), &transfers)
+            .await
+            .unwrap();
+        let second_id = conn
+            .fee_distribution_dal()
+            .insert_sweep(fee_account, MiniblockNumber(8), 10.into(), &[])
+            .await
+            .unwrap();
+        assert!(second_id > first_id);
+
+        let sweeps = conn
+            .fee_distribution_dal()
+            .get_latest_sweeps(10)
+            .await
+            .unwrap();
+        let ids: Vec<_> = sweeps.iter().map(|sweep| sweep.id).collect();
+        assert_eq!(ids, [second_id, first_id]);
+        assert!(sweeps[0].transfers.is_empty());
+
+        let sweep = &sweeps[1];
+        assert_eq!(sweep.fee_account, fee_account);
+        assert_eq!(sweep.miniblock_number, MiniblockNumber(5));
+        assert_eq!(sweep.balance, 1_000.into());
+        // Transfers are ordered by the destination name.
+        assert_eq!(sweep.transfers.len(), 2);
+        assert_eq!(sweep.transfers[0].transfer, transfers[1]);
+        assert_eq!(sweep.transfers[1].transfer, transfers[0]);
+        // Transactions are not persisted.
+        for transfer in &sweep.transfers {
+            assert_eq!(transfer.status, FeeTransferStatus::Dropped);
+        }
+
+        let sweeps = conn
+            .fee_distribution_dal()
+            .get_latest_sweeps(1)
+            .await
+            .unwrap();
+        assert_eq!(sweeps.len(), 1);
+        assert_eq!(sweeps[0].id, second_id);
+    }
+}
@@ -2,21 +2,20 @@
 //!
 //! Query instrumentation allows to:
 //!
-//! - Report query latency as a metric
+//! - Report query latency and the number of returned / affected rows as metrics
 //! - Report slow and failing queries as metrics
-//! - Log slow and failing queries together with their arguments, which makes it easier to debug.
+//! - Log slow and failing queries together with their SQL and arguments, which makes it easier to debug.
 //!
 //! The entry point for instrumentation is the [`InstrumentExt`] trait. After it is imported into the scope,
 //! its `instrument()` method can be placed on the output of `query*` functions or macros. You can then call
-//! [`Instrumented`] methods on the returned struct, e.g. to [report query latency](Instrumented::report_latency())
-//! and/or [to add logged args](Instrumented::with_arg()) for a query.
+//! [`Instrumented`] methods on the returned struct, e.g. [to add logged args](Instrumented::with_arg()) for a query.
 
 use std::{fmt, future::Future, panic::Location};
 
 use sqlx::{
     postgres::{PgQueryResult, PgRow},
     query::{Map, Query, QueryAs},
-    FromRow, IntoArguments, Postgres,
+    Execute, FromRow, IntoArguments, Postgres,
 };
 use tokio::time::Instant;
 
@@ -50,6 +49,22 @@ impl fmt::Display for QueryArgs<'_> {
     }
 }
 
+/// SQL of a query with normalized whitespace, so that it's logged on a single line.
+#[derive(Debug, Clone, Copy)]
+struct QuerySql<'a>(&'a str);
+
+impl fmt::Display for QuerySql<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.0.split_whitespace().enumerate() {
+            if i > 0 {
+                formatter.write_str(" ")?;
+            }
+            formatter.write_str(word)?;
+        }
+        Ok(())
+    }
+}
+
 /// Extension trait for instrumenting `sqlx::query!` outputs.
 pub(crate) trait InstrumentExt: Sized {
     /// Instruments a query, assigning it the provided name.
@@ -102,7 +117,6 @@ struct InstrumentedData<'a> {
     name: &'static str,
     location: &'static Location<'static>,
     args: QueryArgs<'a>,
-    report_latency: bool,
 }
 
 impl<'a> InstrumentedData<'a> {
@@ -111,21 +125,22 @@ impl<'a> InstrumentedData<'a> {
             name,
             location,
             args: QueryArgs::default(),
-            report_latency: false,
         }
     }
 
     async fn fetch<R>(
         self,
         connection_tags: Option<&StorageProcessorTags>,
+        sql: &str,
         query_future: impl Future<Output = Result<R, sqlx::Error>>,
+        row_count: impl FnOnce(&R) -> u64,
     ) -> Result<R, sqlx::Error> {
         let Self {
             name,
             location,
             args,
-            report_latency,
         } = self;
+        let sql = QuerySql(sql);
         let started_at = Instant::now();
         tokio::pin!(query_future);
 
@@ -149,24 +164,30 @@ impl<'a> InstrumentedData<'a> {
         };
 
         let elapsed = started_at.elapsed();
-        if report_latency {
-            REQUEST_METRICS.request[&name].observe(elapsed);
-        }
+        REQUEST_METRICS.request[&name].observe(elapsed);
 
         let connection_tags = StorageProcessorTags::display(connection_tags);
-        if let Err(err) = &output {
-            tracing::warn!(
-                "Query {name}{args} called at {file}:{line} [{connection_tags}] has resulted in error: {err}",
-                file = location.file(),
-                line = location.line()
-            );
-            REQUEST_METRICS.request_error[&name].inc();
-        } else if is_slow {
-            tracing::info!(
-                "Slow query {name}{args} called at {file}:{line} [{connection_tags}] has finished after {elapsed:?}",
-                file = location.file(),
-                line = location.line()
-            );
+        match &output {
+            Err(err) => {
+                tracing::warn!(
+                    "Query {name}{args} called at {file}:{line} [{connection_tags}] has resulted in error: {err}; SQL: {sql}",
+                    file = location.file(),
+                    line = location.line()
+                );
+                REQUEST_METRICS.request_error[&name].inc();
+            }
+            Ok(output) => {
+                let row_count = row_count(output);
+                REQUEST_METRICS.request_rows[&name].observe(row_count);
+                if is_slow {
+                    tracing::info!(
+                        "Slow query {name}{args} called at {file}:{line} [{connection_tags}] has finished after {elapsed:?} \
+                         with {row_count} row(s); SQL: {sql}",
+                        file = location.file(),
+                        line = location.line()
+                    );
+                }
+            }
         }
         output
     }
@@ -177,11 +198,15 @@ impl<'a> InstrumentedData<'a> {
 ///
 /// The following instrumentation logic is included:
 ///
-/// - If the query executes for too long, it is logged with a `WARN` level. The logged info includes
-///   the query name, its args provided via [Self::with_arg()`] and the caller location.
+/// - Query latency and the number of returned (or, for statements, affected) rows are reported as metrics
+///   (`sql_request` and `sql_request_rows`, respectively).
+/// - If the query executes for longer than the slow query threshold (set via
+///   [`GlobalConnectionPoolConfig`](crate::connection::GlobalConnectionPoolConfig)), it is logged with a `WARN` level.
+///   The logged info includes the query name, its args provided via [Self::with_arg()`] and the caller location.
+///   Once the query finishes, its latency, row count and SQL are logged as well.
 /// - If the query returns an error, it is logged with a `WARN` level. The logged info is everything
 ///   included in the case of a slow query, plus the error info.
-/// - Slow and erroneous queries are also reported using metrics (`dal.request.slow` and `dal.request.error`,
+/// - Slow and erroneous queries are also reported using metrics (`sql_request_slow` and `sql_request_error`,
 ///   respectively). The query name is included as a metric label; args are not included for obvious reasons.
 #[derive(Debug)]
 pub(crate) struct Instrumented<'a, Q> {
@@ -190,12 +215,6 @@ pub(crate) struct Instrumented<'a, Q> {
 }
 
 impl<'a, Q> Instrumented<'a, Q> {
-    /// Indicates that latency should be reported for all calls.
-    pub fn report_latency(mut self) -> Self {
-        self.data.report_latency = true;
-        self
-    }
-
     /// Adds a traced query argument. The argument will be logged (using `Debug`) if the query executes too slow
     /// or finishes with an error.
     pub fn with_arg(mut self, name: &'static str, value: &'a ThreadSafeDebug) -> Self {
@@ -211,7 +230,11 @@ where
     /// Executes an SQL statement using this query.
     pub async fn execute(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<PgQueryResult> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.execute(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.execute(conn);
+        self.data
+            .fetch(tags, sql, query_future, PgQueryResult::rows_affected)
+            .await
     }
 
     /// Fetches an optional row using this query.
@@ -220,7 +243,21 @@ where
         storage: &mut StorageProcessor<'_>,
     ) -> Result<Option<PgRow>, sqlx::Error> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_optional(conn);
+        self.data
+            .fetch(tags, sql, query_future, |row| row.is_some().into())
+            .await
+    }
+
+    /// Fetches all rows using this query and collects them into a `Vec`.
+    pub async fn fetch_all(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<Vec<PgRow>> {
+        let (conn, tags) = storage.conn_and_tags();
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_all(conn);
+        self.data
+            .fetch(tags, sql, query_future, |rows| rows.len() as u64)
+            .await
     }
 }
 
@@ -232,7 +269,11 @@ where
     /// Fetches all rows using this query and collects them into a `Vec`.
     pub async fn fetch_all(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<Vec<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_all(conn);
+        self.data
+            .fetch(tags, sql, query_future, |rows| rows.len() as u64)
+            .await
     }
 }
 
@@ -248,19 +289,29 @@ where
         storage: &mut StorageProcessor<'_>,
     ) -> sqlx::Result<Option<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_optional(conn);
+        self.data
+            .fetch(tags, sql, query_future, |row| row.is_some().into())
+            .await
     }
 
     /// Fetches a single row using this query.
     pub async fn fetch_one(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<O> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_one(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_one(conn);
+        self.data.fetch(tags, sql, query_future, |_| 1).await
     }
 
     /// Fetches all rows using this query and collects them into a `Vec`.
     pub async fn fetch_all(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<Vec<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_all(conn);
+        self.data
+            .fetch(tags, sql, query_future, |rows| rows.len() as u64)
+            .await
     }
 }
 
@@ -271,6 +322,23 @@ mod tests {
     use super::*;
     use crate::ConnectionPool;
 
+    #[test]
+    fn formatting_query_sql() {
+        let sql = r#"
+            SELECT
+                number
+            FROM
+                miniblocks
+            WHERE
+                number = $1
+        "#;
+        assert_eq!(
+            QuerySql(sql).to_string(),
+            "SELECT number FROM miniblocks WHERE number = $1"
+        );
+        assert_eq!(QuerySql("").to_string(), "");
+    }
+
     #[tokio::test]
     async fn instrumenting_erroneous_query() {
         let pool = ConnectionPool::test_pool().await;
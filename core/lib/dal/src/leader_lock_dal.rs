@@ -0,0 +1,110 @@
+//! Session-level Postgres advisory lock used for leader election between sequencer instances.
+//!
+//! The lock is tied to the DB connection (session) it was acquired on and is released when the session ends.
+//! Hence, all methods must be called on the same dedicated, non-transactional connection.
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+pub struct LeaderLockDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl LeaderLockDal<'_, '_> {
+    /// Tries to acquire the lock without waiting. Returns `true` if the lock is acquired or is already held
+    /// by this session.
+    pub async fn try_acquire(&mut self, lock_id: i64) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                PG_TRY_ADVISORY_LOCK($1) AS "acquired!"
+            "#,
+            lock_id
+        )
+        .instrument("try_acquire_leader_lock")
+        .with_arg("lock_id", &lock_id)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.acquired)
+    }
+
+    /// Checks whether the lock is held by this session.
+    pub async fn is_held(&mut self, lock_id: i64) -> sqlx::Result<bool> {
+        // 64-bit advisory lock IDs are represented in `pg_locks` as two 32-bit halves.
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        pg_locks
+                    WHERE
+                        locktype = 'advisory'
+                        AND pid = PG_BACKEND_PID()
+                        AND GRANTED
+                        AND objsubid = 1
+                        AND classid = ((($1::BIGINT) >> 32) & 4294967295)::OID
+                        AND objid = (($1::BIGINT) & 4294967295)::OID
+                ) AS "held!"
+            "#,
+            lock_id
+        )
+        .instrument("is_leader_lock_held")
+        .with_arg("lock_id", &lock_id)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.held)
+    }
+
+    /// Releases the lock. Returns `false` if the lock wasn't held by this session.
+    pub async fn release(&mut self, lock_id: i64) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                PG_ADVISORY_UNLOCK($1) AS "released!"
+            "#,
+            lock_id
+        )
+        .instrument("release_leader_lock")
+        .with_arg("lock_id", &lock_id)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.released)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn acquiring_and_releasing_leader_lock() {
+        const LOCK_ID: i64 = -42;
+
+        let pool = ConnectionPool::constrained_test_pool(2).await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut other_conn = pool.access_storage().await.unwrap();
+        assert!(!conn.leader_lock_dal().is_held(LOCK_ID).await.unwrap());
+
+        assert!(conn.leader_lock_dal().try_acquire(LOCK_ID).await.unwrap());
+        assert!(conn.leader_lock_dal().is_held(LOCK_ID).await.unwrap());
+        assert!(!other_conn
+            .leader_lock_dal()
+            .try_acquire(LOCK_ID)
+            .await
+            .unwrap());
+        assert!(!other_conn.leader_lock_dal().is_held(LOCK_ID).await.unwrap());
+        assert!(!other_conn.leader_lock_dal().release(LOCK_ID).await.unwrap());
+
+        assert!(conn.leader_lock_dal().release(LOCK_ID).await.unwrap());
+        assert!(!conn.leader_lock_dal().is_held(LOCK_ID).await.unwrap());
+        assert!(other_conn
+            .leader_lock_dal()
+            .try_acquire(LOCK_ID)
+            .await
+            .unwrap());
+        assert!(other_conn.leader_lock_dal().is_held(LOCK_ID).await.unwrap());
+    }
+}
@@ -4,37 +4,56 @@ pub use sqlx::{types::BigDecimal, Error as SqlxError};
 
 pub use crate::connection::{ConnectionPool, StorageProcessor};
 use crate::{
-    basic_witness_input_producer_dal::BasicWitnessInputProducerDal, blocks_dal::BlocksDal,
-    blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
-    contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
-    events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
-    fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
+    audit_log_dal::AuditLogDal, basic_witness_input_producer_dal::BasicWitnessInputProducerDal,
+    batch_archive_dal::BatchArchiveDal, blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal,
+    bridge_deposits_dal::BridgeDepositsDal, cdc_publisher_dal::CdcPublisherDal,
+    chain_export_dal::ChainExportDal, consensus_dal::ConsensusDal,
+    content_hashes_dal::ContentHashesDal, contract_abis_dal::ContractAbisDal,
+    contract_verification_dal::ContractVerificationDal, data_availability_dal::DataAvailabilityDal,
+    eth_sender_dal::EthSenderDal, events_dal::EventsDal, events_web3_dal::EventsWeb3Dal,
+    factory_deps_dal::FactoryDepsDal, fee_discounts_dal::FeeDiscountsDal,
+    fee_distribution_dal::FeeDistributionDal, fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
     fri_proof_compressor_dal::FriProofCompressorDal,
     fri_protocol_versions_dal::FriProtocolVersionsDal, fri_prover_dal::FriProverDal,
     fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDal,
-    fri_witness_generator_dal::FriWitnessGeneratorDal, proof_generation_dal::ProofGenerationDal,
-    protocol_versions_dal::ProtocolVersionsDal,
-    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal,
-    snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
-    snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
-    storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
-    sync_dal::SyncDal, system_dal::SystemDal, tokens_dal::TokensDal,
-    tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
-    transactions_web3_dal::TransactionsWeb3Dal,
+    fri_witness_generator_dal::FriWitnessGeneratorDal, leader_lock_dal::LeaderLockDal,
+    partitions_dal::PartitionsDal, proof_generation_dal::ProofGenerationDal,
+    proof_verifications_dal::ProofVerificationsDal, protocol_versions_dal::ProtocolVersionsDal,
+    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, pruning_dal::PruningDal,
+    relayed_messages_dal::RelayedMessagesDal, scheduled_txs_dal::ScheduledTxsDal,
+    settlement_costs_dal::SettlementCostsDal, settlement_txs_dal::SettlementTxsDal,
+    snapshot_recovery_dal::SnapshotRecoveryDal,
+    snapshots_creator_dal::SnapshotsCreatorDal, snapshots_dal::SnapshotsDal,
+    storage_logs_dal::StorageLogsDal, storage_logs_dedup_dal::StorageLogsDedupDal,
+    storage_web3_dal::StorageWeb3Dal, sync_dal::SyncDal, system_dal::SystemDal,
+    token_registry_dal::TokenRegistryDal, tokens_dal::TokensDal, tokens_web3_dal::TokensWeb3Dal,
+    transactions_dal::TransactionsDal, transactions_web3_dal::TransactionsWeb3Dal,
+    tx_intake_dal::TxIntakeDal, webhooks_dal::WebhooksDal,
+    withdrawal_limits_dal::WithdrawalLimitsDal, withdrawals_dal::WithdrawalsDal,
 };
 
 #[macro_use]
 mod macro_utils;
+pub mod audit_log_dal;
 pub mod basic_witness_input_producer_dal;
+pub mod batch_archive_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
+pub mod bridge_deposits_dal;
+pub mod cdc_publisher_dal;
+pub mod chain_export_dal;
 pub mod connection;
 pub mod consensus_dal;
+pub mod content_hashes_dal;
+pub mod contract_abis_dal;
 pub mod contract_verification_dal;
+pub mod data_availability_dal;
 pub mod eth_sender_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod factory_deps_dal;
+pub mod fee_discounts_dal;
+pub mod fee_distribution_dal;
 pub mod fri_gpu_prover_queue_dal;
 pub mod fri_proof_compressor_dal;
 pub mod fri_protocol_versions_dal;
@@ -43,11 +62,19 @@ pub mod fri_scheduler_dependency_tracker_dal;
 pub mod fri_witness_generator_dal;
 pub mod healthcheck;
 mod instrument;
+pub mod leader_lock_dal;
 mod metrics;
 mod models;
+pub mod partitions_dal;
 pub mod proof_generation_dal;
+pub mod proof_verifications_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
+pub mod pruning_dal;
+pub mod relayed_messages_dal;
+pub mod scheduled_txs_dal;
+pub mod settlement_costs_dal;
+pub mod settlement_txs_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
@@ -58,10 +85,15 @@ pub mod storage_web3_dal;
 pub mod sync_dal;
 pub mod system_dal;
 pub mod time_utils;
+pub mod token_registry_dal;
 pub mod tokens_dal;
 pub mod tokens_web3_dal;
 pub mod transactions_dal;
 pub mod transactions_web3_dal;
+pub mod tx_intake_dal;
+pub mod webhooks_dal;
+pub mod withdrawal_limits_dal;
+pub mod withdrawals_dal;
 
 #[cfg(test)]
 mod tests;
@@ -87,10 +119,22 @@ impl<'a> StorageProcessor<'a> {
         BlocksWeb3Dal { storage: self }
     }
 
+    pub fn bridge_deposits_dal(&mut self) -> BridgeDepositsDal<'_, 'a> {
+        BridgeDepositsDal { storage: self }
+    }
+
     pub fn consensus_dal(&mut self) -> ConsensusDal<'_, 'a> {
         ConsensusDal { storage: self }
     }
 
+    pub fn content_hashes_dal(&mut self) -> ContentHashesDal<'_, 'a> {
+        ContentHashesDal { storage: self }
+    }
+
+    pub fn data_availability_dal(&mut self) -> DataAvailabilityDal<'_, 'a> {
+        DataAvailabilityDal { storage: self }
+    }
+
     pub fn eth_sender_dal(&mut self) -> EthSenderDal<'_, 'a> {
         EthSenderDal { storage: self }
     }
@@ -133,6 +177,14 @@ impl<'a> StorageProcessor<'a> {
         TokensWeb3Dal { storage: self }
     }
 
+    pub fn token_registry_dal(&mut self) -> TokenRegistryDal<'_, 'a> {
+        TokenRegistryDal { storage: self }
+    }
+
+    pub fn contract_abis_dal(&mut self) -> ContractAbisDal<'_, 'a> {
+        ContractAbisDal { storage: self }
+    }
+
     pub fn contract_verification_dal(&mut self) -> ContractVerificationDal<'_, 'a> {
         ContractVerificationDal { storage: self }
     }
@@ -167,6 +219,10 @@ impl<'a> StorageProcessor<'a> {
         ProofGenerationDal { storage: self }
     }
 
+    pub fn proof_verifications_dal(&mut self) -> ProofVerificationsDal<'_, 'a> {
+        ProofVerificationsDal { storage: self }
+    }
+
     pub fn fri_gpu_prover_queue_dal(&mut self) -> FriGpuProverQueueDal<'_, 'a> {
         FriGpuProverQueueDal { storage: self }
     }
@@ -194,4 +250,72 @@ impl<'a> StorageProcessor<'a> {
     pub fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a> {
         SnapshotRecoveryDal { storage: self }
     }
+
+    pub fn scheduled_txs_dal(&mut self) -> ScheduledTxsDal<'_, 'a> {
+        ScheduledTxsDal { storage: self }
+    }
+
+    pub fn fee_distribution_dal(&mut self) -> FeeDistributionDal<'_, 'a> {
+        FeeDistributionDal { storage: self }
+    }
+
+    pub fn fee_discounts_dal(&mut self) -> FeeDiscountsDal<'_, 'a> {
+        FeeDiscountsDal { storage: self }
+    }
+
+    pub fn settlement_costs_dal(&mut self) -> SettlementCostsDal<'_, 'a> {
+        SettlementCostsDal { storage: self }
+    }
+
+    pub fn pruning_dal(&mut self) -> PruningDal<'_, 'a> {
+        PruningDal { storage: self }
+    }
+
+    pub fn partitions_dal(&mut self) -> PartitionsDal<'_, 'a> {
+        PartitionsDal { storage: self }
+    }
+
+    pub fn chain_export_dal(&mut self) -> ChainExportDal<'_, 'a> {
+        ChainExportDal { storage: self }
+    }
+
+    pub fn batch_archive_dal(&mut self) -> BatchArchiveDal<'_, 'a> {
+        BatchArchiveDal { storage: self }
+    }
+
+    pub fn cdc_publisher_dal(&mut self) -> CdcPublisherDal<'_, 'a> {
+        CdcPublisherDal { storage: self }
+    }
+
+    pub fn settlement_txs_dal(&mut self) -> SettlementTxsDal<'_, 'a> {
+        SettlementTxsDal { storage: self }
+    }
+
+    pub fn withdrawals_dal(&mut self) -> WithdrawalsDal<'_, 'a> {
+        WithdrawalsDal { storage: self }
+    }
+
+    pub fn withdrawal_limits_dal(&mut self) -> WithdrawalLimitsDal<'_, 'a> {
+        WithdrawalLimitsDal { storage: self }
+    }
+
+    pub fn relayed_messages_dal(&mut self) -> RelayedMessagesDal<'_, 'a> {
+        RelayedMessagesDal { storage: self }
+    }
+
+    pub fn webhooks_dal(&mut self) -> WebhooksDal<'_, 'a> {
+        WebhooksDal { storage: self }
+    }
+
+    pub fn audit_log_dal(&mut self) -> AuditLogDal<'_, 'a> {
+        AuditLogDal { storage: self }
+    }
+
+    pub fn tx_intake_dal(&mut self) -> TxIntakeDal<'_, 'a> {
+        TxIntakeDal { storage: self }
+    }
+
+    pub fn leader_lock_dal(&mut self) -> LeaderLockDal<'_, 'a> {
+        LeaderLockDal { storage: self }
+    }
 }
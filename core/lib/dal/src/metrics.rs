@@ -3,10 +3,16 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics, Unit,
 };
 
+use crate::connection::WorkloadClass;
+
+const ROW_COUNT_BUCKETS: Buckets = Buckets::values(&[
+    0.0, 1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1_000.0, 5_000.0, 10_000.0, 50_000.0, 100_000.0,
+]);
+
 /// Request-related DB metrics.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "sql")]
@@ -14,6 +20,9 @@ pub(crate) struct RequestMetrics {
     /// Latency of a DB request.
     #[metrics(buckets = Buckets::LATENCIES, labels = ["method"])]
     pub request: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of rows returned by a DB request, or affected by it for statements.
+    #[metrics(buckets = ROW_COUNT_BUCKETS, labels = ["method"])]
+    pub request_rows: LabeledFamily<&'static str, Histogram<u64>>,
     /// Counter of slow DB requests.
     #[metrics(labels = ["method"])]
     pub request_slow: LabeledFamily<&'static str, Counter>,
@@ -26,7 +35,7 @@ pub(crate) struct RequestMetrics {
 pub(crate) static REQUEST_METRICS: vise::Global<RequestMetrics> = vise::Global::new();
 
 /// Reporter of latency for DAL methods consisting of multiple DB queries. If there's a single query,
-/// use `.instrument().report_latency()` on it instead.
+/// use `.instrument()` on it instead.
 ///
 /// Should be created at the start of the relevant method and dropped when the latency needs to be reported.
 #[derive(Debug)]
@@ -92,6 +101,11 @@ pub(crate) struct ConnectionMetrics {
     /// Lifetime of a DB connection, tagged with the requester label.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["requester"])]
     pub lifetime: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Time spent waiting for the connection quota of a workload class before acquiring a DB connection.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub quota_wait: Family<WorkloadClass, Histogram<Duration>>,
+    /// Number of DB connections currently used by a workload class with a connection quota.
+    pub quota_in_use: Family<WorkloadClass, Gauge<usize>>,
 }
 
 #[vise::register]
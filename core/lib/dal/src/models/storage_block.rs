@@ -286,6 +286,19 @@ pub fn web3_block_number_to_sql(block_number: api::BlockNumber) -> String {
             ) AS number)
         "
         .to_string(),
+        api::BlockNumber::Merkleized => "
+            (SELECT COALESCE(
+                (
+                    SELECT MAX(number) FROM miniblocks
+                    WHERE l1_batch_number = (
+                        SELECT MAX(number) FROM l1_batches
+                        WHERE hash IS NOT NULL
+                    )
+                ),
+                0
+            ) AS number)
+        "
+        .to_string(),
     }
 }
 
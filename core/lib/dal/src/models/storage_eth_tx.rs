@@ -1,9 +1,12 @@
 use std::str::FromStr;
 
-use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    eth_sender::{EthTx, TxHistory, TxHistoryToSend},
+    eth_sender::{
+        EthTx, EthTxBlobSidecar, GasEscalationAction, GasEscalationEntry, SettlementHalt,
+        TxHistory, TxHistoryToSend,
+    },
     Address, L1BatchNumber, Nonce, H256,
 };
 
@@ -22,6 +25,29 @@ pub struct StorageEthTx {
     pub updated_at: NaiveDateTime,
     // TODO (SMA-1614): remove the field
     pub sent_at_block: Option<i32>,
+    pub blob_sidecar: Option<Vec<u8>>,
+    pub from_addr: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct StorageGasEscalationEntry {
+    pub eth_tx_id: i32,
+    pub attempt: i32,
+    pub action: String,
+    pub skip_reason: Option<String>,
+    pub base_fee_per_gas: i64,
+    pub priority_fee_per_gas: i64,
+    pub blob_base_fee_per_gas: Option<i64>,
+    pub tx_hash: Option<String>,
+    pub l1_block_number: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct StorageSettlementHalt {
+    pub id: i64,
+    pub reason: String,
+    pub halted_at: NaiveDateTime,
+    pub resumed_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Default)]
@@ -54,6 +80,8 @@ pub struct StorageTxHistory {
     pub updated_at: NaiveDateTime,
     pub signed_raw_tx: Option<Vec<u8>>,
     pub sent_at_block: Option<i32>,
+    pub blob_base_fee_per_gas: Option<i64>,
+    pub superseded_at: Option<NaiveDateTime>,
 }
 
 impl From<StorageEthTx> for EthTx {
@@ -67,6 +95,11 @@ impl From<StorageEthTx> for EthTx {
             tx_type: AggregatedActionType::from_str(&tx.tx_type).expect("Wrong agg type"),
             created_at_timestamp: tx.created_at.timestamp() as u64,
             predicted_gas_cost: tx.predicted_gas_cost as u64,
+            from_addr: tx.from_addr.map(|addr| Address::from_slice(&addr)),
+            blob_sidecar: tx.blob_sidecar.map(|sidecar| {
+                bincode::deserialize::<EthTxBlobSidecar>(&sidecar)
+                    .expect("EthTxBlobSidecar is encoded correctly")
+            }),
         }
     }
 }
@@ -78,6 +111,7 @@ impl From<StorageTxHistory> for TxHistory {
             eth_tx_id: history.eth_tx_id as u32,
             base_fee_per_gas: history.base_fee_per_gas as u64,
             priority_fee_per_gas: history.priority_fee_per_gas as u64,
+            blob_base_fee_per_gas: history.blob_base_fee_per_gas.map(|fee| fee as u64),
             tx_hash: H256::from_str(&history.tx_hash).expect("Incorrect hash"),
             signed_raw_tx: history
                 .signed_raw_tx
@@ -103,3 +137,35 @@ impl From<StorageTxHistoryToSend> for TxHistoryToSend {
         }
     }
 }
+
+impl From<StorageGasEscalationEntry> for GasEscalationEntry {
+    fn from(entry: StorageGasEscalationEntry) -> GasEscalationEntry {
+        GasEscalationEntry {
+            eth_tx_id: entry.eth_tx_id as u32,
+            attempt: entry.attempt as u32,
+            action: GasEscalationAction::from_str(&entry.action)
+                .expect("Wrong gas escalation action"),
+            skip_reason: entry.skip_reason,
+            base_fee_per_gas: entry.base_fee_per_gas as u64,
+            priority_fee_per_gas: entry.priority_fee_per_gas as u64,
+            blob_base_fee_per_gas: entry.blob_base_fee_per_gas.map(|fee| fee as u64),
+            tx_hash: entry
+                .tx_hash
+                .map(|hash| H256::from_str(&hash).expect("Incorrect hash")),
+            l1_block_number: entry.l1_block_number as u32,
+        }
+    }
+}
+
+impl From<StorageSettlementHalt> for SettlementHalt {
+    fn from(halt: StorageSettlementHalt) -> SettlementHalt {
+        SettlementHalt {
+            id: halt.id as u32,
+            reason: halt.reason,
+            halted_at: DateTime::<Utc>::from_naive_utc_and_offset(halt.halted_at, Utc),
+            resumed_at: halt
+                .resumed_at
+                .map(|resumed_at| DateTime::<Utc>::from_naive_utc_and_offset(resumed_at, Utc)),
+        }
+    }
+}
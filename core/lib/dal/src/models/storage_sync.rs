@@ -23,6 +23,7 @@ pub(crate) struct StorageSyncBlock {
     pub protocol_version: i32,
     pub virtual_blocks: i64,
     pub hash: Vec<u8>,
+    pub content_hash: Option<Vec<u8>>,
 }
 
 fn parse_h256(bytes: &[u8]) -> anyhow::Result<H256> {
@@ -45,6 +46,7 @@ pub(crate) struct SyncBlock {
     pub fee_account_address: Address,
     pub virtual_blocks: u32,
     pub hash: H256,
+    pub content_hash: Option<H256>,
     pub protocol_version: ProtocolVersionId,
 }
 
@@ -89,6 +91,10 @@ impl TryFrom<StorageSyncBlock> for SyncBlock {
                 .context("fee_account_address")?,
             virtual_blocks: block.virtual_blocks.try_into().context("virtual_blocks")?,
             hash: parse_h256(&block.hash).context("hash")?,
+            content_hash: block
+                .content_hash
+                .map(|hash| parse_h256(&hash).context("content_hash"))
+                .transpose()?,
             protocol_version: u16::try_from(block.protocol_version)
                 .context("protocol_version")?
                 .try_into()
@@ -113,6 +119,9 @@ impl SyncBlock {
             virtual_blocks: Some(self.virtual_blocks),
             hash: Some(self.hash),
             protocol_version: self.protocol_version,
+            sequencer_signature: None,
+            content_hash: self.content_hash,
+            sequencer_header_signature: None,
         }
     }
 
@@ -70,6 +70,9 @@ pub struct StorageTransaction {
 
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+
+    pub mempool_at: Option<NaiveDateTime>,
+    pub executed_at: Option<NaiveDateTime>,
 }
 
 impl From<StorageTransaction> for L1TxCommonData {
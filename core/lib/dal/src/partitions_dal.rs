@@ -0,0 +1,444 @@
+//! Management of partitions for tables partitioned by miniblock number ranges.
+
+use std::ops;
+
+use vise::{EncodeLabelSet, EncodeLabelValue};
+use zksync_types::MiniblockNumber;
+
+use crate::{instrument::InstrumentExt, pruning_dal::PrunedRows, StorageProcessor};
+
+#[derive(Debug)]
+pub struct PartitionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Table that can be partitioned by miniblock number ranges. Tables are converted to partitioned ones by
+/// [`PartitionsDal::ensure_partitioned()`]. Besides range partitions, each partitioned table has a default partition
+/// (`<table>_default`) receiving rows for miniblocks not covered by range partitions.
+///
+/// `transactions` cannot be partitioned: Postgres requires unique constraints on a partitioned table to include
+/// the partitioning column, while transactions are deduplicated by hash and by initiator / nonce (e.g., in
+/// `ON CONFLICT` clauses), mempool transactions don't have a miniblock number, and other tables reference transactions
+/// by hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "table", rename_all = "snake_case")]
+pub enum PartitionedTable {
+    Events,
+    StorageLogs,
+}
+
+impl PartitionedTable {
+    pub const ALL: [Self; 2] = [Self::Events, Self::StorageLogs];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::StorageLogs => "storage_logs",
+        }
+    }
+
+    /// ID of the transaction-level advisory lock serializing partition management for the table.
+    fn lock_id(self) -> i64 {
+        const LOCK_ID_BASE: i64 = 0x7061_7274_0000; // "part" in ASCII
+        LOCK_ID_BASE
+            + match self {
+                Self::Events => 0,
+                Self::StorageLogs => 1,
+            }
+    }
+
+    fn default_partition(self) -> String {
+        format!("{}_default", self.name())
+    }
+
+    fn partition_name(self, first_miniblock: MiniblockNumber) -> String {
+        format!("{}_p{}", self.name(), first_miniblock.0)
+    }
+}
+
+/// Range partition of a [`PartitionedTable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TablePartition {
+    pub name: String,
+    /// Range of miniblocks held by the partition. The partition created when migrating a table to partitioning
+    /// (`<table>_legacy`) holds all miniblocks stored at that moment; its range starts from 0.
+    pub miniblocks: ops::Range<MiniblockNumber>,
+}
+
+impl TablePartition {
+    /// Checks whether this is the partition holding data stored before the table was partitioned.
+    pub fn is_legacy(&self) -> bool {
+        self.name.ends_with("_legacy")
+    }
+}
+
+impl PartitionsDal<'_, '_> {
+    /// Acquires the advisory lock serializing partition management for `table` until the end of the current
+    /// transaction.
+    async fn lock(&mut self, table: PartitionedTable) -> sqlx::Result<()> {
+        // Not using `query!` since the function returns `void`, which is not supported by `sqlx` macros.
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(table.lock_id())
+            .instrument("lock_partitions")
+            .with_arg("table", &table)
+            .execute(self.storage)
+            .await?;
+        Ok(())
+    }
+
+    /// Converts the specified table into a table partitioned by miniblock number ranges unless it's partitioned
+    /// already. Returns `true` if the table was converted. Existing data is retained in the `<table>_legacy` partition.
+    /// The conversion may take a long time for large tables.
+    pub async fn ensure_partitioned(&mut self, table: PartitionedTable) -> sqlx::Result<bool> {
+        let mut transaction = self.storage.start_transaction().await?;
+        transaction.partitions_dal().lock(table).await?;
+        let is_partitioned = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        pg_partitioned_table
+                    WHERE
+                        partrelid = $1::TEXT::REGCLASS
+                ) AS "is_partitioned!"
+            "#,
+            table.name()
+        )
+        .instrument("ensure_partitioned#check")
+        .with_arg("table", &table)
+        .fetch_one(&mut transaction)
+        .await?
+        .is_partitioned;
+        if is_partitioned {
+            return Ok(false);
+        }
+
+        sqlx::query("SELECT partition_by_miniblock_number($1)")
+            .bind(table.name())
+            .instrument("ensure_partitioned#convert")
+            .with_arg("table", &table)
+            .execute(&mut transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(true)
+    }
+
+    /// Returns range partitions of the specified table ordered by their miniblock ranges.
+    pub async fn get_partitions(
+        &mut self,
+        table: PartitionedTable,
+    ) -> sqlx::Result<Vec<TablePartition>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                partitions.name AS "name!",
+                (REGEXP_MATCH(partitions.bound, 'FROM \(''(\d+)''\)')) [1]::BIGINT AS "from_miniblock?",
+                (REGEXP_MATCH(partitions.bound, 'TO \(''(\d+)''\)')) [1]::BIGINT AS "to_miniblock!"
+            FROM
+                (
+                    SELECT
+                        child.relname::TEXT AS name,
+                        PG_GET_EXPR(child.relpartbound, child.oid) AS bound
+                    FROM
+                        pg_inherits
+                        JOIN pg_class parent ON parent.oid = pg_inherits.inhparent
+                        JOIN pg_class child ON child.oid = pg_inherits.inhrelid
+                    WHERE
+                        parent.relname::TEXT = $1
+                ) AS partitions
+            WHERE
+                partitions.bound != 'DEFAULT'
+            ORDER BY
+                "to_miniblock!"
+            "#,
+            table.name()
+        )
+        .instrument("get_partitions")
+        .with_arg("table", &table)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let first_miniblock = row.from_miniblock.unwrap_or(0);
+                TablePartition {
+                    name: row.name,
+                    miniblocks: MiniblockNumber(first_miniblock as u32)
+                        ..MiniblockNumber(row.to_miniblock as u32),
+                }
+            })
+            .collect())
+    }
+
+    /// Creates a range partition of the specified table for `miniblocks`; the range must not overlap with existing
+    /// range partitions. Rows for `miniblocks` stored in the default partition are moved to the created partition.
+    /// Returns the number of moved rows.
+    ///
+    /// Inserts into the default partition are blocked while the partition is created, so that rows inserted
+    /// concurrently cannot end up in the default partition after their range partition is created. To avoid blocking
+    /// writers, partitions should be created in advance for miniblocks that aren't being inserted yet.
+    pub async fn create_partition(
+        &mut self,
+        table: PartitionedTable,
+        miniblocks: ops::Range<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let table_name = table.name();
+        let default_partition = table.default_partition();
+        let partition_name = table.partition_name(miniblocks.start);
+        let first_miniblock = i64::from(miniblocks.start.0);
+        let next_miniblock = i64::from(miniblocks.end.0);
+
+        let mut transaction = self.storage.start_transaction().await?;
+        transaction.partitions_dal().lock(table).await?;
+        sqlx::query(&format!("LOCK TABLE {default_partition} IN EXCLUSIVE MODE"))
+            .instrument("create_partition#lock_default_partition")
+            .with_arg("table", &table)
+            .execute(&mut transaction)
+            .await?;
+        // Partitions cannot be created if the default partition contains rows for them, so such rows are moved
+        // to a temporary table first.
+        sqlx::query(&format!(
+            "CREATE TEMPORARY TABLE moved_rows (LIKE {table_name})"
+        ))
+        .instrument("create_partition#create_temp_table")
+        .with_arg("table", &table)
+        .execute(&mut transaction)
+        .await?;
+        let moved_rows = sqlx::query(&format!(
+            "WITH deleted AS ( \
+                DELETE FROM {default_partition} \
+                WHERE miniblock_number >= $1 AND miniblock_number < $2 \
+                RETURNING * \
+            ) \
+            INSERT INTO moved_rows SELECT * FROM deleted"
+        ))
+        .bind(first_miniblock)
+        .bind(next_miniblock)
+        .instrument("create_partition#move_from_default")
+        .with_arg("table", &table)
+        .with_arg("miniblocks", &miniblocks)
+        .execute(&mut transaction)
+        .await?
+        .rows_affected();
+
+        sqlx::query(&format!(
+            "CREATE TABLE {partition_name} PARTITION OF {table_name} \
+            FOR VALUES FROM ({first_miniblock}) TO ({next_miniblock})"
+        ))
+        .instrument("create_partition#create")
+        .with_arg("table", &table)
+        .with_arg("miniblocks", &miniblocks)
+        .execute(&mut transaction)
+        .await?;
+        if moved_rows > 0 {
+            sqlx::query(&format!(
+                "INSERT INTO {table_name} SELECT * FROM moved_rows"
+            ))
+            .instrument("create_partition#move_to_partition")
+            .with_arg("table", &table)
+            .with_arg("moved_rows", &moved_rows)
+            .execute(&mut transaction)
+            .await?;
+        }
+        sqlx::query("DROP TABLE moved_rows")
+            .instrument("create_partition#drop_temp_table")
+            .execute(&mut transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(moved_rows)
+    }
+
+    /// Drops the specified range partition together with all its data. Returns stats for the removed rows.
+    /// Should be called in a transaction, so that the advisory lock for partition management is held until the end of
+    /// the transaction.
+    pub async fn drop_partition(
+        &mut self,
+        table: PartitionedTable,
+        partition: &TablePartition,
+    ) -> sqlx::Result<PrunedRows> {
+        self.lock(table).await?;
+        let partition_name = &partition.name;
+        let (count, size_bytes) = sqlx::query_as::<_, (i64, i64)>(&format!(
+            "SELECT COUNT(*), COALESCE(SUM(PG_COLUMN_SIZE(p.*)), 0)::BIGINT FROM \"{partition_name}\" p"
+        ))
+        .instrument("drop_partition#stats")
+        .with_arg("table", &table)
+        .with_arg("partition", partition)
+        .fetch_one(self.storage)
+        .await?;
+
+        sqlx::query(&format!("DROP TABLE \"{partition_name}\""))
+            .instrument("drop_partition")
+            .with_arg("table", &table)
+            .with_arg("partition", partition)
+            .execute(self.storage)
+            .await?;
+        Ok(PrunedRows::new(count, size_bytes))
+    }
+
+    /// Drops the specified range partition of `storage_logs`, retaining the latest log for each storage slot among logs
+    /// for miniblocks up to and including `last_miniblock` (i.e., the logs that are retained when pruning logs
+    /// one by one). Retained logs are moved to the default partition. Returns stats for the removed rows.
+    /// Should be called in a transaction, similarly to [`Self::drop_partition()`].
+    pub async fn drop_storage_logs_partition(
+        &mut self,
+        partition: &TablePartition,
+        last_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<PrunedRows> {
+        let table = PartitionedTable::StorageLogs;
+        self.lock(table).await?;
+        let partition_name = &partition.name;
+
+        sqlx::query("CREATE TEMPORARY TABLE retained_storage_logs (LIKE storage_logs)")
+            .instrument("drop_storage_logs_partition#create_temp_table")
+            .execute(self.storage)
+            .await?;
+        sqlx::query(&format!(
+            "INSERT INTO retained_storage_logs \
+            SELECT p.* FROM \"{partition_name}\" p \
+            WHERE NOT EXISTS ( \
+                SELECT 1 FROM storage_logs newer \
+                WHERE newer.hashed_key = p.hashed_key \
+                    AND newer.miniblock_number <= $1 \
+                    AND (newer.miniblock_number, newer.operation_number) \
+                        > (p.miniblock_number, p.operation_number) \
+            )"
+        ))
+        .bind(i64::from(last_miniblock.0))
+        .instrument("drop_storage_logs_partition#retain")
+        .with_arg("partition", partition)
+        .with_arg("last_miniblock", &last_miniblock)
+        .execute(self.storage)
+        .await?;
+        let (retained_count, retained_size) = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT COUNT(*), COALESCE(SUM(PG_COLUMN_SIZE(r.*)), 0)::BIGINT FROM retained_storage_logs r",
+        )
+        .instrument("drop_storage_logs_partition#retained_stats")
+        .with_arg("partition", partition)
+        .fetch_one(self.storage)
+        .await?;
+
+        let mut pruned = self.drop_partition(table, partition).await?;
+        // The partition range is no longer covered by range partitions, so retained logs are routed
+        // to the default partition.
+        sqlx::query("INSERT INTO storage_logs SELECT * FROM retained_storage_logs")
+            .instrument("drop_storage_logs_partition#restore")
+            .with_arg("partition", partition)
+            .execute(self.storage)
+            .await?;
+        sqlx::query("DROP TABLE retained_storage_logs")
+            .instrument("drop_storage_logs_partition#drop_temp_table")
+            .execute(self.storage)
+            .await?;
+
+        let retained = PrunedRows::new(retained_count, retained_size);
+        pruned.count = pruned.count.saturating_sub(retained.count);
+        pruned.size_bytes = pruned.size_bytes.saturating_sub(retained.size_bytes);
+        Ok(pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{AccountTreeId, Address, ProtocolVersion, StorageKey, StorageLog, H256};
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    async fn insert_miniblock(conn: &mut StorageProcessor<'_>, number: u32) {
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await
+            .unwrap();
+        let key = StorageKey::new(
+            AccountTreeId::new(Address::repeat_byte(1)),
+            H256::from_low_u64_be(number.into()),
+        );
+        let log = StorageLog::new_write_log(key, H256::repeat_byte(1));
+        let tx_hash = H256::from_low_u64_be(number.into());
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &[(tx_hash, vec![log])])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn creating_and_dropping_partitions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let table = PartitionedTable::StorageLogs;
+        let converted = conn
+            .partitions_dal()
+            .ensure_partitioned(table)
+            .await
+            .unwrap();
+        assert!(converted);
+        let converted = conn
+            .partitions_dal()
+            .ensure_partitioned(table)
+            .await
+            .unwrap();
+        assert!(!converted);
+        for number in 0..5 {
+            insert_miniblock(&mut conn, number).await;
+        }
+
+        let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0].name, "storage_logs_legacy");
+        let legacy_partition_end = partitions[0].miniblocks.end;
+        assert!(legacy_partition_end <= MiniblockNumber(1));
+
+        // Logs for miniblocks 1..5 are stored in the default partition and must be moved.
+        let moved_rows = conn
+            .partitions_dal()
+            .create_partition(table, MiniblockNumber(1)..MiniblockNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(moved_rows, 2);
+        conn.partitions_dal()
+            .create_partition(table, MiniblockNumber(3)..MiniblockNumber(10))
+            .await
+            .unwrap();
+        let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+        let partition_ranges: Vec<_> = partitions
+            .iter()
+            .map(|partition| (partition.name.as_str(), partition.miniblocks.clone()))
+            .collect();
+        assert_eq!(
+            partition_ranges[1..],
+            [
+                ("storage_logs_p1", MiniblockNumber(1)..MiniblockNumber(3)),
+                ("storage_logs_p3", MiniblockNumber(3)..MiniblockNumber(10)),
+            ]
+        );
+        let logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        assert_eq!(logs.len(), 5);
+
+        let stats = conn
+            .partitions_dal()
+            .drop_partition(table, &partitions[1])
+            .await
+            .unwrap();
+        assert_eq!(stats.count, 2);
+        assert!(stats.size_bytes > 0);
+        let mut logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        logs.sort_unstable_by_key(|log| log.miniblock_number);
+        let miniblocks: Vec<_> = logs.iter().map(|log| log.miniblock_number.0).collect();
+        assert_eq!(miniblocks, [0, 3, 4]);
+        let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+        assert_eq!(partitions.len(), 2);
+    }
+}
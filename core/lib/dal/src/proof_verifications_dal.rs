@@ -0,0 +1,209 @@
+use chrono::{DateTime, Utc};
+use zksync_types::{
+    api::idexo::{L1BatchProofStatus, ProofVerificationStatus},
+    L1BatchNumber, H256,
+};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+fn status_to_str(status: ProofVerificationStatus) -> &'static str {
+    match status {
+        ProofVerificationStatus::Verified => "verified",
+        ProofVerificationStatus::MockProof => "mock_proof",
+        ProofVerificationStatus::Invalid => "invalid",
+    }
+}
+
+fn status_from_str(status: &str) -> ProofVerificationStatus {
+    match status {
+        "verified" => ProofVerificationStatus::Verified,
+        "mock_proof" => ProofVerificationStatus::MockProof,
+        "invalid" => ProofVerificationStatus::Invalid,
+        _ => panic!("invalid proof verification status in DB: {status}"),
+    }
+}
+
+/// Results of L1 batch proof verification performed by external nodes.
+#[derive(Debug)]
+pub struct ProofVerificationsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ProofVerificationsDal<'_, '_> {
+    /// Saves the verification result for an L1 batch, overwriting the previous result if any.
+    pub async fn save_verification(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        prove_tx_hash: H256,
+        status: ProofVerificationStatus,
+        details: Option<&str>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batch_proof_verifications (
+                    l1_batch_number,
+                    prove_tx_hash,
+                    status,
+                    details,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                prove_tx_hash = excluded.prove_tx_hash,
+                status = excluded.status,
+                details = excluded.details,
+                updated_at = NOW()
+            "#,
+            l1_batch_number.0 as i64,
+            prove_tx_hash.as_bytes(),
+            status_to_str(status),
+            details
+        )
+        .instrument("save_verification")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_verification(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<L1BatchProofStatus>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                prove_tx_hash,
+                status,
+                details,
+                updated_at
+            FROM
+                l1_batch_proof_verifications
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_verification")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| L1BatchProofStatus {
+            l1_batch_number,
+            prove_tx_hash: H256::from_slice(&row.prove_tx_hash),
+            status: status_from_str(&row.status),
+            details: row.details,
+            verified_at: DateTime::<Utc>::from_naive_utc_and_offset(row.updated_at, Utc),
+        }))
+    }
+
+    /// Returns the greatest L1 batch number with a verified proof (regardless of the verification outcome).
+    pub async fn get_last_verified_l1_batch(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "l1_batch_number"
+            FROM
+                l1_batch_proof_verifications
+            "#
+        )
+        .instrument("get_last_verified_l1_batch")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row
+            .l1_batch_number
+            .map(|number| L1BatchNumber(number as u32)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn saving_proof_verifications() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                Default::default(),
+                ProtocolVersionId::default(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+
+        let mut dal = conn.proof_verifications_dal();
+        assert_eq!(dal.get_last_verified_l1_batch().await.unwrap(), None);
+        let prove_tx_hash = H256::repeat_byte(1);
+        dal.save_verification(
+            L1BatchNumber(1),
+            prove_tx_hash,
+            ProofVerificationStatus::Verified,
+            None,
+        )
+        .await
+        .unwrap();
+        dal.save_verification(
+            L1BatchNumber(2),
+            prove_tx_hash,
+            ProofVerificationStatus::Invalid,
+            Some("wrong commitment"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            dal.get_last_verified_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(2))
+        );
+        let status = dal
+            .get_verification(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.prove_tx_hash, prove_tx_hash);
+        assert_eq!(status.status, ProofVerificationStatus::Verified);
+        assert_eq!(status.details, None);
+
+        // Verification results are overwritten.
+        dal.save_verification(
+            L1BatchNumber(2),
+            H256::repeat_byte(2),
+            ProofVerificationStatus::MockProof,
+            None,
+        )
+        .await
+        .unwrap();
+        let status = dal
+            .get_verification(L1BatchNumber(2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.prove_tx_hash, H256::repeat_byte(2));
+        assert_eq!(status.status, ProofVerificationStatus::MockProof);
+        assert_eq!(status.details, None);
+        assert!(dal
+            .get_verification(L1BatchNumber(3))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,947 @@
+//! Pruning of old data from Postgres.
+
+use std::ops;
+
+use zksync_types::{
+    fee_model::{BatchFeeInput, L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput},
+    L1BatchNumber, MiniblockNumber, ProtocolVersionId,
+};
+
+use crate::{instrument::InstrumentExt, partitions_dal::PartitionedTable, StorageProcessor};
+
+#[derive(Debug)]
+pub struct PruningDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Information about data pruned from Postgres.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningInfo {
+    /// Last L1 batch with pruned data; `None` if no data was pruned.
+    pub last_pruned_l1_batch: Option<L1BatchNumber>,
+    /// Last miniblock with pruned data; `None` if no data was pruned.
+    pub last_pruned_miniblock: Option<MiniblockNumber>,
+}
+
+/// Storage logs removed by [`PruningDal::prune_l1_batches()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageLogsPruning {
+    /// Removes storage logs overwritten by later storage logs, so that only the latest state is retained.
+    #[default]
+    Overwritten,
+    /// Removes storage logs overwritten within the same miniblock, so that the state after each pruned miniblock
+    /// remains available (e.g., to execute calls for pruned miniblocks).
+    WithinMiniblocks,
+}
+
+/// Number and total size of rows removed from a single table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrunedRows {
+    pub count: u64,
+    /// Total size of removed rows in bytes, as reported by `pg_column_size()`. This space becomes reusable
+    /// by Postgres after the relevant tables are vacuumed.
+    pub size_bytes: u64,
+}
+
+impl PrunedRows {
+    pub(crate) fn new(count: i64, size_bytes: i64) -> Self {
+        Self {
+            count: count as u64,
+            size_bytes: size_bytes as u64,
+        }
+    }
+}
+
+impl ops::AddAssign for PrunedRows {
+    fn add_assign(&mut self, rhs: Self) {
+        self.count += rhs.count;
+        self.size_bytes += rhs.size_bytes;
+    }
+}
+
+/// Statistics for a single pruning operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningStats {
+    pub transactions: PrunedRows,
+    pub call_traces: PrunedRows,
+    pub events: PrunedRows,
+    pub l2_to_l1_logs: PrunedRows,
+    /// Only storage logs overwritten by later logs are removed.
+    pub storage_logs: PrunedRows,
+    pub miniblocks: PrunedRows,
+}
+
+impl PruningStats {
+    pub fn total(&self) -> PrunedRows {
+        let mut total = self.transactions;
+        total += self.call_traces;
+        total += self.events;
+        total += self.l2_to_l1_logs;
+        total += self.storage_logs;
+        total += self.miniblocks;
+        total
+    }
+}
+
+impl PruningDal<'_, '_> {
+    pub async fn get_pruning_info(&mut self) -> sqlx::Result<PruningInfo> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                pruned_l1_batch,
+                pruned_miniblock
+            FROM
+                pruning_log
+            ORDER BY
+                pruned_l1_batch DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_pruning_info")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map_or_else(PruningInfo::default, |row| PruningInfo {
+            last_pruned_l1_batch: Some(L1BatchNumber(row.pruned_l1_batch as u32)),
+            last_pruned_miniblock: Some(MiniblockNumber(row.pruned_miniblock as u32)),
+        }))
+    }
+
+    /// Returns the last L1 batch that can be pruned. An L1 batch can be pruned if it was sealed before `max_timestamp`,
+    /// is executed on L1, and has its state hash and commitment computed locally. Data for the last sealed L1 batch
+    /// is never pruned since it is required to process subsequent miniblocks.
+    pub async fn get_last_prunable_l1_batch(
+        &mut self,
+        max_timestamp: u64,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(number) AS "number"
+            FROM
+                l1_batches
+            WHERE
+                eth_execute_tx_id IS NOT NULL
+                AND hash IS NOT NULL
+                AND commitment IS NOT NULL
+                AND timestamp <= $1
+                AND number < (
+                    SELECT
+                        MAX(number)
+                    FROM
+                        l1_batches
+                )
+            "#,
+            max_timestamp as i64
+        )
+        .instrument("get_last_prunable_l1_batch")
+        .with_arg("max_timestamp", &max_timestamp)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Returns whether the state after each miniblock in the specified pruned L1 batch is retained, i.e., the batch
+    /// and all L1 batches pruned after it were pruned with [`StorageLogsPruning::WithinMiniblocks`].
+    pub async fn is_miniblock_state_retained(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                BOOL_AND(miniblock_state_retained) AS "retained"
+            FROM
+                pruning_log
+            WHERE
+                pruned_l1_batch >= $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("is_miniblock_state_retained")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.retained.unwrap_or(false))
+    }
+
+    /// Returns the fee input of a pruned L1 batch, which is recorded from its miniblock headers before they are pruned.
+    /// Returns `None` if the batch is not pruned, or was pruned before fee inputs were recorded.
+    pub async fn get_pruned_l1_batch_fee_input(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<BatchFeeInput>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_gas_price,
+                l2_fair_gas_price,
+                fair_pubdata_price,
+                protocol_version
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+                AND l2_fair_gas_price > 0
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_pruned_l1_batch_fee_input")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| {
+            let protocol_version = row
+                .protocol_version
+                .map(|version| (version as u16).try_into().unwrap());
+            let is_post_1_4_1 =
+                protocol_version.is_some_and(|version: ProtocolVersionId| version.is_post_1_4_1());
+            match row.fair_pubdata_price {
+                Some(fair_pubdata_price) if is_post_1_4_1 => {
+                    BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+                        fair_l2_gas_price: row.l2_fair_gas_price as u64,
+                        fair_pubdata_price: fair_pubdata_price as u64,
+                        l1_gas_price: row.l1_gas_price as u64,
+                    })
+                }
+                _ => BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
+                    fair_l2_gas_price: row.l2_fair_gas_price as u64,
+                    l1_gas_price: row.l1_gas_price as u64,
+                }),
+            }
+        }))
+    }
+
+    /// Removes data for the specified miniblocks that is not required for the node operation: transactions,
+    /// call traces, events, L2-to-L1 logs, storage logs overwritten by later storage logs (or, depending on
+    /// `storage_logs_pruning`, only ones overwritten within the same miniblock) and miniblock headers.
+    /// L1 batch headers, initial writes and factory dependencies are retained, as well as the latest storage log
+    /// for each storage slot, so that the node state and L1 batch data (e.g., commitments) remain available.
+    /// Fee inputs of the pruned L1 batches are recorded in L1 batch headers before miniblock headers are removed.
+    ///
+    /// Event partitions holding only pruned miniblocks are dropped as a whole. Storage log partitions are not dropped
+    /// since they may contain the latest logs for storage slots.
+    ///
+    /// The miniblocks must be the first non-pruned miniblocks; `last_l1_batch` must be the last L1 batch among them.
+    /// This method should be called in a transaction; if the transaction is rolled back, the returned stats
+    /// can be used to estimate what would be pruned.
+    pub async fn prune_l1_batches(
+        &mut self,
+        last_l1_batch: L1BatchNumber,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        storage_logs_pruning: StorageLogsPruning,
+    ) -> sqlx::Result<PruningStats> {
+        let first_miniblock = i64::from(miniblocks.start().0);
+        let last_miniblock = i64::from(miniblocks.end().0);
+
+        let call_traces = sqlx::query!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM call_traces USING transactions
+                    WHERE
+                        call_traces.tx_hash = transactions.hash
+                        AND transactions.miniblock_number BETWEEN $1 AND $2
+                    RETURNING
+                        PG_COLUMN_SIZE(call_traces.*) AS row_size
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(row_size), 0) AS "size!"
+            FROM
+                deleted
+            "#,
+            first_miniblock,
+            last_miniblock
+        )
+        .instrument("prune_l1_batches#call_traces")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+        let call_traces = PrunedRows::new(call_traces.count, call_traces.size);
+
+        let mut events = self
+            .drop_partitions(PartitionedTable::Events, *miniblocks.end())
+            .await?;
+        let deleted_events = sqlx::query!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM events
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                    RETURNING
+                        PG_COLUMN_SIZE(events.*) AS row_size
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(row_size), 0) AS "size!"
+            FROM
+                deleted
+            "#,
+            first_miniblock,
+            last_miniblock
+        )
+        .instrument("prune_l1_batches#events")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+        events += PrunedRows::new(deleted_events.count, deleted_events.size);
+
+        let l2_to_l1_logs = sqlx::query!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM l2_to_l1_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                    RETURNING
+                        PG_COLUMN_SIZE(l2_to_l1_logs.*) AS row_size
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(row_size), 0) AS "size!"
+            FROM
+                deleted
+            "#,
+            first_miniblock,
+            last_miniblock
+        )
+        .instrument("prune_l1_batches#l2_to_l1_logs")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+        let l2_to_l1_logs = PrunedRows::new(l2_to_l1_logs.count, l2_to_l1_logs.size);
+
+        let transactions = sqlx::query!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM transactions
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                    RETURNING
+                        PG_COLUMN_SIZE(transactions.*) AS row_size
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(row_size), 0) AS "size!"
+            FROM
+                deleted
+            "#,
+            first_miniblock,
+            last_miniblock
+        )
+        .instrument("prune_l1_batches#transactions")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+        let transactions = PrunedRows::new(transactions.count, transactions.size);
+
+        let storage_logs = match storage_logs_pruning {
+            StorageLogsPruning::Overwritten => {
+                let mut storage_logs = self
+                    .drop_partitions(PartitionedTable::StorageLogs, *miniblocks.end())
+                    .await?;
+                storage_logs += self.prune_storage_logs(&miniblocks).await?;
+                storage_logs += self.prune_storage_logs_in_range(&miniblocks).await?;
+                storage_logs
+            }
+            StorageLogsPruning::WithinMiniblocks => {
+                self.prune_storage_logs_within_miniblocks(&miniblocks)
+                    .await?
+            }
+        };
+
+        // All miniblocks in an L1 batch share the batch fee input.
+        sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                l1_gas_price = miniblocks.l1_gas_price,
+                l2_fair_gas_price = miniblocks.l2_fair_gas_price,
+                fair_pubdata_price = miniblocks.fair_pubdata_price
+            FROM
+                miniblocks
+            WHERE
+                miniblocks.l1_batch_number = l1_batches.number
+                AND miniblocks.number BETWEEN $1 AND $2
+            "#,
+            first_miniblock,
+            last_miniblock
+        )
+        .instrument("prune_l1_batches#record_fee_inputs")
+        .with_arg("miniblocks", &miniblocks)
+        .execute(self.storage)
+        .await?;
+
+        let pruned_miniblocks = sqlx::query!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM miniblocks
+                    WHERE
+                        number BETWEEN $1 AND $2
+                    RETURNING
+                        PG_COLUMN_SIZE(miniblocks.*) AS row_size
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(row_size), 0) AS "size!"
+            FROM
+                deleted
+            "#,
+            first_miniblock,
+            last_miniblock
+        )
+        .instrument("prune_l1_batches#miniblocks")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+        let pruned_miniblocks = PrunedRows::new(pruned_miniblocks.count, pruned_miniblocks.size);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_log (
+                    pruned_l1_batch,
+                    pruned_miniblock,
+                    miniblock_state_retained,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            "#,
+            i64::from(last_l1_batch.0),
+            last_miniblock,
+            storage_logs_pruning == StorageLogsPruning::WithinMiniblocks
+        )
+        .instrument("prune_l1_batches#insert_pruning_log")
+        .with_arg("last_l1_batch", &last_l1_batch)
+        .execute(self.storage)
+        .await?;
+
+        Ok(PruningStats {
+            transactions,
+            call_traces,
+            events,
+            l2_to_l1_logs,
+            storage_logs,
+            miniblocks: pruned_miniblocks,
+        })
+    }
+
+    /// Drops range partitions of `table` holding only miniblocks up to and including `last_miniblock`.
+    /// For storage logs, the latest log for each storage slot is retained, and the legacy partition is never dropped
+    /// since it holds most of the state; logs in it are pruned one by one.
+    async fn drop_partitions(
+        &mut self,
+        table: PartitionedTable,
+        last_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<PrunedRows> {
+        let partitions = self.storage.partitions_dal().get_partitions(table).await?;
+        let mut pruned = PrunedRows::default();
+        for partition in &partitions {
+            if partition.miniblocks.end > last_miniblock + 1 {
+                continue;
+            }
+            pruned += match table {
+                PartitionedTable::Events => {
+                    self.storage
+                        .partitions_dal()
+                        .drop_partition(table, partition)
+                        .await?
+                }
+                PartitionedTable::StorageLogs if partition.is_legacy() => continue,
+                PartitionedTable::StorageLogs => {
+                    self.storage
+                        .partitions_dal()
+                        .drop_storage_logs_partition(partition, last_miniblock)
+                        .await?
+                }
+            };
+        }
+        Ok(pruned)
+    }
+
+    /// Removes storage logs preceding `miniblocks` for storage slots overwritten in `miniblocks`.
+    async fn prune_storage_logs(
+        &mut self,
+        miniblocks: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<PrunedRows> {
+        let row = sqlx::query!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM storage_logs USING (
+                        SELECT DISTINCT
+                            hashed_key
+                        FROM
+                            storage_logs
+                        WHERE
+                            miniblock_number BETWEEN $1 AND $2
+                    ) AS overwritten_keys
+                    WHERE
+                        storage_logs.hashed_key = overwritten_keys.hashed_key
+                        AND storage_logs.miniblock_number < $1
+                    RETURNING
+                        PG_COLUMN_SIZE(storage_logs.*) AS row_size
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(row_size), 0) AS "size!"
+            FROM
+                deleted
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
+        )
+        .instrument("prune_storage_logs")
+        .with_arg("miniblocks", miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(PrunedRows::new(row.count, row.size))
+    }
+
+    /// Removes storage logs in `miniblocks` except for the last log for each storage slot.
+    async fn prune_storage_logs_in_range(
+        &mut self,
+        miniblocks: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<PrunedRows> {
+        let row = sqlx::query!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM storage_logs USING (
+                        SELECT
+                            hashed_key,
+                            MAX(ARRAY[miniblock_number, operation_number]::INT[]) AS op
+                        FROM
+                            storage_logs
+                        WHERE
+                            miniblock_number BETWEEN $1 AND $2
+                        GROUP BY
+                            hashed_key
+                    ) AS last_storage_logs
+                    WHERE
+                        storage_logs.miniblock_number BETWEEN $1 AND $2
+                        AND last_storage_logs.hashed_key = storage_logs.hashed_key
+                        AND (
+                            storage_logs.miniblock_number != last_storage_logs.op[1]
+                            OR storage_logs.operation_number != last_storage_logs.op[2]
+                        )
+                    RETURNING
+                        PG_COLUMN_SIZE(storage_logs.*) AS row_size
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(row_size), 0) AS "size!"
+            FROM
+                deleted
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
+        )
+        .instrument("prune_storage_logs_in_range")
+        .with_arg("miniblocks", miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(PrunedRows::new(row.count, row.size))
+    }
+
+    /// Removes storage logs in `miniblocks` except for the last log for each storage slot in each miniblock.
+    async fn prune_storage_logs_within_miniblocks(
+        &mut self,
+        miniblocks: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<PrunedRows> {
+        let row = sqlx::query!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM storage_logs USING (
+                        SELECT
+                            hashed_key,
+                            miniblock_number,
+                            MAX(operation_number) AS operation_number
+                        FROM
+                            storage_logs
+                        WHERE
+                            miniblock_number BETWEEN $1 AND $2
+                        GROUP BY
+                            hashed_key,
+                            miniblock_number
+                    ) AS last_storage_logs
+                    WHERE
+                        storage_logs.miniblock_number BETWEEN $1 AND $2
+                        AND last_storage_logs.hashed_key = storage_logs.hashed_key
+                        AND last_storage_logs.miniblock_number = storage_logs.miniblock_number
+                        AND last_storage_logs.operation_number != storage_logs.operation_number
+                    RETURNING
+                        PG_COLUMN_SIZE(storage_logs.*) AS row_size
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(row_size), 0) AS "size!"
+            FROM
+                deleted
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
+        )
+        .instrument("prune_storage_logs_within_miniblocks")
+        .with_arg("miniblocks", miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(PrunedRows::new(row.count, row.size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::L1BatchHeader, tx::IncludedTxLocation, AccountTreeId, Address, ProtocolVersion,
+        StorageKey, StorageLog, VmEvent, H256,
+    };
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    fn mock_storage_log(key: u8, value: u8) -> StorageLog {
+        let key = StorageKey::new(
+            AccountTreeId::new(Address::repeat_byte(1)),
+            H256::repeat_byte(key),
+        );
+        StorageLog::new_write_log(key, H256::repeat_byte(value))
+    }
+
+    async fn insert_miniblock(conn: &mut StorageProcessor<'_>, number: u32, logs: Vec<StorageLog>) {
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await
+            .unwrap();
+        let tx_hash = H256::from_low_u64_be(number.into());
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &[(tx_hash, logs)])
+            .await
+            .unwrap();
+    }
+
+    async fn prune(
+        conn: &mut StorageProcessor<'_>,
+        last_l1_batch: u32,
+        miniblocks: ops::RangeInclusive<u32>,
+    ) -> PruningStats {
+        prune_with(
+            conn,
+            last_l1_batch,
+            miniblocks,
+            StorageLogsPruning::Overwritten,
+        )
+        .await
+    }
+
+    async fn prune_with(
+        conn: &mut StorageProcessor<'_>,
+        last_l1_batch: u32,
+        miniblocks: ops::RangeInclusive<u32>,
+        storage_logs_pruning: StorageLogsPruning,
+    ) -> PruningStats {
+        let mut transaction = conn.start_transaction().await.unwrap();
+        let miniblocks = MiniblockNumber(*miniblocks.start())..=MiniblockNumber(*miniblocks.end());
+        let stats = transaction
+            .pruning_dal()
+            .prune_l1_batches(
+                L1BatchNumber(last_l1_batch),
+                miniblocks,
+                storage_logs_pruning,
+            )
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+        stats
+    }
+
+    #[tokio::test]
+    async fn pruning_storage_logs_and_miniblocks() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info, PruningInfo::default());
+
+        insert_miniblock(
+            &mut conn,
+            1,
+            vec![mock_storage_log(1, 1), mock_storage_log(2, 1)],
+        )
+        .await;
+        insert_miniblock(&mut conn, 2, vec![mock_storage_log(1, 2)]).await;
+        insert_miniblock(
+            &mut conn,
+            3,
+            vec![mock_storage_log(1, 3), mock_storage_log(3, 3)],
+        )
+        .await;
+
+        let stats = prune(&mut conn, 1, 1..=2).await;
+        assert_eq!(stats.miniblocks.count, 2);
+        assert_eq!(stats.storage_logs.count, 1);
+        assert!(stats.storage_logs.size_bytes > 0);
+        assert_eq!(stats.total().count, 3);
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info.last_pruned_l1_batch, Some(L1BatchNumber(1)));
+        assert_eq!(info.last_pruned_miniblock, Some(MiniblockNumber(2)));
+
+        let mut logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        logs.sort_unstable_by_key(|log| (log.miniblock_number, log.key));
+        let logs: Vec<_> = logs
+            .iter()
+            .map(|log| (log.miniblock_number.0, log.key, log.value))
+            .collect();
+        assert_eq!(
+            logs,
+            [
+                (1, H256::repeat_byte(2), H256::repeat_byte(1)),
+                (2, H256::repeat_byte(1), H256::repeat_byte(2)),
+                (3, H256::repeat_byte(1), H256::repeat_byte(3)),
+                (3, H256::repeat_byte(3), H256::repeat_byte(3)),
+            ]
+        );
+        let remaining_miniblock = conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert!(remaining_miniblock.is_none());
+
+        // Key #1 is overwritten in miniblock #3; the log from miniblock #2 must be pruned.
+        let stats = prune(&mut conn, 2, 3..=3).await;
+        assert_eq!(stats.miniblocks.count, 1);
+        assert_eq!(stats.storage_logs.count, 1);
+        let logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        assert_eq!(logs.len(), 3);
+        assert!(logs
+            .iter()
+            .all(|log| log.miniblock_number != MiniblockNumber(2)));
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info.last_pruned_l1_batch, Some(L1BatchNumber(2)));
+        assert_eq!(info.last_pruned_miniblock, Some(MiniblockNumber(3)));
+    }
+
+    #[tokio::test]
+    async fn pruning_drops_partitions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for table in PartitionedTable::ALL {
+            let converted = conn
+                .partitions_dal()
+                .ensure_partitioned(table)
+                .await
+                .unwrap();
+            assert!(converted);
+            conn.partitions_dal()
+                .create_partition(table, MiniblockNumber(1)..MiniblockNumber(3))
+                .await
+                .unwrap();
+            conn.partitions_dal()
+                .create_partition(table, MiniblockNumber(3)..MiniblockNumber(5))
+                .await
+                .unwrap();
+        }
+
+        for number in 1..5 {
+            let mut logs = vec![mock_storage_log(1, number as u8)];
+            if number == 1 {
+                // This log is the latest one for its storage slot, so it must be retained.
+                logs.push(mock_storage_log(2, 1));
+            }
+            insert_miniblock(&mut conn, number, logs).await;
+            let location = IncludedTxLocation {
+                tx_hash: H256::from_low_u64_be(number.into()),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::default(),
+            };
+            let event = VmEvent {
+                location: (L1BatchNumber(1), 0),
+                address: Address::repeat_byte(1),
+                indexed_topics: vec![],
+                value: vec![],
+            };
+            conn.events_dal()
+                .save_events(MiniblockNumber(number), &[(location, vec![&event])])
+                .await;
+        }
+
+        let stats = prune(&mut conn, 1, 1..=3).await;
+        assert_eq!(stats.events.count, 3);
+        assert_eq!(stats.storage_logs.count, 2);
+        let event_partitions = conn
+            .partitions_dal()
+            .get_partitions(PartitionedTable::Events)
+            .await
+            .unwrap();
+        let event_partitions: Vec<_> = event_partitions
+            .iter()
+            .map(|partition| partition.name.as_str())
+            .collect();
+        // The legacy partition is empty, so it's dropped as well.
+        assert_eq!(event_partitions, ["events_p3"]);
+        let storage_log_partitions = conn
+            .partitions_dal()
+            .get_partitions(PartitionedTable::StorageLogs)
+            .await
+            .unwrap();
+        let storage_log_partitions: Vec<_> = storage_log_partitions
+            .iter()
+            .map(|partition| partition.name.as_str())
+            .collect();
+        // The legacy partition is never dropped for storage logs.
+        assert_eq!(
+            storage_log_partitions,
+            ["storage_logs_legacy", "storage_logs_p3"]
+        );
+
+        let mut logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        logs.sort_unstable_by_key(|log| (log.miniblock_number, log.hashed_key));
+        let logs: Vec<_> = logs
+            .iter()
+            .map(|log| (log.miniblock_number.0, log.value))
+            .collect();
+        assert_eq!(
+            logs,
+            [
+                (1, H256::repeat_byte(1)),
+                (3, H256::repeat_byte(3)),
+                (4, H256::repeat_byte(4)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pruning_storage_logs_within_miniblocks() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        insert_miniblock(
+            &mut conn,
+            1,
+            vec![
+                mock_storage_log(1, 1),
+                mock_storage_log(2, 1),
+                mock_storage_log(1, 2),
+            ],
+        )
+        .await;
+        insert_miniblock(&mut conn, 2, vec![mock_storage_log(1, 3)]).await;
+        insert_miniblock(&mut conn, 3, vec![mock_storage_log(1, 4)]).await;
+
+        let stats = prune_with(&mut conn, 1, 1..=2, StorageLogsPruning::WithinMiniblocks).await;
+        // Only the first log for key #1 in miniblock #1 is overwritten within its miniblock.
+        assert_eq!(stats.storage_logs.count, 1);
+        let mut logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        logs.sort_unstable_by_key(|log| (log.miniblock_number, log.key));
+        let logs: Vec<_> = logs
+            .iter()
+            .map(|log| (log.miniblock_number.0, log.key, log.value))
+            .collect();
+        assert_eq!(
+            logs,
+            [
+                (1, H256::repeat_byte(1), H256::repeat_byte(2)),
+                (1, H256::repeat_byte(2), H256::repeat_byte(1)),
+                (2, H256::repeat_byte(1), H256::repeat_byte(3)),
+                (3, H256::repeat_byte(1), H256::repeat_byte(4)),
+            ]
+        );
+        let mut dal = conn.pruning_dal();
+        assert!(dal
+            .is_miniblock_state_retained(L1BatchNumber(1))
+            .await
+            .unwrap());
+
+        // Pruning overwritten storage logs for later L1 batches removes the state for earlier batches as well.
+        prune(&mut conn, 2, 3..=3).await;
+        let mut dal = conn.pruning_dal();
+        assert!(!dal
+            .is_miniblock_state_retained(L1BatchNumber(1))
+            .await
+            .unwrap());
+        assert!(!dal
+            .is_miniblock_state_retained(L1BatchNumber(2))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn recording_fee_inputs_of_pruned_l1_batches() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        insert_miniblock(&mut conn, 1, vec![mock_storage_log(1, 1)]).await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let miniblock_header = conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        let fee_input = conn
+            .pruning_dal()
+            .get_pruned_l1_batch_fee_input(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(fee_input, None);
+
+        prune(&mut conn, 1, 1..=1).await;
+        let fee_input = conn
+            .pruning_dal()
+            .get_pruned_l1_batch_fee_input(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(fee_input, Some(miniblock_header.batch_fee_input));
+    }
+}
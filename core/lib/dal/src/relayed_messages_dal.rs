@@ -0,0 +1,664 @@
+use std::str::FromStr;
+
+use strum::{Display, EnumString};
+use zksync_types::{
+    api::idexo::{
+        MessageDeliveryReceipt, MessageDeliveryStatus, MessageDirection, RelayedMessageStatus,
+    },
+    Address, L1BatchNumber, H256,
+};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Status of a message tracked by the message relay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+pub enum MessageStatus {
+    /// The message waits to be delivered.
+    #[strum(serialize = "pending")]
+    Pending,
+    /// The delivery transaction is sent, but is not mined yet.
+    #[strum(serialize = "sent")]
+    Sent,
+    /// The message is delivered, either by the relay or by a third party.
+    #[strum(serialize = "delivered")]
+    Delivered,
+    /// The message could not be delivered after the maximum number of attempts.
+    #[strum(serialize = "failed")]
+    Failed,
+}
+
+impl From<MessageStatus> for MessageDeliveryStatus {
+    fn from(status: MessageStatus) -> Self {
+        match status {
+            MessageStatus::Pending => Self::Pending,
+            MessageStatus::Sent => Self::Sent,
+            MessageStatus::Delivered => Self::Delivered,
+            MessageStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+/// Origin of a relayed message together with the data necessary to deliver it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageOrigin {
+    /// Outbound message sent via the L1 messenger in an executed L1 batch.
+    L1Batch {
+        l1_batch_number: L1BatchNumber,
+        /// Index of the L2-to-L1 log with the message in the L1 batch.
+        l2_message_index: u32,
+        tx_number_in_batch: u16,
+        merkle_proof: Vec<H256>,
+    },
+    /// Inbound message sent via the outbox contract in a finalized settlement layer block.
+    SettlementLayer {
+        block_number: u64,
+        /// Nonce assigned to the message by the outbox contract.
+        nonce: u64,
+        /// Target contract on the chain.
+        target: Address,
+    },
+}
+
+impl MessageOrigin {
+    pub fn direction(&self) -> MessageDirection {
+        match self {
+            Self::L1Batch { .. } => MessageDirection::Outbound,
+            Self::SettlementLayer { .. } => MessageDirection::Inbound,
+        }
+    }
+}
+
+/// Message relayed between the chain and the settlement layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayedMessage {
+    /// Unique message ID used for deduplication.
+    pub id: H256,
+    /// Hash of the transaction that has sent the message: a chain transaction for outbound messages,
+    /// or a settlement layer transaction for inbound ones.
+    pub source_tx_hash: H256,
+    pub sender: Address,
+    pub data: Vec<u8>,
+    pub origin: MessageOrigin,
+}
+
+fn direction_to_str(direction: MessageDirection) -> &'static str {
+    match direction {
+        MessageDirection::Outbound => "outbound",
+        MessageDirection::Inbound => "inbound",
+    }
+}
+
+#[derive(Debug)]
+struct StorageRelayedMessage {
+    id: Vec<u8>,
+    source_tx_hash: Vec<u8>,
+    sender: Vec<u8>,
+    data: Vec<u8>,
+    l1_batch_number: Option<i64>,
+    l2_message_index: Option<i32>,
+    tx_number_in_batch: Option<i32>,
+    merkle_proof: Option<Vec<u8>>,
+    settlement_block_number: Option<i64>,
+    nonce: Option<i64>,
+    target: Option<Vec<u8>>,
+}
+
+impl From<StorageRelayedMessage> for RelayedMessage {
+    fn from(row: StorageRelayedMessage) -> Self {
+        let origin = if let Some(l1_batch_number) = row.l1_batch_number {
+            MessageOrigin::L1Batch {
+                l1_batch_number: L1BatchNumber(l1_batch_number as u32),
+                l2_message_index: row
+                    .l2_message_index
+                    .expect("no message index for outbound message")
+                    as u32,
+                tx_number_in_batch: row
+                    .tx_number_in_batch
+                    .expect("no tx number for outbound message")
+                    as u16,
+                merkle_proof: row
+                    .merkle_proof
+                    .expect("no Merkle proof for outbound message")
+                    .chunks(32)
+                    .map(H256::from_slice)
+                    .collect(),
+            }
+        } else {
+            MessageOrigin::SettlementLayer {
+                block_number: row
+                    .settlement_block_number
+                    .expect("no block number for inbound message")
+                    as u64,
+                nonce: row.nonce.expect("no nonce for inbound message") as u64,
+                target: Address::from_slice(&row.target.expect("no target for inbound message")),
+            }
+        };
+        Self {
+            id: H256::from_slice(&row.id),
+            source_tx_hash: H256::from_slice(&row.source_tx_hash),
+            sender: Address::from_slice(&row.sender),
+            data: row.data,
+            origin,
+        }
+    }
+}
+
+/// Messages tracked by the message relay.
+#[derive(Debug)]
+pub struct RelayedMessagesDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl RelayedMessagesDal<'_, '_> {
+    /// Inserts new pending messages. Already known messages are ignored.
+    pub async fn insert_messages(&mut self, messages: &[RelayedMessage]) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        for message in messages {
+            let (l1_batch_number, l2_message_index, tx_number_in_batch, merkle_proof) =
+                match &message.origin {
+                    MessageOrigin::L1Batch {
+                        l1_batch_number,
+                        l2_message_index,
+                        tx_number_in_batch,
+                        merkle_proof,
+                    } => {
+                        let merkle_proof: Vec<_> = merkle_proof
+                            .iter()
+                            .flat_map(|hash| hash.as_bytes())
+                            .copied()
+                            .collect();
+                        (
+                            Some(i64::from(l1_batch_number.0)),
+                            Some(*l2_message_index as i32),
+                            Some(i32::from(*tx_number_in_batch)),
+                            Some(merkle_proof),
+                        )
+                    }
+                    MessageOrigin::SettlementLayer { .. } => (None, None, None, None),
+                };
+            let (settlement_block_number, nonce, target) = match &message.origin {
+                MessageOrigin::L1Batch { .. } => (None, None, None),
+                MessageOrigin::SettlementLayer {
+                    block_number,
+                    nonce,
+                    target,
+                } => (
+                    Some(*block_number as i64),
+                    Some(*nonce as i64),
+                    Some(target.as_bytes()),
+                ),
+            };
+
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    relayed_messages (
+                        id,
+                        direction,
+                        source_tx_hash,
+                        sender,
+                        data,
+                        l1_batch_number,
+                        l2_message_index,
+                        tx_number_in_batch,
+                        merkle_proof,
+                        settlement_block_number,
+                        nonce,
+                        target,
+                        status,
+                        created_at,
+                        updated_at
+                    )
+                VALUES
+                    ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, NOW(), NOW())
+                ON CONFLICT (id) DO NOTHING
+                "#,
+                message.id.as_bytes(),
+                direction_to_str(message.origin.direction()),
+                message.source_tx_hash.as_bytes(),
+                message.sender.as_bytes(),
+                &message.data,
+                l1_batch_number,
+                l2_message_index,
+                tx_number_in_batch,
+                merkle_proof,
+                settlement_block_number,
+                nonce,
+                target,
+                MessageStatus::Pending.to_string()
+            )
+            .instrument("insert_messages")
+            .with_arg("id", &message.id)
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await
+    }
+
+    /// Returns the greatest number of an L1 batch with a tracked outbound message.
+    pub async fn get_last_l1_batch_with_messages(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number"
+            FROM
+                relayed_messages
+            "#
+        )
+        .instrument("get_last_l1_batch_with_messages")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Returns the greatest number of a settlement layer block with a tracked inbound message.
+    pub async fn get_last_settlement_block_with_messages(&mut self) -> sqlx::Result<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(settlement_block_number) AS "number"
+            FROM
+                relayed_messages
+            "#
+        )
+        .instrument("get_last_settlement_block_with_messages")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| number as u64))
+    }
+
+    /// Returns the oldest pending messages.
+    pub async fn get_pending_messages(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<RelayedMessage>> {
+        let rows = sqlx::query_as!(
+            StorageRelayedMessage,
+            r#"
+            SELECT
+                id,
+                source_tx_hash,
+                sender,
+                data,
+                l1_batch_number,
+                l2_message_index,
+                tx_number_in_batch,
+                merkle_proof,
+                settlement_block_number,
+                nonce,
+                target
+            FROM
+                relayed_messages
+            WHERE
+                status = $1
+            ORDER BY
+                created_at,
+                id
+            LIMIT
+                $2
+            "#,
+            MessageStatus::Pending.to_string(),
+            limit as i64
+        )
+        .instrument("get_pending_messages")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns messages with sent delivery transactions together with the IDs of the transactions
+    /// (see [`SettlementTxsDal`](crate::settlement_txs_dal::SettlementTxsDal)).
+    pub async fn get_sent_messages(&mut self) -> sqlx::Result<Vec<(u32, RelayedMessage)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                source_tx_hash,
+                sender,
+                data,
+                l1_batch_number,
+                l2_message_index,
+                tx_number_in_batch,
+                merkle_proof,
+                settlement_block_number,
+                nonce,
+                target,
+                delivery_tx_id AS "delivery_tx_id!"
+            FROM
+                relayed_messages
+            WHERE
+                status = $1
+            ORDER BY
+                created_at,
+                id
+            "#,
+            MessageStatus::Sent.to_string()
+        )
+        .instrument("get_sent_messages")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let message = StorageRelayedMessage {
+                    id: row.id,
+                    source_tx_hash: row.source_tx_hash,
+                    sender: row.sender,
+                    data: row.data,
+                    l1_batch_number: row.l1_batch_number,
+                    l2_message_index: row.l2_message_index,
+                    tx_number_in_batch: row.tx_number_in_batch,
+                    merkle_proof: row.merkle_proof,
+                    settlement_block_number: row.settlement_block_number,
+                    nonce: row.nonce,
+                    target: row.target,
+                };
+                (row.delivery_tx_id as u32, message.into())
+            })
+            .collect())
+    }
+
+    /// Marks messages as being delivered by the transaction with the specified ID.
+    pub async fn mark_messages_as_sent(
+        &mut self,
+        ids: &[H256],
+        delivery_tx_id: u32,
+    ) -> sqlx::Result<()> {
+        let ids: Vec<_> = ids.iter().map(H256::as_bytes).collect();
+        sqlx::query!(
+            r#"
+            UPDATE relayed_messages
+            SET
+                status = $1,
+                delivery_tx_id = $2,
+                delivery_tx_hash = NULL,
+                delivery_block_number = NULL,
+                attempts = attempts + 1,
+                updated_at = NOW()
+            WHERE
+                id = ANY ($3)
+            "#,
+            MessageStatus::Sent.to_string(),
+            i64::from(delivery_tx_id),
+            &ids as &[&[u8]]
+        )
+        .instrument("mark_messages_as_sent")
+        .with_arg("ids.len", &ids.len())
+        .with_arg("delivery_tx_id", &delivery_tx_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Marks a message as delivered. `receipt` is `None` if the message was delivered by a third party.
+    pub async fn mark_message_as_delivered(
+        &mut self,
+        id: H256,
+        receipt: Option<MessageDeliveryReceipt>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE relayed_messages
+            SET
+                status = $1,
+                delivery_tx_hash = $2,
+                delivery_block_number = $3,
+                updated_at = NOW()
+            WHERE
+                id = $4
+            "#,
+            MessageStatus::Delivered.to_string(),
+            receipt.as_ref().map(|receipt| receipt.tx_hash.as_bytes()),
+            receipt.map(|receipt| receipt.block_number as i64),
+            id.as_bytes()
+        )
+        .instrument("mark_message_as_delivered")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns a message that was not delivered by the sent transaction to the pending status, or marks it
+    /// as failed if it has reached `max_attempts` delivery attempts. Returns the new status.
+    pub async fn reset_message(
+        &mut self,
+        id: H256,
+        max_attempts: u32,
+    ) -> sqlx::Result<MessageStatus> {
+        let row = sqlx::query!(
+            r#"
+            UPDATE relayed_messages
+            SET
+                status = (
+                    CASE
+                        WHEN attempts >= $1 THEN $2
+                        ELSE $3
+                    END
+                ),
+                updated_at = NOW()
+            WHERE
+                id = $4
+            RETURNING
+                status
+            "#,
+            max_attempts as i32,
+            MessageStatus::Failed.to_string(),
+            MessageStatus::Pending.to_string(),
+            id.as_bytes()
+        )
+        .instrument("reset_message")
+        .with_arg("id", &id)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(MessageStatus::from_str(&row.status).expect("invalid message status in DB"))
+    }
+
+    /// Returns statuses of all messages sent by the specified chain or settlement layer transaction.
+    pub async fn get_messages_by_source_tx(
+        &mut self,
+        source_tx_hash: H256,
+    ) -> sqlx::Result<Vec<RelayedMessageStatus>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                direction,
+                source_tx_hash,
+                sender,
+                data,
+                target,
+                status,
+                attempts,
+                delivery_tx_hash,
+                delivery_block_number
+            FROM
+                relayed_messages
+            WHERE
+                source_tx_hash = $1
+            ORDER BY
+                l1_batch_number,
+                l2_message_index,
+                nonce
+            "#,
+            source_tx_hash.as_bytes()
+        )
+        .instrument("get_messages_by_source_tx")
+        .with_arg("source_tx_hash", &source_tx_hash)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let direction = if row.direction == direction_to_str(MessageDirection::Outbound) {
+                    MessageDirection::Outbound
+                } else {
+                    MessageDirection::Inbound
+                };
+                let receipt = row.delivery_tx_hash.zip(row.delivery_block_number).map(
+                    |(tx_hash, block_number)| MessageDeliveryReceipt {
+                        tx_hash: H256::from_slice(&tx_hash),
+                        block_number: block_number as u64,
+                    },
+                );
+                RelayedMessageStatus {
+                    id: H256::from_slice(&row.id),
+                    direction,
+                    source_tx_hash: H256::from_slice(&row.source_tx_hash),
+                    sender: Address::from_slice(&row.sender),
+                    target: row.target.as_deref().map(Address::from_slice),
+                    data: row.data.into(),
+                    status: MessageStatus::from_str(&row.status)
+                        .expect("invalid message status in DB")
+                        .into(),
+                    attempts: row.attempts as u32,
+                    receipt,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        settlement_txs_dal::{SettlementTx, SignedSettlementTx},
+        ConnectionPool,
+    };
+
+    fn outbound_message(l1_batch_number: u32, l2_message_index: u32) -> RelayedMessage {
+        RelayedMessage {
+            id: H256::from_low_u64_be(
+                (u64::from(l1_batch_number) << 32) | u64::from(l2_message_index),
+            ),
+            source_tx_hash: H256::repeat_byte(1),
+            sender: Address::repeat_byte(2),
+            data: vec![3; 40],
+            origin: MessageOrigin::L1Batch {
+                l1_batch_number: L1BatchNumber(l1_batch_number),
+                l2_message_index,
+                tx_number_in_batch: 4,
+                merkle_proof: vec![H256::repeat_byte(5), H256::repeat_byte(6)],
+            },
+        }
+    }
+
+    fn inbound_message(block_number: u64, nonce: u64) -> RelayedMessage {
+        RelayedMessage {
+            id: H256::from_low_u64_be(u64::MAX - nonce),
+            source_tx_hash: H256::repeat_byte(0x11),
+            sender: Address::repeat_byte(0x12),
+            data: vec![0x13; 20],
+            origin: MessageOrigin::SettlementLayer {
+                block_number,
+                nonce,
+                target: Address::repeat_byte(0x14),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn relayed_message_lifecycle() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.relayed_messages_dal();
+        assert_eq!(dal.get_last_l1_batch_with_messages().await.unwrap(), None);
+        assert_eq!(
+            dal.get_last_settlement_block_with_messages().await.unwrap(),
+            None
+        );
+
+        let messages = [
+            outbound_message(1, 0),
+            outbound_message(2, 3),
+            inbound_message(100, 0),
+        ];
+        dal.insert_messages(&messages).await.unwrap();
+        // Repeated insertion must be a no-op.
+        dal.insert_messages(&messages[..1]).await.unwrap();
+        assert_eq!(
+            dal.get_last_l1_batch_with_messages().await.unwrap(),
+            Some(L1BatchNumber(2))
+        );
+        assert_eq!(
+            dal.get_last_settlement_block_with_messages().await.unwrap(),
+            Some(100)
+        );
+
+        let mut pending = dal.get_pending_messages(10).await.unwrap();
+        pending.sort_by_key(|message| message.id);
+        let mut expected = messages.to_vec();
+        expected.sort_by_key(|message| message.id);
+        assert_eq!(pending, expected);
+        assert_eq!(dal.get_pending_messages(1).await.unwrap().len(), 1);
+
+        let settlement_tx = SettlementTx {
+            id: 0,
+            nonce: 0,
+            contract_address: Address::repeat_byte(0xee),
+            calldata: vec![1, 2, 3],
+            gas_limit: 400_000,
+        };
+        let tx_hash = H256::repeat_byte(0xff);
+        let signed_tx = SignedSettlementTx {
+            tx_hash,
+            raw_tx: vec![0xff; 10],
+            max_fee_per_gas: 100.into(),
+            max_priority_fee_per_gas: 1.into(),
+        };
+        let tx_id = conn
+            .settlement_txs_dal()
+            .insert_tx("message_relay", &settlement_tx, &signed_tx)
+            .await
+            .unwrap();
+        let mut dal = conn.relayed_messages_dal();
+        let sent_ids = [messages[0].id, messages[2].id];
+        dal.mark_messages_as_sent(&sent_ids, tx_id).await.unwrap();
+        let pending = dal.get_pending_messages(10).await.unwrap();
+        assert_eq!(pending, [messages[1].clone()]);
+        let mut sent = dal.get_sent_messages().await.unwrap();
+        sent.sort_by_key(|(_, message)| message.id);
+        let mut expected = vec![(tx_id, messages[0].clone()), (tx_id, messages[2].clone())];
+        expected.sort_by_key(|(_, message)| message.id);
+        assert_eq!(sent, expected);
+
+        let receipt = MessageDeliveryReceipt {
+            tx_hash,
+            block_number: 120,
+        };
+        dal.mark_message_as_delivered(messages[0].id, Some(receipt))
+            .await
+            .unwrap();
+        let status = dal.reset_message(messages[2].id, 1).await.unwrap();
+        assert_eq!(status, MessageStatus::Failed);
+        assert_eq!(dal.get_sent_messages().await.unwrap(), []);
+
+        let statuses = dal
+            .get_messages_by_source_tx(messages[0].source_tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].id, messages[0].id);
+        assert_eq!(statuses[0].direction, MessageDirection::Outbound);
+        assert_eq!(statuses[0].status, MessageDeliveryStatus::Delivered);
+        assert_eq!(statuses[0].attempts, 1);
+        assert_eq!(statuses[0].receipt, Some(receipt));
+        assert_eq!(statuses[1].id, messages[1].id);
+        assert_eq!(statuses[1].status, MessageDeliveryStatus::Pending);
+        assert_eq!(statuses[1].receipt, None);
+
+        let statuses = dal
+            .get_messages_by_source_tx(messages[2].source_tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].direction, MessageDirection::Inbound);
+        assert_eq!(statuses[0].target, Some(Address::repeat_byte(0x14)));
+        assert_eq!(statuses[0].status, MessageDeliveryStatus::Failed);
+        assert_eq!(statuses[0].receipt, None);
+    }
+}
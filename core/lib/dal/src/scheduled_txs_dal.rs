@@ -0,0 +1,256 @@
+use zksync_types::{Address, MiniblockNumber, H256, U256};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Recurring L2 transaction registered by the operator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewScheduledTx {
+    /// Unique human-readable name of the schedule (e.g., `oracle_push`).
+    pub name: String,
+    pub contract_address: Address,
+    pub calldata: Vec<u8>,
+    pub value: U256,
+    pub gas_limit: u64,
+    /// Interval between consecutive transactions.
+    pub interval_sec: u64,
+}
+
+/// Outcome of the last transaction submitted for a schedule.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduledTxOutcome {
+    /// No transaction is in flight.
+    Idle,
+    /// Transaction is waiting in the mempool.
+    Pending(H256),
+    /// Transaction was included in a miniblock and succeeded.
+    Succeeded(H256, MiniblockNumber),
+    /// Transaction was rejected or reverted.
+    Failed(H256, String),
+    /// Transaction is not present in the storage (e.g., it was purged from the mempool).
+    Dropped(H256),
+}
+
+/// Recurring L2 transaction together with its current state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledTx {
+    pub id: i64,
+    pub tx: NewScheduledTx,
+    pub is_due: bool,
+    pub consecutive_failures: u32,
+    pub last_outcome: ScheduledTxOutcome,
+}
+
+#[derive(Debug)]
+pub struct ScheduledTxsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ScheduledTxsDal<'_, '_> {
+    /// Registers a new schedule or updates the existing one with the same name. The first transaction
+    /// is scheduled to be sent immediately.
+    pub async fn register_scheduled_tx(&mut self, tx: &NewScheduledTx) -> sqlx::Result<i64> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                scheduled_txs (
+                    name,
+                    contract_address,
+                    calldata,
+                    value,
+                    gas_limit,
+                    interval_sec,
+                    next_run_at,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, NOW(), NOW(), NOW())
+            ON CONFLICT (name) DO
+            UPDATE
+            SET
+                contract_address = excluded.contract_address,
+                calldata = excluded.calldata,
+                value = excluded.value,
+                gas_limit = excluded.gas_limit,
+                interval_sec = excluded.interval_sec,
+                is_enabled = TRUE,
+                updated_at = NOW()
+            RETURNING
+                id
+            "#,
+            &tx.name,
+            tx.contract_address.as_bytes(),
+            &tx.calldata,
+            u256_to_big_decimal(tx.value),
+            tx.gas_limit as i64,
+            tx.interval_sec as i64,
+        )
+        .instrument("register_scheduled_tx")
+        .with_arg("name", &tx.name)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.id)
+    }
+
+    /// Disables the schedule with the specified name. Returns `false` if there is no such schedule.
+    pub async fn disable_scheduled_tx(&mut self, name: &str) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE scheduled_txs
+            SET
+                is_enabled = FALSE,
+                updated_at = NOW()
+            WHERE
+                name = $1
+            "#,
+            name
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns all enabled schedules together with the outcome of their last submitted transaction.
+    pub async fn get_enabled_scheduled_txs(&mut self) -> sqlx::Result<Vec<ScheduledTx>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                scheduled_txs.id,
+                scheduled_txs.name,
+                scheduled_txs.contract_address,
+                scheduled_txs.calldata,
+                scheduled_txs.value,
+                scheduled_txs.gas_limit,
+                scheduled_txs.interval_sec,
+                scheduled_txs.next_run_at <= NOW() AS "is_due!",
+                scheduled_txs.consecutive_failures,
+                scheduled_txs.last_tx_hash,
+                transactions.hash AS "last_tx_found?",
+                transactions.miniblock_number AS "last_tx_miniblock_number?",
+                transactions.error AS "last_tx_error?"
+            FROM
+                scheduled_txs
+                LEFT JOIN transactions ON transactions.hash = scheduled_txs.last_tx_hash
+            WHERE
+                scheduled_txs.is_enabled = TRUE
+            ORDER BY
+                scheduled_txs.id
+            "#
+        )
+        .instrument("get_enabled_scheduled_txs")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let last_outcome = match row.last_tx_hash {
+                    None => ScheduledTxOutcome::Idle,
+                    Some(hash) => {
+                        let hash = H256::from_slice(&hash);
+                        match (
+                            row.last_tx_found,
+                            row.last_tx_miniblock_number,
+                            row.last_tx_error,
+                        ) {
+                            (None, _, _) => ScheduledTxOutcome::Dropped(hash),
+                            (Some(_), _, Some(error)) => ScheduledTxOutcome::Failed(hash, error),
+                            (Some(_), Some(number), None) => {
+                                ScheduledTxOutcome::Succeeded(hash, MiniblockNumber(number as u32))
+                            }
+                            (Some(_), None, None) => ScheduledTxOutcome::Pending(hash),
+                        }
+                    }
+                };
+
+                ScheduledTx {
+                    id: row.id,
+                    tx: NewScheduledTx {
+                        name: row.name,
+                        contract_address: Address::from_slice(&row.contract_address),
+                        calldata: row.calldata,
+                        value: bigdecimal_to_u256(row.value),
+                        gas_limit: row.gas_limit as u64,
+                        interval_sec: row.interval_sec as u64,
+                    },
+                    is_due: row.is_due,
+                    consecutive_failures: row.consecutive_failures as u32,
+                    last_outcome,
+                }
+            })
+            .collect())
+    }
+
+    /// Records that a transaction was submitted for the schedule and moves the schedule to the next run.
+    pub async fn mark_scheduled_tx_submitted(
+        &mut self,
+        id: i64,
+        tx_hash: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE scheduled_txs
+            SET
+                last_tx_hash = $2,
+                next_run_at = NOW() + interval_sec * INTERVAL '1 second',
+                updated_at = NOW()
+            WHERE
+                id = $1
+            "#,
+            id,
+            tx_hash.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Records the outcome of the in-flight transaction for the schedule.
+    pub async fn mark_scheduled_tx_processed(
+        &mut self,
+        id: i64,
+        success: bool,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE scheduled_txs
+            SET
+                last_tx_hash = NULL,
+                consecutive_failures = CASE
+                    WHEN $2 THEN 0
+                    ELSE consecutive_failures + 1
+                END,
+                updated_at = NOW()
+            WHERE
+                id = $1
+            "#,
+            id,
+            success
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Records that a transaction for the schedule was rejected before submission, counts it as a failure
+    /// and moves the schedule to the next run.
+    pub async fn mark_scheduled_tx_rejected(&mut self, id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE scheduled_txs
+            SET
+                last_tx_hash = NULL,
+                consecutive_failures = consecutive_failures + 1,
+                next_run_at = NOW() + interval_sec * INTERVAL '1 second',
+                updated_at = NOW()
+            WHERE
+                id = $1
+            "#,
+            id
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+}
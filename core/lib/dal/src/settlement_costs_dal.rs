@@ -0,0 +1,273 @@
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    api::idexo::{BatchEconomics, BatchSettlementCost},
+    L1BatchNumber, U256,
+};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+pub struct SettlementCostsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl SettlementCostsDal<'_, '_> {
+    /// Records the settlement cost of an aggregated operation for the specified L1 batch. If the cost is already
+    /// recorded, it is overwritten.
+    pub async fn insert_settlement_cost(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        action_type: AggregatedActionType,
+        cost: &BatchSettlementCost,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batch_settlement_costs (
+                    l1_batch_number,
+                    action_type,
+                    eth_tx_id,
+                    gas_used,
+                    gas_cost,
+                    blob_gas_used,
+                    blob_cost,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, $7, NOW())
+            ON CONFLICT (l1_batch_number, action_type) DO
+            UPDATE
+            SET
+                eth_tx_id = excluded.eth_tx_id,
+                gas_used = excluded.gas_used,
+                gas_cost = excluded.gas_cost,
+                blob_gas_used = excluded.blob_gas_used,
+                blob_cost = excluded.blob_cost
+            "#,
+            i64::from(l1_batch_number.0),
+            action_type.as_str(),
+            cost.eth_tx_id as i32,
+            cost.gas_used as i64,
+            u256_to_big_decimal(cost.gas_cost),
+            cost.blob_gas_used as i64,
+            u256_to_big_decimal(cost.blob_cost)
+        )
+        .instrument("insert_settlement_cost")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("action_type", &action_type)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns settlement costs and collected fees for the specified L1 batch, or `None` if the batch
+    /// is not present in the storage.
+    pub async fn get_batch_economics(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<BatchEconomics>> {
+        let Some(l2_fees_collected) = self.get_l2_fees_collected(l1_batch_number).await? else {
+            return Ok(None);
+        };
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                action_type,
+                eth_tx_id,
+                gas_used,
+                gas_cost,
+                blob_gas_used,
+                blob_cost
+            FROM
+                l1_batch_settlement_costs
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_batch_economics")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let (mut commit, mut prove, mut execute) = (None, None, None);
+        for row in rows {
+            let cost = BatchSettlementCost {
+                eth_tx_id: row.eth_tx_id as u32,
+                gas_used: row.gas_used as u64,
+                gas_cost: bigdecimal_to_u256(row.gas_cost),
+                blob_gas_used: row.blob_gas_used as u64,
+                blob_cost: bigdecimal_to_u256(row.blob_cost),
+            };
+            let action_type = row
+                .action_type
+                .parse()
+                .map_err(|err: &str| sqlx::Error::Decode(err.into()))?;
+            match action_type {
+                AggregatedActionType::Commit => commit = Some(cost),
+                AggregatedActionType::PublishProofOnchain => prove = Some(cost),
+                AggregatedActionType::Execute => execute = Some(cost),
+            }
+        }
+        Ok(Some(BatchEconomics::new(
+            l1_batch_number,
+            commit,
+            prove,
+            execute,
+            l2_fees_collected,
+        )))
+    }
+
+    /// Returns the fees paid by L2 transactions in the specified L1 batch after refunds, or `None` if the batch
+    /// is not present in the storage.
+    pub async fn get_l2_fees_collected(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<U256>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                (
+                    SELECT
+                        SUM(
+                            COALESCE(effective_gas_price, 0) * (gas_limit - refunded_gas)
+                        )
+                    FROM
+                        transactions
+                    WHERE
+                        l1_batch_number = $1
+                ) AS "l2_fees_collected"
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_l2_fees_collected")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| {
+            row.l2_fees_collected
+                .map(bigdecimal_to_u256)
+                .unwrap_or_default()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    fn cost(eth_tx_id: u32, gas_used: u64, blob_gas_used: u64) -> BatchSettlementCost {
+        BatchSettlementCost {
+            eth_tx_id,
+            gas_used,
+            gas_cost: U256::from(gas_used) * 10,
+            blob_gas_used,
+            blob_cost: U256::from(blob_gas_used),
+        }
+    }
+
+    #[tokio::test]
+    async fn recording_settlement_costs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        let economics = conn
+            .settlement_costs_dal()
+            .get_batch_economics(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(economics, None);
+
+        let mut dal = conn.settlement_costs_dal();
+        let economics = dal
+            .get_batch_economics(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .expect("no economics for L1 batch #1");
+        assert_eq!(economics.total_settlement_cost, U256::zero());
+        assert_eq!(economics.l2_fees_collected, U256::zero());
+        assert!(!economics.is_fully_settled);
+
+        let eth_tx_id = insert_eth_tx(&mut conn).await;
+        let mut dal = conn.settlement_costs_dal();
+        let commit_cost = cost(eth_tx_id, 100_000, 131_072);
+        dal.insert_settlement_cost(L1BatchNumber(1), AggregatedActionType::Commit, &commit_cost)
+            .await
+            .unwrap();
+        let economics = dal
+            .get_batch_economics(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(economics.commit, Some(commit_cost.clone()));
+        assert_eq!(economics.prove, None);
+        assert_eq!(economics.total_settlement_cost, commit_cost.total_cost());
+        assert!(!economics.is_profitable);
+
+        let prove_cost = cost(eth_tx_id, 300_000, 0);
+        let execute_cost = cost(eth_tx_id, 50_000, 0);
+        dal.insert_settlement_cost(
+            L1BatchNumber(1),
+            AggregatedActionType::PublishProofOnchain,
+            &prove_cost,
+        )
+        .await
+        .unwrap();
+        dal.insert_settlement_cost(
+            L1BatchNumber(1),
+            AggregatedActionType::Execute,
+            &execute_cost,
+        )
+        .await
+        .unwrap();
+        let economics = dal
+            .get_batch_economics(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(economics.is_fully_settled);
+        assert_eq!(
+            economics.total_settlement_cost,
+            commit_cost.total_cost() + prove_cost.total_cost() + execute_cost.total_cost()
+        );
+    }
+
+    async fn insert_eth_tx(conn: &mut StorageProcessor<'_>) -> u32 {
+        conn.eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                AggregatedActionType::Commit,
+                Default::default(),
+                1_000_000,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+            .id
+    }
+}
@@ -0,0 +1,223 @@
+use sqlx::types::chrono::NaiveDateTime;
+use zksync_types::{Address, H256, U256};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Settlement layer transaction sent by an auxiliary component (e.g., the withdrawal finalizer).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettlementTx {
+    pub id: u32,
+    pub nonce: u64,
+    pub contract_address: Address,
+    pub calldata: Vec<u8>,
+    pub gas_limit: u64,
+}
+
+/// Signed version of a [`SettlementTx`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedSettlementTx {
+    pub tx_hash: H256,
+    pub raw_tx: Vec<u8>,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+/// Signed version of a [`SettlementTx`] together with the time it was persisted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettlementTxAttempt {
+    pub tx: SignedSettlementTx,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct SettlementTxsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl SettlementTxsDal<'_, '_> {
+    /// Inserts a new transaction together with its first signed version. Returns the ID of the transaction.
+    pub async fn insert_tx(
+        &mut self,
+        component: &str,
+        tx: &SettlementTx,
+        signed_tx: &SignedSettlementTx,
+    ) -> sqlx::Result<u32> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                settlement_txs (component, nonce, contract_address, calldata, gas_limit, created_at)
+            VALUES
+                ($1, $2, $3, $4, $5, NOW())
+            RETURNING
+                id
+            "#,
+            component,
+            tx.nonce as i64,
+            tx.contract_address.as_bytes(),
+            &tx.calldata,
+            tx.gas_limit as i64
+        )
+        .instrument("insert_settlement_tx")
+        .with_arg("component", &component)
+        .with_arg("nonce", &tx.nonce)
+        .fetch_one(&mut transaction)
+        .await?;
+        let id = row.id as u32;
+
+        transaction
+            .settlement_txs_dal()
+            .insert_attempt(id, signed_tx)
+            .await?;
+        transaction.commit().await?;
+        Ok(id)
+    }
+
+    /// Inserts a new signed version of the transaction with the specified ID.
+    pub async fn insert_attempt(
+        &mut self,
+        tx_id: u32,
+        signed_tx: &SignedSettlementTx,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                settlement_tx_attempts (
+                    tx_hash,
+                    settlement_tx_id,
+                    raw_tx,
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, NOW())
+            "#,
+            signed_tx.tx_hash.as_bytes(),
+            i64::from(tx_id),
+            &signed_tx.raw_tx,
+            u256_to_big_decimal(signed_tx.max_fee_per_gas),
+            u256_to_big_decimal(signed_tx.max_priority_fee_per_gas)
+        )
+        .instrument("insert_settlement_tx_attempt")
+        .with_arg("tx_id", &tx_id)
+        .with_arg("tx_hash", &signed_tx.tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_tx(&mut self, tx_id: u32) -> sqlx::Result<Option<SettlementTx>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                nonce,
+                contract_address,
+                calldata,
+                gas_limit
+            FROM
+                settlement_txs
+            WHERE
+                id = $1
+            "#,
+            i64::from(tx_id)
+        )
+        .instrument("get_settlement_tx")
+        .with_arg("tx_id", &tx_id)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| SettlementTx {
+            id: row.id as u32,
+            nonce: row.nonce as u64,
+            contract_address: Address::from_slice(&row.contract_address),
+            calldata: row.calldata,
+            gas_limit: row.gas_limit as u64,
+        }))
+    }
+
+    /// Returns signed versions of the transaction with the specified ID, from the oldest to the newest one.
+    pub async fn get_attempts(&mut self, tx_id: u32) -> sqlx::Result<Vec<SettlementTxAttempt>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                raw_tx,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                created_at
+            FROM
+                settlement_tx_attempts
+            WHERE
+                settlement_tx_id = $1
+            ORDER BY
+                created_at,
+                max_fee_per_gas
+            "#,
+            i64::from(tx_id)
+        )
+        .instrument("get_settlement_tx_attempts")
+        .with_arg("tx_id", &tx_id)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SettlementTxAttempt {
+                tx: SignedSettlementTx {
+                    tx_hash: H256::from_slice(&row.tx_hash),
+                    raw_tx: row.raw_tx,
+                    max_fee_per_gas: bigdecimal_to_u256(row.max_fee_per_gas),
+                    max_priority_fee_per_gas: bigdecimal_to_u256(row.max_priority_fee_per_gas),
+                },
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    fn signed_tx(byte: u8, max_fee_per_gas: u64) -> SignedSettlementTx {
+        SignedSettlementTx {
+            tx_hash: H256::repeat_byte(byte),
+            raw_tx: vec![byte; 10],
+            max_fee_per_gas: max_fee_per_gas.into(),
+            max_priority_fee_per_gas: 1.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn settlement_tx_lifecycle() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.settlement_txs_dal();
+        assert_eq!(dal.get_tx(1).await.unwrap(), None);
+
+        let tx = SettlementTx {
+            id: 0,
+            nonce: 5,
+            contract_address: Address::repeat_byte(0x11),
+            calldata: vec![1, 2, 3],
+            gas_limit: 100_000,
+        };
+        let first_attempt = signed_tx(1, 100);
+        let tx_id = dal
+            .insert_tx("withdrawal_finalizer", &tx, &first_attempt)
+            .await
+            .unwrap();
+        let persisted_tx = dal.get_tx(tx_id).await.unwrap().unwrap();
+        assert_eq!(persisted_tx, SettlementTx { id: tx_id, ..tx });
+
+        let second_attempt = signed_tx(2, 120);
+        dal.insert_attempt(tx_id, &second_attempt).await.unwrap();
+        let attempts = dal.get_attempts(tx_id).await.unwrap();
+        let attempts: Vec<_> = attempts.into_iter().map(|attempt| attempt.tx).collect();
+        assert_eq!(attempts, [first_attempt, second_attempt]);
+    }
+}
@@ -32,7 +32,6 @@ impl SnapshotsCreatorDal<'_, '_> {
             l1_batch_number.0 as i32
         )
         .instrument("get_storage_logs_count")
-        .report_latency()
         .fetch_one(self.storage)
         .await?
         .index;
@@ -82,7 +81,6 @@ impl SnapshotsCreatorDal<'_, '_> {
         .with_arg("miniblock_number", &miniblock_number)
         .with_arg("min_hashed_key", &hashed_keys_range.start())
         .with_arg("max_hashed_key", &hashed_keys_range.end())
-        .report_latency()
         .fetch_all(self.storage)
         .await?
         .iter()
@@ -117,7 +115,6 @@ impl SnapshotsCreatorDal<'_, '_> {
             miniblock_number.0 as i64,
         )
         .instrument("get_all_factory_deps")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
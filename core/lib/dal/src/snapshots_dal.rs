@@ -1,4 +1,6 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
 use zksync_types::{
+    api::idexo::SnapshotRequest,
     snapshots::{AllSnapshots, SnapshotMetadata},
     L1BatchNumber,
 };
@@ -26,6 +28,29 @@ impl From<StorageSnapshotMetadata> for SnapshotMetadata {
     }
 }
 
+#[derive(Debug)]
+struct StorageSnapshotRequest {
+    id: i64,
+    requested_at: NaiveDateTime,
+    l1_batch_number: Option<i64>,
+    processed_at: Option<NaiveDateTime>,
+}
+
+impl From<StorageSnapshotRequest> for SnapshotRequest {
+    fn from(request: StorageSnapshotRequest) -> Self {
+        Self {
+            id: request.id as u32,
+            requested_at: DateTime::<Utc>::from_naive_utc_and_offset(request.requested_at, Utc),
+            l1_batch_number: request
+                .l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            processed_at: request
+                .processed_at
+                .map(|processed_at| DateTime::<Utc>::from_naive_utc_and_offset(processed_at, Utc)),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SnapshotsDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -56,7 +81,6 @@ impl SnapshotsDal<'_, '_> {
             factory_deps_filepaths,
         )
         .instrument("add_snapshot")
-        .report_latency()
         .execute(self.storage)
         .await?;
         Ok(())
@@ -101,7 +125,6 @@ impl SnapshotsDal<'_, '_> {
             "#
         )
         .instrument("get_all_complete_snapshots")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
@@ -132,7 +155,6 @@ impl SnapshotsDal<'_, '_> {
             "#
         )
         .instrument("get_newest_snapshot_metadata")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -158,12 +180,85 @@ impl SnapshotsDal<'_, '_> {
             l1_batch_number.0 as i32
         )
         .instrument("get_snapshot_metadata")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
         Ok(row.map(Into::into))
     }
+
+    /// Requests creating a snapshot. Returns the created request, or `None` if there is a pending request already.
+    pub async fn request_snapshot(&mut self) -> sqlx::Result<Option<SnapshotRequest>> {
+        let request = sqlx::query_as!(
+            StorageSnapshotRequest,
+            r#"
+            INSERT INTO
+                snapshot_requests (requested_at)
+            VALUES
+                (NOW())
+            ON CONFLICT ((processed_at IS NULL)) WHERE processed_at IS NULL DO NOTHING
+            RETURNING
+                id,
+                requested_at,
+                l1_batch_number,
+                processed_at
+            "#
+        )
+        .instrument("request_snapshot")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(request.map(Into::into))
+    }
+
+    pub async fn get_pending_snapshot_request(&mut self) -> sqlx::Result<Option<SnapshotRequest>> {
+        let request = sqlx::query_as!(
+            StorageSnapshotRequest,
+            r#"
+            SELECT
+                id,
+                requested_at,
+                l1_batch_number,
+                processed_at
+            FROM
+                snapshot_requests
+            WHERE
+                processed_at IS NULL
+            "#
+        )
+        .instrument("get_pending_snapshot_request")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(request.map(Into::into))
+    }
+
+    /// Marks the pending snapshot request (if any) as processed by the snapshot for the specified L1 batch.
+    /// Returns the processed request.
+    pub async fn mark_snapshot_request_as_processed(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<SnapshotRequest>> {
+        let request = sqlx::query_as!(
+            StorageSnapshotRequest,
+            r#"
+            UPDATE snapshot_requests
+            SET
+                l1_batch_number = $1,
+                processed_at = NOW()
+            WHERE
+                processed_at IS NULL
+            RETURNING
+                id,
+                requested_at,
+                l1_batch_number,
+                processed_at
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("mark_snapshot_request_as_processed")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(request.map(Into::into))
+    }
 }
 
 #[cfg(test)]
@@ -212,6 +307,40 @@ mod tests {
         assert_eq!(snapshot_metadata.l1_batch_number, l1_batch_number);
     }
 
+    #[tokio::test]
+    async fn requesting_snapshot() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        assert_eq!(dal.get_pending_snapshot_request().await.unwrap(), None);
+        let processed = dal
+            .mark_snapshot_request_as_processed(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(processed, None);
+
+        let request = dal.request_snapshot().await.unwrap().unwrap();
+        assert_eq!(request.l1_batch_number, None);
+        assert_eq!(request.processed_at, None);
+        // Only one request can be pending at a time.
+        assert_eq!(dal.request_snapshot().await.unwrap(), None);
+        let pending_request = dal.get_pending_snapshot_request().await.unwrap();
+        assert_eq!(pending_request, Some(request.clone()));
+
+        let processed = dal
+            .mark_snapshot_request_as_processed(L1BatchNumber(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(processed.id, request.id);
+        assert_eq!(processed.l1_batch_number, Some(L1BatchNumber(5)));
+        assert!(processed.processed_at.is_some());
+        assert_eq!(dal.get_pending_snapshot_request().await.unwrap(), None);
+
+        let new_request = dal.request_snapshot().await.unwrap().unwrap();
+        assert_ne!(new_request.id, request.id);
+    }
+
     #[tokio::test]
     async fn adding_files() {
         let pool = ConnectionPool::test_pool().await;
@@ -2,8 +2,9 @@ use std::{collections::HashMap, ops, time::Instant};
 
 use sqlx::{types::chrono::Utc, Row};
 use zksync_types::{
-    get_code_key, snapshots::SnapshotStorageLog, AccountTreeId, Address, L1BatchNumber,
-    MiniblockNumber, StorageKey, StorageLog, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256,
+    api::idexo::StorageDiff, get_code_key, snapshots::SnapshotStorageLog, AccountTreeId, Address,
+    L1BatchNumber, MiniblockNumber, StorageKey, StorageLog,
+    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256,
 };
 
 pub use crate::models::storage_log::{DbStorageLog, StorageRecoveryLogEntry};
@@ -398,6 +399,97 @@ impl StorageLogsDal<'_, '_> {
         Ok(touched_slots.collect())
     }
 
+    /// Returns final values of storage slots written in the specified miniblock range, ordered by hashed key.
+    /// Storage logs are deduplicated by the slot, but writes that don't change the slot value are not filtered out.
+    pub async fn get_storage_diffs(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        miniblock_range: ops::RangeInclusive<MiniblockNumber>,
+        offset: usize,
+        limit: usize,
+    ) -> sqlx::Result<Vec<StorageDiff>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                logs.address,
+                logs.key,
+                logs.hashed_key,
+                logs.value,
+                initial_writes.index AS "enumeration_index?",
+                initial_writes.l1_batch_number AS "initial_write_l1_batch?"
+            FROM
+                (
+                    SELECT DISTINCT
+                        ON (hashed_key) hashed_key,
+                        address,
+                        key,
+                        value
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                    ORDER BY
+                        hashed_key,
+                        miniblock_number DESC,
+                        operation_number DESC
+                ) logs
+                LEFT JOIN initial_writes ON initial_writes.hashed_key = logs.hashed_key
+            ORDER BY
+                logs.hashed_key
+            OFFSET
+                $3
+            LIMIT
+                $4
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64,
+            offset as i64,
+            limit as i64
+        )
+        .instrument("get_storage_diffs")
+        .with_arg("miniblock_range", &miniblock_range)
+        .report_latency()
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| StorageDiff {
+                address: Address::from_slice(&row.address),
+                key: H256::from_slice(&row.key),
+                hashed_key: H256::from_slice(&row.hashed_key),
+                value: H256::from_slice(&row.value),
+                enumeration_index: row.enumeration_index.map(|index| index as u64),
+                is_initial: row.initial_write_l1_batch == Some(l1_batch_number.0.into()),
+            })
+            .collect())
+    }
+
+    /// Returns the number of distinct storage slots written in the specified miniblock range.
+    pub async fn count_storage_diffs(
+        &mut self,
+        miniblock_range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<usize> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(DISTINCT hashed_key) AS "count!"
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64
+        )
+        .instrument("count_storage_diffs")
+        .with_arg("miniblock_range", &miniblock_range)
+        .report_latency()
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.count as usize)
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -505,7 +597,6 @@ impl StorageLogsDal<'_, '_> {
             &hashed_keys as &[&[u8]],
         )
         .instrument("get_l1_batches_and_indices_for_initial_writes")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
@@ -595,6 +686,54 @@ impl StorageLogsDal<'_, '_> {
             .collect())
     }
 
+    /// Returns storage logs for the specified keys written in the specified miniblock, in the execution order.
+    pub async fn get_miniblock_storage_logs_for_keys(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        hashed_keys: &[H256],
+    ) -> sqlx::Result<Vec<DbStorageLog>> {
+        let hashed_keys: Vec<_> = hashed_keys.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hashed_key,
+                address,
+                key,
+                value,
+                operation_number,
+                tx_hash,
+                miniblock_number
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number = $1
+                AND hashed_key = ANY ($2)
+            ORDER BY
+                operation_number
+            "#,
+            miniblock_number.0 as i64,
+            &hashed_keys as &[&[u8]]
+        )
+        .instrument("get_miniblock_storage_logs_for_keys")
+        .with_arg("miniblock_number", &miniblock_number)
+        .with_arg("hashed_keys.len", &hashed_keys.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DbStorageLog {
+                hashed_key: H256::from_slice(&row.hashed_key),
+                address: H160::from_slice(&row.address),
+                key: H256::from_slice(&row.key),
+                value: H256::from_slice(&row.value),
+                operation_number: row.operation_number as u64,
+                tx_hash: H256::from_slice(&row.tx_hash),
+                miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+            })
+            .collect())
+    }
+
     /// Retrieves all storage log entries for testing purposes.
     pub async fn dump_all_storage_logs_for_tests(&mut self) -> Vec<DbStorageLog> {
         let rows = sqlx::query!(
@@ -915,6 +1054,53 @@ mod tests {
         test_rollback(&mut conn, first_key, second_key).await;
     }
 
+    #[tokio::test]
+    async fn getting_latest_values_in_miniblocks() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let first_key = StorageKey::new(account, H256::zero());
+        let second_key = StorageKey::new(account, H256::from_low_u64_be(1));
+        let logs = vec![
+            StorageLog::new_write_log(first_key, H256::repeat_byte(1)),
+            StorageLog::new_write_log(second_key, H256::repeat_byte(2)),
+        ];
+        insert_miniblock(&mut conn, 1, logs).await;
+        let logs = vec![
+            StorageLog::new_write_log(first_key, H256::repeat_byte(3)),
+            StorageLog::new_write_log(first_key, H256::repeat_byte(4)),
+        ];
+        insert_miniblock(&mut conn, 2, logs).await;
+
+        let values = conn
+            .storage_web3_dal()
+            .get_latest_values_in_miniblocks(MiniblockNumber(1)..=MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[&first_key.hashed_key()], H256::repeat_byte(4));
+        assert_eq!(values[&second_key.hashed_key()], H256::repeat_byte(2));
+
+        let values = conn
+            .storage_web3_dal()
+            .get_latest_values_in_miniblocks(MiniblockNumber(1)..=MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[&first_key.hashed_key()], H256::repeat_byte(1));
+
+        let values = conn
+            .storage_web3_dal()
+            .get_latest_values_in_miniblocks(MiniblockNumber(3)..=MiniblockNumber(5))
+            .await
+            .unwrap();
+        assert!(values.is_empty());
+    }
+
     async fn test_rollback(
         conn: &mut StorageProcessor<'_>,
         key: StorageKey,
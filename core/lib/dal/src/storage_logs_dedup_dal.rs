@@ -85,14 +85,30 @@ impl StorageLogsDedupDal<'_, '_> {
         l1_batch_number: L1BatchNumber,
         written_storage_keys: &[StorageKey],
     ) -> sqlx::Result<()> {
-        let hashed_keys: Vec<_> = written_storage_keys
+        let last_index = self.max_enumeration_index().await.unwrap_or(0);
+        let initial_writes: Vec<_> = written_storage_keys
             .iter()
-            .map(|key| StorageKey::raw_hashed_key(key.address(), key.key()).to_vec())
+            .zip((last_index + 1)..)
+            .map(|(key, index)| (key.hashed_key(), index))
             .collect();
+        self.insert_initial_writes_with_indices(l1_batch_number, &initial_writes)
+            .await
+    }
 
-        let last_index = self.max_enumeration_index().await.unwrap_or(0);
-        let indices: Vec<_> = ((last_index + 1)..=(last_index + hashed_keys.len() as u64))
-            .map(|x| x as i64)
+    /// Inserts initial writes with the specified enumeration indices, e.g. when restoring an L1 batch
+    /// from an archive. Initial writes are specified as `(hashed_key, index)` tuples.
+    pub async fn insert_initial_writes_with_indices(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        initial_writes: &[(H256, u64)],
+    ) -> sqlx::Result<()> {
+        let hashed_keys: Vec<_> = initial_writes
+            .iter()
+            .map(|(hashed_key, _)| hashed_key.as_bytes().to_vec())
+            .collect();
+        let indices: Vec<_> = initial_writes
+            .iter()
+            .map(|&(_, index)| index as i64)
             .collect();
 
         sqlx::query!(
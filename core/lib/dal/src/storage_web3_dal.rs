@@ -120,7 +120,6 @@ impl StorageWeb3Dal<'_, '_> {
             block_number.0 as i64
         )
         .instrument("get_historical_value_unchecked")
-        .report_latency()
         .with_arg("key", &hashed_key)
         .fetch_optional(self.storage)
         .await
@@ -167,7 +166,9 @@ impl StorageWeb3Dal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("resolve_l1_batch_number_of_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_one(self.storage)
         .await?;
 
         Ok(ResolvedL1BatchForMiniblock {
@@ -193,7 +194,6 @@ impl StorageWeb3Dal<'_, '_> {
             hashed_key.as_bytes(),
         )
         .instrument("get_l1_batch_number_for_initial_write")
-        .report_latency()
         .with_arg("key", &hashed_key)
         .fetch_optional(self.storage)
         .await?;
@@ -219,7 +219,9 @@ impl StorageWeb3Dal<'_, '_> {
             miniblock_numbers.start().0 as i64,
             miniblock_numbers.end().0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("modified_keys_in_miniblocks")
+        .with_arg("miniblock_numbers", &miniblock_numbers)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -227,6 +229,45 @@ impl StorageWeb3Dal<'_, '_> {
         .collect()
     }
 
+    /// Returns the latest values of storage slots modified in the specified miniblock range, keyed by
+    /// the hashed storage key.
+    pub async fn get_latest_values_in_miniblocks(
+        &mut self,
+        miniblock_numbers: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<HashMap<H256, H256>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT
+                ON (hashed_key) hashed_key,
+                value
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                hashed_key,
+                miniblock_number DESC,
+                operation_number DESC
+            "#,
+            miniblock_numbers.start().0 as i64,
+            miniblock_numbers.end().0 as i64
+        )
+        .instrument("get_latest_values_in_miniblocks")
+        .with_arg("miniblock_numbers", &miniblock_numbers)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    H256::from_slice(&row.hashed_key),
+                    H256::from_slice(&row.value),
+                )
+            })
+            .collect())
+    }
+
     /// This method doesn't check if block with number equals to `block_number`
     /// is present in the database. For such blocks `None` will be returned.
     pub async fn get_contract_code_unchecked(
@@ -263,7 +304,10 @@ impl StorageWeb3Dal<'_, '_> {
                 block_number.0 as i64,
                 FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_contract_code_unchecked")
+            .with_arg("address", &address)
+            .with_arg("block_number", &block_number)
+            .fetch_optional(self.storage)
             .await
             .map(|option_row| option_row.map(|row| row.bytecode))
         }
@@ -290,7 +334,10 @@ impl StorageWeb3Dal<'_, '_> {
                 hash.as_bytes(),
                 block_number.0 as i64
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_factory_dep_unchecked")
+            .with_arg("hash", &hash)
+            .with_arg("block_number", &block_number)
+            .fetch_optional(self.storage)
             .await
             .map(|option_row| option_row.map(|row| row.bytecode))
         }
@@ -1,4 +1,9 @@
-use zksync_types::{api::en, MiniblockNumber};
+use anyhow::Context as _;
+use zksync_types::{
+    api::en,
+    l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
+    AccountTreeId, Address, MiniblockNumber, StorageKey, StorageLog, VmEvent, H256,
+};
 
 use crate::{
     instrument::InstrumentExt,
@@ -55,7 +60,8 @@ impl SyncDal<'_, '_> {
                 miniblocks.virtual_blocks,
                 miniblocks.hash,
                 miniblocks.protocol_version AS "protocol_version!",
-                miniblocks.fee_account_address AS "fee_account_address!"
+                miniblocks.fee_account_address AS "fee_account_address!",
+                miniblocks.content_hash
             FROM
                 miniblocks
             WHERE
@@ -102,6 +108,214 @@ impl SyncDal<'_, '_> {
         };
         Ok(Some(block.into_api(transactions)))
     }
+
+    /// Returns execution outputs of the specified miniblock, or `None` if the miniblock is not sealed.
+    pub async fn sync_block_outputs(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> anyhow::Result<Option<en::SyncBlockOutputs>> {
+        let _latency = MethodLatency::new("sync_dal_sync_block_outputs");
+        let Some(block) = self.sync_block_inner(block_number).await? else {
+            return Ok(None);
+        };
+
+        let transactions = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                error,
+                refunded_gas,
+                execution_info
+            FROM
+                transactions
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                index_in_block
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_block_outputs#transactions")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?;
+        let transactions = transactions
+            .into_iter()
+            .map(|row| {
+                Ok(en::SyncTransactionOutput {
+                    hash: H256::from_slice(&row.hash),
+                    is_successful: row.error.is_none(),
+                    refunded_gas: row.refunded_gas as u32,
+                    execution_info: serde_json::from_value(row.execution_info)?,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let events = sqlx::query!(
+            r#"
+            SELECT
+                tx_index_in_block,
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4,
+                value
+            FROM
+                events
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                event_index_in_block
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_block_outputs#events")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?;
+        let events = events
+            .into_iter()
+            .map(|row| VmEvent {
+                location: (block.l1_batch_number, row.tx_index_in_block as u32),
+                address: Address::from_slice(&row.address),
+                // Missing topics are stored as empty byte arrays.
+                indexed_topics: [row.topic1, row.topic2, row.topic3, row.topic4]
+                    .into_iter()
+                    .filter(|topic| !topic.is_empty())
+                    .map(|topic| H256::from_slice(&topic))
+                    .collect(),
+                value: row.value,
+            })
+            .collect();
+
+        let l2_to_l1_logs = sqlx::query!(
+            r#"
+            SELECT
+                tx_index_in_miniblock,
+                tx_index_in_l1_batch,
+                shard_id,
+                is_service,
+                sender,
+                key,
+                value
+            FROM
+                l2_to_l1_logs
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                log_index_in_miniblock
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_block_outputs#l2_to_l1_logs")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?;
+        let l2_to_l1_logs = l2_to_l1_logs
+            .into_iter()
+            .map(|row| {
+                let log = L2ToL1Log {
+                    shard_id: row.shard_id as u8,
+                    is_service: row.is_service,
+                    tx_number_in_block: row.tx_index_in_l1_batch as u16,
+                    sender: Address::from_slice(&row.sender),
+                    key: H256::from_slice(&row.key),
+                    value: H256::from_slice(&row.value),
+                };
+                (row.tx_index_in_miniblock as u32, UserL2ToL1Log(log))
+            })
+            .collect();
+
+        let storage_logs = sqlx::query!(
+            r#"
+            SELECT
+                address,
+                key,
+                value,
+                tx_hash
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                operation_number
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_block_outputs#storage_logs")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?;
+        let mut grouped_storage_logs: Vec<(H256, Vec<StorageLog>)> = vec![];
+        for row in storage_logs {
+            let tx_hash = H256::from_slice(&row.tx_hash);
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            let log = StorageLog::new_write_log(key, H256::from_slice(&row.value));
+            // Logs are ordered by the operation number, so logs of each transaction are contiguous.
+            match grouped_storage_logs.last_mut() {
+                Some((last_tx_hash, logs)) if *last_tx_hash == tx_hash => logs.push(log),
+                _ => grouped_storage_logs.push((tx_hash, vec![log])),
+            }
+        }
+
+        let factory_deps = sqlx::query!(
+            r#"
+            SELECT
+                bytecode_hash,
+                bytecode
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                bytecode_hash
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("sync_block_outputs#factory_deps")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?;
+        let factory_deps = factory_deps
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode.into()))
+            .collect();
+
+        // The L1 batch header and initial writes are persisted in the same DB transaction as the last miniblock
+        // in the batch, so they are always available for it.
+        let (l1_batch_header, initial_writes) = if block.last_in_batch {
+            let header = self
+                .storage
+                .blocks_dal()
+                .get_l1_batch_header(block.l1_batch_number)
+                .await?
+                .with_context(|| format!("L1 batch #{} is not sealed", block.l1_batch_number))?;
+            let initial_writes = self
+                .storage
+                .storage_logs_dedup_dal()
+                .initial_writes_for_batch(block.l1_batch_number)
+                .await;
+            (Some(header), initial_writes)
+        } else {
+            (None, vec![])
+        };
+
+        Ok(Some(en::SyncBlockOutputs {
+            number: block_number,
+            transactions,
+            events,
+            l2_to_l1_logs,
+            storage_logs: grouped_storage_logs,
+            factory_deps,
+            l1_batch_header,
+            initial_writes,
+            sequencer_signature: None,
+        }))
+    }
 }
 
 #[cfg(test)]
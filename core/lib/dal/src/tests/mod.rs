@@ -17,6 +17,7 @@ use zksync_types::{
 use crate::{
     blocks_dal::BlocksDal,
     connection::ConnectionPool,
+    eth_sender_dal::EthSenderDal,
     protocol_versions_dal::ProtocolVersionsDal,
     transactions_dal::{L2TxSubmissionResult, TransactionsDal},
     transactions_web3_dal::TransactionsWeb3Dal,
@@ -24,7 +25,7 @@ use crate::{
 
 const DEFAULT_GAS_PER_PUBDATA: u32 = 100;
 
-fn mock_tx_execution_metrics() -> TransactionExecutionMetrics {
+pub(crate) fn mock_tx_execution_metrics() -> TransactionExecutionMetrics {
     TransactionExecutionMetrics::default()
 }
 
@@ -116,6 +117,7 @@ pub(crate) fn mock_execution_result(transaction: L2Tx) -> TransactionExecutionRe
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        executed_at: None,
     }
 }
 
@@ -272,3 +274,54 @@ async fn remove_stuck_txs() {
 
     assert_eq!(receipts.len(), 1);
 }
+
+#[tokio::test]
+async fn halting_and_resuming_settlement() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let mut eth_sender_dal = EthSenderDal { storage };
+    assert_eq!(
+        eth_sender_dal.get_active_settlement_halt().await.unwrap(),
+        None
+    );
+    assert_eq!(eth_sender_dal.resume_settlement().await.unwrap(), None);
+
+    assert!(eth_sender_dal.halt_settlement("frozen").await.unwrap());
+    // Settlement is already halted.
+    assert!(!eth_sender_dal.halt_settlement("reorg").await.unwrap());
+    let halt = eth_sender_dal
+        .get_active_settlement_halt()
+        .await
+        .unwrap()
+        .expect("no active halt");
+    assert_eq!(halt.reason, "frozen");
+    assert_eq!(halt.resumed_at, None);
+
+    let resumed_halt = eth_sender_dal
+        .resume_settlement()
+        .await
+        .unwrap()
+        .expect("no resumed halt");
+    assert_eq!(resumed_halt.id, halt.id);
+    assert!(resumed_halt.resumed_at.is_some());
+    assert_eq!(
+        eth_sender_dal.get_active_settlement_halt().await.unwrap(),
+        None
+    );
+
+    // A resumed anomaly halts settlement again if it persists.
+    assert!(eth_sender_dal.halt_settlement("frozen").await.unwrap());
+    assert!(!eth_sender_dal.halt_settlement("reorg").await.unwrap());
+    let halt = eth_sender_dal
+        .get_active_settlement_halt()
+        .await
+        .unwrap()
+        .expect("no active halt");
+    assert_eq!(halt.reason, "frozen");
+    assert_ne!(halt.id, resumed_halt.id);
+
+    eth_sender_dal.resume_settlement().await.unwrap();
+    assert!(eth_sender_dal.halt_settlement("reorg").await.unwrap());
+    let halt = eth_sender_dal.get_active_settlement_halt().await.unwrap();
+    assert_eq!(halt.unwrap().reason, "reorg");
+}
@@ -0,0 +1,197 @@
+use zksync_types::{api::idexo::RegisteredToken, tokens::TokenMetadata, Address};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+struct StorageRegisteredToken {
+    l1_address: Vec<u8>,
+    l2_address: Vec<u8>,
+    name: String,
+    symbol: String,
+    decimals: i32,
+    logo_url: Option<String>,
+}
+
+impl From<StorageRegisteredToken> for RegisteredToken {
+    fn from(row: StorageRegisteredToken) -> Self {
+        Self {
+            l1_address: Address::from_slice(&row.l1_address),
+            l2_address: Address::from_slice(&row.l2_address),
+            metadata: TokenMetadata {
+                name: row.name,
+                symbol: row.symbol,
+                decimals: row.decimals as u8,
+            },
+            logo_url: row.logo_url,
+        }
+    }
+}
+
+/// Registry of tokens with metadata curated by the operator.
+#[derive(Debug)]
+pub struct TokenRegistryDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TokenRegistryDal<'_, '_> {
+    /// Adds the token to the registry, or overwrites its metadata if the token with the same L2 address
+    /// is already registered.
+    pub async fn register_token(&mut self, token: &RegisteredToken) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                token_registry (
+                    l2_address,
+                    l1_address,
+                    NAME,
+                    symbol,
+                    decimals,
+                    logo_url,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+            ON CONFLICT (l2_address) DO
+            UPDATE
+            SET
+                l1_address = $2,
+                NAME = $3,
+                symbol = $4,
+                decimals = $5,
+                logo_url = $6,
+                updated_at = NOW()
+            "#,
+            token.l2_address.as_bytes(),
+            token.l1_address.as_bytes(),
+            &token.metadata.name,
+            &token.metadata.symbol,
+            i32::from(token.metadata.decimals),
+            token.logo_url.as_deref()
+        )
+        .instrument("register_token")
+        .with_arg("l2_address", &token.l2_address)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the token from the registry. Returns `false` if the token was not registered.
+    pub async fn unregister_token(&mut self, l2_address: Address) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM token_registry
+            WHERE
+                l2_address = $1
+            "#,
+            l2_address.as_bytes()
+        )
+        .instrument("unregister_token")
+        .with_arg("l2_address", &l2_address)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns all registered tokens ordered by their symbol.
+    pub async fn get_registered_tokens(&mut self) -> sqlx::Result<Vec<RegisteredToken>> {
+        let records = sqlx::query_as!(
+            StorageRegisteredToken,
+            r#"
+            SELECT
+                l1_address,
+                l2_address,
+                NAME,
+                symbol,
+                decimals,
+                logo_url
+            FROM
+                token_registry
+            ORDER BY
+                symbol,
+                l2_address
+            "#
+        )
+        .instrument("get_registered_tokens")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(records.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    fn registered_token(byte: u8, symbol: &str) -> RegisteredToken {
+        RegisteredToken {
+            l1_address: Address::repeat_byte(byte),
+            l2_address: Address::repeat_byte(byte + 1),
+            metadata: TokenMetadata {
+                name: format!("{symbol} token"),
+                symbol: symbol.to_owned(),
+                decimals: 6,
+            },
+            logo_url: Some(format!("https://example.com/{symbol}.png")),
+        }
+    }
+
+    #[tokio::test]
+    async fn registering_and_unregistering_tokens() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let usdc = registered_token(1, "USDC");
+        let dai = registered_token(3, "DAI");
+        conn.token_registry_dal()
+            .register_token(&usdc)
+            .await
+            .unwrap();
+        conn.token_registry_dal()
+            .register_token(&dai)
+            .await
+            .unwrap();
+
+        let tokens = conn
+            .token_registry_dal()
+            .get_registered_tokens()
+            .await
+            .unwrap();
+        assert_eq!(tokens, [dai.clone(), usdc.clone()]);
+
+        let updated_usdc = RegisteredToken {
+            logo_url: None,
+            ..registered_token(1, "USDC.e")
+        };
+        conn.token_registry_dal()
+            .register_token(&updated_usdc)
+            .await
+            .unwrap();
+        let tokens = conn
+            .token_registry_dal()
+            .get_registered_tokens()
+            .await
+            .unwrap();
+        assert_eq!(tokens, [dai.clone(), updated_usdc]);
+
+        let removed = conn
+            .token_registry_dal()
+            .unregister_token(usdc.l2_address)
+            .await
+            .unwrap();
+        assert!(removed);
+        let removed = conn
+            .token_registry_dal()
+            .unregister_token(usdc.l2_address)
+            .await
+            .unwrap();
+        assert!(!removed);
+        let tokens = conn
+            .token_registry_dal()
+            .get_registered_tokens()
+            .await
+            .unwrap();
+        assert_eq!(tokens, [dai]);
+    }
+}
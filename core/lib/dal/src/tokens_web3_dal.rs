@@ -3,7 +3,7 @@ use zksync_types::{
     Address, MiniblockNumber,
 };
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 struct StorageTokenInfo {
@@ -53,7 +53,8 @@ impl TokensWeb3Dal<'_, '_> {
                 symbol
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_well_known_tokens")
+        .fetch_all(self.storage)
         .await?;
 
         Ok(records.into_iter().map(Into::into).collect())
@@ -79,7 +80,8 @@ impl TokensWeb3Dal<'_, '_> {
                 symbol
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_all_tokens")
+        .fetch_all(self.storage)
         .await?;
 
         let mut all_tokens: Vec<_> = records.into_iter().map(TokenInfo::from).collect();
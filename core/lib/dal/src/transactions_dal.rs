@@ -12,8 +12,8 @@ use zksync_types::{
     protocol_version::ProtocolUpgradeTx,
     tx::{tx_execution_info::TxExecutionStatus, TransactionExecutionResult},
     vm_trace::Call,
-    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, PriorityOpId,
-    Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce,
+    PriorityOpId, Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
 use zksync_utils::u256_to_big_decimal;
 
@@ -45,6 +45,24 @@ impl fmt::Display for L2TxSubmissionResult {
     }
 }
 
+/// Aggregated resources requested by L2 transactions waiting in the mempool.
+/// Values are based on the execution metrics recorded when the transactions were submitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingL2TxsResources {
+    pub tx_count: u64,
+    pub gas_used: u64,
+    pub storage_writes: u64,
+}
+
+/// Pending L2 transaction not yet included into a miniblock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingL2Tx {
+    pub initiator_address: Address,
+    pub nonce: Nonce,
+    /// Whether the transaction was fetched into the state keeper mempool.
+    pub in_mempool: bool,
+}
+
 #[derive(Debug)]
 pub struct TransactionsDal<'c, 'a> {
     pub(crate) storage: &'c mut StorageProcessor<'a>,
@@ -73,6 +91,8 @@ impl TransactionsDal<'_, '_> {
 
             let to_mint = u256_to_big_decimal(tx.common_data.to_mint);
             let refund_recipient = tx.common_data.refund_recipient.as_bytes();
+            // Despite its name, `deadline_block` holds the `expirationTimestamp` of the priority request.
+            let expiration_timestamp = tx.common_data.deadline_block as i64;
 
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
@@ -100,6 +120,7 @@ impl TransactionsDal<'_, '_> {
                         tx_format,
                         l1_tx_mint,
                         l1_tx_refund_recipient,
+                        l1_tx_expiration_timestamp,
                         received_at,
                         created_at,
                         updated_at
@@ -125,6 +146,7 @@ impl TransactionsDal<'_, '_> {
                         $16,
                         $17,
                         $18,
+                        $19,
                         NOW(),
                         NOW()
                     )
@@ -147,6 +169,7 @@ impl TransactionsDal<'_, '_> {
                 tx_format,
                 to_mint,
                 refund_recipient,
+                expiration_timestamp,
                 received_at,
             )
             .fetch_optional(self.storage.conn())
@@ -373,6 +396,7 @@ impl TransactionsDal<'_, '_> {
                     paymaster_input = $15,
                     execution_info = JSONB_BUILD_OBJECT('gas_used', $16::BIGINT, 'storage_writes', $17::INT, 'contracts_used', $18::INT),
                     in_mempool = FALSE,
+                    mempool_at = NULL,
                     received_at = $19,
                     created_at = NOW(),
                     updated_at = NOW(),
@@ -487,6 +511,49 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Sets the L1 batch number and the index in the L1 batch for transactions in miniblocks of the specified
+    /// L1 batch. Unlike [`Self::mark_txs_as_executed_in_l1_batch()`], doesn't require execution results
+    /// for the entire L1 batch; used by external nodes persisting miniblocks without executing them.
+    pub async fn mark_miniblock_txs_as_executed_in_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                l1_batch_number = $1,
+                l1_batch_tx_index = data_table.l1_batch_tx_index,
+                updated_at = NOW()
+            FROM
+                (
+                    SELECT
+                        transactions.hash,
+                        (
+                            ROW_NUMBER() OVER (
+                                ORDER BY
+                                    transactions.miniblock_number,
+                                    transactions.index_in_block
+                            ) - 1
+                        )::INT AS l1_batch_tx_index
+                    FROM
+                        transactions
+                        INNER JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                    WHERE
+                        miniblocks.l1_batch_number = $1
+                ) AS data_table
+            WHERE
+                transactions.hash = data_table.hash
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("mark_miniblock_txs_as_executed_in_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn mark_txs_as_executed_in_miniblock(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -532,6 +599,9 @@ impl TransactionsDal<'_, '_> {
 
             let mut call_traces_tx_hashes = Vec::with_capacity(transactions.len());
             let mut bytea_call_traces = Vec::with_capacity(transactions.len());
+
+            let mut executed_at_tx_hashes = Vec::with_capacity(transactions.len());
+            let mut executed_at_timestamps = Vec::with_capacity(transactions.len());
             transactions
                 .iter()
                 .enumerate()
@@ -542,6 +612,7 @@ impl TransactionsDal<'_, '_> {
                         transaction,
                         execution_status,
                         refunded_gas,
+                        executed_at,
                         ..
                     } = tx_res;
 
@@ -558,6 +629,10 @@ impl TransactionsDal<'_, '_> {
                         bytea_call_traces.push(bincode::serialize(&call_trace).unwrap());
                         call_traces_tx_hashes.push(hash.0.to_vec());
                     }
+                    if let Some(executed_at) = executed_at {
+                        executed_at_tx_hashes.push(hash.0.to_vec());
+                        executed_at_timestamps.push(executed_at.naive_utc());
+                    }
 
                     match &transaction.common_data {
                         ExecuteTransactionCommon::L1(common_data) => {
@@ -807,7 +882,31 @@ impl TransactionsDal<'_, '_> {
                     &bytea_call_traces
                 )
                 .instrument("insert_call_tracer")
-                .report_latency()
+                .execute(&mut transaction)
+                .await
+                .unwrap();
+            }
+
+            if !executed_at_tx_hashes.is_empty() {
+                // L2 transactions have their hashes updated above, so it's safe to match on the executed hashes.
+                sqlx::query!(
+                    r#"
+                    UPDATE transactions
+                    SET
+                        executed_at = data_table.executed_at
+                    FROM
+                        (
+                            SELECT
+                                UNNEST($1::bytea[]) AS hash,
+                                UNNEST($2::TIMESTAMP[]) AS executed_at
+                        ) AS data_table
+                    WHERE
+                        transactions.hash = data_table.hash
+                    "#,
+                    &executed_at_tx_hashes,
+                    &executed_at_timestamps
+                )
+                .instrument("set_tx_executed_at")
                 .execute(&mut transaction)
                 .await
                 .unwrap();
@@ -849,7 +948,8 @@ impl TransactionsDal<'_, '_> {
                     miniblock_number = NULL,
                     error = NULL,
                     index_in_block = NULL,
-                    execution_info = '{}'
+                    execution_info = '{}',
+                    executed_at = NULL
                 WHERE
                     miniblock_number > $1
                 RETURNING
@@ -898,6 +998,163 @@ impl TransactionsDal<'_, '_> {
         Ok(rows.len())
     }
 
+    /// Marks pending transactions of the `initiator_address` account with nonces below `next_nonce` as rejected.
+    /// Such transactions can never be executed since their nonces were used by other transactions.
+    /// `next_nonce` must be read from the storage as of a sealed miniblock. Returns hashes of rejected transactions.
+    pub async fn reject_txs_with_used_nonces(
+        &mut self,
+        initiator_address: Address,
+        next_nonce: Nonce,
+        error: &str,
+    ) -> sqlx::Result<Vec<H256>> {
+        let rows = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                error = $3,
+                updated_at = NOW()
+            WHERE
+                initiator_address = $1
+                AND nonce < $2
+                AND miniblock_number IS NULL
+                AND error IS NULL
+                AND is_priority = FALSE
+            RETURNING
+                hash
+            "#,
+            initiator_address.as_bytes(),
+            i64::from(next_nonce.0),
+            error
+        )
+        .instrument("reject_txs_with_used_nonces")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("next_nonce", &next_nonce)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect())
+    }
+
+    /// Returns the resources requested by pending L2 transactions that can be picked up by the state keeper
+    /// with the provided fee requirements.
+    pub async fn get_pending_l2_txs_resources(
+        &mut self,
+        gas_per_pubdata: u32,
+        fee_per_gas: u64,
+    ) -> sqlx::Result<PendingL2TxsResources> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "tx_count!",
+                COALESCE(SUM((execution_info ->> 'gas_used')::BIGINT), 0)::BIGINT AS "gas_used!",
+                COALESCE(SUM((execution_info ->> 'storage_writes')::BIGINT), 0)::BIGINT AS "storage_writes!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number IS NULL
+                AND error IS NULL
+                AND is_priority = FALSE
+                AND max_fee_per_gas >= $1
+                AND gas_per_pubdata_limit >= $2
+            "#,
+            BigDecimal::from(fee_per_gas),
+            BigDecimal::from(gas_per_pubdata),
+        )
+        .instrument("get_pending_l2_txs_resources")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(PendingL2TxsResources {
+            tx_count: row.tx_count as u64,
+            gas_used: row.gas_used as u64,
+            storage_writes: row.storage_writes as u64,
+        })
+    }
+
+    /// Returns a pending L2 transaction with the specified hash, or `None` if there is no such transaction
+    /// or it's already included into a miniblock.
+    pub async fn get_pending_l2_tx(&mut self, hash: H256) -> sqlx::Result<Option<PendingL2Tx>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                initiator_address,
+                nonce,
+                in_mempool
+            FROM
+                transactions
+            WHERE
+                hash = $1
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("get_pending_l2_tx")
+        .with_arg("hash", &hash)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| PendingL2Tx {
+            initiator_address: Address::from_slice(&row.initiator_address),
+            nonce: Nonce(row.nonce.unwrap_or(0) as u32),
+            in_mempool: row.in_mempool,
+        }))
+    }
+
+    /// Removes a pending L2 transaction. Unless `include_mempool` is set, transactions already fetched into
+    /// the state keeper mempool are not removed. Returns whether the transaction was removed.
+    pub async fn remove_pending_l2_tx(
+        &mut self,
+        hash: H256,
+        include_mempool: bool,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                hash = $1
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND (
+                    $2
+                    OR in_mempool = FALSE
+                )
+            "#,
+            hash.as_bytes(),
+            include_mempool
+        )
+        .instrument("remove_pending_l2_tx")
+        .with_arg("hash", &hash)
+        .with_arg("include_mempool", &include_mempool)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Checks whether a transaction with the specified hash is included into a miniblock.
+    pub async fn is_tx_executed(&mut self, hash: H256) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number
+            FROM
+                transactions
+            WHERE
+                hash = $1
+                AND miniblock_number IS NOT NULL
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("is_tx_executed")
+        .with_arg("hash", &hash)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.is_some())
+    }
+
     /// Fetches new updates for mempool. Returns new transactions and current nonces for related accounts;
     /// the latter are only used to bootstrap mempool for given account.
     pub async fn sync_mempool(
@@ -944,7 +1201,8 @@ impl TransactionsDal<'_, '_> {
             r#"
             UPDATE transactions
             SET
-                in_mempool = TRUE
+                in_mempool = TRUE,
+                mempool_at = COALESCE(transactions.mempool_at, NOW())
             FROM
                 (
                     SELECT
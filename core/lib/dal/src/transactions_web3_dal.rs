@@ -1,8 +1,14 @@
-use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use zksync_types::{
-    api, api::TransactionReceipt, Address, L2ChainId, MiniblockNumber, Transaction,
+    api,
+    api::{
+        idexo::{PriorityOpInfo, TransactionTimeline},
+        TransactionReceipt, TransactionStatus,
+    },
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, Nonce, PriorityOpId, Transaction,
     ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
+use zksync_utils::bigdecimal_to_u256;
 
 use crate::{
     instrument::InstrumentExt,
@@ -16,6 +22,17 @@ use crate::{
     SqlxError, StorageProcessor,
 };
 
+/// L2 transaction that is not included into a miniblock yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnexecutedL2Tx {
+    pub hash: H256,
+    pub nonce: Nonce,
+    pub max_fee_per_gas: U256,
+    pub in_mempool: bool,
+    /// Error returned by the state keeper if the transaction was rejected.
+    pub error: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct TransactionsWeb3Dal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -76,7 +93,9 @@ impl TransactionsWeb3Dal<'_, '_> {
                 .map(|h| h.as_bytes().to_vec())
                 .collect::<Vec<_>>()[..]
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_transaction_receipts")
+        .with_arg("hashes", &hashes)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(Into::into)
@@ -160,7 +179,9 @@ impl TransactionsWeb3Dal<'_, '_> {
         };
 
         let tx = query
-            .fetch_optional(self.storage.conn())
+            .instrument("get_transaction")
+            .with_arg("transaction_id", &transaction_id)
+            .fetch_optional(self.storage)
             .await?
             .map(|row| extract_web3_transaction(row, chain_id));
         Ok(tx)
@@ -219,6 +240,92 @@ impl TransactionsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns timestamps of lifecycle stages for the specified transaction.
+    pub async fn get_transaction_timeline(
+        &mut self,
+        hash: H256,
+    ) -> sqlx::Result<Option<TransactionTimeline>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                transactions.is_priority,
+                transactions.error,
+                transactions.received_at,
+                transactions.created_at,
+                transactions.mempool_at,
+                transactions.executed_at,
+                transactions.miniblock_number,
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                miniblocks.created_at AS "miniblock_sealed_at?",
+                l1_batches.created_at AS "l1_batch_sealed_at?",
+                commit_tx.confirmed_at AS "committed_at?",
+                prove_tx.confirmed_at AS "proven_at?",
+                execute_tx.confirmed_at AS "executed_on_settlement_at?"
+            FROM
+                transactions
+                LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                LEFT JOIN l1_batches ON l1_batches.number = miniblocks.l1_batch_number
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                transactions.hash = $1
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("get_transaction_timeline")
+        .with_arg("hash", &hash)
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let to_utc = |timestamp| DateTime::<Utc>::from_naive_utc_and_offset(timestamp, Utc);
+        let executed_on_settlement_at = row.executed_on_settlement_at.map(to_utc);
+        let status = if row.error.is_some() {
+            TransactionStatus::Failed
+        } else if executed_on_settlement_at.is_some() {
+            TransactionStatus::Verified
+        } else if row.miniblock_number.is_some() {
+            TransactionStatus::Included
+        } else {
+            TransactionStatus::Pending
+        };
+
+        Ok(Some(TransactionTimeline {
+            hash,
+            is_l1_originated: row.is_priority,
+            status,
+            miniblock_number: row
+                .miniblock_number
+                .map(|number| MiniblockNumber(number as u32)),
+            l1_batch_number: row
+                .l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            received_at: to_utc(row.received_at),
+            // L1-originated transactions are not validated by the API server; `created_at` for them
+            // is the time they were observed by the L1 watcher.
+            validated_at: (!row.is_priority).then(|| to_utc(row.created_at)),
+            added_to_mempool_at: row.mempool_at.map(to_utc),
+            executed_at: row.executed_at.map(to_utc),
+            miniblock_sealed_at: row.miniblock_sealed_at.map(to_utc),
+            l1_batch_sealed_at: row.l1_batch_sealed_at.map(to_utc),
+            committed_at: row.committed_at.map(to_utc),
+            proven_at: row.proven_at.map(to_utc),
+            executed_on_settlement_at,
+        }))
+    }
+
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
@@ -243,7 +350,10 @@ impl TransactionsWeb3Dal<'_, '_> {
             from_timestamp,
             limit.map(|limit| limit as i64)
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_pending_txs_hashes_after")
+        .with_arg("from_timestamp", &from_timestamp)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
         .await?;
 
         let last_loc = records.last().map(|record| record.received_at);
@@ -254,6 +364,200 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok((hashes, last_loc))
     }
 
+    /// Returns hashes of the oldest transactions that are not included into a miniblock yet and were not rejected,
+    /// ordered by the time of receiving.
+    pub async fn get_mempool_tx_hashes(&mut self, limit: usize) -> sqlx::Result<Vec<H256>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash
+            FROM
+                transactions
+            WHERE
+                miniblock_number IS NULL
+                AND error IS NULL
+            ORDER BY
+                received_at
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_mempool_tx_hashes")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect())
+    }
+
+    /// Returns the oldest priority operations that are not included into a sealed L1 batch yet, ordered by ID.
+    pub async fn get_pending_priority_ops(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<PriorityOpInfo>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.priority_op_id AS "priority_op_id!",
+                transactions.hash,
+                transactions.l1_block_number AS "l1_block_number!",
+                transactions.l1_tx_expiration_timestamp,
+                transactions.received_at,
+                transactions.miniblock_number,
+                bridge_deposits.settlement_tx_hash AS "settlement_tx_hash?"
+            FROM
+                transactions
+                LEFT JOIN bridge_deposits ON bridge_deposits.priority_op_id = transactions.priority_op_id
+            WHERE
+                transactions.is_priority = TRUE
+                AND transactions.l1_batch_number IS NULL
+            ORDER BY
+                transactions.priority_op_id
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_pending_priority_ops")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let settlement_block_number = row.l1_block_number as u64;
+                PriorityOpInfo {
+                    priority_op_id: PriorityOpId(row.priority_op_id as u64),
+                    tx_hash: H256::from_slice(&row.hash),
+                    settlement_tx_hash: row.settlement_tx_hash.as_deref().map(H256::from_slice),
+                    settlement_block_number,
+                    deadline: row
+                        .l1_tx_expiration_timestamp
+                        .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single()),
+                    received_at: DateTime::from_naive_utc_and_offset(row.received_at, Utc),
+                    miniblock_number: row
+                        .miniblock_number
+                        .map(|number| MiniblockNumber(number as u32)),
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the number of priority operations that are not included into a sealed L1 batch yet.
+    pub async fn get_pending_priority_ops_count(&mut self) -> sqlx::Result<u64> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                transactions
+            WHERE
+                is_priority = TRUE
+                AND l1_batch_number IS NULL
+            "#
+        )
+        .instrument("get_pending_priority_ops_count")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.count as u64)
+    }
+
+    /// Returns the oldest priority operation that is not executed on the settlement layer yet, i.e., is not included
+    /// into an L1 batch up to and including `last_executed_l1_batch`.
+    pub async fn get_oldest_unexecuted_priority_op(
+        &mut self,
+        last_executed_l1_batch: Option<L1BatchNumber>,
+    ) -> sqlx::Result<Option<PriorityOpInfo>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                transactions.priority_op_id AS "priority_op_id!",
+                transactions.hash,
+                transactions.l1_block_number AS "l1_block_number!",
+                transactions.l1_tx_expiration_timestamp,
+                transactions.received_at,
+                transactions.miniblock_number,
+                bridge_deposits.settlement_tx_hash AS "settlement_tx_hash?"
+            FROM
+                transactions
+                LEFT JOIN bridge_deposits ON bridge_deposits.priority_op_id = transactions.priority_op_id
+            WHERE
+                transactions.is_priority = TRUE
+                AND (
+                    transactions.l1_batch_number IS NULL
+                    OR transactions.l1_batch_number > $1
+                )
+            ORDER BY
+                transactions.priority_op_id
+            LIMIT
+                1
+            "#,
+            last_executed_l1_batch.map_or(-1, |number| i64::from(number.0))
+        )
+        .instrument("get_oldest_unexecuted_priority_op")
+        .with_arg("last_executed_l1_batch", &last_executed_l1_batch)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| {
+            let settlement_block_number = row.l1_block_number as u64;
+            PriorityOpInfo {
+                priority_op_id: PriorityOpId(row.priority_op_id as u64),
+                tx_hash: H256::from_slice(&row.hash),
+                settlement_tx_hash: row.settlement_tx_hash.as_deref().map(H256::from_slice),
+                settlement_block_number,
+                deadline: row
+                    .l1_tx_expiration_timestamp
+                    .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single()),
+                received_at: DateTime::from_naive_utc_and_offset(row.received_at, Utc),
+                miniblock_number: row
+                    .miniblock_number
+                    .map(|number| MiniblockNumber(number as u32)),
+            }
+        }))
+    }
+
+    /// Returns the number of priority operations that are not executed on the settlement layer yet (see
+    /// [`Self::get_oldest_unexecuted_priority_op()`]), together with the number of such operations received
+    /// before `received_before`.
+    pub async fn get_unexecuted_priority_ops_count(
+        &mut self,
+        last_executed_l1_batch: Option<L1BatchNumber>,
+        received_before: DateTime<Utc>,
+    ) -> sqlx::Result<(u64, u64)> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!",
+                COUNT(*) FILTER (
+                    WHERE
+                        received_at < $1
+                ) AS "overdue_count!"
+            FROM
+                transactions
+            WHERE
+                is_priority = TRUE
+                AND (
+                    l1_batch_number IS NULL
+                    OR l1_batch_number > $2
+                )
+            "#,
+            received_before.naive_utc(),
+            last_executed_l1_batch.map_or(-1, |number| i64::from(number.0))
+        )
+        .instrument("get_unexecuted_priority_ops_count")
+        .with_arg("last_executed_l1_batch", &last_executed_l1_batch)
+        .with_arg("received_before", &received_before)
+        .fetch_one(self.storage)
+        .await?;
+        Ok((row.count as u64, row.overdue_count as u64))
+    }
+
     /// `committed_next_nonce` should equal the nonce for `initiator_address` in the storage.
     pub async fn next_nonce_by_initiator_account(
         &mut self,
@@ -285,7 +589,10 @@ impl TransactionsWeb3Dal<'_, '_> {
             initiator_address.as_bytes(),
             committed_next_nonce as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("next_nonce_by_initiator_account")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("committed_next_nonce", &committed_next_nonce)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| row.nonce as u64)
@@ -304,6 +611,53 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(U256::from(pending_nonce))
     }
 
+    /// Returns L2 transactions of the specified account that are not included into a miniblock yet, ordered by nonce.
+    /// Transactions rejected by the state keeper are returned as well.
+    pub async fn get_unexecuted_l2_txs_by_initiator(
+        &mut self,
+        initiator_address: Address,
+        limit: usize,
+    ) -> sqlx::Result<Vec<UnexecutedL2Tx>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                nonce AS "nonce!",
+                max_fee_per_gas AS "max_fee_per_gas!",
+                in_mempool,
+                error
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+            ORDER BY
+                nonce
+            LIMIT
+                $2
+            "#,
+            initiator_address.as_bytes(),
+            limit as i64
+        )
+        .instrument("get_unexecuted_l2_txs_by_initiator")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| UnexecutedL2Tx {
+                hash: H256::from_slice(&row.hash),
+                nonce: Nonce(row.nonce as u32),
+                max_fee_per_gas: bigdecimal_to_u256(row.max_fee_per_gas),
+                in_mempool: row.in_mempool,
+                error: row.error,
+            })
+            .collect())
+    }
+
     /// Returns the server transactions (not API ones) from a certain miniblock.
     /// Returns an empty list if the miniblock doesn't exist.
     pub async fn get_raw_miniblock_transactions(
@@ -324,7 +678,9 @@ impl TransactionsWeb3Dal<'_, '_> {
             "#,
             miniblock.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_raw_miniblock_transactions")
+        .with_arg("miniblock", &miniblock)
+        .fetch_all(self.storage)
         .await?;
 
         Ok(rows.into_iter().map(Into::into).collect())
@@ -336,8 +692,8 @@ mod tests {
     use std::collections::HashMap;
 
     use zksync_types::{
-        block::MiniblockHasher, fee::TransactionExecutionMetrics, l2::L2Tx, Nonce, ProtocolVersion,
-        ProtocolVersionId,
+        block::MiniblockHasher, fee::TransactionExecutionMetrics, l2::L2Tx,
+        tx::TransactionExecutionResult, ProtocolVersion, ProtocolVersionId,
     };
 
     use super::*;
@@ -441,6 +797,84 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn getting_transaction_timeline() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+
+        let timeline = conn
+            .transactions_web3_dal()
+            .get_transaction_timeline(tx_hash)
+            .await
+            .unwrap()
+            .expect("no timeline");
+        assert_eq!(timeline.hash, tx_hash);
+        assert_eq!(timeline.status, TransactionStatus::Pending);
+        assert!(!timeline.is_l1_originated);
+        assert!(timeline.validated_at.is_some());
+        assert_eq!(timeline.added_to_mempool_at, None);
+        assert_eq!(timeline.executed_at, None);
+        assert_eq!(timeline.miniblock_number, None);
+
+        let mempool_txs = conn
+            .transactions_dal()
+            .sync_mempool(&[], &[], 0, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(mempool_txs.len(), 1);
+        let timeline = conn
+            .transactions_web3_dal()
+            .get_transaction_timeline(tx_hash)
+            .await
+            .unwrap()
+            .expect("no timeline");
+        let added_to_mempool_at = timeline.added_to_mempool_at.expect("not in mempool");
+
+        let executed_at = NaiveDateTime::from_timestamp_opt(1_700_000_000, 0).unwrap();
+        let executed_at = DateTime::<Utc>::from_naive_utc_and_offset(executed_at, Utc);
+        let tx_result = TransactionExecutionResult {
+            executed_at: Some(executed_at),
+            ..mock_execution_result(tx)
+        };
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], U256::from(1))
+            .await;
+
+        let timeline = conn
+            .transactions_web3_dal()
+            .get_transaction_timeline(tx_hash)
+            .await
+            .unwrap()
+            .expect("no timeline");
+        assert_eq!(timeline.status, TransactionStatus::Included);
+        assert_eq!(timeline.miniblock_number, Some(MiniblockNumber(1)));
+        assert_eq!(timeline.added_to_mempool_at, Some(added_to_mempool_at));
+        assert_eq!(timeline.executed_at, Some(executed_at));
+        assert!(timeline.miniblock_sealed_at.is_some());
+        assert_eq!(timeline.l1_batch_number, None);
+        assert_eq!(timeline.l1_batch_sealed_at, None);
+        assert_eq!(timeline.committed_at, None);
+
+        let missing_timeline = conn
+            .transactions_web3_dal()
+            .get_transaction_timeline(H256::repeat_byte(1))
+            .await
+            .unwrap();
+        assert!(missing_timeline.is_none());
+    }
+
     #[tokio::test]
     async fn getting_receipts() {
         let connection_pool = ConnectionPool::test_pool().await;
@@ -565,6 +999,73 @@ mod tests {
         assert_eq!(next_nonce, 2.into());
     }
 
+    #[tokio::test]
+    async fn getting_unexecuted_l2_txs_by_initiator() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let initiator = Address::repeat_byte(1);
+        let mut tx_by_nonce = HashMap::new();
+        for nonce in [3, 0, 1] {
+            let mut tx = mock_l2_transaction();
+            // Changing transaction fields invalidates its signature, but it's OK for test purposes
+            tx.common_data.nonce = Nonce(nonce);
+            tx.common_data.initiator_address = initiator;
+            tx_by_nonce.insert(nonce, tx.clone());
+            conn.transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await;
+        }
+        conn.transactions_dal()
+            .insert_transaction_l2(
+                mock_l2_transaction(),
+                TransactionExecutionMetrics::default(),
+            )
+            .await;
+        conn.transactions_dal()
+            .mark_tx_as_rejected(tx_by_nonce[&1].hash(), "oops")
+            .await;
+
+        let mut miniblock = create_miniblock_header(1);
+        miniblock.l2_tx_count = 1;
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock)
+            .await
+            .unwrap();
+        let executed_txs = [mock_execution_result(tx_by_nonce[&0].clone())];
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(miniblock.number, &executed_txs, 1.into())
+            .await;
+
+        let txs = conn
+            .transactions_web3_dal()
+            .get_unexecuted_l2_txs_by_initiator(initiator, 10)
+            .await
+            .unwrap();
+        let nonces: Vec<_> = txs.iter().map(|tx| tx.nonce).collect();
+        assert_eq!(nonces, [Nonce(1), Nonce(3)]);
+        assert_eq!(txs[0].hash, tx_by_nonce[&1].hash());
+        assert_eq!(txs[0].error.as_deref(), Some("oops"));
+        assert_eq!(txs[1].hash, tx_by_nonce[&3].hash());
+        assert_eq!(txs[1].error, None);
+        assert!(!txs[1].in_mempool);
+        assert_eq!(
+            txs[1].max_fee_per_gas,
+            tx_by_nonce[&3].common_data.fee.max_fee_per_gas
+        );
+
+        let txs = conn
+            .transactions_web3_dal()
+            .get_unexecuted_l2_txs_by_initiator(initiator, 1)
+            .await
+            .unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].nonce, Nonce(1));
+    }
+
     #[tokio::test]
     async fn getting_next_nonce_by_initiator_account_after_snapshot_recovery() {
         // Emulate snapshot recovery: no transactions with past nonces are present in the storage
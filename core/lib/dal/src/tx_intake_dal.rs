@@ -0,0 +1,143 @@
+//! Pauses of L2 transaction intake by the API servers.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use zksync_types::api::idexo::TxIntakePause;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+struct StorageTxIntakePause {
+    id: i64,
+    reason: String,
+    paused_at: NaiveDateTime,
+    resumed_at: Option<NaiveDateTime>,
+}
+
+impl From<StorageTxIntakePause> for TxIntakePause {
+    fn from(pause: StorageTxIntakePause) -> Self {
+        Self {
+            id: pause.id as u32,
+            reason: pause.reason,
+            paused_at: DateTime::<Utc>::from_naive_utc_and_offset(pause.paused_at, Utc),
+            resumed_at: pause
+                .resumed_at
+                .map(|resumed_at| DateTime::<Utc>::from_naive_utc_and_offset(resumed_at, Utc)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TxIntakeDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TxIntakeDal<'_, '_> {
+    /// Pauses transaction intake. Returns the created pause, or `None` if the intake is already paused.
+    pub async fn pause_intake(&mut self, reason: &str) -> sqlx::Result<Option<TxIntakePause>> {
+        let pause = sqlx::query_as!(
+            StorageTxIntakePause,
+            r#"
+            INSERT INTO
+                tx_intake_pauses (reason, paused_at)
+            VALUES
+                ($1, NOW())
+            ON CONFLICT ((resumed_at IS NULL)) WHERE resumed_at IS NULL DO NOTHING
+            RETURNING
+                id,
+                reason,
+                paused_at,
+                resumed_at
+            "#,
+            reason
+        )
+        .instrument("pause_tx_intake")
+        .with_arg("reason", &reason)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(pause.map(Into::into))
+    }
+
+    /// Resumes transaction intake. Returns the resumed pause, or `None` if the intake wasn't paused.
+    pub async fn resume_intake(&mut self) -> sqlx::Result<Option<TxIntakePause>> {
+        let pause = sqlx::query_as!(
+            StorageTxIntakePause,
+            r#"
+            UPDATE tx_intake_pauses
+            SET
+                resumed_at = NOW()
+            WHERE
+                resumed_at IS NULL
+            RETURNING
+                id,
+                reason,
+                paused_at,
+                resumed_at
+            "#
+        )
+        .instrument("resume_tx_intake")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(pause.map(Into::into))
+    }
+
+    pub async fn get_active_pause(&mut self) -> sqlx::Result<Option<TxIntakePause>> {
+        let pause = sqlx::query_as!(
+            StorageTxIntakePause,
+            r#"
+            SELECT
+                id,
+                reason,
+                paused_at,
+                resumed_at
+            FROM
+                tx_intake_pauses
+            WHERE
+                resumed_at IS NULL
+            "#
+        )
+        .instrument("get_active_tx_intake_pause")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(pause.map(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn pausing_and_resuming_tx_intake() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        assert_eq!(conn.tx_intake_dal().get_active_pause().await.unwrap(), None);
+        assert_eq!(conn.tx_intake_dal().resume_intake().await.unwrap(), None);
+
+        let pause = conn
+            .tx_intake_dal()
+            .pause_intake("incident")
+            .await
+            .unwrap()
+            .expect("intake is not paused");
+        assert_eq!(pause.reason, "incident");
+        assert_eq!(pause.resumed_at, None);
+        let second_pause = conn.tx_intake_dal().pause_intake("other").await.unwrap();
+        assert_eq!(second_pause, None);
+        let active_pause = conn.tx_intake_dal().get_active_pause().await.unwrap();
+        assert_eq!(active_pause.as_ref(), Some(&pause));
+
+        let resumed_pause = conn
+            .tx_intake_dal()
+            .resume_intake()
+            .await
+            .unwrap()
+            .expect("intake is not paused");
+        assert_eq!(resumed_pause.id, pause.id);
+        assert!(resumed_pause.resumed_at.is_some());
+        assert_eq!(conn.tx_intake_dal().get_active_pause().await.unwrap(), None);
+
+        let new_pause = conn.tx_intake_dal().pause_intake("again").await.unwrap();
+        assert!(new_pause.is_some_and(|new_pause| new_pause.id > pause.id));
+    }
+}
@@ -0,0 +1,364 @@
+//! Persistence of the L1 batch status webhook dispatcher: its progress and the delivery log of notifications.
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use zksync_types::L1BatchNumber;
+
+use crate::{instrument::InstrumentExt, time_utils::pg_interval_from_duration, StorageProcessor};
+
+/// Webhook notification that is not delivered yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingWebhookDelivery {
+    pub id: i64,
+    pub url: String,
+    pub l1_batch_number: L1BatchNumber,
+    pub status: String,
+    pub payload: serde_json::Value,
+    /// Number of failed delivery attempts so far.
+    pub attempts: u32,
+}
+
+/// Entry of the webhook delivery log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub url: String,
+    pub status: String,
+    pub attempts: u32,
+    /// HTTP status of the last response received from the webhook, if any.
+    pub last_response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct WebhooksDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl WebhooksDal<'_, '_> {
+    /// Returns the next L1 batch number to notify about for all L1 batch statuses with persisted cursors.
+    pub async fn get_cursors(&mut self) -> sqlx::Result<HashMap<String, L1BatchNumber>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                status,
+                next_l1_batch_number
+            FROM
+                webhook_cursors
+            "#
+        )
+        .instrument("get_webhook_cursors")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.status, L1BatchNumber(row.next_l1_batch_number as u32)))
+            .collect())
+    }
+
+    pub async fn set_cursor(
+        &mut self,
+        status: &str,
+        next_l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                webhook_cursors (status, next_l1_batch_number, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (status) DO
+            UPDATE
+            SET
+                next_l1_batch_number = $2,
+                updated_at = NOW()
+            "#,
+            status,
+            i64::from(next_l1_batch_number.0)
+        )
+        .instrument("set_webhook_cursor")
+        .with_arg("status", &status)
+        .with_arg("next_l1_batch_number", &next_l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Enqueues a notification for delivery to the specified URL. Does nothing if the notification
+    /// is already enqueued.
+    pub async fn insert_delivery(
+        &mut self,
+        url: &str,
+        l1_batch_number: L1BatchNumber,
+        status: &str,
+        payload: &serde_json::Value,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                webhook_deliveries (
+                    url,
+                    l1_batch_number,
+                    status,
+                    payload,
+                    next_attempt_at,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW(), NOW(), NOW())
+            ON CONFLICT (url, l1_batch_number, status) DO NOTHING
+            "#,
+            url,
+            i64::from(l1_batch_number.0),
+            status,
+            payload
+        )
+        .instrument("insert_webhook_delivery")
+        .with_arg("url", &url)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("status", &status)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns notifications due for delivery with fewer than `max_attempts` failed attempts,
+    /// in the order they were enqueued. At most `limit_per_url` notifications are returned for each URL,
+    /// so that a URL with a large backlog doesn't crowd out other URLs.
+    pub async fn get_pending_deliveries(
+        &mut self,
+        max_attempts: u32,
+        limit_per_url: usize,
+    ) -> sqlx::Result<Vec<PendingWebhookDelivery>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                url,
+                l1_batch_number,
+                status,
+                payload,
+                attempts
+            FROM
+                (
+                    SELECT
+                        webhook_deliveries.*,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY
+                                url
+                            ORDER BY
+                                id
+                        ) AS url_row_number
+                    FROM
+                        webhook_deliveries
+                    WHERE
+                        delivered_at IS NULL
+                        AND attempts < $1
+                        AND next_attempt_at <= NOW()
+                ) AS pending
+            WHERE
+                url_row_number <= $2
+            ORDER BY
+                id
+            "#,
+            max_attempts as i32,
+            limit_per_url as i64
+        )
+        .instrument("get_pending_webhook_deliveries")
+        .with_arg("max_attempts", &max_attempts)
+        .with_arg("limit_per_url", &limit_per_url)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PendingWebhookDelivery {
+                id: row.id,
+                url: row.url,
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                status: row.status,
+                payload: row.payload,
+                attempts: row.attempts as u32,
+            })
+            .collect())
+    }
+
+    pub async fn mark_delivered(&mut self, id: i64, response_status: u16) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET
+                attempts = attempts + 1,
+                last_response_status = $2,
+                last_error = NULL,
+                delivered_at = NOW(),
+                updated_at = NOW()
+            WHERE
+                id = $1
+            "#,
+            id,
+            i32::from(response_status)
+        )
+        .instrument("mark_webhook_delivered")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a failed delivery attempt; the next attempt is scheduled after `retry_after`.
+    pub async fn record_failed_attempt(
+        &mut self,
+        id: i64,
+        response_status: Option<u16>,
+        error: &str,
+        retry_after: Duration,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET
+                attempts = attempts + 1,
+                last_response_status = $2,
+                last_error = $3,
+                next_attempt_at = NOW() + $4::INTERVAL,
+                updated_at = NOW()
+            WHERE
+                id = $1
+            "#,
+            id,
+            response_status.map(i32::from),
+            error,
+            &pg_interval_from_duration(retry_after)
+        )
+        .instrument("record_failed_webhook_attempt")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the delivery log for notifications about the specified L1 batch.
+    pub async fn get_deliveries(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<WebhookDelivery>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                url,
+                status,
+                attempts,
+                last_response_status,
+                last_error,
+                delivered_at
+            FROM
+                webhook_deliveries
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                id
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_webhook_deliveries")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| WebhookDelivery {
+                url: row.url,
+                status: row.status,
+                attempts: row.attempts as u32,
+                last_response_status: row.last_response_status.map(|status| status as u16),
+                last_error: row.last_error,
+                delivered_at: row
+                    .delivered_at
+                    .map(|time| DateTime::<Utc>::from_naive_utc_and_offset(time, Utc)),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn setting_webhook_cursors() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.webhooks_dal();
+        assert!(dal.get_cursors().await.unwrap().is_empty());
+
+        dal.set_cursor("committed", L1BatchNumber(3)).await.unwrap();
+        dal.set_cursor("proven", L1BatchNumber(2)).await.unwrap();
+        dal.set_cursor("committed", L1BatchNumber(5)).await.unwrap();
+        let expected_cursors = HashMap::from([
+            ("committed".to_owned(), L1BatchNumber(5)),
+            ("proven".to_owned(), L1BatchNumber(2)),
+        ]);
+        assert_eq!(dal.get_cursors().await.unwrap(), expected_cursors);
+    }
+
+    #[tokio::test]
+    async fn tracking_webhook_deliveries() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.webhooks_dal();
+        let payload = serde_json::json!({ "l1BatchNumber": 1 });
+        for url in ["http://first.test/", "http://second.test/"] {
+            dal.insert_delivery(url, L1BatchNumber(1), "committed", &payload)
+                .await
+                .unwrap();
+        }
+        // Repeated insertion is ignored.
+        dal.insert_delivery(
+            "http://first.test/",
+            L1BatchNumber(1),
+            "committed",
+            &payload,
+        )
+        .await
+        .unwrap();
+
+        let pending = dal.get_pending_deliveries(3, 10).await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].url, "http://first.test/");
+        assert_eq!(pending[0].payload, payload);
+        assert_eq!(pending[0].attempts, 0);
+        assert_eq!(pending[1].url, "http://second.test/");
+
+        dal.mark_delivered(pending[0].id, 200).await.unwrap();
+        dal.record_failed_attempt(pending[1].id, Some(503), "unavailable", Duration::ZERO)
+            .await
+            .unwrap();
+        let new_pending = dal.get_pending_deliveries(3, 10).await.unwrap();
+        assert_eq!(new_pending.len(), 1);
+        assert_eq!(new_pending[0].id, pending[1].id);
+        assert_eq!(new_pending[0].attempts, 1);
+        // The delivery has exhausted its attempts.
+        assert!(dal.get_pending_deliveries(1, 10).await.unwrap().is_empty());
+
+        // The next attempt is scheduled in the future.
+        dal.record_failed_attempt(pending[1].id, None, "timeout", Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(dal.get_pending_deliveries(3, 10).await.unwrap().is_empty());
+
+        let deliveries = dal.get_deliveries(L1BatchNumber(1)).await.unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[0].last_response_status, Some(200));
+        assert!(deliveries[0].delivered_at.is_some());
+        assert_eq!(deliveries[1].attempts, 2);
+        assert_eq!(deliveries[1].last_response_status, None);
+        assert_eq!(deliveries[1].last_error.as_deref(), Some("timeout"));
+        assert!(deliveries[1].delivered_at.is_none());
+    }
+}
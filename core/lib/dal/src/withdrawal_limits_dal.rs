@@ -0,0 +1,721 @@
+//! Rate limits on withdrawals initiated on the chain, exemptions from them, and usage of the limits
+//! by transactions. Usage is reserved when a transaction is accepted to the mempool and is replaced
+//! with the actual usage once the transaction is sealed in a miniblock.
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::types::BigDecimal;
+use zksync_types::{
+    api::idexo::{WithdrawalLimit, WithdrawalLimitExemption},
+    Address, H256, U256,
+};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, time_utils::pg_interval_from_duration, StorageProcessor};
+
+#[derive(Debug)]
+struct StorageWithdrawalLimit {
+    l1_token: Vec<u8>,
+    total_limit: Option<BigDecimal>,
+    account_limit: Option<BigDecimal>,
+    window_sec: i64,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<StorageWithdrawalLimit> for WithdrawalLimit {
+    fn from(row: StorageWithdrawalLimit) -> Self {
+        Self {
+            l1_token: Address::from_slice(&row.l1_token),
+            total_limit: row.total_limit.map(bigdecimal_to_u256),
+            account_limit: row.account_limit.map(bigdecimal_to_u256),
+            window_sec: row.window_sec as u64,
+            created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+            updated_at: DateTime::<Utc>::from_naive_utc_and_offset(row.updated_at, Utc),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StorageWithdrawalLimitExemption {
+    address: Vec<u8>,
+    label: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<StorageWithdrawalLimitExemption> for WithdrawalLimitExemption {
+    fn from(row: StorageWithdrawalLimitExemption) -> Self {
+        Self {
+            address: Address::from_slice(&row.address),
+            label: row.label,
+            created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+        }
+    }
+}
+
+/// Amounts of a token withdrawn within a limit window.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WithdrawnAmounts {
+    /// Amount withdrawn by all accounts.
+    pub total: U256,
+    /// Amount withdrawn by the queried account.
+    pub account: U256,
+}
+
+#[derive(Debug)]
+pub struct WithdrawalLimitsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl WithdrawalLimitsDal<'_, '_> {
+    /// Sets a withdrawal limit for the token, or overwrites the existing limit.
+    pub async fn set_limit(
+        &mut self,
+        l1_token: Address,
+        total_limit: Option<U256>,
+        account_limit: Option<U256>,
+        window_sec: u64,
+    ) -> sqlx::Result<WithdrawalLimit> {
+        let limit = sqlx::query_as!(
+            StorageWithdrawalLimit,
+            r#"
+            INSERT INTO
+                withdrawal_limits (
+                    l1_token,
+                    total_limit,
+                    account_limit,
+                    window_sec,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW(), NOW())
+            ON CONFLICT (l1_token) DO
+            UPDATE
+            SET
+                total_limit = $2,
+                account_limit = $3,
+                window_sec = $4,
+                updated_at = NOW()
+            RETURNING
+                l1_token,
+                total_limit,
+                account_limit,
+                window_sec,
+                created_at,
+                updated_at
+            "#,
+            l1_token.as_bytes(),
+            total_limit.map(u256_to_big_decimal),
+            account_limit.map(u256_to_big_decimal),
+            window_sec as i64
+        )
+        .instrument("set_withdrawal_limit")
+        .with_arg("l1_token", &l1_token)
+        .with_arg("window_sec", &window_sec)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(limit.into())
+    }
+
+    /// Removes the withdrawal limit of the token. Returns the removed limit, or `None` if the token
+    /// had no limit.
+    pub async fn remove_limit(
+        &mut self,
+        l1_token: Address,
+    ) -> sqlx::Result<Option<WithdrawalLimit>> {
+        let limit = sqlx::query_as!(
+            StorageWithdrawalLimit,
+            r#"
+            DELETE FROM withdrawal_limits
+            WHERE
+                l1_token = $1
+            RETURNING
+                l1_token,
+                total_limit,
+                account_limit,
+                window_sec,
+                created_at,
+                updated_at
+            "#,
+            l1_token.as_bytes()
+        )
+        .instrument("remove_withdrawal_limit")
+        .with_arg("l1_token", &l1_token)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(limit.map(Into::into))
+    }
+
+    /// Returns all withdrawal limits ordered by the token address.
+    pub async fn get_limits(&mut self) -> sqlx::Result<Vec<WithdrawalLimit>> {
+        let limits = sqlx::query_as!(
+            StorageWithdrawalLimit,
+            r#"
+            SELECT
+                l1_token,
+                total_limit,
+                account_limit,
+                window_sec,
+                created_at,
+                updated_at
+            FROM
+                withdrawal_limits
+            ORDER BY
+                l1_token
+            "#
+        )
+        .instrument("get_withdrawal_limits")
+        .fetch_all(self.storage)
+        .await?;
+        Ok(limits.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns withdrawal limits for the specified tokens. Tokens without a limit are not present
+    /// in the returned map.
+    pub async fn get_limits_for_tokens(
+        &mut self,
+        l1_tokens: &[Address],
+    ) -> sqlx::Result<HashMap<Address, WithdrawalLimit>> {
+        let token_bytes: Vec<_> = l1_tokens.iter().map(Address::as_bytes).collect();
+        let limits = sqlx::query_as!(
+            StorageWithdrawalLimit,
+            r#"
+            SELECT
+                l1_token,
+                total_limit,
+                account_limit,
+                window_sec,
+                created_at,
+                updated_at
+            FROM
+                withdrawal_limits
+            WHERE
+                l1_token = ANY ($1::bytea[])
+            "#,
+            &token_bytes as &[&[u8]]
+        )
+        .instrument("get_withdrawal_limits_for_tokens")
+        .with_arg("l1_tokens.len", &l1_tokens.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(limits
+            .into_iter()
+            .map(|row| {
+                let limit = WithdrawalLimit::from(row);
+                (limit.l1_token, limit)
+            })
+            .collect())
+    }
+
+    /// Exempts the account from withdrawal limits, or updates the label of an existing exemption.
+    pub async fn add_exemption(
+        &mut self,
+        address: Address,
+        label: Option<&str>,
+    ) -> sqlx::Result<WithdrawalLimitExemption> {
+        let exemption = sqlx::query_as!(
+            StorageWithdrawalLimitExemption,
+            r#"
+            INSERT INTO
+                withdrawal_limit_exemptions (address, label, created_at)
+            VALUES
+                ($1, $2, NOW())
+            ON CONFLICT (address) DO
+            UPDATE
+            SET
+                label = $2
+            RETURNING
+                address,
+                label,
+                created_at
+            "#,
+            address.as_bytes(),
+            label
+        )
+        .instrument("add_withdrawal_limit_exemption")
+        .with_arg("address", &address)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(exemption.into())
+    }
+
+    /// Revokes the exemption of the account. Returns the removed exemption, or `None` if the account
+    /// was not exempt.
+    pub async fn remove_exemption(
+        &mut self,
+        address: Address,
+    ) -> sqlx::Result<Option<WithdrawalLimitExemption>> {
+        let exemption = sqlx::query_as!(
+            StorageWithdrawalLimitExemption,
+            r#"
+            DELETE FROM withdrawal_limit_exemptions
+            WHERE
+                address = $1
+            RETURNING
+                address,
+                label,
+                created_at
+            "#,
+            address.as_bytes()
+        )
+        .instrument("remove_withdrawal_limit_exemption")
+        .with_arg("address", &address)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(exemption.map(Into::into))
+    }
+
+    /// Returns all exemptions ordered by the account address.
+    pub async fn get_exemptions(&mut self) -> sqlx::Result<Vec<WithdrawalLimitExemption>> {
+        let exemptions = sqlx::query_as!(
+            StorageWithdrawalLimitExemption,
+            r#"
+            SELECT
+                address,
+                label,
+                created_at
+            FROM
+                withdrawal_limit_exemptions
+            ORDER BY
+                address
+            "#
+        )
+        .instrument("get_withdrawal_limit_exemptions")
+        .fetch_all(self.storage)
+        .await?;
+        Ok(exemptions.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn is_exempt(&mut self, address: Address) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        withdrawal_limit_exemptions
+                    WHERE
+                        address = $1
+                ) AS "exists!"
+            "#,
+            address.as_bytes()
+        )
+        .instrument("is_exempt_from_withdrawal_limits")
+        .with_arg("address", &address)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.exists)
+    }
+
+    /// Locks withdrawal limit usage until the end of the current transaction, so that concurrent checks
+    /// of the limits followed by reserving usage are serialized. Reads of the usage are not blocked.
+    pub async fn lock_usage(&mut self) -> sqlx::Result<()> {
+        assert!(
+            self.storage.in_transaction(),
+            "withdrawal limit usage can only be locked in a transaction"
+        );
+        // Not using `query!` since `sqlx` macros cannot describe `LOCK` statements.
+        sqlx::query("LOCK TABLE withdrawal_limit_usage IN EXCLUSIVE MODE")
+            .instrument("lock_withdrawal_limit_usage")
+            .execute(self.storage)
+            .await?;
+        Ok(())
+    }
+
+    /// Records withdrawals of `(l1_token, amount)` initiated by a transaction, either reserved when the transaction
+    /// is accepted to the mempool, or actual ones once it's sealed in a miniblock. Replaces usage previously
+    /// recorded for the transaction (e.g., the reservation, or usage recorded before a miniblock revert).
+    pub async fn insert_usage(
+        &mut self,
+        tx_hash: H256,
+        initiator_address: Address,
+        withdrawals: &[(Address, U256)],
+    ) -> sqlx::Result<()> {
+        let l1_tokens: Vec<_> = withdrawals
+            .iter()
+            .map(|(l1_token, _)| l1_token.as_bytes())
+            .collect();
+        let amounts: Vec<_> = withdrawals
+            .iter()
+            .map(|&(_, amount)| u256_to_big_decimal(amount))
+            .collect();
+        sqlx::query!(
+            r#"
+            WITH
+                released AS (
+                    DELETE FROM withdrawal_limit_usage
+                    WHERE
+                        tx_hash = $1
+                )
+            INSERT INTO
+                withdrawal_limit_usage (tx_hash, initiator_address, l1_token, amount, created_at)
+            SELECT
+                $1,
+                $2,
+                data_table.l1_token,
+                data_table.amount,
+                NOW()
+            FROM
+                (
+                    SELECT
+                        UNNEST($3::bytea[]) AS l1_token,
+                        UNNEST($4::NUMERIC[]) AS amount
+                ) AS data_table
+            "#,
+            tx_hash.as_bytes(),
+            initiator_address.as_bytes(),
+            &l1_tokens as &[&[u8]],
+            &amounts
+        )
+        .instrument("insert_withdrawal_limit_usage")
+        .with_arg("tx_hash", &tx_hash)
+        .with_arg("withdrawals.len", &withdrawals.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Releases usage recorded for the specified transactions, e.g. ones rejected by the state keeper.
+    /// Returns the number of released records.
+    pub async fn release_usage(&mut self, tx_hashes: &[H256]) -> sqlx::Result<u64> {
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM withdrawal_limit_usage
+            WHERE
+                tx_hash = ANY ($1)
+            "#,
+            &tx_hashes as &[&[u8]]
+        )
+        .instrument("release_withdrawal_limit_usage")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Releases usage of transactions that are no longer stored, e.g. ones evicted from the mempool or replaced
+    /// by another transaction with the same nonce. Returns the number of released records.
+    pub async fn release_orphaned_usage(&mut self) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM withdrawal_limit_usage
+            WHERE
+                NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        transactions
+                    WHERE
+                        transactions.hash = withdrawal_limit_usage.tx_hash
+                )
+            "#
+        )
+        .instrument("release_orphaned_withdrawal_limit_usage")
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Returns amounts of the token withdrawn or reserved within the `window` ending now, excluding usage
+    /// of the `excluded_tx`. Withdrawals of reverted or rejected transactions are not taken into account.
+    pub async fn get_withdrawn_amounts(
+        &mut self,
+        l1_token: Address,
+        account: Address,
+        window: Duration,
+        excluded_tx: Option<H256>,
+    ) -> sqlx::Result<WithdrawnAmounts> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(withdrawal_limit_usage.amount), 0) AS "total!",
+                COALESCE(
+                    SUM(withdrawal_limit_usage.amount) FILTER (
+                        WHERE
+                            withdrawal_limit_usage.initiator_address = $2
+                    ),
+                    0
+                ) AS "account!"
+            FROM
+                withdrawal_limit_usage
+                INNER JOIN transactions ON transactions.hash = withdrawal_limit_usage.tx_hash
+            WHERE
+                withdrawal_limit_usage.l1_token = $1
+                AND withdrawal_limit_usage.created_at > NOW() - $3::INTERVAL
+                AND withdrawal_limit_usage.tx_hash IS DISTINCT FROM $4
+                AND transactions.error IS NULL
+            "#,
+            l1_token.as_bytes(),
+            account.as_bytes(),
+            pg_interval_from_duration(window),
+            excluded_tx.as_ref().map(H256::as_bytes)
+        )
+        .instrument("get_withdrawn_amounts")
+        .with_arg("l1_token", &l1_token)
+        .with_arg("account", &account)
+        .with_arg("window", &window)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(WithdrawnAmounts {
+            total: bigdecimal_to_u256(row.total),
+            account: bigdecimal_to_u256(row.account),
+        })
+    }
+
+    /// Removes usage records that are older than the longest window among the configured limits.
+    /// Returns the number of removed records.
+    pub async fn prune_usage(&mut self) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM withdrawal_limit_usage
+            WHERE
+                created_at < NOW() - (
+                    SELECT
+                        COALESCE(MAX(window_sec), 0)
+                    FROM
+                        withdrawal_limits
+                ) * INTERVAL '1 second'
+            "#
+        )
+        .instrument("prune_withdrawal_limit_usage")
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        tx::tx_execution_info::TxExecutionStatus, MiniblockNumber, ProtocolVersion,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{
+            create_miniblock_header, mock_execution_result, mock_l2_transaction,
+            mock_tx_execution_metrics,
+        },
+        ConnectionPool,
+    };
+
+    const WINDOW: Duration = Duration::from_secs(3_600);
+
+    #[tokio::test]
+    async fn managing_limits_and_exemptions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let token = Address::repeat_byte(1);
+        let other_token = Address::repeat_byte(2);
+
+        assert!(conn
+            .withdrawal_limits_dal()
+            .get_limits()
+            .await
+            .unwrap()
+            .is_empty());
+        let limit = conn
+            .withdrawal_limits_dal()
+            .set_limit(token, Some(1_000.into()), None, 3_600)
+            .await
+            .unwrap();
+        assert_eq!(limit.total_limit, Some(1_000.into()));
+        assert_eq!(limit.account_limit, None);
+        let limit = conn
+            .withdrawal_limits_dal()
+            .set_limit(token, None, Some(100.into()), 60)
+            .await
+            .unwrap();
+        assert_eq!(limit.total_limit, None);
+        assert_eq!(limit.account_limit, Some(100.into()));
+        assert_eq!(limit.window_sec, 60);
+
+        let limits = conn
+            .withdrawal_limits_dal()
+            .get_limits_for_tokens(&[token, other_token])
+            .await
+            .unwrap();
+        assert_eq!(limits, HashMap::from([(token, limit.clone())]));
+        let removed = conn
+            .withdrawal_limits_dal()
+            .remove_limit(token)
+            .await
+            .unwrap();
+        assert_eq!(removed, Some(limit));
+        assert!(conn
+            .withdrawal_limits_dal()
+            .get_limits()
+            .await
+            .unwrap()
+            .is_empty());
+
+        let account = Address::repeat_byte(0x11);
+        assert!(!conn
+            .withdrawal_limits_dal()
+            .is_exempt(account)
+            .await
+            .unwrap());
+        conn.withdrawal_limits_dal()
+            .add_exemption(account, None)
+            .await
+            .unwrap();
+        let exemption = conn
+            .withdrawal_limits_dal()
+            .add_exemption(account, Some("liquidity manager"))
+            .await
+            .unwrap();
+        assert_eq!(exemption.label.as_deref(), Some("liquidity manager"));
+        assert!(conn
+            .withdrawal_limits_dal()
+            .is_exempt(account)
+            .await
+            .unwrap());
+        assert_eq!(
+            conn.withdrawal_limits_dal().get_exemptions().await.unwrap(),
+            [exemption.clone()]
+        );
+        let removed = conn
+            .withdrawal_limits_dal()
+            .remove_exemption(account)
+            .await
+            .unwrap();
+        assert_eq!(removed, Some(exemption));
+        assert!(!conn
+            .withdrawal_limits_dal()
+            .is_exempt(account)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn computing_withdrawn_amounts() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let token = Address::repeat_byte(1);
+
+        let tx = mock_l2_transaction();
+        let reverted_tx = mock_l2_transaction();
+        let initiator = tx.initiator_account();
+        for tx in [&tx, &reverted_tx] {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+                .await;
+        }
+        conn.withdrawal_limits_dal()
+            .insert_usage(
+                tx.hash(),
+                initiator,
+                &[(token, 100.into()), (token, 50.into())],
+            )
+            .await
+            .unwrap();
+        conn.withdrawal_limits_dal()
+            .insert_usage(reverted_tx.hash(), initiator, &[(token, 10.into())])
+            .await
+            .unwrap();
+        // Usage reserved by pending transactions is taken into account.
+        let amounts = conn
+            .withdrawal_limits_dal()
+            .get_withdrawn_amounts(token, initiator, WINDOW, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            amounts,
+            WithdrawnAmounts {
+                total: 160.into(),
+                account: 160.into(),
+            }
+        );
+        let amounts = conn
+            .withdrawal_limits_dal()
+            .get_withdrawn_amounts(token, initiator, WINDOW, Some(tx.hash()))
+            .await
+            .unwrap();
+        assert_eq!(amounts.total, 10.into());
+
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let mut reverted_execution_result = mock_execution_result(reverted_tx.clone());
+        reverted_execution_result.execution_status = TxExecutionStatus::Failure;
+        let execution_results = [mock_execution_result(tx.clone()), reverted_execution_result];
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &execution_results, 1.into())
+            .await;
+        // Usage of reverted transactions is not taken into account either.
+        let amounts = conn
+            .withdrawal_limits_dal()
+            .get_withdrawn_amounts(token, initiator, WINDOW, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            amounts,
+            WithdrawnAmounts {
+                total: 150.into(),
+                account: 150.into(),
+            }
+        );
+        let other_account = Address::repeat_byte(0x22);
+        let amounts = conn
+            .withdrawal_limits_dal()
+            .get_withdrawn_amounts(token, other_account, WINDOW, None)
+            .await
+            .unwrap();
+        assert_eq!(amounts.total, 150.into());
+        assert_eq!(amounts.account, U256::zero());
+
+        // Recorded usage replaces the previously recorded usage of the transaction.
+        conn.withdrawal_limits_dal()
+            .insert_usage(tx.hash(), initiator, &[(token, 100.into())])
+            .await
+            .unwrap();
+        // Usage of unknown transactions is not taken into account.
+        conn.withdrawal_limits_dal()
+            .insert_usage(H256::repeat_byte(0xff), initiator, &[(token, 1_000.into())])
+            .await
+            .unwrap();
+        let amounts = conn
+            .withdrawal_limits_dal()
+            .get_withdrawn_amounts(token, initiator, WINDOW, None)
+            .await
+            .unwrap();
+        assert_eq!(amounts.total, 100.into());
+
+        let released = conn
+            .withdrawal_limits_dal()
+            .release_orphaned_usage()
+            .await
+            .unwrap();
+        assert_eq!(released, 1);
+        let released = conn
+            .withdrawal_limits_dal()
+            .release_usage(&[tx.hash()])
+            .await
+            .unwrap();
+        assert_eq!(released, 1);
+        let amounts = conn
+            .withdrawal_limits_dal()
+            .get_withdrawn_amounts(token, initiator, WINDOW, None)
+            .await
+            .unwrap();
+        assert_eq!(amounts, WithdrawnAmounts::default());
+
+        // Usage records within the longest window are retained.
+        conn.withdrawal_limits_dal()
+            .set_limit(token, Some(1_000.into()), None, WINDOW.as_secs())
+            .await
+            .unwrap();
+        let pruned = conn.withdrawal_limits_dal().prune_usage().await.unwrap();
+        assert_eq!(pruned, 0);
+    }
+}
@@ -0,0 +1,505 @@
+use std::str::FromStr;
+
+use sqlx::types::BigDecimal;
+use strum::{Display, EnumString};
+use zksync_types::{Address, L1BatchNumber, H256, U256};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Status of a withdrawal tracked by the withdrawal finalizer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+pub enum WithdrawalStatus {
+    /// The withdrawal waits to be finalized.
+    #[strum(serialize = "pending")]
+    Pending,
+    /// The finalization transaction is sent, but is not mined yet.
+    #[strum(serialize = "sent")]
+    Sent,
+    /// The withdrawal is finalized, either by the finalizer or by the user.
+    #[strum(serialize = "finalized")]
+    Finalized,
+    /// The withdrawal could not be finalized after the maximum number of attempts.
+    #[strum(serialize = "failed")]
+    Failed,
+}
+
+/// Withdrawal from the chain in an executed L1 batch together with the data necessary to finalize it
+/// on the settlement layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Withdrawal {
+    pub l1_batch_number: L1BatchNumber,
+    /// Index of the L2-to-L1 log with the withdrawal message in the L1 batch.
+    pub l2_message_index: u32,
+    pub tx_number_in_batch: u16,
+    /// Hash of the transaction initiating the withdrawal.
+    pub tx_hash: H256,
+    /// Contract that has sent the withdrawal message (the base token or a bridge contract).
+    pub sender: Address,
+    /// Settlement layer address of the withdrawn token; the zero address for the base token.
+    pub l1_token: Address,
+    pub l1_receiver: Address,
+    pub amount: U256,
+    pub message: Vec<u8>,
+    pub merkle_proof: Vec<H256>,
+}
+
+#[derive(Debug)]
+struct StorageWithdrawal {
+    l1_batch_number: i64,
+    l2_message_index: i32,
+    tx_number_in_batch: i32,
+    tx_hash: Vec<u8>,
+    sender: Vec<u8>,
+    l1_token: Vec<u8>,
+    l1_receiver: Vec<u8>,
+    amount: BigDecimal,
+    message: Vec<u8>,
+    merkle_proof: Vec<u8>,
+}
+
+impl From<StorageWithdrawal> for Withdrawal {
+    fn from(row: StorageWithdrawal) -> Self {
+        Self {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            l2_message_index: row.l2_message_index as u32,
+            tx_number_in_batch: row.tx_number_in_batch as u16,
+            tx_hash: H256::from_slice(&row.tx_hash),
+            sender: Address::from_slice(&row.sender),
+            l1_token: Address::from_slice(&row.l1_token),
+            l1_receiver: Address::from_slice(&row.l1_receiver),
+            amount: bigdecimal_to_u256(row.amount),
+            message: row.message,
+            merkle_proof: row.merkle_proof.chunks(32).map(H256::from_slice).collect(),
+        }
+    }
+}
+
+/// Withdrawals tracked by the withdrawal finalizer.
+#[derive(Debug)]
+pub struct WithdrawalsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl WithdrawalsDal<'_, '_> {
+    /// Inserts new pending withdrawals. Already known withdrawals are ignored.
+    pub async fn insert_withdrawals(&mut self, withdrawals: &[Withdrawal]) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        for withdrawal in withdrawals {
+            let merkle_proof: Vec<_> = withdrawal
+                .merkle_proof
+                .iter()
+                .flat_map(|hash| hash.as_bytes())
+                .copied()
+                .collect();
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    withdrawals (
+                        l1_batch_number,
+                        l2_message_index,
+                        tx_number_in_batch,
+                        tx_hash,
+                        sender,
+                        l1_token,
+                        l1_receiver,
+                        amount,
+                        message,
+                        merkle_proof,
+                        status,
+                        created_at,
+                        updated_at
+                    )
+                VALUES
+                    ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW(), NOW())
+                ON CONFLICT (l1_batch_number, l2_message_index) DO NOTHING
+                "#,
+                i64::from(withdrawal.l1_batch_number.0),
+                withdrawal.l2_message_index as i32,
+                i32::from(withdrawal.tx_number_in_batch),
+                withdrawal.tx_hash.as_bytes(),
+                withdrawal.sender.as_bytes(),
+                withdrawal.l1_token.as_bytes(),
+                withdrawal.l1_receiver.as_bytes(),
+                u256_to_big_decimal(withdrawal.amount),
+                &withdrawal.message,
+                &merkle_proof,
+                WithdrawalStatus::Pending.to_string()
+            )
+            .instrument("insert_withdrawals")
+            .with_arg("l1_batch_number", &withdrawal.l1_batch_number)
+            .with_arg("l2_message_index", &withdrawal.l2_message_index)
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await
+    }
+
+    /// Returns the greatest number of an L1 batch with a tracked withdrawal.
+    pub async fn get_last_l1_batch_with_withdrawals(
+        &mut self,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number"
+            FROM
+                withdrawals
+            "#
+        )
+        .instrument("get_last_l1_batch_with_withdrawals")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Returns the oldest pending withdrawals of the specified tokens.
+    pub async fn get_pending_withdrawals(
+        &mut self,
+        l1_tokens: &[Address],
+        limit: usize,
+    ) -> sqlx::Result<Vec<Withdrawal>> {
+        let l1_tokens: Vec<_> = l1_tokens.iter().map(Address::as_bytes).collect();
+        let rows = sqlx::query_as!(
+            StorageWithdrawal,
+            r#"
+            SELECT
+                l1_batch_number,
+                l2_message_index,
+                tx_number_in_batch,
+                tx_hash,
+                sender,
+                l1_token,
+                l1_receiver,
+                amount,
+                message,
+                merkle_proof
+            FROM
+                withdrawals
+            WHERE
+                status = $1
+                AND l1_token = ANY ($2)
+            ORDER BY
+                l1_batch_number,
+                l2_message_index
+            LIMIT
+                $3
+            "#,
+            WithdrawalStatus::Pending.to_string(),
+            &l1_tokens as &[&[u8]],
+            limit as i64
+        )
+        .instrument("get_pending_withdrawals")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the total amount of withdrawals of the specified token that are neither finalized nor being finalized,
+    /// i.e., the amount burned on the chain, but still locked on the settlement layer.
+    pub async fn get_unfinalized_withdrawals_amount(
+        &mut self,
+        l1_token: Address,
+    ) -> sqlx::Result<U256> {
+        let statuses = [
+            WithdrawalStatus::Pending.to_string(),
+            WithdrawalStatus::Failed.to_string(),
+        ];
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(amount), 0) AS "amount!"
+            FROM
+                withdrawals
+            WHERE
+                l1_token = $1
+                AND status = ANY ($2)
+            "#,
+            l1_token.as_bytes(),
+            &statuses
+        )
+        .instrument("get_unfinalized_withdrawals_amount")
+        .with_arg("l1_token", &l1_token)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(bigdecimal_to_u256(row.amount))
+    }
+
+    /// Returns withdrawals with sent finalization transactions together with the IDs of the transactions
+    /// (see [`SettlementTxsDal`](crate::settlement_txs_dal::SettlementTxsDal)).
+    pub async fn get_sent_withdrawals(&mut self) -> sqlx::Result<Vec<(u32, Withdrawal)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                l2_message_index,
+                tx_number_in_batch,
+                tx_hash,
+                sender,
+                l1_token,
+                l1_receiver,
+                amount,
+                message,
+                merkle_proof,
+                finalization_tx_id AS "finalization_tx_id!"
+            FROM
+                withdrawals
+            WHERE
+                status = $1
+            ORDER BY
+                l1_batch_number,
+                l2_message_index
+            "#,
+            WithdrawalStatus::Sent.to_string()
+        )
+        .instrument("get_sent_withdrawals")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let withdrawal = StorageWithdrawal {
+                    l1_batch_number: row.l1_batch_number,
+                    l2_message_index: row.l2_message_index,
+                    tx_number_in_batch: row.tx_number_in_batch,
+                    tx_hash: row.tx_hash,
+                    sender: row.sender,
+                    l1_token: row.l1_token,
+                    l1_receiver: row.l1_receiver,
+                    amount: row.amount,
+                    message: row.message,
+                    merkle_proof: row.merkle_proof,
+                };
+                (row.finalization_tx_id as u32, withdrawal.into())
+            })
+            .collect())
+    }
+
+    /// Marks withdrawals as being finalized by the transaction with the specified ID.
+    pub async fn mark_withdrawals_as_sent(
+        &mut self,
+        withdrawals: &[Withdrawal],
+        finalization_tx_id: u32,
+    ) -> sqlx::Result<()> {
+        let (l1_batch_numbers, message_indices): (Vec<_>, Vec<_>) = withdrawals
+            .iter()
+            .map(|withdrawal| {
+                (
+                    i64::from(withdrawal.l1_batch_number.0),
+                    withdrawal.l2_message_index as i32,
+                )
+            })
+            .unzip();
+        sqlx::query!(
+            r#"
+            UPDATE withdrawals
+            SET
+                status = $1,
+                finalization_tx_id = $2,
+                finalization_tx_hash = NULL,
+                attempts = attempts + 1,
+                updated_at = NOW()
+            WHERE
+                (l1_batch_number, l2_message_index) IN (
+                    SELECT
+                        *
+                    FROM
+                        UNNEST($3::BIGINT[], $4::INT[])
+                )
+            "#,
+            WithdrawalStatus::Sent.to_string(),
+            i64::from(finalization_tx_id),
+            &l1_batch_numbers,
+            &message_indices
+        )
+        .instrument("mark_withdrawals_as_sent")
+        .with_arg("withdrawals.len", &withdrawals.len())
+        .with_arg("finalization_tx_id", &finalization_tx_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Marks a withdrawal as finalized. `finalization_tx_hash` is the hash of the mined transaction that has
+    /// finalized the withdrawal; it's `None` if the withdrawal was finalized by a third party.
+    pub async fn mark_withdrawal_as_finalized(
+        &mut self,
+        withdrawal: &Withdrawal,
+        finalization_tx_hash: Option<H256>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE withdrawals
+            SET
+                status = $1,
+                finalization_tx_hash = $2,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $3
+                AND l2_message_index = $4
+            "#,
+            WithdrawalStatus::Finalized.to_string(),
+            finalization_tx_hash.as_ref().map(H256::as_bytes),
+            i64::from(withdrawal.l1_batch_number.0),
+            withdrawal.l2_message_index as i32
+        )
+        .instrument("mark_withdrawal_as_finalized")
+        .with_arg("l1_batch_number", &withdrawal.l1_batch_number)
+        .with_arg("l2_message_index", &withdrawal.l2_message_index)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns a withdrawal that was not finalized by the sent transaction to the pending status, or marks it
+    /// as failed if it has reached `max_attempts` finalization attempts. Returns the new status.
+    pub async fn reset_withdrawal(
+        &mut self,
+        withdrawal: &Withdrawal,
+        max_attempts: u32,
+    ) -> sqlx::Result<WithdrawalStatus> {
+        let row = sqlx::query!(
+            r#"
+            UPDATE withdrawals
+            SET
+                status = (
+                    CASE
+                        WHEN attempts >= $1 THEN $2
+                        ELSE $3
+                    END
+                ),
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $4
+                AND l2_message_index = $5
+            RETURNING
+                status
+            "#,
+            max_attempts as i32,
+            WithdrawalStatus::Failed.to_string(),
+            WithdrawalStatus::Pending.to_string(),
+            i64::from(withdrawal.l1_batch_number.0),
+            withdrawal.l2_message_index as i32
+        )
+        .instrument("reset_withdrawal")
+        .with_arg("l1_batch_number", &withdrawal.l1_batch_number)
+        .with_arg("l2_message_index", &withdrawal.l2_message_index)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(WithdrawalStatus::from_str(&row.status).expect("invalid withdrawal status in DB"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        settlement_txs_dal::{SettlementTx, SignedSettlementTx},
+        ConnectionPool,
+    };
+
+    fn mock_withdrawal(
+        l1_batch_number: u32,
+        l2_message_index: u32,
+        l1_token: Address,
+    ) -> Withdrawal {
+        Withdrawal {
+            l1_batch_number: L1BatchNumber(l1_batch_number),
+            l2_message_index,
+            tx_number_in_batch: 3,
+            tx_hash: H256::repeat_byte(1),
+            sender: Address::repeat_byte(2),
+            l1_token,
+            l1_receiver: Address::repeat_byte(3),
+            amount: U256::from(1_000_000),
+            message: vec![4; 56],
+            merkle_proof: vec![H256::repeat_byte(5), H256::repeat_byte(6)],
+        }
+    }
+
+    #[tokio::test]
+    async fn withdrawal_lifecycle() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.withdrawals_dal();
+        assert_eq!(
+            dal.get_last_l1_batch_with_withdrawals().await.unwrap(),
+            None
+        );
+
+        let token = Address::repeat_byte(0x10);
+        let other_token = Address::repeat_byte(0x20);
+        let withdrawals = [
+            mock_withdrawal(1, 0, token),
+            mock_withdrawal(1, 2, other_token),
+            mock_withdrawal(2, 1, token),
+        ];
+        dal.insert_withdrawals(&withdrawals).await.unwrap();
+        // Repeated insertion must be a no-op.
+        dal.insert_withdrawals(&withdrawals[..1]).await.unwrap();
+        assert_eq!(
+            dal.get_last_l1_batch_with_withdrawals().await.unwrap(),
+            Some(L1BatchNumber(2))
+        );
+
+        let pending = dal.get_pending_withdrawals(&[token], 10).await.unwrap();
+        assert_eq!(pending, [withdrawals[0].clone(), withdrawals[2].clone()]);
+        let pending = dal.get_pending_withdrawals(&[token], 1).await.unwrap();
+        assert_eq!(pending, [withdrawals[0].clone()]);
+        let unfinalized_amount = dal.get_unfinalized_withdrawals_amount(token).await.unwrap();
+        assert_eq!(unfinalized_amount, U256::from(2_000_000));
+
+        let settlement_tx = SettlementTx {
+            id: 0,
+            nonce: 0,
+            contract_address: Address::repeat_byte(0x11),
+            calldata: vec![1, 2, 3],
+            gas_limit: 400_000,
+        };
+        let signed_tx = SignedSettlementTx {
+            tx_hash: H256::repeat_byte(0xff),
+            raw_tx: vec![0xff; 10],
+            max_fee_per_gas: 100.into(),
+            max_priority_fee_per_gas: 1.into(),
+        };
+        let tx_id = conn
+            .settlement_txs_dal()
+            .insert_tx("withdrawal_finalizer", &settlement_tx, &signed_tx)
+            .await
+            .unwrap();
+        let mut dal = conn.withdrawals_dal();
+        let sent = [withdrawals[0].clone(), withdrawals[2].clone()];
+        dal.mark_withdrawals_as_sent(&sent, tx_id).await.unwrap();
+        let pending = dal.get_pending_withdrawals(&[token], 10).await.unwrap();
+        assert_eq!(pending, []);
+        let sent_withdrawals = dal.get_sent_withdrawals().await.unwrap();
+        assert_eq!(
+            sent_withdrawals,
+            [(tx_id, sent[0].clone()), (tx_id, sent[1].clone())]
+        );
+
+        let unfinalized_amount = dal.get_unfinalized_withdrawals_amount(token).await.unwrap();
+        assert_eq!(unfinalized_amount, U256::zero());
+
+        dal.mark_withdrawal_as_finalized(&sent[0], Some(signed_tx.tx_hash))
+            .await
+            .unwrap();
+        let status = dal.reset_withdrawal(&sent[1], 2).await.unwrap();
+        assert_eq!(status, WithdrawalStatus::Pending);
+        assert_eq!(dal.get_sent_withdrawals().await.unwrap(), []);
+        let pending = dal.get_pending_withdrawals(&[token], 10).await.unwrap();
+        assert_eq!(pending, [sent[1].clone()]);
+
+        dal.mark_withdrawals_as_sent(&sent[1..], tx_id)
+            .await
+            .unwrap();
+        let status = dal.reset_withdrawal(&sent[1], 2).await.unwrap();
+        assert_eq!(status, WithdrawalStatus::Failed);
+        let pending = dal.get_pending_withdrawals(&[token], 10).await.unwrap();
+        assert_eq!(pending, []);
+        let unfinalized_amount = dal.get_unfinalized_withdrawals_amount(token).await.unwrap();
+        assert_eq!(unfinalized_amount, U256::from(1_000_000));
+    }
+}
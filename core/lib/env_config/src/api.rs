@@ -51,7 +51,7 @@ mod tests {
     use std::num::NonZeroU32;
 
     use super::*;
-    use crate::test_utils::{hash, EnvMutex};
+    use crate::test_utils::{addr, hash, EnvMutex};
 
     static MUTEX: EnvMutex = EnvMutex::new();
 
@@ -77,6 +77,9 @@ mod tests {
                 estimate_gas_acceptable_overestimation: 1000,
                 l1_to_l2_transactions_compatibility_mode: true,
                 max_tx_size: 1000000,
+                max_factory_deps: Some(16),
+                max_calldata_size: Some(500000),
+                soft_tx_limits_ratio: Some(0.9),
                 vm_execution_cache_misses_limit: None,
                 vm_concurrency_limit: Some(512),
                 factory_deps_cache_size_mb: Some(128),
@@ -87,6 +90,26 @@ mod tests {
                 max_response_body_size_mb: Some(10),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
                 tree_api_url: None,
+                inclusion_horizon_batches: Some(3),
+                operator_namespace_enabled: true,
+                operator_auth_token: Some("operator-secret".into()),
+                admin_namespace_enabled: true,
+                verify_content_hashes: true,
+                name_registry_addr: Some(addr("0x0000000000000000000000000000000000008100")),
+                resolve_names_in_params: true,
+                multicall_compute_budget_ms: Some(500),
+                pubsub_lag_warning_threshold: Some(64),
+                pubsub_lag_close_threshold: Some(256),
+                shadow_api_url: Some("http://127.0.0.1:3051".to_owned()),
+                shadow_requests_percentage: Some(5.0),
+                sandbox_warm_pool_enabled: true,
+                disabled_methods: vec![
+                    "debug_traceBlockByNumber".to_owned(),
+                    "debug_traceBlockByHash".to_owned(),
+                ],
+                archive_calls_enabled: true,
+                pre_eip155_txs_allowed: false,
+                pre_eip155_tx_senders: vec![addr("0x3fab184622dc19b6109349b94811493bf2a45362")],
             },
             contract_verification: ContractVerificationApiConfig {
                 port: 3070,
@@ -97,7 +120,12 @@ mod tests {
                 pushgateway_url: "http://127.0.0.1:9091".into(),
                 push_interval_ms: Some(100),
             },
-            healthcheck: HealthCheckConfig { port: 8081 },
+            healthcheck: HealthCheckConfig {
+                port: 8081,
+                state_keeper_max_lag_sec: Some(300),
+                tree_max_lag: Some(10),
+                eth_sender_max_inflight_txs: None,
+            },
             merkle_tree: MerkleTreeApiConfig { port: 8082 },
         }
     }
@@ -122,6 +150,9 @@ mod tests {
             API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
             API_WEB3_JSON_RPC_L1_TO_L2_TRANSACTIONS_COMPATIBILITY_MODE=true
             API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
+            API_WEB3_JSON_RPC_MAX_FACTORY_DEPS=16
+            API_WEB3_JSON_RPC_MAX_CALLDATA_SIZE=500000
+            API_WEB3_JSON_RPC_SOFT_TX_LIMITS_RATIO=0.9
             API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
             API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
             API_WEB3_JSON_RPC_INITIAL_WRITES_CACHE_SIZE_MB=32
@@ -129,6 +160,22 @@ mod tests {
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
+            API_WEB3_JSON_RPC_INCLUSION_HORIZON_BATCHES=3
+            API_WEB3_JSON_RPC_OPERATOR_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_OPERATOR_AUTH_TOKEN="operator-secret"
+            API_WEB3_JSON_RPC_ADMIN_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_VERIFY_CONTENT_HASHES=true
+            API_WEB3_JSON_RPC_NAME_REGISTRY_ADDR="0x0000000000000000000000000000000000008100"
+            API_WEB3_JSON_RPC_RESOLVE_NAMES_IN_PARAMS=true
+            API_WEB3_JSON_RPC_MULTICALL_COMPUTE_BUDGET_MS=500
+            API_WEB3_JSON_RPC_PUBSUB_LAG_WARNING_THRESHOLD=64
+            API_WEB3_JSON_RPC_PUBSUB_LAG_CLOSE_THRESHOLD=256
+            API_WEB3_JSON_RPC_SHADOW_API_URL="http://127.0.0.1:3051"
+            API_WEB3_JSON_RPC_SHADOW_REQUESTS_PERCENTAGE=5.0
+            API_WEB3_JSON_RPC_SANDBOX_WARM_POOL_ENABLED=true
+            API_WEB3_JSON_RPC_DISABLED_METHODS="debug_traceBlockByNumber,debug_traceBlockByHash"
+            API_WEB3_JSON_RPC_ARCHIVE_CALLS_ENABLED=true
+            API_WEB3_JSON_RPC_PRE_EIP155_TX_SENDERS="0x3fab184622dc19b6109349b94811493bf2a45362"
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
@@ -136,6 +183,8 @@ mod tests {
             API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             API_PROMETHEUS_PUSH_INTERVAL_MS=100
             API_HEALTHCHECK_PORT=8081
+            API_HEALTHCHECK_STATE_KEEPER_MAX_LAG_SEC=300
+            API_HEALTHCHECK_TREE_MAX_LAG=10
             API_MERKLE_TREE_PORT=8082
         "#;
         lock.set_env(config);
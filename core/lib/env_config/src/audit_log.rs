@@ -0,0 +1,36 @@
+use zksync_config::AuditLogConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for AuditLogConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("audit_log", "AUDIT_LOG_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            AUDIT_LOG_FILE_PATH="/var/log/idexo/audit.jsonl"
+        "#;
+        lock.set_env(config);
+
+        let actual = AuditLogConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            AuditLogConfig {
+                file_path: "/var/log/idexo/audit.jsonl".to_owned(),
+                polling_interval_ms: 1_000,
+                export_lag_ms: 10_000,
+            }
+        );
+    }
+}
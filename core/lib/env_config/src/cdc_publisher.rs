@@ -0,0 +1,46 @@
+use zksync_config::CdcPublisherConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for CdcPublisherConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("cdc_publisher", "CDC_PUBLISHER_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::cdc_publisher::{CdcBroker, CdcMessageFormat};
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            CDC_PUBLISHER_BROKER="kafka"
+            CDC_PUBLISHER_BROKER_URLS="127.0.0.1:9092,127.0.0.1:9093"
+            CDC_PUBLISHER_TOPIC_PREFIX="idexo"
+            CDC_PUBLISHER_FORMAT="protobuf"
+            CDC_PUBLISHER_POLLING_INTERVAL_MS="1000"
+            CDC_PUBLISHER_MAX_MINIBLOCKS_PER_ITERATION="50"
+        "#;
+        lock.set_env(config);
+
+        let actual = CdcPublisherConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            CdcPublisherConfig {
+                broker: CdcBroker::Kafka,
+                broker_urls: vec!["127.0.0.1:9092".to_owned(), "127.0.0.1:9093".to_owned()],
+                topic_prefix: "idexo".to_owned(),
+                format: CdcMessageFormat::Protobuf,
+                polling_interval_ms: 1_000,
+                max_miniblocks_per_iteration: 50,
+            }
+        );
+    }
+}
@@ -12,7 +12,9 @@ impl FromEnv for NetworkConfig {
 
 impl FromEnv for StateKeeperConfig {
     fn from_env() -> anyhow::Result<Self> {
-        envy_load("state_keeper", "CHAIN_STATE_KEEPER_")
+        let config: Self = envy_load("state_keeper", "CHAIN_STATE_KEEPER_")?;
+        config.validate()?;
+        Ok(config)
     }
 }
 
@@ -37,7 +39,7 @@ impl FromEnv for MempoolConfig {
 #[cfg(test)]
 mod tests {
     use zksync_basic_types::L2ChainId;
-    use zksync_config::configs::chain::FeeModelVersion;
+    use zksync_config::configs::chain::{DataAvailabilityMode, FeeModelVersion};
 
     use super::*;
     use crate::test_utils::{addr, EnvMutex};
@@ -87,53 +89,81 @@ mod tests {
             batch_overhead_l1_gas: 800_000,
             max_gas_per_batch: 200_000_000,
             max_pubdata_per_batch: 100_000,
-            fee_model_version: FeeModelVersion::V2,
+            fee_model_version: FeeModelVersion::V3,
+            congestion_target_batch_fullness: Some(0.6),
+            congestion_max_change_per_batch: None,
+            congestion_max_multiplier: Some(5.0),
+            congestion_window_size: Some(16),
+            data_availability_mode: DataAvailabilityMode::Validium,
             validation_computational_gas_limit: 10_000_000,
             save_call_traces: false,
             virtual_blocks_interval: 1,
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: Some(2_000),
+            max_txs_per_account_in_miniblock: Some(5),
+            max_txs_per_account_in_batch: None,
+            forced_inclusion_deadline_ms: Some(60_000),
         }
     }
 
+    const STATE_KEEPER_CONFIG: &str = r#"
+        CHAIN_STATE_KEEPER_TRANSACTION_SLOTS="50"
+        CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+        CHAIN_STATE_KEEPER_MAX_SINGLE_TX_GAS="1000000"
+        CHAIN_STATE_KEEPER_MAX_ALLOWED_L2_TX_GAS_LIMIT="2000000000"
+        CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_GEOMETRY_PERCENTAGE="0.5"
+        CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_GAS_PERCENTAGE="0.8"
+        CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_ETH_PARAMS_PERCENTAGE="0.2"
+        CHAIN_STATE_KEEPER_REJECT_TX_AT_GEOMETRY_PERCENTAGE="0.3"
+        CHAIN_STATE_KEEPER_REJECT_TX_AT_ETH_PARAMS_PERCENTAGE="0.8"
+        CHAIN_STATE_KEEPER_REJECT_TX_AT_GAS_PERCENTAGE="0.5"
+        CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE_MS="2500"
+        CHAIN_STATE_KEEPER_MINIBLOCK_COMMIT_DEADLINE_MS="1000"
+        CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_QUEUE_CAPACITY="10"
+        CHAIN_STATE_KEEPER_MINIMAL_L2_GAS_PRICE="100000000"
+        CHAIN_STATE_KEEPER_COMPUTE_OVERHEAD_PART="0.0"
+        CHAIN_STATE_KEEPER_PUBDATA_OVERHEAD_PART="1.0"
+        CHAIN_STATE_KEEPER_BATCH_OVERHEAD_L1_GAS="800000"
+        CHAIN_STATE_KEEPER_MAX_GAS_PER_BATCH="200000000"
+        CHAIN_STATE_KEEPER_MAX_PUBDATA_PER_BATCH="100000"
+        CHAIN_STATE_KEEPER_FEE_MODEL_VERSION="V3"
+        CHAIN_STATE_KEEPER_CONGESTION_TARGET_BATCH_FULLNESS="0.6"
+        CHAIN_STATE_KEEPER_CONGESTION_MAX_MULTIPLIER="5.0"
+        CHAIN_STATE_KEEPER_CONGESTION_WINDOW_SIZE="16"
+        CHAIN_STATE_KEEPER_DATA_AVAILABILITY_MODE="Validium"
+        CHAIN_STATE_KEEPER_VALIDATION_COMPUTATIONAL_GAS_LIMIT="10000000"
+        CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
+        CHAIN_STATE_KEEPER_UPLOAD_WITNESS_INPUTS_TO_GCS="false"
+        CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
+        CHAIN_STATE_KEEPER_MAX_TXS_PER_ACCOUNT_IN_MINIBLOCK="5"
+        CHAIN_STATE_KEEPER_FORCED_INCLUSION_DEADLINE_MS="60000"
+        CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_PER_MINIBLOCK="1"
+        CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_INTERVAL="1"
+    "#;
+
     #[test]
     fn state_keeper_from_env() {
         let mut lock = MUTEX.lock();
-        let config = r#"
-            CHAIN_STATE_KEEPER_TRANSACTION_SLOTS="50"
-            CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
-            CHAIN_STATE_KEEPER_MAX_SINGLE_TX_GAS="1000000"
-            CHAIN_STATE_KEEPER_MAX_ALLOWED_L2_TX_GAS_LIMIT="2000000000"
-            CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_GEOMETRY_PERCENTAGE="0.5"
-            CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_GAS_PERCENTAGE="0.8"
-            CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_ETH_PARAMS_PERCENTAGE="0.2"
-            CHAIN_STATE_KEEPER_REJECT_TX_AT_GEOMETRY_PERCENTAGE="0.3"
-            CHAIN_STATE_KEEPER_REJECT_TX_AT_ETH_PARAMS_PERCENTAGE="0.8"
-            CHAIN_STATE_KEEPER_REJECT_TX_AT_GAS_PERCENTAGE="0.5"
-            CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE_MS="2500"
-            CHAIN_STATE_KEEPER_MINIBLOCK_COMMIT_DEADLINE_MS="1000"
-            CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_QUEUE_CAPACITY="10"
-            CHAIN_STATE_KEEPER_MINIMAL_L2_GAS_PRICE="100000000"
-            CHAIN_STATE_KEEPER_COMPUTE_OVERHEAD_PART="0.0"
-            CHAIN_STATE_KEEPER_PUBDATA_OVERHEAD_PART="1.0"
-            CHAIN_STATE_KEEPER_BATCH_OVERHEAD_L1_GAS="800000"
-            CHAIN_STATE_KEEPER_MAX_GAS_PER_BATCH="200000000"
-            CHAIN_STATE_KEEPER_MAX_PUBDATA_PER_BATCH="100000"
-            CHAIN_STATE_KEEPER_FEE_MODEL_VERSION="V2"
-            CHAIN_STATE_KEEPER_VALIDATION_COMPUTATIONAL_GAS_LIMIT="10000000"
-            CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
-            CHAIN_STATE_KEEPER_UPLOAD_WITNESS_INPUTS_TO_GCS="false"
-            CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
-            CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_PER_MINIBLOCK="1"
-            CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_INTERVAL="1"
-        "#;
-        lock.set_env(config);
+        lock.set_env(STATE_KEEPER_CONFIG);
 
         let actual = StateKeeperConfig::from_env().unwrap();
         assert_eq!(actual, expected_state_keeper_config());
     }
 
+    #[test]
+    fn state_keeper_with_invalid_congestion_target() {
+        let mut lock = MUTEX.lock();
+        lock.set_env(STATE_KEEPER_CONFIG);
+        for fullness in ["0.0", "1.0", "1.5"] {
+            lock.set_env(&format!(
+                r#"CHAIN_STATE_KEEPER_CONGESTION_TARGET_BATCH_FULLNESS="{fullness}""#
+            ));
+            let err = StateKeeperConfig::from_env().unwrap_err().to_string();
+            assert!(err.contains("congestion_target_batch_fullness"), "{err}");
+        }
+    }
+
     fn expected_operations_manager_config() -> OperationsManagerConfig {
         OperationsManagerConfig {
             delay_interval: 100,
@@ -0,0 +1,40 @@
+use zksync_config::ChainExportConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for ChainExportConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("chain_export", "CHAIN_EXPORT_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::chain_export::ChainExportFormat;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            CHAIN_EXPORT_FORMAT="csv"
+            CHAIN_EXPORT_MINIBLOCKS_PER_FILE="1000"
+            CHAIN_EXPORT_EXPORT_INTERVAL_SEC="600"
+        "#;
+        lock.set_env(config);
+
+        let actual = ChainExportConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            ChainExportConfig {
+                format: ChainExportFormat::Csv,
+                miniblocks_per_file: 1_000,
+                export_interval_sec: Some(600),
+            }
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use zksync_config::ConfigReloadConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for ConfigReloadConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("config_reload", "CONFIG_RELOAD_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{envy_load_from, test_utils::EnvMutex};
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            CONFIG_RELOAD_OVERRIDES_PATH="/etc/zksync/overrides.env"
+        "#;
+        lock.set_env(config);
+
+        let actual = ConfigReloadConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            ConfigReloadConfig {
+                polling_interval_ms: 5_000,
+                overrides_path: Some("/etc/zksync/overrides.env".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn loading_from_vars() {
+        let vars = [
+            ("CONFIG_RELOAD_POLLING_INTERVAL_MS", "1000"),
+            ("OTHER_VAR", "value"),
+        ];
+        let vars = vars
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()));
+        let actual: ConfigReloadConfig =
+            envy_load_from("config_reload", "CONFIG_RELOAD_", vars).unwrap();
+        assert_eq!(
+            actual,
+            ConfigReloadConfig {
+                polling_interval_ms: 1_000,
+                overrides_path: None,
+            }
+        );
+    }
+}
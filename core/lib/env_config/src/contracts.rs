@@ -70,6 +70,13 @@ mod tests {
             snark_wrapper_vk_hash: hash(
                 "0x4be443afd605a782b6e56d199df2460a025c81b3dea144e135bece83612563f2",
             ),
+            l1_shared_bridge_proxy_addr: Some(addr("a5b1e9f2f1d5c0a9e8f53efb6b4f8c5c1a0d3b2e")),
+            l2_shared_bridge_addr: Some(addr("4e3f1a2b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f")),
+            l1_shared_bridge_code_hash: Some(hash(
+                "0x3d8e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e",
+            )),
+            l2_shared_bridge_code_hash: None,
+            genesis_manifest_path: Some("etc/genesis/manifest.json".to_owned()),
         }
     }
 
@@ -110,6 +117,10 @@ CONTRACTS_BRIDGEHUB_IMPL_ADDR="0x87d456da9ed212eb49d80d96afb44afddf36adf8"
 CONTRACTS_STATE_TRANSITION_PROXY_ADDR="0xd90f1c081c6117241624e97cb6147257c3cb2097"
 CONTRACTS_STATE_TRANSITION_IMPL_ADDR="0xc957c0e82d3bafb5ad46ffbcc66900648784eb05"
 CONTRACTS_TRANSPARENT_PROXY_ADMIN_ADDR="0xdd6fa5c14e7550b4caf2aa2818d24c69cbc347e5"
+CONTRACTS_L1_SHARED_BRIDGE_PROXY_ADDR="0xa5b1e9f2f1d5c0a9e8f53efb6b4f8c5c1a0d3b2e"
+CONTRACTS_L2_SHARED_BRIDGE_ADDR="0x4e3f1a2b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f"
+CONTRACTS_L1_SHARED_BRIDGE_CODE_HASH="0x3d8e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e"
+CONTRACTS_GENESIS_MANIFEST_PATH="etc/genesis/manifest.json"
         "#;
         lock.set_env(config);
 
@@ -1,7 +1,7 @@
 use std::{env, error, str::FromStr};
 
 use anyhow::Context as _;
-use zksync_config::{DBConfig, PostgresConfig};
+use zksync_config::{configs::database::MerkleTreeConfig, DBConfig, PostgresConfig};
 
 use crate::{envy_load, FromEnv};
 
@@ -21,8 +21,19 @@ where
 
 impl FromEnv for DBConfig {
     fn from_env() -> anyhow::Result<Self> {
+        let merkle_tree = MerkleTreeConfig {
+            rocksdb: envy_load(
+                "database_merkle_tree_rocksdb",
+                "DATABASE_MERKLE_TREE_ROCKSDB_",
+            )?,
+            ..envy_load("database_merkle_tree", "DATABASE_MERKLE_TREE_")?
+        };
         Ok(Self {
-            merkle_tree: envy_load("database_merkle_tree", "DATABASE_MERKLE_TREE_")?,
+            merkle_tree,
+            state_keeper_rocksdb: envy_load(
+                "database_state_keeper_rocksdb",
+                "DATABASE_STATE_KEEPER_ROCKSDB_",
+            )?,
             ..envy_load("database", "DATABASE_")?
         })
     }
@@ -43,6 +54,8 @@ impl FromEnv for PostgresConfig {
         let long_connection_threshold_ms =
             parse_optional_var("DATABASE_LONG_CONNECTION_THRESHOLD_MS")?;
         let slow_query_threshold_ms = parse_optional_var("DATABASE_SLOW_QUERY_THRESHOLD_MS")?;
+        let api_max_connections = parse_optional_var("DATABASE_API_MAX_CONNECTIONS")?;
+        let background_max_connections = parse_optional_var("DATABASE_BACKGROUND_MAX_CONNECTIONS")?;
 
         Ok(Self {
             master_url,
@@ -53,6 +66,8 @@ impl FromEnv for PostgresConfig {
             statement_timeout_sec,
             long_connection_threshold_ms,
             slow_query_threshold_ms,
+            api_max_connections,
+            background_max_connections,
         })
     }
 }
@@ -61,7 +76,7 @@ impl FromEnv for PostgresConfig {
 mod tests {
     use std::time::Duration;
 
-    use zksync_config::configs::database::MerkleTreeMode;
+    use zksync_config::configs::database::{MerkleTreeMode, RocksdbCompactionStyle};
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -79,6 +94,15 @@ mod tests {
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_THREAD_POOL_SIZE=8
+            DATABASE_MERKLE_TREE_INTEGRITY_CHECK_INTERVAL_SEC=600
+            DATABASE_MERKLE_TREE_INTEGRITY_CHECK_SUBTREE_COUNT=32
+            DATABASE_MERKLE_TREE_INTEGRITY_AUTO_REPAIR=true
+            DATABASE_MERKLE_TREE_ROCKSDB_COMPACTION_STYLE=universal
+            DATABASE_MERKLE_TREE_ROCKSDB_MAX_TOTAL_WAL_SIZE_MB=1024
+            DATABASE_STATE_KEEPER_DB_BLOCK_CACHE_SIZE_MB=64
+            DATABASE_STATE_KEEPER_ROCKSDB_WAL_BYTES_PER_SYNC_MB=1
+            DATABASE_API_STATE_CACHE_PATH="/db/api_state_cache"
         "#;
         lock.set_env(config);
 
@@ -90,6 +114,35 @@ mod tests {
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert_eq!(db_config.merkle_tree.thread_pool_size, Some(8));
+        assert_eq!(
+            db_config.merkle_tree.integrity_check_interval(),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(db_config.merkle_tree.integrity_check_subtree_count, 32);
+        assert!(db_config.merkle_tree.integrity_auto_repair);
+        assert_eq!(
+            db_config.merkle_tree.rocksdb.compaction_style,
+            RocksdbCompactionStyle::Universal
+        );
+        assert_eq!(
+            db_config.merkle_tree.rocksdb.max_total_wal_size(),
+            Some(1 << 30)
+        );
+        assert_eq!(db_config.merkle_tree.rocksdb.wal_bytes_per_sync(), None);
+        assert_eq!(db_config.state_keeper_db_block_cache_size(), Some(64 << 20));
+        assert_eq!(
+            db_config.state_keeper_rocksdb.compaction_style,
+            RocksdbCompactionStyle::Level
+        );
+        assert_eq!(
+            db_config.state_keeper_rocksdb.wal_bytes_per_sync(),
+            Some(1 << 20)
+        );
+        assert_eq!(
+            db_config.api_state_cache_path.as_deref(),
+            Some("/db/api_state_cache")
+        );
     }
 
     #[test]
@@ -105,6 +158,18 @@ mod tests {
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_THREAD_POOL_SIZE",
+            "DATABASE_MERKLE_TREE_INTEGRITY_CHECK_INTERVAL_SEC",
+            "DATABASE_MERKLE_TREE_INTEGRITY_CHECK_SUBTREE_COUNT",
+            "DATABASE_MERKLE_TREE_INTEGRITY_AUTO_REPAIR",
+            "DATABASE_MERKLE_TREE_ROCKSDB_COMPACTION_STYLE",
+            "DATABASE_MERKLE_TREE_ROCKSDB_MAX_TOTAL_WAL_SIZE_MB",
+            "DATABASE_MERKLE_TREE_ROCKSDB_WAL_BYTES_PER_SYNC_MB",
+            "DATABASE_STATE_KEEPER_DB_BLOCK_CACHE_SIZE_MB",
+            "DATABASE_STATE_KEEPER_ROCKSDB_COMPACTION_STYLE",
+            "DATABASE_STATE_KEEPER_ROCKSDB_MAX_TOTAL_WAL_SIZE_MB",
+            "DATABASE_STATE_KEEPER_ROCKSDB_WAL_BYTES_PER_SYNC_MB",
+            "DATABASE_API_STATE_CACHE_PATH",
         ]);
 
         let db_config = DBConfig::from_env().unwrap();
@@ -116,6 +181,14 @@ mod tests {
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
+        assert_eq!(db_config.merkle_tree.thread_pool_size, None);
+        assert_eq!(db_config.merkle_tree.integrity_check_interval(), None);
+        assert_eq!(db_config.merkle_tree.integrity_check_subtree_count, 16);
+        assert!(!db_config.merkle_tree.integrity_auto_repair);
+        assert_eq!(db_config.merkle_tree.rocksdb, Default::default());
+        assert_eq!(db_config.state_keeper_db_block_cache_size(), None);
+        assert_eq!(db_config.state_keeper_rocksdb, Default::default());
+        assert_eq!(db_config.api_state_cache_path, None);
 
         // Check that new env variable for Merkle tree path is supported
         lock.set_env("DATABASE_MERKLE_TREE_PATH=/db/tree/main");
@@ -145,6 +218,7 @@ mod tests {
             DATABASE_STATEMENT_TIMEOUT_SEC=300
             DATABASE_LONG_CONNECTION_THRESHOLD_MS=3000
             DATABASE_SLOW_QUERY_THRESHOLD_MS=150
+            DATABASE_API_MAX_CONNECTIONS=30
         "#;
         lock.set_env(config);
 
@@ -170,5 +244,7 @@ mod tests {
             postgres_config.slow_query_threshold(),
             Some(Duration::from_millis(150))
         );
+        assert_eq!(postgres_config.api_max_connections, Some(30));
+        assert_eq!(postgres_config.background_max_connections, None);
     }
 }
@@ -26,6 +26,7 @@ mod tests {
         ETHClientConfig {
             chain_id: 9,
             web3_url: "http://127.0.0.1:8545".into(),
+            l1_web3_url: Some("http://127.0.0.1:8546".into()),
         }
     }
 
@@ -35,6 +36,7 @@ mod tests {
         let config = r#"
             ETH_CLIENT_CHAIN_ID="9"
             ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545"
+            ETH_CLIENT_L1_WEB3_URL="http://127.0.0.1:8546"
         "#;
         lock.set_env(config);
 
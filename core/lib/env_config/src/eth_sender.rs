@@ -1,5 +1,11 @@
 use anyhow::Context as _;
-use zksync_config::{configs::eth_sender::SenderConfig, ETHSenderConfig, GasAdjusterConfig};
+use zksync_config::{
+    configs::eth_sender::{
+        DACostOracleConfig, DADispatcherConfig, EmergencyModeConfig, OperatorBalanceMonitorConfig,
+        OperatorSignerConfig, SenderConfig,
+    },
+    ETHSenderConfig, GasAdjusterConfig,
+};
 
 use crate::{envy_load, FromEnv};
 
@@ -8,6 +14,46 @@ impl FromEnv for ETHSenderConfig {
         Ok(Self {
             sender: SenderConfig::from_env().context("SenderConfig")?,
             gas_adjuster: GasAdjusterConfig::from_env().context("GasAdjusterConfig")?,
+            operator_signer: if std::env::var_os("ETH_SENDER_OPERATOR_SIGNER_MODE").is_some() {
+                Some(OperatorSignerConfig::from_env().context("OperatorSignerConfig")?)
+            } else {
+                None
+            },
+            balance_monitor: if std::env::var_os(
+                "ETH_SENDER_BALANCE_MONITOR_LOW_BALANCE_THRESHOLD_GWEI",
+            )
+            .is_some()
+            {
+                Some(
+                    OperatorBalanceMonitorConfig::from_env()
+                        .context("OperatorBalanceMonitorConfig")?,
+                )
+            } else {
+                None
+            },
+            da_dispatcher: if std::env::var_os("ETH_SENDER_DA_DISPATCHER_POLLING_INTERVAL_MS")
+                .is_some()
+            {
+                Some(DADispatcherConfig::from_env().context("DADispatcherConfig")?)
+            } else {
+                None
+            },
+            da_cost_oracle: if std::env::var_os("ETH_SENDER_DA_COST_ORACLE_MAX_PRICE_SAMPLES")
+                .is_some()
+            {
+                Some(DACostOracleConfig::from_env().context("DACostOracleConfig")?)
+            } else {
+                None
+            },
+            emergency_mode: if std::env::var_os(
+                "ETH_SENDER_EMERGENCY_MODE_INACTIVITY_THRESHOLD_HOURS",
+            )
+            .is_some()
+            {
+                Some(EmergencyModeConfig::from_env().context("EmergencyModeConfig")?)
+            } else {
+                None
+            },
         })
     }
 }
@@ -18,6 +64,36 @@ impl FromEnv for SenderConfig {
     }
 }
 
+impl FromEnv for OperatorSignerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.operator_signer", "ETH_SENDER_OPERATOR_SIGNER_")
+    }
+}
+
+impl FromEnv for OperatorBalanceMonitorConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.balance_monitor", "ETH_SENDER_BALANCE_MONITOR_")
+    }
+}
+
+impl FromEnv for DADispatcherConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.da_dispatcher", "ETH_SENDER_DA_DISPATCHER_")
+    }
+}
+
+impl FromEnv for DACostOracleConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.da_cost_oracle", "ETH_SENDER_DA_COST_ORACLE_")
+    }
+}
+
+impl FromEnv for EmergencyModeConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.emergency_mode", "ETH_SENDER_EMERGENCY_MODE_")
+    }
+}
+
 impl FromEnv for GasAdjusterConfig {
     fn from_env() -> anyhow::Result<Self> {
         envy_load("eth_sender.gas_adjuster", "ETH_SENDER_GAS_ADJUSTER_")
@@ -26,10 +102,12 @@ impl FromEnv for GasAdjusterConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode};
+    use zksync_config::configs::eth_sender::{
+        OperatorSignerMode, ProofLoadingMode, ProofSendingMode, PubdataSendingMode,
+    };
 
     use super::*;
-    use crate::test_utils::{hash, EnvMutex};
+    use crate::test_utils::{addr, hash, EnvMutex};
 
     static MUTEX: EnvMutex = EnvMutex::new();
 
@@ -54,6 +132,15 @@ mod tests {
                 l1_batch_min_age_before_execute_seconds: Some(1000),
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
+                pubdata_sending_mode: PubdataSendingMode::Blobs,
+                resubmission_interval_blocks: Some(3),
+                priority_fee_bump_percent: Some(25),
+                max_fee_per_gas_cap: Some(500_000_000_000),
+                max_fee_bumps_before_replacement: Some(5),
+                dry_run: true,
+                target_cost_per_batch_in_gwei: Some(1_000_000),
+                settlement_checks_enabled: true,
+                max_settlement_reorg_depth: Some(64),
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -64,7 +151,41 @@ mod tests {
                 internal_enforced_l1_gas_price: None,
                 poll_period: 15,
                 max_l1_gas_price: Some(100000000),
+                num_samples_for_blob_base_fee_estimate: 10,
+                l1_pubdata_price_scalar: 0.5,
             },
+            operator_signer: None,
+            balance_monitor: Some(OperatorBalanceMonitorConfig {
+                check_interval_ms: 30_000,
+                low_balance_threshold_gwei: 1_000_000_000,
+                top_up_webhook_url: Some("http://127.0.0.1:3072/top-up".to_owned()),
+                funding_contract_address: Some(addr("dc4f3d1fca4a6f6c1b7e2b5b7c6f8f8d5a0e1d2c")),
+                top_up_cooldown_ms: 600_000,
+            }),
+            da_dispatcher: Some(DADispatcherConfig {
+                polling_interval_ms: 5_000,
+                max_batches_to_dispatch: 100,
+                external_da_url: Some("http://127.0.0.1:3073".to_owned()),
+                celestia_node_url: Some("http://127.0.0.1:26658".to_owned()),
+                celestia_namespace: Some("1d3c0a".to_owned()),
+                eigenda_disperser_url: Some("http://127.0.0.1:3074".to_owned()),
+                failover_timeout_ms: Some(3_600_000),
+                compression_protocol_version: Some(22),
+                compression_level: Some(19),
+                compression_dictionary_path: Some("./etc/pubdata_dictionary.zstd".to_owned()),
+            }),
+            da_cost_oracle: Some(DACostOracleConfig {
+                max_price_samples: 10,
+                native_token_price_in_wei: Some(2_000_000.0),
+                price_scalar: 1.2,
+                max_price_per_byte: Some(100_000_000_000),
+                eigenda_payment_vault_address: Some(addr(
+                    "b2a6e4ec3c3dbf5f6a3c1f2cfbd1c7a1e8d4c3b2",
+                )),
+            }),
+            emergency_mode: Some(EmergencyModeConfig {
+                inactivity_threshold_hours: 72,
+            }),
         }
     }
 
@@ -98,6 +219,36 @@ mod tests {
             ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
             ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             ETH_SENDER_SENDER_PROOF_LOADING_MODE="OldProofFromDb"
+            ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Blobs"
+            ETH_SENDER_SENDER_RESUBMISSION_INTERVAL_BLOCKS="3"
+            ETH_SENDER_SENDER_PRIORITY_FEE_BUMP_PERCENT="25"
+            ETH_SENDER_SENDER_MAX_FEE_PER_GAS_CAP="500000000000"
+            ETH_SENDER_SENDER_MAX_FEE_BUMPS_BEFORE_REPLACEMENT="5"
+            ETH_SENDER_SENDER_DRY_RUN="true"
+            ETH_SENDER_SENDER_TARGET_COST_PER_BATCH_IN_GWEI="1000000"
+            ETH_SENDER_SENDER_SETTLEMENT_CHECKS_ENABLED="true"
+            ETH_SENDER_SENDER_MAX_SETTLEMENT_REORG_DEPTH="64"
+            ETH_SENDER_GAS_ADJUSTER_NUM_SAMPLES_FOR_BLOB_BASE_FEE_ESTIMATE="10"
+            ETH_SENDER_GAS_ADJUSTER_L1_PUBDATA_PRICE_SCALAR="0.5"
+            ETH_SENDER_BALANCE_MONITOR_CHECK_INTERVAL_MS="30000"
+            ETH_SENDER_BALANCE_MONITOR_LOW_BALANCE_THRESHOLD_GWEI="1000000000"
+            ETH_SENDER_BALANCE_MONITOR_TOP_UP_WEBHOOK_URL="http://127.0.0.1:3072/top-up"
+            ETH_SENDER_BALANCE_MONITOR_FUNDING_CONTRACT_ADDRESS="0xdc4f3d1fca4a6f6c1b7e2b5b7c6f8f8d5a0e1d2c"
+            ETH_SENDER_DA_DISPATCHER_POLLING_INTERVAL_MS="5000"
+            ETH_SENDER_DA_DISPATCHER_EXTERNAL_DA_URL="http://127.0.0.1:3073"
+            ETH_SENDER_DA_DISPATCHER_CELESTIA_NODE_URL="http://127.0.0.1:26658"
+            ETH_SENDER_DA_DISPATCHER_CELESTIA_NAMESPACE="1d3c0a"
+            ETH_SENDER_DA_DISPATCHER_EIGENDA_DISPERSER_URL="http://127.0.0.1:3074"
+            ETH_SENDER_DA_DISPATCHER_FAILOVER_TIMEOUT_MS="3600000"
+            ETH_SENDER_DA_DISPATCHER_COMPRESSION_PROTOCOL_VERSION="22"
+            ETH_SENDER_DA_DISPATCHER_COMPRESSION_LEVEL="19"
+            ETH_SENDER_DA_DISPATCHER_COMPRESSION_DICTIONARY_PATH="./etc/pubdata_dictionary.zstd"
+            ETH_SENDER_DA_COST_ORACLE_MAX_PRICE_SAMPLES="10"
+            ETH_SENDER_DA_COST_ORACLE_NATIVE_TOKEN_PRICE_IN_WEI="2000000"
+            ETH_SENDER_DA_COST_ORACLE_PRICE_SCALAR="1.2"
+            ETH_SENDER_DA_COST_ORACLE_MAX_PRICE_PER_BYTE="100000000000"
+            ETH_SENDER_DA_COST_ORACLE_EIGENDA_PAYMENT_VAULT_ADDRESS="0xb2a6e4ec3c3dbf5f6a3c1f2cfbd1c7a1e8d4c3b2"
+            ETH_SENDER_EMERGENCY_MODE_INACTIVITY_THRESHOLD_HOURS="72"
         "#;
         lock.set_env(config);
 
@@ -108,4 +259,30 @@ mod tests {
             hash("27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be")
         );
     }
+
+    #[test]
+    fn operator_signer_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            ETH_SENDER_OPERATOR_SIGNER_MODE="AwsKms"
+            ETH_SENDER_OPERATOR_SIGNER_OPERATOR_ADDRESS="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+            ETH_SENDER_OPERATOR_SIGNER_AWS_KMS_REGION="eu-central-1"
+            ETH_SENDER_OPERATOR_SIGNER_AWS_KMS_KEY_ID="alias/operator"
+            ETH_SENDER_OPERATOR_SIGNER_REQUEST_TIMEOUT_MS="3000"
+        "#;
+        lock.set_env(config);
+
+        let actual = OperatorSignerConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            OperatorSignerConfig {
+                mode: OperatorSignerMode::AwsKms {
+                    aws_kms_region: "eu-central-1".to_owned(),
+                    aws_kms_key_id: "alias/operator".to_owned(),
+                },
+                operator_address: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
+                request_timeout_ms: 3_000,
+            }
+        );
+    }
 }
@@ -10,6 +10,8 @@ impl FromEnv for ETHWatchConfig {
 
 #[cfg(test)]
 mod tests {
+    use zksync_config::configs::eth_watch::EthWatchFinalityMode;
+
     use super::*;
     use crate::test_utils::EnvMutex;
 
@@ -19,6 +21,8 @@ mod tests {
         ETHWatchConfig {
             confirmations_for_eth_event: Some(0),
             eth_node_poll_interval: 300,
+            finality_mode: Some(EthWatchFinalityMode::Safe),
+            reorg_rescan_depth: Some(64),
         }
     }
 
@@ -28,6 +32,8 @@ mod tests {
         let config = r#"
             ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
             ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
+            ETH_WATCH_FINALITY_MODE="safe"
+            ETH_WATCH_REORG_RESCAN_DEPTH="64"
         "#;
         lock.set_env(config);
 
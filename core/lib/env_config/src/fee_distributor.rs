@@ -0,0 +1,53 @@
+use zksync_config::FeeDistributorConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for FeeDistributorConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("fee_distributor", "FEE_DISTRIBUTOR_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_basic_types::Address;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            FEE_DISTRIBUTOR_SWEEP_INTERVAL_SEC="600"
+            FEE_DISTRIBUTOR_MIN_BALANCE="1000000000000000000"
+            FEE_DISTRIBUTOR_TREASURY_ADDR="0x1111111111111111111111111111111111111111"
+            FEE_DISTRIBUTOR_TREASURY_SHARE_BPS="6000"
+            FEE_DISTRIBUTOR_SEQUENCER_ADDR="0x2222222222222222222222222222222222222222"
+            FEE_DISTRIBUTOR_SEQUENCER_SHARE_BPS="2500"
+            FEE_DISTRIBUTOR_BURN_SHARE_BPS="1500"
+        "#;
+        lock.set_env(config);
+
+        let actual = FeeDistributorConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            FeeDistributorConfig {
+                polling_interval_ms: 10_000,
+                sweep_interval_sec: 600,
+                min_balance: 1_000_000_000_000_000_000,
+                min_sweep_amount: 1_000_000_000_000_000,
+                gas_limit_per_transfer: 1_000_000,
+                treasury_addr: Some(Address::repeat_byte(0x11)),
+                treasury_share_bps: 6_000,
+                sequencer_addr: Some(Address::repeat_byte(0x22)),
+                sequencer_share_bps: 2_500,
+                burn_addr: None,
+                burn_share_bps: 1_500,
+            }
+        );
+        assert_eq!(actual.burn_addr(), FeeDistributorConfig::DEFAULT_BURN_ADDR);
+    }
+}
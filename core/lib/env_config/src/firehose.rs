@@ -0,0 +1,37 @@
+use zksync_config::FirehoseConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for FirehoseConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("firehose", "FIREHOSE_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            FIREHOSE_PORT="3093"
+            FIREHOSE_POLL_INTERVAL_MS="1000"
+        "#;
+        lock.set_env(config);
+
+        let actual = FirehoseConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            FirehoseConfig {
+                port: 3093,
+                poll_interval_ms: 1_000,
+                max_streams: 16,
+            }
+        );
+    }
+}
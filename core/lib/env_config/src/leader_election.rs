@@ -0,0 +1,37 @@
+use zksync_config::LeaderElectionConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for LeaderElectionConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("leader_election", "LEADER_ELECTION_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            LEADER_ELECTION_LOCK_ID=42
+            LEADER_ELECTION_CHECK_INTERVAL_MS=500
+        "#;
+        lock.set_env(config);
+
+        let actual = LeaderElectionConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            LeaderElectionConfig {
+                lock_id: 42,
+                check_interval_ms: 500,
+                standby_catchup_interval_ms: 10_000,
+            }
+        );
+    }
+}
@@ -3,13 +3,19 @@ use serde::de::DeserializeOwned;
 
 mod alerts;
 mod api;
+mod audit_log;
+mod cdc_publisher;
 mod chain;
+mod chain_export;
+mod config_reload;
 mod contract_verifier;
 mod contracts;
 mod database;
 mod eth_client;
 mod eth_sender;
 mod eth_watch;
+mod fee_distributor;
+mod firehose;
 mod fri_proof_compressor;
 mod fri_prover;
 mod fri_prover_gateway;
@@ -17,11 +23,21 @@ mod fri_prover_group;
 mod fri_witness_generator;
 mod fri_witness_vector_generator;
 mod house_keeper;
+mod leader_election;
+mod message_relay;
 pub mod object_store;
 mod observability;
 mod proof_data_handler;
+mod reexecution_watchdog;
+mod rosetta_api;
+mod shared_sequencer;
 mod snapshots_creator;
+mod stable_gas_price;
+mod supervisor;
+mod supply_checker;
 mod utils;
+mod webhooks;
+mod withdrawal_finalizer;
 mod witness_generator;
 
 #[cfg(test)]
@@ -38,3 +54,15 @@ pub fn envy_load<T: DeserializeOwned>(name: &str, prefix: &str) -> anyhow::Resul
         .from_env()
         .with_context(|| format!("Cannot load config <{name}>"))
 }
+
+/// Same as [`envy_load()`], but loads the structure from the provided variables instead of the process environment.
+/// Used to reload configs at runtime.
+pub fn envy_load_from<T: DeserializeOwned>(
+    name: &str,
+    prefix: &str,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> anyhow::Result<T> {
+    envy::prefixed(prefix)
+        .from_iter(vars)
+        .with_context(|| format!("Cannot load config <{name}>"))
+}
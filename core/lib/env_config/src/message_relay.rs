@@ -0,0 +1,48 @@
+use zksync_config::configs::MessageRelayConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for MessageRelayConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("message_relay", "MESSAGE_RELAY_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_basic_types::Address;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            MESSAGE_RELAY_POLLING_INTERVAL_MS="5000"
+            MESSAGE_RELAY_OUTBOUND_SENDERS="0x1111111111111111111111111111111111111111,0x2222222222222222222222222222222222222222"
+            MESSAGE_RELAY_INBOX_ADDR="0x3333333333333333333333333333333333333333"
+            MESSAGE_RELAY_MAX_MESSAGES_PER_TX="5"
+            MESSAGE_RELAY_GAS_LIMIT_PER_MESSAGE="250000"
+            MESSAGE_RELAY_TX_RESEND_INTERVAL_SEC="120"
+        "#;
+        lock.set_env(config);
+
+        let actual = MessageRelayConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            MessageRelayConfig {
+                polling_interval_ms: 5_000,
+                outbound_senders: vec![Address::repeat_byte(0x11), Address::repeat_byte(0x22)],
+                inbox_addr: Some(Address::repeat_byte(0x33)),
+                outbox_addr: None,
+                max_messages_per_tx: 5,
+                gas_limit_per_message: 250_000,
+                max_attempts: 3,
+                tx_resend_interval_sec: 120,
+            }
+        );
+    }
+}
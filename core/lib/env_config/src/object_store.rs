@@ -40,6 +40,18 @@ impl FromEnv for SnapshotsObjectStoreConfig {
     }
 }
 
+/// Wrapper for `ObjectStoreConfig` that allows loading object store config for exported chain data
+/// using `CHAIN_EXPORT_` prefix.
+#[derive(Debug)]
+pub struct ChainExportObjectStoreConfig(pub ObjectStoreConfig);
+
+impl FromEnv for ChainExportObjectStoreConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        let config = envy_load("chain_export_object_store", "CHAIN_EXPORT_OBJECT_STORE_")?;
+        Ok(Self(config))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
@@ -140,4 +152,45 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn s3_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            SNAPSHOTS_OBJECT_STORE_BUCKET_BASE_URL="idexo-snapshots"
+            SNAPSHOTS_OBJECT_STORE_MODE="S3"
+            SNAPSHOTS_OBJECT_STORE_REGION="eu-central-1"
+            SNAPSHOTS_OBJECT_STORE_MAX_RETRIES="5"
+        "#;
+        lock.set_env(config);
+        let actual = SnapshotsObjectStoreConfig::from_env().unwrap().0;
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::S3 {
+                bucket_base_url: "idexo-snapshots".to_owned(),
+                region: Some("eu-central-1".to_owned()),
+                endpoint: None,
+            }
+        );
+    }
+
+    #[test]
+    fn azure_blob_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            SNAPSHOTS_OBJECT_STORE_MODE="AzureBlob"
+            SNAPSHOTS_OBJECT_STORE_STORAGE_ACCOUNT="idexo"
+            SNAPSHOTS_OBJECT_STORE_CONTAINER="snapshots"
+        "#;
+        lock.set_env(config);
+        let actual = SnapshotsObjectStoreConfig::from_env().unwrap().0;
+        assert_eq!(actual.max_retries, 5);
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::AzureBlob {
+                storage_account: "idexo".to_owned(),
+                container: "snapshots".to_owned(),
+            }
+        );
+    }
 }
@@ -10,7 +10,9 @@ impl FromEnv for ProofDataHandlerConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::proof_data_handler::ProtocolVersionLoadingMode;
+    use zksync_config::configs::proof_data_handler::{
+        ProofGenerationMode, ProtocolVersionLoadingMode,
+    };
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -23,6 +25,7 @@ mod tests {
             proof_generation_timeout_in_secs: 18000,
             protocol_version_loading_mode: ProtocolVersionLoadingMode::FromEnvVar,
             fri_protocol_version_id: 2,
+            proof_generation_mode: ProofGenerationMode::Mock,
         }
     }
 
@@ -33,6 +36,7 @@ mod tests {
             PROOF_DATA_HANDLER_HTTP_PORT="3320"
             PROOF_DATA_HANDLER_PROTOCOL_VERSION_LOADING_MODE="FromEnvVar"
             PROOF_DATA_HANDLER_FRI_PROTOCOL_VERSION_ID="2"
+            PROOF_DATA_HANDLER_PROOF_GENERATION_MODE="Mock"
         "#;
         let mut lock = MUTEX.lock();
         lock.set_env(config);
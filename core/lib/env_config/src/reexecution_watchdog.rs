@@ -0,0 +1,35 @@
+use zksync_config::ReexecutionWatchdogConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for ReexecutionWatchdogConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("reexecution_watchdog", "REEXECUTION_WATCHDOG_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            REEXECUTION_WATCHDOG_CHECK_INTERVAL_MS="60000"
+        "#;
+        lock.set_env(config);
+
+        let actual = ReexecutionWatchdogConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            ReexecutionWatchdogConfig {
+                check_interval_ms: 60_000,
+                sample_window_batches: 100,
+            }
+        );
+    }
+}
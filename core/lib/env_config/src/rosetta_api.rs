@@ -0,0 +1,40 @@
+use zksync_config::RosettaApiConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for RosettaApiConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("rosetta_api", "ROSETTA_API_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            ROSETTA_API_PORT="3091"
+            ROSETTA_API_NETWORK="testnet"
+            ROSETTA_API_BASE_TOKEN_SYMBOL="IDEXO"
+        "#;
+        lock.set_env(config);
+
+        let actual = RosettaApiConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            RosettaApiConfig {
+                port: 3091,
+                network: "testnet".to_owned(),
+                base_token_symbol: "IDEXO".to_owned(),
+                base_token_decimals: 18,
+                max_block_events: 10_000,
+            }
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use zksync_config::SharedSequencerConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for SharedSequencerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("shared_sequencer", "SHARED_SEQUENCER_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            SHARED_SEQUENCER_URL=http://127.0.0.1:50051
+            SHARED_SEQUENCER_FALLBACK_TIMEOUT_MS=10000
+            SHARED_SEQUENCER_MAX_BUFFERED_TXS=500
+        "#;
+        lock.set_env(config);
+
+        let actual = SharedSequencerConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            SharedSequencerConfig {
+                url: "http://127.0.0.1:50051".to_owned(),
+                fallback_timeout_ms: 10_000,
+                reconnect_interval_ms: 1_000,
+                ack_interval_ms: 500,
+                max_buffered_txs: 500,
+            }
+        );
+    }
+}
@@ -7,3 +7,32 @@ impl FromEnv for SnapshotsCreatorConfig {
         envy_load("snapshots_creator", "SNAPSHOTS_CREATOR_")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            SNAPSHOTS_CREATOR_STORAGE_LOGS_CHUNK_SIZE="500000"
+            SNAPSHOTS_CREATOR_CONCURRENT_QUERIES_COUNT="10"
+            SNAPSHOTS_CREATOR_CREATION_INTERVAL_SEC="3600"
+        "#;
+        lock.set_env(config);
+
+        let actual = SnapshotsCreatorConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            SnapshotsCreatorConfig {
+                storage_logs_chunk_size: 500_000,
+                concurrent_queries_count: 10,
+                creation_interval_sec: Some(3_600),
+            }
+        );
+    }
+}
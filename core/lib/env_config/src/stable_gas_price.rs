@@ -0,0 +1,53 @@
+use zksync_config::StableGasPriceConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for StableGasPriceConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("stable_gas_price", "STABLE_GAS_PRICE_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::stable_gas_price::PriceFeedSource;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            STABLE_GAS_PRICE_SOURCE="http"
+            STABLE_GAS_PRICE_FEED_URL="https://oracle.example.com/price"
+            STABLE_GAS_PRICE_FEED_JSON_POINTER="/ethereum/usd"
+            STABLE_GAS_PRICE_TARGET_TX_COST="0.01"
+            STABLE_GAS_PRICE_MIN_L2_GAS_PRICE="10000000"
+            STABLE_GAS_PRICE_MAX_L2_GAS_PRICE="1000000000"
+            STABLE_GAS_PRICE_SMOOTHING_FACTOR="0.5"
+        "#;
+        lock.set_env(config);
+
+        let actual = StableGasPriceConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            StableGasPriceConfig {
+                source: PriceFeedSource::Http,
+                feed_url: Some("https://oracle.example.com/price".to_owned()),
+                feed_json_pointer: "/ethereum/usd".to_owned(),
+                feed_contract_addr: None,
+                max_price_age_sec: 3_600,
+                update_interval_ms: 60_000,
+                target_tx_cost: 0.01,
+                reference_tx_gas: 300_000,
+                min_l2_gas_price: 10_000_000,
+                max_l2_gas_price: 1_000_000_000,
+                smoothing_factor: 0.5,
+                max_change_per_update: 0.1,
+            }
+        );
+    }
+}
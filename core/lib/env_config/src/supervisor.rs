@@ -0,0 +1,38 @@
+use zksync_config::SupervisorConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for SupervisorConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("supervisor", "SUPERVISOR_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            SUPERVISOR_MAX_RESTARTS=3
+            SUPERVISOR_INITIAL_BACKOFF_MS=500
+        "#;
+        lock.set_env(config);
+
+        let actual = SupervisorConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            SupervisorConfig {
+                max_restarts: 3,
+                restart_window_sec: 600,
+                initial_backoff_ms: 500,
+                max_backoff_ms: 60_000,
+            }
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use zksync_config::SupplyCheckerConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for SupplyCheckerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("supply_checker", "SUPPLY_CHECKER_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_basic_types::Address;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            SUPPLY_CHECKER_CHECK_INTERVAL_MS="30000"
+            SUPPLY_CHECKER_LOCKED_FUNDS_ADDR="0x1111111111111111111111111111111111111111"
+            SUPPLY_CHECKER_UNBACKED_SUPPLY_GWEI="1000000000"
+            SUPPLY_CHECKER_MAX_SURPLUS_GWEI="5000000000"
+        "#;
+        lock.set_env(config);
+
+        let actual = SupplyCheckerConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            SupplyCheckerConfig {
+                check_interval_ms: 30_000,
+                base_token_l1_addr: None,
+                locked_funds_addr: Some(Address::repeat_byte(0x11)),
+                unbacked_supply_gwei: 1_000_000_000,
+                epsilon_gwei: 1_000,
+                max_surplus_gwei: Some(5_000_000_000),
+                alert_after_checks: 3,
+            }
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use zksync_config::WebhooksConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for WebhooksConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("webhooks", "WEBHOOKS_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            WEBHOOKS_URLS="https://example.com/hooks,https://bridge.example.com/batches"
+            WEBHOOKS_POLLING_INTERVAL_MS="500"
+            WEBHOOKS_MAX_ATTEMPTS="5"
+        "#;
+        lock.set_env(config);
+
+        let actual = WebhooksConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            WebhooksConfig {
+                urls: vec![
+                    "https://example.com/hooks".to_owned(),
+                    "https://bridge.example.com/batches".to_owned(),
+                ],
+                polling_interval_ms: 500,
+                request_timeout_ms: 10_000,
+                max_attempts: 5,
+                retry_backoff_ms: 1_000,
+            }
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use zksync_config::WithdrawalFinalizerConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for WithdrawalFinalizerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("withdrawal_finalizer", "WITHDRAWAL_FINALIZER_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_basic_types::Address;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            WITHDRAWAL_FINALIZER_POLLING_INTERVAL_MS="5000"
+            WITHDRAWAL_FINALIZER_TOKEN_ALLOWLIST="0x0000000000000000000000000000000000000000,0x1111111111111111111111111111111111111111"
+            WITHDRAWAL_FINALIZER_MAX_WITHDRAWALS_PER_TX="20"
+            WITHDRAWAL_FINALIZER_GAS_LIMIT_PER_WITHDRAWAL="150000"
+            WITHDRAWAL_FINALIZER_MAX_GAS_PRICE_GWEI="50"
+            WITHDRAWAL_FINALIZER_TX_RESEND_INTERVAL_SEC="120"
+        "#;
+        lock.set_env(config);
+
+        let actual = WithdrawalFinalizerConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            WithdrawalFinalizerConfig {
+                polling_interval_ms: 5_000,
+                token_allowlist: vec![Address::zero(), Address::repeat_byte(0x11)],
+                max_withdrawals_per_tx: 20,
+                gas_limit_per_withdrawal: 150_000,
+                max_gas_price_gwei: Some(50),
+                max_attempts: 3,
+                tx_resend_interval_sec: 120,
+            }
+        );
+    }
+}
@@ -6,7 +6,7 @@ use vise::{
 
 pub use self::{
     query::QueryClient,
-    signing::{PKSigningClient, SigningClient},
+    signing::{PKSigningClient, RemoteSigningClient, SigningClient},
 };
 
 mod query;
@@ -22,16 +22,22 @@ enum Method {
     BaseFeeHistory,
     #[metrics(name = "get_pending_block_base_fee_per_gas")]
     PendingBlockBaseFee,
+    BlobBaseFee,
     GetTxStatus,
     FailureReason,
     GetTx,
     CallContractFunction,
+    Call,
     TxReceipt,
     EthBalance,
     Logs,
     Block,
+    ChainId,
+    GetCode,
     #[metrics(name = "sign_prepared_tx_for_addr")]
     SignPreparedTx,
+    #[metrics(name = "sign_prepared_blob_tx_for_addr")]
+    SignPreparedBlobTx,
     Allowance,
 }
 
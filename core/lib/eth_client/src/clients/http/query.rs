@@ -1,16 +1,19 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use zksync_types::web3::{
-    self,
-    contract::Contract,
-    ethabi,
-    transports::Http,
-    types::{
-        Address, Block, BlockId, BlockNumber, Bytes, Filter, Log, Transaction, TransactionId,
-        TransactionReceipt, H256, U256, U64,
+use zksync_types::{
+    web3::{
+        self,
+        contract::Contract,
+        ethabi,
+        transports::Http,
+        types::{
+            Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionId, TransactionReceipt, H256, U256, U64,
+        },
+        Transport, Web3,
     },
-    Web3,
+    L1ChainId,
 };
 
 use crate::{
@@ -136,6 +139,26 @@ impl EthInterface for QueryClient {
         Ok(block.base_fee_per_gas.unwrap())
     }
 
+    async fn get_blob_base_fee(&self, component: &'static str) -> Result<Option<U256>, Error> {
+        /// JSON-RPC error code returned by nodes that don't support the requested method.
+        const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
+        COUNTERS.call[&(Method::BlobBaseFee, component)].inc();
+        let latency = LATENCIES.direct[&Method::BlobBaseFee].start();
+        // `web3` doesn't support `eth_blobBaseFee` yet, so we call it directly.
+        let response: Result<U256, web3::Error> = web3::helpers::CallFuture::new(
+            self.web3.transport().execute("eth_blobBaseFee", vec![]),
+        )
+        .await;
+        latency.observe();
+
+        match response {
+            Ok(blob_base_fee) => Ok(Some(blob_base_fee)),
+            Err(web3::Error::Rpc(err)) if err.code.code() == METHOD_NOT_FOUND_CODE => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     async fn get_tx_status(
         &self,
         hash: H256,
@@ -249,6 +272,19 @@ impl EthInterface for QueryClient {
         Ok(res)
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        COUNTERS.call[&(Method::Call, component)].inc();
+        let latency = LATENCIES.direct[&Method::Call].start();
+        let output = self.web3.eth().call(request, block).await?;
+        latency.observe();
+        Ok(output)
+    }
+
     async fn tx_receipt(
         &self,
         tx_hash: H256,
@@ -288,4 +324,20 @@ impl EthInterface for QueryClient {
         latency.observe();
         Ok(block)
     }
+
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        COUNTERS.call[&(Method::ChainId, component)].inc();
+        let latency = LATENCIES.direct[&Method::ChainId].start();
+        let chain_id = self.web3.eth().chain_id().await?;
+        latency.observe();
+        Ok(L1ChainId(chain_id.as_u64()))
+    }
+
+    async fn get_code(&self, address: Address, component: &'static str) -> Result<Bytes, Error> {
+        COUNTERS.call[&(Method::GetCode, component)].inc();
+        let latency = LATENCIES.direct[&Method::GetCode].start();
+        let code = self.web3.eth().code(address, None).await?;
+        latency.observe();
+        Ok(code)
+    }
 }
@@ -3,7 +3,9 @@ use std::{fmt, sync::Arc};
 use async_trait::async_trait;
 use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
 use zksync_contracts::zksync_contract;
-use zksync_eth_signer::{raw_ethereum_tx::TransactionParameters, EthereumSigner, PrivateKeySigner};
+use zksync_eth_signer::{
+    raw_ethereum_tx::TransactionParameters, EthereumSigner, PrivateKeySigner, RemoteSigner,
+};
 use zksync_types::{
     web3::{
         self,
@@ -11,16 +13,16 @@ use zksync_types::{
         ethabi,
         transports::Http,
         types::{
-            Address, Block, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt,
-            H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, H160, H256, U256, U64,
         },
     },
-    L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE,
+    L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE, EIP_4844_TX_TYPE,
 };
 
 use super::{query::QueryClient, Method, LATENCIES};
 use crate::{
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    types::{BlobTxParams, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     BoundEthInterface, CallFunctionArgs, ContractCall, EthInterface, RawTransactionBytes,
 };
 
@@ -33,28 +35,65 @@ impl PKSigningClient {
         contracts_config: &ContractsConfig,
         eth_client: &ETHClientConfig,
     ) -> Self {
-        // Gather required data from the config.
-        // It's done explicitly to simplify getting rid of this function later.
-        let main_node_url = &eth_client.web3_url;
         let operator_private_key = eth_sender
             .sender
             .private_key()
             .expect("Operator private key is required for signing client");
+        Self::from_config_with_key(
+            eth_sender,
+            contracts_config,
+            eth_client,
+            operator_private_key,
+        )
+    }
+
+    /// Same as [`Self::from_config()`], but signs transactions with the specified private key
+    /// instead of the main operator key.
+    pub fn from_config_with_key(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+        operator_private_key: H256,
+    ) -> Self {
+        let operator_address = PackedEthSignature::address_from_private_key(&operator_private_key)
+            .expect("Failed to get address from private key");
+        Self::from_config_with_signer(
+            eth_sender,
+            contracts_config,
+            eth_client,
+            operator_address,
+            PrivateKeySigner::new(operator_private_key),
+        )
+    }
+}
+
+/// HTTP-based Ethereum client delegating transaction signing to a remote service (e.g., a cloud KMS).
+pub type RemoteSigningClient = SigningClient<RemoteSigner>;
+
+impl<S: EthereumSigner> SigningClient<S> {
+    /// Creates a client for the specified operator account from the configs.
+    pub fn from_config_with_signer(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+        operator_address: Address,
+        eth_signer: S,
+    ) -> Self {
+        // Gather required data from the config.
+        // It's done explicitly to simplify getting rid of this function later.
+        let main_node_url = &eth_client.web3_url;
         let diamond_proxy_addr = contracts_config.diamond_proxy_addr;
         let default_priority_fee_per_gas = eth_sender.gas_adjuster.default_priority_fee_per_gas;
         let l1_chain_id = eth_client.chain_id;
 
         let transport = Http::new(main_node_url).expect("Failed to create transport");
-        let operator_address = PackedEthSignature::address_from_private_key(&operator_private_key)
-            .expect("Failed to get address from private key");
-
         tracing::info!("Operator address: {:?}", operator_address);
 
         SigningClient::new(
             transport,
             zksync_contract(),
             operator_address,
-            PrivateKeySigner::new(operator_private_key),
+            eth_signer,
             diamond_proxy_addr,
             default_priority_fee_per_gas.into(),
             L1ChainId(l1_chain_id),
@@ -142,6 +181,10 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
             .await
     }
 
+    async fn get_blob_base_fee(&self, component: &'static str) -> Result<Option<U256>, Error> {
+        self.query_client.get_blob_base_fee(component).await
+    }
+
     async fn get_tx_status(
         &self,
         hash: H256,
@@ -169,6 +212,15 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
         self.query_client.call_contract_function(call).await
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        self.query_client.call(request, block, component).await
+    }
+
     async fn tx_receipt(
         &self,
         tx_hash: H256,
@@ -192,6 +244,14 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
     ) -> Result<Option<Block<H256>>, Error> {
         self.query_client.block(block_id, component).await
     }
+
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        self.query_client.fetch_chain_id(component).await
+    }
+
+    async fn get_code(&self, address: Address, component: &'static str) -> Result<Bytes, Error> {
+        self.query_client.get_code(address, component).await
+    }
 }
 
 #[async_trait]
@@ -220,6 +280,75 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
         component: &'static str,
     ) -> Result<SignedCallResult, Error> {
         let latency = LATENCIES.direct[&Method::SignPreparedTx].start();
+        let signed_tx = self
+            .sign_tx(data, contract_addr, options, None, component)
+            .await?;
+        latency.observe();
+        Ok(signed_tx)
+    }
+
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        blob_params: BlobTxParams,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        let latency = LATENCIES.direct[&Method::SignPreparedBlobTx].start();
+        let signed_tx = self
+            .sign_tx(data, contract_addr, options, Some(blob_params), component)
+            .await?;
+        latency.observe();
+        Ok(signed_tx)
+    }
+
+    async fn allowance_on_account(
+        &self,
+        token_address: Address,
+        address: Address,
+        erc20_abi: ethabi::Contract,
+    ) -> Result<U256, Error> {
+        let latency = LATENCIES.direct[&Method::Allowance].start();
+        let args = CallFunctionArgs::new("allowance", (self.inner.sender_account, address))
+            .for_contract(token_address, erc20_abi);
+        let res = self.call_contract_function(args).await?;
+        latency.observe();
+        Ok(U256::from_tokens(res)?)
+    }
+}
+
+impl<S: EthereumSigner> SigningClient<S> {
+    pub fn new(
+        transport: Http,
+        contract: ethabi::Contract,
+        operator_eth_addr: H160,
+        eth_signer: S,
+        contract_eth_addr: H160,
+        default_priority_fee_per_gas: U256,
+        chain_id: L1ChainId,
+    ) -> Self {
+        Self {
+            inner: Arc::new(ETHDirectClientInner {
+                sender_account: operator_eth_addr,
+                eth_signer,
+                contract_addr: contract_eth_addr,
+                chain_id,
+                contract,
+                default_priority_fee_per_gas,
+            }),
+            query_client: transport.into(),
+        }
+    }
+
+    async fn sign_tx(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        blob_params: Option<BlobTxParams>,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
         // Fetch current max priority fee per gas
         let max_priority_fee_per_gas = match options.max_priority_fee_per_gas {
             Some(max_priority_fee_per_gas) => max_priority_fee_per_gas,
@@ -258,6 +387,11 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
             U256::from(FALLBACK_GAS_LIMIT)
         });
 
+        let transaction_type = if blob_params.is_some() {
+            EIP_4844_TX_TYPE
+        } else {
+            EIP_1559_TX_TYPE
+        };
         let tx = TransactionParameters {
             nonce,
             to: Some(contract_addr),
@@ -267,14 +401,23 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
             chain_id: self.inner.chain_id.0,
             max_priority_fee_per_gas,
             gas_price: None,
-            transaction_type: Some(EIP_1559_TX_TYPE.into()),
+            transaction_type: Some(transaction_type.into()),
             access_list: None,
             max_fee_per_gas,
+            max_fee_per_blob_gas: blob_params
+                .as_ref()
+                .map(|params| params.max_fee_per_blob_gas),
+            blob_versioned_hashes: blob_params
+                .as_ref()
+                .map(|params| params.sidecar.versioned_hashes()),
         };
 
-        let signed_tx = self.inner.eth_signer.sign_transaction(tx).await?;
+        let mut signed_tx = self.inner.eth_signer.sign_transaction(tx).await?;
+        // The transaction hash doesn't cover the blob sidecar, so it must be computed beforehand.
         let hash = web3::signing::keccak256(&signed_tx).into();
-        latency.observe();
+        if let Some(blob_params) = &blob_params {
+            signed_tx = blob_params.sidecar.encode_network_tx(&signed_tx);
+        }
         Ok(SignedCallResult {
             raw_tx: RawTransactionBytes(signed_tx),
             max_priority_fee_per_gas,
@@ -283,42 +426,4 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
             hash,
         })
     }
-
-    async fn allowance_on_account(
-        &self,
-        token_address: Address,
-        address: Address,
-        erc20_abi: ethabi::Contract,
-    ) -> Result<U256, Error> {
-        let latency = LATENCIES.direct[&Method::Allowance].start();
-        let args = CallFunctionArgs::new("allowance", (self.inner.sender_account, address))
-            .for_contract(token_address, erc20_abi);
-        let res = self.call_contract_function(args).await?;
-        latency.observe();
-        Ok(U256::from_tokens(res)?)
-    }
-}
-
-impl<S: EthereumSigner> SigningClient<S> {
-    pub fn new(
-        transport: Http,
-        contract: ethabi::Contract,
-        operator_eth_addr: H160,
-        eth_signer: S,
-        contract_eth_addr: H160,
-        default_priority_fee_per_gas: U256,
-        chain_id: L1ChainId,
-    ) -> Self {
-        Self {
-            inner: Arc::new(ETHDirectClientInner {
-                sender_account: operator_eth_addr,
-                eth_signer,
-                contract_addr: contract_eth_addr,
-                chain_id,
-                contract,
-                default_priority_fee_per_gas,
-            }),
-            query_client: transport.into(),
-        }
-    }
 }
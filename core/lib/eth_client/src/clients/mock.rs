@@ -9,19 +9,23 @@ use zksync_types::{
     web3::{
         contract::{tokens::Tokenize, Options},
         ethabi,
-        types::{Block, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt, U64},
+        types::{
+            Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, U64,
+        },
         Error as Web3Error,
     },
     Address, L1ChainId, ProtocolVersionId, H160, H256, U256,
 };
 
 use crate::{
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    types::{BlobTxParams, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     BoundEthInterface, ContractCall, EthInterface, RawTransactionBytes,
 };
 
 #[derive(Debug, Clone)]
 struct MockTx {
+    recipient: Address,
     input: Vec<u8>,
     hash: H256,
     nonce: u64,
@@ -32,6 +36,7 @@ struct MockTx {
 impl From<Vec<u8>> for MockTx {
     fn from(tx: Vec<u8>) -> Self {
         let len = tx.len();
+        let recipient = Address::from_slice(&tx[len - 116..len - 96]);
         let max_fee_per_gas = U256::try_from(&tx[len - 96..len - 64]).unwrap();
         let max_priority_fee_per_gas = U256::try_from(&tx[len - 64..len - 32]).unwrap();
         let nonce = U256::try_from(&tx[len - 32..]).unwrap().as_u64();
@@ -42,7 +47,8 @@ impl From<Vec<u8>> for MockTx {
         };
 
         Self {
-            input: tx[32..len - 96].to_vec(),
+            recipient,
+            input: tx[32..len - 128].to_vec(),
             nonce,
             hash,
             max_fee_per_gas,
@@ -54,6 +60,7 @@ impl From<Vec<u8>> for MockTx {
 impl From<MockTx> for Transaction {
     fn from(tx: MockTx) -> Self {
         Self {
+            to: Some(tx.recipient),
             input: tx.input.into(),
             hash: tx.hash,
             nonce: tx.nonce.into(),
@@ -73,6 +80,10 @@ struct MockEthereumInner {
     current_nonce: u64,
     pending_nonce: u64,
     nonces: BTreeMap<u64, u64>,
+    calls: Vec<CallRequest>,
+    call_responses: HashMap<(Address, Vec<u8>), Bytes>,
+    balances: HashMap<Address, U256>,
+    code: HashMap<Address, Bytes>,
 }
 
 impl MockEthereumInner {
@@ -110,6 +121,14 @@ impl MockEthereumInner {
         };
         self.tx_statuses.insert(tx_hash, status);
     }
+
+    fn execute_external_tx(&mut self, confirmations: u64) {
+        let block_number = self.block_number;
+        self.block_number += confirmations;
+        self.current_nonce += 1;
+        self.pending_nonce = self.pending_nonce.max(self.current_nonce);
+        self.nonces.insert(block_number, self.current_nonce);
+    }
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
@@ -118,10 +137,15 @@ pub struct MockEthereum {
     max_fee_per_gas: U256,
     max_priority_fee_per_gas: U256,
     base_fee_history: Vec<u64>,
+    /// Blob base fee returned by the mock; `None` emulates an L1 network without EIP-4844 support.
+    blob_base_fee: Option<U256>,
     /// If true, the mock will not check the ordering nonces of the transactions.
     /// This is useful for testing the cases when the transactions are executed out of order.
     non_ordering_confirmations: bool,
     multicall_address: Address,
+    sender_account: Address,
+    /// Chain ID returned by [`EthInterface::fetch_chain_id()`].
+    network_chain_id: L1ChainId,
     inner: RwLock<MockEthereumInner>,
 }
 
@@ -131,8 +155,11 @@ impl Default for MockEthereum {
             max_fee_per_gas: 100.into(),
             max_priority_fee_per_gas: 10.into(),
             base_fee_history: vec![],
+            blob_base_fee: None,
             non_ordering_confirmations: false,
             multicall_address: Address::default(),
+            sender_account: Address::repeat_byte(0x11),
+            network_chain_id: L1ChainId(9),
             inner: RwLock::default(),
         }
     }
@@ -155,6 +182,31 @@ impl MockEthereum {
         self.inner.read().unwrap().sent_txs.len()
     }
 
+    /// Returns all requests made via [`EthInterface::call()`], in the order they were made.
+    pub fn calls(&self) -> Vec<CallRequest> {
+        self.inner.read().unwrap().calls.clone()
+    }
+
+    /// Sets the output returned by [`EthInterface::call()`] for the specified contract and calldata.
+    /// Other calls return empty output.
+    pub fn set_call_response(&self, contract: Address, calldata: Vec<u8>, output: Vec<u8>) {
+        self.inner
+            .write()
+            .unwrap()
+            .call_responses
+            .insert((contract, calldata), Bytes(output));
+    }
+
+    /// Sets the code of the specified account returned by [`EthInterface::get_code()`].
+    /// Other accounts have no code.
+    pub fn set_code(&self, address: Address, code: Vec<u8>) {
+        self.inner
+            .write()
+            .unwrap()
+            .code
+            .insert(address, Bytes(code));
+    }
+
     /// Increments the blocks by a provided `confirmations` and marks the sent transaction
     /// as a success.
     pub fn execute_tx(&self, tx_hash: H256, success: bool, confirmations: u64) {
@@ -166,9 +218,19 @@ impl MockEthereum {
         );
     }
 
+    /// Signs a transaction sent to [`BoundEthInterface::contract_addr()`].
     pub fn sign_prepared_tx(
+        &self,
+        raw_tx: Vec<u8>,
+        options: Options,
+    ) -> Result<SignedCallResult, Error> {
+        self.sign_prepared_tx_to(raw_tx, self.contract_addr(), options)
+    }
+
+    fn sign_prepared_tx_to(
         &self,
         mut raw_tx: Vec<u8>,
+        recipient: Address,
         options: Options,
     ) -> Result<SignedCallResult, Error> {
         let max_fee_per_gas = options.max_fee_per_gas.unwrap_or(self.max_fee_per_gas);
@@ -179,6 +241,7 @@ impl MockEthereum {
 
         // Nonce and `gas_price` are appended to distinguish the same transactions
         // with different gas by their hash in tests.
+        raw_tx.append(&mut ethabi::encode(&recipient.into_tokens()));
         raw_tx.append(&mut ethabi::encode(&max_fee_per_gas.into_tokens()));
         raw_tx.append(&mut ethabi::encode(&max_priority_fee_per_gas.into_tokens()));
         raw_tx.append(&mut ethabi::encode(&nonce.into_tokens()));
@@ -196,6 +259,25 @@ impl MockEthereum {
         })
     }
 
+    /// Emulates a transaction from the sender account sent outside of this client (e.g., manually by the operator).
+    /// Increments the blocks by a provided `confirmations` and consumes the current nonce of the account.
+    pub fn execute_external_tx(&self, confirmations: u64) {
+        self.inner
+            .write()
+            .unwrap()
+            .execute_external_tx(confirmations);
+    }
+
+    /// Sets the balance of the specified account returned by [`EthInterface::eth_balance()`].
+    /// Balances of other accounts are zero.
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        self.inner
+            .write()
+            .unwrap()
+            .balances
+            .insert(address, balance);
+    }
+
     pub fn advance_block_number(&self, val: u64) -> u64 {
         let mut inner = self.inner.write().unwrap();
         inner.block_number += val;
@@ -209,6 +291,13 @@ impl MockEthereum {
         }
     }
 
+    pub fn with_blob_base_fee(self, blob_base_fee: Option<U256>) -> Self {
+        Self {
+            blob_base_fee,
+            ..self
+        }
+    }
+
     pub fn with_non_ordering_confirmation(self, non_ordering_confirmations: bool) -> Self {
         Self {
             non_ordering_confirmations,
@@ -222,6 +311,20 @@ impl MockEthereum {
             ..self
         }
     }
+
+    pub fn with_sender_account(self, address: Address) -> Self {
+        Self {
+            sender_account: address,
+            ..self
+        }
+    }
+
+    pub fn with_network_chain_id(self, chain_id: L1ChainId) -> Self {
+        Self {
+            network_chain_id: chain_id,
+            ..self
+        }
+    }
 }
 
 #[async_trait]
@@ -288,6 +391,10 @@ impl EthInterface for MockEthereum {
         Ok(U256::from(*self.base_fee_history.last().unwrap()))
     }
 
+    async fn get_blob_base_fee(&self, _component: &'static str) -> Result<Option<U256>, Error> {
+        Ok(self.blob_base_fee)
+    }
+
     async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
         let tx_status = self.get_tx_status(tx_hash, "failure_reason").await.unwrap();
 
@@ -325,6 +432,22 @@ impl EthInterface for MockEthereum {
         Ok(vec![])
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        _block: Option<BlockId>,
+        _component: &'static str,
+    ) -> Result<Bytes, Error> {
+        let mut inner = self.inner.write().unwrap();
+        let key = (
+            request.to.unwrap_or_default(),
+            request.data.clone().unwrap_or_default().0,
+        );
+        let output = inner.call_responses.get(&key).cloned().unwrap_or_default();
+        inner.calls.push(request);
+        Ok(output)
+    }
+
     async fn get_tx(
         &self,
         hash: H256,
@@ -345,12 +468,9 @@ impl EthInterface for MockEthereum {
         unimplemented!("Not needed right now")
     }
 
-    async fn eth_balance(
-        &self,
-        _address: Address,
-        _component: &'static str,
-    ) -> Result<U256, Error> {
-        unimplemented!("Not needed right now")
+    async fn eth_balance(&self, address: Address, _component: &'static str) -> Result<U256, Error> {
+        let inner = self.inner.read().unwrap();
+        Ok(inner.balances.get(&address).copied().unwrap_or_default())
     }
 
     async fn logs(&self, _filter: Filter, _component: &'static str) -> Result<Vec<Log>, Error> {
@@ -364,6 +484,15 @@ impl EthInterface for MockEthereum {
     ) -> Result<Option<Block<H256>>, Error> {
         unimplemented!("Not needed right now")
     }
+
+    async fn fetch_chain_id(&self, _component: &'static str) -> Result<L1ChainId, Error> {
+        Ok(self.network_chain_id)
+    }
+
+    async fn get_code(&self, address: Address, _component: &'static str) -> Result<Bytes, Error> {
+        let code = self.inner.read().unwrap().code.get(&address).cloned();
+        Ok(code.unwrap_or_default())
+    }
 }
 
 #[async_trait::async_trait]
@@ -381,17 +510,29 @@ impl BoundEthInterface for MockEthereum {
     }
 
     fn sender_account(&self) -> Address {
-        Address::repeat_byte(0x11)
+        self.sender_account
     }
 
     async fn sign_prepared_tx_for_addr(
         &self,
         data: Vec<u8>,
-        _contract_addr: H160,
+        contract_addr: H160,
+        options: Options,
+        _component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        self.sign_prepared_tx_to(data, contract_addr, options)
+    }
+
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
         options: Options,
+        _blob_params: BlobTxParams,
         _component: &'static str,
     ) -> Result<SignedCallResult, Error> {
-        self.sign_prepared_tx(data, options)
+        // The sidecar is not included into the mock transaction, since it's never inspected in tests.
+        self.sign_prepared_tx_to(data, contract_addr, options)
     }
 
     async fn allowance_on_account(
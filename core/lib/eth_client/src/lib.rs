@@ -6,16 +6,16 @@ use zksync_types::{
         contract::Options,
         ethabi,
         types::{
-            Address, Block, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt,
-            H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, H160, H256, U256, U64,
         },
     },
     L1ChainId,
 };
 
 pub use crate::types::{
-    CallFunctionArgs, ContractCall, Error, ExecutedTxStatus, FailureInfo, RawTransactionBytes,
-    SignedCallResult,
+    BlobTxParams, CallFunctionArgs, ContractCall, Error, ExecutedTxStatus, FailureInfo,
+    RawTransactionBytes, SignedCallResult,
 };
 
 pub mod clients;
@@ -64,6 +64,10 @@ pub trait EthInterface: 'static + Sync + Send + fmt::Debug {
         component: &'static str,
     ) -> Result<U256, Error>;
 
+    /// Returns the blob base fee (EIP-4844) for the next L1 block, or `None` if the L1 network
+    /// doesn't support blob transactions.
+    async fn get_blob_base_fee(&self, component: &'static str) -> Result<Option<U256>, Error>;
+
     /// Returns the current gas price.
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error>;
 
@@ -112,6 +116,15 @@ pub trait EthInterface: 'static + Sync + Send + fmt::Debug {
     async fn call_contract_function(&self, call: ContractCall)
         -> Result<Vec<ethabi::Token>, Error>;
 
+    /// Executes a message call without creating a transaction (i.e., `eth_call`) and returns the output data.
+    /// If `block` is not specified, the call is executed against the latest block.
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error>;
+
     /// Returns the logs for the specified filter.
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error>;
 
@@ -121,6 +134,13 @@ pub trait EthInterface: 'static + Sync + Send + fmt::Debug {
         block_id: BlockId,
         component: &'static str,
     ) -> Result<Option<Block<H256>>, Error>;
+
+    /// Fetches the chain ID of the network the client is connected to. Unlike [`BoundEthInterface::chain_id()`],
+    /// the returned value is requested from the network.
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error>;
+
+    /// Returns the code deployed at the specified address at the latest block.
+    async fn get_code(&self, address: Address, component: &'static str) -> Result<Bytes, Error>;
 }
 
 #[cfg(test)]
@@ -172,6 +192,18 @@ pub trait BoundEthInterface: EthInterface {
         component: &'static str,
     ) -> Result<SignedCallResult, Error>;
 
+    /// Signs an EIP-4844 transaction carrying blobs from `blob_params` and returns it in the network
+    /// representation (i.e., with the blob sidecar attached). Other parameters have the same meaning
+    /// as in [`Self::sign_prepared_tx_for_addr()`].
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        blob_params: BlobTxParams,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error>;
+
     /// Returns the nonce of the `Self::sender_account()` at the specified block.
     async fn nonce_at(&self, block: BlockNumber, component: &'static str) -> Result<U256, Error> {
         self.nonce_at_for_account(self.sender_account(), block, component)
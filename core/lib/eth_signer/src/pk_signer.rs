@@ -61,25 +61,9 @@ impl EthereumSigner for PrivateKeySigner {
     ) -> Result<Vec<u8>, SignerError> {
         let key = SecretKey::from_slice(self.private_key.as_bytes()).unwrap();
 
-        // According to the code in web3 <https://docs.rs/web3/latest/src/web3/api/accounts.rs.html#86>
-        // We should use `max_fee_per_gas` as `gas_price` if we use EIP1559
-        let gas_price = raw_tx.max_fee_per_gas;
-
-        let max_priority_fee_per_gas = raw_tx.max_priority_fee_per_gas;
-
-        let tx = Transaction {
-            to: raw_tx.to,
-            nonce: raw_tx.nonce,
-            gas: raw_tx.gas,
-            gas_price,
-            value: raw_tx.value,
-            data: raw_tx.data,
-            transaction_type: raw_tx.transaction_type,
-            access_list: raw_tx.access_list.unwrap_or_default(),
-            max_priority_fee_per_gas,
-        };
-
-        let signed = tx.sign(&key, raw_tx.chain_id);
+        let chain_id = raw_tx.chain_id;
+        let tx = Transaction::from(raw_tx);
+        let signed = tx.sign(&key, chain_id);
         Ok(signed.raw_transaction.0)
     }
 }
@@ -107,6 +91,8 @@ mod test {
             chain_id: 270,
             transaction_type: Some(U64::from(1u32)),
             access_list: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
         };
         let raw_tx = signer
             .sign_transaction(raw_transaction.clone())
@@ -124,4 +110,33 @@ mod test {
         ];
         assert_eq!(raw_tx, precalculated_raw_tx);
     }
+
+    #[tokio::test]
+    async fn test_generating_signed_blob_transaction() {
+        let private_key = H256::from([5; 32]);
+        let signer = PrivateKeySigner::new(private_key);
+        let blob_versioned_hashes = vec![H256::repeat_byte(1), H256::repeat_byte(2)];
+        let raw_transaction = TransactionParameters {
+            nonce: U256::from(1u32),
+            to: Some(H160::default()),
+            gas: U256::from(100_000u32),
+            gas_price: None,
+            max_fee_per_gas: U256::from(2u32),
+            max_priority_fee_per_gas: U256::from(1u32),
+            value: Default::default(),
+            data: vec![1, 2, 3],
+            chain_id: 270,
+            transaction_type: Some(U64::from(3u32)),
+            access_list: None,
+            max_fee_per_blob_gas: Some(U256::from(7u32)),
+            blob_versioned_hashes: Some(blob_versioned_hashes.clone()),
+        };
+        let raw_tx = signer.sign_transaction(raw_transaction).await.unwrap();
+
+        assert_eq!(raw_tx[0], 3);
+        let payload = rlp::Rlp::new(&raw_tx[1..]);
+        assert_eq!(payload.item_count().unwrap(), 14);
+        assert_eq!(payload.val_at::<U256>(9).unwrap(), U256::from(7u32));
+        assert_eq!(payload.list_at::<H256>(10).unwrap(), blob_versioned_hashes);
+    }
 }
@@ -16,12 +16,13 @@ use zksync_types::{
         signing::{self, Signature},
         types::{AccessList, SignedTransaction},
     },
-    U256, U64,
+    H256, U256, U64,
 };
 
 const LEGACY_TX_ID: u64 = 0;
 const ACCESSLISTS_TX_ID: u64 = 1;
 const EIP1559_TX_ID: u64 = 2;
+const EIP4844_TX_ID: u64 = 3;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct TransactionParameters {
@@ -47,6 +48,10 @@ pub struct TransactionParameters {
     pub max_fee_per_gas: U256,
     /// miner bribe
     pub max_priority_fee_per_gas: U256,
+    /// Max fee per blob gas (only for EIP-4844 transactions)
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Versioned hashes of the blobs carried by the transaction (only for EIP-4844 transactions)
+    pub blob_versioned_hashes: Option<Vec<H256>>,
 }
 
 /// A transaction used for RLP encoding, hashing and signing.
@@ -61,6 +66,30 @@ pub struct Transaction {
     pub transaction_type: Option<U64>,
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_blob_gas: Option<U256>,
+    pub blob_versioned_hashes: Option<Vec<H256>>,
+}
+
+impl From<TransactionParameters> for Transaction {
+    fn from(raw_tx: TransactionParameters) -> Self {
+        // According to the code in web3 <https://docs.rs/web3/latest/src/web3/api/accounts.rs.html#86>
+        // We should use `max_fee_per_gas` as `gas_price` if we use EIP1559
+        let gas_price = raw_tx.max_fee_per_gas;
+
+        Self {
+            to: raw_tx.to,
+            nonce: raw_tx.nonce,
+            gas: raw_tx.gas,
+            gas_price,
+            value: raw_tx.value,
+            data: raw_tx.data,
+            transaction_type: raw_tx.transaction_type,
+            access_list: raw_tx.access_list.unwrap_or_default(),
+            max_priority_fee_per_gas: raw_tx.max_priority_fee_per_gas,
+            max_fee_per_blob_gas: raw_tx.max_fee_per_blob_gas,
+            blob_versioned_hashes: raw_tx.blob_versioned_hashes,
+        }
+    }
 }
 
 impl Transaction {
@@ -147,6 +176,44 @@ impl Transaction {
         stream
     }
 
+    /// Encodes the EIP-4844 transaction payload. Blobs, their commitments and proofs ("sidecar")
+    /// are not a part of the signed payload; they are attached to the network representation of
+    /// the transaction separately.
+    fn encode_eip4844_payload(&self, chain_id: u64, signature: Option<&Signature>) -> RlpStream {
+        let mut stream = RlpStream::new();
+
+        let list_size = if signature.is_some() { 14 } else { 11 };
+        stream.begin_list(list_size);
+
+        stream.append(&chain_id);
+        stream.append(&self.nonce);
+        stream.append(&self.max_priority_fee_per_gas);
+        stream.append(&self.gas_price);
+        stream.append(&self.gas);
+        // Unlike other transaction types, blob transactions cannot create contracts.
+        let to = self
+            .to
+            .expect("EIP-4844 transactions must have a recipient");
+        stream.append(&to);
+        stream.append(&self.value);
+        stream.append(&self.data);
+
+        self.rlp_append_access_list(&mut stream);
+
+        stream.append(&self.max_fee_per_blob_gas.unwrap_or_default());
+        let blob_versioned_hashes = self.blob_versioned_hashes.as_deref().unwrap_or_default();
+        stream.begin_list(blob_versioned_hashes.len());
+        for hash in blob_versioned_hashes {
+            stream.append(hash);
+        }
+
+        if let Some(signature) = signature {
+            self.rlp_append_signature(&mut stream, signature);
+        }
+
+        stream
+    }
+
     fn rlp_append_signature(&self, stream: &mut RlpStream, signature: &Signature) {
         stream.append(&signature.v);
         stream.append(&U256::from_big_endian(signature.r.as_bytes()));
@@ -184,6 +251,12 @@ impl Transaction {
                 [&[tx_id], stream.as_raw()].concat()
             }
 
+            Some(EIP4844_TX_ID) => {
+                let tx_id: u8 = EIP4844_TX_ID as u8;
+                let stream = self.encode_eip4844_payload(chain_id, signature);
+                [&[tx_id], stream.as_raw()].concat()
+            }
+
             _ => {
                 panic!("Unsupported transaction type");
             }
@@ -191,29 +264,45 @@ impl Transaction {
     }
 
     /// Sign and return a raw signed transaction.
-    pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
-        let adjust_v_value = matches!(
+    /// Checks whether this is a legacy transaction, for which the signature `v` value encodes the chain ID.
+    pub fn is_legacy(&self) -> bool {
+        matches!(
             self.transaction_type.map(|t| t.as_u64()),
             Some(LEGACY_TX_ID) | None
-        );
+        )
+    }
 
-        let encoded = self.encode(chain_id, None);
+    /// Returns the unsigned transaction encoding; the transaction is signed by signing its `keccak256` hash.
+    pub fn signing_payload(&self, chain_id: u64) -> Vec<u8> {
+        self.encode(chain_id, None)
+    }
 
+    pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
+        let encoded = self.signing_payload(chain_id);
         let hash = signing::keccak256(encoded.as_ref());
 
-        let signature = if adjust_v_value {
+        let signature = if self.is_legacy() {
             sign.sign(&hash, Some(chain_id))
                 .expect("hash is non-zero 32-bytes; qed")
         } else {
             sign.sign_message(&hash)
                 .expect("hash is non-zero 32-bytes; qed")
         };
+        self.into_signed(chain_id, hash.into(), &signature)
+    }
 
-        let signed = self.encode(chain_id, Some(&signature));
+    /// Encodes the transaction together with a signature of its [signing payload](Self::signing_payload()).
+    pub fn into_signed(
+        self,
+        chain_id: u64,
+        message_hash: H256,
+        signature: &Signature,
+    ) -> SignedTransaction {
+        let signed = self.encode(chain_id, Some(signature));
         let transaction_hash = signing::keccak256(signed.as_ref()).into();
 
         SignedTransaction {
-            message_hash: hash.into(),
+            message_hash,
             v: signature.v,
             r: signature.r,
             s: signature.s,
@@ -0,0 +1,292 @@
+//! Backend for AWS KMS asymmetric secp256k1 keys.
+
+use std::{env, fmt, time::Duration};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use hmac::{Hmac, Mac};
+use secp256k1::ecdsa::Signature as EcdsaSignature;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use zksync_types::H256;
+
+use super::{network_error, parse_der_signature, RemoteSignerBackend, RemoteSignerKind};
+use crate::SignerError;
+
+const SERVICE: &str = "kms";
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// AWS credentials used to sign requests.
+#[derive(Clone)]
+pub(super) struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Secrets are not included into the debug representation.
+        formatter
+            .debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish()
+    }
+}
+
+impl AwsCredentials {
+    fn from_env() -> Result<Self, SignerError> {
+        let var = |name: &str| {
+            env::var(name)
+                .map_err(|_| SignerError::CustomError(format!("`{name}` env variable is not set")))
+        };
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+/// Request signed using [AWS Signature Version 4](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_aws-signing.html).
+#[derive(Debug)]
+struct SigV4Request<'a> {
+    method: &'a str,
+    path: &'a str,
+    query: &'a str,
+    /// Headers with lowercase names, sorted by name. Must include `host` and `x-amz-date`.
+    headers: Vec<(&'a str, String)>,
+    payload: &'a [u8],
+}
+
+impl SigV4Request<'_> {
+    fn authorization(
+        &self,
+        credentials: &AwsCredentials,
+        region: &str,
+        service: &str,
+        amz_date: &str,
+    ) -> String {
+        let date = &amz_date[..8];
+        let signed_headers = self
+            .headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_headers: String = self
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+            .collect();
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+            self.method,
+            self.path,
+            self.query,
+            hex::encode(Sha256::digest(self.payload))
+        );
+
+        let scope = format!("{date}/{region}/{service}/aws4_request");
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = signing_key(&credentials.secret_access_key, date, region, service);
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        )
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(
+        format!("AWS4{secret_access_key}").as_bytes(),
+        date.as_bytes(),
+    );
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignResponse {
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DescribeKeyResponse {
+    key_metadata: KeyMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct KeyMetadata {
+    key_state: String,
+}
+
+/// Signs digests using the KMS `Sign` action with the `ECDSA_SHA_256` algorithm. KMS signs the provided
+/// digest as is, so it can be used with `keccak256` digests.
+#[derive(Debug)]
+pub struct AwsKmsBackend {
+    client: reqwest::Client,
+    region: String,
+    key_id: String,
+    credentials: AwsCredentials,
+}
+
+impl AwsKmsBackend {
+    /// Creates a backend with credentials read from the standard AWS env variables.
+    pub fn new(region: &str, key_id: &str, timeout: Duration) -> Result<Self, SignerError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(network_error)?;
+        Ok(Self {
+            client,
+            region: region.to_owned(),
+            key_id: key_id.to_owned(),
+            credentials: AwsCredentials::from_env()?,
+        })
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        action: &str,
+        body: serde_json::Value,
+    ) -> Result<T, SignerError> {
+        let host = format!("{SERVICE}.{}.amazonaws.com", self.region);
+        let payload = serde_json::to_vec(&body).expect("failed serializing request");
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let target = format!("TrentService.{action}");
+
+        let mut headers = vec![
+            ("content-type", CONTENT_TYPE.to_owned()),
+            ("host", host.clone()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(session_token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", session_token.clone()));
+        }
+        headers.push(("x-amz-target", target));
+        let request = SigV4Request {
+            method: "POST",
+            path: "/",
+            query: "",
+            headers,
+            payload: &payload,
+        };
+        let authorization =
+            request.authorization(&self.credentials, &self.region, SERVICE, &amz_date);
+
+        let mut http_request = self
+            .client
+            .post(format!("https://{host}/"))
+            .header("authorization", authorization);
+        for (name, value) in request.headers {
+            if name != "host" {
+                http_request = http_request.header(name, value);
+            }
+        }
+        let response = http_request
+            .body(payload)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(network_error)?;
+        response.json().await.map_err(network_error)
+    }
+}
+
+#[async_trait]
+impl RemoteSignerBackend for AwsKmsBackend {
+    fn kind(&self) -> RemoteSignerKind {
+        RemoteSignerKind::AwsKms
+    }
+
+    async fn sign(&self, _payload: &[u8], digest: H256) -> Result<EcdsaSignature, SignerError> {
+        let body = json!({
+            "KeyId": self.key_id,
+            "Message": BASE64.encode(digest.as_bytes()),
+            "MessageType": "DIGEST",
+            "SigningAlgorithm": "ECDSA_SHA_256",
+        });
+        let response: SignResponse = self.call("Sign", body).await?;
+        let der = BASE64
+            .decode(response.signature)
+            .map_err(|err| SignerError::SigningFailed(format!("malformed signature: {err}")))?;
+        parse_der_signature(&der)
+    }
+
+    async fn check_health(&self) -> Result<(), SignerError> {
+        let body = json!({ "KeyId": self.key_id });
+        let response: DescribeKeyResponse = self.call("DescribeKey", body).await?;
+        let key_state = response.key_metadata.key_state;
+        if key_state == "Enabled" {
+            Ok(())
+        } else {
+            Err(SignerError::CustomError(format!(
+                "KMS key is not enabled: {key_state}"
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from the AWS Signature Version 4 documentation.
+    const SECRET_ACCESS_KEY: &str = "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY";
+
+    #[test]
+    fn deriving_signing_key() {
+        let key = signing_key(SECRET_ACCESS_KEY, "20150830", "us-east-1", "iam");
+        assert_eq!(
+            hex::encode(key),
+            "c4afb1cc5771d871763a393e44b703571b55cc28424d1a5e86da6ed3c154a4b9"
+        );
+    }
+
+    #[test]
+    fn signing_request() {
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_owned(),
+            secret_access_key: SECRET_ACCESS_KEY.to_owned(),
+            session_token: None,
+        };
+        let request = SigV4Request {
+            method: "GET",
+            path: "/",
+            query: "Action=ListUsers&Version=2010-05-08",
+            headers: vec![
+                (
+                    "content-type",
+                    "application/x-www-form-urlencoded; charset=utf-8".to_owned(),
+                ),
+                ("host", "iam.amazonaws.com".to_owned()),
+                ("x-amz-date", "20150830T123600Z".to_owned()),
+            ],
+            payload: b"",
+        };
+        let authorization =
+            request.authorization(&credentials, "us-east-1", "iam", "20150830T123600Z");
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+}
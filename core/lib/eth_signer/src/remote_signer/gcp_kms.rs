@@ -0,0 +1,145 @@
+//! Backend for GCP Cloud KMS asymmetric secp256k1 keys.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use secp256k1::ecdsa::Signature as EcdsaSignature;
+use serde::Deserialize;
+use serde_json::json;
+use zksync_types::H256;
+
+use super::{network_error, parse_der_signature, RemoteSignerBackend, RemoteSignerKind};
+use crate::SignerError;
+
+const KMS_URL: &str = "https://cloudkms.googleapis.com/v1";
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+/// Access tokens are refreshed this long before they expire.
+const TOKEN_EXPIRATION_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AsymmetricSignResponse {
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyVersionResponse {
+    state: String,
+}
+
+/// Signs digests using the Cloud KMS `asymmetricSign` method for keys with the `EC_SIGN_SECP256K1_SHA256`
+/// algorithm. Cloud KMS signs the provided digest as is, so it can be used with `keccak256` digests.
+///
+/// Access tokens are obtained from the GCE metadata server for the default service account.
+#[derive(Debug)]
+pub struct GcpKmsBackend {
+    client: reqwest::Client,
+    key_version_name: String,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl GcpKmsBackend {
+    /// Creates a backend for the specified key version, e.g.
+    /// `projects/{project}/locations/{location}/keyRings/{ring}/cryptoKeys/{key}/cryptoKeyVersions/{version}`.
+    pub fn new(key_version_name: &str, timeout: Duration) -> Result<Self, SignerError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(network_error)?;
+        Ok(Self {
+            client,
+            key_version_name: key_version_name.trim_matches('/').to_owned(),
+            token: Mutex::new(None),
+        })
+    }
+
+    async fn access_token(&self) -> Result<String, SignerError> {
+        {
+            let token = self.token.lock().expect("access token is poisoned");
+            if let Some((token, expires_at)) = &*token {
+                if Instant::now() + TOKEN_EXPIRATION_MARGIN < *expires_at {
+                    return Ok(token.clone());
+                }
+            }
+        }
+
+        let response: TokenResponse = self
+            .client
+            .get(METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(network_error)?
+            .json()
+            .await
+            .map_err(network_error)?;
+        let expires_at = Instant::now() + Duration::from_secs(response.expires_in);
+        *self.token.lock().expect("access token is poisoned") =
+            Some((response.access_token.clone(), expires_at));
+        Ok(response.access_token)
+    }
+}
+
+#[async_trait]
+impl RemoteSignerBackend for GcpKmsBackend {
+    fn kind(&self) -> RemoteSignerKind {
+        RemoteSignerKind::GcpKms
+    }
+
+    async fn sign(&self, _payload: &[u8], digest: H256) -> Result<EcdsaSignature, SignerError> {
+        let url = format!("{KMS_URL}/{}:asymmetricSign", self.key_version_name);
+        let body = json!({
+            "digest": { "sha256": BASE64.encode(digest.as_bytes()) },
+        });
+        let response: AsymmetricSignResponse = self
+            .client
+            .post(url)
+            .bearer_auth(self.access_token().await?)
+            .json(&body)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(network_error)?
+            .json()
+            .await
+            .map_err(network_error)?;
+        let der = BASE64
+            .decode(response.signature)
+            .map_err(|err| SignerError::SigningFailed(format!("malformed signature: {err}")))?;
+        parse_der_signature(&der)
+    }
+
+    async fn check_health(&self) -> Result<(), SignerError> {
+        let url = format!("{KMS_URL}/{}", self.key_version_name);
+        let response: KeyVersionResponse = self
+            .client
+            .get(url)
+            .bearer_auth(self.access_token().await?)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(network_error)?
+            .json()
+            .await
+            .map_err(network_error)?;
+        if response.state == "ENABLED" {
+            Ok(())
+        } else {
+            Err(SignerError::CustomError(format!(
+                "KMS key version is not enabled: {}",
+                response.state
+            )))
+        }
+    }
+}
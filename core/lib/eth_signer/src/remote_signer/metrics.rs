@@ -0,0 +1,45 @@
+//! Metrics for remote signers.
+
+use std::{fmt, time::Duration};
+
+use vise::{Buckets, Counter, EncodeLabelValue, Histogram, LabeledFamily, Metrics};
+
+/// Kind of a remote signing service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub enum RemoteSignerKind {
+    Web3Signer,
+    AwsKms,
+    GcpKms,
+}
+
+impl fmt::Display for RemoteSignerKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Web3Signer => "web3signer",
+            Self::AwsKms => "AWS KMS",
+            Self::GcpKms => "GCP KMS",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum RemoteSignerMethod {
+    Sign,
+    CheckHealth,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "eth_signer_remote")]
+pub(super) struct RemoteSignerMetrics {
+    /// Latency of requests to the remote signer.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["kind", "method"])]
+    pub latency: LabeledFamily<(RemoteSignerKind, RemoteSignerMethod), Histogram<Duration>, 2>,
+    /// Number of failed requests to the remote signer.
+    #[metrics(labels = ["kind", "method"])]
+    pub errors: LabeledFamily<(RemoteSignerKind, RemoteSignerMethod), Counter, 2>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<RemoteSignerMetrics> = vise::Global::new();
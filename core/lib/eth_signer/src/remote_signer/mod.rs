@@ -0,0 +1,176 @@
+//! Signers delegating to remote key management services (cloud KMS, HSMs behind `web3signer`, etc.),
+//! so that the operator key never leaves the service.
+
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use secp256k1::ecdsa::Signature as EcdsaSignature;
+use zksync_types::{
+    tx::primitives::PackedEthSignature,
+    web3::signing::{keccak256, Signature},
+    Address, EIP712TypedStructure, Eip712Domain, H256,
+};
+
+use self::metrics::{RemoteSignerMethod, METRICS};
+pub use self::{
+    aws_kms::AwsKmsBackend, gcp_kms::GcpKmsBackend, metrics::RemoteSignerKind,
+    web3_signer::Web3SignerBackend,
+};
+use crate::{
+    raw_ethereum_tx::{Transaction, TransactionParameters},
+    EthereumSigner, SignerError,
+};
+
+mod aws_kms;
+mod gcp_kms;
+mod metrics;
+#[cfg(test)]
+mod tests;
+mod web3_signer;
+
+/// Remote service able to produce secp256k1 signatures for a single key.
+#[async_trait]
+pub trait RemoteSignerBackend: 'static + fmt::Debug + Send + Sync {
+    /// Returns the kind of this backend used in logs and metrics.
+    fn kind(&self) -> RemoteSignerKind;
+
+    /// Signs `digest`, which is equal to `keccak256(payload)`. Backends may sign either of them, depending
+    /// on whether the service hashes the signed data itself. The recovery ID of the returned signature
+    /// is not required; it's restored by [`RemoteSigner`].
+    async fn sign(&self, payload: &[u8], digest: H256) -> Result<EcdsaSignature, SignerError>;
+
+    /// Checks that the service is reachable and the key is usable.
+    async fn check_health(&self) -> Result<(), SignerError>;
+}
+
+/// [`EthereumSigner`] delegating signing to a [`RemoteSignerBackend`]. All returned signatures
+/// are checked to be produced by the expected operator address.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    backend: Arc<dyn RemoteSignerBackend>,
+    address: Address,
+}
+
+impl RemoteSigner {
+    pub fn new(backend: impl RemoteSignerBackend, address: Address) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            address,
+        }
+    }
+
+    pub fn kind(&self) -> RemoteSignerKind {
+        self.backend.kind()
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Checks that the remote service is reachable and the key is usable.
+    pub async fn check_health(&self) -> Result<(), SignerError> {
+        let kind = self.backend.kind();
+        let latency = METRICS.latency[&(kind, RemoteSignerMethod::CheckHealth)].start();
+        let result = self.backend.check_health().await;
+        latency.observe();
+        if let Err(err) = &result {
+            METRICS.errors[&(kind, RemoteSignerMethod::CheckHealth)].inc();
+            tracing::warn!("Health check for {kind} signer failed: {err}");
+        }
+        result
+    }
+
+    /// Signs the payload using the backend and returns the signature together with its recovery ID.
+    async fn sign_digest(
+        &self,
+        payload: &[u8],
+        digest: H256,
+    ) -> Result<(H256, H256, u8), SignerError> {
+        let kind = self.backend.kind();
+        let latency = METRICS.latency[&(kind, RemoteSignerMethod::Sign)].start();
+        let result = self.backend.sign(payload, digest).await;
+        latency.observe();
+        let mut signature = result.map_err(|err| {
+            METRICS.errors[&(kind, RemoteSignerMethod::Sign)].inc();
+            tracing::warn!("Signing with {kind} signer failed: {err}");
+            err
+        })?;
+
+        // KMS services don't necessarily return signatures with a low `s` value, which is required by Ethereum.
+        signature.normalize_s();
+        let compact = signature.serialize_compact();
+        let r = H256::from_slice(&compact[..32]);
+        let s = H256::from_slice(&compact[32..]);
+        for recovery_id in 0..2 {
+            let signature = PackedEthSignature::from_rsv(&r, &s, recovery_id);
+            if signature.signature_recover_signer(&digest).ok() == Some(self.address) {
+                return Ok((r, s, recovery_id));
+            }
+        }
+
+        METRICS.errors[&(kind, RemoteSignerMethod::Sign)].inc();
+        Err(SignerError::SigningFailed(format!(
+            "signature returned by {kind} signer was not produced by {:?}",
+            self.address
+        )))
+    }
+}
+
+#[async_trait]
+impl EthereumSigner for RemoteSigner {
+    async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+        let digest = PackedEthSignature::message_to_signed_bytes(message);
+        let (r, s, recovery_id) = self.sign_digest(message, digest).await?;
+        Ok(PackedEthSignature::from_rsv(&r, &s, recovery_id))
+    }
+
+    async fn sign_typed_data<S: EIP712TypedStructure + Sync>(
+        &self,
+        domain: &Eip712Domain,
+        typed_struct: &S,
+    ) -> Result<PackedEthSignature, SignerError> {
+        let mut payload = b"\x19\x01".to_vec();
+        payload.extend_from_slice(domain.hash_struct().as_bytes());
+        payload.extend_from_slice(typed_struct.hash_struct().as_bytes());
+        let digest = PackedEthSignature::typed_data_to_signed_bytes(domain, typed_struct);
+        let (r, s, recovery_id) = self.sign_digest(&payload, digest).await?;
+        Ok(PackedEthSignature::from_rsv(&r, &s, recovery_id))
+    }
+
+    async fn sign_transaction(
+        &self,
+        raw_tx: TransactionParameters,
+    ) -> Result<Vec<u8>, SignerError> {
+        let chain_id = raw_tx.chain_id;
+        let tx = Transaction::from(raw_tx);
+        let payload = tx.signing_payload(chain_id);
+        let digest = H256(keccak256(&payload));
+        let (r, s, recovery_id) = self.sign_digest(&payload, digest).await?;
+
+        let v = if tx.is_legacy() {
+            // EIP-155 encoding of the chain ID.
+            u64::from(recovery_id) + 35 + chain_id * 2
+        } else {
+            u64::from(recovery_id)
+        };
+        let signature = Signature { v, r, s };
+        Ok(tx
+            .into_signed(chain_id, digest, &signature)
+            .raw_transaction
+            .0)
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        Ok(self.address)
+    }
+}
+
+/// Parses a DER-encoded ECDSA signature returned by a KMS service.
+fn parse_der_signature(der: &[u8]) -> Result<EcdsaSignature, SignerError> {
+    EcdsaSignature::from_der(der)
+        .map_err(|err| SignerError::SigningFailed(format!("malformed DER signature: {err}")))
+}
+
+fn network_error(err: reqwest::Error) -> SignerError {
+    SignerError::SigningFailed(format!("network error: {err}"))
+}
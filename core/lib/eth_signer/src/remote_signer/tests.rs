@@ -0,0 +1,95 @@
+//! Tests for the remote signer.
+
+use secp256k1::{Message, Secp256k1, SecretKey};
+use zksync_types::{H160, U256, U64};
+
+use super::*;
+use crate::PrivateKeySigner;
+
+const PRIVATE_KEY: H256 = H256([5; 32]);
+
+/// Backend signing digests with a local key, similarly to KMS services.
+#[derive(Debug)]
+struct LocalBackend(SecretKey);
+
+impl LocalBackend {
+    fn new() -> Self {
+        Self(SecretKey::from_slice(PRIVATE_KEY.as_bytes()).unwrap())
+    }
+}
+
+#[async_trait]
+impl RemoteSignerBackend for LocalBackend {
+    fn kind(&self) -> RemoteSignerKind {
+        RemoteSignerKind::AwsKms
+    }
+
+    async fn sign(&self, payload: &[u8], digest: H256) -> Result<EcdsaSignature, SignerError> {
+        assert_eq!(H256(keccak256(payload)), digest);
+        let message = Message::from_slice(digest.as_bytes()).unwrap();
+        Ok(Secp256k1::signing_only().sign_ecdsa(&message, &self.0))
+    }
+
+    async fn check_health(&self) -> Result<(), SignerError> {
+        Ok(())
+    }
+}
+
+fn operator_address() -> Address {
+    PackedEthSignature::address_from_private_key(&PRIVATE_KEY).unwrap()
+}
+
+fn transaction(transaction_type: Option<u32>) -> TransactionParameters {
+    let is_blob = transaction_type == Some(3);
+    TransactionParameters {
+        nonce: U256::from(1u32),
+        to: Some(H160::default()),
+        gas: U256::from(100_000u32),
+        gas_price: Some(U256::from(2u32)),
+        max_fee_per_gas: U256::from(2u32),
+        max_priority_fee_per_gas: U256::from(1u32),
+        value: Default::default(),
+        data: vec![1, 2, 3],
+        chain_id: 270,
+        transaction_type: transaction_type.map(U64::from),
+        access_list: None,
+        max_fee_per_blob_gas: is_blob.then(|| U256::from(7u32)),
+        blob_versioned_hashes: is_blob.then(|| vec![H256::repeat_byte(1)]),
+    }
+}
+
+#[tokio::test]
+async fn remote_signer_produces_same_transactions_as_private_key_signer() {
+    let signer = RemoteSigner::new(LocalBackend::new(), operator_address());
+    let pk_signer = PrivateKeySigner::new(PRIVATE_KEY);
+    assert_eq!(signer.get_address().await.unwrap(), operator_address());
+
+    for transaction_type in [None, Some(1), Some(2), Some(3)] {
+        let tx = transaction(transaction_type);
+        let raw_tx = signer.sign_transaction(tx.clone()).await.unwrap();
+        let expected_raw_tx = pk_signer.sign_transaction(tx).await.unwrap();
+        assert_eq!(raw_tx, expected_raw_tx, "{transaction_type:?}");
+    }
+}
+
+#[tokio::test]
+async fn remote_signer_produces_same_signatures_as_private_key_signer() {
+    let signer = RemoteSigner::new(LocalBackend::new(), operator_address());
+    let pk_signer = PrivateKeySigner::new(PRIVATE_KEY);
+    let signature = signer.sign_message(b"test").await.unwrap();
+    let expected_signature = pk_signer.sign_message(b"test").await.unwrap();
+    assert_eq!(signature, expected_signature);
+}
+
+#[tokio::test]
+async fn remote_signer_rejects_signatures_from_unexpected_key() {
+    let signer = RemoteSigner::new(LocalBackend::new(), Address::repeat_byte(1));
+    let err = signer
+        .sign_transaction(transaction(Some(2)))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, SignerError::SigningFailed(message) if message.contains("was not produced")),
+        "{err:?}"
+    );
+}
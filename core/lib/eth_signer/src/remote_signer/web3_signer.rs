@@ -0,0 +1,79 @@
+//! Backend for services compatible with the `web3signer` raw signing API.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use secp256k1::ecdsa::Signature as EcdsaSignature;
+use serde_json::json;
+use zksync_types::H256;
+
+use super::{network_error, RemoteSignerBackend, RemoteSignerKind};
+use crate::SignerError;
+
+/// Signs data using `POST /api/v1/eth1/sign/{key_id}`. The service hashes the signed data itself
+/// and returns a 65-byte hex-encoded signature.
+#[derive(Debug)]
+pub struct Web3SignerBackend {
+    client: reqwest::Client,
+    url: String,
+    key_id: String,
+}
+
+impl Web3SignerBackend {
+    pub fn new(url: &str, key_id: &str, timeout: Duration) -> Result<Self, SignerError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(network_error)?;
+        Ok(Self {
+            client,
+            url: url.trim_end_matches('/').to_owned(),
+            key_id: key_id.to_owned(),
+        })
+    }
+}
+
+#[async_trait]
+impl RemoteSignerBackend for Web3SignerBackend {
+    fn kind(&self) -> RemoteSignerKind {
+        RemoteSignerKind::Web3Signer
+    }
+
+    async fn sign(&self, payload: &[u8], _digest: H256) -> Result<EcdsaSignature, SignerError> {
+        let url = format!("{}/api/v1/eth1/sign/{}", self.url, self.key_id);
+        let body = json!({ "data": format!("0x{}", hex::encode(payload)) });
+        let response = self
+            .client
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(network_error)?;
+        let signature = response.text().await.map_err(network_error)?;
+
+        let signature = signature.trim();
+        let signature = signature.strip_prefix("0x").unwrap_or(signature);
+        let signature = hex::decode(signature)
+            .map_err(|err| SignerError::SigningFailed(format!("malformed signature: {err}")))?;
+        if signature.len() != 65 {
+            return Err(SignerError::SigningFailed(format!(
+                "unexpected signature length: {}",
+                signature.len()
+            )));
+        }
+        EcdsaSignature::from_compact(&signature[..64])
+            .map_err(|err| SignerError::SigningFailed(format!("malformed signature: {err}")))
+    }
+
+    async fn check_health(&self) -> Result<(), SignerError> {
+        let url = format!("{}/upcheck", self.url);
+        self.client
+            .get(url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(network_error)?;
+        Ok(())
+    }
+}
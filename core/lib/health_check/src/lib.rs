@@ -29,6 +29,12 @@ impl HealthStatus {
         matches!(self, Self::Ready | Self::Affected)
     }
 
+    /// Checks whether a component is alive according to this status, i.e., it hasn't crashed. Unlike [`Self::is_healthy()`],
+    /// this is true for components that are initializing or are temporarily unable to serve requests.
+    pub fn is_live(self) -> bool {
+        !matches!(self, Self::Panicked)
+    }
+
     fn priority_for_aggregation(self) -> usize {
         match self {
             Self::Ready => 0,
@@ -132,9 +138,17 @@ impl AppHealth {
         }
     }
 
+    /// Checks whether the application is ready to serve requests, i.e., all its components are healthy.
     pub fn is_healthy(&self) -> bool {
         self.inner.status.is_healthy()
     }
+
+    /// Checks whether the application is alive, i.e., none of its components has crashed. This is intended
+    /// for liveness probes; a non-live application should be restarted.
+    pub fn is_live(&self) -> bool {
+        // `Panicked` has the highest aggregation priority, so it's sufficient to check the aggregated status.
+        self.inner.status.is_live()
+    }
 }
 
 /// Interface to be used for health checks.
@@ -305,6 +319,7 @@ mod tests {
 
         let app_health = AppHealth::new(&checks).await;
         assert!(!app_health.is_healthy());
+        assert!(app_health.is_live());
         assert_matches!(app_health.inner.status(), HealthStatus::NotReady);
         assert_matches!(
             app_health.components["first"].status,
@@ -351,4 +366,26 @@ mod tests {
             HealthStatus::Affected
         );
     }
+
+    #[tokio::test]
+    async fn aggregating_liveness() {
+        let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+        let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+        let checks: Vec<Box<dyn CheckHealth>> = vec![Box::new(first_check), Box::new(second_check)];
+        first_updater.update(HealthStatus::Ready.into());
+
+        drop(second_updater);
+        let app_health = AppHealth::new(&checks).await;
+        assert!(!app_health.is_healthy());
+        assert!(app_health.is_live());
+
+        let task = tokio::spawn(async move {
+            let _updater = first_updater;
+            panic!("oops");
+        });
+        assert!(task.await.unwrap_err().is_panic());
+        let app_health = AppHealth::new(&checks).await;
+        assert!(!app_health.is_live());
+        assert_matches!(app_health.inner.status(), HealthStatus::Panicked);
+    }
 }
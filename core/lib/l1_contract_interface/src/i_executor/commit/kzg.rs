@@ -1,8 +1,8 @@
-// TODO: Remove once integrated into batch commitment
-#![allow(dead_code)]
+//! KZG commitments for EIP-4844 blobs carrying L1 batch pubdata.
 
-use std::convert::TryInto;
+use std::{convert::TryInto, path::Path};
 
+use once_cell::sync::Lazy;
 use sha2::Sha256;
 use sha3::{Digest, Keccak256};
 use zkevm_test_harness_1_3_3::ff::{PrimeField, PrimeFieldRepr};
@@ -20,8 +20,10 @@ use zkevm_test_harness_1_4_1::{
         },
     },
 };
+use zksync_types::eth_sender::SidecarBlobV1;
 
-const ZK_SYNC_BYTES_PER_BLOB: usize = BLOB_CHUNK_SIZE * ELEMENTS_PER_4844_BLOCK;
+/// Number of pubdata bytes that fit into a single blob.
+pub const ZK_SYNC_BYTES_PER_BLOB: usize = BLOB_CHUNK_SIZE * ELEMENTS_PER_4844_BLOCK;
 const EIP_4844_BYTES_PER_BLOB: usize = 32 * ELEMENTS_PER_4844_BLOCK;
 
 /// Packed pubdata commitments.
@@ -31,6 +33,13 @@ const BYTES_PER_PUBDATA_COMMITMENT: usize = 144;
 
 const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
 
+/// Trusted setup used to compute KZG commitments. Loaded from `$ZKSYNC_HOME/trusted_setup.json`.
+pub static KZG_SETTINGS: Lazy<KzgSettings> = Lazy::new(|| {
+    let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
+    let path = Path::new(&zksync_home).join("trusted_setup.json");
+    KzgSettings::new(path.to_str().expect("path to trusted setup is not UTF-8"))
+});
+
 /// All the info needed for both the network transaction and by our L1 contracts. As part of the network transaction we
 /// need to encode the sidecar which contains the: blob, `kzg` commitment, and the blob proof. The transaction payload
 /// will utilize the versioned hash. The info needed for `commitBatches` is the `kzg` commitment, opening point,
@@ -102,6 +111,30 @@ impl KzgInfo {
         res
     }
 
+    /// Returns the commitment to the blob used by L1 contracts to verify pubdata published in calldata.
+    /// Format: keccak(versioned hash || opening point (16 bytes) || opening value)
+    pub fn to_blob_commitment(&self) -> [u8; 32] {
+        let hash = Keccak256::digest(
+            [
+                &self.versioned_hash[..],
+                &self.opening_point[16..],
+                &self.opening_value[..],
+            ]
+            .concat(),
+        );
+        hash.into()
+    }
+
+    /// Returns the sidecar data for this blob, which is attached to the EIP-4844 transaction.
+    pub fn to_sidecar_blob(&self) -> SidecarBlobV1 {
+        SidecarBlobV1 {
+            blob: self.blob.to_vec(),
+            commitment: self.kzg_commitment.to_vec(),
+            proof: self.blob_proof.to_vec(),
+            versioned_hash: self.versioned_hash.to_vec(),
+        }
+    }
+
     /// Deserializes `Self::SERIALIZED_SIZE` bytes into `KzgInfo` struct
     pub fn from_slice(data: &[u8]) -> Self {
         assert_eq!(data.len(), Self::SERIALIZED_SIZE);
@@ -1 +1,3 @@
-mod kzg;
+//! Helpers for committing L1 batches.
+
+pub mod kzg;
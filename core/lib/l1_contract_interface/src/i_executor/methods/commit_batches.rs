@@ -1,4 +1,9 @@
-use zksync_types::{commitment::L1BatchWithMetadata, ethabi::Token};
+use zksync_types::{
+    commitment::L1BatchWithMetadata,
+    eth_sender::{EthTxBlobSidecar, EthTxBlobSidecarV1},
+    ethabi::Token,
+    pubdata_da::PubdataDA,
+};
 
 use crate::{
     i_executor::structures::{CommitBatchInfo, StoredBatchInfo},
@@ -10,6 +15,26 @@ use crate::{
 pub struct CommitBatches {
     pub last_committed_l1_batch: L1BatchWithMetadata,
     pub l1_batches: Vec<L1BatchWithMetadata>,
+    pub pubdata_da: PubdataDA,
+    /// Inclusion data of an external DA layer for each of `l1_batches`. Only used if pubdata is published
+    /// using [`PubdataDA::Custom`]; missing entries are treated as empty inclusion data.
+    pub da_inclusion_data: Vec<Vec<u8>>,
+}
+
+impl CommitBatches {
+    /// Returns the blob sidecar for the commit transaction, or `None` if pubdata is published in calldata.
+    pub fn blob_sidecar(&self) -> Option<EthTxBlobSidecar> {
+        if self.pubdata_da != PubdataDA::Blobs {
+            return None;
+        }
+        let blobs = self
+            .l1_batches
+            .iter()
+            .flat_map(|batch| CommitBatchInfo::new(batch, self.pubdata_da).blobs())
+            .map(|kzg_info| kzg_info.to_sidecar_blob())
+            .collect();
+        Some(EthTxBlobSidecarV1 { blobs }.into())
+    }
 }
 
 impl Tokenize for CommitBatches {
@@ -18,7 +43,14 @@ impl Tokenize for CommitBatches {
         let l1_batches_to_commit = self
             .l1_batches
             .iter()
-            .map(|batch| CommitBatchInfo(batch).into_token())
+            .enumerate()
+            .map(|(i, batch)| {
+                let da_inclusion_data =
+                    self.da_inclusion_data.get(i).map_or(&[][..], Vec::as_slice);
+                CommitBatchInfo::new(batch, self.pubdata_da)
+                    .with_da_inclusion_data(da_inclusion_data)
+                    .into_token()
+            })
             .collect();
 
         vec![stored_batch_info, Token::Array(l1_batches_to_commit)]
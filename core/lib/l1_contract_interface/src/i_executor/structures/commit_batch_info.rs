@@ -1,15 +1,90 @@
 use zksync_types::{
     commitment::{pre_boojum_serialize_commitments, serialize_commitments, L1BatchWithMetadata},
     ethabi::Token,
+    pubdata_da::PubdataDA,
     web3::{contract::Error as Web3ContractError, error::Error as Web3ApiError},
     U256,
 };
 
-use crate::Tokenizable;
+use crate::{
+    i_executor::commit::kzg::{KzgInfo, KZG_SETTINGS, ZK_SYNC_BYTES_PER_BLOB},
+    Tokenizable,
+};
 
 /// Encoding for `CommitBatchInfo` from `IExecutor.sol`
 #[derive(Debug)]
-pub struct CommitBatchInfo<'a>(pub &'a L1BatchWithMetadata);
+pub struct CommitBatchInfo<'a> {
+    l1_batch_with_metadata: &'a L1BatchWithMetadata,
+    pubdata_da: PubdataDA,
+    da_inclusion_data: &'a [u8],
+}
+
+impl<'a> CommitBatchInfo<'a> {
+    /// Creates encoding for the specified L1 batch. `pubdata_da` is only taken into account
+    /// for batches starting from protocol version 1.4.2; earlier batches always publish pubdata in calldata.
+    pub fn new(l1_batch_with_metadata: &'a L1BatchWithMetadata, pubdata_da: PubdataDA) -> Self {
+        Self {
+            l1_batch_with_metadata,
+            pubdata_da,
+            da_inclusion_data: &[],
+        }
+    }
+
+    /// Sets the inclusion data of an external DA layer. It is only used if pubdata is published
+    /// using [`PubdataDA::Custom`]; by default, it is empty, which is the case for validium chains.
+    pub fn with_da_inclusion_data(mut self, da_inclusion_data: &'a [u8]) -> Self {
+        self.da_inclusion_data = da_inclusion_data;
+        self
+    }
+
+    /// Returns the pubdata published for the L1 batch.
+    pub fn pubdata_input(&self) -> Vec<u8> {
+        self.l1_batch_with_metadata
+            .header
+            .pubdata_input
+            .clone()
+            .unwrap_or_else(|| self.l1_batch_with_metadata.construct_pubdata())
+    }
+
+    /// Returns KZG info for each blob required to publish the L1 batch pubdata.
+    pub fn blobs(&self) -> Vec<KzgInfo> {
+        self.pubdata_input()
+            .chunks(ZK_SYNC_BYTES_PER_BLOB)
+            .map(|chunk| KzgInfo::new(&KZG_SETTINGS, chunk.to_vec()))
+            .collect()
+    }
+
+    /// Returns `pubdataCommitments` for post-1.4.2 L1 batches. The first byte is the pubdata source flag:
+    ///
+    /// - For calldata, it is followed by the pubdata and the blob commitment, so that the L1 contract
+    ///   can verify the proof even if blobs are not used.
+    /// - For blobs, it is followed by the concatenated pubdata commitments for each blob.
+    /// - For custom DA, it is followed by the DA inclusion data.
+    fn pubdata_commitments(&self) -> Vec<u8> {
+        let source_flag = self.pubdata_da.source_flag();
+        match self.pubdata_da {
+            PubdataDA::Calldata => {
+                let pubdata = self.pubdata_input();
+                let blob_commitment =
+                    KzgInfo::new(&KZG_SETTINGS, pubdata.clone()).to_blob_commitment();
+                std::iter::once(source_flag)
+                    .chain(pubdata)
+                    .chain(blob_commitment)
+                    .collect()
+            }
+            PubdataDA::Blobs => std::iter::once(source_flag)
+                .chain(
+                    self.blobs()
+                        .iter()
+                        .flat_map(|kzg_info| kzg_info.to_pubdata_commitment()),
+                )
+                .collect(),
+            PubdataDA::Custom => std::iter::once(source_flag)
+                .chain(self.da_inclusion_data.iter().copied())
+                .collect(),
+        }
+    }
+}
 
 impl<'a> Tokenizable for CommitBatchInfo<'a> {
     fn from_token(_token: Token) -> Result<Self, Web3ContractError>
@@ -25,28 +100,55 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
     }
 
     fn into_token(self) -> Token {
-        if self.0.header.protocol_version.unwrap().is_pre_boojum() {
+        let protocol_version = self.l1_batch_with_metadata.header.protocol_version.unwrap();
+        if protocol_version.is_pre_boojum() {
             Token::Tuple(vec![
-                Token::Uint(U256::from(self.0.header.number.0)),
-                Token::Uint(U256::from(self.0.header.timestamp)),
-                Token::Uint(U256::from(self.0.metadata.rollup_last_leaf_index)),
-                Token::FixedBytes(self.0.metadata.merkle_root_hash.as_bytes().to_vec()),
-                Token::Uint(U256::from(self.0.header.l1_tx_count)),
-                Token::FixedBytes(self.0.metadata.l2_l1_merkle_root.as_bytes().to_vec()),
+                Token::Uint(U256::from(self.l1_batch_with_metadata.header.number.0)),
+                Token::Uint(U256::from(self.l1_batch_with_metadata.header.timestamp)),
+                Token::Uint(U256::from(
+                    self.l1_batch_with_metadata.metadata.rollup_last_leaf_index,
+                )),
+                Token::FixedBytes(
+                    self.l1_batch_with_metadata
+                        .metadata
+                        .merkle_root_hash
+                        .as_bytes()
+                        .to_vec(),
+                ),
+                Token::Uint(U256::from(self.l1_batch_with_metadata.header.l1_tx_count)),
+                Token::FixedBytes(
+                    self.l1_batch_with_metadata
+                        .metadata
+                        .l2_l1_merkle_root
+                        .as_bytes()
+                        .to_vec(),
+                ),
                 Token::FixedBytes(
-                    self.0
+                    self.l1_batch_with_metadata
                         .header
                         .priority_ops_onchain_data_hash()
                         .as_bytes()
                         .to_vec(),
                 ),
-                Token::Bytes(self.0.metadata.initial_writes_compressed.clone().unwrap()),
-                Token::Bytes(self.0.metadata.repeated_writes_compressed.clone().unwrap()),
+                Token::Bytes(
+                    self.l1_batch_with_metadata
+                        .metadata
+                        .initial_writes_compressed
+                        .clone()
+                        .unwrap(),
+                ),
+                Token::Bytes(
+                    self.l1_batch_with_metadata
+                        .metadata
+                        .repeated_writes_compressed
+                        .clone()
+                        .unwrap(),
+                ),
                 Token::Bytes(pre_boojum_serialize_commitments(
-                    &self.0.header.l2_to_l1_logs,
+                    &self.l1_batch_with_metadata.header.l2_to_l1_logs,
                 )),
                 Token::Array(
-                    self.0
+                    self.l1_batch_with_metadata
                         .header
                         .l2_to_l1_messages
                         .iter()
@@ -54,7 +156,7 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                         .collect(),
                 ),
                 Token::Array(
-                    self.0
+                    self.l1_batch_with_metadata
                         .raw_published_factory_deps
                         .iter()
                         .map(|bytecode| Token::Bytes(bytecode.to_vec()))
@@ -64,18 +166,26 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
         } else {
             Token::Tuple(vec![
                 // `batchNumber`
-                Token::Uint(U256::from(self.0.header.number.0)),
+                Token::Uint(U256::from(self.l1_batch_with_metadata.header.number.0)),
                 // `timestamp`
-                Token::Uint(U256::from(self.0.header.timestamp)),
+                Token::Uint(U256::from(self.l1_batch_with_metadata.header.timestamp)),
                 // `indexRepeatedStorageChanges`
-                Token::Uint(U256::from(self.0.metadata.rollup_last_leaf_index)),
+                Token::Uint(U256::from(
+                    self.l1_batch_with_metadata.metadata.rollup_last_leaf_index,
+                )),
                 // `newStateRoot`
-                Token::FixedBytes(self.0.metadata.merkle_root_hash.as_bytes().to_vec()),
+                Token::FixedBytes(
+                    self.l1_batch_with_metadata
+                        .metadata
+                        .merkle_root_hash
+                        .as_bytes()
+                        .to_vec(),
+                ),
                 // `numberOfLayer1Txs`
-                Token::Uint(U256::from(self.0.header.l1_tx_count)),
+                Token::Uint(U256::from(self.l1_batch_with_metadata.header.l1_tx_count)),
                 // `priorityOperationsHash`
                 Token::FixedBytes(
-                    self.0
+                    self.l1_batch_with_metadata
                         .header
                         .priority_ops_onchain_data_hash()
                         .as_bytes()
@@ -83,7 +193,7 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                 ),
                 // `bootloaderHeapInitialContentsHash`
                 Token::FixedBytes(
-                    self.0
+                    self.l1_batch_with_metadata
                         .metadata
                         .bootloader_initial_content_commitment
                         .unwrap()
@@ -92,7 +202,7 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                 ),
                 // `eventsQueueStateHash`
                 Token::FixedBytes(
-                    self.0
+                    self.l1_batch_with_metadata
                         .metadata
                         .events_queue_commitment
                         .unwrap()
@@ -100,15 +210,16 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                         .to_vec(),
                 ),
                 // `systemLogs`
-                Token::Bytes(serialize_commitments(&self.0.header.system_logs)),
-                // `totalL2ToL1Pubdata`
-                Token::Bytes(
-                    self.0
-                        .header
-                        .pubdata_input
-                        .clone()
-                        .unwrap_or(self.0.construct_pubdata()),
-                ),
+                Token::Bytes(serialize_commitments(
+                    &self.l1_batch_with_metadata.header.system_logs,
+                )),
+                if protocol_version.is_pre_1_4_2() {
+                    // `totalL2ToL1Pubdata`
+                    Token::Bytes(self.pubdata_input())
+                } else {
+                    // `pubdataCommitments`
+                    Token::Bytes(self.pubdata_commitments())
+                },
             ])
         }
     }
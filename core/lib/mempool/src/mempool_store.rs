@@ -1,7 +1,8 @@
 use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
+    U256,
 };
 
 use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
@@ -10,6 +11,10 @@ use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    /// Number of L2 transactions removed from the mempool together with `stashed_accounts`.
+    pub stashed_transaction_count: usize,
+    /// Number of L2 transactions removed from the mempool together with `purged_accounts`.
+    pub purged_transaction_count: usize,
 }
 
 #[derive(Debug)]
@@ -17,6 +22,12 @@ pub struct MempoolStats {
     pub l1_transaction_count: usize,
     pub l2_transaction_count: u64,
     pub l2_priority_queue_size: usize,
+    /// Receipt timestamp of the oldest pending L1 transaction.
+    pub oldest_l1_transaction_received_at_ms: Option<u64>,
+    /// Receipt timestamp of the oldest L2 transaction in the priority queue.
+    pub oldest_l2_transaction_received_at_ms: Option<u64>,
+    /// Account with the most pending L2 transactions together with the number of these transactions.
+    pub largest_account: Option<(Address, usize)>,
 }
 
 #[derive(Debug)]
@@ -30,6 +41,8 @@ pub struct MempoolStore {
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
+    /// Number of L2 transactions removed together with `stashed_accounts`.
+    stashed_transaction_count: usize,
     /// Number of L2 transactions in the mempool.
     size: u64,
     capacity: u64,
@@ -43,6 +56,7 @@ impl MempoolStore {
             l2_priority_queue: BTreeSet::new(),
             next_priority_id,
             stashed_accounts: vec![],
+            stashed_transaction_count: 0,
             size: 0,
             capacity,
         }
@@ -132,11 +146,28 @@ impl MempoolStore {
                 .is_some()
     }
 
+    /// Returns next L1 transaction for execution from mempool, ignoring L2 transactions.
+    pub fn next_l1_transaction(&mut self) -> Option<Transaction> {
+        let transaction = self.l1_transactions.remove(&self.next_priority_id)?;
+        self.next_priority_id += 1;
+        Some(transaction.into())
+    }
+
     /// Returns next transaction for execution from mempool
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
-        if let Some(transaction) = self.l1_transactions.remove(&self.next_priority_id) {
-            self.next_priority_id += 1;
-            return Some(transaction.into());
+        self.next_transaction_excluding(filter, &HashSet::new())
+    }
+
+    /// Returns next transaction for execution from mempool, skipping L2 transactions initiated by
+    /// `excluded_accounts`. Unlike transactions not matching the filter, transactions of excluded accounts
+    /// are not stashed; they can be returned by subsequent calls once the account is no longer excluded.
+    pub fn next_transaction_excluding(
+        &mut self,
+        filter: &L2TxFilter,
+        excluded_accounts: &HashSet<Address>,
+    ) -> Option<Transaction> {
+        if let Some(transaction) = self.next_l1_transaction() {
+            return Some(transaction);
         }
 
         let mut removed = 0;
@@ -144,16 +175,21 @@ impl MempoolStore {
         let tx_pointer = self
             .l2_priority_queue
             .iter()
-            .rfind(|el| el.matches_filter(filter))?
+            .rfind(|el| el.matches_filter(filter) && !excluded_accounts.contains(&el.account))?
             .clone();
 
-        // Stash all observed transactions that don't meet criteria
+        // Stash all observed transactions that don't meet criteria, except for ones of excluded accounts
+        let mut skipped_pointers = vec![];
         for stashed_pointer in self
             .l2_priority_queue
             .split_off(&tx_pointer)
             .into_iter()
             .skip(1)
         {
+            if excluded_accounts.contains(&stashed_pointer.account) {
+                skipped_pointers.push(stashed_pointer);
+                continue;
+            }
             removed += self
                 .l2_transactions_per_account
                 .remove(&stashed_pointer.account)
@@ -162,6 +198,8 @@ impl MempoolStore {
 
             self.stashed_accounts.push(stashed_pointer.account);
         }
+        self.l2_priority_queue.extend(skipped_pointers);
+        self.stashed_transaction_count += removed;
         // insert pointer to the next transaction if it exists
         let (transaction, score) = self
             .l2_transactions_per_account
@@ -205,19 +243,79 @@ impl MempoolStore {
         }
     }
 
+    /// Removes a pending L2 transaction from the mempool. Returns `false` if the transaction is not in the mempool
+    /// (e.g., it has already been sent to the state keeper). Subsequent transactions of the same account are retained;
+    /// they can be executed once a transaction filling the nonce gap is inserted.
+    pub fn remove_l2_transaction(&mut self, account: Address, nonce: Nonce, hash: H256) -> bool {
+        let Some(account_txs) = self.l2_transactions_per_account.get_mut(&account) else {
+            return false;
+        };
+        let Some(score) = account_txs.remove(nonce, hash) else {
+            return false;
+        };
+        if let Some(score) = score {
+            self.l2_priority_queue.remove(&score);
+        }
+        self.size -= 1;
+        true
+    }
+
+    /// Changes the max number of L2 transactions in the mempool. If the mempool exceeds the new capacity,
+    /// transactions are purged on the next [`Self::get_mempool_info()`] call.
+    pub fn set_capacity(&mut self, capacity: u64) {
+        self.capacity = capacity;
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
+        let size_before_gc = self.size;
+        let purged_accounts = self.gc();
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
-            purged_accounts: self.gc(),
+            purged_accounts,
+            stashed_transaction_count: std::mem::take(&mut self.stashed_transaction_count),
+            purged_transaction_count: (size_before_gc - self.size) as usize,
         }
     }
 
+    /// Returns mempool statistics. This takes time linear in the number of accounts in the mempool.
     pub fn stats(&self) -> MempoolStats {
+        let oldest_l1_transaction_received_at_ms = self
+            .l1_transactions
+            .values()
+            .map(|tx| tx.received_timestamp_ms)
+            .min();
+        // The priority queue is ordered by the reverse receipt timestamp, so the oldest transaction is the last one.
+        let oldest_l2_transaction_received_at_ms = self
+            .l2_priority_queue
+            .last()
+            .map(|score| score.received_at_ms);
+        let largest_account = self
+            .l2_transactions_per_account
+            .iter()
+            .map(|(&address, txs)| (address, txs.len()))
+            .max_by_key(|&(_, tx_count)| tx_count);
+
         MempoolStats {
             l1_transaction_count: self.l1_transactions.len(),
             l2_transaction_count: self.size,
             l2_priority_queue_size: self.l2_priority_queue.len(),
+            oldest_l1_transaction_received_at_ms,
+            oldest_l2_transaction_received_at_ms,
+            largest_account,
+        }
+    }
+
+    /// Splits transactions in the L2 priority queue into buckets by their `max_fee_per_gas`.
+    /// `upper_bounds` must be sorted in the ascending order; the returned vector has one more element
+    /// than `upper_bounds`, with the last element counting transactions exceeding all bounds.
+    pub fn l2_priority_queue_fee_distribution(&self, upper_bounds: &[U256]) -> Vec<usize> {
+        let mut distribution = vec![0; upper_bounds.len() + 1];
+        for score in &self.l2_priority_queue {
+            let fee_per_gas = score.fee_data.max_fee_per_gas;
+            let bucket_idx = upper_bounds.partition_point(|&bound| bound < fee_per_gas);
+            distribution[bucket_idx] += 1;
         }
+        distribution
     }
 
     fn gc(&mut self) -> Vec<Address> {
@@ -142,6 +142,25 @@ fn l1_txns_priority_id() {
     }
 }
 
+#[test]
+fn taking_only_l1_txns() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account, Nonce(0)),
+        gen_l1_tx(PriorityOpId(0)),
+        gen_l1_tx(PriorityOpId(2)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    assert!(mempool.next_l1_transaction().unwrap().is_l1());
+    // The L1 transaction with the next priority ID is missing.
+    assert!(mempool.next_l1_transaction().is_none());
+    assert!(!mempool
+        .next_transaction(&L2TxFilter::default())
+        .unwrap()
+        .is_l1());
+}
+
 #[test]
 fn rejected_tx() {
     let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
@@ -315,10 +334,46 @@ fn stashed_accounts() {
         view(mempool.next_transaction(&filter_non_zero)),
         (account1, 0)
     );
-    assert_eq!(mempool.get_mempool_info().stashed_accounts, vec![account0]);
+    let mempool_info = mempool.get_mempool_info();
+    assert_eq!(mempool_info.stashed_accounts, vec![account0]);
+    assert_eq!(mempool_info.stashed_transaction_count, 2);
+    assert_eq!(mempool.get_mempool_info().stashed_transaction_count, 0);
     assert!(mempool.next_transaction(&filter_zero).is_none());
 }
 
+#[test]
+fn excluding_accounts() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_timestamp(account0, Nonce(0), 0),
+        gen_l2_tx_with_timestamp(account0, Nonce(1), 0),
+        gen_l2_tx_with_timestamp(account1, Nonce(0), 1),
+        gen_l1_tx(PriorityOpId(0)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    let excluded = HashSet::from([account0]);
+    let filter = L2TxFilter::default();
+
+    // L1 transactions are never excluded.
+    assert!(mempool
+        .next_transaction_excluding(&filter, &excluded)
+        .unwrap()
+        .is_l1());
+    assert_eq!(
+        view(mempool.next_transaction_excluding(&filter, &excluded)),
+        (account1, 0)
+    );
+    assert!(mempool
+        .next_transaction_excluding(&filter, &excluded)
+        .is_none());
+    // Transactions of the excluded account must not be stashed.
+    assert!(mempool.get_mempool_info().stashed_accounts.is_empty());
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 1));
+}
+
 #[test]
 fn mempool_capacity() {
     let mut mempool = MempoolStore::new(PriorityOpId(0), 5);
@@ -334,10 +389,12 @@ fn mempool_capacity() {
     ];
     mempool.insert(transactions, HashMap::new());
     // the mempool is full. Accounts with non-sequential nonces got stashed
+    let mempool_info = mempool.get_mempool_info();
     assert_eq!(
-        HashSet::<_>::from_iter(mempool.get_mempool_info().purged_accounts),
+        HashSet::<_>::from_iter(mempool_info.purged_accounts),
         HashSet::<_>::from_iter(vec![account1, account2]),
     );
+    assert_eq!(mempool_info.purged_transaction_count, 2);
     // verify that existing good-to-go transactions and new ones got picked
     mempool.insert(
         vec![gen_l2_tx_with_timestamp(
@@ -365,6 +422,108 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn changing_mempool_capacity() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 5);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx(account1, Nonce(1)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    assert!(mempool.get_mempool_info().purged_accounts.is_empty());
+
+    mempool.set_capacity(2);
+    let mempool_info = mempool.get_mempool_info();
+    assert_eq!(mempool_info.purged_accounts, [account1]);
+    assert_eq!(mempool_info.purged_transaction_count, 1);
+}
+
+#[test]
+fn mempool_stats() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let stats = mempool.stats();
+    assert_eq!(stats.oldest_l1_transaction_received_at_ms, None);
+    assert_eq!(stats.oldest_l2_transaction_received_at_ms, None);
+    assert_eq!(stats.largest_account, None);
+
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let mut transactions = vec![
+        gen_l2_tx_with_timestamp(account0, Nonce(0), 300),
+        gen_l2_tx_with_timestamp(account0, Nonce(1), 100),
+        gen_l2_tx_with_timestamp(account0, Nonce(2), 400),
+        gen_l2_tx_with_timestamp(account1, Nonce(0), 200),
+        gen_l1_tx(PriorityOpId(0)),
+    ];
+    for (tx, max_fee_per_gas) in transactions.iter_mut().zip([10_u32, 10, 10, 25]) {
+        let ExecuteTransactionCommon::L2(data) = &mut tx.common_data else {
+            unreachable!();
+        };
+        data.fee.max_fee_per_gas = max_fee_per_gas.into();
+    }
+    mempool.insert(transactions, HashMap::new());
+
+    let stats = mempool.stats();
+    assert_eq!(stats.oldest_l1_transaction_received_at_ms, Some(0));
+    // The transaction with nonce 1 is older, but it's not in the priority queue.
+    assert_eq!(stats.oldest_l2_transaction_received_at_ms, Some(200));
+    assert_eq!(stats.largest_account, Some((account0, 3)));
+
+    let upper_bounds = [U256::from(5), U256::from(10), U256::from(20)];
+    let distribution = mempool.l2_priority_queue_fee_distribution(&upper_bounds);
+    assert_eq!(distribution, [0, 1, 0, 1]);
+    let distribution = mempool.l2_priority_queue_fee_distribution(&[]);
+    assert_eq!(distribution, [2]);
+}
+
+#[test]
+fn removing_l2_transaction() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account, Nonce(0)),
+        gen_l2_tx(account, Nonce(1)),
+        gen_l2_tx(account, Nonce(2)),
+    ];
+    let hashes: Vec<_> = transactions.iter().map(Transaction::hash).collect();
+    mempool.insert(transactions, HashMap::new());
+
+    assert!(!mempool.remove_l2_transaction(account, Nonce(1), H256::random()));
+    assert!(!mempool.remove_l2_transaction(Address::random(), Nonce(1), hashes[1]));
+    assert!(mempool.remove_l2_transaction(account, Nonce(1), hashes[1]));
+    assert!(!mempool.remove_l2_transaction(account, Nonce(1), hashes[1]));
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 0)
+    );
+    // The remaining transaction cannot be executed until the nonce gap is filled.
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+    mempool.insert(vec![gen_l2_tx(account, Nonce(1))], HashMap::new());
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 1)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 2)
+    );
+
+    // Removing the next transaction of an account should remove it from the priority queue.
+    let transaction = gen_l2_tx(account, Nonce(3));
+    let hash = transaction.hash();
+    mempool.insert(vec![transaction], HashMap::new());
+    assert_eq!(mempool.stats().l2_priority_queue_size, 1);
+    assert!(mempool.remove_l2_transaction(account, Nonce(3), hash));
+    assert_eq!(mempool.stats().l2_priority_queue_size, 0);
+    assert_eq!(mempool.stats().l2_transaction_count, 0);
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -380,6 +539,7 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
         None,
         Default::default(),
     );
+    txn.set_input(vec![], H256::random());
     txn.received_timestamp_ms = received_at_ms;
     txn.into()
 }
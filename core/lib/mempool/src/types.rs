@@ -1,7 +1,7 @@
 use std::{cmp::Ordering, collections::HashMap};
 
 use zksync_types::{
-    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, U256,
+    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, H256, U256,
 };
 
 /// Pending mempool transactions of account
@@ -70,6 +70,17 @@ impl AccountTransactions {
             .map(Self::score_for_transaction)
     }
 
+    /// Removes the transaction with the specified nonce and hash. Returns `None` if there is no such transaction;
+    /// otherwise, returns the score of the removed transaction if it was the next transaction of the account.
+    pub fn remove(&mut self, nonce: Nonce, hash: H256) -> Option<Option<MempoolScore>> {
+        let transaction = self.transactions.get(&nonce)?;
+        if transaction.hash() != hash {
+            return None;
+        }
+        let transaction = self.transactions.remove(&nonce)?;
+        Some((nonce == self.nonce).then(|| Self::score_for_transaction(&transaction)))
+    }
+
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
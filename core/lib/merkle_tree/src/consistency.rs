@@ -36,6 +36,8 @@ pub enum ConsistencyError {
     HashMismatch {
         key: NodeKey,
         nibble: u8,
+        /// Version of the child node.
+        child_version: u64,
         expected: ValueHash,
         actual: ValueHash,
     },
@@ -66,6 +68,25 @@ pub enum ConsistencyError {
     RootVersionMismatch { max_child_version: u64 },
 }
 
+impl ConsistencyError {
+    /// Returns the earliest tree version that may contain corrupted nodes causing this error. Returns `None`
+    /// if the error isn't attributed to specific nodes (e.g., if the checked tree version is missing).
+    pub fn corrupted_version(&self) -> Option<u64> {
+        match self {
+            Self::MissingNode { key, .. }
+            | Self::TerminalInternalNode { key }
+            | Self::FullKeyMismatch { key, .. }
+            | Self::EmptyInternalNode { key } => Some(key.version),
+            Self::HashMismatch { child_version, .. } => Some(*child_version),
+            Self::KeyVersionMismatch {
+                key,
+                expected_version,
+            } => Some(key.version.min(*expected_version)),
+            _ => None,
+        }
+    }
+}
+
 impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
     /// Verifies the internal tree consistency as stored in the database.
     ///
@@ -106,6 +127,79 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         Ok(())
     }
 
+    /// Verifies consistency of a single subtree of the tree as stored in the database. The subtree
+    /// is rooted at the node `depth` levels (nibbles) down the path to `key`, or at the last node
+    /// on this path if the path is shorter. Besides the subtree itself, this checks hashes
+    /// of all nodes on the path from the tree root to the subtree root.
+    ///
+    /// Unlike [`Self::verify_consistency()`], this check doesn't validate leaf indices and is cheap enough
+    /// to be run on large trees periodically.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (the first encountered one if there are multiple).
+    pub fn verify_subtree_consistency(
+        &self,
+        version: u64,
+        key: &Key,
+        depth: usize,
+    ) -> Result<(), ConsistencyError> {
+        let manifest = self.db.try_manifest()?;
+        let manifest = manifest.ok_or(ConsistencyError::MissingVersion(version))?;
+        if version >= manifest.version_count {
+            return Err(ConsistencyError::MissingVersion(version));
+        }
+
+        let root = self
+            .db
+            .try_root(version)?
+            .ok_or(ConsistencyError::MissingRoot(version))?;
+        let Root::Filled { node, .. } = root else {
+            return Ok(());
+        };
+
+        let mut node = node;
+        let mut node_key = Nibbles::EMPTY.with_version(version);
+        for nibble_idx in 0..depth {
+            let Node::Internal(internal_node) = &node else {
+                break;
+            };
+            let nibble = Nibbles::nibble(key, nibble_idx);
+            let Some(child_ref) = internal_node.child_ref(nibble).copied() else {
+                // The subtree at `key` is empty, and all nodes on the path to it are checked.
+                return Ok(());
+            };
+            let child_key = node_key
+                .nibbles
+                .push(nibble)
+                .ok_or(ConsistencyError::TerminalInternalNode { key: node_key })?;
+            let child_key = child_key.with_version(child_ref.version);
+            let child = self
+                .db
+                .try_tree_node(&child_key, child_ref.is_leaf)?
+                .ok_or(ConsistencyError::MissingNode {
+                    key: child_key,
+                    is_leaf: child_ref.is_leaf,
+                })?;
+
+            let level = child_key.nibbles.nibble_count() * 4;
+            let child_hash = child.hash(&mut HasherWithStats::new(&self.hasher), level);
+            if child_hash != child_ref.hash {
+                return Err(ConsistencyError::HashMismatch {
+                    key: node_key,
+                    nibble,
+                    child_version: child_ref.version,
+                    expected: child_ref.hash,
+                    actual: child_hash,
+                });
+            }
+            node = child;
+            node_key = child_key;
+        }
+        self.validate_node(&node, node_key, None)?;
+        Ok(())
+    }
+
     fn validate_node(
         &self,
         node: &Node,
@@ -170,6 +264,7 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
                             Err(ConsistencyError::HashMismatch {
                                 key,
                                 nibble,
+                                child_version: child_ref.version,
                                 expected: child_ref.hash,
                                 actual: child_hash,
                             })
@@ -516,4 +611,65 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn subtree_consistency_checks() {
+        let db = prepare_database();
+        let tree = MerkleTree::new(db);
+        for depth in 0..=64 {
+            tree.verify_subtree_consistency(0, &FIRST_KEY, depth)
+                .unwrap();
+            tree.verify_subtree_consistency(0, &SECOND_KEY, depth)
+                .unwrap();
+            // The subtree for this key is empty.
+            tree.verify_subtree_consistency(0, &U256::zero(), depth)
+                .unwrap();
+        }
+
+        let err = tree
+            .verify_subtree_consistency(1, &FIRST_KEY, 1)
+            .unwrap_err();
+        assert_matches!(err, ConsistencyError::MissingVersion(1));
+    }
+
+    #[test]
+    fn subtree_hash_mismatch_error() {
+        let mut db = prepare_database();
+        let leaf_key = db.nodes_mut().find_map(|(key, node)| {
+            if let Node::Leaf(leaf) = node {
+                if leaf.full_key == FIRST_KEY {
+                    leaf.value_hash = ValueHash::zero();
+                    return Some(*key);
+                }
+            }
+            None
+        });
+        let leaf_key = leaf_key.unwrap();
+        let tree = MerkleTree::new(db);
+
+        // The corrupted leaf is either in the checked subtree, or on the path to it.
+        for depth in [0, 1, 2, 64] {
+            let err = tree
+                .verify_subtree_consistency(0, &FIRST_KEY, depth)
+                .unwrap_err();
+            assert_matches!(
+                err,
+                ConsistencyError::HashMismatch {
+                    child_version: 0,
+                    ..
+                }
+            );
+            assert_eq!(err.corrupted_version(), Some(0));
+        }
+        let err = tree
+            .verify_subtree_consistency(0, &FIRST_KEY, 64)
+            .unwrap_err();
+        let ConsistencyError::HashMismatch { key, nibble, .. } = err else {
+            unreachable!();
+        };
+        assert_eq!(key.nibbles.push(nibble).unwrap(), leaf_key.nibbles);
+
+        // The subtree for the second key is not affected if it's split from the first key.
+        tree.verify_subtree_consistency(0, &SECOND_KEY, 64).unwrap();
+    }
 }
@@ -1,5 +1,7 @@
 //! Tying the Merkle tree implementation to the problem domain.
 
+use std::path::Path;
+
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_prover_interface::inputs::{PrepareBasicCircuitsJob, StorageLogMetadata};
@@ -9,12 +11,13 @@ use zksync_types::{
 };
 
 use crate::{
+    snapshot::{SnapshotError, SnapshotManifest},
     storage::{PatchSet, Patched, RocksDBWrapper},
     types::{
         Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, ValueHash,
         TREE_DEPTH,
     },
-    BlockOutput, HashTree, MerkleTree, NoVersionError,
+    BlockOutput, ConsistencyError, HashTree, MerkleTree, NoVersionError,
 };
 
 /// Metadata for the current tree state.
@@ -430,6 +433,59 @@ impl ZkSyncTreeReader {
         self.0.latest_root().leaf_count()
     }
 
+    /// Returns the root hash of the tree after applying the specified L1 batch, or `None` if the tree
+    /// doesn't have the corresponding version (e.g., because it was pruned).
+    pub fn root_hash_for_l1_batch(&self, l1_batch_number: L1BatchNumber) -> Option<ValueHash> {
+        self.0.root_hash(u64::from(l1_batch_number.0))
+    }
+
+    /// Verifies consistency of a single subtree of the tree version corresponding to the specified L1 batch.
+    /// See [`MerkleTree::verify_subtree_consistency()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an inconsistency is detected.
+    pub fn verify_subtree_consistency(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        key: &Key,
+        depth: usize,
+    ) -> Result<(), ConsistencyError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.verify_subtree_consistency(version, key, depth)
+    }
+
+    /// Exports the tree version corresponding to the specified L1 batch as a snapshot into `dir`.
+    /// See [`MerkleTree::export_snapshot()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version is missing or if writing snapshot files fails.
+    pub fn export_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        dir: &Path,
+        chunk_size: usize,
+    ) -> Result<SnapshotManifest, SnapshotError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.export_snapshot(version, dir, chunk_size)
+    }
+
+    /// Reads entries with the specified keys from the tree. The entries are returned in the same order as requested.
+    /// Unlike [`Self::entries_with_proofs()`], this doesn't compute Merkle proofs, so it's much cheaper.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: &[Key],
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.entries(version, keys)
+    }
+
     /// Reads entries together with Merkle proofs with the specified keys from the tree. The entries are returned
     /// in the same order as requested.
     ///
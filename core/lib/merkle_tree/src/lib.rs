@@ -49,6 +49,7 @@
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 
 pub use crate::{
+    consistency::ConsistencyError,
     errors::NoVersionError,
     hasher::{HashTree, TreeRangeDigest},
     pruning::{MerkleTreePruner, MerkleTreePrunerHandle},
@@ -71,6 +72,7 @@ mod hasher;
 mod metrics;
 mod pruning;
 pub mod recovery;
+pub mod snapshot;
 mod storage;
 mod types;
 mod utils;
@@ -346,6 +346,9 @@ impl PruningStats {
     }
 }
 
+/// Compactions can take a long time for large databases, hence the custom buckets.
+const COMPACTION_BUCKETS: Buckets = Buckets::exponential(1.0..=4_096.0, 2.0);
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "merkle_tree_pruning")]
 pub(crate) struct PruningTimings {
@@ -355,6 +358,9 @@ pub(crate) struct PruningTimings {
     /// Time spent removing stale keys from RocksDB per pruning iteration.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub apply_patch: Histogram<Duration>,
+    /// Time spent on a manual database compaction triggered by the pruner.
+    #[metrics(buckets = COMPACTION_BUCKETS)]
+    pub compaction: Histogram<Duration>,
 }
 
 #[vise::register]
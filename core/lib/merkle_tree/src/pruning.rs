@@ -1,6 +1,10 @@
 //! Tree pruning logic.
 
-use std::{fmt, sync::mpsc, time::Duration};
+use std::{
+    fmt,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
 
 use crate::{
     metrics::{PruningStats, PRUNING_TIMINGS},
@@ -37,11 +41,18 @@ impl MerkleTreePrunerHandle {
 /// (in RocksDB, this uses simple pointwise `delete_cf()` operations). The range of versions
 /// depends on pruning policies; for now, it's "remove versions older than `latest_version - N`",
 /// where `N` is a configurable number set when the pruner [is created](Self::new()).
+///
+/// Pointwise deletions do not immediately free disk space; it is reclaimed once RocksDB compacts the affected SST files.
+/// To speed this up, the pruner can be configured to [periodically compact](Self::set_compaction_interval())
+/// the database.
 pub struct MerkleTreePruner<DB> {
     db: DB,
     past_versions_to_keep: u64,
     target_pruned_key_count: usize,
     poll_interval: Duration,
+    compaction_interval: Option<Duration>,
+    last_compaction: Instant,
+    pruned_keys_since_compaction: usize,
     aborted_receiver: mpsc::Receiver<()>,
 }
 
@@ -52,6 +63,7 @@ impl<DB> fmt::Debug for MerkleTreePruner<DB> {
             .field("past_versions_to_keep", &self.past_versions_to_keep)
             .field("target_pruned_key_count", &self.target_pruned_key_count)
             .field("poll_interval", &self.poll_interval)
+            .field("compaction_interval", &self.compaction_interval)
             .finish_non_exhaustive()
     }
 }
@@ -72,6 +84,9 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
             past_versions_to_keep,
             target_pruned_key_count: 500_000,
             poll_interval: Duration::from_secs(60),
+            compaction_interval: None,
+            last_compaction: Instant::now(),
+            pruned_keys_since_compaction: 0,
             aborted_receiver,
         };
         (this, handle)
@@ -94,6 +109,14 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
         self.poll_interval = poll_interval;
     }
 
+    /// Sets the minimum interval between database compactions triggered by the pruner. A compaction is only triggered
+    /// if the pruner has caught up with the tree and has removed some nodes since the previous compaction.
+    ///
+    /// By default, the pruner doesn't trigger compactions, relying on background compactions performed by RocksDB.
+    pub fn set_compaction_interval(&mut self, compaction_interval: Duration) {
+        self.compaction_interval = Some(compaction_interval);
+    }
+
     fn target_retained_version(&self) -> Option<u64> {
         let manifest = self.db.manifest()?;
         let latest_version = manifest.version_count.checked_sub(1)?;
@@ -139,9 +162,35 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
         let apply_patch_latency = PRUNING_TIMINGS.apply_patch.start();
         self.db.prune(patch);
         apply_patch_latency.observe();
+        self.pruned_keys_since_compaction += stats.pruned_key_count;
         Some(stats)
     }
 
+    /// Compacts the database if the compaction schedule requires it. Returns `true` if the database was compacted.
+    fn compact_if_scheduled(&mut self) -> bool {
+        let Some(compaction_interval) = self.compaction_interval else {
+            return false;
+        };
+        if self.pruned_keys_since_compaction == 0
+            || self.last_compaction.elapsed() < compaction_interval
+        {
+            return false;
+        }
+
+        tracing::info!(
+            "Compacting Merkle tree database after pruning {} keys",
+            self.pruned_keys_since_compaction
+        );
+        let compaction_latency = PRUNING_TIMINGS.compaction.start();
+        self.db.compact();
+        let compaction_latency = compaction_latency.observe();
+        tracing::info!("Compacted Merkle tree database in {compaction_latency:?}");
+
+        self.pruned_keys_since_compaction = 0;
+        self.last_compaction = Instant::now();
+        true
+    }
+
     /// Runs this pruner indefinitely until it is aborted by dropping its handle.
     pub fn run(mut self) {
         tracing::info!("Started Merkle tree pruner {self:?}");
@@ -152,10 +201,12 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
                 if has_more_work {
                     Duration::ZERO
                 } else {
+                    self.compact_if_scheduled();
                     self.poll_interval
                 }
             } else {
                 tracing::debug!("No pruning required per specified policies; waiting");
+                self.compact_if_scheduled();
                 self.poll_interval
             };
 
@@ -233,6 +284,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn compaction_is_scheduled_after_pruning() {
+        let mut db = create_db();
+        let (mut pruner, _handle) = MerkleTreePruner::new(&mut db, 0);
+        assert!(!pruner.compact_if_scheduled()); // compaction is not configured
+
+        pruner.set_compaction_interval(Duration::ZERO);
+        assert!(!pruner.compact_if_scheduled()); // nothing is pruned yet
+        pruner.run_once().unwrap();
+        assert!(pruner.compact_if_scheduled());
+        assert_eq!(pruner.pruned_keys_since_compaction, 0);
+        assert!(!pruner.compact_if_scheduled());
+
+        let mut db = create_db();
+        let (mut pruner, _handle) = MerkleTreePruner::new(&mut db, 0);
+        pruner.set_compaction_interval(Duration::from_secs(3_600));
+        pruner.run_once().unwrap();
+        assert!(!pruner.compact_if_scheduled()); // the interval has not elapsed yet
+    }
+
     #[test]
     fn pruner_is_aborted_immediately_when_requested() {
         let (mut pruner, pruner_handle) = MerkleTreePruner::new(PatchSet::default(), 0);
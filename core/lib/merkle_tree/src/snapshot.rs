@@ -0,0 +1,400 @@
+//! Portable Merkle tree snapshots.
+//!
+//! # Overview
+//!
+//! A **snapshot** is a set of files in a directory containing all tree entries at a specific tree version.
+//! Unlike the RocksDB instance backing the tree, a snapshot doesn't depend on the RocksDB version
+//! or configuration, and contains only the data necessary to restore the tree. Snapshots allow
+//! migrating the tree between hosts without rebuilding it from scratch.
+//!
+//! A snapshot is [exported](MerkleTree::export_snapshot()) from an existing tree and is imported
+//! into a tree [being recovered](MerkleTreeRecovery::import_snapshot()). Thus, the imported tree
+//! has the same limitations as any recovered tree (e.g., it doesn't contain versions preceding
+//! the snapshot version).
+//!
+//! # Snapshot format
+//!
+//! A snapshot directory contains a [manifest](SnapshotManifest) (a JSON file named [`MANIFEST_FILE_NAME`])
+//! and one or more chunk files. Each chunk contains tree entries ordered by increasing key;
+//! keys in different chunks do not overlap, and chunks are listed in the manifest in the key order.
+//! Entries in a chunk file are encoded back-to-back in a fixed-size format:
+//!
+//! - 32-byte key (big-endian)
+//! - 32-byte value hash
+//! - 8-byte leaf index (big-endian)
+
+use std::{
+    fs,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    hasher::HashTree,
+    recovery::MerkleTreeRecovery,
+    storage::{Database, PruneDatabase},
+    types::{InternalNode, Key, Nibbles, Node, Root, TreeEntry, ValueHash, KEY_SIZE},
+    MerkleTree, NoVersionError,
+};
+
+/// Name of the manifest file in a snapshot directory.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+/// Size of an encoded entry in a snapshot chunk.
+const ENCODED_ENTRY_SIZE: usize = KEY_SIZE * 2 + 8;
+
+/// Errors that can occur when exporting or importing a tree snapshot.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    /// Exported tree version is missing.
+    #[error(transparent)]
+    NoVersion(#[from] NoVersionError),
+    /// Error accessing a snapshot file.
+    #[error("I/O error accessing {path:?}: {err}")]
+    Io {
+        /// Path to the file.
+        path: PathBuf,
+        /// Underlying error.
+        #[source]
+        err: io::Error,
+    },
+    /// Error (de)serializing the snapshot manifest.
+    #[error("failed (de)serializing snapshot manifest: {0}")]
+    Manifest(#[source] serde_json::Error),
+    /// Snapshot chunk doesn't match its description in the manifest.
+    #[error("snapshot chunk `{file_name}` is malformed: {message}")]
+    MalformedChunk {
+        /// Name of the chunk file.
+        file_name: String,
+        /// Human-readable error description.
+        message: String,
+    },
+    /// Snapshot version differs from the version of the tree being recovered.
+    #[error("snapshot has version {snapshot}, while the tree is being recovered for version {recovered}")]
+    VersionMismatch {
+        /// Snapshot version.
+        snapshot: u64,
+        /// Recovered tree version.
+        recovered: u64,
+    },
+    /// Root hash of the imported tree differs from the one specified in the manifest.
+    #[error("root hash of the imported tree {actual:?} differs from the snapshot root hash {expected:?}")]
+    RootHashMismatch {
+        /// Root hash from the snapshot manifest.
+        expected: ValueHash,
+        /// Actual root hash of the imported tree.
+        actual: ValueHash,
+    },
+}
+
+impl SnapshotError {
+    fn io(path: &Path) -> impl FnOnce(io::Error) -> Self + '_ {
+        move |err| Self::Io {
+            path: path.to_owned(),
+            err,
+        }
+    }
+
+    fn malformed_chunk(chunk: &SnapshotChunk, message: impl Into<String>) -> Self {
+        Self::MalformedChunk {
+            file_name: chunk.file_name.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Description of a single chunk file in a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotChunk {
+    /// Name of the chunk file relative to the snapshot directory.
+    pub file_name: String,
+    /// Number of entries in the chunk.
+    pub entry_count: u64,
+    /// Greatest key in the chunk. Used to skip already imported chunks when resuming the import.
+    pub last_key: Key,
+}
+
+/// Snapshot manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Exported tree version.
+    pub version: u64,
+    /// Root hash of the exported tree version.
+    pub root_hash: ValueHash,
+    /// Total number of entries in the snapshot.
+    pub leaf_count: u64,
+    /// Snapshot chunks ordered by increasing keys.
+    pub chunks: Vec<SnapshotChunk>,
+}
+
+impl SnapshotManifest {
+    /// Reads the manifest from the specified snapshot directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest cannot be read or is malformed.
+    pub fn read(dir: &Path) -> Result<Self, SnapshotError> {
+        let path = dir.join(MANIFEST_FILE_NAME);
+        let file = fs::File::open(&path).map_err(SnapshotError::io(&path))?;
+        serde_json::from_reader(BufReader::new(file)).map_err(SnapshotError::Manifest)
+    }
+
+    fn write(&self, dir: &Path) -> Result<(), SnapshotError> {
+        let path = dir.join(MANIFEST_FILE_NAME);
+        let file = fs::File::create(&path).map_err(SnapshotError::io(&path))?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, self).map_err(SnapshotError::Manifest)?;
+        writer.flush().map_err(SnapshotError::io(&path))
+    }
+}
+
+fn write_chunk(
+    dir: &Path,
+    chunk_index: usize,
+    entries: &[TreeEntry],
+) -> Result<SnapshotChunk, SnapshotError> {
+    let file_name = format!("chunk_{chunk_index:06}.bin");
+    let path = dir.join(&file_name);
+    let file = fs::File::create(&path).map_err(SnapshotError::io(&path))?;
+    let mut writer = BufWriter::new(file);
+    let mut buffer = [0_u8; ENCODED_ENTRY_SIZE];
+    for entry in entries {
+        entry.key.to_big_endian(&mut buffer[..KEY_SIZE]);
+        buffer[KEY_SIZE..2 * KEY_SIZE].copy_from_slice(entry.value.as_bytes());
+        buffer[2 * KEY_SIZE..].copy_from_slice(&entry.leaf_index.to_be_bytes());
+        writer
+            .write_all(&buffer)
+            .map_err(SnapshotError::io(&path))?;
+    }
+    writer.flush().map_err(SnapshotError::io(&path))?;
+
+    Ok(SnapshotChunk {
+        file_name,
+        entry_count: entries.len() as u64,
+        last_key: entries.last().map_or_else(Key::zero, |entry| entry.key),
+    })
+}
+
+fn read_chunk(dir: &Path, chunk: &SnapshotChunk) -> Result<Vec<TreeEntry>, SnapshotError> {
+    let path = dir.join(&chunk.file_name);
+    let file = fs::File::open(&path).map_err(SnapshotError::io(&path))?;
+    let mut bytes = vec![];
+    BufReader::new(file)
+        .read_to_end(&mut bytes)
+        .map_err(SnapshotError::io(&path))?;
+
+    let expected_len = usize::try_from(chunk.entry_count)
+        .ok()
+        .and_then(|count| count.checked_mul(ENCODED_ENTRY_SIZE));
+    if expected_len != Some(bytes.len()) {
+        let message = format!(
+            "unexpected file size {} for {} entries",
+            bytes.len(),
+            chunk.entry_count
+        );
+        return Err(SnapshotError::malformed_chunk(chunk, message));
+    }
+
+    let entries: Vec<_> = bytes
+        .chunks_exact(ENCODED_ENTRY_SIZE)
+        .map(|bytes| {
+            let key = Key::from_big_endian(&bytes[..KEY_SIZE]);
+            let value = ValueHash::from_slice(&bytes[KEY_SIZE..2 * KEY_SIZE]);
+            let mut leaf_index = [0_u8; 8];
+            leaf_index.copy_from_slice(&bytes[2 * KEY_SIZE..]);
+            TreeEntry::new(key, u64::from_be_bytes(leaf_index), value)
+        })
+        .collect();
+
+    let is_sorted = entries
+        .windows(2)
+        .all(|window| window[0].key < window[1].key);
+    if !is_sorted {
+        return Err(SnapshotError::malformed_chunk(
+            chunk,
+            "entries are not ordered by increasing key",
+        ));
+    }
+    if entries.last().map(|entry| entry.key) != Some(chunk.last_key) {
+        return Err(SnapshotError::malformed_chunk(
+            chunk,
+            "last key doesn't match the manifest",
+        ));
+    }
+    Ok(entries)
+}
+
+impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
+    /// Exports the specified tree version as a snapshot into `dir`, which must exist. Each snapshot chunk
+    /// will contain at most `chunk_size` entries.
+    ///
+    /// Tree nodes are traversed depth-first, so that entries are output in the order of increasing keys,
+    /// and only nodes on the path to the currently processed leaf are held in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version is missing or if writing snapshot files fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero, or if the tree is inconsistent (e.g., has missing nodes).
+    pub fn export_snapshot(
+        &self,
+        version: u64,
+        dir: &Path,
+        chunk_size: usize,
+    ) -> Result<SnapshotManifest, SnapshotError> {
+        assert!(chunk_size > 0, "snapshot chunk size must be positive");
+
+        let started_at = Instant::now();
+        let root = self.db.root(version).ok_or_else(|| {
+            let manifest = self.db.manifest().unwrap_or_default();
+            NoVersionError {
+                missing_version: version,
+                version_count: manifest.version_count,
+            }
+        })?;
+        let root_hash = self.root_hash(version).unwrap();
+        // ^ `unwrap()` is safe since the root is present
+        let leaf_count = root.leaf_count();
+        tracing::info!(
+            "Exporting snapshot for tree version {version} with {leaf_count} entries to {dir:?}"
+        );
+
+        let mut chunks = vec![];
+        let mut entries = Vec::with_capacity(chunk_size);
+        if let Root::Filled { node, .. } = root {
+            // Children are pushed to the stack in the reverse order, so that they are popped
+            // in the order of increasing keys.
+            let mut stack = vec![(Nibbles::EMPTY, node)];
+            while let Some((nibbles, node)) = stack.pop() {
+                match node {
+                    Node::Leaf(leaf) => {
+                        entries.push(TreeEntry::from(leaf));
+                        if entries.len() == chunk_size {
+                            chunks.push(write_chunk(dir, chunks.len(), &entries)?);
+                            entries.clear();
+                            tracing::debug!(
+                                "Exported {} / {leaf_count} entries",
+                                chunks.len() * chunk_size
+                            );
+                        }
+                    }
+                    Node::Internal(node) => {
+                        let children = self.load_children(nibbles, &node);
+                        stack.extend(children.into_iter().rev());
+                    }
+                }
+            }
+        }
+        if !entries.is_empty() {
+            chunks.push(write_chunk(dir, chunks.len(), &entries)?);
+        }
+
+        let manifest = SnapshotManifest {
+            version,
+            root_hash,
+            leaf_count,
+            chunks,
+        };
+        manifest.write(dir)?;
+        tracing::info!(
+            "Exported snapshot for tree version {version} in {} chunks in {:?}",
+            manifest.chunks.len(),
+            started_at.elapsed()
+        );
+        Ok(manifest)
+    }
+
+    /// Loads all children of an internal node in a single DB query.
+    fn load_children(&self, nibbles: Nibbles, node: &InternalNode) -> Vec<(Nibbles, Node)> {
+        let child_keys: Vec<_> = node
+            .children()
+            .map(|(nibble, child_ref)| {
+                let child_nibbles = nibbles.push(nibble).unwrap();
+                // ^ `unwrap()` is safe: internal nodes cannot be placed at the tree depth
+                (
+                    child_nibbles.with_version(child_ref.version),
+                    child_ref.is_leaf,
+                )
+            })
+            .collect();
+        let children = self.db.tree_nodes(&child_keys);
+        child_keys
+            .into_iter()
+            .zip(children)
+            .map(|((key, is_leaf), child)| {
+                let child = child.unwrap_or_else(|| {
+                    let kind = if is_leaf { "leaf" } else { "internal node" };
+                    panic!("Tree {kind} with key {key} is missing");
+                });
+                (key.nibbles, child)
+            })
+            .collect()
+    }
+}
+
+impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
+    /// Imports a snapshot from `dir` into this tree. The snapshot must have the same version as the recovered tree.
+    /// After the import, the tree root hash is checked against the snapshot manifest; recovery should be
+    /// [finalized](Self::finalize()) by the caller afterwards.
+    ///
+    /// The import is tolerant to interruptions: chunks already imported into the tree (as determined by
+    /// [`Self::last_processed_key()`]) are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading snapshot files fails, if the snapshot is malformed, or if the resulting
+    /// root hash doesn't match the manifest.
+    pub fn import_snapshot(
+        &mut self,
+        dir: &Path,
+        manifest: &SnapshotManifest,
+    ) -> Result<(), SnapshotError> {
+        if manifest.version != self.recovered_version() {
+            return Err(SnapshotError::VersionMismatch {
+                snapshot: manifest.version,
+                recovered: self.recovered_version(),
+            });
+        }
+
+        let started_at = Instant::now();
+        let last_processed_key = self.last_processed_key();
+        let chunk_count = manifest.chunks.len();
+        for (i, chunk) in manifest.chunks.iter().enumerate() {
+            if last_processed_key.is_some_and(|key| chunk.last_key <= key) {
+                tracing::debug!("Skipping already imported chunk `{}`", chunk.file_name);
+                continue;
+            }
+            let entries = read_chunk(dir, chunk)?;
+            if let (Some(first), Some(last_processed_key)) = (entries.first(), last_processed_key) {
+                if first.key <= last_processed_key {
+                    return Err(SnapshotError::malformed_chunk(
+                        chunk,
+                        "entries overlap with previously imported chunks",
+                    ));
+                }
+            }
+            self.extend_linear(entries);
+            tracing::debug!("Imported chunk {} / {chunk_count}", i + 1);
+        }
+
+        let root_hash = self.root_hash();
+        if root_hash != manifest.root_hash {
+            return Err(SnapshotError::RootHashMismatch {
+                expected: manifest.root_hash,
+                actual: root_hash,
+            });
+        }
+        tracing::info!(
+            "Imported snapshot for tree version {} with {} entries in {:?}",
+            manifest.version,
+            manifest.leaf_count,
+            started_at.elapsed()
+        );
+        Ok(())
+    }
+}
@@ -366,6 +366,12 @@ pub trait PruneDatabase: Database {
 
     /// Atomically prunes the tree and updates information about the minimum retained version.
     fn prune(&mut self, patch: PrunePatchSet);
+
+    /// Compacts the database so that the space occupied by pruned nodes is reclaimed. The default implementation
+    /// is a no-op, which is appropriate for in-memory databases.
+    fn compact(&mut self) {
+        // Do nothing
+    }
 }
 
 impl<T: PruneDatabase + ?Sized> PruneDatabase for &mut T {
@@ -380,6 +386,10 @@ impl<T: PruneDatabase + ?Sized> PruneDatabase for &mut T {
     fn prune(&mut self, patch: PrunePatchSet) {
         (**self).prune(patch);
     }
+
+    fn compact(&mut self) {
+        (**self).compact();
+    }
 }
 
 impl PruneDatabase for PatchSet {
@@ -285,6 +285,12 @@ impl PruneDatabase for RocksDBWrapper {
             .write(write_batch)
             .expect("Failed writing a batch to RocksDB");
     }
+
+    fn compact(&mut self) {
+        for &cf in MerkleTreeColumnFamily::ALL {
+            self.db.compact_cf(cf);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -536,7 +536,9 @@ mod rocksdb {
     use serde::{Deserialize, Serialize};
     use serde_with::{hex::Hex, serde_as};
     use tempfile::TempDir;
-    use zksync_merkle_tree::{MerkleTreeColumnFamily, MerkleTreePruner, RocksDBWrapper};
+    use zksync_merkle_tree::{
+        MerkleTreeColumnFamily, MerkleTreePruner, PruneDatabase, RocksDBWrapper,
+    };
     use zksync_storage::RocksDB;
 
     use super::*;
@@ -558,7 +560,7 @@ mod rocksdb {
     type KeyValuePair = (Box<[u8]>, Box<[u8]>);
 
     #[serde_as]
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
     struct DatabaseSnapshot {
         #[serde_as(as = "BTreeMap<Hex, Hex>")]
         tree: Vec<KeyValuePair>,
@@ -618,6 +620,22 @@ mod rocksdb {
         insta::assert_yaml_snapshot!(snapshot_name, db_snapshot);
     }
 
+    #[test]
+    fn compacting_pruned_tree() {
+        let Harness { mut db, dir: _dir } = Harness::new();
+        test_intermediate_commits(&mut db, 8);
+        let (mut pruner, _) = MerkleTreePruner::new(&mut db, 0);
+        pruner.run_once();
+        drop(pruner);
+        let db_snapshot = DatabaseSnapshot::new(&db.clone().into_inner());
+
+        db.compact();
+        assert_eq!(DatabaseSnapshot::new(&db.clone().into_inner()), db_snapshot);
+        let tree = MerkleTree::new(&mut db);
+        let latest_version = tree.latest_version().unwrap();
+        tree.verify_consistency(latest_version, true).unwrap();
+    }
+
     #[test]
     fn root_hash_is_computed_correctly_with_reverts() {
         let Harness { mut db, dir: _dir } = Harness::new();
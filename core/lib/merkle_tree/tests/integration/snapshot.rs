@@ -0,0 +1,195 @@
+//! Tests for exporting and importing tree snapshots.
+
+use std::fs;
+
+use assert_matches::assert_matches;
+use tempfile::TempDir;
+use test_casing::test_casing;
+use zksync_crypto::hasher::blake2::Blake2Hasher;
+use zksync_merkle_tree::{
+    recovery::MerkleTreeRecovery,
+    snapshot::{SnapshotError, SnapshotManifest},
+    HashTree, MerkleTree, PatchSet, RocksDBWrapper, TreeEntry, ValueHash,
+};
+
+use crate::common::{generate_key_value_pairs, ENTRIES_AND_HASH};
+
+/// Creates a tree with 2 versions, so that nodes in the latest version have different versions.
+fn create_tree() -> MerkleTree<PatchSet> {
+    let (kvs, _) = &*ENTRIES_AND_HASH;
+    let mut tree = MerkleTree::new(PatchSet::default());
+    tree.extend(kvs[..50].to_vec());
+    let mut updated_kvs = kvs[50..].to_vec();
+    updated_kvs.extend(
+        kvs[..10]
+            .iter()
+            .map(|entry| entry.with_value(ValueHash::repeat_byte(0x23))),
+    );
+    tree.extend(updated_kvs);
+    tree
+}
+
+fn import_snapshot(dir: &TempDir, manifest: &SnapshotManifest) -> MerkleTree<PatchSet> {
+    let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), manifest.version);
+    recovery.import_snapshot(dir.path(), manifest).unwrap();
+    MerkleTree::new(recovery.finalize())
+}
+
+#[test_casing(4, [1, 7, 50, 1_000])]
+fn exporting_and_importing_snapshot(chunk_size: usize) {
+    let tree = create_tree();
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let manifest = tree.export_snapshot(1, dir.path(), chunk_size).unwrap();
+
+    assert_eq!(manifest.version, 1);
+    assert_eq!(manifest.root_hash, tree.latest_root_hash());
+    assert_eq!(manifest.leaf_count, 100);
+    assert_eq!(manifest.chunks.len(), 100_usize.div_ceil(chunk_size));
+    let entry_count: u64 = manifest.chunks.iter().map(|chunk| chunk.entry_count).sum();
+    assert_eq!(entry_count, 100);
+    assert_eq!(SnapshotManifest::read(dir.path()).unwrap(), manifest);
+
+    let imported_tree = import_snapshot(&dir, &manifest);
+    assert_eq!(imported_tree.latest_version(), Some(1));
+    assert_eq!(imported_tree.latest_root_hash(), manifest.root_hash);
+    imported_tree.verify_consistency(1, true).unwrap();
+
+    let (kvs, _) = &*ENTRIES_AND_HASH;
+    let keys: Vec<_> = kvs.iter().map(|entry| entry.key).collect();
+    assert_eq!(
+        imported_tree.entries(1, &keys).unwrap(),
+        tree.entries(1, &keys).unwrap()
+    );
+}
+
+#[test]
+fn exporting_older_tree_version() {
+    let tree = create_tree();
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let manifest = tree.export_snapshot(0, dir.path(), 16).unwrap();
+    assert_eq!(manifest.leaf_count, 50);
+    assert_eq!(Some(manifest.root_hash), tree.root_hash(0));
+
+    let imported_tree = import_snapshot(&dir, &manifest);
+    assert_eq!(imported_tree.latest_version(), Some(0));
+    assert_eq!(imported_tree.latest_root_hash(), manifest.root_hash);
+}
+
+#[test]
+fn exporting_empty_tree() {
+    let mut tree = MerkleTree::new(PatchSet::default());
+    tree.extend(vec![]);
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let manifest = tree.export_snapshot(0, dir.path(), 16).unwrap();
+    assert_eq!(manifest.leaf_count, 0);
+    assert!(manifest.chunks.is_empty());
+    assert_eq!(manifest.root_hash, Blake2Hasher.empty_tree_hash());
+
+    let imported_tree = import_snapshot(&dir, &manifest);
+    assert_eq!(imported_tree.latest_version(), Some(0));
+    assert_eq!(imported_tree.latest_root_hash(), manifest.root_hash);
+}
+
+#[test]
+fn exporting_missing_tree_version() {
+    let tree = create_tree();
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let err = tree.export_snapshot(2, dir.path(), 16).unwrap_err();
+    assert_matches!(err, SnapshotError::NoVersion(_));
+}
+
+#[test]
+fn resuming_snapshot_import() {
+    let tree = create_tree();
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let manifest = tree.export_snapshot(1, dir.path(), 10).unwrap();
+
+    let temp_dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+    let mut db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+    // Simulate an interrupted import by only importing a part of the snapshot chunks.
+    let mut partial_manifest = manifest.clone();
+    partial_manifest.chunks.truncate(4);
+    let mut recovery = MerkleTreeRecovery::new(&mut db, 1);
+    let err = recovery
+        .import_snapshot(dir.path(), &partial_manifest)
+        .unwrap_err();
+    assert_matches!(err, SnapshotError::RootHashMismatch { .. });
+    let last_imported_key = partial_manifest.chunks.last().unwrap().last_key;
+    assert_eq!(recovery.last_processed_key(), Some(last_imported_key));
+
+    // Remove imported chunks to check that they are not read again.
+    for chunk in &partial_manifest.chunks {
+        fs::remove_file(dir.path().join(&chunk.file_name)).unwrap();
+    }
+    let mut recovery = MerkleTreeRecovery::new(&mut db, 1);
+    recovery.import_snapshot(dir.path(), &manifest).unwrap();
+    let imported_tree = MerkleTree::new(recovery.finalize());
+    assert_eq!(imported_tree.latest_root_hash(), manifest.root_hash);
+    imported_tree.verify_consistency(1, true).unwrap();
+}
+
+#[test]
+fn importing_snapshot_with_different_version() {
+    let tree = create_tree();
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let manifest = tree.export_snapshot(1, dir.path(), 16).unwrap();
+
+    let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 2);
+    let err = recovery.import_snapshot(dir.path(), &manifest).unwrap_err();
+    assert_matches!(
+        err,
+        SnapshotError::VersionMismatch {
+            snapshot: 1,
+            recovered: 2
+        }
+    );
+}
+
+#[test]
+fn importing_truncated_chunk() {
+    let tree = create_tree();
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let manifest = tree.export_snapshot(1, dir.path(), 16).unwrap();
+    let chunk_path = dir.path().join(&manifest.chunks[1].file_name);
+    let chunk_bytes = fs::read(&chunk_path).unwrap();
+    fs::write(&chunk_path, &chunk_bytes[..chunk_bytes.len() - 1]).unwrap();
+
+    let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 1);
+    let err = recovery.import_snapshot(dir.path(), &manifest).unwrap_err();
+    assert_matches!(
+        err,
+        SnapshotError::MalformedChunk { file_name, .. } if file_name == manifest.chunks[1].file_name
+    );
+}
+
+#[test]
+fn importing_tampered_snapshot() {
+    let tree = create_tree();
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let manifest = tree.export_snapshot(1, dir.path(), 16).unwrap();
+    let chunk_path = dir.path().join(&manifest.chunks[0].file_name);
+    let mut chunk_bytes = fs::read(&chunk_path).unwrap();
+    // Change the value of the first entry.
+    chunk_bytes[32] ^= 1;
+    fs::write(&chunk_path, &chunk_bytes).unwrap();
+
+    let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 1);
+    let err = recovery.import_snapshot(dir.path(), &manifest).unwrap_err();
+    assert_matches!(err, SnapshotError::RootHashMismatch { expected, .. } if expected == manifest.root_hash);
+}
+
+#[test]
+fn importing_snapshot_into_tree_with_new_entries() {
+    let tree = create_tree();
+    let dir = TempDir::new().expect("failed creating temporary dir for snapshot");
+    let manifest = tree.export_snapshot(1, dir.path(), 16).unwrap();
+    let mut imported_tree = import_snapshot(&dir, &manifest);
+
+    // The imported tree must be extensible in the same way as the original one.
+    let mut tree = tree;
+    let new_kvs: Vec<TreeEntry> = generate_key_value_pairs(100..120);
+    let expected_output = tree.extend(new_kvs.clone());
+    let output = imported_tree.extend(new_kvs);
+    assert_eq!(output.root_hash, expected_output.root_hash);
+    assert_eq!(output.leaf_count, 120);
+}
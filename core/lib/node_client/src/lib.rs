@@ -0,0 +1,373 @@
+//! Typed async client for the custom JSON-RPC namespaces (`zks_`, `idexo_` and `en_`) served by the node.
+//!
+//! [`NodeClient`] wraps the `jsonrpsee` clients declared in `zksync_web3_decl` and adds:
+//!
+//! - Retries of transient errors (transport errors and timeouts) configured via [`RetryPolicy`].
+//! - [`Middleware`] hooks invoked for each request attempt, e.g. to collect metrics or log requests.
+//! - Errors enriched with the called method and its arguments ([`EnrichedClientError`]).
+//!
+//! Standard Ethereum methods are not wrapped; they can be called on the underlying client returned
+//! by [`NodeClient::inner()`] using the traits from [`zksync_web3_decl::namespaces`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use std::time::Duration;
+//! use zksync_node_client::{NodeClient, RetryPolicy};
+//!
+//! let client = NodeClient::builder("http://127.0.0.1:3050")
+//!     .with_retry_policy(RetryPolicy::new(3, Duration::from_millis(100)))
+//!     .build()?;
+//! let l1_batch_number = client.get_l1_batch_number().await?;
+//! println!("Latest L1 batch: {l1_batch_number}");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use zksync_types::{
+    api::{
+        en::{SyncBlock, SyncBlockOutputs},
+        idexo::{
+            BatchEconomics, BatchStateDiffs, DaInclusionProof, DecodedLog, DepositStatus,
+            ForcedInclusionStatus, L1BatchProofStatus, L2ToL1LogProofRequest, MulticallRead,
+            MulticallReadResult, PriorityQueueStatus, RegisteredToken, RelayedMessageStatus,
+            TransactionDiagnostics, TransactionDiagnosticsTarget, TransactionTimeline, TreeLag,
+        },
+        BlockDetails, BlockIdVariant, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof,
+        ProtocolVersion, TransactionDetails,
+    },
+    contract_verification_api::VerificationInfo,
+    fee::Fee,
+    fee_model::FeeParams,
+    tokens::TokenInfo,
+    transaction_request::CallRequest,
+    Address, L1BatchNumber, MiniblockNumber, Transaction, H256, U256, U64,
+};
+use zksync_web3_decl::{
+    error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
+    jsonrpsee::{
+        core::ClientError,
+        http_client::{HttpClient, HttpClientBuilder},
+    },
+    namespaces::{EnNamespaceClient, IdexoNamespaceClient, ZksNamespaceClient},
+    types::{Filter, Token},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Policy for retrying requests failed with a transient error (a transport error or a timeout).
+/// The delay between attempts grows exponentially from `initial_backoff`, capped by `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Does not retry requests.
+    fn default() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+}
+
+impl RetryPolicy {
+    const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+    /// Creates a policy with the specified max number of retries (i.e., not counting the first attempt)
+    /// and the initial delay between attempts.
+    pub fn new(max_retries: usize, initial_backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_backoff,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF.max(initial_backoff),
+        }
+    }
+
+    /// Sets the max delay between attempts.
+    #[must_use]
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    fn is_retriable(err: &EnrichedClientError) -> bool {
+        matches!(
+            err.as_ref(),
+            ClientError::Transport(_) | ClientError::RequestTimeout
+        )
+    }
+
+    fn backoff(&self, retry: usize) -> Duration {
+        let multiplier = 1_u32.checked_shl(retry as u32).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(multiplier)
+            .min(self.max_backoff)
+    }
+}
+
+/// Information about a single attempt of a client request passed to [`Middleware`] hooks.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestInfo {
+    /// Full JSON-RPC method name, e.g. `zks_getBlockDetails`.
+    pub method: &'static str,
+    /// Zero-based attempt number; non-zero for retried requests.
+    pub attempt: usize,
+}
+
+/// Hooks invoked by [`NodeClient`] for each request attempt. All hooks have no-op default implementations.
+pub trait Middleware: 'static + fmt::Debug + Send + Sync {
+    /// Called before sending a request.
+    fn on_request(&self, _request: &RequestInfo) {}
+
+    /// Called after receiving a response (or an error) for a request.
+    fn on_response(
+        &self,
+        _request: &RequestInfo,
+        _latency: Duration,
+        _result: Result<(), &EnrichedClientError>,
+    ) {
+    }
+}
+
+/// Builder for [`NodeClient`].
+#[derive(Debug)]
+pub struct NodeClientBuilder {
+    url: String,
+    request_timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    middleware: Vec<Arc<dyn Middleware>>,
+}
+
+impl NodeClientBuilder {
+    /// Sets the timeout for a single request attempt.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the retry policy. By default, requests are not retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Adds a middleware. Middleware is invoked in the order it was added.
+    pub fn with_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Builds the client.
+    pub fn build(self) -> anyhow::Result<NodeClient> {
+        let mut builder = HttpClientBuilder::default();
+        if let Some(timeout) = self.request_timeout {
+            builder = builder.request_timeout(timeout);
+        }
+        let inner = builder
+            .build(&self.url)
+            .with_context(|| format!("failed building HTTP client for {}", self.url))?;
+        Ok(NodeClient {
+            inner,
+            retry_policy: self.retry_policy,
+            middleware: self.middleware.into(),
+        })
+    }
+}
+
+/// Typed client for the custom node namespaces. Cheaply cloneable.
+#[derive(Debug, Clone)]
+pub struct NodeClient {
+    inner: HttpClient,
+    retry_policy: RetryPolicy,
+    middleware: Arc<[Arc<dyn Middleware>]>,
+}
+
+/// Defines client methods delegating to `jsonrpsee` client traits. All arguments must be cloneable
+/// since requests may be retried.
+macro_rules! client_methods {
+    ($(
+        $(#[$attr:meta])*
+        $rpc_name:literal => $trait:ident::$method:ident($($arg:ident: $arg_ty:ty),*) -> $ret:ty;
+    )*) => {
+        impl NodeClient {
+            $(
+            $(#[$attr])*
+            #[allow(clippy::clone_on_copy)]
+            pub async fn $method(&self, $($arg: $arg_ty),*) -> EnrichedClientResult<$ret> {
+                self.call($rpc_name, || {
+                    let client = self.inner.clone();
+                    $(let $arg = $arg.clone();)*
+                    async move { <HttpClient as $trait>::$method(&client, $($arg),*).await }
+                })
+                .await
+            }
+            )*
+        }
+    };
+}
+
+impl NodeClient {
+    /// Creates a builder for a client connecting to the specified HTTP JSON-RPC URL.
+    pub fn builder(url: impl Into<String>) -> NodeClientBuilder {
+        NodeClientBuilder {
+            url: url.into(),
+            request_timeout: None,
+            retry_policy: RetryPolicy::default(),
+            middleware: vec![],
+        }
+    }
+
+    /// Returns the underlying `jsonrpsee` client, e.g. to call standard Ethereum methods.
+    /// Requests sent via the returned client are neither retried nor passed to middleware.
+    pub fn inner(&self) -> &HttpClient {
+        &self.inner
+    }
+
+    async fn call<T, F, Fut>(&self, method: &'static str, mut send: F) -> EnrichedClientResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let request = RequestInfo { method, attempt };
+            for middleware in self.middleware.iter() {
+                middleware.on_request(&request);
+            }
+            let started_at = Instant::now();
+            let result = send().rpc_context(method).await;
+            let latency = started_at.elapsed();
+            for middleware in self.middleware.iter() {
+                middleware.on_response(&request, latency, result.as_ref().map(drop));
+            }
+
+            match result {
+                Err(err)
+                    if attempt < self.retry_policy.max_retries
+                        && RetryPolicy::is_retriable(&err) =>
+                {
+                    let backoff = self.retry_policy.backoff(attempt);
+                    tracing::debug!(
+                        "Request {method} failed with a transient error, retrying in {backoff:?}: {err}"
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+client_methods! {
+    // `zks` namespace
+    "zks_estimateFee" => ZksNamespaceClient::estimate_fee(req: CallRequest) -> Fee;
+    "zks_estimateGasL1ToL2" => ZksNamespaceClient::estimate_gas_l1_to_l2(req: CallRequest) -> U256;
+    "zks_getBridgehubContract" => ZksNamespaceClient::get_bridgehub_contract() -> Option<Address>;
+    "zks_getMainContract" => ZksNamespaceClient::get_main_contract() -> Address;
+    "zks_getValidatorTimelockContract" =>
+        ZksNamespaceClient::get_validator_timelock_contract() -> Option<Address>;
+    "zks_getTestnetPaymaster" => ZksNamespaceClient::get_testnet_paymaster() -> Option<Address>;
+    "zks_getBridgeContracts" => ZksNamespaceClient::get_bridge_contracts() -> BridgeAddresses;
+    "zks_L1ChainId" => ZksNamespaceClient::l1_chain_id() -> U64;
+    "zks_getConfirmedTokens" =>
+        ZksNamespaceClient::get_confirmed_tokens(from: u32, limit: u8) -> Vec<Token>;
+    "zks_getAllAccountBalances" =>
+        ZksNamespaceClient::get_all_account_balances(address: Address) -> HashMap<Address, U256>;
+    "zks_getL2ToL1MsgProof" => ZksNamespaceClient::get_l2_to_l1_msg_proof(
+        block: MiniblockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>
+    ) -> Option<L2ToL1LogProof>;
+    "zks_getL2ToL1LogProof" => ZksNamespaceClient::get_l2_to_l1_log_proof(
+        tx_hash: H256,
+        index: Option<usize>
+    ) -> Option<L2ToL1LogProof>;
+    "zks_L1BatchNumber" => ZksNamespaceClient::get_l1_batch_number() -> U64;
+    "zks_getL1BatchBlockRange" =>
+        ZksNamespaceClient::get_miniblock_range(batch: L1BatchNumber) -> Option<(U64, U64)>;
+    "zks_getBlockDetails" =>
+        ZksNamespaceClient::get_block_details(block_number: MiniblockNumber) -> Option<BlockDetails>;
+    "zks_getTransactionDetails" =>
+        ZksNamespaceClient::get_transaction_details(hash: H256) -> Option<TransactionDetails>;
+    "zks_getRawBlockTransactions" =>
+        ZksNamespaceClient::get_raw_block_transactions(block_number: MiniblockNumber) -> Vec<Transaction>;
+    "zks_getL1BatchDetails" =>
+        ZksNamespaceClient::get_l1_batch_details(batch: L1BatchNumber) -> Option<L1BatchDetails>;
+    "zks_getBytecodeByHash" => ZksNamespaceClient::get_bytecode_by_hash(hash: H256) -> Option<Vec<u8>>;
+    "zks_getL1GasPrice" => ZksNamespaceClient::get_l1_gas_price() -> U64;
+    "zks_getFeeParams" => ZksNamespaceClient::get_fee_params() -> FeeParams;
+    "zks_getProtocolVersion" =>
+        ZksNamespaceClient::get_protocol_version(version_id: Option<u16>) -> Option<ProtocolVersion>;
+    "zks_getProof" => ZksNamespaceClient::get_proof(
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber
+    ) -> Proof;
+    "zks_getContractVerificationInfo" =>
+        ZksNamespaceClient::get_contract_verification_info(address: Address) -> Option<VerificationInfo>;
+    "zks_getTreeLag" => ZksNamespaceClient::get_tree_lag() -> TreeLag;
+
+    // `idexo` namespace
+    "idexo_getBatchEconomics" =>
+        IdexoNamespaceClient::get_batch_economics(l1_batch_number: L1BatchNumber) -> Option<BatchEconomics>;
+    "idexo_getDaInclusionProof" =>
+        IdexoNamespaceClient::get_da_inclusion_proof(l1_batch_number: L1BatchNumber) -> Option<DaInclusionProof>;
+    "idexo_getTokenList" => IdexoNamespaceClient::get_token_list() -> Vec<RegisteredToken>;
+    "idexo_getDepositStatus" =>
+        IdexoNamespaceClient::get_deposit_status(settlement_tx_hash: H256) -> Vec<DepositStatus>;
+    "idexo_getRelayedMessages" =>
+        IdexoNamespaceClient::get_relayed_messages(source_tx_hash: H256) -> Vec<RelayedMessageStatus>;
+    "idexo_getPendingPriorityOps" =>
+        IdexoNamespaceClient::get_pending_priority_ops(limit: Option<usize>) -> PriorityQueueStatus;
+    "idexo_getL1BatchProofStatus" => IdexoNamespaceClient::get_l1_batch_proof_status(
+        l1_batch_number: L1BatchNumber
+    ) -> Option<L1BatchProofStatus>;
+    "idexo_getForcedInclusionStatus" =>
+        IdexoNamespaceClient::get_forced_inclusion_status() -> Option<ForcedInclusionStatus>;
+    "idexo_resolveName" => IdexoNamespaceClient::resolve_name(name: String) -> Option<Address>;
+    "idexo_multicallRead" => IdexoNamespaceClient::multicall_read(
+        reads: Vec<MulticallRead>,
+        block: Option<BlockIdVariant>
+    ) -> Vec<MulticallReadResult>;
+    "idexo_getBatchStateDiffs" => IdexoNamespaceClient::get_batch_state_diffs(
+        l1_batch_number: L1BatchNumber,
+        offset: Option<usize>,
+        limit: Option<usize>
+    ) -> Option<BatchStateDiffs>;
+    "idexo_getDecodedLogs" => IdexoNamespaceClient::get_decoded_logs(
+        filter: Filter
+    ) -> Vec<DecodedLog>;
+    "idexo_getL2ToL1LogProofs" => IdexoNamespaceClient::get_l2_to_l1_log_proofs(
+        requests: Vec<L2ToL1LogProofRequest>
+    ) -> Vec<Option<L2ToL1LogProof>>;
+    "idexo_getTransactionTimeline" =>
+        IdexoNamespaceClient::get_transaction_timeline(hash: H256) -> Option<TransactionTimeline>;
+    "idexo_diagnoseTransaction" => IdexoNamespaceClient::diagnose_transaction(
+        target: TransactionDiagnosticsTarget
+    ) -> Vec<TransactionDiagnostics>;
+
+    // `en` namespace
+    "en_syncL2Block" => EnNamespaceClient::sync_l2_block(
+        block_number: MiniblockNumber,
+        include_transactions: bool
+    ) -> Option<SyncBlock>;
+    "en_syncL2BlockOutputs" => EnNamespaceClient::sync_l2_block_outputs(
+        block_number: MiniblockNumber
+    ) -> Option<SyncBlockOutputs>;
+    "en_syncTokens" =>
+        EnNamespaceClient::sync_tokens(block_number: Option<MiniblockNumber>) -> Vec<TokenInfo>;
+}
@@ -0,0 +1,142 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use zksync_web3_decl::jsonrpsee::{
+    server::{ServerBuilder, ServerHandle},
+    types::{error::ErrorCode, ErrorObjectOwned},
+    RpcModule,
+};
+
+use super::*;
+
+#[derive(Debug, Default, Clone)]
+struct RecordingMiddleware {
+    requests: Arc<Mutex<Vec<(&'static str, usize)>>>,
+    errors: Arc<Mutex<usize>>,
+}
+
+impl Middleware for RecordingMiddleware {
+    fn on_request(&self, request: &RequestInfo) {
+        self.requests
+            .lock()
+            .unwrap()
+            .push((request.method, request.attempt));
+    }
+
+    fn on_response(
+        &self,
+        _request: &RequestInfo,
+        _latency: Duration,
+        result: Result<(), &EnrichedClientError>,
+    ) {
+        if result.is_err() {
+            *self.errors.lock().unwrap() += 1;
+        }
+    }
+}
+
+async fn spawn_server() -> (SocketAddr, ServerHandle) {
+    let mut module = RpcModule::new(());
+    module
+        .register_method("zks_L1BatchNumber", |_, _| {
+            Ok::<_, ErrorObjectOwned>(U64::from(42))
+        })
+        .unwrap();
+    module
+        .register_method("zks_getBlockDetails", |params, _| {
+            let (number,): (MiniblockNumber,) = params.parse()?;
+            if number == MiniblockNumber(0) {
+                Ok(None::<BlockDetails>)
+            } else {
+                Err(ErrorObjectOwned::owned(
+                    ErrorCode::InvalidParams.code(),
+                    "unknown block",
+                    None::<()>,
+                ))
+            }
+        })
+        .unwrap();
+
+    let server = ServerBuilder::default()
+        .http_only()
+        .build("127.0.0.1:0")
+        .await
+        .unwrap();
+    let local_addr = server.local_addr().unwrap();
+    (local_addr, server.start(module))
+}
+
+#[test]
+fn retry_backoff() {
+    let policy = RetryPolicy::new(5, Duration::from_millis(100))
+        .with_max_backoff(Duration::from_millis(500));
+    assert_eq!(policy.backoff(0), Duration::from_millis(100));
+    assert_eq!(policy.backoff(1), Duration::from_millis(200));
+    assert_eq!(policy.backoff(2), Duration::from_millis(400));
+    assert_eq!(policy.backoff(3), Duration::from_millis(500));
+    assert_eq!(policy.backoff(100), Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn calling_methods() {
+    let (addr, server) = spawn_server().await;
+    let middleware = RecordingMiddleware::default();
+    let client = NodeClient::builder(format!("http://{addr}/"))
+        .with_retry_policy(RetryPolicy::new(3, Duration::from_millis(1)))
+        .with_middleware(middleware.clone())
+        .build()
+        .unwrap();
+
+    let l1_batch_number = client.get_l1_batch_number().await.unwrap();
+    assert_eq!(l1_batch_number, U64::from(42));
+    let details = client.get_block_details(MiniblockNumber(0)).await.unwrap();
+    assert!(details.is_none());
+
+    // Call errors are not retried.
+    let err = client
+        .get_block_details(MiniblockNumber(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(err.as_ref(), ClientError::Call(_)), "{err:?}");
+
+    let requests = middleware.requests.lock().unwrap().clone();
+    assert_eq!(
+        requests,
+        [
+            ("zks_L1BatchNumber", 0),
+            ("zks_getBlockDetails", 0),
+            ("zks_getBlockDetails", 0)
+        ]
+    );
+    assert_eq!(*middleware.errors.lock().unwrap(), 1);
+    server.stop().unwrap();
+}
+
+#[tokio::test]
+async fn retrying_transport_errors() {
+    let (addr, server) = spawn_server().await;
+    server.stop().unwrap();
+    server.stopped().await;
+
+    let middleware = RecordingMiddleware::default();
+    let client = NodeClient::builder(format!("http://{addr}/"))
+        .with_retry_policy(RetryPolicy::new(2, Duration::from_millis(1)))
+        .with_middleware(middleware.clone())
+        .build()
+        .unwrap();
+    let err = client.get_l1_batch_number().await.unwrap_err();
+    assert!(matches!(err.as_ref(), ClientError::Transport(_)), "{err:?}");
+
+    let requests = middleware.requests.lock().unwrap().clone();
+    assert_eq!(
+        requests,
+        [
+            ("zks_L1BatchNumber", 0),
+            ("zks_L1BatchNumber", 1),
+            ("zks_L1BatchNumber", 2)
+        ]
+    );
+    assert_eq!(*middleware.errors.lock().unwrap(), 3);
+}
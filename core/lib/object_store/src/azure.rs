@@ -0,0 +1,137 @@
+//! Azure Blob Storage-based [`ObjectStore`] implementation.
+
+use std::fmt;
+
+use async_trait::async_trait;
+use azure_core::{error::ErrorKind, StatusCode};
+use azure_storage::StorageCredentials;
+use azure_storage_blobs::prelude::{ClientBuilder, ContainerClient};
+
+use crate::{
+    metrics::GCS_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Env variable containing the access key for the storage account. If not set, the store
+/// is accessed anonymously, which only works for reading from public containers.
+const ACCESS_KEY_ENV_VAR: &str = "AZURE_STORAGE_ACCESS_KEY";
+
+pub(crate) struct AzureBlobStore {
+    storage_account: String,
+    container: String,
+    client: ContainerClient,
+}
+
+impl fmt::Debug for AzureBlobStore {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AzureBlobStore")
+            .field("storage_account", &self.storage_account)
+            .field("container", &self.container)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AzureBlobStore {
+    pub fn new(storage_account: String, container: String) -> Self {
+        let credentials = match std::env::var(ACCESS_KEY_ENV_VAR) {
+            Ok(access_key) => StorageCredentials::access_key(storage_account.clone(), access_key),
+            Err(_) => {
+                tracing::info!(
+                    "`{ACCESS_KEY_ENV_VAR}` is not set; accessing Azure storage account `{storage_account}` anonymously"
+                );
+                StorageCredentials::anonymous()
+            }
+        };
+        let client =
+            ClientBuilder::new(storage_account.clone(), credentials).container_client(&container);
+        Self {
+            storage_account,
+            container,
+            client,
+        }
+    }
+
+    fn filename(bucket: &str, filename: &str) -> String {
+        format!("{bucket}/{filename}")
+    }
+}
+
+impl From<azure_core::Error> for ObjectStoreError {
+    fn from(err: azure_core::Error) -> Self {
+        let is_not_found = matches!(
+            err.kind(),
+            ErrorKind::HttpResponse { status, .. } if *status == StatusCode::NotFound
+        );
+        if is_not_found {
+            ObjectStoreError::KeyNotFound(err.into())
+        } else {
+            ObjectStoreError::Other(err.into())
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for AzureBlobStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = GCS_METRICS.start_fetch(bucket);
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Fetching data from Azure for key {filename} from container {}",
+            self.container
+        );
+
+        let blob = self.client.blob_client(filename).get_content().await?;
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!(
+            "Fetched data from Azure for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(blob)
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = GCS_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Storing data to Azure for key {filename} from container {}",
+            self.container
+        );
+
+        self.client
+            .blob_client(filename)
+            .put_block_blob(value)
+            .await?;
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to Azure for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Removing data from Azure for key {filename} from container {}",
+            self.container
+        );
+
+        self.client.blob_client(filename).delete().await?;
+        Ok(())
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!(
+            "https://{}.blob.core.windows.net/{}/{}",
+            self.storage_account,
+            self.container,
+            bucket.as_str()
+        )
+    }
+}
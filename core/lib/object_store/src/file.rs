@@ -1,7 +1,10 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, io::SeekFrom, ops};
 
 use async_trait::async_trait;
-use tokio::{fs, io};
+use tokio::{
+    fs, io,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
 
 use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
 
@@ -33,6 +36,7 @@ impl FileBackedObjectStore {
             Bucket::SchedulerWitnessJobsFri,
             Bucket::ProofsFri,
             Bucket::StorageSnapshot,
+            Bucket::ChainExport,
         ] {
             let bucket_path = format!("{base_dir}/{bucket}");
             fs::create_dir_all(&bucket_path)
@@ -56,6 +60,26 @@ impl ObjectStore for FileBackedObjectStore {
         fs::read(filename).await.map_err(From::from)
     }
 
+    async fn get_size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        let filename = self.filename(bucket, key);
+        Ok(fs::metadata(filename).await?.len())
+    }
+
+    async fn get_range_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        range: ops::Range<u64>,
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        let filename = self.filename(bucket, key);
+        let mut file = fs::File::open(filename).await?;
+        file.seek(SeekFrom::Start(range.start)).await?;
+        let mut buffer = vec![];
+        let range_len = range.end.saturating_sub(range.start);
+        file.take(range_len).read_to_end(&mut buffer).await?;
+        Ok(buffer)
+    }
+
     async fn put_raw(
         &self,
         bucket: Bucket,
@@ -99,6 +123,33 @@ mod test {
         assert_eq!(expected, bytes, "expected didn't match");
     }
 
+    #[tokio::test]
+    async fn test_get_range() {
+        let dir = TempDir::new("test-data").unwrap();
+        let path = dir.into_path().into_os_string().into_string().unwrap();
+        let object_store = FileBackedObjectStore::new(path).await;
+        let bytes = vec![9, 0, 8, 9, 0, 7];
+        object_store
+            .put_raw(Bucket::ProverJobs, "test-key.bin", bytes)
+            .await
+            .unwrap();
+        let size = object_store
+            .get_size_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(size, 6);
+        let range = object_store
+            .get_range_raw(Bucket::ProverJobs, "test-key.bin", 2..4)
+            .await
+            .unwrap();
+        assert_eq!(range, [8, 9]);
+        let range = object_store
+            .get_range_raw(Bucket::ProverJobs, "test-key.bin", 4..10)
+            .await
+            .unwrap();
+        assert_eq!(range, [0, 7]);
+    }
+
     #[tokio::test]
     async fn test_put() {
         let dir = TempDir::new("test-data").unwrap();
@@ -1,6 +1,6 @@
 //! GCS-based [`ObjectStore`] implementation.
 
-use std::{fmt, future::Future, time::Duration};
+use std::{fmt, future::Future, ops, time::Duration};
 
 use async_trait::async_trait;
 use google_cloud_auth::{credentials::CredentialsFile, error::Error};
@@ -180,6 +180,41 @@ impl ObjectStore for GoogleCloudStorage {
         blob.map_err(ObjectStoreError::from)
     }
 
+    async fn get_size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        let request = GetObjectRequest {
+            bucket: self.bucket_prefix.clone(),
+            object: Self::filename(bucket.as_str(), key),
+            ..GetObjectRequest::default()
+        };
+        let object = retry(self.max_retries, || self.client.get_object(&request)).await?;
+        Ok(object.size as u64)
+    }
+
+    async fn get_range_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        range: ops::Range<u64>,
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        if range.is_empty() {
+            return Ok(vec![]);
+        }
+        let fetch_latency = GCS_METRICS.start_fetch(bucket);
+        let request = GetObjectRequest {
+            bucket: self.bucket_prefix.clone(),
+            object: Self::filename(bucket.as_str(), key),
+            ..GetObjectRequest::default()
+        };
+        // GCS ranges are inclusive.
+        let range = Range(Some(range.start), Some(range.end - 1));
+        let blob = retry(self.max_retries, || {
+            self.client.download_object(&request, &range)
+        })
+        .await;
+        fetch_latency.observe();
+        blob.map_err(ObjectStoreError::from)
+    }
+
     async fn put_raw(
         &self,
         bucket: Bucket,
@@ -3,6 +3,8 @@
 //!
 //! - File-based storage saving blobs as separate files in the local filesystem
 //! - GCS-based storage
+//! - AWS S3-based storage (also usable with S3-compatible stores)
+//! - Azure Blob Storage-based storage
 //!
 //! These implementations are not exposed externally. Instead, a store trait object
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
@@ -23,12 +25,14 @@
     clippy::doc_markdown
 )]
 
+mod azure;
 mod file;
 mod gcs;
 mod metrics;
 mod mock;
 mod objects;
 mod raw;
+mod s3;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
@@ -8,9 +8,10 @@ use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotManifest, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
-    storage::witness_block_state::WitnessBlockState,
+    storage::witness_block_state::{WitnessBlockState, WitnessInputCacheEntry},
     L1BatchNumber,
 };
 
@@ -118,6 +119,24 @@ impl StoredObject for SnapshotStorageLogsChunk {
     }
 }
 
+/// Manifests are stored as JSON, so that they can be inspected by snapshot consumers without additional tooling.
+impl StoredObject for SnapshotManifest {
+    const BUCKET: Bucket = Bucket::StorageSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_manifest.json")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serde_json::to_vec_pretty(self).map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        serde_json::from_slice(&bytes).map_err(From::from)
+    }
+}
+
 impl StoredObject for WitnessBlockState {
     const BUCKET: Bucket = Bucket::WitnessInput;
     type Key<'a> = L1BatchNumber;
@@ -129,6 +148,23 @@ impl StoredObject for WitnessBlockState {
     serialize_using_bincode!();
 }
 
+impl StoredObject for WitnessInputCacheEntry {
+    const BUCKET: Bucket = Bucket::WitnessInput;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("witness_block_state_for_l1_batch_{key}_cache_entry.json")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serde_json::to_vec(self).map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        serde_json::from_slice(&bytes).map_err(From::from)
+    }
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
@@ -159,6 +195,17 @@ impl dyn ObjectStore + '_ {
         Ok(key)
     }
 
+    /// Removes the value associated with the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the removal operation fails. Depending on the store implementation,
+    /// removing a non-existing object may return [`ObjectStoreError::KeyNotFound`].
+    pub async fn remove<V: StoredObject>(&self, key: V::Key<'_>) -> Result<(), ObjectStoreError> {
+        let key = V::encode_key(key);
+        self.remove_raw(V::BUCKET, &key).await
+    }
+
     pub fn get_storage_prefix<V: StoredObject>(&self) -> String {
         self.storage_prefix_raw(V::BUCKET)
     }
@@ -167,8 +214,8 @@ impl dyn ObjectStore + '_ {
 #[cfg(test)]
 mod tests {
     use zksync_types::{
-        snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
-        AccountTreeId, Bytes, StorageKey, H160, H256,
+        snapshots::{SnapshotBlobDigest, SnapshotFactoryDependency, SnapshotStorageLog},
+        AccountTreeId, Bytes, MiniblockNumber, StorageKey, H160, H256,
     };
 
     use super::*;
@@ -248,4 +295,23 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn test_snapshot_manifest_can_be_serialized_and_deserialized() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(123);
+        let manifest = SnapshotManifest {
+            l1_batch_number: key,
+            miniblock_number: MiniblockNumber(456),
+            factory_deps: SnapshotBlobDigest::new(b"factory deps"),
+            storage_logs_chunks: vec![
+                SnapshotBlobDigest::new(b"chunk 0"),
+                SnapshotBlobDigest::new(b"chunk 1"),
+            ],
+        };
+        let filename = store.put(key, &manifest).await.unwrap();
+        assert_eq!(filename, "snapshot_l1_batch_123_manifest.json");
+        let reconstructed_manifest = store.get(key).await.unwrap();
+        assert_eq!(manifest, reconstructed_manifest);
+    }
 }
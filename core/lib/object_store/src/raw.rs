@@ -1,12 +1,14 @@
-use std::{error, fmt, sync::Arc};
+use std::{error, fmt, ops, sync::Arc};
 
 use async_trait::async_trait;
 use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
 
 use crate::{
+    azure::AzureBlobStore,
     file::FileBackedObjectStore,
     gcs::{GoogleCloudStorage, GoogleCloudStorageAuthMode},
     mock::MockStore,
+    s3::S3Store,
 };
 
 /// Bucket for [`ObjectStore`] in which objects can be placed.
@@ -24,6 +26,7 @@ pub enum Bucket {
     SchedulerWitnessJobsFri,
     ProofsFri,
     StorageSnapshot,
+    ChainExport,
 }
 
 impl Bucket {
@@ -40,6 +43,7 @@ impl Bucket {
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
             Self::StorageSnapshot => "storage_logs_snapshots",
+            Self::ChainExport => "chain_export",
         }
     }
 }
@@ -100,6 +104,37 @@ pub trait ObjectStore: 'static + fmt::Debug + Send + Sync {
     /// Returns an error if an object with the `key` does not exist or cannot be accessed.
     async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError>;
 
+    /// Fetches the size of the value for the given key in bytes. The default implementation fetches
+    /// the entire value; stores should override it if they can fetch the size directly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object with the `key` does not exist or cannot be accessed.
+    async fn get_size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        Ok(self.get_raw(bucket, key).await?.len() as u64)
+    }
+
+    /// Fetches the specified byte range of the value for the given key. The range is truncated to the value size.
+    /// The default implementation fetches the entire value; stores should override it if they support
+    /// range requests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object with the `key` does not exist or cannot be accessed.
+    async fn get_range_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        range: ops::Range<u64>,
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        let mut value = self.get_raw(bucket, key).await?;
+        let end = (range.end as usize).min(value.len());
+        let start = (range.start as usize).min(end);
+        value.truncate(end);
+        value.drain(..start);
+        Ok(value)
+    }
+
     /// Stores the value associating it with the key into the given bucket.
     /// If the key already exists, the value is replaced.
     ///
@@ -129,6 +164,19 @@ impl<T: ObjectStore + ?Sized> ObjectStore for Arc<T> {
         (**self).get_raw(bucket, key).await
     }
 
+    async fn get_size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        (**self).get_size_raw(bucket, key).await
+    }
+
+    async fn get_range_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        range: ops::Range<u64>,
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        (**self).get_range_raw(bucket, key, range).await
+    }
+
     async fn put_raw(
         &self,
         bucket: Bucket,
@@ -235,6 +283,29 @@ impl ObjectStoreFactory {
                 .await;
                 Arc::new(store)
             }
+            ObjectStoreMode::S3 {
+                bucket_base_url,
+                region,
+                endpoint,
+            } => {
+                tracing::trace!("Initialized S3 Object store");
+                let store = S3Store::new(
+                    bucket_base_url.clone(),
+                    region.clone(),
+                    endpoint.clone(),
+                    config.max_retries,
+                )
+                .await;
+                Arc::new(store)
+            }
+            ObjectStoreMode::AzureBlob {
+                storage_account,
+                container,
+            } => {
+                tracing::trace!("Initialized AzureBlob Object store");
+                let store = AzureBlobStore::new(storage_account.clone(), container.clone());
+                Arc::new(store)
+            }
         }
     }
 }
@@ -0,0 +1,223 @@
+//! AWS S3-based [`ObjectStore`] implementation. Also works with S3-compatible stores (e.g., MinIO,
+//! Cloudflare R2) if a custom endpoint is specified.
+
+use std::{fmt, ops};
+
+use async_trait::async_trait;
+use aws_config::{retry::RetryConfig, BehaviorVersion, Region};
+use aws_sdk_s3::{
+    error::SdkError,
+    operation::{get_object::GetObjectError, head_object::HeadObjectError},
+    primitives::ByteStream,
+    Client,
+};
+
+use crate::{
+    metrics::GCS_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+pub(crate) struct S3Store {
+    bucket_name: String,
+    endpoint: Option<String>,
+    client: Client,
+}
+
+impl fmt::Debug for S3Store {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("S3Store")
+            .field("bucket_name", &self.bucket_name)
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Store {
+    /// Creates a store using credentials from the standard AWS provider chain (env variables,
+    /// shared credential files, instance metadata etc.).
+    pub async fn new(
+        bucket_name: String,
+        region: Option<String>,
+        endpoint: Option<String>,
+        max_retries: u16,
+    ) -> Self {
+        let retry_config = RetryConfig::standard().with_max_attempts(u32::from(max_retries) + 1);
+        let mut config_loader =
+            aws_config::defaults(BehaviorVersion::latest()).retry_config(retry_config);
+        if let Some(region) = region {
+            config_loader = config_loader.region(Region::new(region));
+        }
+        let sdk_config = config_loader.load().await;
+
+        let mut config = aws_sdk_s3::config::Builder::from(&sdk_config);
+        if let Some(endpoint) = &endpoint {
+            // S3-compatible stores usually don't support virtual-hosted-style bucket addressing.
+            config = config.endpoint_url(endpoint).force_path_style(true);
+        }
+        Self {
+            bucket_name,
+            endpoint,
+            client: Client::from_conf(config.build()),
+        }
+    }
+
+    fn filename(bucket: &str, filename: &str) -> String {
+        format!("{bucket}/{filename}")
+    }
+
+    /// Fetches the value for the given key, or its part if a `range` (in the HTTP `Range` header format)
+    /// is specified.
+    async fn get_inner(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        range: Option<String>,
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = GCS_METRICS.start_fetch(bucket);
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Fetching data from S3 for key {filename} from bucket {}",
+            self.bucket_name
+        );
+
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(&filename)
+            .set_range(range)
+            .send()
+            .await
+            .map_err(|err| {
+                let is_not_found = err
+                    .as_service_error()
+                    .map_or(false, GetObjectError::is_no_such_key);
+                if is_not_found {
+                    ObjectStoreError::KeyNotFound(err.into())
+                } else {
+                    other_error(err)
+                }
+            })?;
+        let blob = response
+            .body
+            .collect()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!(
+            "Fetched data from S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(blob.into_bytes().to_vec())
+    }
+}
+
+fn other_error<E>(err: SdkError<E>) -> ObjectStoreError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    ObjectStoreError::Other(err.into())
+}
+
+#[async_trait]
+impl ObjectStore for S3Store {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        self.get_inner(bucket, key, None).await
+    }
+
+    async fn get_size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        let filename = Self::filename(bucket.as_str(), key);
+        let response = self
+            .client
+            .head_object()
+            .bucket(&self.bucket_name)
+            .key(&filename)
+            .send()
+            .await
+            .map_err(|err| {
+                let is_not_found = err
+                    .as_service_error()
+                    .map_or(false, HeadObjectError::is_not_found);
+                if is_not_found {
+                    ObjectStoreError::KeyNotFound(err.into())
+                } else {
+                    other_error(err)
+                }
+            })?;
+        let size = response.content_length().unwrap_or_default();
+        Ok(size as u64)
+    }
+
+    async fn get_range_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        range: ops::Range<u64>,
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        if range.is_empty() {
+            return Ok(vec![]);
+        }
+        // HTTP ranges are inclusive.
+        let range = format!("bytes={}-{}", range.start, range.end - 1);
+        self.get_inner(bucket, key, Some(range)).await
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = GCS_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Storing data to S3 for key {filename} from bucket {}",
+            self.bucket_name
+        );
+
+        self.client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(filename)
+            .body(ByteStream::from(value))
+            .send()
+            .await
+            .map_err(other_error)?;
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Removing data from S3 for key {filename} from bucket {}",
+            self.bucket_name
+        );
+
+        self.client
+            .delete_object()
+            .bucket(&self.bucket_name)
+            .key(filename)
+            .send()
+            .await
+            .map_err(other_error)?;
+        Ok(())
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        match &self.endpoint {
+            Some(endpoint) => format!(
+                "{}/{}/{}",
+                endpoint.trim_end_matches('/'),
+                self.bucket_name,
+                bucket.as_str()
+            ),
+            None => format!("s3://{}/{}", self.bucket_name, bucket.as_str()),
+        }
+    }
+}
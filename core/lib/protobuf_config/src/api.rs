@@ -3,7 +3,7 @@ use zksync_config::configs::{api, ApiConfig};
 use zksync_protobuf::required;
 
 use crate::{
-    parse_h256, proto,
+    parse_h160, parse_h256, proto,
     repr::{read_required_repr, ProtoRepr},
 };
 
@@ -77,6 +77,17 @@ impl ProtoRepr for proto::Web3JsonRpc {
             max_tx_size: required(&self.max_tx_size)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_tx_size")?,
+            max_factory_deps: self
+                .max_factory_deps
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_factory_deps")?,
+            max_calldata_size: self
+                .max_calldata_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_calldata_size")?,
+            soft_tx_limits_ratio: self.soft_tx_limits_ratio,
             vm_execution_cache_misses_limit: self
                 .vm_execution_cache_misses_limit
                 .map(|x| x.try_into())
@@ -119,6 +130,34 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .transpose()
                 .context("websocket_requests_per_minute_limit")?,
             tree_api_url: self.tree_api_url.clone(),
+            inclusion_horizon_batches: self.inclusion_horizon_batches,
+            operator_namespace_enabled: self.operator_namespace_enabled.unwrap_or(false),
+            operator_auth_token: self.operator_auth_token.clone(),
+            admin_namespace_enabled: self.admin_namespace_enabled.unwrap_or(false),
+            verify_content_hashes: self.verify_content_hashes.unwrap_or(false),
+            name_registry_addr: self
+                .name_registry_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("name_registry_addr")?,
+            resolve_names_in_params: self.resolve_names_in_params.unwrap_or(false),
+            multicall_compute_budget_ms: self.multicall_compute_budget_ms,
+            pubsub_lag_warning_threshold: self.pubsub_lag_warning_threshold,
+            pubsub_lag_close_threshold: self.pubsub_lag_close_threshold,
+            shadow_api_url: self.shadow_api_url.clone(),
+            shadow_requests_percentage: self.shadow_requests_percentage,
+            sandbox_warm_pool_enabled: self.sandbox_warm_pool_enabled.unwrap_or(false),
+            disabled_methods: self.disabled_methods.clone(),
+            archive_calls_enabled: self.archive_calls_enabled.unwrap_or(false),
+            pre_eip155_txs_allowed: self.pre_eip155_txs_allowed.unwrap_or(false),
+            pre_eip155_tx_senders: self
+                .pre_eip155_tx_senders
+                .iter()
+                .enumerate()
+                .map(|(i, x)| parse_h160(x).context(i))
+                .collect::<Result<_, _>>()
+                .context("pre_eip155_tx_senders")?,
         })
     }
     fn build(this: &Self::Type) -> Self {
@@ -145,6 +184,9 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 this.l1_to_l2_transactions_compatibility_mode,
             ),
             max_tx_size: Some(this.max_tx_size.try_into().unwrap()),
+            max_factory_deps: this.max_factory_deps.map(|x| x.try_into().unwrap()),
+            max_calldata_size: this.max_calldata_size.map(|x| x.try_into().unwrap()),
+            soft_tx_limits_ratio: this.soft_tx_limits_ratio,
             vm_execution_cache_misses_limit: this
                 .vm_execution_cache_misses_limit
                 .map(|x| x.try_into().unwrap()),
@@ -167,6 +209,30 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
             tree_api_url: this.tree_api_url.clone(),
+            inclusion_horizon_batches: this.inclusion_horizon_batches,
+            operator_namespace_enabled: Some(this.operator_namespace_enabled),
+            operator_auth_token: this.operator_auth_token.clone(),
+            admin_namespace_enabled: Some(this.admin_namespace_enabled),
+            verify_content_hashes: Some(this.verify_content_hashes),
+            name_registry_addr: this
+                .name_registry_addr
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            resolve_names_in_params: Some(this.resolve_names_in_params),
+            multicall_compute_budget_ms: this.multicall_compute_budget_ms,
+            pubsub_lag_warning_threshold: this.pubsub_lag_warning_threshold,
+            pubsub_lag_close_threshold: this.pubsub_lag_close_threshold,
+            shadow_api_url: this.shadow_api_url.clone(),
+            shadow_requests_percentage: this.shadow_requests_percentage,
+            sandbox_warm_pool_enabled: Some(this.sandbox_warm_pool_enabled),
+            disabled_methods: this.disabled_methods.clone(),
+            archive_calls_enabled: Some(this.archive_calls_enabled),
+            pre_eip155_txs_allowed: Some(this.pre_eip155_txs_allowed),
+            pre_eip155_tx_senders: this
+                .pre_eip155_tx_senders
+                .iter()
+                .map(|address| address.as_bytes().into())
+                .collect(),
         }
     }
 }
@@ -196,11 +262,23 @@ impl ProtoRepr for proto::HealthCheck {
             port: required(&self.port)
                 .and_then(|p| Ok((*p).try_into()?))
                 .context("port")?,
+            state_keeper_max_lag_sec: self.state_keeper_max_lag_sec,
+            tree_max_lag: self.tree_max_lag,
+            eth_sender_max_inflight_txs: self
+                .eth_sender_max_inflight_txs
+                .map(|x| x.try_into())
+                .transpose()
+                .context("eth_sender_max_inflight_txs")?,
         })
     }
     fn build(this: &Self::Type) -> Self {
         Self {
             port: Some(this.port.into()),
+            state_keeper_max_lag_sec: this.state_keeper_max_lag_sec,
+            tree_max_lag: this.tree_max_lag,
+            eth_sender_max_inflight_txs: this
+                .eth_sender_max_inflight_txs
+                .map(|x| x.try_into().unwrap()),
         }
     }
 }
@@ -0,0 +1,25 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::AuditLog {
+    type Type = configs::AuditLogConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            file_path: required(&self.file_path).context("file_path")?.clone(),
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            export_lag_ms: *required(&self.export_lag_ms).context("export_lag_ms")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            file_path: Some(this.file_path.clone()),
+            polling_interval_ms: Some(this.polling_interval_ms),
+            export_lag_ms: Some(this.export_lag_ms),
+        }
+    }
+}
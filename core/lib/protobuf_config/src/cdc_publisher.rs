@@ -0,0 +1,76 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl proto::CdcBroker {
+    fn new(x: &configs::cdc_publisher::CdcBroker) -> Self {
+        type From = configs::cdc_publisher::CdcBroker;
+        match x {
+            From::Kafka => Self::Kafka,
+            From::Nats => Self::Nats,
+        }
+    }
+
+    fn parse(&self) -> configs::cdc_publisher::CdcBroker {
+        type To = configs::cdc_publisher::CdcBroker;
+        match self {
+            Self::Kafka => To::Kafka,
+            Self::Nats => To::Nats,
+        }
+    }
+}
+
+impl proto::CdcMessageFormat {
+    fn new(x: &configs::cdc_publisher::CdcMessageFormat) -> Self {
+        type From = configs::cdc_publisher::CdcMessageFormat;
+        match x {
+            From::Json => Self::Json,
+            From::Protobuf => Self::Protobuf,
+        }
+    }
+
+    fn parse(&self) -> configs::cdc_publisher::CdcMessageFormat {
+        type To = configs::cdc_publisher::CdcMessageFormat;
+        match self {
+            Self::Json => To::Json,
+            Self::Protobuf => To::Protobuf,
+        }
+    }
+}
+
+impl ProtoRepr for proto::CdcPublisher {
+    type Type = configs::CdcPublisherConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            broker: required(&self.broker)
+                .and_then(|x| Ok(proto::CdcBroker::try_from(*x)?))
+                .context("broker")?
+                .parse(),
+            broker_urls: self.broker_urls.clone(),
+            topic_prefix: required(&self.topic_prefix)
+                .context("topic_prefix")?
+                .clone(),
+            format: required(&self.format)
+                .and_then(|x| Ok(proto::CdcMessageFormat::try_from(*x)?))
+                .context("format")?
+                .parse(),
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            max_miniblocks_per_iteration: *required(&self.max_miniblocks_per_iteration)
+                .context("max_miniblocks_per_iteration")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            broker: Some(proto::CdcBroker::new(&this.broker).into()),
+            broker_urls: this.broker_urls.clone(),
+            topic_prefix: Some(this.topic_prefix.clone()),
+            format: Some(proto::CdcMessageFormat::new(&this.format).into()),
+            polling_interval_ms: Some(this.polling_interval_ms),
+            max_miniblocks_per_iteration: Some(this.max_miniblocks_per_iteration),
+        }
+    }
+}
@@ -39,6 +39,7 @@ impl proto::FeeModelVersion {
         match n {
             From::V1 => Self::V1,
             From::V2 => Self::V2,
+            From::V3 => Self::V3,
         }
     }
 
@@ -47,6 +48,31 @@ impl proto::FeeModelVersion {
         match self {
             Self::V1 => To::V1,
             Self::V2 => To::V2,
+            Self::V3 => To::V3,
+        }
+    }
+}
+
+impl proto::DataAvailabilityMode {
+    fn new(n: &configs::chain::DataAvailabilityMode) -> Self {
+        use configs::chain::DataAvailabilityMode as From;
+        match n {
+            From::Rollup => Self::Rollup,
+            From::Validium => Self::Validium,
+            From::External => Self::External,
+            From::Celestia => Self::Celestia,
+            From::EigenDA => Self::EigenDa,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::DataAvailabilityMode {
+        use configs::chain::DataAvailabilityMode as To;
+        match self {
+            Self::Rollup => To::Rollup,
+            Self::Validium => To::Validium,
+            Self::External => To::External,
+            Self::Celestia => To::Celestia,
+            Self::EigenDa => To::EigenDA,
         }
     }
 }
@@ -80,7 +106,7 @@ impl ProtoRepr for proto::EthNetwork {
 impl ProtoRepr for proto::StateKeeper {
     type Type = configs::chain::StateKeeperConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
-        Ok(Self::Type {
+        let config = Self::Type {
             transaction_slots: required(&self.transaction_slots)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("transaction_slots")?,
@@ -126,6 +152,17 @@ impl ProtoRepr for proto::StateKeeper {
                 .and_then(|x| Ok(proto::FeeModelVersion::try_from(*x)?))
                 .context("fee_model_version")?
                 .parse(),
+            congestion_target_batch_fullness: self.congestion_target_batch_fullness,
+            congestion_max_change_per_batch: self.congestion_max_change_per_batch,
+            congestion_max_multiplier: self.congestion_max_multiplier,
+            congestion_window_size: self.congestion_window_size,
+            data_availability_mode: self
+                .data_availability_mode
+                .map(proto::DataAvailabilityMode::try_from)
+                .transpose()
+                .context("data_availability_mode")?
+                .map(|x| x.parse())
+                .unwrap_or_default(),
             validation_computational_gas_limit: *required(&self.validation_computational_gas_limit)
                 .context("validation_computational_gas_limit")?,
             save_call_traces: *required(&self.save_call_traces).context("save_call_traces")?,
@@ -140,7 +177,12 @@ impl ProtoRepr for proto::StateKeeper {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("enum_index_migration_chunk_size")?,
-        })
+            max_txs_per_account_in_miniblock: self.max_txs_per_account_in_miniblock,
+            max_txs_per_account_in_batch: self.max_txs_per_account_in_batch,
+            forced_inclusion_deadline_ms: self.forced_inclusion_deadline_ms,
+        };
+        config.validate()?;
+        Ok(config)
     }
 
     fn build(this: &Self::Type) -> Self {
@@ -167,6 +209,13 @@ impl ProtoRepr for proto::StateKeeper {
             max_gas_per_batch: Some(this.max_gas_per_batch),
             max_pubdata_per_batch: Some(this.max_pubdata_per_batch),
             fee_model_version: Some(proto::FeeModelVersion::new(&this.fee_model_version).into()),
+            congestion_target_batch_fullness: this.congestion_target_batch_fullness,
+            congestion_max_change_per_batch: this.congestion_max_change_per_batch,
+            congestion_max_multiplier: this.congestion_max_multiplier,
+            congestion_window_size: this.congestion_window_size,
+            data_availability_mode: Some(
+                proto::DataAvailabilityMode::new(&this.data_availability_mode).into(),
+            ),
             validation_computational_gas_limit: Some(this.validation_computational_gas_limit),
             save_call_traces: Some(this.save_call_traces),
             virtual_blocks_interval: Some(this.virtual_blocks_interval),
@@ -176,6 +225,9 @@ impl ProtoRepr for proto::StateKeeper {
                 .enum_index_migration_chunk_size
                 .as_ref()
                 .map(|x| (*x).try_into().unwrap()),
+            max_txs_per_account_in_miniblock: this.max_txs_per_account_in_miniblock,
+            max_txs_per_account_in_batch: this.max_txs_per_account_in_batch,
+            forced_inclusion_deadline_ms: this.forced_inclusion_deadline_ms,
         }
     }
 }
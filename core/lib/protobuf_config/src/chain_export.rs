@@ -0,0 +1,46 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl proto::ChainExportFormat {
+    fn new(x: &configs::chain_export::ChainExportFormat) -> Self {
+        type From = configs::chain_export::ChainExportFormat;
+        match x {
+            From::Parquet => Self::Parquet,
+            From::Csv => Self::Csv,
+        }
+    }
+
+    fn parse(&self) -> configs::chain_export::ChainExportFormat {
+        type To = configs::chain_export::ChainExportFormat;
+        match self {
+            Self::Parquet => To::Parquet,
+            Self::Csv => To::Csv,
+        }
+    }
+}
+
+impl ProtoRepr for proto::ChainExport {
+    type Type = configs::ChainExportConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            format: required(&self.format)
+                .and_then(|x| Ok(proto::ChainExportFormat::try_from(*x)?))
+                .context("format")?
+                .parse(),
+            miniblocks_per_file: *required(&self.miniblocks_per_file)
+                .context("miniblocks_per_file")?,
+            export_interval_sec: self.export_interval_sec,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            format: Some(proto::ChainExportFormat::new(&this.format).into()),
+            miniblocks_per_file: Some(this.miniblocks_per_file),
+            export_interval_sec: this.export_interval_sec,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::ConfigReload {
+    type Type = configs::ConfigReloadConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            overrides_path: self.overrides_path.clone(),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            polling_interval_ms: Some(this.polling_interval_ms),
+            overrides_path: this.overrides_path.clone(),
+        }
+    }
+}
@@ -152,6 +152,31 @@ impl ProtoRepr for proto::Contracts {
                 .map(|x| parse_h160(x))
                 .transpose()
                 .context("transparent_proxy_admin_addr")?,
+            l1_shared_bridge_proxy_addr: self
+                .l1_shared_bridge_proxy_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("l1_shared_bridge_proxy_addr")?,
+            l2_shared_bridge_addr: self
+                .l2_shared_bridge_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("l2_shared_bridge_addr")?,
+            l1_shared_bridge_code_hash: self
+                .l1_shared_bridge_code_hash
+                .as_ref()
+                .map(|x| parse_h256(x))
+                .transpose()
+                .context("l1_shared_bridge_code_hash")?,
+            l2_shared_bridge_code_hash: self
+                .l2_shared_bridge_code_hash
+                .as_ref()
+                .map(|x| parse_h256(x))
+                .transpose()
+                .context("l2_shared_bridge_code_hash")?,
+            genesis_manifest_path: self.genesis_manifest_path.clone(),
         })
     }
 
@@ -224,6 +249,23 @@ impl ProtoRepr for proto::Contracts {
                 .transparent_proxy_admin_addr
                 .as_ref()
                 .map(|x| x.as_bytes().into()),
+            l1_shared_bridge_proxy_addr: this
+                .l1_shared_bridge_proxy_addr
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            l2_shared_bridge_addr: this
+                .l2_shared_bridge_addr
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            l1_shared_bridge_code_hash: this
+                .l1_shared_bridge_code_hash
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            l2_shared_bridge_code_hash: this
+                .l2_shared_bridge_code_hash
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            genesis_manifest_path: this.genesis_manifest_path.clone(),
         }
     }
 }
@@ -25,6 +25,48 @@ impl proto::MerkleTreeMode {
     }
 }
 
+impl proto::RocksdbCompactionStyle {
+    fn new(x: &configs::database::RocksdbCompactionStyle) -> Self {
+        use configs::database::RocksdbCompactionStyle as From;
+        match x {
+            From::Level => Self::Level,
+            From::Universal => Self::Universal,
+        }
+    }
+
+    fn parse(&self) -> configs::database::RocksdbCompactionStyle {
+        use configs::database::RocksdbCompactionStyle as To;
+        match self {
+            Self::Level => To::Level,
+            Self::Universal => To::Universal,
+        }
+    }
+}
+
+impl ProtoRepr for proto::RocksdbProfile {
+    type Type = configs::database::RocksdbProfile;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            compaction_style: required(&self.compaction_style)
+                .and_then(|x| Ok(proto::RocksdbCompactionStyle::try_from(*x)?))
+                .context("compaction_style")?
+                .parse(),
+            max_total_wal_size_mb: self.max_total_wal_size_mb,
+            wal_bytes_per_sync_mb: self.wal_bytes_per_sync_mb,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            compaction_style: Some(
+                proto::RocksdbCompactionStyle::new(&this.compaction_style).into(),
+            ),
+            max_total_wal_size_mb: this.max_total_wal_size_mb,
+            wal_bytes_per_sync_mb: this.wal_bytes_per_sync_mb,
+        }
+    }
+}
+
 impl ProtoRepr for proto::MerkleTree {
     type Type = configs::database::MerkleTreeConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -48,6 +90,18 @@ impl ProtoRepr for proto::MerkleTree {
             max_l1_batches_per_iter: required(&self.max_l1_batches_per_iter)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_l1_batches_per_iter")?,
+            thread_pool_size: self
+                .thread_pool_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("thread_pool_size")?,
+            integrity_check_interval_sec: self.integrity_check_interval_sec,
+            integrity_check_subtree_count: required(&self.integrity_check_subtree_count)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("integrity_check_subtree_count")?,
+            integrity_auto_repair: *required(&self.integrity_auto_repair)
+                .context("integrity_auto_repair")?,
+            rocksdb: read_required_repr(&self.rocksdb).context("rocksdb")?,
         })
     }
 
@@ -60,6 +114,13 @@ impl ProtoRepr for proto::MerkleTree {
             memtable_capacity_mb: Some(this.memtable_capacity_mb.try_into().unwrap()),
             stalled_writes_timeout_sec: Some(this.stalled_writes_timeout_sec),
             max_l1_batches_per_iter: Some(this.max_l1_batches_per_iter.try_into().unwrap()),
+            thread_pool_size: this.thread_pool_size.map(|x| x.try_into().unwrap()),
+            integrity_check_interval_sec: this.integrity_check_interval_sec,
+            integrity_check_subtree_count: Some(
+                this.integrity_check_subtree_count.try_into().unwrap(),
+            ),
+            integrity_auto_repair: Some(this.integrity_auto_repair),
+            rocksdb: Some(ProtoRepr::build(&this.rocksdb)),
         }
     }
 }
@@ -71,6 +132,14 @@ impl ProtoRepr for proto::Db {
             state_keeper_db_path: required(&self.state_keeper_db_path)
                 .context("state_keeper_db_path")?
                 .clone(),
+            state_keeper_db_block_cache_size_mb: self
+                .state_keeper_db_block_cache_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("state_keeper_db_block_cache_size_mb")?,
+            state_keeper_rocksdb: read_required_repr(&self.state_keeper_rocksdb)
+                .context("state_keeper_rocksdb")?,
+            api_state_cache_path: self.api_state_cache_path.clone(),
             merkle_tree: read_required_repr(&self.merkle_tree).context("merkle_tree")?,
         })
     }
@@ -78,6 +147,11 @@ impl ProtoRepr for proto::Db {
     fn build(this: &Self::Type) -> Self {
         Self {
             state_keeper_db_path: Some(this.state_keeper_db_path.clone()),
+            state_keeper_db_block_cache_size_mb: this
+                .state_keeper_db_block_cache_size_mb
+                .map(|x| x.try_into().unwrap()),
+            state_keeper_rocksdb: Some(ProtoRepr::build(&this.state_keeper_rocksdb)),
+            api_state_cache_path: this.api_state_cache_path.clone(),
             merkle_tree: Some(ProtoRepr::build(&this.merkle_tree)),
         }
     }
@@ -96,6 +170,8 @@ impl ProtoRepr for proto::Postgres {
             statement_timeout_sec: self.statement_timeout_sec,
             long_connection_threshold_ms: self.long_connection_threshold_ms,
             slow_query_threshold_ms: self.slow_query_threshold_ms,
+            api_max_connections: self.api_max_connections,
+            background_max_connections: self.background_max_connections,
         })
     }
 
@@ -109,6 +185,8 @@ impl ProtoRepr for proto::Postgres {
             statement_timeout_sec: this.statement_timeout_sec,
             long_connection_threshold_ms: this.long_connection_threshold_ms,
             slow_query_threshold_ms: this.slow_query_threshold_ms,
+            api_max_connections: this.api_max_connections,
+            background_max_connections: this.background_max_connections,
         }
     }
 }
@@ -10,6 +10,7 @@ impl ProtoRepr for proto::EthClient {
         Ok(Self::Type {
             chain_id: *required(&self.chain_id).context("chain_id")?,
             web3_url: required(&self.web3_url).context("web3_url")?.clone(),
+            l1_web3_url: self.l1_web3_url.clone(),
         })
     }
 
@@ -17,6 +18,7 @@ impl ProtoRepr for proto::EthClient {
         Self {
             chain_id: Some(this.chain_id),
             web3_url: Some(this.web3_url.clone()),
+            l1_web3_url: this.l1_web3_url.clone(),
         }
     }
 }
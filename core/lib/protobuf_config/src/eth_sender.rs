@@ -3,7 +3,7 @@ use zksync_config::configs;
 use zksync_protobuf::required;
 
 use crate::{
-    proto,
+    parse_h160, proto,
     repr::{read_required_repr, ProtoRepr},
 };
 
@@ -14,6 +14,7 @@ impl proto::ProofSendingMode {
             From::OnlyRealProofs => Self::OnlyRealProofs,
             From::OnlySampledProofs => Self::OnlySampledProofs,
             From::SkipEveryProof => Self::SkipEveryProof,
+            From::OnlyMockProofs => Self::OnlyMockProofs,
         }
     }
 
@@ -23,6 +24,7 @@ impl proto::ProofSendingMode {
             Self::OnlyRealProofs => To::OnlyRealProofs,
             Self::OnlySampledProofs => To::OnlySampledProofs,
             Self::SkipEveryProof => To::SkipEveryProof,
+            Self::OnlyMockProofs => To::OnlyMockProofs,
         }
     }
 }
@@ -45,12 +47,60 @@ impl proto::ProofLoadingMode {
     }
 }
 
+impl proto::PubdataSendingMode {
+    fn new(x: &configs::eth_sender::PubdataSendingMode) -> Self {
+        use configs::eth_sender::PubdataSendingMode as From;
+        match x {
+            From::Calldata => Self::Calldata,
+            From::Blobs => Self::Blobs,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::PubdataSendingMode {
+        use configs::eth_sender::PubdataSendingMode as To;
+        match self {
+            Self::Calldata => To::Calldata,
+            Self::Blobs => To::Blobs,
+        }
+    }
+}
+
 impl ProtoRepr for proto::EthSender {
     type Type = configs::eth_sender::ETHSenderConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
         Ok(Self::Type {
             sender: read_required_repr(&self.sender).context("sender")?,
             gas_adjuster: read_required_repr(&self.gas_adjuster).context("gas_adjuster")?,
+            operator_signer: self
+                .operator_signer
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("operator_signer")?,
+            balance_monitor: self
+                .balance_monitor
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("balance_monitor")?,
+            da_dispatcher: self
+                .da_dispatcher
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("da_dispatcher")?,
+            da_cost_oracle: self
+                .da_cost_oracle
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("da_cost_oracle")?,
+            emergency_mode: self
+                .emergency_mode
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("emergency_mode")?,
         })
     }
 
@@ -58,6 +108,192 @@ impl ProtoRepr for proto::EthSender {
         Self {
             sender: Some(ProtoRepr::build(&this.sender)),
             gas_adjuster: Some(ProtoRepr::build(&this.gas_adjuster)),
+            operator_signer: this.operator_signer.as_ref().map(ProtoRepr::build),
+            balance_monitor: this.balance_monitor.as_ref().map(ProtoRepr::build),
+            da_dispatcher: this.da_dispatcher.as_ref().map(ProtoRepr::build),
+            da_cost_oracle: this.da_cost_oracle.as_ref().map(ProtoRepr::build),
+            emergency_mode: this.emergency_mode.as_ref().map(ProtoRepr::build),
+        }
+    }
+}
+
+impl ProtoRepr for proto::DaDispatcher {
+    type Type = configs::eth_sender::DADispatcherConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            max_batches_to_dispatch: *required(&self.max_batches_to_dispatch)
+                .context("max_batches_to_dispatch")?,
+            external_da_url: self.external_da_url.clone(),
+            celestia_node_url: self.celestia_node_url.clone(),
+            celestia_namespace: self.celestia_namespace.clone(),
+            eigenda_disperser_url: self.eigenda_disperser_url.clone(),
+            failover_timeout_ms: self.failover_timeout_ms,
+            compression_protocol_version: self
+                .compression_protocol_version
+                .map(|x| x.try_into())
+                .transpose()
+                .context("compression_protocol_version")?,
+            compression_level: self.compression_level,
+            compression_dictionary_path: self.compression_dictionary_path.clone(),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            polling_interval_ms: Some(this.polling_interval_ms),
+            max_batches_to_dispatch: Some(this.max_batches_to_dispatch),
+            external_da_url: this.external_da_url.clone(),
+            celestia_node_url: this.celestia_node_url.clone(),
+            celestia_namespace: this.celestia_namespace.clone(),
+            eigenda_disperser_url: this.eigenda_disperser_url.clone(),
+            failover_timeout_ms: this.failover_timeout_ms,
+            compression_protocol_version: this.compression_protocol_version.map(Into::into),
+            compression_level: this.compression_level,
+            compression_dictionary_path: this.compression_dictionary_path.clone(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::DaCostOracle {
+    type Type = configs::eth_sender::DACostOracleConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            max_price_samples: required(&self.max_price_samples)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("max_price_samples")?,
+            native_token_price_in_wei: self.native_token_price_in_wei,
+            price_scalar: *required(&self.price_scalar).context("price_scalar")?,
+            max_price_per_byte: self.max_price_per_byte,
+            eigenda_payment_vault_address: self
+                .eigenda_payment_vault_address
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("eigenda_payment_vault_address")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            max_price_samples: Some(this.max_price_samples.try_into().unwrap()),
+            native_token_price_in_wei: this.native_token_price_in_wei,
+            price_scalar: Some(this.price_scalar),
+            max_price_per_byte: this.max_price_per_byte,
+            eigenda_payment_vault_address: this
+                .eigenda_payment_vault_address
+                .map(|addr| addr.as_bytes().into()),
+        }
+    }
+}
+
+impl ProtoRepr for proto::EmergencyMode {
+    type Type = configs::eth_sender::EmergencyModeConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            inactivity_threshold_hours: *required(&self.inactivity_threshold_hours)
+                .context("inactivity_threshold_hours")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            inactivity_threshold_hours: Some(this.inactivity_threshold_hours),
+        }
+    }
+}
+
+impl ProtoRepr for proto::OperatorBalanceMonitor {
+    type Type = configs::eth_sender::OperatorBalanceMonitorConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            check_interval_ms: *required(&self.check_interval_ms).context("check_interval_ms")?,
+            low_balance_threshold_gwei: *required(&self.low_balance_threshold_gwei)
+                .context("low_balance_threshold_gwei")?,
+            top_up_webhook_url: self.top_up_webhook_url.clone(),
+            funding_contract_address: self
+                .funding_contract_address
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("funding_contract_address")?,
+            top_up_cooldown_ms: *required(&self.top_up_cooldown_ms)
+                .context("top_up_cooldown_ms")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            check_interval_ms: Some(this.check_interval_ms),
+            low_balance_threshold_gwei: Some(this.low_balance_threshold_gwei),
+            top_up_webhook_url: this.top_up_webhook_url.clone(),
+            funding_contract_address: this
+                .funding_contract_address
+                .map(|addr| addr.as_bytes().into()),
+            top_up_cooldown_ms: Some(this.top_up_cooldown_ms),
+        }
+    }
+}
+
+impl ProtoRepr for proto::OperatorSigner {
+    type Type = configs::eth_sender::OperatorSignerConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        use configs::eth_sender::OperatorSignerMode;
+
+        let mode = match required(&self.mode).context("mode")? {
+            proto::operator_signer::Mode::Web3Signer(mode) => OperatorSignerMode::Web3Signer {
+                web3_signer_url: required(&mode.url).context("url")?.clone(),
+                web3_signer_key_id: required(&mode.key_id).context("key_id")?.clone(),
+            },
+            proto::operator_signer::Mode::AwsKms(mode) => OperatorSignerMode::AwsKms {
+                aws_kms_region: required(&mode.region).context("region")?.clone(),
+                aws_kms_key_id: required(&mode.key_id).context("key_id")?.clone(),
+            },
+            proto::operator_signer::Mode::GcpKms(mode) => OperatorSignerMode::GcpKms {
+                gcp_kms_key_version_name: required(&mode.key_version_name)
+                    .context("key_version_name")?
+                    .clone(),
+            },
+        };
+        Ok(Self::Type {
+            mode,
+            operator_address: required(&self.operator_address)
+                .and_then(|x| parse_h160(x))
+                .context("operator_address")?,
+            request_timeout_ms: *required(&self.request_timeout_ms)
+                .context("request_timeout_ms")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        use configs::eth_sender::OperatorSignerMode;
+
+        let mode = match &this.mode {
+            OperatorSignerMode::Web3Signer {
+                web3_signer_url,
+                web3_signer_key_id,
+            } => proto::operator_signer::Mode::Web3Signer(proto::operator_signer::Web3Signer {
+                url: Some(web3_signer_url.clone()),
+                key_id: Some(web3_signer_key_id.clone()),
+            }),
+            OperatorSignerMode::AwsKms {
+                aws_kms_region,
+                aws_kms_key_id,
+            } => proto::operator_signer::Mode::AwsKms(proto::operator_signer::AwsKms {
+                region: Some(aws_kms_region.clone()),
+                key_id: Some(aws_kms_key_id.clone()),
+            }),
+            OperatorSignerMode::GcpKms {
+                gcp_kms_key_version_name,
+            } => proto::operator_signer::Mode::GcpKms(proto::operator_signer::GcpKms {
+                key_version_name: Some(gcp_kms_key_version_name.clone()),
+            }),
+        };
+        Self {
+            mode: Some(mode),
+            operator_address: Some(this.operator_address.as_bytes().into()),
+            request_timeout_ms: Some(this.request_timeout_ms),
         }
     }
 }
@@ -109,6 +345,21 @@ impl ProtoRepr for proto::Sender {
                 .and_then(|x| Ok(proto::ProofLoadingMode::try_from(*x)?))
                 .context("proof_loading_mode")?
                 .parse(),
+            pubdata_sending_mode: self
+                .pubdata_sending_mode
+                .map(proto::PubdataSendingMode::try_from)
+                .transpose()
+                .context("pubdata_sending_mode")?
+                .map(|x| x.parse())
+                .unwrap_or_default(),
+            resubmission_interval_blocks: self.resubmission_interval_blocks,
+            priority_fee_bump_percent: self.priority_fee_bump_percent,
+            max_fee_per_gas_cap: self.max_fee_per_gas_cap,
+            max_fee_bumps_before_replacement: self.max_fee_bumps_before_replacement,
+            dry_run: self.dry_run.unwrap_or(false),
+            target_cost_per_batch_in_gwei: self.target_cost_per_batch_in_gwei,
+            settlement_checks_enabled: self.settlement_checks_enabled.unwrap_or(false),
+            max_settlement_reorg_depth: self.max_settlement_reorg_depth,
         })
     }
 
@@ -137,6 +388,17 @@ impl ProtoRepr for proto::Sender {
             l1_batch_min_age_before_execute_seconds: this.l1_batch_min_age_before_execute_seconds,
             max_acceptable_priority_fee_in_gwei: Some(this.max_acceptable_priority_fee_in_gwei),
             proof_loading_mode: Some(proto::ProofLoadingMode::new(&this.proof_loading_mode).into()),
+            pubdata_sending_mode: Some(
+                proto::PubdataSendingMode::new(&this.pubdata_sending_mode).into(),
+            ),
+            resubmission_interval_blocks: this.resubmission_interval_blocks,
+            priority_fee_bump_percent: this.priority_fee_bump_percent,
+            max_fee_per_gas_cap: this.max_fee_per_gas_cap,
+            max_fee_bumps_before_replacement: this.max_fee_bumps_before_replacement,
+            dry_run: Some(this.dry_run),
+            target_cost_per_batch_in_gwei: this.target_cost_per_batch_in_gwei,
+            settlement_checks_enabled: Some(this.settlement_checks_enabled),
+            max_settlement_reorg_depth: this.max_settlement_reorg_depth,
         }
     }
 }
@@ -159,6 +421,17 @@ impl ProtoRepr for proto::GasAdjuster {
             internal_enforced_l1_gas_price: self.internal_enforced_l1_gas_price,
             poll_period: *required(&self.poll_period).context("poll_period")?,
             max_l1_gas_price: self.max_l1_gas_price,
+            num_samples_for_blob_base_fee_estimate: self
+                .num_samples_for_blob_base_fee_estimate
+                .map(|x| x.try_into())
+                .transpose()
+                .context("num_samples_for_blob_base_fee_estimate")?
+                .unwrap_or_else(
+                    configs::eth_sender::GasAdjusterConfig::default_num_samples_for_blob_base_fee_estimate,
+                ),
+            l1_pubdata_price_scalar: self.l1_pubdata_price_scalar.unwrap_or_else(
+                configs::eth_sender::GasAdjusterConfig::default_l1_pubdata_price_scalar,
+            ),
         })
     }
 
@@ -172,6 +445,12 @@ impl ProtoRepr for proto::GasAdjuster {
             internal_enforced_l1_gas_price: this.internal_enforced_l1_gas_price,
             poll_period: Some(this.poll_period),
             max_l1_gas_price: this.max_l1_gas_price,
+            num_samples_for_blob_base_fee_estimate: Some(
+                this.num_samples_for_blob_base_fee_estimate
+                    .try_into()
+                    .unwrap(),
+            ),
+            l1_pubdata_price_scalar: Some(this.l1_pubdata_price_scalar),
         }
     }
 }
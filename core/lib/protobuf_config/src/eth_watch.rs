@@ -4,6 +4,28 @@ use zksync_protobuf::required;
 
 use crate::{proto, repr::ProtoRepr};
 
+impl proto::EthWatchFinalityMode {
+    fn new(x: &configs::eth_watch::EthWatchFinalityMode) -> Self {
+        type From = configs::eth_watch::EthWatchFinalityMode;
+        match x {
+            From::Confirmations => Self::Confirmations,
+            From::Safe => Self::Safe,
+            From::Finalized => Self::Finalized,
+            From::BatchExecution => Self::BatchExecution,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_watch::EthWatchFinalityMode {
+        type To = configs::eth_watch::EthWatchFinalityMode;
+        match self {
+            Self::Confirmations => To::Confirmations,
+            Self::Safe => To::Safe,
+            Self::Finalized => To::Finalized,
+            Self::BatchExecution => To::BatchExecution,
+        }
+    }
+}
+
 impl ProtoRepr for proto::EthWatch {
     type Type = configs::ETHWatchConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -11,6 +33,13 @@ impl ProtoRepr for proto::EthWatch {
             confirmations_for_eth_event: self.confirmations_for_eth_event,
             eth_node_poll_interval: *required(&self.eth_node_poll_interval)
                 .context("eth_node_poll_interval")?,
+            finality_mode: self
+                .finality_mode
+                .map(proto::EthWatchFinalityMode::try_from)
+                .transpose()
+                .context("finality_mode")?
+                .map(|x| x.parse()),
+            reorg_rescan_depth: self.reorg_rescan_depth,
         })
     }
 
@@ -18,6 +47,11 @@ impl ProtoRepr for proto::EthWatch {
         Self {
             confirmations_for_eth_event: this.confirmations_for_eth_event,
             eth_node_poll_interval: Some(this.eth_node_poll_interval),
+            finality_mode: this
+                .finality_mode
+                .as_ref()
+                .map(|x| proto::EthWatchFinalityMode::new(x).into()),
+            reorg_rescan_depth: this.reorg_rescan_depth,
         }
     }
 }
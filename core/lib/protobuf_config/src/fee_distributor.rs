@@ -0,0 +1,60 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{parse_h160, proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::FeeDistributor {
+    type Type = configs::FeeDistributorConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            sweep_interval_sec: *required(&self.sweep_interval_sec)
+                .context("sweep_interval_sec")?,
+            min_balance: *required(&self.min_balance).context("min_balance")?,
+            min_sweep_amount: *required(&self.min_sweep_amount).context("min_sweep_amount")?,
+            gas_limit_per_transfer: *required(&self.gas_limit_per_transfer)
+                .context("gas_limit_per_transfer")?,
+            treasury_addr: self
+                .treasury_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("treasury_addr")?,
+            treasury_share_bps: *required(&self.treasury_share_bps)
+                .context("treasury_share_bps")?,
+            sequencer_addr: self
+                .sequencer_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("sequencer_addr")?,
+            sequencer_share_bps: *required(&self.sequencer_share_bps)
+                .context("sequencer_share_bps")?,
+            burn_addr: self
+                .burn_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("burn_addr")?,
+            burn_share_bps: *required(&self.burn_share_bps).context("burn_share_bps")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            polling_interval_ms: Some(this.polling_interval_ms),
+            sweep_interval_sec: Some(this.sweep_interval_sec),
+            min_balance: Some(this.min_balance),
+            min_sweep_amount: Some(this.min_sweep_amount),
+            gas_limit_per_transfer: Some(this.gas_limit_per_transfer),
+            treasury_addr: this.treasury_addr.as_ref().map(|x| x.as_bytes().into()),
+            treasury_share_bps: Some(this.treasury_share_bps),
+            sequencer_addr: this.sequencer_addr.as_ref().map(|x| x.as_bytes().into()),
+            sequencer_share_bps: Some(this.sequencer_share_bps),
+            burn_addr: this.burn_addr.as_ref().map(|x| x.as_bytes().into()),
+            burn_share_bps: Some(this.burn_share_bps),
+        }
+    }
+}
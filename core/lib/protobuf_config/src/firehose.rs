@@ -0,0 +1,28 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::Firehose {
+    type Type = configs::FirehoseConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            port: required(&self.port)
+                .and_then(|p| Ok((*p).try_into()?))
+                .context("port")?,
+            poll_interval_ms: *required(&self.poll_interval_ms).context("poll_interval_ms")?,
+            max_streams: required(&self.max_streams)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("max_streams")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            port: Some(this.port.into()),
+            poll_interval_ms: Some(this.poll_interval_ms),
+            max_streams: Some(this.max_streams.try_into().unwrap()),
+        }
+    }
+}
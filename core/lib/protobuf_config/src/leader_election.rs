@@ -0,0 +1,25 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::LeaderElection {
+    type Type = configs::LeaderElectionConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            lock_id: *required(&self.lock_id).context("lock_id")?,
+            check_interval_ms: *required(&self.check_interval_ms).context("check_interval_ms")?,
+            standby_catchup_interval_ms: *required(&self.standby_catchup_interval_ms)
+                .context("standby_catchup_interval_ms")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            lock_id: Some(this.lock_id),
+            check_interval_ms: Some(this.check_interval_ms),
+            standby_catchup_interval_ms: Some(this.standby_catchup_interval_ms),
+        }
+    }
+}
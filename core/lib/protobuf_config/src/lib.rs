@@ -6,13 +6,19 @@
 
 mod alerts;
 mod api;
+mod audit_log;
+mod cdc_publisher;
 mod chain;
+mod chain_export;
+mod config_reload;
 mod contract_verifier;
 mod contracts;
 mod database;
 mod eth_client;
 mod eth_sender;
 mod eth_watch;
+mod fee_distributor;
+mod firehose;
 mod fri_proof_compressor;
 mod fri_prover;
 mod fri_prover_gateway;
@@ -20,10 +26,20 @@ mod fri_prover_group;
 mod fri_witness_generator;
 mod fri_witness_vector_generator;
 mod house_keeper;
+mod leader_election;
+mod message_relay;
 mod object_store;
 mod observability;
 mod proof_data_handler;
+mod reexecution_watchdog;
+mod rosetta_api;
+mod shared_sequencer;
 mod snapshots_creator;
+mod stable_gas_price;
+mod supervisor;
+mod supply_checker;
+mod webhooks;
+mod withdrawal_finalizer;
 mod witness_generator;
 
 pub mod proto;
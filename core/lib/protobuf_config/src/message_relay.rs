@@ -0,0 +1,58 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{parse_h160, proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::MessageRelay {
+    type Type = configs::MessageRelayConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            outbound_senders: self
+                .outbound_senders
+                .iter()
+                .enumerate()
+                .map(|(i, x)| parse_h160(x).context(i))
+                .collect::<Result<_, _>>()
+                .context("outbound_senders")?,
+            inbox_addr: self
+                .inbox_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("inbox_addr")?,
+            outbox_addr: self
+                .outbox_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("outbox_addr")?,
+            max_messages_per_tx: *required(&self.max_messages_per_tx)
+                .context("max_messages_per_tx")?,
+            gas_limit_per_message: *required(&self.gas_limit_per_message)
+                .context("gas_limit_per_message")?,
+            max_attempts: *required(&self.max_attempts).context("max_attempts")?,
+            tx_resend_interval_sec: *required(&self.tx_resend_interval_sec)
+                .context("tx_resend_interval_sec")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            polling_interval_ms: Some(this.polling_interval_ms),
+            outbound_senders: this
+                .outbound_senders
+                .iter()
+                .map(|address| address.as_bytes().into())
+                .collect(),
+            inbox_addr: this.inbox_addr.as_ref().map(|x| x.as_bytes().into()),
+            outbox_addr: this.outbox_addr.as_ref().map(|x| x.as_bytes().into()),
+            max_messages_per_tx: Some(this.max_messages_per_tx),
+            gas_limit_per_message: Some(this.gas_limit_per_message),
+            max_attempts: Some(this.max_attempts),
+            tx_resend_interval_sec: Some(this.tx_resend_interval_sec),
+        }
+    }
+}
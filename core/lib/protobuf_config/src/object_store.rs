@@ -37,6 +37,19 @@ impl ProtoRepr for proto::ObjectStore {
                     .context("file_backed_base_path")?
                     .clone(),
             },
+            proto::object_store::Mode::S3(mode) => ObjectStoreMode::S3 {
+                bucket_base_url: required(&mode.bucket_base_url)
+                    .context("bucket_base_url")?
+                    .clone(),
+                region: mode.region.clone(),
+                endpoint: mode.endpoint.clone(),
+            },
+            proto::object_store::Mode::AzureBlob(mode) => ObjectStoreMode::AzureBlob {
+                storage_account: required(&mode.storage_account)
+                    .context("storage_account")?
+                    .clone(),
+                container: required(&mode.container).context("container")?.clone(),
+            },
         };
 
         Ok(Self::Type {
@@ -75,6 +88,22 @@ impl ProtoRepr for proto::ObjectStore {
             } => proto::object_store::Mode::FileBacked(proto::object_store::FileBacked {
                 file_backed_base_path: Some(file_backed_base_path.clone()),
             }),
+            ObjectStoreMode::S3 {
+                bucket_base_url,
+                region,
+                endpoint,
+            } => proto::object_store::Mode::S3(proto::object_store::S3 {
+                bucket_base_url: Some(bucket_base_url.clone()),
+                region: region.clone(),
+                endpoint: endpoint.clone(),
+            }),
+            ObjectStoreMode::AzureBlob {
+                storage_account,
+                container,
+            } => proto::object_store::Mode::AzureBlob(proto::object_store::AzureBlob {
+                storage_account: Some(storage_account.clone()),
+                container: Some(container.clone()),
+            }),
         };
 
         Self {
@@ -21,6 +21,23 @@ impl proto::ProtocolVersionLoadingMode {
     }
 }
 
+impl proto::ProofGenerationMode {
+    fn new(x: &configs::proof_data_handler::ProofGenerationMode) -> Self {
+        type From = configs::proof_data_handler::ProofGenerationMode;
+        match x {
+            From::Prover => Self::Prover,
+            From::Mock => Self::Mock,
+        }
+    }
+    fn parse(&self) -> configs::proof_data_handler::ProofGenerationMode {
+        type To = configs::proof_data_handler::ProofGenerationMode;
+        match self {
+            Self::Prover => To::Prover,
+            Self::Mock => To::Mock,
+        }
+    }
+}
+
 impl ProtoRepr for proto::ProofDataHandler {
     type Type = configs::ProofDataHandlerConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -38,6 +55,13 @@ impl ProtoRepr for proto::ProofDataHandler {
             fri_protocol_version_id: required(&self.fri_protocol_version_id)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("fri_protocol_version_id")?,
+            proof_generation_mode: self
+                .proof_generation_mode
+                .map(proto::ProofGenerationMode::try_from)
+                .transpose()
+                .context("proof_generation_mode")?
+                .map(|mode| mode.parse())
+                .unwrap_or_default(),
         })
     }
 
@@ -49,6 +73,9 @@ impl ProtoRepr for proto::ProofDataHandler {
                 proto::ProtocolVersionLoadingMode::new(&this.protocol_version_loading_mode).into(),
             ),
             fri_protocol_version_id: Some(this.fri_protocol_version_id.into()),
+            proof_generation_mode: Some(
+                proto::ProofGenerationMode::new(&this.proof_generation_mode).into(),
+            ),
         }
     }
 }
@@ -0,0 +1,23 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::ReexecutionWatchdog {
+    type Type = configs::ReexecutionWatchdogConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            check_interval_ms: *required(&self.check_interval_ms).context("check_interval_ms")?,
+            sample_window_batches: *required(&self.sample_window_batches)
+                .context("sample_window_batches")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            check_interval_ms: Some(this.check_interval_ms),
+            sample_window_batches: Some(this.sample_window_batches),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::RosettaApi {
+    type Type = configs::RosettaApiConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            port: required(&self.port)
+                .and_then(|p| Ok((*p).try_into()?))
+                .context("port")?,
+            network: required(&self.network).context("network")?.clone(),
+            base_token_symbol: required(&self.base_token_symbol)
+                .context("base_token_symbol")?
+                .clone(),
+            base_token_decimals: *required(&self.base_token_decimals)
+                .context("base_token_decimals")?,
+            max_block_events: required(&self.max_block_events)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("max_block_events")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            port: Some(this.port.into()),
+            network: Some(this.network.clone()),
+            base_token_symbol: Some(this.base_token_symbol.clone()),
+            base_token_decimals: Some(this.base_token_decimals),
+            max_block_events: Some(this.max_block_events.try_into().unwrap()),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::SharedSequencer {
+    type Type = configs::SharedSequencerConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            url: required(&self.url).context("url")?.clone(),
+            fallback_timeout_ms: *required(&self.fallback_timeout_ms)
+                .context("fallback_timeout_ms")?,
+            reconnect_interval_ms: *required(&self.reconnect_interval_ms)
+                .context("reconnect_interval_ms")?,
+            ack_interval_ms: *required(&self.ack_interval_ms).context("ack_interval_ms")?,
+            max_buffered_txs: *required(&self.max_buffered_txs).context("max_buffered_txs")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            url: Some(this.url.clone()),
+            fallback_timeout_ms: Some(this.fallback_timeout_ms),
+            reconnect_interval_ms: Some(this.reconnect_interval_ms),
+            ack_interval_ms: Some(this.ack_interval_ms),
+            max_buffered_txs: Some(this.max_buffered_txs),
+        }
+    }
+}
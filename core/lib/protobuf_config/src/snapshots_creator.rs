@@ -12,6 +12,7 @@ impl ProtoRepr for proto::SnapshotsCreator {
                 .context("storage_logs_chunk_size")?,
             concurrent_queries_count: *required(&self.concurrent_queries_count)
                 .context("concurrent_queries_count")?,
+            creation_interval_sec: self.creation_interval_sec,
         })
     }
 
@@ -19,6 +20,7 @@ impl ProtoRepr for proto::SnapshotsCreator {
         Self {
             storage_logs_chunk_size: Some(this.storage_logs_chunk_size),
             concurrent_queries_count: Some(this.concurrent_queries_count),
+            creation_interval_sec: this.creation_interval_sec,
         }
     }
 }
@@ -0,0 +1,75 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{parse_h160, proto, repr::ProtoRepr};
+
+impl proto::PriceFeedSource {
+    fn new(x: &configs::stable_gas_price::PriceFeedSource) -> Self {
+        type From = configs::stable_gas_price::PriceFeedSource;
+        match x {
+            From::Http => Self::Http,
+            From::OnChain => Self::OnChain,
+        }
+    }
+
+    fn parse(&self) -> configs::stable_gas_price::PriceFeedSource {
+        type To = configs::stable_gas_price::PriceFeedSource;
+        match self {
+            Self::Http => To::Http,
+            Self::OnChain => To::OnChain,
+        }
+    }
+}
+
+impl ProtoRepr for proto::StableGasPrice {
+    type Type = configs::StableGasPriceConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            source: required(&self.source)
+                .and_then(|x| Ok(proto::PriceFeedSource::try_from(*x)?))
+                .context("source")?
+                .parse(),
+            feed_url: self.feed_url.clone(),
+            feed_json_pointer: required(&self.feed_json_pointer)
+                .context("feed_json_pointer")?
+                .clone(),
+            feed_contract_addr: self
+                .feed_contract_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("feed_contract_addr")?,
+            max_price_age_sec: *required(&self.max_price_age_sec).context("max_price_age_sec")?,
+            update_interval_ms: *required(&self.update_interval_ms)
+                .context("update_interval_ms")?,
+            target_tx_cost: *required(&self.target_tx_cost).context("target_tx_cost")?,
+            reference_tx_gas: *required(&self.reference_tx_gas).context("reference_tx_gas")?,
+            min_l2_gas_price: *required(&self.min_l2_gas_price).context("min_l2_gas_price")?,
+            max_l2_gas_price: *required(&self.max_l2_gas_price).context("max_l2_gas_price")?,
+            smoothing_factor: *required(&self.smoothing_factor).context("smoothing_factor")?,
+            max_change_per_update: *required(&self.max_change_per_update)
+                .context("max_change_per_update")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            source: Some(proto::PriceFeedSource::new(&this.source).into()),
+            feed_url: this.feed_url.clone(),
+            feed_json_pointer: Some(this.feed_json_pointer.clone()),
+            feed_contract_addr: this
+                .feed_contract_addr
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            max_price_age_sec: Some(this.max_price_age_sec),
+            update_interval_ms: Some(this.update_interval_ms),
+            target_tx_cost: Some(this.target_tx_cost),
+            reference_tx_gas: Some(this.reference_tx_gas),
+            min_l2_gas_price: Some(this.min_l2_gas_price),
+            max_l2_gas_price: Some(this.max_l2_gas_price),
+            smoothing_factor: Some(this.smoothing_factor),
+            max_change_per_update: Some(this.max_change_per_update),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::Supervisor {
+    type Type = configs::SupervisorConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            max_restarts: *required(&self.max_restarts).context("max_restarts")?,
+            restart_window_sec: *required(&self.restart_window_sec)
+                .context("restart_window_sec")?,
+            initial_backoff_ms: *required(&self.initial_backoff_ms)
+                .context("initial_backoff_ms")?,
+            max_backoff_ms: *required(&self.max_backoff_ms).context("max_backoff_ms")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            max_restarts: Some(this.max_restarts),
+            restart_window_sec: Some(this.restart_window_sec),
+            initial_backoff_ms: Some(this.initial_backoff_ms),
+            max_backoff_ms: Some(this.max_backoff_ms),
+        }
+    }
+}
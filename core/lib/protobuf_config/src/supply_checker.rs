@@ -0,0 +1,47 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{parse_h160, proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::SupplyChecker {
+    type Type = configs::SupplyCheckerConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            check_interval_ms: *required(&self.check_interval_ms).context("check_interval_ms")?,
+            base_token_l1_addr: self
+                .base_token_l1_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("base_token_l1_addr")?,
+            locked_funds_addr: self
+                .locked_funds_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("locked_funds_addr")?,
+            unbacked_supply_gwei: *required(&self.unbacked_supply_gwei)
+                .context("unbacked_supply_gwei")?,
+            epsilon_gwei: *required(&self.epsilon_gwei).context("epsilon_gwei")?,
+            max_surplus_gwei: self.max_surplus_gwei,
+            alert_after_checks: *required(&self.alert_after_checks)
+                .context("alert_after_checks")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            check_interval_ms: Some(this.check_interval_ms),
+            base_token_l1_addr: this
+                .base_token_l1_addr
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            locked_funds_addr: this.locked_funds_addr.as_ref().map(|x| x.as_bytes().into()),
+            unbacked_supply_gwei: Some(this.unbacked_supply_gwei),
+            epsilon_gwei: Some(this.epsilon_gwei),
+            max_surplus_gwei: this.max_surplus_gwei,
+            alert_after_checks: Some(this.alert_after_checks),
+        }
+    }
+}
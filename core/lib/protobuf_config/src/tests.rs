@@ -62,6 +62,14 @@ fn test_encoding() {
     encode_decode::<proto::Api>(rng);
     encode_decode::<proto::Prometheus>(rng);
     encode_decode::<proto::EthNetwork>(rng);
+    encode_decode::<proto::ChainExport>(rng);
+    encode_decode::<proto::ConfigReload>(rng);
+    encode_decode::<proto::Supervisor>(rng);
+    encode_decode::<proto::CdcPublisher>(rng);
+    encode_decode::<proto::Webhooks>(rng);
+    encode_decode::<proto::AuditLog>(rng);
+    encode_decode::<proto::LeaderElection>(rng);
+    encode_decode::<proto::SharedSequencer>(rng);
     encode_decode::<proto::StateKeeper>(rng);
     encode_decode::<proto::OperationsManager>(rng);
     encode_decode::<proto::Mempool>(rng);
@@ -85,8 +93,16 @@ fn test_encoding() {
     encode_decode::<proto::FriWitnessGenerator>(rng);
     encode_decode::<proto::FriWitnessVectorGenerator>(rng);
     encode_decode::<proto::HouseKeeper>(rng);
+    encode_decode::<proto::MessageRelay>(rng);
+    encode_decode::<proto::FeeDistributor>(rng);
+    encode_decode::<proto::Firehose>(rng);
     encode_decode::<proto::ObjectStore>(rng);
     encode_decode::<proto::ProofDataHandler>(rng);
+    encode_decode::<proto::ReexecutionWatchdog>(rng);
+    encode_decode::<proto::RosettaApi>(rng);
     encode_decode::<proto::SnapshotsCreator>(rng);
+    encode_decode::<proto::StableGasPrice>(rng);
+    encode_decode::<proto::SupplyChecker>(rng);
+    encode_decode::<proto::WithdrawalFinalizer>(rng);
     encode_decode::<proto::WitnessGenerator>(rng);
 }
@@ -0,0 +1,30 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::Webhooks {
+    type Type = configs::WebhooksConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            urls: self.urls.clone(),
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            request_timeout_ms: *required(&self.request_timeout_ms)
+                .context("request_timeout_ms")?,
+            max_attempts: *required(&self.max_attempts).context("max_attempts")?,
+            retry_backoff_ms: *required(&self.retry_backoff_ms).context("retry_backoff_ms")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            urls: this.urls.clone(),
+            polling_interval_ms: Some(this.polling_interval_ms),
+            request_timeout_ms: Some(this.request_timeout_ms),
+            max_attempts: Some(this.max_attempts),
+            retry_backoff_ms: Some(this.retry_backoff_ms),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::required;
+
+use crate::{parse_h160, proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::WithdrawalFinalizer {
+    type Type = configs::WithdrawalFinalizerConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            token_allowlist: self
+                .token_allowlist
+                .iter()
+                .enumerate()
+                .map(|(i, x)| parse_h160(x).context(i))
+                .collect::<Result<_, _>>()
+                .context("token_allowlist")?,
+            max_withdrawals_per_tx: *required(&self.max_withdrawals_per_tx)
+                .context("max_withdrawals_per_tx")?,
+            gas_limit_per_withdrawal: *required(&self.gas_limit_per_withdrawal)
+                .context("gas_limit_per_withdrawal")?,
+            max_gas_price_gwei: self.max_gas_price_gwei,
+            max_attempts: *required(&self.max_attempts).context("max_attempts")?,
+            tx_resend_interval_sec: *required(&self.tx_resend_interval_sec)
+                .context("tx_resend_interval_sec")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            polling_interval_ms: Some(this.polling_interval_ms),
+            token_allowlist: this
+                .token_allowlist
+                .iter()
+                .map(|address| address.as_bytes().into())
+                .collect(),
+            max_withdrawals_per_tx: Some(this.max_withdrawals_per_tx),
+            gas_limit_per_withdrawal: Some(this.gas_limit_per_withdrawal),
+            max_gas_price_gwei: this.max_gas_price_gwei,
+            max_attempts: Some(this.max_attempts),
+            tx_resend_interval_sec: Some(this.tx_resend_interval_sec),
+        }
+    }
+}
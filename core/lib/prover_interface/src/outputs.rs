@@ -17,6 +17,17 @@ pub struct L1BatchProofForL1 {
     pub scheduler_proof: Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
 }
 
+impl L1BatchProofForL1 {
+    /// Creates a mock proof with the specified aggregation result coordinates and an empty scheduler proof.
+    /// Mock proofs are never sent to the settlement layer as is; they only mark L1 batches as proven.
+    pub fn mock(aggregation_result_coords: [[u8; 32]; 4]) -> Self {
+        Self {
+            aggregation_result_coords,
+            scheduler_proof: Proof::empty(),
+        }
+    }
+}
+
 impl fmt::Debug for L1BatchProofForL1 {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter
@@ -1,17 +1,18 @@
 //! Logic for applying application-level snapshots to Postgres storage.
 
-use std::{collections::HashMap, fmt, time::Duration};
+use std::{collections::HashMap, fmt, num::NonZeroUsize, time::Duration};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use tokio::sync::Semaphore;
 use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
-use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
 use zksync_types::{
     api::en::SyncBlock,
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotHeader, SnapshotRecoveryStatus, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotBlobDigest, SnapshotFactoryDependencies, SnapshotHeader, SnapshotManifest,
+        SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     tokens::TokenInfo,
     web3::futures,
@@ -156,6 +157,14 @@ pub struct SnapshotsApplierConfig {
     pub retry_count: usize,
     pub initial_retry_backoff: Duration,
     pub retry_backoff_multiplier: f32,
+    /// Maximum number of storage logs chunks recovered concurrently. The concurrency is additionally
+    /// limited by the size of the connection pool.
+    pub max_concurrency: NonZeroUsize,
+    /// Expected Keccak-256 hash of the snapshot manifest as stored in the object store. The hash should be obtained
+    /// from a trusted source (e.g., published by the main node operator), so that a tampered object store cannot
+    /// substitute the manifest together with snapshot blobs. If set, recovery fails if the manifest is missing
+    /// or doesn't match the hash.
+    pub expected_manifest_hash: Option<H256>,
 }
 
 impl Default for SnapshotsApplierConfig {
@@ -164,6 +173,8 @@ impl Default for SnapshotsApplierConfig {
             retry_count: 5,
             initial_retry_backoff: Duration::from_secs(2),
             retry_backoff_multiplier: 2.0,
+            max_concurrency: NonZeroUsize::new(10).unwrap(),
+            expected_manifest_hash: None,
         }
     }
 }
@@ -187,9 +198,14 @@ impl SnapshotsApplierConfig {
         let mut backoff = self.initial_retry_backoff;
         let mut last_error = None;
         for retry_id in 0..self.retry_count {
-            let result =
-                SnapshotsApplier::load_snapshot(connection_pool, main_node_client, blob_store)
-                    .await;
+            let result = SnapshotsApplier::load_snapshot(
+                connection_pool,
+                main_node_client,
+                blob_store,
+                self.max_concurrency,
+                self.expected_manifest_hash,
+            )
+            .await;
 
             match result {
                 Ok(()) => return Ok(SnapshotsApplierOutcome::Ok),
@@ -227,6 +243,9 @@ struct SnapshotsApplier<'a> {
     main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
     blob_store: &'a dyn ObjectStore,
     applied_snapshot_status: SnapshotRecoveryStatus,
+    /// Integrity manifest of the snapshot. If present, all blobs fetched from the object store are checked against it.
+    manifest: Option<SnapshotManifest>,
+    max_concurrency: usize,
 }
 
 impl<'a> SnapshotsApplier<'a> {
@@ -277,6 +296,8 @@ impl<'a> SnapshotsApplier<'a> {
         connection_pool: &'a ConnectionPool,
         main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
         blob_store: &'a dyn ObjectStore,
+        max_concurrency: NonZeroUsize,
+        expected_manifest_hash: Option<H256>,
     ) -> Result<(), SnapshotsApplierError> {
         let mut storage = connection_pool
             .access_storage_tagged("snapshots_applier")
@@ -288,12 +309,17 @@ impl<'a> SnapshotsApplier<'a> {
         let (applied_snapshot_status, created_from_scratch) =
             Self::prepare_applied_snapshot_status(&mut storage_transaction, main_node_client)
                 .await?;
+        let manifest =
+            Self::fetch_manifest(blob_store, &applied_snapshot_status, expected_manifest_hash)
+                .await?;
 
         let mut recovery = Self {
             connection_pool,
             main_node_client,
             blob_store,
             applied_snapshot_status,
+            manifest,
+            max_concurrency: max_concurrency.get(),
         };
 
         METRICS.storage_logs_chunks_count.set(
@@ -342,6 +368,13 @@ impl<'a> SnapshotsApplier<'a> {
             "Found snapshot with data up to L1 batch #{l1_batch_number}, storage_logs are divided into {} chunk(s)",
             snapshot.storage_logs_chunks.len()
         );
+        let protocol_version = snapshot
+            .last_l1_batch_with_metadata
+            .header
+            .protocol_version
+            .with_context(|| {
+                format!("snapshot L1 batch #{l1_batch_number} doesn't have protocol version set")
+            })?;
 
         let miniblock = main_node_client
             .fetch_l2_block(miniblock_number)
@@ -358,15 +391,112 @@ impl<'a> SnapshotsApplier<'a> {
             miniblock_number: snapshot.miniblock_number,
             miniblock_timestamp: miniblock.timestamp,
             miniblock_hash,
-            protocol_version: snapshot
-                .last_l1_batch_with_metadata
-                .header
-                .protocol_version
-                .unwrap(),
+            protocol_version,
             storage_logs_chunks_processed: vec![false; snapshot.storage_logs_chunks.len()],
         })
     }
 
+    /// Fetches the snapshot manifest and checks it against the expected hash, if one is configured.
+    async fn fetch_manifest(
+        blob_store: &dyn ObjectStore,
+        status: &SnapshotRecoveryStatus,
+        expected_hash: Option<H256>,
+    ) -> Result<Option<SnapshotManifest>, SnapshotsApplierError> {
+        let l1_batch_number = status.l1_batch_number;
+        let key = SnapshotManifest::encode_key(l1_batch_number);
+        let blob = match blob_store.get_raw(SnapshotManifest::BUCKET, &key).await {
+            Ok(blob) => blob,
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                if let Some(expected_hash) = expected_hash {
+                    let err = anyhow::anyhow!(
+                        "snapshot for L1 batch #{l1_batch_number} has no manifest, but the manifest hash \
+                         {expected_hash:?} is configured"
+                    );
+                    return Err(err.into());
+                }
+                tracing::warn!(
+                    "Snapshot for L1 batch #{l1_batch_number} has no manifest; snapshot integrity will not be checked"
+                );
+                return Ok(None);
+            }
+            Err(err) => {
+                let context = format!(
+                    "cannot fetch snapshot manifest for L1 batch #{l1_batch_number} from object store"
+                );
+                return Err(SnapshotsApplierError::object_store(err, context));
+            }
+        };
+
+        let hash = SnapshotBlobDigest::new(&blob).hash;
+        match expected_hash {
+            Some(expected_hash) if hash != expected_hash => {
+                let err = anyhow::anyhow!(
+                    "snapshot manifest for L1 batch #{l1_batch_number} has hash {hash:?}, which doesn't match \
+                     the configured hash {expected_hash:?}"
+                );
+                return Err(err.into());
+            }
+            Some(_) => {}
+            None => tracing::warn!(
+                "Hash of the snapshot manifest is not configured; the manifest fetched from the object store \
+                 (hash: {hash:?}) is trusted as is"
+            ),
+        }
+        let manifest = SnapshotManifest::deserialize(blob).map_err(|err| {
+            let context =
+                format!("cannot deserialize snapshot manifest for L1 batch #{l1_batch_number}");
+            SnapshotsApplierError::object_store(ObjectStoreError::Serialization(err), context)
+        })?;
+
+        let chunk_count = status.storage_logs_chunks_processed.len();
+        if manifest.l1_batch_number != l1_batch_number
+            || manifest.storage_logs_chunks.len() != chunk_count
+        {
+            let err = anyhow::anyhow!(
+                "snapshot manifest (L1 batch #{}, {} storage logs chunks) doesn't match snapshot \
+                 (L1 batch #{l1_batch_number}, {chunk_count} storage logs chunks)",
+                manifest.l1_batch_number,
+                manifest.storage_logs_chunks.len()
+            );
+            return Err(err.into());
+        }
+        tracing::info!("Fetched manifest for snapshot at L1 batch #{l1_batch_number}");
+        Ok(Some(manifest))
+    }
+
+    /// Fetches a blob from the object store, checking it against the expected digest from the manifest.
+    async fn fetch_blob<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+        expected_digest: Option<&SnapshotBlobDigest>,
+        description: &str,
+    ) -> Result<V, SnapshotsApplierError> {
+        let blob = self
+            .blob_store
+            .get_raw(V::BUCKET, &V::encode_key(key))
+            .await
+            .map_err(|err| {
+                let context = format!("cannot fetch {description} from object store");
+                SnapshotsApplierError::object_store(err, context)
+            })?;
+
+        if let Some(expected_digest) = expected_digest {
+            let digest = SnapshotBlobDigest::new(&blob);
+            if digest != *expected_digest {
+                let err = anyhow::anyhow!(
+                    "{description} in object store doesn't match snapshot manifest: \
+                     expected {expected_digest:?}, got {digest:?}"
+                );
+                return Err(err.into());
+            }
+        }
+
+        V::deserialize(blob).map_err(|err| {
+            let context = format!("cannot deserialize {description}");
+            SnapshotsApplierError::object_store(ObjectStoreError::Serialization(err), context)
+        })
+    }
+
     async fn recover_factory_deps(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -375,13 +505,14 @@ impl<'a> SnapshotsApplier<'a> {
 
         tracing::debug!("Fetching factory dependencies from object store");
         let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
-        let factory_deps: SnapshotFactoryDependencies =
-            self.blob_store.get(l1_batch_number).await.map_err(|err| {
-                let context = format!(
-                    "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
-                );
-                SnapshotsApplierError::object_store(err, context)
-            })?;
+        let expected_digest = self
+            .manifest
+            .as_ref()
+            .map(|manifest| &manifest.factory_deps);
+        let description = format!("factory deps for L1 batch #{l1_batch_number}");
+        let factory_deps: SnapshotFactoryDependencies = self
+            .fetch_blob(l1_batch_number, expected_digest, &description)
+            .await?;
         tracing::debug!(
             "Fetched {} factory dependencies from object store",
             factory_deps.factory_deps.len()
@@ -464,12 +595,14 @@ impl<'a> SnapshotsApplier<'a> {
             chunk_id,
             l1_batch_number: self.applied_snapshot_status.l1_batch_number,
         };
-        let storage_snapshot_chunk: SnapshotStorageLogsChunk =
-            self.blob_store.get(storage_key).await.map_err(|err| {
-                let context =
-                    format!("cannot fetch storage logs {storage_key:?} from object store");
-                SnapshotsApplierError::object_store(err, context)
-            })?;
+        let expected_digest = self
+            .manifest
+            .as_ref()
+            .map(|manifest| &manifest.storage_logs_chunks[chunk_id as usize]);
+        let description = format!("storage logs {storage_key:?}");
+        let storage_snapshot_chunk: SnapshotStorageLogsChunk = self
+            .fetch_blob(storage_key, expected_digest, &description)
+            .await?;
         let storage_logs = &storage_snapshot_chunk.storage_logs;
         let latency = latency.observe();
         tracing::info!(
@@ -516,7 +649,11 @@ impl<'a> SnapshotsApplier<'a> {
     }
 
     async fn recover_storage_logs(&self) -> Result<(), SnapshotsApplierError> {
-        let semaphore = Semaphore::new(self.connection_pool.max_size() as usize);
+        let concurrency = self
+            .max_concurrency
+            .min(self.connection_pool.max_size() as usize);
+        tracing::info!("Recovering storage logs with concurrency {concurrency}");
+        let semaphore = Semaphore::new(concurrency);
         let tasks = self
             .applied_snapshot_status
             .storage_logs_chunks_processed
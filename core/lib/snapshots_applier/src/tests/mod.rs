@@ -11,7 +11,8 @@ use zksync_types::{
 };
 
 use self::utils::{
-    mock_recovery_status, prepare_clients, MockMainNodeClient, ObjectStoreWithErrors,
+    mock_recovery_status, prepare_clients, prepare_manifest, MockMainNodeClient,
+    ObjectStoreWithErrors,
 };
 use super::*;
 use crate::tests::utils::{mock_tokens, random_storage_logs};
@@ -265,3 +266,157 @@ async fn recovering_tokens() {
         .unwrap();
     assert_matches!(outcome, SnapshotsApplierOutcome::Ok);
 }
+
+#[tokio::test]
+async fn recovering_storage_logs_sequentially() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    let config = SnapshotsApplierConfig {
+        max_concurrency: NonZeroUsize::new(1).unwrap(),
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    let outcome = config.run(&pool, &client, &object_store).await.unwrap();
+    assert_matches!(outcome, SnapshotsApplierOutcome::Ok);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), storage_logs.len());
+}
+
+#[tokio::test]
+async fn applier_errors_on_snapshot_without_protocol_version() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let snapshot = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot.last_l1_batch_with_metadata.header.protocol_version = None;
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("protocol version"), "{err}");
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert!(status.is_none());
+}
+
+#[tokio::test]
+async fn recovering_snapshot_with_manifest() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+    let manifest = prepare_manifest(&*object_store, &expected_status).await;
+    object_store
+        .put(expected_status.l1_batch_number, &manifest)
+        .await
+        .unwrap();
+
+    let outcome = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+    assert_matches!(outcome, SnapshotsApplierOutcome::Ok);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), storage_logs.len());
+}
+
+#[tokio::test]
+async fn applier_errors_on_blob_not_matching_manifest() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+    let mut manifest = prepare_manifest(&*object_store, &expected_status).await;
+    manifest.storage_logs_chunks[1] = SnapshotBlobDigest::new(b"tampered chunk");
+    object_store
+        .put(expected_status.l1_batch_number, &manifest)
+        .await
+        .unwrap();
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("doesn't match snapshot manifest"), "{err}");
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap()
+        .unwrap();
+    // The other chunk may or may not be recovered, depending on the task scheduling.
+    assert!(!status.storage_logs_chunks_processed[1]);
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn checking_manifest_against_configured_hash(hash_matches: bool) {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+    let manifest = prepare_manifest(&*object_store, &expected_status).await;
+    let manifest_key = object_store
+        .put(expected_status.l1_batch_number, &manifest)
+        .await
+        .unwrap();
+    let manifest_blob = object_store
+        .get_raw(SnapshotManifest::BUCKET, &manifest_key)
+        .await
+        .unwrap();
+    let manifest_hash = if hash_matches {
+        SnapshotBlobDigest::new(&manifest_blob).hash
+    } else {
+        H256::repeat_byte(0xff)
+    };
+
+    let config = SnapshotsApplierConfig {
+        expected_manifest_hash: Some(manifest_hash),
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    let result = config.run(&pool, &client, &object_store).await;
+    if hash_matches {
+        assert_matches!(result.unwrap(), SnapshotsApplierOutcome::Ok);
+    } else {
+        let err = format!("{:#}", result.unwrap_err());
+        assert!(err.contains("doesn't match the configured hash"), "{err}");
+    }
+}
+
+#[tokio::test]
+async fn applier_errors_on_missing_manifest_with_configured_hash() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    let config = SnapshotsApplierConfig {
+        expected_manifest_hash: Some(H256::repeat_byte(1)),
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    let err = config.run(&pool, &client, &object_store).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("has no manifest"), "{err}");
+}
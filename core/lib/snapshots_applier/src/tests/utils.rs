@@ -3,14 +3,16 @@
 use std::{collections::HashMap, fmt, sync::Arc};
 
 use async_trait::async_trait;
-use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory};
+use zksync_object_store::{
+    Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory, StoredObject,
+};
 use zksync_types::{
     api::en::SyncBlock,
     block::L1BatchHeader,
     commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader,
-        SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotBlobDigest, SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader,
+        SnapshotManifest, SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
         SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey,
     },
     tokens::{TokenInfo, TokenMetadata},
@@ -118,6 +120,9 @@ fn miniblock_metadata(
         virtual_blocks: None,
         hash: Some(hash),
         protocol_version: Default::default(),
+        sequencer_signature: None,
+        content_hash: None,
+        sequencer_header_signature: None,
     }
 }
 
@@ -273,3 +278,40 @@ pub(super) async fn prepare_clients(
     );
     (object_store, client)
 }
+
+/// Creates a manifest for the snapshot blobs previously saved by [`prepare_clients()`].
+pub(super) async fn prepare_manifest(
+    object_store: &dyn ObjectStore,
+    status: &SnapshotRecoveryStatus,
+) -> SnapshotManifest {
+    let factory_deps = object_store
+        .get_raw(
+            SnapshotFactoryDependencies::BUCKET,
+            &SnapshotFactoryDependencies::encode_key(status.l1_batch_number),
+        )
+        .await
+        .unwrap();
+
+    let mut storage_logs_chunks = vec![];
+    for chunk_id in 0..status.storage_logs_chunks_processed.len() as u64 {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: status.l1_batch_number,
+            chunk_id,
+        };
+        let chunk = object_store
+            .get_raw(
+                SnapshotStorageLogsChunk::BUCKET,
+                &SnapshotStorageLogsChunk::encode_key(key),
+            )
+            .await
+            .unwrap();
+        storage_logs_chunks.push(SnapshotBlobDigest::new(&chunk));
+    }
+
+    SnapshotManifest {
+        l1_batch_number: status.l1_batch_number,
+        miniblock_number: status.miniblock_number,
+        factory_deps: SnapshotBlobDigest::new(&factory_deps),
+        storage_logs_chunks,
+    }
+}
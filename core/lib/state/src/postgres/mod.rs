@@ -382,15 +382,36 @@ impl<'a> PostgresStorage<'a> {
             .with_context(|| {
                 format!("failed resolving L1 batch number for miniblock #{block_number}")
             })?;
-        Ok(Self {
+        Ok(Self::new_resolved(
+            rt_handle,
+            connection,
+            block_number,
+            resolved.expected_l1_batch(),
+            resolved.pending_l1_batch,
+            consider_new_l1_batch,
+        ))
+    }
+
+    /// Creates a new storage using L1 batch numbers resolved for the miniblock beforehand, i.e. the L1 batch
+    /// the miniblock belongs to (or will belong to once it's sealed) and the pending L1 batch. Unlike
+    /// [`Self::new_async()`], doesn't query Postgres.
+    pub fn new_resolved(
+        rt_handle: Handle,
+        connection: StorageProcessor<'a>,
+        block_number: MiniblockNumber,
+        l1_batch_number_for_miniblock: L1BatchNumber,
+        pending_l1_batch_number: L1BatchNumber,
+        consider_new_l1_batch: bool,
+    ) -> Self {
+        Self {
             rt_handle,
             connection,
             miniblock_number: block_number,
-            l1_batch_number_for_miniblock: resolved.expected_l1_batch(),
-            pending_l1_batch_number: resolved.pending_l1_batch,
+            l1_batch_number_for_miniblock,
+            pending_l1_batch_number,
             consider_new_l1_batch,
             caches: None,
-        })
+        }
     }
 
     /// Sets the caches to use with the storage.
@@ -31,7 +31,7 @@ use anyhow::Context as _;
 use itertools::{Either, Itertools};
 use tokio::sync::watch;
 use zksync_dal::StorageProcessor;
-use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_storage::{db::NamedColumnFamily, RocksDB, RocksDBOptions};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256, U256};
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
@@ -209,15 +209,28 @@ impl RocksdbStorage {
     ///
     /// Propagates RocksDB I/O errors.
     pub async fn builder(path: &Path) -> anyhow::Result<RocksbStorageBuilder> {
-        Self::new(path.to_path_buf())
+        Self::builder_with_options(path, RocksDBOptions::default()).await
+    }
+
+    /// Creates a new storage builder with the provided RocksDB `path` and `options`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub async fn builder_with_options(
+        path: &Path,
+        options: RocksDBOptions,
+    ) -> anyhow::Result<RocksbStorageBuilder> {
+        Self::new(path.to_path_buf(), options)
             .await
             .map(RocksbStorageBuilder)
     }
 
-    async fn new(path: PathBuf) -> anyhow::Result<Self> {
+    async fn new(path: PathBuf, options: RocksDBOptions) -> anyhow::Result<Self> {
         tokio::task::spawn_blocking(move || {
             Ok(Self {
-                db: RocksDB::new(&path).context("failed initializing state keeper RocksDB")?,
+                db: RocksDB::with_options(&path, options)
+                    .context("failed initializing state keeper RocksDB")?,
                 pending_patch: InMemoryStorage::default(),
                 enum_index_migration_chunk_size: 100,
                 #[cfg(test)]
@@ -228,6 +241,24 @@ impl RocksdbStorage {
         .context("panicked initializing state keeper RocksDB")?
     }
 
+    /// Returns a read-only view of this storage sharing the underlying RocksDB instance.
+    /// The view is not isolated from subsequent updates of this storage; it's the caller's responsibility
+    /// to not read from the view while the storage is being updated.
+    pub fn read_only_view(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            pending_patch: InMemoryStorage::default(),
+            enum_index_migration_chunk_size: self.enum_index_migration_chunk_size,
+            #[cfg(test)]
+            listener: RocksdbStorageEventListener::default(),
+        }
+    }
+
+    /// Converts this storage back into a builder, e.g. in order to synchronize it with Postgres again.
+    pub fn into_builder(self) -> RocksbStorageBuilder {
+        RocksbStorageBuilder(self)
+    }
+
     async fn update_from_postgres(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -599,7 +630,7 @@ impl RocksdbStorage {
     /// # Panics
     ///
     /// Panics on RocksDB errors.
-    async fn l1_batch_number(&self) -> Option<L1BatchNumber> {
+    pub async fn l1_batch_number(&self) -> Option<L1BatchNumber> {
         let cf = StateKeeperColumnFamily::State;
         let db = self.db.clone();
         let number_bytes =
@@ -41,7 +41,9 @@ impl Default for RocksdbStorageEventListener {
 #[tokio::test]
 async fn rocksdb_storage_basics() {
     let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
-    let mut storage = RocksdbStorage::new(dir.path().to_path_buf()).await.unwrap();
+    let mut storage = RocksdbStorage::new(dir.path().to_path_buf(), RocksDBOptions::default())
+        .await
+        .unwrap();
     let mut storage_logs: HashMap<_, _> = gen_storage_logs(0..20)
         .into_iter()
         .map(|log| (log.key, log.value))
@@ -105,6 +107,43 @@ async fn rocksdb_storage_syncing_with_postgres() {
     }
 }
 
+#[tokio::test]
+async fn resyncing_rocksdb_storage_with_read_only_view() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut conn).await;
+    let storage_logs = gen_storage_logs(20..40);
+    create_miniblock(&mut conn, MiniblockNumber(1), storage_logs.clone()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(1), &storage_logs).await;
+
+    let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
+    let storage = sync_test_storage(&dir, &mut conn).await;
+    let mut view = storage.read_only_view();
+    for log in &storage_logs {
+        assert_eq!(view.read_value(&log.key), log.value);
+    }
+
+    let new_storage_logs = gen_storage_logs(40..50);
+    create_miniblock(&mut conn, MiniblockNumber(2), new_storage_logs.clone()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(2), &new_storage_logs).await;
+    for log in &new_storage_logs {
+        assert!(view.is_write_initial(&log.key));
+    }
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let storage = storage
+        .into_builder()
+        .synchronize(&mut conn, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("Storage synchronization unexpectedly stopped");
+    assert_eq!(storage.l1_batch_number().await, Some(L1BatchNumber(3)));
+    // The view must observe the updated storage.
+    for log in new_storage_logs.iter().chain(&storage_logs) {
+        assert_eq!(view.read_value(&log.key), log.value);
+    }
+}
+
 #[tokio::test]
 async fn rocksdb_storage_syncing_fault_tolerance() {
     let pool = ConnectionPool::test_pool().await;
@@ -332,7 +371,9 @@ async fn low_level_snapshot_recovery(log_chunk_size: u64) {
         prepare_postgres_for_snapshot_recovery(&mut conn).await;
 
     let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
-    let mut storage = RocksdbStorage::new(dir.path().to_path_buf()).await.unwrap();
+    let mut storage = RocksdbStorage::new(dir.path().to_path_buf(), RocksDBOptions::default())
+        .await
+        .unwrap();
     let (_stop_sender, stop_receiver) = watch::channel(false);
     let next_l1_batch = storage
         .ensure_ready(&mut conn, log_chunk_size, &stop_receiver)
@@ -458,7 +499,9 @@ async fn recovery_fault_tolerance() {
     let log_chunk_size = storage_logs.len() as u64 / 5;
 
     let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
-    let mut storage = RocksdbStorage::new(dir.path().to_path_buf()).await.unwrap();
+    let mut storage = RocksdbStorage::new(dir.path().to_path_buf(), RocksDBOptions::default())
+        .await
+        .unwrap();
     let (stop_sender, stop_receiver) = watch::channel(false);
     let mut synced_chunk_count = 0_u64;
     storage.listener.on_logs_chunk_recovered = Box::new(move |chunk_id| {
@@ -478,7 +521,9 @@ async fn recovery_fault_tolerance() {
 
     // Resume recovery and check that no chunks are recovered twice.
     let (_stop_sender, stop_receiver) = watch::channel(false);
-    let mut storage = RocksdbStorage::new(dir.path().to_path_buf()).await.unwrap();
+    let mut storage = RocksdbStorage::new(dir.path().to_path_buf(), RocksDBOptions::default())
+        .await
+        .unwrap();
     storage.listener.on_logs_chunk_recovered = Box::new(|chunk_id| {
         assert!(chunk_id >= 2);
     });
@@ -11,11 +11,11 @@ use std::{
 };
 
 use rocksdb::{
-    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice,
-    Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
+    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBCompactionStyle,
+    DBPinnableSlice, Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
 };
 
-use crate::metrics::{RocksdbLabels, RocksdbSizeMetrics, METRICS};
+use crate::metrics::{self, RocksdbLabels, RocksdbSizeMetrics, METRICS};
 
 /// Number of active RocksDB instances used to determine if it's safe to exit current process.
 /// Not properly dropped RocksDB instances can lead to DB corruption.
@@ -159,6 +159,44 @@ impl RocksDBInner {
         }
     }
 
+    fn column_family_sizes(&self) -> Vec<ColumnFamilySizes> {
+        let mut sizes: Vec<_> = self
+            .cf_names
+            .iter()
+            .map(|&cf_name| {
+                let cf = self.db.cf_handle(cf_name).unwrap();
+                // ^ `unwrap()` is safe (CF existence is checked during DB initialization)
+                ColumnFamilySizes {
+                    db_name: self.db_name,
+                    cf_name,
+                    live_data_size: self
+                        .int_property(cf, properties::ESTIMATE_LIVE_DATA_SIZE)
+                        .unwrap_or(0),
+                    total_sst_size: self
+                        .int_property(cf, properties::TOTAL_SST_FILES_SIZE)
+                        .unwrap_or(0),
+                    mem_table_size: self
+                        .int_property(cf, properties::SIZE_ALL_MEM_TABLES)
+                        .unwrap_or(0),
+                }
+            })
+            .collect();
+        sizes.sort_unstable_by_key(|sizes| sizes.cf_name);
+        sizes
+    }
+
+    fn compact_all_cfs(&self) {
+        for &cf_name in &self.cf_names {
+            let cf = self.db.cf_handle(cf_name).unwrap();
+            // ^ `unwrap()` is safe (CF existence is checked during DB initialization)
+            tracing::info!(
+                "Compacting column family `{cf_name}` in DB `{}`",
+                self.db_name
+            );
+            self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        }
+    }
+
     fn int_property(&self, cf: &ColumnFamily, name: &CStr) -> Option<u64> {
         let property = self.db.property_int_value_cf(cf, name);
         let property = property.unwrap_or_else(|err| {
@@ -260,6 +298,27 @@ impl StalledWritesRetries {
     }
 }
 
+/// Compaction style used for column families in a [`RocksDB`] instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RocksDBCompactionStyle {
+    /// Leveled compaction (the RocksDB default). Has the lowest space amplification due to obsolete data,
+    /// at the cost of higher write amplification.
+    #[default]
+    Level,
+    /// Universal compaction. Has lower write amplification than leveled compaction, but can temporarily
+    /// require up to 2x disk space during full compactions.
+    Universal,
+}
+
+impl From<RocksDBCompactionStyle> for DBCompactionStyle {
+    fn from(style: RocksDBCompactionStyle) -> Self {
+        match style {
+            RocksDBCompactionStyle::Level => Self::Level,
+            RocksDBCompactionStyle::Universal => Self::Universal,
+        }
+    }
+}
+
 /// [`RocksDB`] options.
 #[derive(Debug, Clone, Copy)]
 pub struct RocksDBOptions {
@@ -274,6 +333,14 @@ pub struct RocksDBOptions {
     /// Timeout to wait for the database to run compaction on stalled writes during startup or
     /// when the corresponding RocksDB error is encountered.
     pub stalled_writes_retries: StalledWritesRetries,
+    /// Compaction style used for all column families.
+    pub compaction_style: RocksDBCompactionStyle,
+    /// Maximum total byte size of write-ahead log (WAL) files. Once exceeded, RocksDB flushes memtables
+    /// backed by the oldest WAL files. If not set, the limit is chosen by RocksDB based on memtable sizes.
+    pub max_total_wal_size: Option<u64>,
+    /// If set, WAL files are incrementally synced to disk every time this number of bytes is written.
+    /// This smooths out disk I/O, but can reduce write throughput.
+    pub wal_bytes_per_sync: Option<u64>,
 }
 
 impl Default for RocksDBOptions {
@@ -282,6 +349,9 @@ impl Default for RocksDBOptions {
             block_cache_capacity: None,
             large_memtable_capacity: None,
             stalled_writes_retries: StalledWritesRetries::new(Duration::from_secs(10)),
+            compaction_style: RocksDBCompactionStyle::default(),
+            max_total_wal_size: None,
+            wal_bytes_per_sync: None,
         }
     }
 }
@@ -304,7 +374,7 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
 
     pub fn with_options(path: &Path, options: RocksDBOptions) -> Result<Self, rocksdb::Error> {
         let caches = RocksDBCaches::new(options.block_cache_capacity);
-        let db_options = Self::rocksdb_options(None, None);
+        let db_options = Self::rocksdb_options(None, None, options.compaction_style);
         let existing_cfs = DB::list_cf(&db_options, path).unwrap_or_else(|err| {
             tracing::warn!(
                 "Failed getting column families for RocksDB `{}` at `{}`, assuming CFs are empty; {err}",
@@ -350,10 +420,21 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
                 block_based_options.set_block_cache(cache);
             }
             let memtable_capacity = options.large_memtable_capacity.filter(|_| requires_tuning);
-            let cf_options = Self::rocksdb_options(memtable_capacity, Some(block_based_options));
+            let cf_options = Self::rocksdb_options(
+                memtable_capacity,
+                Some(block_based_options),
+                options.compaction_style,
+            );
             ColumnFamilyDescriptor::new(cf_name, cf_options)
         });
 
+        let mut db_options = db_options;
+        if let Some(max_total_wal_size) = options.max_total_wal_size {
+            db_options.set_max_total_wal_size(max_total_wal_size);
+        }
+        if let Some(wal_bytes_per_sync) = options.wal_bytes_per_sync {
+            db_options.set_wal_bytes_per_sync(wal_bytes_per_sync);
+        }
         let db = DB::open_cf_descriptors(&db_options, path, cfs)?;
         let inner = Arc::new(RocksDBInner {
             db,
@@ -390,6 +471,7 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
     fn rocksdb_options(
         memtable_capacity: Option<usize>,
         block_based_options: Option<BlockBasedOptions>,
+        compaction_style: RocksDBCompactionStyle,
     ) -> Options {
         let mut options = Options::default();
         options.create_missing_column_families(true);
@@ -397,8 +479,14 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
 
         let num_cpus = num_cpus::get() as i32;
         options.increase_parallelism(num_cpus);
-        if let Some(memtable_capacity) = memtable_capacity {
-            options.optimize_level_style_compaction(memtable_capacity);
+        match (compaction_style, memtable_capacity) {
+            (RocksDBCompactionStyle::Level, Some(memtable_capacity)) => {
+                options.optimize_level_style_compaction(memtable_capacity);
+            }
+            (RocksDBCompactionStyle::Universal, Some(memtable_capacity)) => {
+                options.optimize_universal_style_compaction(memtable_capacity);
+            }
+            (style, None) => options.set_compaction_style(style.into()),
         }
         // Settings below are taken as per PingCAP recommendations:
         // https://www.pingcap.com/blog/how-to-troubleshoot-rocksdb-write-stalls-in-tikv/
@@ -422,6 +510,16 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             .unwrap_or(0)
     }
 
+    /// Manually compacts the entire key range of the specified column family. This is a blocking operation
+    /// which can take a long time for large column families. It is useful to reclaim disk space
+    /// after removing large amounts of data (e.g., after pruning).
+    pub fn compact_cf(&self, cf: CF) {
+        let cf = self.column_family(cf);
+        self.inner
+            .db
+            .compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+    }
+
     pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
     where
         K: AsRef<[u8]>,
@@ -568,6 +666,51 @@ impl RocksDB<()> {
         }
         tracing::info!("All the RocksDB instances are dropped");
     }
+
+    /// Returns sizes of all column families in all alive RocksDB instances, ordered by the DB name
+    /// and then by the column family name.
+    pub fn column_family_sizes() -> Vec<ColumnFamilySizes> {
+        let mut instances = metrics::alive_instances();
+        instances.sort_unstable_by_key(|instance| instance.db_name);
+        instances
+            .iter()
+            .flat_map(|instance| instance.column_family_sizes())
+            .collect()
+    }
+
+    /// Manually compacts all column families in the alive RocksDB instance with the specified name.
+    /// Returns `false` if there is no such instance.
+    ///
+    /// This method is blocking and can take a long time for large DBs; it should be wrapped
+    /// in `spawn_blocking(_)` if run in the async context.
+    pub fn compact_instance(db_name: &str) -> bool {
+        let instance = metrics::alive_instances()
+            .into_iter()
+            .find(|instance| instance.db_name == db_name);
+        let Some(instance) = instance else {
+            return false;
+        };
+        let started_at = Instant::now();
+        instance.compact_all_cfs();
+        tracing::info!("Compacted DB `{db_name}` in {:?}", started_at.elapsed());
+        true
+    }
+}
+
+/// Sizes of a column family in a [`RocksDB`] instance as estimated by RocksDB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnFamilySizes {
+    /// Name of the database (e.g., `merkle_tree`).
+    pub db_name: &'static str,
+    /// Name of the column family.
+    pub cf_name: &'static str,
+    /// Estimated byte size of live data.
+    pub live_data_size: u64,
+    /// Total byte size of all SST files. The difference with `live_data_size` is mostly obsolete data
+    /// that can be reclaimed by compaction.
+    pub total_sst_size: u64,
+    /// Total byte size of active and immutable memtables.
+    pub mem_table_size: u64,
 }
 
 /// Empty struct used to register RocksDB instance
@@ -732,4 +875,59 @@ mod tests {
             .unwrap();
         assert_eq!(value, b"value2");
     }
+
+    #[derive(Debug, Clone, Copy)]
+    enum CompactedColumnFamilies {
+        Default,
+        Other,
+    }
+
+    impl NamedColumnFamily for CompactedColumnFamilies {
+        const DB_NAME: &'static str = "compacted_test";
+        const ALL: &'static [Self] = &[Self::Default, Self::Other];
+
+        fn name(&self) -> &'static str {
+            match self {
+                Self::Default => "default",
+                Self::Other => "other",
+            }
+        }
+    }
+
+    #[test]
+    fn compacting_alive_instance() {
+        let temp_dir = TempDir::new().unwrap();
+        let options = RocksDBOptions {
+            compaction_style: RocksDBCompactionStyle::Universal,
+            max_total_wal_size: Some(64 << 20),
+            wal_bytes_per_sync: Some(1 << 20),
+            ..RocksDBOptions::default()
+        };
+        let db = RocksDB::<CompactedColumnFamilies>::with_options(temp_dir.path(), options)
+            .unwrap()
+            .with_sync_writes();
+        let mut batch = db.new_write_batch();
+        for i in 0_u32..1_000 {
+            batch.put_cf(CompactedColumnFamilies::Other, &i.to_be_bytes(), &[1; 64]);
+        }
+        db.write(batch).unwrap();
+        let mut batch = db.new_write_batch();
+        let range = 0_u32.to_be_bytes();
+        let range_end = 1_000_u32.to_be_bytes();
+        batch.delete_range_cf(CompactedColumnFamilies::Other, &range[..]..&range_end[..]);
+        db.write(batch).unwrap();
+
+        assert!(RocksDB::compact_instance(CompactedColumnFamilies::DB_NAME));
+        assert!(!RocksDB::compact_instance("non_existing"));
+
+        let sizes: Vec<_> = RocksDB::column_family_sizes()
+            .into_iter()
+            .filter(|sizes| sizes.db_name == CompactedColumnFamilies::DB_NAME)
+            .collect();
+        let cf_names: Vec<_> = sizes.iter().map(|sizes| sizes.cf_name).collect();
+        assert_eq!(cf_names, ["default", "other"]);
+
+        drop(db);
+        assert!(!RocksDB::compact_instance(CompactedColumnFamilies::DB_NAME));
+    }
 }
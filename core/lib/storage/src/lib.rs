@@ -1,5 +1,7 @@
 pub mod db;
 mod metrics;
 
-pub use db::{RocksDB, RocksDBOptions, StalledWritesRetries};
+pub use db::{
+    ColumnFamilySizes, RocksDB, RocksDBCompactionStyle, RocksDBOptions, StalledWritesRetries,
+};
 pub use rocksdb;
@@ -2,7 +2,7 @@
 
 use std::{
     collections::HashMap,
-    sync::{Mutex, Weak},
+    sync::{Arc, Mutex, Weak},
     time::Duration,
 };
 
@@ -138,3 +138,13 @@ impl RocksdbSizeMetrics {
         metrics
     }
 }
+
+/// Returns all alive DB instances registered using [`RocksdbSizeMetrics::register()`].
+pub(crate) fn alive_instances() -> Vec<Arc<RocksDBInner>> {
+    INSTANCES
+        .lock()
+        .expect("instances are poisoned")
+        .values()
+        .filter_map(Weak::upgrade)
+        .collect()
+}
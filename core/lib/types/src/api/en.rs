@@ -1,10 +1,15 @@
 //! API types related to the External Node specific methods.
 
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{Address, L1BatchNumber, MiniblockNumber, H256};
+use zksync_basic_types::{
+    web3::types::Bytes, Address, L1BatchNumber, L2ChainId, MiniblockNumber, H256,
+};
 use zksync_contracts::BaseSystemContractsHashes;
 
-use crate::ProtocolVersionId;
+use crate::{
+    block::L1BatchHeader, l2_to_l1_log::UserL2ToL1Log, tx::ExecutionMetrics,
+    web3::signing::keccak256, PackedEthSignature, ProtocolVersionId, StorageLog, VmEvent,
+};
 
 /// Representation of the L2 block, as needed for the EN synchronization.
 /// This structure has several fields that describe *L1 batch* rather than
@@ -43,4 +48,183 @@ pub struct SyncBlock {
     pub hash: Option<H256>,
     /// Version of the protocol used for this block.
     pub protocol_version: ProtocolVersionId,
+    /// Signature of [`Self::signed_digest()`] by the sequencer key. Only provided by main nodes configured
+    /// with a sequencer signing key, and only if transactions were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequencer_signature: Option<PackedEthSignature>,
+    /// Hash of the L2 block contents (transaction execution results and emitted events) computed by the main node
+    /// when sealing the block. May be `None` for blocks sealed before content hashes were introduced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<H256>,
+    /// Signature of [`Self::header_signed_digest()`] by the sequencer key. Only provided by main nodes configured
+    /// with a sequencer signing key; unlike [`Self::sequencer_signature`], provided regardless of whether
+    /// transactions were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequencer_header_signature: Option<PackedEthSignature>,
+}
+
+impl SyncBlock {
+    /// Domain separator for digests signed by the sequencer.
+    const SIGNED_DIGEST_DOMAIN: &'static [u8] = b"idexo_l2_block_v2";
+    /// Domain separator for header digests signed by the sequencer.
+    const SIGNED_HEADER_DIGEST_DOMAIN: &'static [u8] = b"idexo_l2_block_header_v1";
+
+    /// Encodes block fields used by external nodes to replay the block (i.e., everything except for transactions,
+    /// the content hash and signatures) prefixed with the domain separator and the chain ID.
+    fn encode_header(&self, domain: &[u8], chain_id: L2ChainId, hash: H256) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(256);
+        bytes.extend_from_slice(domain);
+        bytes.extend_from_slice(&chain_id.as_u64().to_be_bytes());
+        bytes.extend_from_slice(&self.number.0.to_be_bytes());
+        bytes.extend_from_slice(&self.l1_batch_number.0.to_be_bytes());
+        bytes.push(self.last_in_batch.into());
+        bytes.extend_from_slice(&self.timestamp.to_be_bytes());
+        bytes.extend_from_slice(&self.l1_gas_price.to_be_bytes());
+        bytes.extend_from_slice(&self.l2_fair_gas_price.to_be_bytes());
+        // Optional fields are prefixed with a presence flag to keep the encoding unambiguous.
+        bytes.push(self.fair_pubdata_price.is_some().into());
+        bytes.extend_from_slice(&self.fair_pubdata_price.unwrap_or(0).to_be_bytes());
+        bytes.extend_from_slice(self.base_system_contracts_hashes.bootloader.as_bytes());
+        bytes.extend_from_slice(self.base_system_contracts_hashes.default_aa.as_bytes());
+        bytes.extend_from_slice(self.operator_address.as_bytes());
+        bytes.push(self.virtual_blocks.is_some().into());
+        bytes.extend_from_slice(&self.virtual_blocks.unwrap_or(0).to_be_bytes());
+        bytes.extend_from_slice(hash.as_bytes());
+        bytes.extend_from_slice(&(self.protocol_version as u16).to_be_bytes());
+        bytes
+    }
+
+    /// Returns the digest signed by the sequencer, or `None` if the hash or transactions are missing.
+    /// The digest commits to the chain ID, all block fields used by external nodes to replay the block,
+    /// and the block transactions in the execution order.
+    ///
+    /// Transactions are committed to by their hashes *and* by hashes of their serialized contents. Transaction
+    /// hashes are not recomputed from the contents by external nodes, so committing only to them would allow
+    /// a relay to substitute transaction bodies without invalidating the signature.
+    pub fn signed_digest(&self, chain_id: L2ChainId) -> Option<H256> {
+        let hash = self.hash?;
+        let transactions = self.transactions.as_ref()?;
+
+        let mut bytes = self.encode_header(Self::SIGNED_DIGEST_DOMAIN, chain_id, hash);
+        bytes.reserve(transactions.len() * 64);
+        for tx in transactions {
+            bytes.extend_from_slice(tx.hash().as_bytes());
+            let serialized_tx =
+                serde_json::to_vec(tx).expect("failed serializing transaction to JSON");
+            bytes.extend_from_slice(&keccak256(&serialized_tx));
+        }
+        Some(H256(keccak256(&bytes)))
+    }
+
+    /// Returns the header digest signed by the sequencer, or `None` if the hash is missing. Unlike
+    /// [`Self::signed_digest()`], the digest doesn't commit to transactions, so it can be verified for blocks
+    /// fetched without transactions. It commits to the content hash instead.
+    pub fn header_signed_digest(&self, chain_id: L2ChainId) -> Option<H256> {
+        let hash = self.hash?;
+        let mut bytes = self.encode_header(Self::SIGNED_HEADER_DIGEST_DOMAIN, chain_id, hash);
+        bytes.push(self.content_hash.is_some().into());
+        bytes.extend_from_slice(self.content_hash.unwrap_or_default().as_bytes());
+        Some(H256(keccak256(&bytes)))
+    }
+}
+
+/// Execution outputs of an L2 block. Served to external nodes running in the read replica mode, which persist
+/// L2 blocks without re-executing their transactions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncBlockOutputs {
+    /// Number of the L2 block.
+    pub number: MiniblockNumber,
+    /// Execution results of the block transactions in the execution order.
+    pub transactions: Vec<SyncTransactionOutput>,
+    /// Events emitted in the block in the order of their emission. Unlike for events produced by the VM,
+    /// the second element of the event location is the index of the emitting transaction in the L2 block
+    /// (rather than in the L1 batch).
+    pub events: Vec<VmEvent>,
+    /// User L2-to-L1 logs emitted in the block together with the index of the emitting transaction in the L2 block.
+    pub l2_to_l1_logs: Vec<(u32, UserL2ToL1Log)>,
+    /// Deduplicated storage writes grouped by the hash of the transaction producing them. Writes produced
+    /// by the bootloader after the last transaction in the L1 batch have zero transaction hash.
+    pub storage_logs: Vec<(H256, Vec<StorageLog>)>,
+    /// Bytecodes published in the block together with their hashes.
+    pub factory_deps: Vec<(H256, Bytes)>,
+    /// Header of the L1 batch. Only provided for the last L2 block in the batch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_batch_header: Option<L1BatchHeader>,
+    /// Initial writes in the L1 batch as `(hashed_key, enumeration_index)` tuples. Only provided
+    /// for the last L2 block in the batch.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub initial_writes: Vec<(H256, u64)>,
+    /// Signature of [`Self::signed_digest()`] by the sequencer key. Only provided by main nodes configured
+    /// with a sequencer signing key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequencer_signature: Option<PackedEthSignature>,
+}
+
+impl SyncBlockOutputs {
+    /// Domain separator for digests signed by the sequencer.
+    const SIGNED_DIGEST_DOMAIN: &'static [u8] = b"idexo_l2_block_outputs_v1";
+
+    /// Returns the digest signed by the sequencer. The digest commits to the chain ID, the block number
+    /// and all outputs (i.e., all fields except for the signature itself).
+    pub fn signed_digest(&self, chain_id: L2ChainId) -> H256 {
+        fn field_hash(field: &impl Serialize) -> [u8; 32] {
+            let serialized = serde_json::to_vec(field).expect("failed serializing block outputs");
+            keccak256(&serialized)
+        }
+
+        let mut bytes = Vec::with_capacity(256);
+        bytes.extend_from_slice(Self::SIGNED_DIGEST_DOMAIN);
+        bytes.extend_from_slice(&chain_id.as_u64().to_be_bytes());
+        bytes.extend_from_slice(&self.number.0.to_be_bytes());
+        bytes.extend_from_slice(&field_hash(&self.transactions));
+        bytes.extend_from_slice(&field_hash(&self.events));
+        bytes.extend_from_slice(&field_hash(&self.l2_to_l1_logs));
+        bytes.extend_from_slice(&field_hash(&self.storage_logs));
+        bytes.extend_from_slice(&field_hash(&self.factory_deps));
+        bytes.extend_from_slice(&field_hash(&self.l1_batch_header));
+        bytes.extend_from_slice(&field_hash(&self.initial_writes));
+        H256(keccak256(&bytes))
+    }
+}
+
+/// Execution result of a transaction included into [`SyncBlockOutputs`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncTransactionOutput {
+    pub hash: H256,
+    pub is_successful: bool,
+    pub refunded_gas: u32,
+    pub execution_info: ExecutionMetrics,
+}
+
+/// Root hash of an L1 batch computed by the main node Merkle tree, as needed by external nodes that don't wait
+/// for their own tree to process the L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncL1BatchRootHash {
+    /// Number of the L1 batch.
+    pub number: L1BatchNumber,
+    /// Root hash of the L1 batch.
+    pub root_hash: H256,
+    /// Signature of [`Self::signed_digest()`] by the sequencer key. Only provided by main nodes configured
+    /// with a sequencer signing key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequencer_signature: Option<PackedEthSignature>,
+}
+
+impl SyncL1BatchRootHash {
+    /// Domain separator for digests signed by the sequencer.
+    const SIGNED_DIGEST_DOMAIN: &'static [u8] = b"idexo_l1_batch_root_hash_v1";
+
+    /// Returns the digest signed by the sequencer. The digest commits to the chain ID, the L1 batch number
+    /// and the root hash.
+    pub fn signed_digest(&self, chain_id: L2ChainId) -> H256 {
+        let mut bytes = Vec::with_capacity(96);
+        bytes.extend_from_slice(Self::SIGNED_DIGEST_DOMAIN);
+        bytes.extend_from_slice(&chain_id.as_u64().to_be_bytes());
+        bytes.extend_from_slice(&self.number.0.to_be_bytes());
+        bytes.extend_from_slice(self.root_hash.as_bytes());
+        H256(keccak256(&bytes))
+    }
 }
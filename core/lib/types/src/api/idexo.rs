@@ -0,0 +1,988 @@
+//! API types related to the idexo-specific methods.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{
+    web3::types::Bytes, Address, L1BatchNumber, MiniblockNumber, Nonce, PriorityOpId, H256, U256,
+};
+
+use super::{Log, TransactionDetails, TransactionStatus};
+use crate::{tokens::TokenMetadata, transaction_request::CallRequest};
+
+/// L1 settlement cost of a single aggregated operation (commit, prove or execute) attributed to an L1 batch.
+/// If an L1 transaction covers several batches, its cost is split evenly among them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSettlementCost {
+    /// ID of the L1 transaction performing the operation.
+    pub eth_tx_id: u32,
+    pub gas_used: u64,
+    /// Cost of the consumed gas in wei.
+    pub gas_cost: U256,
+    pub blob_gas_used: u64,
+    /// Cost of the consumed blob gas in wei.
+    pub blob_cost: U256,
+}
+
+impl BatchSettlementCost {
+    /// Returns the total cost of the operation in wei.
+    pub fn total_cost(&self) -> U256 {
+        self.gas_cost + self.blob_cost
+    }
+}
+
+/// Settlement costs of an L1 batch compared to the fees collected from its L2 transactions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEconomics {
+    pub l1_batch_number: L1BatchNumber,
+    /// Cost of committing the batch; `None` if the commit transaction is not confirmed yet.
+    pub commit: Option<BatchSettlementCost>,
+    /// Cost of proving the batch; `None` if the proof transaction is not confirmed yet.
+    pub prove: Option<BatchSettlementCost>,
+    /// Cost of executing the batch; `None` if the execute transaction is not confirmed yet.
+    pub execute: Option<BatchSettlementCost>,
+    /// Sum of the confirmed settlement costs in wei.
+    pub total_settlement_cost: U256,
+    /// Fees paid by the L2 transactions in the batch (after refunds) in wei.
+    pub l2_fees_collected: U256,
+    /// Whether all settlement operations are confirmed, i.e., the settlement cost is final.
+    pub is_fully_settled: bool,
+    /// Whether the collected fees cover the settlement cost. Only final if `is_fully_settled` is set.
+    pub is_profitable: bool,
+}
+
+impl BatchEconomics {
+    pub fn new(
+        l1_batch_number: L1BatchNumber,
+        commit: Option<BatchSettlementCost>,
+        prove: Option<BatchSettlementCost>,
+        execute: Option<BatchSettlementCost>,
+        l2_fees_collected: U256,
+    ) -> Self {
+        let total_settlement_cost = [&commit, &prove, &execute]
+            .into_iter()
+            .flatten()
+            .fold(U256::zero(), |acc, cost| acc + cost.total_cost());
+        let is_fully_settled = commit.is_some() && prove.is_some() && execute.is_some();
+        Self {
+            l1_batch_number,
+            commit,
+            prove,
+            execute,
+            total_settlement_cost,
+            l2_fees_collected,
+            is_fully_settled,
+            is_profitable: l2_fees_collected >= total_settlement_cost,
+        }
+    }
+}
+
+/// Proof of the L1 batch pubdata inclusion into the data availability layer used by the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaInclusionProof {
+    pub l1_batch_number: L1BatchNumber,
+    /// Identifier of the pubdata blob in the DA layer.
+    pub blob_id: String,
+    /// Namespace of the blob; only set for DA layers organizing blobs by namespaces, such as Celestia.
+    pub namespace: Option<Bytes>,
+    /// Height of the DA layer block including the blob; only set together with `namespace`.
+    pub height: Option<u64>,
+    /// Commitment to the blob data; only set together with `namespace`.
+    pub commitment: Option<Bytes>,
+    /// Inclusion data passed to the settlement layer in the commit transaction; `None` if the blob
+    /// is not included into the DA layer yet.
+    pub inclusion_data: Option<Bytes>,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Token metadata curated by the operator for wallets on the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredToken {
+    pub l1_address: Address,
+    pub l2_address: Address,
+    #[serde(flatten)]
+    pub metadata: TokenMetadata,
+    /// URL of the token logo.
+    pub logo_url: Option<String>,
+}
+
+/// Deposit to the chain observed on the settlement layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeDeposit {
+    /// Hash of the settlement layer transaction requesting the deposit.
+    pub settlement_tx_hash: H256,
+    pub settlement_block_number: u64,
+    pub priority_op_id: PriorityOpId,
+    /// Canonical hash of the priority transaction on the chain.
+    pub tx_hash: H256,
+}
+
+/// Processing stage of a deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DepositStage {
+    /// The deposit is observed on the settlement layer, but is not picked up by the chain yet. This is normal
+    /// until the settlement layer block with the deposit is finalized.
+    Observed,
+    /// The priority transaction is in the mempool.
+    Queued,
+    /// The priority transaction is included into a miniblock.
+    Included,
+    /// The L1 batch with the priority transaction is executed on the settlement layer.
+    Finalized,
+    /// The priority transaction has failed.
+    Failed,
+}
+
+impl DepositStage {
+    /// Determines the stage based on the details of the priority transaction, if it is known to the chain.
+    pub fn new(details: Option<&TransactionDetails>) -> Self {
+        match details.map(|details| &details.status) {
+            None => Self::Observed,
+            Some(TransactionStatus::Pending) => Self::Queued,
+            Some(TransactionStatus::Included) => Self::Included,
+            Some(TransactionStatus::Verified) => Self::Finalized,
+            Some(TransactionStatus::Failed) => Self::Failed,
+        }
+    }
+}
+
+/// Status of a deposit to the chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositStatus {
+    #[serde(flatten)]
+    pub deposit: BridgeDeposit,
+    pub stage: DepositStage,
+    /// Details of the priority transaction; `None` if it is not picked up by the chain yet.
+    pub details: Option<TransactionDetails>,
+}
+
+/// Direction of a message relayed between the chain and the settlement layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageDirection {
+    /// Message sent from the chain to the settlement layer.
+    Outbound,
+    /// Message sent from the settlement layer to the chain.
+    Inbound,
+}
+
+/// Delivery status of a relayed message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageDeliveryStatus {
+    /// The message waits to be delivered.
+    Pending,
+    /// The delivery transaction is sent, but is not mined yet.
+    Sent,
+    /// The message is delivered, either by the relay or by a third party.
+    Delivered,
+    /// The message could not be delivered after the maximum number of attempts.
+    Failed,
+}
+
+/// Receipt of the transaction that has delivered a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageDeliveryReceipt {
+    /// Hash of the delivery transaction. Outbound messages are delivered by a settlement layer transaction,
+    /// and inbound ones by a settlement layer transaction enqueueing a priority operation.
+    pub tx_hash: H256,
+    pub block_number: u64,
+}
+
+/// Status of a message relayed between the chain and the settlement layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayedMessageStatus {
+    /// Unique message ID used for deduplication.
+    pub id: H256,
+    pub direction: MessageDirection,
+    /// Hash of the transaction that has sent the message: a chain transaction for outbound messages,
+    /// or a settlement layer transaction for inbound ones.
+    pub source_tx_hash: H256,
+    pub sender: Address,
+    /// Target contract on the chain; only set for inbound messages.
+    pub target: Option<Address>,
+    pub data: Bytes,
+    pub status: MessageDeliveryStatus,
+    /// Number of delivery attempts made by the relay.
+    pub attempts: u32,
+    /// Receipt of the delivery transaction; `None` if the message is not delivered yet or is delivered
+    /// by a third party.
+    pub receipt: Option<MessageDeliveryReceipt>,
+}
+
+/// Priority operation (e.g., a deposit) picked up by the chain from the settlement layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpInfo {
+    pub priority_op_id: PriorityOpId,
+    /// Canonical hash of the priority transaction on the chain.
+    pub tx_hash: H256,
+    /// Hash of the settlement layer transaction requesting the operation; only known if the operation
+    /// was observed by the bridge watcher.
+    pub settlement_tx_hash: Option<H256>,
+    pub settlement_block_number: u64,
+    /// Deadline for executing the operation set by the settlement layer contract, i.e. the timestamp
+    /// of the settlement layer block requesting the operation plus the priority expiration period.
+    /// `None` for operations received by older node versions, which didn't persist the deadline.
+    pub deadline: Option<DateTime<Utc>>,
+    /// Time when the operation was picked up by the chain.
+    pub received_at: DateTime<Utc>,
+    /// Miniblock including the priority transaction; `None` if the transaction is in the mempool.
+    pub miniblock_number: Option<MiniblockNumber>,
+}
+
+/// Priority operation that is not included into a sealed L1 batch yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPriorityOp {
+    #[serde(flatten)]
+    pub op: PriorityOpInfo,
+    /// Estimated L1 batch that will include the operation.
+    pub estimated_l1_batch: L1BatchNumber,
+}
+
+/// Status of the priority queue of the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityQueueStatus {
+    /// Number of the L1 batch currently being built.
+    pub pending_l1_batch: L1BatchNumber,
+    /// Total number of pending priority operations.
+    pub pending_count: u64,
+    /// Estimated number of priority operations included into a single L1 batch, based on recent L1 batches.
+    pub ops_per_l1_batch: u64,
+    /// Oldest pending priority operations ordered by ID.
+    pub operations: Vec<PendingPriorityOp>,
+}
+
+/// Outcome of the verification of an L1 batch proof submitted to the settlement layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProofVerificationStatus {
+    /// The proof is valid for the locally computed L1 batch commitment.
+    Verified,
+    /// The proof is empty; such proofs are only accepted by the testnet verifier.
+    MockProof,
+    /// The proof is invalid, or the proven L1 batch doesn't match the local one.
+    Invalid,
+}
+
+/// Status of an L1 batch proof verified by an external node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchProofStatus {
+    pub l1_batch_number: L1BatchNumber,
+    /// Settlement layer transaction submitting the proof.
+    pub prove_tx_hash: H256,
+    pub status: ProofVerificationStatus,
+    /// Reason for the [`ProofVerificationStatus::Invalid`] status.
+    pub details: Option<String>,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Sensitive action affecting the node operation that is recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Node storage was rolled back to a previous L1 batch.
+    BlockRevert,
+    /// A transaction reverting committed L1 batches was sent to the settlement layer.
+    SettlementRevert,
+    /// Failed settlement layer transactions were removed from the storage.
+    FailedL1TxsCleared,
+    /// Settlement halted because of a settlement layer anomaly was resumed.
+    SettlementResume,
+    /// A token was added to the token registry, or its metadata was updated.
+    TokenRegistration,
+    /// A token was removed from the token registry.
+    TokenUnregistration,
+    /// A protocol upgrade was persisted, so that it is applied by the node once its timestamp is reached.
+    ProtocolUpgrade,
+    /// The log filter of the node was changed at runtime.
+    LogFilterChange,
+    /// L2 transaction intake by the API servers was paused.
+    TxIntakePause,
+    /// Paused L2 transaction intake was resumed.
+    TxIntakeResume,
+    /// A pending L2 transaction was dropped from the mempool.
+    TransactionDrop,
+    /// Sealing of the current L1 batch was requested.
+    L1BatchSealRequest,
+    /// Manual compaction of a RocksDB instance was performed.
+    RocksdbCompaction,
+    /// A fee discount was granted to an account, or its parameters were updated.
+    FeeDiscountUpdate,
+    /// A fee discount of an account was removed.
+    FeeDiscountRemoval,
+    /// A contract ABI used for decoding event logs was registered or updated.
+    AbiRegistration,
+    /// A registered contract ABI was removed.
+    AbiUnregistration,
+    /// A withdrawal limit was set for a token, or its parameters were updated.
+    WithdrawalLimitUpdate,
+    /// A withdrawal limit of a token was removed.
+    WithdrawalLimitRemoval,
+    /// An account was exempted from withdrawal limits.
+    WithdrawalLimitExemption,
+    /// An exemption from withdrawal limits was revoked.
+    WithdrawalLimitExemptionRemoval,
+    /// Creation of a snapshot was requested.
+    SnapshotRequest,
+    /// Config parameters were changed at runtime by the config reloader.
+    ConfigReload,
+    /// The auth token for the operator API namespaces was set, changed or removed.
+    OperatorAuthTokenChange,
+    /// A recurring L2 transaction was registered, or its parameters were updated.
+    ScheduledTxRegistration,
+    /// A recurring L2 transaction was disabled.
+    ScheduledTxDisabling,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BlockRevert => "block_revert",
+            Self::SettlementRevert => "settlement_revert",
+            Self::FailedL1TxsCleared => "failed_l1_txs_cleared",
+            Self::SettlementResume => "settlement_resume",
+            Self::TokenRegistration => "token_registration",
+            Self::TokenUnregistration => "token_unregistration",
+            Self::ProtocolUpgrade => "protocol_upgrade",
+            Self::LogFilterChange => "log_filter_change",
+            Self::TxIntakePause => "tx_intake_pause",
+            Self::TxIntakeResume => "tx_intake_resume",
+            Self::TransactionDrop => "transaction_drop",
+            Self::L1BatchSealRequest => "l1_batch_seal_request",
+            Self::RocksdbCompaction => "rocksdb_compaction",
+            Self::FeeDiscountUpdate => "fee_discount_update",
+            Self::FeeDiscountRemoval => "fee_discount_removal",
+            Self::AbiRegistration => "abi_registration",
+            Self::AbiUnregistration => "abi_unregistration",
+            Self::WithdrawalLimitUpdate => "withdrawal_limit_update",
+            Self::WithdrawalLimitRemoval => "withdrawal_limit_removal",
+            Self::WithdrawalLimitExemption => "withdrawal_limit_exemption",
+            Self::WithdrawalLimitExemptionRemoval => "withdrawal_limit_exemption_removal",
+            Self::SnapshotRequest => "snapshot_request",
+            Self::ConfigReload => "config_reload",
+            Self::OperatorAuthTokenChange => "operator_auth_token_change",
+            Self::ScheduledTxRegistration => "scheduled_tx_registration",
+            Self::ScheduledTxDisabling => "scheduled_tx_disabling",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block_revert" => Ok(Self::BlockRevert),
+            "settlement_revert" => Ok(Self::SettlementRevert),
+            "failed_l1_txs_cleared" => Ok(Self::FailedL1TxsCleared),
+            "settlement_resume" => Ok(Self::SettlementResume),
+            "token_registration" => Ok(Self::TokenRegistration),
+            "token_unregistration" => Ok(Self::TokenUnregistration),
+            "protocol_upgrade" => Ok(Self::ProtocolUpgrade),
+            "log_filter_change" => Ok(Self::LogFilterChange),
+            "tx_intake_pause" => Ok(Self::TxIntakePause),
+            "tx_intake_resume" => Ok(Self::TxIntakeResume),
+            "transaction_drop" => Ok(Self::TransactionDrop),
+            "l1_batch_seal_request" => Ok(Self::L1BatchSealRequest),
+            "rocksdb_compaction" => Ok(Self::RocksdbCompaction),
+            "fee_discount_update" => Ok(Self::FeeDiscountUpdate),
+            "fee_discount_removal" => Ok(Self::FeeDiscountRemoval),
+            "abi_registration" => Ok(Self::AbiRegistration),
+            "abi_unregistration" => Ok(Self::AbiUnregistration),
+            "withdrawal_limit_update" => Ok(Self::WithdrawalLimitUpdate),
+            "withdrawal_limit_removal" => Ok(Self::WithdrawalLimitRemoval),
+            "withdrawal_limit_exemption" => Ok(Self::WithdrawalLimitExemption),
+            "withdrawal_limit_exemption_removal" => Ok(Self::WithdrawalLimitExemptionRemoval),
+            "snapshot_request" => Ok(Self::SnapshotRequest),
+            "config_reload" => Ok(Self::ConfigReload),
+            "operator_auth_token_change" => Ok(Self::OperatorAuthTokenChange),
+            "scheduled_tx_registration" => Ok(Self::ScheduledTxRegistration),
+            "scheduled_tx_disabling" => Ok(Self::ScheduledTxDisabling),
+            _ => Err("Incorrect audit action; expected one of `block_revert`, `settlement_revert`, \
+                `failed_l1_txs_cleared`, `settlement_resume`, `token_registration`, `token_unregistration`, \
+                `protocol_upgrade`, `log_filter_change`, `tx_intake_pause`, `tx_intake_resume`, \
+                `transaction_drop`, `l1_batch_seal_request`, `rocksdb_compaction`, `fee_discount_update`, \
+                `fee_discount_removal`, `abi_registration`, `abi_unregistration`, `withdrawal_limit_update`, \
+                `withdrawal_limit_removal`, `withdrawal_limit_exemption`, `withdrawal_limit_exemption_removal`, \
+                `snapshot_request`, `config_reload`, `operator_auth_token_change`, `scheduled_tx_registration`, \
+                `scheduled_tx_disabling`"),
+        }
+    }
+}
+
+/// Entry of the append-only audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    /// Sequential ID of the entry.
+    pub id: u64,
+    pub action: AuditAction,
+    /// Component or interface that has performed the action, e.g. `operator_rpc` or `block_reverter`.
+    pub actor: String,
+    /// Action-specific details.
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filter for audit log entries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogFilter {
+    /// Only return entries with the specified action.
+    pub action: Option<AuditAction>,
+    /// Only return entries with ID greater than the specified one; can be used for pagination.
+    pub after_id: Option<u64>,
+    /// Maximum number of returned entries. Capped by the server-side limit.
+    pub limit: Option<usize>,
+}
+
+/// Warning or error logged by the node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Log level, either `WARN` or `ERROR`.
+    pub level: String,
+    /// Target of the event, usually the path of the module emitting it.
+    pub target: String,
+    pub message: String,
+    /// Structured fields of the event other than the message.
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Pause of L2 transaction intake by the API servers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxIntakePause {
+    pub id: u32,
+    /// Human-readable reason provided by the operator.
+    pub reason: String,
+    pub paused_at: DateTime<Utc>,
+    /// Time when the intake was resumed; `None` if the pause is active.
+    pub resumed_at: Option<DateTime<Utc>>,
+}
+
+/// Request to create a snapshot made by the operator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRequest {
+    pub id: u32,
+    pub requested_at: DateTime<Utc>,
+    /// L1 batch of the snapshot created for the request; `None` if the request is not processed yet.
+    pub l1_batch_number: Option<L1BatchNumber>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+/// Discount on L2 fees granted to an account by the operator. Discounts are paid out as rebates of charged fees.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeDiscount {
+    pub address: Address,
+    /// Discount in basis points; 10000 basis points correspond to a full fee exemption.
+    pub discount_bps: u32,
+    /// Human-readable label provided by the operator, e.g. the partner name.
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FeeDiscount {
+    /// Maximum discount corresponding to a full fee exemption.
+    pub const MAX_DISCOUNT_BPS: u32 = 10_000;
+}
+
+/// Rate limit on withdrawals of a token initiated on the chain, configured by the operator. Amounts are summed
+/// over a sliding window ending at the time a transaction is submitted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalLimit {
+    /// Address of the token on the settlement layer; the zero address corresponds to the base token.
+    pub l1_token: Address,
+    /// Max amount withdrawn by all accounts within the window; `None` if not limited.
+    pub total_limit: Option<U256>,
+    /// Max amount withdrawn by a single account within the window; `None` if not limited.
+    pub account_limit: Option<U256>,
+    /// Duration of the window in seconds.
+    pub window_sec: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Account exempt from withdrawal limits, e.g. a bridge liquidity manager.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalLimitExemption {
+    pub address: Address,
+    /// Human-readable label provided by the operator.
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Recurring L2 transaction registered by the operator (e.g., an oracle push or a fee sweep). Transactions are
+/// sent from the dedicated operator account and injected by the state keeper at miniblock boundaries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTxParams {
+    /// Unique human-readable name of the schedule (e.g., `oracle_push`).
+    pub name: String,
+    pub contract_address: Address,
+    pub calldata: Bytes,
+    #[serde(default)]
+    pub value: U256,
+    pub gas_limit: u64,
+    /// Interval between consecutive transactions in seconds.
+    pub interval_sec: u64,
+}
+
+/// State of the last transaction sent for a schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduledTxState {
+    /// No transaction is in flight.
+    Idle,
+    /// Transaction is waiting in the mempool.
+    Pending,
+    /// Transaction was included in a miniblock and succeeded.
+    Succeeded,
+    /// Transaction was rejected or reverted.
+    Failed,
+    /// Transaction was dropped from the mempool.
+    Dropped,
+}
+
+/// Enabled recurring L2 transaction together with the state of its last sent transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTxStatus {
+    #[serde(flatten)]
+    pub params: ScheduledTxParams,
+    /// Whether the next transaction is due to be sent.
+    pub is_due: bool,
+    pub consecutive_failures: u32,
+    pub last_tx_state: ScheduledTxState,
+    /// Hash of the last sent transaction; `None` if no transaction is in flight.
+    pub last_tx_hash: Option<H256>,
+    /// Miniblock the last sent transaction was included in.
+    pub last_tx_miniblock: Option<MiniblockNumber>,
+    /// Error of the failed last sent transaction.
+    pub last_tx_error: Option<String>,
+}
+
+/// Status of the forced inclusion of priority operations submitted via the settlement layer. Priority operations
+/// bypass the operator's mempool policies and must be executed on the settlement layer within a deadline. The state
+/// keeper seals L1 batches with priority operations early so that they meet the deadline, and the node reports
+/// itself as unhealthy while any operation is overdue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedInclusionStatus {
+    /// Max time between a priority operation being picked up by the chain and the execution of its L1 batch
+    /// on the settlement layer.
+    pub deadline_sec: u64,
+    /// Number of priority operations that are not executed on the settlement layer yet.
+    pub unexecuted_count: u64,
+    /// Number of priority operations that are not executed on the settlement layer yet and have exceeded the deadline.
+    pub overdue_count: u64,
+    /// Oldest priority operation that is not executed on the settlement layer yet. The operation may already
+    /// be included into a miniblock.
+    pub oldest_unexecuted_op: Option<PriorityOpInfo>,
+}
+
+/// Lag of the Merkle tree behind L1 batch execution. Tree-derived data (e.g., storage proofs returned by `zks_getProof`)
+/// is only available for L1 batches processed by the tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeLag {
+    pub sealed_l1_batch: Option<L1BatchNumber>,
+    /// Latest L1 batch processed by the Merkle tree.
+    pub last_processed_l1_batch: Option<L1BatchNumber>,
+    /// Number of sealed L1 batches not processed by the tree yet; `None` if the tree hasn't processed
+    /// any L1 batches yet (e.g., right after snapshot recovery).
+    pub lag: Option<u32>,
+}
+
+/// Sizes of a column family in a RocksDB instance used by the node (e.g., the Merkle tree
+/// or the state keeper cache), as estimated by RocksDB.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RocksdbColumnFamilySizes {
+    /// Name of the database, e.g. `merkle_tree` or `state_keeper`.
+    pub db_name: String,
+    pub cf_name: String,
+    /// Estimated byte size of live data.
+    pub live_data_size: u64,
+    /// Total byte size of SST files. The excess over `live_data_size` can be reclaimed by compaction.
+    pub total_sst_size: u64,
+    /// Byte size of active and immutable memtables.
+    pub mem_table_size: u64,
+}
+
+/// Lifecycle stage of an L1 batch reported by the `idexo_subscribeBatchStatus` subscription.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize
+)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchLifecycleStage {
+    /// The batch is sealed by the state keeper.
+    Sealed,
+    /// The commit transaction for the batch is confirmed on the settlement layer.
+    Committed,
+    /// The prove transaction for the batch is confirmed on the settlement layer.
+    Proven,
+    /// The execute transaction for the batch is confirmed on the settlement layer.
+    Executed,
+}
+
+/// Notification about an L1 batch reaching a certain lifecycle stage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStatusEvent {
+    pub l1_batch_number: L1BatchNumber,
+    pub stage: BatchLifecycleStage,
+    /// Settlement layer transaction for the stage; `None` for [`BatchLifecycleStage::Sealed`].
+    pub tx_hash: Option<H256>,
+    /// Time the stage was reached: the batch timestamp for sealed batches, or the confirmation time
+    /// of the settlement layer transaction for other stages.
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Single read in an `idexo_multicallRead` request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MulticallRead {
+    /// View call, equivalent to `eth_call`.
+    Call(CallRequest),
+    /// Storage reads of a single contract, equivalent to a batch of `eth_getStorageAt` calls.
+    Storage { address: Address, keys: Vec<U256> },
+}
+
+impl MulticallRead {
+    /// Returns the number of reads counted towards the request limit.
+    pub fn read_count(&self) -> usize {
+        match self {
+            Self::Call(_) => 1,
+            Self::Storage { keys, .. } => keys.len(),
+        }
+    }
+}
+
+/// Result of a single read in an `idexo_multicallRead` request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MulticallReadResult {
+    /// Output of a successful call.
+    Output(Bytes),
+    /// Values of storage slots in the order of the requested keys.
+    StorageValues(Vec<H256>),
+    /// Error executing the call, e.g. a revert. Reads that didn't fit into the compute budget
+    /// also fail with an error.
+    Error(String),
+}
+
+/// Final value of a storage slot written in an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiff {
+    pub address: Address,
+    pub key: H256,
+    pub hashed_key: H256,
+    /// Value of the slot after the batch is applied.
+    pub value: H256,
+    /// Enumeration index of the slot assigned on its initial write; `None` if the batch is not processed
+    /// by the Merkle tree yet.
+    pub enumeration_index: Option<u64>,
+    /// Whether the slot is written for the first time in the batch.
+    pub is_initial: bool,
+}
+
+/// Bytecode deployed (i.e., published as a factory dependency) in an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployedBytecode {
+    pub bytecode_hash: H256,
+    pub bytecode: Bytes,
+}
+
+/// Page of state diffs of an L1 batch returned by `idexo_getBatchStateDiffs`. Storage diffs and deployed bytecodes
+/// are paginated independently using the same offset and limit; both lists are ordered by hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStateDiffs {
+    pub l1_batch_number: L1BatchNumber,
+    /// State diffs in the compressed form published as the batch pubdata. Only returned for the first page
+    /// (i.e., if the offset is 0), and only if the batch commitment is generated.
+    pub compressed_state_diffs: Option<Bytes>,
+    pub storage_diffs: Vec<StorageDiff>,
+    /// Total number of storage diffs in the batch.
+    pub total_storage_diffs: usize,
+    pub deployed_bytecodes: Vec<DeployedBytecode>,
+    /// Total number of bytecodes deployed in the batch.
+    pub total_deployed_bytecodes: usize,
+}
+
+/// Event parameter decoded from a log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedEventParam {
+    /// Name of the parameter from the ABI. Unnamed parameters are named by their zero-based index, e.g. `0`.
+    pub name: String,
+    /// Solidity type of the parameter, e.g. `uint256` or `address[]`. Indexed parameters of dynamic types,
+    /// arrays and tuples are only logged as Keccak-256 hashes of their values, so their type is `bytes32`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub indexed: bool,
+    /// Decoded value. Addresses, hashes and byte arrays are encoded as hex strings, integers as decimal strings,
+    /// and arrays and tuples as JSON arrays.
+    pub value: serde_json::Value,
+}
+
+/// Event decoded from a log using the ABI of the emitting contract.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedEvent {
+    pub name: String,
+    /// Canonical event signature, e.g. `Transfer(address,address,uint256)`.
+    pub signature: String,
+    pub params: Vec<DecodedEventParam>,
+}
+
+/// Log returned by `idexo_getDecodedLogs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedLog {
+    #[serde(flatten)]
+    pub log: Log,
+    /// Decoded event, or `None` if the ABI of the emitting contract is unknown or does not contain
+    /// a matching event.
+    pub event: Option<DecodedEvent>,
+}
+
+/// Request for a Merkle proof of an L2-to-L1 log in `idexo_getL2ToL1LogProofs`. Has the same semantics
+/// as the arguments of `zks_getL2ToL1LogProof`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2ToL1LogProofRequest {
+    /// Hash of the transaction that emitted the log.
+    pub tx_hash: H256,
+    /// Index of the log among logs emitted by the transaction. Defaults to 0.
+    #[serde(default)]
+    pub index: Option<usize>,
+}
+
+/// Timestamps of the lifecycle stages of a transaction returned by `idexo_getTransactionTimeline`. A stage timestamp
+/// is `None` if the transaction hasn't reached the stage yet, or if the stage isn't tracked for the transaction
+/// (e.g., L1-originated transactions are not validated by the API server, and transactions processed before
+/// the timeline was introduced have no mempool or execution timestamps).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTimeline {
+    pub hash: H256,
+    pub is_l1_originated: bool,
+    pub status: TransactionStatus,
+    pub miniblock_number: Option<MiniblockNumber>,
+    pub l1_batch_number: Option<L1BatchNumber>,
+    /// Time the transaction was received by the API server (for L2 transactions), or was observed on L1
+    /// (for L1-originated transactions).
+    pub received_at: DateTime<Utc>,
+    /// Time the transaction passed validation and was persisted by the API server.
+    pub validated_at: Option<DateTime<Utc>>,
+    /// Time the transaction was first picked up by the state keeper mempool.
+    pub added_to_mempool_at: Option<DateTime<Utc>>,
+    /// Time the transaction was executed by the state keeper.
+    pub executed_at: Option<DateTime<Utc>>,
+    pub miniblock_sealed_at: Option<DateTime<Utc>>,
+    pub l1_batch_sealed_at: Option<DateTime<Utc>>,
+    /// Time the commit transaction of the L1 batch was confirmed on the settlement layer.
+    pub committed_at: Option<DateTime<Utc>>,
+    /// Time the proof transaction of the L1 batch was confirmed on the settlement layer.
+    pub proven_at: Option<DateTime<Utc>>,
+    /// Time the execute transaction of the L1 batch was confirmed on the settlement layer.
+    pub executed_on_settlement_at: Option<DateTime<Utc>>,
+}
+
+/// Target of an `idexo_diagnoseTransaction` request: either a single transaction, or all unexecuted transactions
+/// of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionDiagnosticsTarget {
+    Hash(H256),
+    Account(Address),
+}
+
+/// Settlement stage an included transaction is waiting for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SettlementStage {
+    /// The L1 batch containing the transaction is not sealed yet.
+    BatchSealing,
+    /// The L1 batch is not committed on the settlement layer yet.
+    Commit,
+    /// The L1 batch is not proven on the settlement layer yet.
+    Proof,
+    /// The L1 batch is not executed on the settlement layer yet.
+    Execution,
+}
+
+/// Explanation why a transaction is not progressing, returned by `idexo_diagnoseTransaction`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "camelCase")]
+pub enum TransactionDiagnosis {
+    /// The transaction is unknown to the node.
+    NotFound,
+    /// The transaction was rejected by the state keeper and evicted from the mempool.
+    Evicted { error: String },
+    /// The transaction cannot be executed until a transaction with a lower nonce is submitted.
+    #[serde(rename_all = "camelCase")]
+    NonceGap {
+        /// Next nonce of the account according to the latest sealed miniblock.
+        account_nonce: Nonce,
+        /// Lowest nonce not used by any transaction of the account.
+        missing_nonce: Nonce,
+    },
+    /// The transaction nonce is already used by an executed transaction, so the transaction will be rejected.
+    #[serde(rename_all = "camelCase")]
+    NonceAlreadyUsed { account_nonce: Nonce },
+    /// The max fee per gas of the transaction is below the current base fee, so the transaction won't be picked up
+    /// by the state keeper until the base fee drops.
+    #[serde(rename_all = "camelCase")]
+    FeeBelowFloor {
+        max_fee_per_gas: U256,
+        current_base_fee: U256,
+    },
+    /// The transaction was proxied to the main node and is not synced back yet (only returned by external nodes).
+    AwaitingProxySync,
+    /// The transaction is awaiting inclusion into a miniblock; no issues are detected.
+    #[serde(rename_all = "camelCase")]
+    AwaitingInclusion {
+        /// Whether the transaction was picked up by the state keeper mempool.
+        in_mempool: bool,
+    },
+    /// The transaction is included into a miniblock, but its L1 batch is not executed on the settlement layer yet.
+    #[serde(rename_all = "camelCase")]
+    AwaitingSettlement {
+        miniblock_number: MiniblockNumber,
+        l1_batch_number: Option<L1BatchNumber>,
+        stage: SettlementStage,
+    },
+    /// The L1 batch containing the transaction is executed on the settlement layer.
+    #[serde(rename_all = "camelCase")]
+    Finalized { l1_batch_number: L1BatchNumber },
+}
+
+/// Diagnostics of a single transaction returned by `idexo_diagnoseTransaction`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDiagnostics {
+    pub hash: H256,
+    /// Initiator of the transaction; `None` if the transaction is not found.
+    pub initiator_address: Option<Address>,
+    /// Nonce of the transaction; `None` for L1-originated transactions or if the transaction is not found.
+    pub nonce: Option<Nonce>,
+    pub diagnosis: TransactionDiagnosis,
+}
+
+/// Kind of a size limit imposed on L2 transactions submitted to the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxLimitKind {
+    /// Size of the ABI-encoded transaction in bytes.
+    TxSize,
+    /// Number of factory dependencies (i.e., bytecodes deployed by the transaction).
+    FactoryDeps,
+    /// Size of the transaction calldata in bytes.
+    CalldataSize,
+}
+
+impl TxLimitKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TxSize => "tx_size",
+            Self::FactoryDeps => "factory_deps",
+            Self::CalldataSize => "calldata_size",
+        }
+    }
+}
+
+/// Violation of a transaction size limit. Returned as data of the JSON-RPC error when a transaction is rejected,
+/// so that clients can e.g. split a large deployment into several transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxLimitViolation {
+    pub kind: TxLimitKind,
+    /// Max allowed value.
+    pub limit: usize,
+    /// Value for the rejected transaction.
+    pub actual: usize,
+}
+
+impl fmt::Display for TxLimitViolation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { limit, actual, .. } = self;
+        match self.kind {
+            TxLimitKind::TxSize => {
+                write!(formatter, "oversized data. max: {limit}; actual: {actual}")
+            }
+            TxLimitKind::FactoryDeps => write!(
+                formatter,
+                "too many factory dependencies in the transaction. {actual} provided, \
+                 while only {limit} allowed"
+            ),
+            TxLimitKind::CalldataSize => {
+                write!(
+                    formatter,
+                    "oversized calldata. max: {limit}; actual: {actual}"
+                )
+            }
+        }
+    }
+}
+
+/// Rejection of a transaction because the mempool is saturated, i.e., its backlog is projected to occupy more
+/// L1 batches than allowed by the inclusion horizon. Returned as data of the JSON-RPC error, so that clients
+/// can retry later.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionHorizonViolation {
+    /// Number of L1 batches the pending transactions are projected to fill.
+    pub projected_batches: f64,
+    /// Max allowed number of L1 batches.
+    pub horizon_batches: u32,
+}
+
+impl fmt::Display for InclusionHorizonViolation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "mempool is saturated: pending transactions are projected to fill {:.2} batches, \
+             while only {} are allowed",
+            self.projected_batches, self.horizon_batches
+        )
+    }
+}
@@ -18,6 +18,7 @@ use crate::{
 };
 
 pub mod en;
+pub mod idexo;
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq, Display)]
@@ -26,6 +27,9 @@ pub enum BlockNumber {
     Committed,
     /// Last block that was finalized on L1.
     Finalized,
+    /// Last block of the latest L1 batch processed by the Merkle tree, i.e. the latest block
+    /// for which tree-derived data (e.g., storage proofs) is available.
+    Merkleized,
     /// Latest sealed block
     Latest,
     /// Earliest block (genesis)
@@ -51,6 +55,7 @@ impl Serialize for BlockNumber {
             BlockNumber::Number(ref x) => serializer.serialize_str(&format!("0x{:x}", x)),
             BlockNumber::Committed => serializer.serialize_str("committed"),
             BlockNumber::Finalized => serializer.serialize_str("finalized"),
+            BlockNumber::Merkleized => serializer.serialize_str("merkleized"),
             BlockNumber::Latest => serializer.serialize_str("latest"),
             BlockNumber::Earliest => serializer.serialize_str("earliest"),
             BlockNumber::Pending => serializer.serialize_str("pending"),
@@ -73,6 +78,7 @@ impl<'de> Deserialize<'de> for BlockNumber {
                 let result = match value {
                     "committed" => BlockNumber::Committed,
                     "finalized" => BlockNumber::Finalized,
+                    "merkleized" => BlockNumber::Merkleized,
                     "latest" => BlockNumber::Latest,
                     "earliest" => BlockNumber::Earliest,
                     "pending" => BlockNumber::Pending,
@@ -178,7 +184,7 @@ impl From<H256> for TransactionId {
 }
 
 /// A struct with the proof for the L2->L1 log in a specific block.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct L2ToL1LogProof {
     /// The merkle path for the leaf.
@@ -197,6 +203,8 @@ pub struct BridgeAddresses {
     pub l2_erc20_default_bridge: Address,
     pub l1_weth_bridge: Option<Address>,
     pub l2_weth_bridge: Option<Address>,
+    pub l1_shared_default_bridge: Option<Address>,
+    pub l2_shared_default_bridge: Option<Address>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -523,7 +531,7 @@ pub struct Transaction {
     pub l1_batch_tx_index: Option<U64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TransactionStatus {
     Pending,
@@ -1,4 +1,4 @@
-use std::{fmt, ops};
+use std::{collections::BTreeMap, fmt, ops};
 
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::{Address, H2048, H256, U256};
@@ -8,7 +8,7 @@ use zksync_utils::concat_and_hash;
 
 use crate::{
     fee_model::BatchFeeInput,
-    l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
+    l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log, UserL2ToL1Log},
     priority_op_onchain_data::PriorityOpOnchainData,
     web3::signing::keccak256,
     AccountTreeId, L1BatchNumber, MiniblockNumber, ProtocolVersionId, Transaction,
@@ -203,6 +203,22 @@ impl MiniblockHasher {
         }
     }
 
+    /// Creates a hasher with the already computed rolling hash of miniblock transactions (e.g., one read
+    /// from the `SystemContext` storage).
+    pub fn with_txs_rolling_hash(
+        number: MiniblockNumber,
+        timestamp: u64,
+        prev_miniblock_hash: H256,
+        txs_rolling_hash: H256,
+    ) -> Self {
+        Self {
+            number,
+            timestamp,
+            prev_miniblock_hash,
+            txs_rolling_hash,
+        }
+    }
+
     /// Updates this hasher with a transaction hash. This should be called for all transactions in the block
     /// in the order of their execution.
     pub fn push_tx_hash(&mut self, tx_hash: H256) {
@@ -237,6 +253,107 @@ impl MiniblockHasher {
     }
 }
 
+/// Hasher of the miniblock payload: executed transactions with their outcome, emitted events, user L2-to-L1 logs
+/// and (deduplicated) storage writes. Unlike [`MiniblockHasher`], this hash is not a part of the protocol;
+/// it's persisted alongside the miniblock in order to detect silent corruption of the miniblock data in the storage.
+#[derive(Debug)]
+pub struct MiniblockContentHasher {
+    number: MiniblockNumber,
+    txs_rolling_hash: H256,
+    events_rolling_hash: H256,
+    l2_to_l1_logs_rolling_hash: H256,
+    /// Storage writes keyed by the hashed storage key. Writes are hashed in the key order since the order
+    /// of writes within a miniblock is not deterministic.
+    storage_writes: BTreeMap<H256, H256>,
+}
+
+impl MiniblockContentHasher {
+    pub fn new(number: MiniblockNumber) -> Self {
+        Self {
+            number,
+            txs_rolling_hash: H256::zero(),
+            events_rolling_hash: H256::zero(),
+            l2_to_l1_logs_rolling_hash: H256::zero(),
+            storage_writes: BTreeMap::new(),
+        }
+    }
+
+    /// Updates this hasher with an executed transaction. This should be called for all transactions
+    /// in the block in the order of their execution.
+    pub fn push_tx(&mut self, tx_hash: H256, is_successful: bool, refunded_gas: u64) {
+        let mut bytes = [0_u8; 41];
+        bytes[..32].copy_from_slice(tx_hash.as_bytes());
+        bytes[32] = is_successful.into();
+        bytes[33..].copy_from_slice(&refunded_gas.to_be_bytes());
+        self.txs_rolling_hash = concat_and_hash(self.txs_rolling_hash, H256(keccak256(&bytes)));
+    }
+
+    /// Updates this hasher with an event. This should be called for all events in the block in the order
+    /// of their emission.
+    pub fn push_event(&mut self, tx_hash: H256, address: Address, topics: &[H256], value: &[u8]) {
+        let mut bytes = Vec::with_capacity(57 + topics.len() * 32 + value.len());
+        bytes.extend_from_slice(tx_hash.as_bytes());
+        bytes.extend_from_slice(address.as_bytes());
+        // Topic count and value length are included to keep the encoding unambiguous.
+        bytes.push(topics.len() as u8);
+        for topic in topics {
+            bytes.extend_from_slice(topic.as_bytes());
+        }
+        bytes.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(value);
+        self.events_rolling_hash =
+            concat_and_hash(self.events_rolling_hash, H256(keccak256(&bytes)));
+    }
+
+    /// Updates this hasher with a user L2-to-L1 log. This should be called for all user logs in the block
+    /// in the order of their emission. The transaction is identified by `tx_hash`, so `log.tx_number_in_block`
+    /// is not hashed.
+    pub fn push_l2_to_l1_log(&mut self, tx_hash: H256, log: &L2ToL1Log) {
+        let mut bytes = [0_u8; 118];
+        bytes[..32].copy_from_slice(tx_hash.as_bytes());
+        bytes[32] = log.shard_id;
+        bytes[33] = log.is_service.into();
+        bytes[34..54].copy_from_slice(log.sender.as_bytes());
+        bytes[54..86].copy_from_slice(log.key.as_bytes());
+        bytes[86..118].copy_from_slice(log.value.as_bytes());
+        self.l2_to_l1_logs_rolling_hash =
+            concat_and_hash(self.l2_to_l1_logs_rolling_hash, H256(keccak256(&bytes)));
+    }
+
+    /// Updates this hasher with a deduplicated storage write. Writes may be pushed in any order, but each slot
+    /// must be written at most once.
+    pub fn push_storage_write(&mut self, hashed_key: H256, value: H256) {
+        self.storage_writes.insert(hashed_key, value);
+    }
+
+    /// Returns the content hash of the miniblock, computed as
+    ///
+    /// ```text
+    /// keccak256(
+    ///     u32_be(number) ++ txs_rolling_hash ++ events_rolling_hash ++ l2_to_l1_logs_rolling_hash
+    ///     ++ storage_writes_hash
+    /// )
+    /// ```
+    ///
+    /// where `storage_writes_hash` is the hash of all `(hashed_key, value)` storage writes ordered by `hashed_key`.
+    pub fn finalize(self) -> H256 {
+        let mut storage_writes_bytes = Vec::with_capacity(self.storage_writes.len() * 64);
+        for (hashed_key, value) in &self.storage_writes {
+            storage_writes_bytes.extend_from_slice(hashed_key.as_bytes());
+            storage_writes_bytes.extend_from_slice(value.as_bytes());
+        }
+        let storage_writes_hash = keccak256(&storage_writes_bytes);
+
+        let mut digest = [0_u8; 132];
+        digest[..4].copy_from_slice(&self.number.0.to_be_bytes());
+        digest[4..36].copy_from_slice(self.txs_rolling_hash.as_bytes());
+        digest[36..68].copy_from_slice(self.events_rolling_hash.as_bytes());
+        digest[68..100].copy_from_slice(self.l2_to_l1_logs_rolling_hash.as_bytes());
+        digest[100..].copy_from_slice(&storage_writes_hash);
+        H256(keccak256(&digest))
+    }
+}
+
 /// Returns block.number/timestamp based on the block's information
 pub fn unpack_block_info(info: U256) -> (u64, u64) {
     let block_number = (info / SYSTEM_BLOCK_INFO_BLOCK_NUMBER_MULTIPLIER).as_u64();
@@ -298,6 +415,82 @@ mod tests {
         )
     }
 
+    #[test]
+    fn miniblock_content_hash_depends_on_all_data() {
+        let tx_hash = H256::repeat_byte(1);
+        let address = Address::repeat_byte(2);
+        let topics = [H256::repeat_byte(3)];
+        let content_hash = |refunded_gas: u64, topics: &[H256], value: &[u8]| {
+            let mut hasher = MiniblockContentHasher::new(MiniblockNumber(1));
+            hasher.push_tx(tx_hash, true, refunded_gas);
+            hasher.push_event(tx_hash, address, topics, value);
+            hasher.finalize()
+        };
+
+        let hash = content_hash(100, &topics, b"value");
+        assert_eq!(hash, content_hash(100, &topics, b"value"));
+        assert_ne!(hash, content_hash(101, &topics, b"value"));
+        assert_ne!(hash, content_hash(100, &[], b"value"));
+        assert_ne!(hash, content_hash(100, &topics, b"other"));
+        assert_ne!(
+            hash,
+            MiniblockContentHasher::new(MiniblockNumber(1)).finalize()
+        );
+    }
+
+    #[test]
+    fn miniblock_content_hash_depends_on_logs_and_storage_writes() {
+        let tx_hash = H256::repeat_byte(1);
+        let log = L2ToL1Log {
+            sender: Address::repeat_byte(2),
+            key: H256::repeat_byte(3),
+            value: H256::repeat_byte(4),
+            ..L2ToL1Log::default()
+        };
+        let writes = [
+            (H256::repeat_byte(5), H256::repeat_byte(6)),
+            (H256::repeat_byte(7), H256::repeat_byte(8)),
+        ];
+        let content_hash =
+            |log: Option<&L2ToL1Log>, writes: &mut dyn Iterator<Item = (H256, H256)>| {
+                let mut hasher = MiniblockContentHasher::new(MiniblockNumber(1));
+                hasher.push_tx(tx_hash, true, 0);
+                if let Some(log) = log {
+                    hasher.push_l2_to_l1_log(tx_hash, log);
+                }
+                for (hashed_key, value) in writes {
+                    hasher.push_storage_write(hashed_key, value);
+                }
+                hasher.finalize()
+            };
+
+        let hash = content_hash(Some(&log), &mut writes.into_iter());
+        // Storage writes are order-independent.
+        assert_eq!(
+            hash,
+            content_hash(Some(&log), &mut writes.into_iter().rev())
+        );
+        assert_ne!(hash, content_hash(None, &mut writes.into_iter()));
+        assert_ne!(
+            hash,
+            content_hash(Some(&log), &mut writes.into_iter().take(1))
+        );
+
+        let modified_log = L2ToL1Log {
+            value: H256::repeat_byte(0xff),
+            ..log.clone()
+        };
+        assert_ne!(
+            hash,
+            content_hash(Some(&modified_log), &mut writes.into_iter())
+        );
+        let modified_writes = [writes[0], (writes[1].0, H256::zero())];
+        assert_ne!(
+            hash,
+            content_hash(Some(&log), &mut modified_writes.into_iter())
+        );
+    }
+
     #[test]
     fn test_block_packing() {
         let block_number = 101;
@@ -1,4 +1,86 @@
-use crate::{aggregated_operations::AggregatedActionType, Address, Nonce, H256};
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{aggregated_operations::AggregatedActionType, Address, Nonce, EIP_4844_TX_TYPE, H256};
+
+/// Sidecar of an EIP-4844 transaction, i.e. the blobs together with their KZG commitments and proofs.
+/// The sidecar is not a part of the signed transaction payload, but is required to broadcast it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EthTxBlobSidecar {
+    EthTxBlobSidecarV1(EthTxBlobSidecarV1),
+}
+
+impl From<EthTxBlobSidecarV1> for EthTxBlobSidecar {
+    fn from(value: EthTxBlobSidecarV1) -> Self {
+        Self::EthTxBlobSidecarV1(value)
+    }
+}
+
+impl EthTxBlobSidecar {
+    /// Returns versioned hashes of all blobs in the sidecar, in the order they should be referenced
+    /// by the transaction.
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        match self {
+            Self::EthTxBlobSidecarV1(sidecar) => sidecar
+                .blobs
+                .iter()
+                .map(|blob| H256::from_slice(&blob.versioned_hash))
+                .collect(),
+        }
+    }
+
+    /// Converts a signed EIP-4844 transaction (`0x03 || rlp(tx_payload_body)`) into its network
+    /// representation (`0x03 || rlp([tx_payload_body, blobs, commitments, proofs])`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `signed_tx` is not a typed EIP-4844 transaction.
+    pub fn encode_network_tx(&self, signed_tx: &[u8]) -> Vec<u8> {
+        assert_eq!(
+            signed_tx.first(),
+            Some(&EIP_4844_TX_TYPE),
+            "sidecar can only be attached to EIP-4844 transactions"
+        );
+        let Self::EthTxBlobSidecarV1(sidecar) = self;
+
+        let mut stream = rlp::RlpStream::new_list(4);
+        stream.append_raw(&signed_tx[1..], 1);
+        stream.begin_list(sidecar.blobs.len());
+        for blob in &sidecar.blobs {
+            stream.append(&blob.blob);
+        }
+        stream.begin_list(sidecar.blobs.len());
+        for blob in &sidecar.blobs {
+            stream.append(&blob.commitment);
+        }
+        stream.begin_list(sidecar.blobs.len());
+        for blob in &sidecar.blobs {
+            stream.append(&blob.proof);
+        }
+        [&[EIP_4844_TX_TYPE], stream.as_raw()].concat()
+    }
+}
+
+/// All sidecar data for a single blob of an EIP-4844 transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SidecarBlobV1 {
+    /// Blob itself.
+    pub blob: Vec<u8>,
+    /// KZG commitment to the blob.
+    pub commitment: Vec<u8>,
+    /// KZG proof for the blob and its commitment.
+    pub proof: Vec<u8>,
+    /// Blob commitment versioned hash.
+    pub versioned_hash: Vec<u8>,
+}
+
+/// Version 1 of the blob sidecar.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EthTxBlobSidecarV1 {
+    pub blobs: Vec<SidecarBlobV1>,
+}
 
 #[derive(Clone)]
 pub struct EthTx {
@@ -9,6 +91,10 @@ pub struct EthTx {
     pub tx_type: AggregatedActionType,
     pub created_at_timestamp: u64,
     pub predicted_gas_cost: u64,
+    /// Operator account the transaction is sent from; `None` corresponds to the main operator account.
+    pub from_addr: Option<Address>,
+    /// Blob sidecar for EIP-4844 transactions; `None` for transactions publishing pubdata in calldata.
+    pub blob_sidecar: Option<EthTxBlobSidecar>,
 }
 
 impl std::fmt::Debug for EthTx {
@@ -21,6 +107,8 @@ impl std::fmt::Debug for EthTx {
             .field("tx_type", &self.tx_type)
             .field("created_at_timestamp", &self.created_at_timestamp)
             .field("predicted_gas_cost", &self.predicted_gas_cost)
+            .field("from_addr", &self.from_addr)
+            .field("has_blob_sidecar", &self.blob_sidecar.is_some())
             .finish()
     }
 }
@@ -31,6 +119,7 @@ pub struct TxHistory {
     pub eth_tx_id: u32,
     pub base_fee_per_gas: u64,
     pub priority_fee_per_gas: u64,
+    pub blob_base_fee_per_gas: Option<u64>,
     pub tx_hash: H256,
     pub signed_raw_tx: Vec<u8>,
     pub sent_at_block: Option<u32>,
@@ -46,3 +135,109 @@ pub struct TxHistoryToSend {
     pub signed_raw_tx: Vec<u8>,
     pub nonce: Nonce,
 }
+
+/// Decision made by the Ethereum sender when (re)submitting an L1 transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GasEscalationAction {
+    /// First attempt to send the transaction.
+    Initial,
+    /// Resubmission with an incrementally bumped priority fee.
+    FeeBump,
+    /// Resubmission replacing a transaction that wasn't included after several fee bumps; pays doubled fees.
+    Replacement,
+    /// Resubmission was considered, but skipped.
+    Skipped,
+}
+
+impl GasEscalationAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Initial => "initial",
+            Self::FeeBump => "fee_bump",
+            Self::Replacement => "replacement",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+impl fmt::Display for GasEscalationAction {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GasEscalationAction {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "initial" => Ok(Self::Initial),
+            "fee_bump" => Ok(Self::FeeBump),
+            "replacement" => Ok(Self::Replacement),
+            "skipped" => Ok(Self::Skipped),
+            _ => Err("Incorrect gas escalation action; expected one of `initial`, `fee_bump`, `replacement`, `skipped`"),
+        }
+    }
+}
+
+/// Entry of the audit trail recording how fees of an L1 transaction were escalated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasEscalationEntry {
+    pub eth_tx_id: u32,
+    /// Number of the attempt, starting from 1 for the initial attempt. For skipped resubmissions,
+    /// this is the number of the attempt that would have been sent.
+    pub attempt: u32,
+    pub action: GasEscalationAction,
+    /// Reason for skipping the resubmission; set only for [`GasEscalationAction::Skipped`].
+    pub skip_reason: Option<String>,
+    pub base_fee_per_gas: u64,
+    pub priority_fee_per_gas: u64,
+    pub blob_base_fee_per_gas: Option<u64>,
+    /// Hash of the sent transaction; `None` for skipped resubmissions.
+    pub tx_hash: Option<H256>,
+    /// L1 block at which the decision was made.
+    pub l1_block_number: u32,
+}
+
+/// Halt of new commit and execute operations caused by an anomaly on the settlement layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementHalt {
+    pub id: u32,
+    /// Human-readable description of the detected anomaly.
+    pub reason: String,
+    pub halted_at: DateTime<Utc>,
+    /// Time when the operator resumed settlement; `None` if the halt is active.
+    pub resumed_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_network_blob_tx() {
+        let sidecar = EthTxBlobSidecar::from(EthTxBlobSidecarV1 {
+            blobs: vec![SidecarBlobV1 {
+                blob: vec![1; 64],
+                commitment: vec![2; 48],
+                proof: vec![3; 48],
+                versioned_hash: vec![4; 32],
+            }],
+        });
+        assert_eq!(sidecar.versioned_hashes(), [H256::repeat_byte(4)]);
+
+        let mut payload = rlp::RlpStream::new_list(2);
+        payload.append(&1_u64).append(&vec![5_u8; 10]);
+        let signed_tx = [&[EIP_4844_TX_TYPE], payload.as_raw()].concat();
+        let network_tx = sidecar.encode_network_tx(&signed_tx);
+
+        assert_eq!(network_tx[0], EIP_4844_TX_TYPE);
+        let network_tx = rlp::Rlp::new(&network_tx[1..]);
+        assert_eq!(network_tx.item_count().unwrap(), 4);
+        assert_eq!(network_tx.at(0).unwrap().as_raw(), &signed_tx[1..]);
+        assert_eq!(network_tx.list_at::<Vec<u8>>(1).unwrap(), [vec![1; 64]]);
+        assert_eq!(network_tx.list_at::<Vec<u8>>(2).unwrap(), [vec![2; 48]]);
+        assert_eq!(network_tx.list_at::<Vec<u8>>(3).unwrap(), [vec![3; 48]]);
+    }
+}
@@ -44,7 +44,20 @@ pub static DEPLOY_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     )
 });
 
-static L1_MESSAGE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+/// Signature of the `Transfer(address,address,uint256)` event emitted by ERC-20 tokens (including the base token).
+pub static TRANSFER_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "Transfer",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+        ],
+    )
+});
+
+/// Signature of the `L1MessageSent(address,bytes32,bytes)` event emitted by the L1 messenger for each sent message.
+pub static L1_MESSAGE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     ethabi::long_signature(
         "L1MessageSent",
         &[
@@ -163,10 +163,13 @@ pub struct PubdataIndependentBatchFeeModelInput {
 /// - `V2`, the second model that was used in zkSync Era. There the pubdata price might be independent from the L1 gas price. Also,
 /// The fair L2 gas price is expected to both the proving/computation price for the operator and the costs that come from
 /// processing the batch on L1.
+/// - `V3`, the `V2` model extended with congestion pricing. The fair L2 gas price computed by the `V2` model is multiplied
+/// by a congestion multiplier, which follows an EIP-1559-style curve based on the fullness of recent batches.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum FeeModelConfig {
     V1(FeeModelConfigV1),
     V2(FeeModelConfigV2),
+    V3(FeeModelConfigV3),
 }
 
 /// Config params for the first version of the fee model. Here, the pubdata price is pegged to the L1 gas price and
@@ -200,6 +203,51 @@ pub struct FeeModelConfigV2 {
     pub max_pubdata_per_batch: u64,
 }
 
+/// Config params for the third version of the fee model.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FeeModelConfigV3 {
+    /// Params of the `V2` fee model used to compute prices in the absence of congestion.
+    pub base: FeeModelConfigV2,
+    /// Target fullness of a batch, i.e. the ratio of gas used by transactions in the batch to `max_gas_per_batch`.
+    /// Batches fuller than the target raise the congestion multiplier, and emptier ones lower it. Must be in (0, 1).
+    pub target_batch_fullness: f64,
+    /// The max relative change of the congestion multiplier per batch, which is reached for full and empty batches.
+    /// The EIP-1559 analogue of this value is 1/8.
+    pub max_change_per_batch: f64,
+    /// The upper bound for the congestion multiplier. The lower bound is 1, i.e. congestion pricing never makes
+    /// the L2 gas price lower than the one computed by the `V2` fee model.
+    pub max_congestion_multiplier: f64,
+    /// Number of latest batches taken into account when computing the congestion multiplier.
+    pub window_size: u32,
+}
+
+impl FeeModelConfigV3 {
+    /// Computes the congestion multiplier after a batch with the specified fullness is sealed.
+    /// The result is always in `[1, max_congestion_multiplier]`.
+    pub fn next_congestion_multiplier(&self, multiplier: f64, batch_fullness: f64) -> f64 {
+        let target = self.target_batch_fullness;
+        let batch_fullness = batch_fullness.clamp(0.0, 1.0);
+        // Normalized deviation from the target in `[-1, 1]`.
+        let deviation = if batch_fullness >= target {
+            (batch_fullness - target) / (1.0 - target)
+        } else {
+            (batch_fullness - target) / target
+        };
+        let multiplier = multiplier * (1.0 + self.max_change_per_batch * deviation);
+        multiplier.min(self.max_congestion_multiplier).max(1.0)
+    }
+
+    /// Computes the congestion multiplier for a sequence of batch fullness values ordered from the oldest batch
+    /// to the newest one, starting from the neutral multiplier 1.
+    pub fn congestion_multiplier(&self, batch_fullness: impl IntoIterator<Item = f64>) -> f64 {
+        batch_fullness
+            .into_iter()
+            .fold(1.0, |multiplier, fullness| {
+                self.next_congestion_multiplier(multiplier, fullness)
+            })
+    }
+}
+
 impl Default for FeeModelConfig {
     /// Config with all zeroes is not a valid config (since for instance having 0 max gas per batch may incur division by zero),
     /// so we implement a sensible default config here.
@@ -224,6 +272,29 @@ impl FeeModelConfig {
                 max_gas_per_batch: state_keeper_config.max_gas_per_batch,
                 max_pubdata_per_batch: state_keeper_config.max_pubdata_per_batch,
             }),
+            FeeModelVersion::V3 => Self::V3(FeeModelConfigV3 {
+                base: FeeModelConfigV2 {
+                    minimal_l2_gas_price: state_keeper_config.minimal_l2_gas_price,
+                    compute_overhead_part: state_keeper_config.compute_overhead_part,
+                    pubdata_overhead_part: state_keeper_config.pubdata_overhead_part,
+                    batch_overhead_l1_gas: state_keeper_config.batch_overhead_l1_gas,
+                    max_gas_per_batch: state_keeper_config.max_gas_per_batch,
+                    max_pubdata_per_batch: state_keeper_config.max_pubdata_per_batch,
+                },
+                target_batch_fullness: state_keeper_config.congestion_target_batch_fullness(),
+                max_change_per_batch: state_keeper_config.congestion_max_change_per_batch(),
+                max_congestion_multiplier: state_keeper_config.congestion_max_multiplier(),
+                window_size: state_keeper_config.congestion_window_size(),
+            }),
+        }
+    }
+
+    /// Sets the minimal acceptable L2 gas price regardless of the fee model version.
+    pub fn set_minimal_l2_gas_price(&mut self, price: u64) {
+        match self {
+            Self::V1(config) => config.minimal_l2_gas_price = price,
+            Self::V2(config) => config.minimal_l2_gas_price = price,
+            Self::V3(config) => config.base.minimal_l2_gas_price = price,
         }
     }
 }
@@ -241,10 +312,20 @@ pub struct FeeParamsV2 {
     pub l1_pubdata_price: u64,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FeeParamsV3 {
+    pub config: FeeModelConfigV3,
+    pub l1_gas_price: u64,
+    pub l1_pubdata_price: u64,
+    /// Current congestion multiplier applied to the fair L2 gas price.
+    pub congestion_multiplier: f64,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum FeeParams {
     V1(FeeParamsV1),
     V2(FeeParamsV2),
+    V3(FeeParamsV3),
 }
 
 impl FeeParams {
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{L1BatchNumber, ProtocolVersionId};
+
+/// Enum holding the current values used for DA Layers.
+#[repr(u8)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize
+)]
+pub enum PubdataDA {
+    /// Pubdata is sent to the L1 as a part of the commit transaction calldata.
+    #[default]
+    Calldata = 0,
+    /// Pubdata is sent to the L1 in EIP-4844 blobs carried by the commit transaction.
+    Blobs = 1,
+    /// Pubdata is not published on the L1; the commit transaction only carries the inclusion data
+    /// of an external DA layer, which is empty for validium chains.
+    Custom = 2,
+}
+
+impl PubdataDA {
+    /// Returns the source flag prepended to the pubdata commitments passed to the L1 contract.
+    pub fn source_flag(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Location of a blob in a DA layer organizing blobs by namespaces and block heights, such as Celestia.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DABlobReference {
+    pub namespace: Vec<u8>,
+    /// Height of the DA layer block including the blob.
+    pub height: u64,
+    /// Commitment to the blob data.
+    pub commitment: Vec<u8>,
+}
+
+/// Pubdata of an L1 batch dispatched to a data availability layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataAvailabilityBlob {
+    pub l1_batch_number: L1BatchNumber,
+    /// Identifier of the blob in the DA layer.
+    pub blob_id: String,
+    /// Data proving inclusion of the blob into the DA layer; `None` if the blob isn't included yet.
+    pub inclusion_data: Option<Vec<u8>>,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Pubdata of an L1 batch that is ready to be dispatched to a data availability layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1BatchPubdata {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: Option<ProtocolVersionId>,
+    pub pubdata: Vec<u8>,
+}
@@ -2,7 +2,9 @@ use std::{convert::TryFrom, ops};
 
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{AccountTreeId, L1BatchNumber, MiniblockNumber, H256};
+use zksync_basic_types::{
+    web3::signing::keccak256, AccountTreeId, L1BatchNumber, MiniblockNumber, H256,
+};
 use zksync_protobuf::{required, ProtoFmt};
 use zksync_utils::u256_to_h256;
 
@@ -190,6 +192,37 @@ impl ProtoFmt for SnapshotStorageLogsChunk {
     }
 }
 
+/// Digest of a single blob in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotBlobDigest {
+    /// Keccak-256 hash of the serialized blob as stored in the object store.
+    pub hash: H256,
+    /// Size of the serialized blob in bytes.
+    pub size: u64,
+}
+
+impl SnapshotBlobDigest {
+    pub fn new(blob: &[u8]) -> Self {
+        Self {
+            hash: H256(keccak256(blob)),
+            size: blob.len() as u64,
+        }
+    }
+}
+
+/// Integrity manifest of a snapshot. Persisted in the object store alongside snapshot blobs once
+/// the snapshot is complete, so that snapshot consumers can detect corrupted or tampered blobs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    pub l1_batch_number: L1BatchNumber,
+    pub miniblock_number: MiniblockNumber,
+    pub factory_deps: SnapshotBlobDigest,
+    /// Ordered by chunk IDs.
+    pub storage_logs_chunks: Vec<SnapshotBlobDigest>,
+}
+
 /// Status of snapshot recovery process stored in Postgres.
 #[derive(Debug, PartialEq)]
 pub struct SnapshotRecoveryStatus {
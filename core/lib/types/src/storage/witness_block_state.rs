@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{StorageKey, StorageValue};
+use crate::{L1BatchNumber, StorageKey, StorageValue, H256};
 
 /// Storage data used during Witness Generation.
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -10,3 +10,16 @@ pub struct WitnessBlockState {
     pub read_storage_key: HashMap<StorageKey, StorageValue>,
     pub is_write_initial: HashMap<StorageKey, bool>,
 }
+
+/// Metadata stored alongside a [`WitnessBlockState`] in the object store, allowing to reuse
+/// the state instead of regenerating it (e.g., after a node restart).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WitnessInputCacheEntry {
+    pub l1_batch_number: L1BatchNumber,
+    /// Hash of the L1 batch contents the state was generated for. Used to detect that
+    /// the batch was reverted and re-created with different contents.
+    pub batch_fingerprint: H256,
+    /// Keccak-256 hash of the serialized state blob.
+    pub content_hash: H256,
+}
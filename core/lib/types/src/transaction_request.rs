@@ -175,6 +175,8 @@ pub enum SerializationTransactionError {
     MalformedSignature,
     #[error("wrong chain id {}", .0.unwrap_or_default())]
     WrongChainId(Option<u64>),
+    #[error("only replay-protected (EIP-155) transactions are allowed")]
+    ReplayProtectionRequired,
     #[error("malformed paymaster params")]
     MalforedPaymasterParams,
     #[error("factory dependency #{0} is invalid: {1}")]
@@ -618,8 +620,9 @@ impl TransactionRequest {
                         DecoderError::RlpIncorrectListLen,
                     ));
                 }
+                // Unlike legacy transactions, EIP-712 transactions must always specify the chain ID.
                 let tx_chain_id = rlp.val_at(10).ok();
-                if tx_chain_id.is_some() && tx_chain_id != Some(chain_id.as_u64()) {
+                if tx_chain_id != Some(chain_id.as_u64()) {
                     return Err(SerializationTransactionError::WrongChainId(tx_chain_id));
                 }
 
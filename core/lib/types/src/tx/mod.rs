@@ -6,6 +6,7 @@
 
 use std::fmt::Debug;
 
+use chrono::{DateTime, Utc};
 use zksync_basic_types::{Address, H256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
@@ -28,6 +29,8 @@ pub struct TransactionExecutionResult {
     pub compressed_bytecodes: Vec<CompressedBytecodeInfo>,
     pub call_traces: Vec<Call>,
     pub revert_reason: Option<String>,
+    /// Time the transaction was executed by the state keeper, if known.
+    pub executed_at: Option<DateTime<Utc>>,
 }
 
 impl TransactionExecutionResult {
@@ -56,7 +56,17 @@ impl DeduplicatedWritesMetrics {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize
+)]
+// Metrics persisted by older node versions may lack some fields.
+#[serde(default)]
 pub struct ExecutionMetrics {
     pub gas_used: usize,
     pub published_bytecode_bytes: usize,
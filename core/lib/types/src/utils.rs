@@ -63,6 +63,14 @@ pub fn storage_key_for_eth_balance(address: &Address) -> StorageKey {
     storage_key_for_standard_token_balance(AccountTreeId::new(L2_ETH_TOKEN_ADDRESS), address)
 }
 
+/// Storage key of `totalSupply` in the `L2EthToken` system contract (follows the `balance` mapping).
+pub fn storage_key_for_eth_total_supply() -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
+        H256::from_low_u64_be(1),
+    )
+}
+
 /// Pre-calculated the address of the to-be-deployed contract (via CREATE, not CREATE2).
 pub fn deployed_address_create(sender: Address, deploy_nonce: U256) -> Address {
     let prefix_bytes = keccak256("zksyncCreate".as_bytes());
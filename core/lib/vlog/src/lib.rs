@@ -7,7 +7,15 @@ use std::{backtrace::Backtrace, borrow::Cow, panic::PanicInfo, str::FromStr};
 // crates directly.
 pub use sentry::{capture_message, Level as AlertLevel};
 use sentry::{types::Dsn, ClientInitGuard};
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
+
+use crate::log_control::RecentEventsLayer;
+pub use crate::log_control::{LogControl, LogControlError, LogEvent};
+
+mod log_control;
+
+/// Number of recent `WARN` and `ERROR` events retained for [`LogControl::recent_events()`].
+const RECENT_EVENTS_CAPACITY: usize = 1_000;
 
 /// Specifies the format of the logs in stdout.
 #[derive(Debug, Clone, Copy, Default)]
@@ -92,20 +100,24 @@ impl ObservabilityBuilder {
         self
     }
 
-    /// Initializes the observability subsystem.
+    /// Initializes the observability subsystem. Once initialized, the log filter can be changed
+    /// at runtime via [`LogControl`].
     pub fn build(self) -> ObservabilityGuard {
         // Initialize logs.
+        let (filter, filter_handle) = reload::Layer::new(EnvFilter::from_default_env());
+        let recent_events = RecentEventsLayer::new(RECENT_EVENTS_CAPACITY);
         match self.log_format {
             LogFormat::Plain => {
                 tracing_subscriber::registry()
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
+                    .with(filter)
                     .with(fmt::Layer::default())
+                    .with(recent_events.clone())
                     .init();
             }
             LogFormat::Json => {
                 let timer = tracing_subscriber::fmt::time::UtcTime::rfc_3339();
                 tracing_subscriber::registry()
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
+                    .with(filter)
                     .with(
                         fmt::Layer::default()
                             .with_file(true)
@@ -113,9 +125,11 @@ impl ObservabilityBuilder {
                             .with_timer(timer)
                             .json(),
                     )
+                    .with(recent_events.clone())
                     .init();
             }
         };
+        LogControl::install(filter_handle, recent_events);
 
         // Check whether we need to change the default panic handler.
         // Note that this must happen before we initialize Sentry, since otherwise
@@ -0,0 +1,277 @@
+//! Runtime control of logging: reloading the log filter and querying recent warnings and errors.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use chrono::{DateTime, Utc};
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{filter::ParseError, layer::Context, reload, EnvFilter, Layer, Registry};
+
+static LOG_CONTROL: OnceLock<LogControl> = OnceLock::new();
+
+/// Event with the `WARN` or `ERROR` level captured by the observability subsystem.
+#[derive(Debug, Clone)]
+pub struct LogEvent {
+    pub timestamp: DateTime<Utc>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    /// Event fields other than the message.
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default)]
+struct EventVisitor {
+    message: String,
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl EventVisitor {
+    fn record_value(&mut self, field: &Field, value: serde_json::Value) {
+        self.fields.insert(field.name().to_owned(), value);
+    }
+}
+
+impl Visit for EventVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_owned();
+        } else {
+            self.record_value(field, value.into());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.record_value(field, format!("{value:?}").into());
+        }
+    }
+}
+
+/// Ring buffer of recent events.
+#[derive(Debug)]
+struct RecentEvents {
+    events: VecDeque<LogEvent>,
+    capacity: usize,
+}
+
+/// Layer capturing `WARN` and `ERROR` events into a ring buffer. Events disabled by the log filter
+/// are not captured.
+#[derive(Debug, Clone)]
+pub(crate) struct RecentEventsLayer {
+    inner: Arc<Mutex<RecentEvents>>,
+}
+
+impl RecentEventsLayer {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let recent_events = RecentEvents {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        };
+        Self {
+            inner: Arc::new(Mutex::new(recent_events)),
+        }
+    }
+
+    fn events(&self, limit: usize) -> Vec<LogEvent> {
+        let recent_events = self.inner.lock().expect("recent events are poisoned");
+        let skipped_count = recent_events.events.len().saturating_sub(limit);
+        recent_events
+            .events
+            .iter()
+            .skip(skipped_count)
+            .cloned()
+            .collect()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RecentEventsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // More verbose levels are greater.
+        if *metadata.level() > Level::WARN {
+            return;
+        }
+
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        let event = LogEvent {
+            timestamp: Utc::now(),
+            level: *metadata.level(),
+            target: metadata.target().to_owned(),
+            message: visitor.message,
+            fields: visitor.fields,
+        };
+
+        let mut recent_events = self.inner.lock().expect("recent events are poisoned");
+        if recent_events.capacity == 0 {
+            return;
+        }
+        if recent_events.events.len() == recent_events.capacity {
+            recent_events.events.pop_front();
+        }
+        recent_events.events.push_back(event);
+    }
+}
+
+/// Handle allowing to change the log filter at runtime and to query recent warnings and errors.
+/// Available once the observability subsystem is initialized.
+#[derive(Debug)]
+pub struct LogControl {
+    filter_handle: reload::Handle<EnvFilter, Registry>,
+    recent_events: RecentEventsLayer,
+}
+
+impl LogControl {
+    /// Returns the log control for the observability subsystem, or `None` if the subsystem
+    /// is not initialized.
+    pub fn get() -> Option<&'static Self> {
+        LOG_CONTROL.get()
+    }
+
+    pub(crate) fn install(
+        filter_handle: reload::Handle<EnvFilter, Registry>,
+        recent_events: RecentEventsLayer,
+    ) {
+        let control = Self {
+            filter_handle,
+            recent_events,
+        };
+        if LOG_CONTROL.set(control).is_err() {
+            tracing::warn!("Log control is already installed");
+        }
+    }
+
+    /// Returns the current log filter directives, e.g. `zksync_core=debug,info`.
+    pub fn filter(&self) -> String {
+        self.filter_handle
+            .with_current(ToString::to_string)
+            .unwrap_or_default()
+    }
+
+    /// Replaces the log filter with the provided directives, using the same syntax as the `RUST_LOG`
+    /// env variable. Returns the previous filter directives.
+    pub fn set_filter(&self, directives: &str) -> Result<String, LogControlError> {
+        let new_filter = EnvFilter::try_new(directives).map_err(LogControlError::Parse)?;
+        let mut previous_filter = String::new();
+        self.filter_handle
+            .modify(|filter| {
+                previous_filter = filter.to_string();
+                *filter = new_filter;
+            })
+            .map_err(|err| LogControlError::Reload(err.to_string()))?;
+        Ok(previous_filter)
+    }
+
+    /// Returns up to `limit` most recent `WARN` and `ERROR` events in the order they were emitted.
+    pub fn recent_events(&self, limit: usize) -> Vec<LogEvent> {
+        self.recent_events.events(limit)
+    }
+}
+
+/// Error changing the log filter.
+#[derive(Debug)]
+pub enum LogControlError {
+    /// Provided filter directives are invalid.
+    Parse(ParseError),
+    /// The subscriber holding the filter was dropped.
+    Reload(String),
+}
+
+impl fmt::Display for LogControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "invalid log filter: {err}"),
+            Self::Reload(err) => write!(f, "failed reloading log filter: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LogControlError {}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn capturing_recent_events() {
+        let layer = RecentEventsLayer::new(2);
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("ignored");
+            tracing::warn!(attempt = 1, "first warning");
+            tracing::error!(reason = "test", "error");
+            tracing::warn!(attempt = 2, "second warning");
+        });
+
+        let events = layer.events(10);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].level, Level::ERROR);
+        assert_eq!(events[0].message, "error");
+        assert_eq!(events[0].fields["reason"], "test");
+        assert_eq!(events[1].level, Level::WARN);
+        assert_eq!(events[1].message, "second warning");
+        assert_eq!(events[1].fields["attempt"], 2);
+
+        let events = layer.events(1);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].message, "second warning");
+    }
+
+    #[test]
+    fn reloading_filter() {
+        let (filter, filter_handle) = reload::Layer::new(EnvFilter::new("error"));
+        let recent_events = RecentEventsLayer::new(10);
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(recent_events.clone());
+        let control = LogControl {
+            filter_handle,
+            recent_events,
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!("filtered out");
+            assert_eq!(control.filter(), "error");
+            let previous_filter = control.set_filter("vlog=warn,error").unwrap();
+            assert_eq!(previous_filter, "error");
+            tracing::warn!("captured");
+
+            let err = control.set_filter("vlog=not_a_level").unwrap_err();
+            assert!(matches!(err, LogControlError::Parse(_)), "{err}");
+            // The filter is not changed on error.
+            let filter = control.filter();
+            assert!(filter.contains("vlog=warn"), "{filter}");
+        });
+
+        let events = control.recent_events(10);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].message, "captured");
+    }
+}
@@ -2,7 +2,7 @@ pub mod storage;
 
 use anyhow::{anyhow, Context};
 use multivm::{
-    interface::{VmInterface, VmInterfaceHistoryEnabled},
+    interface::{VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled},
     vm_latest::HistoryEnabled,
     VmInstance,
 };
@@ -65,26 +65,22 @@ pub fn create_vm(
 pub fn execute_tx<S: WriteStorage>(
     tx: &Transaction,
     vm: &mut VmInstance<S, HistoryEnabled>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<VmExecutionResultAndLogs> {
     // Attempt to run VM with bytecode compression on.
     vm.make_snapshot();
-    if vm
-        .execute_transaction_with_bytecode_compression(tx.clone(), true)
-        .0
-        .is_ok()
-    {
+    let (compression_result, result) =
+        vm.execute_transaction_with_bytecode_compression(tx.clone(), true);
+    if compression_result.is_ok() {
         vm.pop_snapshot_no_rollback();
-        return Ok(());
+        return Ok(result);
     }
 
     // If failed with bytecode compression, attempt to run without bytecode compression.
     vm.rollback_to_the_latest_snapshot();
-    if vm
-        .execute_transaction_with_bytecode_compression(tx.clone(), false)
-        .0
-        .is_err()
-    {
+    let (compression_result, result) =
+        vm.execute_transaction_with_bytecode_compression(tx.clone(), false);
+    if compression_result.is_err() {
         return Err(anyhow!("compression can't fail if we don't apply it"));
     }
-    Ok(())
+    Ok(result)
 }
@@ -127,8 +127,11 @@ impl L1BatchParamsProvider {
     ) -> anyhow::Result<(H256, u64)> {
         // If the state root is not known yet, this duration will be used to back off in the while loops
         const SAFE_STATE_ROOT_INTERVAL: Duration = Duration::from_millis(100);
+        /// Interval between warnings logged while the state root is not computed.
+        const WARNING_INTERVAL: Duration = Duration::from_secs(10);
 
         let stage_started_at: Instant = Instant::now();
+        let mut last_warning_at = stage_started_at;
         loop {
             let data = storage
                 .blocks_dal()
@@ -142,6 +145,13 @@ impl L1BatchParamsProvider {
                 return Ok((root_hash, timestamp));
             }
 
+            if last_warning_at.elapsed() >= WARNING_INTERVAL {
+                tracing::warn!(
+                    "Waiting for Merkle tree to process L1 batch #{number} for {:?}",
+                    stage_started_at.elapsed()
+                );
+                last_warning_at = Instant::now();
+            }
             tokio::time::sleep(SAFE_STATE_ROOT_INTERVAL).await;
         }
     }
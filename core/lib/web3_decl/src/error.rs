@@ -14,7 +14,13 @@ use std::{
 use jsonrpsee::core::ClientError;
 use pin_project_lite::pin_project;
 use thiserror::Error;
-use zksync_types::{api::SerializationTransactionError, L1BatchNumber, MiniblockNumber};
+use zksync_types::{
+    api::{
+        idexo::{InclusionHorizonViolation, TxLimitViolation},
+        SerializationTransactionError,
+    },
+    L1BatchNumber, MiniblockNumber,
+};
 
 #[derive(Debug, Error)]
 pub enum Web3Error {
@@ -24,6 +30,10 @@ pub enum Web3Error {
     PrunedBlock(MiniblockNumber),
     #[error("L1 batch with such an ID is pruned; the first retained L1 batch is {0}")]
     PrunedL1Batch(L1BatchNumber),
+    #[error(
+        "State of pruned block {0} is not available; the closest block with available state is {1}"
+    )]
+    PrunedBlockStateUnavailable(MiniblockNumber, MiniblockNumber),
     #[error("Request timeout")]
     RequestTimeout,
     #[error("Internal error")]
@@ -36,6 +46,12 @@ pub enum Web3Error {
     InvalidTransactionData(#[from] zksync_types::ethabi::Error),
     #[error("{0}")]
     SubmitTransactionError(String, Vec<u8>),
+    /// Transaction violates a size limit. Details of the violation are returned as error data.
+    #[error("{0}")]
+    TxLimitExceeded(TxLimitViolation),
+    /// Transaction is rejected by the inclusion horizon admission control. Details are returned as error data.
+    #[error("{0}")]
+    InclusionHorizonExceeded(InclusionHorizonViolation),
     #[error("Failed to serialize transaction: {0}")]
     SerializationError(#[from] SerializationTransactionError),
     #[error("Invalid fee parameters: {0}")]
@@ -54,6 +70,28 @@ pub enum Web3Error {
     InvalidFilterBlockHash,
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error("L1 batch #{0} is not processed by the Merkle tree yet; the last processed L1 batch is {1:?}")]
+    TreeLagging(L1BatchNumber, Option<L1BatchNumber>),
+    #[error("Invalid log filter: {0}")]
+    InvalidLogFilter(String),
+    #[error("Component `{0}` is not running on this node")]
+    ComponentUnavailable(&'static str),
+    #[error("Invalid name `{0}`")]
+    InvalidName(String),
+    #[error("Name `{0}` is not registered")]
+    UnknownName(String),
+    #[error("Name registry is not configured on this node")]
+    NameRegistryUnavailable,
+    #[error("Request contains {0} reads, which exceeds the limit of {1}")]
+    TooManyReads(usize, usize),
+    #[error("Invalid contract ABI: {0}")]
+    InvalidAbi(String),
+    #[error("Request contains {0} log proof requests, which exceeds the limit of {1}")]
+    TooManyLogProofRequests(usize, usize),
+    #[error("Invalid withdrawal limit: {0}")]
+    InvalidWithdrawalLimit(String),
+    #[error("Invalid scheduled transaction: {0}")]
+    InvalidScheduledTx(String),
 }
 
 /// Client RPC error with additional details: the method name and arguments of the called method.
@@ -0,0 +1,112 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::idexo::{
+        FeeDiscount, RocksdbColumnFamilySizes, ScheduledTxParams, ScheduledTxStatus,
+        SnapshotRequest, TxIntakePause, WithdrawalLimit, WithdrawalLimitExemption,
+    },
+    Address, H256, U256,
+};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "admin")
+)]
+pub trait AdminNamespace {
+    #[method(name = "pauseTxIntake")]
+    async fn pause_tx_intake(&self, reason: String) -> RpcResult<TxIntakePause>;
+
+    #[method(name = "resumeTxIntake")]
+    async fn resume_tx_intake(&self) -> RpcResult<Option<TxIntakePause>>;
+
+    #[method(name = "getTxIntakePause")]
+    async fn get_tx_intake_pause(&self) -> RpcResult<Option<TxIntakePause>>;
+
+    #[method(name = "dropTransaction")]
+    async fn drop_transaction(&self, hash: H256) -> RpcResult<bool>;
+
+    #[method(name = "requestSnapshot")]
+    async fn request_snapshot(&self) -> RpcResult<SnapshotRequest>;
+
+    #[method(name = "getSnapshotRequest")]
+    async fn get_snapshot_request(&self) -> RpcResult<Option<SnapshotRequest>>;
+
+    #[method(name = "sealL1Batch")]
+    async fn seal_l1_batch(&self) -> RpcResult<()>;
+
+    #[method(name = "getComponentStatuses")]
+    async fn get_component_statuses(&self) -> RpcResult<serde_json::Value>;
+
+    #[method(name = "getRocksdbSizes")]
+    async fn get_rocksdb_sizes(&self) -> RpcResult<Vec<RocksdbColumnFamilySizes>>;
+
+    #[method(name = "compactRocksdb")]
+    async fn compact_rocksdb(
+        &self,
+        db_name: String,
+    ) -> RpcResult<Option<Vec<RocksdbColumnFamilySizes>>>;
+
+    #[method(name = "setFeeDiscount")]
+    async fn set_fee_discount(
+        &self,
+        address: Address,
+        discount_bps: u32,
+        label: Option<String>,
+    ) -> RpcResult<FeeDiscount>;
+
+    #[method(name = "removeFeeDiscount")]
+    async fn remove_fee_discount(&self, address: Address) -> RpcResult<Option<FeeDiscount>>;
+
+    #[method(name = "getFeeDiscounts")]
+    async fn get_fee_discounts(&self) -> RpcResult<Vec<FeeDiscount>>;
+
+    #[method(name = "setWithdrawalLimit")]
+    async fn set_withdrawal_limit(
+        &self,
+        l1_token: Address,
+        total_limit: Option<U256>,
+        account_limit: Option<U256>,
+        window_sec: u64,
+    ) -> RpcResult<WithdrawalLimit>;
+
+    #[method(name = "removeWithdrawalLimit")]
+    async fn remove_withdrawal_limit(
+        &self,
+        l1_token: Address,
+    ) -> RpcResult<Option<WithdrawalLimit>>;
+
+    #[method(name = "getWithdrawalLimits")]
+    async fn get_withdrawal_limits(&self) -> RpcResult<Vec<WithdrawalLimit>>;
+
+    #[method(name = "addWithdrawalLimitExemption")]
+    async fn add_withdrawal_limit_exemption(
+        &self,
+        address: Address,
+        label: Option<String>,
+    ) -> RpcResult<WithdrawalLimitExemption>;
+
+    #[method(name = "removeWithdrawalLimitExemption")]
+    async fn remove_withdrawal_limit_exemption(
+        &self,
+        address: Address,
+    ) -> RpcResult<Option<WithdrawalLimitExemption>>;
+
+    #[method(name = "getWithdrawalLimitExemptions")]
+    async fn get_withdrawal_limit_exemptions(&self) -> RpcResult<Vec<WithdrawalLimitExemption>>;
+
+    #[method(name = "registerScheduledTx")]
+    async fn register_scheduled_tx(&self, params: ScheduledTxParams) -> RpcResult<()>;
+
+    #[method(name = "disableScheduledTx")]
+    async fn disable_scheduled_tx(&self, name: String) -> RpcResult<bool>;
+
+    #[method(name = "getScheduledTxs")]
+    async fn get_scheduled_txs(&self) -> RpcResult<Vec<ScheduledTxStatus>>;
+}
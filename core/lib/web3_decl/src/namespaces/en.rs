@@ -1,5 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use zksync_types::{api::en::SyncBlock, tokens::TokenInfo, MiniblockNumber};
+use zksync_types::{
+    api::en::{SyncBlock, SyncBlockOutputs, SyncL1BatchRootHash},
+    tokens::TokenInfo,
+    L1BatchNumber, MiniblockNumber,
+};
 
 #[cfg_attr(
     all(feature = "client", feature = "server"),
@@ -21,6 +25,25 @@ pub trait EnNamespace {
         include_transactions: bool,
     ) -> RpcResult<Option<SyncBlock>>;
 
+    /// Returns execution outputs of the specified L2 block, or `None` if the block is not sealed.
+    ///
+    /// This method is used by EN in the read replica mode in order to persist L2 blocks without re-executing them.
+    #[method(name = "syncL2BlockOutputs")]
+    async fn sync_l2_block_outputs(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<SyncBlockOutputs>>;
+
+    /// Returns the root hash of the specified L1 batch, or `None` if the L1 batch is not processed
+    /// by the Merkle tree yet.
+    ///
+    /// This method is used by EN in order to execute L1 batches without waiting for its own Merkle tree.
+    #[method(name = "syncL1BatchRootHash")]
+    async fn sync_l1_batch_root_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<SyncL1BatchRootHash>>;
+
     /// Lists all tokens created at or before the specified `block_number`.
     ///
     /// This method is used by EN after snapshot recovery in order to recover token records.
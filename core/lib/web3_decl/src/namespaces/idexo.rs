@@ -0,0 +1,118 @@
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+};
+use zksync_types::{
+    api::{
+        idexo::{
+            BatchEconomics, BatchStateDiffs, BatchStatusEvent, DaInclusionProof, DecodedLog,
+            DepositStatus, ForcedInclusionStatus, L1BatchProofStatus, L2ToL1LogProofRequest,
+            MulticallRead, MulticallReadResult, PriorityQueueStatus, RegisteredToken,
+            RelayedMessageStatus, TransactionDiagnostics, TransactionDiagnosticsTarget,
+            TransactionTimeline,
+        },
+        BlockIdVariant, L2ToL1LogProof,
+    },
+    Address, L1BatchNumber, H256,
+};
+
+use crate::types::Filter;
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "idexo")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "idexo")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "idexo")
+)]
+pub trait IdexoNamespace {
+    #[method(name = "getBatchEconomics")]
+    async fn get_batch_economics(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BatchEconomics>>;
+
+    #[method(name = "getDaInclusionProof")]
+    async fn get_da_inclusion_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<DaInclusionProof>>;
+
+    #[method(name = "getTokenList")]
+    async fn get_token_list(&self) -> RpcResult<Vec<RegisteredToken>>;
+
+    #[method(name = "getDepositStatus")]
+    async fn get_deposit_status(&self, settlement_tx_hash: H256) -> RpcResult<Vec<DepositStatus>>;
+
+    #[method(name = "getRelayedMessages")]
+    async fn get_relayed_messages(
+        &self,
+        source_tx_hash: H256,
+    ) -> RpcResult<Vec<RelayedMessageStatus>>;
+
+    #[method(name = "getPendingPriorityOps")]
+    async fn get_pending_priority_ops(
+        &self,
+        limit: Option<usize>,
+    ) -> RpcResult<PriorityQueueStatus>;
+
+    #[method(name = "getL1BatchProofStatus")]
+    async fn get_l1_batch_proof_status(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchProofStatus>>;
+
+    #[method(name = "getForcedInclusionStatus")]
+    async fn get_forced_inclusion_status(&self) -> RpcResult<Option<ForcedInclusionStatus>>;
+
+    #[method(name = "resolveName")]
+    async fn resolve_name(&self, name: String) -> RpcResult<Option<Address>>;
+
+    #[method(name = "multicallRead")]
+    async fn multicall_read(
+        &self,
+        reads: Vec<MulticallRead>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Vec<MulticallReadResult>>;
+
+    #[method(name = "getBatchStateDiffs")]
+    async fn get_batch_state_diffs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> RpcResult<Option<BatchStateDiffs>>;
+
+    #[method(name = "getDecodedLogs")]
+    async fn get_decoded_logs(&self, filter: Filter) -> RpcResult<Vec<DecodedLog>>;
+
+    #[method(name = "getL2ToL1LogProofs")]
+    async fn get_l2_to_l1_log_proofs(
+        &self,
+        requests: Vec<L2ToL1LogProofRequest>,
+    ) -> RpcResult<Vec<Option<L2ToL1LogProof>>>;
+
+    #[method(name = "getTransactionTimeline")]
+    async fn get_transaction_timeline(&self, hash: H256) -> RpcResult<Option<TransactionTimeline>>;
+
+    #[method(name = "diagnoseTransaction")]
+    async fn diagnose_transaction(
+        &self,
+        target: TransactionDiagnosticsTarget,
+    ) -> RpcResult<Vec<TransactionDiagnostics>>;
+}
+
+#[rpc(server, namespace = "idexo")]
+pub trait IdexoPubSub {
+    #[subscription(
+        name = "subscribeBatchStatus" => "batchStatus",
+        unsubscribe = "unsubscribeBatchStatus",
+        item = BatchStatusEvent
+    )]
+    async fn subscribe_batch_status(&self) -> SubscriptionResult;
+}
@@ -1,21 +1,26 @@
+pub mod admin;
 pub mod debug;
 pub mod en;
 pub mod eth;
 pub mod eth_subscribe;
+pub mod idexo;
 pub mod net;
+pub mod operator;
 pub mod snapshots;
 pub mod web3;
 pub mod zks;
 
 #[cfg(feature = "client")]
 pub use self::{
-    debug::DebugNamespaceClient, en::EnNamespaceClient, eth::EthNamespaceClient,
-    net::NetNamespaceClient, snapshots::SnapshotsNamespaceServer, web3::Web3NamespaceClient,
-    zks::ZksNamespaceClient,
+    admin::AdminNamespaceClient, debug::DebugNamespaceClient, en::EnNamespaceClient,
+    eth::EthNamespaceClient, idexo::IdexoNamespaceClient, net::NetNamespaceClient,
+    operator::OperatorNamespaceClient, snapshots::SnapshotsNamespaceServer,
+    web3::Web3NamespaceClient, zks::ZksNamespaceClient,
 };
 #[cfg(feature = "server")]
 pub use self::{
-    debug::DebugNamespaceServer, en::EnNamespaceServer, eth::EthNamespaceServer,
-    eth::EthPubSubServer, net::NetNamespaceServer, snapshots::SnapshotsNamespaceClient,
-    web3::Web3NamespaceServer, zks::ZksNamespaceServer,
+    admin::AdminNamespaceServer, debug::DebugNamespaceServer, en::EnNamespaceServer,
+    eth::EthNamespaceServer, eth::EthPubSubServer, idexo::IdexoNamespaceServer,
+    idexo::IdexoPubSubServer, net::NetNamespaceServer, operator::OperatorNamespaceServer,
+    snapshots::SnapshotsNamespaceClient, web3::Web3NamespaceServer, zks::ZksNamespaceServer,
 };
@@ -0,0 +1,50 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::idexo::{AuditLogEntry, AuditLogFilter, LogEvent, RegisteredToken},
+    eth_sender::SettlementHalt,
+    Address,
+};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "operator")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "operator")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "operator")
+)]
+pub trait OperatorNamespace {
+    #[method(name = "getSettlementHalt")]
+    async fn get_settlement_halt(&self) -> RpcResult<Option<SettlementHalt>>;
+
+    #[method(name = "resumeSettlement")]
+    async fn resume_settlement(&self) -> RpcResult<Option<SettlementHalt>>;
+
+    #[method(name = "registerToken")]
+    async fn register_token(&self, token: RegisteredToken) -> RpcResult<()>;
+
+    #[method(name = "unregisterToken")]
+    async fn unregister_token(&self, l2_address: Address) -> RpcResult<bool>;
+
+    #[method(name = "registerAbi")]
+    async fn register_abi(&self, address: Address, abi: serde_json::Value) -> RpcResult<()>;
+
+    #[method(name = "unregisterAbi")]
+    async fn unregister_abi(&self, address: Address) -> RpcResult<bool>;
+
+    #[method(name = "getAuditLog")]
+    async fn get_audit_log(&self, filter: Option<AuditLogFilter>) -> RpcResult<Vec<AuditLogEntry>>;
+
+    #[method(name = "getLogFilter")]
+    async fn get_log_filter(&self) -> RpcResult<String>;
+
+    #[method(name = "setLogFilter")]
+    async fn set_log_filter(&self, filter: String) -> RpcResult<String>;
+
+    #[method(name = "getRecentLogEvents")]
+    async fn get_recent_log_events(&self, limit: Option<usize>) -> RpcResult<Vec<LogEvent>>;
+}
@@ -3,9 +3,10 @@ use std::collections::HashMap;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        idexo::TreeLag, BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof,
+        ProtocolVersion, TransactionDetails,
     },
+    contract_verification_api::VerificationInfo,
     fee::Fee,
     fee_model::FeeParams,
     transaction_request::CallRequest,
@@ -39,6 +40,9 @@ pub trait ZksNamespace {
     #[method(name = "getMainContract")]
     async fn get_main_contract(&self) -> RpcResult<Address>;
 
+    #[method(name = "getValidatorTimelockContract")]
+    async fn get_validator_timelock_contract(&self) -> RpcResult<Option<Address>>;
+
     #[method(name = "getTestnetPaymaster")]
     async fn get_testnet_paymaster(&self) -> RpcResult<Option<Address>>;
 
@@ -118,4 +122,13 @@ pub trait ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Proof>;
+
+    #[method(name = "getContractVerificationInfo")]
+    async fn get_contract_verification_info(
+        &self,
+        address: Address,
+    ) -> RpcResult<Option<VerificationInfo>>;
+
+    #[method(name = "getTreeLag")]
+    async fn get_tree_lag(&self) -> RpcResult<TreeLag>;
 }
@@ -14,6 +14,7 @@ use core::{
 use itertools::unfold;
 use rlp::Rlp;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use zksync_types::api::idexo::BatchStatusEvent;
 pub use zksync_types::{
     api::{Block, BlockNumber, Log, TransactionReceipt, TransactionRequest},
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace, VmExecutionStep},
@@ -345,6 +346,45 @@ pub enum PubSubResult {
     Log(Log),
     TxHash(H256),
     Syncing(bool),
+    BatchStatus(BatchStatusEvent),
+    SlowConsumer(SlowConsumerNotice),
+}
+
+/// Notice sent to a subscriber that doesn't keep up with notifications for its subscription.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowConsumerNotice {
+    /// [`Self::WARNING_CODE`] or [`Self::CLOSE_CODE`].
+    pub code: i32,
+    pub message: String,
+    /// Number of notification batches queued for the subscriber.
+    pub lag: usize,
+}
+
+impl SlowConsumerNotice {
+    /// Code of a notice warning that the subscriber lags behind; the subscription remains active.
+    pub const WARNING_CODE: i32 = 4100;
+    /// Code of a notice sent immediately before the subscription is terminated.
+    pub const CLOSE_CODE: i32 = 4101;
+
+    pub fn warning(lag: usize) -> Self {
+        Self {
+            code: Self::WARNING_CODE,
+            message:
+                "Subscriber lags behind notifications; it will be unsubscribed if the lag grows"
+                    .to_owned(),
+            lag,
+        }
+    }
+
+    pub fn close(lag: usize) -> Self {
+        Self {
+            code: Self::CLOSE_CODE,
+            message: "Subscriber lags behind notifications; the subscription is terminated"
+                .to_owned(),
+            lag,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -358,6 +398,7 @@ mod tests {
         let test_vector = &[
             (r#""committed""#, BlockNumber::Committed),
             (r#""finalized""#, BlockNumber::Finalized),
+            (r#""merkleized""#, BlockNumber::Merkleized),
             (r#""pending""#, BlockNumber::Pending),
             (r#""latest""#, BlockNumber::Latest),
             (r#""earliest""#, BlockNumber::Earliest),
@@ -441,4 +482,18 @@ mod tests {
         let restored_value: ValueOrArray<Address> = serde_json::from_value(json).unwrap();
         assert_eq!(restored_value, value);
     }
+
+    #[test]
+    fn slow_consumer_notice_serde() {
+        let notice = PubSubResult::SlowConsumer(SlowConsumerNotice::close(42));
+        let json = serde_json::to_value(&notice).unwrap();
+        assert_eq!(json["code"], SlowConsumerNotice::CLOSE_CODE);
+        assert_eq!(json["lag"], 42);
+
+        let restored: PubSubResult = serde_json::from_value(json).unwrap();
+        let PubSubResult::SlowConsumer(restored) = restored else {
+            panic!("Unexpected deserialized value: {restored:?}");
+        };
+        assert_eq!(restored, SlowConsumerNotice::close(42));
+    }
 }
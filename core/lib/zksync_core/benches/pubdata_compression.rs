@@ -0,0 +1,111 @@
+//! Benchmarks for compression of pubdata dispatched to DA layers.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use zksync_core::da_dispatcher::{
+    NoCompression, PubdataCompression, PubdataCompressor, ZstdCompressor,
+};
+use zksync_types::{
+    writes::{compress_state_diffs, StateDiffRecord},
+    Address, ProtocolVersionId, U256,
+};
+
+const STATE_DIFF_COUNTS: &[usize] = &[1_000, 10_000];
+const ZSTD_LEVELS: &[i32] = &[3, 9, ZstdCompressor::DEFAULT_LEVEL];
+
+/// Generates pubdata resembling L3 traffic: most writes touch a small set of hot contracts
+/// (e.g., token balances), and values change by small amounts.
+fn generate_pubdata(rng: &mut impl Rng, state_diff_count: usize) -> Vec<u8> {
+    let hot_contracts: Vec<Address> = (0..16).map(|_| Address::random()).collect();
+    let state_diffs = (0..state_diff_count).map(|i| {
+        let is_initial = rng.gen_ratio(1, 4);
+        let initial_value = if is_initial {
+            U256::zero()
+        } else {
+            U256::from(rng.gen_range(0_u64..1 << 40))
+        };
+        StateDiffRecord {
+            address: hot_contracts[rng.gen_range(0..hot_contracts.len())],
+            key: U256::from(i),
+            derived_key: rng.gen(),
+            enumeration_index: if is_initial {
+                0
+            } else {
+                rng.gen_range(1..1 << 24)
+            },
+            initial_value,
+            final_value: initial_value + rng.gen_range(1_u64..1 << 20),
+        }
+    });
+    compress_state_diffs(state_diffs.collect())
+}
+
+fn compressors(dictionary: &[u8]) -> Vec<(String, Box<dyn PubdataCompressor>)> {
+    let mut compressors: Vec<(String, Box<dyn PubdataCompressor>)> =
+        vec![("none".to_owned(), Box::new(NoCompression))];
+    for &level in ZSTD_LEVELS {
+        compressors.push((
+            format!("zstd_{level}"),
+            Box::new(ZstdCompressor::new(level)),
+        ));
+        let compressor = ZstdCompressor::new(level).with_dictionary(dictionary.to_vec());
+        compressors.push((format!("zstd_{level}_dict"), Box::new(compressor)));
+    }
+    compressors
+}
+
+fn compression_benches(criterion: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(123);
+    // Raw content dictionary built from pubdata of a "previous" batch.
+    let dictionary = generate_pubdata(&mut rng, 2_000);
+
+    let mut compress_benches = criterion.benchmark_group("compress");
+    for &state_diff_count in STATE_DIFF_COUNTS {
+        let pubdata = generate_pubdata(&mut rng, state_diff_count);
+        for (name, compressor) in compressors(&dictionary) {
+            let compression = PubdataCompression::new(ProtocolVersionId::latest(), compressor);
+            let compressed_len = compression.compress(&pubdata).unwrap().len();
+            println!(
+                "{name}, {state_diff_count} state diffs: {} -> {compressed_len} bytes ({:.2}x)",
+                pubdata.len(),
+                pubdata.len() as f64 / compressed_len as f64
+            );
+
+            compress_benches
+                .bench_with_input(
+                    BenchmarkId::new(&name, state_diff_count),
+                    &pubdata,
+                    |bencher, pubdata| {
+                        bencher.iter(|| compression.compress(pubdata).unwrap());
+                    },
+                )
+                .throughput(Throughput::Bytes(pubdata.len() as u64));
+        }
+    }
+    compress_benches.finish();
+
+    let mut decompress_benches = criterion.benchmark_group("decompress");
+    for &state_diff_count in STATE_DIFF_COUNTS {
+        let pubdata = generate_pubdata(&mut rng, state_diff_count);
+        for (name, compressor) in compressors(&dictionary) {
+            let compression = PubdataCompression::new(ProtocolVersionId::latest(), compressor);
+            decompress_benches
+                .bench_with_input(
+                    BenchmarkId::new(&name, state_diff_count),
+                    &pubdata,
+                    |bencher, pubdata| {
+                        bencher.iter_batched(
+                            || compression.compress(pubdata).unwrap(),
+                            |compressed| compression.decompress(&compressed).unwrap(),
+                            BatchSize::SmallInput,
+                        );
+                    },
+                )
+                .throughput(Throughput::Bytes(pubdata.len() as u64));
+        }
+    }
+    decompress_benches.finish();
+}
+
+criterion_group!(benches, compression_benches);
+criterion_main!(benches);
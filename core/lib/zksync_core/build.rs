@@ -9,4 +9,37 @@ fn main() {
     }
     .generate()
     .unwrap();
+
+    zksync_protobuf_build::Config {
+        input_root: "src/cdc_publisher/proto".into(),
+        proto_root: "zksync/core/cdc_publisher".into(),
+        dependencies: vec![],
+        protobuf_crate: "::zksync_protobuf".parse().unwrap(),
+        is_public: true,
+    }
+    .generate()
+    .unwrap();
+
+    // gRPC client for the shared sequencer.
+    #[cfg(feature = "shared-sequencer")]
+    tonic_build::configure()
+        .build_server(false)
+        .compile(
+            &["src/shared_sequencer/proto/shared_sequencer.proto"],
+            &["src/shared_sequencer/proto"],
+        )
+        .unwrap();
+
+    // gRPC server for the Firehose block stream.
+    #[cfg(feature = "firehose")]
+    tonic_build::configure()
+        .build_client(false)
+        .compile(
+            &[
+                "src/firehose/proto/firehose.proto",
+                "src/firehose/proto/ethereum.proto",
+            ],
+            &["src/firehose/proto"],
+        )
+        .unwrap();
 }
@@ -19,14 +19,15 @@ use tokio::runtime::Handle;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_state::{PostgresStorage, ReadStorage, StoragePtr, StorageView, WriteStorage};
 use zksync_system_constants::{
-    SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
-    SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION, ZKPORTER_IS_AVAILABLE,
+    SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION,
+    SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION, SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+    SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES, ZKPORTER_IS_AVAILABLE,
 };
 use zksync_types::{
     api,
     block::{pack_block_info, unpack_block_info, MiniblockHasher},
     fee_model::BatchFeeInput,
-    get_nonce_key,
+    get_code_key, get_nonce_key,
     utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
     AccountTreeId, L1BatchNumber, MiniblockNumber, Nonce, ProtocolVersionId, StorageKey,
     Transaction, H256, U256,
@@ -34,11 +35,14 @@ use zksync_types::{
 use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{
+    state_cache::SandboxStorage,
+    tree_state::{read_tree_values, TreeReadError, TreeStorage},
     vm_metrics::{self, SandboxStage, SANDBOX_METRICS},
-    BlockArgs, TxExecutionArgs, TxSharedArgs, VmPermit,
+    BlockArgs, PrunedBlockState, TxExecutionArgs, TxSharedArgs, VmPermit,
 };
+use crate::api_server::tree::TreeApiClient;
 
-type BoxedVm<'a> = Box<VmInstance<StorageView<PostgresStorage<'a>>, HistoryDisabled>>;
+type BoxedVm<'a> = Box<VmInstance<StorageView<SandboxStorage<'a>>, HistoryDisabled>>;
 
 #[derive(Debug)]
 struct Sandbox<'a> {
@@ -46,7 +50,9 @@ struct Sandbox<'a> {
     l1_batch_env: L1BatchEnv,
     execution_args: &'a TxExecutionArgs,
     l2_block_info_to_reset: Option<StoredL2BlockInfo>,
-    storage_view: StorageView<PostgresStorage<'a>>,
+    storage_view: StorageView<SandboxStorage<'a>>,
+    /// Error reading from the Merkle tree during VM execution. Only set for pruned blocks.
+    tree_error: Option<TreeReadError>,
 }
 
 impl<'a> Sandbox<'a> {
@@ -55,32 +61,220 @@ impl<'a> Sandbox<'a> {
         shared_args: TxSharedArgs,
         execution_args: &'a TxExecutionArgs,
         block_args: BlockArgs,
+        tx: &Transaction,
     ) -> anyhow::Result<Sandbox<'a>> {
-        let resolve_started_at = Instant::now();
-        let resolved_block_info = block_args
-            .resolve_block_info(&mut connection)
-            .await
-            .with_context(|| format!("cannot resolve block numbers for {block_args:?}"))?;
-        let resolve_time = resolve_started_at.elapsed();
-        // We don't want to emit too many logs.
-        if resolve_time > Duration::from_millis(10) {
-            tracing::debug!("Resolved block numbers (took {resolve_time:?})");
+        if let Some(pruned_state) = block_args.pruned_state {
+            return Self::new_with_tree(
+                connection,
+                shared_args,
+                execution_args,
+                &block_args,
+                pruned_state,
+                tx,
+            )
+            .await;
         }
 
+        let warm_env = shared_args
+            .warm_pool
+            .as_ref()
+            .and_then(|pool| pool.get(&block_args));
+        let (resolved_block_info, next_l2_block_info, l2_block_info_to_reset) =
+            if let Some(warm_env) = &warm_env {
+                let resolved_block_info = warm_env.resolved_block_info(&block_args)?;
+                let (next_l2_block_info, l2_block_info_to_reset) = l2_block_env(
+                    block_args.is_pending_miniblock(),
+                    &resolved_block_info,
+                    warm_env.current_l2_block_info,
+                    warm_env.prev_l2_block_info,
+                );
+                (
+                    resolved_block_info,
+                    next_l2_block_info,
+                    l2_block_info_to_reset,
+                )
+            } else {
+                let resolve_started_at = Instant::now();
+                let resolved_block_info = block_args
+                    .resolve_block_info(&mut connection)
+                    .await
+                    .with_context(|| format!("cannot resolve block numbers for {block_args:?}"))?;
+                let resolve_time = resolve_started_at.elapsed();
+                // We don't want to emit too many logs.
+                if resolve_time > Duration::from_millis(10) {
+                    tracing::debug!("Resolved block numbers (took {resolve_time:?})");
+                }
+
+                let (next_l2_block_info, l2_block_info_to_reset) = Self::load_l2_block_info(
+                    &mut connection,
+                    block_args.is_pending_miniblock(),
+                    &resolved_block_info,
+                )
+                .await?;
+                (
+                    resolved_block_info,
+                    next_l2_block_info,
+                    l2_block_info_to_reset,
+                )
+            };
+
         if block_args.resolves_to_latest_sealed_miniblock() {
             shared_args
                 .caches
                 .schedule_values_update(resolved_block_info.state_l2_block_number);
         }
 
-        let (next_l2_block_info, l2_block_info_to_reset) = Self::load_l2_block_info(
-            &mut connection,
-            block_args.is_pending_miniblock(),
+        let warm_state_overlay = warm_env
+            .as_ref()
+            .and_then(|warm_env| warm_env.state_overlay.as_ref());
+        let cache_snapshot = match (&shared_args.state_cache, warm_state_overlay) {
+            (Some(state_cache), Some(overlay)) => state_cache.snapshot_with_overlay(overlay),
+            (Some(state_cache), None) => state_cache
+                .snapshot(
+                    &mut connection,
+                    &block_args,
+                    resolved_block_info.state_l2_block_number,
+                )
+                .await
+                .context("failed taking API state cache snapshot")?,
+            (None, _) => None,
+        };
+
+        let storage = if let Some(warm_env) = &warm_env {
+            warm_env.postgres_storage(connection)
+        } else {
+            PostgresStorage::new_async(
+                Handle::current(),
+                connection,
+                resolved_block_info.state_l2_block_number,
+                false,
+            )
+            .await
+            .context("cannot create `PostgresStorage`")?
+        };
+        let storage = storage.with_caches(shared_args.caches.clone());
+
+        let storage_view = StorageView::new(SandboxStorage::new(storage, cache_snapshot));
+        let (system_env, l1_batch_env) = Self::prepare_env(
+            shared_args,
+            execution_args,
             &resolved_block_info,
-        )
-        .await?;
+            next_l2_block_info,
+        );
+
+        Ok(Self {
+            system_env,
+            l1_batch_env,
+            storage_view,
+            execution_args,
+            l2_block_info_to_reset,
+            tree_error: None,
+        })
+    }
 
-        let storage = PostgresStorage::new_async(
+    /// Creates a sandbox for a pruned block, the state for which is read from the Merkle tree and (for blocks
+    /// within an L1 batch) from the storage logs retained by the pruner.
+    async fn new_with_tree(
+        mut connection: StorageProcessor<'a>,
+        shared_args: TxSharedArgs,
+        execution_args: &'a TxExecutionArgs,
+        block_args: &BlockArgs,
+        pruned_state: PrunedBlockState,
+        tx: &Transaction,
+    ) -> anyhow::Result<Sandbox<'a>> {
+        let tree = shared_args
+            .archive_tree
+            .clone()
+            .context("Merkle tree is required to execute VM for pruned blocks")?;
+        let l1_batch_number = pruned_state.l1_batch_number();
+        let l1_batch_header = connection
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await
+            .with_context(|| format!("failed getting header for L1 batch #{l1_batch_number}"))?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not present in storage"))?;
+        let protocol_version = l1_batch_header
+            .protocol_version
+            .unwrap_or(ProtocolVersionId::last_potentially_undefined());
+        let l1_batch_timestamp = block_args
+            .l1_batch_timestamp_s
+            .context("L1 batch timestamp is `None` for pruned block args")?;
+        // Fee inputs of pruned miniblocks are recorded for their L1 batches by the pruner. They may be missing
+        // for batches pruned before fee inputs were recorded; in this case, the current fee input is used.
+        let historical_fee_input = connection
+            .pruning_dal()
+            .get_pruned_l1_batch_fee_input(l1_batch_number)
+            .await
+            .with_context(|| format!("failed getting fee input for L1 batch #{l1_batch_number}"))?;
+        let miniblock_number = block_args.resolved_block_number;
+
+        let (resolved_block_info, next_l2_block_info, l2_block_info_to_reset, tree_l1_batch) =
+            match pruned_state {
+                PrunedBlockState::EndOfL1Batch(_) => {
+                    let current_l2_block_info = StoredL2BlockInfo::from_tree(
+                        tree.as_ref(),
+                        l1_batch_number,
+                        miniblock_number,
+                        protocol_version,
+                    )
+                    .await
+                    .context("failed reading L2 block info from Merkle tree")?;
+                    let resolved_block_info = ResolvedBlockInfo {
+                        state_l2_block_number: miniblock_number,
+                        state_l2_block_hash: current_l2_block_info.l2_block_hash,
+                        vm_l1_batch_number: l1_batch_number + 1,
+                        l1_batch_timestamp,
+                        protocol_version,
+                        historical_fee_input,
+                    };
+                    // The tree only provides the state after the pruned miniblock, so the VM is executed
+                    // in the next miniblock.
+                    let (next_l2_block_info, _) =
+                        l2_block_env(true, &resolved_block_info, current_l2_block_info, None);
+                    (
+                        resolved_block_info,
+                        next_l2_block_info,
+                        None,
+                        l1_batch_number,
+                    )
+                }
+                PrunedBlockState::WithinL1Batch(_) => {
+                    let (current_l2_block_info, prev_l2_block_info) =
+                        StoredL2BlockInfo::load_retained_with_prev(
+                            &mut connection,
+                            miniblock_number,
+                            protocol_version,
+                        )
+                        .await?;
+                    let resolved_block_info = ResolvedBlockInfo {
+                        state_l2_block_number: miniblock_number,
+                        state_l2_block_hash: current_l2_block_info.l2_block_hash,
+                        vm_l1_batch_number: l1_batch_number,
+                        l1_batch_timestamp,
+                        protocol_version,
+                        historical_fee_input,
+                    };
+                    let (next_l2_block_info, l2_block_info_to_reset) = l2_block_env(
+                        false,
+                        &resolved_block_info,
+                        current_l2_block_info,
+                        prev_l2_block_info,
+                    );
+                    // Enumeration indices must not account for writes in the batch the VM is executed in.
+                    let tree_l1_batch = l1_batch_number
+                        .0
+                        .checked_sub(1)
+                        .context("genesis L1 batch cannot be pruned")?;
+                    (
+                        resolved_block_info,
+                        next_l2_block_info,
+                        l2_block_info_to_reset,
+                        L1BatchNumber(tree_l1_batch),
+                    )
+                }
+            };
+
+        let postgres = PostgresStorage::new_async(
             Handle::current(),
             connection,
             resolved_block_info.state_l2_block_number,
@@ -89,8 +283,27 @@ impl<'a> Sandbox<'a> {
         .await
         .context("cannot create `PostgresStorage`")?
         .with_caches(shared_args.caches.clone());
-
-        let storage_view = StorageView::new(storage);
+        let mut tree_storage = TreeStorage::new(Handle::current(), tree, tree_l1_batch);
+        // Load entries that are always accessed by the VM with a single tree request.
+        let initiator = tx.initiator_account();
+        let prefetched_keys = [
+            get_nonce_key(&initiator),
+            storage_key_for_eth_balance(&initiator),
+            storage_key_for_eth_balance(&tx.payer()),
+            get_code_key(&tx.execute.contract_address),
+        ];
+        tree_storage
+            .prefetch(&prefetched_keys)
+            .await
+            .context("failed reading VM state from Merkle tree")?;
+        let tree_error = tree_storage.error();
+        let storage = SandboxStorage::new(postgres, None);
+        let storage = match pruned_state {
+            PrunedBlockState::EndOfL1Batch(_) => storage.with_tree(tree_storage),
+            PrunedBlockState::WithinL1Batch(_) => {
+                storage.with_tree_enumeration_indices(tree_storage)
+            }
+        };
         let (system_env, l1_batch_env) = Self::prepare_env(
             shared_args,
             execution_args,
@@ -101,9 +314,10 @@ impl<'a> Sandbox<'a> {
         Ok(Self {
             system_env,
             l1_batch_env,
-            storage_view,
+            storage_view: StorageView::new(storage),
             execution_args,
             l2_block_info_to_reset,
+            tree_error: Some(tree_error),
         })
     }
 
@@ -112,56 +326,24 @@ impl<'a> Sandbox<'a> {
         is_pending_block: bool,
         resolved_block_info: &ResolvedBlockInfo,
     ) -> anyhow::Result<(L2BlockEnv, Option<StoredL2BlockInfo>)> {
-        let mut l2_block_info_to_reset = None;
-        let current_l2_block_info = StoredL2BlockInfo::new(
-            connection,
-            resolved_block_info.state_l2_block_number,
-            Some(resolved_block_info.state_l2_block_hash),
-        )
-        .await
-        .context("failed reading L2 block info")?;
-
-        let next_l2_block_info = if is_pending_block {
-            L2BlockEnv {
-                number: current_l2_block_info.l2_block_number + 1,
-                timestamp: resolved_block_info.l1_batch_timestamp,
-                prev_block_hash: current_l2_block_info.l2_block_hash,
-                // For simplicity we assume each miniblock create one virtual block.
-                // This may be wrong only during transition period.
-                max_virtual_blocks_to_create: 1,
-            }
-        } else if current_l2_block_info.l2_block_number == 0 {
-            // Special case:
-            // - For environments, where genesis block was created before virtual block upgrade it doesn't matter what we put here.
-            // - Otherwise, we need to put actual values here. We cannot create next L2 block with block_number=0 and `max_virtual_blocks_to_create=0`
-            //   because of SystemContext requirements. But, due to intrinsics of SystemContext, block.number still will be resolved to 0.
-            L2BlockEnv {
-                number: 1,
-                timestamp: 0,
-                prev_block_hash: MiniblockHasher::legacy_hash(MiniblockNumber(0)),
-                max_virtual_blocks_to_create: 1,
-            }
+        let miniblock_number = resolved_block_info.state_l2_block_number;
+        let miniblock_hash = resolved_block_info.state_l2_block_hash;
+        let (current_l2_block_info, prev_l2_block_info) = if is_pending_block {
+            let current_l2_block_info =
+                StoredL2BlockInfo::new(connection, miniblock_number, Some(miniblock_hash))
+                    .await
+                    .context("failed reading L2 block info")?;
+            (current_l2_block_info, None)
         } else {
-            // We need to reset L2 block info in storage to process transaction in the current block context.
-            // Actual resetting will be done after `storage_view` is created.
-            let prev_l2_block_info = StoredL2BlockInfo::new(
-                connection,
-                resolved_block_info.state_l2_block_number - 1,
-                None,
-            )
-            .await
-            .context("failed reading previous L2 block info")?;
-
-            l2_block_info_to_reset = Some(prev_l2_block_info);
-            L2BlockEnv {
-                number: current_l2_block_info.l2_block_number,
-                timestamp: current_l2_block_info.l2_block_timestamp,
-                prev_block_hash: prev_l2_block_info.l2_block_hash,
-                max_virtual_blocks_to_create: 1,
-            }
+            StoredL2BlockInfo::load_with_prev(connection, miniblock_number, miniblock_hash).await?
         };
 
-        Ok((next_l2_block_info, l2_block_info_to_reset))
+        Ok(l2_block_env(
+            is_pending_block,
+            resolved_block_info,
+            current_l2_block_info,
+            prev_l2_block_info,
+        ))
     }
 
     /// This method is blocking.
@@ -259,7 +441,7 @@ impl<'a> Sandbox<'a> {
         mut self,
         tx: &Transaction,
         adjust_pubdata_price: bool,
-    ) -> (BoxedVm<'a>, StoragePtr<StorageView<PostgresStorage<'a>>>) {
+    ) -> (BoxedVm<'a>, StoragePtr<StorageView<SandboxStorage<'a>>>) {
         self.setup_storage_view(tx);
         let protocol_version = self.system_env.version;
         if adjust_pubdata_price {
@@ -281,6 +463,51 @@ impl<'a> Sandbox<'a> {
     }
 }
 
+/// Returns the L2 block environment for the VM and the stored L2 block info to reset in the VM storage, if any.
+/// `prev_l2_block_info` must be set unless `is_pending_block` is set or the current L2 block is the genesis one.
+fn l2_block_env(
+    is_pending_block: bool,
+    resolved_block_info: &ResolvedBlockInfo,
+    current_l2_block_info: StoredL2BlockInfo,
+    prev_l2_block_info: Option<StoredL2BlockInfo>,
+) -> (L2BlockEnv, Option<StoredL2BlockInfo>) {
+    if is_pending_block {
+        let next_l2_block_info = L2BlockEnv {
+            number: current_l2_block_info.l2_block_number + 1,
+            timestamp: resolved_block_info.l1_batch_timestamp,
+            prev_block_hash: current_l2_block_info.l2_block_hash,
+            // For simplicity we assume each miniblock create one virtual block.
+            // This may be wrong only during transition period.
+            max_virtual_blocks_to_create: 1,
+        };
+        (next_l2_block_info, None)
+    } else if current_l2_block_info.l2_block_number == 0 {
+        // Special case:
+        // - For environments, where genesis block was created before virtual block upgrade it doesn't matter what we put here.
+        // - Otherwise, we need to put actual values here. We cannot create next L2 block with block_number=0 and `max_virtual_blocks_to_create=0`
+        //   because of SystemContext requirements. But, due to intrinsics of SystemContext, block.number still will be resolved to 0.
+        let next_l2_block_info = L2BlockEnv {
+            number: 1,
+            timestamp: 0,
+            prev_block_hash: MiniblockHasher::legacy_hash(MiniblockNumber(0)),
+            max_virtual_blocks_to_create: 1,
+        };
+        (next_l2_block_info, None)
+    } else {
+        // We need to reset L2 block info in storage to process transaction in the current block context.
+        // Actual resetting will be done after `storage_view` is created.
+        let prev_l2_block_info = prev_l2_block_info
+            .expect("previous L2 block info must be loaded for non-pending blocks");
+        let next_l2_block_info = L2BlockEnv {
+            number: current_l2_block_info.l2_block_number,
+            timestamp: current_l2_block_info.l2_block_timestamp,
+            prev_block_hash: prev_l2_block_info.l2_block_hash,
+            max_virtual_blocks_to_create: 1,
+        };
+        (next_l2_block_info, Some(prev_l2_block_info))
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(super) fn apply_vm_in_sandbox<T>(
     vm_permit: VmPermit,
@@ -294,7 +521,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
     tx: Transaction,
     block_args: BlockArgs,
     apply: impl FnOnce(
-        &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
+        &mut VmInstance<StorageView<SandboxStorage<'_>>, HistoryDisabled>,
         Transaction,
     ) -> T,
 ) -> anyhow::Result<T> {
@@ -316,7 +543,9 @@ pub(super) fn apply_vm_in_sandbox<T>(
         shared_args,
         execution_args,
         block_args,
+        &tx,
     ))?;
+    let tree_error = sandbox.tree_error.clone();
     let (mut vm, storage_view) = sandbox.into_vm(&tx, adjust_pubdata_price);
 
     SANDBOX_METRICS.sandbox[&SandboxStage::Initialization].observe(stage_started_at.elapsed());
@@ -338,11 +567,17 @@ pub(super) fn apply_vm_in_sandbox<T>(
         vm_execution_took,
         storage_view.as_ref().borrow_mut().metrics(),
     );
+    if let Some(tree_error) = tree_error {
+        // The VM may have read placeholder values if the tree failed, so the result cannot be trusted.
+        tree_error
+            .check()
+            .context("failed reading VM state from Merkle tree")?;
+    }
     Ok(result)
 }
 
-#[derive(Debug, Clone, Copy)]
-struct StoredL2BlockInfo {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct StoredL2BlockInfo {
     l2_block_number: u32,
     l2_block_timestamp: u64,
     l2_block_hash: H256,
@@ -351,7 +586,7 @@ struct StoredL2BlockInfo {
 
 impl StoredL2BlockInfo {
     /// If `miniblock_hash` is `None`, it needs to be fetched from the storage.
-    async fn new(
+    pub(super) async fn new(
         connection: &mut StorageProcessor<'_>,
         miniblock_number: MiniblockNumber,
         miniblock_hash: Option<H256>,
@@ -395,20 +630,152 @@ impl StoredL2BlockInfo {
             txs_rolling_hash,
         })
     }
+
+    /// Returns keys of the system context slots storing info for `miniblock_number`: the packed block number
+    /// and timestamp, the rolling hash of block transactions, and the hash of the previous block.
+    fn storage_keys(miniblock_number: MiniblockNumber) -> [StorageKey; 3] {
+        let l2_block_info_key = StorageKey::new(
+            AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+            SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+        );
+        let l2_block_txs_rolling_hash_key = StorageKey::new(
+            AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+            SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+        );
+        // Hashes of recent L2 blocks are stored in a ring buffer.
+        let prev_l2_block_number = miniblock_number.0.saturating_sub(1);
+        let prev_l2_block_hash_position =
+            h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+                + U256::from(prev_l2_block_number % SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES);
+        let prev_l2_block_hash_key = StorageKey::new(
+            AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+            u256_to_h256(prev_l2_block_hash_position),
+        );
+        [
+            l2_block_info_key,
+            l2_block_txs_rolling_hash_key,
+            prev_l2_block_hash_key,
+        ]
+    }
+
+    /// Creates info from the values for [`Self::storage_keys()`]. The miniblock hash is computed
+    /// from the stored block data.
+    fn from_storage_values(
+        miniblock_number: MiniblockNumber,
+        protocol_version: ProtocolVersionId,
+        [l2_block_info, txs_rolling_hash, prev_l2_block_hash]: [H256; 3],
+    ) -> anyhow::Result<Self> {
+        let (l2_block_number, l2_block_timestamp) = unpack_block_info(h256_to_u256(l2_block_info));
+        anyhow::ensure!(
+            l2_block_number == u64::from(miniblock_number.0),
+            "unexpected L2 block in VM state: expected {miniblock_number}, got {l2_block_number}"
+        );
+
+        let l2_block_hash = if miniblock_number == MiniblockNumber(0) {
+            MiniblockHasher::legacy_hash(miniblock_number)
+        } else {
+            MiniblockHasher::with_txs_rolling_hash(
+                miniblock_number,
+                l2_block_timestamp,
+                prev_l2_block_hash,
+                txs_rolling_hash,
+            )
+            .finalize(protocol_version)
+        };
+        Ok(Self {
+            l2_block_number: miniblock_number.0,
+            l2_block_timestamp,
+            l2_block_hash,
+            txs_rolling_hash,
+        })
+    }
+
+    /// Reads info for `miniblock_number`, which must be the last miniblock in the specified L1 batch,
+    /// from the Merkle tree. The miniblock hash is computed from the stored block data.
+    pub(super) async fn from_tree(
+        tree: &dyn TreeApiClient,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<Self> {
+        let keys = Self::storage_keys(miniblock_number);
+        let values = read_tree_values(tree, l1_batch_number, &keys).await?;
+        let values = <[H256; 3]>::try_from(values)
+            .map_err(|values| anyhow::anyhow!("unexpected number of tree values: {values:?}"))?;
+        Self::from_storage_values(miniblock_number, protocol_version, values)
+            .with_context(|| format!("invalid last miniblock in L1 batch #{l1_batch_number}"))
+    }
+
+    /// Reads info for a pruned `miniblock_number` from the storage logs retained by the pruner.
+    /// The miniblock hash is computed from the stored block data.
+    async fn from_retained_state(
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<Self> {
+        let mut values = [H256::zero(); 3];
+        for (value, key) in values.iter_mut().zip(Self::storage_keys(miniblock_number)) {
+            *value = connection
+                .storage_web3_dal()
+                .get_historical_value_unchecked(&key, miniblock_number)
+                .await
+                .context("failed reading L2 block info from VM state")?;
+        }
+        Self::from_storage_values(miniblock_number, protocol_version, values)
+    }
+
+    /// Same as [`Self::load_with_prev()`], but for a pruned miniblock within an L1 batch, the state for which
+    /// is retained by the pruner.
+    pub(super) async fn load_retained_with_prev(
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<(Self, Option<Self>)> {
+        let current = Self::from_retained_state(connection, miniblock_number, protocol_version)
+            .await
+            .context("failed reading L2 block info")?;
+        if current.l2_block_number == 0 {
+            return Ok((current, None));
+        }
+        let prev = Self::from_retained_state(connection, miniblock_number - 1, protocol_version)
+            .await
+            .context("failed reading previous L2 block info")?;
+        Ok((current, Some(prev)))
+    }
+
+    /// Loads info for the specified miniblock together with info for the previous miniblock, which is required
+    /// to execute transactions in the context of a non-pending miniblock. The previous info is `None`
+    /// for the genesis L2 block.
+    pub(super) async fn load_with_prev(
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        miniblock_hash: H256,
+    ) -> anyhow::Result<(Self, Option<Self>)> {
+        let current = Self::new(connection, miniblock_number, Some(miniblock_hash))
+            .await
+            .context("failed reading L2 block info")?;
+        if current.l2_block_number == 0 {
+            return Ok((current, None));
+        }
+        let prev = Self::new(connection, miniblock_number - 1, None)
+            .await
+            .context("failed reading previous L2 block info")?;
+        Ok((current, Some(prev)))
+    }
 }
 
 #[derive(Debug)]
-struct ResolvedBlockInfo {
-    state_l2_block_number: MiniblockNumber,
-    state_l2_block_hash: H256,
-    vm_l1_batch_number: L1BatchNumber,
-    l1_batch_timestamp: u64,
-    protocol_version: ProtocolVersionId,
-    historical_fee_input: Option<BatchFeeInput>,
+pub(super) struct ResolvedBlockInfo {
+    pub(super) state_l2_block_number: MiniblockNumber,
+    pub(super) state_l2_block_hash: H256,
+    pub(super) vm_l1_batch_number: L1BatchNumber,
+    pub(super) l1_batch_timestamp: u64,
+    pub(super) protocol_version: ProtocolVersionId,
+    pub(super) historical_fee_input: Option<BatchFeeInput>,
 }
 
 impl BlockArgs {
-    fn is_pending_miniblock(&self) -> bool {
+    pub(super) fn is_pending_miniblock(&self) -> bool {
         matches!(
             self.block_id,
             api::BlockId::Number(api::BlockNumber::Pending)
@@ -424,7 +791,7 @@ impl BlockArgs {
         )
     }
 
-    async fn resolve_block_info(
+    pub(super) async fn resolve_block_info(
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<ResolvedBlockInfo> {
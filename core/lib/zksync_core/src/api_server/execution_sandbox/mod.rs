@@ -13,24 +13,31 @@ use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 use self::vm_metrics::SandboxStage;
 pub(super) use self::{
     error::SandboxExecutionError,
-    execute::{TransactionExecutor, TxExecutionArgs},
+    execute::{TransactionExecutionOutput, TransactionExecutor, TxExecutionArgs},
     tracers::ApiTracer,
     validate::ValidationError,
     vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
 };
-use super::tx_sender::MultiVMBaseSystemContracts;
+pub use self::{
+    state_cache::{ApiStateCache, ApiStateCacheUpdater},
+    warm_pool::{SandboxWarmPool, SandboxWarmPoolUpdater},
+};
+use super::{tree::TreeApiClient, tx_sender::MultiVMBaseSystemContracts};
 
 // Note: keep the modules private, and instead re-export functions that make public interface.
 mod apply;
 mod error;
 mod execute;
+mod state_cache;
 #[cfg(test)]
-pub(super) mod testonly;
+pub(crate) mod testonly;
 #[cfg(test)]
 mod tests;
 mod tracers;
+mod tree_state;
 mod validate;
 mod vm_metrics;
+mod warm_pool;
 
 /// Permit to invoke VM code.
 ///
@@ -215,6 +222,9 @@ pub(crate) struct TxSharedArgs {
     pub fee_input: BatchFeeInput,
     pub base_system_contracts: MultiVMBaseSystemContracts,
     pub caches: PostgresStorageCaches,
+    pub state_cache: Option<ApiStateCache>,
+    pub warm_pool: Option<SandboxWarmPool>,
+    pub archive_tree: Option<Arc<dyn TreeApiClient>>,
     pub validation_computational_gas_limit: u32,
     pub chain_id: L2ChainId,
 }
@@ -234,6 +244,9 @@ impl TxSharedArgs {
             fee_input: BatchFeeInput::l1_pegged(55, 555),
             base_system_contracts,
             caches,
+            state_cache: None,
+            warm_pool: None,
+            archive_tree: None,
             validation_computational_gas_limit: u32::MAX,
             chain_id: L2ChainId::default(),
         }
@@ -257,11 +270,23 @@ impl BlockStartInfo {
             .await
             .context("failed getting snapshot recovery status")?;
         let snapshot_recovery = snapshot_recovery.as_ref();
+        let pruning_info = storage
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+
+        let first_miniblock =
+            snapshot_recovery.map_or(MiniblockNumber(0), |recovery| recovery.miniblock_number + 1);
+        let first_l1_batch =
+            snapshot_recovery.map_or(L1BatchNumber(0), |recovery| recovery.l1_batch_number + 1);
         Ok(Self {
-            first_miniblock: snapshot_recovery
-                .map_or(MiniblockNumber(0), |recovery| recovery.miniblock_number + 1),
-            first_l1_batch: snapshot_recovery
-                .map_or(L1BatchNumber(0), |recovery| recovery.l1_batch_number + 1),
+            first_miniblock: pruning_info
+                .last_pruned_miniblock
+                .map_or(first_miniblock, |number| first_miniblock.max(number + 1)),
+            first_l1_batch: pruning_info
+                .last_pruned_l1_batch
+                .map_or(first_l1_batch, |number| first_l1_batch.max(number + 1)),
         })
     }
 
@@ -288,6 +313,10 @@ impl BlockStartInfo {
 pub(crate) enum BlockArgsError {
     #[error("Block is pruned; first retained block is {0}")]
     Pruned(MiniblockNumber),
+    #[error(
+        "State of pruned block {0} is not available; closest block with available state is {1}"
+    )]
+    PrunedStateUnavailable(MiniblockNumber, MiniblockNumber),
     #[error("Block is missing, but can appear in the future")]
     Missing,
     #[error("Database error")]
@@ -295,11 +324,31 @@ pub(crate) enum BlockArgsError {
 }
 
 /// Information about a block provided to VM.
+/// Source of the VM state for a pruned block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PrunedBlockState {
+    /// The block is the last one in the specified L1 batch; the state after it is read from the Merkle tree.
+    EndOfL1Batch(L1BatchNumber),
+    /// The block is within the specified L1 batch. Storage values are read from the storage logs retained
+    /// by the pruner, and enumeration indices from the Merkle tree as of the end of the previous L1 batch.
+    WithinL1Batch(L1BatchNumber),
+}
+
+impl PrunedBlockState {
+    pub(crate) fn l1_batch_number(self) -> L1BatchNumber {
+        match self {
+            Self::EndOfL1Batch(number) | Self::WithinL1Batch(number) => number,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct BlockArgs {
     block_id: api::BlockId,
     resolved_block_number: MiniblockNumber,
     l1_batch_timestamp_s: Option<u64>,
+    /// Source of the VM state. Only set for pruned blocks.
+    pruned_state: Option<PrunedBlockState>,
 }
 
 impl BlockArgs {
@@ -309,6 +358,7 @@ impl BlockArgs {
             block_id,
             resolved_block_number,
             l1_batch_timestamp_s: None,
+            pruned_state: None,
         })
     }
 
@@ -355,6 +405,7 @@ impl BlockArgs {
             block_id,
             resolved_block_number,
             l1_batch_timestamp_s: Some(l1_batch_timestamp),
+            pruned_state: None,
         })
     }
 
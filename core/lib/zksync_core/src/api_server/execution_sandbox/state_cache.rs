@@ -0,0 +1,507 @@
+//! RocksDB replica of the VM state used by the API server to serve storage reads for VM invocations
+//! (`eth_call`, `eth_estimateGas` etc.) at the latest block instead of querying Postgres.
+//!
+//! The replica has the same layout as the state keeper cache, but is stored in a separate RocksDB instance,
+//! so that it doesn't interfere with the state keeper. It is updated by [`ApiStateCacheUpdater`] each time
+//! a new L1 batch is sealed. Since the replica only contains the state as of the end of the last processed
+//! L1 batch, storage logs of miniblocks in the following L1 batch are loaded from Postgres into an in-memory
+//! overlay, which is extended incrementally as new miniblocks are sealed.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use tokio::sync::{watch, OwnedRwLockReadGuard, RwLock};
+use zksync_config::configs::database::RocksdbProfile;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_state::{PostgresStorage, ReadStorage, RocksbStorageBuilder, RocksdbStorage};
+use zksync_storage::RocksDBOptions;
+use zksync_types::{L1BatchNumber, MiniblockNumber, StorageKey, StorageValue, H256};
+
+use super::{
+    tree_state::TreeStorage,
+    vm_metrics::{StateCacheMissReason, STATE_CACHE_METRICS},
+    BlockArgs,
+};
+use crate::utils::rocksdb_options;
+
+/// RocksDB replica together with the next L1 batch to be loaded into it.
+#[derive(Debug)]
+struct SyncedStorage {
+    storage: RocksdbStorage,
+    next_l1_batch: L1BatchNumber,
+}
+
+/// Latest storage values for miniblocks in the L1 batch following the last L1 batch processed by the replica.
+pub(super) struct MiniblockOverlay {
+    l1_batch_number: L1BatchNumber,
+    last_miniblock: MiniblockNumber,
+    values: HashMap<H256, H256>,
+}
+
+impl fmt::Debug for MiniblockOverlay {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("MiniblockOverlay")
+            .field("l1_batch_number", &self.l1_batch_number)
+            .field("last_miniblock", &self.last_miniblock)
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
+
+#[derive(Debug, Default)]
+struct ApiStateCacheInner {
+    /// `None` until the replica is synced with Postgres for the first time.
+    storage: Arc<RwLock<Option<SyncedStorage>>>,
+    overlay: Mutex<Option<Arc<MiniblockOverlay>>>,
+}
+
+/// RocksDB-backed cache of the VM state used by the API server for VM invocations at the latest block.
+/// If the cache cannot be used (e.g., it's not initialized yet, or is being updated), VM invocations
+/// transparently fall back to reading storage from Postgres.
+#[derive(Debug, Clone)]
+pub struct ApiStateCache {
+    inner: Arc<ApiStateCacheInner>,
+}
+
+impl ApiStateCache {
+    /// Creates a new cache backed by RocksDB at the specified path, together with the task updating it.
+    /// RocksDB is tuned in the same way as the state keeper cache. The cache will not be used until
+    /// the update task is run.
+    pub fn new(
+        db_path: PathBuf,
+        block_cache_capacity: Option<usize>,
+        profile: &RocksdbProfile,
+    ) -> (Self, ApiStateCacheUpdater) {
+        let this = Self {
+            inner: Arc::default(),
+        };
+        let updater = ApiStateCacheUpdater {
+            db_path,
+            options: RocksDBOptions {
+                block_cache_capacity,
+                ..rocksdb_options(profile)
+            },
+            cache: this.clone(),
+            builder: None,
+            next_l1_batch: None,
+        };
+        (this, updater)
+    }
+
+    /// Takes a snapshot of the cached state as of `miniblock_number`. Returns `Ok(None)` if the cache
+    /// cannot be used for the specified block.
+    pub(super) async fn snapshot(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block_args: &BlockArgs,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<Option<StateCacheSnapshot>> {
+        let snapshot = self
+            .try_snapshot(connection, block_args, miniblock_number)
+            .await?;
+        match &snapshot {
+            Ok(_) => STATE_CACHE_METRICS.hits.inc(),
+            Err(reason) => STATE_CACHE_METRICS.misses[reason].inc(),
+        };
+        Ok(snapshot.ok())
+    }
+
+    /// Takes a snapshot of the cached state using the miniblock overlay returned by [`Self::warm_up()`].
+    /// Unlike [`Self::snapshot()`], doesn't query Postgres. Returns `None` if the cache cannot be used
+    /// with the overlay (e.g., if the cache was updated after the overlay was loaded).
+    pub(super) fn snapshot_with_overlay(
+        &self,
+        overlay: &Arc<MiniblockOverlay>,
+    ) -> Option<StateCacheSnapshot> {
+        let snapshot = self.try_snapshot_with_overlay(overlay);
+        match &snapshot {
+            Ok(_) => STATE_CACHE_METRICS.hits.inc(),
+            Err(reason) => STATE_CACHE_METRICS.misses[reason].inc(),
+        };
+        snapshot.ok()
+    }
+
+    fn try_snapshot_with_overlay(
+        &self,
+        overlay: &Arc<MiniblockOverlay>,
+    ) -> Result<StateCacheSnapshot, StateCacheMissReason> {
+        let Ok(guard) = Arc::clone(&self.inner.storage).try_read_owned() else {
+            return Err(StateCacheMissReason::Updating);
+        };
+        let Some(synced) = guard.as_ref() else {
+            return Err(StateCacheMissReason::NotReady);
+        };
+        if synced.next_l1_batch != overlay.l1_batch_number {
+            return Err(StateCacheMissReason::L1BatchMismatch);
+        }
+        let storage = synced.storage.read_only_view();
+        Ok(StateCacheSnapshot {
+            storage,
+            overlay: overlay.clone(),
+            _guard: guard,
+        })
+    }
+
+    /// Loads the miniblock overlay as of `miniblock_number` without taking a snapshot, so that it's ready
+    /// for the following VM invocations. Returns the overlay, or `None` if the cache cannot be used
+    /// for the miniblock. Doesn't update hit / miss metrics.
+    pub(super) async fn warm_up(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<Option<Arc<MiniblockOverlay>>> {
+        let block_args = BlockArgs::pending(connection).await?;
+        let snapshot = self
+            .try_snapshot(connection, &block_args, miniblock_number)
+            .await?;
+        Ok(snapshot.ok().map(|snapshot| snapshot.overlay))
+    }
+
+    async fn try_snapshot(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block_args: &BlockArgs,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<Result<StateCacheSnapshot, StateCacheMissReason>> {
+        if !block_args.resolves_to_latest_sealed_miniblock() {
+            return Ok(Err(StateCacheMissReason::HistoricalBlock));
+        }
+        // If the cache is being updated, we don't wait for the update to finish (which may take a while
+        // if the cache needs to catch up), and instead fall back to Postgres.
+        let Ok(guard) = Arc::clone(&self.inner.storage).try_read_owned() else {
+            return Ok(Err(StateCacheMissReason::Updating));
+        };
+        let Some(synced) = guard.as_ref() else {
+            return Ok(Err(StateCacheMissReason::NotReady));
+        };
+
+        let resolved = connection
+            .storage_web3_dal()
+            .resolve_l1_batch_number_of_miniblock(miniblock_number)
+            .await
+            .with_context(|| {
+                format!("failed resolving L1 batch number for miniblock #{miniblock_number}")
+            })?;
+        let l1_batch_number = synced.next_l1_batch;
+        if resolved.expected_l1_batch() != l1_batch_number {
+            return Ok(Err(StateCacheMissReason::L1BatchMismatch));
+        }
+
+        let overlay = self
+            .load_overlay(connection, l1_batch_number, miniblock_number)
+            .await?;
+        let Some(overlay) = overlay else {
+            return Ok(Err(StateCacheMissReason::NoMiniblocks));
+        };
+        let storage = synced.storage.read_only_view();
+        Ok(Ok(StateCacheSnapshot {
+            storage,
+            overlay,
+            _guard: guard,
+        }))
+    }
+
+    /// Loads the overlay for miniblocks in `l1_batch_number` up to and including `miniblock_number`,
+    /// or extends the cached overlay if possible.
+    async fn load_overlay(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<Option<Arc<MiniblockOverlay>>> {
+        let cached = self
+            .inner
+            .overlay
+            .lock()
+            .expect("overlay mutex poisoned")
+            .clone();
+        let cached = cached.filter(|overlay| overlay.l1_batch_number == l1_batch_number);
+        let overlay = match cached {
+            Some(overlay) if overlay.last_miniblock == miniblock_number => {
+                return Ok(Some(overlay));
+            }
+            Some(overlay) if overlay.last_miniblock < miniblock_number => {
+                let latency = STATE_CACHE_METRICS.overlay_load_latency.start();
+                let new_values = connection
+                    .storage_web3_dal()
+                    .get_latest_values_in_miniblocks(overlay.last_miniblock + 1..=miniblock_number)
+                    .await
+                    .context("failed loading storage values for miniblocks")?;
+                latency.observe();
+
+                let mut values = overlay.values.clone();
+                values.extend(new_values);
+                MiniblockOverlay {
+                    l1_batch_number,
+                    last_miniblock: miniblock_number,
+                    values,
+                }
+            }
+            _ => {
+                let first_miniblock = if l1_batch_number == L1BatchNumber(0) {
+                    MiniblockNumber(0)
+                } else {
+                    let prev_l1_batch = l1_batch_number - 1;
+                    let miniblock_range = connection
+                        .blocks_dal()
+                        .get_miniblock_range_of_l1_batch(prev_l1_batch)
+                        .await
+                        .with_context(|| {
+                            format!("failed getting miniblock range for L1 batch #{prev_l1_batch}")
+                        })?;
+                    let Some((_, last_miniblock)) = miniblock_range else {
+                        return Ok(None);
+                    };
+                    last_miniblock + 1
+                };
+
+                let values = if first_miniblock <= miniblock_number {
+                    let latency = STATE_CACHE_METRICS.overlay_load_latency.start();
+                    let values = connection
+                        .storage_web3_dal()
+                        .get_latest_values_in_miniblocks(first_miniblock..=miniblock_number)
+                        .await
+                        .context("failed loading storage values for miniblocks")?;
+                    latency.observe();
+                    values
+                } else {
+                    HashMap::new()
+                };
+                MiniblockOverlay {
+                    l1_batch_number,
+                    last_miniblock: miniblock_number,
+                    values,
+                }
+            }
+        };
+
+        let overlay = Arc::new(overlay);
+        let mut cached = self.inner.overlay.lock().expect("overlay mutex poisoned");
+        let is_newer = cached.as_ref().map_or(true, |cached| {
+            cached.l1_batch_number != l1_batch_number || cached.last_miniblock < miniblock_number
+        });
+        if is_newer {
+            STATE_CACHE_METRICS.overlay_size.set(overlay.values.len());
+            *cached = Some(overlay.clone());
+        }
+        Ok(Some(overlay))
+    }
+}
+
+/// Snapshot of [`ApiStateCache`] as of a certain miniblock. While the snapshot is alive, the cache cannot be updated.
+#[derive(Debug)]
+pub(super) struct StateCacheSnapshot {
+    storage: RocksdbStorage,
+    overlay: Arc<MiniblockOverlay>,
+    _guard: OwnedRwLockReadGuard<Option<SyncedStorage>>,
+}
+
+/// Storage used by the VM sandbox. Reads storage from the Merkle tree for pruned blocks, from
+/// a [`StateCacheSnapshot`] if it's available, and from Postgres otherwise.
+#[derive(Debug)]
+pub(super) struct SandboxStorage<'a> {
+    postgres: PostgresStorage<'a>,
+    cache_snapshot: Option<StateCacheSnapshot>,
+    tree: Option<TreeStorage>,
+    /// Whether storage values (rather than only enumeration indices) are read from `tree`.
+    tree_values: bool,
+}
+
+impl<'a> SandboxStorage<'a> {
+    pub fn new(postgres: PostgresStorage<'a>, cache_snapshot: Option<StateCacheSnapshot>) -> Self {
+        Self {
+            postgres,
+            cache_snapshot,
+            tree: None,
+            tree_values: false,
+        }
+    }
+
+    /// Reads storage values and enumeration indices from the Merkle tree. Postgres is only used
+    /// to load factory deps.
+    pub fn with_tree(mut self, tree: TreeStorage) -> Self {
+        self.tree = Some(tree);
+        self.tree_values = true;
+        self
+    }
+
+    /// Reads enumeration indices from the Merkle tree. Storage values are read from Postgres; this is used
+    /// for pruned blocks within an L1 batch, for which the pruner retains storage logs.
+    pub fn with_tree_enumeration_indices(mut self, tree: TreeStorage) -> Self {
+        self.tree = Some(tree);
+        self.tree_values = false;
+        self
+    }
+}
+
+impl ReadStorage for SandboxStorage<'_> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(tree) = self.tree.as_mut().filter(|_| self.tree_values) {
+            return tree.read_value(key);
+        }
+        let Some(snapshot) = &mut self.cache_snapshot else {
+            return self.postgres.read_value(key);
+        };
+        if let Some(value) = snapshot.overlay.values.get(&key.hashed_key()) {
+            *value
+        } else {
+            snapshot.storage.read_value(key)
+        }
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        // Writes in the miniblock overlay are not taken into account, same as for `PostgresStorage`
+        // that considers only writes in previous L1 batches.
+        if let Some(tree) = &mut self.tree {
+            return tree.is_write_initial(key);
+        }
+        match &mut self.cache_snapshot {
+            Some(snapshot) => snapshot.storage.is_write_initial(key),
+            None => self.postgres.is_write_initial(key),
+        }
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        // Factory deps deployed in overlay miniblocks are not in RocksDB, so we fall back to Postgres for them.
+        let dep = self
+            .cache_snapshot
+            .as_mut()
+            .and_then(|snapshot| snapshot.storage.load_factory_dep(hash));
+        dep.or_else(|| self.postgres.load_factory_dep(hash))
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        if let Some(tree) = &mut self.tree {
+            return tree.get_enumeration_index(key);
+        }
+        let index = self
+            .cache_snapshot
+            .as_mut()
+            .and_then(|snapshot| snapshot.storage.get_enumeration_index(key));
+        index.or_else(|| self.postgres.get_enumeration_index(key))
+    }
+}
+
+/// Task updating [`ApiStateCache`] from Postgres.
+#[derive(Debug)]
+pub struct ApiStateCacheUpdater {
+    db_path: PathBuf,
+    options: RocksDBOptions,
+    cache: ApiStateCache,
+    /// Builder for the replica before it's synced for the first time.
+    builder: Option<RocksbStorageBuilder>,
+    next_l1_batch: Option<L1BatchNumber>,
+}
+
+impl ApiStateCacheUpdater {
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Runs the updater until a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors.
+    pub async fn run(
+        mut self,
+        pool: ConnectionPool,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            if !self.update(&pool, &stop_receiver).await? {
+                break;
+            }
+            if tokio::time::timeout(Self::POLL_INTERVAL, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, API state cache updater is shutting down");
+        Ok(())
+    }
+
+    async fn open_builder(&self) -> anyhow::Result<RocksbStorageBuilder> {
+        RocksdbStorage::builder_with_options(&self.db_path, self.options)
+            .await
+            .with_context(|| format!("failed opening API state cache at {:?}", self.db_path))
+    }
+
+    /// Updates the cache to the last sealed L1 batch in Postgres. Returns `Ok(false)` if the update was interrupted.
+    pub(super) async fn update(
+        &mut self,
+        pool: &ConnectionPool,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<bool> {
+        if self.builder.is_none() && self.next_l1_batch.is_none() {
+            self.builder = Some(self.open_builder().await?);
+        }
+
+        let mut storage = pool.access_storage_tagged("api").await?;
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("failed getting sealed L1 batch number")?;
+        let Some(sealed_l1_batch) = sealed_l1_batch else {
+            return Ok(true); // No L1 batches in Postgres yet; nothing to sync.
+        };
+        if self.next_l1_batch == Some(sealed_l1_batch + 1) {
+            return Ok(true);
+        }
+
+        let latency = STATE_CACHE_METRICS.update_latency.start();
+        let mut guard = self.cache.inner.storage.write().await;
+        *self
+            .cache
+            .inner
+            .overlay
+            .lock()
+            .expect("overlay mutex poisoned") = None;
+        let mut builder = match (self.builder.take(), guard.take()) {
+            (Some(builder), _) => builder,
+            (None, Some(synced)) => synced.storage.into_builder(),
+            (None, None) => unreachable!("API state cache replica is neither opened nor synced"),
+        };
+
+        let next_l1_batch = builder.l1_batch_number().await;
+        if let Some(next_l1_batch) = next_l1_batch.filter(|&number| number > sealed_l1_batch + 1) {
+            tracing::info!(
+                "API state cache is ahead of Postgres (next L1 batch #{next_l1_batch}, last sealed L1 batch \
+                 #{sealed_l1_batch}); rolling it back"
+            );
+            builder
+                .rollback(&mut storage, sealed_l1_batch)
+                .await
+                .context("failed rolling back API state cache")?;
+            builder = self.open_builder().await?;
+        }
+
+        let Some(synced_storage) = builder.synchronize(&mut storage, stop_receiver).await? else {
+            return Ok(false);
+        };
+        let next_l1_batch = synced_storage
+            .l1_batch_number()
+            .await
+            .context("API state cache is not initialized after syncing")?;
+        STATE_CACHE_METRICS
+            .next_l1_batch
+            .set(next_l1_batch.0.into());
+        *guard = Some(SyncedStorage {
+            storage: synced_storage,
+            next_l1_batch,
+        });
+        drop(guard);
+        self.next_l1_batch = Some(next_l1_batch);
+        let elapsed = latency.observe();
+        tracing::debug!("Updated API state cache to L1 batch #{sealed_l1_batch} in {elapsed:?}");
+        Ok(true)
+    }
+}
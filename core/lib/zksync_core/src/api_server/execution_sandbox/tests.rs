@@ -1,12 +1,31 @@
 //! Tests for the VM execution sandbox.
 
+use std::collections::HashMap;
+
 use assert_matches::assert_matches;
+use async_trait::async_trait;
+use tempfile::TempDir;
+use tokio::sync::watch;
+use zksync_config::configs::database::RocksdbProfile;
+use zksync_dal::pruning_dal::StorageLogsPruning;
+use zksync_system_constants::{
+    SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+};
+use zksync_types::{block::pack_block_info, Address, StorageKey, StorageLog, H256, U256};
+use zksync_utils::u256_to_h256;
 
-use super::*;
+use super::{state_cache::SandboxStorage, tree_state::TreeStorage, *};
 use crate::{
-    api_server::{execution_sandbox::apply::apply_vm_in_sandbox, tx_sender::ApiContracts},
+    api_server::{
+        execution_sandbox::apply::{apply_vm_in_sandbox, StoredL2BlockInfo},
+        tree::{TreeEntry, TreeEntryWithProof},
+        tx_sender::ApiContracts,
+    },
     genesis::{ensure_genesis_state, GenesisParams},
-    utils::testonly::{create_l2_transaction, create_miniblock, prepare_recovery_snapshot},
+    metadata_calculator::MerkleTreeInfo,
+    utils::testonly::{
+        create_l1_batch, create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+    },
 };
 
 #[tokio::test]
@@ -196,3 +215,624 @@ async fn test_instantiating_vm(pool: ConnectionPool, block_args: BlockArgs) {
     .expect("VM instantiation panicked")
     .expect("VM instantiation errored");
 }
+
+async fn insert_miniblock_with_logs(
+    storage: &mut StorageProcessor<'_>,
+    number: u32,
+    logs: Vec<StorageLog>,
+) {
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+        .await
+        .unwrap();
+}
+
+async fn seal_l1_batch(
+    storage: &mut StorageProcessor<'_>,
+    number: u32,
+    initial_writes: &[StorageKey],
+) {
+    let l1_batch_number = L1BatchNumber(number);
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(number))
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(l1_batch_number)
+        .await
+        .unwrap();
+    storage
+        .storage_logs_dedup_dal()
+        .insert_initial_writes(l1_batch_number, initial_writes)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn reading_storage_from_state_cache() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let account = AccountTreeId::new(Address::repeat_byte(1));
+    let keys: Vec<_> = (0..3)
+        .map(|i| StorageKey::new(account, H256::from_low_u64_be(i)))
+        .collect();
+    let logs = vec![
+        StorageLog::new_write_log(keys[0], H256::repeat_byte(1)),
+        StorageLog::new_write_log(keys[1], H256::repeat_byte(2)),
+    ];
+    insert_miniblock_with_logs(&mut storage, 1, logs).await;
+    seal_l1_batch(&mut storage, 1, &keys[..2]).await;
+    // Pending miniblock overwriting one of the slots written in the sealed L1 batch.
+    let logs = vec![
+        StorageLog::new_write_log(keys[0], H256::repeat_byte(3)),
+        StorageLog::new_write_log(keys[2], H256::repeat_byte(4)),
+    ];
+    insert_miniblock_with_logs(&mut storage, 2, logs).await;
+
+    let temp_dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+    let (state_cache, mut updater) =
+        ApiStateCache::new(temp_dir.path().to_owned(), None, &RocksdbProfile::default());
+    let block_args = BlockArgs::pending(&mut storage).await.unwrap();
+    let snapshot = state_cache
+        .snapshot(&mut storage, &block_args, MiniblockNumber(2))
+        .await
+        .unwrap();
+    assert!(snapshot.is_none(), "cache is used before being synced");
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    assert!(updater.update(&pool, &stop_receiver).await.unwrap());
+    let start_info = BlockStartInfo::new(&mut storage).await.unwrap();
+    let historical_block_args =
+        BlockArgs::new(&mut storage, api::BlockId::Number(1.into()), start_info)
+            .await
+            .unwrap();
+    let snapshot = state_cache
+        .snapshot(&mut storage, &historical_block_args, MiniblockNumber(1))
+        .await
+        .unwrap();
+    assert!(snapshot.is_none(), "cache is used for a historical block");
+
+    let snapshot = state_cache
+        .snapshot(&mut storage, &block_args, MiniblockNumber(2))
+        .await
+        .unwrap()
+        .expect("cache is not used for the latest block");
+    let connection = pool.access_storage().await.unwrap();
+    let postgres_storage =
+        PostgresStorage::new_async(Handle::current(), connection, MiniblockNumber(2), false)
+            .await
+            .unwrap();
+    let mut sandbox_storage = SandboxStorage::new(postgres_storage, Some(snapshot));
+    let connection = pool.access_storage().await.unwrap();
+    let mut postgres_storage =
+        PostgresStorage::new_async(Handle::current(), connection, MiniblockNumber(2), false)
+            .await
+            .unwrap();
+
+    tokio::task::spawn_blocking(move || {
+        assert_eq!(sandbox_storage.read_value(&keys[0]), H256::repeat_byte(3));
+        assert_eq!(sandbox_storage.read_value(&keys[1]), H256::repeat_byte(2));
+        assert_eq!(sandbox_storage.read_value(&keys[2]), H256::repeat_byte(4));
+        assert!(!sandbox_storage.is_write_initial(&keys[0]));
+        assert!(sandbox_storage.is_write_initial(&keys[2]));
+        for key in &keys {
+            assert_eq!(
+                sandbox_storage.read_value(key),
+                postgres_storage.read_value(key)
+            );
+            assert_eq!(
+                sandbox_storage.is_write_initial(key),
+                postgres_storage.is_write_initial(key)
+            );
+        }
+    })
+    .await
+    .unwrap();
+
+    // After the pending L1 batch is sealed, the cache cannot be used for the next L1 batch until it's updated.
+    seal_l1_batch(&mut storage, 2, &keys[2..]).await;
+    insert_miniblock_with_logs(&mut storage, 3, vec![]).await;
+    let snapshot = state_cache
+        .snapshot(&mut storage, &block_args, MiniblockNumber(3))
+        .await
+        .unwrap();
+    assert!(snapshot.is_none(), "outdated cache is used");
+
+    assert!(updater.update(&pool, &stop_receiver).await.unwrap());
+    let snapshot = state_cache
+        .snapshot(&mut storage, &block_args, MiniblockNumber(3))
+        .await
+        .unwrap();
+    assert!(snapshot.is_some(), "cache is not used after update");
+}
+
+#[tokio::test]
+async fn using_sandbox_warm_pool() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    insert_miniblock_with_logs(&mut storage, 1, vec![]).await;
+
+    let (warm_pool, updater) = SandboxWarmPool::new();
+    let pending_block_args = BlockArgs::pending(&mut storage).await.unwrap();
+    assert!(
+        warm_pool.get(&pending_block_args).is_none(),
+        "pool is used before being refreshed"
+    );
+
+    updater.update(&pool).await.unwrap();
+    let start_info = BlockStartInfo::new(&mut storage).await.unwrap();
+    let latest_block = api::BlockId::Number(api::BlockNumber::Latest);
+    let latest_block_args = BlockArgs::new(&mut storage, latest_block, start_info)
+        .await
+        .unwrap();
+    let historical_block_args =
+        BlockArgs::new(&mut storage, api::BlockId::Number(0.into()), start_info)
+            .await
+            .unwrap();
+    assert!(warm_pool.get(&historical_block_args).is_none());
+
+    for block_args in [pending_block_args, latest_block_args] {
+        let warm_env = warm_pool
+            .get(&block_args)
+            .expect("pool is not used for the latest block");
+        let warm_info = warm_env.resolved_block_info(&block_args).unwrap();
+        let expected_info = block_args.resolve_block_info(&mut storage).await.unwrap();
+        assert_eq!(
+            warm_info.state_l2_block_number,
+            expected_info.state_l2_block_number
+        );
+        assert_eq!(
+            warm_info.state_l2_block_hash,
+            expected_info.state_l2_block_hash
+        );
+        assert_eq!(
+            warm_info.vm_l1_batch_number,
+            expected_info.vm_l1_batch_number
+        );
+        assert_eq!(warm_info.protocol_version, expected_info.protocol_version);
+        assert_eq!(warm_info.historical_fee_input, None);
+        if !block_args.is_pending_miniblock() {
+            assert_eq!(
+                warm_info.l1_batch_timestamp,
+                expected_info.l1_batch_timestamp
+            );
+        }
+
+        let (current_info, prev_info) = StoredL2BlockInfo::load_with_prev(
+            &mut storage,
+            expected_info.state_l2_block_number,
+            expected_info.state_l2_block_hash,
+        )
+        .await
+        .unwrap();
+        assert_eq!(warm_env.current_l2_block_info, current_info);
+        assert_eq!(warm_env.prev_l2_block_info, prev_info);
+    }
+
+    // The pool must not be used after a new miniblock is sealed until it's refreshed.
+    insert_miniblock_with_logs(&mut storage, 2, vec![]).await;
+    let pending_block_args = BlockArgs::pending(&mut storage).await.unwrap();
+    assert!(
+        warm_pool.get(&pending_block_args).is_none(),
+        "stale pool is used"
+    );
+    updater.update(&pool).await.unwrap();
+    assert!(warm_pool.get(&pending_block_args).is_some());
+
+    let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(1);
+    let vm_permit = vm_concurrency_limiter.acquire().await.unwrap();
+    let transaction = create_l2_transaction(10, 100).into();
+    let mut shared_args =
+        TxSharedArgs::mock(ApiContracts::load_from_disk().estimate_gas, pool.clone());
+    shared_args.warm_pool = Some(warm_pool);
+    tokio::task::spawn_blocking(move || {
+        apply_vm_in_sandbox(
+            vm_permit,
+            shared_args,
+            true,
+            &TxExecutionArgs::for_gas_estimate(None, &transaction, 123),
+            &pool,
+            transaction.clone(),
+            pending_block_args,
+            |_, received_tx| {
+                assert_eq!(received_tx, transaction);
+            },
+        )
+    })
+    .await
+    .expect("VM instantiation panicked")
+    .expect("VM instantiation errored");
+}
+
+#[tokio::test]
+async fn using_sandbox_warm_pool_with_state_cache() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let account = AccountTreeId::new(Address::repeat_byte(1));
+    let keys: Vec<_> = (0..2)
+        .map(|i| StorageKey::new(account, H256::from_low_u64_be(i)))
+        .collect();
+    let logs = vec![StorageLog::new_write_log(keys[0], H256::repeat_byte(1))];
+    insert_miniblock_with_logs(&mut storage, 1, logs).await;
+    seal_l1_batch(&mut storage, 1, &keys[..1]).await;
+    let logs = vec![StorageLog::new_write_log(keys[1], H256::repeat_byte(2))];
+    insert_miniblock_with_logs(&mut storage, 2, logs).await;
+
+    let temp_dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+    let (state_cache, mut cache_updater) =
+        ApiStateCache::new(temp_dir.path().to_owned(), None, &RocksdbProfile::default());
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    assert!(cache_updater.update(&pool, &stop_receiver).await.unwrap());
+    let (warm_pool, updater) = SandboxWarmPool::new();
+    let updater = updater.with_state_cache(state_cache.clone());
+    updater.update(&pool).await.unwrap();
+
+    let block_args = BlockArgs::pending(&mut storage).await.unwrap();
+    let warm_env = warm_pool
+        .get(&block_args)
+        .expect("pool is not used for the latest block");
+    let overlay = warm_env
+        .state_overlay
+        .clone()
+        .expect("state cache overlay is not cached");
+    let snapshot = state_cache
+        .snapshot_with_overlay(&overlay)
+        .expect("cached overlay is not used");
+    let connection = pool.access_storage().await.unwrap();
+    let mut sandbox_storage =
+        SandboxStorage::new(warm_env.postgres_storage(connection), Some(snapshot));
+    let connection = pool.access_storage().await.unwrap();
+    let mut postgres_storage =
+        PostgresStorage::new_async(Handle::current(), connection, MiniblockNumber(2), false)
+            .await
+            .unwrap();
+
+    tokio::task::spawn_blocking(move || {
+        assert_eq!(sandbox_storage.read_value(&keys[0]), H256::repeat_byte(1));
+        assert_eq!(sandbox_storage.read_value(&keys[1]), H256::repeat_byte(2));
+        for key in &keys {
+            assert_eq!(
+                sandbox_storage.read_value(key),
+                postgres_storage.read_value(key)
+            );
+            assert_eq!(
+                sandbox_storage.is_write_initial(key),
+                postgres_storage.is_write_initial(key)
+            );
+        }
+    })
+    .await
+    .unwrap();
+
+    // The cached overlay must not be used after the state cache moves on to the next L1 batch.
+    seal_l1_batch(&mut storage, 2, &keys[1..]).await;
+    assert!(cache_updater.update(&pool, &stop_receiver).await.unwrap());
+    assert!(state_cache.snapshot_with_overlay(&overlay).is_none());
+}
+
+/// Merkle tree mock returning the configured entries for each tree version.
+#[derive(Debug, Default)]
+struct MockArchiveTree {
+    entries: HashMap<(L1BatchNumber, U256), (H256, u64)>,
+    is_failing: bool,
+}
+
+impl MockArchiveTree {
+    fn insert(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        key: &StorageKey,
+        value: H256,
+        index: u64,
+    ) {
+        self.entries
+            .insert((l1_batch_number, key.hashed_key_u256()), (value, index));
+    }
+
+    fn insert_l2_block_info(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: u32,
+        timestamp: u64,
+    ) {
+        let key = StorageKey::new(
+            AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+            SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+        );
+        let value = pack_block_info(miniblock_number.into(), timestamp);
+        self.insert(l1_batch_number, &key, u256_to_h256(value), 1);
+    }
+
+    fn entry(&self, l1_batch_number: L1BatchNumber, hashed_key: U256) -> anyhow::Result<TreeEntry> {
+        anyhow::ensure!(!self.is_failing, "tree is unavailable");
+        let (value, index) = self
+            .entries
+            .get(&(l1_batch_number, hashed_key))
+            .copied()
+            .unwrap_or_default();
+        Ok(TreeEntry { value, index })
+    }
+}
+
+#[async_trait]
+impl TreeApiClient for MockArchiveTree {
+    async fn get_info(&self) -> anyhow::Result<MerkleTreeInfo> {
+        anyhow::bail!("not implemented");
+    }
+
+    async fn get_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> anyhow::Result<Vec<TreeEntryWithProof>> {
+        let entries = hashed_keys.into_iter().map(|hashed_key| {
+            let entry = self.entry(l1_batch_number, hashed_key)?;
+            Ok(TreeEntryWithProof {
+                value: entry.value,
+                index: entry.index,
+                merkle_path: vec![],
+            })
+        });
+        entries.collect()
+    }
+
+    async fn get_entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> anyhow::Result<Vec<TreeEntry>> {
+        let entries = hashed_keys
+            .into_iter()
+            .map(|hashed_key| self.entry(l1_batch_number, hashed_key));
+        entries.collect()
+    }
+}
+
+#[tokio::test]
+async fn creating_block_args_from_tree() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    // Each L1 batch contains 2 miniblocks.
+    let mut tree = MockArchiveTree::default();
+    tree.insert_l2_block_info(L1BatchNumber(0), 0, 0);
+    for l1_batch_number in 1..=3 {
+        insert_miniblock_with_logs(&mut storage, l1_batch_number * 2 - 1, vec![]).await;
+        insert_miniblock_with_logs(&mut storage, l1_batch_number * 2, vec![]).await;
+        seal_l1_batch(&mut storage, l1_batch_number, &[]).await;
+        let timestamp = u64::from(l1_batch_number) * 10;
+        tree.insert_l2_block_info(
+            L1BatchNumber(l1_batch_number),
+            l1_batch_number * 2,
+            timestamp,
+        );
+    }
+    // Emulate pruning L1 batches up to #2 inclusive.
+    let start_info = BlockStartInfo {
+        first_miniblock: MiniblockNumber(5),
+        first_l1_batch: L1BatchNumber(3),
+    };
+
+    let block_id = api::BlockId::Number(4.into());
+    let block_args = BlockArgs::from_tree(&mut storage, &tree, block_id, start_info)
+        .await
+        .unwrap();
+    assert_eq!(block_args.block_id, block_id);
+    assert_eq!(block_args.resolved_block_number, MiniblockNumber(4));
+    assert_eq!(block_args.l1_batch_timestamp_s, Some(21));
+    assert_eq!(
+        block_args.pruned_state,
+        Some(PrunedBlockState::EndOfL1Batch(L1BatchNumber(2)))
+    );
+    assert!(!block_args.resolves_to_latest_sealed_miniblock());
+
+    let block_id = api::BlockId::Number(api::BlockNumber::Earliest);
+    let block_args = BlockArgs::from_tree(&mut storage, &tree, block_id, start_info)
+        .await
+        .unwrap();
+    assert_eq!(block_args.resolved_block_number, MiniblockNumber(0));
+    assert_eq!(
+        block_args.pruned_state,
+        Some(PrunedBlockState::EndOfL1Batch(L1BatchNumber(0)))
+    );
+
+    for (number, closest) in [(1, 2), (3, 4)] {
+        let block_id = api::BlockId::Number(number.into());
+        let err = BlockArgs::from_tree(&mut storage, &tree, block_id, start_info)
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            BlockArgsError::PrunedStateUnavailable(requested, available)
+                if requested == MiniblockNumber(number) && available == MiniblockNumber(closest)
+        );
+    }
+
+    let block_id = api::BlockId::Hash(H256::repeat_byte(1));
+    let err = BlockArgs::from_tree(&mut storage, &tree, block_id, start_info)
+        .await
+        .unwrap_err();
+    assert_matches!(err, BlockArgsError::Pruned(MiniblockNumber(5)));
+
+    // Mid-batch miniblocks can be resolved if the pruner retains their state.
+    storage
+        .pruning_dal()
+        .prune_l1_batches(
+            L1BatchNumber(2),
+            MiniblockNumber(1)..=MiniblockNumber(4),
+            StorageLogsPruning::WithinMiniblocks,
+        )
+        .await
+        .unwrap();
+    let l1_batch_header = storage
+        .blocks_dal()
+        .get_l1_batch_header(L1BatchNumber(2))
+        .await
+        .unwrap()
+        .unwrap();
+    let block_id = api::BlockId::Number(3.into());
+    let block_args = BlockArgs::from_tree(&mut storage, &tree, block_id, start_info)
+        .await
+        .unwrap();
+    assert_eq!(block_args.resolved_block_number, MiniblockNumber(3));
+    assert_eq!(
+        block_args.l1_batch_timestamp_s,
+        Some(l1_batch_header.timestamp)
+    );
+    assert_eq!(
+        block_args.pruned_state,
+        Some(PrunedBlockState::WithinL1Batch(L1BatchNumber(2)))
+    );
+}
+
+#[tokio::test]
+async fn reading_storage_from_tree() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let account = AccountTreeId::new(Address::repeat_byte(1));
+    let keys: Vec<_> = (0..2)
+        .map(|i| StorageKey::new(account, H256::from_low_u64_be(i)))
+        .collect();
+    let mut tree = MockArchiveTree::default();
+    tree.insert(L1BatchNumber(1), &keys[0], H256::repeat_byte(1), 5);
+    tree.insert(L1BatchNumber(2), &keys[0], H256::repeat_byte(2), 5);
+
+    let tree_storage = TreeStorage::new(Handle::current(), Arc::new(tree), L1BatchNumber(1));
+    let postgres_storage =
+        PostgresStorage::new_async(Handle::current(), storage, MiniblockNumber(0), false)
+            .await
+            .unwrap();
+    let mut sandbox_storage = SandboxStorage::new(postgres_storage, None).with_tree(tree_storage);
+
+    tokio::task::spawn_blocking(move || {
+        assert_eq!(sandbox_storage.read_value(&keys[0]), H256::repeat_byte(1));
+        assert!(!sandbox_storage.is_write_initial(&keys[0]));
+        assert_eq!(sandbox_storage.get_enumeration_index(&keys[0]), Some(5));
+        assert_eq!(sandbox_storage.read_value(&keys[1]), H256::zero());
+        assert!(sandbox_storage.is_write_initial(&keys[1]));
+        assert_eq!(sandbox_storage.get_enumeration_index(&keys[1]), None);
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn propagating_tree_errors() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+    let mut tree = MockArchiveTree::default();
+    tree.insert(L1BatchNumber(1), &key, H256::repeat_byte(1), 5);
+    tree.is_failing = true;
+
+    let mut tree_storage = TreeStorage::new(Handle::current(), Arc::new(tree), L1BatchNumber(1));
+    tree_storage.prefetch(&[key]).await.unwrap_err();
+    let tree_error = tree_storage.error();
+    let postgres_storage =
+        PostgresStorage::new_async(Handle::current(), storage, MiniblockNumber(0), false)
+            .await
+            .unwrap();
+    let mut sandbox_storage = SandboxStorage::new(postgres_storage, None).with_tree(tree_storage);
+
+    tokio::task::spawn_blocking(move || {
+        assert_eq!(sandbox_storage.read_value(&key), H256::zero());
+    })
+    .await
+    .unwrap();
+    let err = tree_error.check().unwrap_err().to_string();
+    assert!(err.contains("tree is unavailable"), "{err}");
+    // The error is returned once.
+    tree_error.check().unwrap();
+}
+
+#[tokio::test]
+async fn instantiating_vm_with_tree_state() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    // Populate the tree with the genesis state.
+    let mut tree = MockArchiveTree::default();
+    let storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    for (i, log) in storage_logs.iter().enumerate() {
+        let key = StorageKey::new(AccountTreeId::new(log.address), log.key);
+        tree.insert(L1BatchNumber(0), &key, log.value, i as u64 + 1);
+    }
+    let tree: Arc<dyn TreeApiClient> = Arc::new(tree);
+
+    // Emulate pruning the genesis L1 batch.
+    let start_info = BlockStartInfo {
+        first_miniblock: MiniblockNumber(1),
+        first_l1_batch: L1BatchNumber(1),
+    };
+    let block_args = BlockArgs::from_tree(
+        &mut storage,
+        tree.as_ref(),
+        api::BlockId::Number(0.into()),
+        start_info,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        block_args.pruned_state,
+        Some(PrunedBlockState::EndOfL1Batch(L1BatchNumber(0)))
+    );
+
+    let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(1);
+    let vm_permit = vm_concurrency_limiter.acquire().await.unwrap();
+    let transaction = create_l2_transaction(10, 100).into();
+    let mut shared_args =
+        TxSharedArgs::mock(ApiContracts::load_from_disk().estimate_gas, pool.clone());
+    shared_args.archive_tree = Some(tree);
+    tokio::task::spawn_blocking(move || {
+        apply_vm_in_sandbox(
+            vm_permit,
+            shared_args,
+            true,
+            &TxExecutionArgs::for_gas_estimate(None, &transaction, 123),
+            &pool,
+            transaction.clone(),
+            block_args,
+            |_, received_tx| {
+                assert_eq!(received_tx, transaction);
+            },
+        )
+    })
+    .await
+    .expect("VM instantiation panicked")
+    .expect("VM instantiation errored");
+}
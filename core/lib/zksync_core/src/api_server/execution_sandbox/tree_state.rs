@@ -0,0 +1,280 @@
+//! VM state read from the Merkle tree, which is used to execute calls for pruned blocks.
+//!
+//! Pruning removes miniblock headers and overwritten storage logs from Postgres, so the VM state for pruned
+//! miniblocks cannot be restored from Postgres. The Merkle tree retains the state after each L1 batch
+//! (unless the tree is pruned itself), so the state after a pruned miniblock can be read from the tree
+//! if the miniblock is the last one in its L1 batch.
+//!
+//! For other miniblocks, the state can only be restored if the pruner retained the state after each miniblock
+//! (see [`StorageLogsPruning::WithinMiniblocks`]). In this case, storage values are read from the retained
+//! storage logs, and enumeration indices (which are not stored in storage logs) from the tree as of the end
+//! of the previous L1 batch.
+
+use std::{
+    collections::HashMap,
+    slice,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use tokio::runtime::Handle;
+#[cfg(doc)]
+use zksync_dal::pruning_dal::StorageLogsPruning;
+use zksync_dal::StorageProcessor;
+use zksync_system_constants::{
+    SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+};
+use zksync_types::{
+    api, block::unpack_block_info, AccountTreeId, L1BatchNumber, MiniblockNumber, StorageKey,
+    StorageValue, H256,
+};
+use zksync_utils::h256_to_u256;
+
+use super::{BlockArgs, BlockArgsError, BlockStartInfo, PrunedBlockState};
+use crate::api_server::tree::{TreeApiClient, TreeEntry};
+
+/// Reads entries for the specified storage keys from the tree as of the end of the specified L1 batch.
+async fn read_tree_entries(
+    tree: &dyn TreeApiClient,
+    l1_batch_number: L1BatchNumber,
+    keys: &[StorageKey],
+) -> anyhow::Result<Vec<TreeEntry>> {
+    let hashed_keys = keys.iter().map(StorageKey::hashed_key_u256).collect();
+    let entries = tree
+        .get_entries(l1_batch_number, hashed_keys)
+        .await
+        .with_context(|| {
+            format!("failed reading storage from Merkle tree for L1 batch #{l1_batch_number}")
+        })?;
+    anyhow::ensure!(
+        entries.len() == keys.len(),
+        "Merkle tree returned {} entries for {} keys",
+        entries.len(),
+        keys.len()
+    );
+    Ok(entries)
+}
+
+/// Reads values for the specified storage keys from the tree as of the end of the specified L1 batch.
+pub(super) async fn read_tree_values(
+    tree: &dyn TreeApiClient,
+    l1_batch_number: L1BatchNumber,
+    keys: &[StorageKey],
+) -> anyhow::Result<Vec<StorageValue>> {
+    let entries = read_tree_entries(tree, l1_batch_number, keys).await?;
+    Ok(entries.into_iter().map(|entry| entry.value).collect())
+}
+
+/// Returns the number and timestamp of the last miniblock in the specified L1 batch.
+async fn last_miniblock_in_l1_batch(
+    tree: &dyn TreeApiClient,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<(MiniblockNumber, u64)> {
+    let l2_block_info_key = StorageKey::new(
+        AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+        SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+    );
+    let values = read_tree_values(tree, l1_batch_number, &[l2_block_info_key]).await?;
+    let (number, timestamp) = unpack_block_info(h256_to_u256(values[0]));
+    Ok((MiniblockNumber(number as u32), timestamp))
+}
+
+impl BlockArgs {
+    /// Resolves a pruned block using the Merkle tree. If the block is not the last miniblock in its L1 batch,
+    /// the pruner must have retained the state after each miniblock in the batch; otherwise,
+    /// [`BlockArgsError::PrunedStateUnavailable`] is returned.
+    ///
+    /// For the last miniblock in a batch, the VM is executed in the context of the following miniblock (similarly to
+    /// the pending block), since the tree only provides the state *after* the resolved miniblock.
+    pub async fn from_tree(
+        connection: &mut StorageProcessor<'_>,
+        tree: &dyn TreeApiClient,
+        block_id: api::BlockId,
+        start_info: BlockStartInfo,
+    ) -> Result<Self, BlockArgsError> {
+        let pruned_err = BlockArgsError::Pruned(start_info.first_miniblock);
+        let miniblock_number = match block_id {
+            api::BlockId::Number(api::BlockNumber::Number(number)) => {
+                MiniblockNumber(number.as_u32())
+            }
+            api::BlockId::Number(api::BlockNumber::Earliest) => MiniblockNumber(0),
+            _ => return Err(pruned_err),
+        };
+
+        // L1 batch headers are retained by pruning, so they can be used to bound the search.
+        let earliest_l1_batch = connection
+            .blocks_dal()
+            .get_earliest_l1_batch_number()
+            .await
+            .context("failed getting earliest L1 batch number")?;
+        let Some(earliest_l1_batch) =
+            earliest_l1_batch.filter(|&number| number < start_info.first_l1_batch)
+        else {
+            return Err(pruned_err);
+        };
+
+        // Binary search for the first L1 batch ending with the requested miniblock or a later one.
+        let (mut left, mut right) = (earliest_l1_batch.0, start_info.first_l1_batch.0 - 1);
+        let mut found = None;
+        while left <= right {
+            let middle = left + (right - left) / 2;
+            let l1_batch_number = L1BatchNumber(middle);
+            let (last_miniblock, timestamp) =
+                last_miniblock_in_l1_batch(tree, l1_batch_number).await?;
+            if last_miniblock < miniblock_number {
+                left = middle + 1;
+                continue;
+            }
+            found = Some((l1_batch_number, last_miniblock, timestamp));
+            if last_miniblock == miniblock_number || middle == 0 {
+                break;
+            }
+            right = middle - 1;
+        }
+
+        let Some((l1_batch_number, last_miniblock, timestamp)) = found else {
+            return Err(pruned_err);
+        };
+        if last_miniblock == miniblock_number {
+            return Ok(Self {
+                block_id,
+                resolved_block_number: miniblock_number,
+                // Timestamp of the next L1 batch must be greater than the timestamp of the last miniblock.
+                l1_batch_timestamp_s: Some(timestamp + 1),
+                pruned_state: Some(PrunedBlockState::EndOfL1Batch(l1_batch_number)),
+            });
+        }
+
+        let state_retained = connection
+            .pruning_dal()
+            .is_miniblock_state_retained(l1_batch_number)
+            .await
+            .context("failed checking whether miniblock state is retained")?;
+        if !state_retained {
+            return Err(BlockArgsError::PrunedStateUnavailable(
+                miniblock_number,
+                last_miniblock,
+            ));
+        }
+        let l1_batch_header = connection
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await
+            .with_context(|| format!("failed getting header for L1 batch #{l1_batch_number}"))?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not present in storage"))?;
+        Ok(Self {
+            block_id,
+            resolved_block_number: miniblock_number,
+            l1_batch_timestamp_s: Some(l1_batch_header.timestamp),
+            pruned_state: Some(PrunedBlockState::WithinL1Batch(l1_batch_number)),
+        })
+    }
+}
+
+/// Error reading from the Merkle tree during VM execution. [`ReadStorage`](zksync_state::ReadStorage) methods
+/// are infallible, so the first error is recorded and returned after the execution.
+#[derive(Debug, Clone, Default)]
+pub(super) struct TreeReadError(Arc<Mutex<Option<anyhow::Error>>>);
+
+impl TreeReadError {
+    fn record(&self, err: anyhow::Error) {
+        self.0.lock().unwrap().get_or_insert(err);
+    }
+
+    /// Returns the recorded error, if any.
+    pub fn check(&self) -> anyhow::Result<()> {
+        match self.0.lock().unwrap().take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Storage reading values and enumeration indices from the Merkle tree as of the end of a specific L1 batch.
+/// The tree doesn't store factory deps, so they should be loaded from Postgres.
+#[derive(Debug)]
+pub(super) struct TreeStorage {
+    rt_handle: Handle,
+    tree: Arc<dyn TreeApiClient>,
+    l1_batch_number: L1BatchNumber,
+    /// Values and enumeration indices of the already read entries.
+    entries: HashMap<H256, TreeEntry>,
+    error: TreeReadError,
+}
+
+impl TreeStorage {
+    pub fn new(
+        rt_handle: Handle,
+        tree: Arc<dyn TreeApiClient>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Self {
+        Self {
+            rt_handle,
+            tree,
+            l1_batch_number,
+            entries: HashMap::new(),
+            error: TreeReadError::default(),
+        }
+    }
+
+    /// Returns a handle to the error encountered when reading entries during VM execution.
+    pub fn error(&self) -> TreeReadError {
+        self.error.clone()
+    }
+
+    /// Loads entries for the specified keys with a single tree request.
+    pub async fn prefetch(&mut self, keys: &[StorageKey]) -> anyhow::Result<()> {
+        let missing_keys: Vec<_> = keys
+            .iter()
+            .filter(|key| !self.entries.contains_key(&key.hashed_key()))
+            .copied()
+            .collect();
+        if missing_keys.is_empty() {
+            return Ok(());
+        }
+        let entries =
+            read_tree_entries(self.tree.as_ref(), self.l1_batch_number, &missing_keys).await?;
+        for (key, entry) in missing_keys.iter().zip(entries) {
+            self.entries.insert(key.hashed_key(), entry);
+        }
+        Ok(())
+    }
+
+    /// Reads an entry from the tree. On tree API errors, the error is recorded, and an empty entry is returned.
+    fn entry(&mut self, key: &StorageKey) -> TreeEntry {
+        let hashed_key = key.hashed_key();
+        if let Some(&entry) = self.entries.get(&hashed_key) {
+            return entry;
+        }
+
+        let entries = self.rt_handle.block_on(read_tree_entries(
+            self.tree.as_ref(),
+            self.l1_batch_number,
+            slice::from_ref(key),
+        ));
+        match entries {
+            Ok(entries) => {
+                let entry = entries[0];
+                self.entries.insert(hashed_key, entry);
+                entry
+            }
+            Err(err) => {
+                self.error.record(err);
+                TreeEntry::default()
+            }
+        }
+    }
+
+    pub fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        self.entry(key).value
+    }
+
+    pub fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.entry(key).index == 0
+    }
+
+    pub fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        let index = self.entry(key).index;
+        (index != 0).then_some(index)
+    }
+}
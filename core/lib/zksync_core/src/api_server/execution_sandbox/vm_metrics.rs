@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use multivm::interface::{VmExecutionResultAndLogs, VmMemoryMetrics};
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
 use zksync_state::StorageViewMetrics;
 use zksync_types::{
     event::{extract_long_l2_to_l1_messages, extract_published_bytecodes},
@@ -125,6 +125,61 @@ pub(super) struct ExecutionMetrics {
 #[vise::register]
 pub(super) static EXECUTION_METRICS: vise::Global<ExecutionMetrics> = vise::Global::new();
 
+/// Reason why a VM invocation didn't use [`ApiStateCache`](super::ApiStateCache) and fell back to Postgres.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum StateCacheMissReason {
+    /// The invocation doesn't target the latest sealed miniblock.
+    HistoricalBlock,
+    /// The cache is not initialized yet.
+    NotReady,
+    /// The cache is being updated.
+    Updating,
+    /// The cache lags behind or is ahead of the L1 batch of the requested miniblock.
+    L1BatchMismatch,
+    /// Miniblocks for the previous L1 batch are not available in Postgres (e.g., after snapshot recovery).
+    NoMiniblocks,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_state_cache")]
+pub(super) struct StateCacheMetrics {
+    /// Number of VM invocations served by the cache.
+    pub hits: Counter,
+    /// Number of VM invocations that fell back to Postgres, grouped by the reason.
+    pub misses: Family<StateCacheMissReason, Counter>,
+    /// Next L1 batch to be loaded into the cache.
+    pub next_l1_batch: Gauge<u64>,
+    /// Number of storage slots in the overlay of miniblocks not yet included into the cache.
+    pub overlay_size: Gauge<usize>,
+    /// Latency of updating the cache from Postgres.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub update_latency: Histogram<Duration>,
+    /// Latency of loading the miniblock overlay from Postgres.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub overlay_load_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static STATE_CACHE_METRICS: vise::Global<StateCacheMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_sandbox_warm_pool")]
+pub(super) struct WarmPoolMetrics {
+    /// Number of VM invocations at the latest block served by the warm pool.
+    pub hits: Counter,
+    /// Number of VM invocations at the latest block that fell back to initializing the sandbox from Postgres.
+    pub misses: Counter,
+    /// Miniblock the warm pool is pinned to.
+    pub miniblock: Gauge<u64>,
+    /// Latency of refreshing the warm pool from Postgres.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub refresh_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static WARM_POOL_METRICS: vise::Global<WarmPoolMetrics> = vise::Global::new();
+
 pub(super) fn report_vm_memory_metrics(
     tx_id: &str,
     memory_metrics: &VmMemoryMetrics,
@@ -0,0 +1,252 @@
+//! Warm pool of sandbox environments for VM invocations (`eth_call`, `eth_estimateGas` etc.) at the latest
+//! sealed miniblock.
+//!
+//! Initializing a sandbox requires resolving the block context, loading L2 block info from the VM state
+//! and preparing the storage the VM reads from (resolving L1 batches for Postgres storage, and loading
+//! the miniblock overlay for the API state cache), which takes several Postgres queries per VM invocation.
+//! [`SandboxWarmPool`] keeps this data pre-loaded for the latest sealed miniblock; it is refreshed by
+//! [`SandboxWarmPoolUpdater`] each time a new miniblock is sealed. If the pool cannot be used for a VM invocation
+//! (e.g., the invocation targets a historical block, or the pool lags behind Postgres), the sandbox is initialized
+//! from Postgres as usual.
+
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use tokio::{runtime::Handle, sync::watch};
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_state::PostgresStorage;
+use zksync_types::{block::MiniblockHeader, L1BatchNumber, ProtocolVersionId};
+use zksync_utils::time::seconds_since_epoch;
+
+use super::{
+    apply::{ResolvedBlockInfo, StoredL2BlockInfo},
+    state_cache::{ApiStateCache, MiniblockOverlay},
+    vm_metrics::WARM_POOL_METRICS,
+    BlockArgs,
+};
+
+/// Sandbox environment pre-loaded for the latest sealed miniblock.
+#[derive(Debug)]
+pub(super) struct WarmSandboxEnv {
+    miniblock: MiniblockHeader,
+    /// Last sealed L1 batch; used as the VM L1 batch for the pending miniblock.
+    sealed_l1_batch: L1BatchNumber,
+    /// L1 batch the miniblock belongs to (may be the pending L1 batch).
+    miniblock_l1_batch: L1BatchNumber,
+    /// Pending L1 batch as of the miniblock.
+    pending_l1_batch: L1BatchNumber,
+    pub(super) current_l2_block_info: StoredL2BlockInfo,
+    pub(super) prev_l2_block_info: Option<StoredL2BlockInfo>,
+    /// Miniblock overlay of the API state cache as of the miniblock; `None` if the cache is not used
+    /// or cannot be used for the miniblock.
+    pub(super) state_overlay: Option<Arc<MiniblockOverlay>>,
+}
+
+impl WarmSandboxEnv {
+    async fn load(
+        connection: &mut StorageProcessor<'_>,
+        miniblock: MiniblockHeader,
+        state_overlay: Option<Arc<MiniblockOverlay>>,
+    ) -> anyhow::Result<Option<Self>> {
+        let sealed_l1_batch = connection
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("failed getting sealed L1 batch number")?;
+        let Some(sealed_l1_batch) = sealed_l1_batch else {
+            return Ok(None);
+        };
+        let resolved_l1_batch = connection
+            .storage_web3_dal()
+            .resolve_l1_batch_number_of_miniblock(miniblock.number)
+            .await
+            .context("failed resolving L1 batch for miniblock")?;
+        let (current_l2_block_info, prev_l2_block_info) =
+            StoredL2BlockInfo::load_with_prev(connection, miniblock.number, miniblock.hash).await?;
+
+        Ok(Some(Self {
+            miniblock,
+            sealed_l1_batch,
+            miniblock_l1_batch: resolved_l1_batch.expected_l1_batch(),
+            pending_l1_batch: resolved_l1_batch.pending_l1_batch,
+            current_l2_block_info,
+            prev_l2_block_info,
+            state_overlay,
+        }))
+    }
+
+    fn matches(&self, block_args: &BlockArgs) -> bool {
+        let expected_block_number = if block_args.is_pending_miniblock() {
+            self.miniblock.number + 1
+        } else {
+            self.miniblock.number
+        };
+        block_args.resolves_to_latest_sealed_miniblock()
+            && block_args.resolved_block_number == expected_block_number
+    }
+
+    /// Returns the same info as [`BlockArgs::resolve_block_info()`] without querying Postgres.
+    pub(super) fn resolved_block_info(
+        &self,
+        block_args: &BlockArgs,
+    ) -> anyhow::Result<ResolvedBlockInfo> {
+        let (vm_l1_batch_number, l1_batch_timestamp) = if block_args.is_pending_miniblock() {
+            // Timestamp of the next L1 batch must be greater than the timestamp of the last miniblock.
+            let timestamp = seconds_since_epoch().max(self.miniblock.timestamp + 1);
+            (self.sealed_l1_batch, timestamp)
+        } else {
+            let timestamp = block_args
+                .l1_batch_timestamp_s
+                .context("L1 batch timestamp is `None` for non-pending block args")?;
+            (self.miniblock_l1_batch, timestamp)
+        };
+
+        Ok(ResolvedBlockInfo {
+            state_l2_block_number: self.miniblock.number,
+            state_l2_block_hash: self.miniblock.hash,
+            vm_l1_batch_number,
+            l1_batch_timestamp,
+            // Blocks without version specified are considered to be of `Version9`.
+            protocol_version: self
+                .miniblock
+                .protocol_version
+                .unwrap_or(ProtocolVersionId::last_potentially_undefined()),
+            // The pool is only used for estimate-like blocks, which use the current fee input.
+            historical_fee_input: None,
+        })
+    }
+
+    /// Creates Postgres storage for the miniblock without querying Postgres. Equivalent to
+    /// [`PostgresStorage::new_async()`] for [`ResolvedBlockInfo::state_l2_block_number`].
+    pub(super) fn postgres_storage<'a>(
+        &self,
+        connection: StorageProcessor<'a>,
+    ) -> PostgresStorage<'a> {
+        PostgresStorage::new_resolved(
+            Handle::current(),
+            connection,
+            self.miniblock.number,
+            self.miniblock_l1_batch,
+            self.pending_l1_batch,
+            false,
+        )
+    }
+}
+
+/// Pool of sandbox environments pinned to the latest sealed miniblock. The environment is immutable
+/// and is shared among all VM invocations for the miniblock.
+#[derive(Debug, Clone, Default)]
+pub struct SandboxWarmPool {
+    env: Arc<RwLock<Option<Arc<WarmSandboxEnv>>>>,
+}
+
+impl SandboxWarmPool {
+    /// Creates a new pool together with the task refreshing it. The pool will not be used until
+    /// the refresh task is run.
+    pub fn new() -> (Self, SandboxWarmPoolUpdater) {
+        let this = Self::default();
+        let updater = SandboxWarmPoolUpdater {
+            pool: this.clone(),
+            state_cache: None,
+        };
+        (this, updater)
+    }
+
+    fn current(&self) -> Option<Arc<WarmSandboxEnv>> {
+        self.env.read().expect("warm pool lock poisoned").clone()
+    }
+
+    /// Returns a pre-loaded environment for the specified block, or `None` if the pool cannot be used for it.
+    pub(super) fn get(&self, block_args: &BlockArgs) -> Option<Arc<WarmSandboxEnv>> {
+        if !block_args.resolves_to_latest_sealed_miniblock() {
+            return None;
+        }
+        let env = self.current().filter(|env| env.matches(block_args));
+        if env.is_some() {
+            WARM_POOL_METRICS.hits.inc();
+        } else {
+            WARM_POOL_METRICS.misses.inc();
+        }
+        env
+    }
+}
+
+/// Task refreshing [`SandboxWarmPool`] from Postgres.
+#[derive(Debug)]
+pub struct SandboxWarmPoolUpdater {
+    pool: SandboxWarmPool,
+    state_cache: Option<ApiStateCache>,
+}
+
+impl SandboxWarmPoolUpdater {
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Sets the API state cache to warm up together with the pool, so that its miniblock overlay is loaded
+    /// before the first VM invocation for a new miniblock and is shared by VM invocations using the pool.
+    pub fn with_state_cache(mut self, state_cache: ApiStateCache) -> Self {
+        self.state_cache = Some(state_cache);
+        self
+    }
+
+    /// Runs the updater until a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors.
+    pub async fn run(
+        self,
+        pool: ConnectionPool,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            self.update(&pool).await?;
+            if tokio::time::timeout(Self::POLL_INTERVAL, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, sandbox warm pool updater is shutting down");
+        Ok(())
+    }
+
+    /// Refreshes the pool if the latest sealed miniblock has changed (including the case of a reverted
+    /// and re-sealed miniblock with the same number).
+    pub(super) async fn update(&self, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage_tagged("api").await?;
+        let miniblock = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .context("failed getting sealed miniblock header")?;
+        let Some(miniblock) = miniblock else {
+            return Ok(()); // No miniblocks in Postgres yet.
+        };
+        let current = self.pool.current();
+        if current.map_or(false, |env| env.miniblock.hash == miniblock.hash) {
+            return Ok(());
+        }
+
+        let latency = WARM_POOL_METRICS.refresh_latency.start();
+        let miniblock_number = miniblock.number;
+        let state_overlay = if let Some(state_cache) = &self.state_cache {
+            state_cache.warm_up(&mut storage, miniblock_number).await?
+        } else {
+            None
+        };
+        let Some(env) = WarmSandboxEnv::load(&mut storage, miniblock, state_overlay).await? else {
+            return Ok(());
+        };
+        *self.pool.env.write().expect("warm pool lock poisoned") = Some(Arc::new(env));
+        WARM_POOL_METRICS.miniblock.set(miniblock_number.0.into());
+        let elapsed = latency.observe();
+        tracing::trace!(
+            "Refreshed sandbox warm pool to miniblock #{miniblock_number} in {elapsed:?}"
+        );
+        Ok(())
+    }
+}
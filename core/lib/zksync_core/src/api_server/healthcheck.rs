@@ -4,6 +4,7 @@ use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use tokio::sync::watch;
 use zksync_health_check::{AppHealth, CheckHealth};
 
+/// Readiness check: responds with 200 if all components are healthy.
 async fn check_health<T: AsRef<dyn CheckHealth>>(
     health_checks: State<Arc<[T]>>,
 ) -> (StatusCode, Json<AppHealth>) {
@@ -16,6 +17,21 @@ async fn check_health<T: AsRef<dyn CheckHealth>>(
     (response_code, Json(response))
 }
 
+/// Liveness check: responds with 200 unless a component has crashed. Components that are initializing
+/// or are affected by external issues (e.g., an unreachable main node) don't influence liveness, so that
+/// the orchestrator doesn't restart the application because of them.
+async fn check_liveness<T: AsRef<dyn CheckHealth>>(
+    health_checks: State<Arc<[T]>>,
+) -> (StatusCode, Json<AppHealth>) {
+    let response = AppHealth::new(&health_checks).await;
+    let response_code = if response.is_live() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (response_code, Json(response))
+}
+
 async fn run_server<T>(
     bind_address: &SocketAddr,
     health_checks: Vec<T>,
@@ -40,6 +56,8 @@ async fn run_server<T>(
     let health_checks = Arc::from(health_checks);
     let app = Router::new()
         .route("/health", get(check_health))
+        .route("/health/ready", get(check_health))
+        .route("/health/live", get(check_liveness))
         .with_state(health_checks);
 
     axum::Server::bind(bind_address)
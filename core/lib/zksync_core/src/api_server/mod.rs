@@ -3,6 +3,7 @@
 pub mod contract_verification;
 pub mod execution_sandbox;
 pub mod healthcheck;
+pub mod rosetta;
 pub mod tree;
 pub mod tx_sender;
 pub mod web3;
@@ -0,0 +1,31 @@
+//! Metrics for the Rosetta API.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics, Unit};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "method", rename_all = "snake_case")]
+pub(super) enum RosettaApiMethod {
+    NetworkList,
+    NetworkOptions,
+    NetworkStatus,
+    Block,
+    BlockTransaction,
+    AccountBalance,
+    Mempool,
+}
+
+/// Metrics for the Rosetta API.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_rosetta_api")]
+pub(super) struct RosettaApiMetrics {
+    /// Server latency of the Rosetta API methods.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub latency: Family<RosettaApiMethod, Histogram<Duration>>,
+    /// Number of errors returned by the Rosetta API methods.
+    pub errors: Family<RosettaApiMethod, Counter>,
+}
+
+#[vise::register]
+pub(super) static API_METRICS: vise::Global<RosettaApiMetrics> = vise::Global::new();
@@ -0,0 +1,1042 @@
+//! Rosetta (Mesh) API used by exchanges to integrate with the chain.
+//!
+//! Only the Data API is supported; transactions should be constructed and submitted via the Web3 API.
+//! Operations are derived from events emitted by the base token contract and bridged ERC20 tokens
+//! (i.e., tokens in the `tokens` table). For the base token:
+//!
+//! - `Transfer` events are mapped to a debit / credit pair of `TRANSFER` operations, or `FEE` operations
+//!   if the bootloader is the sender or the recipient (i.e., for fee payments and refunds).
+//! - `Mint` events emitted when processing deposits are mapped to `DEPOSIT` credits.
+//! - `Withdrawal` events are mapped to `WITHDRAWAL` debits of the base token contract, which holds the withdrawn
+//!   funds before they are burned.
+//!
+//! For ERC20 tokens, `Transfer` events are mapped to `TRANSFER` pairs, except for mints (i.e., transfers
+//! from the zero address) and burns (transfers to the zero address) performed by the bridge, which are mapped
+//! to `DEPOSIT` credits and `WITHDRAWAL` debits respectively. ERC20 currencies are identified by
+//! the `contract_address` in their metadata.
+//!
+//! Blocks with more than `max_block_events` events are paged: `/block` returns operations only
+//! for the leading transactions fitting into the limit and lists the remaining transactions
+//! in `other_transactions`, which should be fetched via `/block/transaction`.
+//!
+//! Since events are only persisted for applied state changes, all operations have the `SUCCESS` status.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    str::FromStr,
+};
+
+use anyhow::Context as _;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing, Json, Router,
+};
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+use zksync_config::RosettaApiConfig;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    api,
+    ethabi::{self, ParamType},
+    event::TRANSFER_EVENT_SIGNATURE,
+    tokens::TokenInfo,
+    AccountTreeId, Address, L2ChainId, MiniblockNumber, BOOTLOADER_ADDRESS, H256,
+    L2_ETH_TOKEN_ADDRESS, U256,
+};
+use zksync_utils::h256_to_account_address;
+
+use self::{
+    metrics::{RosettaApiMethod, API_METRICS},
+    types::*,
+};
+use crate::api_server::execution_sandbox::BlockStartInfo;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+mod types;
+
+/// Key of the ERC20 token contract address in the currency metadata.
+const CONTRACT_ADDRESS_METADATA_KEY: &str = "contract_address";
+
+/// Maximum number of transactions returned by the `/mempool` endpoint.
+const MEMPOOL_LIMIT: usize = 1_000;
+
+/// Signature of the `Mint(address,uint256)` event emitted by the base token contract.
+static MINT_EVENT_SIGNATURE: Lazy<H256> =
+    Lazy::new(|| ethabi::long_signature("Mint", &[ParamType::Address, ParamType::Uint(256)]));
+
+/// Signature of the `Withdrawal(address,address,uint256)` event emitted by the base token contract.
+static WITHDRAWAL_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "Withdrawal",
+        &[ParamType::Address, ParamType::Address, ParamType::Uint(256)],
+    )
+});
+
+/// Signature of the `WithdrawalWithMessage(address,address,uint256,bytes)` event emitted by the base token contract.
+static WITHDRAWAL_WITH_MESSAGE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "WithdrawalWithMessage",
+        &[
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Uint(256),
+            ParamType::Bytes,
+        ],
+    )
+});
+
+#[derive(Debug)]
+enum RosettaError {
+    UnknownNetwork,
+    InvalidRequest(String),
+    BlockNotFound,
+    BlockPruned(MiniblockNumber),
+    TransactionNotFound,
+    UnsupportedCurrency,
+    Internal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for RosettaError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::Internal(err)
+    }
+}
+
+impl RosettaError {
+    fn code(&self) -> u32 {
+        match self {
+            Self::UnknownNetwork => 1,
+            Self::InvalidRequest(_) => 2,
+            Self::BlockNotFound => 3,
+            Self::BlockPruned(_) => 4,
+            Self::TransactionNotFound => 5,
+            Self::UnsupportedCurrency => 6,
+            // Code 7 was used for the removed "too many events" error.
+            Self::Internal(_) => 8,
+        }
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            Self::UnknownNetwork => "Unknown network",
+            Self::InvalidRequest(_) => "Invalid request",
+            Self::BlockNotFound => "Block not found",
+            Self::BlockPruned(_) => "Block is pruned",
+            Self::TransactionNotFound => "Transaction not found",
+            Self::UnsupportedCurrency => "Unsupported currency",
+            Self::Internal(_) => "Internal error",
+        }
+    }
+
+    fn retriable(&self) -> bool {
+        // Blocks may be missing because they are not sealed yet.
+        matches!(self, Self::BlockNotFound | Self::Internal(_))
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::InvalidRequest(message) => Some(serde_json::json!({ "error": message })),
+            Self::BlockPruned(first_retained) => {
+                Some(serde_json::json!({ "first_retained_block": first_retained.0 }))
+            }
+            _ => None,
+        }
+    }
+
+    fn to_response(&self) -> ErrorResponse {
+        ErrorResponse {
+            code: self.code(),
+            message: self.message().to_owned(),
+            retriable: self.retriable(),
+            details: self.details(),
+        }
+    }
+
+    /// Returns descriptions of all errors that can be returned by the API.
+    fn all() -> Vec<ErrorResponse> {
+        let errors = [
+            Self::UnknownNetwork,
+            Self::InvalidRequest(String::new()),
+            Self::BlockNotFound,
+            Self::BlockPruned(MiniblockNumber(0)),
+            Self::TransactionNotFound,
+            Self::UnsupportedCurrency,
+            Self::Internal(anyhow::anyhow!("")),
+        ];
+        errors
+            .iter()
+            .map(|err| ErrorResponse {
+                details: None,
+                ..err.to_response()
+            })
+            .collect()
+    }
+}
+
+impl IntoResponse for RosettaError {
+    fn into_response(self) -> Response {
+        // Rosetta requires all errors to be returned with the 500 status code.
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(self.to_response())).into_response()
+    }
+}
+
+fn parse_h256(hash: &str) -> Result<H256, RosettaError> {
+    let hash = hash.strip_prefix("0x").unwrap_or(hash);
+    H256::from_str(hash)
+        .map_err(|err| RosettaError::InvalidRequest(format!("invalid hash {hash:?}: {err}")))
+}
+
+fn parse_address(address: &str) -> Result<Address, RosettaError> {
+    let address = address.strip_prefix("0x").unwrap_or(address);
+    Address::from_str(address)
+        .map_err(|err| RosettaError::InvalidRequest(format!("invalid address {address:?}: {err}")))
+}
+
+fn parse_amount(data: &[u8]) -> Option<U256> {
+    data.get(..32).map(U256::from_big_endian)
+}
+
+fn block_identifier(block: &api::Block<api::TransactionVariant>) -> BlockIdentifier {
+    BlockIdentifier {
+        index: block.number.as_u64(),
+        hash: format!("{:?}", block.hash),
+    }
+}
+
+fn parent_block_identifier(block: &api::Block<api::TransactionVariant>) -> BlockIdentifier {
+    if block.number.is_zero() {
+        // Rosetta requires the genesis block to be its own parent.
+        block_identifier(block)
+    } else {
+        BlockIdentifier {
+            index: block.number.as_u64() - 1,
+            hash: format!("{:?}", block.parent_hash),
+        }
+    }
+}
+
+fn transaction_hashes(block: &api::Block<api::TransactionVariant>) -> Vec<H256> {
+    block
+        .transactions
+        .iter()
+        .map(|tx| match tx {
+            api::TransactionVariant::Hash(hash) => *hash,
+            api::TransactionVariant::Full(tx) => tx.hash,
+        })
+        .collect()
+}
+
+/// Converts information about a bridged ERC20 token to a Rosetta currency.
+fn token_currency(token: &TokenInfo) -> Currency {
+    Currency {
+        symbol: token.metadata.symbol.clone(),
+        decimals: token.metadata.decimals.into(),
+        metadata: Some(serde_json::json!({
+            CONTRACT_ADDRESS_METADATA_KEY: format!("{:?}", token.l2_address),
+        })),
+    }
+}
+
+/// Currencies supported by the API keyed by the token contract address.
+#[derive(Debug)]
+struct Currencies<'a> {
+    base_token: &'a Currency,
+    tokens: BTreeMap<Address, Currency>,
+}
+
+impl<'a> Currencies<'a> {
+    fn new(base_token: &'a Currency, tokens: &[TokenInfo]) -> Self {
+        let tokens = tokens
+            .iter()
+            .filter(|token| token.l2_address != L2_ETH_TOKEN_ADDRESS)
+            .map(|token| (token.l2_address, token_currency(token)))
+            .collect();
+        Self { base_token, tokens }
+    }
+
+    fn addresses(&self) -> Vec<Address> {
+        let token_addresses = self.tokens.keys().copied();
+        [L2_ETH_TOKEN_ADDRESS]
+            .into_iter()
+            .chain(token_addresses)
+            .collect()
+    }
+
+    fn get(&self, address: Address) -> Option<&Currency> {
+        if address == L2_ETH_TOKEN_ADDRESS {
+            Some(self.base_token)
+        } else {
+            self.tokens.get(&address)
+        }
+    }
+
+    /// Resolves the token contract for a currency specified in a request. ERC20 currencies are looked up
+    /// by the contract address in their metadata, or by the symbol and decimals if the metadata is missing.
+    fn resolve(&self, currency: &Currency) -> Result<Address, RosettaError> {
+        if currency == self.base_token {
+            return Ok(L2_ETH_TOKEN_ADDRESS);
+        }
+        let contract_address = currency
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(CONTRACT_ADDRESS_METADATA_KEY));
+        if let Some(contract_address) = contract_address {
+            let address = contract_address
+                .as_str()
+                .ok_or(RosettaError::UnsupportedCurrency)?;
+            let address = parse_address(address)?;
+            let token_currency = self
+                .tokens
+                .get(&address)
+                .ok_or(RosettaError::UnsupportedCurrency)?;
+            return if token_currency.symbol == currency.symbol
+                && token_currency.decimals == currency.decimals
+            {
+                Ok(address)
+            } else {
+                Err(RosettaError::UnsupportedCurrency)
+            };
+        }
+
+        let mut matching_tokens = self.tokens.iter().filter(|(_, token_currency)| {
+            token_currency.symbol == currency.symbol && token_currency.decimals == currency.decimals
+        });
+        match (matching_tokens.next(), matching_tokens.next()) {
+            (Some((&address, _)), None) => Ok(address),
+            (Some(_), Some(_)) => Err(RosettaError::InvalidRequest(format!(
+                "currency {} is ambiguous; specify `{CONTRACT_ADDRESS_METADATA_KEY}` in its metadata",
+                currency.symbol
+            ))),
+            (None, _) => Err(RosettaError::UnsupportedCurrency),
+        }
+    }
+}
+
+/// Builder of Rosetta operations for a single transaction.
+#[derive(Debug)]
+struct OperationsBuilder<'a> {
+    currencies: &'a Currencies<'a>,
+    operations: Vec<Operation>,
+}
+
+impl<'a> OperationsBuilder<'a> {
+    fn new(currencies: &'a Currencies<'a>) -> Self {
+        Self {
+            currencies,
+            operations: vec![],
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        currency: &Currency,
+        op_type: &str,
+        account: Address,
+        value: U256,
+        is_debit: bool,
+        related_operations: Vec<OperationIdentifier>,
+        metadata: Option<serde_json::Value>,
+    ) -> OperationIdentifier {
+        let operation_identifier = OperationIdentifier {
+            index: self.operations.len() as u64,
+        };
+        let sign = if is_debit { "-" } else { "" };
+        self.operations.push(Operation {
+            operation_identifier,
+            related_operations,
+            op_type: op_type.to_owned(),
+            status: OP_STATUS_SUCCESS.to_owned(),
+            account: AccountIdentifier {
+                address: format!("{account:?}"),
+            },
+            amount: Amount {
+                value: format!("{sign}{value}"),
+                currency: currency.clone(),
+            },
+            metadata,
+        });
+        operation_identifier
+    }
+
+    fn push_log(&mut self, log: &api::Log) {
+        if log.address == L2_ETH_TOKEN_ADDRESS {
+            self.push_base_token_log(log);
+        } else if let Some(currency) = self.currencies.get(log.address) {
+            self.push_token_log(currency, log);
+        } else {
+            tracing::warn!(
+                "Skipping event of unknown token {:?} in transaction {:?}",
+                log.address,
+                log.transaction_hash
+            );
+        }
+    }
+
+    fn push_base_token_log(&mut self, log: &api::Log) {
+        let currency = self.currencies.base_token;
+        let signature = log.topics.first().copied().unwrap_or_default();
+        let amount = parse_amount(&log.data.0);
+        match (log.topics.as_slice(), amount) {
+            ([_, from, to], Some(value)) if signature == *TRANSFER_EVENT_SIGNATURE => {
+                let from = h256_to_account_address(from);
+                let to = h256_to_account_address(to);
+                let op_type = if from == BOOTLOADER_ADDRESS || to == BOOTLOADER_ADDRESS {
+                    OP_TYPE_FEE
+                } else {
+                    OP_TYPE_TRANSFER
+                };
+                let debit = self.push(currency, op_type, from, value, true, vec![], None);
+                self.push(currency, op_type, to, value, false, vec![debit], None);
+            }
+            ([_, account], Some(value)) if signature == *MINT_EVENT_SIGNATURE => {
+                let account = h256_to_account_address(account);
+                let op_type = OP_TYPE_DEPOSIT;
+                self.push(currency, op_type, account, value, false, vec![], None);
+            }
+            ([_, l2_sender, l1_receiver], Some(value))
+                if signature == *WITHDRAWAL_EVENT_SIGNATURE
+                    || signature == *WITHDRAWAL_WITH_MESSAGE_EVENT_SIGNATURE =>
+            {
+                let metadata = serde_json::json!({
+                    "l2_sender": h256_to_account_address(l2_sender),
+                    "l1_receiver": h256_to_account_address(l1_receiver),
+                });
+                let account = L2_ETH_TOKEN_ADDRESS;
+                self.push(
+                    currency,
+                    OP_TYPE_WITHDRAWAL,
+                    account,
+                    value,
+                    true,
+                    vec![],
+                    Some(metadata),
+                );
+            }
+            _ => {
+                tracing::warn!(
+                    "Skipping unexpected base token event in transaction {:?}: {log:?}",
+                    log.transaction_hash
+                );
+            }
+        }
+    }
+
+    fn push_token_log(&mut self, currency: &Currency, log: &api::Log) {
+        // Other events (e.g., bridge-specific ones) don't change balances on their own.
+        if log.topics.first() != Some(&*TRANSFER_EVENT_SIGNATURE) {
+            return;
+        }
+        let amount = parse_amount(&log.data.0);
+        let ([_, from, to], Some(value)) = (log.topics.as_slice(), amount) else {
+            tracing::warn!(
+                "Skipping unexpected ERC20 event in transaction {:?}: {log:?}",
+                log.transaction_hash
+            );
+            return;
+        };
+
+        let from = h256_to_account_address(from);
+        let to = h256_to_account_address(to);
+        if from == Address::zero() {
+            self.push(currency, OP_TYPE_DEPOSIT, to, value, false, vec![], None);
+        } else if to == Address::zero() {
+            self.push(
+                currency,
+                OP_TYPE_WITHDRAWAL,
+                from,
+                value,
+                true,
+                vec![],
+                None,
+            );
+        } else {
+            let op_type = OP_TYPE_TRANSFER;
+            let debit = self.push(currency, op_type, from, value, true, vec![], None);
+            self.push(currency, op_type, to, value, false, vec![debit], None);
+        }
+    }
+}
+
+/// Operations of transactions in a block.
+#[derive(Debug)]
+struct BlockTransactions {
+    /// Transactions with operations, in the block order.
+    transactions: Vec<Transaction>,
+    /// Transactions which operations didn't fit into the limit and must be loaded separately.
+    other_transactions: Vec<TransactionIdentifier>,
+}
+
+/// Rosetta API server state.
+#[derive(Debug, Clone)]
+pub struct RosettaApi {
+    pool: ConnectionPool,
+    chain_id: L2ChainId,
+    network_identifier: NetworkIdentifier,
+    currency: Currency,
+    max_block_events: usize,
+}
+
+impl RosettaApi {
+    pub fn new(pool: ConnectionPool, chain_id: L2ChainId, config: &RosettaApiConfig) -> Self {
+        Self {
+            pool,
+            chain_id,
+            network_identifier: NetworkIdentifier {
+                blockchain: BLOCKCHAIN.to_owned(),
+                network: config.network.clone(),
+            },
+            currency: Currency {
+                symbol: config.base_token_symbol.clone(),
+                decimals: config.base_token_decimals,
+                metadata: None,
+            },
+            max_block_events: config.max_block_events,
+        }
+    }
+
+    fn check_network(&self, network_identifier: &NetworkIdentifier) -> Result<(), RosettaError> {
+        if *network_identifier == self.network_identifier {
+            Ok(())
+        } else {
+            Err(RosettaError::UnknownNetwork)
+        }
+    }
+
+    async fn access_storage(&self) -> Result<StorageProcessor<'_>, RosettaError> {
+        Ok(self.pool.access_storage_tagged("rosetta_api").await?)
+    }
+
+    async fn get_block(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block_id: api::BlockId,
+    ) -> Result<Option<api::Block<api::TransactionVariant>>, RosettaError> {
+        let block = storage
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(block_id, false, self.chain_id)
+            .await
+            .with_context(|| format!("failed getting block {block_id:?}"))?;
+        Ok(block)
+    }
+
+    async fn resolve_block(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block_identifier: &PartialBlockIdentifier,
+    ) -> Result<api::Block<api::TransactionVariant>, RosettaError> {
+        let expected_hash = block_identifier
+            .hash
+            .as_deref()
+            .map(parse_h256)
+            .transpose()?;
+        let block_id = match (block_identifier.index, expected_hash) {
+            (Some(index), _) => api::BlockId::Number(api::BlockNumber::Number(index.into())),
+            (None, Some(hash)) => api::BlockId::Hash(hash),
+            (None, None) => api::BlockId::Number(api::BlockNumber::Latest),
+        };
+
+        let start_info = BlockStartInfo::new(storage).await?;
+        let first_miniblock = start_info.first_miniblock;
+        if matches!(block_identifier.index, Some(index) if index < u64::from(first_miniblock.0)) {
+            return Err(RosettaError::BlockPruned(first_miniblock));
+        }
+        let block = self
+            .get_block(storage, block_id)
+            .await?
+            .ok_or(RosettaError::BlockNotFound)?;
+        if block.number.as_u64() < u64::from(first_miniblock.0) {
+            return Err(RosettaError::BlockPruned(first_miniblock));
+        }
+        if matches!(expected_hash, Some(hash) if hash != block.hash) {
+            return Err(RosettaError::BlockNotFound);
+        }
+        Ok(block)
+    }
+
+    async fn load_currencies(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<Currencies<'_>, RosettaError> {
+        let tokens = storage
+            .tokens_web3_dal()
+            .get_all_tokens(None)
+            .await
+            .context("failed getting tokens")?;
+        Ok(Currencies::new(&self.currency, &tokens))
+    }
+
+    /// Loads up to `limit` token events in the specified block (optionally, only for the specified transaction)
+    /// following the event with the `after_log_index` index in the block.
+    async fn load_logs(
+        storage: &mut StorageProcessor<'_>,
+        number: MiniblockNumber,
+        tx_hash: Option<H256>,
+        currencies: &Currencies<'_>,
+        after_log_index: Option<u32>,
+        limit: usize,
+    ) -> Result<Vec<api::Log>, RosettaError> {
+        let logs = storage
+            .events_web3_dal()
+            .get_miniblock_logs(
+                number,
+                tx_hash,
+                &currencies.addresses(),
+                &[
+                    *TRANSFER_EVENT_SIGNATURE,
+                    *MINT_EVENT_SIGNATURE,
+                    *WITHDRAWAL_EVENT_SIGNATURE,
+                    *WITHDRAWAL_WITH_MESSAGE_EVENT_SIGNATURE,
+                ],
+                after_log_index,
+                limit,
+            )
+            .await
+            .with_context(|| format!("failed getting token events for block #{number}"))?;
+        Ok(logs)
+    }
+
+    fn transaction(
+        tx_hash: H256,
+        currencies: &Currencies<'_>,
+        logs: impl IntoIterator<Item = api::Log>,
+    ) -> Transaction {
+        let mut builder = OperationsBuilder::new(currencies);
+        for log in logs {
+            builder.push_log(&log);
+        }
+        Transaction {
+            transaction_identifier: TransactionIdentifier {
+                hash: format!("{tx_hash:?}"),
+            },
+            operations: builder.operations,
+        }
+    }
+
+    /// Loads operations for transactions in the specified block. Transactions are returned in the block order,
+    /// including transactions without any operations. If the block has more than `max_block_events` events,
+    /// operations are only loaded for the leading transactions with all their events fitting into the limit.
+    async fn load_transactions(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block: &api::Block<api::TransactionVariant>,
+    ) -> Result<BlockTransactions, RosettaError> {
+        let number = MiniblockNumber(block.number.as_u32());
+        let currencies = self.load_currencies(storage).await?;
+        let limit = self.max_block_events;
+        let mut logs = Self::load_logs(storage, number, None, &currencies, None, limit + 1).await?;
+        // The transaction which events don't fit into the limit (if any). Since events are ordered
+        // by their index in the block, all events of the preceding transactions are loaded.
+        let mut first_truncated_tx = None;
+        if logs.len() > limit {
+            first_truncated_tx = logs[limit].transaction_hash;
+            logs.retain(|log| log.transaction_hash != first_truncated_tx);
+        }
+
+        let mut tx_hashes = transaction_hashes(block);
+        let mut logs_by_tx = HashMap::<_, Vec<_>>::new();
+        for log in logs {
+            let Some(tx_hash) = log.transaction_hash else {
+                continue;
+            };
+            if !logs_by_tx.contains_key(&tx_hash) && !tx_hashes.contains(&tx_hash) {
+                tx_hashes.push(tx_hash);
+            }
+            logs_by_tx.entry(tx_hash).or_default().push(log);
+        }
+        if let Some(tx_hash) = first_truncated_tx {
+            if !tx_hashes.contains(&tx_hash) {
+                tx_hashes.push(tx_hash);
+            }
+        }
+
+        let mut transactions = vec![];
+        let mut other_transactions = vec![];
+        for tx_hash in tx_hashes {
+            if Some(tx_hash) == first_truncated_tx || !other_transactions.is_empty() {
+                other_transactions.push(TransactionIdentifier {
+                    hash: format!("{tx_hash:?}"),
+                });
+            } else {
+                let logs = logs_by_tx.remove(&tx_hash).unwrap_or_default();
+                transactions.push(Self::transaction(tx_hash, &currencies, logs));
+            }
+        }
+        Ok(BlockTransactions {
+            transactions,
+            other_transactions,
+        })
+    }
+
+    /// Loads operations for a single transaction in the specified block, paging its events
+    /// by `max_block_events`.
+    async fn load_transaction(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block: &api::Block<api::TransactionVariant>,
+        tx_hash: H256,
+    ) -> Result<Transaction, RosettaError> {
+        let number = MiniblockNumber(block.number.as_u32());
+        let currencies = self.load_currencies(storage).await?;
+        let limit = self.max_block_events;
+        let mut logs: Vec<api::Log> = vec![];
+        loop {
+            let after_log_index = logs
+                .last()
+                .and_then(|log| log.log_index)
+                .map(|index| index.as_u32());
+            let page = Self::load_logs(
+                storage,
+                number,
+                Some(tx_hash),
+                &currencies,
+                after_log_index,
+                limit,
+            )
+            .await?;
+            let is_last_page = page.len() < limit;
+            logs.extend(page);
+            if is_last_page {
+                break;
+            }
+        }
+        Ok(Self::transaction(tx_hash, &currencies, logs))
+    }
+
+    fn network_list(&self) -> NetworkListResponse {
+        NetworkListResponse {
+            network_identifiers: vec![self.network_identifier.clone()],
+        }
+    }
+
+    fn network_options(
+        &self,
+        request: NetworkRequest,
+    ) -> Result<NetworkOptionsResponse, RosettaError> {
+        self.check_network(&request.network_identifier)?;
+        Ok(NetworkOptionsResponse {
+            version: Version {
+                rosetta_version: ROSETTA_VERSION.to_owned(),
+                node_version: env!("CARGO_PKG_VERSION").to_owned(),
+            },
+            allow: Allow {
+                operation_statuses: vec![OperationStatus {
+                    status: OP_STATUS_SUCCESS.to_owned(),
+                    successful: true,
+                }],
+                operation_types: OPERATION_TYPES.iter().map(|&ty| ty.to_owned()).collect(),
+                errors: RosettaError::all(),
+                historical_balance_lookup: true,
+            },
+        })
+    }
+
+    async fn network_status(
+        &self,
+        request: NetworkRequest,
+    ) -> Result<NetworkStatusResponse, RosettaError> {
+        self.check_network(&request.network_identifier)?;
+        let mut storage = self.access_storage().await?;
+        let start_info = BlockStartInfo::new(&mut storage).await?;
+        let latest_block = self
+            .get_block(&mut storage, api::BlockId::Number(api::BlockNumber::Latest))
+            .await?
+            .ok_or(RosettaError::BlockNotFound)?;
+        let oldest_block_number = api::BlockNumber::Number(start_info.first_miniblock.0.into());
+        let oldest_block = self
+            .get_block(&mut storage, api::BlockId::Number(oldest_block_number))
+            .await?
+            .ok_or(RosettaError::BlockNotFound)?;
+
+        Ok(NetworkStatusResponse {
+            current_block_identifier: block_identifier(&latest_block),
+            current_block_timestamp: latest_block.timestamp.as_u64() * 1_000,
+            // If the node was recovered from a snapshot or pruned, the genesis block is not available,
+            // so we report the oldest available block instead.
+            genesis_block_identifier: block_identifier(&oldest_block),
+            oldest_block_identifier: block_identifier(&oldest_block),
+            peers: vec![],
+        })
+    }
+
+    async fn block(&self, request: BlockRequest) -> Result<BlockResponse, RosettaError> {
+        self.check_network(&request.network_identifier)?;
+        let mut storage = self.access_storage().await?;
+        let block = self
+            .resolve_block(&mut storage, &request.block_identifier)
+            .await?;
+        let transactions = self.load_transactions(&mut storage, &block).await?;
+        Ok(BlockResponse {
+            block: Block {
+                block_identifier: block_identifier(&block),
+                parent_block_identifier: parent_block_identifier(&block),
+                timestamp: block.timestamp.as_u64() * 1_000,
+                transactions: transactions.transactions,
+            },
+            other_transactions: transactions.other_transactions,
+        })
+    }
+
+    async fn block_transaction(
+        &self,
+        request: BlockTransactionRequest,
+    ) -> Result<BlockTransactionResponse, RosettaError> {
+        self.check_network(&request.network_identifier)?;
+        let tx_hash = parse_h256(&request.transaction_identifier.hash)?;
+        let block_identifier = PartialBlockIdentifier {
+            index: Some(request.block_identifier.index),
+            hash: Some(request.block_identifier.hash),
+        };
+
+        let mut storage = self.access_storage().await?;
+        let block = self.resolve_block(&mut storage, &block_identifier).await?;
+        if !transaction_hashes(&block).contains(&tx_hash) {
+            return Err(RosettaError::TransactionNotFound);
+        }
+        let transaction = self.load_transaction(&mut storage, &block, tx_hash).await?;
+        Ok(BlockTransactionResponse { transaction })
+    }
+
+    async fn account_balance(
+        &self,
+        request: AccountBalanceRequest,
+    ) -> Result<AccountBalanceResponse, RosettaError> {
+        self.check_network(&request.network_identifier)?;
+        let address = parse_address(&request.account_identifier.address)?;
+
+        let mut storage = self.access_storage().await?;
+        let block = self
+            .resolve_block(&mut storage, &request.block_identifier)
+            .await?;
+        let number = MiniblockNumber(block.number.as_u32());
+        // If currencies are not specified, balances for all supported tokens are returned.
+        let currencies = self.load_currencies(&mut storage).await?;
+        let token_addresses = match &request.currencies {
+            Some(currencies_request) => currencies_request
+                .iter()
+                .map(|currency| currencies.resolve(currency))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => currencies.addresses(),
+        };
+
+        let mut balances = Vec::with_capacity(token_addresses.len());
+        for token_address in token_addresses {
+            let balance = storage
+                .storage_web3_dal()
+                .standard_token_historical_balance(
+                    AccountTreeId::new(token_address),
+                    AccountTreeId::new(address),
+                    number,
+                )
+                .await
+                .with_context(|| {
+                    format!("failed getting balance of token {token_address:?} for {address:?}")
+                })?;
+            let currency = currencies
+                .get(token_address)
+                .expect("resolved token has no currency");
+            balances.push(Amount {
+                value: balance.to_string(),
+                currency: currency.clone(),
+            });
+        }
+
+        Ok(AccountBalanceResponse {
+            block_identifier: block_identifier(&block),
+            balances,
+        })
+    }
+
+    async fn mempool(&self, request: NetworkRequest) -> Result<MempoolResponse, RosettaError> {
+        self.check_network(&request.network_identifier)?;
+        let mut storage = self.access_storage().await?;
+        let tx_hashes = storage
+            .transactions_web3_dal()
+            .get_mempool_tx_hashes(MEMPOOL_LIMIT)
+            .await
+            .context("failed getting mempool transactions")?;
+        let transaction_identifiers = tx_hashes
+            .into_iter()
+            .map(|hash| TransactionIdentifier {
+                hash: format!("{hash:?}"),
+            })
+            .collect();
+        Ok(MempoolResponse {
+            transaction_identifiers,
+        })
+    }
+
+    async fn observe<T>(
+        method: RosettaApiMethod,
+        future: impl Future<Output = Result<T, RosettaError>>,
+    ) -> Result<Json<T>, RosettaError> {
+        let latency = API_METRICS.latency[&method].start();
+        let result = future.await;
+        latency.observe();
+        if let Err(err) = &result {
+            API_METRICS.errors[&method].inc();
+            if let RosettaError::Internal(err) = err {
+                tracing::error!("Internal error in Rosetta API method {method:?}: {err:#}");
+            }
+        }
+        result.map(Json)
+    }
+
+    async fn network_list_handler(State(this): State<Self>) -> Json<NetworkListResponse> {
+        let latency = API_METRICS.latency[&RosettaApiMethod::NetworkList].start();
+        let response = this.network_list();
+        latency.observe();
+        Json(response)
+    }
+
+    async fn network_options_handler(
+        State(this): State<Self>,
+        Json(request): Json<NetworkRequest>,
+    ) -> Result<Json<NetworkOptionsResponse>, RosettaError> {
+        let future = async { this.network_options(request) };
+        Self::observe(RosettaApiMethod::NetworkOptions, future).await
+    }
+
+    async fn network_status_handler(
+        State(this): State<Self>,
+        Json(request): Json<NetworkRequest>,
+    ) -> Result<Json<NetworkStatusResponse>, RosettaError> {
+        Self::observe(
+            RosettaApiMethod::NetworkStatus,
+            this.network_status(request),
+        )
+        .await
+    }
+
+    async fn block_handler(
+        State(this): State<Self>,
+        Json(request): Json<BlockRequest>,
+    ) -> Result<Json<BlockResponse>, RosettaError> {
+        Self::observe(RosettaApiMethod::Block, this.block(request)).await
+    }
+
+    async fn block_transaction_handler(
+        State(this): State<Self>,
+        Json(request): Json<BlockTransactionRequest>,
+    ) -> Result<Json<BlockTransactionResponse>, RosettaError> {
+        let future = this.block_transaction(request);
+        Self::observe(RosettaApiMethod::BlockTransaction, future).await
+    }
+
+    async fn account_balance_handler(
+        State(this): State<Self>,
+        Json(request): Json<AccountBalanceRequest>,
+    ) -> Result<Json<AccountBalanceResponse>, RosettaError> {
+        let future = this.account_balance(request);
+        Self::observe(RosettaApiMethod::AccountBalance, future).await
+    }
+
+    async fn mempool_handler(
+        State(this): State<Self>,
+        Json(request): Json<NetworkRequest>,
+    ) -> Result<Json<MempoolResponse>, RosettaError> {
+        Self::observe(RosettaApiMethod::Mempool, this.mempool(request)).await
+    }
+
+    fn create_api_server(
+        self,
+        bind_address: &SocketAddr,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<RosettaApiServer> {
+        tracing::debug!("Starting Rosetta API server on {bind_address}");
+
+        let app = Router::new()
+            .route("/network/list", routing::post(Self::network_list_handler))
+            .route(
+                "/network/options",
+                routing::post(Self::network_options_handler),
+            )
+            .route(
+                "/network/status",
+                routing::post(Self::network_status_handler),
+            )
+            .route("/block", routing::post(Self::block_handler))
+            .route(
+                "/block/transaction",
+                routing::post(Self::block_transaction_handler),
+            )
+            .route(
+                "/account/balance",
+                routing::post(Self::account_balance_handler),
+            )
+            .route("/mempool", routing::post(Self::mempool_handler))
+            .with_state(self);
+
+        let server = axum::Server::try_bind(bind_address)
+            .with_context(|| format!("Failed binding Rosetta API server to {bind_address}"))?
+            .serve(app.into_make_service());
+        let local_addr = server.local_addr();
+        let server_future = async move {
+            server
+                .with_graceful_shutdown(async move {
+                    if stop_receiver.changed().await.is_err() {
+                        tracing::warn!(
+                            "Stop signal sender for Rosetta API server was dropped without sending a signal"
+                        );
+                    }
+                    tracing::info!("Stop signal received, Rosetta API server is shutting down");
+                })
+                .await
+                .context("Rosetta API server failed")?;
+
+            tracing::info!("Rosetta API server shut down");
+            Ok(())
+        };
+
+        Ok(RosettaApiServer {
+            local_addr,
+            server_future: Box::pin(server_future),
+        })
+    }
+
+    /// Runs the HTTP API server.
+    pub async fn run(
+        self,
+        bind_address: SocketAddr,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        self.create_api_server(&bind_address, stop_receiver)?
+            .run()
+            .await
+    }
+}
+
+/// `axum`-powered REST server for the Rosetta API.
+#[must_use = "Server must be `run()`"]
+struct RosettaApiServer {
+    local_addr: SocketAddr,
+    server_future: Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>,
+}
+
+impl fmt::Debug for RosettaApiServer {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RosettaApiServer")
+            .field("local_addr", &self.local_addr)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RosettaApiServer {
+    #[cfg(test)]
+    fn local_addr(&self) -> &SocketAddr {
+        &self.local_addr
+    }
+
+    async fn run(self) -> anyhow::Result<()> {
+        self.server_future.await
+    }
+}
@@ -0,0 +1,618 @@
+//! Tests for the Rosetta API.
+
+use std::net::Ipv4Addr;
+
+use assert_matches::assert_matches;
+use zksync_types::{
+    fee::TransactionExecutionMetrics,
+    tokens::TokenMetadata,
+    tx::IncludedTxLocation,
+    utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
+    web3::types::Bytes,
+    L1BatchNumber, StorageLog, VmEvent,
+};
+use zksync_utils::{address_to_h256, u256_to_h256};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l2_transaction, create_miniblock, execute_l2_transaction},
+};
+
+const SENDER: Address = Address::repeat_byte(1);
+const RECIPIENT: Address = Address::repeat_byte(2);
+const TOKEN_ADDRESS: Address = Address::repeat_byte(0x10);
+
+fn test_config() -> RosettaApiConfig {
+    RosettaApiConfig {
+        port: 0,
+        network: "testnet".to_owned(),
+        base_token_symbol: "ETH".to_owned(),
+        base_token_decimals: 18,
+        max_block_events: 100,
+    }
+}
+
+fn network_request() -> NetworkRequest {
+    NetworkRequest {
+        network_identifier: NetworkIdentifier {
+            blockchain: BLOCKCHAIN.to_owned(),
+            network: "testnet".to_owned(),
+        },
+    }
+}
+
+fn base_currency() -> Currency {
+    Currency {
+        symbol: "ETH".to_owned(),
+        decimals: 18,
+        metadata: None,
+    }
+}
+
+fn test_token() -> TokenInfo {
+    TokenInfo {
+        l1_address: Address::repeat_byte(0x20),
+        l2_address: TOKEN_ADDRESS,
+        metadata: TokenMetadata {
+            name: "USD Coin".to_owned(),
+            symbol: "USDC".to_owned(),
+            decimals: 6,
+        },
+    }
+}
+
+fn base_token_log(tx_hash: H256, topics: Vec<H256>, amount: u64) -> api::Log {
+    token_log(L2_ETH_TOKEN_ADDRESS, tx_hash, topics, amount)
+}
+
+fn token_log(address: Address, tx_hash: H256, topics: Vec<H256>, amount: u64) -> api::Log {
+    api::Log {
+        address,
+        topics,
+        data: Bytes(u256_to_h256(amount.into()).0.to_vec()),
+        block_hash: None,
+        block_number: None,
+        l1_batch_number: None,
+        transaction_hash: Some(tx_hash),
+        transaction_index: None,
+        log_index: None,
+        transaction_log_index: None,
+        log_type: None,
+        removed: None,
+    }
+}
+
+fn base_token_event(index: u32, topics: Vec<H256>, amount: u64) -> VmEvent {
+    VmEvent {
+        location: (L1BatchNumber(1), index),
+        address: L2_ETH_TOKEN_ADDRESS,
+        indexed_topics: topics,
+        value: u256_to_h256(amount.into()).0.to_vec(),
+    }
+}
+
+fn transfer_topics(from: Address, to: Address) -> Vec<H256> {
+    vec![
+        *TRANSFER_EVENT_SIGNATURE,
+        address_to_h256(&from),
+        address_to_h256(&to),
+    ]
+}
+
+#[test]
+fn converting_base_token_events() {
+    let currency = base_currency();
+    let currencies = Currencies::new(&currency, &[]);
+    let tx_hash = H256::repeat_byte(0xaa);
+    let logs = [
+        base_token_log(tx_hash, transfer_topics(SENDER, BOOTLOADER_ADDRESS), 10),
+        base_token_log(tx_hash, transfer_topics(SENDER, RECIPIENT), 100),
+        base_token_log(
+            tx_hash,
+            vec![*MINT_EVENT_SIGNATURE, address_to_h256(&RECIPIENT)],
+            50,
+        ),
+        base_token_log(
+            tx_hash,
+            vec![
+                *WITHDRAWAL_EVENT_SIGNATURE,
+                address_to_h256(&SENDER),
+                address_to_h256(&RECIPIENT),
+            ],
+            20,
+        ),
+        // Malformed events are skipped.
+        base_token_log(tx_hash, vec![*TRANSFER_EVENT_SIGNATURE], 1),
+    ];
+
+    let mut builder = OperationsBuilder::new(&currencies);
+    for log in &logs {
+        builder.push_log(log);
+    }
+    let operations = builder.operations;
+    assert_eq!(operations.len(), 6);
+
+    let summary: Vec<_> = operations
+        .iter()
+        .map(|op| {
+            (
+                op.operation_identifier.index,
+                op.op_type.as_str(),
+                parse_address(&op.account.address).unwrap(),
+                op.amount.value.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (0, OP_TYPE_FEE, SENDER, "-10"),
+            (1, OP_TYPE_FEE, BOOTLOADER_ADDRESS, "10"),
+            (2, OP_TYPE_TRANSFER, SENDER, "-100"),
+            (3, OP_TYPE_TRANSFER, RECIPIENT, "100"),
+            (4, OP_TYPE_DEPOSIT, RECIPIENT, "50"),
+            (5, OP_TYPE_WITHDRAWAL, L2_ETH_TOKEN_ADDRESS, "-20"),
+        ]
+    );
+    assert_eq!(
+        operations[3].related_operations,
+        [OperationIdentifier { index: 2 }]
+    );
+    assert!(operations.iter().all(|op| op.status == OP_STATUS_SUCCESS));
+    let metadata = operations[5].metadata.as_ref().unwrap();
+    assert_eq!(metadata["l1_receiver"], format!("{RECIPIENT:?}"));
+}
+
+#[test]
+fn converting_erc20_events() {
+    let currency = base_currency();
+    let currencies = Currencies::new(&currency, &[test_token()]);
+    let tx_hash = H256::repeat_byte(0xaa);
+    let logs = [
+        token_log(
+            TOKEN_ADDRESS,
+            tx_hash,
+            transfer_topics(Address::zero(), SENDER),
+            100,
+        ),
+        token_log(
+            TOKEN_ADDRESS,
+            tx_hash,
+            transfer_topics(SENDER, RECIPIENT),
+            30,
+        ),
+        token_log(
+            TOKEN_ADDRESS,
+            tx_hash,
+            transfer_topics(RECIPIENT, Address::zero()),
+            10,
+        ),
+        // Events of unknown tokens are skipped.
+        token_log(
+            Address::repeat_byte(0x11),
+            tx_hash,
+            transfer_topics(SENDER, RECIPIENT),
+            1,
+        ),
+    ];
+
+    let mut builder = OperationsBuilder::new(&currencies);
+    for log in &logs {
+        builder.push_log(log);
+    }
+    let summary: Vec<_> = builder
+        .operations
+        .iter()
+        .map(|op| {
+            (
+                op.op_type.as_str(),
+                parse_address(&op.account.address).unwrap(),
+                op.amount.value.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (OP_TYPE_DEPOSIT, SENDER, "100"),
+            (OP_TYPE_TRANSFER, SENDER, "-30"),
+            (OP_TYPE_TRANSFER, RECIPIENT, "30"),
+            (OP_TYPE_WITHDRAWAL, RECIPIENT, "-10"),
+        ]
+    );
+    let expected_currency = token_currency(&test_token());
+    assert!(builder
+        .operations
+        .iter()
+        .all(|op| op.amount.currency == expected_currency));
+    assert_eq!(
+        expected_currency.metadata.unwrap()[CONTRACT_ADDRESS_METADATA_KEY],
+        format!("{TOKEN_ADDRESS:?}")
+    );
+}
+
+#[test]
+fn resolving_currencies() {
+    let currency = base_currency();
+    let currencies = Currencies::new(&currency, &[test_token()]);
+    assert_eq!(currencies.resolve(&currency).unwrap(), L2_ETH_TOKEN_ADDRESS);
+    let token_currency = token_currency(&test_token());
+    assert_eq!(currencies.resolve(&token_currency).unwrap(), TOKEN_ADDRESS);
+    let currency_without_metadata = Currency {
+        metadata: None,
+        ..token_currency.clone()
+    };
+    assert_eq!(
+        currencies.resolve(&currency_without_metadata).unwrap(),
+        TOKEN_ADDRESS
+    );
+
+    let unknown_currency = Currency {
+        symbol: "DAI".to_owned(),
+        decimals: 18,
+        metadata: None,
+    };
+    let err = currencies.resolve(&unknown_currency).unwrap_err();
+    assert_matches!(err, RosettaError::UnsupportedCurrency);
+    let mismatched_currency = Currency {
+        decimals: 18,
+        ..token_currency
+    };
+    let err = currencies.resolve(&mismatched_currency).unwrap_err();
+    assert_matches!(err, RosettaError::UnsupportedCurrency);
+}
+
+/// Stores miniblock #1 with a single transaction transferring the base token, and a pending transaction.
+/// Returns hashes of the executed and pending transactions.
+async fn prepare_storage(storage: &mut StorageProcessor<'_>) -> (H256, H256) {
+    ensure_genesis_state(storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let executed_tx = create_l2_transaction(10, 100);
+    let pending_tx = create_l2_transaction(10, 100);
+    let executed_tx_hash = executed_tx.hash();
+    let pending_tx_hash = pending_tx.hash();
+    for tx in [executed_tx.clone(), pending_tx] {
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+            .await;
+    }
+
+    let miniblock = create_miniblock(1);
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(
+            miniblock.number,
+            &[execute_l2_transaction(executed_tx)],
+            1.into(),
+        )
+        .await;
+
+    let tx_location = IncludedTxLocation {
+        tx_hash: executed_tx_hash,
+        tx_index_in_miniblock: 0,
+        tx_initiator_address: SENDER,
+    };
+    let events = [
+        base_token_event(0, transfer_topics(SENDER, BOOTLOADER_ADDRESS), 10),
+        base_token_event(1, transfer_topics(SENDER, RECIPIENT), 100),
+    ];
+    storage
+        .events_dal()
+        .save_events(miniblock.number, &[(tx_location, events.iter().collect())])
+        .await;
+
+    let balance_log = StorageLog::new_write_log(
+        storage_key_for_eth_balance(&RECIPIENT),
+        u256_to_h256(100.into()),
+    );
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(miniblock.number, &[(executed_tx_hash, vec![balance_log])])
+        .await
+        .unwrap();
+
+    (executed_tx_hash, pending_tx_hash)
+}
+
+#[tokio::test]
+async fn rosetta_api_methods() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let (executed_tx_hash, pending_tx_hash) = prepare_storage(&mut storage).await;
+    drop(storage);
+    let api = RosettaApi::new(pool, L2ChainId::default(), &test_config());
+
+    let networks = api.network_list();
+    assert_eq!(networks.network_identifiers.len(), 1);
+    assert_eq!(networks.network_identifiers[0].network, "testnet");
+
+    let mut unknown_network = network_request();
+    unknown_network.network_identifier.network = "mainnet".to_owned();
+    let err = api.network_status(unknown_network).await.unwrap_err();
+    assert_matches!(err, RosettaError::UnknownNetwork);
+
+    let options = api.network_options(network_request()).unwrap();
+    assert_eq!(options.allow.operation_types.len(), OPERATION_TYPES.len());
+    assert!(options.allow.historical_balance_lookup);
+
+    let status = api.network_status(network_request()).await.unwrap();
+    assert_eq!(status.current_block_identifier.index, 1);
+    assert_eq!(status.current_block_timestamp, 1_000);
+    assert_eq!(status.genesis_block_identifier.index, 0);
+    assert_eq!(status.oldest_block_identifier.index, 0);
+
+    let response = api
+        .block(BlockRequest {
+            network_identifier: network_request().network_identifier,
+            block_identifier: PartialBlockIdentifier {
+                index: Some(1),
+                hash: None,
+            },
+        })
+        .await
+        .unwrap();
+    let block = response.block;
+    assert_eq!(block.block_identifier, status.current_block_identifier);
+    assert_eq!(
+        block.parent_block_identifier,
+        status.genesis_block_identifier
+    );
+    assert_eq!(block.transactions.len(), 1);
+    let transaction = &block.transactions[0];
+    assert_eq!(
+        transaction.transaction_identifier.hash,
+        format!("{executed_tx_hash:?}")
+    );
+    let op_types: Vec<_> = transaction
+        .operations
+        .iter()
+        .map(|op| op.op_type.as_str())
+        .collect();
+    assert_eq!(
+        op_types,
+        [OP_TYPE_FEE, OP_TYPE_FEE, OP_TYPE_TRANSFER, OP_TYPE_TRANSFER]
+    );
+
+    let response = api
+        .block(BlockRequest {
+            network_identifier: network_request().network_identifier,
+            block_identifier: PartialBlockIdentifier {
+                index: None,
+                hash: Some(block.block_identifier.hash.clone()),
+            },
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.block, block);
+
+    let genesis_response = api
+        .block(BlockRequest {
+            network_identifier: network_request().network_identifier,
+            block_identifier: PartialBlockIdentifier {
+                index: Some(0),
+                hash: None,
+            },
+        })
+        .await
+        .unwrap();
+    let genesis_block = genesis_response.block;
+    assert_eq!(
+        genesis_block.parent_block_identifier,
+        genesis_block.block_identifier
+    );
+
+    let err = api
+        .block(BlockRequest {
+            network_identifier: network_request().network_identifier,
+            block_identifier: PartialBlockIdentifier {
+                index: Some(2),
+                hash: None,
+            },
+        })
+        .await
+        .unwrap_err();
+    assert_matches!(err, RosettaError::BlockNotFound);
+
+    let response = api
+        .block_transaction(BlockTransactionRequest {
+            network_identifier: network_request().network_identifier,
+            block_identifier: block.block_identifier.clone(),
+            transaction_identifier: transaction.transaction_identifier.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.transaction, *transaction);
+
+    let err = api
+        .block_transaction(BlockTransactionRequest {
+            network_identifier: network_request().network_identifier,
+            block_identifier: block.block_identifier.clone(),
+            transaction_identifier: TransactionIdentifier {
+                hash: format!("{pending_tx_hash:?}"),
+            },
+        })
+        .await
+        .unwrap_err();
+    assert_matches!(err, RosettaError::TransactionNotFound);
+
+    let balance_request = |index| AccountBalanceRequest {
+        network_identifier: network_request().network_identifier,
+        account_identifier: AccountIdentifier {
+            address: format!("{RECIPIENT:?}"),
+        },
+        block_identifier: PartialBlockIdentifier { index, hash: None },
+        currencies: None,
+    };
+    let response = api.account_balance(balance_request(None)).await.unwrap();
+    assert_eq!(response.block_identifier.index, 1);
+    assert_eq!(response.balances[0].value, "100");
+    let response = api.account_balance(balance_request(Some(0))).await.unwrap();
+    assert_eq!(response.balances[0].value, "0");
+
+    let mut request = balance_request(None);
+    request.currencies = Some(vec![Currency {
+        symbol: "USDC".to_owned(),
+        decimals: 6,
+        metadata: None,
+    }]);
+    let err = api.account_balance(request).await.unwrap_err();
+    assert_matches!(err, RosettaError::UnsupportedCurrency);
+
+    let mempool = api.mempool(network_request()).await.unwrap();
+    assert_eq!(
+        mempool.transaction_identifiers,
+        [TransactionIdentifier {
+            hash: format!("{pending_tx_hash:?}")
+        }]
+    );
+}
+
+#[tokio::test]
+async fn erc20_balances() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let (executed_tx_hash, _) = prepare_storage(&mut storage).await;
+    storage
+        .tokens_dal()
+        .add_tokens(&[test_token()])
+        .await
+        .unwrap();
+    let balance_log = StorageLog::new_write_log(
+        storage_key_for_standard_token_balance(AccountTreeId::new(TOKEN_ADDRESS), &RECIPIENT),
+        u256_to_h256(42.into()),
+    );
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(1), &[(executed_tx_hash, vec![balance_log])])
+        .await
+        .unwrap();
+    drop(storage);
+    let api = RosettaApi::new(pool, L2ChainId::default(), &test_config());
+
+    let balance_request = |currencies| AccountBalanceRequest {
+        network_identifier: network_request().network_identifier,
+        account_identifier: AccountIdentifier {
+            address: format!("{RECIPIENT:?}"),
+        },
+        block_identifier: PartialBlockIdentifier::default(),
+        currencies,
+    };
+    let response = api.account_balance(balance_request(None)).await.unwrap();
+    let balances: Vec<_> = response
+        .balances
+        .iter()
+        .map(|amount| (amount.currency.symbol.as_str(), amount.value.as_str()))
+        .collect();
+    assert_eq!(balances, [("ETH", "100"), ("USDC", "42")]);
+
+    let request = balance_request(Some(vec![token_currency(&test_token())]));
+    let response = api.account_balance(request).await.unwrap();
+    assert_eq!(response.balances.len(), 1);
+    assert_eq!(response.balances[0].value, "42");
+    assert_eq!(response.balances[0].currency, token_currency(&test_token()));
+}
+
+#[tokio::test]
+async fn paging_block_operations() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let (executed_tx_hash, _) = prepare_storage(&mut storage).await;
+    drop(storage);
+    // The executed transaction has 2 events, so it doesn't fit into the limit.
+    let config = RosettaApiConfig {
+        max_block_events: 1,
+        ..test_config()
+    };
+    let api = RosettaApi::new(pool, L2ChainId::default(), &config);
+
+    let response = api
+        .block(BlockRequest {
+            network_identifier: network_request().network_identifier,
+            block_identifier: PartialBlockIdentifier {
+                index: Some(1),
+                hash: None,
+            },
+        })
+        .await
+        .unwrap();
+    assert!(response.block.transactions.is_empty());
+    let transaction_identifier = TransactionIdentifier {
+        hash: format!("{executed_tx_hash:?}"),
+    };
+    assert_eq!(
+        response.other_transactions,
+        [transaction_identifier.clone()]
+    );
+
+    let response = api
+        .block_transaction(BlockTransactionRequest {
+            network_identifier: network_request().network_identifier,
+            block_identifier: response.block.block_identifier,
+            transaction_identifier,
+        })
+        .await
+        .unwrap();
+    let op_types: Vec<_> = response
+        .transaction
+        .operations
+        .iter()
+        .map(|op| op.op_type.as_str())
+        .collect();
+    assert_eq!(
+        op_types,
+        [OP_TYPE_FEE, OP_TYPE_FEE, OP_TYPE_TRANSFER, OP_TYPE_TRANSFER]
+    );
+}
+
+#[tokio::test]
+async fn rosetta_api_server() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage).await;
+    drop(storage);
+
+    let api = RosettaApi::new(pool, L2ChainId::default(), &test_config());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let api_server = api
+        .create_api_server(&(Ipv4Addr::LOCALHOST, 0).into(), stop_receiver)
+        .unwrap();
+    let local_addr = *api_server.local_addr();
+    let api_server_task = tokio::spawn(api_server.run());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("http://{local_addr}/network/status"))
+        .json(&network_request())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let status: NetworkStatusResponse = response.json().await.unwrap();
+    assert_eq!(status.current_block_identifier.index, 1);
+
+    let response = client
+        .post(format!("http://{local_addr}/block"))
+        .json(&serde_json::json!({
+            "network_identifier": network_request().network_identifier,
+            "block_identifier": { "index": 100 },
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let err: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(err.code, RosettaError::BlockNotFound.code());
+    assert!(err.retriable);
+
+    stop_sender.send_replace(true);
+    api_server_task.await.unwrap().unwrap();
+}
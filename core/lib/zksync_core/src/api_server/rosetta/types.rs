@@ -0,0 +1,202 @@
+//! Request and response types of the Rosetta API. See [the spec](https://docs.cdp.coinbase.com/mesh/reference)
+//! for the details; only the subset of fields relevant to the chain is supported.
+
+use serde::{Deserialize, Serialize};
+
+pub(super) const ROSETTA_VERSION: &str = "1.4.13";
+pub(super) const BLOCKCHAIN: &str = "idexo";
+
+pub(super) const OP_STATUS_SUCCESS: &str = "SUCCESS";
+pub(super) const OP_TYPE_TRANSFER: &str = "TRANSFER";
+pub(super) const OP_TYPE_FEE: &str = "FEE";
+pub(super) const OP_TYPE_DEPOSIT: &str = "DEPOSIT";
+pub(super) const OP_TYPE_WITHDRAWAL: &str = "WITHDRAWAL";
+pub(super) const OPERATION_TYPES: [&str; 4] = [
+    OP_TYPE_TRANSFER,
+    OP_TYPE_FEE,
+    OP_TYPE_DEPOSIT,
+    OP_TYPE_WITHDRAWAL,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct NetworkIdentifier {
+    pub blockchain: String,
+    pub network: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct BlockIdentifier {
+    pub index: u64,
+    pub hash: String,
+}
+
+/// Block identifier with optional fields. If both fields are missing, refers to the latest block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(super) struct PartialBlockIdentifier {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct TransactionIdentifier {
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct AccountIdentifier {
+    pub address: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Currency {
+    pub symbol: String,
+    pub decimals: u32,
+    /// For ERC20 tokens, contains the `contract_address` of the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Amount {
+    /// Signed amount in the smallest currency units, as a decimal string.
+    pub value: String,
+    pub currency: Currency,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct OperationIdentifier {
+    pub index: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct Operation {
+    pub operation_identifier: OperationIdentifier,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_operations: Vec<OperationIdentifier>,
+    #[serde(rename = "type")]
+    pub op_type: String,
+    pub status: String,
+    pub account: AccountIdentifier,
+    pub amount: Amount,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct Transaction {
+    pub transaction_identifier: TransactionIdentifier,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct Block {
+    pub block_identifier: BlockIdentifier,
+    pub parent_block_identifier: BlockIdentifier,
+    /// Block timestamp in milliseconds.
+    pub timestamp: u64,
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct NetworkRequest {
+    pub network_identifier: NetworkIdentifier,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct NetworkListResponse {
+    pub network_identifiers: Vec<NetworkIdentifier>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct Version {
+    pub rosetta_version: String,
+    pub node_version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct OperationStatus {
+    pub status: String,
+    pub successful: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct Allow {
+    pub operation_statuses: Vec<OperationStatus>,
+    pub operation_types: Vec<String>,
+    pub errors: Vec<ErrorResponse>,
+    pub historical_balance_lookup: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct NetworkOptionsResponse {
+    pub version: Version,
+    pub allow: Allow,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct NetworkStatusResponse {
+    pub current_block_identifier: BlockIdentifier,
+    pub current_block_timestamp: u64,
+    pub genesis_block_identifier: BlockIdentifier,
+    pub oldest_block_identifier: BlockIdentifier,
+    pub peers: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct BlockRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub block_identifier: PartialBlockIdentifier,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct BlockResponse {
+    pub block: Block,
+    /// Transactions in the block which operations must be fetched via the `/block/transaction` endpoint.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_transactions: Vec<TransactionIdentifier>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct BlockTransactionRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub block_identifier: BlockIdentifier,
+    pub transaction_identifier: TransactionIdentifier,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct BlockTransactionResponse {
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct AccountBalanceRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub account_identifier: AccountIdentifier,
+    #[serde(default)]
+    pub block_identifier: PartialBlockIdentifier,
+    #[serde(default)]
+    pub currencies: Option<Vec<Currency>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct AccountBalanceResponse {
+    pub block_identifier: BlockIdentifier,
+    pub balances: Vec<Amount>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct MempoolResponse {
+    pub transaction_identifiers: Vec<TransactionIdentifier>,
+}
+
+/// Error object returned by all endpoints.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ErrorResponse {
+    pub code: u32,
+    pub message: String,
+    pub retriable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
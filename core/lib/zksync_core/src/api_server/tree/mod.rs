@@ -33,6 +33,29 @@ struct TreeProofsResponse {
     entries: Vec<TreeEntryWithProof>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeEntriesResponse {
+    entries: Vec<TreeEntry>,
+}
+
+/// Tree entry without a Merkle proof.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TreeEntry {
+    #[serde(default, skip_serializing_if = "H256::is_zero")]
+    pub value: H256,
+    #[serde(default, skip_serializing_if = "TreeEntryWithProof::is_zero")]
+    pub index: u64,
+}
+
+impl From<zksync_merkle_tree::TreeEntry> for TreeEntry {
+    fn from(src: zksync_merkle_tree::TreeEntry) -> Self {
+        Self {
+            value: src.value,
+            index: src.leaf_index,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct TreeEntryWithProof {
     #[serde(default, skip_serializing_if = "H256::is_zero")]
@@ -86,7 +109,7 @@ impl IntoResponse for TreeApiError {
 
 /// Client accessing Merkle tree API.
 #[async_trait]
-pub(crate) trait TreeApiClient {
+pub(crate) trait TreeApiClient: 'static + Send + Sync + fmt::Debug {
     /// Obtains general information about the tree.
     async fn get_info(&self) -> anyhow::Result<MerkleTreeInfo>;
 
@@ -96,6 +119,14 @@ pub(crate) trait TreeApiClient {
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
     ) -> anyhow::Result<Vec<TreeEntryWithProof>>;
+
+    /// Obtains entries for the specified `hashed_keys` at the specified tree version (= L1 batch number)
+    /// without Merkle proofs.
+    async fn get_entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> anyhow::Result<Vec<TreeEntry>>;
 }
 
 /// In-memory client implementation.
@@ -114,6 +145,16 @@ impl TreeApiClient for AsyncTreeReader {
             .await
             .map_err(Into::into)
     }
+
+    async fn get_entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> anyhow::Result<Vec<TreeEntry>> {
+        self.get_entries_inner(l1_batch_number, hashed_keys)
+            .await
+            .map_err(Into::into)
+    }
 }
 
 /// [`TreeApiClient`] implementation requesting data from a Merkle tree API server.
@@ -122,6 +163,7 @@ pub struct TreeApiHttpClient {
     inner: reqwest::Client,
     info_url: String,
     proofs_url: String,
+    entries_url: String,
 }
 
 impl TreeApiHttpClient {
@@ -130,6 +172,7 @@ impl TreeApiHttpClient {
             inner: reqwest::Client::new(),
             info_url: url_base.to_owned(),
             proofs_url: format!("{url_base}/proofs"),
+            entries_url: format!("{url_base}/entries"),
         }
     }
 }
@@ -175,6 +218,32 @@ impl TreeApiClient for TreeApiHttpClient {
         })?;
         Ok(response.entries)
     }
+
+    async fn get_entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> anyhow::Result<Vec<TreeEntry>> {
+        let response = self
+            .inner
+            .post(&self.entries_url)
+            .json(&TreeProofsRequest {
+                l1_batch_number,
+                hashed_keys,
+            })
+            .send()
+            .await
+            .with_context(|| {
+                format!("Failed requesting entries for L1 batch #{l1_batch_number}")
+            })?;
+        let response = response.error_for_status().with_context(|| {
+            format!("Requesting entries for L1 batch #{l1_batch_number} returned non-OK response")
+        })?;
+        let response: TreeEntriesResponse = response.json().await.with_context(|| {
+            format!("Failed deserializing entries for L1 batch #{l1_batch_number}")
+        })?;
+        Ok(response.entries)
+    }
 }
 
 impl AsyncTreeReader {
@@ -211,6 +280,29 @@ impl AsyncTreeReader {
         Ok(Json(response))
     }
 
+    async fn get_entries_inner(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        let entries = self.clone().entries(l1_batch_number, hashed_keys).await?;
+        Ok(entries.into_iter().map(TreeEntry::from).collect())
+    }
+
+    async fn get_entries_handler(
+        State(this): State<Self>,
+        Json(request): Json<TreeProofsRequest>,
+    ) -> Result<Json<TreeEntriesResponse>, TreeApiError> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetEntries].start();
+        let entries = this
+            .get_entries_inner(request.l1_batch_number, request.hashed_keys)
+            .await
+            .map_err(TreeApiError::NoTreeVersion)?;
+        let response = TreeEntriesResponse { entries };
+        latency.observe();
+        Ok(Json(response))
+    }
+
     fn create_api_server(
         self,
         bind_address: &SocketAddr,
@@ -221,6 +313,7 @@ impl AsyncTreeReader {
         let app = Router::new()
             .route("/", routing::get(Self::info_handler))
             .route("/proofs", routing::post(Self::get_proofs_handler))
+            .route("/entries", routing::post(Self::get_entries_handler))
             .with_state(self);
 
         let server = axum::Server::try_bind(bind_address)
@@ -46,7 +46,7 @@ async fn merkle_tree_api() {
     hashed_keys.extend((0_u8..10).map(|byte| U256::from_big_endian(&[byte; 32])));
 
     let proofs = api_client
-        .get_proofs(L1BatchNumber(5), hashed_keys)
+        .get_proofs(L1BatchNumber(5), hashed_keys.clone())
         .await
         .unwrap();
     assert_eq!(proofs.len(), 20);
@@ -56,6 +56,17 @@ async fn merkle_tree_api() {
         assert!(!proof.merkle_path.is_empty());
     }
 
+    let entries = api_client
+        .get_entries(L1BatchNumber(5), hashed_keys.clone())
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 20);
+    for (i, entry) in entries.into_iter().enumerate() {
+        let should_be_present = i < 10;
+        assert_eq!(entry.index == 0, !should_be_present);
+        assert_eq!(entry.value.is_zero(), !should_be_present);
+    }
+
     let err = api_client
         .get_proofs(L1BatchNumber(10), vec![])
         .await
@@ -0,0 +1,252 @@
+//! Forecasting of L1 batch resource usage based on the mempool backlog.
+//!
+//! The backlog only includes pending transactions that the state keeper can pick up at the current base fee;
+//! transactions with a lower max fee are stashed until the fee drops. The mempool is ordered by arrival time,
+//! so the entire backlog is ahead of a newly submitted transaction regardless of its fee.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use multivm::utils::derive_base_fee_and_gas_per_pubdata;
+use vise::{Counter, Gauge, Metrics};
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::{transactions_dal::PendingL2TxsResources, StorageProcessor};
+use zksync_types::fee_model::BatchFeeInput;
+
+use crate::utils::pending_protocol_version;
+
+/// Approximate number of pubdata bytes published for a single storage write.
+const PUBDATA_BYTES_PER_STORAGE_WRITE: u64 = 64;
+/// Interval after which the cached forecast is considered stale.
+const FORECAST_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_tx_sender_batch_forecast")]
+struct BatchForecastMetrics {
+    /// Number of L1 batches required to include all pending L2 transactions.
+    projected_batches: Gauge<f64>,
+    /// Upper bound on the time required to include all pending L2 transactions, assuming that each batch
+    /// is sealed by the commit deadline.
+    projected_batch_fill_time: Gauge<Duration>,
+    /// Number of transactions rejected because the mempool backlog exceeds the inclusion horizon.
+    rejected_beyond_horizon: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<BatchForecastMetrics> = vise::Global::new();
+
+/// Resources available to a single L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BatchCapacity {
+    pub gas: u64,
+    pub pubdata: u64,
+    pub commit_deadline: Duration,
+}
+
+impl BatchCapacity {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            gas: config.max_gas_per_batch,
+            pubdata: config.max_pubdata_per_batch,
+            commit_deadline: Duration::from_millis(config.block_commit_deadline_ms),
+        }
+    }
+}
+
+/// Projection of how the pending mempool backlog maps onto L1 batches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BatchFillForecast {
+    pub pending: PendingL2TxsResources,
+    pub projected_batches: f64,
+    pub projected_fill_time: Duration,
+}
+
+impl BatchFillForecast {
+    pub fn new(pending: PendingL2TxsResources, capacity: BatchCapacity) -> Self {
+        let gas_batches = ratio(pending.gas_used, capacity.gas);
+        let pubdata = pending.storage_writes * PUBDATA_BYTES_PER_STORAGE_WRITE;
+        let pubdata_batches = ratio(pubdata, capacity.pubdata);
+        let projected_batches = gas_batches.max(pubdata_batches);
+        let projected_fill_time = capacity
+            .commit_deadline
+            .mul_f64(projected_batches.ceil().max(1.0));
+        Self {
+            pending,
+            projected_batches,
+            projected_fill_time,
+        }
+    }
+
+    /// Checks whether a transaction submitted now would be included within `horizon_batches` L1 batches.
+    pub fn fits_horizon(&self, horizon_batches: u32) -> bool {
+        self.projected_batches < f64::from(horizon_batches)
+    }
+}
+
+fn ratio(value: u64, capacity: u64) -> f64 {
+    if capacity == 0 {
+        return 0.0;
+    }
+    value as f64 / capacity as f64
+}
+
+/// Caches [`BatchFillForecast`]s for the transaction sender so that the backlog is not re-queried
+/// for each submitted transaction.
+#[derive(Debug)]
+pub(crate) struct BatchFillForecaster {
+    capacity: BatchCapacity,
+    horizon_batches: u32,
+    cached: Mutex<Option<(Instant, BatchFillForecast)>>,
+}
+
+impl BatchFillForecaster {
+    pub fn new(capacity: BatchCapacity, horizon_batches: u32) -> Self {
+        Self {
+            capacity,
+            horizon_batches,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn horizon_batches(&self) -> u32 {
+        self.horizon_batches
+    }
+
+    /// Returns the current forecast, refreshing it from the storage if necessary.
+    pub async fn forecast(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        fee_input: BatchFeeInput,
+    ) -> anyhow::Result<BatchFillForecast> {
+        if let Some((updated_at, forecast)) = *self.cached.lock().unwrap() {
+            if updated_at.elapsed() < FORECAST_REFRESH_INTERVAL {
+                return Ok(forecast);
+            }
+        }
+
+        let protocol_version = pending_protocol_version(storage)
+            .await
+            .context("failed getting pending protocol version")?;
+        let (fee_per_gas, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+        let pending = storage
+            .transactions_dal()
+            .get_pending_l2_txs_resources(gas_per_pubdata as u32, fee_per_gas)
+            .await
+            .context("failed getting resources of pending transactions")?;
+
+        let forecast = BatchFillForecast::new(pending, self.capacity);
+        METRICS.projected_batches.set(forecast.projected_batches);
+        METRICS
+            .projected_batch_fill_time
+            .set(forecast.projected_fill_time);
+        *self.cached.lock().unwrap() = Some((Instant::now(), forecast));
+        Ok(forecast)
+    }
+
+    pub fn report_rejection(&self) {
+        METRICS.rejected_beyond_horizon.inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::ConnectionPool;
+    use zksync_types::{fee::TransactionExecutionMetrics, L2ChainId};
+
+    use super::*;
+    use crate::{
+        genesis::{ensure_genesis_state, GenesisParams},
+        utils::testonly::create_l2_transaction,
+    };
+
+    const CAPACITY: BatchCapacity = BatchCapacity {
+        gas: 1_000,
+        pubdata: 640,
+        commit_deadline: Duration::from_secs(2),
+    };
+
+    #[test]
+    fn forecast_is_bounded_by_gas() {
+        let pending = PendingL2TxsResources {
+            tx_count: 10,
+            gas_used: 2_500,
+            storage_writes: 1,
+        };
+        let forecast = BatchFillForecast::new(pending, CAPACITY);
+        assert_eq!(forecast.projected_batches, 2.5);
+        assert_eq!(forecast.projected_fill_time, Duration::from_secs(6));
+        assert!(forecast.fits_horizon(3));
+        assert!(!forecast.fits_horizon(2));
+    }
+
+    #[test]
+    fn forecast_is_bounded_by_pubdata() {
+        let pending = PendingL2TxsResources {
+            tx_count: 10,
+            gas_used: 100,
+            storage_writes: 40,
+        };
+        let forecast = BatchFillForecast::new(pending, CAPACITY);
+        assert_eq!(forecast.projected_batches, 4.0);
+        assert_eq!(forecast.projected_fill_time, Duration::from_secs(8));
+    }
+
+    #[test]
+    fn empty_backlog_fits_any_horizon() {
+        let forecast = BatchFillForecast::new(PendingL2TxsResources::default(), CAPACITY);
+        assert_eq!(forecast.projected_batches, 0.0);
+        assert_eq!(forecast.projected_fill_time, CAPACITY.commit_deadline);
+        assert!(forecast.fits_horizon(1));
+    }
+
+    #[tokio::test]
+    async fn forecast_only_includes_backlog_executable_at_base_fee() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        let protocol_version = pending_protocol_version(&mut storage).await.unwrap();
+        let low_fee_input = BatchFeeInput::l1_pegged(1_000_000, 100_000_000);
+        let high_fee_input = BatchFeeInput::l1_pegged(1_000_000, 1_000_000_000);
+        let (low_base_fee, _) =
+            derive_base_fee_and_gas_per_pubdata(low_fee_input, protocol_version.into());
+        let (high_base_fee, _) =
+            derive_base_fee_and_gas_per_pubdata(high_fee_input, protocol_version.into());
+        assert!(low_base_fee < high_base_fee);
+
+        // Each transaction fills an entire L1 batch.
+        let metrics = TransactionExecutionMetrics {
+            gas_used: CAPACITY.gas as usize,
+            ..TransactionExecutionMetrics::default()
+        };
+        for max_fee_per_gas in [low_base_fee, high_base_fee] {
+            let tx = create_l2_transaction(max_fee_per_gas, 1_000_000);
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(tx, metrics)
+                .await;
+        }
+
+        let forecaster = BatchFillForecaster::new(CAPACITY, 2);
+        let forecast = forecaster
+            .forecast(&mut storage, low_fee_input)
+            .await
+            .unwrap();
+        assert_eq!(forecast.pending.tx_count, 2);
+        assert!(!forecast.fits_horizon(forecaster.horizon_batches()));
+
+        // The transaction with the low fee cannot be executed at the high base fee, so it doesn't saturate the mempool.
+        let forecaster = BatchFillForecaster::new(CAPACITY, 2);
+        let forecast = forecaster
+            .forecast(&mut storage, high_fee_input)
+            .await
+            .unwrap();
+        assert_eq!(forecast.pending.tx_count, 1);
+        assert!(forecast.fits_horizon(forecaster.horizon_batches()));
+    }
+}
@@ -0,0 +1,93 @@
+//! Policies deciding whether L2 transactions are accepted for execution: the transaction intake pause
+//! managed by the operator, and the fee floor.
+//!
+//! The same policies apply to all L2 transactions entering the node, i.e. both to the transactions submitted
+//! via `eth_sendRawTransaction` and to the transactions streamed by the shared sequencer.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use zksync_dal::ConnectionPool;
+use zksync_types::l2::L2Tx;
+
+use super::SubmitTxError;
+use crate::fee_model::BatchFeeModelInputProvider;
+
+/// Interval after which the cached transaction intake pause is refreshed from the storage.
+const INTAKE_PAUSE_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Intake policies for L2 transactions. See the module-level docs for details.
+#[derive(Debug)]
+pub struct TxIntakePolicy {
+    pool: ConnectionPool,
+    batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    /// Cached reason of the active transaction intake pause (`None` if the intake is not paused).
+    pause: Mutex<Option<(Instant, Option<String>)>>,
+}
+
+impl TxIntakePolicy {
+    pub fn new(
+        pool: ConnectionPool,
+        batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    ) -> Self {
+        Self {
+            pool,
+            batch_fee_input_provider,
+            pause: Mutex::new(None),
+        }
+    }
+
+    /// Rejects transactions while the transaction intake is paused by the operator. The pause is cached
+    /// for [`INTAKE_PAUSE_REFRESH_INTERVAL`], so pauses made via other API servers take effect with a delay.
+    pub(crate) async fn ensure_not_paused(&self) -> Result<(), SubmitTxError> {
+        let cached = self.pause.lock().unwrap().clone();
+        let pause_reason = match cached {
+            Some((updated_at, pause_reason))
+                if updated_at.elapsed() < INTAKE_PAUSE_REFRESH_INTERVAL =>
+            {
+                pause_reason
+            }
+            _ => {
+                let mut connection = self
+                    .pool
+                    .access_storage_tagged("api")
+                    .await
+                    .context("failed acquiring DB connection")?;
+                let pause = connection
+                    .tx_intake_dal()
+                    .get_active_pause()
+                    .await
+                    .context("failed getting transaction intake pause")?;
+                let pause_reason = pause.map(|pause| pause.reason);
+                *self.pause.lock().unwrap() = Some((Instant::now(), pause_reason.clone()));
+                pause_reason
+            }
+        };
+        match pause_reason {
+            Some(reason) => Err(SubmitTxError::TxIntakePaused(reason)),
+            None => Ok(()),
+        }
+    }
+
+    /// Resets the cached transaction intake pause, so that a pause made via this node takes effect immediately.
+    pub(crate) fn reset_pause_cache(&self) {
+        *self.pause.lock().unwrap() = None;
+    }
+
+    /// Rejects transactions with the max fee per gas below the current fair L2 gas price.
+    pub(crate) async fn ensure_fee_above_floor(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let fee_input = self.batch_fee_input_provider.get_batch_fee_input().await;
+        if tx.common_data.fee.max_fee_per_gas < fee_input.fair_l2_gas_price().into() {
+            tracing::info!(
+                "Submitted Tx is Unexecutable {:?} because of MaxFeePerGasTooLow {}",
+                tx.hash(),
+                tx.common_data.fee.max_fee_per_gas
+            );
+            return Err(SubmitTxError::MaxFeePerGasTooLow);
+        }
+        Ok(())
+    }
+}
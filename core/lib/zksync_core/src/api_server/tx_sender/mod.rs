@@ -1,6 +1,11 @@
 //! Helper module to submit transactions into the zkSync Network.
 
-use std::{cmp, sync::Arc, time::Instant};
+use std::{
+    cmp,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use multivm::{
@@ -14,6 +19,7 @@ use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, Storage
 use zksync_state::PostgresStorageCaches;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
+    api::idexo::InclusionHorizonViolation,
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     get_code_key, get_intrinsic_constants,
@@ -22,19 +28,27 @@ use zksync_types::{
     utils::storage_key_for_eth_balance,
     AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, MiniblockNumber, Nonce,
     PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
-    MAX_NEW_FACTORY_DEPS, U256,
+    U256,
 };
 use zksync_utils::h256_to_u256;
 
-pub(super) use self::{proxy::TxProxy, result::SubmitTxError};
+pub(super) use self::proxy::TxProxy;
+pub(crate) use self::{
+    batch_forecast::{BatchCapacity, BatchFillForecaster},
+    result::{ApiCallResult, SubmitTxError},
+    withdrawal_limits::{extract_withdrawals, WithdrawalLimiter},
+};
+pub use self::{
+    intake_policy::TxIntakePolicy, replay_protection::ReplayProtection, tx_limits::TxLimits,
+};
 use crate::{
     api_server::{
         execution_sandbox::{
-            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, SubmitTxStage,
-            TransactionExecutor, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
-            SANDBOX_METRICS,
+            get_pubdata_for_factory_deps, ApiStateCache, BlockArgs, BlockStartInfo,
+            SandboxWarmPool, SubmitTxStage, TransactionExecutionOutput, TransactionExecutor,
+            TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit, SANDBOX_METRICS,
         },
-        tx_sender::result::ApiCallResult,
+        tree::{TreeApiClient, TreeApiHttpClient},
     },
     fee_model::BatchFeeModelInputProvider,
     metrics::{TxStage, APP_METRICS},
@@ -42,10 +56,18 @@ use crate::{
     utils::pending_protocol_version,
 };
 
+mod batch_forecast;
+mod intake_policy;
 mod proxy;
+mod replay_protection;
 mod result;
 #[cfg(test)]
 pub(crate) mod tests;
+mod tx_limits;
+mod withdrawal_limits;
+
+/// Interval after which the cached fee discounts are refreshed from the storage.
+const FEE_DISCOUNTS_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone)]
 pub struct MultiVMBaseSystemContracts {
@@ -146,6 +168,16 @@ pub struct TxSenderBuilder {
     proxy: Option<TxProxy>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Option<Arc<dyn ConditionalSealer>>,
+    /// Forecaster used to reject transactions that cannot be included within the configured batch horizon.
+    batch_fill_forecaster: Option<BatchFillForecaster>,
+    /// RocksDB cache of the VM state used for VM invocations at the latest block.
+    state_cache: Option<ApiStateCache>,
+    /// Warm pool of sandbox environments used for VM invocations at the latest block.
+    warm_pool: Option<SandboxWarmPool>,
+    /// Merkle tree used for VM invocations at pruned blocks.
+    archive_tree: Option<Arc<dyn TreeApiClient>>,
+    /// Limiter of withdrawals initiated by submitted transactions.
+    withdrawal_limiter: Option<WithdrawalLimiter>,
 }
 
 impl TxSenderBuilder {
@@ -156,6 +188,11 @@ impl TxSenderBuilder {
             master_connection_pool: None,
             proxy: None,
             sealer: None,
+            batch_fill_forecaster: None,
+            state_cache: None,
+            warm_pool: None,
+            archive_tree: None,
+            withdrawal_limiter: None,
         }
     }
 
@@ -164,6 +201,35 @@ impl TxSenderBuilder {
         self
     }
 
+    pub(crate) fn with_batch_fill_forecaster(mut self, forecaster: BatchFillForecaster) -> Self {
+        self.batch_fill_forecaster = Some(forecaster);
+        self
+    }
+
+    pub fn with_state_cache(mut self, state_cache: ApiStateCache) -> Self {
+        self.state_cache = Some(state_cache);
+        self
+    }
+
+    pub fn with_warm_pool(mut self, warm_pool: SandboxWarmPool) -> Self {
+        self.warm_pool = Some(warm_pool);
+        self
+    }
+
+    /// Enables `eth_call` for pruned blocks. The VM state for such blocks is read from the Merkle tree
+    /// via the tree API at the specified URL.
+    pub fn with_archive_tree_api(mut self, tree_api_url: &str) -> Self {
+        self.archive_tree = Some(Arc::new(TreeApiHttpClient::new(tree_api_url)));
+        self
+    }
+
+    /// Enables withdrawal limits. Only makes sense on the main node; external nodes proxy transactions
+    /// to the main node, which enforces the limits.
+    pub(crate) fn with_withdrawal_limiter(mut self, limiter: WithdrawalLimiter) -> Self {
+        self.withdrawal_limiter = Some(limiter);
+        self
+    }
+
     pub fn with_tx_proxy(mut self, main_node_url: &str) -> Self {
         self.proxy = Some(TxProxy::new(main_node_url));
         self
@@ -188,17 +254,28 @@ impl TxSenderBuilder {
 
         // Use noop sealer if no sealer was explicitly provided.
         let sealer = self.sealer.unwrap_or_else(|| Arc::new(NoopSealer));
+        let intake_policy = TxIntakePolicy::new(
+            self.replica_connection_pool.clone(),
+            batch_fee_input_provider.clone(),
+        );
 
         TxSender(Arc::new(TxSenderInner {
             sender_config: self.config,
             master_connection_pool: self.master_connection_pool,
             replica_connection_pool: self.replica_connection_pool,
+            intake_policy,
             batch_fee_input_provider,
             api_contracts,
             proxy: self.proxy,
             vm_concurrency_limiter,
             storage_caches,
+            state_cache: self.state_cache,
+            warm_pool: self.warm_pool,
+            archive_tree: self.archive_tree,
             sealer,
+            batch_fill_forecaster: self.batch_fill_forecaster,
+            withdrawal_limiter: self.withdrawal_limiter,
+            fee_discounts: Mutex::new(None),
             executor: TransactionExecutor::Real,
         }))
     }
@@ -218,6 +295,7 @@ pub struct TxSenderConfig {
     pub validation_computational_gas_limit: u32,
     pub l1_to_l2_transactions_compatibility_mode: bool,
     pub chain_id: L2ChainId,
+    pub tx_limits: TxLimits,
 }
 
 impl TxSenderConfig {
@@ -237,6 +315,7 @@ impl TxSenderConfig {
             l1_to_l2_transactions_compatibility_mode: web3_json_config
                 .l1_to_l2_transactions_compatibility_mode,
             chain_id,
+            tx_limits: TxLimits::from_config(web3_json_config),
         }
     }
 }
@@ -254,8 +333,22 @@ pub struct TxSenderInner {
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Caches used in VM execution.
     storage_caches: PostgresStorageCaches,
+    /// RocksDB cache of the VM state used in VM execution at the latest block.
+    state_cache: Option<ApiStateCache>,
+    /// Warm pool of sandbox environments used in VM execution at the latest block.
+    warm_pool: Option<SandboxWarmPool>,
+    /// Merkle tree used in VM execution at pruned blocks.
+    archive_tree: Option<Arc<dyn TreeApiClient>>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
+    /// Forecaster of L1 batch resource usage used for admission control.
+    batch_fill_forecaster: Option<BatchFillForecaster>,
+    /// Limiter of withdrawals initiated by submitted transactions.
+    withdrawal_limiter: Option<WithdrawalLimiter>,
+    /// Intake pause and fee floor policies.
+    intake_policy: TxIntakePolicy,
+    /// Cached fee discounts in basis points keyed by the account address.
+    fee_discounts: Mutex<Option<(Instant, Arc<HashMap<Address, u32>>)>>,
     pub(super) executor: TransactionExecutor,
 }
 
@@ -277,6 +370,18 @@ impl TxSender {
         self.0.storage_caches.clone()
     }
 
+    pub(crate) fn state_cache(&self) -> Option<ApiStateCache> {
+        self.0.state_cache.clone()
+    }
+
+    pub(crate) fn warm_pool(&self) -> Option<SandboxWarmPool> {
+        self.0.warm_pool.clone()
+    }
+
+    pub(crate) fn archive_tree(&self) -> Option<Arc<dyn TreeApiClient>> {
+        self.0.archive_tree.clone()
+    }
+
     async fn acquire_replica_connection(&self) -> anyhow::Result<StorageProcessor<'_>> {
         self.0
             .replica_connection_pool
@@ -285,67 +390,62 @@ impl TxSender {
             .context("failed acquiring connection to replica DB")
     }
 
-    #[tracing::instrument(skip(self, tx))]
-    pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
-        let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::Validate].start();
-        self.validate_tx(&tx).await?;
-        stage_latency.observe();
+    async fn ensure_intake_not_paused(&self) -> Result<(), SubmitTxError> {
+        self.0.intake_policy.ensure_not_paused().await
+    }
 
-        let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::DryRun].start();
-        let shared_args = self.shared_args().await;
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
-        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
-        let mut connection = self.acquire_replica_connection().await?;
-        let block_args = BlockArgs::pending(&mut connection).await?;
-        drop(connection);
+    /// Resets the cached transaction intake pause, so that a pause made via this API server takes effect immediately.
+    pub(crate) fn reset_intake_pause_cache(&self) {
+        self.0.intake_policy.reset_pause_cache();
+    }
 
-        let execution_output = self
-            .0
-            .executor
-            .execute_tx_in_sandbox(
-                vm_permit.clone(),
-                shared_args.clone(),
-                true,
-                TxExecutionArgs::for_validation(&tx),
-                self.0.replica_connection_pool.clone(),
-                tx.clone().into(),
-                block_args,
-                vec![],
-            )
-            .await?;
+    /// Returns the fee discount of the transaction in basis points, or `None` if the transaction is not discounted.
+    /// Discounts are cached for [`FEE_DISCOUNTS_REFRESH_INTERVAL`], so changes made via other API servers
+    /// take effect with a delay.
+    async fn fee_discount(&self, tx: &L2Tx) -> anyhow::Result<Option<u32>> {
+        // Fees of sponsored transactions are paid by the paymaster rather than by the initiator.
+        if tx.common_data.paymaster_params.paymaster != Address::zero() {
+            return Ok(None);
+        }
 
-        tracing::info!(
-            "Submit tx {:?} with execution metrics {:?}",
-            tx.hash(),
-            execution_output.metrics
-        );
-        stage_latency.observe();
+        let cached = self.0.fee_discounts.lock().unwrap().clone();
+        let discounts = match cached {
+            Some((updated_at, discounts))
+                if updated_at.elapsed() < FEE_DISCOUNTS_REFRESH_INTERVAL =>
+            {
+                discounts
+            }
+            _ => {
+                let mut connection = self.acquire_replica_connection().await?;
+                let discounts = connection
+                    .fee_discounts_dal()
+                    .get_discounts()
+                    .await
+                    .context("failed getting fee discounts")?;
+                let discounts: HashMap<_, _> = discounts
+                    .into_iter()
+                    .map(|discount| (discount.address, discount.discount_bps))
+                    .collect();
+                let discounts = Arc::new(discounts);
+                *self.0.fee_discounts.lock().unwrap() = Some((Instant::now(), discounts.clone()));
+                discounts
+            }
+        };
+        Ok(discounts.get(&tx.initiator_account()).copied())
+    }
 
-        let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::VerifyExecute].start();
-        let computational_gas_limit = self.0.sender_config.validation_computational_gas_limit;
-        let validation_result = self
-            .0
-            .executor
-            .validate_tx_in_sandbox(
-                self.0.replica_connection_pool.clone(),
-                vm_permit,
-                tx.clone(),
-                shared_args,
-                block_args,
-                computational_gas_limit,
-            )
-            .await;
-        stage_latency.observe();
+    /// Resets the cached fee discounts, so that changes made via this API server take effect immediately.
+    pub(crate) fn reset_fee_discounts_cache(&self) {
+        *self.0.fee_discounts.lock().unwrap() = None;
+    }
 
-        if let Err(err) = validation_result {
-            return Err(err.into());
-        }
-        if !execution_output.are_published_bytecodes_ok {
-            return Err(SubmitTxError::FailedToPublishCompressedBytecodes);
-        }
+    #[tracing::instrument(skip(self, tx))]
+    pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        self.ensure_intake_not_paused().await?;
+        let execution_output = self.validate_and_execute_tx(&tx).await?;
 
         let stage_started_at = Instant::now();
-        self.ensure_tx_executable(tx.clone().into(), &execution_output.metrics, true)?;
+        self.ensure_tx_fits_inclusion_horizon(&tx).await?;
 
         if let Some(proxy) = &self.0.proxy {
             // We're running an external node: we have to proxy the transaction to the main node.
@@ -370,16 +470,69 @@ impl TxSender {
         let nonce = tx.common_data.nonce.0;
         let hash = tx.hash();
         let initiator_account = tx.initiator_account();
-        let submission_res_handle = self
+        let fee_discount = self.fee_discount(&tx).await?;
+        let withdrawals = self.0.withdrawal_limiter.as_ref().map(|limiter| {
+            (
+                limiter,
+                limiter.extract_withdrawals(&execution_output.vm.logs.events),
+            )
+        });
+        let mut connection = self
             .0
             .master_connection_pool
             .as_ref()
             .unwrap() // Checked above
             .access_storage_tagged("api")
-            .await?
+            .await?;
+        let mut transaction = connection
+            .start_transaction()
+            .await
+            .context("failed starting DB transaction")?;
+        let submission_res_handle = transaction
             .transactions_dal()
             .insert_transaction_l2(tx, execution_output.metrics)
             .await;
+        let is_inserted = matches!(
+            submission_res_handle,
+            L2TxSubmissionResult::Added | L2TxSubmissionResult::Replaced
+        );
+        if matches!(submission_res_handle, L2TxSubmissionResult::Replaced) {
+            // The replaced transaction will never be executed, so its discount must not be kept around.
+            transaction
+                .fee_discounts_dal()
+                .remove_replaced_discounted_txs(initiator_account)
+                .await
+                .context("failed removing discount of the replaced transaction")?;
+            if withdrawals.is_some() {
+                // Same for withdrawal limit usage reserved by the replaced transaction.
+                transaction
+                    .withdrawal_limits_dal()
+                    .release_orphaned_usage()
+                    .await
+                    .context(
+                        "failed releasing withdrawal limit usage of the replaced transaction",
+                    )?;
+            }
+        }
+        if let (Some((limiter, withdrawals)), true) = (&withdrawals, is_inserted) {
+            // If the withdrawals exceed the limits, the DB transaction is rolled back, i.e., the transaction
+            // is not inserted into the mempool.
+            limiter
+                .reserve(&mut transaction, hash, initiator_account, withdrawals)
+                .await?;
+        }
+        if let (Some(discount_bps), true) = (fee_discount, is_inserted) {
+            // The rebate is computed from the fee paid by the transaction once it's executed.
+            transaction
+                .fee_discounts_dal()
+                .insert_discounted_tx(hash, initiator_account, discount_bps)
+                .await
+                .context("failed recording fee discount")?;
+        }
+        transaction
+            .commit()
+            .await
+            .context("failed committing DB transaction")?;
 
         APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
 
@@ -406,12 +559,93 @@ impl TxSender {
         }
     }
 
+    /// Validates the transaction in the same way as [`Self::submit_tx()`], but doesn't submit it. Used to validate
+    /// transactions that are inserted into the mempool directly, e.g. operator-scheduled ones. Returns execution
+    /// metrics of the transaction dry run.
+    pub(crate) async fn dry_run_tx(
+        &self,
+        tx: &L2Tx,
+    ) -> Result<TransactionExecutionMetrics, SubmitTxError> {
+        let execution_output = self.validate_and_execute_tx(tx).await?;
+        Ok(execution_output.metrics)
+    }
+
+    /// Validates the transaction and executes it in the sandbox. Checks that the transaction can be executed
+    /// by the state keeper based on the execution results.
+    async fn validate_and_execute_tx(
+        &self,
+        tx: &L2Tx,
+    ) -> Result<TransactionExecutionOutput, SubmitTxError> {
+        let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::Validate].start();
+        self.validate_tx(tx).await?;
+        stage_latency.observe();
+
+        let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::DryRun].start();
+        let shared_args = self.shared_args().await;
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+        let mut connection = self.acquire_replica_connection().await?;
+        let block_args = BlockArgs::pending(&mut connection).await?;
+        drop(connection);
+
+        let execution_output = self
+            .0
+            .executor
+            .execute_tx_in_sandbox(
+                vm_permit.clone(),
+                shared_args.clone(),
+                true,
+                TxExecutionArgs::for_validation(tx),
+                self.0.replica_connection_pool.clone(),
+                tx.clone().into(),
+                block_args,
+                vec![],
+            )
+            .await?;
+
+        tracing::info!(
+            "Submit tx {:?} with execution metrics {:?}",
+            tx.hash(),
+            execution_output.metrics
+        );
+        stage_latency.observe();
+
+        let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::VerifyExecute].start();
+        let computational_gas_limit = self.0.sender_config.validation_computational_gas_limit;
+        let validation_result = self
+            .0
+            .executor
+            .validate_tx_in_sandbox(
+                self.0.replica_connection_pool.clone(),
+                vm_permit,
+                tx.clone(),
+                shared_args,
+                block_args,
+                computational_gas_limit,
+            )
+            .await;
+        stage_latency.observe();
+
+        if let Err(err) = validation_result {
+            return Err(err.into());
+        }
+        if !execution_output.are_published_bytecodes_ok {
+            return Err(SubmitTxError::FailedToPublishCompressedBytecodes);
+        }
+
+        self.ensure_tx_executable(tx.clone().into(), &execution_output.metrics, true)?;
+        Ok(execution_output)
+    }
+
     async fn shared_args(&self) -> TxSharedArgs {
         TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
             fee_input: self.0.batch_fee_input_provider.get_batch_fee_input().await,
             base_system_contracts: self.0.api_contracts.eth_call.clone(),
             caches: self.storage_caches(),
+            state_cache: self.state_cache(),
+            warm_pool: self.warm_pool(),
+            archive_tree: self.archive_tree(),
             validation_computational_gas_limit: self
                 .0
                 .sender_config
@@ -421,6 +655,8 @@ impl TxSender {
     }
 
     async fn validate_tx(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        self.0.sender_config.tx_limits.check(tx)?;
+
         let max_gas = U256::from(u32::MAX);
         if tx.common_data.fee.gas_limit > max_gas
             || tx.common_data.fee.gas_per_pubdata_limit > max_gas
@@ -428,8 +664,6 @@ impl TxSender {
             return Err(SubmitTxError::GasLimitIsTooBig);
         }
 
-        let fee_input = self.0.batch_fee_input_provider.get_batch_fee_input().await;
-
         // TODO (SMA-1715): do not subsidize the overhead for the transaction
 
         if tx.common_data.fee.gas_limit > self.0.sender_config.max_allowed_l2_tx_gas_limit.into() {
@@ -440,14 +674,7 @@ impl TxSender {
             );
             return Err(SubmitTxError::GasLimitIsTooBig);
         }
-        if tx.common_data.fee.max_fee_per_gas < fee_input.fair_l2_gas_price().into() {
-            tracing::info!(
-                "Submitted Tx is Unexecutable {:?} because of MaxFeePerGasTooLow {}",
-                tx.hash(),
-                tx.common_data.fee.max_fee_per_gas
-            );
-            return Err(SubmitTxError::MaxFeePerGasTooLow);
-        }
+        self.0.intake_policy.ensure_fee_above_floor(tx).await?;
         if tx.common_data.fee.max_fee_per_gas < tx.common_data.fee.max_priority_fee_per_gas {
             tracing::info!(
                 "Submitted Tx is Unexecutable {:?} because of MaxPriorityFeeGreaterThanMaxFee {}",
@@ -456,13 +683,6 @@ impl TxSender {
             );
             return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee);
         }
-        if tx.execute.factory_deps_length() > MAX_NEW_FACTORY_DEPS {
-            return Err(SubmitTxError::TooManyFactoryDependencies(
-                tx.execute.factory_deps_length(),
-                MAX_NEW_FACTORY_DEPS,
-            ));
-        }
-
         let intrinsic_consts = get_intrinsic_constants();
         assert!(
             intrinsic_consts.l2_tx_intrinsic_pubdata == 0,
@@ -482,6 +702,34 @@ impl TxSender {
         Ok(())
     }
 
+    /// Checks that the mempool backlog leaves enough space for the transaction to be included within
+    /// the configured number of L1 batches.
+    async fn ensure_tx_fits_inclusion_horizon(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let Some(forecaster) = &self.0.batch_fill_forecaster else {
+            return Ok(());
+        };
+        let fee_input = self.0.batch_fee_input_provider.get_batch_fee_input().await;
+        let mut connection = self.acquire_replica_connection().await?;
+        let forecast = forecaster.forecast(&mut connection, fee_input).await?;
+        drop(connection);
+
+        let horizon_batches = forecaster.horizon_batches();
+        if forecast.fits_horizon(horizon_batches) {
+            return Ok(());
+        }
+        tracing::info!(
+            "Submitted tx {:?} is rejected since pending transactions are projected to fill {:.2} batches \
+             (horizon: {horizon_batches} batches)",
+            tx.hash(),
+            forecast.projected_batches
+        );
+        forecaster.report_rejection();
+        Err(SubmitTxError::MempoolSaturated(InclusionHorizonViolation {
+            projected_batches: forecast.projected_batches,
+            horizon_batches,
+        }))
+    }
+
     async fn validate_account_nonce(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
         let Nonce(expected_nonce) = self
             .get_expected_nonce(tx.initiator_account())
@@ -650,6 +898,9 @@ impl TxSender {
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
             base_system_contracts: self.0.api_contracts.estimate_gas.clone(),
             caches: self.storage_caches(),
+            state_cache: self.state_cache(),
+            warm_pool: self.warm_pool(),
+            archive_tree: self.archive_tree(),
             chain_id: config.chain_id,
         }
     }
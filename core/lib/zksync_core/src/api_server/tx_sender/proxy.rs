@@ -18,6 +18,8 @@ use zksync_web3_decl::{
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
+use crate::supervisor::{RestartPolicy, SupervisedTask};
+
 #[derive(Debug, Clone, Default)]
 pub(crate) struct TxCache {
     inner: Arc<RwLock<TxCacheInner>>,
@@ -44,6 +46,16 @@ impl TxCache {
         self.inner.read().await.tx_cache.get(&tx_hash).cloned()
     }
 
+    async fn get_txs_for_account(&self, account_address: Address) -> Vec<L2Tx> {
+        let inner = self.inner.read().await;
+        inner
+            .tx_cache
+            .values()
+            .filter(|tx| tx.initiator_account() == account_address)
+            .cloned()
+            .collect()
+    }
+
     async fn get_nonces_for_account(&self, account_address: Address) -> BTreeSet<Nonce> {
         let inner = self.inner.read().await;
         if let Some(nonces) = inner.nonces_by_account.get(&account_address) {
@@ -121,6 +133,12 @@ impl TxProxy {
         self.tx_cache.get_tx(tx_hash).await
     }
 
+    /// Returns cached transactions initiated by the specified account. The returned transactions may be
+    /// already synced back from the main node.
+    pub async fn find_txs_by_account(&self, account_address: Address) -> Vec<L2Tx> {
+        self.tx_cache.get_txs_for_account(account_address).await
+    }
+
     pub async fn forget_tx(&self, tx_hash: H256) {
         self.tx_cache.remove_tx(tx_hash).await;
     }
@@ -203,12 +221,17 @@ impl TxProxy {
             .await
     }
 
+    /// Returns a task sweeping cached transactions with stale nonces. If the task fails and is restarted
+    /// according to `restart_policy`, it continues with the same cache.
     pub fn run_account_nonce_sweeper(
         &self,
         pool: ConnectionPool,
+        restart_policy: RestartPolicy,
         stop_receiver: watch::Receiver<bool>,
     ) -> impl Future<Output = anyhow::Result<()>> {
         let tx_cache = self.tx_cache.clone();
-        tx_cache.run_updates(pool, stop_receiver)
+        let create_task =
+            move |stop_receiver| tx_cache.clone().run_updates(pool.clone(), stop_receiver);
+        SupervisedTask::new("account_nonce_sweeper", restart_policy, create_task).run(stop_receiver)
     }
 }
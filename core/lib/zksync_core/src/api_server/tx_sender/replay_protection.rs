@@ -0,0 +1,136 @@
+//! Chain ID checks for raw transactions.
+//!
+//! The same policy applies to all raw transactions entering the node, i.e. both to the transactions
+//! submitted via `eth_sendRawTransaction` and to the transactions streamed by the shared sequencer.
+//! Transactions signed for another chain (e.g., another Idexo L3) are always rejected; legacy transactions
+//! without the chain ID (i.e., ones that can be replayed on any chain) are only accepted if explicitly allowed
+//! by the API config.
+
+use vise::{Counter, EncodeLabelValue, LabeledFamily, Metrics};
+use zksync_types::{
+    api, transaction_request::SerializationTransactionError, Address, L2ChainId, EIP_1559_TX_TYPE,
+    EIP_712_TX_TYPE, H256,
+};
+
+/// Type of a submitted raw transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+enum SubmittedTxType {
+    Legacy,
+    Eip1559,
+    Eip712,
+    Unknown,
+}
+
+impl SubmittedTxType {
+    fn from_raw_bytes(bytes: &[u8]) -> Self {
+        match bytes.first() {
+            Some(&byte) if byte >= 0x80 => Self::Legacy,
+            Some(&EIP_1559_TX_TYPE) => Self::Eip1559,
+            Some(&EIP_712_TX_TYPE) => Self::Eip712,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Reason of rejecting a submitted transaction because of its chain ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+enum ChainIdRejectionReason {
+    /// Transaction is signed for another chain (e.g., another Idexo L3).
+    ForeignChain,
+    /// Transaction type requires the chain ID, but it is not specified.
+    MissingChainId,
+    /// Legacy transaction without replay protection, which is not allowed by the API config.
+    Unprotected,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_web3_replay_protection")]
+struct ReplayProtectionMetrics {
+    /// Number of submitted transactions rejected because of their chain ID.
+    #[metrics(labels = ["tx_type", "reason"])]
+    rejected_txs: LabeledFamily<(SubmittedTxType, ChainIdRejectionReason), Counter, 2>,
+    /// Number of accepted legacy transactions without replay protection.
+    accepted_unprotected_txs: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ReplayProtectionMetrics> = vise::Global::new();
+
+/// Replay protection policy for raw transactions. See the module-level docs for details.
+#[derive(Debug, Clone)]
+pub struct ReplayProtection {
+    chain_id: L2ChainId,
+    pre_eip155_txs_allowed: bool,
+    pre_eip155_tx_senders: Vec<Address>,
+}
+
+impl ReplayProtection {
+    /// Creates a policy rejecting all transactions without replay protection.
+    pub fn new(chain_id: L2ChainId) -> Self {
+        Self {
+            chain_id,
+            pre_eip155_txs_allowed: false,
+            pre_eip155_tx_senders: vec![],
+        }
+    }
+
+    /// Allows legacy transactions without the chain ID, either from all senders (if `allowed_for_all`
+    /// is set) or from the specified senders only.
+    #[must_use]
+    pub fn with_pre_eip155_txs(mut self, allowed_for_all: bool, senders: Vec<Address>) -> Self {
+        self.pre_eip155_txs_allowed = allowed_for_all;
+        self.pre_eip155_tx_senders = senders;
+        self
+    }
+
+    /// Parses a raw transaction and checks it against the policy.
+    pub fn parse_raw_tx(
+        &self,
+        bytes: &[u8],
+    ) -> Result<(api::TransactionRequest, H256), SerializationTransactionError> {
+        let chain_id = self.chain_id;
+        let tx_type = SubmittedTxType::from_raw_bytes(bytes);
+        let (tx_request, hash) = match api::TransactionRequest::from_bytes(bytes, chain_id) {
+            Ok(parsed) => parsed,
+            Err(err @ SerializationTransactionError::WrongChainId(tx_chain_id)) => {
+                let reason = if tx_chain_id.is_some() {
+                    ChainIdRejectionReason::ForeignChain
+                } else {
+                    ChainIdRejectionReason::MissingChainId
+                };
+                METRICS.rejected_txs[&(tx_type, reason)].inc();
+                tracing::debug!(
+                    "Rejected {tx_type:?} transaction with chain ID {tx_chain_id:?} (expected {chain_id:?})"
+                );
+                return Err(err);
+            }
+            Err(err) => return Err(err),
+        };
+        self.check_unprotected_tx(&tx_request, tx_type)?;
+        Ok((tx_request, hash))
+    }
+
+    /// Checks that a legacy transaction without the chain ID is explicitly allowed.
+    fn check_unprotected_tx(
+        &self,
+        tx_request: &api::TransactionRequest,
+        tx_type: SubmittedTxType,
+    ) -> Result<(), SerializationTransactionError> {
+        if !tx_request.is_legacy_tx() || tx_request.chain_id.is_some() {
+            return Ok(());
+        }
+
+        let is_allowed_sender = tx_request
+            .from
+            .is_some_and(|sender| self.pre_eip155_tx_senders.contains(&sender));
+        if self.pre_eip155_txs_allowed || is_allowed_sender {
+            METRICS.accepted_unprotected_txs.inc();
+            return Ok(());
+        }
+
+        METRICS.rejected_txs[&(tx_type, ChainIdRejectionReason::Unprotected)].inc();
+        Err(SerializationTransactionError::ReplayProtectionRequired)
+    }
+}
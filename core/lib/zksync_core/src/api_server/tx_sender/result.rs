@@ -1,6 +1,10 @@
 use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
 use thiserror::Error;
-use zksync_types::{l2::error::TxCheckError, U256};
+use zksync_types::{
+    api::idexo::{InclusionHorizonViolation, TxLimitKind, TxLimitViolation},
+    l2::error::TxCheckError,
+    Address, U256,
+};
 use zksync_web3_decl::error::EnrichedClientError;
 
 use crate::api_server::execution_sandbox::{SandboxExecutionError, ValidationError};
@@ -49,10 +53,9 @@ pub enum SubmitTxError {
     UnexpectedVMBehavior(String),
     #[error("pubdata price limit is too low, ensure that the price limit is correct")]
     UnrealisticPubdataPriceLimit,
-    #[error(
-        "too many factory dependencies in the transaction. {0} provided, while only {1} allowed"
-    )]
-    TooManyFactoryDependencies(usize, usize),
+    /// Transaction exceeds one of the configured size limits.
+    #[error("{0}")]
+    TxLimitExceeded(TxLimitViolation),
     #[error("max fee per gas higher than 2^32")]
     FeePerGasTooHigh,
     #[error("max fee per pubdata byte higher than 2^32")]
@@ -70,6 +73,25 @@ pub enum SubmitTxError {
     ProxyError(#[from] EnrichedClientError),
     #[error("not enough gas to publish compressed bytecodes")]
     FailedToPublishCompressedBytecodes,
+    /// The mempool is saturated: its backlog executable at the current base fee is projected to occupy more
+    /// batches than the configured inclusion horizon. Since the mempool is ordered by arrival time, raising the fee
+    /// doesn't help; the transaction should be resubmitted later.
+    #[error("{0}")]
+    MempoolSaturated(InclusionHorizonViolation),
+    /// Transaction intake is paused by the operator.
+    #[error("transaction intake is paused: {0}")]
+    TxIntakePaused(String),
+    /// Withdrawals initiated by the transaction exceed a limit configured by the operator.
+    #[error(
+        "withdrawal limit exceeded for token {l1_token:?}: at most {limit} can be withdrawn {} within {window_sec}s",
+        if *.per_account { "per account" } else { "in total" }
+    )]
+    WithdrawalLimitExceeded {
+        l1_token: Address,
+        limit: U256,
+        window_sec: u64,
+        per_account: bool,
+    },
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
@@ -97,13 +119,20 @@ impl SubmitTxError {
             Self::MaxPriorityFeeGreaterThanMaxFee => "max-priority-fee-greater-than-max-fee",
             Self::UnexpectedVMBehavior(_) => "unexpected-vm-behavior",
             Self::UnrealisticPubdataPriceLimit => "unrealistic-pubdata-price-limit",
-            Self::TooManyFactoryDependencies(_, _) => "too-many-factory-dependencies",
+            Self::TxLimitExceeded(violation) => match violation.kind {
+                TxLimitKind::TxSize => "tx-size-limit-exceeded",
+                TxLimitKind::FactoryDeps => "too-many-factory-dependencies",
+                TxLimitKind::CalldataSize => "calldata-size-limit-exceeded",
+            },
             Self::FeePerGasTooHigh => "gas-price-limit-too-high",
             Self::FeePerPubdataByteTooHigh => "pubdata-price-limit-too-high",
             Self::InsufficientFundsForTransfer => "insufficient-funds-for-transfer",
             Self::IntrinsicGas => "intrinsic-gas",
             Self::ProxyError(_) => "proxy-error",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
+            Self::MempoolSaturated(_) => "mempool-saturated",
+            Self::TxIntakePaused(_) => "tx-intake-paused",
+            Self::WithdrawalLimitExceeded { .. } => "withdrawal-limit-exceeded",
             Self::Internal(_) => "internal",
         }
     }
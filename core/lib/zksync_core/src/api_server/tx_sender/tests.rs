@@ -1,12 +1,16 @@
 //! Tests for the transaction sender.
 
+use assert_matches::assert_matches;
 use zksync_types::{get_nonce_key, L1BatchNumber, StorageLog};
 
 use super::*;
 use crate::{
     api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
     genesis::{ensure_genesis_state, GenesisParams},
-    utils::testonly::{create_miniblock, prepare_recovery_snapshot, MockBatchFeeParamsProvider},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+        MockBatchFeeParamsProvider,
+    },
 };
 
 pub(crate) async fn create_test_tx_sender(
@@ -35,6 +39,9 @@ pub(crate) async fn create_test_tx_sender(
         pool,
         batch_fee_model_input_provider,
         storage_caches,
+        None,
+        None,
+        Address::repeat_byte(0x5a), // L2 ERC-20 bridge; isn't relevant
     )
     .await;
 
@@ -146,3 +153,63 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
     let nonce = tx_sender.get_expected_nonce(missing_address).await.unwrap();
     assert_eq!(nonce, Nonce(0));
 }
+
+#[tokio::test]
+async fn rejecting_transactions_while_intake_is_paused() {
+    let l2_chain_id = L2ChainId::default();
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, l2_chain_id, &GenesisParams::mock())
+        .await
+        .unwrap();
+    storage
+        .tx_intake_dal()
+        .pause_intake("maintenance")
+        .await
+        .unwrap()
+        .expect("intake is already paused");
+
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (tx_sender, _) = create_test_tx_sender(pool.clone(), l2_chain_id, tx_executor).await;
+    let tx = create_l2_transaction(55, 555);
+    let err = tx_sender.submit_tx(tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::TxIntakePaused(reason) if reason == "maintenance");
+
+    // The pause is cached, so resuming the intake takes effect only after the cache is reset.
+    storage.tx_intake_dal().resume_intake().await.unwrap();
+    let err = tx_sender.ensure_intake_not_paused().await.unwrap_err();
+    assert_matches!(err, SubmitTxError::TxIntakePaused(_));
+    tx_sender.reset_intake_pause_cache();
+    tx_sender.ensure_intake_not_paused().await.unwrap();
+}
+
+#[tokio::test]
+async fn caching_fee_discounts() {
+    let l2_chain_id = L2ChainId::default();
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let tx = create_l2_transaction(55, 555);
+    let partner = tx.initiator_account();
+    storage
+        .fee_discounts_dal()
+        .set_discount(partner, 2_500, None)
+        .await
+        .unwrap();
+
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (tx_sender, _) = create_test_tx_sender(pool.clone(), l2_chain_id, tx_executor).await;
+    assert_eq!(tx_sender.fee_discount(&tx).await.unwrap(), Some(2_500));
+    let mut sponsored_tx = tx.clone();
+    sponsored_tx.common_data.paymaster_params.paymaster = Address::repeat_byte(0x11);
+    assert_eq!(tx_sender.fee_discount(&sponsored_tx).await.unwrap(), None);
+
+    // Discounts are cached, so removing the discount takes effect only after the cache is reset.
+    storage
+        .fee_discounts_dal()
+        .remove_discount(partner)
+        .await
+        .unwrap();
+    assert_eq!(tx_sender.fee_discount(&tx).await.unwrap(), Some(2_500));
+    tx_sender.reset_fee_discounts_cache();
+    assert_eq!(tx_sender.fee_discount(&tx).await.unwrap(), None);
+}
@@ -0,0 +1,189 @@
+//! Size limits imposed on submitted L2 transactions.
+//!
+//! Hard limits reject a transaction with [`SubmitTxError::TxLimitExceeded`], which carries machine-readable details
+//! of the violated limit, so that clients can guide users to e.g. split a large deployment into several transactions.
+//! Soft limits are a fraction of the corresponding hard limits; transactions exceeding a soft limit are accepted,
+//! but are reported in logs and metrics. This allows the operator to see how close real traffic gets to hard limits
+//! before changing them.
+
+use vise::{Counter, LabeledFamily, Metrics};
+use zksync_config::configs::api::Web3JsonRpcConfig;
+use zksync_types::{
+    api::idexo::{TxLimitKind, TxLimitViolation},
+    l2::L2Tx,
+    MAX_NEW_FACTORY_DEPS,
+};
+
+use super::SubmitTxError;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_tx_sender_tx_limits")]
+struct TxLimitsMetrics {
+    /// Number of accepted transactions exceeding a soft limit.
+    #[metrics(labels = ["kind"])]
+    soft_limit_exceeded: LabeledFamily<&'static str, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<TxLimitsMetrics> = vise::Global::new();
+
+/// Size limits for submitted transactions. See the module-level docs for details.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TxLimits {
+    /// Max size of an ABI-encoded transaction in bytes.
+    pub max_tx_size: usize,
+    /// Max number of factory dependencies in a transaction. Cannot exceed [`MAX_NEW_FACTORY_DEPS`] supported
+    /// by the bootloader.
+    pub max_factory_deps: usize,
+    /// Max size of transaction calldata in bytes. If not set, calldata is only limited by `max_tx_size`.
+    pub max_calldata_size: Option<usize>,
+    /// Fraction of each hard limit used as the corresponding soft limit.
+    pub soft_limit_ratio: f64,
+}
+
+impl TxLimits {
+    const DEFAULT_SOFT_LIMIT_RATIO: f64 = 0.8;
+
+    /// Creates limits with the specified max transaction size. Other limits are set to their default values.
+    pub fn new(max_tx_size: usize) -> Self {
+        Self {
+            max_tx_size,
+            max_factory_deps: MAX_NEW_FACTORY_DEPS,
+            max_calldata_size: None,
+            soft_limit_ratio: Self::DEFAULT_SOFT_LIMIT_RATIO,
+        }
+    }
+
+    pub fn from_config(config: &Web3JsonRpcConfig) -> Self {
+        let mut this = Self::new(config.max_tx_size);
+        if let Some(max_factory_deps) = config.max_factory_deps {
+            if max_factory_deps > MAX_NEW_FACTORY_DEPS {
+                tracing::warn!(
+                    "Configured max number of factory deps {max_factory_deps} exceeds the number supported \
+                     by the bootloader ({MAX_NEW_FACTORY_DEPS}); the latter will be used"
+                );
+            }
+            this.max_factory_deps = max_factory_deps.min(MAX_NEW_FACTORY_DEPS);
+        }
+        this.max_calldata_size = config.max_calldata_size;
+        if let Some(ratio) = config.soft_tx_limits_ratio {
+            this.soft_limit_ratio = ratio;
+        }
+        this
+    }
+
+    fn hard_limit(&self, kind: TxLimitKind) -> Option<usize> {
+        match kind {
+            TxLimitKind::TxSize => Some(self.max_tx_size),
+            TxLimitKind::FactoryDeps => Some(self.max_factory_deps),
+            TxLimitKind::CalldataSize => self.max_calldata_size,
+        }
+    }
+
+    fn measure(tx: &L2Tx, kind: TxLimitKind) -> usize {
+        match kind {
+            // `abi_encoding_len()` returns the number of 32-byte words
+            TxLimitKind::TxSize => tx.abi_encoding_len() * 32,
+            TxLimitKind::FactoryDeps => tx.execute.factory_deps_length(),
+            TxLimitKind::CalldataSize => tx.execute.calldata.len(),
+        }
+    }
+
+    /// Checks the transaction against all limits, returning an error on the first violated hard limit.
+    pub(super) fn check(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        const KINDS: [TxLimitKind; 3] = [
+            TxLimitKind::FactoryDeps,
+            TxLimitKind::CalldataSize,
+            TxLimitKind::TxSize,
+        ];
+
+        for kind in KINDS {
+            let Some(limit) = self.hard_limit(kind) else {
+                continue;
+            };
+            let actual = Self::measure(tx, kind);
+            if actual > limit {
+                return Err(SubmitTxError::TxLimitExceeded(TxLimitViolation {
+                    kind,
+                    limit,
+                    actual,
+                }));
+            }
+
+            let soft_limit = (limit as f64 * self.soft_limit_ratio) as usize;
+            if actual > soft_limit {
+                tracing::info!(
+                    "Transaction {:?} exceeds soft {kind:?} limit: {actual} > {soft_limit} (hard limit: {limit})",
+                    tx.hash()
+                );
+                METRICS.soft_limit_exceeded[&kind.as_str()].inc();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_types::{Address, Execute};
+
+    use super::*;
+    use crate::utils::testonly::create_l2_transaction;
+
+    #[test]
+    fn checking_tx_limits() {
+        let mut limits = TxLimits::new(10_000);
+        limits.max_factory_deps = 2;
+        limits.max_calldata_size = Some(100);
+
+        let mut tx = create_l2_transaction(55, 555);
+        tx.execute = Execute {
+            contract_address: Address::repeat_byte(1),
+            calldata: vec![0; 100],
+            value: 0.into(),
+            factory_deps: Some(vec![vec![0; 32]; 2]),
+        };
+        limits.check(&tx).unwrap();
+
+        tx.execute.calldata.push(0);
+        let err = limits.check(&tx).unwrap_err();
+        let expected_violation = TxLimitViolation {
+            kind: TxLimitKind::CalldataSize,
+            limit: 100,
+            actual: 101,
+        };
+        assert_matches!(
+            err,
+            SubmitTxError::TxLimitExceeded(violation) if violation == expected_violation
+        );
+
+        tx.execute.factory_deps = Some(vec![vec![0; 32]; 3]);
+        let err = limits.check(&tx).unwrap_err();
+        assert_eq!(err.prom_error_code(), "too-many-factory-dependencies");
+
+        tx.execute.factory_deps = None;
+        tx.execute.calldata = vec![0; 100];
+        limits.max_tx_size = 64;
+        let err = limits.check(&tx).unwrap_err();
+        assert_matches!(
+            err,
+            SubmitTxError::TxLimitExceeded(TxLimitViolation {
+                kind: TxLimitKind::TxSize,
+                limit: 64,
+                actual,
+            }) if actual > 64
+        );
+    }
+
+    #[test]
+    fn factory_deps_limit_is_capped() {
+        let mut config = Web3JsonRpcConfig::for_tests();
+        config.max_factory_deps = Some(1_000);
+        config.max_calldata_size = Some(1_024);
+        let limits = TxLimits::from_config(&config);
+        assert_eq!(limits.max_tx_size, config.max_tx_size);
+        assert_eq!(limits.max_factory_deps, MAX_NEW_FACTORY_DEPS);
+        assert_eq!(limits.max_calldata_size, Some(1_024));
+    }
+}
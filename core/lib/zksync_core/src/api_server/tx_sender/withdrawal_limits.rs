@@ -0,0 +1,460 @@
+//! Rate limits on withdrawals initiated by L2 transactions.
+//!
+//! Withdrawals are detected by the withdrawal messages sent to the settlement layer via the base token contract
+//! or the L2 ERC-20 bridge. Limits are configured per token by the operator and cap the amounts withdrawn within
+//! a sliding window, both in total and by a single account. Accounts exempted by the operator are not limited.
+//!
+//! Submitted transactions are checked based on the withdrawals in their dry run. Withdrawals of an accepted
+//! transaction are reserved in the same DB transaction that inserts it into the mempool, so that concurrently
+//! submitted transactions cannot exceed a limit together. Reservations are released if the transaction is rejected,
+//! evicted from the mempool or replaced, and are replaced with the actual withdrawals (see [`extract_withdrawals()`])
+//! once the transaction is sealed in a miniblock.
+//!
+//! The state keeper re-checks the limits for each executed transaction based on its actual withdrawals before
+//! including it into a miniblock. This covers transactions received from the shared sequencer, and transactions
+//! with withdrawals differing from their dry run.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Metrics};
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    ethabi::{self, ParamType},
+    event::L1_MESSAGE_EVENT_SIGNATURE,
+    Address, VmEvent, H256, L1_MESSENGER_ADDRESS, U256,
+};
+use zksync_utils::h256_to_account_address;
+
+use super::SubmitTxError;
+use crate::withdrawal_finalizer::parse_withdrawal_message;
+
+/// Interval between pruning usage records that are outside all limit windows.
+const PRUNING_INTERVAL: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "scope", rename_all = "snake_case")]
+enum LimitScope {
+    Total,
+    Account,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_tx_sender_withdrawal_limits")]
+struct WithdrawalLimitsMetrics {
+    /// Number of transactions rejected because their withdrawals exceed a limit.
+    rejected: Family<LimitScope, Counter>,
+    /// Number of transactions with limited withdrawals accepted because the initiator is exempt from limits.
+    exempted: Counter,
+    /// Number of transactions that reserved usage of the limits.
+    reserved: Counter,
+    /// Number of pruned usage records.
+    pruned_usage: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<WithdrawalLimitsMetrics> = vise::Global::new();
+
+/// Withdrawal of a token initiated by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct InitiatedWithdrawal {
+    /// Address of the token on the settlement layer; the zero address for the base token.
+    pub l1_token: Address,
+    pub amount: U256,
+}
+
+/// Extracts withdrawals from `L1MessageSent` events emitted during transaction execution.
+pub(crate) fn extract_withdrawals<'a>(
+    l2_erc20_bridge_addr: Address,
+    events: impl IntoIterator<Item = &'a VmEvent>,
+) -> Vec<InitiatedWithdrawal> {
+    events
+        .into_iter()
+        .filter_map(|event| {
+            if event.address != L1_MESSENGER_ADDRESS
+                || event.indexed_topics.len() != 3
+                || event.indexed_topics[0] != *L1_MESSAGE_EVENT_SIGNATURE
+            {
+                return None;
+            }
+            let sender = h256_to_account_address(&event.indexed_topics[1]);
+            let message = ethabi::decode(&[ParamType::Bytes], &event.value)
+                .ok()?
+                .into_iter()
+                .next()?
+                .into_bytes()?;
+            let parsed = parse_withdrawal_message(l2_erc20_bridge_addr, sender, &message)?;
+            Some(InitiatedWithdrawal {
+                l1_token: parsed.l1_token,
+                amount: parsed.amount,
+            })
+        })
+        .collect()
+}
+
+/// Enforces withdrawal limits for the transaction sender and the state keeper. See the module-level docs
+/// for details.
+#[derive(Debug)]
+pub(crate) struct WithdrawalLimiter {
+    l2_erc20_bridge_addr: Address,
+    last_pruned_at: Mutex<Option<Instant>>,
+}
+
+impl WithdrawalLimiter {
+    pub fn new(l2_erc20_bridge_addr: Address) -> Self {
+        Self {
+            l2_erc20_bridge_addr,
+            last_pruned_at: Mutex::new(None),
+        }
+    }
+
+    pub fn extract_withdrawals(&self, events: &[VmEvent]) -> Vec<InitiatedWithdrawal> {
+        extract_withdrawals(self.l2_erc20_bridge_addr, events)
+    }
+
+    /// Checks that `withdrawals` initiated by `initiator` in the transaction with `tx_hash` fit into the configured
+    /// limits, taking into account withdrawals of sealed transactions and reservations of pending ones other than
+    /// this transaction. Returns the limited withdrawals as `(l1_token, amount)` pairs; these are empty
+    /// if the initiator is exempt from limits. Occasionally prunes usage records that are outside all limit windows.
+    pub async fn check(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx_hash: H256,
+        initiator: Address,
+        withdrawals: &[InitiatedWithdrawal],
+    ) -> Result<Vec<(Address, U256)>, SubmitTxError> {
+        let mut requested = HashMap::<_, U256>::new();
+        for withdrawal in withdrawals {
+            let amount = requested.entry(withdrawal.l1_token).or_default();
+            *amount = amount.saturating_add(withdrawal.amount);
+        }
+        if requested.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let l1_tokens: Vec<_> = requested.keys().copied().collect();
+        let limits = storage
+            .withdrawal_limits_dal()
+            .get_limits_for_tokens(&l1_tokens)
+            .await
+            .context("failed getting withdrawal limits")?;
+        if limits.is_empty() {
+            return Ok(vec![]);
+        }
+        self.prune_usage_if_necessary(storage).await?;
+        let is_exempt = storage
+            .withdrawal_limits_dal()
+            .is_exempt(initiator)
+            .await
+            .context("failed checking exemption from withdrawal limits")?;
+        if is_exempt {
+            METRICS.exempted.inc();
+            return Ok(vec![]);
+        }
+
+        let mut limited = vec![];
+        for (l1_token, amount) in requested {
+            let Some(limit) = limits.get(&l1_token) else {
+                continue;
+            };
+            let window = Duration::from_secs(limit.window_sec);
+            let withdrawn = storage
+                .withdrawal_limits_dal()
+                .get_withdrawn_amounts(l1_token, initiator, window, Some(tx_hash))
+                .await
+                .with_context(|| format!("failed getting withdrawn amounts of {l1_token:?}"))?;
+
+            let checks = [
+                (LimitScope::Total, limit.total_limit, withdrawn.total),
+                (LimitScope::Account, limit.account_limit, withdrawn.account),
+            ];
+            for (scope, scope_limit, withdrawn) in checks {
+                let Some(scope_limit) = scope_limit else {
+                    continue;
+                };
+                if withdrawn.saturating_add(amount) > scope_limit {
+                    tracing::info!(
+                        "Withdrawal of {amount} {l1_token:?} by {initiator:?} is rejected; {scope:?} limit is \
+                         {scope_limit} per {}s, already withdrawn: {withdrawn}",
+                        limit.window_sec
+                    );
+                    METRICS.rejected[&scope].inc();
+                    return Err(SubmitTxError::WithdrawalLimitExceeded {
+                        l1_token,
+                        limit: scope_limit,
+                        window_sec: limit.window_sec,
+                        per_account: scope == LimitScope::Account,
+                    });
+                }
+            }
+            limited.push((l1_token, amount));
+        }
+        Ok(limited)
+    }
+
+    /// Checks `withdrawals` initiated by `initiator` in the transaction with `tx_hash` (see [`Self::check()`])
+    /// and reserves usage of the limits for them. Must be called in the DB transaction inserting the transaction
+    /// into the mempool, so that the reservation is committed or rolled back together with the transaction.
+    pub async fn reserve(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx_hash: H256,
+        initiator: Address,
+        withdrawals: &[InitiatedWithdrawal],
+    ) -> Result<(), SubmitTxError> {
+        if withdrawals.is_empty() {
+            return Ok(());
+        }
+        storage
+            .withdrawal_limits_dal()
+            .lock_usage()
+            .await
+            .context("failed locking withdrawal limit usage")?;
+        let limited = self.check(storage, tx_hash, initiator, withdrawals).await?;
+        if !limited.is_empty() {
+            storage
+                .withdrawal_limits_dal()
+                .insert_usage(tx_hash, initiator, &limited)
+                .await
+                .context("failed reserving withdrawal limit usage")?;
+            METRICS.reserved.inc();
+        }
+        Ok(())
+    }
+
+    async fn prune_usage_if_necessary(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let should_prune = {
+            let mut last_pruned_at = self.last_pruned_at.lock().unwrap();
+            let should_prune = last_pruned_at.map_or(true, |at| at.elapsed() >= PRUNING_INTERVAL);
+            if should_prune {
+                *last_pruned_at = Some(Instant::now());
+            }
+            should_prune
+        };
+        if should_prune {
+            let pruned = storage
+                .withdrawal_limits_dal()
+                .prune_usage()
+                .await
+                .context("failed pruning withdrawal limit usage")?;
+            METRICS.pruned_usage.inc_by(pruned);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_dal::ConnectionPool;
+    use zksync_types::{L1BatchNumber, L2ChainId, MiniblockNumber, L2_ETH_TOKEN_ADDRESS};
+    use zksync_utils::address_to_h256;
+
+    use super::*;
+    use crate::{
+        genesis::{ensure_genesis_state, GenesisParams},
+        utils::testonly::{create_l2_transaction, create_miniblock, execute_l2_transaction},
+        withdrawal_finalizer::tests::{erc20_withdrawal_message, eth_withdrawal_message},
+    };
+
+    const L2_ERC20_BRIDGE_ADDR: Address = Address::repeat_byte(0xbb);
+
+    fn message_event(sender: Address, message: Vec<u8>) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: L1_MESSENGER_ADDRESS,
+            indexed_topics: vec![
+                *L1_MESSAGE_EVENT_SIGNATURE,
+                address_to_h256(&sender),
+                H256::zero(), // message hash; not checked
+            ],
+            value: ethabi::encode(&[ethabi::Token::Bytes(message)]),
+        }
+    }
+
+    #[test]
+    fn extracting_withdrawals() {
+        let limiter = WithdrawalLimiter::new(L2_ERC20_BRIDGE_ADDR);
+        let receiver = Address::repeat_byte(1);
+        let l1_token = Address::repeat_byte(2);
+        let events = [
+            message_event(
+                L2_ETH_TOKEN_ADDRESS,
+                eth_withdrawal_message(receiver, 100.into()),
+            ),
+            message_event(
+                L2_ERC20_BRIDGE_ADDR,
+                erc20_withdrawal_message(receiver, l1_token, 200.into()),
+            ),
+            // Messages from other senders are not withdrawals.
+            message_event(
+                Address::repeat_byte(0xcc),
+                erc20_withdrawal_message(receiver, l1_token, 300.into()),
+            ),
+            // Neither are malformed messages.
+            message_event(L2_ERC20_BRIDGE_ADDR, vec![1, 2, 3]),
+        ];
+
+        let withdrawals = limiter.extract_withdrawals(&events);
+        assert_eq!(
+            withdrawals,
+            [
+                InitiatedWithdrawal {
+                    l1_token: Address::zero(),
+                    amount: 100.into(),
+                },
+                InitiatedWithdrawal {
+                    l1_token,
+                    amount: 200.into(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn enforcing_withdrawal_limits() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        let limiter = WithdrawalLimiter::new(L2_ERC20_BRIDGE_ADDR);
+        let l1_token = Address::repeat_byte(2);
+        let withdrawal = |amount: u64| InitiatedWithdrawal {
+            l1_token,
+            amount: amount.into(),
+        };
+
+        let tx = create_l2_transaction(10, 100);
+        let initiator = tx.initiator_account();
+        // Without limits, any withdrawals are allowed.
+        let limited = limiter
+            .check(&mut storage, tx.hash(), initiator, &[withdrawal(1_000_000)])
+            .await
+            .unwrap();
+        assert!(limited.is_empty());
+
+        storage
+            .withdrawal_limits_dal()
+            .set_limit(l1_token, Some(1_000.into()), Some(300.into()), 3_600)
+            .await
+            .unwrap();
+        let mut transaction = storage.start_transaction().await.unwrap();
+        transaction
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), Default::default())
+            .await;
+        limiter
+            .reserve(&mut transaction, tx.hash(), initiator, &[withdrawal(200)])
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        // The reservation of the pending transaction counts towards the limits for other transactions...
+        let other_tx_hash = H256::repeat_byte(1);
+        let err = limiter
+            .check(
+                &mut storage,
+                other_tx_hash,
+                initiator,
+                &[withdrawal(60), withdrawal(50)],
+            )
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            SubmitTxError::WithdrawalLimitExceeded {
+                limit,
+                window_sec: 3_600,
+                per_account: true,
+                ..
+            } if limit == 300.into()
+        );
+        // ...but not for the transaction itself, e.g. when it's re-checked by the state keeper after execution.
+        let limited = limiter
+            .check(&mut storage, tx.hash(), initiator, &[withdrawal(250)])
+            .await
+            .unwrap();
+        assert_eq!(limited, [(l1_token, 250.into())]);
+
+        // Emulate sealing the transaction.
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(1))
+            .await
+            .unwrap();
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                MiniblockNumber(1),
+                &[execute_l2_transaction(tx.clone())],
+                1.into(),
+            )
+            .await;
+        storage
+            .withdrawal_limits_dal()
+            .insert_usage(tx.hash(), initiator, &[(l1_token, 200.into())])
+            .await
+            .unwrap();
+        limiter
+            .check(&mut storage, other_tx_hash, initiator, &[withdrawal(100)])
+            .await
+            .unwrap();
+        limiter
+            .check(&mut storage, other_tx_hash, initiator, &[withdrawal(101)])
+            .await
+            .unwrap_err();
+
+        // Other accounts are only limited by the total limit.
+        let other_account = Address::repeat_byte(0x11);
+        limiter
+            .check(
+                &mut storage,
+                other_tx_hash,
+                other_account,
+                &[withdrawal(800)],
+            )
+            .await
+            .unwrap();
+        let err = limiter
+            .check(
+                &mut storage,
+                other_tx_hash,
+                other_account,
+                &[withdrawal(801)],
+            )
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            SubmitTxError::WithdrawalLimitExceeded {
+                per_account: false,
+                ..
+            }
+        );
+
+        // Exempt accounts are not limited, and don't reserve usage.
+        storage
+            .withdrawal_limits_dal()
+            .add_exemption(initiator, None)
+            .await
+            .unwrap();
+        let limited = limiter
+            .check(
+                &mut storage,
+                other_tx_hash,
+                initiator,
+                &[withdrawal(1_000_000)],
+            )
+            .await
+            .unwrap();
+        assert!(limited.is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+//! RPC middleware rejecting calls to disabled methods.
+
+use tokio::sync::watch;
+use zksync_web3_decl::jsonrpsee::{
+    server::middleware::rpc::{layer::ResponseFuture, RpcServiceT},
+    types::{error::ErrorCode, ErrorObject, Request},
+    MethodResponse,
+};
+
+use crate::config_reload::ReloadableConfig;
+
+/// Middleware rejecting calls to methods disabled in the reloadable config, so that methods can be disabled
+/// and re-enabled without restarting the server. If `config` is not set, requests are passed through.
+#[derive(Debug, Clone)]
+pub(crate) struct MethodFilterMiddleware<S> {
+    inner: S,
+    config: Option<watch::Receiver<ReloadableConfig>>,
+}
+
+impl<S> MethodFilterMiddleware<S> {
+    pub(crate) fn new(inner: S, config: Option<watch::Receiver<ReloadableConfig>>) -> Self {
+        Self { inner, config }
+    }
+
+    fn is_disabled(&self, method_name: &str) -> bool {
+        let Some(config) = &self.config else {
+            return false;
+        };
+        let config = config.borrow();
+        config
+            .api
+            .as_ref()
+            .is_some_and(|params| params.disabled_methods.contains(method_name))
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for MethodFilterMiddleware<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        if self.is_disabled(request.method_name()) {
+            let err =
+                ErrorObject::borrowed(ErrorCode::MethodNotFound.code(), "Method is disabled", None);
+            return ResponseFuture::ready(MethodResponse::error(request.id, err));
+        }
+        ResponseFuture::future(self.inner.call(request))
+    }
+}
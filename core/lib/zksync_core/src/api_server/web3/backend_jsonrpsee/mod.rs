@@ -12,11 +12,20 @@ use zksync_web3_decl::{
 use crate::api_server::{tx_sender::SubmitTxError, web3::metrics::API_METRICS};
 
 pub mod batch_limiter_middleware;
+pub(crate) mod method_filter_middleware;
+pub(crate) mod name_resolution_middleware;
 pub mod namespaces;
+pub mod operator_auth;
+pub(crate) mod shadowing_middleware;
 
 pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
     let data = match &err {
-        Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
+        Web3Error::SubmitTransactionError(_, data) => Some(serde_json::Value::String(format!(
+            "0x{}",
+            hex::encode(data)
+        ))),
+        Web3Error::TxLimitExceeded(violation) => serde_json::to_value(violation).ok(),
+        Web3Error::InclusionHorizonExceeded(violation) => serde_json::to_value(violation).ok(),
         _ => None,
     };
     ErrorObjectOwned::owned(
@@ -25,6 +34,7 @@ pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             Web3Error::NoBlock
             | Web3Error::PrunedBlock(_)
             | Web3Error::PrunedL1Batch(_)
+            | Web3Error::PrunedBlockStateUnavailable(..)
             | Web3Error::NoSuchFunction
             | Web3Error::RLPError(_)
             | Web3Error::InvalidTransactionData(_)
@@ -32,11 +42,25 @@ pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
-            Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
+            | Web3Error::InvalidLogFilter(_)
+            | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::InvalidName(_)
+            | Web3Error::UnknownName(_)
+            | Web3Error::TooManyReads(..)
+            | Web3Error::InvalidAbi(_)
+            | Web3Error::TooManyLogProofRequests(..)
+            | Web3Error::InvalidWithdrawalLimit(_)
+            | Web3Error::InvalidScheduledTx(_) => ErrorCode::InvalidParams.code(),
+            Web3Error::SubmitTransactionError(_, _)
+            | Web3Error::TxLimitExceeded(_)
+            | Web3Error::InclusionHorizonExceeded(_)
+            | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
-            Web3Error::TreeApiUnavailable => 6,
+            Web3Error::TreeApiUnavailable
+            | Web3Error::TreeLagging(..)
+            | Web3Error::ComponentUnavailable(_)
+            | Web3Error::NameRegistryUnavailable => 6,
         },
         match err {
             Web3Error::SubmitTransactionError(message, _) => message,
@@ -57,6 +81,8 @@ impl SubmitTxError {
                 tracing::warn!("Error proxying call to main node in method {method_name}: {err}");
                 Web3Error::SubmitTransactionError(err.as_ref().to_string(), self.data())
             }
+            Self::TxLimitExceeded(violation) => Web3Error::TxLimitExceeded(violation),
+            Self::MempoolSaturated(violation) => Web3Error::InclusionHorizonExceeded(violation),
             _ => Web3Error::SubmitTransactionError(self.to_string(), self.data()),
         }
     }
@@ -0,0 +1,64 @@
+//! RPC middleware resolving names in address parameters of RPC methods.
+
+use std::borrow::Cow;
+
+use futures::{
+    future::{BoxFuture, Either},
+    FutureExt,
+};
+use zksync_web3_decl::jsonrpsee::{
+    server::middleware::rpc::RpcServiceT, types::Request, MethodResponse,
+};
+
+use crate::api_server::web3::{
+    backend_jsonrpsee::into_jsrpc_error,
+    name_resolution::{NameParam, NameResolver},
+};
+
+/// Middleware replacing names in address parameters with addresses resolved using the name registry,
+/// so that RPC methods can keep accepting addresses only. If `resolver` is not set, requests are passed through.
+#[derive(Debug, Clone)]
+pub(crate) struct NameResolutionMiddleware<S> {
+    inner: S,
+    resolver: Option<NameResolver>,
+}
+
+impl<S> NameResolutionMiddleware<S> {
+    pub(crate) fn new(inner: S, resolver: Option<NameResolver>) -> Self {
+        Self { inner, resolver }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for NameResolutionMiddleware<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'a,
+    S::Future: Send,
+{
+    type Future = Either<S::Future, BoxFuture<'a, MethodResponse>>;
+
+    fn call(&self, mut request: Request<'a>) -> Self::Future {
+        let param = NameParam::for_method(request.method_name());
+        let (Some(resolver), Some(param)) = (&self.resolver, param) else {
+            return Either::Left(self.inner.call(request));
+        };
+        let resolver = resolver.clone();
+        let inner = self.inner.clone();
+
+        let resolution = async move {
+            let Some(params) = request.params.as_deref() else {
+                return inner.call(request).await;
+            };
+            match resolver.resolve_params(param, params).await {
+                Ok(Some(resolved_params)) => {
+                    request.params = Some(Cow::Owned(resolved_params));
+                }
+                Ok(None) => { /* No names in params */ }
+                Err(err) => {
+                    return MethodResponse::error(request.id, into_jsrpc_error(err));
+                }
+            }
+            inner.call(request).await
+        };
+        Either::Right(resolution.boxed())
+    }
+}
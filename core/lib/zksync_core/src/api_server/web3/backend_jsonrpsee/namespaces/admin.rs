@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use zksync_types::{
+    api::idexo::{
+        FeeDiscount, RocksdbColumnFamilySizes, ScheduledTxParams, ScheduledTxStatus,
+        SnapshotRequest, TxIntakePause, WithdrawalLimit, WithdrawalLimitExemption,
+    },
+    Address, H256, U256,
+};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::AdminNamespaceServer};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::AdminNamespace};
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    async fn pause_tx_intake(&self, reason: String) -> RpcResult<TxIntakePause> {
+        self.pause_tx_intake_impl(reason)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn resume_tx_intake(&self) -> RpcResult<Option<TxIntakePause>> {
+        self.resume_tx_intake_impl().await.map_err(into_jsrpc_error)
+    }
+
+    async fn get_tx_intake_pause(&self) -> RpcResult<Option<TxIntakePause>> {
+        self.get_tx_intake_pause_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn drop_transaction(&self, hash: H256) -> RpcResult<bool> {
+        self.drop_transaction_impl(hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn request_snapshot(&self) -> RpcResult<SnapshotRequest> {
+        self.request_snapshot_impl().await.map_err(into_jsrpc_error)
+    }
+
+    async fn get_snapshot_request(&self) -> RpcResult<Option<SnapshotRequest>> {
+        self.get_snapshot_request_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn seal_l1_batch(&self) -> RpcResult<()> {
+        self.seal_l1_batch_impl().await.map_err(into_jsrpc_error)
+    }
+
+    async fn get_component_statuses(&self) -> RpcResult<serde_json::Value> {
+        self.get_component_statuses_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_rocksdb_sizes(&self) -> RpcResult<Vec<RocksdbColumnFamilySizes>> {
+        self.get_rocksdb_sizes_impl().map_err(into_jsrpc_error)
+    }
+
+    async fn compact_rocksdb(
+        &self,
+        db_name: String,
+    ) -> RpcResult<Option<Vec<RocksdbColumnFamilySizes>>> {
+        self.compact_rocksdb_impl(db_name)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn set_fee_discount(
+        &self,
+        address: Address,
+        discount_bps: u32,
+        label: Option<String>,
+    ) -> RpcResult<FeeDiscount> {
+        self.set_fee_discount_impl(address, discount_bps, label)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn remove_fee_discount(&self, address: Address) -> RpcResult<Option<FeeDiscount>> {
+        self.remove_fee_discount_impl(address)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_fee_discounts(&self) -> RpcResult<Vec<FeeDiscount>> {
+        self.get_fee_discounts_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn set_withdrawal_limit(
+        &self,
+        l1_token: Address,
+        total_limit: Option<U256>,
+        account_limit: Option<U256>,
+        window_sec: u64,
+    ) -> RpcResult<WithdrawalLimit> {
+        self.set_withdrawal_limit_impl(l1_token, total_limit, account_limit, window_sec)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn remove_withdrawal_limit(
+        &self,
+        l1_token: Address,
+    ) -> RpcResult<Option<WithdrawalLimit>> {
+        self.remove_withdrawal_limit_impl(l1_token)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_withdrawal_limits(&self) -> RpcResult<Vec<WithdrawalLimit>> {
+        self.get_withdrawal_limits_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn add_withdrawal_limit_exemption(
+        &self,
+        address: Address,
+        label: Option<String>,
+    ) -> RpcResult<WithdrawalLimitExemption> {
+        self.add_withdrawal_limit_exemption_impl(address, label)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn remove_withdrawal_limit_exemption(
+        &self,
+        address: Address,
+    ) -> RpcResult<Option<WithdrawalLimitExemption>> {
+        self.remove_withdrawal_limit_exemption_impl(address)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_withdrawal_limit_exemptions(&self) -> RpcResult<Vec<WithdrawalLimitExemption>> {
+        self.get_withdrawal_limit_exemptions_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn register_scheduled_tx(&self, params: ScheduledTxParams) -> RpcResult<()> {
+        self.register_scheduled_tx_impl(params)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn disable_scheduled_tx(&self, name: String) -> RpcResult<bool> {
+        self.disable_scheduled_tx_impl(name)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_scheduled_txs(&self) -> RpcResult<Vec<ScheduledTxStatus>> {
+        self.get_scheduled_txs_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+}
@@ -1,4 +1,8 @@
-use zksync_types::{api::en::SyncBlock, tokens::TokenInfo, MiniblockNumber};
+use zksync_types::{
+    api::en::{SyncBlock, SyncBlockOutputs, SyncL1BatchRootHash},
+    tokens::TokenInfo,
+    L1BatchNumber, MiniblockNumber,
+};
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
     namespaces::en::EnNamespaceServer,
@@ -18,6 +22,24 @@ impl EnNamespaceServer for EnNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn sync_l2_block_outputs(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<SyncBlockOutputs>> {
+        self.sync_l2_block_outputs_impl(block_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn sync_l1_batch_root_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<SyncL1BatchRootHash>> {
+        self.sync_l1_batch_root_hash_impl(l1_batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn sync_tokens(
         &self,
         block_number: Option<MiniblockNumber>,
@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use zksync_types::{
+    api::{
+        idexo::{
+            BatchEconomics, BatchStateDiffs, DaInclusionProof, DecodedLog, DepositStatus,
+            ForcedInclusionStatus, L1BatchProofStatus, L2ToL1LogProofRequest, MulticallRead,
+            MulticallReadResult, PriorityQueueStatus, RegisteredToken, RelayedMessageStatus,
+            TransactionDiagnostics, TransactionDiagnosticsTarget, TransactionTimeline,
+        },
+        BlockIdVariant, L2ToL1LogProof,
+    },
+    Address, L1BatchNumber, H256,
+};
+use zksync_web3_decl::{
+    jsonrpsee::core::RpcResult, namespaces::IdexoNamespaceServer, types::Filter,
+};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::IdexoNamespace};
+
+#[async_trait]
+impl IdexoNamespaceServer for IdexoNamespace {
+    async fn get_batch_economics(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BatchEconomics>> {
+        self.get_batch_economics_impl(l1_batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_da_inclusion_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<DaInclusionProof>> {
+        self.get_da_inclusion_proof_impl(l1_batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_token_list(&self) -> RpcResult<Vec<RegisteredToken>> {
+        self.get_token_list_impl().await.map_err(into_jsrpc_error)
+    }
+
+    async fn get_deposit_status(&self, settlement_tx_hash: H256) -> RpcResult<Vec<DepositStatus>> {
+        self.get_deposit_status_impl(settlement_tx_hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_relayed_messages(
+        &self,
+        source_tx_hash: H256,
+    ) -> RpcResult<Vec<RelayedMessageStatus>> {
+        self.get_relayed_messages_impl(source_tx_hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_pending_priority_ops(
+        &self,
+        limit: Option<usize>,
+    ) -> RpcResult<PriorityQueueStatus> {
+        self.get_pending_priority_ops_impl(limit)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_l1_batch_proof_status(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchProofStatus>> {
+        self.get_l1_batch_proof_status_impl(l1_batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_forced_inclusion_status(&self) -> RpcResult<Option<ForcedInclusionStatus>> {
+        self.get_forced_inclusion_status_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn resolve_name(&self, name: String) -> RpcResult<Option<Address>> {
+        self.resolve_name_impl(&name)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn multicall_read(
+        &self,
+        reads: Vec<MulticallRead>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Vec<MulticallReadResult>> {
+        self.multicall_read_impl(reads, block.map(Into::into))
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_batch_state_diffs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> RpcResult<Option<BatchStateDiffs>> {
+        self.get_batch_state_diffs_impl(l1_batch_number, offset, limit)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_decoded_logs(&self, filter: Filter) -> RpcResult<Vec<DecodedLog>> {
+        self.get_decoded_logs_impl(filter)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_l2_to_l1_log_proofs(
+        &self,
+        requests: Vec<L2ToL1LogProofRequest>,
+    ) -> RpcResult<Vec<Option<L2ToL1LogProof>>> {
+        self.get_l2_to_l1_log_proofs_impl(requests)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_transaction_timeline(&self, hash: H256) -> RpcResult<Option<TransactionTimeline>> {
+        self.get_transaction_timeline_impl(hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn diagnose_transaction(
+        &self,
+        target: TransactionDiagnosticsTarget,
+    ) -> RpcResult<Vec<TransactionDiagnostics>> {
+        self.diagnose_transaction_impl(target)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+}
@@ -1,8 +1,11 @@
+pub mod admin;
 pub mod debug;
 pub mod en;
 pub mod eth;
 pub mod eth_subscribe;
+pub mod idexo;
 pub mod net;
+pub mod operator;
 pub mod snapshots;
 pub mod web3;
 pub mod zks;
@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use zksync_types::{
+    api::idexo::{AuditLogEntry, AuditLogFilter, LogEvent, RegisteredToken},
+    eth_sender::SettlementHalt,
+    Address,
+};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::OperatorNamespaceServer};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::OperatorNamespace};
+
+#[async_trait]
+impl OperatorNamespaceServer for OperatorNamespace {
+    async fn get_settlement_halt(&self) -> RpcResult<Option<SettlementHalt>> {
+        self.get_settlement_halt_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn resume_settlement(&self) -> RpcResult<Option<SettlementHalt>> {
+        self.resume_settlement_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn register_token(&self, token: RegisteredToken) -> RpcResult<()> {
+        self.register_token_impl(token)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn unregister_token(&self, l2_address: Address) -> RpcResult<bool> {
+        self.unregister_token_impl(l2_address)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn register_abi(&self, address: Address, abi: serde_json::Value) -> RpcResult<()> {
+        self.register_abi_impl(address, abi)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn unregister_abi(&self, address: Address) -> RpcResult<bool> {
+        self.unregister_abi_impl(address)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_audit_log(&self, filter: Option<AuditLogFilter>) -> RpcResult<Vec<AuditLogEntry>> {
+        self.get_audit_log_impl(filter.unwrap_or_default())
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_log_filter(&self) -> RpcResult<String> {
+        self.get_log_filter_impl().map_err(into_jsrpc_error)
+    }
+
+    async fn set_log_filter(&self, filter: String) -> RpcResult<String> {
+        self.set_log_filter_impl(filter)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_recent_log_events(&self, limit: Option<usize>) -> RpcResult<Vec<LogEvent>> {
+        self.get_recent_log_events_impl(limit)
+            .map_err(into_jsrpc_error)
+    }
+}
@@ -2,9 +2,10 @@ use std::collections::HashMap;
 
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        idexo::TreeLag, BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof,
+        ProtocolVersion, TransactionDetails,
     },
+    contract_verification_api::VerificationInfo,
     fee::Fee,
     fee_model::FeeParams,
     transaction_request::CallRequest,
@@ -38,6 +39,10 @@ impl ZksNamespaceServer for ZksNamespace {
         Ok(self.get_main_contract_impl())
     }
 
+    async fn get_validator_timelock_contract(&self) -> RpcResult<Option<Address>> {
+        Ok(self.get_validator_timelock_contract_impl())
+    }
+
     async fn get_testnet_paymaster(&self) -> RpcResult<Option<Address>> {
         Ok(self.get_testnet_paymaster_impl())
     }
@@ -165,4 +170,17 @@ impl ZksNamespaceServer for ZksNamespace {
             .await
             .map_err(into_jsrpc_error)
     }
+
+    async fn get_contract_verification_info(
+        &self,
+        address: Address,
+    ) -> RpcResult<Option<VerificationInfo>> {
+        self.get_contract_verification_info_impl(address)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_tree_lag(&self) -> RpcResult<TreeLag> {
+        self.get_tree_lag_impl().await.map_err(into_jsrpc_error)
+    }
 }
@@ -0,0 +1,177 @@
+//! HTTP middleware authenticating calls to the `operator` and `admin` namespaces.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use hyper::{body::HttpBody, header, Body, Request, Response, StatusCode};
+use tower::{Layer, Service};
+
+/// Prefixes of the methods in namespaces requiring authentication.
+const AUTHENTICATED_METHOD_PREFIXES: &[&str] = &["operator_", "admin_"];
+/// Maximum size of a buffered request body; larger requests are rejected. Matches the default limit of `jsonrpsee` servers.
+const MAX_REQUEST_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Checks whether the JSON-RPC request body (potentially, a batch one) calls any `operator` or `admin` methods.
+/// Bodies that are not valid JSON are passed through; they will be rejected by the server.
+fn calls_authenticated_methods(body: &[u8]) -> bool {
+    let is_authenticated_method = |request: &serde_json::Value| {
+        request
+            .get("method")
+            .and_then(serde_json::Value::as_str)
+            .map_or(false, |method| {
+                AUTHENTICATED_METHOD_PREFIXES
+                    .iter()
+                    .any(|prefix| method.starts_with(prefix))
+            })
+    };
+    match serde_json::from_slice(body) {
+        Ok(serde_json::Value::Array(requests)) => requests.iter().any(is_authenticated_method),
+        Ok(request) => is_authenticated_method(&request),
+        Err(_) => false,
+    }
+}
+
+/// Compares tokens in constant time to not leak the expected token via timing.
+fn tokens_match(expected: &[u8], actual: &[u8]) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .iter()
+            .zip(actual)
+            .fold(0, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+/// Layer requiring HTTP requests calling `operator` or `admin` methods to be authenticated with a bearer token.
+/// Other requests are passed through as is.
+#[derive(Debug, Clone)]
+pub(crate) struct OperatorAuthLayer {
+    token: Arc<str>,
+}
+
+impl OperatorAuthLayer {
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+impl<S> Layer<S> for OperatorAuthLayer {
+    type Service = OperatorAuth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        OperatorAuth {
+            inner,
+            token: self.token.clone(),
+        }
+    }
+}
+
+/// Service produced by [`OperatorAuthLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct OperatorAuth<S> {
+    inner: S,
+    token: Arc<str>,
+}
+
+impl<S> OperatorAuth<S> {
+    fn is_authenticated(&self, request: &Request<Body>) -> bool {
+        let Some(header_value) = request.headers().get(header::AUTHORIZATION) else {
+            return false;
+        };
+        let Some(token) = header_value.as_bytes().strip_prefix(b"Bearer ") else {
+            return false;
+        };
+        tokens_match(self.token.as_bytes(), token)
+    }
+}
+
+impl<S> Service<Request<Body>> for OperatorAuth<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if self.is_authenticated(&request) {
+            return Box::pin(self.inner.call(request));
+        }
+
+        // The inner service is ready, while its clone may be not; see `tower::Service` docs.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let (parts, mut body) = request.into_parts();
+            let mut buffer = Vec::new();
+            while let Some(chunk) = body.data().await {
+                let Ok(chunk) = chunk else {
+                    return Ok(empty_response(StatusCode::BAD_REQUEST));
+                };
+                if buffer.len() + chunk.len() > MAX_REQUEST_BODY_SIZE {
+                    return Ok(empty_response(StatusCode::PAYLOAD_TOO_LARGE));
+                }
+                buffer.extend_from_slice(&chunk);
+            }
+
+            if calls_authenticated_methods(&buffer) {
+                tracing::info!(
+                    "Rejected unauthenticated request calling `operator` or `admin` methods"
+                );
+                return Ok(empty_response(StatusCode::UNAUTHORIZED));
+            }
+            inner
+                .call(Request::from_parts(parts, Body::from(buffer)))
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detecting_authenticated_methods() {
+        let body = br#"{"jsonrpc":"2.0","id":1,"method":"operator_registerToken","params":[]}"#;
+        assert!(calls_authenticated_methods(body));
+        let body = br#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":["operator_"]}"#;
+        assert!(!calls_authenticated_methods(body));
+        let body = br#"[
+            {"jsonrpc":"2.0","id":1,"method":"eth_chainId"},
+            {"jsonrpc":"2.0","id":2,"method":"operator_getSettlementHalt"}
+        ]"#;
+        assert!(calls_authenticated_methods(body));
+        let body = br#"[{"jsonrpc":"2.0","id":1,"method":"eth_chainId"}]"#;
+        assert!(!calls_authenticated_methods(body));
+        let body = br#"[1, {"jsonrpc":"2.0","id":2,"method":"operator\u005fgetSettlementHalt"}]"#;
+        assert!(calls_authenticated_methods(body));
+        assert!(!calls_authenticated_methods(b"operator_ is not JSON"));
+        let body = br#"{"jsonrpc":"2.0","id":1,"method":"admin_pauseTxIntake","params":["test"]}"#;
+        assert!(calls_authenticated_methods(body));
+    }
+
+    #[test]
+    fn comparing_tokens() {
+        assert!(tokens_match(b"secret", b"secret"));
+        assert!(!tokens_match(b"secret", b"secreT"));
+        assert!(!tokens_match(b"secret", b"secret!"));
+        assert!(!tokens_match(b"secret", b""));
+    }
+}
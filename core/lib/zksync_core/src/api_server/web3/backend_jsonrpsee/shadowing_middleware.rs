@@ -0,0 +1,228 @@
+//! RPC middleware mirroring a share of read requests to a shadow endpoint (e.g., a canary node running
+//! a new server version) and comparing its responses with the local ones.
+
+use std::{sync::Arc, time::Instant};
+
+use anyhow::Context as _;
+use futures::{
+    future::{BoxFuture, Either},
+    FutureExt,
+};
+use rand::Rng;
+use serde_json::{value::RawValue, Value};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use zksync_web3_decl::jsonrpsee::{
+    core::{client::ClientT, traits::ToRpcParams, ClientError},
+    http_client::{HttpClient, HttpClientBuilder},
+    server::middleware::rpc::RpcServiceT,
+    types::Request,
+    MethodResponse,
+};
+
+use crate::api_server::web3::metrics::{ShadowOutcome, SHADOW_METRICS};
+
+/// Prefixes of namespaces with methods mirrored to the shadow endpoint.
+const SHADOWED_NAMESPACES: &[&str] = &["eth_", "zks_", "net_", "web3_", "idexo_", "debug_"];
+/// Methods from [`SHADOWED_NAMESPACES`] that are not mirrored, either because they change the node state,
+/// or because their responses depend on the state local to the node (e.g., filter IDs).
+const NOT_SHADOWED_METHODS: &[&str] = &[
+    "eth_sendRawTransaction",
+    "eth_newFilter",
+    "eth_newBlockFilter",
+    "eth_newPendingTransactionFilter",
+    "eth_uninstallFilter",
+    "eth_getFilterChanges",
+    "eth_getFilterLogs",
+    "eth_subscribe",
+    "eth_unsubscribe",
+    "idexo_subscribeBatchStatus",
+    "idexo_unsubscribeBatchStatus",
+];
+/// Maximum number of concurrent requests to the shadow endpoint. Sampled requests exceeding this limit
+/// are not mirrored, so that a slow shadow endpoint cannot accumulate unbounded work on the node.
+const MAX_IN_FLIGHT_SHADOW_REQUESTS: usize = 100;
+
+fn is_shadowed_method(method: &str) -> bool {
+    SHADOWED_NAMESPACES
+        .iter()
+        .any(|namespace| method.starts_with(namespace))
+        && !NOT_SHADOWED_METHODS.contains(&method)
+}
+
+/// Params passed to the shadow endpoint as is.
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+/// Outcome of an RPC call compared between the local node and the shadow endpoint.
+/// Error messages are not compared since they may legitimately change between server versions.
+#[derive(Debug, PartialEq)]
+enum CallOutcome {
+    Success(Value),
+    Error(i32),
+}
+
+impl CallOutcome {
+    /// Parses a serialized JSON-RPC response produced by the local server.
+    fn from_response(response: &str) -> Option<Self> {
+        let mut response: Value = serde_json::from_str(response).ok()?;
+        if let Some(result) = response.get_mut("result") {
+            return Some(Self::Success(result.take()));
+        }
+        let code = response.get("error")?.get("code")?.as_i64()?;
+        Some(Self::Error(code as i32))
+    }
+}
+
+/// Mirrors a configured percentage of read requests to the shadow endpoint.
+#[derive(Debug)]
+pub(crate) struct RequestShadow {
+    client: HttpClient,
+    percentage: f64,
+    in_flight_requests: Arc<Semaphore>,
+}
+
+impl RequestShadow {
+    pub(crate) fn new(url: &str, percentage: f64) -> anyhow::Result<Self> {
+        let client = HttpClientBuilder::default().build(url).with_context(|| {
+            format!("failed creating JSON-RPC client for shadow endpoint {url}")
+        })?;
+        Ok(Self {
+            client,
+            percentage: percentage.clamp(0.0, 100.0),
+            in_flight_requests: Arc::new(Semaphore::new(MAX_IN_FLIGHT_SHADOW_REQUESTS)),
+        })
+    }
+
+    fn sample(&self, method: &str) -> bool {
+        is_shadowed_method(method) && rand::thread_rng().gen_bool(self.percentage / 100.0)
+    }
+
+    async fn compare(
+        self: Arc<Self>,
+        method: String,
+        params: Option<Box<RawValue>>,
+        local_outcome: CallOutcome,
+        _permit: OwnedSemaphorePermit,
+    ) {
+        let started_at = Instant::now();
+        let shadow_result = self
+            .client
+            .request::<Value, _>(&method, RawParams(params.clone()))
+            .await;
+        SHADOW_METRICS.shadow_latency.observe(started_at.elapsed());
+
+        let shadow_outcome = match shadow_result {
+            Ok(value) => CallOutcome::Success(value),
+            Err(ClientError::Call(err)) => CallOutcome::Error(err.code()),
+            Err(err) => {
+                tracing::debug!("Failed mirroring `{method}` call to shadow endpoint: {err}");
+                SHADOW_METRICS.requests[&ShadowOutcome::Failed].inc();
+                return;
+            }
+        };
+
+        if shadow_outcome == local_outcome {
+            SHADOW_METRICS.requests[&ShadowOutcome::Matched].inc();
+        } else {
+            let params = params.as_deref().map_or("[]", RawValue::get);
+            tracing::warn!(
+                "Shadow endpoint response for `{method}` with params {params} diverges from the local one: \
+                 local {local_outcome:?}, shadow {shadow_outcome:?}"
+            );
+            SHADOW_METRICS.requests[&ShadowOutcome::Diverged].inc();
+            SHADOW_METRICS.diverged_methods[&method].inc();
+        }
+    }
+}
+
+/// Middleware mirroring sampled read requests to the shadow endpoint after they are processed locally.
+/// Mirroring is asynchronous and doesn't affect responses returned to clients. If `shadow` is not set,
+/// requests are passed through.
+#[derive(Debug, Clone)]
+pub(crate) struct ShadowingMiddleware<S> {
+    inner: S,
+    shadow: Option<Arc<RequestShadow>>,
+}
+
+impl<S> ShadowingMiddleware<S> {
+    pub(crate) fn new(inner: S, shadow: Option<Arc<RequestShadow>>) -> Self {
+        Self { inner, shadow }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for ShadowingMiddleware<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'a,
+    S::Future: Send,
+{
+    type Future = Either<S::Future, BoxFuture<'a, MethodResponse>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let Some(shadow) = &self.shadow else {
+            return Either::Left(self.inner.call(request));
+        };
+        if !shadow.sample(request.method_name()) {
+            return Either::Left(self.inner.call(request));
+        }
+        let Ok(permit) = shadow.in_flight_requests.clone().try_acquire_owned() else {
+            SHADOW_METRICS.requests[&ShadowOutcome::Skipped].inc();
+            return Either::Left(self.inner.call(request));
+        };
+
+        let shadow = shadow.clone();
+        let inner = self.inner.clone();
+        let method = request.method_name().to_owned();
+        let params = request.params.as_deref().map(ToOwned::to_owned);
+        let mirroring = async move {
+            let response = inner.call(request).await;
+            if !response.is_subscription {
+                if let Some(local_outcome) = CallOutcome::from_response(&response.result) {
+                    tokio::spawn(shadow.compare(method, params, local_outcome, permit));
+                }
+            }
+            response
+        };
+        Either::Right(mirroring.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selecting_shadowed_methods() {
+        assert!(is_shadowed_method("eth_getBalance"));
+        assert!(is_shadowed_method("zks_getBlockDetails"));
+        assert!(is_shadowed_method("idexo_multicallRead"));
+        assert!(!is_shadowed_method("eth_sendRawTransaction"));
+        assert!(!is_shadowed_method("eth_getFilterChanges"));
+        assert!(!is_shadowed_method("admin_pauseTxIntake"));
+        assert!(!is_shadowed_method("en_syncL2Block"));
+    }
+
+    #[test]
+    fn parsing_call_outcome() {
+        let response = r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#;
+        assert_eq!(
+            CallOutcome::from_response(response),
+            Some(CallOutcome::Success("0x1".into()))
+        );
+        let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+        assert_eq!(
+            CallOutcome::from_response(response),
+            Some(CallOutcome::Success(Value::Null))
+        );
+        let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"oops"},"id":1}"#;
+        assert_eq!(
+            CallOutcome::from_response(response),
+            Some(CallOutcome::Error(-32602))
+        );
+        assert_eq!(CallOutcome::from_response("not a response"), None);
+    }
+}
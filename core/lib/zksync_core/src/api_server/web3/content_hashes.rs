@@ -0,0 +1,76 @@
+//! Cache of verified miniblock content hashes.
+//!
+//! Recomputing a content hash requires loading all transactions, events, logs and storage writes of a miniblock,
+//! which is too expensive to do on each API request for popular (e.g., recent) miniblocks. Instead, the cache
+//! remembers the persisted content hash of each successfully verified miniblock; a miniblock is re-verified only
+//! if its persisted hash changes (e.g., after a revert). As a consequence, corruption of miniblock data
+//! that happens after the miniblock was verified is only detected once the miniblock is evicted from the cache.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use zksync_dal::{content_hashes_dal::ContentHashCheck, StorageProcessor};
+use zksync_types::{MiniblockNumber, H256};
+
+use super::metrics::CONTENT_HASH_METRICS;
+
+/// LRU cache of verified content hashes shared among all API requests.
+#[derive(Debug, Clone)]
+pub(super) struct VerifiedContentHashes {
+    hashes: Arc<Mutex<LruCache<MiniblockNumber, H256>>>,
+}
+
+impl VerifiedContentHashes {
+    /// Number of miniblocks with cached verified hashes.
+    const CAPACITY: NonZeroUsize = match NonZeroUsize::new(4_096) {
+        Some(capacity) => capacity,
+        None => unreachable!(),
+    };
+
+    pub fn new() -> Self {
+        Self {
+            hashes: Arc::new(Mutex::new(LruCache::new(Self::CAPACITY))),
+        }
+    }
+
+    /// Checks the miniblock data in the storage against its persisted content hash, using the cache if possible.
+    pub async fn check(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<ContentHashCheck> {
+        let mut content_hashes = storage.content_hashes_dal();
+        let Some(persisted) = content_hashes.get_content_hash(miniblock_number).await? else {
+            return Ok(ContentHashCheck::Missing);
+        };
+        let cached = self
+            .hashes
+            .lock()
+            .expect("content hash cache is poisoned")
+            .get(&miniblock_number)
+            .copied();
+        if cached == Some(persisted) {
+            CONTENT_HASH_METRICS.cache_hits.inc();
+            return Ok(ContentHashCheck::Valid(persisted));
+        }
+
+        CONTENT_HASH_METRICS.cache_misses.inc();
+        let computed = content_hashes
+            .compute_content_hash(miniblock_number)
+            .await?;
+        if computed != persisted {
+            return Ok(ContentHashCheck::Mismatch {
+                persisted,
+                computed,
+            });
+        }
+        self.hashes
+            .lock()
+            .expect("content hash cache is poisoned")
+            .put(miniblock_number, persisted);
+        Ok(ContentHashCheck::Valid(persisted))
+    }
+}
@@ -0,0 +1,464 @@
+//! Decoding of event logs using contract ABIs registered by the operator or obtained from contract verification.
+//!
+//! Events are matched by the first log topic, so anonymous events are never decoded. Parsed ABIs are cached
+//! and shared among all API requests; ABIs are still loaded from Postgres on each request, so that changes
+//! to registered ABIs take effect immediately, but are only re-parsed if they have changed.
+
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use serde_json::Value;
+use zksync_types::{
+    api::{
+        idexo::{DecodedEvent, DecodedEventParam},
+        Log,
+    },
+    ethabi::{Contract, Event, EventParam, ParamType, RawLog, Token},
+    Address, U256,
+};
+
+use super::metrics::EVENT_DECODING_METRICS;
+
+/// Contract ABI together with its parsed form (`None` if the ABI cannot be parsed).
+#[derive(Debug)]
+struct CachedAbi {
+    abi: Value,
+    contract: Option<Arc<Contract>>,
+}
+
+/// LRU cache of parsed contract ABIs shared among all API requests.
+#[derive(Debug, Clone)]
+pub(super) struct ContractAbiCache {
+    abis: Arc<Mutex<LruCache<Address, Arc<CachedAbi>>>>,
+}
+
+impl ContractAbiCache {
+    /// Number of contracts with cached ABIs.
+    const CAPACITY: NonZeroUsize = match NonZeroUsize::new(1_024) {
+        Some(capacity) => capacity,
+        None => unreachable!(),
+    };
+
+    pub fn new() -> Self {
+        Self {
+            abis: Arc::new(Mutex::new(LruCache::new(Self::CAPACITY))),
+        }
+    }
+
+    /// Creates a decoder from JSON ABIs of contracts, reusing cached parsed ABIs where possible. ABIs that
+    /// cannot be parsed are skipped; logs emitted by the corresponding contracts will not be decoded.
+    pub fn decoder(&self, abis: HashMap<Address, Value>) -> EventDecoder {
+        let mut contracts = HashMap::with_capacity(abis.len());
+        for (address, abi) in abis {
+            let cached = self
+                .abis
+                .lock()
+                .expect("contract ABI cache is poisoned")
+                .get(&address)
+                .cloned();
+            let contract = match cached {
+                Some(cached) if cached.abi == abi => {
+                    EVENT_DECODING_METRICS.cache_hits.inc();
+                    cached.contract.clone()
+                }
+                _ => {
+                    EVENT_DECODING_METRICS.cache_misses.inc();
+                    let contract = parse_abi(address, &abi).map(Arc::new);
+                    let cached = CachedAbi {
+                        abi,
+                        contract: contract.clone(),
+                    };
+                    self.abis
+                        .lock()
+                        .expect("contract ABI cache is poisoned")
+                        .put(address, Arc::new(cached));
+                    contract
+                }
+            };
+            if let Some(contract) = contract {
+                contracts.insert(address, contract);
+            }
+        }
+        EventDecoder { contracts }
+    }
+}
+
+/// Parses a JSON ABI. Unnamed event params are named by their index, since params are matched by name
+/// when decoding logs.
+fn parse_abi(address: Address, abi: &Value) -> Option<Contract> {
+    let mut contract: Contract = match serde_json::from_value(abi.clone()) {
+        Ok(contract) => contract,
+        Err(err) => {
+            tracing::debug!("Skipping unparseable ABI for contract {address:?}: {err}");
+            return None;
+        }
+    };
+    for event in contract.events.values_mut().flatten() {
+        for (i, input) in event.inputs.iter_mut().enumerate() {
+            if input.name.is_empty() {
+                input.name = i.to_string();
+            }
+        }
+    }
+    Some(contract)
+}
+
+/// Decoder of event logs emitted by a fixed set of contracts.
+#[derive(Debug, Default)]
+pub(super) struct EventDecoder {
+    contracts: HashMap<Address, Arc<Contract>>,
+}
+
+impl EventDecoder {
+    /// Decodes the log, or returns `None` if the emitting contract has no known ABI, the ABI has no event
+    /// matching the log, or the log data does not conform to the event.
+    pub fn decode(&self, log: &Log) -> Option<DecodedEvent> {
+        let contract = self.contracts.get(&log.address)?;
+        let topic0 = *log.topics.first()?;
+        let event = contract
+            .events()
+            .find(|event| !event.anonymous && event.signature() == topic0)?;
+        let raw_log = RawLog {
+            topics: log.topics.clone(),
+            data: log.data.0.clone(),
+        };
+        let parsed = event.parse_log(raw_log).ok()?;
+
+        let params = event
+            .inputs
+            .iter()
+            .zip(parsed.params)
+            .map(|(input, param)| DecodedEventParam {
+                name: param.name,
+                kind: logged_param_kind(input),
+                indexed: input.indexed,
+                value: token_to_json(param.value),
+            })
+            .collect();
+        Some(DecodedEvent {
+            name: event.name.clone(),
+            signature: event_signature(event),
+            params,
+        })
+    }
+}
+
+/// Returns the type of the param as it is logged. Indexed params of dynamic types, arrays and tuples
+/// are logged as Keccak-256 hashes of their values.
+fn logged_param_kind(input: &EventParam) -> String {
+    let is_hashed = matches!(
+        input.kind,
+        ParamType::String
+            | ParamType::Bytes
+            | ParamType::Array(_)
+            | ParamType::FixedArray(..)
+            | ParamType::Tuple(_)
+    );
+    if input.indexed && is_hashed {
+        ParamType::FixedBytes(32).to_string()
+    } else {
+        input.kind.to_string()
+    }
+}
+
+fn event_signature(event: &Event) -> String {
+    let param_types: Vec<_> = event
+        .inputs
+        .iter()
+        .map(|input| input.kind.to_string())
+        .collect();
+    format!("{}({})", event.name, param_types.join(","))
+}
+
+fn token_to_json(token: Token) -> Value {
+    match token {
+        Token::Address(address) => Value::String(format!("{address:?}")),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => {
+            Value::String(format!("0x{}", hex::encode(bytes)))
+        }
+        Token::Uint(value) => Value::String(value.to_string()),
+        Token::Int(value) => Value::String(signed_to_string(value)),
+        Token::Bool(value) => Value::Bool(value),
+        Token::String(value) => Value::String(value),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            Value::Array(tokens.into_iter().map(token_to_json).collect())
+        }
+    }
+}
+
+/// Formats a two's complement 256-bit integer (ABI decoding sign-extends all `int*` values to 256 bits).
+fn signed_to_string(value: U256) -> String {
+    if value.bit(255) {
+        let abs = (!value).overflowing_add(U256::one()).0;
+        format!("-{abs}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Returns JSON ABI of the specified events; used in tests.
+#[cfg(test)]
+pub(super) fn events_abi(events: &[Event]) -> Value {
+    let items = events.iter().map(|event| {
+        let inputs: Vec<_> = event
+            .inputs
+            .iter()
+            .map(|input| {
+                serde_json::json!({
+                    "name": input.name,
+                    "type": input.kind.to_string(),
+                    "indexed": input.indexed,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "type": "event",
+            "name": event.name,
+            "inputs": inputs,
+            "anonymous": event.anonymous,
+        })
+    });
+    Value::Array(items.collect())
+}
+
+/// Returns the `Transfer(address,address,uint256)` event of ERC-20 tokens; used in tests.
+#[cfg(test)]
+pub(super) fn transfer_event() -> Event {
+    Event {
+        name: "Transfer".to_owned(),
+        inputs: vec![
+            EventParam {
+                name: "from".to_owned(),
+                kind: ParamType::Address,
+                indexed: true,
+            },
+            EventParam {
+                name: "to".to_owned(),
+                kind: ParamType::Address,
+                indexed: true,
+            },
+            EventParam {
+                name: "value".to_owned(),
+                kind: ParamType::Uint(256),
+                indexed: false,
+            },
+        ],
+        anonymous: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{ethabi, web3::types::Bytes, H256};
+    use zksync_utils::u256_to_h256;
+
+    use super::*;
+
+    fn test_log(address: Address, topics: Vec<H256>, data: Vec<u8>) -> Log {
+        Log {
+            address,
+            topics,
+            data: Bytes(data),
+            block_hash: None,
+            block_number: None,
+            l1_batch_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        }
+    }
+
+    #[test]
+    fn decoding_transfer_event() {
+        let token_address = Address::repeat_byte(1);
+        let event = transfer_event();
+        let decoder = ContractAbiCache::new().decoder(HashMap::from([(
+            token_address,
+            events_abi(&[event.clone()]),
+        )]));
+
+        let from = Address::repeat_byte(0x11);
+        let to = Address::repeat_byte(0x22);
+        let topics = vec![event.signature(), H256::from(from), H256::from(to)];
+        let data = ethabi::encode(&[Token::Uint(12_345.into())]);
+        let log = test_log(token_address, topics.clone(), data.clone());
+
+        let decoded = decoder.decode(&log).unwrap();
+        assert_eq!(decoded.name, "Transfer");
+        assert_eq!(decoded.signature, "Transfer(address,address,uint256)");
+        let values: Vec<_> = decoded
+            .params
+            .iter()
+            .map(|param| (param.name.as_str(), param.indexed, &param.value))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("from", true, &Value::String(format!("{from:?}"))),
+                ("to", true, &Value::String(format!("{to:?}"))),
+                ("value", false, &Value::String("12345".to_owned())),
+            ]
+        );
+        assert_eq!(decoded.params[2].kind, "uint256");
+
+        // Logs from other contracts or with unknown signatures are not decoded.
+        let other_log = test_log(Address::repeat_byte(2), topics, data.clone());
+        assert!(decoder.decode(&other_log).is_none());
+        let unknown_log = test_log(token_address, vec![H256::repeat_byte(0xff)], data);
+        assert!(decoder.decode(&unknown_log).is_none());
+        // Malformed logs are not decoded either.
+        let malformed_log = test_log(token_address, vec![event.signature()], vec![]);
+        assert!(decoder.decode(&malformed_log).is_none());
+    }
+
+    #[test]
+    fn decoding_complex_params() {
+        let address = Address::repeat_byte(1);
+        let event = Event {
+            name: "Complex".to_owned(),
+            inputs: vec![
+                EventParam {
+                    name: "delta".to_owned(),
+                    kind: ParamType::Int(64),
+                    indexed: true,
+                },
+                EventParam {
+                    name: "memo".to_owned(),
+                    kind: ParamType::String,
+                    indexed: false,
+                },
+                EventParam {
+                    name: "ids".to_owned(),
+                    kind: ParamType::Array(Box::new(ParamType::Uint(32))),
+                    indexed: false,
+                },
+                EventParam {
+                    name: "flags".to_owned(),
+                    kind: ParamType::FixedArray(Box::new(ParamType::Bool), 2),
+                    indexed: false,
+                },
+                EventParam {
+                    name: "payload".to_owned(),
+                    kind: ParamType::Bytes,
+                    indexed: false,
+                },
+            ],
+            anonymous: false,
+        };
+        let decoder = ContractAbiCache::new()
+            .decoder(HashMap::from([(address, events_abi(&[event.clone()]))]));
+
+        let delta = !U256::from(41); // -42 in two's complement
+        let topics = vec![event.signature(), u256_to_h256(delta)];
+        let data = ethabi::encode(&[
+            Token::String("hello".to_owned()),
+            Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+            Token::FixedArray(vec![Token::Bool(true), Token::Bool(false)]),
+            Token::Bytes(vec![0xab, 0xcd]),
+        ]);
+        let decoded = decoder.decode(&test_log(address, topics, data)).unwrap();
+
+        assert_eq!(
+            decoded.signature,
+            "Complex(int64,string,uint32[],bool[2],bytes)"
+        );
+        let values: Vec<_> = decoded
+            .params
+            .into_iter()
+            .map(|param| param.value)
+            .collect();
+        assert_eq!(
+            values,
+            [
+                serde_json::json!("-42"),
+                serde_json::json!("hello"),
+                serde_json::json!(["1", "2"]),
+                serde_json::json!([true, false]),
+                serde_json::json!("0xabcd"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skipping_invalid_abis() {
+        let address = Address::repeat_byte(1);
+        let decoder = ContractAbiCache::new()
+            .decoder(HashMap::from([(address, serde_json::json!({ "foo": 1 }))]));
+        assert!(decoder.contracts.is_empty());
+    }
+
+    #[test]
+    fn decoding_unnamed_and_hashed_params() {
+        let address = Address::repeat_byte(1);
+        let event = Event {
+            name: "Unnamed".to_owned(),
+            inputs: vec![
+                EventParam {
+                    name: String::new(),
+                    kind: ParamType::String,
+                    indexed: true,
+                },
+                EventParam {
+                    name: String::new(),
+                    kind: ParamType::Uint(256),
+                    indexed: false,
+                },
+                EventParam {
+                    name: String::new(),
+                    kind: ParamType::Bool,
+                    indexed: false,
+                },
+            ],
+            anonymous: false,
+        };
+        let decoder = ContractAbiCache::new()
+            .decoder(HashMap::from([(address, events_abi(&[event.clone()]))]));
+
+        let memo_hash = H256::repeat_byte(0xaa);
+        let topics = vec![event.signature(), memo_hash];
+        let data = ethabi::encode(&[Token::Uint(5.into()), Token::Bool(true)]);
+        let decoded = decoder.decode(&test_log(address, topics, data)).unwrap();
+
+        assert_eq!(decoded.signature, "Unnamed(string,uint256,bool)");
+        let params: Vec<_> = decoded
+            .params
+            .iter()
+            .map(|param| (param.name.as_str(), param.kind.as_str(), &param.value))
+            .collect();
+        assert_eq!(
+            params,
+            [
+                ("0", "bytes32", &Value::String(format!("{memo_hash:?}"))),
+                ("1", "uint256", &Value::String("5".to_owned())),
+                ("2", "bool", &Value::Bool(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn caching_parsed_abis() {
+        let address = Address::repeat_byte(1);
+        let cache = ContractAbiCache::new();
+        let abi = events_abi(&[transfer_event()]);
+        let decoder = cache.decoder(HashMap::from([(address, abi.clone())]));
+        let contract = decoder.contracts[&address].clone();
+
+        let decoder = cache.decoder(HashMap::from([(address, abi)]));
+        assert!(Arc::ptr_eq(&decoder.contracts[&address], &contract));
+
+        // A changed ABI must be re-parsed.
+        let mut event = transfer_event();
+        event.name = "OtherTransfer".to_owned();
+        let decoder = cache.decoder(HashMap::from([(address, events_abi(&[event]))]));
+        let reparsed = &decoder.contracts[&address];
+        assert!(!Arc::ptr_eq(reparsed, &contract));
+        assert!(reparsed.event("OtherTransfer").is_ok());
+    }
+}
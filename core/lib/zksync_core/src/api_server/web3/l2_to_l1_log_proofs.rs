@@ -0,0 +1,129 @@
+//! Cache of L2-to-L1 log Merkle trees used to serve log proofs.
+//!
+//! L2-to-L1 logs of a sealed L1 batch never change, so the Merkle tree built over them can be reused
+//! for all proofs in the batch. This is important for withdrawal finalizers, which request proofs
+//! for many logs in the same recent L1 batches. The cache is not invalidated on block reverts;
+//! API servers are restarted after a revert anyway.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use zksync_dal::StorageProcessor;
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_types::{
+    api::L2ToL1LogProof, commitment::SerializeCommitment, l2_to_l1_log::L2ToL1Log, L1BatchNumber,
+};
+
+use super::metrics::L2_TO_L1_LOG_PROOF_METRICS;
+
+/// L2-to-L1 logs of a sealed L1 batch together with their Merkle tree.
+#[derive(Debug)]
+pub(super) struct L1BatchLogsTree {
+    logs: Vec<L2ToL1Log>,
+    tree: MiniMerkleTree<{ L2ToL1Log::SERIALIZED_SIZE }>,
+}
+
+impl L1BatchLogsTree {
+    async fn load(
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<Self>> {
+        let Some(batch) = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let logs = storage
+            .blocks_web3_dal()
+            .get_l2_to_l1_logs(l1_batch_number)
+            .await?;
+
+        let min_tree_size = if batch
+            .protocol_version
+            .map(|v| v.is_pre_boojum())
+            .unwrap_or(true)
+        {
+            Some(L2ToL1Log::PRE_BOOJUM_MIN_L2_L1_LOGS_TREE_SIZE)
+        } else {
+            Some(L2ToL1Log::MIN_L2_L1_LOGS_TREE_SIZE)
+        };
+        let tree = MiniMerkleTree::new(logs.iter().map(L2ToL1Log::to_bytes), min_tree_size);
+        Ok(Some(Self { logs, tree }))
+    }
+
+    /// Returns the proof for the log with the specified index among logs matching `log_filter`,
+    /// or `None` if there is no such log.
+    pub fn proof(
+        &self,
+        index_in_filtered_logs: usize,
+        log_filter: impl Fn(&L2ToL1Log) -> bool,
+    ) -> Option<L2ToL1LogProof> {
+        let (l1_log_index, _) = self
+            .logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| log_filter(log))
+            .nth(index_in_filtered_logs)?;
+        let (root, proof) = self.tree.clone().merkle_root_and_path(l1_log_index);
+        Some(L2ToL1LogProof {
+            proof,
+            root,
+            id: l1_log_index as u32,
+        })
+    }
+}
+
+/// LRU cache of [`L1BatchLogsTree`]s shared among all API requests.
+#[derive(Debug, Clone)]
+pub(super) struct L2ToL1LogProofCache {
+    trees: Arc<Mutex<LruCache<L1BatchNumber, Arc<L1BatchLogsTree>>>>,
+}
+
+impl L2ToL1LogProofCache {
+    /// Number of L1 batches with cached trees.
+    const CAPACITY: NonZeroUsize = match NonZeroUsize::new(128) {
+        Some(capacity) => capacity,
+        None => unreachable!(),
+    };
+
+    pub fn new() -> Self {
+        Self {
+            trees: Arc::new(Mutex::new(LruCache::new(Self::CAPACITY))),
+        }
+    }
+
+    /// Returns the tree for the specified L1 batch, loading it from Postgres if necessary.
+    /// Returns `None` if the batch is not sealed.
+    pub async fn get(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<Arc<L1BatchLogsTree>>> {
+        let cached = self
+            .trees
+            .lock()
+            .expect("L2-to-L1 log proof cache is poisoned")
+            .get(&l1_batch_number)
+            .cloned();
+        if let Some(tree) = cached {
+            L2_TO_L1_LOG_PROOF_METRICS.cache_hits.inc();
+            return Ok(Some(tree));
+        }
+
+        L2_TO_L1_LOG_PROOF_METRICS.cache_misses.inc();
+        let Some(tree) = L1BatchLogsTree::load(storage, l1_batch_number).await? else {
+            return Ok(None);
+        };
+        let tree = Arc::new(tree);
+        self.trees
+            .lock()
+            .expect("L2-to-L1 log proof cache is poisoned")
+            .put(l1_batch_number, tree.clone());
+        Ok(Some(tree))
+    }
+}
@@ -35,6 +35,7 @@ enum BlockIdLabel {
     Hash,
     Committed,
     Finalized,
+    Merkleized,
     Latest,
     Earliest,
     Pending,
@@ -73,6 +74,7 @@ impl MethodLabels {
             api::BlockId::Number(api::BlockNumber::Number(_)) => BlockIdLabel::Number,
             api::BlockId::Number(api::BlockNumber::Committed) => BlockIdLabel::Committed,
             api::BlockId::Number(api::BlockNumber::Finalized) => BlockIdLabel::Finalized,
+            api::BlockId::Number(api::BlockNumber::Merkleized) => BlockIdLabel::Merkleized,
             api::BlockId::Number(api::BlockNumber::Latest) => BlockIdLabel::Latest,
             api::BlockId::Number(api::BlockNumber::Earliest) => BlockIdLabel::Earliest,
             api::BlockId::Number(api::BlockNumber::Pending) => BlockIdLabel::Pending,
@@ -179,6 +181,7 @@ pub(super) enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    BatchStatus,
 }
 
 #[derive(Debug, Metrics)]
@@ -205,11 +208,87 @@ pub(super) struct PubSubMetrics {
     pub skipped_broadcast_messages: Family<SubscriptionType, Histogram<u64>>,
     /// Number of subscribers dropped because of a send timeout.
     pub subscriber_send_timeouts: Family<SubscriptionType, Counter>,
+    /// Number of notification batches queued for a subscriber, observed each time a batch is received.
+    #[metrics(buckets = Buckets::exponential(1.0..=1_024.0, 2.0))]
+    pub subscriber_lag: Family<SubscriptionType, Histogram<u64>>,
+    /// Number of warnings sent to subscribers lagging behind notifications.
+    pub slow_subscriber_warnings: Family<SubscriptionType, Counter>,
+    /// Number of subscriptions terminated because subscribers lagged behind notifications.
+    pub slow_subscribers_closed: Family<SubscriptionType, Counter>,
 }
 
 #[vise::register]
 pub(super) static PUB_SUB_METRICS: vise::Global<PubSubMetrics> = vise::Global::new();
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum ShadowOutcome {
+    /// Responses of the local node and the shadow endpoint match.
+    Matched,
+    /// Responses of the local node and the shadow endpoint differ.
+    Diverged,
+    /// The shadow endpoint is unreachable or has returned a malformed response.
+    Failed,
+    /// The request was not mirrored because of too many in-flight shadow requests.
+    Skipped,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_web3_shadow")]
+pub(super) struct ShadowMetrics {
+    /// Number of sampled requests grouped by the outcome of mirroring them to the shadow endpoint.
+    pub requests: Family<ShadowOutcome, Counter>,
+    /// Number of diverged responses grouped by the Web3 method.
+    #[metrics(labels = ["method"])]
+    pub diverged_methods: LabeledFamily<String, Counter>,
+    /// Latency of requests to the shadow endpoint.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub shadow_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static SHADOW_METRICS: vise::Global<ShadowMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_web3_l2_to_l1_log_proofs")]
+pub(super) struct L2ToL1LogProofMetrics {
+    /// Number of L1 batch lookups served from the cache of L2-to-L1 log Merkle trees.
+    pub cache_hits: Counter,
+    /// Number of L1 batch lookups that required loading L2-to-L1 logs from Postgres.
+    pub cache_misses: Counter,
+    /// Number of proofs requested in a single batched request.
+    #[metrics(buckets = Buckets::exponential(1.0..=1024.0, 2.0))]
+    pub batch_size: Histogram<usize>,
+}
+
+#[vise::register]
+pub(super) static L2_TO_L1_LOG_PROOF_METRICS: vise::Global<L2ToL1LogProofMetrics> =
+    vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_web3_content_hashes")]
+pub(super) struct ContentHashMetrics {
+    /// Number of content hash checks served from the cache of verified hashes.
+    pub cache_hits: Counter,
+    /// Number of content hash checks that required recomputing the hash from Postgres.
+    pub cache_misses: Counter,
+}
+
+#[vise::register]
+pub(super) static CONTENT_HASH_METRICS: vise::Global<ContentHashMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_web3_event_decoding")]
+pub(super) struct EventDecodingMetrics {
+    /// Number of contract ABIs served from the cache of parsed ABIs.
+    pub cache_hits: Counter,
+    /// Number of contract ABIs that required parsing.
+    pub cache_misses: Counter,
+}
+
+#[vise::register]
+pub(super) static EVENT_DECODING_METRICS: vise::Global<EventDecodingMetrics> = vise::Global::new();
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "type", rename_all = "snake_case")]
 pub(super) enum FilterType {
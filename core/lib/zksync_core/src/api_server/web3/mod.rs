@@ -11,46 +11,64 @@ use tokio::{
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 use zksync_dal::ConnectionPool;
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_types::MiniblockNumber;
+use zksync_types::{MiniblockNumber, H256};
 use zksync_web3_decl::{
     jsonrpsee::{
         server::{BatchRequestConfig, RpcServiceBuilder, ServerBuilder},
         RpcModule,
     },
     namespaces::{
-        DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        AdminNamespaceServer, DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer,
+        EthPubSubServer, IdexoNamespaceServer, IdexoPubSubServer, NetNamespaceServer,
+        OperatorNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
     },
     types::Filter,
 };
 
 use self::{
+    content_hashes::VerifiedContentHashes,
+    event_decoding::ContractAbiCache,
+    l2_to_l1_log_proofs::L2ToL1LogProofCache,
     metrics::API_METRICS,
+    name_resolution::NameResolver,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        AdminHandles, AdminNamespace, DebugNamespace, EnNamespace, EthNamespace, IdexoNamespace,
+        NetNamespace, OperatorNamespace, SnapshotsNamespace, Web3Namespace, ZksNamespace,
     },
-    pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
-    state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
+    pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent, SlowConsumerPolicy},
+    state::{CachedBlockStartInfo, Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
 };
 use crate::{
     api_server::{
-        execution_sandbox::{BlockStartInfo, VmConcurrencyBarrier},
+        execution_sandbox::VmConcurrencyBarrier,
         tree::TreeApiHttpClient,
         tx_sender::TxSender,
-        web3::backend_jsonrpsee::batch_limiter_middleware::LimitMiddleware,
+        web3::backend_jsonrpsee::{
+            batch_limiter_middleware::LimitMiddleware,
+            method_filter_middleware::MethodFilterMiddleware,
+            name_resolution_middleware::NameResolutionMiddleware,
+            operator_auth::OperatorAuthLayer,
+            shadowing_middleware::{RequestShadow, ShadowingMiddleware},
+        },
     },
+    config_reload::ReloadableConfig,
+    supervisor::RestartPolicy,
     sync_layer::SyncState,
     utils::wait_for_l1_batch,
 };
 
 pub mod backend_jsonrpsee;
+mod content_hashes;
+mod event_decoding;
+mod l2_to_l1_log_proofs;
 mod metrics;
+mod name_resolution;
 pub mod namespaces;
 mod pubsub;
 pub mod state;
 #[cfg(test)]
 pub(crate) mod tests;
+mod tx_diagnostics;
 
 /// Timeout for graceful shutdown logic within API servers.
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
@@ -83,6 +101,9 @@ pub enum Namespace {
     En,
     Pubsub,
     Snapshots,
+    Idexo,
+    Operator,
+    Admin,
 }
 
 impl Namespace {
@@ -93,6 +114,7 @@ impl Namespace {
         Self::Zks,
         Self::En,
         Self::Pubsub,
+        Self::Idexo,
     ];
 }
 
@@ -111,11 +133,18 @@ struct OptionalApiParams {
     sync_state: Option<SyncState>,
     filters_limit: Option<usize>,
     subscriptions_limit: Option<usize>,
+    slow_consumer_policy: Option<SlowConsumerPolicy>,
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<usize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tree_api_url: Option<String>,
+    merkle_tree_disabled: bool,
+    operator_auth_token: Option<String>,
+    admin_handles: AdminHandles,
+    sequencer_signing_key: Option<H256>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    reloadable_config: Option<watch::Receiver<ReloadableConfig>>,
+    restart_policy: RestartPolicy,
 }
 
 /// Full API server parameters.
@@ -200,6 +229,22 @@ impl ApiBuilder {
         self
     }
 
+    /// Sets lag thresholds for pub/sub subscribers, in the number of queued notification batches. Subscribers
+    /// exceeding `warning_threshold` are sent a warning; subscriptions exceeding `close_threshold` are terminated.
+    /// The thresholds are validated when the server is built; `close_threshold` must not exceed 1,024
+    /// (the capacity of notification channels).
+    pub fn with_pubsub_lag_thresholds(
+        mut self,
+        warning_threshold: usize,
+        close_threshold: usize,
+    ) -> Self {
+        self.optional.slow_consumer_policy = Some(SlowConsumerPolicy {
+            warning_threshold,
+            close_threshold,
+        });
+        self
+    }
+
     pub fn with_batch_request_size_limit(mut self, batch_request_size_limit: usize) -> Self {
         self.optional.batch_request_size_limit = Some(batch_request_size_limit);
         self
@@ -239,6 +284,46 @@ impl ApiBuilder {
         self
     }
 
+    /// Marks the Merkle tree as not running on the node if `disabled` is set. In this case, proof-serving methods
+    /// (`zks_getProof`, `zks_getTreeLag`) return an error stating that the tree is unavailable.
+    pub fn with_merkle_tree_disabled(mut self, disabled: bool) -> Self {
+        self.optional.merkle_tree_disabled = disabled;
+        self
+    }
+
+    /// Sets the bearer token authenticating calls to the `operator` and `admin` namespaces over HTTP.
+    /// If the token is set, these namespaces are not served over WebSocket.
+    pub fn with_operator_auth_token(mut self, token: Option<String>) -> Self {
+        self.optional.operator_auth_token = token;
+        self
+    }
+
+    /// Sets handles to the node components controlled by the `admin` namespace.
+    pub fn with_admin_handles(mut self, handles: AdminHandles) -> Self {
+        self.optional.admin_handles = handles;
+        self
+    }
+
+    /// Sets the private key used to sign L2 blocks served via the `en` namespace.
+    pub fn with_sequencer_signing_key(mut self, key: Option<H256>) -> Self {
+        self.optional.sequencer_signing_key = key;
+        self
+    }
+
+    /// Makes the WebSocket rate limit and disabled methods follow the values changed at runtime.
+    /// A changed rate limit applies to new WebSocket connections.
+    pub fn with_reloadable_config(mut self, config: watch::Receiver<ReloadableConfig>) -> Self {
+        self.optional.reloadable_config = Some(config);
+        self
+    }
+
+    /// Sets the policy applied to failed pubsub notifiers and the account nonce sweeper. By default,
+    /// a failure of any of these tasks shuts down the server.
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.optional.restart_policy = policy;
+        self
+    }
+
     #[cfg(test)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
         self.optional.pub_sub_events_sender = Some(sender);
@@ -246,20 +331,46 @@ impl ApiBuilder {
     }
 
     fn into_full_params(self) -> anyhow::Result<FullApiParams> {
+        let transport = self.transport.context("API transport not set")?;
+        if let Some(policy) = &self.optional.slow_consumer_policy {
+            policy.validate()?;
+        }
+        let mut namespaces = self.namespaces.unwrap_or_else(|| {
+            tracing::warn!(
+                "debug_ and snapshots_ API namespace will be disabled by default in ApiBuilder"
+            );
+            Namespace::DEFAULT.to_vec()
+        });
+        for (namespace, name) in [
+            (Namespace::Operator, "operator_"),
+            (Namespace::Admin, "admin_"),
+        ] {
+            if !namespaces.contains(&namespace) {
+                continue;
+            }
+            // Operator and admin methods override node safety mechanisms and mutate the node state,
+            // so they are never served without authentication.
+            anyhow::ensure!(
+                self.optional.operator_auth_token.is_some(),
+                "{name} API namespace cannot be enabled without `operator_auth_token`"
+            );
+            if matches!(transport, ApiTransport::WebSocket(_)) {
+                tracing::info!(
+                    "{name} API namespace is disabled for WS server since its requests cannot be authenticated"
+                );
+                namespaces.retain(|enabled| *enabled != namespace);
+            }
+        }
+
         Ok(FullApiParams {
             pool: self.pool,
             updaters_pool: self.updaters_pool,
             config: self.config,
-            transport: self.transport.context("API transport not set")?,
+            transport,
             tx_sender: self.tx_sender.context("Transaction sender not set")?,
             vm_barrier: self.vm_barrier.context("VM barrier not set")?,
             polling_interval: self.polling_interval,
-            namespaces: self.namespaces.unwrap_or_else(|| {
-                tracing::warn!(
-                    "debug_ and snapshots_ API namespace will be disabled by default in ApiBuilder"
-                );
-                Namespace::DEFAULT.to_vec()
-            }),
+            namespaces,
             optional: self.optional,
         })
     }
@@ -275,19 +386,28 @@ impl ApiBuilder {
 }
 
 impl FullApiParams {
-    async fn build_rpc_state(
+    fn name_resolver(&self) -> Option<NameResolver> {
+        let registry_address = self.config.name_registry_addr?;
+        Some(NameResolver::new(
+            registry_address,
+            self.pool.clone(),
+            self.tx_sender.clone(),
+            self.config.max_tx_size,
+        ))
+    }
+
+    fn build_rpc_state(
         self,
         last_sealed_miniblock: SealedMiniblockNumber,
-    ) -> anyhow::Result<RpcState> {
-        let mut storage = self.updaters_pool.access_storage_tagged("api").await?;
-        let start_info = BlockStartInfo::new(&mut storage).await?;
-        drop(storage);
-
-        Ok(RpcState {
+        start_info: CachedBlockStartInfo,
+    ) -> RpcState {
+        let name_resolver = self.name_resolver();
+        RpcState {
             installed_filters: Arc::new(Mutex::new(Filters::new(self.optional.filters_limit))),
             connection_pool: self.pool,
             tx_sender: self.tx_sender,
             sync_state: self.optional.sync_state,
+            replay_protection: self.config.replay_protection(),
             api_config: self.config,
             start_info,
             last_sealed_miniblock,
@@ -295,22 +415,34 @@ impl FullApiParams {
                 .optional
                 .tree_api_url
                 .map(|url| TreeApiHttpClient::new(url.as_str())),
-        })
+            merkle_tree_disabled: self.optional.merkle_tree_disabled,
+            name_resolver,
+            l2_to_l1_log_proofs: L2ToL1LogProofCache::new(),
+            verified_content_hashes: VerifiedContentHashes::new(),
+            contract_abis: ContractAbiCache::new(),
+        }
     }
 
     async fn build_rpc_module(
         self,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
+        start_info: CachedBlockStartInfo,
     ) -> anyhow::Result<RpcModule<()>> {
         let namespaces = self.namespaces.clone();
         let zksync_network_id = self.config.l2_chain_id;
-        let rpc_state = self.build_rpc_state(last_sealed_miniblock).await?;
+        let admin_handles = self.optional.admin_handles.clone();
+        let sequencer_signing_key = self.optional.sequencer_signing_key;
+        let rpc_state = self.build_rpc_state(last_sealed_miniblock, start_info);
 
         // Collect all the methods into a single RPC module.
         let mut rpc = RpcModule::new(());
         if let Some(pub_sub) = pub_sub {
-            rpc.merge(pub_sub.into_rpc())
+            if namespaces.contains(&Namespace::Idexo) {
+                rpc.merge(IdexoPubSubServer::into_rpc(pub_sub.clone()))
+                    .expect("Can't merge idexo pubsub namespace");
+            }
+            rpc.merge(EthPubSubServer::into_rpc(pub_sub))
                 .expect("Can't merge eth pubsub namespace");
         }
 
@@ -331,7 +463,7 @@ impl FullApiParams {
                 .expect("Can't merge zks namespace");
         }
         if namespaces.contains(&Namespace::En) {
-            rpc.merge(EnNamespace::new(rpc_state.clone()).into_rpc())
+            rpc.merge(EnNamespace::new(rpc_state.clone(), sequencer_signing_key).into_rpc())
                 .expect("Can't merge en namespace");
         }
         if namespaces.contains(&Namespace::Debug) {
@@ -339,9 +471,21 @@ impl FullApiParams {
                 .expect("Can't merge debug namespace");
         }
         if namespaces.contains(&Namespace::Snapshots) {
-            rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
+            rpc.merge(SnapshotsNamespace::new(rpc_state.clone()).into_rpc())
                 .expect("Can't merge snapshots namespace");
         }
+        if namespaces.contains(&Namespace::Idexo) {
+            rpc.merge(IdexoNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge idexo namespace");
+        }
+        if namespaces.contains(&Namespace::Operator) {
+            rpc.merge(OperatorNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge operator namespace");
+        }
+        if namespaces.contains(&Namespace::Admin) {
+            rpc.merge(AdminNamespace::new(rpc_state, admin_handles).into_rpc())
+                .expect("Can't merge admin namespace");
+        }
         Ok(rpc)
     }
 
@@ -398,6 +542,8 @@ impl FullApiParams {
         // processes enough requests, information about the latest sealed miniblock will be updated
         // by reporting block difference metrics, so the actual update lag would be much smaller than this value.
         const SEALED_MINIBLOCK_UPDATE_INTERVAL: Duration = Duration::from_millis(25);
+        // Pruning is performed in chunks of several L1 batches, so updates once per second are more than enough.
+        const START_INFO_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
 
         let transport = self.transport;
         let health_check_name = match transport {
@@ -412,10 +558,24 @@ impl FullApiParams {
             stop_receiver.clone(),
         );
 
-        let mut tasks = vec![tokio::spawn(update_task)];
+        let (start_info, start_info_update_task) = CachedBlockStartInfo::new(
+            self.updaters_pool.clone(),
+            START_INFO_UPDATE_INTERVAL,
+            stop_receiver.clone(),
+        )
+        .await
+        .context("failed loading block start info")?;
+
+        let mut tasks = vec![
+            tokio::spawn(update_task),
+            tokio::spawn(start_info_update_task),
+        ];
         if let Some(tx_proxy) = &self.tx_sender.0.proxy {
-            let task = tx_proxy
-                .run_account_nonce_sweeper(self.updaters_pool.clone(), stop_receiver.clone());
+            let task = tx_proxy.run_account_nonce_sweeper(
+                self.updaters_pool.clone(),
+                self.optional.restart_policy.clone(),
+                stop_receiver.clone(),
+            );
             tasks.push(tokio::spawn(task));
         }
         let pub_sub = if matches!(transport, ApiTransport::WebSocket(_))
@@ -425,6 +585,10 @@ impl FullApiParams {
             if let Some(sender) = &self.optional.pub_sub_events_sender {
                 pub_sub.set_events_sender(sender.clone());
             }
+            if let Some(policy) = self.optional.slow_consumer_policy {
+                pub_sub.set_slow_consumer_policy(policy);
+            }
+            pub_sub.set_restart_policy(self.optional.restart_policy.clone());
 
             tasks.extend(pub_sub.spawn_notifiers(
                 self.pool.clone(),
@@ -442,6 +606,7 @@ impl FullApiParams {
             stop_receiver,
             pub_sub,
             last_sealed_miniblock,
+            start_info,
             local_addr_sender,
             health_updater,
         ));
@@ -459,6 +624,7 @@ impl FullApiParams {
         mut stop_receiver: watch::Receiver<bool>,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
+        start_info: CachedBlockStartInfo,
         local_addr_sender: oneshot::Sender<SocketAddr>,
         health_updater: HealthUpdater,
     ) -> anyhow::Result<()> {
@@ -496,12 +662,45 @@ impl FullApiParams {
             .optional
             .response_body_size_limit
             .map_or(u32::MAX, |limit| limit as u32);
-        let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
+        let reloadable_config = self.optional.reloadable_config.clone();
+        let websocket_requests_per_minute_limit = {
+            let static_limit = self.optional.websocket_requests_per_minute_limit;
+            let reloadable_config = reloadable_config.clone();
+            move || {
+                let Some(config) = &reloadable_config else {
+                    return static_limit;
+                };
+                let config = config.borrow();
+                config.api.as_ref().map_or(static_limit, |params| {
+                    params.websocket_requests_per_minute_limit
+                })
+            }
+        };
         let subscriptions_limit = self.optional.subscriptions_limit;
         let vm_barrier = self.vm_barrier.clone();
+        let operator_auth = self
+            .optional
+            .operator_auth_token
+            .as_deref()
+            .filter(|_| {
+                is_http
+                    && (self.namespaces.contains(&Namespace::Operator)
+                        || self.namespaces.contains(&Namespace::Admin))
+            })
+            .map(OperatorAuthLayer::new);
+        let name_resolver = self
+            .name_resolver()
+            .filter(|_| self.config.resolve_names_in_params);
+        let request_shadow = self
+            .config
+            .shadow_api_url
+            .as_deref()
+            .map(|url| RequestShadow::new(url, self.config.shadow_requests_percentage))
+            .transpose()?
+            .map(Arc::new);
 
         let rpc = self
-            .build_rpc_module(pub_sub, last_sealed_miniblock)
+            .build_rpc_module(pub_sub, last_sealed_miniblock, start_info)
             .await?;
 
         // Setup CORS.
@@ -524,7 +723,8 @@ impl FullApiParams {
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .option_layer(operator_auth);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -540,6 +740,14 @@ impl FullApiParams {
         let (local_addr, server_handle) = if is_http {
             // HTTP-specific settings
             let server = server_builder
+                .set_rpc_middleware(
+                    RpcServiceBuilder::new()
+                        .layer_fn(move |a| {
+                            MethodFilterMiddleware::new(a, reloadable_config.clone())
+                        })
+                        .layer_fn(move |a| ShadowingMiddleware::new(a, request_shadow.clone()))
+                        .layer_fn(move |a| NameResolutionMiddleware::new(a, name_resolver.clone())),
+                )
                 .http_only()
                 .build(addr)
                 .await
@@ -548,9 +756,17 @@ impl FullApiParams {
         } else {
             // WS specific settings
             let server = server_builder
-                .set_rpc_middleware(RpcServiceBuilder::new().layer_fn(move |a| {
-                    LimitMiddleware::new(a, websocket_requests_per_minute_limit)
-                }))
+                .set_rpc_middleware(
+                    RpcServiceBuilder::new()
+                        .layer_fn(move |a| {
+                            LimitMiddleware::new(a, websocket_requests_per_minute_limit())
+                        })
+                        .layer_fn(move |a| {
+                            MethodFilterMiddleware::new(a, reloadable_config.clone())
+                        })
+                        .layer_fn(move |a| ShadowingMiddleware::new(a, request_shadow.clone()))
+                        .layer_fn(move |a| NameResolutionMiddleware::new(a, name_resolver.clone())),
+                )
                 .set_id_provider(EthSubscriptionIdProvider)
                 .build(addr)
                 .await
@@ -0,0 +1,219 @@
+//! Resolution of human-readable names using an on-chain name registry.
+//!
+//! Names are resolved using the ENS resolver interface: the registry is called with `addr(bytes32 node)`,
+//! where `node` is the [namehash](https://eips.ethereum.org/EIPS/eip-137#namehash-algorithm) of the name.
+//! The zero address returned by the registry means that the name is not registered.
+
+use serde_json::{value::RawValue, Value};
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    l2::L2Tx, transaction_request::CallRequest, web3::signing::keccak256, Address, H256,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::{
+    execution_sandbox::BlockArgs, tx_sender::TxSender, web3::backend_jsonrpsee::internal_error,
+};
+
+/// Selector of the `addr(bytes32)` method of ENS resolvers.
+pub(super) const ADDR_SELECTOR: [u8; 4] = [0x3b, 0x3b, 0x57, 0xde];
+
+/// Location of an address parameter that may be specified as a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum NameParam {
+    /// Positional parameter with the specified index.
+    Address(usize),
+    /// `to` field of the call request passed as the first parameter.
+    CallRequestTo,
+}
+
+impl NameParam {
+    /// Returns the location of the address parameter for the specified RPC method, or `None` if the method
+    /// doesn't support names.
+    pub(super) fn for_method(method: &str) -> Option<Self> {
+        Some(match method {
+            "eth_getBalance"
+            | "eth_getTransactionCount"
+            | "eth_getCode"
+            | "eth_getStorageAt"
+            | "zks_getAllAccountBalances" => Self::Address(0),
+            "eth_call" | "eth_estimateGas" | "zks_estimateFee" | "debug_traceCall" => {
+                Self::CallRequestTo
+            }
+            _ => return None,
+        })
+    }
+
+    fn get_mut(self, params: &mut [Value]) -> Option<&mut Value> {
+        match self {
+            Self::Address(index) => params.get_mut(index),
+            Self::CallRequestTo => params.first_mut()?.get_mut("to"),
+        }
+    }
+}
+
+/// Checks whether the provided address parameter should be treated as a name. Addresses are hex-encoded
+/// with the `0x` prefix, while names must contain at least one dot (e.g., `alice.idexo`).
+fn is_name(value: &str) -> bool {
+    !value.starts_with("0x") && value.contains('.')
+}
+
+/// Computes the namehash of the specified name. Labels are lowercased; otherwise, names are expected
+/// to be normalized by the caller. Returns `None` if the name contains empty labels.
+pub(super) fn namehash(name: &str) -> Option<H256> {
+    let mut node = [0_u8; 32];
+    if name.is_empty() {
+        return Some(H256(node));
+    }
+    for label in name.rsplit('.') {
+        if label.is_empty() {
+            return None;
+        }
+        let label_hash = keccak256(label.to_lowercase().as_bytes());
+        node = keccak256(&[node, label_hash].concat());
+    }
+    Some(H256(node))
+}
+
+/// Resolves names using the on-chain name registry.
+#[derive(Debug, Clone)]
+pub(crate) struct NameResolver {
+    registry_address: Address,
+    pool: ConnectionPool,
+    tx_sender: TxSender,
+    max_tx_size: usize,
+}
+
+impl NameResolver {
+    pub(super) fn new(
+        registry_address: Address,
+        pool: ConnectionPool,
+        tx_sender: TxSender,
+        max_tx_size: usize,
+    ) -> Self {
+        Self {
+            registry_address,
+            pool,
+            tx_sender,
+            max_tx_size,
+        }
+    }
+
+    /// Resolves the name in the pending block. Returns `None` if the name is not registered.
+    pub(super) async fn resolve(&self, name: &str) -> Result<Option<Address>, Web3Error> {
+        const METHOD_NAME: &str = "resolve_name";
+
+        let node = namehash(name).ok_or_else(|| Web3Error::InvalidName(name.to_owned()))?;
+        let mut connection = self
+            .pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let block_args = BlockArgs::pending(&mut connection)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(connection);
+
+        let calldata = [&ADDR_SELECTOR[..], node.as_bytes()].concat();
+        let request = CallRequest {
+            to: Some(self.registry_address),
+            data: Some(calldata.into()),
+            ..CallRequest::default()
+        };
+        let tx = L2Tx::from_request(request.into(), self.max_tx_size)?;
+        let output = self
+            .tx_sender
+            .eth_call(block_args, tx)
+            .await
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
+
+        // The output is a single ABI-encoded address.
+        if output.len() != 32 {
+            let err = anyhow::anyhow!(
+                "unexpected output from name registry {:?}: 0x{}",
+                self.registry_address,
+                hex::encode(&output)
+            );
+            return Err(internal_error(METHOD_NAME, err));
+        }
+        let address = Address::from_slice(&output[12..]);
+        Ok((!address.is_zero()).then_some(address))
+    }
+
+    /// Resolves a name in the address parameter of an RPC method call, if any. Returns updated params,
+    /// or `None` if params don't need to be changed. Only positional params are supported.
+    pub(super) async fn resolve_params(
+        &self,
+        param: NameParam,
+        raw_params: &RawValue,
+    ) -> Result<Option<Box<RawValue>>, Web3Error> {
+        // If params cannot be parsed, let the RPC method report the error.
+        let Ok(mut params) = serde_json::from_str::<Vec<Value>>(raw_params.get()) else {
+            return Ok(None);
+        };
+        let name = match param.get_mut(&mut params) {
+            Some(Value::String(name)) if is_name(name) => name.clone(),
+            _ => return Ok(None),
+        };
+
+        let address = self
+            .resolve(&name)
+            .await?
+            .ok_or(Web3Error::UnknownName(name))?;
+        if let Some(value) = param.get_mut(&mut params) {
+            *value = serde_json::json!(address);
+        }
+        let params = serde_json::value::to_raw_value(&params)
+            .map_err(|err| internal_error("resolve_name", err))?;
+        Ok(Some(params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computing_namehash() {
+        // Test vectors from EIP-137.
+        assert_eq!(namehash(""), Some(H256::zero()));
+        let expected_hash: H256 =
+            "0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+                .parse()
+                .unwrap();
+        assert_eq!(namehash("eth"), Some(expected_hash));
+        let expected_hash: H256 =
+            "0xde9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+                .parse()
+                .unwrap();
+        assert_eq!(namehash("foo.eth"), Some(expected_hash));
+        assert_eq!(namehash("FOO.eth"), Some(expected_hash));
+
+        assert_eq!(namehash("foo..eth"), None);
+        assert_eq!(namehash(".eth"), None);
+    }
+
+    #[test]
+    fn detecting_names() {
+        assert!(is_name("alice.idexo"));
+        assert!(!is_name("0x0000000000000000000000000000000000000001"));
+        assert!(!is_name("alice"));
+        assert!(!is_name("0x.idexo"));
+    }
+
+    #[test]
+    fn locating_name_params() {
+        let mut params = vec![
+            serde_json::json!({ "to": "alice.idexo", "data": "0x" }),
+            serde_json::json!("latest"),
+        ];
+        let param = NameParam::for_method("eth_call").unwrap();
+        assert_eq!(param, NameParam::CallRequestTo);
+        assert_eq!(param.get_mut(&mut params).unwrap(), "alice.idexo");
+
+        let param = NameParam::for_method("eth_getBalance").unwrap();
+        assert_eq!(param, NameParam::Address(0));
+        assert!(param.get_mut(&mut params).unwrap().is_object());
+        assert_eq!(NameParam::for_method("eth_sendRawTransaction"), None);
+    }
+}
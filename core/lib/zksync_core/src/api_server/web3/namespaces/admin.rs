@@ -0,0 +1,924 @@
+use std::{fmt, sync::Arc};
+
+use once_cell::sync::OnceCell;
+use zksync_dal::{
+    scheduled_txs_dal::{NewScheduledTx, ScheduledTx, ScheduledTxOutcome},
+    StorageProcessor,
+};
+use zksync_health_check::{AppHealth, CheckHealth};
+use zksync_storage::{ColumnFamilySizes, RocksDB};
+use zksync_types::{
+    api::idexo::{
+        AuditAction, FeeDiscount, RocksdbColumnFamilySizes, ScheduledTxParams, ScheduledTxState,
+        ScheduledTxStatus, SnapshotRequest, TxIntakePause, WithdrawalLimit,
+        WithdrawalLimitExemption,
+    },
+    Address, H256, MAX_L2_TX_GAS_LIMIT, U256,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::{
+    api_server::web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState},
+    state_keeper::StateKeeperControl,
+};
+
+/// Actor recorded in the audit log for actions performed via this namespace.
+const AUDIT_ACTOR: &str = "admin_rpc";
+
+/// Handles to node components controlled by the `admin` namespace. Components are registered
+/// once they are initialized; components not running in the same process as the API server are never registered.
+#[derive(Clone, Default)]
+pub struct AdminHandles {
+    state_keeper: Arc<OnceCell<StateKeeperControl>>,
+    health_checks: Arc<OnceCell<Vec<Arc<dyn CheckHealth>>>>,
+}
+
+impl fmt::Debug for AdminHandles {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let health_checks = self
+            .health_checks
+            .get()
+            .map(|checks| checks.iter().map(|check| check.name()).collect::<Vec<_>>());
+        formatter
+            .debug_struct("AdminHandles")
+            .field("state_keeper", &self.state_keeper.get())
+            .field("health_checks", &health_checks)
+            .finish()
+    }
+}
+
+impl AdminHandles {
+    pub fn set_state_keeper(&self, control: StateKeeperControl) {
+        if self.state_keeper.set(control).is_err() {
+            tracing::warn!("State keeper control is already registered for admin API");
+        }
+    }
+
+    pub fn set_health_checks(&self, health_checks: Vec<Arc<dyn CheckHealth>>) {
+        if self.health_checks.set(health_checks).is_err() {
+            tracing::warn!("Health checks are already registered for admin API");
+        }
+    }
+}
+
+/// Methods controlling the operation of the node. Must not be exposed publicly.
+#[derive(Debug, Clone)]
+pub struct AdminNamespace {
+    state: RpcState,
+    handles: AdminHandles,
+}
+
+impl AdminNamespace {
+    pub fn new(state: RpcState, handles: AdminHandles) -> Self {
+        Self { state, handles }
+    }
+
+    /// Accesses the master DB; required by methods modifying the node state.
+    async fn access_master_storage(
+        &self,
+        method_name: &'static str,
+    ) -> Result<StorageProcessor<'_>, Web3Error> {
+        let pool = self
+            .state
+            .tx_sender
+            .0
+            .master_connection_pool
+            .as_ref()
+            .ok_or_else(|| {
+                internal_error(method_name, "master connection pool is not available")
+            })?;
+        pool.access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))
+    }
+
+    fn state_keeper(&self) -> Result<&StateKeeperControl, Web3Error> {
+        self.handles
+            .state_keeper
+            .get()
+            .ok_or(Web3Error::ComponentUnavailable("state_keeper"))
+    }
+
+    /// Appends an entry to the audit log. Should be called in the same transaction as the recorded action.
+    async fn record_audit_entry(
+        storage: &mut StorageProcessor<'_>,
+        method_name: &'static str,
+        action: AuditAction,
+        details: serde_json::Value,
+    ) -> Result<(), Web3Error> {
+        storage
+            .audit_log_dal()
+            .insert_entry(action, AUDIT_ACTOR, &details)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        Ok(())
+    }
+
+    /// Pauses accepting L2 transactions by all API servers (other API servers pick up the pause within a second).
+    /// Returns the active pause; if the intake is already paused, the existing pause is returned
+    /// and `reason` is ignored.
+    pub async fn pause_tx_intake_impl(&self, reason: String) -> Result<TxIntakePause, Web3Error> {
+        let method_name = "pause_tx_intake";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let new_pause = transaction
+            .tx_intake_dal()
+            .pause_intake(&reason)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let pause = if let Some(pause) = new_pause {
+            tracing::info!("Operator paused transaction intake: {reason}");
+            let details =
+                serde_json::to_value(&pause).map_err(|err| internal_error(method_name, err))?;
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::TxIntakePause,
+                details,
+            )
+            .await?;
+            pause
+        } else {
+            transaction
+                .tx_intake_dal()
+                .get_active_pause()
+                .await
+                .map_err(|err| internal_error(method_name, err))?
+                .ok_or_else(|| {
+                    internal_error(method_name, "active pause was concurrently resumed")
+                })?
+        };
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        self.state.tx_sender.reset_intake_pause_cache();
+        method_latency.observe();
+        Ok(pause)
+    }
+
+    /// Resumes accepting L2 transactions. Returns the resumed pause, or `None` if the intake wasn't paused.
+    pub async fn resume_tx_intake_impl(&self) -> Result<Option<TxIntakePause>, Web3Error> {
+        let method_name = "resume_tx_intake";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let pause = transaction
+            .tx_intake_dal()
+            .resume_intake()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if let Some(pause) = &pause {
+            tracing::info!(
+                "Operator resumed transaction intake paused at {}",
+                pause.paused_at
+            );
+            let details =
+                serde_json::to_value(pause).map_err(|err| internal_error(method_name, err))?;
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::TxIntakeResume,
+                details,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        self.state.tx_sender.reset_intake_pause_cache();
+        method_latency.observe();
+        Ok(pause)
+    }
+
+    pub async fn get_tx_intake_pause_impl(&self) -> Result<Option<TxIntakePause>, Web3Error> {
+        let method_name = "get_tx_intake_pause";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let pause = storage
+            .tx_intake_dal()
+            .get_active_pause()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(pause)
+    }
+
+    /// Drops a pending L2 transaction. Returns `false` if there is no such pending transaction. Transactions
+    /// fetched into the mempool can only be dropped if the state keeper runs in the same process as the API server;
+    /// such transactions are removed from the mempool after the removal from Postgres is committed.
+    pub async fn drop_transaction_impl(&self, hash: H256) -> Result<bool, Web3Error> {
+        let method_name = "drop_transaction";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let pending_tx = transaction
+            .transactions_dal()
+            .get_pending_l2_tx(hash)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let Some(pending_tx) = pending_tx else {
+            method_latency.observe();
+            return Ok(false);
+        };
+
+        let state_keeper = if pending_tx.in_mempool {
+            Some(self.state_keeper()?)
+        } else {
+            None
+        };
+        let removed = transaction
+            .transactions_dal()
+            .remove_pending_l2_tx(hash, pending_tx.in_mempool)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if removed {
+            tracing::info!("Operator dropped pending transaction {hash:?}");
+            transaction
+                .withdrawal_limits_dal()
+                .release_usage(&[hash])
+                .await
+                .map_err(|err| internal_error(method_name, err))?;
+            let details = serde_json::json!({
+                "hash": hash,
+                "initiatorAddress": pending_tx.initiator_address,
+                "nonce": pending_tx.nonce,
+            });
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::TransactionDrop,
+                details,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+
+        // The mempool is only modified after the DB transaction is committed, so that a failed commit
+        // doesn't leave a transaction in Postgres that is not in the mempool.
+        if let (true, Some(state_keeper)) = (removed, state_keeper) {
+            let removed_from_mempool = state_keeper.remove_mempool_transaction(
+                pending_tx.initiator_address,
+                pending_tx.nonce,
+                hash,
+            );
+            if !removed_from_mempool {
+                tracing::warn!(
+                    "Dropped transaction {hash:?} is not in the mempool; it may be executed by the state keeper \
+                     at the moment"
+                );
+            }
+        }
+        method_latency.observe();
+        Ok(removed)
+    }
+
+    /// Requests creating a snapshot; the request is processed by the snapshot creator running with a creation
+    /// interval. Returns the pending request; if a snapshot is already requested, the existing request is returned.
+    pub async fn request_snapshot_impl(&self) -> Result<SnapshotRequest, Web3Error> {
+        let method_name = "request_snapshot";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let new_request = transaction
+            .snapshots_dal()
+            .request_snapshot()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let request = if let Some(request) = new_request {
+            tracing::info!("Operator requested creating a snapshot");
+            let details =
+                serde_json::to_value(&request).map_err(|err| internal_error(method_name, err))?;
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::SnapshotRequest,
+                details,
+            )
+            .await?;
+            request
+        } else {
+            transaction
+                .snapshots_dal()
+                .get_pending_snapshot_request()
+                .await
+                .map_err(|err| internal_error(method_name, err))?
+                .ok_or_else(|| {
+                    internal_error(method_name, "pending request was concurrently processed")
+                })?
+        };
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(request)
+    }
+
+    pub async fn get_snapshot_request_impl(&self) -> Result<Option<SnapshotRequest>, Web3Error> {
+        let method_name = "get_snapshot_request";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let request = storage
+            .snapshots_dal()
+            .get_pending_snapshot_request()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(request)
+    }
+
+    /// Requests the state keeper to seal the current L1 batch as soon as it contains at least one transaction.
+    pub async fn seal_l1_batch_impl(&self) -> Result<(), Web3Error> {
+        let method_name = "seal_l1_batch";
+        let method_latency = API_METRICS.start_call(method_name);
+        let state_keeper = self.state_keeper()?;
+        let mut storage = self.access_master_storage(method_name).await?;
+        Self::record_audit_entry(
+            &mut storage,
+            method_name,
+            AuditAction::L1BatchSealRequest,
+            serde_json::Value::Null,
+        )
+        .await?;
+        state_keeper.request_l1_batch_seal();
+        tracing::info!("Operator requested sealing the current L1 batch");
+        method_latency.observe();
+        Ok(())
+    }
+
+    /// Returns the aggregated health of the node components, in the same format as the healthcheck server.
+    pub async fn get_component_statuses_impl(&self) -> Result<serde_json::Value, Web3Error> {
+        let method_name = "get_component_statuses";
+        let method_latency = API_METRICS.start_call(method_name);
+        let health_checks = self
+            .handles
+            .health_checks
+            .get()
+            .ok_or(Web3Error::ComponentUnavailable("healthcheck"))?;
+        let health = AppHealth::new(health_checks).await;
+        let health =
+            serde_json::to_value(health).map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(health)
+    }
+
+    fn convert_cf_sizes(sizes: ColumnFamilySizes) -> RocksdbColumnFamilySizes {
+        RocksdbColumnFamilySizes {
+            db_name: sizes.db_name.to_owned(),
+            cf_name: sizes.cf_name.to_owned(),
+            live_data_size: sizes.live_data_size,
+            total_sst_size: sizes.total_sst_size,
+            mem_table_size: sizes.mem_table_size,
+        }
+    }
+
+    /// Returns sizes of column families in all RocksDB instances opened by the node process
+    /// (e.g., the Merkle tree and the state keeper cache).
+    pub fn get_rocksdb_sizes_impl(&self) -> Result<Vec<RocksdbColumnFamilySizes>, Web3Error> {
+        let method_name = "get_rocksdb_sizes";
+        let method_latency = API_METRICS.start_call(method_name);
+        let sizes = RocksDB::column_family_sizes()
+            .into_iter()
+            .map(Self::convert_cf_sizes)
+            .collect();
+        method_latency.observe();
+        Ok(sizes)
+    }
+
+    /// Manually compacts all column families in the specified RocksDB instance and returns their sizes
+    /// after compaction. Returns `None` if the instance is not opened by the node process.
+    pub async fn compact_rocksdb_impl(
+        &self,
+        db_name: String,
+    ) -> Result<Option<Vec<RocksdbColumnFamilySizes>>, Web3Error> {
+        let method_name = "compact_rocksdb";
+        let method_latency = API_METRICS.start_call(method_name);
+        let size_before: u64 = RocksDB::column_family_sizes()
+            .iter()
+            .filter(|sizes| sizes.db_name == db_name)
+            .map(|sizes| sizes.total_sst_size)
+            .sum();
+        tracing::info!("Operator requested compaction of RocksDB `{db_name}`");
+        let compacted = tokio::task::spawn_blocking({
+            let db_name = db_name.clone();
+            move || RocksDB::compact_instance(&db_name)
+        })
+        .await
+        .map_err(|err| internal_error(method_name, err))?;
+        if !compacted {
+            method_latency.observe();
+            return Ok(None);
+        }
+
+        let sizes: Vec<_> = RocksDB::column_family_sizes()
+            .into_iter()
+            .filter(|sizes| sizes.db_name == db_name)
+            .map(Self::convert_cf_sizes)
+            .collect();
+        let size_after = sizes.iter().map(|sizes| sizes.total_sst_size).sum::<u64>();
+        tracing::info!(
+            "Compacted RocksDB `{db_name}`; SST files size changed from {size_before}B to {size_after}B"
+        );
+        let details = serde_json::json!({
+            "dbName": db_name,
+            "totalSstSizeBefore": size_before,
+            "totalSstSizeAfter": size_after,
+        });
+        let mut storage = self.access_master_storage(method_name).await?;
+        Self::record_audit_entry(
+            &mut storage,
+            method_name,
+            AuditAction::RocksdbCompaction,
+            details,
+        )
+        .await?;
+        method_latency.observe();
+        Ok(Some(sizes))
+    }
+
+    /// Grants a fee discount to the account, or updates its existing discount. The discount applies to transactions
+    /// submitted after the call. Transactions are still charged the full fee; the discounted part is rebated.
+    pub async fn set_fee_discount_impl(
+        &self,
+        address: Address,
+        discount_bps: u32,
+        label: Option<String>,
+    ) -> Result<FeeDiscount, Web3Error> {
+        let method_name = "set_fee_discount";
+        let method_latency = API_METRICS.start_call(method_name);
+        if discount_bps == 0 || discount_bps > FeeDiscount::MAX_DISCOUNT_BPS {
+            return Err(Web3Error::InvalidFeeParams(format!(
+                "discount must be in 1..={} basis points, got {discount_bps}",
+                FeeDiscount::MAX_DISCOUNT_BPS
+            )));
+        }
+
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let discount = transaction
+            .fee_discounts_dal()
+            .set_discount(address, discount_bps, label.as_deref())
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let details =
+            serde_json::to_value(&discount).map_err(|err| internal_error(method_name, err))?;
+        Self::record_audit_entry(
+            &mut transaction,
+            method_name,
+            AuditAction::FeeDiscountUpdate,
+            details,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        self.state.tx_sender.reset_fee_discounts_cache();
+        tracing::info!("Operator set fee discount for {address:?} to {discount_bps} basis points");
+        method_latency.observe();
+        Ok(discount)
+    }
+
+    /// Removes the fee discount of the account. Returns the removed discount, or `None` if the account
+    /// had no discount. Rebates for already submitted transactions are still paid out.
+    pub async fn remove_fee_discount_impl(
+        &self,
+        address: Address,
+    ) -> Result<Option<FeeDiscount>, Web3Error> {
+        let method_name = "remove_fee_discount";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let discount = transaction
+            .fee_discounts_dal()
+            .remove_discount(address)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if let Some(discount) = &discount {
+            tracing::info!("Operator removed fee discount for {address:?}");
+            let details =
+                serde_json::to_value(discount).map_err(|err| internal_error(method_name, err))?;
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::FeeDiscountRemoval,
+                details,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        self.state.tx_sender.reset_fee_discounts_cache();
+        method_latency.observe();
+        Ok(discount)
+    }
+
+    pub async fn get_fee_discounts_impl(&self) -> Result<Vec<FeeDiscount>, Web3Error> {
+        let method_name = "get_fee_discounts";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let discounts = storage
+            .fee_discounts_dal()
+            .get_discounts()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(discounts)
+    }
+
+    /// Sets a limit on withdrawals of the token initiated on the chain, or updates the existing limit.
+    /// The limit applies to transactions submitted after the call.
+    pub async fn set_withdrawal_limit_impl(
+        &self,
+        l1_token: Address,
+        total_limit: Option<U256>,
+        account_limit: Option<U256>,
+        window_sec: u64,
+    ) -> Result<WithdrawalLimit, Web3Error> {
+        let method_name = "set_withdrawal_limit";
+        let method_latency = API_METRICS.start_call(method_name);
+        if total_limit.is_none() && account_limit.is_none() {
+            return Err(Web3Error::InvalidWithdrawalLimit(
+                "at least one of total and account limits must be specified".to_owned(),
+            ));
+        }
+        if window_sec == 0 || window_sec > i64::MAX as u64 {
+            return Err(Web3Error::InvalidWithdrawalLimit(format!(
+                "window must be in 1..={} seconds, got {window_sec}",
+                i64::MAX
+            )));
+        }
+
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let limit = transaction
+            .withdrawal_limits_dal()
+            .set_limit(l1_token, total_limit, account_limit, window_sec)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let details =
+            serde_json::to_value(&limit).map_err(|err| internal_error(method_name, err))?;
+        Self::record_audit_entry(
+            &mut transaction,
+            method_name,
+            AuditAction::WithdrawalLimitUpdate,
+            details,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        tracing::info!(
+            "Operator set withdrawal limit for {l1_token:?}: total {total_limit:?}, per account {account_limit:?} \
+             within {window_sec}s"
+        );
+        method_latency.observe();
+        Ok(limit)
+    }
+
+    /// Removes the withdrawal limit of the token. Returns the removed limit, or `None` if the token had no limit.
+    pub async fn remove_withdrawal_limit_impl(
+        &self,
+        l1_token: Address,
+    ) -> Result<Option<WithdrawalLimit>, Web3Error> {
+        let method_name = "remove_withdrawal_limit";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let limit = transaction
+            .withdrawal_limits_dal()
+            .remove_limit(l1_token)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if let Some(limit) = &limit {
+            tracing::info!("Operator removed withdrawal limit for {l1_token:?}");
+            let details =
+                serde_json::to_value(limit).map_err(|err| internal_error(method_name, err))?;
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::WithdrawalLimitRemoval,
+                details,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(limit)
+    }
+
+    pub async fn get_withdrawal_limits_impl(&self) -> Result<Vec<WithdrawalLimit>, Web3Error> {
+        let method_name = "get_withdrawal_limits";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let limits = storage
+            .withdrawal_limits_dal()
+            .get_limits()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(limits)
+    }
+
+    /// Exempts the account from withdrawal limits, e.g. for bridge rebalancing by the operator.
+    pub async fn add_withdrawal_limit_exemption_impl(
+        &self,
+        address: Address,
+        label: Option<String>,
+    ) -> Result<WithdrawalLimitExemption, Web3Error> {
+        let method_name = "add_withdrawal_limit_exemption";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let exemption = transaction
+            .withdrawal_limits_dal()
+            .add_exemption(address, label.as_deref())
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let details =
+            serde_json::to_value(&exemption).map_err(|err| internal_error(method_name, err))?;
+        Self::record_audit_entry(
+            &mut transaction,
+            method_name,
+            AuditAction::WithdrawalLimitExemption,
+            details,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        tracing::info!("Operator exempted {address:?} from withdrawal limits");
+        method_latency.observe();
+        Ok(exemption)
+    }
+
+    /// Revokes the exemption of the account from withdrawal limits. Returns the removed exemption, or `None`
+    /// if the account was not exempt.
+    pub async fn remove_withdrawal_limit_exemption_impl(
+        &self,
+        address: Address,
+    ) -> Result<Option<WithdrawalLimitExemption>, Web3Error> {
+        let method_name = "remove_withdrawal_limit_exemption";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let exemption = transaction
+            .withdrawal_limits_dal()
+            .remove_exemption(address)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if let Some(exemption) = &exemption {
+            tracing::info!("Operator revoked exemption of {address:?} from withdrawal limits");
+            let details =
+                serde_json::to_value(exemption).map_err(|err| internal_error(method_name, err))?;
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::WithdrawalLimitExemptionRemoval,
+                details,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(exemption)
+    }
+
+    pub async fn get_withdrawal_limit_exemptions_impl(
+        &self,
+    ) -> Result<Vec<WithdrawalLimitExemption>, Web3Error> {
+        let method_name = "get_withdrawal_limit_exemptions";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let exemptions = storage
+            .withdrawal_limits_dal()
+            .get_exemptions()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(exemptions)
+    }
+
+    /// Registers a recurring L2 transaction, or updates the parameters of the existing schedule with the same name.
+    /// The first transaction is sent by the state keeper as soon as possible.
+    pub async fn register_scheduled_tx_impl(
+        &self,
+        params: ScheduledTxParams,
+    ) -> Result<(), Web3Error> {
+        let method_name = "register_scheduled_tx";
+        let method_latency = API_METRICS.start_call(method_name);
+        if params.name.is_empty() {
+            return Err(Web3Error::InvalidScheduledTx(
+                "name must not be empty".to_owned(),
+            ));
+        }
+        if params.interval_sec == 0 || params.interval_sec > i64::MAX as u64 {
+            return Err(Web3Error::InvalidScheduledTx(format!(
+                "interval must be in 1..={} seconds, got {}",
+                i64::MAX,
+                params.interval_sec
+            )));
+        }
+        if params.gas_limit == 0 || params.gas_limit > MAX_L2_TX_GAS_LIMIT {
+            return Err(Web3Error::InvalidScheduledTx(format!(
+                "gas limit must be in 1..={MAX_L2_TX_GAS_LIMIT}, got {}",
+                params.gas_limit
+            )));
+        }
+
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let scheduled_tx = NewScheduledTx {
+            name: params.name.clone(),
+            contract_address: params.contract_address,
+            calldata: params.calldata.0.clone(),
+            value: params.value,
+            gas_limit: params.gas_limit,
+            interval_sec: params.interval_sec,
+        };
+        transaction
+            .scheduled_txs_dal()
+            .register_scheduled_tx(&scheduled_tx)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let details =
+            serde_json::to_value(&params).map_err(|err| internal_error(method_name, err))?;
+        Self::record_audit_entry(
+            &mut transaction,
+            method_name,
+            AuditAction::ScheduledTxRegistration,
+            details,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        tracing::info!(
+            "Operator registered scheduled transaction `{}` to {:?} every {}s",
+            params.name,
+            params.contract_address,
+            params.interval_sec
+        );
+        method_latency.observe();
+        Ok(())
+    }
+
+    /// Disables the recurring L2 transaction. Returns `false` if there is no schedule with the specified name.
+    /// A transaction already sent for the schedule is not affected.
+    pub async fn disable_scheduled_tx_impl(&self, name: String) -> Result<bool, Web3Error> {
+        let method_name = "disable_scheduled_tx";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let disabled = transaction
+            .scheduled_txs_dal()
+            .disable_scheduled_tx(&name)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if disabled {
+            tracing::info!("Operator disabled scheduled transaction `{name}`");
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::ScheduledTxDisabling,
+                serde_json::json!({ "name": name }),
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(disabled)
+    }
+
+    pub async fn get_scheduled_txs_impl(&self) -> Result<Vec<ScheduledTxStatus>, Web3Error> {
+        let method_name = "get_scheduled_txs";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let scheduled_txs = storage
+            .scheduled_txs_dal()
+            .get_enabled_scheduled_txs()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(scheduled_txs.into_iter().map(scheduled_tx_status).collect())
+    }
+}
+
+fn scheduled_tx_status(scheduled_tx: ScheduledTx) -> ScheduledTxStatus {
+    let (last_tx_state, last_tx_hash, last_tx_miniblock, last_tx_error) = match scheduled_tx
+        .last_outcome
+    {
+        ScheduledTxOutcome::Idle => (ScheduledTxState::Idle, None, None, None),
+        ScheduledTxOutcome::Pending(hash) => (ScheduledTxState::Pending, Some(hash), None, None),
+        ScheduledTxOutcome::Succeeded(hash, number) => {
+            (ScheduledTxState::Succeeded, Some(hash), Some(number), None)
+        }
+        ScheduledTxOutcome::Failed(hash, error) => {
+            (ScheduledTxState::Failed, Some(hash), None, Some(error))
+        }
+        ScheduledTxOutcome::Dropped(hash) => (ScheduledTxState::Dropped, Some(hash), None, None),
+    };
+    let tx = scheduled_tx.tx;
+    ScheduledTxStatus {
+        params: ScheduledTxParams {
+            name: tx.name,
+            contract_address: tx.contract_address,
+            calldata: tx.calldata.into(),
+            value: tx.value,
+            gas_limit: tx.gas_limit,
+            interval_sec: tx.interval_sec,
+        },
+        is_due: scheduled_tx.is_due,
+        consecutive_failures: scheduled_tx.consecutive_failures,
+        last_tx_state,
+        last_tx_hash,
+        last_tx_miniblock,
+        last_tx_error,
+    }
+}
@@ -224,6 +224,9 @@ impl DebugNamespace {
             fee_input: self.batch_fee_input,
             base_system_contracts: self.api_contracts.eth_call.clone(),
             caches: self.state.tx_sender.storage_caches().clone(),
+            state_cache: self.state.tx_sender.state_cache(),
+            warm_pool: self.state.tx_sender.warm_pool(),
+            archive_tree: self.state.tx_sender.archive_tree(),
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
             chain_id: sender_config.chain_id,
         }
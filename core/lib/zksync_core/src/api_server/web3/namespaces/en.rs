@@ -1,18 +1,75 @@
-use zksync_types::{api::en::SyncBlock, tokens::TokenInfo, MiniblockNumber};
+use std::fmt;
+
+use zksync_types::{
+    api::en::{SyncBlock, SyncBlockOutputs, SyncL1BatchRootHash},
+    tokens::TokenInfo,
+    L1BatchNumber, MiniblockNumber, PackedEthSignature, H256,
+};
 use zksync_web3_decl::error::Web3Error;
 
 use crate::api_server::web3::{backend_jsonrpsee::internal_error, state::RpcState};
 
 /// Namespace for External Node unique methods.
 /// Main use case for it is the EN synchronization.
-#[derive(Debug)]
 pub struct EnNamespace {
     state: RpcState,
+    sequencer_signing_key: Option<H256>,
+}
+
+impl fmt::Debug for EnNamespace {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("EnNamespace")
+            .field("state", &self.state)
+            .field("signs_blocks", &self.sequencer_signing_key.is_some())
+            .finish()
+    }
 }
 
 impl EnNamespace {
-    pub fn new(state: RpcState) -> Self {
-        Self { state }
+    pub fn new(state: RpcState, sequencer_signing_key: Option<H256>) -> Self {
+        Self {
+            state,
+            sequencer_signing_key,
+        }
+    }
+
+    /// Signs `block` with the sequencer key, so that external nodes can verify that the block originates
+    /// from the main node even if it's relayed by an untrusted proxy. The block header is always signed;
+    /// the full block is only signed if transactions were requested.
+    fn sign_block(&self, block: &mut SyncBlock) -> Result<(), Web3Error> {
+        const METHOD_NAME: &str = "en_syncL2Block";
+
+        let Some(signing_key) = &self.sequencer_signing_key else {
+            return Ok(());
+        };
+        let chain_id = self.state.api_config.l2_chain_id;
+        if let Some(digest) = block.header_signed_digest(chain_id) {
+            let signature = PackedEthSignature::sign_raw(signing_key, &digest)
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            block.sequencer_header_signature = Some(signature);
+        }
+        if let Some(digest) = block.signed_digest(chain_id) {
+            let signature = PackedEthSignature::sign_raw(signing_key, &digest)
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            block.sequencer_signature = Some(signature);
+        }
+        Ok(())
+    }
+
+    /// Signs block `outputs` with the sequencer key. Read replicas persist outputs without re-executing
+    /// transactions, so they must be able to authenticate them in the same way as blocks.
+    fn sign_block_outputs(&self, outputs: &mut SyncBlockOutputs) -> Result<(), Web3Error> {
+        const METHOD_NAME: &str = "en_syncL2BlockOutputs";
+
+        let Some(signing_key) = &self.sequencer_signing_key else {
+            return Ok(());
+        };
+        let digest = outputs.signed_digest(self.state.api_config.l2_chain_id);
+        let signature = PackedEthSignature::sign_raw(signing_key, &digest)
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        outputs.sequencer_signature = Some(signature);
+        Ok(())
     }
 
     #[tracing::instrument(skip(self))]
@@ -29,11 +86,77 @@ impl EnNamespace {
             .access_storage_tagged("api")
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
-        storage
+        let mut block = storage
             .sync_dal()
             .sync_block(block_number, include_transactions)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if let Some(block) = &mut block {
+            self.sign_block(block)?;
+        }
+        Ok(block)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn sync_l2_block_outputs_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<SyncBlockOutputs>, Web3Error> {
+        const METHOD_NAME: &str = "en_syncL2BlockOutputs";
+
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut outputs = storage
+            .sync_dal()
+            .sync_block_outputs(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if let Some(outputs) = &mut outputs {
+            self.sign_block_outputs(outputs)?;
+        }
+        Ok(outputs)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn sync_l1_batch_root_hash_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<SyncL1BatchRootHash>, Web3Error> {
+        const METHOD_NAME: &str = "en_syncL1BatchRootHash";
+
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(root_hash) = root_hash else {
+            return Ok(None);
+        };
+
+        let mut response = SyncL1BatchRootHash {
+            number: l1_batch_number,
+            root_hash,
+            sequencer_signature: None,
+        };
+        // Root hashes are used by external nodes as inputs for executing L1 batches, so they are authenticated
+        // in the same way as blocks.
+        if let Some(signing_key) = &self.sequencer_signing_key {
+            let digest = response.signed_digest(self.state.api_config.l2_chain_id);
+            let signature = PackedEthSignature::sign_raw(signing_key, &digest)
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            response.sequencer_signature = Some(signature);
+        }
+        Ok(Some(response))
     }
 
     #[tracing::instrument(skip(self))]
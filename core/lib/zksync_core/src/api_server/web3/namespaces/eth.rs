@@ -79,7 +79,7 @@ impl EthNamespace {
             .map_err(|err| internal_error(METHOD_NAME, err))?;
         let block_args = self
             .state
-            .resolve_block_args(&mut connection, block_id, METHOD_NAME)
+            .resolve_call_block_args(&mut connection, block_id, METHOD_NAME)
             .await?;
 
         drop(connection);
@@ -271,13 +271,14 @@ impl EthNamespace {
         };
         let method_latency = API_METRICS.start_block_call(method_name, block_id);
 
-        self.state.start_info.ensure_not_pruned(block_id)?;
-        let block = self
+        self.state.start_info().ensure_not_pruned(block_id)?;
+        let mut storage = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .map_err(|err| internal_error(method_name, err))?
+            .map_err(|err| internal_error(method_name, err))?;
+        let block = storage
             .blocks_web3_dal()
             .get_block_by_web3_block_id(
                 block_id,
@@ -286,6 +287,13 @@ impl EthNamespace {
             )
             .await
             .map_err(|err| internal_error(method_name, err));
+        if let Ok(Some(block)) = &block {
+            let block_number = MiniblockNumber(block.number.as_u32());
+            self.state
+                .verify_content_hash(&mut storage, block_number, method_name)
+                .await?;
+        }
+        drop(storage);
 
         if let Ok(Some(block)) = &block {
             let block_number = MiniblockNumber(block.number.as_u32());
@@ -305,7 +313,7 @@ impl EthNamespace {
 
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
 
-        self.state.start_info.ensure_not_pruned(block_id)?;
+        self.state.start_info().ensure_not_pruned(block_id)?;
         let tx_count = self
             .state
             .connection_pool
@@ -334,18 +342,26 @@ impl EthNamespace {
 
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
 
-        self.state.start_info.ensure_not_pruned(block_id)?;
+        self.state.start_info().ensure_not_pruned(block_id)?;
 
-        let block = self
+        let mut storage = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let block = storage
             .blocks_web3_dal()
             .get_block_by_web3_block_id(block_id, false, self.state.api_config.l2_chain_id)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if let Some(block) = &block {
+            let block_number = MiniblockNumber(block.number.as_u32());
+            self.state
+                .verify_content_hash(&mut storage, block_number, METHOD_NAME)
+                .await?;
+        }
+        drop(storage);
 
         let transactions: &[TransactionVariant] =
             block.as_ref().map_or(&[], |block| &block.transactions);
@@ -562,20 +578,28 @@ impl EthNamespace {
         const METHOD_NAME: &str = "get_transaction_receipt";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let receipts = self
+        let mut storage = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .unwrap()
+            .unwrap();
+        let receipts = storage
             .transactions_web3_dal()
             .get_transaction_receipts(&[hash])
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let receipt = receipts.into_iter().next();
+        if let Some(receipt) = &receipt {
+            let block_number = MiniblockNumber(receipt.block_number.as_u32());
+            self.state
+                .verify_content_hash(&mut storage, block_number, METHOD_NAME)
+                .await?;
+        }
 
         method_latency.observe();
 
-        Ok(receipts.into_iter().next())
+        Ok(receipt)
     }
 
     #[tracing::instrument(skip(self))]
@@ -0,0 +1,774 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+use chrono::Utc;
+use zksync_dal::{transactions_web3_dal::UnexecutedL2Tx, StorageProcessor};
+use zksync_types::{
+    api::{
+        idexo::{
+            BatchEconomics, BatchStateDiffs, DaInclusionProof, DecodedLog, DepositStage,
+            DepositStatus, ForcedInclusionStatus, L1BatchProofStatus, L2ToL1LogProofRequest,
+            MulticallRead, MulticallReadResult, PendingPriorityOp, PriorityQueueStatus,
+            RegisteredToken, RelayedMessageStatus, TransactionDiagnosis, TransactionDiagnostics,
+            TransactionDiagnosticsTarget, TransactionTimeline,
+        },
+        BlockId, BlockNumber, L2ToL1LogProof, TransactionId,
+    },
+    l2::L2Tx,
+    AccountTreeId, Address, L1BatchNumber, Nonce, StorageKey, H256, U256,
+};
+use zksync_utils::u256_to_h256;
+use zksync_web3_decl::{error::Web3Error, types::Filter};
+
+use crate::api_server::web3::{
+    backend_jsonrpsee::internal_error,
+    metrics::{API_METRICS, L2_TO_L1_LOG_PROOF_METRICS},
+    namespaces::EthNamespace,
+    state::RpcState,
+    tx_diagnostics::{diagnose_by_timeline, diagnose_unexecuted_tx, AccountState},
+};
+
+/// Number of recent L1 batches used to estimate the number of priority operations included into an L1 batch.
+const RECENT_L1_BATCH_COUNT: u32 = 100;
+
+/// Operator-facing methods specific to the idexo L3.
+#[derive(Debug, Clone)]
+pub struct IdexoNamespace {
+    state: RpcState,
+}
+
+impl IdexoNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    pub async fn get_batch_economics_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BatchEconomics>, Web3Error> {
+        let method_name = "get_batch_economics";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let response = storage_processor
+            .settlement_costs_dal()
+            .get_batch_economics(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(method_name, err));
+        method_latency.observe();
+        response
+    }
+
+    pub async fn get_da_inclusion_proof_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<DaInclusionProof>, Web3Error> {
+        let method_name = "get_da_inclusion_proof";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let response = storage_processor
+            .data_availability_dal()
+            .get_da_inclusion_proof(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(method_name, err));
+        method_latency.observe();
+        response
+    }
+
+    /// Returns tokens registered by the operator together with their curated metadata.
+    pub async fn get_token_list_impl(&self) -> Result<Vec<RegisteredToken>, Web3Error> {
+        let method_name = "get_token_list";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let response = storage_processor
+            .token_registry_dal()
+            .get_registered_tokens()
+            .await
+            .map_err(|err| internal_error(method_name, err));
+        method_latency.observe();
+        response
+    }
+
+    /// Returns statuses of deposits requested by the specified settlement layer transaction. Deposits are only
+    /// tracked if the bridge watcher component is running.
+    pub async fn get_deposit_status_impl(
+        &self,
+        settlement_tx_hash: H256,
+    ) -> Result<Vec<DepositStatus>, Web3Error> {
+        let method_name = "get_deposit_status";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let deposits = storage_processor
+            .bridge_deposits_dal()
+            .get_deposits(settlement_tx_hash)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+
+        let mut statuses = Vec::with_capacity(deposits.len());
+        for deposit in deposits {
+            let details = storage_processor
+                .transactions_web3_dal()
+                .get_transaction_details(deposit.tx_hash)
+                .await
+                .map_err(|err| internal_error(method_name, err))?;
+            statuses.push(DepositStatus {
+                deposit,
+                stage: DepositStage::new(details.as_ref()),
+                details,
+            });
+        }
+        method_latency.observe();
+        Ok(statuses)
+    }
+
+    pub async fn get_relayed_messages_impl(
+        &self,
+        source_tx_hash: H256,
+    ) -> Result<Vec<RelayedMessageStatus>, Web3Error> {
+        let method_name = "get_relayed_messages";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let messages = storage_processor
+            .relayed_messages_dal()
+            .get_messages_by_source_tx(source_tx_hash)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(messages)
+    }
+
+    pub async fn get_pending_priority_ops_impl(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<PriorityQueueStatus, Web3Error> {
+        let method_name = "get_pending_priority_ops";
+        let method_latency = API_METRICS.start_call(method_name);
+        let max_limit = self.state.api_config.req_entities_limit;
+        let limit = limit.map_or(max_limit, |limit| limit.min(max_limit));
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+
+        let sealed_l1_batch = storage_processor
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let pending_l1_batch = sealed_l1_batch.map_or(L1BatchNumber(0), |number| number + 1);
+        // L1 batches include as many priority operations as possible, so the busiest recent L1 batch
+        // is a reasonable estimate of the throughput.
+        let ops_per_l1_batch = storage_processor
+            .blocks_web3_dal()
+            .get_max_l1_tx_count_in_recent_l1_batches(RECENT_L1_BATCH_COUNT)
+            .await
+            .map_err(|err| internal_error(method_name, err))?
+            .max(1);
+        let mut transactions_dal = storage_processor.transactions_web3_dal();
+        let pending_count = transactions_dal
+            .get_pending_priority_ops_count()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let ops = transactions_dal
+            .get_pending_priority_ops(limit)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+
+        // Operations are returned starting from the head of the queue, so their position in the list
+        // is their position in the queue.
+        let operations = ops
+            .into_iter()
+            .enumerate()
+            .map(|(position, op)| {
+                let estimated_l1_batch = if op.miniblock_number.is_some() {
+                    pending_l1_batch
+                } else {
+                    pending_l1_batch + (position as u64 / ops_per_l1_batch) as u32
+                };
+                PendingPriorityOp {
+                    op,
+                    estimated_l1_batch,
+                }
+            })
+            .collect();
+        method_latency.observe();
+        Ok(PriorityQueueStatus {
+            pending_l1_batch,
+            pending_count,
+            ops_per_l1_batch,
+            operations,
+        })
+    }
+
+    /// Returns the result of the local verification of the L1 batch proof submitted to L1. Only available
+    /// on external nodes with proof verification enabled.
+    pub async fn get_l1_batch_proof_status_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchProofStatus>, Web3Error> {
+        let method_name = "get_l1_batch_proof_status";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let response = storage_processor
+            .proof_verifications_dal()
+            .get_verification(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(method_name, err));
+        method_latency.observe();
+        response
+    }
+
+    /// Returns the status of forced inclusion of priority operations. Only available on the main node,
+    /// which enforces the inclusion deadline. Operations are considered executed once their L1 batch
+    /// is executed on the settlement layer.
+    pub async fn get_forced_inclusion_status_impl(
+        &self,
+    ) -> Result<Option<ForcedInclusionStatus>, Web3Error> {
+        let method_name = "get_forced_inclusion_status";
+        let Some(deadline) = self.state.api_config.forced_inclusion_deadline else {
+            return Ok(None);
+        };
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+
+        let overdue_threshold = Utc::now()
+            - chrono::Duration::from_std(deadline)
+                .map_err(|err| internal_error(method_name, err))?;
+        let last_executed_l1_batch = storage_processor
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let mut transactions_dal = storage_processor.transactions_web3_dal();
+        let (unexecuted_count, overdue_count) = transactions_dal
+            .get_unexecuted_priority_ops_count(last_executed_l1_batch, overdue_threshold)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let oldest_unexecuted_op = transactions_dal
+            .get_oldest_unexecuted_priority_op(last_executed_l1_batch)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(Some(ForcedInclusionStatus {
+            deadline_sec: deadline.as_secs(),
+            unexecuted_count,
+            overdue_count,
+            oldest_unexecuted_op,
+        }))
+    }
+
+    /// Resolves the name using the on-chain name registry. Returns `None` if the name is not registered.
+    pub async fn resolve_name_impl(&self, name: &str) -> Result<Option<Address>, Web3Error> {
+        let method_name = "resolve_name";
+        let resolver = self
+            .state
+            .name_resolver
+            .as_ref()
+            .ok_or(Web3Error::NameRegistryUnavailable)?;
+        let method_latency = API_METRICS.start_call(method_name);
+        let response = resolver.resolve(name).await;
+        method_latency.observe();
+        response
+    }
+
+    /// Executes view calls and storage reads at a single block. Calls are executed sequentially; once the compute
+    /// budget is exhausted, the remaining calls fail with an error. Storage reads are cheap and are always performed.
+    pub async fn multicall_read_impl(
+        &self,
+        reads: Vec<MulticallRead>,
+        block_id: Option<BlockId>,
+    ) -> Result<Vec<MulticallReadResult>, Web3Error> {
+        const METHOD_NAME: &str = "multicall_read";
+
+        let read_count: usize = reads.iter().map(MulticallRead::read_count).sum();
+        let max_read_count = self.state.api_config.req_entities_limit;
+        if read_count > max_read_count {
+            return Err(Web3Error::TooManyReads(read_count, max_read_count));
+        }
+
+        let started_at = Instant::now();
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id, METHOD_NAME)
+            .await?;
+
+        let hashed_keys: Vec<_> = reads
+            .iter()
+            .filter_map(|read| match read {
+                MulticallRead::Storage { address, keys } => Some((address, keys)),
+                MulticallRead::Call(_) => None,
+            })
+            .flat_map(|(address, keys)| {
+                keys.iter().map(|key| {
+                    StorageKey::new(AccountTreeId::new(*address), u256_to_h256(*key)).hashed_key()
+                })
+            })
+            .collect();
+        let storage_values = connection
+            .storage_logs_dal()
+            .get_storage_values(&hashed_keys, block_args.resolved_block_number())
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(connection);
+
+        let compute_budget = self.state.api_config.multicall_compute_budget;
+        let mut hashed_keys = hashed_keys.into_iter();
+        let mut results = Vec::with_capacity(reads.len());
+        for read in reads {
+            let result = match read {
+                MulticallRead::Storage { keys, .. } => {
+                    let values = hashed_keys
+                        .by_ref()
+                        .take(keys.len())
+                        .map(|key| storage_values.get(&key).copied().flatten())
+                        .map(Option::unwrap_or_default)
+                        .collect();
+                    MulticallReadResult::StorageValues(values)
+                }
+                MulticallRead::Call(_) if started_at.elapsed() > compute_budget => {
+                    MulticallReadResult::Error("compute budget exhausted".to_owned())
+                }
+                MulticallRead::Call(request) => {
+                    let max_tx_size = self.state.api_config.max_tx_size;
+                    match L2Tx::from_request(request.into(), max_tx_size) {
+                        Ok(tx) => match self.state.tx_sender.eth_call(block_args, tx).await {
+                            Ok(output) => MulticallReadResult::Output(output.into()),
+                            Err(err) => MulticallReadResult::Error(
+                                err.into_web3_error(METHOD_NAME).to_string(),
+                            ),
+                        },
+                        Err(err) => MulticallReadResult::Error(err.to_string()),
+                    }
+                }
+            };
+            results.push(result);
+        }
+
+        let block_diff = self
+            .state
+            .last_sealed_miniblock
+            .diff_with_block_args(&block_args);
+        method_latency.observe(block_diff);
+        Ok(results)
+    }
+
+    /// Returns a page of storage diffs and deployed bytecodes for a sealed L1 batch, so that the chain state
+    /// can be mirrored without re-executing transactions. Returns `None` if the batch is not sealed.
+    pub async fn get_batch_state_diffs_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<Option<BatchStateDiffs>, Web3Error> {
+        const METHOD_NAME: &str = "get_batch_state_diffs";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let offset = offset.unwrap_or(0);
+        let max_limit = self.state.api_config.req_entities_limit;
+        let limit = limit.map_or(max_limit, |limit| limit.min(max_limit));
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let miniblock_range = storage_processor
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some((first_miniblock, last_miniblock)) = miniblock_range else {
+            method_latency.observe();
+            return Ok(None);
+        };
+        let miniblock_range = first_miniblock..=last_miniblock;
+
+        let compressed_state_diffs = if offset == 0 {
+            storage_processor
+                .blocks_dal()
+                .get_compressed_state_diffs(l1_batch_number)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+        } else {
+            None
+        };
+        let mut storage_logs_dal = storage_processor.storage_logs_dal();
+        let storage_diffs = storage_logs_dal
+            .get_storage_diffs(l1_batch_number, miniblock_range.clone(), offset, limit)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let total_storage_diffs = storage_logs_dal
+            .count_storage_diffs(miniblock_range.clone())
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut factory_deps_dal = storage_processor.factory_deps_dal();
+        let deployed_bytecodes = factory_deps_dal
+            .get_factory_deps_in_miniblocks(miniblock_range.clone(), offset, limit)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let total_deployed_bytecodes = factory_deps_dal
+            .count_factory_deps_in_miniblocks(miniblock_range)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(Some(BatchStateDiffs {
+            l1_batch_number,
+            compressed_state_diffs: compressed_state_diffs.map(Into::into),
+            storage_diffs,
+            total_storage_diffs,
+            deployed_bytecodes,
+            total_deployed_bytecodes,
+        }))
+    }
+
+    /// Returns logs matching the filter (with the same semantics and limits as `eth_getLogs`) together with events
+    /// decoded using ABIs registered by the operator or obtained from contract verification.
+    pub async fn get_decoded_logs_impl(
+        &self,
+        filter: Filter,
+    ) -> Result<Vec<DecodedLog>, Web3Error> {
+        const METHOD_NAME: &str = "get_decoded_logs";
+
+        let logs = EthNamespace::new(self.state.clone())
+            .get_logs_impl(filter)
+            .await?;
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let addresses: HashSet<_> = logs.iter().map(|log| log.address).collect();
+        let addresses: Vec<_> = addresses.into_iter().collect();
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let abis = storage_processor
+            .contract_abis_dal()
+            .get_abis(&addresses)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let decoder = self.state.contract_abis.decoder(abis);
+        let decoded_logs = logs
+            .into_iter()
+            .map(|log| DecodedLog {
+                event: decoder.decode(&log),
+                log,
+            })
+            .collect();
+        method_latency.observe();
+        Ok(decoded_logs)
+    }
+
+    /// Returns Merkle proofs for multiple L2-to-L1 logs in the order of requests. A proof is `None` if the transaction
+    /// is not included into a sealed L1 batch, or if it has emitted fewer logs than the requested index.
+    pub async fn get_l2_to_l1_log_proofs_impl(
+        &self,
+        requests: Vec<L2ToL1LogProofRequest>,
+    ) -> Result<Vec<Option<L2ToL1LogProof>>, Web3Error> {
+        const METHOD_NAME: &str = "get_l2_to_l1_log_proofs";
+
+        let max_requests = self.state.api_config.req_entities_limit;
+        if requests.len() > max_requests {
+            return Err(Web3Error::TooManyLogProofRequests(
+                requests.len(),
+                max_requests,
+            ));
+        }
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        L2_TO_L1_LOG_PROOF_METRICS
+            .batch_size
+            .observe(requests.len());
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let tx_hashes: Vec<_> = requests.iter().map(|request| request.tx_hash).collect();
+        let tx_locations = storage_processor
+            .blocks_web3_dal()
+            .get_l1_batch_info_for_txs(&tx_hashes)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let mut logs_trees = HashMap::new();
+        let mut proofs = Vec::with_capacity(requests.len());
+        for request in &requests {
+            let Some(&(l1_batch_number, l1_batch_tx_index)) = tx_locations.get(&request.tx_hash)
+            else {
+                proofs.push(None);
+                continue;
+            };
+            if !logs_trees.contains_key(&l1_batch_number) {
+                let logs_tree = self
+                    .state
+                    .l2_to_l1_log_proofs
+                    .get(&mut storage_processor, l1_batch_number)
+                    .await
+                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+                logs_trees.insert(l1_batch_number, logs_tree);
+            }
+            let proof = logs_trees[&l1_batch_number].as_ref().and_then(|tree| {
+                tree.proof(request.index.unwrap_or(0), |log| {
+                    log.tx_number_in_block == l1_batch_tx_index
+                })
+            });
+            proofs.push(proof);
+        }
+        method_latency.observe();
+        Ok(proofs)
+    }
+
+    /// Returns timestamps of the lifecycle stages of the specified transaction.
+    pub async fn get_transaction_timeline_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<TransactionTimeline>, Web3Error> {
+        let method_name = "get_transaction_timeline";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let response = storage_processor
+            .transactions_web3_dal()
+            .get_transaction_timeline(hash)
+            .await
+            .map_err(|err| internal_error(method_name, err));
+        method_latency.observe();
+        response
+    }
+
+    /// Explains why the specified transaction, or unexecuted transactions of the specified account, are not
+    /// progressing.
+    pub async fn diagnose_transaction_impl(
+        &self,
+        target: TransactionDiagnosticsTarget,
+    ) -> Result<Vec<TransactionDiagnostics>, Web3Error> {
+        const METHOD_NAME: &str = "diagnose_transaction";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let response = match target {
+            TransactionDiagnosticsTarget::Hash(hash) => {
+                vec![self.diagnose_transaction_by_hash(hash).await?]
+            }
+            TransactionDiagnosticsTarget::Account(address) => {
+                self.diagnose_account_transactions(address).await?
+            }
+        };
+        method_latency.observe();
+        Ok(response)
+    }
+
+    async fn diagnose_transaction_by_hash(
+        &self,
+        hash: H256,
+    ) -> Result<TransactionDiagnostics, Web3Error> {
+        const METHOD_NAME: &str = "diagnose_transaction";
+
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let timeline = storage_processor
+            .transactions_web3_dal()
+            .get_transaction_timeline(hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(timeline) = timeline else {
+            drop(storage_processor);
+            let proxied_tx = match &self.state.tx_sender.0.proxy {
+                Some(proxy) => proxy.find_tx(hash).await,
+                None => None,
+            };
+            return Ok(match proxied_tx {
+                Some(tx) => TransactionDiagnostics {
+                    hash,
+                    initiator_address: Some(tx.initiator_account()),
+                    nonce: Some(tx.nonce()),
+                    diagnosis: TransactionDiagnosis::AwaitingProxySync,
+                },
+                None => TransactionDiagnostics {
+                    hash,
+                    initiator_address: None,
+                    nonce: None,
+                    diagnosis: TransactionDiagnosis::NotFound,
+                },
+            });
+        };
+
+        let transaction = storage_processor
+            .transactions_web3_dal()
+            .get_transaction(TransactionId::Hash(hash), self.state.api_config.l2_chain_id)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        // The transaction may be purged from the mempool in the meantime.
+        let initiator_address = transaction.as_ref().and_then(|tx| tx.from);
+        let nonce = transaction
+            .filter(|_| !timeline.is_l1_originated)
+            .map(|tx| Nonce(tx.nonce.as_u32()));
+
+        let mut diagnosis = None;
+        if let (None, Some(address)) = (timeline.miniblock_number, initiator_address) {
+            if !timeline.is_l1_originated {
+                let unexecuted_txs = self
+                    .load_unexecuted_txs(&mut storage_processor, address)
+                    .await?;
+                drop(storage_processor);
+                // The transaction may be missing if it was included into a miniblock in the meantime.
+                if let Some(tx) = unexecuted_txs.iter().find(|tx| tx.hash == hash) {
+                    let account = self.load_account_state(address, &unexecuted_txs).await?;
+                    let base_fee = self.current_base_fee().await?;
+                    diagnosis = Some(diagnose_unexecuted_tx(tx, &account, base_fee));
+                }
+            }
+        }
+        Ok(TransactionDiagnostics {
+            hash,
+            initiator_address,
+            nonce,
+            diagnosis: diagnosis.unwrap_or_else(|| diagnose_by_timeline(&timeline)),
+        })
+    }
+
+    async fn diagnose_account_transactions(
+        &self,
+        address: Address,
+    ) -> Result<Vec<TransactionDiagnostics>, Web3Error> {
+        const METHOD_NAME: &str = "diagnose_transaction";
+
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let unexecuted_txs = self
+            .load_unexecuted_txs(&mut storage_processor, address)
+            .await?;
+        drop(storage_processor);
+        let account = self.load_account_state(address, &unexecuted_txs).await?;
+
+        let mut diagnostics = Vec::with_capacity(unexecuted_txs.len());
+        if !unexecuted_txs.is_empty() {
+            let base_fee = self.current_base_fee().await?;
+            diagnostics.extend(unexecuted_txs.iter().map(|tx| TransactionDiagnostics {
+                hash: tx.hash,
+                initiator_address: Some(address),
+                nonce: Some(tx.nonce),
+                diagnosis: diagnose_unexecuted_tx(tx, &account, base_fee),
+            }));
+        }
+
+        if let Some(proxy) = &self.state.tx_sender.0.proxy {
+            let mut proxied_txs = proxy.find_txs_by_account(address).await;
+            // Transactions with past nonces are already synced back from the main node.
+            proxied_txs.retain(|tx| {
+                tx.nonce() >= account.nonce
+                    && !unexecuted_txs
+                        .iter()
+                        .any(|unexecuted_tx| unexecuted_tx.hash == tx.hash())
+            });
+            proxied_txs.sort_unstable_by_key(L2Tx::nonce);
+            diagnostics.extend(proxied_txs.iter().map(|tx| TransactionDiagnostics {
+                hash: tx.hash(),
+                initiator_address: Some(address),
+                nonce: Some(tx.nonce()),
+                diagnosis: TransactionDiagnosis::AwaitingProxySync,
+            }));
+        }
+        Ok(diagnostics)
+    }
+
+    async fn load_unexecuted_txs(
+        &self,
+        storage_processor: &mut StorageProcessor<'_>,
+        address: Address,
+    ) -> Result<Vec<UnexecutedL2Tx>, Web3Error> {
+        storage_processor
+            .transactions_web3_dal()
+            .get_unexecuted_l2_txs_by_initiator(address, self.state.api_config.req_entities_limit)
+            .await
+            .map_err(|err| internal_error("diagnose_transaction", err))
+    }
+
+    async fn load_account_state(
+        &self,
+        address: Address,
+        unexecuted_txs: &[UnexecutedL2Tx],
+    ) -> Result<AccountState, Web3Error> {
+        const METHOD_NAME: &str = "diagnose_transaction";
+
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let nonces = storage_processor
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&[address])
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let nonce = nonces.get(&address).copied().unwrap_or(Nonce(0));
+        Ok(AccountState::new(nonce, unexecuted_txs))
+    }
+
+    async fn current_base_fee(&self) -> Result<U256, Web3Error> {
+        let base_fee = self.state.tx_sender.gas_price().await;
+        let base_fee = base_fee.map_err(|err| internal_error("diagnose_transaction", err))?;
+        Ok(base_fee.into())
+    }
+}
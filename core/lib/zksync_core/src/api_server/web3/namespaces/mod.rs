@@ -1,15 +1,26 @@
 //! Actual implementation of Web3 API namespaces logic, not tied to the backend
 //! used to create a JSON RPC server.
 
+mod admin;
 mod debug;
 mod en;
 pub(crate) mod eth;
+mod idexo;
 mod net;
+mod operator;
 mod snapshots;
 mod web3;
 mod zks;
 
 pub use self::{
-    debug::DebugNamespace, en::EnNamespace, eth::EthNamespace, net::NetNamespace,
-    snapshots::SnapshotsNamespace, web3::Web3Namespace, zks::ZksNamespace,
+    admin::{AdminHandles, AdminNamespace},
+    debug::DebugNamespace,
+    en::EnNamespace,
+    eth::{EthNamespace, PROTOCOL_VERSION},
+    idexo::IdexoNamespace,
+    net::NetNamespace,
+    operator::OperatorNamespace,
+    snapshots::SnapshotsNamespace,
+    web3::Web3Namespace,
+    zks::ZksNamespace,
 };
@@ -0,0 +1,357 @@
+use vlog::{LogControl, LogControlError};
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    api::idexo::{AuditAction, AuditLogEntry, AuditLogFilter, LogEvent, RegisteredToken},
+    eth_sender::SettlementHalt,
+    ethabi, Address,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{
+    backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState,
+};
+
+/// Actor recorded in the audit log for actions performed via this namespace.
+const AUDIT_ACTOR: &str = "operator_rpc";
+
+/// Methods overriding automated safety mechanisms of the node. Must not be exposed publicly.
+#[derive(Debug, Clone)]
+pub struct OperatorNamespace {
+    state: RpcState,
+}
+
+impl OperatorNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    /// Accesses the master DB; required by methods modifying the node state.
+    async fn access_master_storage(
+        &self,
+        method_name: &'static str,
+    ) -> Result<StorageProcessor<'_>, Web3Error> {
+        let pool = self
+            .state
+            .tx_sender
+            .0
+            .master_connection_pool
+            .as_ref()
+            .ok_or_else(|| {
+                internal_error(method_name, "master connection pool is not available")
+            })?;
+        pool.access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))
+    }
+
+    fn log_control(method_name: &'static str) -> Result<&'static LogControl, Web3Error> {
+        LogControl::get().ok_or_else(|| {
+            internal_error(method_name, "observability subsystem is not initialized")
+        })
+    }
+
+    /// Appends an entry to the audit log. Should be called in the same transaction as the recorded action.
+    async fn record_audit_entry(
+        storage: &mut StorageProcessor<'_>,
+        method_name: &'static str,
+        action: AuditAction,
+        details: serde_json::Value,
+    ) -> Result<(), Web3Error> {
+        storage
+            .audit_log_dal()
+            .insert_entry(action, AUDIT_ACTOR, &details)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        Ok(())
+    }
+
+    pub async fn get_settlement_halt_impl(&self) -> Result<Option<SettlementHalt>, Web3Error> {
+        let method_name = "get_settlement_halt";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let response = storage
+            .eth_sender_dal()
+            .get_active_settlement_halt()
+            .await
+            .map_err(|err| internal_error(method_name, err));
+        method_latency.observe();
+        response
+    }
+
+    /// Resumes commit and execute operations halted because of a settlement layer anomaly.
+    /// Returns the overridden halt, or `None` if settlement wasn't halted.
+    pub async fn resume_settlement_impl(&self) -> Result<Option<SettlementHalt>, Web3Error> {
+        let method_name = "resume_settlement";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let halt = transaction
+            .eth_sender_dal()
+            .resume_settlement()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if let Some(halt) = &halt {
+            tracing::info!(
+                "Settlement halted at {} was resumed by the operator; anomaly: {}",
+                halt.halted_at,
+                halt.reason
+            );
+            let details =
+                serde_json::to_value(halt).map_err(|err| internal_error(method_name, err))?;
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::SettlementResume,
+                details,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(halt)
+    }
+
+    /// Adds the token to the registry surfaced to wallets, or updates its metadata if it's already registered.
+    pub async fn register_token_impl(&self, token: RegisteredToken) -> Result<(), Web3Error> {
+        let method_name = "register_token";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        transaction
+            .token_registry_dal()
+            .register_token(&token)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let details =
+            serde_json::to_value(&token).map_err(|err| internal_error(method_name, err))?;
+        Self::record_audit_entry(
+            &mut transaction,
+            method_name,
+            AuditAction::TokenRegistration,
+            details,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        tracing::info!(
+            "Operator registered token {} ({:?})",
+            token.metadata.symbol,
+            token.l2_address
+        );
+        method_latency.observe();
+        Ok(())
+    }
+
+    /// Removes the token from the registry. Returns `false` if the token was not registered.
+    pub async fn unregister_token_impl(&self, l2_address: Address) -> Result<bool, Web3Error> {
+        let method_name = "unregister_token";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let removed = transaction
+            .token_registry_dal()
+            .unregister_token(l2_address)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if removed {
+            tracing::info!("Operator unregistered token {l2_address:?}");
+            let details = serde_json::json!({ "l2Address": l2_address });
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::TokenUnregistration,
+                details,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(removed)
+    }
+
+    /// Registers the ABI used to decode event logs emitted by the contract, or replaces the previously registered ABI.
+    /// Registered ABIs take precedence over ABIs of verified contracts.
+    pub async fn register_abi_impl(
+        &self,
+        address: Address,
+        abi: serde_json::Value,
+    ) -> Result<(), Web3Error> {
+        let method_name = "register_abi";
+        let method_latency = API_METRICS.start_call(method_name);
+        serde_json::from_value::<ethabi::Contract>(abi.clone())
+            .map_err(|err| Web3Error::InvalidAbi(err.to_string()))?;
+
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        transaction
+            .contract_abis_dal()
+            .register_abi(address, &abi)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let details = serde_json::json!({ "address": address, "abi": abi });
+        Self::record_audit_entry(
+            &mut transaction,
+            method_name,
+            AuditAction::AbiRegistration,
+            details,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        tracing::info!("Operator registered ABI for contract {address:?}");
+        method_latency.observe();
+        Ok(())
+    }
+
+    /// Removes the registered ABI of the contract. Returns `false` if no ABI was registered.
+    pub async fn unregister_abi_impl(&self, address: Address) -> Result<bool, Web3Error> {
+        let method_name = "unregister_abi";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self.access_master_storage(method_name).await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let removed = transaction
+            .contract_abis_dal()
+            .unregister_abi(address)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if removed {
+            tracing::info!("Operator unregistered ABI for contract {address:?}");
+            let details = serde_json::json!({ "address": address });
+            Self::record_audit_entry(
+                &mut transaction,
+                method_name,
+                AuditAction::AbiUnregistration,
+                details,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(removed)
+    }
+
+    /// Returns audit log entries matching the filter in the order they were recorded.
+    pub async fn get_audit_log_impl(
+        &self,
+        filter: AuditLogFilter,
+    ) -> Result<Vec<AuditLogEntry>, Web3Error> {
+        let method_name = "get_audit_log";
+        let method_latency = API_METRICS.start_call(method_name);
+        let max_limit = self.state.api_config.req_entities_limit;
+        let limit = filter.limit.map_or(max_limit, |limit| limit.min(max_limit));
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let entries = storage
+            .audit_log_dal()
+            .get_entries(filter.action, filter.after_id.unwrap_or(0), limit)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        method_latency.observe();
+        Ok(entries)
+    }
+
+    pub fn get_log_filter_impl(&self) -> Result<String, Web3Error> {
+        let method_name = "get_log_filter";
+        let method_latency = API_METRICS.start_call(method_name);
+        let filter = Self::log_control(method_name)?.filter();
+        method_latency.observe();
+        Ok(filter)
+    }
+
+    /// Replaces the log filter of the node, using the same syntax as the `RUST_LOG` env variable.
+    /// Returns the previous filter.
+    pub async fn set_log_filter_impl(&self, filter: String) -> Result<String, Web3Error> {
+        let method_name = "set_log_filter";
+        let method_latency = API_METRICS.start_call(method_name);
+        let log_control = Self::log_control(method_name)?;
+        let mut storage = self.access_master_storage(method_name).await?;
+
+        let previous_filter = log_control.set_filter(&filter).map_err(|err| match err {
+            LogControlError::Parse(err) => Web3Error::InvalidLogFilter(err.to_string()),
+            LogControlError::Reload(_) => internal_error(method_name, err),
+        })?;
+        let details = serde_json::json!({
+            "previousFilter": previous_filter,
+            "filter": filter,
+        });
+        let record_result = Self::record_audit_entry(
+            &mut storage,
+            method_name,
+            AuditAction::LogFilterChange,
+            details,
+        )
+        .await;
+        if let Err(err) = record_result {
+            // Changes not recorded in the audit log must not take effect.
+            log_control
+                .set_filter(&previous_filter)
+                .map_err(|err| internal_error(method_name, err))?;
+            return Err(err);
+        }
+
+        tracing::info!("Operator changed log filter from `{previous_filter}` to `{filter}`");
+        method_latency.observe();
+        Ok(previous_filter)
+    }
+
+    /// Returns the most recent warnings and errors logged by the node in the order they were emitted.
+    pub fn get_recent_log_events_impl(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<Vec<LogEvent>, Web3Error> {
+        let method_name = "get_recent_log_events";
+        let method_latency = API_METRICS.start_call(method_name);
+        let max_limit = self.state.api_config.req_entities_limit;
+        let limit = limit.map_or(max_limit, |limit| limit.min(max_limit));
+        let events = Self::log_control(method_name)?
+            .recent_events(limit)
+            .into_iter()
+            .map(|event| LogEvent {
+                timestamp: event.timestamp,
+                level: event.level.to_string(),
+                target: event.target,
+                message: event.message,
+                fields: event.fields,
+            })
+            .collect();
+        method_latency.observe();
+        Ok(events)
+    }
+}
@@ -1,13 +1,13 @@
 use std::{collections::HashMap, convert::TryInto};
 
 use zksync_dal::StorageProcessor;
-use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        idexo::TreeLag, BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails,
+        L2ToL1LogProof, Proof, ProtocolVersion, StorageProof, TransactionDetails,
     },
+    contract_verification_api::VerificationInfo,
     fee::Fee,
     fee_model::FeeParams,
     l1::L1Tx,
@@ -133,6 +133,11 @@ impl ZksNamespace {
         self.state.api_config.diamond_proxy_addr
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn get_validator_timelock_contract_impl(&self) -> Option<Address> {
+        self.state.api_config.validator_timelock_addr
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn get_testnet_paymaster_impl(&self) -> Option<Address> {
         self.state.api_config.l2_testnet_paymaster_addr
@@ -158,23 +163,45 @@ impl ZksNamespace {
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let mut storage = self.access_storage(METHOD_NAME).await?;
-        let tokens = storage
+        let well_known_tokens = storage
             .tokens_web3_dal()
             .get_well_known_tokens()
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let registered_tokens = storage
+            .token_registry_dal()
+            .get_registered_tokens()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let well_known_tokens = well_known_tokens.into_iter().map(|token_info| Token {
+            l1_address: token_info.l1_address,
+            l2_address: token_info.l2_address,
+            name: token_info.metadata.name,
+            symbol: token_info.metadata.symbol,
+            decimals: token_info.metadata.decimals,
+        });
+        let registered_tokens = registered_tokens.into_iter().map(|token| Token {
+            l1_address: token.l1_address,
+            l2_address: token.l2_address,
+            name: token.metadata.name,
+            symbol: token.metadata.symbol,
+            decimals: token.metadata.decimals,
+        });
+        // Metadata curated by the operator takes precedence over the metadata of well-known tokens.
+        let tokens: HashMap<_, _> = well_known_tokens
+            .chain(registered_tokens)
+            .map(|token| (token.l2_address, token))
+            .collect();
+        let mut tokens: Vec<_> = tokens.into_values().collect();
+        tokens.sort_unstable_by(|token, other| {
+            (&token.symbol, token.l2_address).cmp(&(&other.symbol, other.l2_address))
+        });
 
         let tokens = tokens
             .into_iter()
             .skip(from as usize)
             .take(limit.into())
-            .map(|token_info| Token {
-                l1_address: token_info.l1_address,
-                l2_address: token_info.l2_address,
-                name: token_info.metadata.name,
-                symbol: token_info.metadata.symbol,
-                decimals: token_info.metadata.decimals,
-            })
             .collect();
         method_latency.observe();
         Ok(tokens)
@@ -238,7 +265,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_l2_to_l1_msg_proof";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(block_number)?;
+        self.state.start_info().ensure_not_pruned(block_number)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let Some(l1_batch_number) = storage
             .blocks_web3_dal()
@@ -308,49 +335,15 @@ impl ZksNamespace {
         index_in_filtered_logs: usize,
         log_filter: impl Fn(&L2ToL1Log) -> bool,
     ) -> Result<Option<L2ToL1LogProof>, Web3Error> {
-        let all_l1_logs_in_batch = storage
-            .blocks_web3_dal()
-            .get_l2_to_l1_logs(l1_batch_number)
+        // Logs of a partially pruned L1 batch cannot be proven, since the proof covers all logs in the batch.
+        self.state.start_info().ensure_not_pruned(l1_batch_number)?;
+        let logs_tree = self
+            .state
+            .l2_to_l1_log_proofs
+            .get(storage, l1_batch_number)
             .await
             .map_err(|err| internal_error(method_name, err))?;
-
-        let Some((l1_log_index, _)) = all_l1_logs_in_batch
-            .iter()
-            .enumerate()
-            .filter(|(_, log)| log_filter(log))
-            .nth(index_in_filtered_logs)
-        else {
-            return Ok(None);
-        };
-
-        let Some(batch) = storage
-            .blocks_dal()
-            .get_l1_batch_header(l1_batch_number)
-            .await
-            .map_err(|err| internal_error(method_name, err))?
-        else {
-            return Ok(None);
-        };
-
-        let merkle_tree_leaves = all_l1_logs_in_batch.iter().map(L2ToL1Log::to_bytes);
-
-        let min_tree_size = if batch
-            .protocol_version
-            .map(|v| v.is_pre_boojum())
-            .unwrap_or(true)
-        {
-            Some(L2ToL1Log::PRE_BOOJUM_MIN_L2_L1_LOGS_TREE_SIZE)
-        } else {
-            Some(L2ToL1Log::MIN_L2_L1_LOGS_TREE_SIZE)
-        };
-
-        let (root, proof) = MiniMerkleTree::new(merkle_tree_leaves, min_tree_size)
-            .merkle_root_and_path(l1_log_index);
-        Ok(Some(L2ToL1LogProof {
-            proof,
-            root,
-            id: l1_log_index as u32,
-        }))
+        Ok(logs_tree.and_then(|tree| tree.proof(index_in_filtered_logs, log_filter)))
     }
 
     #[tracing::instrument(skip(self))]
@@ -411,7 +404,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_miniblock_range";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(batch)?;
+        self.state.start_info().ensure_not_pruned(batch)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let minmax = storage
             .blocks_web3_dal()
@@ -432,7 +425,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_block_details";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(block_number)?;
+        self.state.start_info().ensure_not_pruned(block_number)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let block_details = storage
             .blocks_web3_dal()
@@ -452,7 +445,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_raw_block_transactions";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(block_number)?;
+        self.state.start_info().ensure_not_pruned(block_number)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let transactions = storage
             .transactions_web3_dal()
@@ -504,7 +497,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_l1_batch";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(batch_number)?;
+        self.state.start_info().ensure_not_pruned(batch_number)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let l1_batch = storage
             .blocks_web3_dal()
@@ -603,16 +596,33 @@ impl ZksNamespace {
     ) -> Result<Proof, Web3Error> {
         const METHOD_NAME: &str = "get_proofs";
 
-        self.state.start_info.ensure_not_pruned(l1_batch_number)?;
+        self.ensure_merkle_tree_enabled()?;
+        self.state.start_info().ensure_not_pruned(l1_batch_number)?;
+        let tree_api = self
+            .state
+            .tree_api
+            .as_ref()
+            .ok_or(Web3Error::TreeApiUnavailable)?;
+        let tree_lag = self.load_tree_lag(METHOD_NAME).await?;
+        let is_processed = tree_lag
+            .last_processed_l1_batch
+            .is_some_and(|last_processed| l1_batch_number <= last_processed);
+        if !is_processed {
+            let is_sealed = tree_lag
+                .sealed_l1_batch
+                .is_some_and(|sealed| l1_batch_number <= sealed);
+            return Err(if is_sealed {
+                Web3Error::TreeLagging(l1_batch_number, tree_lag.last_processed_l1_batch)
+            } else {
+                Web3Error::NoBlock
+            });
+        }
+
         let hashed_keys = keys
             .iter()
             .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
             .collect();
-        let storage_proof = self
-            .state
-            .tree_api
-            .as_ref()
-            .ok_or(Web3Error::TreeApiUnavailable)?
+        let storage_proof = tree_api
             .get_proofs(l1_batch_number, hashed_keys)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?
@@ -631,4 +641,63 @@ impl ZksNamespace {
             storage_proof,
         })
     }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_contract_verification_info_impl(
+        &self,
+        address: Address,
+    ) -> Result<Option<VerificationInfo>, Web3Error> {
+        const METHOD_NAME: &str = "get_contract_verification_info";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let info = storage
+            .contract_verification_dal()
+            .get_contract_verification_info(address)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(info)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_tree_lag_impl(&self) -> Result<TreeLag, Web3Error> {
+        const METHOD_NAME: &str = "get_tree_lag";
+
+        self.ensure_merkle_tree_enabled()?;
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let tree_lag = self.load_tree_lag(METHOD_NAME).await?;
+        method_latency.observe();
+        Ok(tree_lag)
+    }
+
+    fn ensure_merkle_tree_enabled(&self) -> Result<(), Web3Error> {
+        if self.state.merkle_tree_disabled {
+            return Err(Web3Error::ComponentUnavailable("merkle_tree"));
+        }
+        Ok(())
+    }
+
+    async fn load_tree_lag(&self, method_name: &'static str) -> Result<TreeLag, Web3Error> {
+        let mut storage = self.access_storage(method_name).await?;
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let last_processed_l1_batch = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let lag = sealed_l1_batch
+            .zip(last_processed_l1_batch)
+            .map(|(sealed, processed)| sealed.0.saturating_sub(processed.0));
+        Ok(TreeLag {
+            sealed_l1_batch,
+            last_processed_l1_batch,
+            lag,
+        })
+    }
 }
@@ -1,14 +1,26 @@
 //! (Largely) backend-agnostic logic for dealing with Web3 subscriptions.
 
+use std::{
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
 use anyhow::Context as _;
+use chrono::{TimeZone, Utc};
 use futures::FutureExt;
 use tokio::{
     sync::{broadcast, mpsc, watch},
     task::JoinHandle,
     time::{interval, Duration},
 };
-use zksync_dal::ConnectionPool;
-use zksync_types::{MiniblockNumber, H128, H256};
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    api::{
+        idexo::{BatchLifecycleStage, BatchStatusEvent},
+        L1BatchDetails,
+    },
+    L1BatchNumber, MiniblockNumber, H128, H256,
+};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::{server::SubscriptionMessage, SubscriptionResult},
@@ -16,18 +28,29 @@ use zksync_web3_decl::{
         types::{error::ErrorCode, ErrorObject, SubscriptionId},
         PendingSubscriptionSink, SendTimeoutError, SubscriptionSink,
     },
-    namespaces::EthPubSubServer,
-    types::{BlockHeader, Log, PubSubFilter, PubSubResult},
+    namespaces::{EthPubSubServer, IdexoPubSubServer},
+    types::{BlockHeader, Log, PubSubFilter, PubSubResult, SlowConsumerNotice},
 };
 
 use super::{
     metrics::{SubscriptionType, PUB_SUB_METRICS},
     namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
 };
-use crate::api_server::execution_sandbox::BlockStartInfo;
+use crate::{
+    api_server::execution_sandbox::BlockStartInfo,
+    supervisor::{RestartPolicy, SupervisedTask},
+};
 
 const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
 const SUBSCRIPTION_SINK_SEND_TIMEOUT: Duration = Duration::from_secs(1);
+/// Maximum number of L1 batches reported for a single lifecycle stage during one notifier iteration.
+const MAX_BATCH_STATUS_EVENTS_PER_STAGE: u32 = 100;
+const BATCH_LIFECYCLE_STAGES: [BatchLifecycleStage; 4] = [
+    BatchLifecycleStage::Sealed,
+    BatchLifecycleStage::Committed,
+    BatchLifecycleStage::Proven,
+    BatchLifecycleStage::Executed,
+];
 
 #[derive(Debug, Clone, Copy)]
 pub struct EthSubscriptionIdProvider;
@@ -39,6 +62,72 @@ impl IdProvider for EthSubscriptionIdProvider {
     }
 }
 
+/// Policy for subscribers lagging behind notifications. The lag is measured as the number of notification batches
+/// queued for the subscriber in the broadcast channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct SlowConsumerPolicy {
+    /// Lag after which the subscriber is warned using a [`SlowConsumerNotice`].
+    pub warning_threshold: usize,
+    /// Lag after which the subscription is terminated.
+    pub close_threshold: usize,
+}
+
+impl Default for SlowConsumerPolicy {
+    fn default() -> Self {
+        Self {
+            warning_threshold: 128,
+            close_threshold: 512,
+        }
+    }
+}
+
+/// Action to take for a subscriber with a certain lag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum LagAction {
+    None,
+    Warn,
+    Close,
+}
+
+impl SlowConsumerPolicy {
+    /// Checks that the thresholds are reachable, i.e. don't exceed the capacity of broadcast channels.
+    pub(super) fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.close_threshold <= BROADCAST_CHANNEL_CAPACITY,
+            "pub/sub lag close threshold ({}) must not exceed the notification channel capacity ({})",
+            self.close_threshold,
+            BROADCAST_CHANNEL_CAPACITY
+        );
+        anyhow::ensure!(
+            self.warning_threshold <= self.close_threshold,
+            "pub/sub lag warning threshold ({}) must not exceed the close threshold ({})",
+            self.warning_threshold,
+            self.close_threshold
+        );
+        Ok(())
+    }
+
+    /// Determines the action for the specified lag. A subscriber is warned once until it fully catches up
+    /// with notifications; `warned` tracks this state.
+    pub(super) fn check_lag(&self, lag: usize, warned: &mut bool) -> LagAction {
+        if lag >= self.close_threshold {
+            LagAction::Close
+        } else if lag >= self.warning_threshold {
+            if *warned {
+                LagAction::None
+            } else {
+                *warned = true;
+                LagAction::Warn
+            }
+        } else {
+            if lag == 0 {
+                *warned = false;
+            }
+            LagAction::None
+        }
+    }
+}
+
 /// Events emitted by the subscription logic. Only used in WebSocket server tests so far.
 #[derive(Debug)]
 pub(super) enum PubSubEvent {
@@ -47,16 +136,108 @@ pub(super) enum PubSubEvent {
     MiniblockAdvanced(SubscriptionType, MiniblockNumber),
 }
 
+/// Latest L1 batches that have reached each of the lifecycle stages.
+#[derive(Debug, Clone, Copy, Default)]
+struct BatchStages {
+    sealed: Option<L1BatchNumber>,
+    committed: Option<L1BatchNumber>,
+    proven: Option<L1BatchNumber>,
+    executed: Option<L1BatchNumber>,
+}
+
+impl BatchStages {
+    async fn load(storage: &mut StorageProcessor<'_>) -> anyhow::Result<Self> {
+        let mut blocks_dal = storage.blocks_dal();
+        Ok(Self {
+            sealed: blocks_dal
+                .get_sealed_l1_batch_number()
+                .await
+                .context("get_sealed_l1_batch_number()")?,
+            committed: blocks_dal
+                .get_number_of_last_l1_batch_committed_on_eth()
+                .await
+                .context("get_number_of_last_l1_batch_committed_on_eth()")?,
+            proven: blocks_dal
+                .get_number_of_last_l1_batch_proven_on_eth()
+                .await
+                .context("get_number_of_last_l1_batch_proven_on_eth()")?,
+            executed: blocks_dal
+                .get_number_of_last_l1_batch_executed_on_eth()
+                .await
+                .context("get_number_of_last_l1_batch_executed_on_eth()")?,
+        })
+    }
+
+    fn get(&self, stage: BatchLifecycleStage) -> Option<L1BatchNumber> {
+        match stage {
+            BatchLifecycleStage::Sealed => self.sealed,
+            BatchLifecycleStage::Committed => self.committed,
+            BatchLifecycleStage::Proven => self.proven,
+            BatchLifecycleStage::Executed => self.executed,
+        }
+    }
+
+    fn get_mut(&mut self, stage: BatchLifecycleStage) -> &mut Option<L1BatchNumber> {
+        match stage {
+            BatchLifecycleStage::Sealed => &mut self.sealed,
+            BatchLifecycleStage::Committed => &mut self.committed,
+            BatchLifecycleStage::Proven => &mut self.proven,
+            BatchLifecycleStage::Executed => &mut self.executed,
+        }
+    }
+}
+
+fn batch_status_event(
+    details: &L1BatchDetails,
+    stage: BatchLifecycleStage,
+) -> Option<BatchStatusEvent> {
+    let base = &details.base;
+    let (tx_hash, timestamp) = match stage {
+        BatchLifecycleStage::Sealed => {
+            let timestamp = Utc.timestamp_opt(base.timestamp as i64, 0).single()?;
+            (None, timestamp)
+        }
+        BatchLifecycleStage::Committed => (base.commit_tx_hash, base.committed_at?),
+        BatchLifecycleStage::Proven => (base.prove_tx_hash, base.proven_at?),
+        BatchLifecycleStage::Executed => (base.execute_tx_hash, base.executed_at?),
+    };
+    Some(BatchStatusEvent {
+        l1_batch_number: details.number,
+        stage,
+        tx_hash,
+        timestamp,
+    })
+}
+
+/// Last notified position of a notifier. Only the field corresponding to the notifier type is used.
+#[derive(Debug, Default)]
+struct NotifierCursor {
+    /// Last notified miniblock (for block and log notifiers).
+    miniblock_number: Option<MiniblockNumber>,
+    /// Time of receiving the last notified transaction (for the transaction notifier).
+    tx_received_at: Option<chrono::NaiveDateTime>,
+    /// Last notified L1 batch stages (for the batch status notifier).
+    batch_stages: Option<BatchStages>,
+}
+
 /// Manager of notifications for a certain type of subscriptions.
-#[derive(Debug)]
+///
+/// Clones of the notifier share the last notified position, so that a notifier task restarted by the supervisor
+/// resumes from the position where the failed task has stopped rather than from the current chain state.
+#[derive(Debug, Clone)]
 struct PubSubNotifier {
     sender: broadcast::Sender<Vec<PubSubResult>>,
     connection_pool: ConnectionPool,
     polling_interval: Duration,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    cursor: Arc<Mutex<NotifierCursor>>,
 }
 
 impl PubSubNotifier {
+    fn cursor(&self) -> MutexGuard<'_, NotifierCursor> {
+        self.cursor.lock().expect("notifier cursor is poisoned")
+    }
+
     async fn get_starting_miniblock_number(&self) -> anyhow::Result<MiniblockNumber> {
         let mut storage = self
             .connection_pool
@@ -87,7 +268,11 @@ impl PubSubNotifier {
 
 impl PubSubNotifier {
     async fn notify_blocks(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
-        let mut last_block_number = self.get_starting_miniblock_number().await?;
+        let last_block_number = self.cursor().miniblock_number;
+        let mut last_block_number = match last_block_number {
+            Some(number) => number,
+            None => self.get_starting_miniblock_number().await?,
+        };
         let mut timer = interval(self.polling_interval);
         loop {
             if *stop_receiver.borrow() {
@@ -104,6 +289,7 @@ impl PubSubNotifier {
                 last_block_number = MiniblockNumber(last_block.number.unwrap().as_u32());
                 let new_blocks = new_blocks.into_iter().map(PubSubResult::Header).collect();
                 self.send_pub_sub_results(new_blocks, SubscriptionType::Blocks);
+                self.cursor().miniblock_number = Some(last_block_number);
                 self.emit_event(PubSubEvent::MiniblockAdvanced(
                     SubscriptionType::Blocks,
                     last_block_number,
@@ -137,7 +323,8 @@ impl PubSubNotifier {
     }
 
     async fn notify_txs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
-        let mut last_time = chrono::Utc::now().naive_utc();
+        let last_time = self.cursor().tx_received_at;
+        let mut last_time = last_time.unwrap_or_else(|| chrono::Utc::now().naive_utc());
         let mut timer = interval(self.polling_interval);
         loop {
             if *stop_receiver.borrow() {
@@ -154,6 +341,7 @@ impl PubSubNotifier {
                 last_time = new_last_time;
                 let new_txs = new_txs.into_iter().map(PubSubResult::TxHash).collect();
                 self.send_pub_sub_results(new_txs, SubscriptionType::Txs);
+                self.cursor().tx_received_at = Some(last_time);
             }
             self.emit_event(PubSubEvent::NotifyIterationFinished(SubscriptionType::Txs));
         }
@@ -175,7 +363,11 @@ impl PubSubNotifier {
     }
 
     async fn notify_logs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
-        let mut last_block_number = self.get_starting_miniblock_number().await?;
+        let last_block_number = self.cursor().miniblock_number;
+        let mut last_block_number = match last_block_number {
+            Some(number) => number,
+            None => self.get_starting_miniblock_number().await?,
+        };
 
         let mut timer = interval(self.polling_interval);
         loop {
@@ -193,6 +385,7 @@ impl PubSubNotifier {
                 last_block_number = MiniblockNumber(last_log.block_number.unwrap().as_u32());
                 let new_logs = new_logs.into_iter().map(PubSubResult::Log).collect();
                 self.send_pub_sub_results(new_logs, SubscriptionType::Logs);
+                self.cursor().miniblock_number = Some(last_block_number);
                 self.emit_event(PubSubEvent::MiniblockAdvanced(
                     SubscriptionType::Logs,
                     last_block_number,
@@ -213,14 +406,114 @@ impl PubSubNotifier {
             .await
             .context("events_web3_dal().get_all_logs()")
     }
+
+    async fn notify_batch_status(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let (mut reported_stages, first_l1_batch) = {
+            let mut storage = self
+                .connection_pool
+                .access_storage_tagged("api")
+                .await
+                .context("access_storage_tagged")?;
+            let start_info = BlockStartInfo::new(&mut storage).await?;
+            let reported_stages = self.cursor().batch_stages;
+            let reported_stages = match reported_stages {
+                Some(stages) => stages,
+                None => BatchStages::load(&mut storage).await?,
+            };
+            (reported_stages, start_info.first_l1_batch)
+        };
+
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!(
+                    "Stop signal received, pubsub_batch_status_notifier is shutting down"
+                );
+                break;
+            }
+            timer.tick().await;
+
+            let db_latency =
+                PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::BatchStatus].start();
+            let new_events = self
+                .new_batch_status_events(&mut reported_stages, first_l1_batch)
+                .await?;
+            db_latency.observe();
+
+            if !new_events.is_empty() {
+                let new_events = new_events
+                    .into_iter()
+                    .map(PubSubResult::BatchStatus)
+                    .collect();
+                self.send_pub_sub_results(new_events, SubscriptionType::BatchStatus);
+            }
+            self.cursor().batch_stages = Some(reported_stages);
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::BatchStatus,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns events for L1 batches that have reached a lifecycle stage since the last call. Events are ordered
+    /// by the stage, then by the L1 batch number.
+    async fn new_batch_status_events(
+        &self,
+        reported_stages: &mut BatchStages,
+        first_l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<Vec<BatchStatusEvent>> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let latest_stages = BatchStages::load(&mut storage).await?;
+
+        let mut events = vec![];
+        for stage in BATCH_LIFECYCLE_STAGES {
+            let Some(latest) = latest_stages.get(stage) else {
+                continue;
+            };
+            let reported = reported_stages.get_mut(stage);
+            if reported.map_or(false, |reported| reported > latest) {
+                // L1 batches were reverted; resume reporting from the new latest batch.
+                *reported = Some(latest);
+                continue;
+            }
+
+            let start = reported.map_or(first_l1_batch.0, |reported| reported.0 + 1);
+            let end = latest
+                .0
+                .min(start.saturating_add(MAX_BATCH_STATUS_EVENTS_PER_STAGE - 1));
+            for number in start..=end {
+                let l1_batch_number = L1BatchNumber(number);
+                let details = storage
+                    .blocks_web3_dal()
+                    .get_l1_batch_details(l1_batch_number)
+                    .await
+                    .with_context(|| format!("get_l1_batch_details({l1_batch_number})"))?;
+                // Details may be missing for the snapshot L1 batch after snapshot recovery.
+                if let Some(event) = details.and_then(|details| batch_status_event(&details, stage))
+                {
+                    events.push(event);
+                }
+                *reported = Some(l1_batch_number);
+            }
+        }
+        Ok(events)
+    }
 }
 
 /// Subscription support for Web3 APIs.
+#[derive(Clone)]
 pub(super) struct EthSubscribe {
     blocks: broadcast::Sender<Vec<PubSubResult>>,
     transactions: broadcast::Sender<Vec<PubSubResult>>,
     logs: broadcast::Sender<Vec<PubSubResult>>,
+    batch_status: broadcast::Sender<Vec<PubSubResult>>,
+    slow_consumer_policy: SlowConsumerPolicy,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    restart_policy: RestartPolicy,
 }
 
 impl EthSubscribe {
@@ -228,19 +521,32 @@ impl EthSubscribe {
         let (blocks, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (logs, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (batch_status, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
 
         Self {
             blocks,
             transactions,
             logs,
+            batch_status,
+            slow_consumer_policy: SlowConsumerPolicy::default(),
             events_sender: None,
+            restart_policy: RestartPolicy::default(),
         }
     }
 
+    pub fn set_slow_consumer_policy(&mut self, policy: SlowConsumerPolicy) {
+        self.slow_consumer_policy = policy;
+    }
+
     pub fn set_events_sender(&mut self, sender: mpsc::UnboundedSender<PubSubEvent>) {
         self.events_sender = Some(sender);
     }
 
+    /// Sets the policy applied to failed notifier tasks. By default, a failed notifier shuts down the server.
+    pub fn set_restart_policy(&mut self, policy: RestartPolicy) {
+        self.restart_policy = policy;
+    }
+
     async fn reject(sink: PendingSubscriptionSink) {
         sink.reject(ErrorObject::borrowed(
             ErrorCode::InvalidParams.code(),
@@ -255,11 +561,13 @@ impl EthSubscribe {
         subscription_type: SubscriptionType,
         mut receiver: broadcast::Receiver<Vec<PubSubResult>>,
         filter: Option<PubSubFilter>,
+        slow_consumer_policy: SlowConsumerPolicy,
     ) {
         let _guard = PUB_SUB_METRICS.active_subscribers[&subscription_type].inc_guard(1);
         let lifetime_latency = PUB_SUB_METRICS.subscriber_lifetime[&subscription_type].start();
         let closed = sink.closed().fuse();
         tokio::pin!(closed);
+        let mut warned = false;
 
         loop {
             tokio::select! {
@@ -275,10 +583,27 @@ impl EthSubscribe {
                             PUB_SUB_METRICS
                                 .skipped_broadcast_messages[&subscription_type]
                                 .observe(message_count);
+                            let notice = SlowConsumerNotice::close(message_count as usize);
+                            Self::send_slow_consumer_notice(&sink, subscription_type, notice).await;
                             break;
                         }
                     };
 
+                    let lag = receiver.len();
+                    PUB_SUB_METRICS.subscriber_lag[&subscription_type].observe(lag as u64);
+                    match slow_consumer_policy.check_lag(lag, &mut warned) {
+                        LagAction::None => { /* the subscriber is healthy */ }
+                        LagAction::Warn => {
+                            let notice = SlowConsumerNotice::warning(lag);
+                            Self::send_slow_consumer_notice(&sink, subscription_type, notice).await;
+                        }
+                        LagAction::Close => {
+                            let notice = SlowConsumerNotice::close(lag);
+                            Self::send_slow_consumer_notice(&sink, subscription_type, notice).await;
+                            break;
+                        }
+                    }
+
                     let handle_result = Self::handle_new_items(
                         &sink,
                         subscription_type,
@@ -299,6 +624,36 @@ impl EthSubscribe {
         lifetime_latency.observe();
     }
 
+    /// Sends a notice to a slow subscriber. Errors are ignored since the subscriber is likely to be unresponsive.
+    async fn send_slow_consumer_notice(
+        sink: &SubscriptionSink,
+        subscription_type: SubscriptionType,
+        notice: SlowConsumerNotice,
+    ) {
+        let closes_subscription = notice.code == SlowConsumerNotice::CLOSE_CODE;
+        tracing::info!(
+            "Subscriber {:?} for {subscription_type:?} lags behind by {} notification batches{}",
+            sink.subscription_id(),
+            notice.lag,
+            if closes_subscription {
+                "; terminating the subscription"
+            } else {
+                ""
+            }
+        );
+        if closes_subscription {
+            PUB_SUB_METRICS.slow_subscribers_closed[&subscription_type].inc();
+        } else {
+            PUB_SUB_METRICS.slow_subscriber_warnings[&subscription_type].inc();
+        }
+
+        let message = SubscriptionMessage::from_json(&PubSubResult::SlowConsumer(notice))
+            .expect("PubSubResult always serializable to json;qed");
+        sink.send_timeout(message, SUBSCRIPTION_SINK_SEND_TIMEOUT)
+            .await
+            .ok();
+    }
+
     async fn handle_new_items(
         sink: &SubscriptionSink,
         subscription_type: SubscriptionType,
@@ -347,6 +702,7 @@ impl EthSubscribe {
                     SubscriptionType::Blocks,
                     blocks_rx,
                     None,
+                    self.slow_consumer_policy,
                 ));
 
                 Some(SubscriptionType::Blocks)
@@ -361,6 +717,7 @@ impl EthSubscribe {
                     SubscriptionType::Txs,
                     transactions_rx,
                     None,
+                    self.slow_consumer_policy,
                 ));
                 Some(SubscriptionType::Txs)
             }
@@ -381,6 +738,7 @@ impl EthSubscribe {
                         SubscriptionType::Logs,
                         logs_rx,
                         Some(filter),
+                        self.slow_consumer_policy,
                     ));
                     Some(SubscriptionType::Logs)
                 }
@@ -419,36 +777,57 @@ impl EthSubscribe {
         polling_interval: Duration,
         stop_receiver: watch::Receiver<bool>,
     ) -> Vec<JoinHandle<anyhow::Result<()>>> {
-        let mut notifier_tasks = Vec::with_capacity(3);
-
-        let notifier = PubSubNotifier {
-            sender: self.blocks.clone(),
+        let notifier = |sender: &broadcast::Sender<_>| PubSubNotifier {
+            sender: sender.clone(),
             connection_pool: connection_pool.clone(),
             polling_interval,
             events_sender: self.events_sender.clone(),
+            cursor: Arc::default(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_blocks(stop_receiver.clone()));
-        notifier_tasks.push(notifier_task);
 
-        let notifier = PubSubNotifier {
-            sender: self.transactions.clone(),
-            connection_pool: connection_pool.clone(),
-            polling_interval,
-            events_sender: self.events_sender.clone(),
-        };
-        let notifier_task = tokio::spawn(notifier.notify_txs(stop_receiver.clone()));
-        notifier_tasks.push(notifier_task);
-
-        let notifier = PubSubNotifier {
-            sender: self.logs.clone(),
-            connection_pool,
-            polling_interval,
-            events_sender: self.events_sender.clone(),
-        };
-        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver));
+        vec![
+            self.spawn_notifier(
+                "pubsub_blocks_notifier",
+                notifier(&self.blocks),
+                PubSubNotifier::notify_blocks,
+                stop_receiver.clone(),
+            ),
+            self.spawn_notifier(
+                "pubsub_txs_notifier",
+                notifier(&self.transactions),
+                PubSubNotifier::notify_txs,
+                stop_receiver.clone(),
+            ),
+            self.spawn_notifier(
+                "pubsub_logs_notifier",
+                notifier(&self.logs),
+                PubSubNotifier::notify_logs,
+                stop_receiver.clone(),
+            ),
+            self.spawn_notifier(
+                "pubsub_batch_status_notifier",
+                notifier(&self.batch_status),
+                PubSubNotifier::notify_batch_status,
+                stop_receiver,
+            ),
+        ]
+    }
 
-        notifier_tasks.push(notifier_task);
-        notifier_tasks
+    /// Spawns a notifier task supervised according to the restart policy. A restarted notifier resumes
+    /// from the last notified position, so subscribers don't miss notifications emitted while it was down.
+    fn spawn_notifier<Fut>(
+        &self,
+        name: &'static str,
+        notifier: PubSubNotifier,
+        notify: fn(PubSubNotifier, watch::Receiver<bool>) -> Fut,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> JoinHandle<anyhow::Result<()>>
+    where
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let create_task = move |stop_receiver| notify(notifier.clone(), stop_receiver);
+        let task = SupervisedTask::new(name, self.restart_policy.clone(), create_task);
+        tokio::spawn(task.run(stop_receiver))
     }
 }
 
@@ -464,3 +843,26 @@ impl EthPubSubServer for EthSubscribe {
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl IdexoPubSubServer for EthSubscribe {
+    async fn subscribe_batch_status(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let Ok(sink) = pending.accept().await else {
+            return Ok(());
+        };
+        let batch_status_rx = self.batch_status.subscribe();
+        tokio::spawn(Self::run_subscriber(
+            sink,
+            SubscriptionType::BatchStatus,
+            batch_status_rx,
+            None,
+            self.slow_consumer_policy,
+        ));
+        if let Some(sender) = &self.events_sender {
+            sender
+                .send(PubSubEvent::Subscribed(SubscriptionType::BatchStatus))
+                .ok();
+        }
+        Ok(())
+    }
+}
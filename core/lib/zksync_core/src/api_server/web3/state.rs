@@ -2,7 +2,7 @@ use std::{
     future::Future,
     sync::{
         atomic::{AtomicU32, Ordering},
-        Arc,
+        Arc, RwLock,
     },
     time::{Duration, Instant},
 };
@@ -10,8 +10,12 @@ use std::{
 use lru::LruCache;
 use tokio::sync::{watch, Mutex};
 use vise::GaugeGuard;
-use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
-use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_config::configs::{
+    api::Web3JsonRpcConfig,
+    chain::{NetworkConfig, StateKeeperConfig},
+    ContractsConfig,
+};
+use zksync_dal::{content_hashes_dal::ContentHashCheck, ConnectionPool, StorageProcessor};
 use zksync_types::{
     api, l2::L2Tx, transaction_request::CallRequest, Address, L1BatchNumber, L1ChainId, L2ChainId,
     MiniblockNumber, H256, U256, U64,
@@ -23,8 +27,12 @@ use crate::{
     api_server::{
         execution_sandbox::{BlockArgs, BlockArgsError, BlockStartInfo},
         tree::TreeApiHttpClient,
-        tx_sender::TxSender,
-        web3::{backend_jsonrpsee::internal_error, TypedFilter},
+        tx_sender::{ReplayProtection, TxSender},
+        web3::{
+            backend_jsonrpsee::internal_error, content_hashes::VerifiedContentHashes,
+            event_decoding::ContractAbiCache, l2_to_l1_log_proofs::L2ToL1LogProofCache,
+            name_resolution::NameResolver, TypedFilter,
+        },
     },
     sync_layer::SyncState,
 };
@@ -83,9 +91,29 @@ pub struct InternalApiConfig {
     pub bridge_addresses: api::BridgeAddresses,
     pub bridgehub_proxy_addr: Option<Address>,
     pub diamond_proxy_addr: Address,
+    pub validator_timelock_addr: Option<Address>,
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub req_entities_limit: usize,
     pub fee_history_limit: u64,
+    /// Deadline for the execution of priority operations on the settlement layer enforced by the state keeper.
+    /// Only set on the main node.
+    pub forced_inclusion_deadline: Option<Duration>,
+    /// Whether to verify miniblock content hashes when serving blocks and transaction receipts.
+    pub verify_content_hashes: bool,
+    /// Address of the name registry used to resolve human-readable names.
+    pub name_registry_addr: Option<Address>,
+    /// Whether to resolve names in address parameters of RPC methods.
+    pub resolve_names_in_params: bool,
+    /// Compute budget for a single `idexo_multicallRead` request.
+    pub multicall_compute_budget: Duration,
+    /// URL of a secondary JSON-RPC endpoint that read requests are mirrored to.
+    pub shadow_api_url: Option<String>,
+    /// Percentage of read requests mirrored to `shadow_api_url`.
+    pub shadow_requests_percentage: f64,
+    /// Whether to accept legacy transactions without replay protection (i.e., without the chain ID).
+    pub pre_eip155_txs_allowed: bool,
+    /// Senders of legacy transactions without replay protection accepted regardless of `pre_eip155_txs_allowed`.
+    pub pre_eip155_tx_senders: Vec<Address>,
 }
 
 impl InternalApiConfig {
@@ -93,6 +121,7 @@ impl InternalApiConfig {
         eth_config: &NetworkConfig,
         web3_config: &Web3JsonRpcConfig,
         contracts_config: &ContractsConfig,
+        state_keeper_config: &StateKeeperConfig,
     ) -> Self {
         Self {
             l1_chain_id: eth_config.network.chain_id(),
@@ -106,14 +135,34 @@ impl InternalApiConfig {
                 l2_erc20_default_bridge: contracts_config.l2_erc20_bridge_addr,
                 l1_weth_bridge: contracts_config.l1_weth_bridge_proxy_addr,
                 l2_weth_bridge: contracts_config.l2_weth_bridge_addr,
+                l1_shared_default_bridge: contracts_config.l1_shared_bridge_proxy_addr,
+                l2_shared_default_bridge: contracts_config.l2_shared_bridge_addr,
             },
             bridgehub_proxy_addr: contracts_config.bridgehub_proxy_addr,
             diamond_proxy_addr: contracts_config.diamond_proxy_addr,
+            validator_timelock_addr: Some(contracts_config.validator_timelock_addr),
             l2_testnet_paymaster_addr: contracts_config.l2_testnet_paymaster_addr,
             req_entities_limit: web3_config.req_entities_limit(),
             fee_history_limit: web3_config.fee_history_limit(),
+            forced_inclusion_deadline: Some(state_keeper_config.forced_inclusion_deadline()),
+            verify_content_hashes: web3_config.verify_content_hashes,
+            name_registry_addr: web3_config.name_registry_addr,
+            resolve_names_in_params: web3_config.resolve_names_in_params,
+            multicall_compute_budget: web3_config.multicall_compute_budget(),
+            shadow_api_url: web3_config.shadow_api_url.clone(),
+            shadow_requests_percentage: web3_config.shadow_requests_percentage(),
+            pre_eip155_txs_allowed: web3_config.pre_eip155_txs_allowed,
+            pre_eip155_tx_senders: web3_config.pre_eip155_tx_senders.clone(),
         }
     }
+
+    /// Returns the replay protection policy for raw transactions.
+    pub fn replay_protection(&self) -> ReplayProtection {
+        ReplayProtection::new(self.l2_chain_id).with_pre_eip155_txs(
+            self.pre_eip155_txs_allowed,
+            self.pre_eip155_tx_senders.clone(),
+        )
+    }
 }
 
 /// Thread-safe updatable information about the last sealed miniblock number.
@@ -191,30 +240,86 @@ impl SealedMiniblockNumber {
     }
 }
 
+/// Thread-safe updatable information about the first locally available miniblock / L1 batch.
+///
+/// The information is updated on an interval specified when creating an instance, so that data removed
+/// by the DB pruner while the API server is running is reported as pruned rather than missing.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedBlockStartInfo(Arc<RwLock<BlockStartInfo>>);
+
+impl CachedBlockStartInfo {
+    /// Loads the current start info, and creates a handle to it together with a task that will update it
+    /// on a schedule.
+    pub async fn new(
+        connection_pool: ConnectionPool,
+        update_interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<(Self, impl Future<Output = anyhow::Result<()>>)> {
+        let mut connection = connection_pool.access_storage_tagged("api").await?;
+        let start_info = BlockStartInfo::new(&mut connection).await?;
+        drop(connection);
+
+        let this = Self(Arc::new(RwLock::new(start_info)));
+        let info_updater = this.clone();
+        let update_task = async move {
+            loop {
+                if tokio::time::timeout(update_interval, stop_receiver.changed())
+                    .await
+                    .is_ok()
+                {
+                    tracing::debug!("Stopping block start info updates");
+                    return Ok(());
+                }
+
+                let mut connection = connection_pool.access_storage_tagged("api").await?;
+                let start_info = BlockStartInfo::new(&mut connection).await?;
+                drop(connection);
+                *info_updater
+                    .0
+                    .write()
+                    .expect("block start info is poisoned") = start_info;
+            }
+        };
+        Ok((this, update_task))
+    }
+
+    pub fn get(&self) -> BlockStartInfo {
+        *self.0.read().expect("block start info is poisoned")
+    }
+}
+
 /// Holder for the data required for the API to be functional.
 #[derive(Debug, Clone)]
 pub struct RpcState {
     pub(crate) installed_filters: Arc<Mutex<Filters>>,
     pub connection_pool: ConnectionPool,
     pub tree_api: Option<TreeApiHttpClient>,
+    /// Set if the node runs without a Merkle tree, so that proofs and tree lag cannot be served.
+    pub(super) merkle_tree_disabled: bool,
     pub tx_sender: TxSender,
     pub sync_state: Option<SyncState>,
     pub(super) api_config: InternalApiConfig,
+    pub(super) replay_protection: ReplayProtection,
     /// Number of the first locally available miniblock / L1 batch. May differ from 0 if the node state was recovered
-    /// from a snapshot.
-    pub(super) start_info: BlockStartInfo,
+    /// from a snapshot, or if old data was pruned.
+    pub(super) start_info: CachedBlockStartInfo,
     pub(super) last_sealed_miniblock: SealedMiniblockNumber,
+    pub(super) name_resolver: Option<NameResolver>,
+    pub(super) l2_to_l1_log_proofs: L2ToL1LogProofCache,
+    pub(super) verified_content_hashes: VerifiedContentHashes,
+    pub(super) contract_abis: ContractAbiCache,
 }
 
 impl RpcState {
-    pub fn parse_transaction_bytes(&self, bytes: &[u8]) -> Result<(L2Tx, H256), Web3Error> {
-        let chain_id = self.api_config.l2_chain_id;
-        let (tx_request, hash) = api::TransactionRequest::from_bytes(bytes, chain_id)?;
+    pub(super) fn start_info(&self) -> BlockStartInfo {
+        self.start_info.get()
+    }
 
-        Ok((
-            L2Tx::from_request(tx_request, self.api_config.max_tx_size)?,
-            hash,
-        ))
+    pub fn parse_transaction_bytes(&self, bytes: &[u8]) -> Result<(L2Tx, H256), Web3Error> {
+        let (tx_request, hash) = self.replay_protection.parse_raw_tx(bytes)?;
+        // The transaction size is checked by the transaction sender, which surfaces a dedicated error
+        // for oversized transactions.
+        Ok((L2Tx::from_request(tx_request, usize::MAX)?, hash))
     }
 
     pub fn u64_to_block_number(n: U64) -> MiniblockNumber {
@@ -231,7 +336,7 @@ impl RpcState {
         block: api::BlockId,
         method_name: &'static str,
     ) -> Result<MiniblockNumber, Web3Error> {
-        self.start_info.ensure_not_pruned(block)?;
+        self.start_info().ensure_not_pruned(block)?;
         let result = connection.blocks_web3_dal().resolve_block_id(block).await;
         result
             .map_err(|err| internal_error(method_name, err))?
@@ -244,13 +349,72 @@ impl RpcState {
         block: api::BlockId,
         method_name: &'static str,
     ) -> Result<BlockArgs, Web3Error> {
-        BlockArgs::new(connection, block, self.start_info)
+        BlockArgs::new(connection, block, self.start_info())
             .await
-            .map_err(|err| match err {
-                BlockArgsError::Pruned(number) => Web3Error::PrunedBlock(number),
-                BlockArgsError::Missing => Web3Error::NoBlock,
-                BlockArgsError::Database(err) => internal_error(method_name, err),
-            })
+            .map_err(|err| Self::map_block_args_error(err, method_name))
+    }
+
+    /// Same as [`Self::resolve_block_args()`], but resolves pruned blocks using the Merkle tree if the tree
+    /// is configured for the transaction sender. Should only be used for methods supporting such blocks
+    /// (i.e., `eth_call`).
+    pub(crate) async fn resolve_call_block_args(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: api::BlockId,
+        method_name: &'static str,
+    ) -> Result<BlockArgs, Web3Error> {
+        let start_info = self.start_info();
+        let block_args = BlockArgs::new(connection, block, start_info).await;
+        let block_args = match (block_args, self.tx_sender.archive_tree()) {
+            (Err(BlockArgsError::Pruned(_)), Some(tree)) => {
+                BlockArgs::from_tree(connection, tree.as_ref(), block, start_info).await
+            }
+            (block_args, _) => block_args,
+        };
+        block_args.map_err(|err| Self::map_block_args_error(err, method_name))
+    }
+
+    fn map_block_args_error(err: BlockArgsError, method_name: &'static str) -> Web3Error {
+        match err {
+            BlockArgsError::Pruned(number) => Web3Error::PrunedBlock(number),
+            BlockArgsError::PrunedStateUnavailable(number, closest) => {
+                Web3Error::PrunedBlockStateUnavailable(number, closest)
+            }
+            BlockArgsError::Missing => Web3Error::NoBlock,
+            BlockArgsError::Database(err) => internal_error(method_name, err),
+        }
+    }
+
+    /// Checks that the miniblock data in Postgres matches the content hash persisted when sealing the miniblock.
+    /// No-op if content hash verification is disabled, or if the miniblock has no persisted hash. Successfully
+    /// verified hashes are cached, so that miniblock data isn't reloaded on each request.
+    pub(crate) async fn verify_content_hash(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        method_name: &'static str,
+    ) -> Result<(), Web3Error> {
+        if !self.api_config.verify_content_hashes {
+            return Ok(());
+        }
+
+        let check = self
+            .verified_content_hashes
+            .check(connection, miniblock_number)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        if let ContentHashCheck::Mismatch {
+            persisted,
+            computed,
+        } = check
+        {
+            let err = format!(
+                "miniblock #{miniblock_number} data is corrupted: persisted content hash {persisted:?}, \
+                 hash computed from the current data {computed:?}"
+            );
+            return Err(internal_error(method_name, err));
+        }
+        Ok(())
     }
 
     pub async fn resolve_filter_block_number(
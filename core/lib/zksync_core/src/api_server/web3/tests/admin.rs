@@ -0,0 +1,440 @@
+//! Tests for the `admin` Web3 namespace.
+
+use tempfile::TempDir;
+use test_casing::test_casing;
+use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_types::api::idexo::{AuditAction, ScheduledTxParams, ScheduledTxState};
+use zksync_web3_decl::namespaces::{AdminNamespaceClient, OperatorNamespaceClient};
+
+use super::*;
+
+fn assert_component_unavailable_error(error: &ClientError) {
+    if let ClientError::Call(error) = error {
+        assert_eq!(error.code(), 6);
+        assert!(error.message().contains("state_keeper"), "{error:?}");
+    } else {
+        panic!("Unexpected error: {error:?}");
+    }
+}
+
+#[derive(Debug)]
+struct PausingTxIntakeTest;
+
+#[async_trait]
+impl HttpTest for PausingTxIntakeTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        assert_eq!(client.get_tx_intake_pause().await?, None);
+        assert_eq!(client.resume_tx_intake().await?, None);
+
+        let pause = client.pause_tx_intake("incident #1".to_owned()).await?;
+        assert_eq!(pause.reason, "incident #1");
+        assert_eq!(pause.resumed_at, None);
+        // Repeated pauses return the active pause.
+        let repeated_pause = client.pause_tx_intake("incident #2".to_owned()).await?;
+        assert_eq!(repeated_pause, pause);
+        assert_eq!(client.get_tx_intake_pause().await?, Some(pause.clone()));
+
+        let mut storage = pool.access_storage().await?;
+        let active_pause = storage.tx_intake_dal().get_active_pause().await?;
+        assert_eq!(active_pause, Some(pause.clone()));
+
+        let resumed_pause = client
+            .resume_tx_intake()
+            .await?
+            .context("intake is not resumed")?;
+        assert_eq!(resumed_pause.id, pause.id);
+        assert!(resumed_pause.resumed_at.is_some());
+        assert_eq!(client.get_tx_intake_pause().await?, None);
+
+        let audit_log = client.get_audit_log(None).await?;
+        let actions: Vec<_> = audit_log.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            [AuditAction::TxIntakePause, AuditAction::TxIntakeResume]
+        );
+        assert_eq!(audit_log[0].actor, "admin_rpc");
+        assert_eq!(audit_log[0].details["reason"], "incident #1");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn pausing_tx_intake() {
+    test_http_server(PausingTxIntakeTest).await;
+}
+
+#[derive(Debug)]
+struct RequestingSnapshotTest;
+
+#[async_trait]
+impl HttpTest for RequestingSnapshotTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        assert_eq!(client.get_snapshot_request().await?, None);
+
+        let request = client.request_snapshot().await?;
+        assert_eq!(request.l1_batch_number, None);
+        // Repeated requests return the pending request.
+        assert_eq!(client.request_snapshot().await?, request);
+        assert_eq!(client.get_snapshot_request().await?, Some(request.clone()));
+
+        let mut storage = pool.access_storage().await?;
+        storage
+            .snapshots_dal()
+            .mark_snapshot_request_as_processed(L1BatchNumber(1))
+            .await?;
+        assert_eq!(client.get_snapshot_request().await?, None);
+
+        let audit_log = client.get_audit_log(None).await?;
+        let actions: Vec<_> = audit_log.iter().map(|entry| entry.action).collect();
+        assert_eq!(actions, [AuditAction::SnapshotRequest]);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn requesting_snapshot() {
+    test_http_server(RequestingSnapshotTest).await;
+}
+
+#[derive(Debug)]
+struct DroppingTransactionTest;
+
+#[async_trait]
+impl HttpTest for DroppingTransactionTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let tx = create_l2_transaction(10, 100);
+        let tx_hash = tx.hash();
+        assert!(!client.drop_transaction(tx_hash).await?);
+
+        let mut storage = pool.access_storage().await?;
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        assert!(client.drop_transaction(tx_hash).await?);
+        assert!(!client.drop_transaction(tx_hash).await?);
+        let pending_tx = storage
+            .transactions_dal()
+            .get_pending_l2_tx(tx_hash)
+            .await?;
+        assert_eq!(pending_tx, None);
+
+        let audit_log = client.get_audit_log(None).await?;
+        assert_eq!(audit_log.len(), 1, "{audit_log:?}");
+        assert_eq!(audit_log[0].action, AuditAction::TransactionDrop);
+        assert_eq!(audit_log[0].actor, "admin_rpc");
+
+        // Transactions fetched into the mempool cannot be dropped w/o the state keeper running alongside the server.
+        let tx = create_l2_transaction(10, 100);
+        let tx_hash = tx.hash();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+            .await;
+        let mempool_txs = storage
+            .transactions_dal()
+            .sync_mempool(&[], &[], 0, 0, 1_000)
+            .await?;
+        assert_eq!(mempool_txs.len(), 1);
+        let error = client.drop_transaction(tx_hash).await.unwrap_err();
+        assert_component_unavailable_error(&error);
+        let pending_tx = storage
+            .transactions_dal()
+            .get_pending_l2_tx(tx_hash)
+            .await?;
+        assert!(pending_tx.is_some_and(|tx| tx.in_mempool));
+
+        let error = client.seal_l1_batch().await.unwrap_err();
+        assert_component_unavailable_error(&error);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn dropping_transaction() {
+    test_http_server(DroppingTransactionTest).await;
+}
+
+#[derive(Debug)]
+struct ManagingFeeDiscountsTest;
+
+#[async_trait]
+impl HttpTest for ManagingFeeDiscountsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let partner = Address::repeat_byte(1);
+        assert!(client.get_fee_discounts().await?.is_empty());
+        assert_eq!(client.remove_fee_discount(partner).await?, None);
+
+        let error = client
+            .set_fee_discount(partner, 10_001, None)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+
+        let discount = client
+            .set_fee_discount(partner, 10_000, Some("partner".to_owned()))
+            .await?;
+        assert_eq!(discount.address, partner);
+        assert_eq!(discount.discount_bps, 10_000);
+        assert_eq!(client.get_fee_discounts().await?, [discount.clone()]);
+        let mut storage = pool.access_storage().await?;
+        let discounts = storage
+            .fee_discounts_dal()
+            .get_discounts_for_accounts(&[partner])
+            .await?;
+        assert_eq!(discounts, HashMap::from([(partner, 10_000)]));
+
+        let removed_discount = client.remove_fee_discount(partner).await?;
+        assert_eq!(removed_discount, Some(discount));
+        assert!(client.get_fee_discounts().await?.is_empty());
+
+        let audit_log = client.get_audit_log(None).await?;
+        let actions: Vec<_> = audit_log.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            [
+                AuditAction::FeeDiscountUpdate,
+                AuditAction::FeeDiscountRemoval
+            ]
+        );
+        assert_eq!(audit_log[0].details["discountBps"], 10_000);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn managing_fee_discounts() {
+    test_http_server(ManagingFeeDiscountsTest).await;
+}
+
+#[derive(Debug)]
+struct ManagingWithdrawalLimitsTest;
+
+#[async_trait]
+impl HttpTest for ManagingWithdrawalLimitsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let l1_token = Address::repeat_byte(1);
+        let account = Address::repeat_byte(2);
+        assert!(client.get_withdrawal_limits().await?.is_empty());
+        assert_eq!(client.remove_withdrawal_limit(l1_token).await?, None);
+
+        for (total_limit, account_limit, window_sec) in
+            [(None, None, 60), (Some(1.into()), None, 0)]
+        {
+            let error = client
+                .set_withdrawal_limit(l1_token, total_limit, account_limit, window_sec)
+                .await
+                .unwrap_err();
+            if let ClientError::Call(error) = error {
+                assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+            } else {
+                panic!("Unexpected error: {error:?}");
+            }
+        }
+
+        let limit = client
+            .set_withdrawal_limit(l1_token, Some(1_000.into()), Some(100.into()), 3_600)
+            .await?;
+        assert_eq!(limit.l1_token, l1_token);
+        assert_eq!(limit.total_limit, Some(1_000.into()));
+        assert_eq!(limit.account_limit, Some(100.into()));
+        assert_eq!(client.get_withdrawal_limits().await?, [limit.clone()]);
+        let mut storage = pool.access_storage().await?;
+        let limits = storage
+            .withdrawal_limits_dal()
+            .get_limits_for_tokens(&[l1_token])
+            .await?;
+        assert_eq!(limits, HashMap::from([(l1_token, limit.clone())]));
+
+        let exemption = client
+            .add_withdrawal_limit_exemption(account, Some("rebalancer".to_owned()))
+            .await?;
+        assert_eq!(exemption.address, account);
+        assert_eq!(
+            client.get_withdrawal_limit_exemptions().await?,
+            [exemption.clone()]
+        );
+        assert!(storage.withdrawal_limits_dal().is_exempt(account).await?);
+
+        let removed_exemption = client.remove_withdrawal_limit_exemption(account).await?;
+        assert_eq!(removed_exemption, Some(exemption));
+        assert!(client.get_withdrawal_limit_exemptions().await?.is_empty());
+        let removed_limit = client.remove_withdrawal_limit(l1_token).await?;
+        assert_eq!(removed_limit, Some(limit));
+        assert!(client.get_withdrawal_limits().await?.is_empty());
+
+        let audit_log = client.get_audit_log(None).await?;
+        let actions: Vec<_> = audit_log.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            [
+                AuditAction::WithdrawalLimitUpdate,
+                AuditAction::WithdrawalLimitExemption,
+                AuditAction::WithdrawalLimitExemptionRemoval,
+                AuditAction::WithdrawalLimitRemoval,
+            ]
+        );
+        assert_eq!(audit_log[0].details["windowSec"], 3_600);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn managing_withdrawal_limits() {
+    test_http_server(ManagingWithdrawalLimitsTest).await;
+}
+
+#[derive(Debug)]
+struct ManagingScheduledTxsTest;
+
+#[async_trait]
+impl HttpTest for ManagingScheduledTxsTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let params = ScheduledTxParams {
+            name: "oracle_push".to_owned(),
+            contract_address: Address::repeat_byte(1),
+            calldata: vec![1, 2, 3].into(),
+            value: 0.into(),
+            gas_limit: 1_000_000,
+            interval_sec: 60,
+        };
+        assert!(client.get_scheduled_txs().await?.is_empty());
+        assert!(!client.disable_scheduled_tx(params.name.clone()).await?);
+
+        let invalid_params = ScheduledTxParams {
+            interval_sec: 0,
+            ..params.clone()
+        };
+        let error = client
+            .register_scheduled_tx(invalid_params)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+
+        client.register_scheduled_tx(params.clone()).await?;
+        let scheduled_txs = client.get_scheduled_txs().await?;
+        assert_eq!(scheduled_txs.len(), 1);
+        assert_eq!(scheduled_txs[0].params, params);
+        assert!(scheduled_txs[0].is_due);
+        assert_eq!(scheduled_txs[0].last_tx_state, ScheduledTxState::Idle);
+        assert_eq!(scheduled_txs[0].last_tx_hash, None);
+
+        assert!(client.disable_scheduled_tx(params.name.clone()).await?);
+        assert!(client.get_scheduled_txs().await?.is_empty());
+        assert!(!client.disable_scheduled_tx(params.name.clone()).await?);
+
+        let audit_log = client.get_audit_log(None).await?;
+        let actions: Vec<_> = audit_log.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            [
+                AuditAction::ScheduledTxRegistration,
+                AuditAction::ScheduledTxDisabling,
+            ]
+        );
+        assert_eq!(audit_log[0].details["intervalSec"], 60);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn managing_scheduled_txs() {
+    test_http_server(ManagingScheduledTxsTest).await;
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TestColumnFamily;
+
+impl NamedColumnFamily for TestColumnFamily {
+    const DB_NAME: &'static str = "admin_api_test";
+    const ALL: &'static [Self] = &[Self];
+
+    fn name(&self) -> &'static str {
+        "default"
+    }
+}
+
+#[derive(Debug)]
+struct CompactingRocksdbTest;
+
+#[async_trait]
+impl HttpTest for CompactingRocksdbTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db = RocksDB::<TestColumnFamily>::new(temp_dir.path())?.with_sync_writes();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(TestColumnFamily, b"test", b"value");
+        db.write(batch)?;
+
+        let sizes = client.get_rocksdb_sizes().await?;
+        let sizes: Vec<_> = sizes
+            .into_iter()
+            .filter(|sizes| sizes.db_name == TestColumnFamily::DB_NAME)
+            .collect();
+        assert_eq!(sizes.len(), 1, "{sizes:?}");
+        assert_eq!(sizes[0].cf_name, "default");
+
+        let sizes = client
+            .compact_rocksdb(TestColumnFamily::DB_NAME.to_owned())
+            .await?
+            .context("RocksDB instance is not found")?;
+        assert_eq!(sizes.len(), 1, "{sizes:?}");
+        assert_eq!(sizes[0].db_name, TestColumnFamily::DB_NAME);
+        let sizes = client.compact_rocksdb("non_existing".to_owned()).await?;
+        assert_eq!(sizes, None);
+
+        let audit_log = client.get_audit_log(None).await?;
+        assert_eq!(audit_log.len(), 1, "{audit_log:?}");
+        assert_eq!(audit_log[0].action, AuditAction::RocksdbCompaction);
+        assert_eq!(audit_log[0].details["dbName"], TestColumnFamily::DB_NAME);
+
+        drop(db);
+        let sizes = client
+            .compact_rocksdb(TestColumnFamily::DB_NAME.to_owned())
+            .await?;
+        assert_eq!(sizes, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn compacting_rocksdb() {
+    test_http_server(CompactingRocksdbTest).await;
+}
+
+#[test_casing(2, [Namespace::Operator, Namespace::Admin])]
+#[tokio::test]
+async fn privileged_namespaces_require_auth_token(namespace: Namespace) {
+    let pool = ConnectionPool::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let api_config = InternalApiConfig::new(
+        &network_config,
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &StateKeeperConfig::for_tests(),
+    );
+    let (tx_sender, vm_barrier) = create_test_tx_sender(
+        pool.clone(),
+        api_config.l2_chain_id,
+        MockTransactionExecutor::default().into(),
+    )
+    .await;
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = ApiBuilder::jsonrpsee_backend(api_config, pool)
+        .http(0)
+        .with_tx_sender(tx_sender, vm_barrier)
+        .enable_api_namespaces(vec![Namespace::Eth, namespace])
+        .build(stop_receiver)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("operator_auth_token"), "{err}");
+}
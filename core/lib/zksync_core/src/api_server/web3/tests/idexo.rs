@@ -0,0 +1,1081 @@
+//! Tests for the `idexo` Web3 namespace.
+
+use chrono::TimeZone as _;
+use multivm::interface::{ExecutionResult, VmRevertReason};
+use zksync_dal::{
+    relayed_messages_dal::{MessageOrigin, RelayedMessage},
+    settlement_txs_dal::{SettlementTx, SignedSettlementTx},
+};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    api::idexo::{
+        BatchSettlementCost, BridgeDeposit, DepositStage, L2ToL1LogProofRequest,
+        MessageDeliveryReceipt, MessageDeliveryStatus, MessageDirection, MulticallRead,
+        MulticallReadResult, ProofVerificationStatus, SettlementStage, TransactionDiagnosis,
+        TransactionDiagnosticsTarget,
+    },
+    helpers::unix_timestamp_ms,
+    l1::L1Tx,
+    l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
+    pubdata_da::DABlobReference,
+    transaction_request::CallRequest,
+    web3::types::Bytes,
+    Execute, L1BlockNumber, L1TxCommonData, PriorityOpId, L1_MESSENGER_ADDRESS,
+};
+use zksync_utils::{address_to_h256, u256_to_h256};
+use zksync_web3_decl::{
+    jsonrpsee::{core::client::ClientT, rpc_params},
+    namespaces::IdexoNamespaceClient,
+};
+
+use super::*;
+use crate::api_server::web3::{
+    event_decoding::{events_abi, transfer_event},
+    name_resolution::{namehash, ADDR_SELECTOR},
+};
+
+#[derive(Debug)]
+struct BatchEconomicsTest;
+
+#[async_trait]
+impl HttpTest for BatchEconomicsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let tx_results = [
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+        ];
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &tx_results)
+            .await;
+
+        let economics = client.get_batch_economics(L1BatchNumber(2)).await?;
+        assert!(economics.is_none(), "{economics:?}");
+
+        let economics = client
+            .get_batch_economics(L1BatchNumber(1))
+            .await?
+            .context("no economics for L1 batch #1")?;
+        // Each transaction pays for 1,000 gas at the base fee of 1 wei.
+        assert_eq!(economics.l2_fees_collected, 2_000.into());
+        assert_eq!(economics.commit, None);
+        assert_eq!(economics.total_settlement_cost, U256::zero());
+        assert!(!economics.is_fully_settled);
+
+        let eth_tx = storage
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                AggregatedActionType::Commit,
+                Address::zero(),
+                1_000_000,
+                None,
+                None,
+            )
+            .await?;
+        let commit_cost = BatchSettlementCost {
+            eth_tx_id: eth_tx.id,
+            gas_used: 100_000,
+            gas_cost: 100_000.into(),
+            blob_gas_used: 0,
+            blob_cost: U256::zero(),
+        };
+        storage
+            .settlement_costs_dal()
+            .insert_settlement_cost(L1BatchNumber(1), AggregatedActionType::Commit, &commit_cost)
+            .await?;
+
+        let economics = client
+            .get_batch_economics(L1BatchNumber(1))
+            .await?
+            .context("no economics for L1 batch #1")?;
+        assert_eq!(economics.commit, Some(commit_cost));
+        assert_eq!(economics.total_settlement_cost, 100_000.into());
+        assert!(!economics.is_profitable);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_batch_economics() {
+    test_http_server(BatchEconomicsTest).await;
+}
+
+#[derive(Debug)]
+struct DaInclusionProofTest;
+
+#[async_trait]
+impl HttpTest for DaInclusionProofTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+
+        let proof = client.get_da_inclusion_proof(L1BatchNumber(1)).await?;
+        assert!(proof.is_none(), "{proof:?}");
+
+        let reference = DABlobReference {
+            namespace: vec![0; 29],
+            height: 42,
+            commitment: vec![1; 32],
+        };
+        storage
+            .data_availability_dal()
+            .insert_l1_batch_da(
+                L1BatchNumber(1),
+                "42:0101",
+                Some(&reference),
+                chrono::Utc::now(),
+            )
+            .await?;
+        storage
+            .data_availability_dal()
+            .save_l1_batch_inclusion_data(L1BatchNumber(1), &[1, 2, 3])
+            .await?;
+
+        let proof = client
+            .get_da_inclusion_proof(L1BatchNumber(1))
+            .await?
+            .context("no DA inclusion proof for L1 batch #1")?;
+        assert_eq!(proof.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(proof.blob_id, "42:0101");
+        assert_eq!(proof.height, Some(42));
+        assert_eq!(proof.commitment.unwrap().0, reference.commitment);
+        assert_eq!(proof.inclusion_data.unwrap().0, [1, 2, 3]);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_da_inclusion_proof() {
+    test_http_server(DaInclusionProofTest).await;
+}
+
+#[derive(Debug)]
+struct DepositStatusTest;
+
+#[async_trait]
+impl HttpTest for DepositStatusTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let settlement_tx_hash = H256::repeat_byte(0x01);
+        let deposits = [0, 1].map(|id| BridgeDeposit {
+            settlement_tx_hash,
+            settlement_block_number: 10,
+            priority_op_id: PriorityOpId(id),
+            tx_hash: H256::from_low_u64_be(id + 1),
+        });
+        let mut storage = pool.access_storage().await?;
+        storage
+            .bridge_deposits_dal()
+            .replace_deposits(0, &deposits)
+            .await?;
+        // Only the first deposit is picked up by the chain.
+        let l1_tx = L1Tx {
+            execute: Execute::default(),
+            common_data: L1TxCommonData {
+                serial_id: PriorityOpId(0),
+                gas_limit: 100_000.into(),
+                eth_hash: settlement_tx_hash,
+                eth_block: 10,
+                canonical_tx_hash: deposits[0].tx_hash,
+                ..L1TxCommonData::default()
+            },
+            received_timestamp_ms: 0,
+        };
+        storage
+            .transactions_dal()
+            .insert_transaction_l1(l1_tx, L1BlockNumber(10))
+            .await;
+
+        let statuses = client.get_deposit_status(H256::zero()).await?;
+        assert!(statuses.is_empty(), "{statuses:?}");
+
+        let statuses = client.get_deposit_status(settlement_tx_hash).await?;
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].deposit, deposits[0]);
+        assert_eq!(statuses[0].stage, DepositStage::Queued);
+        let details = statuses[0].details.as_ref().context("no details")?;
+        assert!(details.is_l1_originated);
+        assert_eq!(statuses[1].deposit, deposits[1]);
+        assert_eq!(statuses[1].stage, DepositStage::Observed);
+        assert!(statuses[1].details.is_none());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_deposit_status() {
+    test_http_server(DepositStatusTest).await;
+}
+
+#[derive(Debug)]
+struct RelayedMessagesTest;
+
+#[async_trait]
+impl HttpTest for RelayedMessagesTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let source_tx_hash = H256::repeat_byte(0x01);
+        let message = RelayedMessage {
+            id: H256::repeat_byte(0x02),
+            source_tx_hash,
+            sender: Address::repeat_byte(0x03),
+            data: b"hello".to_vec(),
+            origin: MessageOrigin::L1Batch {
+                l1_batch_number: L1BatchNumber(1),
+                l2_message_index: 0,
+                tx_number_in_batch: 0,
+                merkle_proof: vec![H256::repeat_byte(0x04)],
+            },
+        };
+        let receipt = MessageDeliveryReceipt {
+            tx_hash: H256::repeat_byte(0x05),
+            block_number: 100,
+        };
+        let settlement_tx = SettlementTx {
+            id: 0,
+            nonce: 0,
+            contract_address: Address::repeat_byte(0x06),
+            calldata: vec![],
+            gas_limit: 300_000,
+        };
+        let signed_tx = SignedSettlementTx {
+            tx_hash: receipt.tx_hash,
+            raw_tx: vec![0x05; 10],
+            max_fee_per_gas: 100.into(),
+            max_priority_fee_per_gas: 1.into(),
+        };
+        let mut storage = pool.access_storage().await?;
+        let tx_id = storage
+            .settlement_txs_dal()
+            .insert_tx("message_relay", &settlement_tx, &signed_tx)
+            .await?;
+        let mut dal = storage.relayed_messages_dal();
+        dal.insert_messages(&[message.clone()]).await?;
+        dal.mark_messages_as_sent(&[message.id], tx_id).await?;
+        dal.mark_message_as_delivered(message.id, Some(receipt))
+            .await?;
+
+        let statuses = client.get_relayed_messages(H256::zero()).await?;
+        assert!(statuses.is_empty(), "{statuses:?}");
+
+        let statuses = client.get_relayed_messages(source_tx_hash).await?;
+        assert_eq!(statuses.len(), 1);
+        let status = &statuses[0];
+        assert_eq!(status.id, message.id);
+        assert_eq!(status.direction, MessageDirection::Outbound);
+        assert_eq!(status.sender, message.sender);
+        assert_eq!(status.target, None);
+        assert_eq!(status.data.0, message.data);
+        assert_eq!(status.status, MessageDeliveryStatus::Delivered);
+        assert_eq!(status.attempts, 1);
+        assert_eq!(status.receipt, Some(receipt));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_relayed_messages() {
+    test_http_server(RelayedMessagesTest).await;
+}
+
+#[derive(Debug)]
+struct PendingPriorityOpsTest;
+
+#[async_trait]
+impl HttpTest for PendingPriorityOpsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let settlement_tx_hash = H256::repeat_byte(0x01);
+        let mut storage = pool.access_storage().await?;
+        // Only the first priority operation is tracked by the bridge watcher.
+        let deposit = BridgeDeposit {
+            settlement_tx_hash,
+            settlement_block_number: 10,
+            priority_op_id: PriorityOpId(0),
+            tx_hash: H256::from_low_u64_be(1),
+        };
+        storage
+            .bridge_deposits_dal()
+            .replace_deposits(0, &[deposit])
+            .await?;
+        for id in 0..3 {
+            let l1_tx = L1Tx {
+                execute: Execute::default(),
+                common_data: L1TxCommonData {
+                    serial_id: PriorityOpId(id),
+                    gas_limit: 100_000.into(),
+                    eth_hash: H256::from_low_u64_be(id + 0x100),
+                    eth_block: 10,
+                    deadline_block: 1_700_000_000 + id,
+                    canonical_tx_hash: H256::from_low_u64_be(id + 1),
+                    ..L1TxCommonData::default()
+                },
+                received_timestamp_ms: 0,
+            };
+            storage
+                .transactions_dal()
+                .insert_transaction_l1(l1_tx, L1BlockNumber(10))
+                .await;
+        }
+
+        let status = client.get_pending_priority_ops(None).await?;
+        assert_eq!(status.pending_l1_batch, L1BatchNumber(1));
+        assert_eq!(status.pending_count, 3);
+        assert_eq!(status.ops_per_l1_batch, 1);
+        assert_eq!(status.operations.len(), 3);
+        for (i, pending_op) in status.operations.iter().enumerate() {
+            assert_eq!(pending_op.op.priority_op_id, PriorityOpId(i as u64));
+            assert_eq!(pending_op.op.tx_hash, H256::from_low_u64_be(i as u64 + 1));
+            assert_eq!(pending_op.op.settlement_block_number, 10);
+            let expected_deadline = chrono::Utc
+                .timestamp_opt(1_700_000_000 + i as i64, 0)
+                .unwrap();
+            assert_eq!(pending_op.op.deadline, Some(expected_deadline));
+            assert_eq!(pending_op.op.miniblock_number, None);
+            assert_eq!(pending_op.estimated_l1_batch, L1BatchNumber(i as u32 + 1));
+        }
+        assert_eq!(
+            status.operations[0].op.settlement_tx_hash,
+            Some(settlement_tx_hash)
+        );
+        assert_eq!(status.operations[1].op.settlement_tx_hash, None);
+
+        let status = client.get_pending_priority_ops(Some(2)).await?;
+        assert_eq!(status.pending_count, 3);
+        assert_eq!(status.operations.len(), 2);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_pending_priority_ops() {
+    test_http_server(PendingPriorityOpsTest).await;
+}
+
+#[derive(Debug)]
+struct L1BatchProofStatusTest;
+
+#[async_trait]
+impl HttpTest for L1BatchProofStatusTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        let status = client.get_l1_batch_proof_status(L1BatchNumber(1)).await?;
+        assert!(status.is_none(), "{status:?}");
+
+        let prove_tx_hash = H256::repeat_byte(0x01);
+        storage
+            .proof_verifications_dal()
+            .save_verification(
+                L1BatchNumber(1),
+                prove_tx_hash,
+                ProofVerificationStatus::Invalid,
+                Some("verifier rejected proof"),
+            )
+            .await?;
+        let status = client
+            .get_l1_batch_proof_status(L1BatchNumber(1))
+            .await?
+            .context("no proof status for L1 batch #1")?;
+        assert_eq!(status.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(status.prove_tx_hash, prove_tx_hash);
+        assert_eq!(status.status, ProofVerificationStatus::Invalid);
+        assert_eq!(status.details.as_deref(), Some("verifier rejected proof"));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_l1_batch_proof_status() {
+    test_http_server(L1BatchProofStatusTest).await;
+}
+
+#[derive(Debug)]
+struct ForcedInclusionStatusTest;
+
+#[async_trait]
+impl HttpTest for ForcedInclusionStatusTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let status = client
+            .get_forced_inclusion_status()
+            .await?
+            .context("no forced inclusion status")?;
+        let expected_deadline = StateKeeperConfig::for_tests().forced_inclusion_deadline();
+        assert_eq!(status.deadline_sec, expected_deadline.as_secs());
+        assert_eq!(status.unexecuted_count, 0);
+        assert_eq!(status.overdue_count, 0);
+        assert_eq!(status.oldest_unexecuted_op, None);
+
+        let mut storage = pool.access_storage().await?;
+        // The first operation is received long ago and is overdue; the second one is received just now.
+        let received_timestamps_ms = [0, unix_timestamp_ms()];
+        for (id, received_timestamp_ms) in (0..).zip(received_timestamps_ms) {
+            let l1_tx = L1Tx {
+                execute: Execute::default(),
+                common_data: L1TxCommonData {
+                    serial_id: PriorityOpId(id),
+                    gas_limit: 100_000.into(),
+                    eth_hash: H256::from_low_u64_be(id + 0x100),
+                    eth_block: 10,
+                    canonical_tx_hash: H256::from_low_u64_be(id + 1),
+                    ..L1TxCommonData::default()
+                },
+                received_timestamp_ms,
+            };
+            storage
+                .transactions_dal()
+                .insert_transaction_l1(l1_tx, L1BlockNumber(10))
+                .await;
+        }
+
+        let status = client
+            .get_forced_inclusion_status()
+            .await?
+            .context("no forced inclusion status")?;
+        assert_eq!(status.unexecuted_count, 2);
+        assert_eq!(status.overdue_count, 1);
+        let oldest_op = status
+            .oldest_unexecuted_op
+            .context("no oldest unexecuted op")?;
+        assert_eq!(oldest_op.priority_op_id, PriorityOpId(0));
+        assert_eq!(oldest_op.tx_hash, H256::from_low_u64_be(1));
+        assert_eq!(oldest_op.miniblock_number, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_forced_inclusion_status() {
+    test_http_server(ForcedInclusionStatusTest).await;
+}
+
+#[derive(Debug)]
+struct NameResolutionTest;
+
+impl NameResolutionTest {
+    const NAME: &'static str = "alice.idexo";
+    const ADDRESS: Address = Address::repeat_byte(0xa1);
+}
+
+#[async_trait]
+impl HttpTest for NameResolutionTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let node = namehash(Self::NAME).unwrap();
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(move |tx, _| {
+            let calldata = tx.execute.calldata();
+            let output = if tx.recipient_account() == NAME_REGISTRY_ADDRESS {
+                assert_eq!(calldata[..4], ADDR_SELECTOR);
+                let address = if calldata[4..] == *node.as_bytes() {
+                    Self::ADDRESS
+                } else {
+                    Address::zero()
+                };
+                address_to_h256(&address).0.to_vec()
+            } else {
+                assert_eq!(tx.recipient_account(), Self::ADDRESS);
+                b"output".to_vec()
+            };
+            ExecutionResult::Success { output }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let address = client.resolve_name(Self::NAME.to_owned()).await?;
+        assert_eq!(address, Some(Self::ADDRESS));
+        let address = client.resolve_name("bob.idexo".to_owned()).await?;
+        assert_eq!(address, None);
+        let err = client
+            .resolve_name("alice..idexo".to_owned())
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+        );
+
+        // Names in address params are resolved.
+        let balance: U256 = client
+            .request("eth_getBalance", rpc_params![Self::NAME, "latest"])
+            .await?;
+        let expected_balance = client.get_balance(Self::ADDRESS, None).await?;
+        assert_eq!(balance, expected_balance);
+
+        let call_request = serde_json::json!({ "to": Self::NAME, "data": "0x" });
+        let output: Bytes = client
+            .request("eth_call", rpc_params![call_request])
+            .await?;
+        assert_eq!(output.0, b"output");
+
+        let err = client
+            .request::<U256, _>("eth_getBalance", rpc_params!["bob.idexo", "latest"])
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+                && err.message().contains("bob.idexo")
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn resolving_names() {
+    test_http_server(NameResolutionTest).await;
+}
+
+#[derive(Debug)]
+struct MulticallReadTest;
+
+impl MulticallReadTest {
+    const CONTRACT: Address = Address::repeat_byte(0x11);
+    const REVERTING_CONTRACT: Address = Address::repeat_byte(0x12);
+}
+
+#[async_trait]
+impl HttpTest for MulticallReadTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(|tx, block_args| {
+            assert_eq!(block_args.resolved_block_number(), MiniblockNumber(1));
+            if tx.recipient_account() == Self::REVERTING_CONTRACT {
+                ExecutionResult::Revert {
+                    output: VmRevertReason::VmError,
+                }
+            } else {
+                assert_eq!(tx.recipient_account(), Self::CONTRACT);
+                ExecutionResult::Success {
+                    output: tx.execute.calldata().to_vec(),
+                }
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        let storage_log = StorageLog::new_write_log(
+            StorageKey::new(AccountTreeId::new(Self::CONTRACT), H256::zero()),
+            H256::repeat_byte(0xff),
+        );
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), vec![storage_log])])
+            .await?;
+        drop(storage);
+
+        let call = |to, data: &[u8]| {
+            MulticallRead::Call(CallRequest {
+                to: Some(to),
+                data: Some(data.to_vec().into()),
+                ..CallRequest::default()
+            })
+        };
+        let reads = vec![
+            call(Self::CONTRACT, b"first"),
+            MulticallRead::Storage {
+                address: Self::CONTRACT,
+                keys: vec![0.into(), 1.into()],
+            },
+            call(Self::REVERTING_CONTRACT, b"second"),
+            call(Self::CONTRACT, b"third"),
+        ];
+        let block = api::BlockIdVariant::BlockNumber(api::BlockNumber::Latest);
+        let results = client.multicall_read(reads, Some(block)).await?;
+        assert_eq!(results.len(), 4);
+        assert_matches!(&results[0], MulticallReadResult::Output(output) if output.0 == b"first");
+        assert_matches!(
+            &results[1],
+            MulticallReadResult::StorageValues(values)
+                if *values == [H256::repeat_byte(0xff), H256::zero()]
+        );
+        assert_matches!(&results[2], MulticallReadResult::Error(_));
+        assert_matches!(&results[3], MulticallReadResult::Output(output) if output.0 == b"third");
+
+        let too_many_reads = vec![MulticallRead::Storage {
+            address: Self::CONTRACT,
+            keys: vec![U256::zero(); 10_001],
+        }];
+        let err = client
+            .multicall_read(too_many_reads, Some(block))
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn multicall_read() {
+    test_http_server(MulticallReadTest).await;
+}
+
+#[derive(Debug)]
+struct BatchStateDiffsTest;
+
+#[async_trait]
+impl HttpTest for BatchStateDiffsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let keys = [
+            StorageKey::new(account, H256::zero()),
+            StorageKey::new(account, H256::repeat_byte(1)),
+        ];
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        let storage_logs = vec![
+            StorageLog::new_write_log(keys[0], H256::repeat_byte(0xaa)),
+            StorageLog::new_write_log(keys[1], H256::repeat_byte(0xbb)),
+            // Overwrites the first write; only the final value must be returned.
+            StorageLog::new_write_log(keys[0], H256::repeat_byte(0xcc)),
+        ];
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), storage_logs)])
+            .await?;
+        let bytecode = vec![0_u8; 32];
+        let bytecode_hash = H256::repeat_byte(0xdd);
+        storage
+            .factory_deps_dal()
+            .insert_factory_deps(
+                MiniblockNumber(1),
+                &HashMap::from([(bytecode_hash, bytecode.clone())]),
+            )
+            .await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        storage
+            .storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(1), &keys)
+            .await?;
+        drop(storage);
+
+        let diffs = client
+            .get_batch_state_diffs(L1BatchNumber(1), None, None)
+            .await?
+            .context("no state diffs for L1 batch #1")?;
+        assert_eq!(diffs.l1_batch_number, L1BatchNumber(1));
+        assert!(diffs.compressed_state_diffs.is_some());
+        assert_eq!(diffs.total_storage_diffs, 2);
+        let mut expected_keys: Vec<_> = keys.iter().map(StorageKey::hashed_key).collect();
+        expected_keys.sort_unstable();
+        let diff_keys: Vec<_> = diffs
+            .storage_diffs
+            .iter()
+            .map(|diff| diff.hashed_key)
+            .collect();
+        assert_eq!(diff_keys, expected_keys);
+        let first_diff = diffs
+            .storage_diffs
+            .iter()
+            .find(|diff| diff.hashed_key == keys[0].hashed_key())
+            .unwrap();
+        assert_eq!(first_diff.address, *account.address());
+        assert_eq!(first_diff.key, *keys[0].key());
+        assert_eq!(first_diff.value, H256::repeat_byte(0xcc));
+        assert!(first_diff.is_initial);
+        assert!(first_diff.enumeration_index.is_some());
+        assert_eq!(diffs.total_deployed_bytecodes, 1);
+        assert_eq!(diffs.deployed_bytecodes.len(), 1);
+        assert_eq!(diffs.deployed_bytecodes[0].bytecode_hash, bytecode_hash);
+        assert_eq!(diffs.deployed_bytecodes[0].bytecode.0, bytecode);
+
+        let second_page = client
+            .get_batch_state_diffs(L1BatchNumber(1), Some(1), Some(1))
+            .await?
+            .context("no state diffs for L1 batch #1")?;
+        assert_eq!(second_page.compressed_state_diffs, None);
+        assert_eq!(second_page.storage_diffs, diffs.storage_diffs[1..]);
+        assert_eq!(second_page.total_storage_diffs, 2);
+        assert!(second_page.deployed_bytecodes.is_empty());
+        assert_eq!(second_page.total_deployed_bytecodes, 1);
+
+        let missing_diffs = client
+            .get_batch_state_diffs(L1BatchNumber(2), None, None)
+            .await?;
+        assert_eq!(missing_diffs, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_batch_state_diffs() {
+    test_http_server(BatchStateDiffsTest).await;
+}
+
+#[derive(Debug)]
+struct DecodedLogsTest;
+
+#[async_trait]
+impl HttpTest for DecodedLogsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let token_address = Address::repeat_byte(0x10);
+        let transfer_event = transfer_event();
+        let mut storage = pool.access_storage().await?;
+        storage
+            .contract_abis_dal()
+            .register_abi(token_address, &events_abi(&[transfer_event.clone()]))
+            .await?;
+
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(1))
+            .await?;
+        let tx_location = IncludedTxLocation {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::repeat_byte(2),
+        };
+        let sender = Address::repeat_byte(0x11);
+        let recipient = Address::repeat_byte(0x22);
+        let events = [
+            VmEvent {
+                location: (L1BatchNumber(1), 0),
+                address: token_address,
+                indexed_topics: vec![
+                    transfer_event.signature(),
+                    address_to_h256(&sender),
+                    address_to_h256(&recipient),
+                ],
+                value: u256_to_h256(100.into()).as_bytes().to_vec(),
+            },
+            // Event of a contract without a known ABI
+            VmEvent {
+                location: (L1BatchNumber(1), 1),
+                address: Address::repeat_byte(0x20),
+                indexed_topics: vec![transfer_event.signature()],
+                value: vec![],
+            },
+        ];
+        storage
+            .events_dal()
+            .save_events(
+                MiniblockNumber(1),
+                &[(tx_location, events.iter().collect())],
+            )
+            .await;
+        drop(storage);
+
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Number(1.into())),
+            ..Filter::default()
+        };
+        let logs = client.get_decoded_logs(filter).await?;
+        assert_eq!(logs.len(), 2, "{logs:?}");
+        assert_eq!(logs[0].log.address, token_address);
+        let event = logs[0]
+            .event
+            .as_ref()
+            .context("Transfer event is not decoded")?;
+        assert_eq!(event.signature, "Transfer(address,address,uint256)");
+        let values: Vec<_> = event.params.iter().map(|param| &param.value).collect();
+        assert_eq!(
+            values,
+            [
+                &serde_json::json!(format!("{sender:?}")),
+                &serde_json::json!(format!("{recipient:?}")),
+                &serde_json::json!("100"),
+            ]
+        );
+        assert_eq!(logs[1].log.address, Address::repeat_byte(0x20));
+        assert!(logs[1].event.is_none(), "{logs:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_decoded_logs() {
+    test_http_server(DecodedLogsTest).await;
+}
+
+#[derive(Debug)]
+struct L2ToL1LogProofsTest;
+
+#[async_trait]
+impl HttpTest for L2ToL1LogProofsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let tx_results = [
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+        ];
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        let l2_to_l1_log = |tx_number_in_block: u16, value: u8| {
+            UserL2ToL1Log(L2ToL1Log {
+                tx_number_in_block,
+                sender: L1_MESSENGER_ADDRESS,
+                value: H256::repeat_byte(value),
+                ..L2ToL1Log::default()
+            })
+        };
+        let mut l1_batch = create_l1_batch(1);
+        l1_batch.l2_to_l1_logs = vec![l2_to_l1_log(0, 1), l2_to_l1_log(1, 2), l2_to_l1_log(0, 3)];
+        storage.blocks_dal().insert_mock_l1_batch(&l1_batch).await?;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &tx_results)
+            .await;
+        drop(storage);
+
+        let requests = [
+            (tx_results[0].hash, None),
+            (tx_results[0].hash, Some(1)),
+            (tx_results[1].hash, Some(0)),
+            (tx_results[1].hash, Some(1)),
+            (H256::repeat_byte(0xff), None),
+        ];
+        let requests: Vec<_> = requests
+            .into_iter()
+            .map(|(tx_hash, index)| L2ToL1LogProofRequest { tx_hash, index })
+            .collect();
+        let proofs = client.get_l2_to_l1_log_proofs(requests.clone()).await?;
+        assert_eq!(proofs.len(), requests.len());
+
+        let ids: Vec<_> = proofs
+            .iter()
+            .map(|proof| proof.as_ref().map(|proof| proof.id))
+            .collect();
+        assert_eq!(ids, [Some(0), Some(2), Some(1), None, None]);
+        for (request, proof) in requests.iter().zip(&proofs) {
+            let single_proof = client
+                .get_l2_to_l1_log_proof(request.tx_hash, request.index)
+                .await?;
+            assert_eq!(*proof, single_proof, "{request:?}");
+        }
+
+        let too_many_requests = vec![requests[0].clone(); 10_001];
+        let err = client
+            .get_l2_to_l1_log_proofs(too_many_requests)
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_l2_to_l1_log_proofs() {
+    test_http_server(L2ToL1LogProofsTest).await;
+}
+
+#[derive(Debug)]
+struct TransactionTimelineTest;
+
+#[async_trait]
+impl HttpTest for TransactionTimelineTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let pending_tx = create_l2_transaction(10, 200);
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(pending_tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+
+        let timeline = client
+            .get_transaction_timeline(pending_tx.hash())
+            .await?
+            .context("no timeline for pending transaction")?;
+        assert_eq!(timeline.status, api::TransactionStatus::Pending);
+        assert!(timeline.validated_at.is_some());
+        assert_eq!(timeline.added_to_mempool_at, None);
+        assert_eq!(timeline.miniblock_number, None);
+
+        let executed_at = chrono::NaiveDateTime::from_timestamp_opt(1_700_000_000, 0).unwrap();
+        let tx_result = TransactionExecutionResult {
+            executed_at: Some(chrono::DateTime::from_naive_utc_and_offset(
+                executed_at,
+                chrono::Utc,
+            )),
+            ..execute_l2_transaction(create_l2_transaction(10, 200))
+        };
+        store_miniblock(
+            &mut storage,
+            MiniblockNumber(1),
+            slice::from_ref(&tx_result),
+        )
+        .await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+
+        let timeline = client
+            .get_transaction_timeline(tx_result.hash)
+            .await?
+            .context("no timeline for executed transaction")?;
+        assert_eq!(timeline.status, api::TransactionStatus::Included);
+        assert_eq!(timeline.executed_at, tx_result.executed_at);
+        assert_eq!(timeline.miniblock_number, Some(MiniblockNumber(1)));
+        assert_eq!(timeline.l1_batch_number, Some(L1BatchNumber(1)));
+        assert!(timeline.miniblock_sealed_at.is_some());
+        assert!(timeline.l1_batch_sealed_at.is_some());
+        assert_eq!(timeline.committed_at, None);
+        assert_eq!(timeline.executed_on_settlement_at, None);
+
+        let eth_tx = storage
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                AggregatedActionType::Commit,
+                Address::zero(),
+                1_000_000,
+                None,
+                None,
+            )
+            .await?;
+        storage
+            .blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(1)..=L1BatchNumber(1),
+                eth_tx.id,
+                AggregatedActionType::Commit,
+            )
+            .await?;
+        let commit_tx_hash = H256::repeat_byte(1);
+        storage
+            .eth_sender_dal()
+            .insert_tx_history(eth_tx.id, 1, 1, None, commit_tx_hash, &[])
+            .await?;
+        storage
+            .eth_sender_dal()
+            .confirm_tx(commit_tx_hash, 100_000.into())
+            .await?;
+        drop(storage);
+
+        let timeline = client
+            .get_transaction_timeline(tx_result.hash)
+            .await?
+            .context("no timeline for committed transaction")?;
+        assert!(timeline.committed_at.is_some());
+        assert_eq!(timeline.proven_at, None);
+
+        let timeline = client
+            .get_transaction_timeline(H256::repeat_byte(0xff))
+            .await?;
+        assert!(timeline.is_none(), "{timeline:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_transaction_timeline() {
+    test_http_server(TransactionTimelineTest).await;
+}
+
+#[derive(Debug)]
+struct TransactionDiagnosticsTest;
+
+#[async_trait]
+impl HttpTest for TransactionDiagnosticsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let base_fee = client.gas_price().await?.as_u64();
+        assert!(base_fee > 1, "{base_fee}");
+
+        let mut storage = pool.access_storage().await?;
+        let initiator = Address::repeat_byte(1);
+        let mut txs = vec![];
+        for (nonce, fee_per_gas) in [(0, base_fee), (1, base_fee - 1), (3, base_fee)] {
+            let mut tx = create_l2_transaction(fee_per_gas, 200);
+            // Changing transaction fields invalidates its signature, but it's OK for test purposes
+            tx.common_data.nonce = Nonce(nonce);
+            tx.common_data.initiator_address = initiator;
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+            txs.push(tx);
+        }
+        let executed_tx = execute_l2_transaction(create_l2_transaction(10, 200));
+        store_miniblock(
+            &mut storage,
+            MiniblockNumber(1),
+            slice::from_ref(&executed_tx),
+        )
+        .await?;
+        drop(storage);
+
+        let diagnostics = client
+            .diagnose_transaction(TransactionDiagnosticsTarget::Account(initiator))
+            .await?;
+        let hashes: Vec<_> = diagnostics.iter().map(|diag| diag.hash).collect();
+        let expected_hashes: Vec<_> = txs.iter().map(L2Tx::hash).collect();
+        assert_eq!(hashes, expected_hashes);
+        assert_eq!(
+            diagnostics[0].diagnosis,
+            TransactionDiagnosis::AwaitingInclusion { in_mempool: false }
+        );
+        assert_eq!(
+            diagnostics[1].diagnosis,
+            TransactionDiagnosis::FeeBelowFloor {
+                max_fee_per_gas: (base_fee - 1).into(),
+                current_base_fee: base_fee.into(),
+            }
+        );
+        assert_eq!(
+            diagnostics[2].diagnosis,
+            TransactionDiagnosis::NonceGap {
+                account_nonce: Nonce(0),
+                missing_nonce: Nonce(2),
+            }
+        );
+
+        let diagnostics = client
+            .diagnose_transaction(TransactionDiagnosticsTarget::Hash(txs[2].hash()))
+            .await?;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].initiator_address, Some(initiator));
+        assert_eq!(diagnostics[0].nonce, Some(Nonce(3)));
+        assert_matches!(
+            diagnostics[0].diagnosis,
+            TransactionDiagnosis::NonceGap { .. }
+        );
+
+        let diagnostics = client
+            .diagnose_transaction(TransactionDiagnosticsTarget::Hash(executed_tx.hash))
+            .await?;
+        assert_eq!(
+            diagnostics[0].diagnosis,
+            TransactionDiagnosis::AwaitingSettlement {
+                miniblock_number: MiniblockNumber(1),
+                l1_batch_number: None,
+                stage: SettlementStage::BatchSealing,
+            }
+        );
+
+        let diagnostics = client
+            .diagnose_transaction(TransactionDiagnosticsTarget::Hash(H256::repeat_byte(0xff)))
+            .await?;
+        assert_eq!(diagnostics[0].diagnosis, TransactionDiagnosis::NotFound);
+        assert_eq!(diagnostics[0].initiator_address, None);
+
+        // Check that the target can be specified as a raw hash or address.
+        let raw_diagnostics: serde_json::Value = client
+            .request(
+                "idexo_diagnoseTransaction",
+                rpc_params![format!("{initiator:?}")],
+            )
+            .await?;
+        assert_eq!(raw_diagnostics.as_array().unwrap().len(), 3);
+        assert_eq!(raw_diagnostics[2]["diagnosis"]["reason"], "nonceGap");
+        assert_eq!(raw_diagnostics[2]["diagnosis"]["missingNonce"], 2);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn diagnosing_transactions() {
+    test_http_server(TransactionDiagnosticsTest).await;
+}
@@ -10,11 +10,18 @@ use zksync_config::configs::{
     chain::{NetworkConfig, StateKeeperConfig},
     ContractsConfig,
 };
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
+use zksync_dal::{
+    pruning_dal::StorageLogsPruning, transactions_dal::L2TxSubmissionResult, ConnectionPool,
+    StorageProcessor,
+};
 use zksync_health_check::CheckHealth;
 use zksync_types::{
     api,
     block::MiniblockHeader,
+    contract_verification_api::{
+        CompilationArtifacts, CompilerVersions, SourceCodeData, VerificationIncomingRequest,
+        VerificationInfo, VerificationRequest,
+    },
     fee::TransactionExecutionMetrics,
     get_nonce_key,
     l2::L2Tx,
@@ -29,7 +36,10 @@ use zksync_types::{
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::{
-    jsonrpsee::{http_client::HttpClient, types::error::ErrorCode},
+    jsonrpsee::{
+        http_client::{HeaderMap, HeaderValue, HttpClient},
+        types::error::ErrorCode,
+    },
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
@@ -46,14 +56,20 @@ use crate::{
     },
 };
 
+mod admin;
 mod debug;
 mod filters;
+mod idexo;
+mod operator;
 mod snapshots;
 mod vm;
 mod ws;
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(10);
 const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const NAME_REGISTRY_ADDRESS: Address = Address::repeat_byte(0x81);
+/// Auth token for `operator_` and `admin_` namespaces used by test servers.
+const TEST_OPERATOR_AUTH_TOKEN: &str = "operator-secret";
 
 impl ApiServerHandles {
     /// Waits until the server health check reports the ready state. Must be called once per server instance.
@@ -111,6 +127,7 @@ pub(crate) async fn spawn_http_server(
         pool,
         None,
         tx_executor,
+        Some(TEST_OPERATOR_AUTH_TOKEN),
         stop_receiver,
     )
     .await
@@ -129,6 +146,7 @@ async fn spawn_ws_server(
         pool,
         websocket_requests_per_minute_limit,
         MockTransactionExecutor::default(),
+        Some(TEST_OPERATOR_AUTH_TOKEN),
         stop_receiver,
     )
     .await
@@ -140,17 +158,32 @@ async fn spawn_server(
     pool: ConnectionPool,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tx_executor: MockTransactionExecutor,
+    operator_auth_token: Option<&str>,
     stop_receiver: watch::Receiver<bool>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     let contracts_config = ContractsConfig::for_tests();
-    let web3_config = Web3JsonRpcConfig::for_tests();
-    let api_config = InternalApiConfig::new(network_config, &web3_config, &contracts_config);
+    let mut web3_config = Web3JsonRpcConfig::for_tests();
+    web3_config.verify_content_hashes = true;
+    web3_config.name_registry_addr = Some(NAME_REGISTRY_ADDRESS);
+    web3_config.resolve_names_in_params = true;
+    let state_keeper_config = StateKeeperConfig::for_tests();
+    let api_config = InternalApiConfig::new(
+        network_config,
+        &web3_config,
+        &contracts_config,
+        &state_keeper_config,
+    );
     let (tx_sender, vm_barrier) =
         create_test_tx_sender(pool.clone(), api_config.l2_chain_id, tx_executor.into()).await;
     let (pub_sub_events_sender, pub_sub_events_receiver) = mpsc::unbounded_channel();
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
-    namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
+    namespaces.extend([
+        Namespace::Debug,
+        Namespace::Snapshots,
+        Namespace::Operator,
+        Namespace::Admin,
+    ]);
 
     let server_builder = match transport {
         ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
@@ -169,6 +202,7 @@ async fn spawn_server(
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender, vm_barrier)
         .with_pub_sub_events(pub_sub_events_sender)
+        .with_operator_auth_token(operator_auth_token.map(str::to_owned))
         .enable_api_namespaces(namespaces)
         .build(stop_receiver)
         .await
@@ -269,15 +303,23 @@ async fn test_http_server(test: impl HttpTest) {
     .await;
 
     let local_addr = server_handles.wait_until_ready().await;
-    let client = <HttpClient>::builder()
-        .build(format!("http://{local_addr}/"))
-        .unwrap();
+    let client = http_client_with_token(&format!("http://{local_addr}/"), TEST_OPERATOR_AUTH_TOKEN);
     test.test(&client, &pool).await.unwrap();
 
     stop_sender.send_replace(true);
     server_handles.shutdown().await;
 }
 
+fn http_client_with_token(url: &str, auth_token: &str) -> HttpClient {
+    let mut headers = HeaderMap::new();
+    let header_value = HeaderValue::from_str(&format!("Bearer {auth_token}")).unwrap();
+    headers.insert(hyper::header::AUTHORIZATION, header_value);
+    <HttpClient>::builder()
+        .set_headers(headers)
+        .build(url)
+        .unwrap()
+}
+
 fn assert_logs_match(actual_logs: &[api::Log], expected_logs: &[&VmEvent]) {
     assert_eq!(
         actual_logs.len(),
@@ -311,6 +353,7 @@ fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionResult {
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        executed_at: None,
     }
 }
 
@@ -599,6 +642,71 @@ async fn l1_batch_methods_with_snapshot_recovery() {
     test_http_server(L1BatchMethodsWithSnapshotRecovery).await;
 }
 
+#[derive(Debug)]
+struct BlockMethodsAfterPruning;
+
+#[async_trait]
+impl HttpTest for BlockMethodsAfterPruning {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+
+        let details = client.get_block_details(MiniblockNumber(0)).await?;
+        assert!(details.is_some(), "{details:?}");
+
+        // Prune the genesis block while the server is running.
+        storage
+            .pruning_dal()
+            .prune_l1_batches(
+                L1BatchNumber(0),
+                MiniblockNumber(0)..=MiniblockNumber(0),
+                StorageLogsPruning::Overwritten,
+            )
+            .await?;
+        drop(storage);
+
+        // The server should pick up pruning info without a restart.
+        let started_at = Instant::now();
+        let error = loop {
+            assert!(
+                started_at.elapsed() <= TEST_TIMEOUT,
+                "Timed out waiting for pruning info to be updated"
+            );
+            match client.get_block_details(MiniblockNumber(0)).await {
+                Ok(_) => tokio::time::sleep(POLL_INTERVAL).await,
+                Err(err) => break err,
+            }
+        };
+        assert_pruned_block_error(&error, MiniblockNumber(1));
+
+        let error = client
+            .get_block_by_number(api::BlockNumber::Number(0.into()), false)
+            .await
+            .unwrap_err();
+        assert_pruned_block_error(&error, MiniblockNumber(1));
+        let error = client
+            .get_l1_batch_details(L1BatchNumber(0))
+            .await
+            .unwrap_err();
+        assert_pruned_l1_batch_error(&error, L1BatchNumber(1));
+        let error = client
+            .get_l2_to_l1_msg_proof(MiniblockNumber(0), Address::zero(), H256::zero(), None)
+            .await
+            .unwrap_err();
+        assert_pruned_block_error(&error, MiniblockNumber(1));
+
+        let details = client.get_block_details(MiniblockNumber(1)).await?;
+        assert!(details.is_some(), "{details:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn block_methods_after_pruning() {
+    test_http_server(BlockMethodsAfterPruning).await;
+}
+
 #[derive(Debug)]
 struct StorageAccessWithSnapshotRecovery;
 
@@ -858,6 +966,71 @@ async fn transaction_receipts() {
     test_http_server(TransactionReceiptsTest).await;
 }
 
+#[derive(Debug)]
+struct ContentHashVerificationTest;
+
+impl ContentHashVerificationTest {
+    fn assert_internal_error(error: &ClientError) {
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InternalError.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTest for ContentHashVerificationTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let miniblock_number = MiniblockNumber(1);
+        let tx_result = execute_l2_transaction(create_l2_transaction(10, 200));
+        store_miniblock(&mut storage, miniblock_number, &[tx_result.clone()]).await?;
+
+        // Miniblocks without a content hash are served as usual.
+        client.get_transaction_receipt(tx_result.hash).await?;
+
+        let content_hash = storage
+            .content_hashes_dal()
+            .compute_content_hash(miniblock_number)
+            .await?;
+        storage
+            .content_hashes_dal()
+            .set_content_hash(miniblock_number, content_hash)
+            .await?;
+        let block_id = api::BlockId::Number(miniblock_number.0.into());
+        client.get_transaction_receipt(tx_result.hash).await?;
+        client.get_block_receipts(block_id).await?;
+        client
+            .get_block_by_number(miniblock_number.0.into(), false)
+            .await?;
+
+        // Emulate data corruption.
+        storage
+            .content_hashes_dal()
+            .set_content_hash(miniblock_number, H256::repeat_byte(0xff))
+            .await?;
+        let error = client
+            .get_transaction_receipt(tx_result.hash)
+            .await
+            .unwrap_err();
+        Self::assert_internal_error(&error);
+        let error = client.get_block_receipts(block_id).await.unwrap_err();
+        Self::assert_internal_error(&error);
+        let error = client
+            .get_block_by_number(miniblock_number.0.into(), false)
+            .await
+            .unwrap_err();
+        Self::assert_internal_error(&error);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn content_hash_verification() {
+    test_http_server(ContentHashVerificationTest).await;
+}
+
 #[derive(Debug)]
 struct AllAccountBalancesTest;
 
@@ -927,3 +1100,131 @@ impl HttpTest for AllAccountBalancesTest {
 async fn getting_all_account_balances() {
     test_http_server(AllAccountBalancesTest).await;
 }
+
+#[derive(Debug)]
+struct ContractVerificationInfoTest;
+
+impl ContractVerificationInfoTest {
+    const ADDRESS: Address = Address::repeat_byte(0x22);
+
+    fn verification_request() -> VerificationIncomingRequest {
+        VerificationIncomingRequest {
+            contract_address: Self::ADDRESS,
+            source_code_data: SourceCodeData::SolSingleFile("contract Test {}".to_owned()),
+            contract_name: "Test".to_owned(),
+            compiler_versions: CompilerVersions::Solc {
+                compiler_zksolc_version: "v1.3.18".to_owned(),
+                compiler_solc_version: "0.8.20".to_owned(),
+            },
+            optimization_used: true,
+            optimizer_mode: None,
+            constructor_arguments: Default::default(),
+            is_system: false,
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTest for ContractVerificationInfoTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let info = client.get_contract_verification_info(Self::ADDRESS).await?;
+        assert!(info.is_none(), "{info:?}");
+
+        let mut storage = pool.access_storage().await?;
+        let request_id = storage
+            .contract_verification_dal()
+            .add_contract_verification_request(Self::verification_request())
+            .await?;
+        let verified_at = chrono::Utc::now();
+        let verification_info = VerificationInfo {
+            request: VerificationRequest {
+                id: request_id,
+                req: Self::verification_request(),
+            },
+            artifacts: CompilationArtifacts {
+                bytecode: vec![0; 32],
+                abi: serde_json::json!([]),
+            },
+            verified_at,
+        };
+        storage
+            .contract_verification_dal()
+            .save_verification_info(verification_info)
+            .await?;
+
+        let info = client
+            .get_contract_verification_info(Self::ADDRESS)
+            .await?
+            .context("no verification info")?;
+        assert_eq!(info.request.id, request_id);
+        assert_eq!(info.request.req.contract_address, Self::ADDRESS);
+        assert_eq!(info.request.req.contract_name, "Test");
+        assert_eq!(info.artifacts.bytecode, [0; 32]);
+        assert_eq!(info.verified_at, verified_at);
+
+        let other_info = client
+            .get_contract_verification_info(Address::repeat_byte(0x23))
+            .await?;
+        assert!(other_info.is_none(), "{other_info:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_contract_verification_info() {
+    test_http_server(ContractVerificationInfoTest).await;
+}
+
+#[derive(Debug)]
+struct TreeLagTest;
+
+#[async_trait]
+impl HttpTest for TreeLagTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let tree_lag = client.get_tree_lag().await?;
+        assert_eq!(tree_lag.sealed_l1_batch, Some(L1BatchNumber(0)));
+        assert_eq!(tree_lag.last_processed_l1_batch, Some(L1BatchNumber(0)));
+        assert_eq!(tree_lag.lag, Some(0));
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(1))
+            .await?;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await?;
+
+        let tree_lag = client.get_tree_lag().await?;
+        assert_eq!(tree_lag.sealed_l1_batch, Some(L1BatchNumber(1)));
+        assert_eq!(tree_lag.last_processed_l1_batch, Some(L1BatchNumber(0)));
+        assert_eq!(tree_lag.lag, Some(1));
+        let block = client
+            .get_block_by_number(api::BlockNumber::Merkleized, false)
+            .await?
+            .context("no merkleized block")?;
+        assert_eq!(block.number, 0.into());
+
+        let metadata = create_l1_batch_metadata(1);
+        storage
+            .blocks_dal()
+            .save_l1_batch_tree_data(L1BatchNumber(1), &metadata.tree_data())
+            .await?;
+        let tree_lag = client.get_tree_lag().await?;
+        assert_eq!(tree_lag.last_processed_l1_batch, Some(L1BatchNumber(1)));
+        assert_eq!(tree_lag.lag, Some(0));
+        let block = client
+            .get_block_by_number(api::BlockNumber::Merkleized, false)
+            .await?
+            .context("no merkleized block")?;
+        assert_eq!(block.number, 1.into());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_tree_lag() {
+    test_http_server(TreeLagTest).await;
+}
@@ -0,0 +1,251 @@
+//! Tests for the `operator` Web3 namespace.
+
+use zksync_types::{
+    api::idexo::{AuditAction, AuditLogFilter, RegisteredToken},
+    tokens::ETHEREUM_ADDRESS,
+};
+use zksync_web3_decl::{
+    namespaces::{IdexoNamespaceClient, OperatorNamespaceClient},
+    types::Token,
+};
+
+use super::*;
+use crate::api_server::web3::event_decoding::{events_abi, transfer_event};
+
+#[derive(Debug)]
+struct ResumingSettlementTest;
+
+#[async_trait]
+impl HttpTest for ResumingSettlementTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        assert_eq!(client.get_settlement_halt().await?, None);
+        assert_eq!(client.resume_settlement().await?, None);
+
+        let mut storage = pool.access_storage().await?;
+        storage
+            .eth_sender_dal()
+            .halt_settlement("diamond proxy storage is frozen")
+            .await?;
+        let halt = client
+            .get_settlement_halt()
+            .await?
+            .context("settlement is not halted")?;
+        assert_eq!(halt.reason, "diamond proxy storage is frozen");
+        assert_eq!(halt.resumed_at, None);
+
+        let resumed_halt = client
+            .resume_settlement()
+            .await?
+            .context("settlement is not resumed")?;
+        assert_eq!(resumed_halt.id, halt.id);
+        assert!(resumed_halt.resumed_at.is_some());
+        assert_eq!(client.get_settlement_halt().await?, None);
+        let active_halt = storage
+            .eth_sender_dal()
+            .get_active_settlement_halt()
+            .await?;
+        assert_eq!(active_halt, None);
+
+        let audit_log = client.get_audit_log(None).await?;
+        assert_eq!(audit_log.len(), 1, "{audit_log:?}");
+        assert_eq!(audit_log[0].action, AuditAction::SettlementResume);
+        assert_eq!(audit_log[0].actor, "operator_rpc");
+        assert_eq!(
+            audit_log[0].details["reason"],
+            "diamond proxy storage is frozen"
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn resuming_settlement() {
+    test_http_server(ResumingSettlementTest).await;
+}
+
+#[derive(Debug)]
+struct RegisteringTokensTest;
+
+impl RegisteringTokensTest {
+    fn ether() -> RegisteredToken {
+        RegisteredToken {
+            l1_address: ETHEREUM_ADDRESS,
+            l2_address: ETHEREUM_ADDRESS,
+            metadata: TokenMetadata {
+                name: "Ether".to_owned(),
+                symbol: "ETH".to_owned(),
+                decimals: 18,
+            },
+            logo_url: Some("https://example.com/eth.png".to_owned()),
+        }
+    }
+
+    fn usdc() -> RegisteredToken {
+        RegisteredToken {
+            l1_address: Address::repeat_byte(0x11),
+            l2_address: Address::repeat_byte(0x12),
+            metadata: TokenMetadata {
+                name: "USD Coin".to_owned(),
+                symbol: "USDC".to_owned(),
+                decimals: 6,
+            },
+            logo_url: None,
+        }
+    }
+
+    fn confirmed_token(token: &RegisteredToken) -> Token {
+        Token {
+            l1_address: token.l1_address,
+            l2_address: token.l2_address,
+            name: token.metadata.name.clone(),
+            symbol: token.metadata.symbol.clone(),
+            decimals: token.metadata.decimals,
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTest for RegisteringTokensTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        // Ether is marked as a well-known token during genesis.
+        let confirmed_tokens = client.get_confirmed_tokens(0, 100).await?;
+        assert_eq!(confirmed_tokens.len(), 1, "{confirmed_tokens:?}");
+        assert_eq!(confirmed_tokens[0].l2_address, ETHEREUM_ADDRESS);
+        assert_eq!(client.get_token_list().await?, []);
+
+        client.register_token(Self::usdc()).await?;
+        client.register_token(Self::ether()).await?;
+        assert_eq!(
+            client.get_token_list().await?,
+            [Self::ether(), Self::usdc()]
+        );
+        let confirmed_tokens = client.get_confirmed_tokens(0, 100).await?;
+        assert_eq!(
+            confirmed_tokens,
+            [
+                Self::confirmed_token(&Self::ether()),
+                Self::confirmed_token(&Self::usdc())
+            ]
+        );
+        let confirmed_tokens = client.get_confirmed_tokens(1, 100).await?;
+        assert_eq!(confirmed_tokens, [Self::confirmed_token(&Self::usdc())]);
+
+        assert!(client.unregister_token(Self::usdc().l2_address).await?);
+        assert!(!client.unregister_token(Self::usdc().l2_address).await?);
+        assert_eq!(client.get_token_list().await?, [Self::ether()]);
+        let confirmed_tokens = client.get_confirmed_tokens(0, 100).await?;
+        assert_eq!(confirmed_tokens, [Self::confirmed_token(&Self::ether())]);
+
+        // The no-op unregistration is not recorded in the audit log.
+        let audit_log = client.get_audit_log(None).await?;
+        let actions: Vec<_> = audit_log.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            [
+                AuditAction::TokenRegistration,
+                AuditAction::TokenRegistration,
+                AuditAction::TokenUnregistration
+            ]
+        );
+        let filter = AuditLogFilter {
+            action: Some(AuditAction::TokenRegistration),
+            after_id: Some(audit_log[0].id),
+            limit: None,
+        };
+        let audit_log = client.get_audit_log(Some(filter)).await?;
+        assert_eq!(audit_log.len(), 1, "{audit_log:?}");
+        assert_eq!(audit_log[0].details["metadata"]["symbol"], "ETH");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn registering_tokens() {
+    test_http_server(RegisteringTokensTest).await;
+}
+
+#[derive(Debug)]
+struct RegisteringAbisTest;
+
+#[async_trait]
+impl HttpTest for RegisteringAbisTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let address = Address::repeat_byte(0x10);
+        let error = client
+            .register_abi(address, serde_json::json!({ "not": "an ABI" }))
+            .await
+            .unwrap_err();
+        assert_matches!(
+            error,
+            ClientError::Call(error) if error.code() == ErrorCode::InvalidParams.code()
+        );
+
+        let abi = events_abi(&[transfer_event()]);
+        client.register_abi(address, abi.clone()).await?;
+        let mut storage = pool.access_storage().await?;
+        let abis = storage.contract_abis_dal().get_abis(&[address]).await?;
+        assert_eq!(abis, HashMap::from([(address, abi)]));
+
+        assert!(client.unregister_abi(address).await?);
+        assert!(!client.unregister_abi(address).await?);
+        let abis = storage.contract_abis_dal().get_abis(&[address]).await?;
+        assert!(abis.is_empty(), "{abis:?}");
+
+        let audit_log = client.get_audit_log(None).await?;
+        let actions: Vec<_> = audit_log.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            [AuditAction::AbiRegistration, AuditAction::AbiUnregistration]
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn registering_abis() {
+    test_http_server(RegisteringAbisTest).await;
+}
+
+#[tokio::test]
+async fn operator_methods_require_auth_token() {
+    let pool = ConnectionPool::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let mut storage = pool.access_storage().await.unwrap();
+    StorageInitialization::Genesis
+        .prepare_storage(&network_config, &mut storage)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (mut server_handles, _) = spawn_server(
+        ApiTransportLabel::Http,
+        &network_config,
+        pool,
+        None,
+        MockTransactionExecutor::default(),
+        Some(TEST_OPERATOR_AUTH_TOKEN),
+        stop_receiver,
+    )
+    .await;
+    let local_addr = server_handles.wait_until_ready().await;
+    let url = format!("http://{local_addr}/");
+
+    let client = <HttpClient>::builder().build(&url).unwrap();
+    let err = client.get_settlement_halt().await.unwrap_err();
+    assert_matches!(err, ClientError::Transport(_));
+    let err = http_client_with_token(&url, "wrong")
+        .get_settlement_halt()
+        .await
+        .unwrap_err();
+    assert_matches!(err, ClientError::Transport(_));
+    // Methods from other namespaces don't require authentication.
+    client.get_token_list().await.unwrap();
+
+    let authenticated_client = http_client_with_token(&url, TEST_OPERATOR_AUTH_TOKEN);
+    let halt = authenticated_client.get_settlement_halt().await.unwrap();
+    assert_eq!(halt, None);
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
@@ -4,7 +4,10 @@ use std::sync::atomic::{AtomicU32, Ordering};
 
 use multivm::interface::{ExecutionResult, VmRevertReason};
 use zksync_types::{
-    get_intrinsic_constants, transaction_request::CallRequest, L2ChainId, PackedEthSignature, U256,
+    api::idexo::{TxLimitKind, TxLimitViolation},
+    get_intrinsic_constants,
+    transaction_request::CallRequest,
+    L2ChainId, PackedEthSignature, U256,
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::namespaces::DebugNamespaceClient;
@@ -151,29 +154,38 @@ struct SendRawTransactionTest {
 
 impl SendRawTransactionTest {
     fn transaction_bytes_and_hash() -> (Vec<u8>, H256) {
+        Self::transaction_bytes_and_hash_with_input(vec![1, 2, 3, 4])
+    }
+
+    fn transaction_bytes_and_hash_with_input(input: Vec<u8>) -> (Vec<u8>, H256) {
+        let data = Self::signed_transaction_bytes(input, L2ChainId::default().as_u64());
+        let (_, tx_hash) =
+            api::TransactionRequest::from_bytes(&data, L2ChainId::default()).unwrap();
+        (data, tx_hash)
+    }
+
+    /// Signs a legacy transaction for the specified chain. If `chain_id` is 0, the transaction is not replay-protected.
+    fn signed_transaction_bytes(input: Vec<u8>, chain_id: u64) -> Vec<u8> {
         let (private_key, address) = Self::private_key_and_address();
         let tx_request = api::TransactionRequest {
-            chain_id: Some(L2ChainId::default().as_u64()),
+            chain_id: (chain_id != 0).then_some(chain_id),
             from: Some(address),
             to: Some(Address::repeat_byte(2)),
             value: 123_456.into(),
             gas: (get_intrinsic_constants().l2_tx_intrinsic_gas * 2).into(),
             gas_price: StateKeeperConfig::for_tests().minimal_l2_gas_price.into(),
-            input: vec![1, 2, 3, 4].into(),
+            input: input.into(),
             ..api::TransactionRequest::default()
         };
         let mut rlp = Default::default();
-        tx_request.rlp(&mut rlp, L2ChainId::default().as_u64(), None);
+        tx_request.rlp(&mut rlp, chain_id, None);
         let data = rlp.out();
         let signed_message = PackedEthSignature::message_to_signed_bytes(&data);
         let signature = PackedEthSignature::sign_raw(&private_key, &signed_message).unwrap();
 
         let mut rlp = Default::default();
-        tx_request.rlp(&mut rlp, L2ChainId::default().as_u64(), Some(&signature));
-        let data = rlp.out();
-        let (_, tx_hash) =
-            api::TransactionRequest::from_bytes(&data, L2ChainId::default()).unwrap();
-        (data.into(), tx_hash)
+        tx_request.rlp(&mut rlp, chain_id, Some(&signature));
+        rlp.out().into()
     }
 
     fn private_key_and_address() -> (H256, Address) {
@@ -254,6 +266,74 @@ async fn send_raw_transaction_after_snapshot_recovery() {
     .await;
 }
 
+#[derive(Debug)]
+struct SendOversizedTransactionTest;
+
+#[async_trait]
+impl HttpTest for SendOversizedTransactionTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let max_tx_size = Web3JsonRpcConfig::for_tests().max_tx_size;
+        let input = vec![1; max_tx_size];
+        let (tx_bytes, _) = SendRawTransactionTest::transaction_bytes_and_hash_with_input(input);
+        let error = client
+            .send_raw_transaction(tx_bytes.into())
+            .await
+            .unwrap_err();
+        let ClientError::Call(error) = error else {
+            panic!("Unexpected error: {error:?}");
+        };
+        assert_eq!(error.code(), 3);
+        assert!(error.message().starts_with("oversized data"), "{error:?}");
+
+        let violation: TxLimitViolation = serde_json::from_str(error.data().unwrap().get())?;
+        assert_eq!(violation.kind, TxLimitKind::TxSize);
+        assert_eq!(violation.limit, max_tx_size);
+        assert!(violation.actual > max_tx_size, "{violation:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn send_raw_transaction_exceeding_size_limit() {
+    test_http_server(SendOversizedTransactionTest).await;
+}
+
+#[derive(Debug)]
+struct SendTransactionWithoutReplayProtectionTest;
+
+impl SendTransactionWithoutReplayProtectionTest {
+    async fn assert_rejected(client: &HttpClient, tx_bytes: Vec<u8>, expected_message: &str) {
+        let error = client
+            .send_raw_transaction(tx_bytes.into())
+            .await
+            .unwrap_err();
+        let ClientError::Call(error) = error else {
+            panic!("Unexpected error: {error:?}");
+        };
+        assert_eq!(error.code(), 3);
+        assert!(error.message().contains(expected_message), "{error:?}");
+    }
+}
+
+#[async_trait]
+impl HttpTest for SendTransactionWithoutReplayProtectionTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let foreign_chain_id = L2ChainId::default().as_u64() + 1;
+        let tx_bytes = SendRawTransactionTest::signed_transaction_bytes(vec![1], foreign_chain_id);
+        Self::assert_rejected(client, tx_bytes, "wrong chain id").await;
+
+        // Pre-EIP-155 transactions are rejected by default.
+        let tx_bytes = SendRawTransactionTest::signed_transaction_bytes(vec![1], 0);
+        Self::assert_rejected(client, tx_bytes, "replay-protected").await;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn send_raw_transaction_without_replay_protection() {
+    test_http_server(SendTransactionWithoutReplayProtectionTest).await;
+}
+
 #[derive(Debug)]
 struct TraceCallTest;
 
@@ -3,12 +3,20 @@
 use std::collections::HashSet;
 
 use async_trait::async_trait;
+use chrono::Utc;
 use jsonrpsee::core::{client::ClientT, params::BatchRequestBuilder, ClientError};
 use reqwest::StatusCode;
 use tokio::sync::watch;
 use zksync_config::configs::chain::NetworkConfig;
 use zksync_dal::ConnectionPool;
-use zksync_types::{api, Address, L1BatchNumber, H256, U64};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    api::{
+        self,
+        idexo::{BatchLifecycleStage, BatchStatusEvent},
+    },
+    Address, L1BatchNumber, H256, U64,
+};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::client::{Subscription, SubscriptionClientT},
@@ -20,7 +28,10 @@ use zksync_web3_decl::{
 };
 
 use super::*;
-use crate::api_server::web3::metrics::SubscriptionType;
+use crate::api_server::web3::{
+    metrics::SubscriptionType,
+    pubsub::{LagAction, SlowConsumerPolicy},
+};
 
 #[allow(clippy::needless_pass_by_ref_mut)] // false positive
 async fn wait_for_subscription(
@@ -94,6 +105,43 @@ async fn wait_for_notifier_miniblock(
     wait_future.await.expect("Timed out waiting for notifier");
 }
 
+#[test]
+fn checking_subscriber_lag() {
+    let policy = SlowConsumerPolicy {
+        warning_threshold: 2,
+        close_threshold: 5,
+    };
+    let mut warned = false;
+    assert_eq!(policy.check_lag(1, &mut warned), LagAction::None);
+    assert_eq!(policy.check_lag(2, &mut warned), LagAction::Warn);
+    assert!(warned);
+    // The subscriber is warned only once until it catches up.
+    assert_eq!(policy.check_lag(3, &mut warned), LagAction::None);
+    assert_eq!(policy.check_lag(1, &mut warned), LagAction::None);
+    assert_eq!(policy.check_lag(4, &mut warned), LagAction::None);
+    assert_eq!(policy.check_lag(0, &mut warned), LagAction::None);
+    assert!(!warned);
+    assert_eq!(policy.check_lag(2, &mut warned), LagAction::Warn);
+    assert_eq!(policy.check_lag(5, &mut warned), LagAction::Close);
+}
+
+#[test]
+fn validating_slow_consumer_policy() {
+    SlowConsumerPolicy::default().validate().unwrap();
+    let policy = SlowConsumerPolicy {
+        warning_threshold: 128,
+        close_threshold: 2_048,
+    };
+    let err = policy.validate().unwrap_err().to_string();
+    assert!(err.contains("channel capacity"), "{err}");
+    let policy = SlowConsumerPolicy {
+        warning_threshold: 512,
+        close_threshold: 128,
+    };
+    let err = policy.validate().unwrap_err().to_string();
+    assert!(err.contains("warning threshold"), "{err}");
+}
+
 #[tokio::test]
 async fn notifiers_start_after_snapshot_recovery() {
     let pool = ConnectionPool::test_pool().await;
@@ -132,6 +180,7 @@ async fn notifiers_start_after_snapshot_recovery() {
             SubscriptionType::Blocks,
             SubscriptionType::Txs,
             SubscriptionType::Logs,
+            SubscriptionType::BatchStatus,
         ],
     )
     .await;
@@ -624,6 +673,74 @@ async fn log_subscriptions_with_delay() {
     test_ws_server(LogSubscriptionsWithDelayTest).await;
 }
 
+#[derive(Debug)]
+struct BatchStatusSubscriptionTest;
+
+#[async_trait]
+impl WsTest for BatchStatusSubscriptionTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::BatchStatus]).await;
+
+        let mut subscription = client
+            .subscribe::<BatchStatusEvent, _>(
+                "idexo_subscribeBatchStatus",
+                rpc_params![],
+                "idexo_unsubscribeBatchStatus",
+            )
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::BatchStatus).await;
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        drop(storage);
+
+        let event = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for batch status")?
+            .context("Batch status subscription terminated")??;
+        assert_eq!(event.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(event.stage, BatchLifecycleStage::Sealed);
+        assert_eq!(event.tx_hash, None);
+
+        let commit_tx_hash = H256::repeat_byte(0xc0);
+        let committed_at = Utc::now();
+        let mut storage = pool.access_storage().await?;
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Commit,
+                commit_tx_hash,
+                committed_at,
+            )
+            .await?;
+        drop(storage);
+
+        let event = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for batch status")?
+            .context("Batch status subscription terminated")??;
+        assert_eq!(event.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(event.stage, BatchLifecycleStage::Committed);
+        assert_eq!(event.tx_hash, Some(commit_tx_hash));
+        assert_eq!(event.timestamp.timestamp(), committed_at.timestamp());
+
+        subscription.unsubscribe().await?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn batch_status_subscription() {
+    test_ws_server(BatchStatusSubscriptionTest).await;
+}
+
 #[derive(Debug)]
 struct RateLimitingTest;
 
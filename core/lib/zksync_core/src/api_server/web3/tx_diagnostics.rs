@@ -0,0 +1,237 @@
+//! Diagnostics explaining why a transaction is not progressing, used by `idexo_diagnoseTransaction`.
+
+use std::collections::BTreeSet;
+
+use zksync_dal::transactions_web3_dal::UnexecutedL2Tx;
+use zksync_types::{
+    api::idexo::{SettlementStage, TransactionDiagnosis, TransactionTimeline},
+    Nonce, U256,
+};
+
+/// State of an account relevant for diagnosing its unexecuted transactions.
+#[derive(Debug)]
+pub(super) struct AccountState {
+    /// Next nonce of the account according to the latest sealed miniblock.
+    pub nonce: Nonce,
+    /// Nonces of unexecuted transactions of the account that were not rejected by the state keeper.
+    pub pending_nonces: BTreeSet<Nonce>,
+}
+
+impl AccountState {
+    pub fn new(nonce: Nonce, unexecuted_txs: &[UnexecutedL2Tx]) -> Self {
+        let pending_nonces = unexecuted_txs
+            .iter()
+            .filter(|tx| tx.error.is_none())
+            .map(|tx| tx.nonce)
+            .collect();
+        Self {
+            nonce,
+            pending_nonces,
+        }
+    }
+
+    /// Returns the lowest nonce below `nonce` not used by any pending transaction of the account.
+    fn missing_nonce_before(&self, nonce: Nonce) -> Option<Nonce> {
+        // The iteration is bounded by the number of pending nonces since it stops on the first missing nonce.
+        (self.nonce.0..nonce.0)
+            .map(Nonce)
+            .find(|nonce| !self.pending_nonces.contains(nonce))
+    }
+}
+
+/// Diagnoses an L2 transaction that is not included into a miniblock yet. Checks are performed in the order
+/// the state keeper would encounter the problems.
+pub(super) fn diagnose_unexecuted_tx(
+    tx: &UnexecutedL2Tx,
+    account: &AccountState,
+    base_fee: U256,
+) -> TransactionDiagnosis {
+    if let Some(error) = &tx.error {
+        return TransactionDiagnosis::Evicted {
+            error: error.clone(),
+        };
+    }
+    if tx.nonce < account.nonce {
+        return TransactionDiagnosis::NonceAlreadyUsed {
+            account_nonce: account.nonce,
+        };
+    }
+    if let Some(missing_nonce) = account.missing_nonce_before(tx.nonce) {
+        return TransactionDiagnosis::NonceGap {
+            account_nonce: account.nonce,
+            missing_nonce,
+        };
+    }
+    if tx.max_fee_per_gas < base_fee {
+        return TransactionDiagnosis::FeeBelowFloor {
+            max_fee_per_gas: tx.max_fee_per_gas,
+            current_base_fee: base_fee,
+        };
+    }
+    TransactionDiagnosis::AwaitingInclusion {
+        in_mempool: tx.in_mempool,
+    }
+}
+
+/// Diagnoses a transaction based on its timeline. Should be used for transactions included into a miniblock
+/// and for L1-originated transactions, which cannot get stuck because of nonces or fees.
+pub(super) fn diagnose_by_timeline(timeline: &TransactionTimeline) -> TransactionDiagnosis {
+    let Some(miniblock_number) = timeline.miniblock_number else {
+        return TransactionDiagnosis::AwaitingInclusion {
+            in_mempool: timeline.added_to_mempool_at.is_some(),
+        };
+    };
+    let stage = match timeline.l1_batch_number {
+        None => SettlementStage::BatchSealing,
+        Some(_) if timeline.committed_at.is_none() => SettlementStage::Commit,
+        Some(_) if timeline.proven_at.is_none() => SettlementStage::Proof,
+        Some(_) if timeline.executed_on_settlement_at.is_none() => SettlementStage::Execution,
+        Some(l1_batch_number) => return TransactionDiagnosis::Finalized { l1_batch_number },
+    };
+    TransactionDiagnosis::AwaitingSettlement {
+        miniblock_number,
+        l1_batch_number: timeline.l1_batch_number,
+        stage,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use zksync_types::{api::TransactionStatus, L1BatchNumber, MiniblockNumber, H256};
+
+    use super::*;
+
+    fn unexecuted_tx(nonce: u32) -> UnexecutedL2Tx {
+        UnexecutedL2Tx {
+            hash: H256::from_low_u64_be(nonce.into()),
+            nonce: Nonce(nonce),
+            max_fee_per_gas: 100.into(),
+            in_mempool: true,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn diagnosing_unexecuted_txs() {
+        let mut rejected_tx = unexecuted_tx(6);
+        rejected_tx.error = Some("oops".to_owned());
+        let txs = [
+            unexecuted_tx(3),
+            unexecuted_tx(4),
+            rejected_tx.clone(),
+            unexecuted_tx(7),
+        ];
+        let account = AccountState::new(Nonce(3), &txs);
+        assert_eq!(
+            account.pending_nonces,
+            BTreeSet::from([Nonce(3), Nonce(4), Nonce(7)])
+        );
+        let base_fee = U256::from(100);
+
+        let diagnosis = diagnose_unexecuted_tx(&txs[0], &account, base_fee);
+        assert_eq!(
+            diagnosis,
+            TransactionDiagnosis::AwaitingInclusion { in_mempool: true }
+        );
+        let diagnosis = diagnose_unexecuted_tx(&txs[1], &account, base_fee);
+        assert_eq!(
+            diagnosis,
+            TransactionDiagnosis::AwaitingInclusion { in_mempool: true }
+        );
+        let diagnosis = diagnose_unexecuted_tx(&rejected_tx, &account, base_fee);
+        assert_eq!(
+            diagnosis,
+            TransactionDiagnosis::Evicted {
+                error: "oops".to_owned()
+            }
+        );
+        let diagnosis = diagnose_unexecuted_tx(&txs[3], &account, base_fee);
+        assert_eq!(
+            diagnosis,
+            TransactionDiagnosis::NonceGap {
+                account_nonce: Nonce(3),
+                missing_nonce: Nonce(5),
+            }
+        );
+
+        let diagnosis = diagnose_unexecuted_tx(&txs[0], &account, 101.into());
+        assert_eq!(
+            diagnosis,
+            TransactionDiagnosis::FeeBelowFloor {
+                max_fee_per_gas: 100.into(),
+                current_base_fee: 101.into(),
+            }
+        );
+
+        let account = AccountState::new(Nonce(4), &txs[1..]);
+        let diagnosis = diagnose_unexecuted_tx(&txs[0], &account, base_fee);
+        assert_eq!(
+            diagnosis,
+            TransactionDiagnosis::NonceAlreadyUsed {
+                account_nonce: Nonce(4)
+            }
+        );
+    }
+
+    #[test]
+    fn diagnosing_txs_by_timeline() {
+        let mut timeline = TransactionTimeline {
+            hash: H256::repeat_byte(1),
+            is_l1_originated: true,
+            status: TransactionStatus::Pending,
+            miniblock_number: None,
+            l1_batch_number: None,
+            received_at: Utc::now(),
+            validated_at: None,
+            added_to_mempool_at: None,
+            executed_at: None,
+            miniblock_sealed_at: None,
+            l1_batch_sealed_at: None,
+            committed_at: None,
+            proven_at: None,
+            executed_on_settlement_at: None,
+        };
+        assert_eq!(
+            diagnose_by_timeline(&timeline),
+            TransactionDiagnosis::AwaitingInclusion { in_mempool: false }
+        );
+
+        timeline.miniblock_number = Some(MiniblockNumber(5));
+        let expected_diagnosis = TransactionDiagnosis::AwaitingSettlement {
+            miniblock_number: MiniblockNumber(5),
+            l1_batch_number: None,
+            stage: SettlementStage::BatchSealing,
+        };
+        assert_eq!(diagnose_by_timeline(&timeline), expected_diagnosis);
+
+        timeline.l1_batch_number = Some(L1BatchNumber(2));
+        let expected_stages = [
+            SettlementStage::Commit,
+            SettlementStage::Proof,
+            SettlementStage::Execution,
+        ];
+        for stage in expected_stages {
+            let expected_diagnosis = TransactionDiagnosis::AwaitingSettlement {
+                miniblock_number: MiniblockNumber(5),
+                l1_batch_number: Some(L1BatchNumber(2)),
+                stage,
+            };
+            assert_eq!(diagnose_by_timeline(&timeline), expected_diagnosis);
+
+            let stage_timestamp = match stage {
+                SettlementStage::Commit => &mut timeline.committed_at,
+                SettlementStage::Proof => &mut timeline.proven_at,
+                SettlementStage::Execution => &mut timeline.executed_on_settlement_at,
+                SettlementStage::BatchSealing => unreachable!(),
+            };
+            *stage_timestamp = Some(Utc::now());
+        }
+        assert_eq!(
+            diagnose_by_timeline(&timeline),
+            TransactionDiagnosis::Finalized {
+                l1_batch_number: L1BatchNumber(2)
+            }
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Exporter of the audit log of sensitive node actions (block reverts, settlement overrides, token registry changes,
+//! protocol upgrades etc.) to a file.
+//!
+//! Postgres is the source of truth for the audit log: entries are recorded by the components performing
+//! the actions, usually in the same DB transaction as the action itself. The exporter copies new entries
+//! to the configured file as JSON lines, so that they can be shipped to external log storage. The file is
+//! only appended to; on restart, the export continues after the last entry in the file.
+//!
+//! Entries are exported in the `(created_at, id)` order once they are older than the configured lag. IDs and
+//! timestamps are assigned on insertion, but entries only become visible once the recording DB transaction
+//! is committed; the lag ensures that entries committed out of order are not skipped.
+
+use std::{
+    fs,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use tokio::sync::watch;
+use zksync_config::AuditLogConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api::idexo::{AuditAction, AuditLogEntry},
+    web3::signing::keccak256,
+};
+
+/// Position of the last exported entry in the export order.
+type ExportCursor = (DateTime<Utc>, u64);
+
+/// Returns a fingerprint identifying the operator API auth token without revealing it.
+fn auth_token_fingerprint(token: &str) -> String {
+    hex::encode(&keccak256(token.as_bytes())[..8])
+}
+
+/// Records a change of the operator API auth token in the audit log, comparing the token with the last recorded one.
+/// Since the token is a part of the node config, changes can only be detected on startup. Only the token
+/// fingerprint is recorded.
+pub async fn record_operator_auth_token(
+    pool: &ConnectionPool,
+    token: Option<&str>,
+) -> anyhow::Result<()> {
+    let fingerprint = token.map(auth_token_fingerprint);
+    let mut storage = pool.access_storage_tagged("audit_log").await?;
+    let mut transaction = storage.start_transaction().await?;
+    let last_entry = transaction
+        .audit_log_dal()
+        .get_last_entry(AuditAction::OperatorAuthTokenChange)
+        .await?;
+    let last_fingerprint = last_entry.and_then(|entry| {
+        let fingerprint = entry.details.get("fingerprint")?.as_str()?;
+        Some(fingerprint.to_owned())
+    });
+    if last_fingerprint == fingerprint {
+        return Ok(());
+    }
+
+    tracing::info!(
+        "Operator API auth token has changed ({last_fingerprint:?} -> {fingerprint:?}); recording in audit log"
+    );
+    let details = serde_json::json!({ "fingerprint": fingerprint });
+    transaction
+        .audit_log_dal()
+        .insert_entry(
+            AuditAction::OperatorAuthTokenChange,
+            "node_startup",
+            &details,
+        )
+        .await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Copies new audit log entries from Postgres to a file.
+#[derive(Debug)]
+pub struct AuditLogExporter {
+    pool: ConnectionPool,
+    file_path: PathBuf,
+    polling_interval: Duration,
+    export_lag: Duration,
+}
+
+impl AuditLogExporter {
+    /// Maximum number of entries exported in a single iteration.
+    const BATCH_SIZE: usize = 1_000;
+
+    pub fn new(pool: ConnectionPool, config: &AuditLogConfig) -> Self {
+        Self {
+            pool,
+            file_path: config.file_path.clone().into(),
+            polling_interval: config.polling_interval(),
+            export_lag: config.export_lag(),
+        }
+    }
+
+    /// Returns the cursor of the last entry in the file, or `None` if the file doesn't exist or is empty.
+    fn last_exported_cursor(path: &Path) -> anyhow::Result<Option<ExportCursor>> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).context("failed reading audit log file"),
+        };
+        let Some(last_line) = contents.lines().rev().find(|line| !line.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let entry: AuditLogEntry = serde_json::from_str(last_line)
+            .context("failed parsing last entry in audit log file")?;
+        Ok(Some((entry.created_at, entry.id)))
+    }
+
+    fn append_entries(path: &Path, entries: &[AuditLogEntry]) -> anyhow::Result<()> {
+        let mut buffer = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut buffer, entry)?;
+            buffer.push(b'\n');
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .context("failed opening audit log file")?;
+        file.write_all(&buffer)
+            .context("failed writing to audit log file")?;
+        file.sync_data().context("failed syncing audit log file")?;
+        Ok(())
+    }
+
+    /// Exports entries after the specified cursor. Returns the cursor of the last exported entry.
+    async fn export_new_entries(
+        &self,
+        mut after: Option<ExportCursor>,
+    ) -> anyhow::Result<Option<ExportCursor>> {
+        loop {
+            let mut storage = self.pool.access_storage_tagged("audit_log").await?;
+            let entries = storage
+                .audit_log_dal()
+                .get_entries_for_export(after, self.export_lag, Self::BATCH_SIZE)
+                .await?;
+            drop(storage);
+
+            let Some(last_entry) = entries.last() else {
+                return Ok(after);
+            };
+            let last_cursor = (last_entry.created_at, last_entry.id);
+            let is_last_batch = entries.len() < Self::BATCH_SIZE;
+            let path = self.file_path.clone();
+            tokio::task::spawn_blocking(move || Self::append_entries(&path, &entries))
+                .await
+                .context("panicked appending audit log entries")??;
+            tracing::debug!("Exported audit log entries up to #{}", last_cursor.1);
+            after = Some(last_cursor);
+            if is_last_batch {
+                return Ok(after);
+            }
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let path = self.file_path.clone();
+        let mut last_exported =
+            tokio::task::spawn_blocking(move || Self::last_exported_cursor(&path))
+                .await
+                .context("panicked reading audit log file")??;
+        tracing::info!(
+            "Exporting audit log to {:?} starting after entry {last_exported:?}",
+            self.file_path
+        );
+
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, audit log exporter is shutting down");
+                break;
+            }
+
+            match self.export_new_entries(last_exported).await {
+                Ok(cursor) => last_exported = cursor,
+                Err(err) => tracing::warn!("Failed exporting audit log entries: {err:#}"),
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.polling_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn recording_operator_auth_token_changes() {
+        let pool = ConnectionPool::test_pool().await;
+        // The token being disabled initially is not recorded.
+        record_operator_auth_token(&pool, None).await.unwrap();
+        for token in [Some("secret"), Some("secret"), Some("other"), None, None] {
+            record_operator_auth_token(&pool, token).await.unwrap();
+        }
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let entries = storage
+            .audit_log_dal()
+            .get_entries(Some(AuditAction::OperatorAuthTokenChange), 0, 10)
+            .await
+            .unwrap();
+        let fingerprints: Vec<_> = entries
+            .iter()
+            .map(|entry| entry.details["fingerprint"].clone())
+            .collect();
+        let expected_fingerprints = [
+            auth_token_fingerprint("secret").into(),
+            auth_token_fingerprint("other").into(),
+            serde_json::Value::Null,
+        ];
+        assert_eq!(fingerprints, expected_fingerprints);
+    }
+
+    #[tokio::test]
+    async fn exporting_audit_log() {
+        let pool = ConnectionPool::test_pool().await;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = AuditLogConfig {
+            file_path: temp_dir
+                .path()
+                .join("audit.jsonl")
+                .to_str()
+                .unwrap()
+                .to_owned(),
+            polling_interval_ms: 10,
+            export_lag_ms: 0,
+        };
+        let mut exporter = AuditLogExporter::new(pool.clone(), &config);
+        assert_eq!(
+            AuditLogExporter::last_exported_cursor(&exporter.file_path).unwrap(),
+            None
+        );
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let mut ids = vec![];
+        for action in [AuditAction::TokenRegistration, AuditAction::BlockRevert] {
+            let details = serde_json::json!({ "action": action });
+            let id = storage
+                .audit_log_dal()
+                .insert_entry(action, "test", &details)
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+        drop(storage);
+
+        // Entries recorded within the lag are not exported.
+        exporter.export_lag = Duration::from_secs(3_600);
+        assert_eq!(exporter.export_new_entries(None).await.unwrap(), None);
+        assert!(!exporter.file_path.exists());
+
+        exporter.export_lag = Duration::ZERO;
+        let last_cursor = exporter.export_new_entries(None).await.unwrap().unwrap();
+        assert_eq!(last_cursor.1, ids[1]);
+        assert_eq!(
+            AuditLogExporter::last_exported_cursor(&exporter.file_path).unwrap(),
+            Some(last_cursor)
+        );
+        // Re-exporting from the last exported entry doesn't duplicate entries.
+        exporter
+            .export_new_entries(Some(last_cursor))
+            .await
+            .unwrap();
+
+        let contents = fs::read_to_string(&exporter.file_path).unwrap();
+        let entries: Vec<AuditLogEntry> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let exported_ids: Vec<_> = entries.iter().map(|entry| entry.id).collect();
+        assert_eq!(exported_ids, ids);
+        assert_eq!(entries[1].action, AuditAction::BlockRevert);
+        assert_eq!(entries[1].actor, "test");
+    }
+}
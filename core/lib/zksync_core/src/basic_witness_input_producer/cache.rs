@@ -0,0 +1,285 @@
+//! Caching of generated witness inputs in the object store.
+
+use std::{ops, sync::Arc};
+
+use anyhow::Context as _;
+use zksync_dal::StorageProcessor;
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
+use zksync_types::{
+    web3::signing::keccak256,
+    witness_block_state::{WitnessBlockState, WitnessInputCacheEntry},
+    L1BatchNumber, H256,
+};
+
+use super::metrics::{CacheMissReason, METRICS};
+
+/// Computes a fingerprint of the L1 batch contents based on hashes of its miniblocks. The fingerprint changes
+/// if the batch is reverted and then re-created with different contents.
+pub(super) async fn batch_fingerprint(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<H256> {
+    let miniblock_hashes = storage
+        .blocks_dal()
+        .get_miniblock_hashes_of_l1_batch(l1_batch_number)
+        .await
+        .with_context(|| {
+            format!("failed getting miniblock hashes for L1 batch #{l1_batch_number}")
+        })?;
+    anyhow::ensure!(
+        !miniblock_hashes.is_empty(),
+        "L1 batch #{l1_batch_number} has no miniblocks"
+    );
+    let hashed_bytes: Vec<u8> = miniblock_hashes
+        .iter()
+        .flat_map(|hash| hash.as_bytes())
+        .copied()
+        .collect();
+    Ok(H256(keccak256(&hashed_bytes)))
+}
+
+/// Cache of witness inputs ([`WitnessBlockState`]s) persisted in the object store. Each input is stored
+/// together with a [`WitnessInputCacheEntry`] containing the hash of the input blob and the fingerprint
+/// of the L1 batch it was generated for. An input is only reused if both of these match.
+#[derive(Debug, Clone)]
+pub struct WitnessInputCache {
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl WitnessInputCache {
+    pub fn new(object_store: Arc<dyn ObjectStore>) -> Self {
+        Self { object_store }
+    }
+
+    /// Returns the cached witness input for the specified L1 batch if it exists and was generated
+    /// for a batch with the same fingerprint.
+    pub async fn get(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        batch_fingerprint: H256,
+    ) -> anyhow::Result<Option<WitnessBlockState>> {
+        let entry = match self
+            .object_store
+            .get::<WitnessInputCacheEntry>(l1_batch_number)
+            .await
+        {
+            Ok(entry) => entry,
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                METRICS.cache_misses[&CacheMissReason::Missing].inc();
+                return Ok(None);
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!(
+                        "failed getting witness input cache entry for L1 batch #{l1_batch_number}"
+                    )
+                });
+            }
+        };
+        if entry.batch_fingerprint != batch_fingerprint {
+            tracing::info!(
+                "Cached witness input for L1 batch #{l1_batch_number} was generated for a batch with fingerprint \
+                 {:?}, while the current batch has fingerprint {batch_fingerprint:?}; ignoring it",
+                entry.batch_fingerprint
+            );
+            METRICS.cache_misses[&CacheMissReason::FingerprintMismatch].inc();
+            return Ok(None);
+        }
+
+        let key = WitnessBlockState::encode_key(l1_batch_number);
+        let bytes = match self
+            .object_store
+            .get_raw(WitnessBlockState::BUCKET, &key)
+            .await
+        {
+            Ok(bytes) => bytes,
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                tracing::warn!(
+                    "Witness input for L1 batch #{l1_batch_number} is missing even though its cache entry exists"
+                );
+                METRICS.cache_misses[&CacheMissReason::Missing].inc();
+                return Ok(None);
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed getting witness input for L1 batch #{l1_batch_number}")
+                });
+            }
+        };
+        let content_hash = H256(keccak256(&bytes));
+        if content_hash != entry.content_hash {
+            tracing::warn!(
+                "Cached witness input for L1 batch #{l1_batch_number} has unexpected hash {content_hash:?} \
+                 (expected {:?}); ignoring it",
+                entry.content_hash
+            );
+            METRICS.cache_misses[&CacheMissReason::ContentHashMismatch].inc();
+            return Ok(None);
+        }
+        let witness_block_state = WitnessBlockState::deserialize(bytes)
+            .map_err(ObjectStoreError::Serialization)
+            .with_context(|| {
+                format!("failed deserializing witness input for L1 batch #{l1_batch_number}")
+            })?;
+        METRICS.cache_hits.inc();
+        Ok(Some(witness_block_state))
+    }
+
+    /// Uploads the witness input for the specified L1 batch together with its cache entry.
+    /// Returns the object path of the uploaded input.
+    pub async fn put(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        batch_fingerprint: H256,
+        witness_block_state: &WitnessBlockState,
+    ) -> anyhow::Result<String> {
+        let bytes = witness_block_state
+            .serialize()
+            .map_err(ObjectStoreError::Serialization)
+            .context("failed serializing witness input")?;
+        let entry = WitnessInputCacheEntry {
+            l1_batch_number,
+            batch_fingerprint,
+            content_hash: H256(keccak256(&bytes)),
+        };
+        let key = WitnessBlockState::encode_key(l1_batch_number);
+        self.object_store
+            .put_raw(WitnessBlockState::BUCKET, &key, bytes)
+            .await
+            .with_context(|| {
+                format!("failed uploading witness input for L1 batch #{l1_batch_number}")
+            })?;
+        // The cache entry is uploaded after the input, so that it never references a missing or outdated input.
+        self.object_store
+            .put(l1_batch_number, &entry)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed uploading witness input cache entry for L1 batch #{l1_batch_number}"
+                )
+            })?;
+        Ok(key)
+    }
+
+    /// Invalidates cached witness inputs for the specified range of L1 batches. Inputs themselves are not removed;
+    /// they will be overwritten once the corresponding batches are re-created and processed.
+    pub async fn invalidate(
+        &self,
+        l1_batch_numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<()> {
+        let (start, end) = l1_batch_numbers.into_inner();
+        for l1_batch_number in (start.0..=end.0).map(L1BatchNumber) {
+            match self
+                .object_store
+                .remove::<WitnessInputCacheEntry>(l1_batch_number)
+                .await
+            {
+                Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => {}
+                Err(err) => {
+                    return Err(err).with_context(|| {
+                        format!("failed removing witness input cache entry for L1 batch #{l1_batch_number}")
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::{AccountTreeId, Address, StorageKey};
+
+    use super::*;
+
+    fn mock_witness_block_state() -> WitnessBlockState {
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        WitnessBlockState {
+            read_storage_key: [(key, H256::repeat_byte(2))].into(),
+            is_write_initial: [(key, true)].into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn caching_witness_inputs() {
+        let object_store = ObjectStoreFactory::mock().create_store().await;
+        let cache = WitnessInputCache::new(object_store.clone());
+        let l1_batch_number = L1BatchNumber(1);
+        let fingerprint = H256::repeat_byte(0x11);
+        assert!(cache
+            .get(l1_batch_number, fingerprint)
+            .await
+            .unwrap()
+            .is_none());
+
+        let state = mock_witness_block_state();
+        let object_path = cache
+            .put(l1_batch_number, fingerprint, &state)
+            .await
+            .unwrap();
+        assert_eq!(object_path, WitnessBlockState::encode_key(l1_batch_number));
+        let cached_state = cache
+            .get(l1_batch_number, fingerprint)
+            .await
+            .unwrap()
+            .expect("no cached input");
+        assert_eq!(cached_state.read_storage_key, state.read_storage_key);
+        assert_eq!(cached_state.is_write_initial, state.is_write_initial);
+
+        // Inputs generated for different batch contents must not be reused.
+        let other_fingerprint = H256::repeat_byte(0x22);
+        assert!(cache
+            .get(l1_batch_number, other_fingerprint)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Tampered inputs must not be reused either.
+        object_store
+            .put_raw(
+                WitnessBlockState::BUCKET,
+                &object_path,
+                WitnessBlockState::default().serialize().unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(cache
+            .get(l1_batch_number, fingerprint)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn invalidating_witness_inputs() {
+        let object_store = ObjectStoreFactory::mock().create_store().await;
+        let cache = WitnessInputCache::new(object_store);
+        let fingerprint = H256::repeat_byte(0x11);
+        let state = mock_witness_block_state();
+        for number in 1..=3 {
+            cache
+                .put(L1BatchNumber(number), fingerprint, &state)
+                .await
+                .unwrap();
+        }
+
+        // The range intentionally includes a batch without a cached input.
+        cache
+            .invalidate(L1BatchNumber(2)..=L1BatchNumber(4))
+            .await
+            .unwrap();
+        assert!(cache
+            .get(L1BatchNumber(1), fingerprint)
+            .await
+            .unwrap()
+            .is_some());
+        for number in 2..=3 {
+            assert!(cache
+                .get(L1BatchNumber(number), fingerprint)
+                .await
+                .unwrap()
+                .is_none());
+        }
+    }
+}
@@ -2,7 +2,18 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, Gauge, Histogram, Metrics, Unit};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+
+/// Reason why a cached witness input was not reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(crate) enum CacheMissReason {
+    Missing,
+    FingerprintMismatch,
+    ContentHashMismatch,
+}
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "basic_witness_input_producer")]
@@ -12,6 +23,10 @@ pub(crate) struct BasicWitnessInputProducerMetrics {
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub upload_input_time: Histogram<Duration>,
     pub block_number_processed: Gauge,
+    /// Number of L1 batches for which a cached witness input was reused.
+    pub cache_hits: Counter,
+    /// Number of L1 batches for which a cached witness input could not be reused.
+    pub cache_misses: Family<CacheMissReason, Counter>,
 }
 
 #[vise::register]
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Instant};
+use std::time::Instant;
 
 use anyhow::Context;
 use async_trait::async_trait;
@@ -6,23 +6,41 @@ use multivm::interface::{L2BlockEnv, VmInterface};
 use tokio::{runtime::Handle, task::JoinHandle};
 use vm_utils::{create_vm, execute_tx};
 use zksync_dal::{basic_witness_input_producer_dal::JOB_MAX_ATTEMPT, ConnectionPool};
-use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_object_store::{ObjectStoreFactory, StoredObject};
 use zksync_queued_job_processor::JobProcessor;
-use zksync_types::{witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId};
+use zksync_types::{witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId, H256};
 
-use self::metrics::METRICS;
+pub use self::cache::WitnessInputCache;
+use self::{cache::batch_fingerprint, metrics::METRICS};
 
+mod cache;
 mod metrics;
+
+/// Artifacts produced by [`BasicWitnessInputProducer`] for a single L1 batch.
+#[derive(Debug)]
+pub enum BasicWitnessInputProducerArtifacts {
+    /// Witness input was generated by re-executing the batch and needs to be uploaded.
+    Generated {
+        witness_block_state: WitnessBlockState,
+        batch_fingerprint: H256,
+    },
+    /// Witness input was found in the cache and is already uploaded to the object store.
+    Cached { object_path: String },
+}
+
 /// Component that extracts all data (from DB) necessary to run a Basic Witness Generator.
 /// Does this by rerunning an entire L1Batch and extracting information from both the VM run and DB.
 /// This component will upload Witness Inputs to the object store.
 /// This allows Witness Generator workflow (that needs only Basic Witness Generator Inputs)
 /// to be run only using the object store information, having no other external dependency.
+///
+/// Uploaded inputs are cached (see [`WitnessInputCache`]), so that if a batch is processed again
+/// (e.g., after a node restart or a retry), its input is reused instead of being regenerated.
 #[derive(Debug)]
 pub struct BasicWitnessInputProducer {
     connection_pool: ConnectionPool,
     l2_chain_id: L2ChainId,
-    object_store: Arc<dyn ObjectStore>,
+    cache: WitnessInputCache,
 }
 
 impl BasicWitnessInputProducer {
@@ -33,7 +51,7 @@ impl BasicWitnessInputProducer {
     ) -> anyhow::Result<Self> {
         Ok(BasicWitnessInputProducer {
             connection_pool,
-            object_store: store_factory.create_store().await,
+            cache: WitnessInputCache::new(store_factory.create_store().await),
             l2_chain_id,
         })
     }
@@ -44,11 +62,24 @@ impl BasicWitnessInputProducer {
         started_at: Instant,
         connection_pool: ConnectionPool,
         l2_chain_id: L2ChainId,
-    ) -> anyhow::Result<WitnessBlockState> {
+        cache: WitnessInputCache,
+    ) -> anyhow::Result<BasicWitnessInputProducerArtifacts> {
         let mut connection = rt_handle
             .block_on(connection_pool.access_storage())
             .context("failed to get connection for BasicWitnessInputProducer")?;
 
+        let batch_fingerprint =
+            rt_handle.block_on(batch_fingerprint(&mut connection, l1_batch_number))?;
+        if rt_handle
+            .block_on(cache.get(l1_batch_number, batch_fingerprint))?
+            .is_some()
+        {
+            tracing::info!("Reusing cached witness input for l1_batch: {l1_batch_number:?}");
+            return Ok(BasicWitnessInputProducerArtifacts::Cached {
+                object_path: WitnessBlockState::encode_key(l1_batch_number),
+            });
+        }
+
         let miniblocks_execution_data = rt_handle.block_on(
             connection
                 .transactions_dal()
@@ -100,7 +131,10 @@ impl BasicWitnessInputProducer {
         );
 
         let witness_block_state = (*storage_view).borrow().witness_block_state();
-        Ok(witness_block_state)
+        Ok(BasicWitnessInputProducerArtifacts::Generated {
+            witness_block_state,
+            batch_fingerprint,
+        })
     }
 }
 
@@ -108,7 +142,7 @@ impl BasicWitnessInputProducer {
 impl JobProcessor for BasicWitnessInputProducer {
     type Job = L1BatchNumber;
     type JobId = L1BatchNumber;
-    type JobArtifacts = WitnessBlockState;
+    type JobArtifacts = BasicWitnessInputProducerArtifacts;
     const SERVICE_NAME: &'static str = "basic_witness_input_producer";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
@@ -145,6 +179,7 @@ impl JobProcessor for BasicWitnessInputProducer {
     ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
         let l2_chain_id = self.l2_chain_id;
         let connection_pool = self.connection_pool.clone();
+        let cache = self.cache.clone();
         tokio::task::spawn_blocking(move || {
             let rt_handle = Handle::current();
             Self::process_job_impl(
@@ -153,6 +188,7 @@ impl JobProcessor for BasicWitnessInputProducer {
                 started_at,
                 connection_pool.clone(),
                 l2_chain_id,
+                cache,
             )
         })
     }
@@ -163,15 +199,24 @@ impl JobProcessor for BasicWitnessInputProducer {
         started_at: Instant,
         artifacts: Self::JobArtifacts,
     ) -> anyhow::Result<()> {
-        let upload_started_at = Instant::now();
-        let object_path = self
-            .object_store
-            .put(job_id, &artifacts)
-            .await
-            .context("failed to upload artifacts for BasicWitnessInputProducer")?;
-        METRICS
-            .upload_input_time
-            .observe(upload_started_at.elapsed());
+        let object_path = match artifacts {
+            BasicWitnessInputProducerArtifacts::Generated {
+                witness_block_state,
+                batch_fingerprint,
+            } => {
+                let upload_started_at = Instant::now();
+                let object_path = self
+                    .cache
+                    .put(job_id, batch_fingerprint, &witness_block_state)
+                    .await
+                    .context("failed to upload artifacts for BasicWitnessInputProducer")?;
+                METRICS
+                    .upload_input_time
+                    .observe(upload_started_at.elapsed());
+                object_path
+            }
+            BasicWitnessInputProducerArtifacts::Cached { object_path } => object_path,
+        };
         let mut connection = self
             .connection_pool
             .access_storage()
@@ -0,0 +1,890 @@
+//! Portable archives of sealed L1 batches used for disaster recovery.
+//!
+//! # Overview
+//!
+//! Losing the only Postgres instance of the main node is unrecoverable by other means: the settlement layer
+//! only contains batch commitments and (depending on the DA mode) state diffs, but not transactions, receipts
+//! or the data necessary to continue producing batches. An [`L1BatchArchive`] captures everything the node
+//! persists in Postgres for a sealed L1 batch, so that the database can be reconstructed by importing archives
+//! batch by batch on top of the genesis state.
+//!
+//! Archives are produced with [`L1BatchArchive::export()`] and applied with [`L1BatchArchive::import()`];
+//! the `batch_archive` binary wraps both. Batch proofs are stored in the object store rather than in Postgres,
+//! so they are handled separately with [`L1BatchArchive::load_proof()`] and [`L1BatchArchive::store_proof()`].
+//!
+//! # Recovery procedure
+//!
+//! 1. Run the genesis procedure on an empty database with the same genesis parameters as the original chain.
+//! 2. Restore protocol versions by running the node with the Ethereum watcher only (`--components=eth_watcher`).
+//!    Protocol versions are not archived since they are loaded from the settlement layer anyway.
+//! 3. Import archives in the order of L1 batch numbers. Each batch is imported atomically. Import is refused
+//!    if the batch doesn't directly follow the last sealed batch in the storage, if its protocol version is unknown,
+//!    or if the imported data doesn't match miniblock content hashes recorded in the archive.
+//! 4. Start the node as usual. The Merkle tree is rebuilt from the imported storage logs, and the consistency checker
+//!    verifies imported batches against the commitments posted on the settlement layer. Settlement transactions
+//!    are restored as confirmed placeholders (in the same way as on external nodes), so that the ETH sender doesn't
+//!    resubmit operations for imported batches.
+//!
+//! The following data is not archived:
+//!
+//! - Predicted circuit statistics, which are only used as a heuristic by proof generation.
+//! - Fee rebates and other operator-specific accounting data.
+//!
+//! # Archive format
+//!
+//! An archive is a JSON serialization of [`L1BatchArchive`]; binary data such as bytecodes and proofs is
+//! hex-encoded. Archives record their [format version](L1BatchArchive::FORMAT_VERSION); import rejects archives
+//! with an unsupported version.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::StorageProcessor;
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, StoredObject};
+use zksync_prover_interface::outputs::L1BatchProofForL1;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+    circuit::CircuitStatistic,
+    commitment::L1BatchMetadata,
+    event::extract_added_tokens,
+    fee_model::BatchFeeInput,
+    l1::L1Tx,
+    l2::L2Tx,
+    l2_to_l1_log::UserL2ToL1Log,
+    protocol_version::ProtocolUpgradeTx,
+    tx::{tx_execution_info::TxExecutionStatus, IncludedTxLocation, TransactionExecutionResult},
+    vm_trace::Call,
+    zk_evm_types::{LogQuery, Timestamp},
+    Address, Bytes, L1BatchNumber, L1BlockNumber, MiniblockNumber, ProtocolVersionId, StorageKey,
+    StorageLog, Transaction, VmEvent, H256, U256,
+};
+use zksync_utils::h256_to_u256;
+
+use crate::utils::l1_batch_metadata_to_commitment_artifacts;
+
+#[cfg(test)]
+mod tests;
+
+/// Portable archive of a sealed L1 batch. See the module-level docs for details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchArchive {
+    pub format_version: u32,
+    pub header: L1BatchHeader,
+    /// Batch metadata; `None` if the batch was archived before its metadata was calculated.
+    pub metadata: Option<L1BatchMetadata>,
+    pub initial_bootloader_heap: Vec<(usize, U256)>,
+    pub events_queue: Vec<LogQuery>,
+    pub storage_refunds: Vec<u32>,
+    pub predicted_gas: PredictedGas,
+    pub miniblocks: Vec<ArchivedMiniblock>,
+    /// Initial writes in the batch as `(hashed_key, enumeration_index)` tuples, ordered by index.
+    pub initial_writes: Vec<(H256, u64)>,
+    pub protective_reads: Vec<StorageKey>,
+    pub settlement: ArchivedSettlement,
+    /// Serialized batch proof as stored in the object store. Only set if the archive was supplemented with
+    /// a proof using [`Self::load_proof()`].
+    pub proof: Option<Bytes>,
+}
+
+/// Gas predicted to be spent on settlement operations for an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictedGas {
+    pub commit: u32,
+    pub prove: u32,
+    pub execute: u32,
+}
+
+/// Miniblock in an [`L1BatchArchive`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedMiniblock {
+    pub number: MiniblockNumber,
+    pub timestamp: u64,
+    pub hash: H256,
+    pub fee_account_address: Address,
+    pub base_fee_per_gas: u64,
+    pub fee_input: ArchivedFeeInput,
+    pub gas_per_pubdata_limit: u64,
+    pub base_system_contracts_hashes: BaseSystemContractsHashes,
+    pub protocol_version: Option<ProtocolVersionId>,
+    pub virtual_blocks: u32,
+    /// Content hash of the miniblock; `None` if the miniblock was sealed before content hashes were introduced.
+    pub content_hash: Option<H256>,
+    pub transactions: Vec<ArchivedTransaction>,
+    /// Storage writes grouped by transaction, in the order they were applied.
+    pub storage_logs: Vec<(H256, Vec<StorageLog>)>,
+    pub events: Vec<ArchivedEvent>,
+    pub factory_deps: BTreeMap<H256, Bytes>,
+}
+
+/// Fee input of an [`ArchivedMiniblock`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedFeeInput {
+    pub l1_gas_price: u64,
+    pub fair_l2_gas_price: u64,
+    /// Set only for the pubdata-independent fee model.
+    pub fair_pubdata_price: Option<u64>,
+}
+
+impl From<BatchFeeInput> for ArchivedFeeInput {
+    fn from(input: BatchFeeInput) -> Self {
+        Self {
+            l1_gas_price: input.l1_gas_price(),
+            fair_l2_gas_price: input.fair_l2_gas_price(),
+            fair_pubdata_price: match input {
+                BatchFeeInput::L1Pegged(_) => None,
+                BatchFeeInput::PubdataIndependent(input) => Some(input.fair_pubdata_price),
+            },
+        }
+    }
+}
+
+impl From<ArchivedFeeInput> for BatchFeeInput {
+    fn from(input: ArchivedFeeInput) -> Self {
+        match input.fair_pubdata_price {
+            None => Self::l1_pegged(input.l1_gas_price, input.fair_l2_gas_price),
+            Some(fair_pubdata_price) => Self::pubdata_independent(
+                input.l1_gas_price,
+                input.fair_l2_gas_price,
+                fair_pubdata_price,
+            ),
+        }
+    }
+}
+
+/// Transaction in an [`ArchivedMiniblock`] together with its execution outcome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedTransaction {
+    pub transaction: Transaction,
+    /// Error marker; `None` if the transaction has succeeded.
+    pub error: Option<String>,
+    pub refunded_gas: u64,
+    /// Execution metrics of the transaction as persisted by the state keeper.
+    pub execution_info: serde_json::Value,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub call_trace: Option<Call>,
+}
+
+impl ArchivedTransaction {
+    fn to_execution_result(&self) -> anyhow::Result<TransactionExecutionResult> {
+        let execution_info = serde_json::from_value(self.execution_info.clone())
+            .context("cannot deserialize execution info")?;
+        let (revert_reason, call_traces) = match &self.call_trace {
+            Some(call_trace) => (call_trace.revert_reason.clone(), call_trace.calls.clone()),
+            None => (None, vec![]),
+        };
+        Ok(TransactionExecutionResult {
+            transaction: self.transaction.clone(),
+            hash: self.transaction.hash(),
+            execution_info,
+            execution_status: if self.error.is_some() {
+                TxExecutionStatus::Failure
+            } else {
+                TxExecutionStatus::Success
+            },
+            refunded_gas: self.refunded_gas as u32,
+            operator_suggested_refund: 0,
+            compressed_bytecodes: vec![],
+            call_traces,
+            revert_reason,
+            executed_at: self.executed_at,
+        })
+    }
+}
+
+/// Event in an [`ArchivedMiniblock`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedEvent {
+    /// Hash of the emitting transaction; zero for events emitted by the bootloader after the last transaction
+    /// in the batch.
+    pub tx_hash: H256,
+    pub tx_index_in_block: u32,
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+/// Settlement operations for an archived L1 batch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedSettlement {
+    pub commit: Option<ArchivedSettlementTx>,
+    pub prove: Option<ArchivedSettlementTx>,
+    pub execute: Option<ArchivedSettlementTx>,
+}
+
+/// Confirmed settlement layer transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedSettlementTx {
+    pub tx_hash: H256,
+    pub confirmed_at: DateTime<Utc>,
+}
+
+impl ArchivedSettlementTx {
+    fn new(tx_hash: Option<H256>, confirmed_at: Option<DateTime<Utc>>) -> Option<Self> {
+        Some(Self {
+            tx_hash: tx_hash?,
+            confirmed_at: confirmed_at?,
+        })
+    }
+}
+
+impl L1BatchArchive {
+    /// Current version of the archive format.
+    pub const FORMAT_VERSION: u32 = 1;
+
+    pub fn number(&self) -> L1BatchNumber {
+        self.header.number
+    }
+
+    /// Exports the specified sealed L1 batch from the storage. Returns `Ok(None)` if the batch is not sealed.
+    pub async fn export(
+        storage: &mut StorageProcessor<'_>,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<Option<Self>> {
+        let mut storage = storage
+            .start_transaction()
+            .await
+            .context("start_transaction()")?;
+        let Some(header) = storage
+            .blocks_dal()
+            .get_l1_batch_header(number)
+            .await
+            .context("get_l1_batch_header()")?
+        else {
+            return Ok(None);
+        };
+        let metadata = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(number)
+            .await
+            .context("get_l1_batch_metadata()")?
+            .map(|l1_batch| l1_batch.metadata);
+        let initial_bootloader_heap = storage
+            .blocks_dal()
+            .get_initial_bootloader_heap(number)
+            .await
+            .context("get_initial_bootloader_heap()")?
+            .with_context(|| format!("no initial bootloader heap for L1 batch #{number}"))?;
+        let events_queue = storage
+            .blocks_dal()
+            .get_events_queue(number)
+            .await
+            .context("get_events_queue()")?
+            .with_context(|| format!("no events queue for L1 batch #{number}"))?;
+        let storage_refunds = storage
+            .blocks_dal()
+            .get_storage_refunds(number)
+            .await
+            .context("get_storage_refunds()")?
+            .unwrap_or_default(); // Storage refunds are not persisted for old L1 batches
+
+        let mut predicted_gas = [0; 3];
+        let action_types = [
+            AggregatedActionType::Commit,
+            AggregatedActionType::PublishProofOnchain,
+            AggregatedActionType::Execute,
+        ];
+        for (gas, action_type) in predicted_gas.iter_mut().zip(action_types) {
+            *gas = storage
+                .blocks_dal()
+                .get_l1_batches_predicted_gas(number..=number, action_type)
+                .await
+                .with_context(|| format!("get_l1_batches_predicted_gas({action_type:?})"))?;
+        }
+        let [commit, prove, execute] = predicted_gas;
+
+        let (first_miniblock, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(number)
+            .await
+            .context("get_miniblock_range_of_l1_batch()")?
+            .with_context(|| format!("L1 batch #{number} has no miniblocks"))?;
+        let mut miniblocks = vec![];
+        for miniblock_number in first_miniblock.0..=last_miniblock.0 {
+            let miniblock = Self::export_miniblock(&mut storage, MiniblockNumber(miniblock_number))
+                .await
+                .with_context(|| format!("failed exporting miniblock #{miniblock_number}"))?;
+            miniblocks.push(miniblock);
+        }
+
+        let initial_writes = storage
+            .storage_logs_dedup_dal()
+            .initial_writes_for_batch(number)
+            .await;
+        let mut protective_reads: Vec<_> = storage
+            .storage_logs_dedup_dal()
+            .get_protective_reads_for_l1_batch(number)
+            .await
+            .into_iter()
+            .collect();
+        protective_reads.sort_unstable();
+
+        let details = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(number)
+            .await
+            .context("get_l1_batch_details()")?
+            .with_context(|| format!("no details for L1 batch #{number}"))?;
+        let settlement = ArchivedSettlement {
+            commit: ArchivedSettlementTx::new(
+                details.base.commit_tx_hash,
+                details.base.committed_at,
+            ),
+            prove: ArchivedSettlementTx::new(details.base.prove_tx_hash, details.base.proven_at),
+            execute: ArchivedSettlementTx::new(
+                details.base.execute_tx_hash,
+                details.base.executed_at,
+            ),
+        };
+
+        Ok(Some(Self {
+            format_version: Self::FORMAT_VERSION,
+            header,
+            metadata,
+            initial_bootloader_heap,
+            events_queue,
+            storage_refunds,
+            predicted_gas: PredictedGas {
+                commit,
+                prove,
+                execute,
+            },
+            miniblocks,
+            initial_writes,
+            protective_reads,
+            settlement,
+            proof: None,
+        }))
+    }
+
+    async fn export_miniblock(
+        storage: &mut StorageProcessor<'_>,
+        number: MiniblockNumber,
+    ) -> anyhow::Result<ArchivedMiniblock> {
+        let header = storage
+            .blocks_dal()
+            .get_miniblock_header(number)
+            .await
+            .context("get_miniblock_header()")?
+            .context("miniblock disappeared from storage")?;
+        let content_hash = storage
+            .content_hashes_dal()
+            .get_content_hash(number)
+            .await
+            .context("get_content_hash()")?;
+
+        let raw_transactions = storage
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(number)
+            .await
+            .context("get_raw_miniblock_transactions()")?;
+        let executions = storage
+            .batch_archive_dal()
+            .get_tx_executions(number)
+            .await
+            .context("get_tx_executions()")?;
+        anyhow::ensure!(
+            raw_transactions.len() == executions.len(),
+            "mismatch between the number of transactions ({}) and their execution outcomes ({})",
+            raw_transactions.len(),
+            executions.len()
+        );
+        let transactions = raw_transactions
+            .into_iter()
+            .zip(executions)
+            .map(|(transaction, execution)| {
+                anyhow::ensure!(
+                    transaction.hash() == execution.hash,
+                    "transaction order mismatch: {:?} vs {:?}",
+                    transaction.hash(),
+                    execution.hash
+                );
+                Ok(ArchivedTransaction {
+                    transaction,
+                    error: execution.error,
+                    refunded_gas: execution.refunded_gas,
+                    execution_info: execution.execution_info,
+                    executed_at: execution.executed_at,
+                    call_trace: execution.call_trace,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let storage_logs = storage
+            .batch_archive_dal()
+            .get_storage_logs(number)
+            .await
+            .context("get_storage_logs()")?;
+        let events = storage
+            .chain_export_dal()
+            .get_events(number..=number)
+            .await
+            .context("get_events()")?;
+        let events = events
+            .into_iter()
+            .map(|event| ArchivedEvent {
+                tx_hash: event.tx_hash,
+                tx_index_in_block: event.tx_index_in_block,
+                address: event.address,
+                topics: event.topics,
+                data: event.data.into(),
+            })
+            .collect();
+        let factory_deps = storage
+            .batch_archive_dal()
+            .get_factory_deps(number)
+            .await
+            .context("get_factory_deps()")?;
+        let factory_deps = factory_deps
+            .into_iter()
+            .map(|(hash, bytecode)| (hash, bytecode.into()))
+            .collect();
+
+        Ok(ArchivedMiniblock {
+            number: header.number,
+            timestamp: header.timestamp,
+            hash: header.hash,
+            fee_account_address: header.fee_account_address,
+            base_fee_per_gas: header.base_fee_per_gas,
+            fee_input: header.batch_fee_input.into(),
+            gas_per_pubdata_limit: header.gas_per_pubdata_limit,
+            base_system_contracts_hashes: header.base_system_contracts_hashes,
+            protocol_version: header.protocol_version,
+            virtual_blocks: header.virtual_blocks,
+            content_hash,
+            transactions,
+            storage_logs,
+            events,
+            factory_deps,
+        })
+    }
+
+    /// Supplements this archive with the batch proof from the object store, if the proof exists.
+    pub async fn load_proof(&mut self, blob_store: &dyn ObjectStore) -> anyhow::Result<()> {
+        let key = L1BatchProofForL1::encode_key(self.number());
+        self.proof = match blob_store.get_raw(Bucket::ProofsFri, &key).await {
+            Ok(proof) => Some(proof.into()),
+            Err(ObjectStoreError::KeyNotFound(_)) => None,
+            Err(err) => {
+                return Err(anyhow::Error::new(err).context(format!(
+                    "failed loading proof for L1 batch #{}",
+                    self.number()
+                )))
+            }
+        };
+        Ok(())
+    }
+
+    /// Stores the batch proof contained in this archive (if any) to the object store.
+    pub async fn store_proof(&self, blob_store: &dyn ObjectStore) -> anyhow::Result<()> {
+        let Some(proof) = &self.proof else {
+            return Ok(());
+        };
+        let key = L1BatchProofForL1::encode_key(self.number());
+        blob_store
+            .put_raw(Bucket::ProofsFri, &key, proof.0.clone())
+            .await
+            .with_context(|| format!("failed storing proof for L1 batch #{}", self.number()))
+    }
+
+    /// Imports this archive into the storage. The archived batch must directly follow the last sealed L1 batch
+    /// in the storage. `l2_erc20_bridge_addr` is used to restore the list of bridged tokens.
+    pub async fn import(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l2_erc20_bridge_addr: Address,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.format_version == Self::FORMAT_VERSION,
+            "unsupported archive format version {}; expected {}",
+            self.format_version,
+            Self::FORMAT_VERSION
+        );
+        let number = self.number();
+        let mut storage = storage
+            .start_transaction()
+            .await
+            .context("start_transaction()")?;
+        self.check_continuity(&mut storage).await?;
+        if let Some(protocol_version) = self.header.protocol_version {
+            let known_version = storage
+                .protocol_versions_dal()
+                .get_protocol_version(protocol_version)
+                .await;
+            anyhow::ensure!(
+                known_version.is_some(),
+                "protocol version {protocol_version:?} of L1 batch #{number} is unknown; \
+                 it should be loaded from the settlement layer before import"
+            );
+        }
+
+        let mut executed_transactions = vec![];
+        let mut first_tx_index = 0;
+        for (i, miniblock) in self.miniblocks.iter().enumerate() {
+            let is_last = i + 1 == self.miniblocks.len();
+            let tx_results = self
+                .import_miniblock(
+                    &mut storage,
+                    miniblock,
+                    first_tx_index,
+                    is_last,
+                    l2_erc20_bridge_addr,
+                )
+                .await
+                .with_context(|| format!("failed importing miniblock #{}", miniblock.number))?;
+            first_tx_index += tx_results.len();
+            executed_transactions.extend(tx_results);
+        }
+
+        let predicted_gas = BlockGasCount {
+            commit: self.predicted_gas.commit,
+            prove: self.predicted_gas.prove,
+            execute: self.predicted_gas.execute,
+        };
+        storage
+            .blocks_dal()
+            .insert_l1_batch(
+                &self.header,
+                &self.initial_bootloader_heap,
+                predicted_gas,
+                &self.events_queue,
+                &self.storage_refunds,
+                CircuitStatistic::default(),
+            )
+            .await
+            .context("insert_l1_batch()")?;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(number)
+            .await
+            .context("mark_miniblocks_as_executed_in_l1_batch()")?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(number, &executed_transactions)
+            .await;
+
+        let protective_reads: Vec<_> = self
+            .protective_reads
+            .iter()
+            .map(|key| read_log_query(*key))
+            .collect();
+        storage
+            .storage_logs_dedup_dal()
+            .insert_protective_reads(number, &protective_reads)
+            .await
+            .context("insert_protective_reads()")?;
+        storage
+            .storage_logs_dedup_dal()
+            .insert_initial_writes_with_indices(number, &self.initial_writes)
+            .await
+            .context("insert_initial_writes_with_indices()")?;
+
+        if let Some(metadata) = &self.metadata {
+            storage
+                .blocks_dal()
+                .save_l1_batch_tree_data(number, &metadata.tree_data())
+                .await
+                .context("save_l1_batch_tree_data()")?;
+            storage
+                .blocks_dal()
+                .save_l1_batch_commitment_artifacts(
+                    number,
+                    &l1_batch_metadata_to_commitment_artifacts(metadata),
+                )
+                .await
+                .context("save_l1_batch_commitment_artifacts()")?;
+        }
+
+        let settlement_txs = [
+            (AggregatedActionType::Commit, &self.settlement.commit),
+            (
+                AggregatedActionType::PublishProofOnchain,
+                &self.settlement.prove,
+            ),
+            (AggregatedActionType::Execute, &self.settlement.execute),
+        ];
+        for (action_type, tx) in settlement_txs {
+            let Some(tx) = tx else {
+                continue;
+            };
+            storage
+                .eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(number, action_type, tx.tx_hash, tx.confirmed_at)
+                .await
+                .with_context(|| format!("insert_bogus_confirmed_eth_tx({action_type:?})"))?;
+        }
+
+        storage.commit().await.context("commit()")?;
+        tracing::info!(
+            "Imported L1 batch #{number} with {} miniblocks and {} transactions",
+            self.miniblocks.len(),
+            executed_transactions.len()
+        );
+        Ok(())
+    }
+
+    async fn check_continuity(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+        let last_sealed = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")?;
+        let last_sealed = match last_sealed {
+            Some(number) => Some(number),
+            None => storage
+                .snapshot_recovery_dal()
+                .get_applied_snapshot_status()
+                .await
+                .context("get_applied_snapshot_status()")?
+                .map(|status| status.l1_batch_number),
+        };
+        let last_sealed =
+            last_sealed.context("storage is empty; genesis must be performed before import")?;
+        anyhow::ensure!(
+            self.number() == last_sealed + 1,
+            "L1 batch #{} doesn't follow the last sealed L1 batch #{last_sealed} in the storage",
+            self.number()
+        );
+        anyhow::ensure!(
+            !self.miniblocks.is_empty(),
+            "archive contains no miniblocks"
+        );
+        Ok(())
+    }
+
+    /// Imports a single miniblock, mirroring the logic of miniblock sealing in the state keeper.
+    /// Returns execution results of the miniblock transactions.
+    async fn import_miniblock(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        miniblock: &ArchivedMiniblock,
+        first_tx_index: usize,
+        is_last: bool,
+        l2_erc20_bridge_addr: Address,
+    ) -> anyhow::Result<Vec<TransactionExecutionResult>> {
+        let number = miniblock.number;
+        let tx_results = miniblock
+            .transactions
+            .iter()
+            .map(ArchivedTransaction::to_execution_result)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for tx in &tx_results {
+            if let Ok(l1_tx) = L1Tx::try_from(tx.transaction.clone()) {
+                let l1_block_number = L1BlockNumber(l1_tx.common_data.eth_block as u32);
+                storage
+                    .transactions_dal()
+                    .insert_transaction_l1(l1_tx, l1_block_number)
+                    .await;
+            } else if let Ok(l2_tx) = L2Tx::try_from(tx.transaction.clone()) {
+                storage
+                    .transactions_dal()
+                    .insert_transaction_l2(l2_tx, Default::default())
+                    .await;
+            } else if let Ok(upgrade_tx) = ProtocolUpgradeTx::try_from(tx.transaction.clone()) {
+                storage
+                    .transactions_dal()
+                    .insert_system_transaction(upgrade_tx)
+                    .await;
+            } else {
+                anyhow::bail!("transaction {:?} is neither L1 nor L2", tx.hash);
+            }
+        }
+
+        let l1_tx_count = tx_results
+            .iter()
+            .filter(|tx| tx.transaction.is_l1())
+            .count();
+        let header = MiniblockHeader {
+            number,
+            timestamp: miniblock.timestamp,
+            hash: miniblock.hash,
+            l1_tx_count: l1_tx_count as u16,
+            l2_tx_count: (tx_results.len() - l1_tx_count) as u16,
+            fee_account_address: miniblock.fee_account_address,
+            base_fee_per_gas: miniblock.base_fee_per_gas,
+            batch_fee_input: miniblock.fee_input.into(),
+            gas_per_pubdata_limit: miniblock.gas_per_pubdata_limit,
+            base_system_contracts_hashes: miniblock.base_system_contracts_hashes,
+            protocol_version: miniblock.protocol_version,
+            virtual_blocks: miniblock.virtual_blocks,
+        };
+        storage
+            .blocks_dal()
+            .insert_miniblock(&header)
+            .await
+            .context("insert_miniblock()")?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                number,
+                &tx_results,
+                miniblock.base_fee_per_gas.into(),
+            )
+            .await;
+
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(number, &miniblock.storage_logs)
+            .await
+            .context("insert_storage_logs()")?;
+        #[allow(deprecated)] // Will be removed together with the state keeper counterpart
+        storage
+            .storage_dal()
+            .apply_storage_logs(&miniblock.storage_logs)
+            .await;
+
+        if !miniblock.factory_deps.is_empty() {
+            let factory_deps = miniblock
+                .factory_deps
+                .iter()
+                .map(|(hash, bytecode)| (*hash, bytecode.0.clone()))
+                .collect();
+            storage
+                .factory_deps_dal()
+                .insert_factory_deps(number, &factory_deps)
+                .await
+                .context("insert_factory_deps()")?;
+        }
+
+        let events: Vec<_> = miniblock
+            .events
+            .iter()
+            .map(|event| VmEvent {
+                location: (
+                    self.number(),
+                    first_tx_index as u32 + event.tx_index_in_block,
+                ),
+                address: event.address,
+                indexed_topics: event.topics.clone(),
+                value: event.data.0.clone(),
+            })
+            .collect();
+        let added_tokens = extract_added_tokens(l2_erc20_bridge_addr, &events);
+        if !added_tokens.is_empty() {
+            storage
+                .tokens_dal()
+                .add_tokens(&added_tokens)
+                .await
+                .context("add_tokens()")?;
+        }
+        let mut events_by_tx: Vec<(IncludedTxLocation, Vec<&VmEvent>)> = vec![];
+        for (event, archived_event) in events.iter().zip(&miniblock.events) {
+            match events_by_tx.last_mut() {
+                Some((location, events)) if location.tx_hash == archived_event.tx_hash => {
+                    events.push(event);
+                }
+                _ => {
+                    let location = tx_location(
+                        &tx_results,
+                        archived_event.tx_hash,
+                        archived_event.tx_index_in_block,
+                    );
+                    events_by_tx.push((location, vec![event]));
+                }
+            }
+        }
+        storage
+            .events_dal()
+            .save_events(number, &events_by_tx)
+            .await;
+
+        // User L2-to-L1 logs are only stored in the batch header, so they are assigned to miniblocks based on
+        // the transaction index in the batch. Logs emitted after the last transaction belong to the last
+        // (fictive) miniblock.
+        let tx_index_range = if is_last {
+            first_tx_index..usize::MAX
+        } else {
+            first_tx_index..first_tx_index + tx_results.len()
+        };
+        let mut logs_by_tx: Vec<(IncludedTxLocation, Vec<&UserL2ToL1Log>)> = vec![];
+        let miniblock_logs = self
+            .header
+            .l2_to_l1_logs
+            .iter()
+            .filter(|log| tx_index_range.contains(&usize::from(log.0.tx_number_in_block)));
+        for log in miniblock_logs {
+            let tx_index_in_block = (usize::from(log.0.tx_number_in_block) - first_tx_index) as u32;
+            match logs_by_tx.last_mut() {
+                Some((location, logs)) if location.tx_index_in_miniblock == tx_index_in_block => {
+                    logs.push(log);
+                }
+                _ => {
+                    let tx_hash = tx_results
+                        .get(tx_index_in_block as usize)
+                        .map_or_else(H256::zero, |tx| tx.hash);
+                    let location = tx_location(&tx_results, tx_hash, tx_index_in_block);
+                    logs_by_tx.push((location, vec![log]));
+                }
+            }
+        }
+        storage
+            .events_dal()
+            .save_user_l2_to_l1_logs(number, &logs_by_tx)
+            .await;
+
+        let content_hash = storage
+            .content_hashes_dal()
+            .compute_content_hash(number)
+            .await
+            .context("compute_content_hash()")?;
+        if let Some(archived_hash) = miniblock.content_hash {
+            anyhow::ensure!(
+                content_hash == archived_hash,
+                "content hash of the imported miniblock {content_hash:?} differs from the archived one \
+                 {archived_hash:?}; the archive is corrupted"
+            );
+        }
+        storage
+            .content_hashes_dal()
+            .set_content_hash(number, content_hash)
+            .await
+            .context("set_content_hash()")?;
+        Ok(tx_results)
+    }
+}
+
+fn tx_location(
+    tx_results: &[TransactionExecutionResult],
+    tx_hash: H256,
+    tx_index_in_miniblock: u32,
+) -> IncludedTxLocation {
+    let tx_initiator_address = tx_results
+        .iter()
+        .find(|tx| tx.hash == tx_hash)
+        .map_or_else(Address::zero, |tx| tx.transaction.initiator_account());
+    IncludedTxLocation {
+        tx_hash,
+        tx_index_in_miniblock,
+        tx_initiator_address,
+    }
+}
+
+/// Creates a read log query for the storage slot. Only the address and key of the query are persisted
+/// as a protective read.
+fn read_log_query(key: StorageKey) -> LogQuery {
+    LogQuery {
+        timestamp: Timestamp(0),
+        tx_number_in_block: 0,
+        aux_byte: 0,
+        shard_id: 0,
+        address: *key.address(),
+        key: h256_to_u256(*key.key()),
+        read_value: U256::zero(),
+        written_value: U256::zero(),
+        rw_flag: false,
+        rollback: false,
+        is_service: false,
+    }
+}
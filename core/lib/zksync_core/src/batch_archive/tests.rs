@@ -0,0 +1,230 @@
+//! Tests for L1 batch archives.
+
+use std::collections::HashMap;
+
+use zksync_dal::ConnectionPool;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::{AccountTreeId, L2ChainId};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l1_batch_metadata, store_executed_l1_batch_with_messages},
+};
+
+fn storage_key(key: u64) -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(1)),
+        H256::from_low_u64_be(key),
+    )
+}
+
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+}
+
+/// Stores L1 batch #1 with transactions, storage logs, factory deps and metadata.
+async fn store_l1_batch(storage: &mut StorageProcessor<'_>) {
+    let messages = [
+        vec![(Address::repeat_byte(1), b"message".to_vec())],
+        vec![(Address::repeat_byte(2), b"other message".to_vec())],
+    ];
+    let tx_hashes = store_executed_l1_batch_with_messages(storage, 1, &messages).await;
+
+    let storage_logs = vec![
+        (
+            tx_hashes[0],
+            vec![StorageLog::new_write_log(
+                storage_key(1),
+                H256::repeat_byte(1),
+            )],
+        ),
+        (
+            tx_hashes[1],
+            vec![
+                StorageLog::new_write_log(storage_key(2), H256::repeat_byte(2)),
+                StorageLog::new_write_log(storage_key(1), H256::repeat_byte(3)),
+            ],
+        ),
+    ];
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(1), &storage_logs)
+        .await
+        .unwrap();
+    #[allow(deprecated)]
+    storage
+        .storage_dal()
+        .apply_storage_logs(&storage_logs)
+        .await;
+    storage
+        .storage_logs_dedup_dal()
+        .insert_initial_writes(L1BatchNumber(1), &[storage_key(1), storage_key(2)])
+        .await
+        .unwrap();
+    storage
+        .storage_logs_dedup_dal()
+        .insert_protective_reads(L1BatchNumber(1), &[read_log_query(storage_key(3))])
+        .await
+        .unwrap();
+
+    let factory_deps = HashMap::from([(H256::repeat_byte(0xfd), vec![0xfd; 64])]);
+    storage
+        .factory_deps_dal()
+        .insert_factory_deps(MiniblockNumber(1), &factory_deps)
+        .await
+        .unwrap();
+
+    let metadata = create_l1_batch_metadata(1);
+    storage
+        .blocks_dal()
+        .save_l1_batch_tree_data(L1BatchNumber(1), &metadata.tree_data())
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .save_l1_batch_commitment_artifacts(
+            L1BatchNumber(1),
+            &l1_batch_metadata_to_commitment_artifacts(&metadata),
+        )
+        .await
+        .unwrap();
+
+    let content_hash = storage
+        .content_hashes_dal()
+        .compute_content_hash(MiniblockNumber(1))
+        .await
+        .unwrap();
+    storage
+        .content_hashes_dal()
+        .set_content_hash(MiniblockNumber(1), content_hash)
+        .await
+        .unwrap();
+}
+
+async fn export_l1_batch() -> L1BatchArchive {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    store_l1_batch(&mut storage).await;
+
+    L1BatchArchive::export(&mut storage, L1BatchNumber(1))
+        .await
+        .unwrap()
+        .expect("no L1 batch #1")
+}
+
+#[tokio::test]
+async fn exporting_missing_l1_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let archive = L1BatchArchive::export(&mut storage, L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert!(archive.is_none());
+}
+
+#[tokio::test]
+async fn archive_round_trip() {
+    let archive = export_l1_batch().await;
+    assert_eq!(archive.format_version, L1BatchArchive::FORMAT_VERSION);
+    assert_eq!(archive.miniblocks.len(), 1);
+    let miniblock = &archive.miniblocks[0];
+    assert_eq!(miniblock.transactions.len(), 2);
+    assert_eq!(miniblock.storage_logs.len(), 2);
+    assert_eq!(miniblock.events.len(), 2);
+    assert_eq!(miniblock.factory_deps.len(), 1);
+    assert!(miniblock.content_hash.is_some());
+    assert_eq!(archive.initial_writes.len(), 2);
+    assert_eq!(archive.protective_reads, [storage_key(3)]);
+    assert!(archive.metadata.is_some());
+    assert!(archive.settlement.commit.is_none());
+    assert!(archive.settlement.execute.is_some());
+
+    let serialized = serde_json::to_value(&archive).unwrap();
+    let archive: L1BatchArchive = serde_json::from_value(serialized.clone()).unwrap();
+
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    archive.import(&mut storage, Address::zero()).await.unwrap();
+
+    let sealed_l1_batch = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_l1_batch, Some(L1BatchNumber(1)));
+    let reexported_archive = L1BatchArchive::export(&mut storage, L1BatchNumber(1))
+        .await
+        .unwrap()
+        .expect("no imported L1 batch");
+    assert_eq!(
+        serde_json::to_value(&reexported_archive).unwrap(),
+        serialized
+    );
+}
+
+#[tokio::test]
+async fn importing_non_sequential_l1_batch() {
+    let mut archive = export_l1_batch().await;
+    archive.header.number = L1BatchNumber(2);
+
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let err = archive
+        .import(&mut storage, Address::zero())
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("doesn't follow"), "{err}");
+}
+
+#[tokio::test]
+async fn importing_tampered_archive() {
+    let mut archive = export_l1_batch().await;
+    archive.miniblocks[0].transactions[0].refunded_gas += 1;
+
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let err = archive
+        .import(&mut storage, Address::zero())
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("content hash"), "{err}");
+
+    // Import must be atomic.
+    let sealed_l1_batch = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_l1_batch, Some(L1BatchNumber(0)));
+    let miniblock_header = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(1))
+        .await
+        .unwrap();
+    assert!(miniblock_header.is_none());
+}
+
+#[tokio::test]
+async fn proofs_are_transferred_via_object_store() {
+    let mut archive = export_l1_batch().await;
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    archive.load_proof(&*object_store).await.unwrap();
+    assert!(archive.proof.is_none());
+
+    archive.proof = Some(vec![1, 2, 3].into());
+    archive.store_proof(&*object_store).await.unwrap();
+    archive.proof = None;
+    archive.load_proof(&*object_store).await.unwrap();
+    assert_eq!(archive.proof.unwrap().0, [1, 2, 3]);
+}
@@ -12,6 +12,7 @@ use zksync_state::RocksdbStorage;
 use zksync_storage::RocksDB;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
+    api::idexo::AuditAction,
     ethabi::Token,
     web3::{
         contract::{Contract, Options},
@@ -22,6 +23,13 @@ use zksync_types::{
     L1BatchNumber, PackedEthSignature, H160, H256, U256,
 };
 
+pub use self::plan::{RocksdbRollback, RollbackCheckFailure, RollbackReport, SettlementLayerState};
+use crate::basic_witness_input_producer::WitnessInputCache;
+
+mod plan;
+#[cfg(test)]
+mod tests;
+
 bitflags! {
     pub struct BlockReverterFlags: u32 {
         const POSTGRES = 0b_0001;
@@ -83,8 +91,15 @@ impl BlockReverterEthConfig {
 /// There are a few state components that we can roll back
 /// - State of the Postgres database
 /// - State of the merkle tree
-/// - State of the state_keeper cache
+/// - State of the state_keeper cache (together with the API state cache, if its path is provided)
 /// - State of the Ethereum contract (if the block was committed)
+///
+/// Additionally, if a [`WitnessInputCache`] is provided, cached witness inputs for reverted batches
+/// are invalidated together with Postgres data.
+///
+/// Manual rollbacks should be planned with [`Self::plan_rollback()`] first, which reports affected data
+/// and checks the rollback against the settlement layer state without modifying anything. The rollback
+/// should then be confirmed with [`Self::confirm_rollback()`] before performing it.
 #[derive(Debug)]
 pub struct BlockReverter {
     state_keeper_cache_path: String,
@@ -92,6 +107,8 @@ pub struct BlockReverter {
     eth_config: Option<BlockReverterEthConfig>,
     connection_pool: ConnectionPool,
     executed_batches_revert_mode: L1ExecutedBatchesRevert,
+    witness_input_cache: Option<WitnessInputCache>,
+    api_state_cache_path: Option<String>,
 }
 
 impl BlockReverter {
@@ -108,9 +125,22 @@ impl BlockReverter {
             eth_config,
             connection_pool,
             executed_batches_revert_mode,
+            witness_input_cache: None,
+            api_state_cache_path: None,
         }
     }
 
+    /// Sets the witness input cache to invalidate when rolling back Postgres data.
+    pub fn set_witness_input_cache(&mut self, cache: WitnessInputCache) {
+        self.witness_input_cache = Some(cache);
+    }
+
+    /// Sets the path to the API state cache. The cache is a replica of the state keeper cache,
+    /// so it's rolled back together with the state keeper cache.
+    pub fn set_api_state_cache_path(&mut self, path: String) {
+        self.api_state_cache_path = Some(path);
+    }
+
     /// Rolls back DBs (Postgres + RocksDB) to a previous state.
     pub async fn rollback_db(
         &self,
@@ -142,8 +172,30 @@ impl BlockReverter {
         self.rollback_rocks_dbs(last_l1_batch_to_keep, rollback_tree, rollback_sk_cache)
             .await;
         if rollback_postgres {
+            // Witness inputs must be invalidated first since this requires the reverted L1 batches in Postgres.
+            self.invalidate_witness_inputs(last_l1_batch_to_keep).await;
             self.rollback_postgres(last_l1_batch_to_keep).await;
         }
+
+        let details = serde_json::json!({
+            "lastL1BatchToKeep": last_l1_batch_to_keep,
+            "rollbackPostgres": rollback_postgres,
+            "rollbackTree": rollback_tree,
+            "rollbackSkCache": rollback_sk_cache,
+        });
+        self.record_audit_entry(AuditAction::BlockRevert, details)
+            .await;
+    }
+
+    async fn record_audit_entry(&self, action: AuditAction, details: serde_json::Value) {
+        self.connection_pool
+            .access_storage()
+            .await
+            .unwrap()
+            .audit_log_dal()
+            .insert_entry(action, "block_reverter", &details)
+            .await
+            .expect("failed recording audit log entry");
     }
 
     async fn rollback_rocks_dbs(
@@ -175,12 +227,28 @@ impl BlockReverter {
         }
 
         if rollback_sk_cache {
+            let sk_cache_path = Path::new(&self.state_keeper_cache_path);
             assert!(
-                Path::new(&self.state_keeper_cache_path).exists(),
+                sk_cache_path.exists(),
                 "Path with state keeper cache DB doesn't exist"
             );
-            self.rollback_state_keeper_cache(last_l1_batch_to_keep)
+            self.rollback_state_cache(sk_cache_path, "state keeper cache", last_l1_batch_to_keep)
                 .await;
+
+            if let Some(api_state_cache_path) = &self.api_state_cache_path {
+                // Unlike the state keeper cache, the API state cache is optional and is created lazily.
+                let api_state_cache_path = Path::new(api_state_cache_path);
+                if api_state_cache_path.exists() {
+                    self.rollback_state_cache(
+                        api_state_cache_path,
+                        "API state cache",
+                        last_l1_batch_to_keep,
+                    )
+                    .await;
+                } else {
+                    tracing::info!("API state cache not found; skipping");
+                }
+            }
         }
     }
 
@@ -204,25 +272,62 @@ impl BlockReverter {
         tree.save();
     }
 
-    /// Reverts blocks in the state keeper cache.
-    async fn rollback_state_keeper_cache(&self, last_l1_batch_to_keep: L1BatchNumber) {
-        tracing::info!("opening DB with state keeper cache...");
-        let sk_cache = RocksdbStorage::builder(self.state_keeper_cache_path.as_ref())
+    /// Reverts blocks in a state cache (the state keeper cache or the API state cache).
+    async fn rollback_state_cache(
+        &self,
+        path: &Path,
+        name: &str,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) {
+        tracing::info!("opening DB with {name}...");
+        let cache = RocksdbStorage::builder(path)
             .await
-            .expect("Failed initializing state keeper cache");
+            .unwrap_or_else(|err| panic!("Failed initializing {name}: {err:#}"));
 
-        if sk_cache.l1_batch_number().await > Some(last_l1_batch_to_keep + 1) {
+        if cache.l1_batch_number().await > Some(last_l1_batch_to_keep + 1) {
             let mut storage = self.connection_pool.access_storage().await.unwrap();
-            tracing::info!("Rolling back state keeper cache...");
-            sk_cache
+            tracing::info!("Rolling back {name}...");
+            cache
                 .rollback(&mut storage, last_l1_batch_to_keep)
                 .await
-                .expect("Failed rolling back state keeper cache");
+                .unwrap_or_else(|err| panic!("Failed rolling back {name}: {err:#}"));
         } else {
-            tracing::info!("Nothing to revert in state keeper cache");
+            tracing::info!("Nothing to revert in {name}");
         }
     }
 
+    /// Invalidates cached witness inputs for the reverted L1 batches.
+    async fn invalidate_witness_inputs(&self, last_l1_batch_to_keep: L1BatchNumber) {
+        let Some(cache) = &self.witness_input_cache else {
+            return;
+        };
+        let last_sealed_l1_batch = self
+            .connection_pool
+            .access_storage()
+            .await
+            .unwrap()
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        let Some(last_sealed_l1_batch) = last_sealed_l1_batch else {
+            return;
+        };
+        if last_sealed_l1_batch <= last_l1_batch_to_keep {
+            tracing::info!("Nothing to invalidate in witness input cache");
+            return;
+        }
+
+        tracing::info!(
+            "invalidating cached witness inputs for L1 batches #{}..=#{last_sealed_l1_batch}...",
+            last_l1_batch_to_keep + 1
+        );
+        cache
+            .invalidate(last_l1_batch_to_keep + 1..=last_sealed_l1_batch)
+            .await
+            .expect("failed invalidating witness input cache");
+    }
+
     /// Reverts data in the Postgres database.
     async fn rollback_postgres(&self, last_l1_batch_to_keep: L1BatchNumber) {
         tracing::info!("rolling back postgres data...");
@@ -351,6 +456,13 @@ impl BlockReverter {
             .send_raw_transaction(signed_tx.into())
             .await
             .unwrap();
+        let details = serde_json::json!({
+            "lastL1BatchToKeep": last_l1_batch_to_keep,
+            "txHash": hash,
+            "nonce": nonce,
+        });
+        self.record_audit_entry(AuditAction::SettlementRevert, details)
+            .await;
 
         loop {
             if let Some(receipt) = web3.eth().transaction_receipt(hash).await.unwrap() {
@@ -439,6 +551,8 @@ impl BlockReverter {
             .clear_failed_transactions()
             .await
             .unwrap();
+        self.record_audit_entry(AuditAction::FailedL1TxsCleared, serde_json::json!({}))
+            .await;
     }
 
     pub fn change_rollback_executed_l1_batches_allowance(
@@ -0,0 +1,446 @@
+//! Dry-run reports and safety checks for rollbacks.
+//!
+//! A rollback is planned with [`BlockReverter::plan_rollback()`], which doesn't modify any data. The returned
+//! [`RollbackReport`] lists the data affected by the rollback, the settlement layer state, and failed safety checks.
+//! The report contains a confirmation token derived from its contents; [`BlockReverter::confirm_rollback()`]
+//! re-plans the rollback and only succeeds if the token is unchanged and all checks pass. Thus, a rollback
+//! can only be performed after the operator has reviewed the report for exactly the same parameters and state.
+
+use std::{fmt, path::Path};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use zksync_dal::blocks_dal::RowsToRevert;
+use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_state::RocksdbStorage;
+use zksync_storage::RocksDB;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    web3::{
+        contract::{Contract, Options},
+        signing::keccak256,
+        transports::Http,
+        Web3,
+    },
+    L1BatchNumber, MiniblockNumber, H256, U256,
+};
+
+use super::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert};
+use crate::eth_sender::stored_batch_hash;
+
+/// Report on the effects of a rollback produced by [`BlockReverter::plan_rollback()`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackReport {
+    pub last_l1_batch_to_keep: L1BatchNumber,
+    pub last_miniblock_to_keep: MiniblockNumber,
+    pub last_sealed_l1_batch: L1BatchNumber,
+    pub rollback_postgres: bool,
+    pub rollback_tree: bool,
+    pub rollback_sk_cache: bool,
+    /// Postgres rows removed or reset by the rollback; `None` if Postgres is not rolled back.
+    pub postgres: Option<RowsToRevert>,
+    /// Number of L1 batches for which cached witness inputs are removed from the object store.
+    /// `None` if the cache is not configured or Postgres is not rolled back.
+    pub witness_inputs_to_invalidate: Option<u32>,
+    /// Merkle tree versions removed by the rollback; `None` if the tree is not rolled back or doesn't exist.
+    pub tree: Option<RocksdbRollback>,
+    /// State keeper cache changes; `None` if the cache is not rolled back or doesn't exist.
+    pub state_keeper_cache: Option<RocksdbRollback>,
+    /// API state cache changes; `None` if the state keeper cache is not rolled back, or if the API state cache
+    /// is not configured or doesn't exist.
+    pub api_state_cache: Option<RocksdbRollback>,
+    /// Settlement layer state; `None` if the reverter has no settlement layer config (e.g., on the external node).
+    pub settlement_layer: Option<SettlementLayerState>,
+    /// Failed safety checks. A rollback cannot be confirmed if any checks fail.
+    pub failed_checks: Vec<RollbackCheckFailure>,
+    /// Token that must be supplied to [`BlockReverter::confirm_rollback()`] to confirm the rollback.
+    pub confirmation_token: String,
+}
+
+/// Changes to a RocksDB instance performed by a rollback.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RocksdbRollback {
+    /// Next L1 batch to be processed by the instance before the rollback.
+    pub next_l1_batch: L1BatchNumber,
+    /// Number of L1 batches reverted in the instance.
+    pub l1_batches_to_revert: u32,
+}
+
+impl RocksdbRollback {
+    fn new(next_l1_batch: L1BatchNumber, last_l1_batch_to_keep: L1BatchNumber) -> Self {
+        Self {
+            next_l1_batch,
+            l1_batches_to_revert: next_l1_batch.0.saturating_sub(last_l1_batch_to_keep.0 + 1),
+        }
+    }
+}
+
+/// Settlement layer state relevant for a rollback.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementLayerState {
+    pub last_committed_l1_batch: L1BatchNumber,
+    pub last_proven_l1_batch: L1BatchNumber,
+    pub last_executed_l1_batch: L1BatchNumber,
+    /// Hash of the target L1 batch stored by the settlement layer contract; `None` if the batch is not committed.
+    pub target_l1_batch_hash: Option<H256>,
+}
+
+/// Failed safety check for a rollback.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RollbackCheckFailure {
+    /// The target L1 batch is not sealed locally.
+    #[serde(rename_all = "camelCase")]
+    TargetNotSealed { last_sealed_l1_batch: L1BatchNumber },
+    /// The rollback reverts L1 batches executed on the settlement layer, and this isn't allowed.
+    #[serde(rename_all = "camelCase")]
+    ExecutedL1BatchesReverted {
+        last_executed_l1_batch: L1BatchNumber,
+    },
+    /// Hash of the target L1 batch stored on the settlement layer differs from the locally computed one.
+    /// Indicates that the local state has diverged from the settlement layer.
+    #[serde(rename_all = "camelCase")]
+    SettlementHashMismatch {
+        settlement_hash: H256,
+        local_hash: Option<H256>,
+    },
+    /// Postgres is rolled back while L1 batches after the target are still committed on the settlement layer.
+    /// The settlement layer revert transaction must be sent first.
+    #[serde(rename_all = "camelCase")]
+    SettlementRevertRequired {
+        last_committed_l1_batch: L1BatchNumber,
+    },
+    /// Root hash of the Merkle tree for the target L1 batch differs from the one in Postgres.
+    #[serde(rename_all = "camelCase")]
+    TreeRootHashMismatch {
+        tree_hash: Option<H256>,
+        postgres_hash: Option<H256>,
+    },
+    /// State keeper cache is requested to be rolled back, but it doesn't exist.
+    StateKeeperCacheMissing,
+}
+
+impl fmt::Display for RollbackCheckFailure {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TargetNotSealed {
+                last_sealed_l1_batch,
+            } => write!(
+                formatter,
+                "target L1 batch is not sealed; last sealed L1 batch is #{last_sealed_l1_batch}"
+            ),
+            Self::ExecutedL1BatchesReverted {
+                last_executed_l1_batch,
+            } => write!(
+                formatter,
+                "rollback reverts executed L1 batches (last executed: #{last_executed_l1_batch})"
+            ),
+            Self::SettlementHashMismatch {
+                settlement_hash,
+                local_hash,
+            } => write!(
+                formatter,
+                "hash of the target L1 batch on the settlement layer ({settlement_hash:?}) \
+                 differs from the local one ({local_hash:?})"
+            ),
+            Self::SettlementRevertRequired {
+                last_committed_l1_batch,
+            } => write!(
+                formatter,
+                "L1 batches up to #{last_committed_l1_batch} are committed on the settlement layer; \
+                 send the revert transaction before rolling back Postgres"
+            ),
+            Self::TreeRootHashMismatch {
+                tree_hash,
+                postgres_hash,
+            } => write!(
+                formatter,
+                "Merkle tree root hash for the target L1 batch ({tree_hash:?}) differs from the one \
+                 in Postgres ({postgres_hash:?})"
+            ),
+            Self::StateKeeperCacheMissing => {
+                formatter.write_str("state keeper cache doesn't exist at the configured path")
+            }
+        }
+    }
+}
+
+impl BlockReverter {
+    /// Plans a rollback without modifying any data. Settlement layer checks are only performed if
+    /// the reverter has the settlement layer config.
+    pub async fn plan_rollback(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        flags: BlockReverterFlags,
+    ) -> anyhow::Result<RollbackReport> {
+        let rollback_postgres = flags.contains(BlockReverterFlags::POSTGRES);
+        let rollback_tree = flags.contains(BlockReverterFlags::TREE);
+        let rollback_sk_cache = flags.contains(BlockReverterFlags::SK_CACHE);
+        let mut failed_checks = vec![];
+
+        let mut storage = self.connection_pool.access_storage().await?;
+        let last_sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("storage contains no L1 batches")?;
+        let target_range = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .await?;
+        let last_miniblock_to_keep = match target_range {
+            Some((_, last_miniblock)) if last_l1_batch_to_keep <= last_sealed_l1_batch => {
+                last_miniblock
+            }
+            _ => {
+                failed_checks.push(RollbackCheckFailure::TargetNotSealed {
+                    last_sealed_l1_batch,
+                });
+                storage
+                    .blocks_dal()
+                    .get_sealed_miniblock_number()
+                    .await?
+                    .unwrap_or(MiniblockNumber(0))
+            }
+        };
+
+        let mut last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?;
+        let settlement_layer = if self.eth_config.is_some() {
+            let state = self
+                .settlement_layer_state(last_l1_batch_to_keep)
+                .await
+                .context("failed getting settlement layer state")?;
+            last_executed_l1_batch = last_executed_l1_batch.max(Some(state.last_executed_l1_batch));
+
+            if let Some(settlement_hash) = state.target_l1_batch_hash {
+                let local_l1_batch = storage
+                    .blocks_dal()
+                    .get_l1_batch_metadata(last_l1_batch_to_keep)
+                    .await?;
+                let local_hash = local_l1_batch.as_ref().map(stored_batch_hash);
+                if local_hash != Some(settlement_hash) {
+                    failed_checks.push(RollbackCheckFailure::SettlementHashMismatch {
+                        settlement_hash,
+                        local_hash,
+                    });
+                }
+            }
+            if rollback_postgres && state.last_committed_l1_batch > last_l1_batch_to_keep {
+                failed_checks.push(RollbackCheckFailure::SettlementRevertRequired {
+                    last_committed_l1_batch: state.last_committed_l1_batch,
+                });
+            }
+            Some(state)
+        } else {
+            None
+        };
+
+        if matches!(
+            self.executed_batches_revert_mode,
+            L1ExecutedBatchesRevert::Disallowed
+        ) {
+            if let Some(last_executed_l1_batch) = last_executed_l1_batch {
+                if last_l1_batch_to_keep < last_executed_l1_batch {
+                    failed_checks.push(RollbackCheckFailure::ExecutedL1BatchesReverted {
+                        last_executed_l1_batch,
+                    });
+                }
+            }
+        }
+
+        let postgres = if rollback_postgres {
+            let rows = storage
+                .blocks_dal()
+                .get_rows_to_revert(last_l1_batch_to_keep, last_miniblock_to_keep)
+                .await?;
+            Some(rows)
+        } else {
+            None
+        };
+        let witness_inputs_to_invalidate =
+            (rollback_postgres && self.witness_input_cache.is_some()).then(|| {
+                last_sealed_l1_batch
+                    .0
+                    .saturating_sub(last_l1_batch_to_keep.0)
+            });
+
+        let tree = if rollback_tree {
+            let postgres_hash = storage
+                .blocks_dal()
+                .get_l1_batch_state_root(last_l1_batch_to_keep)
+                .await?;
+            let tree = self.plan_tree_rollback(last_l1_batch_to_keep)?;
+            if let Some((rollback, tree_hash)) = tree {
+                if rollback.l1_batches_to_revert > 0 && tree_hash != postgres_hash {
+                    failed_checks.push(RollbackCheckFailure::TreeRootHashMismatch {
+                        tree_hash,
+                        postgres_hash,
+                    });
+                }
+            }
+            tree.map(|(rollback, _)| rollback)
+        } else {
+            None
+        };
+        drop(storage);
+
+        let state_keeper_cache = if rollback_sk_cache {
+            let rollback = Self::plan_state_cache_rollback(
+                Path::new(&self.state_keeper_cache_path),
+                last_l1_batch_to_keep,
+            )
+            .await
+            .context("failed opening state keeper cache")?;
+            if rollback.is_none() {
+                failed_checks.push(RollbackCheckFailure::StateKeeperCacheMissing);
+            }
+            rollback
+        } else {
+            None
+        };
+        let api_state_cache = match &self.api_state_cache_path {
+            Some(path) if rollback_sk_cache => {
+                Self::plan_state_cache_rollback(Path::new(path), last_l1_batch_to_keep)
+                    .await
+                    .context("failed opening API state cache")?
+            }
+            _ => None,
+        };
+
+        let mut report = RollbackReport {
+            last_l1_batch_to_keep,
+            last_miniblock_to_keep,
+            last_sealed_l1_batch,
+            rollback_postgres,
+            rollback_tree,
+            rollback_sk_cache,
+            postgres,
+            witness_inputs_to_invalidate,
+            tree,
+            state_keeper_cache,
+            api_state_cache,
+            settlement_layer,
+            failed_checks,
+            confirmation_token: String::new(),
+        };
+        let report_bytes = serde_json::to_vec(&report).context("failed serializing report")?;
+        report.confirmation_token = hex::encode(&keccak256(&report_bytes)[..8]);
+        Ok(report)
+    }
+
+    /// Checks that the rollback with the specified params can be performed: the rollback is re-planned,
+    /// all safety checks must pass, and the confirmation token must match the re-planned report.
+    /// On success, the rollback can be performed with [`Self::rollback_db()`] and / or
+    /// [`Self::send_ethereum_revert_transaction()`].
+    pub async fn confirm_rollback(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        flags: BlockReverterFlags,
+        confirmation_token: &str,
+    ) -> anyhow::Result<RollbackReport> {
+        let report = self.plan_rollback(last_l1_batch_to_keep, flags).await?;
+        if !report.failed_checks.is_empty() {
+            let failed_checks: Vec<_> = report
+                .failed_checks
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            anyhow::bail!(
+                "rollback safety checks failed: {}",
+                failed_checks.join("; ")
+            );
+        }
+        anyhow::ensure!(
+            report.confirmation_token == confirmation_token,
+            "confirmation token doesn't match the rollback plan; rollback params or node state have changed \
+             since the dry run. Re-run the dry run and review its report"
+        );
+        Ok(report)
+    }
+
+    /// Returns the tree rollback together with the tree root hash for the target L1 batch.
+    fn plan_tree_rollback(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> anyhow::Result<Option<(RocksdbRollback, Option<H256>)>> {
+        let merkle_tree_path = Path::new(&self.merkle_tree_path);
+        if !merkle_tree_path.exists() {
+            return Ok(None);
+        }
+        let db = RocksDB::new(merkle_tree_path).context("failed opening Merkle tree RocksDB")?;
+        let tree = ZkSyncTree::new_lightweight(db.into()).reader();
+        let rollback = RocksdbRollback::new(tree.next_l1_batch_number(), last_l1_batch_to_keep);
+        let root_hash = tree.root_hash_for_l1_batch(last_l1_batch_to_keep);
+        Ok(Some((rollback, root_hash)))
+    }
+
+    /// Returns the rollback of a state cache (the state keeper cache or the API state cache).
+    async fn plan_state_cache_rollback(
+        cache_path: &Path,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> anyhow::Result<Option<RocksdbRollback>> {
+        if !cache_path.exists() {
+            return Ok(None);
+        }
+        let cache = RocksdbStorage::builder(cache_path).await?;
+        let next_l1_batch = cache.l1_batch_number().await.unwrap_or(L1BatchNumber(0));
+        Ok(Some(RocksdbRollback::new(
+            next_l1_batch,
+            last_l1_batch_to_keep,
+        )))
+    }
+
+    async fn settlement_layer_state(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> anyhow::Result<SettlementLayerState> {
+        let last_committed_l1_batch = self
+            .get_l1_batch_number_from_contract(AggregatedActionType::Commit)
+            .await;
+        let last_proven_l1_batch = self
+            .get_l1_batch_number_from_contract(AggregatedActionType::PublishProofOnchain)
+            .await;
+        let last_executed_l1_batch = self
+            .get_l1_batch_number_from_contract(AggregatedActionType::Execute)
+            .await;
+
+        let target_l1_batch_hash = if last_l1_batch_to_keep <= last_committed_l1_batch {
+            let eth_config = self
+                .eth_config
+                .as_ref()
+                .context("eth_config is not provided")?;
+            let web3 = Web3::new(Http::new(&eth_config.eth_client_url)?);
+            let contract = Contract::new(
+                web3.eth(),
+                eth_config.diamond_proxy_addr,
+                zksync_contracts::zksync_contract(),
+            );
+            let hash: H256 = contract
+                .query(
+                    "storedBatchHash",
+                    U256::from(last_l1_batch_to_keep.0),
+                    None,
+                    Options::default(),
+                    None,
+                )
+                .await
+                .context("failed querying stored batch hash")?;
+            Some(hash)
+        } else {
+            None
+        };
+
+        Ok(SettlementLayerState {
+            last_committed_l1_batch,
+            last_proven_l1_batch,
+            last_executed_l1_batch,
+            target_l1_batch_hash,
+        })
+    }
+}
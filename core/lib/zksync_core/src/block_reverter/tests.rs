@@ -0,0 +1,237 @@
+//! Tests for block reverter safety checks.
+
+use tempfile::TempDir;
+use tokio::sync::watch;
+use zksync_dal::blocks_dal::RowsToRevert;
+use zksync_types::{L2ChainId, MiniblockNumber};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::store_executed_l1_batch_with_messages,
+};
+
+async fn prepare_storage(pool: &ConnectionPool, l1_batch_count: u32) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=l1_batch_count {
+        store_executed_l1_batch_with_messages(&mut storage, number, &[vec![]]).await;
+    }
+}
+
+fn create_reverter(
+    pool: &ConnectionPool,
+    temp_dir: &TempDir,
+    mode: L1ExecutedBatchesRevert,
+) -> BlockReverter {
+    let path = |name: &str| temp_dir.path().join(name).to_str().unwrap().to_owned();
+    BlockReverter::new(path("sk_cache"), path("tree"), None, pool.clone(), mode)
+}
+
+#[tokio::test]
+async fn planning_rollback() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let temp_dir = TempDir::new().unwrap();
+    let reverter = create_reverter(&pool, &temp_dir, L1ExecutedBatchesRevert::Allowed);
+
+    let report = reverter
+        .plan_rollback(L1BatchNumber(1), BlockReverterFlags::POSTGRES)
+        .await
+        .unwrap();
+    assert!(report.failed_checks.is_empty(), "{report:?}");
+    assert_eq!(report.last_miniblock_to_keep, MiniblockNumber(1));
+    assert_eq!(report.last_sealed_l1_batch, L1BatchNumber(3));
+    let expected_rows = RowsToRevert {
+        l1_batches: 2,
+        miniblocks: 2,
+        transactions: 2,
+        ..RowsToRevert::default()
+    };
+    assert_eq!(report.postgres, Some(expected_rows));
+    assert_eq!(report.witness_inputs_to_invalidate, None);
+    assert_eq!(report.tree, None);
+    assert_eq!(report.settlement_layer, None);
+
+    // Planning must not modify data.
+    let mut storage = pool.access_storage().await.unwrap();
+    let sealed_l1_batch = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_l1_batch, Some(L1BatchNumber(3)));
+
+    let report = reverter
+        .plan_rollback(L1BatchNumber(5), BlockReverterFlags::POSTGRES)
+        .await
+        .unwrap();
+    assert_eq!(
+        report.failed_checks,
+        [RollbackCheckFailure::TargetNotSealed {
+            last_sealed_l1_batch: L1BatchNumber(3)
+        }]
+    );
+
+    let report = reverter
+        .plan_rollback(L1BatchNumber(1), BlockReverterFlags::SK_CACHE)
+        .await
+        .unwrap();
+    assert_eq!(
+        report.failed_checks,
+        [RollbackCheckFailure::StateKeeperCacheMissing]
+    );
+}
+
+async fn sync_state_cache(pool: &ConnectionPool, path: &Path) {
+    let mut storage = pool.access_storage().await.unwrap();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    RocksdbStorage::builder(path)
+        .await
+        .unwrap()
+        .synchronize(&mut storage, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("state cache sync was interrupted");
+}
+
+async fn next_l1_batch_in_state_cache(path: &Path) -> Option<L1BatchNumber> {
+    RocksdbStorage::builder(path)
+        .await
+        .unwrap()
+        .l1_batch_number()
+        .await
+}
+
+#[tokio::test]
+async fn rolling_back_state_caches() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let temp_dir = TempDir::new().unwrap();
+    let mut reverter = create_reverter(&pool, &temp_dir, L1ExecutedBatchesRevert::Allowed);
+    let sk_cache_path = temp_dir.path().join("sk_cache");
+    let api_state_cache_path = temp_dir.path().join("api_state_cache");
+    reverter.set_api_state_cache_path(api_state_cache_path.to_str().unwrap().to_owned());
+    sync_state_cache(&pool, &sk_cache_path).await;
+    sync_state_cache(&pool, &api_state_cache_path).await;
+
+    let report = reverter
+        .plan_rollback(L1BatchNumber(1), BlockReverterFlags::SK_CACHE)
+        .await
+        .unwrap();
+    assert!(report.failed_checks.is_empty(), "{report:?}");
+    let expected_rollback = RocksdbRollback {
+        next_l1_batch: L1BatchNumber(4),
+        l1_batches_to_revert: 2,
+    };
+    assert_eq!(report.state_keeper_cache, Some(expected_rollback));
+    assert_eq!(report.api_state_cache, Some(expected_rollback));
+
+    reverter
+        .rollback_db(L1BatchNumber(1), BlockReverterFlags::SK_CACHE)
+        .await;
+    for path in [&sk_cache_path, &api_state_cache_path] {
+        let next_l1_batch = next_l1_batch_in_state_cache(path).await;
+        assert_eq!(next_l1_batch, Some(L1BatchNumber(2)), "{path:?}");
+    }
+}
+
+#[tokio::test]
+async fn reverting_executed_l1_batches_is_reported() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 2).await;
+    let temp_dir = TempDir::new().unwrap();
+    let reverter = create_reverter(&pool, &temp_dir, L1ExecutedBatchesRevert::Disallowed);
+
+    let report = reverter
+        .plan_rollback(L1BatchNumber(1), BlockReverterFlags::POSTGRES)
+        .await
+        .unwrap();
+    assert_eq!(
+        report.failed_checks,
+        [RollbackCheckFailure::ExecutedL1BatchesReverted {
+            last_executed_l1_batch: L1BatchNumber(2)
+        }]
+    );
+    let err = reverter
+        .confirm_rollback(
+            L1BatchNumber(1),
+            BlockReverterFlags::POSTGRES,
+            &report.confirmation_token,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("safety checks failed"), "{err}");
+}
+
+#[tokio::test]
+async fn confirming_rollback() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 2).await;
+    let temp_dir = TempDir::new().unwrap();
+    let reverter = create_reverter(&pool, &temp_dir, L1ExecutedBatchesRevert::Allowed);
+
+    let report = reverter
+        .plan_rollback(L1BatchNumber(1), BlockReverterFlags::POSTGRES)
+        .await
+        .unwrap();
+    let other_report = reverter
+        .plan_rollback(L1BatchNumber(0), BlockReverterFlags::POSTGRES)
+        .await
+        .unwrap();
+    assert_ne!(report.confirmation_token, other_report.confirmation_token);
+    let other_report = reverter
+        .plan_rollback(
+            L1BatchNumber(1),
+            BlockReverterFlags::POSTGRES | BlockReverterFlags::TREE,
+        )
+        .await
+        .unwrap();
+    assert_ne!(report.confirmation_token, other_report.confirmation_token);
+
+    let err = reverter
+        .confirm_rollback(
+            L1BatchNumber(1),
+            BlockReverterFlags::POSTGRES,
+            &other_report.confirmation_token,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("confirmation token"), "{err}");
+    reverter
+        .confirm_rollback(
+            L1BatchNumber(1),
+            BlockReverterFlags::POSTGRES,
+            &report.confirmation_token,
+        )
+        .await
+        .unwrap();
+
+    // Changing the node state invalidates the token.
+    let mut storage = pool.access_storage().await.unwrap();
+    store_executed_l1_batch_with_messages(&mut storage, 3, &[vec![]]).await;
+    let err = reverter
+        .confirm_rollback(
+            L1BatchNumber(1),
+            BlockReverterFlags::POSTGRES,
+            &report.confirmation_token,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("confirmation token"), "{err}");
+
+    reverter
+        .rollback_db(L1BatchNumber(1), BlockReverterFlags::POSTGRES)
+        .await;
+    let sealed_l1_batch = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_l1_batch, Some(L1BatchNumber(1)));
+}
@@ -0,0 +1,57 @@
+//! Kafka client for the CDC publisher.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use rdkafka::{
+    producer::{FutureProducer, FutureRecord},
+    ClientConfig,
+};
+
+use super::MessageBroker;
+
+/// Kafka producer. Uses an idempotent producer, and waits for each message to be acknowledged by the broker.
+pub struct KafkaBroker {
+    producer: FutureProducer,
+}
+
+impl fmt::Debug for KafkaBroker {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("KafkaBroker")
+            .finish_non_exhaustive()
+    }
+}
+
+impl KafkaBroker {
+    const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+    pub fn new(bootstrap_servers: &[String]) -> anyhow::Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", bootstrap_servers.join(","))
+            .set("enable.idempotence", "true")
+            .create()
+            .context("failed creating Kafka producer")?;
+        Ok(Self { producer })
+    }
+}
+
+#[async_trait]
+impl MessageBroker for KafkaBroker {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+        let record = FutureRecord::to(topic).key(key).payload(payload.as_slice());
+        self.producer
+            .send(record, Self::DELIVERY_TIMEOUT)
+            .await
+            .map_err(|(err, _)| err)
+            .with_context(|| {
+                format!("failed publishing message `{key}` to Kafka topic `{topic}`")
+            })?;
+        Ok(())
+    }
+}
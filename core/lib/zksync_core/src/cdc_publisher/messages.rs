@@ -0,0 +1,283 @@
+//! Messages published by the CDC publisher and their encoding.
+
+use serde::{Deserialize, Serialize};
+use vise::{EncodeLabelSet, EncodeLabelValue};
+use zksync_config::configs::cdc_publisher::CdcMessageFormat;
+use zksync_dal::chain_export_dal::{ExportedEvent, ExportedMiniblock, ExportedTransaction};
+use zksync_types::{Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256};
+
+use super::proto;
+
+/// Stream of published messages. Each stream is published to a separate topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stream", rename_all = "snake_case")]
+pub enum CdcStream {
+    Miniblocks,
+    Receipts,
+    L1Batches,
+}
+
+impl CdcStream {
+    /// Returns the topic (subject) suffix for this stream.
+    pub fn topic_suffix(self) -> &'static str {
+        match self {
+            Self::Miniblocks => "miniblocks",
+            Self::Receipts => "receipts",
+            Self::L1Batches => "l1_batches",
+        }
+    }
+}
+
+/// Sealed miniblock header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MiniblockMessage {
+    pub number: MiniblockNumber,
+    pub hash: H256,
+    pub timestamp: u64,
+    pub l1_tx_count: u32,
+    pub l2_tx_count: u32,
+    pub base_fee_per_gas: U256,
+    pub protocol_version: Option<u16>,
+    pub fee_account_address: Address,
+}
+
+impl From<&ExportedMiniblock> for MiniblockMessage {
+    fn from(block: &ExportedMiniblock) -> Self {
+        Self {
+            number: block.number,
+            hash: block.hash,
+            timestamp: block.timestamp,
+            l1_tx_count: block.l1_tx_count,
+            l2_tx_count: block.l2_tx_count,
+            base_fee_per_gas: block.base_fee_per_gas,
+            protocol_version: block.protocol_version,
+            fee_account_address: block.fee_account_address,
+        }
+    }
+}
+
+/// Log (event) emitted by a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogMessage {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+    /// Index of the log in the miniblock.
+    pub log_index: u32,
+    pub log_index_in_transaction: u32,
+}
+
+impl From<&ExportedEvent> for LogMessage {
+    fn from(event: &ExportedEvent) -> Self {
+        Self {
+            address: event.address,
+            topics: event.topics.clone(),
+            data: Bytes(event.data.clone()),
+            log_index: event.event_index_in_block,
+            log_index_in_transaction: event.event_index_in_tx,
+        }
+    }
+}
+
+/// Receipt of an executed transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptMessage {
+    pub transaction_hash: H256,
+    pub block_number: MiniblockNumber,
+    pub transaction_index: u32,
+    pub from: Address,
+    /// Called contract; `None` for deployment transactions.
+    pub to: Option<Address>,
+    /// 1 for succeeded transactions, 0 for failed ones (same as in Web3 receipts).
+    pub status: u32,
+    pub revert_reason: Option<String>,
+    pub gas_used: Option<U256>,
+    pub effective_gas_price: Option<U256>,
+    pub logs: Vec<LogMessage>,
+}
+
+impl ReceiptMessage {
+    pub(super) fn new(tx: &ExportedTransaction, logs: Vec<LogMessage>) -> Self {
+        Self {
+            transaction_hash: tx.hash,
+            block_number: tx.miniblock_number,
+            transaction_index: tx.index_in_block,
+            from: tx.initiator_address,
+            to: tx.contract_address,
+            status: if tx.error.is_some() { 0 } else { 1 },
+            revert_reason: tx.error.clone(),
+            gas_used: tx.gas_used,
+            effective_gas_price: tx.effective_gas_price,
+            logs,
+        }
+    }
+}
+
+/// Status of an L1 batch. Statuses are published in the order they are listed for each L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum L1BatchStatus {
+    Sealed,
+    Committed,
+    Proven,
+    Executed,
+}
+
+impl L1BatchStatus {
+    pub const ALL: [Self; 4] = [Self::Sealed, Self::Committed, Self::Proven, Self::Executed];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sealed => "sealed",
+            Self::Committed => "committed",
+            Self::Proven => "proven",
+            Self::Executed => "executed",
+        }
+    }
+
+    /// Name of the stream cursor for this status.
+    pub(super) fn cursor_name(self) -> &'static str {
+        match self {
+            Self::Sealed => "l1_batches_sealed",
+            Self::Committed => "l1_batches_committed",
+            Self::Proven => "l1_batches_proven",
+            Self::Executed => "l1_batches_executed",
+        }
+    }
+}
+
+/// Change of an L1 batch status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchStatusMessage {
+    pub number: L1BatchNumber,
+    pub status: L1BatchStatus,
+    pub first_miniblock: MiniblockNumber,
+    pub last_miniblock: MiniblockNumber,
+    /// Hash of the L1 transaction that has changed the status; `None` for sealed L1 batches.
+    pub l1_tx_hash: Option<H256>,
+}
+
+/// Message published by the CDC publisher.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CdcMessage {
+    Miniblock(MiniblockMessage),
+    Receipt(ReceiptMessage),
+    L1BatchStatus(L1BatchStatusMessage),
+}
+
+impl CdcMessage {
+    pub fn stream(&self) -> CdcStream {
+        match self {
+            Self::Miniblock(_) => CdcStream::Miniblocks,
+            Self::Receipt(_) => CdcStream::Receipts,
+            Self::L1BatchStatus(_) => CdcStream::L1Batches,
+        }
+    }
+
+    /// Returns the message key. Keys are used to partition and deduplicate messages in the broker; since delivery
+    /// is at-least-once, consumers can use keys to deduplicate messages as well.
+    pub fn key(&self) -> String {
+        match self {
+            Self::Miniblock(message) => message.number.0.to_string(),
+            Self::Receipt(message) => format!("{:?}", message.transaction_hash),
+            Self::L1BatchStatus(message) => {
+                format!("{}.{}", message.number.0, message.status.name())
+            }
+        }
+    }
+
+    pub fn encode(&self, format: CdcMessageFormat) -> anyhow::Result<Vec<u8>> {
+        Ok(match format {
+            CdcMessageFormat::Json => match self {
+                Self::Miniblock(message) => serde_json::to_vec(message)?,
+                Self::Receipt(message) => serde_json::to_vec(message)?,
+                Self::L1BatchStatus(message) => serde_json::to_vec(message)?,
+            },
+            CdcMessageFormat::Protobuf => {
+                use prost::Message as _;
+
+                match self {
+                    Self::Miniblock(message) => proto::Miniblock::from(message).encode_to_vec(),
+                    Self::Receipt(message) => proto::Receipt::from(message).encode_to_vec(),
+                    Self::L1BatchStatus(message) => {
+                        proto::L1BatchStatusChange::from(message).encode_to_vec()
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn u256_bytes(value: U256) -> Vec<u8> {
+    let mut bytes = vec![0_u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+impl From<&MiniblockMessage> for proto::Miniblock {
+    fn from(message: &MiniblockMessage) -> Self {
+        Self {
+            number: Some(message.number.0),
+            hash: Some(message.hash.as_bytes().to_vec()),
+            timestamp: Some(message.timestamp),
+            l1_tx_count: Some(message.l1_tx_count),
+            l2_tx_count: Some(message.l2_tx_count),
+            base_fee_per_gas: Some(u256_bytes(message.base_fee_per_gas)),
+            protocol_version: message.protocol_version.map(u32::from),
+            fee_account_address: Some(message.fee_account_address.as_bytes().to_vec()),
+        }
+    }
+}
+
+impl From<&ReceiptMessage> for proto::Receipt {
+    fn from(message: &ReceiptMessage) -> Self {
+        Self {
+            transaction_hash: Some(message.transaction_hash.as_bytes().to_vec()),
+            block_number: Some(message.block_number.0),
+            transaction_index: Some(message.transaction_index),
+            from: Some(message.from.as_bytes().to_vec()),
+            to: message.to.map(|address| address.as_bytes().to_vec()),
+            status: Some(message.status),
+            revert_reason: message.revert_reason.clone(),
+            gas_used: message.gas_used.map(u256_bytes),
+            effective_gas_price: message.effective_gas_price.map(u256_bytes),
+            logs: message
+                .logs
+                .iter()
+                .map(|log| proto::Log {
+                    address: Some(log.address.as_bytes().to_vec()),
+                    topics: log
+                        .topics
+                        .iter()
+                        .map(|topic| topic.as_bytes().to_vec())
+                        .collect(),
+                    data: Some(log.data.0.clone()),
+                    log_index: Some(log.log_index),
+                    log_index_in_transaction: Some(log.log_index_in_transaction),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&L1BatchStatusMessage> for proto::L1BatchStatusChange {
+    fn from(message: &L1BatchStatusMessage) -> Self {
+        let status = match message.status {
+            L1BatchStatus::Sealed => proto::L1BatchStatus::Sealed,
+            L1BatchStatus::Committed => proto::L1BatchStatus::Committed,
+            L1BatchStatus::Proven => proto::L1BatchStatus::Proven,
+            L1BatchStatus::Executed => proto::L1BatchStatus::Executed,
+        };
+        Self {
+            number: Some(message.number.0),
+            status: Some(status.into()),
+            first_miniblock: Some(message.first_miniblock.0),
+            last_miniblock: Some(message.last_miniblock.0),
+            l1_tx_hash: message.l1_tx_hash.map(|hash| hash.as_bytes().to_vec()),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Family, Gauge, Histogram, Metrics, Unit};
+
+use super::messages::CdcStream;
+
+/// Metrics for the CDC publisher.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_cdc_publisher")]
+pub(super) struct CdcPublisherMetrics {
+    /// Number of published messages split by stream.
+    pub published_messages: Family<CdcStream, Counter>,
+    /// Size of published messages split by stream.
+    #[metrics(buckets = Buckets::exponential(64.0..=1_048_576.0, 4.0), unit = Unit::Bytes)]
+    pub message_size: Family<CdcStream, Histogram<usize>>,
+    /// Latency of publishing a single message to the broker.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub publish_latency: Histogram<Duration>,
+    /// Number of the last miniblock published together with its receipts.
+    pub last_published_miniblock: Gauge<u64>,
+    /// Number of the last L1 batch with a published `sealed` status.
+    pub last_published_l1_batch: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<CdcPublisherMetrics> = vise::Global::new();
@@ -0,0 +1,346 @@
+//! Change data capture (CDC) publisher: publishes messages for sealed miniblocks, transaction receipts
+//! and L1 batch status changes to a message broker (Kafka or NATS). This gives downstream services
+//! a push-based integration with the node instead of polling the Web3 API.
+//!
+//! # Delivery guarantees
+//!
+//! Messages are published in the chain order within each stream: a miniblock message is followed
+//! by receipts of all its transactions, and an L1 batch status change is published only after the corresponding
+//! change of the previous L1 batch. Publishing progress is persisted in Postgres after messages are acknowledged
+//! by the broker, so delivery is at-least-once; messages may be re-published after a restart. Consumers should
+//! deduplicate messages using message keys if necessary.
+//!
+//! On the first start, the publisher starts from the current state of the node storage; historical data is
+//! not published.
+
+use std::{collections::HashMap, fmt};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use zksync_config::{configs::cdc_publisher::CdcBroker, CdcPublisherConfig};
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+
+#[cfg(feature = "kafka")]
+pub use self::kafka::KafkaBroker;
+pub use self::messages::{
+    CdcMessage, CdcStream, L1BatchStatus, L1BatchStatusMessage, LogMessage, MiniblockMessage,
+    ReceiptMessage,
+};
+use self::metrics::METRICS;
+#[cfg(feature = "nats")]
+pub use self::nats::NatsBroker;
+
+#[cfg(feature = "kafka")]
+mod kafka;
+mod messages;
+mod metrics;
+#[cfg(feature = "nats")]
+mod nats;
+pub mod proto;
+#[cfg(test)]
+mod tests;
+
+const MINIBLOCKS_CURSOR: &str = "miniblocks";
+
+/// Client of a message broker.
+#[async_trait]
+pub trait MessageBroker: fmt::Debug + Send + Sync {
+    /// Name of the broker used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Publishes a message to the specified topic (subject). Returns after the message is acknowledged
+    /// by the broker, or is buffered by the client if the broker doesn't support acknowledgements.
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> anyhow::Result<()>;
+
+    /// Flushes all buffered messages to the broker.
+    async fn flush(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Creates a broker client based on the config. Fails if the node is built without the crate feature
+/// for the configured broker (`kafka` or `nats`).
+pub async fn create_broker(config: &CdcPublisherConfig) -> anyhow::Result<Box<dyn MessageBroker>> {
+    anyhow::ensure!(
+        !config.broker_urls.is_empty(),
+        "`broker_urls` must not be empty"
+    );
+    Ok(match config.broker {
+        #[cfg(feature = "kafka")]
+        CdcBroker::Kafka => Box::new(KafkaBroker::new(&config.broker_urls)?),
+        #[cfg(feature = "nats")]
+        CdcBroker::Nats => Box::new(NatsBroker::new(&config.broker_urls).await?),
+        #[allow(unreachable_patterns)]
+        broker => anyhow::bail!(
+            "CDC publisher is configured with {broker:?} broker, but the node is built without \
+             the corresponding feature (`kafka` or `nats`)"
+        ),
+    })
+}
+
+/// Task publishing CDC messages. See the module-level docs for details.
+#[derive(Debug)]
+pub struct CdcPublisher {
+    broker: Box<dyn MessageBroker>,
+    pool: ConnectionPool,
+    config: CdcPublisherConfig,
+}
+
+impl CdcPublisher {
+    pub fn new(
+        broker: Box<dyn MessageBroker>,
+        pool: ConnectionPool,
+        config: CdcPublisherConfig,
+    ) -> Self {
+        Self {
+            broker,
+            pool,
+            config,
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, CDC publisher is shutting down");
+                break;
+            }
+
+            if let Err(err) = self.publish_new_data().await {
+                tracing::warn!(
+                    "Failed publishing CDC messages to {}: {err:#}",
+                    self.broker.name()
+                );
+            }
+            let polling_interval = self.config.polling_interval();
+            if tokio::time::timeout(polling_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                tracing::info!("Stop signal received, CDC publisher is shutting down");
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Publishes messages for the data changed since the previous call.
+    pub(crate) async fn publish_new_data(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("cdc_publisher").await?;
+        let cursors = storage.cdc_publisher_dal().get_cursors().await?;
+        drop(storage);
+
+        self.publish_miniblocks(cursors.get(MINIBLOCKS_CURSOR).copied())
+            .await?;
+        for status in L1BatchStatus::ALL {
+            self.publish_l1_batch_statuses(status, cursors.get(status.cursor_name()).copied())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn publish(&self, message: CdcMessage) -> anyhow::Result<()> {
+        let stream = message.stream();
+        let topic = format!("{}.{}", self.config.topic_prefix, stream.topic_suffix());
+        let key = message.key();
+        let payload = message
+            .encode(self.config.format)
+            .with_context(|| format!("failed encoding message `{key}`"))?;
+        METRICS.message_size[&stream].observe(payload.len());
+
+        let latency = METRICS.publish_latency.start();
+        self.broker.publish(&topic, &key, payload).await?;
+        latency.observe();
+        METRICS.published_messages[&stream].inc();
+        Ok(())
+    }
+
+    /// Returns the first number to publish and the current head of the stream in the node storage given
+    /// the persisted cursor, or `None` if there is nothing to publish. Initializes the cursor if necessary.
+    async fn prepare_cursor(
+        storage: &mut StorageProcessor<'_>,
+        name: &str,
+        cursor: Option<u32>,
+        head: Option<u32>,
+        first_number: u32,
+    ) -> anyhow::Result<Option<(u32, u32)>> {
+        let next_head = head.map_or(first_number, |head| head + 1);
+        let Some(cursor) = cursor else {
+            tracing::info!("Initializing CDC cursor `{name}` at {next_head}");
+            storage
+                .cdc_publisher_dal()
+                .set_cursor(name, next_head)
+                .await?;
+            return Ok(None);
+        };
+
+        if cursor > next_head {
+            // Can happen if the node storage was rolled back (e.g., by the block reverter).
+            tracing::warn!(
+                "CDC cursor `{name}` ({cursor}) is ahead of the node storage ({next_head}); resetting it. \
+                 Messages for rolled back data will not be retracted"
+            );
+            storage
+                .cdc_publisher_dal()
+                .set_cursor(name, next_head)
+                .await?;
+            return Ok(None);
+        }
+        Ok(head
+            .filter(|&head| cursor <= head)
+            .map(|head| (cursor, head)))
+    }
+
+    async fn publish_miniblocks(&self, cursor: Option<u32>) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("cdc_publisher").await?;
+        let sealed_miniblock = storage.blocks_dal().get_sealed_miniblock_number().await?;
+        let sealed_miniblock = sealed_miniblock.map(|number| number.0);
+        let Some((first_miniblock, sealed_miniblock)) =
+            Self::prepare_cursor(&mut storage, MINIBLOCKS_CURSOR, cursor, sealed_miniblock, 0)
+                .await?
+        else {
+            return Ok(());
+        };
+        let last_miniblock = sealed_miniblock
+            .min(first_miniblock + self.config.max_miniblocks_per_iteration.max(1) - 1);
+        let miniblocks = MiniblockNumber(first_miniblock)..=MiniblockNumber(last_miniblock);
+
+        let mut export_dal = storage.chain_export_dal();
+        let blocks = export_dal.get_miniblocks(miniblocks.clone()).await?;
+        let transactions = export_dal.get_transactions(miniblocks.clone()).await?;
+        let events = export_dal.get_events(miniblocks.clone()).await?;
+        drop(storage);
+
+        let mut logs_by_tx = HashMap::<_, Vec<_>>::new();
+        for event in &events {
+            logs_by_tx
+                .entry(event.tx_hash)
+                .or_default()
+                .push(LogMessage::from(event));
+        }
+        let mut tx_iter = transactions.iter().peekable();
+        for block in &blocks {
+            self.publish(CdcMessage::Miniblock(block.into())).await?;
+            while let Some(tx) = tx_iter.next_if(|tx| tx.miniblock_number == block.number) {
+                let logs = logs_by_tx.remove(&tx.hash).unwrap_or_default();
+                self.publish(CdcMessage::Receipt(ReceiptMessage::new(tx, logs)))
+                    .await?;
+            }
+        }
+        self.broker.flush().await?;
+
+        let mut storage = self.pool.access_storage_tagged("cdc_publisher").await?;
+        storage
+            .cdc_publisher_dal()
+            .set_cursor(MINIBLOCKS_CURSOR, last_miniblock + 1)
+            .await?;
+        METRICS.last_published_miniblock.set(last_miniblock.into());
+        tracing::debug!(
+            "Published CDC messages for miniblocks {miniblocks:?} ({} blocks, {} receipts)",
+            blocks.len(),
+            transactions.len()
+        );
+        Ok(())
+    }
+
+    async fn publish_l1_batch_statuses(
+        &self,
+        status: L1BatchStatus,
+        cursor: Option<u32>,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("cdc_publisher").await?;
+        let mut blocks_dal = storage.blocks_dal();
+        let head = match status {
+            L1BatchStatus::Sealed => blocks_dal.get_sealed_l1_batch_number().await?,
+            L1BatchStatus::Committed => {
+                blocks_dal
+                    .get_number_of_last_l1_batch_committed_on_eth()
+                    .await?
+            }
+            L1BatchStatus::Proven => {
+                blocks_dal
+                    .get_number_of_last_l1_batch_proven_on_eth()
+                    .await?
+            }
+            L1BatchStatus::Executed => {
+                blocks_dal
+                    .get_number_of_last_l1_batch_executed_on_eth()
+                    .await?
+            }
+        };
+        let head = head.map(|number| number.0);
+        // The genesis L1 batch is never committed, proven or executed.
+        let first_number = if status == L1BatchStatus::Sealed {
+            0
+        } else {
+            1
+        };
+        let Some((first_l1_batch, head)) = Self::prepare_cursor(
+            &mut storage,
+            status.cursor_name(),
+            cursor,
+            head,
+            first_number,
+        )
+        .await?
+        else {
+            return Ok(());
+        };
+        let last_l1_batch =
+            head.min(first_l1_batch + self.config.max_miniblocks_per_iteration.max(1) - 1);
+
+        let mut messages = vec![];
+        for number in first_l1_batch..=last_l1_batch {
+            let l1_batch_number = L1BatchNumber(number);
+            let details = storage
+                .blocks_web3_dal()
+                .get_l1_batch_details(l1_batch_number)
+                .await?;
+            let miniblocks = storage
+                .blocks_dal()
+                .get_miniblock_range_of_l1_batch(l1_batch_number)
+                .await?;
+            // L1 batches may be missing in the storage after snapshot recovery.
+            let (Some(details), Some((first_miniblock, last_miniblock))) = (details, miniblocks)
+            else {
+                continue;
+            };
+            let l1_tx_hash = match status {
+                L1BatchStatus::Sealed => None,
+                L1BatchStatus::Committed => details.base.commit_tx_hash,
+                L1BatchStatus::Proven => details.base.prove_tx_hash,
+                L1BatchStatus::Executed => details.base.execute_tx_hash,
+            };
+            messages.push(L1BatchStatusMessage {
+                number: l1_batch_number,
+                status,
+                first_miniblock,
+                last_miniblock,
+                l1_tx_hash,
+            });
+        }
+        drop(storage);
+
+        for message in messages {
+            self.publish(CdcMessage::L1BatchStatus(message)).await?;
+        }
+        self.broker.flush().await?;
+
+        let mut storage = self.pool.access_storage_tagged("cdc_publisher").await?;
+        storage
+            .cdc_publisher_dal()
+            .set_cursor(status.cursor_name(), last_l1_batch + 1)
+            .await?;
+        if status == L1BatchStatus::Sealed {
+            METRICS.last_published_l1_batch.set(last_l1_batch.into());
+        }
+        tracing::debug!(
+            "Published CDC messages for L1 batches #{first_l1_batch}..=#{last_l1_batch} \
+             with status `{}`",
+            status.name()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! NATS client for the CDC publisher.
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+
+use super::MessageBroker;
+
+/// NATS client. Message keys are sent in the `Nats-Msg-Id` header, so that JetStream streams capturing
+/// the published subjects deduplicate messages re-published after a restart.
+#[derive(Debug)]
+pub struct NatsBroker {
+    client: async_nats::Client,
+}
+
+impl NatsBroker {
+    pub async fn new(server_urls: &[String]) -> anyhow::Result<Self> {
+        let client = async_nats::connect(server_urls.join(","))
+            .await
+            .context("failed connecting to NATS")?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl MessageBroker for NatsBroker {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Nats-Msg-Id", key);
+        self.client
+            .publish_with_headers(topic.to_owned(), headers, payload.into())
+            .await
+            .with_context(|| format!("failed publishing message `{key}` to NATS subject `{topic}`"))
+    }
+
+    async fn flush(&self) -> anyhow::Result<()> {
+        self.client
+            .flush()
+            .await
+            .context("failed flushing NATS client")
+    }
+}
@@ -0,0 +1,3 @@
+#![allow(warnings)]
+
+include!(concat!(env!("OUT_DIR"), "/src/cdc_publisher/proto/gen.rs"));
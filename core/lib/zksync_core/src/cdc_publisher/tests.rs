@@ -0,0 +1,359 @@
+//! Tests for the CDC publisher.
+
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use prost::Message as _;
+use zksync_config::configs::cdc_publisher::CdcMessageFormat;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, tx::IncludedTxLocation, Address, L2ChainId,
+    VmEvent, H256,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{
+        create_l1_batch, create_l2_transaction, create_miniblock, execute_l2_transaction,
+    },
+};
+
+/// Published message: topic, key and payload.
+type PublishedMessage = (String, String, Vec<u8>);
+
+/// Broker recording published messages.
+#[derive(Debug, Default)]
+struct MockBroker {
+    messages: Mutex<Vec<PublishedMessage>>,
+}
+
+impl MockBroker {
+    fn take_messages(&self) -> Vec<PublishedMessage> {
+        std::mem::take(&mut *self.messages.lock().unwrap())
+    }
+}
+
+#[async_trait]
+impl MessageBroker for Arc<MockBroker> {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+        self.messages
+            .lock()
+            .unwrap()
+            .push((topic.to_owned(), key.to_owned(), payload));
+        Ok(())
+    }
+}
+
+fn config(format: CdcMessageFormat) -> CdcPublisherConfig {
+    CdcPublisherConfig {
+        broker: CdcBroker::Kafka,
+        broker_urls: vec![],
+        topic_prefix: "test".to_owned(),
+        format,
+        polling_interval_ms: 10,
+        max_miniblocks_per_iteration: 10,
+    }
+}
+
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+}
+
+/// Stores a miniblock with the specified number of transactions, each emitting a single event.
+/// Returns hashes of the stored transactions.
+async fn store_miniblock(
+    storage: &mut StorageProcessor<'_>,
+    number: u32,
+    tx_count: usize,
+) -> Vec<H256> {
+    let transaction_results: Vec<_> = (0..tx_count)
+        .map(|_| execute_l2_transaction(create_l2_transaction(10, 100)))
+        .collect();
+    for result in &transaction_results {
+        let l2_tx = result.transaction.clone().try_into().unwrap();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(l2_tx, Default::default())
+            .await;
+    }
+    let miniblock_number = MiniblockNumber(number);
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(miniblock_number, &transaction_results, 1.into())
+        .await;
+
+    let events: Vec<_> = (0..tx_count)
+        .map(|i| VmEvent {
+            location: (L1BatchNumber(number), i as u32),
+            address: Address::repeat_byte(0x10),
+            indexed_topics: vec![H256::repeat_byte(i as u8)],
+            value: vec![i as u8; 4],
+        })
+        .collect();
+    let events_by_tx: Vec<_> = transaction_results
+        .iter()
+        .zip(&events)
+        .enumerate()
+        .map(|(i, (result, event))| {
+            let location = IncludedTxLocation {
+                tx_hash: result.hash,
+                tx_index_in_miniblock: i as u32,
+                tx_initiator_address: result.transaction.initiator_account(),
+            };
+            (location, vec![event])
+        })
+        .collect();
+    storage
+        .events_dal()
+        .save_events(miniblock_number, &events_by_tx)
+        .await;
+    transaction_results
+        .iter()
+        .map(|result| result.hash)
+        .collect()
+}
+
+async fn seal_l1_batch(storage: &mut StorageProcessor<'_>, number: u32) {
+    let l1_batch_number = L1BatchNumber(number);
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(number))
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(l1_batch_number)
+        .await
+        .unwrap();
+}
+
+fn decode_json<T: serde::de::DeserializeOwned>(message: &PublishedMessage) -> T {
+    serde_json::from_slice(&message.2).unwrap()
+}
+
+#[tokio::test]
+async fn cursors_are_initialized_at_storage_head() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let broker = Arc::new(MockBroker::default());
+    let publisher = CdcPublisher::new(
+        Box::new(broker.clone()),
+        pool.clone(),
+        config(CdcMessageFormat::Json),
+    );
+
+    publisher.publish_new_data().await.unwrap();
+    assert!(broker.take_messages().is_empty());
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let cursors = storage.cdc_publisher_dal().get_cursors().await.unwrap();
+    let expected_cursors = HashMap::from([
+        (MINIBLOCKS_CURSOR.to_owned(), 1),
+        ("l1_batches_sealed".to_owned(), 1),
+        ("l1_batches_committed".to_owned(), 1),
+        ("l1_batches_proven".to_owned(), 1),
+        ("l1_batches_executed".to_owned(), 1),
+    ]);
+    assert_eq!(cursors, expected_cursors);
+}
+
+#[tokio::test]
+async fn publishing_miniblocks_and_receipts() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let broker = Arc::new(MockBroker::default());
+    let publisher = CdcPublisher::new(
+        Box::new(broker.clone()),
+        pool.clone(),
+        config(CdcMessageFormat::Json),
+    );
+    publisher.publish_new_data().await.unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let tx_hashes = store_miniblock(&mut storage, 1, 2).await;
+    store_miniblock(&mut storage, 2, 0).await;
+    drop(storage);
+    publisher.publish_new_data().await.unwrap();
+
+    let messages = broker.take_messages();
+    let topics_and_keys: Vec<_> = messages
+        .iter()
+        .map(|(topic, key, _)| (topic.as_str(), key.clone()))
+        .collect();
+    assert_eq!(
+        topics_and_keys,
+        [
+            ("test.miniblocks", "1".to_owned()),
+            ("test.receipts", format!("{:?}", tx_hashes[0])),
+            ("test.receipts", format!("{:?}", tx_hashes[1])),
+            ("test.miniblocks", "2".to_owned()),
+        ]
+    );
+
+    let miniblock: MiniblockMessage = decode_json(&messages[0]);
+    assert_eq!(miniblock.number, MiniblockNumber(1));
+    assert_eq!(miniblock.hash, H256::from_low_u64_be(1));
+    let receipt: ReceiptMessage = decode_json(&messages[2]);
+    assert_eq!(receipt.transaction_hash, tx_hashes[1]);
+    assert_eq!(receipt.block_number, MiniblockNumber(1));
+    assert_eq!(receipt.transaction_index, 1);
+    assert_eq!(receipt.status, 1);
+    assert_eq!(receipt.logs.len(), 1);
+    assert_eq!(receipt.logs[0].topics, [H256::repeat_byte(1)]);
+    assert_eq!(receipt.logs[0].data.0, [1; 4]);
+
+    // Repeated publishing must not produce any messages.
+    publisher.publish_new_data().await.unwrap();
+    assert!(broker.take_messages().is_empty());
+}
+
+#[tokio::test]
+async fn publishing_l1_batch_status_changes() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let broker = Arc::new(MockBroker::default());
+    let publisher = CdcPublisher::new(
+        Box::new(broker.clone()),
+        pool.clone(),
+        config(CdcMessageFormat::Json),
+    );
+    publisher.publish_new_data().await.unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    store_miniblock(&mut storage, 1, 0).await;
+    store_miniblock(&mut storage, 2, 0).await;
+    seal_l1_batch(&mut storage, 1).await;
+    drop(storage);
+    publisher.publish_new_data().await.unwrap();
+
+    let messages = broker.take_messages();
+    assert_eq!(messages.len(), 3, "{messages:?}");
+    let (topic, key, _) = &messages[2];
+    assert_eq!(topic, "test.l1_batches");
+    assert_eq!(key, "1.sealed");
+    let status_change: L1BatchStatusMessage = decode_json(&messages[2]);
+    assert_eq!(
+        status_change,
+        L1BatchStatusMessage {
+            number: L1BatchNumber(1),
+            status: L1BatchStatus::Sealed,
+            first_miniblock: MiniblockNumber(1),
+            last_miniblock: MiniblockNumber(2),
+            l1_tx_hash: None,
+        }
+    );
+
+    let commit_tx_hash = H256::repeat_byte(0xc0);
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            L1BatchNumber(1),
+            AggregatedActionType::Commit,
+            commit_tx_hash,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+    drop(storage);
+    publisher.publish_new_data().await.unwrap();
+
+    let messages = broker.take_messages();
+    assert_eq!(messages.len(), 1, "{messages:?}");
+    assert_eq!(messages[0].1, "1.committed");
+    let status_change: L1BatchStatusMessage = decode_json(&messages[0]);
+    assert_eq!(status_change.status, L1BatchStatus::Committed);
+    assert_eq!(status_change.l1_tx_hash, Some(commit_tx_hash));
+}
+
+#[tokio::test]
+async fn publishing_messages_in_protobuf_format() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let broker = Arc::new(MockBroker::default());
+    let publisher = CdcPublisher::new(
+        Box::new(broker.clone()),
+        pool.clone(),
+        config(CdcMessageFormat::Protobuf),
+    );
+    publisher.publish_new_data().await.unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let tx_hashes = store_miniblock(&mut storage, 1, 1).await;
+    drop(storage);
+    publisher.publish_new_data().await.unwrap();
+
+    let messages = broker.take_messages();
+    assert_eq!(messages.len(), 2, "{messages:?}");
+    let miniblock = proto::Miniblock::decode(messages[0].2.as_slice()).unwrap();
+    assert_eq!(miniblock.number, Some(1));
+    assert_eq!(miniblock.hash.unwrap(), H256::from_low_u64_be(1).as_bytes());
+    let receipt = proto::Receipt::decode(messages[1].2.as_slice()).unwrap();
+    assert_eq!(receipt.transaction_hash.unwrap(), tx_hashes[0].as_bytes());
+    assert_eq!(receipt.status, Some(1));
+    assert_eq!(receipt.logs.len(), 1);
+    assert_eq!(receipt.logs[0].data.as_deref(), Some([0_u8; 4].as_slice()));
+}
+
+#[tokio::test]
+async fn cursor_is_reset_after_storage_rollback() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .cdc_publisher_dal()
+        .set_cursor(MINIBLOCKS_CURSOR, 10)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let broker = Arc::new(MockBroker::default());
+    let publisher = CdcPublisher::new(
+        Box::new(broker.clone()),
+        pool.clone(),
+        config(CdcMessageFormat::Json),
+    );
+    publisher.publish_new_data().await.unwrap();
+    assert!(broker.take_messages().is_empty());
+
+    let mut storage = pool.access_storage().await.unwrap();
+    store_miniblock(&mut storage, 1, 0).await;
+    drop(storage);
+    publisher.publish_new_data().await.unwrap();
+    let messages = broker.take_messages();
+    assert_eq!(messages.len(), 1, "{messages:?}");
+    assert_eq!(messages[0].1, "1");
+}
+
+#[tokio::test]
+async fn publisher_stops_while_waiting_for_next_iteration() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let broker = Arc::new(MockBroker::default());
+    let config = CdcPublisherConfig {
+        polling_interval_ms: 3_600_000,
+        ..config(CdcMessageFormat::Json)
+    };
+    let publisher = CdcPublisher::new(Box::new(broker), pool, config);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let publisher_task = tokio::spawn(publisher.run(stop_receiver));
+    stop_sender.send_replace(true);
+    tokio::time::timeout(std::time::Duration::from_secs(10), publisher_task)
+        .await
+        .expect("publisher didn't stop")
+        .unwrap()
+        .unwrap();
+}
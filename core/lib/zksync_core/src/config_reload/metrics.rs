@@ -0,0 +1,25 @@
+use serde::Serialize;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "section", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub(super) enum ConfigSection {
+    Api,
+    Mempool,
+    StateKeeper,
+    FeeDistributor,
+}
+
+/// Metrics for the config reloader.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_config_reload")]
+pub(super) struct ConfigReloadMetrics {
+    /// Number of applied changes split by the config section.
+    pub applied_changes: Family<ConfigSection, Counter>,
+    /// Number of failed reloads, e.g. because of a malformed overrides file or invalid values.
+    pub errors: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<ConfigReloadMetrics> = vise::Global::new();
@@ -0,0 +1,348 @@
+//! Runtime reloading of config parameters that are safe to change without restarting the server.
+//!
+//! [`ReloadableConfig`] contains parameters that components pick up at runtime: the WebSocket rate limit
+//! and disabled methods of API servers, the mempool capacity, per-account transaction caps and the minimal L2 gas
+//! price of the state keeper, and the fee sweep interval. Components subscribe to changes via a [`watch`] channel
+//! and apply new values at points where this is safe; e.g., per-account caps are changed at the start
+//! of an L1 batch, and the WebSocket rate limit applies to new connections.
+//!
+//! [`ConfigReloader`] periodically reads the parameters from the process environment merged with the overrides
+//! file, using the same variable names as on startup. If any of the parameters is invalid, the entire reload
+//! is rejected and previous values are retained. Changes to other parameters are ignored; they still require
+//! a restart. If a DB connection pool is provided, applied changes are recorded in the audit log.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    num::NonZeroU32,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use zksync_config::{
+    configs::{
+        api::Web3JsonRpcConfig,
+        chain::{MempoolConfig, StateKeeperConfig},
+    },
+    ConfigReloadConfig, FeeDistributorConfig,
+};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::envy_load_from;
+use zksync_types::api::idexo::AuditAction;
+
+use self::metrics::{ConfigSection, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Reloadable parameters of API servers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiParams {
+    /// Maximum number of requests per minute for a WebSocket connection.
+    pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    /// JSON-RPC methods rejected by API servers.
+    #[serde(default)]
+    pub disabled_methods: HashSet<String>,
+}
+
+impl From<&Web3JsonRpcConfig> for ApiParams {
+    fn from(config: &Web3JsonRpcConfig) -> Self {
+        Self {
+            websocket_requests_per_minute_limit: config.websocket_requests_per_minute_limit,
+            disabled_methods: config.disabled_methods.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Reloadable parameters of the mempool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolParams {
+    /// Max number of L2 transactions in the in-memory mempool.
+    pub capacity: u64,
+}
+
+impl From<&MempoolConfig> for MempoolParams {
+    fn from(config: &MempoolConfig) -> Self {
+        Self {
+            capacity: config.capacity,
+        }
+    }
+}
+
+/// Reloadable parameters of the state keeper.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateKeeperParams {
+    /// Minimal L2 gas price used by the fee model.
+    pub minimal_l2_gas_price: u64,
+    /// Max number of L2 transactions from a single account in a miniblock.
+    pub max_txs_per_account_in_miniblock: Option<u32>,
+    /// Max number of L2 transactions from a single account in an L1 batch.
+    pub max_txs_per_account_in_batch: Option<u32>,
+}
+
+impl From<&StateKeeperConfig> for StateKeeperParams {
+    fn from(config: &StateKeeperConfig) -> Self {
+        Self {
+            minimal_l2_gas_price: config.minimal_l2_gas_price,
+            max_txs_per_account_in_miniblock: config.max_txs_per_account_in_miniblock,
+            max_txs_per_account_in_batch: config.max_txs_per_account_in_batch,
+        }
+    }
+}
+
+/// Reloadable parameters of the fee distributor.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeDistributorParams {
+    /// Minimum interval between consecutive fee sweeps.
+    pub sweep_interval: Duration,
+}
+
+impl From<&FeeDistributorConfig> for FeeDistributorParams {
+    fn from(config: &FeeDistributorConfig) -> Self {
+        Self {
+            sweep_interval: config.sweep_interval(),
+        }
+    }
+}
+
+/// Config parameters that can be changed at runtime. A section is `None` if the corresponding config
+/// was not provided on startup; such sections are not reloaded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReloadableConfig {
+    pub api: Option<ApiParams>,
+    pub mempool: Option<MempoolParams>,
+    pub state_keeper: Option<StateKeeperParams>,
+    pub fee_distributor: Option<FeeDistributorParams>,
+}
+
+impl ReloadableConfig {
+    pub fn new(
+        api: Option<&Web3JsonRpcConfig>,
+        mempool: Option<&MempoolConfig>,
+        state_keeper: Option<&StateKeeperConfig>,
+        fee_distributor: Option<&FeeDistributorConfig>,
+    ) -> Self {
+        Self {
+            api: api.map(ApiParams::from),
+            mempool: mempool.map(MempoolParams::from),
+            state_keeper: state_keeper.map(StateKeeperParams::from),
+            fee_distributor: fee_distributor.map(FeeDistributorParams::from),
+        }
+    }
+
+    /// Loads sections present in this config from the provided env variables.
+    fn reload(&self, vars: &HashMap<String, String>) -> anyhow::Result<Self> {
+        let vars = || {
+            vars.iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+        };
+
+        let api = self
+            .api
+            .as_ref()
+            .map(|_| envy_load_from::<ApiParams>("web3_json_rpc", "API_WEB3_JSON_RPC_", vars()))
+            .transpose()?;
+        let mempool = self
+            .mempool
+            .as_ref()
+            .map(|_| envy_load_from::<MempoolParams>("mempool", "CHAIN_MEMPOOL_", vars()))
+            .transpose()?;
+        if let Some(mempool) = &mempool {
+            anyhow::ensure!(mempool.capacity > 0, "mempool capacity must be positive");
+        }
+        let state_keeper = self
+            .state_keeper
+            .as_ref()
+            .map(|_| {
+                envy_load_from::<StateKeeperParams>("state_keeper", "CHAIN_STATE_KEEPER_", vars())
+            })
+            .transpose()?;
+        if let Some(state_keeper) = &state_keeper {
+            anyhow::ensure!(
+                state_keeper.minimal_l2_gas_price > 0,
+                "minimal L2 gas price must be positive"
+            );
+        }
+        let fee_distributor = self
+            .fee_distributor
+            .as_ref()
+            .map(|_| {
+                let config: FeeDistributorConfig =
+                    envy_load_from("fee_distributor", "FEE_DISTRIBUTOR_", vars())?;
+                anyhow::Ok(FeeDistributorParams::from(&config))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            api,
+            mempool,
+            state_keeper,
+            fee_distributor,
+        })
+    }
+
+    fn changed_sections(&self, new_config: &Self) -> Vec<ConfigSection> {
+        let mut sections = vec![];
+        if self.api != new_config.api {
+            sections.push(ConfigSection::Api);
+        }
+        if self.mempool != new_config.mempool {
+            sections.push(ConfigSection::Mempool);
+        }
+        if self.state_keeper != new_config.state_keeper {
+            sections.push(ConfigSection::StateKeeper);
+        }
+        if self.fee_distributor != new_config.fee_distributor {
+            sections.push(ConfigSection::FeeDistributor);
+        }
+        sections
+    }
+}
+
+/// Parses overridden env variables in the `NAME=value` format. Values may be enclosed in double quotes.
+fn parse_overrides(contents: &str) -> anyhow::Result<HashMap<String, String>> {
+    let mut vars = HashMap::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, value) = line
+            .split_once('=')
+            .with_context(|| format!("line {} is not in the `NAME=value` format", i + 1))?;
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        vars.insert(name.trim().to_owned(), value.to_owned());
+    }
+    Ok(vars)
+}
+
+/// Periodically reloads [`ReloadableConfig`] and notifies subscribed components about changes.
+#[derive(Debug)]
+pub struct ConfigReloader {
+    config: ConfigReloadConfig,
+    sender: watch::Sender<ReloadableConfig>,
+    audit_log_pool: Option<ConnectionPool>,
+}
+
+impl ConfigReloader {
+    pub fn new(config: ConfigReloadConfig, initial_config: ReloadableConfig) -> Self {
+        Self {
+            config,
+            sender: watch::channel(initial_config).0,
+            audit_log_pool: None,
+        }
+    }
+
+    /// Records applied changes in the audit log.
+    #[must_use]
+    pub fn with_audit_log(mut self, pool: ConnectionPool) -> Self {
+        self.audit_log_pool = Some(pool);
+        self
+    }
+
+    /// Returns a receiver for the reloaded config.
+    pub fn subscribe(&self) -> watch::Receiver<ReloadableConfig> {
+        self.sender.subscribe()
+    }
+
+    /// Reads the process environment merged with the overrides file.
+    fn read_vars(&self) -> anyhow::Result<HashMap<String, String>> {
+        let mut vars: HashMap<_, _> = std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .collect();
+        let Some(path) = &self.config.overrides_path else {
+            return Ok(vars);
+        };
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                let overrides = parse_overrides(&contents)
+                    .with_context(|| format!("failed parsing overrides file `{path}`"))?;
+                vars.extend(overrides);
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => { /* no overrides */ }
+            Err(err) => {
+                return Err(anyhow::Error::new(err)
+                    .context(format!("failed reading overrides file `{path}`")));
+            }
+        }
+        Ok(vars)
+    }
+
+    /// Applies parameters from the provided env variables. Returns the changed config sections.
+    fn apply(&self, vars: &HashMap<String, String>) -> anyhow::Result<Vec<ConfigSection>> {
+        let current_config = self.sender.borrow().clone();
+        let new_config = current_config.reload(vars)?;
+        let changed_sections = current_config.changed_sections(&new_config);
+        if changed_sections.is_empty() {
+            return Ok(changed_sections);
+        }
+
+        tracing::info!(
+            "Applying config changes in sections {changed_sections:?}: {current_config:?} -> {new_config:?}"
+        );
+        for &section in &changed_sections {
+            METRICS.applied_changes[&section].inc();
+        }
+        self.sender.send_replace(new_config);
+        Ok(changed_sections)
+    }
+
+    async fn record_changes(
+        &self,
+        pool: &ConnectionPool,
+        previous_config: &ReloadableConfig,
+        changed_sections: &[ConfigSection],
+    ) -> anyhow::Result<()> {
+        let details = serde_json::json!({
+            "sections": changed_sections,
+            "previous": previous_config,
+            "new": *self.sender.borrow(),
+        });
+        let mut storage = pool.access_storage_tagged("config_reload").await?;
+        storage
+            .audit_log_dal()
+            .insert_entry(AuditAction::ConfigReload, "config_reloader", &details)
+            .await
+            .context("failed recording config changes in audit log")?;
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            let previous_config = self.sender.borrow().clone();
+            let result = self.read_vars().and_then(|vars| self.apply(&vars));
+            match result {
+                Ok(changed_sections) if !changed_sections.is_empty() => {
+                    if let Some(pool) = &self.audit_log_pool {
+                        let result = self
+                            .record_changes(pool, &previous_config, &changed_sections)
+                            .await;
+                        if let Err(err) = result {
+                            tracing::warn!("Failed recording applied config changes: {err:#}");
+                        }
+                    }
+                }
+                Ok(_) => { /* no changes */ }
+                Err(err) => {
+                    tracing::warn!("Failed reloading config, retaining previous values: {err:#}");
+                    METRICS.errors.inc();
+                }
+            }
+            if tokio::time::timeout(self.config.polling_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, config reloader is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+//! Tests for the config reloader.
+
+use tempfile::TempDir;
+
+use super::*;
+
+fn mock_config() -> ConfigReloadConfig {
+    ConfigReloadConfig {
+        polling_interval_ms: 10,
+        overrides_path: None,
+    }
+}
+
+fn mock_mempool_config() -> MempoolConfig {
+    MempoolConfig {
+        sync_interval_ms: 10,
+        sync_batch_size: 1_000,
+        capacity: 10_000,
+        stuck_tx_timeout: 10,
+        remove_stuck_txs: true,
+        delay_interval: 100,
+    }
+}
+
+fn mock_fee_distributor_config() -> FeeDistributorConfig {
+    FeeDistributorConfig {
+        polling_interval_ms: 10_000,
+        sweep_interval_sec: 3_600,
+        min_balance: 0,
+        min_sweep_amount: 1_000,
+        gas_limit_per_transfer: 1_000_000,
+        treasury_addr: None,
+        treasury_share_bps: 0,
+        sequencer_addr: None,
+        sequencer_share_bps: 0,
+        burn_addr: None,
+        burn_share_bps: 10_000,
+    }
+}
+
+fn mock_reloadable_config() -> ReloadableConfig {
+    ReloadableConfig::new(
+        Some(&Web3JsonRpcConfig::for_tests()),
+        Some(&mock_mempool_config()),
+        Some(&StateKeeperConfig::for_tests()),
+        Some(&mock_fee_distributor_config()),
+    )
+}
+
+fn mock_vars(config: &ReloadableConfig) -> HashMap<String, String> {
+    let state_keeper = config.state_keeper.as_ref().unwrap();
+    let vars = [
+        (
+            "CHAIN_MEMPOOL_CAPACITY",
+            config.mempool.as_ref().unwrap().capacity.to_string(),
+        ),
+        (
+            "CHAIN_STATE_KEEPER_MINIMAL_L2_GAS_PRICE",
+            state_keeper.minimal_l2_gas_price.to_string(),
+        ),
+        (
+            "FEE_DISTRIBUTOR_SWEEP_INTERVAL_SEC",
+            config
+                .fee_distributor
+                .as_ref()
+                .unwrap()
+                .sweep_interval
+                .as_secs()
+                .to_string(),
+        ),
+    ];
+    let mut vars: HashMap<_, _> = vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect();
+
+    let api = config.api.as_ref().unwrap();
+    if let Some(limit) = api.websocket_requests_per_minute_limit {
+        vars.insert(
+            "API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT".to_owned(),
+            limit.to_string(),
+        );
+    }
+    if let Some(max_txs) = state_keeper.max_txs_per_account_in_miniblock {
+        vars.insert(
+            "CHAIN_STATE_KEEPER_MAX_TXS_PER_ACCOUNT_IN_MINIBLOCK".to_owned(),
+            max_txs.to_string(),
+        );
+    }
+    if let Some(max_txs) = state_keeper.max_txs_per_account_in_batch {
+        vars.insert(
+            "CHAIN_STATE_KEEPER_MAX_TXS_PER_ACCOUNT_IN_BATCH".to_owned(),
+            max_txs.to_string(),
+        );
+    }
+    vars
+}
+
+#[test]
+fn parsing_overrides() {
+    let contents = r#"
+        # Comment
+        CHAIN_MEMPOOL_CAPACITY=100
+
+        API_WEB3_JSON_RPC_DISABLED_METHODS = "debug_traceCall,eth_call"
+    "#;
+    let vars = parse_overrides(contents).unwrap();
+    assert_eq!(
+        vars,
+        HashMap::from([
+            ("CHAIN_MEMPOOL_CAPACITY".to_owned(), "100".to_owned()),
+            (
+                "API_WEB3_JSON_RPC_DISABLED_METHODS".to_owned(),
+                "debug_traceCall,eth_call".to_owned()
+            ),
+        ])
+    );
+
+    let err = parse_overrides("CHAIN_MEMPOOL_CAPACITY=100\nwhat").unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+}
+
+#[test]
+fn unchanged_config_is_not_applied() {
+    let config = mock_reloadable_config();
+    let reloader = ConfigReloader::new(mock_config(), config.clone());
+    let receiver = reloader.subscribe();
+
+    let changed_sections = reloader.apply(&mock_vars(&config)).unwrap();
+    assert!(changed_sections.is_empty(), "{changed_sections:?}");
+    assert!(!receiver.has_changed().unwrap());
+    assert_eq!(*receiver.borrow(), config);
+}
+
+#[test]
+fn applying_config_changes() {
+    let config = mock_reloadable_config();
+    let reloader = ConfigReloader::new(mock_config(), config.clone());
+    let mut receiver = reloader.subscribe();
+
+    let mut vars = mock_vars(&config);
+    vars.insert("CHAIN_MEMPOOL_CAPACITY".to_owned(), "42".to_owned());
+    vars.insert(
+        "API_WEB3_JSON_RPC_DISABLED_METHODS".to_owned(),
+        "debug_traceCall,eth_call".to_owned(),
+    );
+    vars.insert(
+        "API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT".to_owned(),
+        "100".to_owned(),
+    );
+    let changed_sections = reloader.apply(&vars).unwrap();
+    assert_eq!(
+        changed_sections,
+        [ConfigSection::Api, ConfigSection::Mempool]
+    );
+
+    assert!(receiver.has_changed().unwrap());
+    let new_config = receiver.borrow_and_update().clone();
+    assert_eq!(new_config.mempool, Some(MempoolParams { capacity: 42 }));
+    let api = new_config.api.unwrap();
+    assert_eq!(
+        api.disabled_methods,
+        HashSet::from(["debug_traceCall".to_owned(), "eth_call".to_owned()])
+    );
+    assert_eq!(
+        api.websocket_requests_per_minute_limit,
+        NonZeroU32::new(100)
+    );
+    assert_eq!(new_config.state_keeper, config.state_keeper);
+    assert_eq!(new_config.fee_distributor, config.fee_distributor);
+
+    vars.insert(
+        "CHAIN_STATE_KEEPER_MINIMAL_L2_GAS_PRICE".to_owned(),
+        "1000".to_owned(),
+    );
+    vars.insert(
+        "FEE_DISTRIBUTOR_SWEEP_INTERVAL_SEC".to_owned(),
+        "60".to_owned(),
+    );
+    let changed_sections = reloader.apply(&vars).unwrap();
+    assert_eq!(
+        changed_sections,
+        [ConfigSection::StateKeeper, ConfigSection::FeeDistributor]
+    );
+    let new_config = receiver.borrow_and_update().clone();
+    assert_eq!(new_config.state_keeper.unwrap().minimal_l2_gas_price, 1_000);
+    assert_eq!(
+        new_config.fee_distributor.unwrap().sweep_interval,
+        Duration::from_secs(60)
+    );
+}
+
+#[test]
+fn invalid_config_changes_are_rejected() {
+    let config = mock_reloadable_config();
+    let reloader = ConfigReloader::new(mock_config(), config.clone());
+    let receiver = reloader.subscribe();
+
+    let mut vars = mock_vars(&config);
+    vars.insert(
+        "API_WEB3_JSON_RPC_DISABLED_METHODS".to_owned(),
+        "eth_call".to_owned(),
+    );
+    vars.insert("CHAIN_MEMPOOL_CAPACITY".to_owned(), "0".to_owned());
+    let err = reloader.apply(&vars).unwrap_err().to_string();
+    assert!(err.contains("capacity"), "{err}");
+
+    vars.insert("CHAIN_MEMPOOL_CAPACITY".to_owned(), "many".to_owned());
+    reloader.apply(&vars).unwrap_err();
+
+    assert!(!receiver.has_changed().unwrap());
+    assert_eq!(*receiver.borrow(), config);
+}
+
+#[test]
+fn missing_sections_are_not_reloaded() {
+    let config = ReloadableConfig::new(None, Some(&mock_mempool_config()), None, None);
+    let reloader = ConfigReloader::new(mock_config(), config);
+    let receiver = reloader.subscribe();
+
+    let vars = HashMap::from([("CHAIN_MEMPOOL_CAPACITY".to_owned(), "42".to_owned())]);
+    let changed_sections = reloader.apply(&vars).unwrap();
+    assert_eq!(changed_sections, [ConfigSection::Mempool]);
+    let new_config = receiver.borrow().clone();
+    assert_eq!(
+        new_config,
+        ReloadableConfig {
+            mempool: Some(MempoolParams { capacity: 42 }),
+            ..ReloadableConfig::default()
+        }
+    );
+}
+
+#[test]
+fn reading_overrides_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("overrides.env");
+    let config = ConfigReloadConfig {
+        overrides_path: Some(path.to_str().unwrap().to_owned()),
+        ..mock_config()
+    };
+    let reloader = ConfigReloader::new(config, ReloadableConfig::default());
+
+    // A missing file is treated as having no overrides.
+    let vars = reloader.read_vars().unwrap();
+    assert!(!vars.contains_key("CONFIG_RELOAD_TEST_VAR"));
+
+    std::fs::write(&path, "CONFIG_RELOAD_TEST_VAR=\"value\"\n").unwrap();
+    let vars = reloader.read_vars().unwrap();
+    assert_eq!(vars["CONFIG_RELOAD_TEST_VAR"], "value");
+
+    std::fs::write(&path, "CONFIG_RELOAD_TEST_VAR\n").unwrap();
+    let err = reloader.read_vars().unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("overrides file"), "{err}");
+}
+
+#[tokio::test]
+async fn applied_changes_are_recorded_in_audit_log() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("overrides.env");
+    std::fs::write(&path, "CHAIN_MEMPOOL_CAPACITY=42\n").unwrap();
+    let config = ConfigReloadConfig {
+        overrides_path: Some(path.to_str().unwrap().to_owned()),
+        ..mock_config()
+    };
+    let initial_config = ReloadableConfig::new(None, Some(&mock_mempool_config()), None, None);
+    let reloader = ConfigReloader::new(config, initial_config).with_audit_log(pool.clone());
+    let mut receiver = reloader.subscribe();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let reloader_task = tokio::spawn(reloader.run(stop_receiver));
+
+    receiver.changed().await.unwrap();
+    stop_sender.send_replace(true);
+    reloader_task.await.unwrap().unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let entry = storage
+        .audit_log_dal()
+        .get_last_entry(AuditAction::ConfigReload)
+        .await
+        .unwrap()
+        .expect("config reload is not recorded");
+    assert_eq!(entry.actor, "config_reloader");
+    assert_eq!(entry.details["sections"], serde_json::json!(["mempool"]));
+    assert_eq!(entry.details["previous"]["mempool"]["capacity"], 10_000);
+    assert_eq!(entry.details["new"]["mempool"]["capacity"], 42);
+}
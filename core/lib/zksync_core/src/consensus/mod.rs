@@ -63,12 +63,17 @@ impl MainNodeConfig {
 
 /// Periodically fetches the head of the main node
 /// and updates `SyncState` accordingly.
+///
+/// Blocks received from the gossip network also update `SyncState` (see [`FetcherConfig::run()`]), so polling
+/// is only required to detect the lag while the node catches up; hence, the polling interval is relatively large.
+/// Unlike gossiped blocks, the polled head is authoritative, so it can move the main node block back
+/// (e.g., after the main node reverts blocks).
 pub async fn run_main_node_state_fetcher(
     ctx: &ctx::Ctx,
     client: &dyn MainNodeClient,
     sync_state: &SyncState,
 ) -> ctx::OrCanceled<()> {
-    const DELAY_INTERVAL: time::Duration = time::Duration::milliseconds(500);
+    const DELAY_INTERVAL: time::Duration = time::Duration::seconds(5);
     const RETRY_DELAY_INTERVAL: time::Duration = time::Duration::seconds(5);
     loop {
         match ctx.wait(client.fetch_l2_block_number()).await? {
@@ -91,18 +96,23 @@ pub struct FetcherConfig {
 }
 
 impl FetcherConfig {
-    /// Task fetching L2 blocks using peer-to-peer gossip network.
+    /// Task fetching L2 blocks using peer-to-peer gossip network. Blocks are accompanied by certificates
+    /// signed by the main node validator, so they can be received from any peer rather than only from the main node;
+    /// thus, external nodes can form a gossip network relaying blocks to each other.
+    ///
+    /// The main node head in `sync_state` is updated with the received blocks.
     pub async fn run(
         self,
         ctx: &ctx::Ctx,
         pool: ConnectionPool,
         actions: ActionQueueSender,
+        sync_state: SyncState,
     ) -> anyhow::Result<()> {
         scope::run!(ctx, |ctx, s| async {
             let store = Store::new(pool);
             let mut block_store = store.clone().into_block_store();
             block_store
-                .set_actions_queue(ctx, actions)
+                .set_actions_queue(ctx, actions, sync_state)
                 .await
                 .wrap("block_store.set_actions_queue()")?;
             let (block_store, runner) = BlockStore::new(ctx, Box::new(block_store))
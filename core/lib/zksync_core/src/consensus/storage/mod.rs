@@ -13,7 +13,7 @@ mod testonly;
 
 use crate::{
     state_keeper::io::common::IoCursor,
-    sync_layer::{fetcher::FetchedBlock, sync_action::ActionQueueSender},
+    sync_layer::{fetcher::FetchedBlock, sync_action::ActionQueueSender, SyncState},
 };
 
 /// Context-aware `zksync_dal::StorageProcessor` wrapper.
@@ -137,6 +137,7 @@ impl<'a> CtxStorage<'a> {
 struct Cursor {
     inner: IoCursor,
     actions: ActionQueueSender,
+    sync_state: SyncState,
 }
 
 impl Cursor {
@@ -174,7 +175,10 @@ impl Cursor {
             operator_address: payload.operator_address,
             transactions: payload.transactions,
         };
-        self.actions.push_actions(self.inner.advance(block)).await;
+        let new_actions = self.inner.advance(block)?;
+        self.actions.push_actions(new_actions).await;
+        // The block is certified by the validator (i.e., the main node), so the main node has at least this block.
+        self.sync_state.observe_main_node_block(number);
         Ok(())
     }
 }
@@ -258,10 +262,12 @@ impl BlockStore {
     }
 
     /// Sets an `ActionQueueSender` in the `BlockStore`. See `store_next_block()` for details.
+    /// `sync_state` is updated with the numbers of blocks received from the gossip network.
     pub async fn set_actions_queue(
         &mut self,
         ctx: &ctx::Ctx,
         actions: ActionQueueSender,
+        sync_state: SyncState,
     ) -> ctx::Result<()> {
         let mut storage = CtxStorage::access(ctx, &self.inner.pool)
             .await
@@ -270,7 +276,11 @@ impl BlockStore {
             .new_fetcher_cursor(ctx)
             .await
             .wrap("new_fetcher_cursor()")?;
-        *sync::lock(ctx, &self.store_next_block_mutex).await? = Some(Cursor { inner, actions });
+        *sync::lock(ctx, &self.store_next_block_mutex).await? = Some(Cursor {
+            inner,
+            actions,
+            sync_state,
+        });
         Ok(())
     }
 }
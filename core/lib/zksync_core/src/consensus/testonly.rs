@@ -12,7 +12,7 @@ use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::ConnectionPool;
 use zksync_types::{
     api, block::MiniblockHasher, snapshots::SnapshotRecoveryStatus, Address, L1BatchNumber,
-    L2ChainId, MiniblockNumber, ProtocolVersionId, H256,
+    L2ChainId, MiniblockNumber, PackedEthSignature, ProtocolVersionId, H256,
 };
 use zksync_web3_decl::error::{EnrichedClientError, EnrichedClientResult};
 
@@ -57,13 +57,14 @@ impl Distribution<Config> for Standard {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct MockMainNodeClient {
     prev_miniblock_hash: H256,
     l2_blocks: Vec<api::en::SyncBlock>,
     block_number_offset: u32,
     protocol_versions: HashMap<u16, api::ProtocolVersion>,
     system_contracts: HashMap<H256, Vec<u8>>,
+    l1_batch_root_hashes: HashMap<L1BatchNumber, api::en::SyncL1BatchRootHash>,
 }
 
 impl MockMainNodeClient {
@@ -83,6 +84,9 @@ impl MockMainNodeClient {
             virtual_blocks: Some(0),
             hash: Some(snapshot.miniblock_hash),
             protocol_version: ProtocolVersionId::latest(),
+            sequencer_signature: None,
+            content_hash: None,
+            sequencer_header_signature: None,
         };
 
         Self {
@@ -140,6 +144,9 @@ impl MockMainNodeClient {
                 virtual_blocks: Some(!is_fictive as u32),
                 hash: Some(miniblock_hash),
                 protocol_version: ProtocolVersionId::latest(),
+                sequencer_signature: None,
+                content_hash: None,
+                sequencer_header_signature: None,
             }
         });
 
@@ -147,6 +154,44 @@ impl MockMainNodeClient {
         tx_hashes
     }
 
+    /// Returns mutable access to miniblocks currently held by the client, e.g., to tamper with them.
+    pub fn l2_blocks_mut(&mut self) -> &mut [api::en::SyncBlock] {
+        &mut self.l2_blocks
+    }
+
+    /// Signs all miniblocks currently held by the client with the specified sequencer key.
+    pub fn sign_l2_blocks(&mut self, signing_key: &H256, chain_id: L2ChainId) {
+        for block in &mut self.l2_blocks {
+            let digest = block
+                .signed_digest(chain_id)
+                .expect("block cannot be signed");
+            let signature = PackedEthSignature::sign_raw(signing_key, &digest).unwrap();
+            block.sequencer_signature = Some(signature);
+            let header_digest = block
+                .header_signed_digest(chain_id)
+                .expect("block header cannot be signed");
+            let signature = PackedEthSignature::sign_raw(signing_key, &header_digest).unwrap();
+            block.sequencer_header_signature = Some(signature);
+        }
+    }
+
+    pub fn set_content_hash(&mut self, number: MiniblockNumber, content_hash: H256) {
+        self.update_l2_block(number, |block| block.content_hash = Some(content_hash));
+    }
+
+    pub fn update_l2_block(
+        &mut self,
+        number: MiniblockNumber,
+        update: impl FnOnce(&mut api::en::SyncBlock),
+    ) {
+        let block = self
+            .l2_blocks
+            .iter_mut()
+            .find(|block| block.number == number)
+            .expect("miniblock not found");
+        update(block);
+    }
+
     pub fn insert_protocol_version(&mut self, version: api::ProtocolVersion) {
         self.system_contracts
             .insert(version.base_system_contracts.bootloader, vec![]);
@@ -154,6 +199,24 @@ impl MockMainNodeClient {
             .insert(version.base_system_contracts.default_aa, vec![]);
         self.protocol_versions.insert(version.version_id, version);
     }
+
+    pub fn insert_l1_batch_root_hash(&mut self, number: L1BatchNumber, root_hash: H256) {
+        let root_hash = api::en::SyncL1BatchRootHash {
+            number,
+            root_hash,
+            sequencer_signature: None,
+        };
+        self.l1_batch_root_hashes.insert(number, root_hash);
+    }
+
+    /// Signs all L1 batch root hashes currently held by the client with the specified sequencer key.
+    pub fn sign_l1_batch_root_hashes(&mut self, signing_key: &H256, chain_id: L2ChainId) {
+        for root_hash in self.l1_batch_root_hashes.values_mut() {
+            let digest = root_hash.signed_digest(chain_id);
+            let signature = PackedEthSignature::sign_raw(signing_key, &digest).unwrap();
+            root_hash.sequencer_signature = Some(signature);
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -214,9 +277,17 @@ impl MainNodeClient for MockMainNodeClient {
         };
         if !with_transactions {
             block.transactions = None;
+            block.sequencer_signature = None;
         }
         Ok(Some(block))
     }
+
+    async fn fetch_l1_batch_root_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<api::en::SyncL1BatchRootHash>> {
+        Ok(self.l1_batch_root_hashes.get(&number).cloned())
+    }
 }
 
 /// Fake StateKeeper for tests.
@@ -214,7 +214,8 @@ async fn test_fetcher() {
             let i = NoCopy::from(i);
             let pool = template.create_db(4).await?.build().await?;
             let (fetcher, runner) = testonly::StateKeeper::new(pool).await?;
-            fetchers.push(fetcher.store());
+            let sync_state = SyncState::default();
+            fetchers.push((fetcher.store(), sync_state.clone()));
             s.spawn_bg(async {
                 let i = i;
                 runner
@@ -223,7 +224,7 @@ async fn test_fetcher() {
                     .await
                     .with_context(|| format!("fetcher{}", *i))
             });
-            s.spawn_bg(cfg.run(ctx, fetcher.pool, fetcher.actions_sender));
+            s.spawn_bg(cfg.run(ctx, fetcher.pool, fetcher.actions_sender, sync_state));
         }
 
         // Make validator produce blocks and wait for fetchers to get them.
@@ -233,13 +234,15 @@ async fn test_fetcher() {
             .store()
             .wait_for_blocks_and_verify(ctx, &validators, want_last)
             .await?;
-        for fetcher in &fetchers {
+        for (fetcher, sync_state) in &fetchers {
             assert_eq!(
                 want,
                 fetcher
                     .wait_for_blocks_and_verify(ctx, &validators, want_last)
                     .await?
             );
+            // The main node head is learned from the gossiped blocks.
+            assert_eq!(u64::from(sync_state.get_main_node_block().0), want_last.0);
         }
         Ok(())
     })
@@ -303,7 +306,12 @@ async fn test_fetcher_backfill_certs() {
         let (fetcher, runner) = testonly::StateKeeper::new(pool).await?;
         let fetcher_store = fetcher.store();
         s.spawn_bg(runner.run(ctx));
-        s.spawn_bg(fetcher_cfg.run(ctx, fetcher.pool, fetcher.actions_sender));
+        s.spawn_bg(fetcher_cfg.run(
+            ctx,
+            fetcher.pool,
+            fetcher.actions_sender,
+            SyncState::default(),
+        ));
 
         // Make validator produce new blocks and
         // wait for the fetcher to get both the missing certs and the new blocks.
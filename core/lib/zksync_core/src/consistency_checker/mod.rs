@@ -8,7 +8,10 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{clients::QueryClient, Error as L1ClientError, EthInterface};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::{i_executor::structures::CommitBatchInfo, Tokenizable};
-use zksync_types::{web3::ethabi, L1BatchNumber, H256};
+use zksync_types::{
+    commitment::L1BatchWithMetadata, pubdata_da::PubdataDA, web3::ethabi, Address, L1BatchNumber,
+    H256,
+};
 
 use crate::{
     metrics::{CheckerComponent, EN_METRICS},
@@ -102,35 +105,50 @@ impl HandleConsistencyCheckerEvent for ConsistencyCheckerHealthUpdater {
     }
 
     fn report_inconsistent_batch(&mut self, number: L1BatchNumber) {
-        tracing::warn!("L1 batch #{number} is inconsistent with L1");
+        tracing::error!("L1 batch #{number} is inconsistent with L1");
+        EN_METRICS.l1_batch_inconsistencies.inc();
         self.current_details.inconsistent_batches.push(number);
         self.inner.update(self.current_details.health());
     }
 }
 
 /// Consistency checker behavior when L1 commit data divergence is detected.
-// This is a temporary workaround for a bug that sometimes leads to incorrect L1 batch data returned by the server
-// (and thus persisted by external nodes). Eventually, we want to go back to bailing on L1 data mismatch;
-// for now, it's only enabled for the unit tests.
+// Logging is the default as a temporary workaround for a bug that sometimes leads to incorrect L1 batch data
+// returned by the server (and thus persisted by external nodes). Eventually, we want to go back to bailing
+// on L1 data mismatch by default.
 #[derive(Debug)]
 enum L1DataMismatchBehavior {
-    #[cfg(test)]
     Bail,
     Log,
 }
 
+/// Names of fields in a post-Boojum L1 batch commitment, used to report mismatches. The last field is either
+/// `totalL2ToL1Pubdata` or `pubdataCommitments` depending on the protocol version.
+const COMMITMENT_FIELD_NAMES: [&str; 10] = [
+    "batchNumber",
+    "timestamp",
+    "indexRepeatedStorageChanges",
+    "newStateRoot",
+    "numberOfLayer1Txs",
+    "priorityOperationsHash",
+    "bootloaderHeapInitialContentsHash",
+    "eventsQueueStateHash",
+    "systemLogs",
+    "pubdata",
+];
+
 /// L1 commit data loaded from Postgres.
 #[derive(Debug)]
-struct LocalL1BatchCommitData {
-    is_pre_boojum: bool,
-    l1_commit_data: ethabi::Token,
-    commit_tx_hash: H256,
+pub(crate) struct LocalL1BatchCommitData {
+    pub is_pre_boojum: bool,
+    pub l1_batch: L1BatchWithMetadata,
+    pub commit_tx_hash: H256,
 }
 
 impl LocalL1BatchCommitData {
     /// Returns `Ok(None)` if Postgres doesn't contain all data necessary to check L1 commitment
     /// for the specified batch.
-    async fn new(
+    pub async fn new(
         storage: &mut StorageProcessor<'_>,
         batch_number: L1BatchNumber,
     ) -> anyhow::Result<Option<Self>> {
@@ -180,10 +198,94 @@ impl LocalL1BatchCommitData {
 
         Ok(Some(Self {
             is_pre_boojum,
-            l1_commit_data: CommitBatchInfo(&l1_batch).into_token(),
+            l1_batch,
             commit_tx_hash,
         }))
     }
+
+    /// Checks that the local L1 batch data matches the reference commitment extracted from L1.
+    fn verify_commitment(&self, reference: &ethabi::Token) -> bool {
+        let local = self.build_commitment(reference);
+        if local == *reference {
+            return true;
+        }
+        tracing::error!(
+            "Locally computed commitment for L1 batch #{} diverges from the one committed on L1; \
+             mismatched fields: {:?}",
+            self.l1_batch.header.number,
+            self.mismatched_fields(&local, reference)
+        );
+        false
+    }
+
+    fn build_commitment(&self, reference: &ethabi::Token) -> ethabi::Token {
+        let is_pre_1_4_2 = self
+            .l1_batch
+            .header
+            .protocol_version
+            .map_or(true, |version| version.is_pre_1_4_2());
+        let pubdata_da = if is_pre_1_4_2 {
+            PubdataDA::Calldata
+        } else {
+            Self::detect_pubdata_da(reference).unwrap_or_default()
+        };
+        // DA inclusion data cannot be computed locally, so it's taken from the reference.
+        let da_inclusion_data = match pubdata_da {
+            PubdataDA::Custom => {
+                Self::pubdata_commitments(reference).map_or(&[][..], |bytes| &bytes[1..])
+            }
+            PubdataDA::Calldata | PubdataDA::Blobs => &[],
+        };
+        CommitBatchInfo::new(&self.l1_batch, pubdata_da)
+            .with_da_inclusion_data(da_inclusion_data)
+            .into_token()
+    }
+
+    /// Returns names (or indices for pre-Boojum batches) of the commitment fields that differ
+    /// between the local and reference commitments.
+    fn mismatched_fields(&self, local: &ethabi::Token, reference: &ethabi::Token) -> Vec<String> {
+        let (ethabi::Token::Tuple(local), ethabi::Token::Tuple(reference)) = (local, reference)
+        else {
+            return vec!["(entire commitment)".to_owned()];
+        };
+        if local.len() != reference.len() {
+            return vec!["(number of fields)".to_owned()];
+        }
+        let field_names = (!self.is_pre_boojum).then_some(&COMMITMENT_FIELD_NAMES);
+        let mismatched_indices = local
+            .iter()
+            .zip(reference)
+            .enumerate()
+            .filter_map(|(i, (local, reference))| (local != reference).then_some(i));
+        mismatched_indices
+            .map(|i| match field_names.and_then(|names| names.get(i)) {
+                Some(name) => (*name).to_owned(),
+                None => format!("#{i}"),
+            })
+            .collect()
+    }
+
+    /// Returns `pubdataCommitments` (the last field) of a post-1.4.2 commitment.
+    pub fn pubdata_commitments(reference: &ethabi::Token) -> Option<&[u8]> {
+        let ethabi::Token::Tuple(fields) = reference else {
+            return None;
+        };
+        let ethabi::Token::Bytes(pubdata_commitments) = fields.last()? else {
+            return None;
+        };
+        Some(pubdata_commitments)
+    }
+
+    /// Detects the pubdata source used for a post-1.4.2 commitment. The source is encoded
+    /// as the first byte of `pubdataCommitments`.
+    pub fn detect_pubdata_da(reference: &ethabi::Token) -> Option<PubdataDA> {
+        match *Self::pubdata_commitments(reference)?.first()? {
+            flag if flag == PubdataDA::Calldata.source_flag() => Some(PubdataDA::Calldata),
+            flag if flag == PubdataDA::Blobs.source_flag() => Some(PubdataDA::Blobs),
+            flag if flag == PubdataDA::Custom.source_flag() => Some(PubdataDA::Custom),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -196,6 +298,11 @@ pub struct ConsistencyChecker {
     l1_client: Box<dyn EthInterface>,
     event_handler: Box<dyn HandleConsistencyCheckerEvent>,
     l1_data_mismatch_behavior: L1DataMismatchBehavior,
+    /// Address of the diamond proxy contract on L1. If set, commit transactions are checked to be sent to it
+    /// (or to `validator_timelock_addr`).
+    diamond_proxy_addr: Option<Address>,
+    /// Address of the validator timelock contract on L1, which commit transactions are sent to by the main node.
+    validator_timelock_addr: Option<Address>,
     pool: ConnectionPool,
     health_check: ReactiveHealthCheck,
 }
@@ -213,11 +320,34 @@ impl ConsistencyChecker {
             l1_client: Box::new(web3),
             event_handler: Box::new(health_updater),
             l1_data_mismatch_behavior: L1DataMismatchBehavior::Log,
+            diamond_proxy_addr: None,
+            validator_timelock_addr: None,
             pool,
             health_check,
         }
     }
 
+    /// Makes the checker check that commit transactions are sent to the specified diamond proxy contract.
+    /// Without this check, a malicious main node could point to a transaction with arbitrary calldata.
+    pub fn with_diamond_proxy_addr(mut self, address: Address) -> Self {
+        self.diamond_proxy_addr = Some(address);
+        self
+    }
+
+    /// Makes the checker additionally accept commit transactions sent to the specified validator timelock contract.
+    /// Has no effect unless [`Self::with_diamond_proxy_addr()`] is called as well.
+    pub fn with_validator_timelock_addr(mut self, address: Address) -> Self {
+        self.validator_timelock_addr = Some(address);
+        self
+    }
+
+    /// Makes the checker stop with an error once it detects an L1 batch inconsistent with L1, rather than
+    /// just reporting the batch and continuing.
+    pub fn bail_on_l1_data_mismatch(mut self) -> Self {
+        self.l1_data_mismatch_behavior = L1DataMismatchBehavior::Bail;
+        self
+    }
+
     /// Returns health check associated with this checker.
     pub fn health_check(&self) -> &ReactiveHealthCheck {
         &self.health_check
@@ -242,13 +372,26 @@ impl ConsistencyChecker {
         }
 
         // We can't get tx calldata from db because it can be fake.
-        let commit_tx_input_data = self
+        let commit_tx = self
             .l1_client
             .get_tx(commit_tx_hash, "consistency_checker")
             .await?
-            .with_context(|| format!("Commit for tx {commit_tx_hash:?} not found on L1"))?
-            .input;
-        // TODO (PLA-721): Check receiving contract and selector
+            .with_context(|| format!("Commit for tx {commit_tx_hash:?} not found on L1"))?;
+        if let Some(diamond_proxy_addr) = self.diamond_proxy_addr {
+            let is_valid_recipient = commit_tx.to == Some(diamond_proxy_addr)
+                || (commit_tx.to.is_some() && commit_tx.to == self.validator_timelock_addr);
+            if !is_valid_recipient {
+                let err = anyhow::anyhow!(
+                    "Main node gave us a commit tx {commit_tx_hash:?} sent to {:?} rather than \
+                     the diamond proxy contract {diamond_proxy_addr:?} or the validator timelock contract {:?}",
+                    commit_tx.to,
+                    self.validator_timelock_addr
+                );
+                return Err(err.into());
+            }
+        }
+        let commit_tx_input_data = commit_tx.input;
+
         // TODO: Add support for post shared bridge commits
         let commit_function = if local.is_pre_boojum {
             &*PRE_BOOJUM_COMMIT_FUNCTION
@@ -257,15 +400,23 @@ impl ConsistencyChecker {
                 .function("commitBatches")
                 .context("L1 contract does not have `commitBatches` function")?
         };
+        let selector = commit_tx_input_data.0.get(..4);
+        if selector != Some(&commit_function.short_signature()[..]) {
+            let err = anyhow::anyhow!(
+                "Main node gave us a commit tx {commit_tx_hash:?} not calling `{}` function",
+                commit_function.name
+            );
+            return Err(err.into());
+        }
         let commitment =
             Self::extract_commit_data(&commit_tx_input_data.0, commit_function, batch_number)
                 .with_context(|| {
                     format!("Failed extracting commit data for transaction {commit_tx_hash:?}")
                 })?;
-        Ok(commitment == local.l1_commit_data)
+        Ok(local.verify_commitment(&commitment))
     }
 
-    fn extract_commit_data(
+    pub(crate) fn extract_commit_data(
         commit_tx_input_data: &[u8],
         commit_function: &ethabi::Function,
         batch_number: L1BatchNumber,
@@ -378,7 +529,6 @@ impl ConsistencyChecker {
                 Ok(false) => {
                     self.event_handler.report_inconsistent_batch(batch_number);
                     match &self.l1_data_mismatch_behavior {
-                        #[cfg(test)]
                         L1DataMismatchBehavior::Bail => {
                             anyhow::bail!("L1 batch #{batch_number} is inconsistent with L1");
                         }
@@ -6,7 +6,7 @@ use assert_matches::assert_matches;
 use test_casing::{test_casing, Product};
 use tokio::sync::mpsc;
 use zksync_dal::StorageProcessor;
-use zksync_eth_client::clients::MockEthereum;
+use zksync_eth_client::{clients::MockEthereum, BoundEthInterface};
 use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
 use zksync_types::{
     aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata,
@@ -44,15 +44,30 @@ fn create_pre_boojum_l1_batch_with_metadata(number: u32) -> L1BatchWithMetadata
     l1_batch
 }
 
+fn commit_function_selector(is_pre_boojum: bool) -> [u8; 4] {
+    if is_pre_boojum {
+        PRE_BOOJUM_COMMIT_FUNCTION.short_signature()
+    } else {
+        let contract = zksync_contracts::zksync_contract();
+        contract
+            .function("commitBatches")
+            .unwrap()
+            .short_signature()
+    }
+}
+
 fn build_commit_tx_input_data(batches: &[L1BatchWithMetadata]) -> Vec<u8> {
     let commit_tokens = batches
         .iter()
-        .map(|batch| CommitBatchInfo(batch).into_token());
+        .map(|batch| CommitBatchInfo::new(batch, PubdataDA::Calldata).into_token());
     let commit_tokens = ethabi::Token::Array(commit_tokens.collect());
 
+    let is_pre_boojum = batches[0]
+        .header
+        .protocol_version
+        .map_or(true, |version| version.is_pre_boojum());
     let mut encoded = vec![];
-    // Fake Solidity function selector (not checked for now)
-    encoded.extend_from_slice(b"fake");
+    encoded.extend_from_slice(&commit_function_selector(is_pre_boojum));
     // Mock an additional argument used in real `commitBlocks` / `commitBatches`. In real transactions,
     // it's taken from the L1 batch previous to `batches[0]`, but since this argument is not checked,
     // it's OK to use `batches[0]`.
@@ -61,8 +76,11 @@ fn build_commit_tx_input_data(batches: &[L1BatchWithMetadata]) -> Vec<u8> {
     encoded
 }
 
+const VALIDATOR_TIMELOCK_ADDR: Address = Address::repeat_byte(0x0a);
+
 fn create_mock_checker(client: MockEthereum, pool: ConnectionPool) -> ConsistencyChecker {
     let (health_check, health_updater) = ConsistencyCheckerHealthUpdater::new();
+    let diamond_proxy_addr = client.contract_addr();
     ConsistencyChecker {
         contract: zksync_contracts::zksync_contract(),
         max_batches_to_recheck: 100,
@@ -70,6 +88,8 @@ fn create_mock_checker(client: MockEthereum, pool: ConnectionPool) -> Consistenc
         l1_client: Box::new(client),
         event_handler: Box::new(health_updater),
         l1_data_mismatch_behavior: L1DataMismatchBehavior::Bail,
+        diamond_proxy_addr: Some(diamond_proxy_addr),
+        validator_timelock_addr: Some(VALIDATOR_TIMELOCK_ADDR),
         pool,
         health_check,
     }
@@ -93,6 +113,28 @@ impl HandleConsistencyCheckerEvent for mpsc::UnboundedSender<L1BatchNumber> {
     }
 }
 
+/// Event handler reporting both consistent (`true`) and inconsistent (`false`) L1 batches.
+#[derive(Debug)]
+struct AllBatchesReporter(mpsc::UnboundedSender<(L1BatchNumber, bool)>);
+
+impl HandleConsistencyCheckerEvent for AllBatchesReporter {
+    fn initialize(&mut self) {
+        // Do nothing
+    }
+
+    fn set_first_batch_to_check(&mut self, _first_batch_to_check: L1BatchNumber) {
+        // Do nothing
+    }
+
+    fn update_checked_batch(&mut self, last_checked_batch: L1BatchNumber) {
+        self.0.send((last_checked_batch, true)).ok();
+    }
+
+    fn report_inconsistent_batch(&mut self, number: L1BatchNumber) {
+        self.0.send((number, false)).ok();
+    }
+}
+
 #[test]
 fn build_commit_tx_input_data_is_correct() {
     let contract = zksync_contracts::zksync_contract();
@@ -111,10 +153,36 @@ fn build_commit_tx_input_data_is_correct() {
             batch.header.number,
         )
         .unwrap();
-        assert_eq!(commit_data, CommitBatchInfo(batch).into_token());
+        assert_eq!(
+            commit_data,
+            CommitBatchInfo::new(batch, PubdataDA::Calldata).into_token()
+        );
     }
 }
 
+#[test]
+fn reporting_mismatched_commitment_fields() {
+    let l1_batch = create_l1_batch_with_metadata(1);
+    let local = LocalL1BatchCommitData {
+        is_pre_boojum: false,
+        l1_batch: l1_batch.clone(),
+        commit_tx_hash: H256::zero(),
+    };
+    let reference = CommitBatchInfo::new(&l1_batch, PubdataDA::Calldata).into_token();
+    assert!(local.verify_commitment(&reference));
+
+    let mut bogus_l1_batch = l1_batch;
+    bogus_l1_batch.header.timestamp += 1;
+    bogus_l1_batch.metadata.merkle_root_hash = H256::repeat_byte(0xff);
+    let bogus_reference = CommitBatchInfo::new(&bogus_l1_batch, PubdataDA::Calldata).into_token();
+    assert!(!local.verify_commitment(&bogus_reference));
+    let local_commitment = local.build_commitment(&bogus_reference);
+    assert_eq!(
+        local.mismatched_fields(&local_commitment, &bogus_reference),
+        ["timestamp", "newStateRoot"]
+    );
+}
+
 #[test]
 fn extracting_commit_data_for_boojum_batch() {
     let contract = zksync_contracts::zksync_contract();
@@ -145,6 +213,37 @@ fn extracting_commit_data_for_boojum_batch() {
     }
 }
 
+#[test]
+fn detecting_pubdata_da_from_commitment() {
+    let commitment = |pubdata_commitments: Vec<u8>| {
+        ethabi::Token::Tuple(vec![
+            ethabi::Token::Uint(1.into()),
+            ethabi::Token::Bytes(pubdata_commitments),
+        ])
+    };
+
+    assert_eq!(
+        LocalL1BatchCommitData::detect_pubdata_da(&commitment(vec![0, 1, 2])),
+        Some(PubdataDA::Calldata)
+    );
+    assert_eq!(
+        LocalL1BatchCommitData::detect_pubdata_da(&commitment(vec![1; 145])),
+        Some(PubdataDA::Blobs)
+    );
+    assert_eq!(
+        LocalL1BatchCommitData::detect_pubdata_da(&commitment(vec![2, 3])),
+        Some(PubdataDA::Custom)
+    );
+    assert_eq!(
+        LocalL1BatchCommitData::detect_pubdata_da(&commitment(vec![])),
+        None
+    );
+    assert_eq!(
+        LocalL1BatchCommitData::detect_pubdata_da(&commitment(vec![42])),
+        None
+    );
+}
+
 #[test]
 fn extracting_commit_data_for_multiple_batches() {
     let contract = zksync_contracts::zksync_contract();
@@ -369,6 +468,59 @@ async fn normal_checker_function(
     checker_task.await.unwrap().unwrap();
 }
 
+#[tokio::test]
+async fn checker_accepts_commit_tx_sent_to_validator_timelock() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batch = create_l1_batch_with_metadata(1);
+    let client = MockEthereum::default();
+    let signed_tx = client
+        .sign_prepared_tx_for_addr(
+            build_commit_tx_input_data(slice::from_ref(&l1_batch)),
+            VALIDATOR_TIMELOCK_ADDR,
+            Options {
+                nonce: Some(0.into()),
+                ..Options::default()
+            },
+            "test",
+        )
+        .await
+        .unwrap();
+    client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    client.execute_tx(signed_tx.hash, true, 1);
+
+    let commit_tx_hash_by_l1_batch = HashMap::from([(l1_batch.header.number, signed_tx.hash)]);
+    let save_actions = [
+        SaveAction::InsertBatch(&l1_batch),
+        SaveAction::SaveMetadata(&l1_batch),
+        SaveAction::InsertCommitTx(l1_batch.header.number),
+    ];
+    for save_action in save_actions {
+        save_action
+            .apply(&mut storage, &commit_tx_hash_by_l1_batch)
+            .await;
+    }
+    drop(storage);
+
+    let (l1_batch_updates_sender, mut l1_batch_updates_receiver) = mpsc::unbounded_channel();
+    let checker = ConsistencyChecker {
+        event_handler: Box::new(AllBatchesReporter(l1_batch_updates_sender)),
+        ..create_mock_checker(client, pool)
+    };
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    let update = l1_batch_updates_receiver.recv().await.unwrap();
+    assert_eq!(update, (L1BatchNumber(1), true));
+
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+}
+
 #[test_casing(4, SAVE_ACTION_MAPPERS)]
 #[tokio::test]
 async fn checker_processes_pre_boojum_batches(
@@ -517,19 +669,24 @@ enum IncorrectDataKind {
     MismatchedCommitDataTimestamp,
     CommitDataForAnotherBatch,
     CommitDataForPreBoojum,
+    MismatchedSelector,
+    MismatchedRecipient,
 }
 
 impl IncorrectDataKind {
-    const ALL: [Self; 6] = [
+    const ALL: [Self; 8] = [
         Self::MissingStatus,
         Self::MismatchedStatus,
         Self::BogusCommitDataFormat,
         Self::MismatchedCommitDataTimestamp,
         Self::CommitDataForAnotherBatch,
         Self::CommitDataForPreBoojum,
+        Self::MismatchedSelector,
+        Self::MismatchedRecipient,
     ];
 
     async fn apply(self, client: &MockEthereum, l1_batch: &L1BatchWithMetadata) -> H256 {
+        let mut recipient = client.contract_addr();
         let (commit_tx_input_data, successful_status) = match self {
             Self::MissingStatus => {
                 return H256::zero(); // Do not execute the transaction
@@ -539,7 +696,7 @@ impl IncorrectDataKind {
                 (commit_tx_input_data, false)
             }
             Self::BogusCommitDataFormat => {
-                let mut bogus_tx_input_data = b"test".to_vec(); // Preserve the function selector
+                let mut bogus_tx_input_data = commit_function_selector(false).to_vec();
                 bogus_tx_input_data
                     .extend_from_slice(&ethabi::encode(&[ethabi::Token::Bool(true)]));
                 (bogus_tx_input_data, true)
@@ -561,15 +718,29 @@ impl IncorrectDataKind {
                 let bogus_tx_input_data = build_commit_tx_input_data(slice::from_ref(&l1_batch));
                 (bogus_tx_input_data, true)
             }
+            Self::MismatchedSelector => {
+                let mut bogus_tx_input_data = build_commit_tx_input_data(slice::from_ref(l1_batch));
+                bogus_tx_input_data[..4].copy_from_slice(b"fake");
+                (bogus_tx_input_data, true)
+            }
+            Self::MismatchedRecipient => {
+                recipient = Address::repeat_byte(0x33);
+                let commit_tx_input_data = build_commit_tx_input_data(slice::from_ref(l1_batch));
+                (commit_tx_input_data, true)
+            }
         };
 
-        let signed_tx = client.sign_prepared_tx(
-            commit_tx_input_data,
-            Options {
-                nonce: Some(0.into()),
-                ..Options::default()
-            },
-        );
+        let signed_tx = client
+            .sign_prepared_tx_for_addr(
+                commit_tx_input_data,
+                recipient,
+                Options {
+                    nonce: Some(0.into()),
+                    ..Options::default()
+                },
+                "test",
+            )
+            .await;
         let signed_tx = signed_tx.unwrap();
         client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
         client.execute_tx(signed_tx.hash, successful_status, 1);
@@ -577,7 +748,7 @@ impl IncorrectDataKind {
     }
 }
 
-#[test_casing(6, Product((IncorrectDataKind::ALL, [false])))]
+#[test_casing(8, Product((IncorrectDataKind::ALL, [false])))]
 // ^ `snapshot_recovery = true` is tested below; we don't want to run it with all incorrect data kinds
 #[tokio::test]
 async fn checker_detects_incorrect_tx_data(kind: IncorrectDataKind, snapshot_recovery: bool) {
@@ -624,3 +795,63 @@ async fn checker_detects_incorrect_tx_data(kind: IncorrectDataKind, snapshot_rec
 async fn checker_detects_incorrect_tx_data_after_snapshot_recovery() {
     checker_detects_incorrect_tx_data(IncorrectDataKind::CommitDataForAnotherBatch, true).await;
 }
+
+#[tokio::test]
+async fn checker_continues_after_inconsistent_batch_without_bailing() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batches: Vec<_> = (1..=2).map(create_l1_batch_with_metadata).collect();
+    let mut bogus_l1_batch = l1_batches[0].clone();
+    bogus_l1_batch.header.timestamp += 1;
+    let client = MockEthereum::default();
+    let mut commit_tx_hash_by_l1_batch = HashMap::new();
+    for (i, l1_batch) in [&bogus_l1_batch, &l1_batches[1]].into_iter().enumerate() {
+        let input_data = build_commit_tx_input_data(slice::from_ref(l1_batch));
+        let signed_tx = client.sign_prepared_tx(
+            input_data,
+            Options {
+                nonce: Some(i.into()),
+                ..Options::default()
+            },
+        );
+        let signed_tx = signed_tx.unwrap();
+        client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+        client.execute_tx(signed_tx.hash, true, 1);
+        commit_tx_hash_by_l1_batch.insert(l1_batch.header.number, signed_tx.hash);
+    }
+
+    for l1_batch in &l1_batches {
+        let save_actions = [
+            SaveAction::InsertBatch(l1_batch),
+            SaveAction::SaveMetadata(l1_batch),
+            SaveAction::InsertCommitTx(l1_batch.header.number),
+        ];
+        for save_action in save_actions {
+            save_action
+                .apply(&mut storage, &commit_tx_hash_by_l1_batch)
+                .await;
+        }
+    }
+    drop(storage);
+
+    let (l1_batch_updates_sender, mut l1_batch_updates_receiver) = mpsc::unbounded_channel();
+    let checker = ConsistencyChecker {
+        event_handler: Box::new(AllBatchesReporter(l1_batch_updates_sender)),
+        l1_data_mismatch_behavior: L1DataMismatchBehavior::Log,
+        ..create_mock_checker(client, pool)
+    };
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    let update = l1_batch_updates_receiver.recv().await.unwrap();
+    assert_eq!(update, (L1BatchNumber(1), false));
+    let update = l1_batch_updates_receiver.recv().await.unwrap();
+    assert_eq!(update, (L1BatchNumber(2), true));
+
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+}
@@ -0,0 +1,347 @@
+//! Client for the Celestia DA layer.
+
+use std::fmt;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zksync_types::{ethabi, pubdata_da::DABlobReference, L1BatchNumber};
+
+use super::{
+    clients::base64_bytes, DAPubdataPrice, DataAvailabilityClient, DispatchResponse, InclusionData,
+};
+
+/// Size of a Celestia namespace: a version byte followed by a 28-byte namespace ID.
+const NAMESPACE_SIZE: usize = 29;
+/// Max number of user-specified bytes in a version 0 namespace ID. The remaining bytes of the ID must be zero.
+const MAX_NAMESPACE_V0_ID_SIZE: usize = 10;
+/// Gas price passed to `blob.Submit`; a negative value makes the node estimate the price on its own.
+const DEFAULT_GAS_PRICE: f64 = -1.0;
+/// Gas charged by Celestia per byte of blob shares (the `GasPerBlobByte` parameter of the blob module).
+const GAS_PER_BLOB_BYTE: f64 = 8.0;
+/// Size of a Celestia share.
+const SHARE_SIZE: f64 = 512.0;
+/// Number of blob bytes in a continuation share of a sparse blob; the remaining bytes are taken
+/// by the namespace and the share info byte.
+const SHARE_PAYLOAD_SIZE: f64 = 482.0;
+
+/// Converts the gas price (in utia) to the price of a byte of blob data (also in utia). The fixed per-transaction
+/// gas is negligible for blobs with L1 batch pubdata and is not accounted for.
+pub(super) fn price_per_blob_byte(gas_price: f64) -> f64 {
+    gas_price * GAS_PER_BLOB_BYTE * SHARE_SIZE / SHARE_PAYLOAD_SIZE
+}
+
+/// Parses a hex-encoded version 0 namespace ID into a full Celestia namespace.
+pub(super) fn parse_namespace(namespace_id: &str) -> anyhow::Result<Vec<u8>> {
+    let namespace_id = namespace_id.strip_prefix("0x").unwrap_or(namespace_id);
+    let namespace_id =
+        hex::decode(namespace_id).context("namespace ID is not a valid hex string")?;
+    anyhow::ensure!(
+        !namespace_id.is_empty() && namespace_id.len() <= MAX_NAMESPACE_V0_ID_SIZE,
+        "namespace ID must have 1 to {MAX_NAMESPACE_V0_ID_SIZE} bytes, got {}",
+        namespace_id.len()
+    );
+    // Namespace version (0) and the ID are left-padded with zeros.
+    let mut namespace = vec![0; NAMESPACE_SIZE - namespace_id.len()];
+    namespace.extend_from_slice(&namespace_id);
+    Ok(namespace)
+}
+
+/// Blob IDs are formatted as `{height}:{hex-encoded commitment}`, which is sufficient to locate the blob
+/// given the namespace.
+pub(super) fn format_blob_id(height: u64, commitment: &[u8]) -> String {
+    format!("{height}:{}", hex::encode(commitment))
+}
+
+pub(super) fn parse_blob_id(blob_id: &str) -> anyhow::Result<(u64, Vec<u8>)> {
+    let (height, commitment) = blob_id
+        .split_once(':')
+        .with_context(|| format!("malformed Celestia blob ID `{blob_id}`"))?;
+    let height = height
+        .parse()
+        .with_context(|| format!("malformed height in Celestia blob ID `{blob_id}`"))?;
+    let commitment = hex::decode(commitment)
+        .with_context(|| format!("malformed commitment in Celestia blob ID `{blob_id}`"))?;
+    Ok((height, commitment))
+}
+
+/// Encodes the blob reference as the inclusion data passed to the settlement layer:
+/// `abi.encode(bytes29 namespace, uint256 height, bytes32 commitment)`.
+pub(super) fn encode_inclusion_data(reference: &DABlobReference) -> Vec<u8> {
+    ethabi::encode(&[
+        ethabi::Token::FixedBytes(reference.namespace.clone()),
+        ethabi::Token::Uint(reference.height.into()),
+        ethabi::Token::FixedBytes(reference.commitment.clone()),
+    ])
+}
+
+/// Decodes the blob reference from the inclusion data produced by [`encode_inclusion_data()`].
+pub(super) fn decode_inclusion_data(data: &[u8]) -> anyhow::Result<DABlobReference> {
+    let param_types = [
+        ethabi::ParamType::FixedBytes(NAMESPACE_SIZE),
+        ethabi::ParamType::Uint(256),
+        ethabi::ParamType::FixedBytes(32),
+    ];
+    let tokens = ethabi::decode(&param_types, data).context("malformed Celestia inclusion data")?;
+    let [namespace, height, commitment]: [ethabi::Token; 3] = tokens
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("unexpected number of tokens in Celestia inclusion data"))?;
+    let height = height.into_uint().context("height is not an integer")?;
+    anyhow::ensure!(height <= u64::MAX.into(), "height {height} is out of range");
+    Ok(DABlobReference {
+        namespace: namespace
+            .into_fixed_bytes()
+            .context("namespace is not a byte sequence")?,
+        height: height.as_u64(),
+        commitment: commitment
+            .into_fixed_bytes()
+            .context("commitment is not a byte sequence")?,
+    })
+}
+
+/// Blob in the format used by the Celestia node API.
+#[derive(Debug, Serialize, Deserialize)]
+struct CelestiaBlob {
+    #[serde(with = "base64_bytes")]
+    namespace: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    data: Vec<u8>,
+    share_version: u32,
+    /// Computed by the node; may be left empty when submitting a blob.
+    #[serde(with = "base64_bytes", default)]
+    commitment: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+impl JsonRpcError {
+    fn is_not_found(&self) -> bool {
+        self.message.contains("not found")
+    }
+}
+
+/// Client posting pubdata to Celestia via the JSON-RPC API of a Celestia light or bridge node.
+///
+/// Each L1 batch is posted as a single blob in the configured namespace. Since `blob.Submit` only returns
+/// after the blob is included into a Celestia block, the blob commitment is looked up right after
+/// the submission, and inclusion is confirmed by requesting the blob inclusion proof from the node.
+/// Pubdata prices are derived from the gas price estimated by the node (`state.EstimateGasPrice`).
+pub struct CelestiaClient {
+    client: reqwest::Client,
+    url: String,
+    auth_token: Option<String>,
+    namespace: Vec<u8>,
+}
+
+impl fmt::Debug for CelestiaClient {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("CelestiaClient")
+            .field("url", &self.url)
+            .field("namespace", &hex::encode(&self.namespace))
+            .finish_non_exhaustive()
+    }
+}
+
+impl CelestiaClient {
+    pub fn new(
+        url: String,
+        auth_token: Option<String>,
+        namespace_id: &str,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url,
+            auth_token,
+            namespace: parse_namespace(namespace_id).context("invalid Celestia namespace")?,
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> anyhow::Result<Result<T, JsonRpcError>> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params,
+        };
+        let mut request = self.client.post(&self.url).json(&request);
+        if let Some(auth_token) = &self.auth_token {
+            request = request.bearer_auth(auth_token);
+        }
+        let response: JsonRpcResponse<T> =
+            request
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+                .with_context(|| format!("failed parsing response to `{method}`"))?;
+
+        match (response.result, response.error) {
+            (_, Some(err)) => Ok(Err(err)),
+            (Some(result), None) => Ok(Ok(result)),
+            (None, None) => anyhow::bail!("response to `{method}` has neither result nor error"),
+        }
+    }
+
+    fn encoded_namespace(&self) -> String {
+        STANDARD.encode(&self.namespace)
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for CelestiaClient {
+    fn name(&self) -> &'static str {
+        "celestia"
+    }
+
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let blob = CelestiaBlob {
+            namespace: self.namespace.clone(),
+            data,
+            share_version: 0,
+            commitment: vec![],
+        };
+        let params = serde_json::json!([[&blob], DEFAULT_GAS_PRICE]);
+        let height: u64 = self.call("blob.Submit", params).await?.map_err(|err| {
+            anyhow::anyhow!(
+                "failed submitting blob for L1 batch #{l1_batch_number}: {} (code {})",
+                err.message,
+                err.code
+            )
+        })?;
+
+        // Commitments are computed by the node, so we need to find the submitted blob to learn its commitment.
+        let params = serde_json::json!([height, [self.encoded_namespace()]]);
+        let blobs: Vec<CelestiaBlob> = self.call("blob.GetAll", params).await?.map_err(|err| {
+            anyhow::anyhow!(
+                "failed getting blobs at height {height}: {} (code {})",
+                err.message,
+                err.code
+            )
+        })?;
+        let submitted_blob = blobs
+            .into_iter()
+            .find(|included_blob| included_blob.data == blob.data)
+            .with_context(|| {
+                format!("blob for L1 batch #{l1_batch_number} is missing at height {height}")
+            })?;
+
+        Ok(DispatchResponse {
+            blob_id: format_blob_id(height, &submitted_blob.commitment),
+            reference: Some(DABlobReference {
+                namespace: self.namespace.clone(),
+                height,
+                commitment: submitted_blob.commitment,
+            }),
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>> {
+        let (height, commitment) = parse_blob_id(blob_id)?;
+        let params = serde_json::json!([
+            height,
+            self.encoded_namespace(),
+            STANDARD.encode(&commitment)
+        ]);
+        let proof: Result<serde_json::Value, _> = self.call("blob.GetProof", params).await?;
+        match proof {
+            Ok(_) => {}
+            Err(err) if err.is_not_found() => return Ok(None),
+            Err(err) => anyhow::bail!(
+                "failed getting inclusion proof for blob `{blob_id}`: {} (code {})",
+                err.message,
+                err.code
+            ),
+        }
+
+        let reference = DABlobReference {
+            namespace: self.namespace.clone(),
+            height,
+            commitment,
+        };
+        Ok(Some(InclusionData {
+            data: encode_inclusion_data(&reference),
+        }))
+    }
+
+    /// Blobs published outside the namespace of this client are considered missing.
+    async fn fetch_blob(&self, inclusion_data: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        let reference = decode_inclusion_data(inclusion_data)?;
+        if reference.namespace != self.namespace {
+            tracing::warn!(
+                "Celestia blob at height {} is published in unexpected namespace {}",
+                reference.height,
+                hex::encode(&reference.namespace)
+            );
+            return Ok(None);
+        }
+
+        let params = serde_json::json!([
+            reference.height,
+            self.encoded_namespace(),
+            STANDARD.encode(&reference.commitment)
+        ]);
+        let blob: Result<CelestiaBlob, _> = self.call("blob.Get", params).await?;
+        match blob {
+            Ok(blob) => Ok(Some(blob.data)),
+            Err(err) if err.is_not_found() => Ok(None),
+            Err(err) => anyhow::bail!(
+                "failed getting blob at height {}: {} (code {})",
+                reference.height,
+                err.message,
+                err.code
+            ),
+        }
+    }
+
+    async fn pubdata_price(&self) -> anyhow::Result<Option<DAPubdataPrice>> {
+        // Use the default transaction config, so that the node estimates the price with the same priority
+        // as for submitted blobs.
+        let params = serde_json::json!([{}]);
+        let gas_price: f64 = self
+            .call("state.EstimateGasPrice", params)
+            .await?
+            .map_err(|err| {
+                anyhow::anyhow!(
+                    "failed estimating gas price: {} (code {})",
+                    err.message,
+                    err.code
+                )
+            })?;
+        anyhow::ensure!(
+            gas_price.is_finite() && gas_price >= 0.0,
+            "node returned invalid gas price: {gas_price}"
+        );
+        Ok(Some(DAPubdataPrice::NativeToken(price_per_blob_byte(
+            gas_price,
+        ))))
+    }
+}
@@ -0,0 +1,179 @@
+//! Data availability clients.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use zksync_types::{Bytes, L1BatchNumber};
+
+use super::{DAPubdataPrice, DataAvailabilityClient, DispatchResponse, InclusionData};
+
+/// Client for validium chains that don't publish pubdata anywhere. Blobs are "included" immediately
+/// with empty inclusion data.
+#[derive(Debug, Clone, Copy)]
+pub struct NoDAClient;
+
+#[async_trait]
+impl DataAvailabilityClient for NoDAClient {
+    fn name(&self) -> &'static str {
+        "no_da"
+    }
+
+    async fn dispatch_blob(
+        &self,
+        _l1_batch_number: L1BatchNumber,
+        _data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        Ok(DispatchResponse {
+            blob_id: String::new(),
+            reference: None,
+        })
+    }
+
+    async fn get_inclusion_data(&self, _blob_id: &str) -> anyhow::Result<Option<InclusionData>> {
+        Ok(Some(InclusionData { data: vec![] }))
+    }
+
+    /// Pubdata is not published, so it's free.
+    async fn pubdata_price(&self) -> anyhow::Result<Option<DAPubdataPrice>> {
+        Ok(Some(DAPubdataPrice::Wei(0.0)))
+    }
+}
+
+/// (De)serialization of byte sequences as base64 strings, used by the APIs of some DA layers.
+pub(super) mod base64_bytes {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    /// Treats `null` as an empty byte sequence.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DispatchBlobRequest {
+    l1_batch_number: u32,
+    data: Bytes,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DispatchBlobResponse {
+    blob_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InclusionDataResponse {
+    inclusion_data: Option<Bytes>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum PriceDenomination {
+    Wei,
+    NativeToken,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PriceResponse {
+    price_per_byte: f64,
+    denomination: PriceDenomination,
+}
+
+impl From<PriceResponse> for DAPubdataPrice {
+    fn from(response: PriceResponse) -> Self {
+        match response.denomination {
+            PriceDenomination::Wei => Self::Wei(response.price_per_byte),
+            PriceDenomination::NativeToken => Self::NativeToken(response.price_per_byte),
+        }
+    }
+}
+
+/// Client for an external DA layer exposing a simple HTTP API, usually implemented by a sidecar service
+/// in front of the DA provider:
+///
+/// - `POST /blobs` with a `{ "l1BatchNumber": _, "data": "0x.." }` JSON body dispatches a blob and returns
+///   `{ "blobId": _ }`.
+/// - `GET /blobs/{blobId}/inclusion_data` returns `{ "inclusionData": "0x.." }`, or `{ "inclusionData": null }`
+///   if the blob is not included yet.
+/// - Optionally, `GET /price` returns the current price of publishing a pubdata byte as
+///   `{ "pricePerByte": _, "denomination": "wei" | "nativeToken" }`. If the endpoint is missing, the price is unknown.
+#[derive(Debug)]
+pub struct HttpDAClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpDAClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for HttpDAClient {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let request = DispatchBlobRequest {
+            l1_batch_number: l1_batch_number.0,
+            data: Bytes(data),
+        };
+        let response: DispatchBlobResponse = self
+            .client
+            .post(format!("{}/blobs", self.url))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(DispatchResponse {
+            blob_id: response.blob_id,
+            reference: None,
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>> {
+        let response: InclusionDataResponse = self
+            .client
+            .get(format!("{}/blobs/{blob_id}/inclusion_data", self.url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response
+            .inclusion_data
+            .map(|data| InclusionData { data: data.0 }))
+    }
+
+    async fn pubdata_price(&self) -> anyhow::Result<Option<DAPubdataPrice>> {
+        let response = self
+            .client
+            .get(format!("{}/price", self.url))
+            .send()
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response: PriceResponse = response.error_for_status()?.json().await?;
+        Ok(Some(response.into()))
+    }
+}
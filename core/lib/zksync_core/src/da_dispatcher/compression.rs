@@ -0,0 +1,200 @@
+//! Compression of pubdata dispatched to DA layers.
+//!
+//! Unlike pubdata published on L1 (in calldata or EIP-4844 blobs), which is checked by the L1 contract
+//! and thus must have the format expected by it, pubdata dispatched to an external DA layer is only referenced
+//! by the inclusion data. Hence, it can be compressed with an arbitrary algorithm, as long as DA verifiers
+//! are able to decompress it.
+//!
+//! Compressed pubdata has the following format: `compressor ID (u8) || compressed length (u32, big-endian)
+//! || compressed data`. Data following the compressed data (e.g., zero padding added by the DA layer) is ignored.
+
+use std::{fmt, io::Read, path::Path};
+
+use anyhow::Context as _;
+use zksync_config::configs::eth_sender::DADispatcherConfig;
+use zksync_types::ProtocolVersionId;
+
+/// Size of the header prepended to compressed pubdata.
+const HEADER_SIZE: usize = 5;
+/// Upper bound on decompressed pubdata size to protect verifiers from decompression bombs.
+const MAX_DECOMPRESSED_SIZE: u64 = 64 << 20;
+
+/// Compression algorithm for pubdata.
+pub trait PubdataCompressor: fmt::Debug + Send + Sync {
+    /// ID of the algorithm recorded in compressed pubdata. Must be unique among compressors.
+    fn id(&self) -> u8;
+
+    fn compress(&self, pubdata: &[u8]) -> anyhow::Result<Vec<u8>>;
+
+    fn decompress(&self, compressed: &[u8]) -> anyhow::Result<Vec<u8>>;
+}
+
+/// Compressor storing pubdata as is.
+#[derive(Debug, Clone, Copy)]
+pub struct NoCompression;
+
+impl PubdataCompressor for NoCompression {
+    fn id(&self) -> u8 {
+        0
+    }
+
+    fn compress(&self, pubdata: &[u8]) -> anyhow::Result<Vec<u8>> {
+        Ok(pubdata.to_vec())
+    }
+
+    fn decompress(&self, compressed: &[u8]) -> anyhow::Result<Vec<u8>> {
+        Ok(compressed.to_vec())
+    }
+}
+
+/// zstd compressor, optionally using a dictionary. Since pubdata consists of many small similar records
+/// (e.g., state diffs, L2-to-L1 logs), a dictionary trained on recent pubdata significantly improves
+/// the compression ratio.
+pub struct ZstdCompressor {
+    level: i32,
+    dictionary: Vec<u8>,
+}
+
+impl fmt::Debug for ZstdCompressor {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ZstdCompressor")
+            .field("level", &self.level)
+            .field("dictionary_len", &self.dictionary.len())
+            .finish()
+    }
+}
+
+impl ZstdCompressor {
+    /// Compression level used by default. DA costs are usually much more important than compression speed.
+    pub const DEFAULT_LEVEL: i32 = 19;
+
+    pub fn new(level: i32) -> Self {
+        Self {
+            level,
+            dictionary: vec![],
+        }
+    }
+
+    pub fn with_dictionary(mut self, dictionary: Vec<u8>) -> Self {
+        self.dictionary = dictionary;
+        self
+    }
+}
+
+impl PubdataCompressor for ZstdCompressor {
+    fn id(&self) -> u8 {
+        1
+    }
+
+    fn compress(&self, pubdata: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut compressor = zstd::bulk::Compressor::with_dictionary(self.level, &self.dictionary)
+            .context("failed initializing zstd compressor")?;
+        compressor
+            .compress(pubdata)
+            .context("failed compressing pubdata")
+    }
+
+    fn decompress(&self, compressed: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let decoder = zstd::stream::Decoder::with_dictionary(compressed, &self.dictionary)
+            .context("failed initializing zstd decoder")?;
+        let mut decompressed = vec![];
+        decoder
+            .take(MAX_DECOMPRESSED_SIZE + 1)
+            .read_to_end(&mut decompressed)
+            .context("failed decompressing pubdata")?;
+        anyhow::ensure!(
+            decompressed.len() as u64 <= MAX_DECOMPRESSED_SIZE,
+            "decompressed pubdata exceeds {MAX_DECOMPRESSED_SIZE} bytes"
+        );
+        Ok(decompressed)
+    }
+}
+
+/// Pubdata compression enabled starting from a certain protocol version. Pubdata of earlier L1 batches
+/// is dispatched uncompressed, so that their pubdata can still be verified.
+#[derive(Debug)]
+pub struct PubdataCompression {
+    first_protocol_version: ProtocolVersionId,
+    compressor: Box<dyn PubdataCompressor>,
+}
+
+impl PubdataCompression {
+    pub fn new(
+        first_protocol_version: ProtocolVersionId,
+        compressor: Box<dyn PubdataCompressor>,
+    ) -> Self {
+        Self {
+            first_protocol_version,
+            compressor,
+        }
+    }
+
+    /// Creates zstd compression from the dispatcher config. Returns `Ok(None)` if compression is not enabled.
+    pub fn from_config(config: &DADispatcherConfig) -> anyhow::Result<Option<Self>> {
+        let Some(protocol_version) = config.compression_protocol_version else {
+            return Ok(None);
+        };
+        let level = match config.compression_level {
+            Some(level) => i32::try_from(level).context("compression level is too large")?,
+            None => ZstdCompressor::DEFAULT_LEVEL,
+        };
+        let compression = Self::zstd(
+            protocol_version,
+            level,
+            config.compression_dictionary_path.as_deref().map(Path::new),
+        )?;
+        Ok(Some(compression))
+    }
+
+    /// Creates zstd compression with an optional dictionary loaded from the file system.
+    pub fn zstd(
+        first_protocol_version: u16,
+        level: i32,
+        dictionary_path: Option<&Path>,
+    ) -> anyhow::Result<Self> {
+        let first_protocol_version = ProtocolVersionId::try_from(first_protocol_version)
+            .map_err(|_| anyhow::anyhow!("unknown protocol version {first_protocol_version}"))?;
+        let mut compressor = ZstdCompressor::new(level);
+        if let Some(path) = dictionary_path {
+            let dictionary = std::fs::read(path).with_context(|| {
+                format!("failed reading zstd dictionary from `{}`", path.display())
+            })?;
+            compressor = compressor.with_dictionary(dictionary);
+        }
+        Ok(Self::new(first_protocol_version, Box::new(compressor)))
+    }
+
+    /// Checks whether pubdata of an L1 batch with the specified protocol version is compressed.
+    pub fn applies_to(&self, protocol_version: Option<ProtocolVersionId>) -> bool {
+        protocol_version.map_or(false, |version| version >= self.first_protocol_version)
+    }
+
+    pub fn compress(&self, pubdata: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let compressed = self.compressor.compress(pubdata)?;
+        let len = u32::try_from(compressed.len()).context("compressed pubdata is too large")?;
+        let mut data = Vec::with_capacity(HEADER_SIZE + compressed.len());
+        data.push(self.compressor.id());
+        data.extend_from_slice(&len.to_be_bytes());
+        data.extend_from_slice(&compressed);
+        Ok(data)
+    }
+
+    pub fn decompress(&self, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        anyhow::ensure!(data.len() >= HEADER_SIZE, "compressed pubdata is too short");
+        let (header, payload) = data.split_at(HEADER_SIZE);
+        let len = u32::from_be_bytes(header[1..].try_into().unwrap()) as usize;
+        let compressed = payload.get(..len).with_context(|| {
+            format!(
+                "compressed pubdata is truncated: expected {len} bytes, got {}",
+                payload.len()
+            )
+        })?;
+
+        match header[0] {
+            id if id == NoCompression.id() => NoCompression.decompress(compressed),
+            id if id == self.compressor.id() => self.compressor.decompress(compressed),
+            id => anyhow::bail!("unsupported pubdata compressor ID {id}"),
+        }
+    }
+}
@@ -0,0 +1,419 @@
+//! Client for the EigenDA DA layer.
+
+use std::{slice, sync::Arc};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zksync_eth_client::EthInterface;
+use zksync_types::{
+    ethabi,
+    web3::{signing::keccak256, types::CallRequest},
+    Address, L1BatchNumber, U256,
+};
+
+use super::{
+    clients::base64_bytes, BlobDispersalFailed, DAPubdataPrice, DataAvailabilityClient,
+    DispatchResponse, InclusionData,
+};
+
+/// Number of payload bytes packed into a single 32-byte BN254 field element of an EigenDA blob.
+const BYTES_PER_FIELD_ELEMENT: usize = 31;
+
+/// Encodes data so that each 32-byte chunk is a valid BN254 field element, as required by EigenDA:
+/// each 31 bytes of the data are prefixed with a zero byte.
+pub(super) fn encode_blob_data(data: &[u8]) -> Vec<u8> {
+    let chunk_count = data.len().div_ceil(BYTES_PER_FIELD_ELEMENT);
+    let mut encoded = Vec::with_capacity(chunk_count * 32);
+    for chunk in data.chunks(BYTES_PER_FIELD_ELEMENT) {
+        encoded.push(0);
+        encoded.extend_from_slice(chunk);
+    }
+    encoded
+}
+
+/// Converts the on-demand EigenDA price per 32-byte symbol (in wei) to the price of a byte of pubdata.
+pub(super) fn price_per_pubdata_byte(price_per_symbol: u64) -> f64 {
+    price_per_symbol as f64 / BYTES_PER_FIELD_ELEMENT as f64
+}
+
+/// Inverse of [`encode_blob_data()`]. Trailing zero padding added by the disperser is retained.
+pub(super) fn decode_blob_data(data: &[u8]) -> Vec<u8> {
+    data.chunks(BYTES_PER_FIELD_ELEMENT + 1)
+        .flat_map(|chunk| chunk.get(1..).unwrap_or_default())
+        .copied()
+        .collect()
+}
+
+/// Location of a blob in an EigenDA batch, extracted from a blob certificate.
+#[derive(Debug, PartialEq)]
+pub(super) struct BlobLocation {
+    /// Hash of the batch header: `keccak256(abi.encode(BatchHeader))`.
+    pub batch_header_hash: [u8; 32],
+    pub blob_index: u32,
+}
+
+/// Extracts the blob location from the certificate produced by [`BlobInfo::encode_certificate()`].
+pub(super) fn decode_certificate(data: &[u8]) -> anyhow::Result<BlobLocation> {
+    use ethabi::{ParamType, Token};
+
+    let uint = || ParamType::Uint(256);
+    let batch_header_type = ParamType::Tuple(vec![
+        ParamType::FixedBytes(32),
+        ParamType::Bytes,
+        ParamType::Bytes,
+        uint(),
+    ]);
+    let header_type = ParamType::Tuple(vec![
+        ParamType::Tuple(vec![uint(), uint()]),
+        uint(),
+        ParamType::Array(Box::new(ParamType::Tuple(vec![uint(); 4]))),
+    ]);
+    let proof_type = ParamType::Tuple(vec![
+        uint(),
+        uint(),
+        ParamType::Tuple(vec![batch_header_type, ParamType::FixedBytes(32), uint()]),
+        ParamType::Bytes,
+        ParamType::Bytes,
+    ]);
+    let mut tokens = ethabi::decode(&[header_type, proof_type], data)
+        .context("malformed EigenDA blob certificate")?;
+
+    let Some(Token::Tuple(proof)) = tokens.pop() else {
+        anyhow::bail!("unexpected blob verification proof in EigenDA certificate");
+    };
+    let [_, Token::Uint(blob_index), Token::Tuple(metadata), ..] = proof.as_slice() else {
+        anyhow::bail!("unexpected blob verification proof in EigenDA certificate");
+    };
+    let Some(batch_header) = metadata.first() else {
+        anyhow::bail!("unexpected batch metadata in EigenDA certificate");
+    };
+    anyhow::ensure!(
+        *blob_index <= u32::MAX.into(),
+        "blob index {blob_index} is out of range"
+    );
+    Ok(BlobLocation {
+        batch_header_hash: keccak256(&ethabi::encode(slice::from_ref(batch_header))),
+        blob_index: blob_index.as_u32(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DisperseBlobRequest {
+    #[serde(with = "base64_bytes")]
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DisperseBlobReply {
+    #[serde(default)]
+    result: String,
+    #[serde(with = "base64_bytes", default)]
+    request_id: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobStatusRequest {
+    #[serde(with = "base64_bytes")]
+    request_id: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RetrieveBlobRequest {
+    #[serde(with = "base64_bytes")]
+    batch_header_hash: Vec<u8>,
+    blob_index: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RetrieveBlobReply {
+    #[serde(with = "base64_bytes", default)]
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobStatusReply {
+    #[serde(default)]
+    status: String,
+    info: Option<BlobInfo>,
+}
+
+// The types below mirror the messages of the disperser API. Fields with default values are omitted
+// in the JSON representation of protobuf messages, hence `#[serde(default)]` everywhere.
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(super) struct BlobInfo {
+    pub blob_header: BlobHeader,
+    pub blob_verification_proof: BlobVerificationProof,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(super) struct BlobHeader {
+    pub commitment: G1Commitment,
+    pub data_length: u32,
+    pub blob_quorum_params: Vec<BlobQuorumParam>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(super) struct G1Commitment {
+    #[serde(with = "base64_bytes")]
+    pub x: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub y: Vec<u8>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(super) struct BlobQuorumParam {
+    pub quorum_number: u32,
+    pub adversary_threshold_percentage: u32,
+    pub confirmation_threshold_percentage: u32,
+    pub chunk_length: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(super) struct BlobVerificationProof {
+    pub batch_id: u32,
+    pub blob_index: u32,
+    pub batch_metadata: BatchMetadata,
+    #[serde(with = "base64_bytes")]
+    pub inclusion_proof: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub quorum_indexes: Vec<u8>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(super) struct BatchMetadata {
+    pub batch_header: BatchHeader,
+    #[serde(with = "base64_bytes")]
+    pub signatory_record_hash: Vec<u8>,
+    pub confirmation_block_number: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(super) struct BatchHeader {
+    #[serde(with = "base64_bytes")]
+    pub batch_root: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub quorum_numbers: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub quorum_signed_percentages: Vec<u8>,
+    pub reference_block_number: u32,
+}
+
+impl BlobInfo {
+    /// Encodes the blob certificate as the inclusion data passed to the settlement layer:
+    /// `abi.encode(IEigenDAServiceManager.BlobHeader, EigenDARollupUtils.BlobVerificationProof)`.
+    pub fn encode_certificate(&self) -> Vec<u8> {
+        use ethabi::Token;
+
+        let uint = |value: u32| Token::Uint(value.into());
+        let header = &self.blob_header;
+        let quorum_params = header
+            .blob_quorum_params
+            .iter()
+            .map(|param| {
+                Token::Tuple(vec![
+                    uint(param.quorum_number),
+                    uint(param.adversary_threshold_percentage),
+                    uint(param.confirmation_threshold_percentage),
+                    uint(param.chunk_length),
+                ])
+            })
+            .collect();
+        let header = Token::Tuple(vec![
+            Token::Tuple(vec![
+                Token::Uint(U256::from_big_endian(&header.commitment.x)),
+                Token::Uint(U256::from_big_endian(&header.commitment.y)),
+            ]),
+            uint(header.data_length),
+            Token::Array(quorum_params),
+        ]);
+
+        let proof = &self.blob_verification_proof;
+        let metadata = &proof.batch_metadata;
+        let batch_header = &metadata.batch_header;
+        let proof = Token::Tuple(vec![
+            uint(proof.batch_id),
+            uint(proof.blob_index),
+            Token::Tuple(vec![
+                Token::Tuple(vec![
+                    Token::FixedBytes(batch_header.batch_root.clone()),
+                    Token::Bytes(batch_header.quorum_numbers.clone()),
+                    Token::Bytes(batch_header.quorum_signed_percentages.clone()),
+                    uint(batch_header.reference_block_number),
+                ]),
+                Token::FixedBytes(metadata.signatory_record_hash.clone()),
+                uint(metadata.confirmation_block_number),
+            ]),
+            Token::Bytes(proof.inclusion_proof.clone()),
+            Token::Bytes(proof.quorum_indexes.clone()),
+        ]);
+        ethabi::encode(&[header, proof])
+    }
+}
+
+/// Client dispersing pubdata to EigenDA via the disperser API (the `disperser.Disperser` gRPC service
+/// exposed with the HTTP / JSON transcoding, e.g. by a gRPC gateway in front of the disperser).
+///
+/// Dispersal is asynchronous: a dispersed blob is identified by the request ID returned by the disperser,
+/// and inclusion is polled until the blob is confirmed on Ethereum, at which point the blob certificate
+/// is available. Blobs rejected by the disperser are dispersed again.
+///
+/// If the `PaymentVault` contract is specified, pubdata prices are read from it (the on-demand price per symbol).
+#[derive(Debug)]
+pub struct EigenDAClient {
+    client: reqwest::Client,
+    url: String,
+    payment_vault: Option<(Arc<dyn EthInterface>, Address)>,
+}
+
+impl EigenDAClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_owned(),
+            payment_vault: None,
+        }
+    }
+
+    /// Specifies the EigenDA `PaymentVault` contract on L1 used to determine pubdata prices.
+    pub fn with_payment_vault(
+        mut self,
+        l1_client: Arc<dyn EthInterface>,
+        address: Address,
+    ) -> Self {
+        self.payment_vault = Some((l1_client, address));
+        self
+    }
+
+    async fn call<Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        method: &str,
+        request: &Req,
+    ) -> anyhow::Result<Resp> {
+        self.send(method, request)
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("failed parsing response to `{method}`"))
+    }
+
+    async fn send<Req: Serialize>(
+        &self,
+        method: &str,
+        request: &Req,
+    ) -> reqwest::Result<reqwest::Response> {
+        self.client
+            .post(format!("{}/disperser.Disperser/{method}", self.url))
+            .json(request)
+            .send()
+            .await
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for EigenDAClient {
+    fn name(&self) -> &'static str {
+        "eigenda"
+    }
+
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let request = DisperseBlobRequest {
+            data: encode_blob_data(&data),
+        };
+        let reply: DisperseBlobReply = self.call("DisperseBlob", &request).await?;
+        anyhow::ensure!(
+            !reply.request_id.is_empty(),
+            "disperser has not accepted blob for L1 batch #{l1_batch_number}: {}",
+            reply.result
+        );
+        Ok(DispatchResponse {
+            blob_id: hex::encode(reply.request_id),
+            reference: None,
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>> {
+        let request = BlobStatusRequest {
+            request_id: hex::decode(blob_id)
+                .with_context(|| format!("malformed EigenDA blob ID `{blob_id}`"))?,
+        };
+        let reply: BlobStatusReply = self.call("GetBlobStatus", &request).await?;
+        match reply.status.as_str() {
+            "CONFIRMED" | "FINALIZED" => {
+                let info = reply.info.with_context(|| {
+                    format!("no certificate for confirmed EigenDA blob `{blob_id}`")
+                })?;
+                Ok(Some(InclusionData {
+                    data: info.encode_certificate(),
+                }))
+            }
+            "FAILED" | "INSUFFICIENT_SIGNATURES" => Err(BlobDispersalFailed {
+                blob_id: blob_id.to_owned(),
+                reason: reply.status,
+            }
+            .into()),
+            _ => Ok(None),
+        }
+    }
+
+    async fn fetch_blob(&self, inclusion_data: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        let location = decode_certificate(inclusion_data)?;
+        let request = RetrieveBlobRequest {
+            batch_header_hash: location.batch_header_hash.to_vec(),
+            blob_index: location.blob_index,
+        };
+        let response = self.send("RetrieveBlob", &request).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let reply: RetrieveBlobReply = response
+            .error_for_status()?
+            .json()
+            .await
+            .context("failed parsing response to `RetrieveBlob`")?;
+        Ok(Some(decode_blob_data(&reply.data)))
+    }
+    async fn pubdata_price(&self) -> anyhow::Result<Option<DAPubdataPrice>> {
+        let Some((l1_client, address)) = &self.payment_vault else {
+            return Ok(None);
+        };
+        let request = CallRequest {
+            to: Some(*address),
+            data: Some(
+                ethabi::short_signature("pricePerSymbol", &[])
+                    .to_vec()
+                    .into(),
+            ),
+            ..CallRequest::default()
+        };
+        let output = l1_client.call(request, None, "eigenda_client").await?;
+        let tokens = ethabi::decode(&[ethabi::ParamType::Uint(64)], &output.0)
+            .context("failed decoding `PaymentVault.pricePerSymbol()` output")?;
+        let price_per_symbol = tokens
+            .into_iter()
+            .next()
+            .and_then(ethabi::Token::into_uint)
+            .context("unexpected `PaymentVault.pricePerSymbol()` output")?;
+        Ok(Some(DAPubdataPrice::Wei(price_per_pubdata_byte(
+            price_per_symbol.low_u64(),
+        ))))
+    }
+}
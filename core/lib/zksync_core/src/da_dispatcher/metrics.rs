@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, Metrics, Unit};
+
+/// Metrics for the DA dispatcher.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_da_dispatcher")]
+pub(super) struct DataAvailabilityDispatcherMetrics {
+    /// Latency of dispatching a blob to the DA layer.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub blob_dispatch_latency: Histogram<Duration>,
+    /// Latency between dispatching a blob and receiving its inclusion data.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub inclusion_latency: Histogram<Duration>,
+    /// Size of a dispatched blob.
+    #[metrics(buckets = Buckets::exponential(1_024.0..=1_048_576.0, 2.0), unit = Unit::Bytes)]
+    pub blob_size: Histogram<usize>,
+    /// Size of a dispatched blob after compression; only reported if pubdata compression is enabled.
+    #[metrics(buckets = Buckets::exponential(1_024.0..=1_048_576.0, 2.0), unit = Unit::Bytes)]
+    pub compressed_blob_size: Histogram<usize>,
+    /// Number of the last L1 batch dispatched to the DA layer.
+    pub last_dispatched_l1_batch: Gauge<u64>,
+    /// Number of the last L1 batch with received inclusion data.
+    pub last_included_l1_batch: Gauge<u64>,
+    /// Number of blobs rejected by the DA layer and scheduled for re-dispatching.
+    pub failed_dispersals: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DataAvailabilityDispatcherMetrics> = vise::Global::new();
@@ -0,0 +1,294 @@
+//! Dispatching of L1 batch pubdata to data availability (DA) layers for chains that don't publish pubdata
+//! on the settlement layer.
+
+use std::fmt;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::watch;
+use zksync_config::configs::{chain::DataAvailabilityMode, eth_sender::DADispatcherConfig};
+use zksync_dal::ConnectionPool;
+use zksync_types::{pubdata_da::DABlobReference, L1BatchNumber};
+
+use self::metrics::METRICS;
+pub use self::{
+    celestia::CelestiaClient,
+    clients::{HttpDAClient, NoDAClient},
+    compression::{NoCompression, PubdataCompression, PubdataCompressor, ZstdCompressor},
+    eigenda::EigenDAClient,
+};
+
+mod celestia;
+mod clients;
+mod compression;
+mod eigenda;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Response of a DA layer to a dispatched blob.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DispatchResponse {
+    /// Identifier of the blob in the DA layer used to poll for its inclusion.
+    pub blob_id: String,
+    /// Location of the blob in the DA layer; only set for DA layers organizing blobs by namespaces.
+    pub reference: Option<DABlobReference>,
+}
+
+/// Data proving inclusion of a blob into a DA layer. It is passed to the settlement layer
+/// as a part of the commit transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InclusionData {
+    pub data: Vec<u8>,
+}
+
+/// Error returned by [`DataAvailabilityClient::get_inclusion_data()`] if the DA layer has failed to include
+/// the blob, e.g. because it was rejected. The blob is then dispatched again.
+#[derive(Debug, thiserror::Error)]
+#[error("dispersal of blob `{blob_id}` has failed: {reason}")]
+pub struct BlobDispersalFailed {
+    pub blob_id: String,
+    pub reason: String,
+}
+
+/// Price of publishing a byte of pubdata on a DA layer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DAPubdataPrice {
+    /// Price in wei.
+    Wei(f64),
+    /// Price in the smallest units of the DA layer native token (e.g., utia for Celestia).
+    NativeToken(f64),
+}
+
+/// Client of a data availability layer.
+#[async_trait]
+pub trait DataAvailabilityClient: fmt::Debug + Send + Sync {
+    /// Name of the client used in logs and metrics.
+    fn name(&self) -> &'static str;
+
+    /// Dispatches pubdata of the specified L1 batch to the DA layer.
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse>;
+
+    /// Returns the inclusion data for a previously dispatched blob, or `None` if the blob is not included
+    /// into the DA layer yet. Returns [`BlobDispersalFailed`] if the blob will never be included.
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>>;
+
+    /// Retrieves pubdata referenced by the inclusion data published on the settlement layer. Returns `None`
+    /// if the DA layer doesn't have the referenced blob. Used by external nodes to verify data availability;
+    /// not all DA layers support retrieval.
+    async fn fetch_blob(&self, _inclusion_data: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        anyhow::bail!("{} DA layer does not support blob retrieval", self.name())
+    }
+
+    /// Returns the current price of publishing a byte of pubdata on the DA layer, or `None` if the DA layer
+    /// doesn't expose its prices. Used to price pubdata in the fee model.
+    async fn pubdata_price(&self) -> anyhow::Result<Option<DAPubdataPrice>> {
+        Ok(None)
+    }
+}
+
+/// Creates a DA client for the specified mode. Returns an error for rollup chains, which publish pubdata
+/// on the settlement layer and thus must not be configured with a DA layer.
+pub fn create_da_client(
+    mode: DataAvailabilityMode,
+    config: &DADispatcherConfig,
+) -> anyhow::Result<Box<dyn DataAvailabilityClient>> {
+    Ok(match mode {
+        DataAvailabilityMode::Rollup => {
+            anyhow::bail!(
+                "DA client cannot be created for a rollup chain, which publishes pubdata on L1"
+            )
+        }
+        DataAvailabilityMode::Validium => Box::new(NoDAClient),
+        DataAvailabilityMode::External => {
+            let url = config.external_da_url.clone().ok_or_else(|| {
+                anyhow::anyhow!("`external_da_url` must be set for the external DA mode")
+            })?;
+            Box::new(HttpDAClient::new(url))
+        }
+        DataAvailabilityMode::Celestia => {
+            let url = config.celestia_node_url.clone().ok_or_else(|| {
+                anyhow::anyhow!("`celestia_node_url` must be set for the Celestia DA mode")
+            })?;
+            let namespace = config.celestia_namespace.as_deref().ok_or_else(|| {
+                anyhow::anyhow!("`celestia_namespace` must be set for the Celestia DA mode")
+            })?;
+            let client = CelestiaClient::new(url, config.celestia_auth_token(), namespace)?;
+            Box::new(client)
+        }
+        DataAvailabilityMode::EigenDA => {
+            let url = config.eigenda_disperser_url.clone().ok_or_else(|| {
+                anyhow::anyhow!("`eigenda_disperser_url` must be set for the EigenDA mode")
+            })?;
+            Box::new(EigenDAClient::new(url))
+        }
+    })
+}
+
+/// Task dispatching pubdata of sealed L1 batches to a DA layer and polling for their inclusion.
+/// Commit operations for validium chains and chains using an external DA layer are only created
+/// for L1 batches with saved inclusion data.
+#[derive(Debug)]
+pub struct DataAvailabilityDispatcher {
+    client: Box<dyn DataAvailabilityClient>,
+    pool: ConnectionPool,
+    config: DADispatcherConfig,
+    compression: Option<PubdataCompression>,
+}
+
+impl DataAvailabilityDispatcher {
+    pub fn new(
+        client: Box<dyn DataAvailabilityClient>,
+        pool: ConnectionPool,
+        config: DADispatcherConfig,
+    ) -> Self {
+        Self {
+            client,
+            pool,
+            config,
+            compression: None,
+        }
+    }
+
+    /// Enables compression of dispatched pubdata.
+    pub fn with_compression(mut self, compression: PubdataCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, DA dispatcher is shutting down");
+                break;
+            }
+
+            if let Err(err) = self.dispatch().await {
+                tracing::warn!(
+                    "Failed dispatching pubdata to {} DA layer: {err:#}",
+                    self.client.name()
+                );
+            }
+            if let Err(err) = self.poll_for_inclusion().await {
+                tracing::warn!(
+                    "Failed polling {} DA layer for inclusion data: {err:#}",
+                    self.client.name()
+                );
+            }
+            tokio::time::sleep(self.config.polling_interval()).await;
+        }
+        Ok(())
+    }
+
+    /// Dispatches pubdata of L1 batches that are not dispatched yet.
+    pub(crate) async fn dispatch(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+        let batches = storage
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(self.config.max_batches_to_dispatch as usize)
+            .await?;
+        drop(storage);
+
+        for batch in batches {
+            METRICS.blob_size.observe(batch.pubdata.len());
+            let blob = match &self.compression {
+                Some(compression) if compression.applies_to(batch.protocol_version) => {
+                    let compressed = compression.compress(&batch.pubdata).with_context(|| {
+                        format!(
+                            "failed compressing pubdata for L1 batch #{}",
+                            batch.l1_batch_number
+                        )
+                    })?;
+                    METRICS.compressed_blob_size.observe(compressed.len());
+                    compressed
+                }
+                _ => batch.pubdata,
+            };
+
+            let dispatch_latency = METRICS.blob_dispatch_latency.start();
+            let sent_at = Utc::now();
+            let response = self
+                .client
+                .dispatch_blob(batch.l1_batch_number, blob)
+                .await?;
+            dispatch_latency.observe();
+
+            let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+            storage
+                .data_availability_dal()
+                .insert_l1_batch_da(
+                    batch.l1_batch_number,
+                    &response.blob_id,
+                    response.reference.as_ref(),
+                    sent_at,
+                )
+                .await?;
+            tracing::info!(
+                "Dispatched pubdata for L1 batch #{} to {} DA layer; blob ID: {}",
+                batch.l1_batch_number,
+                self.client.name(),
+                response.blob_id
+            );
+            METRICS
+                .last_dispatched_l1_batch
+                .set(batch.l1_batch_number.0.into());
+        }
+        Ok(())
+    }
+
+    /// Polls the DA layer for inclusion data of the dispatched blobs.
+    pub(crate) async fn poll_for_inclusion(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+        let blobs = storage
+            .data_availability_dal()
+            .get_blobs_awaiting_inclusion(self.config.max_batches_to_dispatch as usize)
+            .await?;
+        drop(storage);
+
+        for blob in blobs {
+            let inclusion_data = match self.client.get_inclusion_data(&blob.blob_id).await {
+                Ok(Some(inclusion_data)) => inclusion_data,
+                Ok(None) => continue,
+                Err(err) => {
+                    let Some(err) = err.downcast_ref::<BlobDispersalFailed>() else {
+                        return Err(err);
+                    };
+                    tracing::warn!(
+                        "{err} (L1 batch #{}); scheduling pubdata for re-dispatching",
+                        blob.l1_batch_number
+                    );
+                    METRICS.failed_dispersals.inc();
+                    let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+                    storage
+                        .data_availability_dal()
+                        .remove_l1_batch_da(blob.l1_batch_number)
+                        .await?;
+                    continue;
+                }
+            };
+            let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+            storage
+                .data_availability_dal()
+                .save_l1_batch_inclusion_data(blob.l1_batch_number, &inclusion_data.data)
+                .await?;
+            tracing::info!(
+                "Received inclusion data for L1 batch #{} from {} DA layer",
+                blob.l1_batch_number,
+                self.client.name()
+            );
+            let inclusion_latency = Utc::now() - blob.sent_at;
+            if let Ok(latency) = inclusion_latency.to_std() {
+                METRICS.inclusion_latency.observe(latency);
+            }
+            METRICS
+                .last_included_l1_batch
+                .set(blob.l1_batch_number.0.into());
+        }
+        Ok(())
+    }
+}
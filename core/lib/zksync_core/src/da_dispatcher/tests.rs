@@ -0,0 +1,535 @@
+//! Tests for the DA dispatcher.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use zksync_types::{ethabi, web3::signing::keccak256, ProtocolVersion, ProtocolVersionId};
+
+use super::*;
+use crate::utils::testonly::create_l1_batch;
+
+/// DA client recording dispatched blobs. Blobs are only included once explicitly requested.
+#[derive(Debug, Default)]
+struct MockDAClient {
+    dispatched_blobs: Mutex<Vec<(L1BatchNumber, Vec<u8>)>>,
+    included_blobs: Mutex<HashSet<String>>,
+    failed_blobs: Mutex<HashSet<String>>,
+}
+
+impl MockDAClient {
+    fn include_blob(&self, l1_batch_number: L1BatchNumber) {
+        self.included_blobs
+            .lock()
+            .unwrap()
+            .insert(format!("blob{l1_batch_number}"));
+    }
+
+    fn fail_blob(&self, l1_batch_number: L1BatchNumber) {
+        self.failed_blobs
+            .lock()
+            .unwrap()
+            .insert(format!("blob{l1_batch_number}"));
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for Arc<MockDAClient> {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        self.dispatched_blobs
+            .lock()
+            .unwrap()
+            .push((l1_batch_number, data));
+        Ok(DispatchResponse {
+            blob_id: format!("blob{l1_batch_number}"),
+            reference: None,
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>> {
+        if self.failed_blobs.lock().unwrap().remove(blob_id) {
+            return Err(BlobDispersalFailed {
+                blob_id: blob_id.to_owned(),
+                reason: "rejected".to_owned(),
+            }
+            .into());
+        }
+        let is_included = self.included_blobs.lock().unwrap().contains(blob_id);
+        Ok(is_included.then(|| InclusionData {
+            data: blob_id.as_bytes().to_vec(),
+        }))
+    }
+}
+
+fn config() -> DADispatcherConfig {
+    DADispatcherConfig {
+        polling_interval_ms: 10,
+        max_batches_to_dispatch: 10,
+        external_da_url: None,
+        celestia_node_url: None,
+        celestia_namespace: None,
+        eigenda_disperser_url: None,
+        failover_timeout_ms: None,
+        compression_protocol_version: None,
+        compression_level: None,
+        compression_dictionary_path: None,
+    }
+}
+
+async fn prepare_storage(pool: &ConnectionPool, l1_batch_count: u32) {
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    for number in 0..=l1_batch_count {
+        let mut header = create_l1_batch(number);
+        header.pubdata_input = Some(vec![number as u8; 16]);
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+    }
+}
+
+async fn get_inclusion_data(pool: &ConnectionPool, last_l1_batch: u32) -> Vec<Vec<u8>> {
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .data_availability_dal()
+        .get_inclusion_data_for_range(L1BatchNumber(1)..=L1BatchNumber(last_l1_batch))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn dispatching_pubdata_to_external_da_layer() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 2).await;
+    let client = Arc::new(MockDAClient::default());
+    let dispatcher =
+        DataAvailabilityDispatcher::new(Box::new(client.clone()), pool.clone(), config());
+
+    dispatcher.dispatch().await.unwrap();
+    let dispatched_blobs = client.dispatched_blobs.lock().unwrap().clone();
+    assert_eq!(
+        dispatched_blobs,
+        [
+            (L1BatchNumber(1), vec![1; 16]),
+            (L1BatchNumber(2), vec![2; 16])
+        ]
+    );
+    // Dispatched blobs must not be dispatched again.
+    dispatcher.dispatch().await.unwrap();
+    assert_eq!(client.dispatched_blobs.lock().unwrap().len(), 2);
+
+    dispatcher.poll_for_inclusion().await.unwrap();
+    assert!(get_inclusion_data(&pool, 2).await.is_empty());
+
+    client.include_blob(L1BatchNumber(1));
+    dispatcher.poll_for_inclusion().await.unwrap();
+    assert_eq!(get_inclusion_data(&pool, 2).await, [b"blob1".to_vec()]);
+
+    client.include_blob(L1BatchNumber(2));
+    dispatcher.poll_for_inclusion().await.unwrap();
+    assert_eq!(
+        get_inclusion_data(&pool, 2).await,
+        [b"blob1".to_vec(), b"blob2".to_vec()]
+    );
+}
+
+#[tokio::test]
+async fn failed_blobs_are_dispatched_again() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 2).await;
+    let client = Arc::new(MockDAClient::default());
+    let dispatcher =
+        DataAvailabilityDispatcher::new(Box::new(client.clone()), pool.clone(), config());
+
+    dispatcher.dispatch().await.unwrap();
+    client.fail_blob(L1BatchNumber(1));
+    client.include_blob(L1BatchNumber(2));
+    dispatcher.poll_for_inclusion().await.unwrap();
+    // L1 batch #2 is included, but cannot be committed before L1 batch #1.
+    assert!(get_inclusion_data(&pool, 2).await.is_empty());
+
+    dispatcher.dispatch().await.unwrap();
+    let dispatched_batches: Vec<_> = client
+        .dispatched_blobs
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(number, _)| *number)
+        .collect();
+    assert_eq!(
+        dispatched_batches,
+        [L1BatchNumber(1), L1BatchNumber(2), L1BatchNumber(1)]
+    );
+
+    client.include_blob(L1BatchNumber(1));
+    dispatcher.poll_for_inclusion().await.unwrap();
+    assert_eq!(
+        get_inclusion_data(&pool, 2).await,
+        [b"blob1".to_vec(), b"blob2".to_vec()]
+    );
+}
+
+#[tokio::test]
+async fn validium_pubdata_is_included_immediately() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let client = create_da_client(DataAvailabilityMode::Validium, &config()).unwrap();
+    let dispatcher = DataAvailabilityDispatcher::new(client, pool.clone(), config());
+
+    dispatcher.dispatch().await.unwrap();
+    dispatcher.poll_for_inclusion().await.unwrap();
+    assert_eq!(
+        get_inclusion_data(&pool, 3).await,
+        vec![Vec::<u8>::new(); 3]
+    );
+}
+
+#[tokio::test]
+async fn dispatching_compressed_pubdata() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 1).await;
+    let client = Arc::new(MockDAClient::default());
+    let compression = PubdataCompression::new(
+        ProtocolVersionId::latest(),
+        Box::new(ZstdCompressor::new(3)),
+    );
+    let dispatcher =
+        DataAvailabilityDispatcher::new(Box::new(client.clone()), pool.clone(), config())
+            .with_compression(compression);
+
+    dispatcher.dispatch().await.unwrap();
+    let dispatched_blobs = client.dispatched_blobs.lock().unwrap().clone();
+    assert_eq!(dispatched_blobs.len(), 1);
+    let (l1_batch_number, blob) = &dispatched_blobs[0];
+    assert_eq!(*l1_batch_number, L1BatchNumber(1));
+    assert_eq!(blob[0], ZstdCompressor::new(3).id());
+    let compression = dispatcher.compression.as_ref().unwrap();
+    assert_eq!(compression.decompress(blob).unwrap(), [1; 16]);
+}
+
+fn mock_pubdata(seed: u8) -> Vec<u8> {
+    // Resembles compressed state diffs: a derived key or an enumeration index followed by a short value.
+    (0..200_u32)
+        .flat_map(|i| {
+            let mut entry = vec![seed; 4];
+            entry.extend_from_slice(&i.to_be_bytes());
+            entry.extend_from_slice(&[0x21, seed ^ (i as u8)]);
+            entry
+        })
+        .collect()
+}
+
+#[test]
+fn pubdata_compression_roundtrip() {
+    let pubdata = mock_pubdata(1);
+    let compressors: [Box<dyn PubdataCompressor>; 3] = [
+        Box::new(NoCompression),
+        Box::new(ZstdCompressor::new(ZstdCompressor::DEFAULT_LEVEL)),
+        // Raw content dictionaries are supported by zstd as well as trained ones.
+        Box::new(ZstdCompressor::new(3).with_dictionary(mock_pubdata(2))),
+    ];
+    for compressor in compressors {
+        let compressor_id = compressor.id();
+        let compression = PubdataCompression::new(ProtocolVersionId::latest(), compressor);
+        let compressed = compression.compress(&pubdata).unwrap();
+        assert_eq!(compressed[0], compressor_id);
+        if compressor_id != NoCompression.id() {
+            assert!(compressed.len() < pubdata.len(), "{compression:?}");
+        }
+        assert_eq!(compression.decompress(&compressed).unwrap(), pubdata);
+
+        // Padding added by the DA layer must be ignored.
+        let mut padded = compressed.clone();
+        padded.resize(compressed.len() + 100, 0);
+        assert_eq!(compression.decompress(&padded).unwrap(), pubdata);
+
+        let err = compression
+            .decompress(&compressed[..compressed.len() - 1])
+            .unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{err}");
+    }
+}
+
+#[test]
+fn decompressing_pubdata_with_unknown_compressor() {
+    let compression = PubdataCompression::new(
+        ProtocolVersionId::latest(),
+        Box::new(ZstdCompressor::new(3)),
+    );
+    // Uncompressed pubdata is always supported.
+    let uncompressed =
+        PubdataCompression::new(ProtocolVersionId::latest(), Box::new(NoCompression))
+            .compress(b"test")
+            .unwrap();
+    assert_eq!(compression.decompress(&uncompressed).unwrap(), b"test");
+
+    let err = compression.decompress(&[42, 0, 0, 0, 0]).unwrap_err();
+    assert!(err.to_string().contains("unsupported"), "{err}");
+    compression.decompress(&[1, 0]).unwrap_err();
+}
+
+#[test]
+fn pubdata_compression_is_gated_by_protocol_version() {
+    let compression = PubdataCompression::zstd(21, 3, None).unwrap();
+    assert!(compression.applies_to(Some(ProtocolVersionId::Version21)));
+    assert!(!compression.applies_to(Some(ProtocolVersionId::Version20)));
+    assert!(!compression.applies_to(None));
+
+    PubdataCompression::zstd(u16::MAX, 3, None).unwrap_err();
+    assert!(PubdataCompression::from_config(&config())
+        .unwrap()
+        .is_none());
+    let compression_config = DADispatcherConfig {
+        compression_protocol_version: Some(21),
+        ..config()
+    };
+    let compression = PubdataCompression::from_config(&compression_config)
+        .unwrap()
+        .unwrap();
+    assert!(compression.applies_to(Some(ProtocolVersionId::Version21)));
+}
+
+#[test]
+fn creating_da_clients() {
+    let err = create_da_client(DataAvailabilityMode::Rollup, &config()).unwrap_err();
+    assert!(err.to_string().contains("rollup"), "{err}");
+    let err = create_da_client(DataAvailabilityMode::External, &config()).unwrap_err();
+    assert!(err.to_string().contains("external_da_url"), "{err}");
+
+    let external_config = DADispatcherConfig {
+        external_da_url: Some("http://127.0.0.1:3073/".to_owned()),
+        ..config()
+    };
+    let client = create_da_client(DataAvailabilityMode::External, &external_config).unwrap();
+    assert_eq!(client.name(), "http");
+}
+
+#[test]
+fn parsing_celestia_namespace() {
+    let namespace = celestia::parse_namespace("0x1d3c0a").unwrap();
+    assert_eq!(namespace.len(), 29);
+    assert!(namespace[..26].iter().all(|&byte| byte == 0));
+    assert_eq!(namespace[26..], [0x1d, 0x3c, 0x0a]);
+
+    celestia::parse_namespace("").unwrap_err();
+    celestia::parse_namespace("not hex").unwrap_err();
+    // Version 0 namespace IDs are limited to 10 bytes.
+    celestia::parse_namespace(&"01".repeat(11)).unwrap_err();
+}
+
+#[test]
+fn celestia_blob_id_roundtrip() {
+    let blob_id = celestia::format_blob_id(123, &[0xab; 32]);
+    assert_eq!(blob_id, format!("123:{}", "ab".repeat(32)));
+    let (height, commitment) = celestia::parse_blob_id(&blob_id).unwrap();
+    assert_eq!(height, 123);
+    assert_eq!(commitment, [0xab; 32]);
+
+    celestia::parse_blob_id("123").unwrap_err();
+    celestia::parse_blob_id("height:abab").unwrap_err();
+}
+
+#[test]
+fn encoding_celestia_inclusion_data() {
+    let reference = DABlobReference {
+        namespace: celestia::parse_namespace("1d3c0a").unwrap(),
+        height: 123,
+        commitment: vec![0xab; 32],
+    };
+    let inclusion_data = celestia::encode_inclusion_data(&reference);
+    assert_eq!(inclusion_data.len(), 3 * 32);
+    // `bytes29` is right-padded to 32 bytes.
+    assert_eq!(inclusion_data[..29], reference.namespace[..]);
+    assert_eq!(inclusion_data[29..32], [0; 3]);
+    assert_eq!(inclusion_data[63], 123);
+    assert_eq!(inclusion_data[64..], [0xab; 32]);
+
+    let decoded = celestia::decode_inclusion_data(&inclusion_data).unwrap();
+    assert_eq!(decoded, reference);
+    celestia::decode_inclusion_data(&inclusion_data[..64]).unwrap_err();
+}
+
+#[test]
+fn creating_celestia_client() {
+    let err = create_da_client(DataAvailabilityMode::Celestia, &config()).unwrap_err();
+    assert!(err.to_string().contains("celestia_node_url"), "{err}");
+
+    let celestia_config = DADispatcherConfig {
+        celestia_node_url: Some("http://127.0.0.1:26658".to_owned()),
+        celestia_namespace: Some("1d3c0a".to_owned()),
+        ..config()
+    };
+    let client = create_da_client(DataAvailabilityMode::Celestia, &celestia_config).unwrap();
+    assert_eq!(client.name(), "celestia");
+}
+
+#[test]
+fn encoding_eigenda_blob_data() {
+    assert_eq!(eigenda::encode_blob_data(&[]), Vec::<u8>::new());
+    assert_eq!(eigenda::encode_blob_data(&[1, 2]), [0, 1, 2]);
+    let encoded = eigenda::encode_blob_data(&[1; 40]);
+    assert_eq!(encoded.len(), 42);
+    assert_eq!(encoded[0], 0);
+    assert_eq!(encoded[32], 0);
+    assert!(encoded[1..32]
+        .iter()
+        .chain(&encoded[33..])
+        .all(|&byte| byte == 1));
+}
+
+#[test]
+fn decoding_eigenda_blob_data() {
+    assert_eq!(eigenda::decode_blob_data(&[]), Vec::<u8>::new());
+    for len in [1, 31, 32, 40, 62, 100] {
+        let data: Vec<u8> = (1..=len).collect();
+        let encoded = eigenda::encode_blob_data(&data);
+        assert_eq!(eigenda::decode_blob_data(&encoded), data);
+    }
+
+    // The disperser may pad blobs to a whole number of field elements.
+    let mut encoded = eigenda::encode_blob_data(&[1, 2]);
+    encoded.resize(64, 0);
+    let decoded = eigenda::decode_blob_data(&encoded);
+    assert_eq!(decoded.len(), 62);
+    assert_eq!(decoded[..2], [1, 2]);
+    assert!(decoded[2..].iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn encoding_eigenda_certificate() {
+    // Protobuf JSON encoding omits fields with default values, such as `quorumNumber` for quorum 0.
+    let info = serde_json::json!({
+        "blobHeader": {
+            "commitment": { "x": "AQ==", "y": "Ag==" },
+            "dataLength": 4,
+            "blobQuorumParams": [{
+                "adversaryThresholdPercentage": 33,
+                "confirmationThresholdPercentage": 55,
+                "chunkLength": 1,
+            }],
+        },
+        "blobVerificationProof": {
+            "batchId": 12,
+            "blobIndex": 3,
+            "batchMetadata": {
+                "batchHeader": {
+                    "batchRoot": "q".repeat(43) + "=",
+                    "quorumNumbers": "AA==",
+                    "quorumSignedPercentages": "Xw==",
+                    "referenceBlockNumber": 100,
+                },
+                "signatoryRecordHash": "u".repeat(43) + "=",
+                "confirmationBlockNumber": 110,
+            },
+            "inclusionProof": "",
+            "quorumIndexes": "AA==",
+        },
+    });
+    let info: eigenda::BlobInfo = serde_json::from_value(info).unwrap();
+    assert_eq!(info.blob_header.blob_quorum_params[0].quorum_number, 0);
+    assert_eq!(
+        info.blob_verification_proof
+            .batch_metadata
+            .batch_header
+            .batch_root
+            .len(),
+        32
+    );
+
+    let certificate = info.encode_certificate();
+    let tokens = ethabi::decode(
+        &[
+            ethabi::ParamType::Tuple(vec![
+                ethabi::ParamType::Tuple(vec![ethabi::ParamType::Uint(256); 2]),
+                ethabi::ParamType::Uint(32),
+                ethabi::ParamType::Array(Box::new(ethabi::ParamType::Tuple(vec![
+                    ethabi::ParamType::Uint(8),
+                    ethabi::ParamType::Uint(8),
+                    ethabi::ParamType::Uint(8),
+                    ethabi::ParamType::Uint(32),
+                ]))),
+            ]),
+            ethabi::ParamType::Tuple(vec![
+                ethabi::ParamType::Uint(32),
+                ethabi::ParamType::Uint(32),
+                ethabi::ParamType::Tuple(vec![
+                    ethabi::ParamType::Tuple(vec![
+                        ethabi::ParamType::FixedBytes(32),
+                        ethabi::ParamType::Bytes,
+                        ethabi::ParamType::Bytes,
+                        ethabi::ParamType::Uint(32),
+                    ]),
+                    ethabi::ParamType::FixedBytes(32),
+                    ethabi::ParamType::Uint(32),
+                ]),
+                ethabi::ParamType::Bytes,
+                ethabi::ParamType::Bytes,
+            ]),
+        ],
+        &certificate,
+    )
+    .unwrap();
+    let ethabi::Token::Tuple(proof) = &tokens[1] else {
+        panic!("unexpected proof token: {:?}", tokens[1]);
+    };
+    assert_eq!(proof[0], ethabi::Token::Uint(12.into()));
+    assert_eq!(proof[1], ethabi::Token::Uint(3.into()));
+
+    let ethabi::Token::Tuple(batch_metadata) = &proof[2] else {
+        panic!("unexpected batch metadata token: {:?}", proof[2]);
+    };
+    let expected_batch_header_hash = keccak256(&ethabi::encode(&batch_metadata[..1]));
+    let location = eigenda::decode_certificate(&certificate).unwrap();
+    assert_eq!(
+        location,
+        eigenda::BlobLocation {
+            batch_header_hash: expected_batch_header_hash,
+            blob_index: 3,
+        }
+    );
+    eigenda::decode_certificate(&certificate[..64]).unwrap_err();
+}
+
+#[test]
+fn creating_eigenda_client() {
+    let err = create_da_client(DataAvailabilityMode::EigenDA, &config()).unwrap_err();
+    assert!(err.to_string().contains("eigenda_disperser_url"), "{err}");
+
+    let eigenda_config = DADispatcherConfig {
+        eigenda_disperser_url: Some("http://127.0.0.1:3074".to_owned()),
+        ..config()
+    };
+    let client = create_da_client(DataAvailabilityMode::EigenDA, &eigenda_config).unwrap();
+    assert_eq!(client.name(), "eigenda");
+}
+
+#[test]
+fn converting_da_layer_prices() {
+    // 0.002 utia per gas * 8 gas per byte, adjusted for the share overhead
+    let price = celestia::price_per_blob_byte(0.002);
+    assert!((price - 0.016 * 512.0 / 482.0).abs() < 1e-12, "{price}");
+    assert_eq!(eigenda::price_per_pubdata_byte(31_000), 1_000.0);
+}
+
+#[tokio::test]
+async fn validium_pubdata_is_free() {
+    let price = NoDAClient.pubdata_price().await.unwrap();
+    assert_eq!(price, Some(DAPubdataPrice::Wei(0.0)));
+
+    let client = EigenDAClient::new("http://127.0.0.1:3074".to_owned());
+    assert_eq!(client.pubdata_price().await.unwrap(), None);
+}
@@ -0,0 +1,130 @@
+//! Client for the L1 beacon node API used to retrieve EIP-4844 blob sidecars.
+
+use std::fmt;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::sync::OnceCell;
+use zksync_types::Bytes;
+
+/// Blob sidecar of an L1 block.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BlobSidecar {
+    pub blob: Bytes,
+    pub kzg_commitment: Bytes,
+}
+
+/// Source of blob sidecars for L1 blocks.
+#[async_trait]
+pub trait BlobSidecarSource: fmt::Debug + Send + Sync {
+    /// Returns all blob sidecars of the L1 block with the specified timestamp. Returns an empty list
+    /// if the sidecars are not available (e.g., were pruned by the beacon node).
+    async fn fetch_sidecars(&self, l1_block_timestamp: u64) -> anyhow::Result<Vec<BlobSidecar>>;
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconResponse<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct Genesis {
+    genesis_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Spec {
+    #[serde(rename = "SECONDS_PER_SLOT")]
+    seconds_per_slot: String,
+}
+
+/// Parameters of the beacon chain necessary to map L1 block timestamps to slots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct SlotClock {
+    pub genesis_time: u64,
+    pub seconds_per_slot: u64,
+}
+
+impl SlotClock {
+    pub fn slot(&self, timestamp: u64) -> anyhow::Result<u64> {
+        anyhow::ensure!(self.seconds_per_slot > 0, "slot duration is zero");
+        anyhow::ensure!(
+            timestamp >= self.genesis_time,
+            "timestamp {timestamp} precedes beacon chain genesis at {}",
+            self.genesis_time
+        );
+        Ok((timestamp - self.genesis_time) / self.seconds_per_slot)
+    }
+}
+
+/// [`BlobSidecarSource`] using the standard beacon node REST API.
+#[derive(Debug)]
+pub struct BeaconClient {
+    client: reqwest::Client,
+    url: String,
+    slot_clock: OnceCell<SlotClock>,
+}
+
+impl BeaconClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_owned(),
+            slot_clock: OnceCell::new(),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<Option<T>> {
+        let response = self
+            .client
+            .get(format!("{}/eth/v1/{path}", self.url))
+            .send()
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response: BeaconResponse<T> = response
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("failed parsing response to `{path}`"))?;
+        Ok(Some(response.data))
+    }
+
+    async fn slot_clock(&self) -> anyhow::Result<SlotClock> {
+        let slot_clock = self
+            .slot_clock
+            .get_or_try_init(|| async {
+                let genesis: Genesis = self
+                    .get("beacon/genesis")
+                    .await?
+                    .context("beacon node has no genesis")?;
+                let spec: Spec = self
+                    .get("config/spec")
+                    .await?
+                    .context("beacon node has no chain spec")?;
+                anyhow::Ok(SlotClock {
+                    genesis_time: genesis
+                        .genesis_time
+                        .parse()
+                        .context("malformed genesis time")?,
+                    seconds_per_slot: spec
+                        .seconds_per_slot
+                        .parse()
+                        .context("malformed slot duration")?,
+                })
+            })
+            .await?;
+        Ok(*slot_clock)
+    }
+}
+
+#[async_trait]
+impl BlobSidecarSource for BeaconClient {
+    async fn fetch_sidecars(&self, l1_block_timestamp: u64) -> anyhow::Result<Vec<BlobSidecar>> {
+        let slot = self.slot_clock().await?.slot(l1_block_timestamp)?;
+        let sidecars = self.get(&format!("beacon/blob_sidecars/{slot}")).await?;
+        Ok(sidecars.unwrap_or_default())
+    }
+}
@@ -0,0 +1,411 @@
+//! Verification of pubdata availability for external nodes. For each L1 batch committed on L1, the verifier
+//! retrieves the pubdata referenced by the L1 commitment from the DA layer (EIP-4844 blob sidecars,
+//! or an external DA layer such as Celestia or EigenDA) and compares it with the locally known pubdata.
+
+use std::{ops, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::{clients::QueryClient, Error as L1ClientError, EthInterface};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_l1_contract_interface::i_executor::{commit::kzg::KzgInfo, structures::CommitBatchInfo};
+use zksync_types::{
+    pubdata_da::PubdataDA,
+    web3::{
+        ethabi,
+        types::{BlockId, BlockNumber},
+    },
+    L1BatchNumber,
+};
+
+pub use self::beacon::{BeaconClient, BlobSidecar, BlobSidecarSource};
+use crate::{
+    consistency_checker::{ConsistencyChecker, LocalL1BatchCommitData},
+    da_dispatcher::{DataAvailabilityClient, PubdataCompression},
+    metrics::{CheckerComponent, EN_METRICS},
+    utils::wait_for_l1_batch_with_metadata,
+};
+
+mod beacon;
+#[cfg(test)]
+mod tests;
+
+/// Size of a single blob commitment in `pubdataCommitments`:
+/// opening point (16 bytes) || claimed value (32 bytes) || commitment (48 bytes) || opening proof (48 bytes).
+const BYTES_PER_PUBDATA_COMMITMENT: usize = 144;
+/// Location of the KZG commitment in a blob commitment.
+const KZG_COMMITMENT_RANGE: ops::Range<usize> = 48..96;
+
+#[derive(Debug, thiserror::Error)]
+enum VerifyError {
+    #[error("Web3 error communicating with L1")]
+    Web3(#[from] L1ClientError),
+    #[error("Error communicating with the DA layer")]
+    DALayer(#[source] anyhow::Error),
+    #[error("Internal error")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<zksync_dal::SqlxError> for VerifyError {
+    fn from(err: zksync_dal::SqlxError) -> Self {
+        Self::Internal(err.into())
+    }
+}
+
+/// Outcome of the DA verification for a single L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+enum Verification {
+    /// Pubdata retrieved from the DA layer matches the local pubdata.
+    Verified,
+    /// Pubdata is not verified, e.g. because it's published in calldata (and thus checked by the consistency checker),
+    /// not published at all (validium), or because the DA layer is not configured.
+    Skipped,
+    /// Pubdata is missing from the DA layer, or doesn't match the local pubdata.
+    Mismatch(String),
+}
+
+/// Verifies blob sidecars of the commit transaction against the blobs computed from the local pubdata
+/// and the KZG commitments published on L1 (`pubdata_commitments` without the source flag).
+fn verify_blobs(
+    local_blobs: &[KzgInfo],
+    pubdata_commitments: &[u8],
+    sidecars: &[BlobSidecar],
+) -> Verification {
+    if pubdata_commitments.len() != local_blobs.len() * BYTES_PER_PUBDATA_COMMITMENT {
+        return Verification::Mismatch(format!(
+            "expected {} blob commitments, got {} bytes of commitments on L1",
+            local_blobs.len(),
+            pubdata_commitments.len()
+        ));
+    }
+
+    let blob_commitments = pubdata_commitments.chunks(BYTES_PER_PUBDATA_COMMITMENT);
+    for (i, (local_blob, commitment)) in local_blobs.iter().zip(blob_commitments).enumerate() {
+        let kzg_commitment = &commitment[KZG_COMMITMENT_RANGE];
+        if local_blob.kzg_commitment[..] != *kzg_commitment {
+            return Verification::Mismatch(format!(
+                "KZG commitment for blob #{i} computed from local pubdata (0x{}) differs from the one on L1 (0x{})",
+                hex::encode(local_blob.kzg_commitment),
+                hex::encode(kzg_commitment)
+            ));
+        }
+        let Some(sidecar) = sidecars
+            .iter()
+            .find(|sidecar| sidecar.kzg_commitment.0 == kzg_commitment)
+        else {
+            return Verification::Mismatch(format!(
+                "no sidecar for blob #{i} with KZG commitment 0x{}",
+                hex::encode(kzg_commitment)
+            ));
+        };
+        if sidecar.blob.0 != local_blob.blob {
+            return Verification::Mismatch(format!(
+                "sidecar for blob #{i} differs from the blob computed from local pubdata"
+            ));
+        }
+    }
+    Verification::Verified
+}
+
+/// Verifies pubdata retrieved from an external DA layer against the local pubdata. Retrieved data may be padded
+/// with zeros by the DA layer.
+fn verify_external_pubdata(local_pubdata: &[u8], retrieved: Option<&[u8]>) -> Verification {
+    let Some(retrieved) = retrieved else {
+        return Verification::Mismatch("blob is missing from the DA layer".to_owned());
+    };
+    let padding = retrieved.get(local_pubdata.len()..);
+    let padding_is_zero = padding.map_or(false, |padding| padding.iter().all(|&byte| byte == 0));
+    if !retrieved.starts_with(local_pubdata) || !padding_is_zero {
+        return Verification::Mismatch(format!(
+            "blob retrieved from the DA layer ({} bytes) differs from local pubdata ({} bytes)",
+            retrieved.len(),
+            local_pubdata.len()
+        ));
+    }
+    Verification::Verified
+}
+
+/// Health details reported by [`DataAvailabilityVerifier`].
+#[derive(Debug, Default, Serialize)]
+struct DataAvailabilityVerifierDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_checked_batch: Option<L1BatchNumber>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mismatched_batches: Vec<L1BatchNumber>,
+}
+
+impl DataAvailabilityVerifierDetails {
+    fn health(&self) -> Health {
+        let status = if self.mismatched_batches.is_empty() {
+            HealthStatus::Ready
+        } else {
+            HealthStatus::Affected
+        };
+        Health::from(status).with_details(self)
+    }
+}
+
+/// External node component verifying that pubdata of committed L1 batches is available on the DA layer
+/// referenced by the L1 commitment. Mismatches are logged as errors, counted in metrics and make the component
+/// health [`HealthStatus::Affected`].
+#[derive(Debug)]
+pub struct DataAvailabilityVerifier {
+    /// ABI of the zkSync contract
+    contract: ethabi::Contract,
+    /// How many past batches to check when starting
+    max_batches_to_recheck: u32,
+    sleep_interval: Duration,
+    l1_client: Box<dyn EthInterface>,
+    sidecar_source: Option<Box<dyn BlobSidecarSource>>,
+    da_client: Option<Box<dyn DataAvailabilityClient>>,
+    compression: Option<PubdataCompression>,
+    pool: ConnectionPool,
+    health_updater: HealthUpdater,
+    health_check: ReactiveHealthCheck,
+    details: DataAvailabilityVerifierDetails,
+}
+
+impl DataAvailabilityVerifier {
+    const DEFAULT_SLEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(web3_url: &str, max_batches_to_recheck: u32, pool: ConnectionPool) -> Self {
+        let web3 = QueryClient::new(web3_url).unwrap();
+        let (health_check, health_updater) = ReactiveHealthCheck::new("da_verifier");
+        Self {
+            contract: zksync_contracts::zksync_contract(),
+            max_batches_to_recheck,
+            sleep_interval: Self::DEFAULT_SLEEP_INTERVAL,
+            l1_client: Box::new(web3),
+            sidecar_source: None,
+            da_client: None,
+            compression: None,
+            pool,
+            health_updater,
+            health_check,
+            details: DataAvailabilityVerifierDetails::default(),
+        }
+    }
+
+    /// Enables verification of pubdata published in EIP-4844 blobs.
+    pub fn with_blob_sidecar_source(mut self, source: Box<dyn BlobSidecarSource>) -> Self {
+        self.sidecar_source = Some(source);
+        self
+    }
+
+    /// Enables verification of pubdata published on an external DA layer. The client must support
+    /// [blob retrieval](DataAvailabilityClient::fetch_blob()).
+    pub fn with_da_client(mut self, client: Box<dyn DataAvailabilityClient>) -> Self {
+        self.da_client = Some(client);
+        self
+    }
+
+    /// Sets compression of pubdata dispatched to the external DA layer; must match the compression
+    /// used by the main node.
+    pub fn with_compression(mut self, compression: PubdataCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Returns health check associated with this verifier.
+    pub fn health_check(&self) -> &ReactiveHealthCheck {
+        &self.health_check
+    }
+
+    async fn verify_batch(
+        &self,
+        batch_number: L1BatchNumber,
+        local: &LocalL1BatchCommitData,
+    ) -> Result<Verification, VerifyError> {
+        let is_pre_1_4_2 = local
+            .l1_batch
+            .header
+            .protocol_version
+            .map_or(true, |version| version.is_pre_1_4_2());
+        if is_pre_1_4_2 {
+            return Ok(Verification::Skipped);
+        }
+
+        let commit_tx_hash = local.commit_tx_hash;
+        let commit_tx = self
+            .l1_client
+            .get_tx(commit_tx_hash, "da_verifier")
+            .await?
+            .with_context(|| format!("Commit tx {commit_tx_hash:?} not found on L1"))?;
+        let commit_function = self
+            .contract
+            .function("commitBatches")
+            .context("L1 contract does not have `commitBatches` function")?;
+        let commitment = ConsistencyChecker::extract_commit_data(
+            &commit_tx.input.0,
+            commit_function,
+            batch_number,
+        )
+        .with_context(|| {
+            format!("Failed extracting commit data for transaction {commit_tx_hash:?}")
+        })?;
+        let pubdata_commitments = LocalL1BatchCommitData::pubdata_commitments(&commitment)
+            .context("L1 commitment has no pubdata commitments")?;
+
+        match LocalL1BatchCommitData::detect_pubdata_da(&commitment) {
+            None => Ok(Verification::Mismatch(
+                "L1 commitment has unknown pubdata source".to_owned(),
+            )),
+            Some(PubdataDA::Calldata) => Ok(Verification::Skipped),
+            Some(PubdataDA::Blobs) => {
+                let Some(source) = &self.sidecar_source else {
+                    return Ok(Verification::Skipped);
+                };
+                let block_number = commit_tx
+                    .block_number
+                    .with_context(|| format!("Commit tx {commit_tx_hash:?} is not mined"))?;
+                let block_id = BlockId::Number(BlockNumber::Number(block_number));
+                let block = self
+                    .l1_client
+                    .block(block_id, "da_verifier")
+                    .await?
+                    .with_context(|| format!("L1 block #{block_number} not found"))?;
+                let sidecars = source
+                    .fetch_sidecars(block.timestamp.as_u64())
+                    .await
+                    .map_err(VerifyError::DALayer)?;
+                let local_blobs = CommitBatchInfo::new(&local.l1_batch, PubdataDA::Blobs).blobs();
+                Ok(verify_blobs(
+                    &local_blobs,
+                    &pubdata_commitments[1..],
+                    &sidecars,
+                ))
+            }
+            Some(PubdataDA::Custom) => {
+                let inclusion_data = &pubdata_commitments[1..];
+                // Validium batches have empty inclusion data; there's nothing to retrieve.
+                let Some(client) = self
+                    .da_client
+                    .as_deref()
+                    .filter(|_| !inclusion_data.is_empty())
+                else {
+                    return Ok(Verification::Skipped);
+                };
+                let retrieved = client
+                    .fetch_blob(inclusion_data)
+                    .await
+                    .map_err(VerifyError::DALayer)?;
+                let protocol_version = local.l1_batch.header.protocol_version;
+                let compression = self
+                    .compression
+                    .as_ref()
+                    .filter(|compression| compression.applies_to(protocol_version));
+                let retrieved = match (retrieved, compression) {
+                    (Some(blob), Some(compression)) => match compression.decompress(&blob) {
+                        Ok(decompressed) => Some(decompressed),
+                        Err(err) => {
+                            return Ok(Verification::Mismatch(format!(
+                                "cannot decompress blob retrieved from the DA layer: {err:#}"
+                            )));
+                        }
+                    },
+                    (retrieved, _) => retrieved,
+                };
+                let local_pubdata =
+                    CommitBatchInfo::new(&local.l1_batch, PubdataDA::Custom).pubdata_input();
+                Ok(verify_external_pubdata(
+                    &local_pubdata,
+                    retrieved.as_deref(),
+                ))
+            }
+        }
+    }
+
+    fn report(&mut self, batch_number: L1BatchNumber, verification: Verification) {
+        match verification {
+            Verification::Verified => {
+                tracing::info!("Pubdata of L1 batch #{batch_number} is available on the DA layer");
+                EN_METRICS.last_correct_batch[&CheckerComponent::DataAvailabilityVerifier]
+                    .set(batch_number.0.into());
+            }
+            Verification::Skipped => {
+                tracing::debug!("Skipped DA verification for L1 batch #{batch_number}");
+            }
+            Verification::Mismatch(reason) => {
+                tracing::error!(
+                    "Pubdata of L1 batch #{batch_number} is not available on the DA layer: {reason}"
+                );
+                EN_METRICS.da_mismatches.inc();
+                self.details.mismatched_batches.push(batch_number);
+            }
+        }
+        self.details.last_checked_batch = Some(batch_number);
+        self.health_updater.update(self.details.health());
+    }
+
+    async fn last_committed_batch(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        Ok(self
+            .pool
+            .access_storage()
+            .await?
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await?)
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater.update(self.details.health());
+
+        let earliest_l1_batch_number =
+            wait_for_l1_batch_with_metadata(&self.pool, self.sleep_interval, &mut stop_receiver)
+                .await?;
+        let Some(earliest_l1_batch_number) = earliest_l1_batch_number else {
+            return Ok(()); // Stop signal received
+        };
+
+        let last_committed_batch = self
+            .last_committed_batch()
+            .await?
+            .unwrap_or(earliest_l1_batch_number);
+        let first_batch_to_check: L1BatchNumber = last_committed_batch
+            .0
+            .saturating_sub(self.max_batches_to_recheck)
+            .into();
+        // The genesis batch is not committed on L1.
+        let mut batch_number = first_batch_to_check
+            .max(earliest_l1_batch_number)
+            .max(L1BatchNumber(1));
+        tracing::info!("Starting DA verification from L1 batch #{batch_number}");
+
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, DA verifier is shutting down");
+                break;
+            }
+
+            let mut storage = self.pool.access_storage_tagged("da_verifier").await?;
+            let Some(local) = LocalL1BatchCommitData::new(&mut storage, batch_number).await? else {
+                drop(storage);
+                tokio::time::sleep(self.sleep_interval).await;
+                continue;
+            };
+            drop(storage);
+
+            match self.verify_batch(batch_number, &local).await {
+                Ok(verification) => {
+                    self.report(batch_number, verification);
+                    batch_number += 1;
+                }
+                Err(VerifyError::Web3(err)) => {
+                    tracing::warn!("Error accessing L1; will retry after a delay: {err}");
+                    tokio::time::sleep(self.sleep_interval).await;
+                }
+                Err(VerifyError::DALayer(err)) => {
+                    tracing::warn!("Error accessing DA layer; will retry after a delay: {err:#}");
+                    tokio::time::sleep(self.sleep_interval).await;
+                }
+                Err(VerifyError::Internal(err)) => {
+                    let context = format!("Failed verifying DA for L1 batch #{batch_number}");
+                    return Err(err.context(context));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+//! Tests for the DA verifier.
+
+use assert_matches::assert_matches;
+use zksync_types::Bytes;
+
+use super::{beacon::SlotClock, *};
+
+fn mock_kzg_info(byte: u8) -> KzgInfo {
+    KzgInfo {
+        blob: [byte; 4_096 * 32],
+        kzg_commitment: [byte; 48],
+        opening_point: [0; 32],
+        opening_value: [0; 32],
+        opening_proof: [0; 48],
+        versioned_hash: [0; 32],
+        blob_proof: [0; 48],
+    }
+}
+
+fn pubdata_commitments(kzg_commitments: &[[u8; 48]]) -> Vec<u8> {
+    kzg_commitments
+        .iter()
+        .flat_map(|kzg_commitment| {
+            let mut commitment = [0; BYTES_PER_PUBDATA_COMMITMENT];
+            commitment[KZG_COMMITMENT_RANGE].copy_from_slice(kzg_commitment);
+            commitment
+        })
+        .collect()
+}
+
+fn sidecar(kzg_info: &KzgInfo) -> BlobSidecar {
+    BlobSidecar {
+        blob: Bytes(kzg_info.blob.to_vec()),
+        kzg_commitment: Bytes(kzg_info.kzg_commitment.to_vec()),
+    }
+}
+
+#[test]
+fn verifying_blobs() {
+    let local_blobs = [mock_kzg_info(1), mock_kzg_info(2)];
+    let commitments = pubdata_commitments(&[[1; 48], [2; 48]]);
+    // Sidecars may be ordered arbitrarily and include blobs from other transactions.
+    let sidecars = [
+        sidecar(&mock_kzg_info(3)),
+        sidecar(&local_blobs[1]),
+        sidecar(&local_blobs[0]),
+    ];
+    assert_eq!(
+        verify_blobs(&local_blobs, &commitments, &sidecars),
+        Verification::Verified
+    );
+
+    let verification = verify_blobs(
+        &local_blobs,
+        &commitments[..BYTES_PER_PUBDATA_COMMITMENT],
+        &sidecars,
+    );
+    assert_matches!(verification, Verification::Mismatch(reason) if reason.contains("expected 2 blob commitments"));
+
+    let other_commitments = pubdata_commitments(&[[1; 48], [3; 48]]);
+    let verification = verify_blobs(&local_blobs, &other_commitments, &sidecars);
+    assert_matches!(verification, Verification::Mismatch(reason) if reason.contains("KZG commitment for blob #1"));
+
+    let verification = verify_blobs(&local_blobs, &commitments, &sidecars[1..2]);
+    assert_matches!(verification, Verification::Mismatch(reason) if reason.contains("no sidecar for blob #0"));
+
+    let mut corrupted_sidecar = sidecar(&local_blobs[0]);
+    corrupted_sidecar.blob.0[100] ^= 1;
+    let sidecars = [corrupted_sidecar, sidecar(&local_blobs[1])];
+    let verification = verify_blobs(&local_blobs, &commitments, &sidecars);
+    assert_matches!(verification, Verification::Mismatch(reason) if reason.contains("sidecar for blob #0 differs"));
+}
+
+#[test]
+fn verifying_external_pubdata() {
+    let local_pubdata = b"pubdata";
+    assert_eq!(
+        verify_external_pubdata(local_pubdata, Some(&b"pubdata"[..])),
+        Verification::Verified
+    );
+    assert_eq!(
+        verify_external_pubdata(local_pubdata, Some(&b"pubdata\0\0\0"[..])),
+        Verification::Verified
+    );
+
+    for retrieved in [
+        &b"pubdat"[..],
+        &b"pubdata!"[..],
+        &b"pubdata\0!"[..],
+        &b"PUBDATA"[..],
+    ] {
+        assert_matches!(
+            verify_external_pubdata(local_pubdata, Some(retrieved)),
+            Verification::Mismatch(_)
+        );
+    }
+    assert_matches!(
+        verify_external_pubdata(local_pubdata, None),
+        Verification::Mismatch(reason) if reason.contains("missing")
+    );
+}
+
+#[test]
+fn mapping_timestamps_to_beacon_slots() {
+    let clock = SlotClock {
+        genesis_time: 1_606_824_023,
+        seconds_per_slot: 12,
+    };
+    assert_eq!(clock.slot(1_606_824_023).unwrap(), 0);
+    assert_eq!(clock.slot(1_606_824_035).unwrap(), 1);
+    assert_eq!(clock.slot(1_606_824_046).unwrap(), 1);
+    assert_eq!(clock.slot(1_710_338_135).unwrap(), 8_626_176);
+    clock.slot(1_606_824_000).unwrap_err();
+}
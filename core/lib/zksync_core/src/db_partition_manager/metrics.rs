@@ -0,0 +1,17 @@
+use vise::{Counter, Family, Gauge, Metrics};
+use zksync_dal::partitions_dal::PartitionedTable;
+
+/// Metrics for the DB partition manager.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "db_partition_manager")]
+pub(super) struct DbPartitionManagerMetrics {
+    /// Number of range partitions for a table.
+    pub partitions: Family<PartitionedTable, Gauge<usize>>,
+    /// Next miniblock not covered by range partitions of a table.
+    pub next_partitioned_miniblock: Family<PartitionedTable, Gauge<u64>>,
+    /// Number of rows moved from the default partition to created range partitions.
+    pub moved_rows: Family<PartitionedTable, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DbPartitionManagerMetrics> = vise::Global::new();
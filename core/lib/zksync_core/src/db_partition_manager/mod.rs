@@ -0,0 +1,159 @@
+//! Management of Postgres table partitions.
+
+use std::{num::NonZeroU32, ops, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{partitions_dal::PartitionedTable, ConnectionPool};
+use zksync_types::MiniblockNumber;
+
+use self::metrics::METRICS;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Configuration of [`DbPartitionManager`].
+#[derive(Debug, Clone)]
+pub struct DbPartitionManagerConfig {
+    /// Number of miniblocks in a partition.
+    pub partition_size: NonZeroU32,
+    /// Number of partitions created in advance of the last sealed miniblock.
+    pub partitions_ahead: NonZeroU32,
+    /// Interval between checks for partitions to create.
+    pub interval: Duration,
+}
+
+/// Component creating range partitions for [partitioned tables](PartitionedTable) in advance, so that new data
+/// doesn't end up in the default partitions of these tables. This keeps index sizes bounded and allows the
+/// [`DbPruner`](crate::db_pruner::DbPruner) to remove old data by dropping partitions.
+///
+/// Tables are converted to partitioned ones when the component starts; tables are not partitioned unless
+/// this component is run. Partitions are aligned to multiples of the configured partition size. Partitions are only
+/// created ahead of the miniblock currently processed by the state keeper (i.e., the one following the last sealed
+/// miniblock), so that creating partitions doesn't race with inserting data. Data for sealed and currently processed
+/// miniblocks remains in the partition it was inserted into.
+#[derive(Debug)]
+pub struct DbPartitionManager {
+    config: DbPartitionManagerConfig,
+    pool: ConnectionPool,
+}
+
+impl DbPartitionManager {
+    pub fn new(config: DbPartitionManagerConfig, pool: ConnectionPool) -> Self {
+        Self { config, pool }
+    }
+
+    /// Returns miniblock ranges for partitions that should be created after the last existing partition
+    /// ending at `next_partitioned_miniblock`.
+    fn partitions_to_create(
+        &self,
+        next_partitioned_miniblock: MiniblockNumber,
+        sealed_miniblock: Option<MiniblockNumber>,
+    ) -> Vec<ops::Range<MiniblockNumber>> {
+        let partition_size = self.config.partition_size.get();
+        let next_miniblock = sealed_miniblock.map_or(0, |number| number.0 + 1);
+        let target_miniblock = next_miniblock + self.config.partitions_ahead.get() * partition_size;
+
+        // `next_miniblock` may be inserted concurrently, so partitions are created for the following miniblocks only.
+        let mut start = next_partitioned_miniblock.0.max(next_miniblock + 1);
+        let mut partitions = vec![];
+        while start < target_miniblock {
+            let end = (start / partition_size + 1) * partition_size;
+            partitions.push(MiniblockNumber(start)..MiniblockNumber(end));
+            start = end;
+        }
+        partitions
+    }
+
+    async fn ensure_partitioned(&self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("db_partition_manager")
+            .await?;
+        for table in PartitionedTable::ALL {
+            let converted = storage
+                .partitions_dal()
+                .ensure_partitioned(table)
+                .await
+                .with_context(|| format!("failed partitioning `{}`", table.name()))?;
+            if converted {
+                tracing::info!("Converted `{}` to a partitioned table", table.name());
+            }
+        }
+        Ok(())
+    }
+
+    async fn update_partitions(&self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("db_partition_manager")
+            .await?;
+        let sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("failed getting sealed miniblock number")?;
+
+        for table in PartitionedTable::ALL {
+            let partitions = storage
+                .partitions_dal()
+                .get_partitions(table)
+                .await
+                .with_context(|| format!("failed getting partitions for `{}`", table.name()))?;
+            let mut partition_count = partitions.len();
+            let mut next_partitioned_miniblock = partitions
+                .last()
+                .map_or(MiniblockNumber(0), |partition| partition.miniblocks.end);
+
+            for miniblocks in
+                self.partitions_to_create(next_partitioned_miniblock, sealed_miniblock)
+            {
+                let moved_rows = storage
+                    .partitions_dal()
+                    .create_partition(table, miniblocks.clone())
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "failed creating partition for `{}` and miniblocks {miniblocks:?}",
+                            table.name()
+                        )
+                    })?;
+                tracing::info!(
+                    "Created partition for `{}` and miniblocks {miniblocks:?}; moved {moved_rows} rows \
+                     from the default partition",
+                    table.name()
+                );
+                METRICS.moved_rows[&table].inc_by(moved_rows);
+                partition_count += 1;
+                next_partitioned_miniblock = miniblocks.end;
+            }
+            METRICS.partitions[&table].set(partition_count);
+            METRICS.next_partitioned_miniblock[&table].set(next_partitioned_miniblock.0.into());
+        }
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting DB partition manager with config {:?}",
+            self.config
+        );
+        self.ensure_partitioned().await?;
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, DB partition manager is shutting down");
+                break;
+            }
+            self.update_partitions().await?;
+            if tokio::time::timeout(self.config.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                tracing::info!("Stop signal received, DB partition manager is shutting down");
+                break;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+//! Tests for the DB partition manager.
+
+use zksync_types::{L2ChainId, MiniblockNumber};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::create_miniblock,
+};
+
+fn mock_config() -> DbPartitionManagerConfig {
+    DbPartitionManagerConfig {
+        partition_size: NonZeroU32::new(10).unwrap(),
+        partitions_ahead: NonZeroU32::new(2).unwrap(),
+        interval: Duration::from_millis(10),
+    }
+}
+
+#[tokio::test]
+async fn computing_partitions_to_create() {
+    let manager = DbPartitionManager::new(mock_config(), ConnectionPool::test_pool().await);
+    let partitions = manager.partitions_to_create(MiniblockNumber(0), None);
+    assert_eq!(
+        partitions,
+        [
+            MiniblockNumber(1)..MiniblockNumber(10),
+            MiniblockNumber(10)..MiniblockNumber(20),
+        ]
+    );
+
+    let partitions = manager.partitions_to_create(MiniblockNumber(30), Some(MiniblockNumber(5)));
+    assert_eq!(partitions, []);
+    let partitions = manager.partitions_to_create(MiniblockNumber(20), Some(MiniblockNumber(9)));
+    assert_eq!(partitions, [MiniblockNumber(20)..MiniblockNumber(30)]);
+
+    // Partitions must not be created for sealed or currently processed miniblocks.
+    let partitions = manager.partitions_to_create(MiniblockNumber(5), Some(MiniblockNumber(33)));
+    assert_eq!(
+        partitions,
+        [
+            MiniblockNumber(35)..MiniblockNumber(40),
+            MiniblockNumber(40)..MiniblockNumber(50),
+            MiniblockNumber(50)..MiniblockNumber(60),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn creating_partitions() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    // Tables are not partitioned until the partition manager is started.
+    for table in PartitionedTable::ALL {
+        let partitions = storage
+            .partitions_dal()
+            .get_partitions(table)
+            .await
+            .unwrap();
+        assert!(partitions.is_empty());
+    }
+
+    let manager = DbPartitionManager::new(mock_config(), pool.clone());
+    manager.ensure_partitioned().await.unwrap();
+    manager.update_partitions().await.unwrap();
+    for table in PartitionedTable::ALL {
+        let partitions = storage
+            .partitions_dal()
+            .get_partitions(table)
+            .await
+            .unwrap();
+        let last_partition = partitions.last().unwrap();
+        assert_eq!(last_partition.miniblocks.end, MiniblockNumber(30));
+    }
+
+    for number in 1..=15 {
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(number))
+            .await
+            .unwrap();
+    }
+    manager.update_partitions().await.unwrap();
+    // Updating partitions is idempotent.
+    manager.update_partitions().await.unwrap();
+    for table in PartitionedTable::ALL {
+        let partitions = storage
+            .partitions_dal()
+            .get_partitions(table)
+            .await
+            .unwrap();
+        let ranges: Vec<_> = partitions
+            .iter()
+            .map(|partition| partition.miniblocks.clone())
+            .collect();
+        assert_eq!(
+            ranges[ranges.len() - 4..],
+            [
+                MiniblockNumber(2)..MiniblockNumber(10),
+                MiniblockNumber(10)..MiniblockNumber(20),
+                MiniblockNumber(20)..MiniblockNumber(30),
+                MiniblockNumber(30)..MiniblockNumber(40),
+            ]
+        );
+    }
+}
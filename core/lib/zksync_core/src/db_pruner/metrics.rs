@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    Metrics, Unit,
+};
+use zksync_dal::pruning_dal::{PrunedRows, PruningStats};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "mode", rename_all = "snake_case")]
+pub(super) enum PruningMode {
+    Prune,
+    DryRun,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum PrunedEntity {
+    Transactions,
+    CallTraces,
+    Events,
+    L2ToL1Logs,
+    StorageLogs,
+    Miniblocks,
+}
+
+/// Metrics for the DB pruner.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "db_pruner")]
+pub(super) struct DbPrunerMetrics {
+    /// Number of rows removed from Postgres (or that would be removed in the dry-run mode).
+    #[metrics(labels = ["entity", "mode"])]
+    pub pruned_rows: LabeledFamily<(PrunedEntity, PruningMode), Counter, 2>,
+    /// Total size of rows removed from Postgres (or that would be removed in the dry-run mode).
+    #[metrics(labels = ["entity", "mode"], unit = Unit::Bytes)]
+    pub pruned_size: LabeledFamily<(PrunedEntity, PruningMode), Counter, 2>,
+    /// Last L1 batch with pruned data.
+    pub last_pruned_l1_batch: Family<PruningMode, Gauge<u64>>,
+    /// Latency of pruning a chunk of L1 batches.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub chunk_latency: Family<PruningMode, Histogram<Duration>>,
+}
+
+impl DbPrunerMetrics {
+    pub fn observe_stats(&self, mode: PruningMode, stats: &PruningStats) {
+        let entities = [
+            (PrunedEntity::Transactions, stats.transactions),
+            (PrunedEntity::CallTraces, stats.call_traces),
+            (PrunedEntity::Events, stats.events),
+            (PrunedEntity::L2ToL1Logs, stats.l2_to_l1_logs),
+            (PrunedEntity::StorageLogs, stats.storage_logs),
+            (PrunedEntity::Miniblocks, stats.miniblocks),
+        ];
+        for (entity, PrunedRows { count, size_bytes }) in entities {
+            self.pruned_rows[&(entity, mode)].inc_by(count);
+            self.pruned_size[&(entity, mode)].inc_by(size_bytes);
+        }
+    }
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DbPrunerMetrics> = vise::Global::new();
@@ -0,0 +1,225 @@
+//! Pruning of old data from Postgres.
+
+use std::{num::NonZeroU32, ops, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{
+    pruning_dal::{PruningInfo, StorageLogsPruning},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+use zksync_utils::time::seconds_since_epoch;
+
+use self::metrics::{PruningMode, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Configuration of [`DbPruner`].
+#[derive(Debug, Clone)]
+pub struct DbPrunerConfig {
+    /// Minimum age of an L1 batch (counted from its timestamp) for its data to be pruned.
+    pub data_retention: Duration,
+    /// Maximum number of L1 batches pruned in a single database transaction.
+    pub chunk_size: NonZeroU32,
+    /// Interval between checks for new prunable L1 batches.
+    pub interval: Duration,
+    /// If set, the pruner only reports data that would be pruned without actually removing it.
+    pub dry_run: bool,
+    /// Storage logs removed by the pruner.
+    pub storage_logs_pruning: StorageLogsPruning,
+}
+
+/// Health details reported by [`DbPruner`].
+#[derive(Debug, Serialize)]
+struct DbPrunerHealth {
+    dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_pruned_l1_batch: Option<L1BatchNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_pruned_miniblock: Option<MiniblockNumber>,
+}
+
+/// Component periodically removing data for old L1 batches from Postgres (see
+/// [`PruningDal::prune_l1_batches()`](zksync_dal::pruning_dal::PruningDal::prune_l1_batches()) for details
+/// on what data is removed). Only L1 batches executed on L1 and older than the configured retention period
+/// are pruned.
+///
+/// In the dry-run mode, each chunk is pruned in a transaction which is then rolled back, so that
+/// the pruner reports the exact amount of data that would be removed for the chunk. The next chunk is then processed
+/// as if the previous chunk was pruned; thus, stats for storage logs can be overestimated in this mode.
+#[derive(Debug)]
+pub struct DbPruner {
+    config: DbPrunerConfig,
+    pool: ConnectionPool,
+    health_updater: HealthUpdater,
+}
+
+impl DbPruner {
+    pub fn new(config: DbPrunerConfig, pool: ConnectionPool) -> Self {
+        Self {
+            config,
+            pool,
+            health_updater: ReactiveHealthCheck::new("db_pruner").1,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    fn mode(&self) -> PruningMode {
+        if self.config.dry_run {
+            PruningMode::DryRun
+        } else {
+            PruningMode::Prune
+        }
+    }
+
+    fn update_health(&self, info: PruningInfo) {
+        let details = DbPrunerHealth {
+            dry_run: self.config.dry_run,
+            last_pruned_l1_batch: info.last_pruned_l1_batch,
+            last_pruned_miniblock: info.last_pruned_miniblock,
+        };
+        let health = Health::from(HealthStatus::Ready).with_details(details);
+        self.health_updater.update(health);
+    }
+
+    /// Returns the last L1 batch and the miniblock range for the next chunk to prune, or `None` if there is nothing
+    /// to prune.
+    async fn next_chunk(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        last_pruned: PruningInfo,
+    ) -> anyhow::Result<Option<(L1BatchNumber, ops::RangeInclusive<MiniblockNumber>)>> {
+        let max_timestamp =
+            seconds_since_epoch().saturating_sub(self.config.data_retention.as_secs());
+        let Some(last_prunable_l1_batch) = storage
+            .pruning_dal()
+            .get_last_prunable_l1_batch(max_timestamp)
+            .await
+            .context("failed getting last prunable L1 batch")?
+        else {
+            return Ok(None);
+        };
+
+        let first_l1_batch = if let Some(last_pruned_l1_batch) = last_pruned.last_pruned_l1_batch {
+            last_pruned_l1_batch + 1
+        } else {
+            storage
+                .blocks_dal()
+                .get_earliest_l1_batch_number()
+                .await
+                .context("failed getting earliest L1 batch number")?
+                .context("no L1 batches in Postgres")?
+        };
+        let last_l1_batch =
+            last_prunable_l1_batch.min(first_l1_batch + (self.config.chunk_size.get() - 1));
+        if last_l1_batch < first_l1_batch {
+            return Ok(None);
+        }
+
+        let (_, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch)
+            .await
+            .with_context(|| {
+                format!("failed getting miniblock range for L1 batch #{last_l1_batch}")
+            })?
+            .with_context(|| format!("L1 batch #{last_l1_batch} has no miniblocks"))?;
+        let first_miniblock = last_pruned
+            .last_pruned_miniblock
+            .map_or(MiniblockNumber(0), |number| number + 1);
+        Ok(Some((last_l1_batch, first_miniblock..=last_miniblock)))
+    }
+
+    /// Prunes the next chunk of L1 batches. Returns the updated pruning info, or `None` if there is nothing to prune.
+    async fn prune_chunk(&self, last_pruned: PruningInfo) -> anyhow::Result<Option<PruningInfo>> {
+        let mut storage = self.pool.access_storage_tagged("db_pruner").await?;
+        let Some((last_l1_batch, miniblocks)) = self.next_chunk(&mut storage, last_pruned).await?
+        else {
+            return Ok(None);
+        };
+
+        let mode = self.mode();
+        let latency = METRICS.chunk_latency[&mode].start();
+        let mut transaction = storage.start_transaction().await?;
+        let stats = transaction
+            .pruning_dal()
+            .prune_l1_batches(
+                last_l1_batch,
+                miniblocks.clone(),
+                self.config.storage_logs_pruning,
+            )
+            .await
+            .with_context(|| format!("failed pruning data up to L1 batch #{last_l1_batch}"))?;
+        if !self.config.dry_run {
+            transaction.commit().await?;
+        }
+        // Otherwise, the transaction is rolled back once dropped.
+        drop(transaction);
+        let latency = latency.observe();
+
+        METRICS.observe_stats(mode, &stats);
+        METRICS.last_pruned_l1_batch[&mode].set(last_l1_batch.0.into());
+        let total = stats.total();
+        let action = if self.config.dry_run {
+            "[dry run] Would prune"
+        } else {
+            "Pruned"
+        };
+        tracing::info!(
+            "{action} data up to L1 batch #{last_l1_batch} (miniblocks {miniblocks:?}) in {latency:?}: \
+             {count} rows, {size_bytes} bytes; details: {stats:?}",
+            count = total.count,
+            size_bytes = total.size_bytes
+        );
+
+        Ok(Some(PruningInfo {
+            last_pruned_l1_batch: Some(last_l1_batch),
+            last_pruned_miniblock: Some(*miniblocks.end()),
+        }))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut last_pruned = self
+            .pool
+            .access_storage_tagged("db_pruner")
+            .await?
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+        tracing::info!(
+            "Starting DB pruner with config {:?}; last pruned data: {last_pruned:?}",
+            self.config
+        );
+        self.update_health(last_pruned);
+
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, DB pruner is shutting down");
+                break;
+            }
+
+            if let Some(info) = self.prune_chunk(last_pruned).await? {
+                last_pruned = info;
+                self.update_health(last_pruned);
+                continue;
+            }
+            if tokio::time::timeout(self.config.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                tracing::info!("Stop signal received, DB pruner is shutting down");
+                break;
+            }
+        }
+        Ok(())
+    }
+}
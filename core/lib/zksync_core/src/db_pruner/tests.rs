@@ -0,0 +1,263 @@
+//! Tests for the DB pruner.
+
+use chrono::Utc;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    block::{L1BatchTreeData, MiniblockHeader},
+    AccountTreeId, Address, L2ChainId, StorageKey, StorageLog, H256,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{
+        create_l1_batch, create_l1_batch_metadata, create_miniblock,
+        l1_batch_metadata_to_commitment_artifacts,
+    },
+};
+
+const DEFAULT_RETENTION: Duration = Duration::from_secs(3_600);
+
+fn mock_config(dry_run: bool) -> DbPrunerConfig {
+    DbPrunerConfig {
+        data_retention: DEFAULT_RETENTION,
+        chunk_size: NonZeroU32::new(2).unwrap(),
+        interval: Duration::from_millis(10),
+        dry_run,
+        storage_logs_pruning: StorageLogsPruning::Overwritten,
+    }
+}
+
+fn mock_storage_log(value: u32) -> StorageLog {
+    let key = StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(1)),
+        H256::repeat_byte(1),
+    );
+    StorageLog::new_write_log(key, H256::from_low_u64_be(value.into()))
+}
+
+/// Seals an L1 batch with a single miniblock overwriting the same storage slot, and marks it as executed on L1.
+async fn seal_executed_l1_batch(storage: &mut StorageProcessor<'_>, number: u32, timestamp: u64) {
+    let miniblock = MiniblockHeader {
+        timestamp,
+        ..create_miniblock(number)
+    };
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+    let tx_hash = H256::from_low_u64_be(number.into());
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(
+            miniblock.number,
+            &[(tx_hash, vec![mock_storage_log(number)])],
+        )
+        .await
+        .unwrap();
+
+    let mut l1_batch = create_l1_batch(number);
+    l1_batch.timestamp = timestamp;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&l1_batch)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(l1_batch.number)
+        .await
+        .unwrap();
+
+    let metadata = create_l1_batch_metadata(number);
+    let tree_data = L1BatchTreeData {
+        hash: metadata.root_hash,
+        rollup_last_leaf_index: metadata.rollup_last_leaf_index,
+    };
+    storage
+        .blocks_dal()
+        .save_l1_batch_tree_data(l1_batch.number, &tree_data)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .save_l1_batch_commitment_artifacts(
+            l1_batch.number,
+            &l1_batch_metadata_to_commitment_artifacts(&metadata),
+        )
+        .await
+        .unwrap();
+    storage
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            l1_batch.number,
+            AggregatedActionType::Execute,
+            H256::from_low_u64_be(number.into()),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+}
+
+/// Prepares genesis and L1 batches #1..=5. L1 batches #4 and #5 are too recent to be pruned.
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=3 {
+        seal_executed_l1_batch(&mut storage, number, number.into()).await;
+    }
+    let now = seconds_since_epoch();
+    for number in 4..=5 {
+        seal_executed_l1_batch(&mut storage, number, now).await;
+    }
+}
+
+async fn prune_all_chunks(pruner: &DbPruner, mut info: PruningInfo) -> (PruningInfo, usize) {
+    let mut chunk_count = 0;
+    while let Some(new_info) = pruner.prune_chunk(info).await.unwrap() {
+        info = new_info;
+        chunk_count += 1;
+    }
+    (info, chunk_count)
+}
+
+#[tokio::test]
+async fn pruning_data_in_chunks() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let pruner = DbPruner::new(mock_config(false), pool.clone());
+
+    let (info, chunk_count) = prune_all_chunks(&pruner, PruningInfo::default()).await;
+    assert_eq!(chunk_count, 2); // L1 batches #0..=1 and #2..=3
+    assert_eq!(info.last_pruned_l1_batch, Some(L1BatchNumber(3)));
+    assert_eq!(info.last_pruned_miniblock, Some(MiniblockNumber(3)));
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let stored_info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(stored_info, info);
+    for number in 0..=3 {
+        let header = storage
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(number))
+            .await
+            .unwrap();
+        assert!(header.is_none(), "{number}");
+    }
+    for number in 4..=5 {
+        let header = storage
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(number))
+            .await
+            .unwrap();
+        assert!(header.is_some(), "{number}");
+    }
+    // L1 batch headers must be retained.
+    let l1_batch_header = storage
+        .blocks_dal()
+        .get_l1_batch_header(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert!(l1_batch_header.is_some());
+
+    // Only the latest log for the overwritten slot in the pruned range must be retained.
+    let slot = mock_storage_log(0).key.hashed_key();
+    let slot_logs: Vec<_> = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await
+        .into_iter()
+        .filter(|log| log.hashed_key == slot)
+        .map(|log| log.miniblock_number)
+        .collect();
+    assert_eq!(slot_logs.len(), 3, "{slot_logs:?}");
+    for number in [3, 4, 5] {
+        assert!(
+            slot_logs.contains(&MiniblockNumber(number)),
+            "{slot_logs:?}"
+        );
+    }
+
+    // New L1 batches are not prunable yet.
+    let new_info = pruner.prune_chunk(info).await.unwrap();
+    assert_eq!(new_info, None);
+}
+
+#[tokio::test]
+async fn dry_run_does_not_remove_data() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let storage_logs_count = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await
+        .len();
+
+    let pruner = DbPruner::new(mock_config(true), pool.clone());
+    let (info, chunk_count) = prune_all_chunks(&pruner, PruningInfo::default()).await;
+    assert_eq!(chunk_count, 2);
+    assert_eq!(info.last_pruned_l1_batch, Some(L1BatchNumber(3)));
+
+    let stored_info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(stored_info, PruningInfo::default());
+    for number in 0..=5 {
+        let header = storage
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(number))
+            .await
+            .unwrap();
+        assert!(header.is_some(), "{number}");
+    }
+    let new_storage_logs_count = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await
+        .len();
+    assert_eq!(new_storage_logs_count, storage_logs_count);
+}
+
+#[tokio::test]
+async fn pruner_with_zero_retention() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let config = DbPrunerConfig {
+        data_retention: Duration::ZERO,
+        ..mock_config(false)
+    };
+    let pruner = DbPruner::new(config, pool.clone());
+
+    let (info, _) = prune_all_chunks(&pruner, PruningInfo::default()).await;
+    // The last sealed L1 batch must never be pruned.
+    assert_eq!(info.last_pruned_l1_batch, Some(L1BatchNumber(4)));
+    assert_eq!(info.last_pruned_miniblock, Some(MiniblockNumber(4)));
+}
+
+#[tokio::test]
+async fn running_pruner() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let pruner = DbPruner::new(mock_config(false), pool.clone());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let pruner_task = tokio::spawn(pruner.run(stop_receiver));
+
+    loop {
+        let info = pool
+            .access_storage()
+            .await
+            .unwrap()
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .unwrap();
+        if info.last_pruned_l1_batch == Some(L1BatchNumber(3)) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    stop_sender.send_replace(true);
+    pruner_task.await.unwrap().unwrap();
+}
@@ -1,6 +1,9 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode, SenderConfig};
+use zksync_config::configs::{
+    chain::DataAvailabilityMode,
+    eth_sender::{ProofLoadingMode, ProofSendingMode, SenderConfig},
+};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::StorageProcessor;
 use zksync_l1_contract_interface::i_executor::methods::{
@@ -10,17 +13,19 @@ use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_prover_interface::outputs::L1BatchProofForL1;
 use zksync_types::{
     aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata,
-    helpers::unix_timestamp_ms, protocol_version::L1VerifierConfig, L1BatchNumber,
-    ProtocolVersionId,
+    helpers::unix_timestamp_ms, protocol_version::L1VerifierConfig, pubdata_da::PubdataDA,
+    L1BatchNumber, ProtocolVersionId,
 };
 
 use super::{
     aggregated_operations::AggregatedOperation,
+    emergency_mode::{load_l1_batches_metadata, SettlementLayerBatches},
     publish_criterion::{
-        DataSizeCriterion, GasCriterion, L1BatchPublishCriterion, NumberCriterion,
+        CostCriterion, DataSizeCriterion, GasCriterion, L1BatchPublishCriterion, NumberCriterion,
         TimestampDeadlineCriterion,
     },
 };
+use crate::l1_gas_price::L1TxParamsProvider;
 
 #[derive(Debug)]
 pub struct Aggregator {
@@ -29,11 +34,17 @@ pub struct Aggregator {
     execute_criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
     config: SenderConfig,
     blob_store: Arc<dyn ObjectStore>,
+    data_availability_mode: DataAvailabilityMode,
+    da_failover_timeout: Option<Duration>,
 }
 
 impl Aggregator {
-    pub fn new(config: SenderConfig, blob_store: Arc<dyn ObjectStore>) -> Self {
-        Self {
+    pub fn new(
+        config: SenderConfig,
+        blob_store: Arc<dyn ObjectStore>,
+        gas_adjuster: Arc<dyn L1TxParamsProvider>,
+    ) -> Self {
+        let mut this = Self {
             commit_criteria: vec![
                 Box::from(NumberCriterion {
                     op: AggregatedActionType::Commit,
@@ -88,15 +99,49 @@ impl Aggregator {
             ],
             config,
             blob_store,
+            data_availability_mode: DataAvailabilityMode::Rollup,
+            da_failover_timeout: None,
+        };
+
+        if let Some(target_cost_per_batch_in_gwei) = this.config.target_cost_per_batch_in_gwei {
+            // Proofs are not included since proof ranges are governed by `aggregated_proof_sizes`.
+            for (op, criteria) in [
+                (AggregatedActionType::Commit, &mut this.commit_criteria),
+                (AggregatedActionType::Execute, &mut this.execute_criteria),
+            ] {
+                criteria.push(Box::new(CostCriterion {
+                    op,
+                    target_cost_per_batch_in_gwei,
+                    gas_adjuster: gas_adjuster.clone(),
+                }));
+            }
         }
+        this
     }
 
+    /// Sets the data availability mode of the chain. For non-rollup modes, L1 batches are only committed
+    /// once the inclusion data for their pubdata is saved by the DA dispatcher.
+    pub fn with_data_availability_mode(mut self, mode: DataAvailabilityMode) -> Self {
+        self.data_availability_mode = mode;
+        self
+    }
+
+    /// Sets the timeout after which L1 batches with pubdata not included into the DA layer are committed
+    /// with pubdata published on the settlement layer. Only applies to non-rollup modes.
+    pub fn with_da_failover_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.da_failover_timeout = timeout;
+        self
+    }
+
+    /// Returns the next operation ready to be sent. If `settlement_halted` is set, only proof operations
+    /// are returned.
     pub async fn get_next_ready_operation(
         &mut self,
         storage: &mut StorageProcessor<'_>,
         base_system_contracts_hashes: BaseSystemContractsHashes,
         protocol_version_id: ProtocolVersionId,
         l1_verifier_config: L1VerifierConfig,
+        settlement_halted: bool,
     ) -> Option<AggregatedOperation> {
         let Some(last_sealed_l1_batch_number) = storage
             .blocks_dal()
@@ -107,14 +152,17 @@ impl Aggregator {
             return None; // No L1 batches in Postgres; no operations are ready yet
         };
 
-        if let Some(op) = self
-            .get_execute_operations(
+        let execute_op = if settlement_halted {
+            None
+        } else {
+            self.get_execute_operations(
                 storage,
                 self.config.max_aggregated_blocks_to_execute as usize,
                 last_sealed_l1_batch_number,
             )
             .await
-        {
+        };
+        if let Some(op) = execute_op {
             Some(AggregatedOperation::Execute(op))
         } else if let Some(op) = self
             .get_proof_operation(
@@ -126,6 +174,8 @@ impl Aggregator {
             .await
         {
             Some(AggregatedOperation::PublishProofOnchain(op))
+        } else if settlement_halted {
+            None
         } else {
             self.get_commit_operation(
                 storage,
@@ -139,6 +189,152 @@ impl Aggregator {
         }
     }
 
+    /// Returns operations covering the next pending L1 batches, i.e. L1 batches ready to be executed, proven
+    /// and committed. Unlike [`Self::get_next_ready_operation()`], publish criteria are ignored; operations are still
+    /// limited to the max number of L1 batches per operation configured for the respective action, so that
+    /// simulated calldata matches operations that could be sent. Used in the dry-run mode, in which operations
+    /// are simulated rather than sent. Real proofs are published one L1 batch at a time, so the proof operation
+    /// only covers the next L1 batch if real proofs are sent.
+    pub async fn get_pending_operations(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        base_system_contracts_hashes: BaseSystemContractsHashes,
+        protocol_version_id: ProtocolVersionId,
+        l1_verifier_config: L1VerifierConfig,
+    ) -> Vec<AggregatedOperation> {
+        let Some(last_sealed_l1_batch_number) = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap()
+        else {
+            return vec![];
+        };
+
+        let mut operations = vec![];
+        if let Some(op) = self
+            .get_execute_operations(
+                storage,
+                self.config.max_aggregated_blocks_to_execute as usize,
+                last_sealed_l1_batch_number,
+            )
+            .await
+        {
+            operations.push(AggregatedOperation::Execute(op));
+        }
+        if let Some(op) = self
+            .get_proof_operation(
+                storage,
+                *self.config.aggregated_proof_sizes.iter().max().unwrap(),
+                last_sealed_l1_batch_number,
+                l1_verifier_config,
+            )
+            .await
+        {
+            operations.push(AggregatedOperation::PublishProofOnchain(op));
+        }
+        if let Some(op) = self
+            .get_commit_operation(
+                storage,
+                self.config.max_aggregated_blocks_to_commit as usize,
+                last_sealed_l1_batch_number,
+                base_system_contracts_hashes,
+                protocol_version_id,
+            )
+            .await
+        {
+            operations.push(AggregatedOperation::Commit(op));
+        }
+        operations
+    }
+
+    /// Returns the next proof or execute operation in the emergency mode. Unlike [`Self::get_next_ready_operation()`],
+    /// statuses of L1 batches are taken from the settlement layer since L1 batches were committed by the operator
+    /// rather than by this node. L1 batches with transactions saved in the database are skipped, so that
+    /// in-flight operations are not duplicated.
+    pub(super) async fn get_next_emergency_operation(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batches: SettlementLayerBatches,
+        l1_verifier_config: L1VerifierConfig,
+    ) -> Option<AggregatedOperation> {
+        let last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_last_l1_batch_with_execute_tx()
+            .await
+            .unwrap()
+            .max(l1_batches.executed);
+        let last_l1_batch_to_execute = l1_batches
+            .proven
+            .min(last_executed_l1_batch + self.config.max_aggregated_blocks_to_execute);
+        if last_executed_l1_batch < last_l1_batch_to_execute {
+            let l1_batches = load_l1_batches_metadata(
+                storage,
+                (last_executed_l1_batch + 1)..=last_l1_batch_to_execute,
+            )
+            .await?;
+            return Some(AggregatedOperation::Execute(ExecuteBatches { l1_batches }));
+        }
+
+        let last_proven_l1_batch = storage
+            .blocks_dal()
+            .get_last_l1_batch_with_prove_tx()
+            .await
+            .unwrap()
+            .max(l1_batches.proven);
+        if last_proven_l1_batch >= l1_batches.committed {
+            return None;
+        }
+        let batch_to_prove = last_proven_l1_batch + 1;
+        let (last_l1_batch_to_prove, proofs, should_verify) = match self.config.proof_sending_mode {
+            ProofSendingMode::SkipEveryProof => {
+                let max_proof_size = *self.config.aggregated_proof_sizes.iter().max().unwrap();
+                let last_l1_batch = l1_batches
+                    .committed
+                    .min(last_proven_l1_batch + max_proof_size as u32);
+                (last_l1_batch, vec![], false)
+            }
+            proof_sending_mode => {
+                if !Self::verifier_config_matches(storage, batch_to_prove, l1_verifier_config).await
+                {
+                    return None;
+                }
+                let proofs = match self.config.proof_loading_mode {
+                    ProofLoadingMode::OldProofFromDb => {
+                        unreachable!("OldProofFromDb is not supported anymore")
+                    }
+                    ProofLoadingMode::FriProofFromGcs => {
+                        load_wrapped_fri_proofs_for_range(
+                            batch_to_prove,
+                            batch_to_prove,
+                            &*self.blob_store,
+                        )
+                        .await
+                    }
+                };
+                if proofs.is_empty() {
+                    return None; // The proof for the next L1 batch is not generated yet
+                }
+                if proof_sending_mode == ProofSendingMode::OnlyMockProofs {
+                    (batch_to_prove, vec![], false)
+                } else {
+                    (batch_to_prove, proofs, true)
+                }
+            }
+        };
+
+        let mut l1_batches =
+            load_l1_batches_metadata(storage, last_proven_l1_batch..=last_l1_batch_to_prove)
+                .await?;
+        let prev_l1_batch = l1_batches.remove(0);
+        Some(AggregatedOperation::PublishProofOnchain(ProveBatches {
+            prev_l1_batch,
+            l1_batches,
+            proofs,
+            should_verify,
+        }))
+    }
+
     async fn get_execute_operations(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -159,6 +355,7 @@ impl Aggregator {
             &mut self.execute_criteria,
             ready_for_execute_batches,
             last_sealed_l1_batch,
+            self.config.dry_run,
         )
         .await;
 
@@ -179,7 +376,7 @@ impl Aggregator {
             .await
             .unwrap()?;
 
-        let ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
+        let mut ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
             blocks_dal
                 .pre_boojum_get_ready_for_commit_l1_batches(
                     limit,
@@ -212,17 +409,71 @@ impl Aggregator {
                 }
             });
 
+        // For rollups, the pubdata source is chosen by the caller, since it depends on the current L1 gas prices.
+        let mut pubdata_da = match self.data_availability_mode {
+            DataAvailabilityMode::Rollup => PubdataDA::Calldata,
+            DataAvailabilityMode::Validium
+            | DataAvailabilityMode::External
+            | DataAvailabilityMode::Celestia
+            | DataAvailabilityMode::EigenDA => PubdataDA::Custom,
+        };
+        let mut da_inclusion_data = vec![];
+        if pubdata_da == PubdataDA::Custom {
+            if let (Some(first), Some(last)) = (
+                ready_for_commit_l1_batches.first(),
+                ready_for_commit_l1_batches.last(),
+            ) {
+                da_inclusion_data = storage
+                    .data_availability_dal()
+                    .get_inclusion_data_for_range(first.header.number..=last.header.number)
+                    .await
+                    .unwrap();
+            }
+
+            let failover_batch_count = if da_inclusion_data.is_empty() {
+                self.da_failover_timeout.map_or(0, |timeout| {
+                    da_failover_batch_count(
+                        &ready_for_commit_l1_batches,
+                        timeout,
+                        unix_timestamp_ms() / 1_000,
+                    )
+                })
+            } else {
+                0
+            };
+            if failover_batch_count > 0 {
+                tracing::warn!(
+                    "Pubdata for L1 batches #{}..#{} is not included into the DA layer within {:?}; \
+                     failing over to publishing pubdata on the settlement layer",
+                    ready_for_commit_l1_batches[0].header.number,
+                    ready_for_commit_l1_batches[failover_batch_count - 1].header.number,
+                    self.da_failover_timeout.unwrap_or_default()
+                );
+                ready_for_commit_l1_batches.truncate(failover_batch_count);
+                pubdata_da = PubdataDA::Calldata;
+            } else {
+                // L1 batches can only be committed after their pubdata is included into the DA layer.
+                ready_for_commit_l1_batches.truncate(da_inclusion_data.len());
+            }
+        }
+
         let batches = extract_ready_subrange(
             storage,
             &mut self.commit_criteria,
             ready_for_commit_l1_batches,
             last_sealed_batch,
+            self.config.dry_run,
         )
         .await;
 
-        batches.map(|batches| CommitBatches {
-            last_committed_l1_batch,
-            l1_batches: batches,
+        batches.map(|batches| {
+            da_inclusion_data.truncate(batches.len());
+            CommitBatches {
+                last_committed_l1_batch,
+                l1_batches: batches,
+                pubdata_da,
+                da_inclusion_data,
+            }
         })
     }
 
@@ -239,27 +490,19 @@ impl Aggregator {
             .unwrap();
         let batch_to_prove = previous_proven_batch_number + 1;
 
-        // Return `None` if batch is not committed yet.
-        storage
+        // Return `None` if the batch commitment is not confirmed on L1 yet. Commit and prove txs may be sent
+        // from different operator accounts, so the prove tx must not race the commit tx.
+        let last_committed_batch = storage
             .blocks_dal()
-            .get_eth_commit_tx_id(batch_to_prove)
+            .get_number_of_last_l1_batch_committed_on_eth()
             .await
             .unwrap()?;
+        if last_committed_batch < batch_to_prove {
+            return None;
+        }
 
-        if let Some(version_id) = storage
-            .blocks_dal()
-            .get_batch_protocol_version_id(batch_to_prove)
-            .await
-            .unwrap()
-        {
-            let verifier_config_for_next_batch = storage
-                .protocol_versions_dal()
-                .l1_verifier_config_for_version(version_id)
-                .await
-                .unwrap();
-            if verifier_config_for_next_batch != l1_verifier_config {
-                return None;
-            }
+        if !Self::verifier_config_matches(storage, batch_to_prove, l1_verifier_config).await {
+            return None;
         }
         let proofs = match proof_loading_mode {
             ProofLoadingMode::OldProofFromDb => {
@@ -307,6 +550,28 @@ impl Aggregator {
         })
     }
 
+    /// Checks whether the L1 batch is proven using the verifier config currently used on L1.
+    async fn verifier_config_matches(
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+        l1_verifier_config: L1VerifierConfig,
+    ) -> bool {
+        let Some(version_id) = storage
+            .blocks_dal()
+            .get_batch_protocol_version_id(l1_batch_number)
+            .await
+            .unwrap()
+        else {
+            return true;
+        };
+        let verifier_config_for_batch = storage
+            .protocol_versions_dal()
+            .l1_verifier_config_for_version(version_id)
+            .await
+            .unwrap();
+        verifier_config_for_batch == l1_verifier_config
+    }
+
     async fn prepare_dummy_proof_operation(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -318,6 +583,7 @@ impl Aggregator {
             &mut self.proof_criteria,
             ready_for_proof_l1_batches,
             last_sealed_l1_batch,
+            self.config.dry_run,
         )
         .await?;
 
@@ -354,6 +620,22 @@ impl Aggregator {
                 .await
             }
 
+            ProofSendingMode::OnlyMockProofs => {
+                let operation = Self::load_real_proof_operation(
+                    storage,
+                    l1_verifier_config,
+                    &self.config.proof_loading_mode,
+                    &*self.blob_store,
+                )
+                .await?;
+                // Mock proofs are replaced with an empty proof accepted by the testnet verifier.
+                Some(ProveBatches {
+                    proofs: vec![],
+                    should_verify: false,
+                    ..operation
+                })
+            }
+
             ProofSendingMode::SkipEveryProof => {
                 let ready_for_proof_l1_batches = storage
                     .blocks_dal()
@@ -397,12 +679,33 @@ impl Aggregator {
     }
 }
 
+/// Returns the number of leading L1 batches sealed at least `timeout` before `now` (a UNIX timestamp in seconds).
+/// These batches are committed with pubdata published on the settlement layer if their pubdata
+/// is not included into the DA layer.
+pub(super) fn da_failover_batch_count(
+    l1_batches: &[L1BatchWithMetadata],
+    timeout: Duration,
+    now: u64,
+) -> usize {
+    l1_batches
+        .iter()
+        .take_while(|batch| batch.header.timestamp.saturating_add(timeout.as_secs()) <= now)
+        .count()
+}
+
+/// Selects L1 batches published in a single operation. If `ignore_criteria` is set (in the dry-run mode),
+/// the operation covers all loaded unpublished L1 batches.
 async fn extract_ready_subrange(
     storage: &mut StorageProcessor<'_>,
     publish_criteria: &mut [Box<dyn L1BatchPublishCriterion>],
     unpublished_l1_batches: Vec<L1BatchWithMetadata>,
     last_sealed_l1_batch: L1BatchNumber,
+    ignore_criteria: bool,
 ) -> Option<Vec<L1BatchWithMetadata>> {
+    if ignore_criteria {
+        return (!unpublished_l1_batches.is_empty()).then_some(unpublished_l1_batches);
+    }
+
     let mut last_l1_batch: Option<L1BatchNumber> = None;
     for criterion in publish_criteria {
         let l1_batch_by_criterion = criterion
@@ -0,0 +1,337 @@
+//! Monitoring of the operator account balances on the settlement layer.
+
+use std::{collections::HashMap, fmt, sync::Arc, time::Instant};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_config::configs::eth_sender::OperatorBalanceMonitorConfig;
+use zksync_eth_client::BoundEthInterface;
+use zksync_types::{ethabi, web3::contract::Options, Address, U256};
+
+use super::{
+    metrics::{AccountLabel, TopUpLabels, METRICS},
+    ETHSenderError, OperatorAccounts,
+};
+
+const COMPONENT: &str = "eth_sender_balance_monitor";
+const GWEI: u64 = 1_000_000_000;
+/// Gas limit for calls to the funding contract.
+const FUNDING_CONTRACT_GAS_LIMIT: u64 = 100_000;
+
+/// Hook requesting funds for an operator account with a low balance.
+#[async_trait]
+pub trait TopUpHook: fmt::Debug + Send + Sync {
+    /// Name of the hook used in logs and metrics.
+    fn name(&self) -> &'static str;
+
+    /// Requests a top-up for the specified account. The hook is not expected to wait until the funds arrive.
+    async fn request_top_up(&self, request: &TopUpRequest) -> anyhow::Result<()>;
+}
+
+/// Information about an operator account with a low balance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopUpRequest {
+    pub account: Address,
+    /// Current balance of the account in wei.
+    pub balance: U256,
+    /// Balance threshold in wei.
+    pub threshold: U256,
+}
+
+/// Top-up hook sending a POST request with a JSON-serialized [`TopUpRequest`] to a webhook.
+#[derive(Debug)]
+pub struct WebhookTopUpHook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookTopUpHook {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait]
+impl TopUpHook for WebhookTopUpHook {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn request_top_up(&self, request: &TopUpRequest) -> anyhow::Result<()> {
+        self.client
+            .post(&self.url)
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Top-up hook calling `topUp(address)` on a funding contract from a dedicated funder account.
+#[derive(Debug)]
+pub struct FundingContractTopUpHook {
+    funder: Arc<dyn BoundEthInterface>,
+    contract_address: Address,
+}
+
+impl FundingContractTopUpHook {
+    pub fn new(funder: Arc<dyn BoundEthInterface>, contract_address: Address) -> Self {
+        Self {
+            funder,
+            contract_address,
+        }
+    }
+
+    fn encode_call(account: Address) -> Vec<u8> {
+        let mut data = ethabi::short_signature("topUp", &[ethabi::ParamType::Address]).to_vec();
+        data.extend(ethabi::encode(&[ethabi::Token::Address(account)]));
+        data
+    }
+}
+
+#[async_trait]
+impl TopUpHook for FundingContractTopUpHook {
+    fn name(&self) -> &'static str {
+        "funding_contract"
+    }
+
+    async fn request_top_up(&self, request: &TopUpRequest) -> anyhow::Result<()> {
+        let nonce = self.funder.pending_nonce(COMPONENT).await?;
+        let options = Options {
+            nonce: Some(nonce),
+            gas: Some(FUNDING_CONTRACT_GAS_LIMIT.into()),
+            ..Options::default()
+        };
+        let signed_tx = self
+            .funder
+            .sign_prepared_tx_for_addr(
+                Self::encode_call(request.account),
+                self.contract_address,
+                options,
+                COMPONENT,
+            )
+            .await?;
+        let tx_hash = self.funder.send_raw_tx(signed_tx.raw_tx).await?;
+        tracing::info!(
+            "Sent funding contract top-up for operator account {:?} in transaction {tx_hash:?}",
+            request.account
+        );
+        Ok(())
+    }
+}
+
+/// Task monitoring balances of the operator accounts used by the Ethereum sender. If the balance of an account
+/// drops below the configured threshold, the task raises an alert and requests a top-up using the configured hooks,
+/// so that the account can be funded before the Ethereum sender stalls.
+#[derive(Debug)]
+pub struct OperatorBalanceMonitor {
+    config: OperatorBalanceMonitorConfig,
+    operator_accounts: OperatorAccounts,
+    top_up_hooks: Vec<Box<dyn TopUpHook>>,
+    last_top_up_requests: HashMap<Address, Instant>,
+}
+
+impl OperatorBalanceMonitor {
+    pub fn new(config: OperatorBalanceMonitorConfig, operator_accounts: OperatorAccounts) -> Self {
+        Self {
+            config,
+            operator_accounts,
+            top_up_hooks: Vec::new(),
+            last_top_up_requests: HashMap::new(),
+        }
+    }
+
+    /// Adds a hook invoked for each account with a low balance.
+    pub fn with_top_up_hook(mut self, hook: Box<dyn TopUpHook>) -> Self {
+        self.top_up_hooks.push(hook);
+        self
+    }
+
+    fn threshold(&self) -> U256 {
+        U256::from(self.config.low_balance_threshold_gwei) * GWEI
+    }
+
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, operator balance monitor is shutting down");
+                break;
+            }
+
+            if let Err(err) = self.check_balances().await {
+                tracing::warn!("Failed checking operator account balances: {err}");
+            }
+            tokio::time::sleep(self.config.check_interval()).await;
+        }
+        Ok(())
+    }
+
+    /// Checks balances of all active operator accounts and requests top-ups for accounts with low balances.
+    /// Returns the accounts with low balances.
+    pub(super) async fn check_balances(&mut self) -> Result<Vec<TopUpRequest>, ETHSenderError> {
+        let threshold = self.threshold();
+        let mut low_balances = vec![];
+        for (_, client) in self.operator_accounts.active_accounts() {
+            let account = client.sender_account();
+            let balance = client.sender_eth_balance(COMPONENT).await?;
+            let label = AccountLabel::from(account);
+            METRICS.operator_balance_gwei[&label].set((balance / GWEI).low_u64());
+
+            let is_low = balance < threshold;
+            METRICS.operator_balance_low[&label].set(is_low.into());
+            if is_low {
+                tracing::error!(
+                    "Balance of operator account {account:?} is {balance} wei, which is below \
+                     the threshold of {threshold} wei"
+                );
+                low_balances.push(TopUpRequest {
+                    account,
+                    balance,
+                    threshold,
+                });
+            }
+        }
+
+        for request in &low_balances {
+            self.request_top_up(request).await;
+        }
+        Ok(low_balances)
+    }
+
+    async fn request_top_up(&mut self, request: &TopUpRequest) {
+        if self.top_up_hooks.is_empty() {
+            return;
+        }
+        let cooldown = self.config.top_up_cooldown();
+        if let Some(requested_at) = self.last_top_up_requests.get(&request.account) {
+            if requested_at.elapsed() < cooldown {
+                tracing::debug!(
+                    "Top-up for operator account {:?} was requested recently; skipping",
+                    request.account
+                );
+                return;
+            }
+        }
+
+        for hook in &self.top_up_hooks {
+            let hook_name = hook.name();
+            let result = match hook.request_top_up(request).await {
+                Ok(()) => {
+                    tracing::info!(
+                        "Requested top-up for operator account {:?} using {hook_name} hook",
+                        request.account
+                    );
+                    "success"
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed requesting top-up for operator account {:?} using {hook_name} hook: {err:#}",
+                        request.account
+                    );
+                    "failure"
+                }
+            };
+            METRICS.operator_top_up_requests[&TopUpLabels::new(hook_name, result)].inc();
+        }
+        self.last_top_up_requests
+            .insert(request.account, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use zksync_eth_client::clients::MockEthereum;
+    use zksync_types::aggregated_operations::AggregatedActionType;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct RecordingHook(Mutex<Vec<TopUpRequest>>);
+
+    #[async_trait]
+    impl TopUpHook for Arc<RecordingHook> {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        async fn request_top_up(&self, request: &TopUpRequest) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(request.clone());
+            Ok(())
+        }
+    }
+
+    fn config(top_up_cooldown_ms: u64) -> OperatorBalanceMonitorConfig {
+        OperatorBalanceMonitorConfig {
+            check_interval_ms: 1_000,
+            low_balance_threshold_gwei: 1_000,
+            top_up_webhook_url: None,
+            funding_contract_address: None,
+            top_up_cooldown_ms,
+        }
+    }
+
+    fn mock_account(address: Address, balance_gwei: u64) -> Arc<MockEthereum> {
+        let client = MockEthereum::default().with_sender_account(address);
+        client.set_balance(address, U256::from(balance_gwei) * GWEI);
+        Arc::new(client)
+    }
+
+    #[tokio::test]
+    async fn low_balances_trigger_top_up_requests() {
+        let main = mock_account(Address::repeat_byte(1), 5_000);
+        let commit = mock_account(Address::repeat_byte(2), 500);
+        let accounts = OperatorAccounts::new(main)
+            .with_dedicated_account(AggregatedActionType::Commit, commit);
+        let hook = Arc::new(RecordingHook::default());
+        let mut monitor = OperatorBalanceMonitor::new(config(3_600_000), accounts)
+            .with_top_up_hook(Box::new(hook.clone()));
+
+        let low_balances = monitor.check_balances().await.unwrap();
+        let expected_request = TopUpRequest {
+            account: Address::repeat_byte(2),
+            balance: U256::from(500) * GWEI,
+            threshold: U256::from(1_000) * GWEI,
+        };
+        assert_eq!(low_balances, [expected_request.clone()]);
+        assert_eq!(*hook.0.lock().unwrap(), [expected_request.clone()]);
+
+        // The second request should be suppressed because of the cooldown.
+        let low_balances = monitor.check_balances().await.unwrap();
+        assert_eq!(low_balances, [expected_request]);
+        assert_eq!(hook.0.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn top_up_requests_are_repeated_after_cooldown() {
+        let main = mock_account(Address::repeat_byte(1), 500);
+        let hook = Arc::new(RecordingHook::default());
+        let mut monitor = OperatorBalanceMonitor::new(config(0), OperatorAccounts::new(main))
+            .with_top_up_hook(Box::new(hook.clone()));
+
+        monitor.check_balances().await.unwrap();
+        monitor.check_balances().await.unwrap();
+        assert_eq!(hook.0.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn funding_contract_hook_sends_transaction() {
+        let funder = Arc::new(MockEthereum::default().with_sender_account(Address::repeat_byte(3)));
+        let hook = FundingContractTopUpHook::new(funder.clone(), Address::repeat_byte(4));
+        let request = TopUpRequest {
+            account: Address::repeat_byte(2),
+            balance: 0.into(),
+            threshold: GWEI.into(),
+        };
+
+        hook.request_top_up(&request).await.unwrap();
+        assert_eq!(funder.sent_tx_count(), 1);
+    }
+}
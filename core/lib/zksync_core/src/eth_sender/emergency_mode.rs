@@ -0,0 +1,459 @@
+//! Settlement layer emergency mode (aka escape hatch). After the operator hasn't committed L1 batches
+//! for a certain time, the settlement layer contract allows anyone to prove and execute L1 batches committed
+//! by the operator. In this mode, the node is run by a third party and never commits L1 batches.
+
+use std::{ops, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use zksync_config::configs::eth_sender::EmergencyModeConfig;
+use zksync_contracts::zksync_contract;
+use zksync_dal::StorageProcessor;
+use zksync_eth_client::{BoundEthInterface, CallFunctionArgs};
+use zksync_l1_contract_interface::{
+    i_executor::{
+        methods::{ExecuteBatches, ProveBatches},
+        structures::StoredBatchInfo,
+    },
+    Detokenize, Tokenizable, Tokenize,
+};
+use zksync_object_store::ObjectStore;
+use zksync_types::{
+    commitment::L1BatchWithMetadata,
+    ethabi,
+    web3::types::{BlockId, BlockNumber, Bytes, FilterBuilder},
+    Address, L1BatchNumber, L2ChainId, H256, U256, U64,
+};
+use zksync_utils::time::seconds_since_epoch;
+
+use super::{
+    aggregated_operations::AggregatedOperation,
+    aggregator::load_wrapped_fri_proofs_for_range,
+    metrics::METRICS,
+    settlement_guard::{stored_batch_hash, SettlementAnomaly},
+    zksync_functions::ZkSyncFunctions,
+    ETHSenderError,
+};
+
+const COMPONENT: &str = "eth_sender_emergency_mode";
+/// Max number of L1 blocks covered by a single `eth_getLogs` request.
+const MAX_LOGS_BLOCK_RANGE: u64 = 10_000;
+
+/// Numbers of the last L1 batches committed, proved and executed on the settlement layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct SettlementLayerBatches {
+    pub committed: L1BatchNumber,
+    pub proven: L1BatchNumber,
+    pub executed: L1BatchNumber,
+}
+
+/// Checks whether the operator is inactive, i.e. hasn't committed L1 batches on the settlement layer
+/// for the configured time. The check relies on the settlement layer only, so it doesn't require
+/// the node database to contain transactions sent by the operator.
+#[derive(Debug)]
+pub(super) struct EmergencyModeGuard {
+    zksync_contract: ethabi::Contract,
+    main_zksync_contract_address: Address,
+    inactivity_threshold: Duration,
+    last_commit: Option<L1BatchCommit>,
+}
+
+/// Commitment of an L1 batch on the settlement layer.
+#[derive(Debug, Clone, Copy)]
+struct L1BatchCommit {
+    l1_batch_number: L1BatchNumber,
+    /// L1 block containing the commitment. If the commitment is older than the inactivity threshold,
+    /// this may be a later block; the commitment is known to be not newer than it.
+    block_number: u64,
+    /// Timestamp of the L1 block.
+    timestamp: u64,
+}
+
+impl EmergencyModeGuard {
+    pub fn new(main_zksync_contract_address: Address, config: &EmergencyModeConfig) -> Self {
+        Self {
+            zksync_contract: zksync_contract(),
+            main_zksync_contract_address,
+            inactivity_threshold: config.inactivity_threshold(),
+            last_commit: None,
+        }
+    }
+
+    /// Returns the state of L1 batches on the settlement layer if the operator is inactive, or `None` otherwise.
+    pub async fn check(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        client: &dyn BoundEthInterface,
+    ) -> Result<Option<SettlementLayerBatches>, ETHSenderError> {
+        let batches = SettlementLayerBatches {
+            committed: self
+                .total_batches(client, "getTotalBatchesCommitted")
+                .await?,
+            proven: self
+                .total_batches(client, "getTotalBatchesVerified")
+                .await?,
+            executed: self
+                .total_batches(client, "getTotalBatchesExecuted")
+                .await?,
+        };
+        if batches.committed == L1BatchNumber(0) {
+            // Only the genesis batch is committed, so there's nothing to prove or execute.
+            METRICS.emergency_mode_active.set(0);
+            return Ok(None);
+        }
+
+        let commit_timestamp = match self.last_commit {
+            Some(commit) if commit.l1_batch_number == batches.committed => commit.timestamp,
+            _ => {
+                let Some(commit) = self.find_commit(storage, client, batches.committed).await?
+                else {
+                    tracing::warn!(
+                        "Cannot find the commitment of L1 batch #{} on the settlement layer; \
+                         considering the operator active",
+                        batches.committed
+                    );
+                    METRICS.emergency_mode_active.set(0);
+                    return Ok(None);
+                };
+                self.last_commit = Some(commit);
+                commit.timestamp
+            }
+        };
+
+        let inactivity =
+            Duration::from_secs(seconds_since_epoch().saturating_sub(commit_timestamp));
+        METRICS.operator_inactivity.set(inactivity);
+        let is_inactive = inactivity >= self.inactivity_threshold;
+        METRICS.emergency_mode_active.set(is_inactive.into());
+        if is_inactive {
+            tracing::info!(
+                "Operator hasn't committed L1 batches for {inactivity:?} (last committed L1 batch: #{}); \
+                 proving and executing committed L1 batches",
+                batches.committed
+            );
+            Ok(Some(batches))
+        } else {
+            tracing::debug!(
+                "Operator has committed L1 batch #{} {inactivity:?} ago; waiting for inactivity threshold {:?}",
+                batches.committed,
+                self.inactivity_threshold
+            );
+            Ok(None)
+        }
+    }
+
+    /// Checks that local metadata of L1 batches in the operation matches L1 batches committed by the operator.
+    /// Otherwise, the operation would prove or execute a state diverged from the committed one.
+    pub async fn verify_operation(
+        &self,
+        client: &dyn BoundEthInterface,
+        operation: &AggregatedOperation,
+    ) -> Result<Option<SettlementAnomaly>, ETHSenderError> {
+        let l1_batches: Vec<_> = match operation {
+            AggregatedOperation::Commit(_) => vec![],
+            AggregatedOperation::PublishProofOnchain(op) => std::iter::once(&op.prev_l1_batch)
+                .chain(&op.l1_batches)
+                .collect(),
+            AggregatedOperation::Execute(op) => op.l1_batches.iter().collect(),
+        };
+        for l1_batch in l1_batches {
+            let l1_batch_number = l1_batch.header.number;
+            let l1_hash: H256 = self
+                .call_zksync_contract(client, "storedBatchHash", U256::from(l1_batch_number.0))
+                .await?;
+            let local_hash = stored_batch_hash(l1_batch);
+            if l1_hash != local_hash {
+                return Ok(Some(SettlementAnomaly::CommittedBatchHashMismatch {
+                    l1_batch_number,
+                    local_hash,
+                    l1_hash,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn call_zksync_contract<R: Detokenize>(
+        &self,
+        client: &dyn BoundEthInterface,
+        function_name: &str,
+        params: impl Tokenize,
+    ) -> Result<R, ETHSenderError> {
+        let args = CallFunctionArgs::new(function_name, params).for_contract(
+            self.main_zksync_contract_address,
+            self.zksync_contract.clone(),
+        );
+        let output = client.call_contract_function(args).await?;
+        Ok(R::from_tokens(output)?)
+    }
+
+    async fn total_batches(
+        &self,
+        client: &dyn BoundEthInterface,
+        function_name: &str,
+    ) -> Result<L1BatchNumber, ETHSenderError> {
+        let total: U256 = self.call_zksync_contract(client, function_name, ()).await?;
+        Ok(L1BatchNumber(total.as_u32()))
+    }
+
+    /// Finds the commitment of the specified L1 batch. Logs are only queried in bounded block ranges: forward
+    /// from the known commit block if there is one, or backward from the latest block until the inactivity
+    /// threshold is exceeded.
+    async fn find_commit(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        client: &dyn BoundEthInterface,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchCommit>, ETHSenderError> {
+        let latest_block = client.block_number(COMPONENT).await?.as_u64();
+        if let Some(from_block) = self
+            .known_commit_block(storage, client, l1_batch_number)
+            .await?
+        {
+            // The batch may have been reverted and committed again since then, so the latest commitment is searched.
+            let mut commit_block = None;
+            let mut window_start = from_block;
+            while window_start <= latest_block {
+                let window_end = latest_block.min(window_start + MAX_LOGS_BLOCK_RANGE - 1);
+                let block = self
+                    .last_commit_block(client, l1_batch_number, window_start, window_end)
+                    .await?;
+                commit_block = block.or(commit_block);
+                window_start = window_end + 1;
+            }
+            return match commit_block {
+                Some(block_number) => self.commit_at(client, l1_batch_number, block_number).await,
+                None => Ok(None),
+            };
+        }
+
+        let mut window_end = latest_block;
+        loop {
+            let window_start = window_end.saturating_sub(MAX_LOGS_BLOCK_RANGE - 1);
+            if let Some(block_number) = self
+                .last_commit_block(client, l1_batch_number, window_start, window_end)
+                .await?
+            {
+                return self.commit_at(client, l1_batch_number, block_number).await;
+            }
+            if window_start == 0 {
+                return Ok(None);
+            }
+            let Some(commit) = self
+                .commit_at(client, l1_batch_number, window_start)
+                .await?
+            else {
+                return Ok(None);
+            };
+            let age = Duration::from_secs(seconds_since_epoch().saturating_sub(commit.timestamp));
+            if age >= self.inactivity_threshold {
+                // The commitment is older than the inactivity threshold, so its exact time is irrelevant.
+                return Ok(Some(commit));
+            }
+            window_end = window_start - 1;
+        }
+    }
+
+    /// Returns an L1 block not newer than the commitment of the specified L1 batch, if it's known. L1 batches
+    /// are committed in order, so the commitment of a previously observed batch is a lower bound as well.
+    async fn known_commit_block(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        client: &dyn BoundEthInterface,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<u64>, ETHSenderError> {
+        let eth_tx_id = storage
+            .blocks_dal()
+            .get_eth_commit_tx_id(l1_batch_number)
+            .await
+            .unwrap();
+        let tx_hash = match eth_tx_id {
+            Some(eth_tx_id) => storage
+                .eth_sender_dal()
+                .get_confirmed_tx_hash_by_eth_tx_id(eth_tx_id as u32)
+                .await
+                .unwrap(),
+            None => None,
+        };
+        if let Some(tx_hash) = tx_hash {
+            let receipt = client.tx_receipt(tx_hash, COMPONENT).await?;
+            if let Some(block_number) = receipt.and_then(|receipt| receipt.block_number) {
+                return Ok(Some(block_number.as_u64()));
+            }
+        }
+        Ok(self
+            .last_commit
+            .filter(|commit| commit.l1_batch_number < l1_batch_number)
+            .map(|commit| commit.block_number))
+    }
+
+    /// Returns the last L1 block in the range containing the `BlockCommit` event for the specified L1 batch.
+    async fn last_commit_block(
+        &self,
+        client: &dyn BoundEthInterface,
+        l1_batch_number: L1BatchNumber,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Option<u64>, ETHSenderError> {
+        let event_signature = self
+            .zksync_contract
+            .event("BlockCommit")
+            .expect("`BlockCommit` event is missing in zkSync contract ABI")
+            .signature();
+        let filter = FilterBuilder::default()
+            .address(vec![self.main_zksync_contract_address])
+            .from_block(BlockNumber::Number(U64::from(from_block)))
+            .to_block(BlockNumber::Number(U64::from(to_block)))
+            .topics(
+                Some(vec![event_signature]),
+                Some(vec![H256::from_low_u64_be(l1_batch_number.0.into())]),
+                None,
+                None,
+            )
+            .build();
+        let logs = client.logs(filter, COMPONENT).await?;
+        // If the batch was reverted and committed again, the latest commitment is relevant.
+        Ok(logs
+            .iter()
+            .filter_map(|log| log.block_number)
+            .max()
+            .map(|number| number.as_u64()))
+    }
+
+    async fn commit_at(
+        &self,
+        client: &dyn BoundEthInterface,
+        l1_batch_number: L1BatchNumber,
+        block_number: u64,
+    ) -> Result<Option<L1BatchCommit>, ETHSenderError> {
+        let block_id = BlockId::Number(BlockNumber::Number(block_number.into()));
+        let block = client.block(block_id, COMPONENT).await?;
+        Ok(block.map(|block| L1BatchCommit {
+            l1_batch_number,
+            block_number,
+            timestamp: block.timestamp.as_u64(),
+        }))
+    }
+}
+
+/// Loads metadata for the specified range of L1 batches. Returns `None` if any of the batches
+/// doesn't have metadata in the database.
+pub(super) async fn load_l1_batches_metadata(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_numbers: ops::RangeInclusive<L1BatchNumber>,
+) -> Option<Vec<L1BatchWithMetadata>> {
+    let mut l1_batches = Vec::new();
+    for number in l1_batch_numbers.start().0..=l1_batch_numbers.end().0 {
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(number))
+            .await
+            .unwrap();
+        let Some(l1_batch) = l1_batch else {
+            tracing::warn!("L1 batch #{number} is not complete in the DB");
+            return None;
+        };
+        l1_batches.push(l1_batch);
+    }
+    Some(l1_batches)
+}
+
+/// Information about an L1 batch stored by the settlement layer contract.
+#[derive(Debug, Serialize)]
+pub struct StoredL1BatchArtifact {
+    pub number: L1BatchNumber,
+    /// Hash of the batch information as stored by the contract.
+    pub stored_batch_hash: H256,
+    /// ABI-encoded `StoredBatchInfo` struct.
+    pub stored_batch_info: Bytes,
+}
+
+/// Artifacts required to prove and execute a range of committed L1 batches on the settlement layer
+/// in the emergency mode without running the Ethereum sender, e.g. by sending transactions from a multisig.
+#[derive(Debug, Serialize)]
+pub struct EmergencyArtifacts {
+    pub chain_id: u64,
+    /// Information about the L1 batch preceding the range, followed by batches in the range.
+    pub l1_batches: Vec<StoredL1BatchArtifact>,
+    /// Calldata of prove calls that must be sent in order. If real proofs are used, there's a separate call
+    /// for each L1 batch since the contract accepts a single proof per call.
+    pub prove_calldata: Vec<Bytes>,
+    /// Calldata of the execute call that must be sent after all prove calls succeed.
+    pub execute_calldata: Bytes,
+}
+
+impl EmergencyArtifacts {
+    /// Loads artifacts for the specified range of committed L1 batches. If `with_proofs` is not set,
+    /// L1 batches are proved with an empty proof, which is only accepted by the testnet verifier.
+    pub async fn load(
+        storage: &mut StorageProcessor<'_>,
+        blob_store: &dyn ObjectStore,
+        l1_batch_numbers: ops::RangeInclusive<L1BatchNumber>,
+        rollup_chain_id: L2ChainId,
+        with_proofs: bool,
+    ) -> anyhow::Result<Self> {
+        let (first, last) = (*l1_batch_numbers.start(), *l1_batch_numbers.end());
+        anyhow::ensure!(
+            first > L1BatchNumber(0) && first <= last,
+            "invalid L1 batch range: {first}..={last}"
+        );
+        let mut l1_batches = load_l1_batches_metadata(storage, (first - 1)..=last)
+            .await
+            .context("some L1 batches don't have metadata in the DB")?;
+        let functions = ZkSyncFunctions::default();
+        let encode = |op: &AggregatedOperation| -> Bytes {
+            let contracts_are_pre_shared_bridge = op.protocol_version().is_pre_shared_bridge();
+            functions
+                .encode_aggregated_op(rollup_chain_id, op, contracts_are_pre_shared_bridge)
+                .into()
+        };
+
+        let stored_l1_batches = l1_batches
+            .iter()
+            .map(|l1_batch| StoredL1BatchArtifact {
+                number: l1_batch.header.number,
+                stored_batch_hash: stored_batch_hash(l1_batch),
+                stored_batch_info: ethabi::encode(&[StoredBatchInfo(l1_batch).into_token()]).into(),
+            })
+            .collect();
+
+        let batches_in_range = l1_batches.split_off(1);
+        let mut prev_l1_batch = l1_batches.pop().unwrap();
+        let mut prove_calldata = vec![];
+        if with_proofs {
+            for l1_batch in &batches_in_range {
+                let number = l1_batch.header.number;
+                let proofs = load_wrapped_fri_proofs_for_range(number, number, blob_store).await;
+                anyhow::ensure!(proofs.len() == 1, "no proof for L1 batch #{number}");
+                prove_calldata.push(encode(&AggregatedOperation::PublishProofOnchain(
+                    ProveBatches {
+                        prev_l1_batch,
+                        l1_batches: vec![l1_batch.clone()],
+                        proofs,
+                        should_verify: true,
+                    },
+                )));
+                prev_l1_batch = l1_batch.clone();
+            }
+        } else {
+            prove_calldata.push(encode(&AggregatedOperation::PublishProofOnchain(
+                ProveBatches {
+                    prev_l1_batch,
+                    l1_batches: batches_in_range.clone(),
+                    proofs: vec![],
+                    should_verify: false,
+                },
+            )));
+        }
+        let execute_calldata = encode(&AggregatedOperation::Execute(ExecuteBatches {
+            l1_batches: batches_in_range,
+        }));
+
+        Ok(Self {
+            chain_id: rollup_chain_id.as_u64(),
+            l1_batches: stored_l1_batches,
+            prove_calldata,
+            execute_calldata,
+        })
+    }
+}
@@ -1,35 +1,49 @@
-use std::{convert::TryInto, sync::Arc};
+use std::{collections::HashMap, convert::TryInto, ops, sync::Arc};
 
 use tokio::sync::watch;
-use zksync_config::configs::eth_sender::SenderConfig;
+use zksync_config::configs::eth_sender::{EmergencyModeConfig, PubdataSendingMode, SenderConfig};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_eth_client::{BoundEthInterface, CallFunctionArgs};
+use zksync_eth_client::{CallFunctionArgs, Error as EthClientError};
 use zksync_l1_contract_interface::{
+    i_executor::{
+        commit::kzg::ZK_SYNC_BYTES_PER_BLOB, methods::CommitBatches, structures::CommitBatchInfo,
+    },
     multicall3::{Multicall3Call, Multicall3Result},
-    Detokenize, Tokenizable, Tokenize,
+    Detokenize, Tokenizable,
 };
+use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     commitment::SerializeCommitment,
     eth_sender::EthTx,
     ethabi::Token,
     l2_to_l1_log::UserL2ToL1Log,
     protocol_version::{L1VerifierConfig, VerifierParams},
-    web3::contract::Error as Web3ContractError,
-    Address, L2ChainId, ProtocolVersionId, H256, U256,
+    pubdata_da::PubdataDA,
+    web3::{contract::Error as Web3ContractError, error::Error as Web3Error, types::CallRequest},
+    Address, L1BatchNumber, L2ChainId, ProtocolVersionId, H256, U256,
 };
 
 use super::aggregated_operations::AggregatedOperation;
 use crate::{
     eth_sender::{
-        metrics::{PubdataKind, METRICS},
+        emergency_mode::EmergencyModeGuard,
+        metrics::{PubdataDAFallbackReason, PubdataKind, METRICS},
+        settlement_guard::{SettlementAnomaly, SettlementGuard},
         zksync_functions::ZkSyncFunctions,
-        Aggregator, ETHSenderError,
+        Aggregator, ETHSenderError, OperatorAccounts,
     },
     gas_tracker::agg_l1_batch_base_cost,
+    l1_gas_price::L1TxParamsProvider,
     metrics::BlockL1Stage,
 };
 
+/// Gas consumed by a single blob (EIP-4844).
+pub(super) const GAS_PER_BLOB: u64 = 1 << 17;
+/// Maximum number of blobs that can be carried by a single L1 transaction (EIP-4844).
+const MAX_BLOBS_PER_TX: usize = 6;
+
 /// Data queried from L1 using multicall contract.
 #[derive(Debug)]
 pub struct MulticallData {
@@ -45,45 +59,72 @@ pub struct MulticallData {
 #[derive(Debug)]
 pub struct EthTxAggregator {
     aggregator: Aggregator,
-    eth_client: Arc<dyn BoundEthInterface>,
+    operator_accounts: OperatorAccounts,
+    gas_adjuster: Arc<dyn L1TxParamsProvider>,
     config: SenderConfig,
     timelock_contract_address: Address,
     l1_multicall3_address: Address,
     pub(super) main_zksync_contract_address: Address,
     functions: ZkSyncFunctions,
-    base_nonce: u64,
+    /// Pending L1 nonces of the active operator accounts at the start of the component, keyed by `from_addr`.
+    base_nonces: HashMap<Option<Address>, u64>,
     rollup_chain_id: L2ChainId,
+    /// L1 batch ranges of the operations last previewed in the dry-run mode, keyed by the operation type.
+    previewed_ops: HashMap<AggregatedActionType, ops::RangeInclusive<L1BatchNumber>>,
+    settlement_guard: Option<SettlementGuard>,
+    emergency_mode_guard: Option<EmergencyModeGuard>,
 }
 
 impl EthTxAggregator {
     pub async fn new(
         config: SenderConfig,
         aggregator: Aggregator,
-        eth_client: Arc<dyn BoundEthInterface>,
+        operator_accounts: OperatorAccounts,
+        gas_adjuster: Arc<dyn L1TxParamsProvider>,
         timelock_contract_address: Address,
         l1_multicall3_address: Address,
         main_zksync_contract_address: Address,
         rollup_chain_id: L2ChainId,
     ) -> Self {
         let functions = ZkSyncFunctions::default();
-        let base_nonce = eth_client
-            .pending_nonce("eth_sender")
-            .await
-            .unwrap()
-            .as_u64();
+        let mut base_nonces = HashMap::new();
+        for (from_addr, client) in operator_accounts.active_accounts() {
+            let base_nonce = client.pending_nonce("eth_sender").await.unwrap().as_u64();
+            base_nonces.insert(from_addr, base_nonce);
+        }
+        let settlement_guard = config.settlement_checks_enabled.then(|| {
+            SettlementGuard::new(
+                main_zksync_contract_address,
+                config.max_settlement_reorg_depth,
+            )
+        });
         Self {
             config,
             aggregator,
-            eth_client,
+            operator_accounts,
+            gas_adjuster,
             timelock_contract_address,
             l1_multicall3_address,
             main_zksync_contract_address,
             functions,
-            base_nonce,
+            base_nonces,
             rollup_chain_id,
+            previewed_ops: HashMap::new(),
+            settlement_guard,
+            emergency_mode_guard: None,
         }
     }
 
+    /// Switches the aggregator to the settlement layer emergency mode. In this mode, L1 batches are never committed;
+    /// L1 batches committed by the operator are proved and executed once the operator becomes inactive.
+    pub fn with_emergency_mode(mut self, config: &EmergencyModeConfig) -> Self {
+        self.emergency_mode_guard = Some(EmergencyModeGuard::new(
+            self.main_zksync_contract_address,
+            config,
+        ));
+        self
+    }
+
     pub async fn run(
         mut self,
         pool: ConnectionPool,
@@ -114,7 +155,11 @@ impl EthTxAggregator {
             self.l1_multicall3_address,
             self.functions.multicall_contract.clone(),
         );
-        let aggregate3_result = self.eth_client.call_contract_function(args).await?;
+        let aggregate3_result = self
+            .operator_accounts
+            .main()
+            .call_contract_function(args)
+            .await?;
         self.parse_multicall_data(Token::from_tokens(aggregate3_result)?)
     }
 
@@ -306,7 +351,11 @@ impl EthTxAggregator {
         let get_vk_hash = &self.functions.verification_key_hash;
         let args = CallFunctionArgs::new(&get_vk_hash.name, ())
             .for_contract(verifier_address, self.functions.verifier_contract.clone());
-        let vk_hash = self.eth_client.call_contract_function(args).await?;
+        let vk_hash = self
+            .operator_accounts
+            .main()
+            .call_contract_function(args)
+            .await?;
         Ok(H256::from_tokens(vk_hash)?)
     }
 
@@ -337,16 +386,67 @@ impl EthTxAggregator {
             params: verifier_params,
             recursion_scheduler_level_vk_hash,
         };
-        if let Some(agg_op) = self
-            .aggregator
-            .get_next_ready_operation(
-                storage,
-                base_system_contracts_hashes,
-                protocol_version_id,
-                l1_verifier_config,
-            )
-            .await
-        {
+        if self.config.dry_run {
+            let agg_ops = self
+                .aggregator
+                .get_pending_operations(
+                    storage,
+                    base_system_contracts_hashes,
+                    protocol_version_id,
+                    l1_verifier_config,
+                )
+                .await;
+            for agg_op in agg_ops {
+                let agg_op = match agg_op {
+                    AggregatedOperation::Commit(op) => {
+                        AggregatedOperation::Commit(self.choose_pubdata_da(op))
+                    }
+                    agg_op => agg_op,
+                };
+                self.preview_operation(storage, &agg_op, contracts_are_pre_shared_bridge)
+                    .await?;
+            }
+            return Ok(());
+        }
+
+        let settlement_halted = self.check_settlement_layer(storage).await?;
+        let agg_op = if let Some(guard) = &mut self.emergency_mode_guard {
+            let client = self.operator_accounts.main();
+            let Some(l1_batches) = guard.check(storage, client.as_ref()).await? else {
+                return Ok(()); // The operator is active; nothing to do
+            };
+            if settlement_halted {
+                return Ok(());
+            }
+            let agg_op = self
+                .aggregator
+                .get_next_emergency_operation(storage, l1_batches, l1_verifier_config)
+                .await;
+            if let Some(agg_op) = &agg_op {
+                if let Some(anomaly) = guard.verify_operation(client.as_ref(), agg_op).await? {
+                    Self::halt_settlement(storage, &anomaly).await;
+                    return Ok(());
+                }
+            }
+            agg_op
+        } else {
+            self.aggregator
+                .get_next_ready_operation(
+                    storage,
+                    base_system_contracts_hashes,
+                    protocol_version_id,
+                    l1_verifier_config,
+                    settlement_halted,
+                )
+                .await
+        };
+        if let Some(agg_op) = agg_op {
+            let agg_op = match agg_op {
+                AggregatedOperation::Commit(op) => {
+                    AggregatedOperation::Commit(self.choose_pubdata_da(op))
+                }
+                agg_op => agg_op,
+            };
             let tx = self
                 .save_eth_tx(storage, &agg_op, contracts_are_pre_shared_bridge)
                 .await?;
@@ -355,6 +455,206 @@ impl EthTxAggregator {
         Ok(())
     }
 
+    /// Checks the settlement layer for anomalies, halting settlement if one is detected. Returns whether
+    /// commit and execute operations are halted; halts are persisted until overridden by the operator.
+    /// If the anomaly persists after the override, settlement is halted again.
+    pub(super) async fn check_settlement_layer(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<bool, ETHSenderError> {
+        if let Some(guard) = &mut self.settlement_guard {
+            let client = self.operator_accounts.main();
+            if let Some(anomaly) = guard.check(storage, client.as_ref()).await? {
+                Self::halt_settlement(storage, &anomaly).await;
+            }
+        }
+
+        let active_halt = storage
+            .eth_sender_dal()
+            .get_active_settlement_halt()
+            .await
+            .unwrap();
+        if let Some(halt) = &active_halt {
+            tracing::warn!(
+                "Commit and execute operations are halted since {} because of a settlement layer anomaly: {}",
+                halt.halted_at,
+                halt.reason
+            );
+        }
+        METRICS.settlement_halted.set(active_halt.is_some().into());
+        Ok(active_halt.is_some())
+    }
+
+    async fn halt_settlement(storage: &mut StorageProcessor<'_>, anomaly: &SettlementAnomaly) {
+        let reason = anomaly.to_string();
+        let is_new_halt = storage
+            .eth_sender_dal()
+            .halt_settlement(&reason)
+            .await
+            .unwrap();
+        if is_new_halt {
+            tracing::error!(
+                "Halting commit and execute operations because of a settlement layer anomaly: {reason}"
+            );
+            METRICS.settlement_halts.inc();
+        }
+    }
+
+    /// Returns the address L1 transactions are sent to. In the emergency mode, operations are sent
+    /// to the diamond proxy directly since the third party running the node cannot use the operator's timelock.
+    fn operations_contract_address(&self) -> Address {
+        if self.emergency_mode_guard.is_some() {
+            self.main_zksync_contract_address
+        } else {
+            self.timelock_contract_address
+        }
+    }
+
+    /// Chooses how pubdata is published for the commit operation. Blobs are used only if they are enabled
+    /// in the config, supported by L1 and all committed batches, and are not more expensive than calldata;
+    /// otherwise, the operation falls back to calldata. The operation may be truncated to fit all blobs
+    /// into a single L1 transaction.
+    ///
+    /// Operations for chains not publishing pubdata on L1 (i.e., using [`PubdataDA::Custom`]) are returned as is.
+    fn choose_pubdata_da(&self, mut op: CommitBatches) -> CommitBatches {
+        if op.pubdata_da == PubdataDA::Custom {
+            return op;
+        }
+        op.pubdata_da = PubdataDA::Calldata;
+        if self.config.pubdata_sending_mode != PubdataSendingMode::Blobs {
+            return op;
+        }
+        let fallback_reason = 'fallback: {
+            let Some(blob_base_fee) = self.gas_adjuster.get_blob_base_fee(0) else {
+                break 'fallback PubdataDAFallbackReason::BlobsNotSupported;
+            };
+            let all_batches_support_blobs = op.l1_batches.iter().all(|batch| {
+                batch
+                    .header
+                    .protocol_version
+                    .map_or(false, |version| version.is_post_1_4_2())
+            });
+            if !all_batches_support_blobs {
+                break 'fallback PubdataDAFallbackReason::UnsupportedProtocolVersion;
+            }
+
+            let pubdata_sizes: Vec<_> = op
+                .l1_batches
+                .iter()
+                .map(|batch| {
+                    CommitBatchInfo::new(batch, PubdataDA::Blobs)
+                        .pubdata_input()
+                        .len()
+                })
+                .collect();
+            let batch_count = max_batches_fitting_into_blobs(&pubdata_sizes);
+            let pubdata_sizes = &pubdata_sizes[..batch_count];
+            let blob_count: usize = pubdata_sizes.iter().copied().map(blobs_for_pubdata).sum();
+            let pubdata_size: usize = pubdata_sizes.iter().sum();
+
+            let blobs_cost =
+                u128::from(GAS_PER_BLOB) * u128::from(blob_base_fee) * blob_count as u128;
+            let calldata_cost = u128::from(L1_GAS_PER_PUBDATA_BYTE)
+                * u128::from(self.gas_adjuster.get_base_fee(0))
+                * pubdata_size as u128;
+            if blobs_cost > calldata_cost {
+                tracing::info!(
+                    "Publishing pubdata in blobs is more expensive than in calldata \
+                     ({blobs_cost} vs {calldata_cost} wei); falling back to calldata"
+                );
+                break 'fallback PubdataDAFallbackReason::BlobsTooExpensive;
+            }
+
+            if batch_count < op.l1_batches.len() {
+                tracing::info!(
+                    "Truncating commit operation to {batch_count} L1 batches to fit pubdata into {MAX_BLOBS_PER_TX} blobs"
+                );
+                op.l1_batches.truncate(batch_count);
+            }
+            op.pubdata_da = PubdataDA::Blobs;
+            return op;
+        };
+
+        let first_l1_batch = op.l1_batches.first().map(|batch| batch.header.number);
+        let last_l1_batch = op.l1_batches.last().map(|batch| batch.header.number);
+        tracing::info!(
+            "Publishing pubdata for L1 batches {first_l1_batch:?}..={last_l1_batch:?} in calldata: {fallback_reason:?}"
+        );
+        METRICS.pubdata_da_fallback[&fallback_reason].inc();
+        op
+    }
+
+    /// Simulates the operation using `eth_call` instead of saving it, and logs the result. Since the operation
+    /// isn't saved, the aggregator produces it again on the following iterations; it's only previewed again
+    /// once its L1 batch range changes (e.g., when new L1 batches become pending).
+    pub(super) async fn preview_operation(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        aggregated_op: &AggregatedOperation,
+        contracts_are_pre_shared_bridge: bool,
+    ) -> Result<(), ETHSenderError> {
+        let op_type = aggregated_op.get_action_type();
+        let l1_batch_number_range = aggregated_op.l1_batch_range();
+        if self.previewed_ops.get(&op_type) == Some(&l1_batch_number_range) {
+            return Ok(());
+        }
+
+        let calldata = self.encode_aggregated_op(aggregated_op, contracts_are_pre_shared_bridge);
+        let predicted_gas_for_batches = storage
+            .blocks_dal()
+            .get_l1_batches_predicted_gas(l1_batch_number_range.clone(), op_type)
+            .await
+            .unwrap();
+        let predicted_gas = agg_l1_batch_base_cost(op_type) + predicted_gas_for_batches;
+        let blob_count = match aggregated_op {
+            AggregatedOperation::Commit(op) => op
+                .blob_sidecar()
+                .map_or(0, |sidecar| sidecar.versioned_hashes().len()),
+            _ => 0,
+        };
+        let caption = aggregated_op.get_action_caption();
+        tracing::info!(
+            "[dry run] Prepared {caption} operation for L1 batches {l1_batch_number_range:?}: \
+             calldata size {} bytes, {blob_count} blobs, predicted gas {predicted_gas}",
+            calldata.len()
+        );
+
+        if blob_count > 0 {
+            // `eth_call` doesn't support blob-carrying transactions, so the commitment would fail
+            // to find the blobs it references.
+            tracing::info!(
+                "[dry run] Skipped simulating {caption} operation for L1 batches {l1_batch_number_range:?} \
+                 since it publishes pubdata in blobs"
+            );
+            self.previewed_ops.insert(op_type, l1_batch_number_range);
+            return Ok(());
+        }
+
+        let sender = self.operator_accounts.for_operation(op_type);
+        let request = CallRequest {
+            from: Some(sender.sender_account()),
+            to: Some(self.operations_contract_address()),
+            data: Some(calldata.into()),
+            ..CallRequest::default()
+        };
+        match sender.call(request, None, "eth_tx_aggregator").await {
+            Ok(_) => tracing::info!(
+                "[dry run] Simulation of {caption} operation for L1 batches {l1_batch_number_range:?} succeeded"
+            ),
+            Err(EthClientError::EthereumGateway(Web3Error::Rpc(err))) => tracing::warn!(
+                "[dry run] Simulation of {caption} operation for L1 batches {l1_batch_number_range:?} \
+                 reverted: {} (code {}, data {:?})",
+                err.message,
+                err.code.code(),
+                err.data
+            ),
+            // Transport errors are retried on the next iteration.
+            Err(err) => return Err(err.into()),
+        }
+        self.previewed_ops.insert(op_type, l1_batch_number_range);
+        Ok(())
+    }
+
     async fn report_eth_tx_saving(
         storage: &mut StorageProcessor<'_>,
         aggregated_op: AggregatedOperation,
@@ -393,64 +693,11 @@ impl EthTxAggregator {
         op: &AggregatedOperation,
         contracts_are_pre_shared_bridge: bool,
     ) -> Vec<u8> {
-        let operation_is_pre_shared_bridge = op.protocol_version().is_pre_shared_bridge();
-        assert_eq!(
+        self.functions.encode_aggregated_op(
+            self.rollup_chain_id,
+            op,
             contracts_are_pre_shared_bridge,
-            operation_is_pre_shared_bridge
-        );
-
-        let mut args = vec![Token::Uint(self.rollup_chain_id.as_u64().into())];
-
-        match op.clone() {
-            AggregatedOperation::Commit(op) => {
-                if contracts_are_pre_shared_bridge {
-                    self.functions
-                        .pre_shared_bridge_commit
-                        .encode_input(&op.into_tokens())
-                        .expect("Failed to encode commit transaction data")
-                } else {
-                    args.extend(op.into_tokens());
-                    self.functions
-                        .post_shared_bridge_commit
-                        .as_ref()
-                        .expect("Missing ABI for commitBatchesSharedBridge")
-                        .encode_input(&args)
-                        .expect("Failed to encode commit transaction data")
-                }
-            }
-            AggregatedOperation::PublishProofOnchain(op) => {
-                if contracts_are_pre_shared_bridge {
-                    self.functions
-                        .pre_shared_bridge_prove
-                        .encode_input(&op.into_tokens())
-                        .expect("Failed to encode prove transaction data")
-                } else {
-                    args.extend(op.into_tokens());
-                    self.functions
-                        .post_shared_bridge_prove
-                        .as_ref()
-                        .expect("Missing ABI for proveBatchesSharedBridge")
-                        .encode_input(&args)
-                        .expect("Failed to encode prove transaction data")
-                }
-            }
-            AggregatedOperation::Execute(op) => {
-                if contracts_are_pre_shared_bridge {
-                    self.functions
-                        .pre_shared_bridge_execute
-                        .encode_input(&op.into_tokens())
-                        .expect("Failed to encode execute transaction data")
-                } else {
-                    args.extend(op.into_tokens());
-                    self.functions
-                        .post_shared_bridge_execute
-                        .as_ref()
-                        .expect("Missing ABI for executeBatchesSharedBridge")
-                        .encode_input(&args)
-                        .expect("Failed to encode execute transaction data")
-                }
-            }
-        }
+        )
     }
 
     pub(super) async fn save_eth_tx(
@@ -460,10 +707,11 @@ impl EthTxAggregator {
         contracts_are_pre_shared_bridge: bool,
     ) -> Result<EthTx, ETHSenderError> {
         let mut transaction = storage.start_transaction().await.unwrap();
-        let nonce = self.get_next_nonce(&mut transaction).await?;
+        let op_type = aggregated_op.get_action_type();
+        let from_addr = self.operator_accounts.from_addr(op_type);
+        let nonce = self.get_next_nonce(&mut transaction, from_addr).await?;
         let calldata = self.encode_aggregated_op(aggregated_op, contracts_are_pre_shared_bridge);
         let l1_batch_number_range = aggregated_op.l1_batch_range();
-        let op_type = aggregated_op.get_action_type();
 
         let predicted_gas_for_batches = transaction
             .blocks_dal()
@@ -471,6 +719,10 @@ impl EthTxAggregator {
             .await
             .unwrap();
         let eth_tx_predicted_gas = agg_l1_batch_base_cost(op_type) + predicted_gas_for_batches;
+        let blob_sidecar = match aggregated_op {
+            AggregatedOperation::Commit(op) => op.blob_sidecar(),
+            _ => None,
+        };
 
         let eth_tx = transaction
             .eth_sender_dal()
@@ -478,8 +730,10 @@ impl EthTxAggregator {
                 nonce,
                 calldata,
                 op_type,
-                self.timelock_contract_address,
+                self.operations_contract_address(),
                 eth_tx_predicted_gas,
+                from_addr,
+                blob_sidecar,
             )
             .await
             .unwrap();
@@ -496,15 +750,67 @@ impl EthTxAggregator {
     async fn get_next_nonce(
         &self,
         storage: &mut StorageProcessor<'_>,
+        from_addr: Option<Address>,
     ) -> Result<u64, ETHSenderError> {
         let db_nonce = storage
             .eth_sender_dal()
-            .get_next_nonce()
+            .get_next_nonce(from_addr)
             .await
             .unwrap()
             .unwrap_or(0);
         // Between server starts we can execute some txs using operator account or remove some txs from the database
         // At the start we have to consider this fact and get the max nonce.
-        Ok(db_nonce.max(self.base_nonce))
+        let base_nonce = self.base_nonces.get(&from_addr).copied().unwrap_or(0);
+        Ok(db_nonce.max(base_nonce))
+    }
+}
+
+fn blobs_for_pubdata(pubdata_size: usize) -> usize {
+    pubdata_size.div_ceil(ZK_SYNC_BYTES_PER_BLOB)
+}
+
+/// Returns the number of leading L1 batches whose pubdata fits into a single L1 transaction.
+/// Always returns at least 1 for a non-empty input.
+fn max_batches_fitting_into_blobs(pubdata_sizes: &[usize]) -> usize {
+    let mut blob_count = 0;
+    for (i, &pubdata_size) in pubdata_sizes.iter().enumerate() {
+        blob_count += blobs_for_pubdata(pubdata_size);
+        if blob_count > MAX_BLOBS_PER_TX {
+            return i.max(1);
+        }
+    }
+    pubdata_sizes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blobs_are_counted_correctly() {
+        assert_eq!(blobs_for_pubdata(0), 0);
+        assert_eq!(blobs_for_pubdata(1), 1);
+        assert_eq!(blobs_for_pubdata(ZK_SYNC_BYTES_PER_BLOB), 1);
+        assert_eq!(blobs_for_pubdata(ZK_SYNC_BYTES_PER_BLOB + 1), 2);
+    }
+
+    #[test]
+    fn batches_are_truncated_to_fit_into_blobs() {
+        let half_blob = ZK_SYNC_BYTES_PER_BLOB / 2;
+        assert_eq!(max_batches_fitting_into_blobs(&[]), 0);
+        assert_eq!(max_batches_fitting_into_blobs(&[half_blob; 6]), 6);
+        assert_eq!(max_batches_fitting_into_blobs(&[half_blob; 7]), 6);
+        assert_eq!(
+            max_batches_fitting_into_blobs(&[
+                ZK_SYNC_BYTES_PER_BLOB * 4,
+                ZK_SYNC_BYTES_PER_BLOB * 3
+            ]),
+            1
+        );
+        // The first batch is always included, even if it doesn't fit.
+        assert_eq!(
+            max_batches_fitting_into_blobs(&[ZK_SYNC_BYTES_PER_BLOB * 7, 1]),
+            1
+        );
     }
 }
@@ -1,30 +1,48 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{
-    BoundEthInterface, Error, EthInterface, ExecutedTxStatus, RawTransactionBytes, SignedCallResult,
+    BlobTxParams, BoundEthInterface, Error, EthInterface, ExecutedTxStatus, RawTransactionBytes,
+    SignedCallResult,
 };
 use zksync_types::{
-    eth_sender::EthTx,
+    aggregated_operations::AggregatedActionType,
+    api::idexo::BatchSettlementCost,
+    eth_sender::{EthTx, GasEscalationAction, GasEscalationEntry},
     web3::{
         contract::Options,
         error::Error as Web3Error,
         types::{BlockId, BlockNumber},
     },
-    L1BlockNumber, Nonce, H256, U256,
+    Address, L1BlockNumber, Nonce, H256, U256,
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::METRICS, ETHSenderError};
+use super::{
+    eth_tx_aggregator::GAS_PER_BLOB,
+    gas_escalation::{
+        AttemptFees, EscalationDecision, GasEscalationPolicy, SkipReason, StuckTxInfo,
+    },
+    metrics::METRICS,
+    ETHSenderError, OperatorAccounts,
+};
 use crate::{l1_gas_price::L1TxParamsProvider, metrics::BlockL1Stage};
 
+const GWEI: u64 = 1_000_000_000;
+
 #[derive(Debug)]
 struct EthFee {
     base_fee_per_gas: u64,
     priority_fee_per_gas: u64,
+    /// Set only for transactions carrying blobs.
+    blob_base_fee_per_gas: Option<u64>,
+    /// Escalation action leading to these fees.
+    action: GasEscalationAction,
+    /// Number of the attempt, starting from 1.
+    attempt: u32,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -46,11 +64,18 @@ pub(super) struct L1BlockNumbers {
 /// Based on eth_tx queue the component generates new attempt with the minimum possible fee,
 /// save it to the database, and send it to Ethereum.
 /// Based on eth_tx_history queue the component can mark txs as stuck and create the new attempt
-/// with higher gas price
+/// with higher gas price, as decided by the [`GasEscalationPolicy`]. All escalation decisions are recorded
+/// in the DB as an audit trail.
+/// Transactions are signed by the operator account they were created for; nonces and in-flight transactions
+/// are tracked independently for each account. If a nonce of an account is consumed by a transaction sent outside
+/// the node (e.g., a manual rescue transaction), the affected transactions are resent with new nonces.
 #[derive(Debug)]
 pub struct EthTxManager {
+    /// Main operator account, also used for read-only L1 queries.
     ethereum_gateway: Arc<dyn BoundEthInterface>,
+    operator_accounts: OperatorAccounts,
     config: SenderConfig,
+    gas_escalation_policy: GasEscalationPolicy,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
 }
 
@@ -58,10 +83,12 @@ impl EthTxManager {
     pub fn new(
         config: SenderConfig,
         gas_adjuster: Arc<dyn L1TxParamsProvider>,
-        ethereum_gateway: Arc<dyn BoundEthInterface>,
+        operator_accounts: OperatorAccounts,
     ) -> Self {
         Self {
-            ethereum_gateway,
+            ethereum_gateway: operator_accounts.main().clone(),
+            operator_accounts,
+            gas_escalation_policy: GasEscalationPolicy::new(&config),
             config,
             gas_adjuster,
         }
@@ -110,74 +137,159 @@ impl EthTxManager {
         storage: &mut StorageProcessor<'_>,
         tx: &EthTx,
         time_in_mempool: u32,
+        current_block: L1BlockNumber,
     ) -> Result<EthFee, ETHSenderError> {
-        let base_fee_per_gas = self.gas_adjuster.get_base_fee(time_in_mempool);
+        let suggested_fees = AttemptFees {
+            base_fee_per_gas: self.gas_adjuster.get_base_fee(time_in_mempool),
+            priority_fee_per_gas: self.gas_adjuster.get_priority_fee(),
+        };
 
-        let priority_fee_per_gas = if time_in_mempool != 0 {
-            METRICS.transaction_resent.inc();
-            let priority_fee_per_gas = self
-                .increase_priority_fee(storage, tx.id, base_fee_per_gas)
+        let (action, fees, attempt) = if time_in_mempool != 0 {
+            let (action, fees, attempt) = self
+                .escalate_fees(storage, tx, suggested_fees, current_block)
                 .await?;
+            METRICS.transaction_resent.inc();
+            if action == GasEscalationAction::Replacement {
+                METRICS.transaction_replaced.inc();
+            }
             tracing::info!(
-                "Resending operation {} with base fee {:?} and priority fee {:?}",
+                "Resending operation {} (attempt {attempt}, {action}) with base fee {:?} and priority fee {:?}",
                 tx.id,
-                base_fee_per_gas,
-                priority_fee_per_gas
+                fees.base_fee_per_gas,
+                fees.priority_fee_per_gas
             );
-            priority_fee_per_gas
+            (action, fees, attempt)
         } else {
-            self.gas_adjuster.get_priority_fee()
+            let fees = self.gas_escalation_policy.initial_fees(suggested_fees);
+            (GasEscalationAction::Initial, fees, 1)
         };
 
         // Extra check to prevent sending transaction will extremely high priority fee.
-        if priority_fee_per_gas > self.config.max_acceptable_priority_fee_in_gwei {
+        if fees.priority_fee_per_gas > self.config.max_acceptable_priority_fee_in_gwei {
             panic!(
                 "Extremely high value of priority_fee_per_gas is suggested: {}, while max acceptable is {}",
-                priority_fee_per_gas,
+                fees.priority_fee_per_gas,
                 self.config.max_acceptable_priority_fee_in_gwei
             );
         }
 
+        let blob_base_fee_per_gas = if tx.blob_sidecar.is_some() {
+            Some(
+                self.calculate_blob_base_fee(storage, tx, time_in_mempool)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
         Ok(EthFee {
-            base_fee_per_gas,
-            priority_fee_per_gas,
+            base_fee_per_gas: fees.base_fee_per_gas,
+            priority_fee_per_gas: fees.priority_fee_per_gas,
+            blob_base_fee_per_gas,
+            action,
+            attempt,
         })
     }
 
-    async fn increase_priority_fee(
+    async fn calculate_blob_base_fee(
         &self,
         storage: &mut StorageProcessor<'_>,
-        eth_tx_id: u32,
-        base_fee_per_gas: u64,
+        tx: &EthTx,
+        time_in_mempool: u32,
     ) -> Result<u64, ETHSenderError> {
+        let Some(blob_base_fee_per_gas) = self.gas_adjuster.get_blob_base_fee(time_in_mempool)
+        else {
+            tracing::warn!(
+                "Cannot send operation {} carrying blobs: blob base fee is unknown",
+                tx.id
+            );
+            return Err(ETHSenderError::from(Error::from(Web3Error::Internal)));
+        };
+        if time_in_mempool == 0 {
+            return Ok(blob_base_fee_per_gas);
+        }
+
+        let previous_blob_base_fee = storage
+            .eth_sender_dal()
+            .get_last_sent_eth_tx(tx.id)
+            .await
+            .unwrap()
+            .and_then(|previous_sent_tx| previous_sent_tx.blob_base_fee_per_gas)
+            .unwrap_or(0);
+        // Replacing a transaction carrying blobs requires at least doubling its blob fee.
+        Ok(blob_base_fee_per_gas.max(previous_blob_base_fee * 2))
+    }
+
+    /// Decides on fees for resubmitting a stuck transaction. Skipped resubmissions are recorded
+    /// in the audit trail and returned as errors.
+    async fn escalate_fees(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx: &EthTx,
+        suggested_fees: AttemptFees,
+        current_block: L1BlockNumber,
+    ) -> Result<(GasEscalationAction, AttemptFees, u32), ETHSenderError> {
         let previous_sent_tx = storage
             .eth_sender_dal()
-            .get_last_sent_eth_tx(eth_tx_id)
+            .get_last_sent_eth_tx(tx.id)
             .await
             .unwrap()
             .unwrap();
-
-        let previous_base_fee = previous_sent_tx.base_fee_per_gas;
-        let previous_priority_fee = previous_sent_tx.priority_fee_per_gas;
+        let attempts = storage
+            .eth_sender_dal()
+            .get_number_of_attempts(tx.id)
+            .await
+            .unwrap();
+        let previous_fees = AttemptFees {
+            base_fee_per_gas: previous_sent_tx.base_fee_per_gas,
+            priority_fee_per_gas: previous_sent_tx.priority_fee_per_gas,
+        };
+        // If the previous attempt wasn't sent, there's no reason to wait before resubmitting it.
+        let blocks_since_last_attempt = previous_sent_tx
+            .sent_at_block
+            .map_or(u32::MAX, |block| current_block.0.saturating_sub(block));
         let next_block_minimal_base_fee = self.gas_adjuster.get_next_block_minimal_base_fee();
 
-        if base_fee_per_gas <= next_block_minimal_base_fee.min(previous_base_fee) {
-            // If the base fee is lower than the previous used one
-            // or is lower than the minimal possible value for the next block, sending is skipped.
-            tracing::info!(
-                "Skipping gas adjustment for operation {}, \
-                 base_fee_per_gas: suggested for resending {:?}, previously sent {:?}, next block minimum {:?}",
-                eth_tx_id,
-                base_fee_per_gas,
-                previous_base_fee,
-                next_block_minimal_base_fee
-            );
-            return Err(ETHSenderError::from(Error::from(Web3Error::Internal)));
-        }
+        let decision = self.gas_escalation_policy.escalate(StuckTxInfo {
+            attempts,
+            blocks_since_last_attempt,
+            previous_fees,
+            suggested_fees,
+            next_block_minimal_base_fee,
+        });
+        let reason = match decision {
+            EscalationDecision::Send { action, fees } => return Ok((action, fees, attempts + 1)),
+            EscalationDecision::Skip(reason) => reason,
+        };
 
-        // Increase `priority_fee_per_gas` by at least 20% to prevent "replacement transaction under-priced" error.
-        Ok((previous_priority_fee + (previous_priority_fee / 5) + 1)
-            .max(self.gas_adjuster.get_priority_fee()))
+        METRICS.transaction_resend_skipped[&reason].inc();
+        tracing::info!(
+            "Skipping gas adjustment for operation {} ({}), \
+             fees: suggested for resending {suggested_fees:?}, previously sent {previous_fees:?}, \
+             next block minimum base fee {next_block_minimal_base_fee:?}",
+            tx.id,
+            reason.as_str()
+        );
+        // Skips caused by the resubmission interval are expected and are not recorded.
+        if reason != SkipReason::ResubmissionInterval {
+            let entry = GasEscalationEntry {
+                eth_tx_id: tx.id,
+                attempt: attempts + 1,
+                action: GasEscalationAction::Skipped,
+                skip_reason: Some(reason.as_str().to_owned()),
+                base_fee_per_gas: suggested_fees.base_fee_per_gas,
+                priority_fee_per_gas: suggested_fees.priority_fee_per_gas,
+                blob_base_fee_per_gas: None,
+                tx_hash: None,
+                l1_block_number: current_block.0,
+            };
+            storage
+                .eth_sender_dal()
+                .insert_gas_escalation(&entry)
+                .await
+                .unwrap();
+        }
+        Err(ETHSenderError::from(Error::from(Web3Error::Internal)))
     }
 
     pub(crate) async fn send_eth_tx(
@@ -190,16 +302,31 @@ impl EthTxManager {
         let EthFee {
             base_fee_per_gas,
             priority_fee_per_gas,
-        } = self.calculate_fee(storage, tx, time_in_mempool).await?;
+            blob_base_fee_per_gas,
+            action,
+            attempt,
+        } = self
+            .calculate_fee(storage, tx, time_in_mempool, current_block)
+            .await?;
 
         METRICS.used_base_fee_per_gas.observe(base_fee_per_gas);
         METRICS
             .used_priority_fee_per_gas
             .observe(priority_fee_per_gas);
+        if let Some(blob_base_fee_per_gas) = blob_base_fee_per_gas {
+            METRICS
+                .used_blob_base_fee_per_gas
+                .observe(blob_base_fee_per_gas);
+        }
 
         let signed_tx = self
-            .sign_tx(tx, base_fee_per_gas, priority_fee_per_gas)
-            .await;
+            .sign_tx(
+                tx,
+                base_fee_per_gas,
+                priority_fee_per_gas,
+                blob_base_fee_per_gas,
+            )
+            .await?;
 
         if let Some(tx_history_id) = storage
             .eth_sender_dal()
@@ -207,12 +334,30 @@ impl EthTxManager {
                 tx.id,
                 base_fee_per_gas,
                 priority_fee_per_gas,
+                blob_base_fee_per_gas,
                 signed_tx.hash,
                 signed_tx.raw_tx.as_ref(),
             )
             .await
             .unwrap()
         {
+            let entry = GasEscalationEntry {
+                eth_tx_id: tx.id,
+                attempt,
+                action,
+                skip_reason: None,
+                base_fee_per_gas,
+                priority_fee_per_gas,
+                blob_base_fee_per_gas,
+                tx_hash: Some(signed_tx.hash),
+                l1_block_number: current_block.0,
+            };
+            storage
+                .eth_sender_dal()
+                .insert_gas_escalation(&entry)
+                .await
+                .unwrap();
+
             if let Err(error) = self
                 .send_raw_transaction(storage, tx_history_id, signed_tx.raw_tx, current_block)
                 .await
@@ -256,19 +401,31 @@ impl EthTxManager {
         }
     }
 
+    fn signer(&self, from_addr: Option<Address>) -> Result<&dyn BoundEthInterface, ETHSenderError> {
+        if let Some(signer) = self.operator_accounts.signer(from_addr) {
+            Ok(signer.as_ref())
+        } else {
+            tracing::error!(
+                "No operator account is configured for address {from_addr:?}; \
+                 its key must be kept among the retired operator keys until all its transactions are confirmed"
+            );
+            Err(ETHSenderError::from(Error::from(Web3Error::Internal)))
+        }
+    }
+
     async fn get_operator_nonce(
         &self,
         block_numbers: L1BlockNumbers,
+        from_addr: Option<Address>,
     ) -> Result<OperatorNonce, ETHSenderError> {
-        let finalized = self
-            .ethereum_gateway
+        let account = self.signer(from_addr)?;
+        let finalized = account
             .nonce_at(block_numbers.finalized.0.into(), "eth_tx_manager")
             .await?
             .as_u32()
             .into();
 
-        let latest = self
-            .ethereum_gateway
+        let latest = account
             .nonce_at(block_numbers.latest.0.into(), "eth_tx_manager")
             .await?
             .as_u32()
@@ -324,19 +481,55 @@ impl EthTxManager {
     }
 
     // Monitors the in-flight transactions, marks mined ones as confirmed,
-    // returns the one that has to be resent (if there is one).
+    // returns the ones that have to be resent (at most one per operator account).
     pub(super) async fn monitor_inflight_transactions(
         &mut self,
         storage: &mut StorageProcessor<'_>,
         l1_block_numbers: L1BlockNumbers,
-    ) -> Result<Option<(EthTx, u32)>, ETHSenderError> {
+    ) -> Result<Vec<(EthTx, u32)>, ETHSenderError> {
         METRICS.track_block_numbers(&l1_block_numbers);
-        let operator_nonce = self.get_operator_nonce(l1_block_numbers).await?;
         let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
         METRICS.number_of_inflight_txs.set(inflight_txs.len());
 
+        let mut inflight_txs_by_account = HashMap::<_, Vec<_>>::new();
+        for tx in inflight_txs {
+            inflight_txs_by_account
+                .entry(tx.from_addr)
+                .or_default()
+                .push(tx);
+        }
+
+        let mut txs_to_resend = vec![];
+        for (from_addr, mut inflight_txs) in inflight_txs_by_account {
+            // Nonces can be reassigned out of the ID order (see `resync_nonces()`).
+            inflight_txs.sort_unstable_by_key(|tx| tx.nonce);
+            if let Some(tx_to_resend) = self
+                .monitor_account_inflight_transactions(
+                    storage,
+                    l1_block_numbers,
+                    from_addr,
+                    inflight_txs,
+                )
+                .await?
+            {
+                txs_to_resend.push(tx_to_resend);
+            }
+        }
+        Ok(txs_to_resend)
+    }
+
+    // Monitors the in-flight transactions of a single operator account, ordered by nonce.
+    async fn monitor_account_inflight_transactions(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        l1_block_numbers: L1BlockNumbers,
+        from_addr: Option<Address>,
+        inflight_txs: Vec<EthTx>,
+    ) -> Result<Option<(EthTx, u32)>, ETHSenderError> {
+        let operator_nonce = self.get_operator_nonce(l1_block_numbers, from_addr).await?;
+
         tracing::trace!(
-            "Going through not confirmed txs. \
+            "Going through not confirmed txs for account {from_addr:?}. \
              Block numbers: latest {}, finalized {}, \
              operator's nonce: latest {}, finalized {}",
             l1_block_numbers.latest,
@@ -384,41 +577,147 @@ impl EthTxManager {
                         .await;
                 }
                 None => {
-                    // The nonce has increased but we did not find the receipt.
-                    // This is an error because such a big re-org may cause transactions that were
-                    // previously recorded as confirmed to become pending again and we have to
-                    // make sure it's not the case - otherwise `eth_sender` may not work properly.
-                    tracing::error!(
-                        "Possible block reorgs: finalized nonce increase detected, but no tx receipt found for tx {:?}",
+                    // The finalized nonce has increased, but none of the sending attempts was mined, i.e.,
+                    // the nonce was consumed by a transaction sent outside the node (e.g., a manual rescue
+                    // transaction). This and subsequent transactions that can no longer be mined are resent
+                    // with new nonces.
+                    tracing::warn!(
+                        "Finalized nonce of account {from_addr:?} ({}) has increased past tx {:?}, \
+                         but no receipt was found for its sending attempts; the nonce was likely consumed \
+                         by an external transaction",
+                        operator_nonce.finalized,
                         &tx
                     );
+                    self.resync_nonces(storage, &tx, operator_nonce).await;
+                    return Ok(None);
                 }
             }
         }
         Ok(None)
     }
 
+    /// Resynchronizes nonces of an operator account after its nonces were consumed by transactions sent
+    /// outside the node. `first_tx` and subsequent unconfirmed transactions of the account that weren't mined
+    /// by the node get consecutive nonces (preserving their order), and their sending attempts are marked
+    /// as superseded, so that they are sent anew.
+    ///
+    /// A transaction is only reassigned if none of its sending attempts can be mined anymore, i.e., if its nonce
+    /// is below the finalized nonce of the account, or if it was never sent. Other transactions keep their nonces,
+    /// and reassigned transactions get nonces following them.
+    async fn resync_nonces(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        first_tx: &EthTx,
+        operator_nonce: OperatorNonce,
+    ) {
+        let txs = storage
+            .eth_sender_dal()
+            .get_unconfirmed_txs(first_tx.from_addr, first_tx.id)
+            .await
+            .unwrap();
+        let mut txs_to_reassign = vec![];
+        let mut next_nonce = operator_nonce.latest;
+        for tx in txs {
+            // Transactions mined by the node are processed as usual.
+            if tx.id != first_tx.id
+                && self
+                    .check_all_sending_attempts(storage, &tx)
+                    .await
+                    .is_some()
+            {
+                continue;
+            }
+            let is_stale = tx.nonce < operator_nonce.finalized;
+            let is_sent = storage
+                .eth_sender_dal()
+                .get_number_of_attempts(tx.id)
+                .await
+                .unwrap()
+                > 0;
+            if is_stale || !is_sent {
+                txs_to_reassign.push(tx);
+            } else {
+                // Sending attempts of the transaction may still be mined, so its nonce must be retained.
+                tracing::warn!(
+                    "Not reassigning nonce of tx {} sent from account {:?} since its sending attempts \
+                     may still be mined; nonce: {}, finalized account nonce: {}",
+                    tx.id,
+                    tx.from_addr,
+                    tx.nonce,
+                    operator_nonce.finalized
+                );
+                next_nonce = next_nonce.max(tx.nonce + 1);
+            }
+        }
+
+        let mut reassignments = vec![];
+        let mut nonce = u64::from(next_nonce.0);
+        for tx in txs_to_reassign {
+            if u64::from(tx.nonce.0) != nonce {
+                tracing::info!(
+                    "Reassigning nonce of tx {} sent from account {:?}: {} -> {nonce}",
+                    tx.id,
+                    tx.from_addr,
+                    tx.nonce
+                );
+            }
+            reassignments.push((tx.id, nonce));
+            nonce += 1;
+        }
+
+        storage
+            .eth_sender_dal()
+            .reassign_nonces(&reassignments)
+            .await
+            .unwrap();
+        METRICS.external_nonce_jumps.inc();
+        METRICS.resynced_txs.inc_by(reassignments.len() as u64);
+    }
+
     async fn sign_tx(
         &self,
         tx: &EthTx,
         base_fee_per_gas: u64,
         priority_fee_per_gas: u64,
-    ) -> SignedCallResult {
-        self.ethereum_gateway
-            .sign_prepared_tx_for_addr(
-                tx.raw_tx.clone(),
-                tx.contract_address,
-                Options::with(|opt| {
-                    // TODO Calculate gas for every operation SMA-1436
-                    opt.gas = Some(self.config.max_aggregated_tx_gas.into());
-                    opt.max_fee_per_gas = Some(U256::from(base_fee_per_gas + priority_fee_per_gas));
-                    opt.max_priority_fee_per_gas = Some(U256::from(priority_fee_per_gas));
-                    opt.nonce = Some(tx.nonce.0.into());
-                }),
-                "eth_tx_manager",
-            )
-            .await
-            .expect("Failed to sign transaction")
+        blob_base_fee_per_gas: Option<u64>,
+    ) -> Result<SignedCallResult, ETHSenderError> {
+        let signer = self.signer(tx.from_addr)?;
+        let options = Options::with(|opt| {
+            // TODO Calculate gas for every operation SMA-1436
+            opt.gas = Some(self.config.max_aggregated_tx_gas.into());
+            opt.max_fee_per_gas = Some(U256::from(base_fee_per_gas + priority_fee_per_gas));
+            opt.max_priority_fee_per_gas = Some(U256::from(priority_fee_per_gas));
+            opt.nonce = Some(tx.nonce.0.into());
+        });
+
+        let signed_tx = match (&tx.blob_sidecar, blob_base_fee_per_gas) {
+            (Some(sidecar), Some(blob_base_fee_per_gas)) => {
+                let blob_params = BlobTxParams {
+                    max_fee_per_blob_gas: blob_base_fee_per_gas.into(),
+                    sidecar: sidecar.clone(),
+                };
+                signer
+                    .sign_prepared_blob_tx_for_addr(
+                        tx.raw_tx.clone(),
+                        tx.contract_address,
+                        options,
+                        blob_params,
+                        "eth_tx_manager",
+                    )
+                    .await
+            }
+            _ => {
+                signer
+                    .sign_prepared_tx_for_addr(
+                        tx.raw_tx.clone(),
+                        tx.contract_address,
+                        options,
+                        "eth_tx_manager",
+                    )
+                    .await
+            }
+        };
+        Ok(signed_tx.expect("Failed to sign transaction"))
     }
 
     async fn send_unsent_txs(
@@ -536,6 +835,17 @@ impl EthTxManager {
             .track_eth_tx_metrics(storage, BlockL1Stage::Mined, tx)
             .await;
 
+        // Settlement cost accounting is informational, so failing to record costs must not stop the sender.
+        if let Err(err) = self
+            .record_settlement_costs(storage, tx, &tx_status, gas_used)
+            .await
+        {
+            tracing::warn!(
+                "Failed recording settlement costs for eth_tx {}: {err:#}",
+                tx.id
+            );
+        }
+
         if gas_used > U256::from(tx.predicted_gas_cost) {
             tracing::error!(
                 "Predicted gas {} lower than used gas {gas_used} for tx {:?} {}",
@@ -565,11 +875,112 @@ impl EthTxManager {
         METRICS.l1_blocks_waited_in_mempool[&tx_type_label].observe(waited_blocks.into());
     }
 
+    /// Attributes the cost of the confirmed transaction to the L1 batches it covers, splitting it evenly.
+    /// Once a batch is executed, its settlement cost is compared to the fees collected on L2.
+    async fn record_settlement_costs(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx: &EthTx,
+        tx_status: &ExecutedTxStatus,
+        gas_used: U256,
+    ) -> anyhow::Result<()> {
+        let l1_batches = storage
+            .blocks_dal()
+            .get_l1_batches_for_eth_tx_id(tx.id)
+            .await
+            .context("get_l1_batches_for_eth_tx_id()")?;
+        if l1_batches.is_empty() {
+            return Ok(());
+        }
+        let tx_history = storage
+            .eth_sender_dal()
+            .get_tx_history_to_check(tx.id)
+            .await
+            .context("get_tx_history_to_check()")?;
+        let confirmed_attempt = tx_history
+            .iter()
+            .find(|attempt| attempt.tx_hash == tx_status.tx_hash);
+
+        let effective_gas_price = match (tx_status.receipt.effective_gas_price, confirmed_attempt) {
+            (Some(price), _) => price,
+            (None, Some(attempt)) => {
+                U256::from(attempt.base_fee_per_gas) + U256::from(attempt.priority_fee_per_gas)
+            }
+            (None, None) => anyhow::bail!("cannot determine effective gas price"),
+        };
+        // Receipts don't include the blob gas price, so blobs are priced using the blob fee cap of the attempt,
+        // which is an upper bound for the actual cost.
+        let blob_count = tx
+            .blob_sidecar
+            .as_ref()
+            .map_or(0, |sidecar| sidecar.versioned_hashes().len() as u64);
+        let blob_gas_used = blob_count * GAS_PER_BLOB;
+        let blob_gas_price = confirmed_attempt
+            .and_then(|attempt| attempt.blob_base_fee_per_gas)
+            .unwrap_or(0);
+
+        let gas_cost = gas_used * effective_gas_price;
+        let blob_cost = U256::from(blob_gas_used) * U256::from(blob_gas_price);
+        METRICS.settlement_cost_gwei[&tx.tx_type.into()]
+            .inc_by(((gas_cost + blob_cost) / GWEI).low_u64());
+
+        let batch_count = l1_batches.len() as u64;
+        let cost = BatchSettlementCost {
+            eth_tx_id: tx.id,
+            gas_used: gas_used.low_u64() / batch_count,
+            gas_cost: gas_cost / batch_count,
+            blob_gas_used: blob_gas_used / batch_count,
+            blob_cost: blob_cost / batch_count,
+        };
+        for header in &l1_batches {
+            storage
+                .settlement_costs_dal()
+                .insert_settlement_cost(header.number, tx.tx_type, &cost)
+                .await
+                .context("insert_settlement_cost()")?;
+        }
+
+        if tx.tx_type != AggregatedActionType::Execute {
+            return Ok(());
+        }
+        for header in &l1_batches {
+            let economics = storage
+                .settlement_costs_dal()
+                .get_batch_economics(header.number)
+                .await
+                .context("get_batch_economics()")?;
+            let Some(economics) = economics.filter(|economics| economics.is_fully_settled) else {
+                continue;
+            };
+            METRICS
+                .l2_fees_collected_gwei
+                .inc_by((economics.l2_fees_collected / GWEI).low_u64());
+            if !economics.is_profitable {
+                METRICS.unprofitable_batches.inc();
+                tracing::warn!(
+                    "L1 batch #{} is unprofitable: settlement cost {} wei, L2 fees collected {} wei",
+                    header.number,
+                    economics.total_settlement_cost,
+                    economics.l2_fees_collected
+                );
+            }
+        }
+        Ok(())
+    }
+
     pub async fn run(
         mut self,
         pool: ConnectionPool,
-        stop_receiver: watch::Receiver<bool>,
+        mut stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
+        if self.config.dry_run {
+            tracing::info!(
+                "Ethereum sender runs in the dry-run mode; eth_tx_manager won't send transactions"
+            );
+            stop_receiver.changed().await.ok();
+            return Ok(());
+        }
+
         {
             let l1_block_numbers = self
                 .get_l1_block_numbers()
@@ -604,36 +1015,96 @@ impl EthTxManager {
         Ok(())
     }
 
-    async fn send_new_eth_txs(
+    pub(super) async fn send_new_eth_txs(
         &mut self,
         storage: &mut StorageProcessor<'_>,
-        current_block: L1BlockNumber,
+        l1_block_numbers: L1BlockNumbers,
     ) {
-        let number_inflight_txs = storage
-            .eth_sender_dal()
-            .get_inflight_txs()
-            .await
-            .unwrap()
-            .len();
-        let number_of_available_slots_for_eth_txs = self
-            .config
-            .max_txs_in_flight
-            .saturating_sub(number_inflight_txs as u64);
-
-        if number_of_available_slots_for_eth_txs > 0 {
-            // Get the new eth tx and create history item for them
-            let new_eth_tx = storage
-                .eth_sender_dal()
-                .get_new_eth_txs(number_of_available_slots_for_eth_txs)
-                .await
-                .unwrap();
+        let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
+        let from_addrs: Vec<_> = self
+            .operator_accounts
+            .signing_accounts()
+            .into_iter()
+            .map(|(from_addr, _)| from_addr)
+            .collect();
+
+        // Each operator account has its own nonce sequence, so the in-flight limit is applied per account.
+        for from_addr in from_addrs {
+            let number_inflight_txs = inflight_txs
+                .iter()
+                .filter(|tx| tx.from_addr == from_addr)
+                .count();
+            let number_of_available_slots_for_eth_txs = self
+                .config
+                .max_txs_in_flight
+                .saturating_sub(number_inflight_txs as u64);
+
+            if number_of_available_slots_for_eth_txs > 0 {
+                // Get the new eth tx and create history item for them
+                let mut new_eth_tx = storage
+                    .eth_sender_dal()
+                    .get_new_eth_txs(number_of_available_slots_for_eth_txs, from_addr)
+                    .await
+                    .unwrap();
+                if let Some(first_tx) = new_eth_tx.first() {
+                    match self
+                        .check_new_txs_nonce(storage, l1_block_numbers, first_tx)
+                        .await
+                    {
+                        Ok(true) => {}
+                        // Nonces were reassigned, so new transactions must be reloaded.
+                        Ok(false) => {
+                            new_eth_tx = storage
+                                .eth_sender_dal()
+                                .get_new_eth_txs(number_of_available_slots_for_eth_txs, from_addr)
+                                .await
+                                .unwrap();
+                        }
+                        Err(err) => {
+                            tracing::warn!(
+                                "Cannot check nonce of account {from_addr:?} before sending new txs: {err}"
+                            );
+                            continue;
+                        }
+                    }
+                }
 
-            for tx in new_eth_tx {
-                let _ = self.send_eth_tx(storage, &tx, 0, current_block).await;
+                for tx in new_eth_tx {
+                    let _ = self
+                        .send_eth_tx(storage, &tx, 0, l1_block_numbers.latest)
+                        .await;
+                }
             }
         }
     }
 
+    /// Checks that the nonce of the first new (i.e., never sent) transaction of an account wasn't consumed
+    /// by a transaction sent outside the node. If it was, nonces of the account are resynchronized
+    /// and `false` is returned.
+    async fn check_new_txs_nonce(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_block_numbers: L1BlockNumbers,
+        first_tx: &EthTx,
+    ) -> Result<bool, ETHSenderError> {
+        let operator_nonce = self
+            .get_operator_nonce(l1_block_numbers, first_tx.from_addr)
+            .await?;
+        if operator_nonce.finalized <= first_tx.nonce {
+            return Ok(true);
+        }
+
+        tracing::warn!(
+            "Finalized nonce of account {:?} ({}) has increased past new tx {:?}; \
+             the nonce was consumed by an external transaction",
+            first_tx.from_addr,
+            operator_nonce.finalized,
+            first_tx
+        );
+        self.resync_nonces(storage, first_tx, operator_nonce).await;
+        Ok(false)
+    }
+
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(
         &mut self,
@@ -642,15 +1113,14 @@ impl EthTxManager {
     ) -> Result<L1BlockNumber, ETHSenderError> {
         let l1_block_numbers = self.get_l1_block_numbers().await?;
 
-        self.send_new_eth_txs(storage, l1_block_numbers.latest)
-            .await;
+        self.send_new_eth_txs(storage, l1_block_numbers).await;
 
         if l1_block_numbers.latest <= previous_block {
             // Nothing to do - no new blocks were mined.
             return Ok(previous_block);
         }
 
-        if let Some((tx, sent_at_block)) = self
+        for (tx, sent_at_block) in self
             .monitor_inflight_transactions(storage, l1_block_numbers)
             .await?
         {
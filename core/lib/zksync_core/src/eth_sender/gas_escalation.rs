@@ -0,0 +1,344 @@
+//! Policy deciding how fees of stuck L1 transactions are escalated on resubmission.
+
+use vise::{EncodeLabelSet, EncodeLabelValue};
+use zksync_config::configs::eth_sender::SenderConfig;
+use zksync_types::eth_sender::GasEscalationAction;
+
+/// Default percentage by which the priority fee is increased on each resubmission.
+const DEFAULT_PRIORITY_FEE_BUMP_PERCENT: u64 = 20;
+/// Minimum fee increase (in percent) accepted by L1 nodes for a replacement transaction.
+const MIN_REPLACEMENT_BUMP_PERCENT: u64 = 10;
+
+/// Fees of a single sending attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct AttemptFees {
+    pub base_fee_per_gas: u64,
+    pub priority_fee_per_gas: u64,
+}
+
+impl AttemptFees {
+    fn max_fee_per_gas(self) -> u64 {
+        self.base_fee_per_gas + self.priority_fee_per_gas
+    }
+
+    /// Checks whether a transaction with these fees can replace a transaction with `previous` fees
+    /// in L1 node mempools.
+    fn can_replace(self, previous: Self) -> bool {
+        let min_fee = |fee: u64| fee + fee * MIN_REPLACEMENT_BUMP_PERCENT / 100;
+        self.priority_fee_per_gas >= min_fee(previous.priority_fee_per_gas)
+            && self.max_fee_per_gas() >= min_fee(previous.max_fee_per_gas())
+    }
+}
+
+/// Reason for skipping resubmission of a stuck transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum SkipReason {
+    /// Not enough L1 blocks have passed since the previous attempt.
+    ResubmissionInterval,
+    /// The suggested base fee hasn't increased compared to the previous attempt.
+    BaseFeeNotIncreased,
+    /// Fees cannot be bumped further because of the configured cap.
+    FeeCapReached,
+}
+
+impl SkipReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ResubmissionInterval => "resubmission_interval",
+            Self::BaseFeeNotIncreased => "base_fee_not_increased",
+            Self::FeeCapReached => "fee_cap_reached",
+        }
+    }
+}
+
+/// Outcome of [`GasEscalationPolicy::escalate()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum EscalationDecision {
+    Send {
+        action: GasEscalationAction,
+        fees: AttemptFees,
+    },
+    Skip(SkipReason),
+}
+
+/// Information about a stuck transaction used to decide on its resubmission.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct StuckTxInfo {
+    /// Number of attempts sent so far (including the initial one).
+    pub attempts: u32,
+    /// Number of L1 blocks since the previous attempt was sent.
+    pub blocks_since_last_attempt: u32,
+    /// Fees of the previous attempt.
+    pub previous_fees: AttemptFees,
+    /// Fees currently suggested by the gas adjuster.
+    pub suggested_fees: AttemptFees,
+    /// Minimum base fee of the next L1 block.
+    pub next_block_minimal_base_fee: u64,
+}
+
+/// Configurable strategy for resubmitting stuck L1 transactions:
+///
+/// - Stuck transactions are resubmitted at most once per `resubmission_interval_blocks` L1 blocks.
+/// - On each resubmission, the priority fee is bumped by `priority_fee_bump_percent`, and the base fee follows
+///   the gas adjuster suggestion (which grows with the time the transaction spends in the mempool).
+/// - After `max_fee_bumps_before_replacement` bumps, the transaction is replaced by a transaction paying
+///   doubled fees, cancelling the incremental escalation.
+/// - `max_fee_per_gas` of all attempts is capped by `max_fee_per_gas_cap`.
+#[derive(Debug, Clone)]
+pub(super) struct GasEscalationPolicy {
+    resubmission_interval_blocks: u32,
+    priority_fee_bump_percent: u64,
+    max_fee_per_gas_cap: Option<u64>,
+    max_fee_bumps_before_replacement: Option<u32>,
+}
+
+impl GasEscalationPolicy {
+    pub fn new(config: &SenderConfig) -> Self {
+        let priority_fee_bump_percent = config
+            .priority_fee_bump_percent
+            .unwrap_or(DEFAULT_PRIORITY_FEE_BUMP_PERCENT);
+        if priority_fee_bump_percent < MIN_REPLACEMENT_BUMP_PERCENT {
+            tracing::warn!(
+                "Configured priority fee bump ({priority_fee_bump_percent}%) is lower than required by L1 nodes \
+                 for replacement transactions; using {MIN_REPLACEMENT_BUMP_PERCENT}%"
+            );
+        }
+
+        Self {
+            resubmission_interval_blocks: config.resubmission_interval_blocks.unwrap_or(1).max(1),
+            priority_fee_bump_percent: priority_fee_bump_percent.max(MIN_REPLACEMENT_BUMP_PERCENT),
+            max_fee_per_gas_cap: config.max_fee_per_gas_cap,
+            max_fee_bumps_before_replacement: config
+                .max_fee_bumps_before_replacement
+                .filter(|&bumps| bumps > 0),
+        }
+    }
+
+    /// Returns fees for the initial attempt to send a transaction.
+    pub fn initial_fees(&self, suggested_fees: AttemptFees) -> AttemptFees {
+        self.apply_cap(suggested_fees)
+    }
+
+    /// Decides whether and with which fees a stuck transaction should be resubmitted.
+    pub fn escalate(&self, tx: StuckTxInfo) -> EscalationDecision {
+        if tx.blocks_since_last_attempt < self.resubmission_interval_blocks {
+            return EscalationDecision::Skip(SkipReason::ResubmissionInterval);
+        }
+
+        let is_replacement = self
+            .max_fee_bumps_before_replacement
+            .map_or(false, |bumps| tx.attempts % (bumps + 1) == 0);
+        let (action, fees) = if is_replacement {
+            let fees = AttemptFees {
+                base_fee_per_gas: tx
+                    .suggested_fees
+                    .base_fee_per_gas
+                    .max(tx.previous_fees.base_fee_per_gas * 2),
+                priority_fee_per_gas: tx
+                    .suggested_fees
+                    .priority_fee_per_gas
+                    .max(tx.previous_fees.priority_fee_per_gas * 2),
+            };
+            (GasEscalationAction::Replacement, fees)
+        } else {
+            let suggested_base_fee = tx.suggested_fees.base_fee_per_gas;
+            if suggested_base_fee
+                <= tx
+                    .next_block_minimal_base_fee
+                    .min(tx.previous_fees.base_fee_per_gas)
+            {
+                // If the base fee is lower than the previous used one
+                // or is lower than the minimal possible value for the next block, sending is skipped.
+                return EscalationDecision::Skip(SkipReason::BaseFeeNotIncreased);
+            }
+
+            let previous_priority_fee = tx.previous_fees.priority_fee_per_gas;
+            let bumped_priority_fee = previous_priority_fee
+                + previous_priority_fee * self.priority_fee_bump_percent / 100
+                + 1;
+            let fees = AttemptFees {
+                base_fee_per_gas: suggested_base_fee,
+                priority_fee_per_gas: bumped_priority_fee
+                    .max(tx.suggested_fees.priority_fee_per_gas),
+            };
+            (GasEscalationAction::FeeBump, fees)
+        };
+
+        let capped_fees = self.apply_cap(fees);
+        if capped_fees != fees && !capped_fees.can_replace(tx.previous_fees) {
+            return EscalationDecision::Skip(SkipReason::FeeCapReached);
+        }
+        EscalationDecision::Send {
+            action,
+            fees: capped_fees,
+        }
+    }
+
+    /// Caps `max_fee_per_gas` of the provided fees, reducing the base fee first.
+    fn apply_cap(&self, fees: AttemptFees) -> AttemptFees {
+        let Some(cap) = self.max_fee_per_gas_cap else {
+            return fees;
+        };
+        if fees.max_fee_per_gas() <= cap {
+            return fees;
+        }
+        let priority_fee_per_gas = fees.priority_fee_per_gas.min(cap);
+        AttemptFees {
+            base_fee_per_gas: cap - priority_fee_per_gas,
+            priority_fee_per_gas,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_config::ETHSenderConfig;
+
+    use super::*;
+
+    fn policy(customize: impl FnOnce(&mut SenderConfig)) -> GasEscalationPolicy {
+        let mut config = ETHSenderConfig::for_tests().sender;
+        customize(&mut config);
+        GasEscalationPolicy::new(&config)
+    }
+
+    fn fees(base_fee_per_gas: u64, priority_fee_per_gas: u64) -> AttemptFees {
+        AttemptFees {
+            base_fee_per_gas,
+            priority_fee_per_gas,
+        }
+    }
+
+    fn stuck_tx(attempts: u32, blocks_since_last_attempt: u32) -> StuckTxInfo {
+        StuckTxInfo {
+            attempts,
+            blocks_since_last_attempt,
+            previous_fees: fees(100, 10),
+            suggested_fees: fees(120, 5),
+            next_block_minimal_base_fee: 90,
+        }
+    }
+
+    #[test]
+    fn default_policy_bumps_priority_fee() {
+        let policy = policy(|_| {});
+        assert_eq!(
+            policy.escalate(stuck_tx(1, 1)),
+            EscalationDecision::Send {
+                action: GasEscalationAction::FeeBump,
+                fees: fees(120, 13),
+            }
+        );
+        // Replacements are disabled by default.
+        assert_eq!(
+            policy.escalate(stuck_tx(10, 1)),
+            EscalationDecision::Send {
+                action: GasEscalationAction::FeeBump,
+                fees: fees(120, 13),
+            }
+        );
+
+        let tx = StuckTxInfo {
+            suggested_fees: fees(80, 5),
+            ..stuck_tx(1, 1)
+        };
+        assert_eq!(
+            policy.escalate(tx),
+            EscalationDecision::Skip(SkipReason::BaseFeeNotIncreased)
+        );
+    }
+
+    #[test]
+    fn resubmission_interval_and_bump_percent() {
+        let policy = policy(|config| {
+            config.resubmission_interval_blocks = Some(3);
+            config.priority_fee_bump_percent = Some(50);
+        });
+        assert_eq!(
+            policy.escalate(stuck_tx(1, 2)),
+            EscalationDecision::Skip(SkipReason::ResubmissionInterval)
+        );
+        assert_eq!(
+            policy.escalate(stuck_tx(1, 3)),
+            EscalationDecision::Send {
+                action: GasEscalationAction::FeeBump,
+                fees: fees(120, 16),
+            }
+        );
+
+        // Bumps lower than required by L1 nodes are not allowed.
+        let policy = policy(|config| config.priority_fee_bump_percent = Some(1));
+        assert_eq!(
+            policy.priority_fee_bump_percent,
+            MIN_REPLACEMENT_BUMP_PERCENT
+        );
+    }
+
+    #[test]
+    fn replacement_after_max_bumps() {
+        let policy = policy(|config| config.max_fee_bumps_before_replacement = Some(2));
+        for attempts in [1, 2, 4, 5] {
+            assert_matches!(
+                policy.escalate(stuck_tx(attempts, 1)),
+                EscalationDecision::Send {
+                    action: GasEscalationAction::FeeBump,
+                    ..
+                }
+            );
+        }
+        for attempts in [3, 6] {
+            assert_eq!(
+                policy.escalate(stuck_tx(attempts, 1)),
+                EscalationDecision::Send {
+                    action: GasEscalationAction::Replacement,
+                    fees: fees(200, 20),
+                }
+            );
+        }
+
+        // Replacements are sent even if the base fee has decreased.
+        let tx = StuckTxInfo {
+            suggested_fees: fees(80, 5),
+            ..stuck_tx(3, 1)
+        };
+        assert_matches!(
+            policy.escalate(tx),
+            EscalationDecision::Send {
+                action: GasEscalationAction::Replacement,
+                ..
+            }
+        );
+    }
+
+    #[test]
+    fn fee_cap() {
+        let policy = policy(|config| config.max_fee_per_gas_cap = Some(125));
+        assert_eq!(policy.initial_fees(fees(150, 5)), fees(120, 5));
+        assert_eq!(policy.initial_fees(fees(100, 5)), fees(100, 5));
+
+        // The capped fees are still sufficient to replace the previous transaction.
+        let tx = StuckTxInfo {
+            previous_fees: fees(100, 5),
+            suggested_fees: fees(120, 5),
+            ..stuck_tx(1, 1)
+        };
+        assert_eq!(
+            policy.escalate(tx),
+            EscalationDecision::Send {
+                action: GasEscalationAction::FeeBump,
+                fees: fees(118, 7),
+            }
+        );
+
+        let tx = StuckTxInfo {
+            previous_fees: fees(118, 7),
+            ..tx
+        };
+        assert_eq!(
+            policy.escalate(tx),
+            EscalationDecision::Skip(SkipReason::FeeCapReached)
+        );
+    }
+}
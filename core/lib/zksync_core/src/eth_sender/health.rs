@@ -0,0 +1,85 @@
+//! Health check for L1 transactions sent by `eth_sender`.
+
+use serde::Serialize;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
+use zksync_types::L1BatchNumber;
+use zksync_utils::time::seconds_since_epoch;
+
+#[derive(Debug, Serialize)]
+struct EthSenderHealthDetails {
+    inflight_txs: usize,
+    /// Seconds elapsed since the oldest in-flight transaction was created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oldest_inflight_tx_age_sec: Option<u64>,
+    last_committed_l1_batch: Option<L1BatchNumber>,
+    last_proven_l1_batch: Option<L1BatchNumber>,
+    last_executed_l1_batch: Option<L1BatchNumber>,
+}
+
+/// Health check reporting in-flight L1 transactions and the progress of L1 batches on the settlement layer.
+#[derive(Debug)]
+pub struct EthSenderHealthCheck {
+    pool: ConnectionPool,
+    max_inflight_txs: Option<usize>,
+}
+
+impl EthSenderHealthCheck {
+    pub fn new(pool: ConnectionPool, max_inflight_txs: Option<usize>) -> Self {
+        Self {
+            pool,
+            max_inflight_txs,
+        }
+    }
+
+    async fn details(&self) -> anyhow::Result<EthSenderHealthDetails> {
+        let mut storage = self.pool.access_storage_tagged("healthcheck").await?;
+        let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await?;
+        let oldest_created_at = inflight_txs.iter().map(|tx| tx.created_at_timestamp).min();
+        let mut blocks_dal = storage.blocks_dal();
+        Ok(EthSenderHealthDetails {
+            inflight_txs: inflight_txs.len(),
+            oldest_inflight_tx_age_sec: oldest_created_at
+                .map(|created_at| seconds_since_epoch().saturating_sub(created_at)),
+            last_committed_l1_batch: blocks_dal
+                .get_number_of_last_l1_batch_committed_on_eth()
+                .await?,
+            last_proven_l1_batch: blocks_dal
+                .get_number_of_last_l1_batch_proven_on_eth()
+                .await?,
+            last_executed_l1_batch: blocks_dal
+                .get_number_of_last_l1_batch_executed_on_eth()
+                .await?,
+        })
+    }
+}
+
+#[async_trait]
+impl CheckHealth for EthSenderHealthCheck {
+    fn name(&self) -> &'static str {
+        "eth_sender"
+    }
+
+    async fn check_health(&self) -> Health {
+        match self.details().await {
+            Ok(details) => {
+                let is_congested = self
+                    .max_inflight_txs
+                    .is_some_and(|max_txs| details.inflight_txs > max_txs);
+                let status = if is_congested {
+                    HealthStatus::Affected
+                } else {
+                    HealthStatus::Ready
+                };
+                Health::from(status).with_details(details)
+            }
+            Err(err) => {
+                tracing::warn!("Failed checking eth_sender health: {err:#}");
+                let details = serde_json::json!({
+                    "error": format!("{err:#}"),
+                });
+                Health::from(HealthStatus::NotReady).with_details(details)
+            }
+        }
+    }
+}
@@ -2,13 +2,15 @@
 
 use std::{fmt, time::Duration};
 
-use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
 use zksync_dal::StorageProcessor;
-use zksync_types::{aggregated_operations::AggregatedActionType, eth_sender::EthTx};
+use zksync_types::{aggregated_operations::AggregatedActionType, eth_sender::EthTx, Address};
 use zksync_utils::time::seconds_since_epoch;
 
 use crate::{
-    eth_sender::eth_tx_manager::L1BlockNumbers,
+    eth_sender::{eth_tx_manager::L1BlockNumbers, gas_escalation::SkipReason},
     metrics::{BlockL1Stage, BlockStage, APP_METRICS},
 };
 
@@ -22,6 +24,18 @@ pub(super) enum PubdataKind {
     RawPublishedBytecodes,
 }
 
+/// Reason for publishing pubdata in calldata when blobs are enabled in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum PubdataDAFallbackReason {
+    /// L1 doesn't support EIP-4844 (or the blob base fee is unknown).
+    BlobsNotSupported,
+    /// Some of the committed L1 batches predate protocol version 1.4.2.
+    UnsupportedProtocolVersion,
+    /// Blob fees are higher than the cost of publishing pubdata in calldata.
+    BlobsTooExpensive,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
 #[metrics(label = "block_number_variant", rename_all = "snake_case")]
 #[allow(clippy::enum_variant_names)]
@@ -62,6 +76,35 @@ impl From<(AggregatedActionType, &'static str)> for AggregationReasonLabels {
     }
 }
 
+/// Operator account address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "account")]
+pub(super) struct AccountLabel(Address);
+
+impl From<Address> for AccountLabel {
+    fn from(address: Address) -> Self {
+        Self(address)
+    }
+}
+
+impl fmt::Display for AccountLabel {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{:?}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct TopUpLabels {
+    hook: &'static str,
+    result: &'static str,
+}
+
+impl TopUpLabels {
+    pub fn new(hook: &'static str, result: &'static str) -> Self {
+        Self { hook, result }
+    }
+}
+
 /// Roughly exponential buckets for fees (100M – 500B).
 const FEE_BUCKETS: Buckets = Buckets::values(&[
     1e7, 2e7, 5e7, 1e8, 2e8, 5e8, 1e9, 2e9, 5e9, 1e10, 2e10, 5e10, 1e11, 2e11, 5e11,
@@ -84,10 +127,20 @@ pub(super) struct EthSenderMetrics {
     pub block_range_size: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of transactions resent by the Ethereum sender.
     pub transaction_resent: Counter,
+    /// Number of stuck transactions replaced by transactions with doubled fees after reaching the maximum number of fee bumps.
+    pub transaction_replaced: Counter,
+    /// Number of detected nonce jumps of operator accounts caused by transactions sent outside the node.
+    pub external_nonce_jumps: Counter,
+    /// Number of transactions that got new nonces after external nonce jumps.
+    pub resynced_txs: Counter,
+    /// Number of skipped resubmissions of stuck transactions.
+    pub transaction_resend_skipped: Family<SkipReason, Counter>,
     #[metrics(buckets = FEE_BUCKETS)]
     pub used_base_fee_per_gas: Histogram<u64>,
     #[metrics(buckets = FEE_BUCKETS)]
     pub used_priority_fee_per_gas: Histogram<u64>,
+    #[metrics(buckets = FEE_BUCKETS)]
+    pub used_blob_base_fee_per_gas: Histogram<u64>,
     /// Last L1 block observed by the Ethereum sender.
     pub last_known_l1_block: Family<BlockNumberVariant, Gauge<usize>>,
     /// Number of in-flight txs produced by the Ethereum sender.
@@ -100,6 +153,29 @@ pub(super) struct EthSenderMetrics {
     pub l1_blocks_waited_in_mempool: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of L1 batches aggregated for publishing with a specific reason.
     pub block_aggregation_reason: Family<AggregationReasonLabels, Counter>,
+    /// Number of commit operations that fell back to publishing pubdata in calldata although blobs are enabled.
+    pub pubdata_da_fallback: Family<PubdataDAFallbackReason, Counter>,
+    /// Total L1 settlement cost of confirmed operations in gwei.
+    pub settlement_cost_gwei: Family<ActionTypeLabel, Counter>,
+    /// Total fees collected from L2 transactions in fully settled L1 batches in gwei.
+    pub l2_fees_collected_gwei: Counter,
+    /// Number of fully settled L1 batches whose collected fees do not cover the settlement cost.
+    pub unprofitable_batches: Counter,
+    /// Number of settlement layer anomalies that halted commit and execute operations.
+    pub settlement_halts: Counter,
+    /// Set to 1 if commit and execute operations are currently halted; 0 otherwise.
+    pub settlement_halted: Gauge<u64>,
+    /// Set to 1 if the node proves and executes L1 batches in the emergency mode; 0 otherwise.
+    pub emergency_mode_active: Gauge<u64>,
+    /// Time since the last L1 batch commitment on the settlement layer observed in the emergency mode.
+    #[metrics(unit = Unit::Seconds)]
+    pub operator_inactivity: Gauge<Duration>,
+    /// Balance of an operator account on the settlement layer in gwei.
+    pub operator_balance_gwei: Family<AccountLabel, Gauge<u64>>,
+    /// Set to 1 if the balance of an operator account is below the configured threshold; 0 otherwise.
+    pub operator_balance_low: Family<AccountLabel, Gauge<u64>>,
+    /// Number of top-up requests for operator accounts with low balances, grouped by the hook and result.
+    pub operator_top_up_requests: Family<TopUpLabels, Counter>,
 }
 
 impl EthSenderMetrics {
@@ -1,16 +1,33 @@
 mod aggregated_operations;
 mod aggregator;
+mod balance_monitor;
+mod emergency_mode;
 mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
+mod gas_escalation;
+mod health;
 mod metrics;
+mod operator_accounts;
 mod publish_criterion;
+mod settlement_guard;
+mod signer_health;
 mod zksync_functions;
 
 #[cfg(test)]
 mod tests;
 
+pub(crate) use self::settlement_guard::stored_batch_hash;
 pub use self::{
-    aggregator::Aggregator, error::ETHSenderError, eth_tx_aggregator::EthTxAggregator,
+    aggregator::Aggregator,
+    balance_monitor::{
+        FundingContractTopUpHook, OperatorBalanceMonitor, TopUpHook, TopUpRequest, WebhookTopUpHook,
+    },
+    emergency_mode::{EmergencyArtifacts, StoredL1BatchArtifact},
+    error::ETHSenderError,
+    eth_tx_aggregator::EthTxAggregator,
     eth_tx_manager::EthTxManager,
+    health::EthSenderHealthCheck,
+    operator_accounts::OperatorAccounts,
+    signer_health::RemoteSignerHealthCheck,
 };
@@ -0,0 +1,176 @@
+//! Operator accounts used by the Ethereum sender, with optional separation by operation type.
+
+use std::{collections::HashMap, sync::Arc};
+
+use zksync_eth_client::BoundEthInterface;
+use zksync_types::{aggregated_operations::AggregatedActionType, Address};
+
+/// Operator accounts used to sign L1 transactions produced by the Ethereum sender.
+///
+/// Each operation type (commit, prove, execute) can be sent from a dedicated account, so that a compromised key
+/// or nonce issues for one operation type don't halt the entire settlement pipeline. Operation types without
+/// a dedicated account are sent from the main operator account.
+///
+/// Keys are rotated by replacing a dedicated account and moving the previous one to the retired accounts.
+/// Retired accounts are never used for new transactions; they are only used to resend transactions
+/// that were in flight during the rotation.
+#[derive(Debug, Clone)]
+pub struct OperatorAccounts {
+    main: Arc<dyn BoundEthInterface>,
+    dedicated: HashMap<AggregatedActionType, Arc<dyn BoundEthInterface>>,
+    retired: Vec<Arc<dyn BoundEthInterface>>,
+}
+
+impl OperatorAccounts {
+    /// Creates accounts where all operations are sent from the main operator account.
+    pub fn new(main: Arc<dyn BoundEthInterface>) -> Self {
+        Self {
+            main,
+            dedicated: HashMap::new(),
+            retired: Vec::new(),
+        }
+    }
+
+    /// Sets a dedicated account for the specified operation type. If the account coincides
+    /// with the main operator account, this is a no-op.
+    pub fn with_dedicated_account(
+        mut self,
+        op: AggregatedActionType,
+        client: Arc<dyn BoundEthInterface>,
+    ) -> Self {
+        if client.sender_account() != self.main.sender_account() {
+            self.dedicated.insert(op, client);
+        }
+        self
+    }
+
+    /// Adds a retired account.
+    pub fn with_retired_account(mut self, client: Arc<dyn BoundEthInterface>) -> Self {
+        self.retired.push(client);
+        self
+    }
+
+    /// Returns the main operator account. Besides sending operations without a dedicated account,
+    /// it's used for read-only L1 queries.
+    pub fn main(&self) -> &Arc<dyn BoundEthInterface> {
+        &self.main
+    }
+
+    /// Returns the address stored with L1 transactions for the specified operation type.
+    /// `None` corresponds to the main operator account.
+    pub fn from_addr(&self, op: AggregatedActionType) -> Option<Address> {
+        self.dedicated
+            .get(&op)
+            .map(|client| client.sender_account())
+    }
+
+    /// Returns the account that should send new transactions for the specified operation type.
+    pub fn for_operation(&self, op: AggregatedActionType) -> &Arc<dyn BoundEthInterface> {
+        self.dedicated.get(&op).unwrap_or(&self.main)
+    }
+
+    /// Returns all accounts able to send new transactions, identified by their `from_addr`.
+    pub fn active_accounts(&self) -> Vec<(Option<Address>, &Arc<dyn BoundEthInterface>)> {
+        let mut accounts = vec![(None, &self.main)];
+        for client in self.dedicated.values() {
+            let from_addr = Some(client.sender_account());
+            if !accounts.iter().any(|(addr, _)| *addr == from_addr) {
+                accounts.push((from_addr, client));
+            }
+        }
+        accounts
+    }
+
+    /// Returns all accounts that may have transactions to send or monitor, including retired accounts,
+    /// identified by their `from_addr`.
+    pub fn signing_accounts(&self) -> Vec<(Option<Address>, &Arc<dyn BoundEthInterface>)> {
+        let mut accounts = self.active_accounts();
+        for client in &self.retired {
+            let from_addr = Some(client.sender_account());
+            if !accounts.iter().any(|(addr, _)| *addr == from_addr) {
+                accounts.push((from_addr, client));
+            }
+        }
+        accounts
+    }
+
+    /// Returns the account able to sign transactions for the specified `from_addr`, including retired accounts.
+    pub fn signer(&self, from_addr: Option<Address>) -> Option<&Arc<dyn BoundEthInterface>> {
+        let Some(from_addr) = from_addr else {
+            return Some(&self.main);
+        };
+        self.dedicated
+            .values()
+            .chain(&self.retired)
+            .chain([&self.main])
+            .find(|client| client.sender_account() == from_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_eth_client::clients::MockEthereum;
+
+    use super::*;
+
+    fn mock_account(address: Address) -> Arc<dyn BoundEthInterface> {
+        Arc::new(MockEthereum::default().with_sender_account(address))
+    }
+
+    #[test]
+    fn operations_are_mapped_to_accounts() {
+        let main = mock_account(Address::repeat_byte(1));
+        let commit = mock_account(Address::repeat_byte(2));
+        let retired = mock_account(Address::repeat_byte(3));
+        let accounts = OperatorAccounts::new(main)
+            .with_dedicated_account(AggregatedActionType::Commit, commit)
+            .with_dedicated_account(
+                AggregatedActionType::Execute,
+                mock_account(Address::repeat_byte(1)),
+            )
+            .with_retired_account(retired);
+
+        assert_eq!(
+            accounts.from_addr(AggregatedActionType::Commit),
+            Some(Address::repeat_byte(2))
+        );
+        // The execute account coincides with the main one.
+        assert_eq!(accounts.from_addr(AggregatedActionType::Execute), None);
+        assert_eq!(
+            accounts.from_addr(AggregatedActionType::PublishProofOnchain),
+            None
+        );
+        assert_eq!(
+            accounts
+                .for_operation(AggregatedActionType::Commit)
+                .sender_account(),
+            Address::repeat_byte(2)
+        );
+
+        let active_addresses: Vec<_> = accounts
+            .active_accounts()
+            .into_iter()
+            .map(|(addr, _)| addr)
+            .collect();
+        assert_eq!(active_addresses, [None, Some(Address::repeat_byte(2))]);
+        let signing_addresses: Vec<_> = accounts
+            .signing_accounts()
+            .into_iter()
+            .map(|(addr, _)| addr)
+            .collect();
+        assert_eq!(
+            signing_addresses,
+            [
+                None,
+                Some(Address::repeat_byte(2)),
+                Some(Address::repeat_byte(3))
+            ]
+        );
+
+        let retired_signer = accounts.signer(Some(Address::repeat_byte(3))).unwrap();
+        assert_eq!(retired_signer.sender_account(), Address::repeat_byte(3));
+        let main_signer = accounts.signer(Some(Address::repeat_byte(1))).unwrap();
+        assert_eq!(main_signer.sender_account(), Address::repeat_byte(1));
+        assert!(accounts.signer(Some(Address::repeat_byte(4))).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -6,11 +6,11 @@ use zksync_dal::StorageProcessor;
 use zksync_l1_contract_interface::{i_executor::structures::CommitBatchInfo, Tokenizable};
 use zksync_types::{
     aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata, ethabi,
-    L1BatchNumber,
+    pubdata_da::PubdataDA, L1BatchNumber,
 };
 
 use super::metrics::METRICS;
-use crate::gas_tracker::agg_l1_batch_base_cost;
+use crate::{gas_tracker::agg_l1_batch_base_cost, l1_gas_price::L1TxParamsProvider};
 
 #[async_trait]
 pub trait L1BatchPublishCriterion: fmt::Debug + Send + Sync {
@@ -218,10 +218,15 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
 
         for (index, l1_batch) in consecutive_l1_batches.iter().enumerate() {
             // TODO (PLA-771): Make sure that this estimation is correct.
-            let l1_commit_data_size = ethabi::encode(&[ethabi::Token::Array(vec![
-                CommitBatchInfo(l1_batch).into_token(),
-            ])])
-            .len();
+            // Publishing pubdata in calldata is the worst case in terms of the data size, so we use it
+            // regardless of the pubdata source chosen for the commit transaction.
+            let l1_commit_data_size =
+                ethabi::encode(&[ethabi::Token::Array(vec![CommitBatchInfo::new(
+                    l1_batch,
+                    PubdataDA::Calldata,
+                )
+                .into_token()])])
+                .len();
             if data_size_left < l1_commit_data_size {
                 if index == 0 {
                     panic!(
@@ -247,3 +252,73 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
         None
     }
 }
+
+/// Publishes L1 batches once the estimated L1 cost of the operation split among the included batches
+/// doesn't exceed the target. The more expensive L1 gas is, the more batches are accumulated per operation.
+#[derive(Debug)]
+pub struct CostCriterion {
+    pub op: AggregatedActionType,
+    /// Target cost of the operation per L1 batch, in gwei.
+    pub target_cost_per_batch_in_gwei: u64,
+    pub gas_adjuster: Arc<dyn L1TxParamsProvider>,
+}
+
+impl CostCriterion {
+    const GWEI: u128 = 1_000_000_000;
+
+    fn gas_price(&self) -> u128 {
+        u128::from(self.gas_adjuster.get_base_fee(0))
+            + u128::from(self.gas_adjuster.get_priority_fee())
+    }
+}
+
+#[async_trait]
+impl L1BatchPublishCriterion for CostCriterion {
+    fn name(&self) -> &'static str {
+        "cost"
+    }
+
+    async fn last_l1_batch_to_publish(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        consecutive_l1_batches: &[L1BatchWithMetadata],
+        _last_sealed_l1_batch: L1BatchNumber,
+    ) -> Option<L1BatchNumber> {
+        let (first_l1_batch, last_l1_batch) = (
+            consecutive_l1_batches.first()?,
+            consecutive_l1_batches.last()?,
+        );
+        let gas_price = self.gas_price();
+        let target_cost = u128::from(self.target_cost_per_batch_in_gwei) * Self::GWEI;
+        let mut total_gas = u128::from(agg_l1_batch_base_cost(self.op));
+        let gas_per_batch = storage
+            .blocks_dal()
+            .get_predicted_gas_per_l1_batch(
+                first_l1_batch.header.number..=last_l1_batch.header.number,
+                self.op,
+            )
+            .await
+            .unwrap();
+
+        for (index, l1_batch) in consecutive_l1_batches.iter().enumerate() {
+            let batch_number = l1_batch.header.number;
+            let batch_gas = gas_per_batch.get(&batch_number).copied().unwrap_or(0);
+            total_gas += u128::from(batch_gas);
+
+            let batch_count = index as u128 + 1;
+            let cost_per_batch = total_gas * gas_price / batch_count;
+            if cost_per_batch <= target_cost {
+                let first_l1_batch_number = first_l1_batch.header.number.0;
+                tracing::debug!(
+                    "`cost` publish criterion (cost per batch={cost_per_batch} wei, gas price={gas_price} wei) \
+                     triggered for op {} with L1 batch range {:?}",
+                    self.op,
+                    first_l1_batch_number..=batch_number.0
+                );
+                METRICS.block_aggregation_reason[&(self.op, "cost").into()].inc();
+                return Some(batch_number);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,202 @@
+//! Safety checks of the settlement layer state performed before new operations are aggregated.
+
+use std::fmt;
+
+use zksync_contracts::zksync_contract;
+use zksync_dal::StorageProcessor;
+use zksync_eth_client::{BoundEthInterface, CallFunctionArgs};
+use zksync_l1_contract_interface::{
+    i_executor::structures::StoredBatchInfo, Detokenize, Tokenizable, Tokenize,
+};
+use zksync_types::{
+    commitment::L1BatchWithMetadata,
+    ethabi,
+    web3::{
+        signing::keccak256,
+        types::{BlockId, BlockNumber},
+    },
+    Address, L1BatchNumber, H256, U256,
+};
+
+use super::ETHSenderError;
+
+const COMPONENT: &str = "eth_sender_settlement_guard";
+
+/// Unexpected settlement layer state that halts new commit and execute operations.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum SettlementAnomaly {
+    /// Diamond proxy storage is frozen, e.g. because of an ongoing upgrade or an emergency.
+    DiamondStorageFrozen,
+    /// Hash of a committed L1 batch stored on L1 differs from the locally computed one.
+    CommittedBatchHashMismatch {
+        l1_batch_number: L1BatchNumber,
+        local_hash: H256,
+        l1_hash: H256,
+    },
+    /// L1 block buried deeper than the max tolerated reorg depth was reorganized.
+    DeepReorg {
+        block_number: u64,
+        expected_hash: H256,
+        actual_hash: Option<H256>,
+    },
+}
+
+impl fmt::Display for SettlementAnomaly {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DiamondStorageFrozen => formatter.write_str("diamond proxy storage is frozen"),
+            Self::CommittedBatchHashMismatch {
+                l1_batch_number,
+                local_hash,
+                l1_hash,
+            } => write!(
+                formatter,
+                "hash of committed L1 batch #{l1_batch_number} on L1 ({l1_hash:?}) \
+                 differs from the local one ({local_hash:?})"
+            ),
+            Self::DeepReorg {
+                block_number,
+                expected_hash,
+                actual_hash,
+            } => write!(
+                formatter,
+                "L1 block #{block_number} ({expected_hash:?}) was reorganized; \
+                 the current block hash is {actual_hash:?}"
+            ),
+        }
+    }
+}
+
+/// Checks the settlement layer state for anomalies.
+#[derive(Debug)]
+pub(super) struct SettlementGuard {
+    zksync_contract: ethabi::Contract,
+    main_zksync_contract_address: Address,
+    max_reorg_depth: Option<u64>,
+    /// L1 block buried under `max_reorg_depth` blocks at the time of the previous check.
+    reorg_checkpoint: Option<(u64, H256)>,
+}
+
+impl SettlementGuard {
+    pub fn new(main_zksync_contract_address: Address, max_reorg_depth: Option<u64>) -> Self {
+        Self {
+            zksync_contract: zksync_contract(),
+            main_zksync_contract_address,
+            max_reorg_depth,
+            reorg_checkpoint: None,
+        }
+    }
+
+    /// Returns the first detected anomaly, or `None` if the settlement layer state is as expected.
+    pub async fn check(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        client: &dyn BoundEthInterface,
+    ) -> Result<Option<SettlementAnomaly>, ETHSenderError> {
+        if let Some(max_reorg_depth) = self.max_reorg_depth {
+            if let Some(anomaly) = self.check_reorg(client, max_reorg_depth).await? {
+                return Ok(Some(anomaly));
+            }
+        }
+
+        let is_frozen: bool = self
+            .call_zksync_contract(client, "isDiamondStorageFrozen", ())
+            .await?;
+        if is_frozen {
+            return Ok(Some(SettlementAnomaly::DiamondStorageFrozen));
+        }
+        self.check_last_committed_batch(storage, client).await
+    }
+
+    async fn call_zksync_contract<R: Detokenize>(
+        &self,
+        client: &dyn BoundEthInterface,
+        function_name: &str,
+        params: impl Tokenize,
+    ) -> Result<R, ETHSenderError> {
+        let args = CallFunctionArgs::new(function_name, params).for_contract(
+            self.main_zksync_contract_address,
+            self.zksync_contract.clone(),
+        );
+        let output = client.call_contract_function(args).await?;
+        Ok(R::from_tokens(output)?)
+    }
+
+    async fn check_reorg(
+        &mut self,
+        client: &dyn BoundEthInterface,
+        max_reorg_depth: u64,
+    ) -> Result<Option<SettlementAnomaly>, ETHSenderError> {
+        let mut anomaly = None;
+        if let Some((block_number, expected_hash)) = self.reorg_checkpoint {
+            let actual_hash = Self::block_hash(client, block_number).await?;
+            if actual_hash != Some(expected_hash) {
+                anomaly = Some(SettlementAnomaly::DeepReorg {
+                    block_number,
+                    expected_hash,
+                    actual_hash,
+                });
+            }
+        }
+
+        let latest_block_number = client.block_number(COMPONENT).await?.as_u64();
+        self.reorg_checkpoint = match latest_block_number.checked_sub(max_reorg_depth) {
+            Some(block_number) => Self::block_hash(client, block_number)
+                .await?
+                .map(|hash| (block_number, hash)),
+            None => None,
+        };
+        Ok(anomaly)
+    }
+
+    async fn block_hash(
+        client: &dyn BoundEthInterface,
+        block_number: u64,
+    ) -> Result<Option<H256>, ETHSenderError> {
+        let block_id = BlockId::Number(BlockNumber::Number(block_number.into()));
+        let block = client.block(block_id, COMPONENT).await?;
+        Ok(block.and_then(|block| block.hash))
+    }
+
+    async fn check_last_committed_batch(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        client: &dyn BoundEthInterface,
+    ) -> Result<Option<SettlementAnomaly>, ETHSenderError> {
+        let last_committed: U256 = self
+            .call_zksync_contract(client, "getTotalBatchesCommitted", ())
+            .await?;
+        let l1_batch_number = L1BatchNumber(last_committed.as_u32());
+        if l1_batch_number == L1BatchNumber(0) {
+            return Ok(None);
+        }
+        let Some(l1_batch) = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await
+            .unwrap()
+        else {
+            // The batch isn't sealed locally yet, so there's nothing to compare with.
+            return Ok(None);
+        };
+
+        let l1_hash: H256 = self
+            .call_zksync_contract(client, "storedBatchHash", U256::from(l1_batch_number.0))
+            .await?;
+        let local_hash = stored_batch_hash(&l1_batch);
+        Ok(
+            (l1_hash != local_hash).then_some(SettlementAnomaly::CommittedBatchHashMismatch {
+                l1_batch_number,
+                local_hash,
+                l1_hash,
+            }),
+        )
+    }
+}
+
+/// Computes the hash of the batch information as stored by the L1 contract.
+pub(crate) fn stored_batch_hash(l1_batch: &L1BatchWithMetadata) -> H256 {
+    H256(keccak256(&ethabi::encode(&[
+        StoredBatchInfo(l1_batch).into_token()
+    ])))
+}
@@ -0,0 +1,49 @@
+//! Health check for the remote operator signer.
+
+use serde::Serialize;
+use zksync_eth_signer::RemoteSigner;
+use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
+use zksync_types::Address;
+
+#[derive(Debug, Serialize)]
+struct RemoteSignerHealthDetails {
+    kind: String,
+    address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Health check verifying that the remote service signing operator transactions is reachable
+/// and the operator key is usable.
+#[derive(Debug, Clone)]
+pub struct RemoteSignerHealthCheck {
+    signer: RemoteSigner,
+}
+
+impl RemoteSignerHealthCheck {
+    pub fn new(signer: RemoteSigner) -> Self {
+        Self { signer }
+    }
+}
+
+#[async_trait]
+impl CheckHealth for RemoteSignerHealthCheck {
+    fn name(&self) -> &'static str {
+        "operator_signer"
+    }
+
+    async fn check_health(&self) -> Health {
+        let result = self.signer.check_health().await;
+        let status = if result.is_ok() {
+            HealthStatus::Ready
+        } else {
+            HealthStatus::NotReady
+        };
+        let details = RemoteSignerHealthDetails {
+            kind: self.signer.kind().to_string(),
+            address: self.signer.address(),
+            error: result.err().map(|err| err.to_string()),
+        };
+        Health::from(status).with_details(details)
+    }
+}
@@ -1,32 +1,43 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use anyhow::Context as _;
 use assert_matches::assert_matches;
 use once_cell::sync::Lazy;
 use zksync_config::{
     configs::eth_sender::{ProofSendingMode, SenderConfig},
     ContractsConfig, ETHSenderConfig, GasAdjusterConfig,
 };
+use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_eth_client::{clients::MockEthereum, EthInterface};
+use zksync_eth_client::{clients::MockEthereum, BoundEthInterface, EthInterface};
 use zksync_l1_contract_interface::i_executor::methods::{
     CommitBatches, ExecuteBatches, ProveBatches,
 };
 use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     block::L1BatchHeader,
     commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
+    eth_sender::GasEscalationAction,
     ethabi::Token,
     helpers::unix_timestamp_ms,
+    protocol_version::L1VerifierConfig,
+    pubdata_da::PubdataDA,
     web3::contract::Error,
-    Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256,
+    Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256, U256,
 };
 
 use crate::{
     eth_sender::{
-        aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers, Aggregator,
-        ETHSenderError, EthTxAggregator, EthTxManager,
+        aggregated_operations::AggregatedOperation,
+        aggregator::da_failover_batch_count,
+        emergency_mode::SettlementLayerBatches,
+        eth_tx_manager::L1BlockNumbers,
+        publish_criterion::{CostCriterion, L1BatchPublishCriterion},
+        Aggregator, ETHSenderError, EthTxAggregator, EthTxManager, OperatorAccounts,
     },
-    l1_gas_price::GasAdjuster,
+    gas_tracker::agg_l1_batch_base_cost,
+    l1_gas_price::{GasAdjuster, L1TxParamsProvider},
     utils::testonly::{create_l1_batch, l1_batch_metadata_to_commitment_artifacts},
 };
 
@@ -60,6 +71,21 @@ impl EthSenderTester {
         connection_pool: ConnectionPool,
         history: Vec<u64>,
         non_ordering_confirmations: bool,
+    ) -> Self {
+        Self::with_operator_accounts(
+            connection_pool,
+            history,
+            non_ordering_confirmations,
+            |accounts| accounts,
+        )
+        .await
+    }
+
+    async fn with_operator_accounts(
+        connection_pool: ConnectionPool,
+        history: Vec<u64>,
+        non_ordering_confirmations: bool,
+        customize_accounts: impl FnOnce(OperatorAccounts) -> OperatorAccounts,
     ) -> Self {
         let eth_sender_config = ETHSenderConfig::for_tests();
         let contracts_config = ContractsConfig::for_tests();
@@ -95,6 +121,7 @@ impl EthSenderTester {
             .unwrap(),
         );
         let store_factory = ObjectStoreFactory::mock();
+        let operator_accounts = customize_accounts(OperatorAccounts::new(gateway.clone()));
 
         let aggregator = EthTxAggregator::new(
             SenderConfig {
@@ -105,8 +132,10 @@ impl EthSenderTester {
             Aggregator::new(
                 aggregator_config.clone(),
                 store_factory.create_store().await,
+                gas_adjuster.clone(),
             ),
-            gateway.clone(),
+            operator_accounts.clone(),
+            gas_adjuster.clone(),
             // zkSync contract address
             Address::random(),
             contracts_config.l1_multicall3_addr,
@@ -118,7 +147,7 @@ impl EthSenderTester {
         let manager = EthTxManager::new(
             eth_sender_config.sender,
             gas_adjuster.clone(),
-            gateway.clone(),
+            operator_accounts,
         );
         Self {
             gateway,
@@ -215,7 +244,7 @@ async fn confirm_many() -> anyhow::Result<()> {
     );
 
     // also check that we didn't try to resend it
-    assert!(to_resend.is_none());
+    assert!(to_resend.is_empty());
 
     Ok(())
 }
@@ -282,13 +311,14 @@ async fn resend_each_block() -> anyhow::Result<()> {
     tester.gas_adjuster.keep_updated().await?;
     let block_numbers = tester.get_block_numbers().await;
 
-    let (to_resend, _) = tester
+    let [(to_resend, _)]: [_; 1] = tester
         .manager
         .monitor_inflight_transactions(
             &mut tester.conn.access_storage().await.unwrap(),
             block_numbers,
         )
         .await?
+        .try_into()
         .unwrap();
 
     let resent_hash = tester
@@ -330,6 +360,86 @@ async fn resend_each_block() -> anyhow::Result<()> {
     Ok(())
 }
 
+// Tests that a stuck transaction is replaced after the configured number of fee bumps,
+// and that all attempts are recorded in the gas escalation audit trail.
+#[tokio::test]
+async fn replace_after_max_fee_bumps() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![7, 6, 5, 5, 5, 2, 1], false).await;
+    tester.manager = EthTxManager::new(
+        SenderConfig {
+            max_fee_bumps_before_replacement: Some(1),
+            ..ETHSenderConfig::for_tests().sender
+        },
+        tester.gas_adjuster.clone(),
+        OperatorAccounts::new(tester.gateway.clone()),
+    );
+    tester.gateway.advance_block_number(3);
+    tester.gas_adjuster.keep_updated().await?;
+
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await?;
+    let mut hashes = vec![];
+    for time_in_mempool in 0..3 {
+        let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+        let hash = tester
+            .manager
+            .send_eth_tx(
+                &mut tester.conn.access_storage().await.unwrap(),
+                &tx,
+                time_in_mempool,
+                block,
+            )
+            .await?;
+        hashes.push(hash);
+        tester.gateway.advance_block_number(1);
+    }
+    assert_eq!(tester.gateway.sent_tx_count(), 3);
+
+    let mut priority_fees = vec![];
+    for hash in &hashes {
+        let sent_tx = tester.gateway.get_tx(*hash, "").await?.unwrap();
+        assert_eq!(sent_tx.nonce, 0.into());
+        priority_fees.push(sent_tx.max_priority_fee_per_gas.unwrap().as_u64());
+    }
+    assert_eq!(
+        priority_fees[1],
+        priority_fees[0] + priority_fees[0] / 5 + 1
+    );
+    assert_eq!(priority_fees[2], priority_fees[1] * 2);
+
+    let escalations = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_gas_escalations(tx.id)
+        .await?;
+    let actions: Vec<_> = escalations
+        .iter()
+        .map(|entry| (entry.attempt, entry.action))
+        .collect();
+    assert_eq!(
+        actions,
+        [
+            (1, GasEscalationAction::Initial),
+            (2, GasEscalationAction::FeeBump),
+            (3, GasEscalationAction::Replacement)
+        ]
+    );
+    for (entry, hash) in escalations.iter().zip(&hashes) {
+        assert_eq!(entry.tx_hash, Some(*hash));
+    }
+    assert_eq!(escalations[2].priority_fee_per_gas, priority_fees[2]);
+
+    Ok(())
+}
+
 // Tests that if transaction was mined, but not enough blocks has been mined since,
 // we won't mark it as confirmed but also won't resend it.
 #[tokio::test]
@@ -398,7 +508,7 @@ async fn dont_resend_already_mined() -> anyhow::Result<()> {
     );
 
     // also check that we didn't try to resend it
-    assert!(to_resend.is_none());
+    assert!(to_resend.is_empty());
 
     Ok(())
 }
@@ -446,13 +556,14 @@ async fn three_scenarios() -> anyhow::Result<()> {
         .gateway
         .execute_tx(hashes[1], true, EthSenderTester::WAIT_CONFIRMATIONS - 1);
 
-    let (to_resend, _) = tester
+    let [(to_resend, _)]: [_; 1] = tester
         .manager
         .monitor_inflight_transactions(
             &mut tester.conn.access_storage().await.unwrap(),
             tester.get_block_numbers().await,
         )
         .await?
+        .try_into()
         .expect("we should be trying to resend the last tx");
 
     // check that last 2 transactions are still considered in-flight
@@ -474,6 +585,66 @@ async fn three_scenarios() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn dedicated_operator_account() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let execute_account = Address::repeat_byte(0x33);
+    let execute_gateway = Arc::new(MockEthereum::default().with_sender_account(execute_account));
+    let mut tester = EthSenderTester::with_operator_accounts(
+        connection_pool,
+        vec![100; 100],
+        false,
+        |accounts| accounts.with_dedicated_account(AggregatedActionType::Execute, execute_gateway),
+    )
+    .await;
+
+    for expected_nonce in 0..2 {
+        let tx = tester
+            .aggregator
+            .save_eth_tx(
+                &mut tester.conn.access_storage().await.unwrap(),
+                &DUMMY_OPERATION,
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(tx.from_addr, Some(execute_account));
+        assert_eq!(tx.nonce.0, expected_nonce);
+    }
+
+    // Nonces of the main operator account must not be affected.
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    let main_nonce = storage.eth_sender_dal().get_next_nonce(None).await.unwrap();
+    assert_eq!(main_nonce, None);
+    let execute_nonce = storage
+        .eth_sender_dal()
+        .get_next_nonce(Some(execute_account))
+        .await
+        .unwrap();
+    assert_eq!(execute_nonce, Some(2));
+
+    let new_txs = storage
+        .eth_sender_dal()
+        .get_new_eth_txs(10, None)
+        .await
+        .unwrap();
+    assert!(new_txs.is_empty());
+    let new_txs = storage
+        .eth_sender_dal()
+        .get_new_eth_txs(10, Some(execute_account))
+        .await
+        .unwrap();
+    assert_eq!(new_txs.len(), 2);
+
+    let block_number = L1BlockNumber(tester.gateway.block_number("").await.unwrap().as_u32());
+    tester
+        .manager
+        .send_eth_tx(&mut storage, &new_txs[0], 0, block_number)
+        .await
+        .unwrap();
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+}
+
 #[should_panic(expected = "We can't operate after tx fail")]
 #[tokio::test]
 async fn failed_eth_tx() {
@@ -607,6 +778,502 @@ async fn correct_order_for_confirmations() -> anyhow::Result<()> {
     Ok(())
 }
 
+// Tests that nonces consumed by transactions sent outside the node are detected, and in-flight transactions
+// whose sending attempts cannot be mined anymore are resent with new nonces.
+#[tokio::test]
+async fn external_nonce_jump_for_inflight_txs() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+
+    let mut tx_ids = vec![];
+    for _ in 0..2 {
+        let tx = tester
+            .aggregator
+            .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, true)
+            .await?;
+        tester
+            .manager
+            .send_eth_tx(
+                &mut tester.conn.access_storage().await.unwrap(),
+                &tx,
+                0,
+                L1BlockNumber(tester.gateway.block_number("").await?.as_u32()),
+            )
+            .await?;
+        tx_ids.push(tx.id);
+    }
+    // The operator sends a rescue transaction consuming the nonce of the first transaction.
+    tester
+        .gateway
+        .execute_external_tx(EthSenderTester::WAIT_CONFIRMATIONS);
+
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    assert!(to_resend.is_empty());
+
+    let mut storage = tester.storage().await;
+    let txs = storage
+        .eth_sender_dal()
+        .get_unconfirmed_txs(None, tx_ids[0])
+        .await?;
+    let nonces: Vec<_> = txs.iter().map(|tx| tx.nonce.0).collect();
+    // The sending attempt of the second transaction may still be mined, so it keeps its nonce;
+    // the first transaction gets the nonce following it.
+    assert_eq!(nonces, [2, 1]);
+    // The sending attempt of the first transaction is kept for auditing, but it's superseded.
+    let tx_history = storage
+        .eth_sender_dal()
+        .get_tx_history_to_check(tx_ids[0])
+        .await?;
+    assert_eq!(tx_history.len(), 1);
+    assert!(storage
+        .eth_sender_dal()
+        .get_last_sent_eth_tx(tx_ids[0])
+        .await?
+        .is_none());
+    let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await?;
+    let inflight_tx_ids: Vec<_> = inflight_txs.iter().map(|tx| tx.id).collect();
+    assert_eq!(inflight_tx_ids, tx_ids);
+    assert!(storage
+        .eth_sender_dal()
+        .get_new_eth_txs(10, None)
+        .await?
+        .is_empty());
+
+    // Newly saved transactions get nonces following the reassigned ones.
+    let new_tx = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await?;
+    assert_eq!(new_tx.nonce.0, 3);
+
+    // Once the second transaction is mined, the first one is sent anew.
+    let hash = storage
+        .eth_sender_dal()
+        .get_last_sent_eth_tx(tx_ids[1])
+        .await?
+        .unwrap()
+        .tx_hash;
+    drop(storage);
+    tester
+        .gateway
+        .execute_tx(hash, true, EthSenderTester::WAIT_CONFIRMATIONS);
+    let block_numbers = tester.get_block_numbers().await;
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            block_numbers,
+        )
+        .await?;
+    let [(tx_to_resend, _)]: [_; 1] = to_resend.try_into().unwrap();
+    assert_eq!(tx_to_resend.id, tx_ids[0]);
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &tx_to_resend,
+            0,
+            block_numbers.latest,
+        )
+        .await?;
+    tester
+        .manager
+        .send_new_eth_txs(
+            &mut tester.conn.access_storage().await.unwrap(),
+            block_numbers,
+        )
+        .await;
+    assert_eq!(tester.gateway.sent_tx_count(), 4);
+    let inflight_txs = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?;
+    let inflight_tx_ids: Vec<_> = inflight_txs.iter().map(|tx| tx.id).collect();
+    assert_eq!(inflight_tx_ids, [tx_ids[0], new_tx.id]);
+
+    for tx in &inflight_txs {
+        let hash = tester
+            .storage()
+            .await
+            .eth_sender_dal()
+            .get_last_sent_eth_tx(tx.id)
+            .await?
+            .unwrap()
+            .tx_hash;
+        tester
+            .gateway
+            .execute_tx(hash, true, EthSenderTester::WAIT_CONFIRMATIONS);
+    }
+    tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    assert!(tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?
+        .is_empty());
+    Ok(())
+}
+
+// Tests that a nonce consumed outside the node before a transaction was sent is detected.
+#[tokio::test]
+async fn external_nonce_jump_for_new_txs() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, true)
+        .await?;
+    assert_eq!(tx.nonce.0, 0);
+    tester
+        .gateway
+        .execute_external_tx(EthSenderTester::WAIT_CONFIRMATIONS);
+
+    let block_numbers = tester.get_block_numbers().await;
+    tester
+        .manager
+        .send_new_eth_txs(
+            &mut tester.conn.access_storage().await.unwrap(),
+            block_numbers,
+        )
+        .await;
+
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+    let [inflight_tx]: [_; 1] = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?
+        .try_into()
+        .unwrap();
+    assert_eq!(inflight_tx.id, tx.id);
+    assert_eq!(inflight_tx.nonce.0, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn settlement_costs_are_recorded() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], true).await;
+    insert_genesis_protocol_version(&tester).await;
+    let genesis_l1_batch = insert_l1_batch(&tester, L1BatchNumber(0)).await;
+    let first_l1_batch = insert_l1_batch(&tester, L1BatchNumber(1)).await;
+
+    commit_l1_batch(
+        &mut tester,
+        genesis_l1_batch.clone(),
+        first_l1_batch.clone(),
+        true,
+    )
+    .await;
+    let economics = tester
+        .storage()
+        .await
+        .settlement_costs_dal()
+        .get_batch_economics(L1BatchNumber(1))
+        .await?
+        .context("no economics for L1 batch #1")?;
+    let commit_cost = economics.commit.context("no commit cost")?;
+    assert!(economics.prove.is_none());
+    assert!(!economics.is_fully_settled);
+
+    // The mock receipt doesn't specify the effective gas price, so the fees of the confirmed attempt are used.
+    let tx_history = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_tx_history_to_check(commit_cost.eth_tx_id)
+        .await?;
+    let [attempt] = tx_history.as_slice() else {
+        panic!("unexpected history: {tx_history:?}");
+    };
+    assert_eq!(commit_cost.gas_used, 21_000);
+    assert_eq!(
+        commit_cost.gas_cost,
+        U256::from(21_000) * (attempt.base_fee_per_gas + attempt.priority_fee_per_gas)
+    );
+    assert_eq!(commit_cost.blob_gas_used, 0);
+
+    prove_l1_batch(
+        &mut tester,
+        genesis_l1_batch.clone(),
+        first_l1_batch.clone(),
+        true,
+    )
+    .await;
+    execute_l1_batches(&mut tester, vec![first_l1_batch.clone()], true).await;
+    let economics = tester
+        .storage()
+        .await
+        .settlement_costs_dal()
+        .get_batch_economics(L1BatchNumber(1))
+        .await?
+        .context("no economics for L1 batch #1")?;
+    assert!(economics.is_fully_settled);
+    // The batch doesn't contain L2 transactions, so it cannot be profitable.
+    assert_eq!(economics.l2_fees_collected, U256::zero());
+    assert!(!economics.is_profitable);
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run_simulates_operations_without_saving() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], true).await;
+    insert_genesis_protocol_version(&tester).await;
+    let genesis_l1_batch = insert_l1_batch(&tester, L1BatchNumber(0)).await;
+    let first_l1_batch = insert_l1_batch(&tester, L1BatchNumber(1)).await;
+    let operation = AggregatedOperation::Commit(CommitBatches {
+        last_committed_l1_batch: l1_batch_with_metadata(genesis_l1_batch),
+        l1_batches: vec![l1_batch_with_metadata(first_l1_batch)],
+        pubdata_da: PubdataDA::Calldata,
+        da_inclusion_data: vec![],
+    });
+
+    // The same operation is only simulated once.
+    for _ in 0..2 {
+        tester
+            .aggregator
+            .preview_operation(
+                &mut tester.conn.access_storage().await.unwrap(),
+                &operation,
+                true,
+            )
+            .await?;
+    }
+
+    let calls = tester.gateway.calls();
+    let [call] = calls.as_slice() else {
+        panic!("unexpected calls: {calls:?}");
+    };
+    assert_eq!(call.from, Some(tester.gateway.sender_account()));
+    assert!(call.to.is_some());
+    assert!(call.data.as_ref().map_or(false, |data| !data.0.is_empty()));
+
+    assert_eq!(tester.gateway.sent_tx_count(), 0);
+    let inflight_txs = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await?;
+    assert!(inflight_txs.is_empty(), "{inflight_txs:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run_operations_cover_pending_l1_batches_up_to_limit() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let tester = EthSenderTester::new(connection_pool, vec![100; 100], true).await;
+    insert_genesis_protocol_version(&tester).await;
+    for number in 0..=4 {
+        insert_l1_batch(&tester, L1BatchNumber(number)).await;
+    }
+    let mut aggregator = Aggregator::new(
+        SenderConfig {
+            max_aggregated_blocks_to_commit: 2,
+            proof_sending_mode: ProofSendingMode::SkipEveryProof,
+            dry_run: true,
+            ..ETHSenderConfig::for_tests().sender
+        },
+        ObjectStoreFactory::mock().create_store().await,
+        tester.gas_adjuster.clone(),
+    );
+
+    // The commit operation ignores publish criteria, but is limited by `max_aggregated_blocks_to_commit`;
+    // no L1 batches are committed yet, so there are no proof or execute operations.
+    let ops = aggregator
+        .get_pending_operations(
+            &mut tester.storage().await,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+            L1VerifierConfig::default(),
+        )
+        .await;
+    let [op] = ops.as_slice() else {
+        panic!("unexpected operations: {ops:?}");
+    };
+    assert_matches!(op, AggregatedOperation::Commit(_));
+    assert_eq!(op.l1_batch_range(), L1BatchNumber(1)..=L1BatchNumber(2));
+    Ok(())
+}
+
+#[tokio::test]
+async fn settlement_halt_is_respected_until_resumed() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], true).await;
+    let mut storage = tester.conn.access_storage().await?;
+    assert!(
+        !tester
+            .aggregator
+            .check_settlement_layer(&mut storage)
+            .await?
+    );
+
+    storage
+        .eth_sender_dal()
+        .halt_settlement("diamond proxy storage is frozen")
+        .await?;
+    assert!(
+        tester
+            .aggregator
+            .check_settlement_layer(&mut storage)
+            .await?
+    );
+
+    storage.eth_sender_dal().resume_settlement().await?;
+    assert!(
+        !tester
+            .aggregator
+            .check_settlement_layer(&mut storage)
+            .await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn emergency_mode_proves_and_executes_batches_committed_on_l1() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let tester = EthSenderTester::new(connection_pool, vec![100; 100], true).await;
+    insert_genesis_protocol_version(&tester).await;
+    for number in 0..=4 {
+        insert_l1_batch(&tester, L1BatchNumber(number)).await;
+    }
+    let mut aggregator = Aggregator::new(
+        SenderConfig {
+            aggregated_proof_sizes: vec![4],
+            proof_sending_mode: ProofSendingMode::SkipEveryProof,
+            ..ETHSenderConfig::for_tests().sender
+        },
+        ObjectStoreFactory::mock().create_store().await,
+        tester.gas_adjuster.clone(),
+    );
+    let mut l1_batches = SettlementLayerBatches {
+        committed: L1BatchNumber(3),
+        proven: L1BatchNumber(1),
+        executed: L1BatchNumber(0),
+    };
+    let mut storage = tester.storage().await;
+
+    let op = aggregator
+        .get_next_emergency_operation(&mut storage, l1_batches, L1VerifierConfig::default())
+        .await
+        .unwrap();
+    assert_matches!(&op, AggregatedOperation::Execute(_));
+    assert_eq!(op.l1_batch_range(), L1BatchNumber(1)..=L1BatchNumber(1));
+    tester
+        .aggregator
+        .save_eth_tx(&mut storage, &op, true)
+        .await?;
+
+    // L1 batch #4 is not committed on L1, so it must not be proven.
+    let op = aggregator
+        .get_next_emergency_operation(&mut storage, l1_batches, L1VerifierConfig::default())
+        .await
+        .unwrap();
+    let AggregatedOperation::PublishProofOnchain(prove_op) = &op else {
+        panic!("unexpected operation: {op:?}");
+    };
+    assert_eq!(prove_op.prev_l1_batch.header.number, L1BatchNumber(1));
+    assert_eq!(op.l1_batch_range(), L1BatchNumber(2)..=L1BatchNumber(3));
+    tester
+        .aggregator
+        .save_eth_tx(&mut storage, &op, true)
+        .await?;
+
+    // Operations in flight must not be duplicated.
+    let op = aggregator
+        .get_next_emergency_operation(&mut storage, l1_batches, L1VerifierConfig::default())
+        .await;
+    assert!(op.is_none(), "{op:?}");
+
+    l1_batches.proven = L1BatchNumber(3);
+    let op = aggregator
+        .get_next_emergency_operation(&mut storage, l1_batches, L1VerifierConfig::default())
+        .await
+        .unwrap();
+    assert_matches!(&op, AggregatedOperation::Execute(_));
+    assert_eq!(op.l1_batch_range(), L1BatchNumber(2)..=L1BatchNumber(3));
+    Ok(())
+}
+
+#[tokio::test]
+async fn cost_criterion_adapts_to_gas_price() -> anyhow::Result<()> {
+    const GWEI: u64 = 1_000_000_000;
+
+    let connection_pool = ConnectionPool::test_pool().await;
+    let tester = EthSenderTester::new(connection_pool, vec![100 * GWEI; 100], true).await;
+    insert_genesis_protocol_version(&tester).await;
+    let mut l1_batches = vec![];
+    for number in 1..=3 {
+        let header = insert_l1_batch(&tester, L1BatchNumber(number)).await;
+        l1_batches.push(l1_batch_with_metadata(header));
+    }
+
+    let op = AggregatedActionType::Commit;
+    let gas_price = tester.gas_adjuster.get_base_fee(0) + tester.gas_adjuster.get_priority_fee();
+    let mut storage = tester.storage().await;
+    let predicted_gas = storage
+        .blocks_dal()
+        .get_l1_batches_predicted_gas(L1BatchNumber(1)..=L1BatchNumber(2), op)
+        .await?;
+    let cost_of_two_batches = (agg_l1_batch_base_cost(op) + predicted_gas) as u64 * gas_price;
+    let mut criterion = CostCriterion {
+        op,
+        target_cost_per_batch_in_gwei: cost_of_two_batches.div_ceil(2 * GWEI),
+        gas_adjuster: tester.gas_adjuster.clone(),
+    };
+
+    // A single L1 batch is too expensive to publish, but the cost split among 2 batches fits the target.
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches, L1BatchNumber(3))
+        .await;
+    assert_eq!(last_l1_batch, Some(L1BatchNumber(2)));
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches[..1], L1BatchNumber(3))
+        .await;
+    assert_eq!(last_l1_batch, None);
+
+    // The target cannot be reached at all.
+    criterion.target_cost_per_batch_in_gwei = 0;
+    let last_l1_batch = criterion
+        .last_l1_batch_to_publish(&mut storage, &l1_batches, L1BatchNumber(3))
+        .await;
+    assert_eq!(last_l1_batch, None);
+    Ok(())
+}
+
+#[test]
+fn da_failover_applies_to_old_l1_batches() {
+    // L1 batch timestamps are equal to their numbers.
+    let l1_batches: Vec<_> = (1..=5)
+        .map(|number| l1_batch_with_metadata(create_l1_batch(number)))
+        .collect();
+    let timeout = Duration::from_secs(10);
+
+    assert_eq!(da_failover_batch_count(&l1_batches, timeout, 10), 0);
+    assert_eq!(da_failover_batch_count(&l1_batches, timeout, 11), 1);
+    assert_eq!(da_failover_batch_count(&l1_batches, timeout, 13), 3);
+    assert_eq!(da_failover_batch_count(&l1_batches, timeout, 100), 5);
+    assert_eq!(da_failover_batch_count(&[], timeout, 100), 0);
+}
+
 #[tokio::test]
 async fn skipped_l1_batch_at_the_start() -> anyhow::Result<()> {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -787,6 +1454,59 @@ async fn skipped_l1_batch_in_the_middle() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn l1_batches_wait_for_preceding_operation_confirmation() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], true).await;
+    insert_genesis_protocol_version(&tester).await;
+    let genesis_l1_batch = insert_l1_batch(&tester, L1BatchNumber(0)).await;
+    let first_l1_batch = insert_l1_batch(&tester, L1BatchNumber(1)).await;
+
+    let commit_tx_hash = commit_l1_batch(
+        &mut tester,
+        genesis_l1_batch.clone(),
+        first_l1_batch.clone(),
+        false,
+    )
+    .await;
+    let l1_batches = tester
+        .storage()
+        .await
+        .blocks_dal()
+        .get_ready_for_dummy_proof_l1_batches(10)
+        .await?;
+    assert!(l1_batches.is_empty());
+
+    confirm_tx(&mut tester, commit_tx_hash).await;
+    let l1_batches = tester
+        .storage()
+        .await
+        .blocks_dal()
+        .get_ready_for_dummy_proof_l1_batches(10)
+        .await?;
+    assert_eq!(l1_batches.len(), 1);
+
+    let prove_tx_hash = prove_l1_batch(&mut tester, genesis_l1_batch, first_l1_batch, false).await;
+    let l1_batches = tester
+        .storage()
+        .await
+        .blocks_dal()
+        .get_ready_for_execute_l1_batches(10, None)
+        .await?;
+    assert!(l1_batches.is_empty());
+
+    confirm_tx(&mut tester, prove_tx_hash).await;
+    let l1_batches = tester
+        .storage()
+        .await
+        .blocks_dal()
+        .get_ready_for_execute_l1_batches(10, None)
+        .await?;
+    assert_eq!(l1_batches.len(), 1);
+    assert_eq!(l1_batches[0].header.number, L1BatchNumber(1));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_parse_multicall_data() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -951,6 +1671,8 @@ async fn commit_l1_batch(
     let operation = AggregatedOperation::Commit(CommitBatches {
         last_committed_l1_batch: l1_batch_with_metadata(last_committed_l1_batch),
         l1_batches: vec![l1_batch_with_metadata(l1_batch)],
+        pubdata_da: PubdataDA::Calldata,
+        da_inclusion_data: vec![],
     });
     send_operation(tester, operation, confirm).await
 }
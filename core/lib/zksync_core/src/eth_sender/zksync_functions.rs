@@ -1,5 +1,11 @@
 use zksync_contracts::{multicall_contract, verifier_contract, zksync_contract};
-use zksync_types::ethabi::{Contract, Function};
+use zksync_l1_contract_interface::Tokenize;
+use zksync_types::{
+    ethabi::{Contract, Function, Token},
+    L2ChainId,
+};
+
+use super::aggregated_operations::AggregatedOperation;
 
 #[derive(Debug)]
 pub(super) struct ZkSyncFunctions {
@@ -84,3 +90,66 @@ impl Default for ZkSyncFunctions {
         }
     }
 }
+
+impl ZkSyncFunctions {
+    /// Encodes calldata of the settlement layer contract call for the specified operation.
+    pub(super) fn encode_aggregated_op(
+        &self,
+        rollup_chain_id: L2ChainId,
+        op: &AggregatedOperation,
+        contracts_are_pre_shared_bridge: bool,
+    ) -> Vec<u8> {
+        let operation_is_pre_shared_bridge = op.protocol_version().is_pre_shared_bridge();
+        assert_eq!(
+            contracts_are_pre_shared_bridge,
+            operation_is_pre_shared_bridge
+        );
+
+        let mut args = vec![Token::Uint(rollup_chain_id.as_u64().into())];
+
+        match op.clone() {
+            AggregatedOperation::Commit(op) => {
+                if contracts_are_pre_shared_bridge {
+                    self.pre_shared_bridge_commit
+                        .encode_input(&op.into_tokens())
+                        .expect("Failed to encode commit transaction data")
+                } else {
+                    args.extend(op.into_tokens());
+                    self.post_shared_bridge_commit
+                        .as_ref()
+                        .expect("Missing ABI for commitBatchesSharedBridge")
+                        .encode_input(&args)
+                        .expect("Failed to encode commit transaction data")
+                }
+            }
+            AggregatedOperation::PublishProofOnchain(op) => {
+                if contracts_are_pre_shared_bridge {
+                    self.pre_shared_bridge_prove
+                        .encode_input(&op.into_tokens())
+                        .expect("Failed to encode prove transaction data")
+                } else {
+                    args.extend(op.into_tokens());
+                    self.post_shared_bridge_prove
+                        .as_ref()
+                        .expect("Missing ABI for proveBatchesSharedBridge")
+                        .encode_input(&args)
+                        .expect("Failed to encode prove transaction data")
+                }
+            }
+            AggregatedOperation::Execute(op) => {
+                if contracts_are_pre_shared_bridge {
+                    self.pre_shared_bridge_execute
+                        .encode_input(&op.into_tokens())
+                        .expect("Failed to encode execute transaction data")
+                } else {
+                    args.extend(op.into_tokens());
+                    self.post_shared_bridge_execute
+                        .as_ref()
+                        .expect("Missing ABI for executeBatchesSharedBridge")
+                        .encode_input(&args)
+                        .expect("Failed to encode execute transaction data")
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Bridge watcher tracks deposits to the chain on the settlement layer.
+//!
+//! Unlike [`EthWatch`](super::EthWatch), the bridge watcher processes non-finalized blocks as well, so that
+//! deposits can be tracked by the settlement layer transaction hash as soon as they are mined. Non-finalized blocks
+//! are rescanned on each iteration until they are finalized, so deposits removed by a reorg are discarded.
+
+use std::{convert::TryFrom, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_contracts::zksync_contract;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_system_constants::PRIORITY_EXPIRATION;
+use zksync_types::{
+    api::idexo::BridgeDeposit, l1::L1Tx, web3::types::BlockNumber as Web3BlockNumber, H256,
+};
+
+use super::client::{EthClient, RETRY_LIMIT};
+
+#[derive(Debug)]
+pub struct BridgeWatcher {
+    client: Box<dyn EthClient>,
+    poll_interval: Duration,
+    new_priority_request_signature: H256,
+    /// Next settlement layer block to scan.
+    next_block: u64,
+}
+
+impl BridgeWatcher {
+    pub async fn new(
+        mut client: Box<dyn EthClient>,
+        pool: &ConnectionPool,
+        poll_interval: Duration,
+    ) -> anyhow::Result<Self> {
+        let new_priority_request_signature = zksync_contract()
+            .event("NewPriorityRequest")
+            .context("NewPriorityRequest event is missing in ABI")?
+            .signature();
+        client.set_topics(vec![new_priority_request_signature]);
+
+        let mut storage = pool.access_storage_tagged("bridge_watcher").await?;
+        let last_deposit_block = storage
+            .bridge_deposits_dal()
+            .get_last_deposit_block()
+            .await?;
+        let next_block = match last_deposit_block {
+            // The block is rescanned since the watcher could have stopped mid-block.
+            Some(block) => block,
+            // There are no deposits observed - to be safe, scan the same range as the Ethereum watcher.
+            None => client
+                .finalized_block_number()
+                .await
+                .context("cannot get finalized block number")?
+                .saturating_sub(PRIORITY_EXPIRATION),
+        };
+        tracing::info!("Initialized bridge watcher starting from block #{next_block}");
+
+        Ok(Self {
+            client,
+            poll_interval,
+            new_priority_request_signature,
+            next_block,
+        })
+    }
+
+    pub async fn run(
+        mut self,
+        pool: ConnectionPool,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut timer = tokio::time::interval(self.poll_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, bridge watcher is shutting down");
+                break;
+            }
+            timer.tick().await;
+
+            let mut storage = pool.access_storage_tagged("bridge_watcher").await?;
+            if let Err(err) = self.loop_iteration(&mut storage).await {
+                // Blocks will be rescanned on the next iteration.
+                tracing::warn!("Failed to process new blocks: {err:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, storage))]
+    pub(super) async fn loop_iteration(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let finalized_block = self.client.finalized_block_number().await?;
+        let latest_block = self.client.latest_block_number().await?;
+        // Non-finalized blocks could have been reorged since they were scanned.
+        let from_block = self.next_block.min(finalized_block + 1);
+        if from_block > latest_block {
+            return Ok(());
+        }
+
+        let events = self
+            .client
+            .get_events(
+                Web3BlockNumber::Number(from_block.into()),
+                Web3BlockNumber::Number(latest_block.into()),
+                RETRY_LIMIT,
+            )
+            .await?;
+        let deposits = events
+            .into_iter()
+            .filter(|event| event.topics.first() == Some(&self.new_priority_request_signature))
+            .map(|event| {
+                let tx = L1Tx::try_from(event).context("failed parsing priority request")?;
+                Ok(BridgeDeposit {
+                    settlement_tx_hash: tx.common_data.eth_hash,
+                    settlement_block_number: tx.common_data.eth_block,
+                    priority_op_id: tx.serial_id(),
+                    tx_hash: tx.hash(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        if !deposits.is_empty() {
+            tracing::debug!(
+                "Observed {} deposits in blocks #{from_block}..=#{latest_block}",
+                deposits.len()
+            );
+        }
+        storage
+            .bridge_deposits_dal()
+            .replace_deposits(from_block, &deposits)
+            .await?;
+        self.next_block = latest_block + 1;
+        Ok(())
+    }
+}
@@ -1,5 +1,13 @@
-use std::{fmt, sync::Arc};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
 
+use anyhow::Context as _;
+use zksync_config::{configs::eth_watch::EthWatchFinalityMode, ETHWatchConfig};
 use zksync_contracts::verifier_contract;
 use zksync_eth_client::{CallFunctionArgs, Error as EthClientError, EthInterface};
 use zksync_l1_contract_interface::pre_boojum_verifier::old_l1_vk_commitment;
@@ -10,10 +18,16 @@ use zksync_types::{
         contract::tokens::Detokenize,
         types::{BlockId, BlockNumber, FilterBuilder, Log},
     },
-    Address, H256,
+    Address, L1BatchNumber, H256,
+};
+use zksync_web3_decl::{
+    error::{ClientRpcContext, EnrichedClientError},
+    jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
+    namespaces::ZksNamespaceClient,
 };
 
 use super::metrics::METRICS;
+use crate::utils::binary_search_with;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -23,6 +37,10 @@ pub enum Error {
     EthClient(#[from] EthClientError),
     #[error("Infinite recursion caused by too many responses")]
     InfiniteRecursion,
+    #[error("Settlement layer RPC error: {0}")]
+    Rpc(#[from] EnrichedClientError),
+    #[error("Unexpected settlement layer response: {0}")]
+    UnexpectedResponse(String),
 }
 
 impl From<web3::contract::Error> for Error {
@@ -40,8 +58,10 @@ pub trait EthClient: 'static + fmt::Debug + Send + Sync {
         to: BlockNumber,
         retries_left: usize,
     ) -> Result<Vec<Log>, Error>;
-    /// Returns finalized L1 block number.
+    /// Returns the number of the last L1 block that can be processed according to the finality mode.
     async fn finalized_block_number(&self) -> Result<u64, Error>;
+    /// Returns latest L1 block number. Unlike the finalized block, the latest block may be reverted by a reorg.
+    async fn latest_block_number(&self) -> Result<u64, Error>;
     /// Returns scheduler verification key hash by verifier address.
     async fn scheduler_vk_hash(&self, verifier_address: Address) -> Result<H256, Error>;
     /// Sets list of topics to return events for.
@@ -52,6 +72,83 @@ pub const RETRY_LIMIT: usize = 5;
 const TOO_MANY_RESULTS_INFURA: &str = "query returned more than";
 const TOO_MANY_RESULTS_ALCHEMY: &str = "response size exceeded";
 
+/// Source of the last processable settlement layer block, which corresponds to [`EthWatchFinalityMode`].
+#[derive(Debug)]
+pub enum FinalitySource {
+    /// Latest block minus the specified number of confirmations.
+    Confirmations(u64),
+    /// Block resolved by the specified tag (`safe` or `finalized`).
+    BlockTag(BlockNumber),
+    /// Last block of the last L1 batch executed by a zkSync-based settlement layer.
+    BatchExecution {
+        client: HttpClient,
+        /// Last L1 batch known to be executed; used as a lower bound when searching for the last executed batch.
+        last_executed_batch: AtomicU32,
+    },
+}
+
+impl FinalitySource {
+    pub fn new(config: &ETHWatchConfig, settlement_layer_url: &str) -> anyhow::Result<Self> {
+        Ok(match config.finality_mode() {
+            EthWatchFinalityMode::Confirmations => {
+                Self::Confirmations(config.confirmations_for_eth_event.unwrap_or(0))
+            }
+            EthWatchFinalityMode::Safe => Self::BlockTag(BlockNumber::Safe),
+            EthWatchFinalityMode::Finalized => Self::BlockTag(BlockNumber::Finalized),
+            EthWatchFinalityMode::BatchExecution => Self::BatchExecution {
+                client: HttpClientBuilder::default()
+                    .build(settlement_layer_url)
+                    .context("failed creating settlement layer JSON-RPC client")?,
+                // The genesis L1 batch is always executed.
+                last_executed_batch: AtomicU32::new(0),
+            },
+        })
+    }
+
+    async fn is_l1_batch_executed(
+        client: &HttpClient,
+        number: L1BatchNumber,
+    ) -> Result<bool, Error> {
+        let details = client
+            .get_l1_batch_details(number)
+            .rpc_context("get_l1_batch_details")
+            .with_arg("number", &number)
+            .await?;
+        Ok(details.map_or(false, |details| details.base.executed_at.is_some()))
+    }
+
+    /// Returns the last block of the last executed L1 batch. L1 batches are executed sequentially,
+    /// so the last executed batch is found using binary search.
+    async fn last_executed_block(
+        client: &HttpClient,
+        last_executed_batch: &AtomicU32,
+    ) -> Result<u64, Error> {
+        let sealed_batch = client
+            .get_l1_batch_number()
+            .rpc_context("get_l1_batch_number")
+            .await?
+            .as_u32();
+        let executed_batch = binary_search_with(
+            last_executed_batch.load(Ordering::Relaxed),
+            sealed_batch + 1,
+            |number| Self::is_l1_batch_executed(client, L1BatchNumber(number)),
+        )
+        .await?;
+        last_executed_batch.fetch_max(executed_batch, Ordering::Relaxed);
+
+        let batch = L1BatchNumber(executed_batch);
+        let (_, last_block) = client
+            .get_miniblock_range(batch)
+            .rpc_context("get_miniblock_range")
+            .with_arg("batch", &batch)
+            .await?
+            .ok_or_else(|| {
+                Error::UnexpectedResponse(format!("no blocks for executed L1 batch #{batch}"))
+            })?;
+        Ok(last_block.as_u64())
+    }
+}
+
 #[derive(Debug)]
 pub struct EthHttpQueryClient {
     client: Arc<dyn EthInterface>,
@@ -61,7 +158,7 @@ pub struct EthHttpQueryClient {
     /// If address is some then client will listen to events coming from it.
     governance_address: Option<Address>,
     verifier_contract_abi: Contract,
-    confirmations_for_eth_event: Option<u64>,
+    finality_source: FinalitySource,
 }
 
 impl EthHttpQueryClient {
@@ -69,7 +166,7 @@ impl EthHttpQueryClient {
         client: Arc<dyn EthInterface>,
         zksync_contract_addr: Address,
         governance_address: Option<Address>,
-        confirmations_for_eth_event: Option<u64>,
+        finality_source: FinalitySource,
     ) -> Self {
         tracing::debug!(
             "New eth client, zkSync addr: {:x}, governance addr: {:?}",
@@ -82,7 +179,7 @@ impl EthHttpQueryClient {
             zksync_contract_addr,
             governance_address,
             verifier_contract_abi: verifier_contract(),
-            confirmations_for_eth_event,
+            finality_source,
         }
     }
 
@@ -211,23 +308,29 @@ impl EthClient for EthHttpQueryClient {
     }
 
     async fn finalized_block_number(&self) -> Result<u64, Error> {
-        if let Some(confirmations) = self.confirmations_for_eth_event {
-            let latest_block_number = self.client.block_number("watch").await?.as_u64();
-            Ok(latest_block_number.saturating_sub(confirmations))
-        } else {
-            self.client
-                .block(BlockId::Number(BlockNumber::Finalized), "watch")
-                .await
-                .map_err(Into::into)
-                .map(|res| {
-                    res.expect("Finalized block must be present on L1")
-                        .number
-                        .expect("Finalized block must contain number")
-                        .as_u64()
-                })
+        match &self.finality_source {
+            FinalitySource::Confirmations(confirmations) => {
+                let latest_block_number = self.client.block_number("watch").await?.as_u64();
+                Ok(latest_block_number.saturating_sub(*confirmations))
+            }
+            FinalitySource::BlockTag(tag) => {
+                let block = self.client.block(BlockId::Number(*tag), "watch").await?;
+                let number = block.and_then(|block| block.number).ok_or_else(|| {
+                    Error::UnexpectedResponse(format!("block {tag:?} is missing on L1"))
+                })?;
+                Ok(number.as_u64())
+            }
+            FinalitySource::BatchExecution {
+                client,
+                last_executed_batch,
+            } => FinalitySource::last_executed_block(client, last_executed_batch).await,
         }
     }
 
+    async fn latest_block_number(&self) -> Result<u64, Error> {
+        Ok(self.client.block_number("watch").await?.as_u64())
+    }
+
     fn set_topics(&mut self, topics: Vec<H256>) {
         self.topics = topics;
     }
@@ -8,7 +8,7 @@ use zksync_types::{
 
 use crate::eth_watch::{
     client::{Error, EthClient},
-    event_processors::EventProcessor,
+    event_processors::{record_protocol_upgrade, EventProcessor},
 };
 
 /// Listens to operation events coming from the governance contract and saves new protocol upgrade proposals to the database.
@@ -102,6 +102,7 @@ impl EventProcessor for GovernanceUpgradesEventProcessor {
                     )
                 });
             let new_version = previous_version.apply_upgrade(upgrade, scheduler_vk_hash);
+            record_protocol_upgrade(storage, &new_version).await;
             storage
                 .protocol_versions_dal()
                 .save_protocol_version_with_tx(new_version)
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use zksync_dal::StorageProcessor;
-use zksync_types::{web3::types::Log, H256};
+use zksync_types::{api::idexo::AuditAction, web3::types::Log, ProtocolVersion, H256};
 
 use crate::eth_watch::client::{Error, EthClient};
 
@@ -22,3 +22,19 @@ pub trait EventProcessor: 'static + fmt::Debug + Send + Sync {
     /// Relevant topic which defines what events to be processed
     fn relevant_topic(&self) -> H256;
 }
+
+/// Records a protocol version persisted by an event processor in the audit log.
+async fn record_protocol_upgrade(storage: &mut StorageProcessor<'_>, version: &ProtocolVersion) {
+    let details = serde_json::json!({
+        "protocolVersion": version.id as u16,
+        "timestamp": version.timestamp,
+        "baseSystemContractsHashes": version.base_system_contracts_hashes,
+        "verifierAddress": version.verifier_address,
+        "upgradeTxHash": version.tx.as_ref().map(|tx| tx.common_data.canonical_tx_hash),
+    });
+    storage
+        .audit_log_dal()
+        .insert_entry(AuditAction::ProtocolUpgrade, "eth_watch", &details)
+        .await
+        .expect("failed recording audit log entry");
+}
@@ -5,7 +5,7 @@ use zksync_types::{web3::types::Log, ProtocolUpgrade, ProtocolVersionId, H256};
 
 use crate::eth_watch::{
     client::{Error, EthClient},
-    event_processors::EventProcessor,
+    event_processors::{record_protocol_upgrade, EventProcessor},
     metrics::{PollStage, METRICS},
 };
 
@@ -73,6 +73,7 @@ impl EventProcessor for UpgradesEventProcessor {
                 .await
                 .expect("Expected previous version to be present in DB");
             let new_version = previous_version.apply_upgrade(upgrade, scheduler_vk_hash);
+            record_protocol_upgrade(storage, &new_version).await;
             storage
                 .protocol_versions_dal()
                 .save_protocol_version_with_tx(new_version)
@@ -20,6 +20,8 @@ pub(super) struct EthWatcherMetrics {
     pub poll_eth_node: Family<PollStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub get_priority_op_events: Histogram<Duration>,
+    /// Number of events skipped because they were already processed, e.g. because of rescanning blocks after a reorg.
+    pub duplicate_events: Counter,
 }
 
 #[vise::register]
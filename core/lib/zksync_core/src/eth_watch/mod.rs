@@ -1,10 +1,21 @@
 //! Ethereum watcher polls the Ethereum node for PriorityQueue events.
-//! New events are accepted to the zkSync network once they have the sufficient amount of L1 confirmations.
+//! New events are accepted to the zkSync network once their block is final according to the configured
+//! finality mode: after a number of confirmations, once the block is `safe` or `finalized`, or once the L1 batch
+//! containing the block is executed (for zkSync-based settlement layers).
 //!
-//! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
-//! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//! Poll interval is configured using the `ETH_WATCH_ETH_NODE_POLL_INTERVAL` environment variable.
+//! Finality mode is configured using the `ETH_WATCH_FINALITY_MODE` and `ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT`
+//! environment variables.
+//!
+//! On settlement layers with probabilistic finality, the last `ETH_WATCH_REORG_RESCAN_DEPTH` processed blocks
+//! are rescanned on each poll. Events are deduplicated by their contents rather than by their position
+//! in the chain, so that an event moved to another block by a reorg is processed only once.
 
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_config::ETHWatchConfig;
@@ -12,19 +23,23 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::EthInterface;
 use zksync_system_constants::PRIORITY_EXPIRATION;
 use zksync_types::{
-    ethabi::Contract, web3::types::BlockNumber as Web3BlockNumber, Address, PriorityOpId,
-    ProtocolVersionId,
+    ethabi::Contract,
+    web3::types::{BlockNumber as Web3BlockNumber, Log},
+    Address, PriorityOpId, ProtocolVersionId, H256,
 };
 
 use self::{
-    client::{Error, EthClient, EthHttpQueryClient, RETRY_LIMIT},
+    bridge_watcher::BridgeWatcher,
+    client::{Error, EthClient, EthHttpQueryClient, FinalitySource, RETRY_LIMIT},
     event_processors::{
         governance_upgrades::GovernanceUpgradesEventProcessor,
         priority_ops::PriorityOpsEventProcessor, upgrades::UpgradesEventProcessor, EventProcessor,
     },
     metrics::{PollStage, METRICS},
 };
+use crate::supervisor::{RestartPolicy, SupervisedTask};
 
+mod bridge_watcher;
 mod client;
 mod event_processors;
 mod metrics;
@@ -38,11 +53,72 @@ struct EthWatchState {
     last_processed_ethereum_block: u64,
 }
 
+/// Identity of an event that doesn't depend on the event position in the chain.
+type EventKey = (Option<H256>, Vec<H256>, Vec<u8>);
+
+/// Deduplicates events observed in overlapping block ranges.
+#[derive(Debug, Default)]
+struct EventDeduplicator {
+    /// Processed events together with the last block they were observed in.
+    processed_events: HashMap<EventKey, u64>,
+}
+
+impl EventDeduplicator {
+    fn event_key(event: &Log) -> EventKey {
+        (
+            event.transaction_hash,
+            event.topics.clone(),
+            event.data.0.clone(),
+        )
+    }
+
+    fn block_number(event: &Log) -> u64 {
+        event.block_number.map_or(0, |number| number.as_u64())
+    }
+
+    /// Returns events that are not processed yet, skipping duplicates and events removed by a reorg.
+    fn new_events(&mut self, events: Vec<Log>) -> Vec<Log> {
+        let mut new_event_keys = HashSet::new();
+        let mut new_events = Vec::with_capacity(events.len());
+        for event in events {
+            if event.removed == Some(true) {
+                continue;
+            }
+            let key = Self::event_key(&event);
+            if let Some(last_seen_block) = self.processed_events.get_mut(&key) {
+                *last_seen_block = (*last_seen_block).max(Self::block_number(&event));
+                METRICS.duplicate_events.inc();
+            } else if new_event_keys.insert(key) {
+                new_events.push(event);
+            } else {
+                METRICS.duplicate_events.inc();
+            }
+        }
+        new_events
+    }
+
+    fn mark_as_processed(&mut self, events: &[Log]) {
+        for event in events {
+            self.processed_events
+                .insert(Self::event_key(event), Self::block_number(event));
+        }
+    }
+
+    /// Forgets events last observed before the specified block; these blocks are never rescanned.
+    fn prune(&mut self, first_scanned_block: u64) {
+        self.processed_events
+            .retain(|_, last_seen_block| *last_seen_block >= first_scanned_block);
+    }
+}
+
 #[derive(Debug)]
 pub struct EthWatch {
     client: Box<dyn EthClient>,
     poll_interval: Duration,
     event_processors: Vec<Box<dyn EventProcessor>>,
+    /// Number of already processed blocks rescanned on each iteration.
+    reorg_rescan_depth: u64,
+    deduplicator: EventDeduplicator,
 
     last_processed_ethereum_block: u64,
 }
@@ -54,6 +130,7 @@ impl EthWatch {
         mut client: Box<dyn EthClient>,
         pool: &ConnectionPool,
         poll_interval: Duration,
+        reorg_rescan_depth: u64,
     ) -> Self {
         let mut storage = pool.access_storage_tagged("eth_watch").await.unwrap();
 
@@ -88,6 +165,8 @@ impl EthWatch {
             client,
             poll_interval,
             event_processors,
+            reorg_rescan_depth,
+            deduplicator: EventDeduplicator::default(),
             last_processed_ethereum_block: state.last_processed_ethereum_block,
         }
     }
@@ -167,52 +246,95 @@ impl EthWatch {
         if to_block <= self.last_processed_ethereum_block {
             return Ok(());
         }
+        // Processed blocks may have been changed by a reorg if the settlement layer has probabilistic finality.
+        let from_block = self
+            .last_processed_ethereum_block
+            .saturating_sub(self.reorg_rescan_depth);
+        self.deduplicator.prune(from_block);
 
         let events = self
             .client
             .get_events(
-                Web3BlockNumber::Number(self.last_processed_ethereum_block.into()),
+                Web3BlockNumber::Number(from_block.into()),
                 Web3BlockNumber::Number(to_block.into()),
                 RETRY_LIMIT,
             )
             .await?;
         stage_latency.observe();
+        let events = self.deduplicator.new_events(events);
 
         for processor in self.event_processors.iter_mut() {
             processor
                 .process_events(storage, &*self.client, events.clone())
                 .await?;
         }
+        self.deduplicator.mark_as_processed(&events);
         self.last_processed_ethereum_block = to_block;
         Ok(())
     }
 }
 
+/// Starts the Ethereum watcher. If the watcher fails and is restarted according to `restart_policy`,
+/// it's re-created from the persisted state.
+#[allow(clippy::too_many_arguments)]
 pub async fn start_eth_watch(
     config: ETHWatchConfig,
     pool: ConnectionPool,
     eth_gateway: Arc<dyn EthInterface>,
+    settlement_layer_url: &str,
     diamond_proxy_addr: Address,
     governance: (Contract, Address),
+    restart_policy: RestartPolicy,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
+    // Check the finality config before spawning the watcher, so that misconfiguration isn't retried.
+    FinalitySource::new(&config, settlement_layer_url)?;
+
+    let settlement_layer_url = settlement_layer_url.to_owned();
+    let create_task = move |stop_receiver| {
+        let config = config.clone();
+        let pool = pool.clone();
+        let eth_gateway = eth_gateway.clone();
+        let settlement_layer_url = settlement_layer_url.clone();
+        let governance = governance.clone();
+        async move {
+            let eth_client = EthHttpQueryClient::new(
+                eth_gateway,
+                diamond_proxy_addr,
+                Some(governance.1),
+                FinalitySource::new(&config, &settlement_layer_url)?,
+            );
+            let mut eth_watch = EthWatch::new(
+                diamond_proxy_addr,
+                Some(governance.0),
+                Box::new(eth_client),
+                &pool,
+                config.poll_interval(),
+                config.reorg_rescan_depth.unwrap_or(0),
+            )
+            .await;
+            eth_watch.run(pool, stop_receiver).await
+        }
+    };
+    let task = SupervisedTask::new("eth_watch", restart_policy, create_task);
+    Ok(tokio::spawn(task.run(stop_receiver)))
+}
+
+pub async fn start_bridge_watcher(
+    config: ETHWatchConfig,
+    pool: ConnectionPool,
+    eth_gateway: Arc<dyn EthInterface>,
+    settlement_layer_url: &str,
+    diamond_proxy_addr: Address,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
     let eth_client = EthHttpQueryClient::new(
         eth_gateway,
         diamond_proxy_addr,
-        Some(governance.1),
-        config.confirmations_for_eth_event,
+        None,
+        FinalitySource::new(&config, settlement_layer_url)?,
     );
-
-    let mut eth_watch = EthWatch::new(
-        diamond_proxy_addr,
-        Some(governance.0),
-        Box::new(eth_client),
-        &pool,
-        config.poll_interval(),
-    )
-    .await;
-
-    Ok(tokio::spawn(async move {
-        eth_watch.run(pool, stop_receiver).await
-    }))
+    let bridge_watcher =
+        BridgeWatcher::new(Box::new(eth_client), &pool, config.poll_interval()).await?;
+    Ok(tokio::spawn(bridge_watcher.run(pool, stop_receiver)))
 }
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, convert::TryInto, sync::Arc};
+use std::{
+    collections::HashMap,
+    convert::{TryFrom, TryInto},
+    sync::Arc,
+};
 
 use tokio::sync::RwLock;
 use zksync_contracts::{governance_contract, zksync_contract};
@@ -14,7 +18,8 @@ use zksync_types::{
 
 use super::client::Error;
 use crate::eth_watch::{
-    client::EthClient, event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE, EthWatch,
+    bridge_watcher::BridgeWatcher, client::EthClient,
+    event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE, EthWatch,
 };
 
 #[derive(Debug)]
@@ -23,6 +28,7 @@ struct FakeEthClientData {
     diamond_upgrades: HashMap<u64, Vec<Log>>,
     governance_upgrades: HashMap<u64, Vec<Log>>,
     last_finalized_block_number: u64,
+    last_block_number: u64,
 }
 
 impl FakeEthClientData {
@@ -32,6 +38,7 @@ impl FakeEthClientData {
             diamond_upgrades: Default::default(),
             governance_upgrades: Default::default(),
             last_finalized_block_number: 0,
+            last_block_number: 0,
         }
     }
 
@@ -65,6 +72,26 @@ impl FakeEthClientData {
 
     fn set_last_finalized_block_number(&mut self, number: u64) {
         self.last_finalized_block_number = number;
+        self.last_block_number = self.last_block_number.max(number);
+    }
+
+    fn set_last_block_number(&mut self, number: u64) {
+        self.last_block_number = number;
+    }
+
+    fn remove_transactions(&mut self, eth_block: u64) {
+        self.transactions.remove(&eth_block);
+    }
+
+    /// Emulates a reorg moving priority operation events to another block.
+    fn copy_transactions(&mut self, from_block: u64, to_block: u64) {
+        let logs = self.transactions[&from_block].iter().map(|log| Log {
+            block_hash: Some(H256::repeat_byte(0x22)),
+            block_number: Some(to_block.into()),
+            ..log.clone()
+        });
+        let logs: Vec<_> = logs.collect();
+        self.transactions.entry(to_block).or_default().extend(logs);
     }
 }
 
@@ -99,6 +126,21 @@ impl FakeEthClient {
             .set_last_finalized_block_number(number);
     }
 
+    async fn set_last_block_number(&mut self, number: u64) {
+        self.inner.write().await.set_last_block_number(number);
+    }
+
+    async fn remove_transactions(&mut self, eth_block: u64) {
+        self.inner.write().await.remove_transactions(eth_block);
+    }
+
+    async fn copy_transactions(&mut self, from_block: u64, to_block: u64) {
+        self.inner
+            .write()
+            .await
+            .copy_transactions(from_block, to_block);
+    }
+
     async fn block_to_number(&self, block: BlockNumber) -> u64 {
         match block {
             BlockNumber::Earliest => 0,
@@ -145,6 +187,10 @@ impl EthClient for FakeEthClient {
     async fn finalized_block_number(&self) -> Result<u64, Error> {
         Ok(self.inner.read().await.last_finalized_block_number)
     }
+
+    async fn latest_block_number(&self) -> Result<u64, Error> {
+        Ok(self.inner.read().await.last_block_number)
+    }
 }
 
 fn build_l1_tx(serial_id: u64, eth_block: u64) -> L1Tx {
@@ -212,6 +258,7 @@ async fn test_normal_operation_l1_txs() {
         Box::new(client.clone()),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        0,
     )
     .await;
 
@@ -260,6 +307,7 @@ async fn test_normal_operation_upgrades() {
         Box::new(client.clone()),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        0,
     )
     .await;
 
@@ -321,6 +369,7 @@ async fn test_gap_in_upgrades() {
         Box::new(client.clone()),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        0,
     )
     .await;
 
@@ -360,6 +409,7 @@ async fn test_normal_operation_governance_upgrades() {
         Box::new(client.clone()),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        0,
     )
     .await;
 
@@ -422,6 +472,7 @@ async fn test_gap_in_single_batch() {
         Box::new(client.clone()),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        0,
     )
     .await;
 
@@ -452,6 +503,7 @@ async fn test_gap_between_batches() {
         Box::new(client.clone()),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        0,
     )
     .await;
 
@@ -487,6 +539,7 @@ async fn test_overlapping_batches() {
         Box::new(client.clone()),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        0,
     )
     .await;
 
@@ -523,6 +576,107 @@ async fn test_overlapping_batches() {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+#[tokio::test]
+async fn rescanning_blocks_after_reorg() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        Box::new(client.clone()),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        10,
+    )
+    .await;
+
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 2);
+
+    // The node returns the event both in the old and the new block, e.g. because it's lagging behind.
+    client.copy_transactions(14, 17).await;
+    client.add_transactions(&[build_l1_tx(2, 18)]).await;
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 3);
+
+    // Repeated iterations rescanning the same blocks must be no-ops.
+    client.set_last_finalized_block_number(21).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 3);
+}
+
+#[tokio::test]
+async fn bridge_watcher_tracks_non_finalized_deposits() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut client = FakeEthClient::new();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(12).await;
+    client.set_last_block_number(15).await;
+    let logs = client.inner.read().await.transactions.clone();
+    let first_tx_hash = logs[&10][0].transaction_hash.unwrap();
+    let second_tx_hash = logs[&14][0].transaction_hash.unwrap();
+
+    let mut watcher = BridgeWatcher::new(
+        Box::new(client.clone()),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+    )
+    .await
+    .unwrap();
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let deposits = storage
+        .bridge_deposits_dal()
+        .get_deposits(first_tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(deposits.len(), 1);
+    assert_eq!(deposits[0].settlement_block_number, 10);
+    assert_eq!(deposits[0].priority_op_id, PriorityOpId(0));
+    let expected_tx = L1Tx::try_from(logs[&10][0].clone()).unwrap();
+    assert_eq!(deposits[0].tx_hash, expected_tx.hash());
+    // The second deposit is not finalized, but it must be tracked nevertheless.
+    let deposits = storage
+        .bridge_deposits_dal()
+        .get_deposits(second_tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(deposits.len(), 1);
+    assert_eq!(deposits[0].priority_op_id, PriorityOpId(1));
+
+    // Emulate a reorg removing the non-finalized deposit.
+    client.remove_transactions(14).await;
+    client.set_last_block_number(16).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let deposits = storage
+        .bridge_deposits_dal()
+        .get_deposits(first_tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(deposits.len(), 1);
+    let deposits = storage
+        .bridge_deposits_dal()
+        .get_deposits(second_tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(deposits, []);
+}
+
 async fn get_all_db_txs(storage: &mut StorageProcessor<'_>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await.unwrap();
     storage
@@ -0,0 +1,28 @@
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum TransferOutcome {
+    Succeeded,
+    Failed,
+    Dropped,
+}
+
+/// Metrics for the fee distributor.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_fee_distributor")]
+pub(super) struct FeeDistributorMetrics {
+    /// Number of submitted sweeps.
+    pub sweeps: Counter,
+    /// Number of processed sweep transfers split by the outcome.
+    pub transfers: Family<TransferOutcome, Counter>,
+    /// Number of submitted transfers paying out fee rebates.
+    pub rebate_transfers: Counter,
+    /// Number of transfers rejected by the transaction sender.
+    pub rejected_transfers: Counter,
+    /// Number of the miniblock at which the fee account balance was read for the last sweep.
+    pub last_swept_miniblock: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<FeeDistributorMetrics> = vise::Global::new();
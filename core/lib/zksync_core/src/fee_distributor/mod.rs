@@ -0,0 +1,502 @@
+//! Fee distributor: periodically sweeps fees collected by the fee account to the configured destinations.
+//!
+//! Fees paid by transactions are accumulated on the fee account (the state keeper `fee_account_addr`). Once in
+//! the configured sweep interval, the distributor reads the fee account balance, leaves the configured minimum
+//! balance and the fees for the sweep transfers, and splits the remaining amount between the treasury, the sequencer
+//! and the burn address according to their shares in basis points. Rounding dust stays on the fee account and is
+//! distributed by a later sweep.
+//!
+//! Before the distribution, the distributor pays out rebates owed to accounts with discounted fees for their executed
+//! transactions (discounts are recorded by the API server when accepting transactions; see
+//! [`FeeDiscountsDal::get_unpaid_rebates()`](zksync_dal::fee_discounts_dal::FeeDiscountsDal::get_unpaid_rebates)).
+//! Rebates are retained from the balance together with the fees for their transfers; if the balance doesn't cover them,
+//! the sweep is postponed. Like sweep transfers, rebate transfers must be processed before the next sweep starts.
+//!
+//! Transfers are signed with the fee account key and recorded in the history table, after which they are submitted
+//! via the transaction sender, i.e. are subject to the same validation as transactions submitted via the API.
+//! If a transfer is rejected, the following transfers of the sweep are not submitted (they would have a nonce gap);
+//! such transfers are reported as dropped. The next sweep is only started after all transfers of the previous one
+//! have been processed; since the balance is reread for each sweep, funds of failed or dropped transfers are
+//! distributed by the next sweep. Pending transfers which nonces were used by other transactions from the fee account
+//! can never be executed, so they are marked as failed.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use chrono::Utc;
+use tokio::sync::watch;
+use zksync_config::FeeDistributorConfig;
+use zksync_dal::{
+    fee_discounts_dal::UnpaidFeeRebate,
+    fee_distribution_dal::{FeeDestination, FeeSweep, FeeTransferStatus, NewFeeTransfer},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_types::{
+    api, fee::Fee, l2::L2Tx, transaction_request::PaymasterParams, AccountTreeId, Address,
+    L2ChainId, MiniblockNumber, Nonce, PackedEthSignature, H256, L2_ETH_TOKEN_ADDRESS, U256,
+};
+
+use self::metrics::{TransferOutcome, METRICS};
+use crate::{api_server::tx_sender::TxSender, config_reload::ReloadableConfig};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Total of the destination shares.
+const TOTAL_SHARE_BPS: u32 = 10_000;
+
+/// Destination receiving a non-zero share of the swept fees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DestinationShare {
+    destination: FeeDestination,
+    recipient: Address,
+    share_bps: u32,
+}
+
+impl DestinationShare {
+    fn from_config(config: &FeeDistributorConfig) -> anyhow::Result<Vec<Self>> {
+        let destinations = [
+            (
+                FeeDestination::Treasury,
+                config.treasury_addr,
+                config.treasury_share_bps,
+            ),
+            (
+                FeeDestination::Sequencer,
+                config.sequencer_addr,
+                config.sequencer_share_bps,
+            ),
+            (
+                FeeDestination::Burn,
+                Some(config.burn_addr()),
+                config.burn_share_bps,
+            ),
+        ];
+
+        let total_share_bps = destinations
+            .iter()
+            .try_fold(0_u32, |acc, &(_, _, share_bps)| acc.checked_add(share_bps));
+        anyhow::ensure!(
+            total_share_bps == Some(TOTAL_SHARE_BPS),
+            "fee destination shares must add up to {TOTAL_SHARE_BPS} basis points"
+        );
+
+        let mut shares = vec![];
+        for (destination, recipient, share_bps) in destinations {
+            if share_bps == 0 {
+                continue;
+            }
+            let recipient = recipient.with_context(|| {
+                format!(
+                    "address of the {} fee destination is not set",
+                    destination.as_str()
+                )
+            })?;
+            shares.push(Self {
+                destination,
+                recipient,
+                share_bps,
+            });
+        }
+        Ok(shares)
+    }
+}
+
+/// Splits `amount` between destinations proportionally to their shares.
+fn split_amount(amount: U256, shares: &[DestinationShare]) -> Vec<U256> {
+    shares
+        .iter()
+        .map(|share| amount * U256::from(share.share_bps) / U256::from(TOTAL_SHARE_BPS))
+        .collect()
+}
+
+/// Task distributing collected fees. See the module-level docs for details.
+#[derive(Debug)]
+pub struct FeeDistributor {
+    pool: ConnectionPool,
+    tx_sender: TxSender,
+    config: FeeDistributorConfig,
+    private_key: H256,
+    fee_account: Address,
+    chain_id: L2ChainId,
+    shares: Vec<DestinationShare>,
+    /// ID of the last sweep with reported transfer outcomes.
+    last_reported_sweep_id: Option<i64>,
+    reloadable_config: Option<watch::Receiver<ReloadableConfig>>,
+}
+
+impl FeeDistributor {
+    pub fn new(
+        pool: ConnectionPool,
+        tx_sender: TxSender,
+        config: FeeDistributorConfig,
+        private_key: H256,
+        fee_account: Address,
+        chain_id: L2ChainId,
+    ) -> anyhow::Result<Self> {
+        let address = PackedEthSignature::address_from_private_key(&private_key)
+            .context("invalid private key for fee distributor")?;
+        anyhow::ensure!(
+            address == fee_account,
+            "fee distributor private key corresponds to {address:?}, while the fee account is {fee_account:?}"
+        );
+        let shares = DestinationShare::from_config(&config)?;
+        tracing::info!(
+            "Initialized fee distributor for fee account {fee_account:?} with shares {shares:?}"
+        );
+
+        Ok(Self {
+            pool,
+            tx_sender,
+            config,
+            private_key,
+            fee_account,
+            chain_id,
+            shares,
+            last_reported_sweep_id: None,
+            reloadable_config: None,
+        })
+    }
+
+    /// Makes the sweep interval follow the value changed at runtime.
+    pub fn with_reloadable_config(mut self, config: watch::Receiver<ReloadableConfig>) -> Self {
+        self.reloadable_config = Some(config);
+        self
+    }
+
+    fn sweep_interval(&self) -> Duration {
+        if let Some(config) = &self.reloadable_config {
+            if let Some(params) = &config.borrow().fee_distributor {
+                return params.sweep_interval;
+            }
+        }
+        self.config.sweep_interval()
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow() {
+            if let Err(err) = self.loop_iteration().await {
+                tracing::warn!("Failed distributing fees: {err:#}");
+            }
+            if tokio::time::timeout(self.config.polling_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, fee distributor is shutting down");
+        Ok(())
+    }
+
+    /// Returns the ID of the submitted sweep, if any.
+    pub(crate) async fn loop_iteration(&mut self) -> anyhow::Result<Option<i64>> {
+        let mut storage = self.pool.access_storage_tagged("fee_distributor").await?;
+        self.reject_superseded_transfers(&mut storage).await?;
+        let last_sweep = storage
+            .fee_distribution_dal()
+            .get_latest_sweeps(1)
+            .await?
+            .pop();
+        if let Some(last_sweep) = &last_sweep {
+            let has_pending_transfers = last_sweep
+                .transfers
+                .iter()
+                .any(|transfer| transfer.status == FeeTransferStatus::Pending);
+            // The balance read by the next sweep must account for all transfers from the fee account.
+            let has_pending_rebates = storage
+                .fee_discounts_dal()
+                .has_pending_rebate_transfers()
+                .await?;
+            if has_pending_transfers || has_pending_rebates {
+                return Ok(None);
+            }
+            self.report_sweep(last_sweep);
+
+            let sweep_interval = chrono::Duration::from_std(self.sweep_interval())
+                .context("sweep interval is too large")?;
+            if Utc::now() < last_sweep.created_at + sweep_interval {
+                return Ok(None);
+            }
+        }
+        self.sweep(&mut storage).await
+    }
+
+    /// Marks pending transactions from the fee account which nonces were used by other transactions as failed,
+    /// so that they don't block sweeps forever.
+    async fn reject_superseded_transfers(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let stored_nonce = self.load_stored_nonce(storage).await?;
+        let rejected_tx_hashes = storage
+            .transactions_dal()
+            .reject_txs_with_used_nonces(
+                self.fee_account,
+                stored_nonce,
+                "dropped: nonce was used by another transaction",
+            )
+            .await
+            .context("failed rejecting superseded transactions from fee account")?;
+        if !rejected_tx_hashes.is_empty() {
+            tracing::warn!(
+                "Marked pending transactions from fee account {rejected_tx_hashes:?} as failed since their nonces \
+                 were used by other transactions"
+            );
+        }
+        Ok(())
+    }
+
+    fn report_sweep(&mut self, sweep: &FeeSweep) {
+        if self.last_reported_sweep_id == Some(sweep.id) {
+            return;
+        }
+        self.last_reported_sweep_id = Some(sweep.id);
+
+        for transfer in &sweep.transfers {
+            let destination = transfer.transfer.destination.as_str();
+            let tx_hash = transfer.transfer.tx_hash;
+            let outcome = match &transfer.status {
+                FeeTransferStatus::Pending => unreachable!("checked by the caller"),
+                FeeTransferStatus::Succeeded(miniblock_number) => {
+                    tracing::info!(
+                        "Transfer of {} wei to {destination} in sweep #{} ({tx_hash:?}) was included in miniblock #{miniblock_number}",
+                        transfer.transfer.amount,
+                        sweep.id
+                    );
+                    TransferOutcome::Succeeded
+                }
+                FeeTransferStatus::Failed(error) => {
+                    tracing::error!(
+                        "Transfer to {destination} in sweep #{} ({tx_hash:?}) failed: {error}",
+                        sweep.id
+                    );
+                    TransferOutcome::Failed
+                }
+                FeeTransferStatus::Dropped => {
+                    tracing::error!(
+                        "Transfer to {destination} in sweep #{} ({tx_hash:?}) was dropped from the mempool",
+                        sweep.id
+                    );
+                    TransferOutcome::Dropped
+                }
+            };
+            METRICS.transfers[&outcome].inc();
+        }
+    }
+
+    async fn sweep(&mut self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<Option<i64>> {
+        let Some(miniblock) = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await?
+        else {
+            return Ok(None);
+        };
+        let balance = storage
+            .storage_web3_dal()
+            .standard_token_historical_balance(
+                AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
+                AccountTreeId::new(self.fee_account),
+                miniblock.number,
+            )
+            .await?;
+
+        // Overpay the current fee to not get stuck in the mempool if the fee grows slightly.
+        let fee = Fee {
+            gas_limit: self.config.gas_limit_per_transfer.into(),
+            max_fee_per_gas: (miniblock.base_fee_per_gas + miniblock.base_fee_per_gas / 2).into(),
+            max_priority_fee_per_gas: U256::zero(),
+            gas_per_pubdata_limit: miniblock.gas_per_pubdata_limit.into(),
+        };
+        let transfer_fee = fee.gas_limit * fee.max_fee_per_gas;
+        let min_balance = U256::from(self.config.min_balance);
+
+        let rebates = storage
+            .fee_discounts_dal()
+            .get_unpaid_rebates(miniblock.number)
+            .await?;
+        let rebates_cost = rebates.iter().fold(U256::zero(), |acc, rebate| {
+            acc + rebate.amount + transfer_fee
+        });
+        let pays_rebates = !rebates.is_empty() && balance >= rebates_cost + min_balance;
+
+        let transfer_fees = transfer_fee * U256::from(self.shares.len());
+        let retained_balance = rebates_cost + transfer_fees + min_balance;
+        let mut distributed_amount = balance.saturating_sub(retained_balance);
+        if distributed_amount < self.config.min_sweep_amount.into() {
+            distributed_amount = U256::zero();
+        }
+        let amounts = split_amount(distributed_amount, &self.shares);
+        if !pays_rebates && amounts.iter().all(U256::is_zero) {
+            tracing::debug!(
+                "Postponing fee sweep: fee account balance at miniblock #{} is {balance} wei, \
+                 {retained_balance} wei should be retained",
+                miniblock.number
+            );
+            return Ok(None);
+        }
+
+        let mut nonce = self.load_next_nonce(storage).await?;
+        // Transfers are persisted before they are submitted, so that the history contains all transfers
+        // sent from the fee account even if the distributor crashes in between.
+        let mut signed_txs = vec![];
+        let mut transaction = storage.start_transaction().await?;
+        if pays_rebates {
+            for rebate in &rebates {
+                let tx = self
+                    .record_rebate(
+                        &mut transaction,
+                        rebate,
+                        miniblock.number,
+                        nonce,
+                        fee.clone(),
+                    )
+                    .await?;
+                signed_txs.push(tx);
+                nonce += 1;
+            }
+            tracing::info!(
+                "Signed {} fee rebate transfers for miniblocks up to #{}",
+                rebates.len(),
+                miniblock.number
+            );
+        }
+
+        let mut transfers = Vec::with_capacity(self.shares.len());
+        for (share, amount) in self.shares.iter().zip(amounts) {
+            if amount.is_zero() {
+                continue;
+            }
+            let tx = self
+                .sign_transfer(share.recipient, amount, nonce, fee.clone())
+                .with_context(|| {
+                    format!("failed signing transfer to {}", share.destination.as_str())
+                })?;
+            nonce += 1;
+            transfers.push(NewFeeTransfer {
+                destination: share.destination,
+                recipient: share.recipient,
+                share_bps: share.share_bps,
+                amount,
+                tx_hash: tx.hash(),
+            });
+            signed_txs.push(tx);
+        }
+        let sweep_id = transaction
+            .fee_distribution_dal()
+            .insert_sweep(self.fee_account, miniblock.number, balance, &transfers)
+            .await?;
+        transaction.commit().await?;
+        self.submit_transfers(signed_txs).await;
+
+        tracing::info!(
+            "Submitted fee sweep #{sweep_id} distributing {distributed_amount} wei from fee account balance \
+             {balance} wei at miniblock #{}: {transfers:?}",
+            miniblock.number
+        );
+        METRICS.sweeps.inc();
+        METRICS.last_swept_miniblock.set(miniblock.number.0.into());
+        Ok(Some(sweep_id))
+    }
+
+    /// Signs a rebate transfer and assigns it to the paid out rebates.
+    async fn record_rebate(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        rebate: &UnpaidFeeRebate,
+        up_to_miniblock: MiniblockNumber,
+        nonce: Nonce,
+        fee: Fee,
+    ) -> anyhow::Result<L2Tx> {
+        let tx = self
+            .sign_transfer(rebate.account, rebate.amount, nonce, fee)
+            .with_context(|| format!("failed signing rebate transfer to {:?}", rebate.account))?;
+        let tx_hash = tx.hash();
+        storage
+            .fee_discounts_dal()
+            .assign_rebate_transfer(rebate.account, up_to_miniblock, tx_hash)
+            .await?;
+        tracing::debug!(
+            "Signed rebate transfer of {} wei to {:?} ({tx_hash:?})",
+            rebate.amount,
+            rebate.account
+        );
+        METRICS.rebate_transfers.inc();
+        Ok(tx)
+    }
+
+    /// Submits signed transfers in the nonce order. Submission stops on the first rejected transfer, since
+    /// the following transfers would have a nonce gap; transfers that weren't submitted are reported as dropped.
+    async fn submit_transfers(&self, txs: Vec<L2Tx>) {
+        let tx_count = txs.len();
+        for (i, tx) in txs.into_iter().enumerate() {
+            let tx_hash = tx.hash();
+            if let Err(err) = self.tx_sender.submit_tx(tx).await {
+                tracing::error!(
+                    "Transfer {tx_hash:?} from fee account was rejected: {err}; {} following transfers \
+                     are not submitted",
+                    tx_count - i - 1
+                );
+                METRICS.rejected_transfers.inc();
+                break;
+            }
+        }
+    }
+
+    fn sign_transfer(
+        &self,
+        recipient: Address,
+        amount: U256,
+        nonce: Nonce,
+        fee: Fee,
+    ) -> anyhow::Result<L2Tx> {
+        let mut tx = L2Tx::new_signed(
+            recipient,
+            vec![],
+            nonce,
+            fee,
+            amount,
+            self.chain_id,
+            &self.private_key,
+            None,
+            PaymasterParams::default(),
+        )
+        .context("failed signing transaction")?;
+
+        let request = api::TransactionRequest::from(tx.clone());
+        let signature = PackedEthSignature::deserialize_packed(&tx.common_data.signature)
+            .context("invalid transaction signature")?;
+        let raw_bytes = request.get_signed_bytes(&signature, self.chain_id);
+        let tx_hash = request
+            .get_tx_hash(self.chain_id)
+            .context("failed computing transaction hash")?;
+        tx.set_input(raw_bytes, tx_hash);
+        Ok(tx)
+    }
+
+    async fn load_stored_nonce(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<Nonce> {
+        let stored_nonces = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&[self.fee_account])
+            .await
+            .context("failed loading stored nonce for fee account")?;
+        Ok(stored_nonces
+            .get(&self.fee_account)
+            .copied()
+            .unwrap_or(Nonce(0)))
+    }
+
+    /// Loads the next nonce for the fee account. Unlike other transaction injectors, the distributor cannot
+    /// cache the nonce since the fee account may be used to send other transactions.
+    async fn load_next_nonce(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<Nonce> {
+        let stored_nonce = self.load_stored_nonce(storage).await?;
+        let next_nonce = storage
+            .transactions_web3_dal()
+            .next_nonce_by_initiator_account(self.fee_account, stored_nonce.0.into())
+            .await
+            .context("failed loading pending nonce for fee account")?;
+        Ok(Nonce(next_nonce.as_u32()))
+    }
+}
@@ -0,0 +1,430 @@
+//! Tests for the fee distributor.
+
+use assert_matches::assert_matches;
+use multivm::interface::ExecutionResult;
+use zksync_types::{
+    block::MiniblockHeader, fee::TransactionExecutionMetrics, get_nonce_key,
+    utils::storage_key_for_eth_balance, MiniblockNumber, StorageLog,
+};
+use zksync_utils::u256_to_h256;
+
+use super::*;
+use crate::{
+    api_server::{
+        execution_sandbox::testonly::MockTransactionExecutor,
+        tx_sender::tests::create_test_tx_sender,
+    },
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l2_transaction, create_miniblock, execute_l2_transaction},
+};
+
+const PRIVATE_KEY: H256 = H256::repeat_byte(0x11);
+const TREASURY: Address = Address::repeat_byte(0x71);
+const SEQUENCER: Address = Address::repeat_byte(0x72);
+/// Base fee of the test miniblock. Should exceed the fee floor enforced by the transaction sender.
+const BASE_FEE_PER_GAS: u64 = 100_000_000;
+
+fn fee_account() -> Address {
+    PackedEthSignature::address_from_private_key(&PRIVATE_KEY).unwrap()
+}
+
+fn test_config() -> FeeDistributorConfig {
+    FeeDistributorConfig {
+        polling_interval_ms: 10,
+        sweep_interval_sec: 0,
+        min_balance: 1_000,
+        min_sweep_amount: 1_000,
+        gas_limit_per_transfer: 1_000_000,
+        treasury_addr: Some(TREASURY),
+        treasury_share_bps: 6_000,
+        sequencer_addr: Some(SEQUENCER),
+        sequencer_share_bps: 3_000,
+        burn_addr: None,
+        burn_share_bps: 1_000,
+    }
+}
+
+async fn create_tx_sender(pool: &ConnectionPool) -> TxSender {
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_tx_responses(|_, _| ExecutionResult::Success { output: vec![] });
+    let (tx_sender, _) =
+        create_test_tx_sender(pool.clone(), L2ChainId::default(), tx_executor.into()).await;
+    tx_sender
+}
+
+async fn create_distributor(pool: &ConnectionPool, config: FeeDistributorConfig) -> FeeDistributor {
+    let tx_sender = create_tx_sender(pool).await;
+    FeeDistributor::new(
+        pool.clone(),
+        tx_sender,
+        config,
+        PRIVATE_KEY,
+        fee_account(),
+        L2ChainId::default(),
+    )
+    .unwrap()
+}
+
+/// Returns the cost of a single transfer paid by the distributor, which overpays the base fee by 50%.
+fn transfer_fee(config: &FeeDistributorConfig) -> U256 {
+    U256::from(config.gas_limit_per_transfer * BASE_FEE_PER_GAS / 2 * 3)
+}
+
+async fn transfer_nonces(storage: &mut StorageProcessor<'_>, sweep: &FeeSweep) -> Vec<U256> {
+    let mut nonces = vec![];
+    for transfer in &sweep.transfers {
+        let tx = storage
+            .transactions_web3_dal()
+            .get_transaction(
+                api::TransactionId::Hash(transfer.transfer.tx_hash),
+                L2ChainId::default(),
+            )
+            .await
+            .unwrap()
+            .expect("transfer transaction is not persisted");
+        assert_eq!(tx.from, Some(fee_account()));
+        assert_eq!(tx.to, Some(transfer.transfer.recipient));
+        assert_eq!(tx.value, transfer.transfer.amount);
+        nonces.push(tx.nonce);
+    }
+    nonces.sort_unstable();
+    nonces
+}
+
+async fn prepare_storage(storage: &mut StorageProcessor<'_>, fee_account_balance: U256) {
+    ensure_genesis_state(storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let miniblock = MiniblockHeader {
+        base_fee_per_gas: BASE_FEE_PER_GAS,
+        ..create_miniblock(1)
+    };
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+    let balance_log = StorageLog::new_write_log(
+        storage_key_for_eth_balance(&fee_account()),
+        u256_to_h256(fee_account_balance),
+    );
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), vec![balance_log])])
+        .await
+        .unwrap();
+}
+
+#[test]
+fn destination_shares_are_validated() {
+    let shares = DestinationShare::from_config(&test_config()).unwrap();
+    let destinations: Vec<_> = shares.iter().map(|share| share.destination).collect();
+    assert_eq!(
+        destinations,
+        [
+            FeeDestination::Treasury,
+            FeeDestination::Sequencer,
+            FeeDestination::Burn
+        ]
+    );
+    assert_eq!(shares[2].recipient, FeeDistributorConfig::DEFAULT_BURN_ADDR);
+
+    let config = FeeDistributorConfig {
+        sequencer_addr: None,
+        sequencer_share_bps: 0,
+        burn_share_bps: 4_000,
+        ..test_config()
+    };
+    let shares = DestinationShare::from_config(&config).unwrap();
+    assert_eq!(shares.len(), 2);
+    assert_eq!(shares[1].share_bps, 4_000);
+
+    let config = FeeDistributorConfig {
+        burn_share_bps: 2_000,
+        ..test_config()
+    };
+    let err = DestinationShare::from_config(&config).unwrap_err();
+    assert!(err.to_string().contains("add up"), "{err}");
+
+    let config = FeeDistributorConfig {
+        treasury_addr: None,
+        ..test_config()
+    };
+    let err = DestinationShare::from_config(&config).unwrap_err();
+    assert!(err.to_string().contains("treasury"), "{err}");
+}
+
+#[test]
+fn splitting_amount() {
+    let shares = DestinationShare::from_config(&test_config()).unwrap();
+    let amounts = split_amount(1_000_000.into(), &shares);
+    assert_eq!(amounts, [600_000.into(), 300_000.into(), 100_000.into()]);
+
+    // Rounding dust is not distributed.
+    let amounts = split_amount(19.into(), &shares);
+    assert_eq!(amounts, [11.into(), 5.into(), 1.into()]);
+}
+
+#[tokio::test]
+async fn fee_account_must_match_private_key() {
+    let pool = ConnectionPool::test_pool().await;
+    let tx_sender = create_tx_sender(&pool).await;
+    let err = FeeDistributor::new(
+        pool,
+        tx_sender,
+        test_config(),
+        PRIVATE_KEY,
+        Address::repeat_byte(1),
+        L2ChainId::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("fee account"), "{err}");
+}
+
+#[tokio::test]
+async fn sweeping_fees() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let balance = U256::from(10_u64.pow(18));
+    prepare_storage(&mut storage, balance).await;
+
+    let config = test_config();
+    let mut distributor = create_distributor(&pool, config.clone()).await;
+    let sweep_id = distributor.loop_iteration().await.unwrap().unwrap();
+
+    let sweeps = storage
+        .fee_distribution_dal()
+        .get_latest_sweeps(10)
+        .await
+        .unwrap();
+    assert_eq!(sweeps.len(), 1);
+    let sweep = &sweeps[0];
+    assert_eq!(sweep.id, sweep_id);
+    assert_eq!(sweep.fee_account, fee_account());
+    assert_eq!(sweep.miniblock_number, MiniblockNumber(1));
+    assert_eq!(sweep.balance, balance);
+
+    let transfer_fee = transfer_fee(&config);
+    let distributed_amount = balance - transfer_fee * 3 - U256::from(config.min_balance);
+    let expected_amounts = [
+        (FeeDestination::Burn, distributed_amount / 10),
+        (FeeDestination::Sequencer, distributed_amount * 3 / 10),
+        (FeeDestination::Treasury, distributed_amount * 6 / 10),
+    ];
+    assert_eq!(sweep.transfers.len(), expected_amounts.len());
+    for (transfer, (destination, amount)) in sweep.transfers.iter().zip(expected_amounts) {
+        assert_eq!(transfer.transfer.destination, destination);
+        assert_eq!(transfer.transfer.amount, amount);
+        assert_eq!(transfer.status, FeeTransferStatus::Pending);
+    }
+    let expected_nonces = [U256::zero(), U256::one(), U256::from(2)];
+    assert_eq!(transfer_nonces(&mut storage, sweep).await, expected_nonces);
+
+    // No new sweeps should be submitted while transfers are pending.
+    assert_eq!(distributor.loop_iteration().await.unwrap(), None);
+
+    for transfer in &sweep.transfers {
+        storage
+            .transactions_dal()
+            .mark_tx_as_rejected(transfer.transfer.tx_hash, "rejected: test")
+            .await;
+    }
+    let new_sweep_id = distributor.loop_iteration().await.unwrap().unwrap();
+    assert!(new_sweep_id > sweep_id);
+    let sweeps = storage
+        .fee_distribution_dal()
+        .get_latest_sweeps(10)
+        .await
+        .unwrap();
+    assert_eq!(sweeps.len(), 2);
+    assert_eq!(sweeps[0].id, new_sweep_id);
+    for transfer in &sweeps[1].transfers {
+        assert_matches!(transfer.status, FeeTransferStatus::Failed(_));
+    }
+    // Nonces of the rejected transactions should be reused.
+    assert_eq!(
+        transfer_nonces(&mut storage, &sweeps[0]).await,
+        expected_nonces
+    );
+}
+
+#[tokio::test]
+async fn paying_out_fee_rebates() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let balance = U256::from(10_u64.pow(18));
+    prepare_storage(&mut storage, balance).await;
+    // The transaction pays `1_000 * 20 = 20_000` wei in fees, so the rebate is 10_000 wei.
+    let discounted_tx = create_l2_transaction(20, 50);
+    let partner = discounted_tx.initiator_account();
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(
+            discounted_tx.clone(),
+            TransactionExecutionMetrics::default(),
+        )
+        .await;
+    storage
+        .fee_discounts_dal()
+        .insert_discounted_tx(discounted_tx.hash(), partner, 5_000)
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(
+            MiniblockNumber(1),
+            &[execute_l2_transaction(discounted_tx)],
+            20.into(),
+        )
+        .await;
+
+    let config = test_config();
+    let mut distributor = create_distributor(&pool, config.clone()).await;
+    distributor.loop_iteration().await.unwrap().unwrap();
+    assert!(storage
+        .fee_discounts_dal()
+        .get_unpaid_rebates(MiniblockNumber(1))
+        .await
+        .unwrap()
+        .is_empty());
+
+    let sweep = storage
+        .fee_distribution_dal()
+        .get_latest_sweeps(1)
+        .await
+        .unwrap()
+        .pop()
+        .unwrap();
+    // The rebate is paid out before the distribution, so sweep transfers use subsequent nonces.
+    let expected_nonces = [U256::one(), U256::from(2), U256::from(3)];
+    assert_eq!(transfer_nonces(&mut storage, &sweep).await, expected_nonces);
+    let transfer_fee = transfer_fee(&config);
+    let distributed_amount =
+        balance - transfer_fee * 4 - U256::from(config.min_balance) - U256::from(10_000);
+    let distributed_total = sweep
+        .transfers
+        .iter()
+        .fold(U256::zero(), |acc, transfer| acc + transfer.transfer.amount);
+    assert_eq!(distributed_total, distributed_amount);
+
+    // No new sweeps should be submitted while the rebate transfer is pending, even if sweep transfers are processed.
+    for transfer in &sweep.transfers {
+        storage
+            .transactions_dal()
+            .mark_tx_as_rejected(transfer.transfer.tx_hash, "rejected: test")
+            .await;
+    }
+    assert_eq!(distributor.loop_iteration().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn sweeps_are_postponed_for_low_balance() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let config = test_config();
+    // Only covers transfer fees and the minimum balance.
+    let balance = transfer_fee(&config) * 3 + U256::from(config.min_balance);
+    prepare_storage(&mut storage, balance).await;
+
+    let mut distributor = create_distributor(&pool, config).await;
+    assert_eq!(distributor.loop_iteration().await.unwrap(), None);
+    assert!(storage
+        .fee_distribution_dal()
+        .get_latest_sweeps(10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn sweeps_respect_interval() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, 10_u64.pow(18).into()).await;
+
+    let config = FeeDistributorConfig {
+        sweep_interval_sec: 3_600,
+        ..test_config()
+    };
+    let mut distributor = create_distributor(&pool, config).await;
+    let sweep_id = distributor.loop_iteration().await.unwrap().unwrap();
+
+    let sweep = storage
+        .fee_distribution_dal()
+        .get_latest_sweeps(1)
+        .await
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(sweep.id, sweep_id);
+    for transfer in &sweep.transfers {
+        storage
+            .transactions_dal()
+            .mark_tx_as_rejected(transfer.transfer.tx_hash, "rejected: test")
+            .await;
+    }
+    // All transfers are processed, but the sweep interval hasn't elapsed yet.
+    assert_eq!(distributor.loop_iteration().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn transfers_with_used_nonces_are_failed() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, 10_u64.pow(18).into()).await;
+    let mut distributor = create_distributor(&pool, test_config()).await;
+    let sweep_id = distributor.loop_iteration().await.unwrap().unwrap();
+
+    // Emulate the fee account nonces used by other transactions.
+    let nonce_log =
+        StorageLog::new_write_log(get_nonce_key(&fee_account()), H256::from_low_u64_be(3));
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), vec![nonce_log])])
+        .await
+        .unwrap();
+
+    let new_sweep_id = distributor.loop_iteration().await.unwrap().unwrap();
+    assert!(new_sweep_id > sweep_id);
+    let sweeps = storage
+        .fee_distribution_dal()
+        .get_latest_sweeps(2)
+        .await
+        .unwrap();
+    for transfer in &sweeps[1].transfers {
+        assert_matches!(&transfer.status, FeeTransferStatus::Failed(err) if err.contains("nonce"));
+    }
+    let expected_nonces = [U256::from(3), U256::from(4), U256::from(5)];
+    assert_eq!(
+        transfer_nonces(&mut storage, &sweeps[0]).await,
+        expected_nonces
+    );
+}
+
+#[tokio::test]
+async fn rejected_transfers_are_dropped() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, 10_u64.pow(18).into()).await;
+    storage
+        .tx_intake_dal()
+        .pause_intake("maintenance")
+        .await
+        .unwrap()
+        .expect("intake is already paused");
+
+    let mut distributor = create_distributor(&pool, test_config()).await;
+    let sweep_id = distributor.loop_iteration().await.unwrap().unwrap();
+    let sweep = storage
+        .fee_distribution_dal()
+        .get_latest_sweeps(1)
+        .await
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(sweep.id, sweep_id);
+    assert_eq!(sweep.transfers.len(), 3);
+    for transfer in &sweep.transfers {
+        assert_eq!(transfer.status, FeeTransferStatus::Dropped);
+    }
+}
@@ -1,16 +1,27 @@
-use std::{fmt, sync::Arc};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
+use anyhow::Context as _;
+use tokio::sync::watch;
 use zksync_dal::ConnectionPool;
 use zksync_types::{
     fee_model::{
-        BatchFeeInput, FeeModelConfig, FeeModelConfigV2, FeeParams, FeeParamsV1, FeeParamsV2,
-        L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput,
+        BatchFeeInput, FeeModelConfig, FeeModelConfigV2, FeeModelConfigV3, FeeParams, FeeParamsV1,
+        FeeParamsV2, FeeParamsV3, L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput,
     },
     U256,
 };
 use zksync_utils::ceil_div_u256;
 
-use crate::l1_gas_price::GasAdjuster;
+use crate::{
+    config_reload::ReloadableConfig, l1_gas_price::GasAdjuster, stable_gas_price::StableGasPrice,
+};
 
 /// Trait responsible for providing fee info for a batch
 #[async_trait::async_trait]
@@ -22,21 +33,11 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
         l1_gas_price_scale_factor: f64,
         l1_pubdata_price_scale_factor: f64,
     ) -> BatchFeeInput {
-        let params = self.get_fee_model_params();
-
-        match params {
-            FeeParams::V1(params) => BatchFeeInput::L1Pegged(compute_batch_fee_model_input_v1(
-                params,
-                l1_gas_price_scale_factor,
-            )),
-            FeeParams::V2(params) => {
-                BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
-                    params,
-                    l1_gas_price_scale_factor,
-                    l1_pubdata_price_scale_factor,
-                ))
-            }
-        }
+        compute_batch_fee_input(
+            self.get_fee_model_params(),
+            l1_gas_price_scale_factor,
+            l1_pubdata_price_scale_factor,
+        )
     }
 
     /// Returns the batch fee input as-is, i.e. without any scaling for the L1 gas and pubdata prices.
@@ -55,11 +56,39 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
 pub struct MainNodeFeeInputProvider {
     provider: Arc<GasAdjuster>,
     config: FeeModelConfig,
+    congestion_multiplier: CongestionMultiplier,
+    stable_gas_price: StableGasPrice,
+    reloadable_config: Option<watch::Receiver<ReloadableConfig>>,
 }
 
+#[async_trait::async_trait]
 impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
+    async fn get_batch_fee_input_scaled(
+        &self,
+        l1_gas_price_scale_factor: f64,
+        l1_pubdata_price_scale_factor: f64,
+    ) -> BatchFeeInput {
+        let params = self.get_fee_model_params();
+        let input = compute_batch_fee_input(
+            params,
+            l1_gas_price_scale_factor,
+            l1_pubdata_price_scale_factor,
+        );
+        match self.stable_gas_price.get() {
+            Some(stable_gas_price) => apply_stable_gas_price(params, input, stable_gas_price),
+            None => input,
+        }
+    }
+
     fn get_fee_model_params(&self) -> FeeParams {
-        match self.config {
+        let mut config = self.config;
+        if let Some(reloadable_config) = &self.reloadable_config {
+            if let Some(params) = &reloadable_config.borrow().state_keeper {
+                config.set_minimal_l2_gas_price(params.minimal_l2_gas_price);
+            }
+        }
+
+        match config {
             FeeModelConfig::V1(config) => FeeParams::V1(FeeParamsV1 {
                 config,
                 l1_gas_price: self.provider.estimate_effective_gas_price(),
@@ -69,13 +98,126 @@ impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
                 l1_gas_price: self.provider.estimate_effective_gas_price(),
                 l1_pubdata_price: self.provider.estimate_effective_pubdata_price(),
             }),
+            FeeModelConfig::V3(config) => FeeParams::V3(FeeParamsV3 {
+                config,
+                l1_gas_price: self.provider.estimate_effective_gas_price(),
+                l1_pubdata_price: self.provider.estimate_effective_pubdata_price(),
+                congestion_multiplier: self.congestion_multiplier.get(),
+            }),
         }
     }
 }
 
 impl MainNodeFeeInputProvider {
     pub fn new(provider: Arc<GasAdjuster>, config: FeeModelConfig) -> Self {
-        Self { provider, config }
+        Self {
+            provider,
+            config,
+            congestion_multiplier: CongestionMultiplier::default(),
+            stable_gas_price: StableGasPrice::default(),
+            reloadable_config: None,
+        }
+    }
+
+    /// Sets the congestion multiplier used by the `V3` fee model. If not set, the multiplier is always 1,
+    /// i.e. the `V3` fee model is equivalent to `V2`.
+    pub fn with_congestion_multiplier(mut self, multiplier: CongestionMultiplier) -> Self {
+        self.congestion_multiplier = multiplier;
+        self
+    }
+
+    /// Sets the L2 gas price for the stable pricing mode. If the price is set, it replaces the fair L2 gas price
+    /// computed by the fee model; for the `V3` fee model, the congestion multiplier is still applied on top of it.
+    pub fn with_stable_gas_price(mut self, price: StableGasPrice) -> Self {
+        self.stable_gas_price = price;
+        self
+    }
+
+    /// Makes the minimal L2 gas price follow the value changed at runtime, instead of the one from the startup config.
+    pub fn with_reloadable_config(mut self, config: watch::Receiver<ReloadableConfig>) -> Self {
+        self.reloadable_config = Some(config);
+        self
+    }
+}
+
+/// Congestion multiplier for the `V3` fee model. Cloned instances share the value, so that it can be used
+/// by multiple fee input providers and updated by a single [`CongestionMultiplierUpdater`].
+#[derive(Debug, Clone)]
+pub struct CongestionMultiplier(Arc<AtomicU64>);
+
+impl Default for CongestionMultiplier {
+    fn default() -> Self {
+        Self(Arc::new(AtomicU64::new(1.0_f64.to_bits())))
+    }
+}
+
+impl CongestionMultiplier {
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Periodically recomputes the [`CongestionMultiplier`] based on the fullness of the latest sealed L1 batches.
+#[derive(Debug)]
+pub struct CongestionMultiplierUpdater {
+    pool: ConnectionPool,
+    config: FeeModelConfigV3,
+    multiplier: CongestionMultiplier,
+}
+
+impl CongestionMultiplierUpdater {
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new(pool: ConnectionPool, config: FeeModelConfigV3) -> Self {
+        Self {
+            pool,
+            config,
+            multiplier: CongestionMultiplier::default(),
+        }
+    }
+
+    /// Returns the multiplier updated by this updater.
+    pub fn multiplier(&self) -> CongestionMultiplier {
+        self.multiplier.clone()
+    }
+
+    async fn update(&self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("congestion_multiplier_updater")
+            .await?;
+        let gas_used = storage
+            .blocks_dal()
+            .get_gas_used_in_latest_l1_batches(self.config.window_size)
+            .await
+            .context("get_gas_used_in_latest_l1_batches()")?;
+        drop(storage);
+
+        let max_gas_per_batch = self.config.base.max_gas_per_batch as f64;
+        let batch_fullness = gas_used
+            .into_iter()
+            .map(|(_, gas_used)| gas_used as f64 / max_gas_per_batch);
+        self.multiplier
+            .set(self.config.congestion_multiplier(batch_fullness));
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            self.update().await?;
+            if tokio::time::timeout(Self::POLL_INTERVAL, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, congestion multiplier updater is shutting down");
+        Ok(())
     }
 }
 
@@ -131,6 +273,63 @@ impl BatchFeeModelInputProvider for ApiFeeInputProvider {
     }
 }
 
+/// Calculates the batch fee input for the specified fee model parameters.
+fn compute_batch_fee_input(
+    params: FeeParams,
+    l1_gas_price_scale_factor: f64,
+    l1_pubdata_price_scale_factor: f64,
+) -> BatchFeeInput {
+    match params {
+        FeeParams::V1(params) => BatchFeeInput::L1Pegged(compute_batch_fee_model_input_v1(
+            params,
+            l1_gas_price_scale_factor,
+        )),
+        FeeParams::V2(params) => {
+            BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
+                params,
+                l1_gas_price_scale_factor,
+                l1_pubdata_price_scale_factor,
+            ))
+        }
+        FeeParams::V3(params) => {
+            BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v3(
+                params,
+                l1_gas_price_scale_factor,
+                l1_pubdata_price_scale_factor,
+            ))
+        }
+    }
+}
+
+/// Replaces the fair L2 gas price in the batch fee input with the stable gas price. For the `V3` fee model,
+/// the congestion multiplier is applied to the stable price.
+fn apply_stable_gas_price(
+    params: FeeParams,
+    input: BatchFeeInput,
+    stable_gas_price: u64,
+) -> BatchFeeInput {
+    let congestion_multiplier = match params {
+        FeeParams::V3(params) => params
+            .congestion_multiplier
+            .min(params.config.max_congestion_multiplier)
+            .max(1.0),
+        FeeParams::V1(_) | FeeParams::V2(_) => 1.0,
+    };
+    let fair_l2_gas_price = (stable_gas_price as f64 * congestion_multiplier) as u64;
+    match input {
+        BatchFeeInput::L1Pegged(input) => BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
+            fair_l2_gas_price,
+            ..input
+        }),
+        BatchFeeInput::PubdataIndependent(input) => {
+            BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+                fair_l2_gas_price,
+                ..input
+            })
+        }
+    }
+}
+
 /// Calculates the batch fee input based on the main node parameters.
 /// This function uses the `V1` fee model, i.e. where the pubdata price does not include the proving costs.
 fn compute_batch_fee_model_input_v1(
@@ -212,8 +411,41 @@ fn compute_batch_fee_model_input_v2(
     }
 }
 
+/// Calculates the batch fee input based on the main node parameters.
+/// This function uses the `V3` fee model, i.e. the `V2` model where the fair L2 gas price is multiplied by the congestion multiplier.
+fn compute_batch_fee_model_input_v3(
+    params: FeeParamsV3,
+    l1_gas_price_scale_factor: f64,
+    l1_pubdata_price_scale_factor: f64,
+) -> PubdataIndependentBatchFeeModelInput {
+    let FeeParamsV3 {
+        config,
+        l1_gas_price,
+        l1_pubdata_price,
+        congestion_multiplier,
+    } = params;
+
+    let mut input = compute_batch_fee_model_input_v2(
+        FeeParamsV2 {
+            config: config.base,
+            l1_gas_price,
+            l1_pubdata_price,
+        },
+        l1_gas_price_scale_factor,
+        l1_pubdata_price_scale_factor,
+    );
+    // The multiplier may be provided by the main node, so we don't trust it to be within bounds.
+    let congestion_multiplier = congestion_multiplier
+        .min(config.max_congestion_multiplier)
+        .max(1.0);
+    input.fair_l2_gas_price = (input.fair_l2_gas_price as f64 * congestion_multiplier) as u64;
+    input
+}
+
 #[cfg(test)]
 mod tests {
+    use zksync_types::fee_model::FeeModelConfigV1;
+
     use super::*;
 
     // To test that overflow never happens, we'll use giant L1 gas price, i.e.
@@ -437,4 +669,141 @@ mod tests {
             "Max pubdata increase lowers pubdata price"
         );
     }
+
+    fn mock_config_v3() -> FeeModelConfigV3 {
+        FeeModelConfigV3 {
+            base: FeeModelConfigV2 {
+                minimal_l2_gas_price: 100_000_000_000,
+                compute_overhead_part: 0.5,
+                pubdata_overhead_part: 0.5,
+                batch_overhead_l1_gas: 700_000,
+                max_gas_per_batch: 500_000_000,
+                max_pubdata_per_batch: 100_000,
+            },
+            target_batch_fullness: 0.5,
+            max_change_per_batch: 0.125,
+            max_congestion_multiplier: 2.0,
+            window_size: 32,
+        }
+    }
+
+    #[test]
+    fn test_congestion_multiplier_curve() {
+        let config = mock_config_v3();
+        assert_eq!(config.congestion_multiplier([]), 1.0);
+        assert_eq!(config.congestion_multiplier([0.5, 0.5]), 1.0);
+        assert_eq!(config.congestion_multiplier([1.0]), 1.125);
+        assert_eq!(config.congestion_multiplier([0.75]), 1.0625);
+        // The multiplier never goes below 1.
+        assert_eq!(config.congestion_multiplier([0.0, 0.0]), 1.0);
+        assert_eq!(config.congestion_multiplier([1.0, 0.0]), 1.125 * 0.875);
+        // Fullness is clamped to `[0, 1]`.
+        assert_eq!(config.congestion_multiplier([3.0]), 1.125);
+        // The multiplier is capped.
+        assert_eq!(config.congestion_multiplier([1.0; 10]), 2.0);
+        assert_eq!(
+            config.congestion_multiplier([1.0; 10].into_iter().chain([0.0])),
+            1.75
+        );
+    }
+
+    #[test]
+    fn test_compute_batch_fee_model_input_v3() {
+        let config = mock_config_v3();
+        let params = FeeParamsV3 {
+            config,
+            l1_gas_price: 1_000_000_000,
+            l1_pubdata_price: 1_000_000_000,
+            congestion_multiplier: 1.0,
+        };
+        let base_input = compute_batch_fee_model_input_v2(
+            FeeParamsV2 {
+                config: config.base,
+                l1_gas_price: params.l1_gas_price,
+                l1_pubdata_price: params.l1_pubdata_price,
+            },
+            1.0,
+            1.0,
+        );
+        assert_eq!(
+            compute_batch_fee_model_input_v3(params, 1.0, 1.0),
+            base_input
+        );
+
+        let congested_input = compute_batch_fee_model_input_v3(
+            FeeParamsV3 {
+                congestion_multiplier: 1.5,
+                ..params
+            },
+            1.0,
+            1.0,
+        );
+        assert_eq!(
+            congested_input.fair_l2_gas_price,
+            base_input.fair_l2_gas_price * 3 / 2
+        );
+        assert_eq!(congested_input.l1_gas_price, base_input.l1_gas_price);
+        assert_eq!(
+            congested_input.fair_pubdata_price,
+            base_input.fair_pubdata_price
+        );
+
+        // Out-of-bounds multipliers are clamped.
+        let input = compute_batch_fee_model_input_v3(
+            FeeParamsV3 {
+                congestion_multiplier: 100.0,
+                ..params
+            },
+            1.0,
+            1.0,
+        );
+        assert_eq!(input.fair_l2_gas_price, base_input.fair_l2_gas_price * 2);
+        let input = compute_batch_fee_model_input_v3(
+            FeeParamsV3 {
+                congestion_multiplier: 0.1,
+                ..params
+            },
+            1.0,
+            1.0,
+        );
+        assert_eq!(input, base_input);
+    }
+
+    #[test]
+    fn test_applying_stable_gas_price() {
+        let params = FeeParamsV3 {
+            config: mock_config_v3(),
+            l1_gas_price: 1_000_000_000,
+            l1_pubdata_price: 1_000_000_000,
+            congestion_multiplier: 1.0,
+        };
+        let input = compute_batch_fee_input(FeeParams::V3(params), 1.0, 1.0);
+        let stable_input = apply_stable_gas_price(FeeParams::V3(params), input, 10_000_000)
+            .into_pubdata_independent();
+        assert_eq!(stable_input.fair_l2_gas_price, 10_000_000);
+        assert_eq!(stable_input.l1_gas_price, input.l1_gas_price());
+        assert_eq!(stable_input.fair_pubdata_price, input.fair_pubdata_price());
+
+        // The congestion multiplier is applied on top of the stable price.
+        let congested_params = FeeParams::V3(FeeParamsV3 {
+            congestion_multiplier: 1.5,
+            ..params
+        });
+        let input = compute_batch_fee_input(congested_params, 1.0, 1.0);
+        let stable_input = apply_stable_gas_price(congested_params, input, 10_000_000);
+        assert_eq!(stable_input.fair_l2_gas_price(), 15_000_000);
+
+        let params_v1 = FeeParams::V1(FeeParamsV1 {
+            config: FeeModelConfigV1 {
+                minimal_l2_gas_price: 100_000_000_000,
+            },
+            l1_gas_price: 1_000_000_000,
+        });
+        let input = compute_batch_fee_input(params_v1, 1.0, 1.0);
+        let stable_input = apply_stable_gas_price(params_v1, input, 10_000_000);
+        assert_eq!(
+            stable_input,
+            BatchFeeInput::l1_pegged(1_000_000_000, 10_000_000)
+        );
+    }
 }
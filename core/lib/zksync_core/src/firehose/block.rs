@@ -0,0 +1,370 @@
+//! Conversion of miniblocks to Firehose blocks.
+
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::Context as _;
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    api,
+    utils::storage_key_for_eth_balance,
+    vm_trace::{Call, CallType},
+    zk_evm_types::FarCallOpcode,
+    Address, L2ChainId, MiniblockNumber, H256, L2_ETH_TOKEN_ADDRESS, U256,
+};
+use zksync_utils::{h256_to_account_address, h256_to_u256};
+
+use super::proto::ethereum as eth;
+
+/// Version of the block model reported in [`eth::Block::ver`].
+const BLOCK_MODEL_VERSION: i32 = 3;
+
+/// Source of ordinals, which order transactions, calls, logs and balance changes within a block
+/// in the execution order.
+#[derive(Debug, Default)]
+struct Ordinals(u64);
+
+impl Ordinals {
+    fn next(&mut self) -> u64 {
+        self.0 += 1;
+        self.0
+    }
+}
+
+fn big_int(value: U256) -> Option<eth::BigInt> {
+    let mut bytes = [0_u8; 32];
+    value.to_big_endian(&mut bytes);
+    let first_nonzero = bytes
+        .iter()
+        .position(|&byte| byte != 0)
+        .unwrap_or(bytes.len());
+    Some(eth::BigInt {
+        bytes: bytes[first_nonzero..].to_vec(),
+    })
+}
+
+fn timestamp(seconds: U256) -> Option<prost_types::Timestamp> {
+    Some(prost_types::Timestamp {
+        seconds: seconds.as_u64() as i64,
+        nanos: 0,
+    })
+}
+
+fn convert_header(block: &api::Block<api::TransactionVariant>) -> eth::BlockHeader {
+    eth::BlockHeader {
+        parent_hash: block.parent_hash.as_bytes().to_vec(),
+        uncle_hash: block.uncles_hash.as_bytes().to_vec(),
+        coinbase: block.author.as_bytes().to_vec(),
+        state_root: block.state_root.as_bytes().to_vec(),
+        transactions_root: block.transactions_root.as_bytes().to_vec(),
+        receipt_root: block.receipts_root.as_bytes().to_vec(),
+        logs_bloom: block.logs_bloom.as_bytes().to_vec(),
+        number: block.number.as_u64(),
+        gas_limit: block.gas_limit.low_u64(),
+        gas_used: block.gas_used.low_u64(),
+        timestamp: timestamp(block.timestamp),
+        extra_data: block.extra_data.0.clone(),
+        hash: block.hash.as_bytes().to_vec(),
+        base_fee_per_gas: big_int(block.base_fee_per_gas),
+    }
+}
+
+fn convert_log(log: &api::Log, ordinal: u64) -> eth::Log {
+    eth::Log {
+        address: log.address.as_bytes().to_vec(),
+        topics: log
+            .topics
+            .iter()
+            .map(|topic| topic.as_bytes().to_vec())
+            .collect(),
+        data: log.data.0.clone(),
+        index: log.transaction_log_index.map_or(0, |idx| idx.as_u32()),
+        block_index: log.log_index.map_or(0, |idx| idx.as_u32()),
+        ordinal,
+    }
+}
+
+/// Flattens the call tree in the depth-first order as expected by Firehose. Near calls don't correspond
+/// to EVM message calls, so they are skipped, with their subcalls attached to the closest far call.
+fn flatten_calls(
+    call: &Call,
+    parent_index: u32,
+    depth: u32,
+    ordinals: &mut Ordinals,
+    output: &mut Vec<eth::Call>,
+) {
+    let call_type = match call.r#type {
+        CallType::Call(FarCallOpcode::Delegate) => eth::CallType::Delegate,
+        CallType::Call(FarCallOpcode::Normal | FarCallOpcode::Mimic) => eth::CallType::Call,
+        CallType::Create => eth::CallType::Create,
+        CallType::NearCall => {
+            for subcall in &call.calls {
+                flatten_calls(subcall, parent_index, depth, ordinals, output);
+            }
+            return;
+        }
+    };
+
+    // Call indices are 1-based; 0 is reserved for the parent of the root call.
+    let position = output.len();
+    let index = position as u32 + 1;
+    let failure_reason = call.revert_reason.as_ref().or(call.error.as_ref());
+    output.push(eth::Call {
+        index,
+        parent_index,
+        depth,
+        call_type: call_type as i32,
+        caller: call.from.as_bytes().to_vec(),
+        address: call.to.as_bytes().to_vec(),
+        value: big_int(call.value),
+        gas_limit: call.gas.into(),
+        gas_consumed: call.gas_used.into(),
+        status_failed: failure_reason.is_some(),
+        failure_reason: failure_reason.cloned().unwrap_or_default(),
+        status_reverted: call.revert_reason.is_some(),
+        return_data: call.output.clone(),
+        input: call.input.clone(),
+        balance_changes: vec![],
+        begin_ordinal: ordinals.next(),
+        end_ordinal: 0,
+    });
+    for subcall in &call.calls {
+        flatten_calls(subcall, index, depth + 1, ordinals, output);
+    }
+    output[position].end_ordinal = ordinals.next();
+}
+
+/// Converts a transaction. Balance changes caused by the transaction are attributed to its root call,
+/// so they are dropped if the call trace is not provided.
+fn convert_transaction(
+    tx: &api::Transaction,
+    receipt: Option<&api::TransactionReceipt>,
+    call_trace: Option<&Call>,
+    balance_changes: Vec<eth::BalanceChange>,
+    ordinals: &mut Ordinals,
+) -> eth::TransactionTrace {
+    let status = match receipt {
+        None => eth::TransactionTraceStatus::Unknown,
+        Some(receipt) if receipt.status.as_u64() == 1 => eth::TransactionTraceStatus::Succeeded,
+        Some(_) if call_trace.map_or(false, |call| call.revert_reason.is_some()) => {
+            eth::TransactionTraceStatus::Reverted
+        }
+        Some(_) => eth::TransactionTraceStatus::Failed,
+    };
+    let begin_ordinal = ordinals.next();
+    let mut calls = vec![];
+    if let Some(call_trace) = call_trace {
+        flatten_calls(call_trace, 0, 0, ordinals, &mut calls);
+    }
+    // Logs are not attributed to calls, so they are ordered after all calls of the transaction.
+    let logs = receipt.map_or_else(Vec::new, |receipt| {
+        let logs = receipt.logs.iter();
+        logs.map(|log| convert_log(log, ordinals.next())).collect()
+    });
+    if let Some(root_call) = calls.first_mut() {
+        root_call.balance_changes = with_ordinals(balance_changes, ordinals);
+        // The root call must enclose the logs and balance changes of the transaction.
+        root_call.end_ordinal = ordinals.next();
+    }
+
+    eth::TransactionTrace {
+        to: tx.to.map(|to| to.as_bytes().to_vec()).unwrap_or_default(),
+        nonce: tx.nonce.low_u64(),
+        gas_price: tx.gas_price.and_then(big_int),
+        gas_limit: tx.gas.low_u64(),
+        value: big_int(tx.value),
+        input: tx.input.0.clone(),
+        gas_used: receipt
+            .and_then(|receipt| receipt.gas_used)
+            .map_or(0, |gas| gas.low_u64()),
+        max_fee_per_gas: tx.max_fee_per_gas.and_then(big_int),
+        r#type: tx.transaction_type.map_or(0, |ty| ty.as_u32() as i32),
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas.and_then(big_int),
+        index: tx.transaction_index.map_or(0, |idx| idx.as_u32()),
+        hash: tx.hash.as_bytes().to_vec(),
+        from: tx
+            .from
+            .map(|from| from.as_bytes().to_vec())
+            .unwrap_or_default(),
+        begin_ordinal,
+        status: status as i32,
+        receipt: receipt.map(|receipt| eth::TransactionReceipt {
+            state_root: receipt.root.as_bytes().to_vec(),
+            cumulative_gas_used: receipt.cumulative_gas_used.low_u64(),
+            logs_bloom: receipt.logs_bloom.as_bytes().to_vec(),
+            logs,
+        }),
+        calls,
+        end_ordinal: ordinals.next(),
+    }
+}
+
+fn with_ordinals(
+    mut balance_changes: Vec<eth::BalanceChange>,
+    ordinals: &mut Ordinals,
+) -> Vec<eth::BalanceChange> {
+    for change in &mut balance_changes {
+        change.ordinal = ordinals.next();
+    }
+    balance_changes
+}
+
+/// Loads base token balance changes in the specified miniblock together with hashes of the transactions
+/// that caused them, in the execution order. Balance storage keys cannot be mapped back to accounts, so the changes
+/// are checked for transaction initiators and for all addresses in indexed topics of base token events (which covers
+/// senders and recipients of transfers, and recipients of deposits).
+async fn load_balance_changes(
+    storage: &mut StorageProcessor<'_>,
+    number: MiniblockNumber,
+    transactions: &[api::Transaction],
+    receipts: &HashMap<H256, api::TransactionReceipt>,
+) -> anyhow::Result<Vec<(H256, eth::BalanceChange)>> {
+    let mut accounts: BTreeSet<Address> = transactions.iter().filter_map(|tx| tx.from).collect();
+    let base_token_logs = receipts
+        .values()
+        .flat_map(|receipt| &receipt.logs)
+        .filter(|log| log.address == L2_ETH_TOKEN_ADDRESS);
+    for log in base_token_logs {
+        accounts.extend(log.topics.iter().skip(1).map(h256_to_account_address));
+    }
+    let accounts_by_key: HashMap<_, _> = accounts
+        .into_iter()
+        .map(|address| (storage_key_for_eth_balance(&address).hashed_key(), address))
+        .collect();
+    let hashed_keys: Vec<_> = accounts_by_key.keys().copied().collect();
+
+    let mut values = if number == MiniblockNumber(0) {
+        HashMap::new()
+    } else {
+        storage
+            .storage_logs_dal()
+            .get_storage_values(&hashed_keys, number - 1)
+            .await
+            .context("failed loading old balances")?
+    };
+    let balance_logs = storage
+        .storage_logs_dal()
+        .get_miniblock_storage_logs_for_keys(number, &hashed_keys)
+        .await
+        .context("failed loading balance writes")?;
+
+    let mut changes = vec![];
+    for log in balance_logs {
+        let old_value = values
+            .insert(log.hashed_key, Some(log.value))
+            .flatten()
+            .unwrap_or_default();
+        if old_value == log.value {
+            continue;
+        }
+        let address = accounts_by_key[&log.hashed_key];
+        let change = eth::BalanceChange {
+            address: address.as_bytes().to_vec(),
+            old_value: big_int(h256_to_u256(old_value)),
+            new_value: big_int(h256_to_u256(log.value)),
+            reason: eth::balance_change::Reason::Unknown as i32,
+            ordinal: 0,
+        };
+        changes.push((log.tx_hash, change));
+    }
+    Ok(changes)
+}
+
+/// Loads the specified miniblock in the Firehose format. Returns `None` if the miniblock is not present
+/// in the storage.
+pub(super) async fn load_block(
+    storage: &mut StorageProcessor<'_>,
+    number: MiniblockNumber,
+    chain_id: L2ChainId,
+) -> anyhow::Result<Option<eth::Block>> {
+    let block_id = api::BlockId::Number(api::BlockNumber::Number(number.0.into()));
+    let Some(block) = storage
+        .blocks_web3_dal()
+        .get_block_by_web3_block_id(block_id, true, chain_id)
+        .await
+        .with_context(|| format!("failed loading miniblock #{number}"))?
+    else {
+        return Ok(None);
+    };
+
+    let transactions: Vec<_> = block
+        .transactions
+        .iter()
+        .filter_map(|tx| match tx {
+            api::TransactionVariant::Full(tx) => Some(tx.clone()),
+            api::TransactionVariant::Hash(_) => None,
+        })
+        .collect();
+    let tx_hashes: Vec<_> = transactions.iter().map(|tx| tx.hash).collect();
+    let receipts = storage
+        .transactions_web3_dal()
+        .get_transaction_receipts(&tx_hashes)
+        .await
+        .with_context(|| format!("failed loading receipts for miniblock #{number}"))?;
+    let receipts: HashMap<_, _> = receipts
+        .into_iter()
+        .map(|receipt| (receipt.transaction_hash, receipt))
+        .collect();
+
+    let mut call_traces = Vec::with_capacity(transactions.len());
+    for tx in &transactions {
+        let call_trace = storage
+            .transactions_dal()
+            .get_call_trace(tx.hash)
+            .await
+            .with_context(|| format!("failed loading call trace for transaction {:?}", tx.hash))?;
+        call_traces.push(call_trace);
+    }
+
+    // Balance changes are attributed to calls, so they can only be reported if all call traces are available
+    // (call traces are not persisted if the node doesn't save them).
+    let is_extended = call_traces.iter().all(Option::is_some);
+    let mut balance_changes_by_tx = HashMap::<_, Vec<_>>::new();
+    let mut block_balance_changes = vec![];
+    if is_extended {
+        let balance_changes =
+            load_balance_changes(storage, number, &transactions, &receipts).await?;
+        for (tx_hash, change) in balance_changes {
+            if tx_hashes.contains(&tx_hash) {
+                balance_changes_by_tx
+                    .entry(tx_hash)
+                    .or_default()
+                    .push(change);
+            } else {
+                block_balance_changes.push(change);
+            }
+        }
+    }
+
+    let mut ordinals = Ordinals::default();
+    let transaction_traces = transactions
+        .iter()
+        .zip(&call_traces)
+        .map(|(tx, call_trace)| {
+            let balance_changes = balance_changes_by_tx.remove(&tx.hash).unwrap_or_default();
+            let receipt = receipts.get(&tx.hash);
+            convert_transaction(
+                tx,
+                receipt,
+                call_trace.as_ref(),
+                balance_changes,
+                &mut ordinals,
+            )
+        })
+        .collect();
+    let detail_level = if is_extended {
+        eth::block::DetailLevel::DetaillevelExtended
+    } else {
+        eth::block::DetailLevel::DetaillevelBase
+    };
+
+    Ok(Some(eth::Block {
+        ver: BLOCK_MODEL_VERSION,
+        hash: block.hash.as_bytes().to_vec(),
+        number: number.0.into(),
+        size: block.size.low_u64(),
+        header: Some(convert_header(&block)),
+        transaction_traces,
+        balance_changes: with_ordinals(block_balance_changes, &mut ordinals),
+        detail_level: detail_level as i32,
+    }))
+}
@@ -0,0 +1,20 @@
+//! Metrics for the Firehose block stream.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, Metrics, Unit};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_firehose")]
+pub(super) struct FirehoseMetrics {
+    /// Number of active block streams.
+    pub active_streams: Gauge<usize>,
+    /// Number of blocks sent to clients, either in streams or as single blocks.
+    pub sent_blocks: Counter,
+    /// Latency of loading a single block from Postgres.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub block_load_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<FirehoseMetrics> = vise::Global::new();
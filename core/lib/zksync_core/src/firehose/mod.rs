@@ -0,0 +1,412 @@
+//! Firehose-compatible block stream, allowing The Graph and Substreams indexers to index the chain
+//! without a custom adapter.
+//!
+//! Miniblocks are served over gRPC in the `sf.ethereum.type.v2.Block` format, with transaction receipts,
+//! flattened call traces and base token balance changes. Both the `sf.firehose.v2.Stream` and
+//! `sf.firehose.v2.Fetch` services are supported; transforms are not.
+//!
+//! The server requires the `firehose` crate feature.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use futures::{stream::BoxStream, StreamExt};
+use prost::Message as _;
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore};
+use tonic::{Request, Response, Status};
+use zksync_config::FirehoseConfig;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{L2ChainId, MiniblockNumber, H256};
+
+use self::{
+    block::load_block,
+    metrics::METRICS,
+    proto::{
+        ethereum as eth,
+        firehose::{
+            self as fh,
+            fetch_server::{Fetch, FetchServer},
+            single_block_request::Reference,
+            stream_server::{Stream, StreamServer},
+        },
+    },
+};
+use crate::api_server::execution_sandbox::BlockStartInfo;
+
+mod block;
+mod metrics;
+mod proto;
+#[cfg(test)]
+mod tests;
+
+const BLOCK_TYPE_URL: &str = "type.googleapis.com/sf.ethereum.type.v2.Block";
+
+fn internal_error(err: anyhow::Error) -> Status {
+    tracing::warn!("Internal error in Firehose server: {err:#}");
+    Status::internal(format!("{err:#}"))
+}
+
+/// Position in the block stream. Cursors are opaque to clients; they contain the number and hash
+/// of the last streamed block, so that a client resuming the stream on a diverged node gets an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cursor {
+    number: MiniblockNumber,
+    hash: H256,
+}
+
+impl Cursor {
+    fn parse(raw: &str) -> Result<Self, Status> {
+        let parse = || {
+            let (number, hash) = raw.split_once(':')?;
+            Some(Self {
+                number: MiniblockNumber(number.parse().ok()?),
+                hash: hash.parse().ok()?,
+            })
+        };
+        parse().ok_or_else(|| Status::invalid_argument(format!("invalid cursor `{raw}`")))
+    }
+
+    fn encode(&self) -> String {
+        format!("{}:{:x}", self.number.0, self.hash)
+    }
+}
+
+/// Decrements the active stream gauge and releases the stream permit on drop.
+#[derive(Debug)]
+struct StreamGuard {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl StreamGuard {
+    fn new(permit: OwnedSemaphorePermit) -> Self {
+        METRICS.active_streams.inc_by(1);
+        Self { _permit: permit }
+    }
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        METRICS.active_streams.dec_by(1);
+    }
+}
+
+/// Firehose gRPC server implementing both streaming and fetching single blocks.
+#[derive(Debug, Clone)]
+pub struct FirehoseServer {
+    pool: ConnectionPool,
+    chain_id: L2ChainId,
+    poll_interval: Duration,
+    stream_permits: Arc<Semaphore>,
+    stop_receiver: watch::Receiver<bool>,
+}
+
+impl FirehoseServer {
+    pub fn new(
+        pool: ConnectionPool,
+        chain_id: L2ChainId,
+        config: &FirehoseConfig,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            pool,
+            chain_id,
+            poll_interval: config.poll_interval(),
+            stream_permits: Arc::new(Semaphore::new(config.max_streams)),
+            stop_receiver,
+        }
+    }
+
+    async fn access_storage(&self) -> Result<StorageProcessor<'_>, Status> {
+        self.pool
+            .access_storage_tagged("firehose")
+            .await
+            .map_err(internal_error)
+    }
+
+    /// Returns the last miniblock that can be streamed. If `final_only` is set, this is the last miniblock
+    /// in an L1 batch executed on the settlement layer.
+    async fn last_available_block(
+        storage: &mut StorageProcessor<'_>,
+        final_only: bool,
+    ) -> anyhow::Result<Option<MiniblockNumber>> {
+        if !final_only {
+            return storage
+                .blocks_dal()
+                .get_sealed_miniblock_number()
+                .await
+                .context("failed getting sealed miniblock number");
+        }
+
+        let Some(l1_batch_number) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("failed getting last executed L1 batch")?
+        else {
+            return Ok(None);
+        };
+        let range = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed getting miniblocks for L1 batch #{l1_batch_number}")
+            })?;
+        Ok(range.map(|(_, last)| last))
+    }
+
+    async fn load_block(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        number: MiniblockNumber,
+    ) -> Result<Option<eth::Block>, Status> {
+        let latency = METRICS.block_load_latency.start();
+        let block = load_block(storage, number, self.chain_id)
+            .await
+            .map_err(internal_error)?;
+        latency.observe();
+        Ok(block)
+    }
+
+    /// Checks that the block referenced by a cursor is present in the storage.
+    async fn check_cursor(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        cursor: Cursor,
+    ) -> Result<(), Status> {
+        let hash = storage
+            .blocks_web3_dal()
+            .get_miniblock_hash(cursor.number)
+            .await
+            .context("failed getting miniblock hash")
+            .map_err(internal_error)?;
+        if hash == Some(cursor.hash) {
+            Ok(())
+        } else {
+            Err(Status::invalid_argument(format!(
+                "cursor references miniblock #{} with hash {:?}, which is not present on the node",
+                cursor.number, cursor.hash
+            )))
+        }
+    }
+
+    async fn resolve_start_block(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        request: &fh::Request,
+    ) -> Result<MiniblockNumber, Status> {
+        let start_block = if !request.cursor.is_empty() {
+            let cursor = Cursor::parse(&request.cursor)?;
+            self.check_cursor(storage, cursor).await?;
+            cursor.number + 1
+        } else if request.start_block_num >= 0 {
+            let start_block = u32::try_from(request.start_block_num)
+                .map_err(|_| Status::invalid_argument("start block number is too large"))?;
+            MiniblockNumber(start_block)
+        } else {
+            let head = Self::last_available_block(storage, request.final_blocks_only)
+                .await
+                .map_err(internal_error)?
+                .unwrap_or(MiniblockNumber(0));
+            let start_block = i64::from(head.0) + 1 + request.start_block_num;
+            MiniblockNumber(start_block.max(0) as u32)
+        };
+
+        let start_info = BlockStartInfo::new(storage).await.map_err(internal_error)?;
+        if start_block < start_info.first_miniblock {
+            return Err(Status::out_of_range(format!(
+                "miniblock #{start_block} is pruned; the first available miniblock is #{}",
+                start_info.first_miniblock
+            )));
+        }
+        Ok(start_block)
+    }
+
+    fn block_response(block: &eth::Block, step: fh::ForkStep) -> fh::Response {
+        let cursor = Cursor {
+            number: MiniblockNumber(block.number as u32),
+            hash: H256::from_slice(&block.hash),
+        };
+        fh::Response {
+            block: Some(prost_types::Any {
+                type_url: BLOCK_TYPE_URL.to_owned(),
+                value: block.encode_to_vec(),
+            }),
+            step: step as i32,
+            cursor: cursor.encode(),
+        }
+    }
+
+    pub async fn run(self, bind_address: SocketAddr) -> anyhow::Result<()> {
+        tracing::info!("Starting Firehose gRPC server on {bind_address}");
+        let mut stop_receiver = self.stop_receiver.clone();
+        tonic::transport::Server::builder()
+            .add_service(StreamServer::new(self.clone()))
+            .add_service(FetchServer::new(self))
+            .serve_with_shutdown(bind_address, async move {
+                if stop_receiver.changed().await.is_err() {
+                    tracing::warn!(
+                        "Stop signal sender for Firehose server was dropped without sending a signal"
+                    );
+                }
+                tracing::info!("Stop signal received, Firehose server is shutting down");
+            })
+            .await
+            .context("Firehose gRPC server failed")?;
+        tracing::info!("Firehose server shut down");
+        Ok(())
+    }
+}
+
+/// State of a single block stream.
+#[derive(Debug)]
+struct BlockStream {
+    server: FirehoseServer,
+    next_block: MiniblockNumber,
+    stop_block: Option<MiniblockNumber>,
+    final_blocks_only: bool,
+    _guard: StreamGuard,
+}
+
+impl BlockStream {
+    /// Returns the next block in the stream, waiting for it to be sealed if necessary. Returns `None`
+    /// if the stream has reached the stop block, or if the server is shutting down.
+    async fn next_response(&mut self) -> Result<Option<fh::Response>, Status> {
+        let mut stop_receiver = self.server.stop_receiver.clone();
+        loop {
+            if *stop_receiver.borrow() {
+                return Ok(None);
+            }
+            if matches!(self.stop_block, Some(stop_block) if self.next_block > stop_block) {
+                return Ok(None);
+            }
+
+            let mut storage = self.server.access_storage().await?;
+            let last_available =
+                FirehoseServer::last_available_block(&mut storage, self.final_blocks_only)
+                    .await
+                    .map_err(internal_error)?;
+            if matches!(last_available, Some(last) if last >= self.next_block) {
+                let block = self
+                    .server
+                    .load_block(&mut storage, self.next_block)
+                    .await?
+                    .ok_or_else(|| {
+                        let number = self.next_block;
+                        internal_error(anyhow::anyhow!("miniblock #{number} disappeared"))
+                    })?;
+                self.next_block += 1;
+                let step = if self.final_blocks_only {
+                    fh::ForkStep::StepFinal
+                } else {
+                    fh::ForkStep::StepNew
+                };
+                METRICS.sent_blocks.inc();
+                return Ok(Some(FirehoseServer::block_response(&block, step)));
+            }
+            drop(storage);
+
+            let wait_result =
+                tokio::time::timeout(self.server.poll_interval, stop_receiver.changed()).await;
+            if let Ok(Err(_)) = wait_result {
+                // The stop sender is dropped, i.e., the node is shutting down.
+                return Ok(None);
+            }
+        }
+    }
+
+    fn into_stream(self) -> BoxStream<'static, Result<fh::Response, Status>> {
+        futures::stream::unfold(Some(self), |state| async move {
+            let mut state = state?;
+            match state.next_response().await {
+                Ok(Some(response)) => Some((Ok(response), Some(state))),
+                Ok(None) => None,
+                // Terminate the stream after an error.
+                Err(status) => Some((Err(status), None)),
+            }
+        })
+        .boxed()
+    }
+}
+
+#[tonic::async_trait]
+impl Stream for FirehoseServer {
+    type BlocksStream = BoxStream<'static, Result<fh::Response, Status>>;
+
+    async fn blocks(
+        &self,
+        request: Request<fh::Request>,
+    ) -> Result<Response<Self::BlocksStream>, Status> {
+        let request = request.into_inner();
+        if !request.transforms.is_empty() {
+            return Err(Status::unimplemented("transforms are not supported"));
+        }
+        let permit = self
+            .stream_permits
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| Status::resource_exhausted("too many concurrent block streams"))?;
+
+        let mut storage = self.access_storage().await?;
+        let next_block = self.resolve_start_block(&mut storage, &request).await?;
+        drop(storage);
+
+        let stop_block = (request.stop_block_num != 0)
+            .then(|| u32::try_from(request.stop_block_num).unwrap_or(u32::MAX))
+            .map(MiniblockNumber);
+        let stream = BlockStream {
+            server: self.clone(),
+            next_block,
+            stop_block,
+            final_blocks_only: request.final_blocks_only,
+            _guard: StreamGuard::new(permit),
+        };
+        Ok(Response::new(stream.into_stream()))
+    }
+}
+
+#[tonic::async_trait]
+impl Fetch for FirehoseServer {
+    async fn block(
+        &self,
+        request: Request<fh::SingleBlockRequest>,
+    ) -> Result<Response<fh::SingleBlockResponse>, Status> {
+        let request = request.into_inner();
+        if !request.transforms.is_empty() {
+            return Err(Status::unimplemented("transforms are not supported"));
+        }
+        let (number, expected_hash) = match request.reference {
+            Some(Reference::BlockNumber(reference)) => {
+                let number = u32::try_from(reference.num)
+                    .map_err(|_| Status::invalid_argument("block number is too large"))?;
+                (MiniblockNumber(number), None)
+            }
+            Some(Reference::BlockHashAndNumber(reference)) => {
+                let number = u32::try_from(reference.num)
+                    .map_err(|_| Status::invalid_argument("block number is too large"))?;
+                let hash = reference.hash.strip_prefix("0x").unwrap_or(&reference.hash);
+                let hash: H256 = hash
+                    .parse()
+                    .map_err(|_| Status::invalid_argument("invalid block hash"))?;
+                (MiniblockNumber(number), Some(hash))
+            }
+            Some(Reference::Cursor(reference)) => {
+                let cursor = Cursor::parse(&reference.cursor)?;
+                (cursor.number, Some(cursor.hash))
+            }
+            None => return Err(Status::invalid_argument("block reference is not specified")),
+        };
+
+        let mut storage = self.access_storage().await?;
+        let block = self
+            .load_block(&mut storage, number)
+            .await?
+            .filter(|block| expected_hash.map_or(true, |hash| block.hash == hash.as_bytes()))
+            .ok_or_else(|| Status::not_found(format!("miniblock #{number} is not found")))?;
+        METRICS.sent_blocks.inc();
+        let response = FirehoseServer::block_response(&block, fh::ForkStep::StepNew);
+        Ok(Response::new(fh::SingleBlockResponse {
+            block: response.block,
+        }))
+    }
+}
@@ -0,0 +1,9 @@
+#![allow(warnings)]
+
+pub mod firehose {
+    tonic::include_proto!("sf.firehose.v2");
+}
+
+pub mod ethereum {
+    tonic::include_proto!("sf.ethereum.type.v2");
+}
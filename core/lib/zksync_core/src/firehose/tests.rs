@@ -0,0 +1,368 @@
+//! Tests for the Firehose block stream.
+
+use assert_matches::assert_matches;
+use prost::Message as _;
+use zksync_types::{
+    event::TRANSFER_EVENT_SIGNATURE, fee::TransactionExecutionMetrics, tx::IncludedTxLocation,
+    utils::storage_key_for_eth_balance, vm_trace::Call, Address, L1BatchNumber, StorageLog,
+    VmEvent, L2_ETH_TOKEN_ADDRESS,
+};
+use zksync_utils::{address_to_h256, u256_to_h256};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l2_transaction, create_miniblock, execute_l2_transaction},
+};
+
+const SENDER: Address = Address::repeat_byte(1);
+const RECIPIENT: Address = Address::repeat_byte(2);
+
+fn test_config() -> FirehoseConfig {
+    FirehoseConfig {
+        port: 0,
+        poll_interval_ms: 10,
+        max_streams: 1,
+    }
+}
+
+/// Stores miniblock #1 with a single transaction transferring the base token. Returns the transaction hash.
+async fn prepare_storage(storage: &mut StorageProcessor<'_>, save_call_trace: bool) -> H256 {
+    ensure_genesis_state(storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let tx = create_l2_transaction(10, 100);
+    let tx_hash = tx.hash();
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+        .await;
+    let miniblock = create_miniblock(1);
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+    let mut tx_result = execute_l2_transaction(tx);
+    if save_call_trace {
+        tx_result.call_traces = vec![Call::default()];
+    }
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(miniblock.number, &[tx_result], 1.into())
+        .await;
+
+    let tx_location = IncludedTxLocation {
+        tx_hash,
+        tx_index_in_miniblock: 0,
+        tx_initiator_address: SENDER,
+    };
+    let transfer_event = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: L2_ETH_TOKEN_ADDRESS,
+        indexed_topics: vec![
+            *TRANSFER_EVENT_SIGNATURE,
+            address_to_h256(&SENDER),
+            address_to_h256(&RECIPIENT),
+        ],
+        value: u256_to_h256(100.into()).0.to_vec(),
+    };
+    storage
+        .events_dal()
+        .save_events(miniblock.number, &[(tx_location, vec![&transfer_event])])
+        .await;
+
+    let balance_log = StorageLog::new_write_log(
+        storage_key_for_eth_balance(&RECIPIENT),
+        u256_to_h256(100.into()),
+    );
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(miniblock.number, &[(tx_hash, vec![balance_log])])
+        .await
+        .unwrap();
+    tx_hash
+}
+
+fn decode_block(response: &fh::Response) -> eth::Block {
+    let block = response.block.as_ref().unwrap();
+    assert_eq!(block.type_url, BLOCK_TYPE_URL);
+    eth::Block::decode(block.value.as_slice()).unwrap()
+}
+
+#[test]
+fn cursor_roundtrip() {
+    let cursor = Cursor {
+        number: MiniblockNumber(42),
+        hash: H256::repeat_byte(0xab),
+    };
+    let encoded = cursor.encode();
+    assert!(encoded.starts_with("42:abab"), "{encoded}");
+    assert_eq!(Cursor::parse(&encoded).unwrap(), cursor);
+
+    for invalid_cursor in ["", "42", "42:", "x:abab", "42:zz"] {
+        let err = Cursor::parse(invalid_cursor).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+}
+
+#[tokio::test]
+async fn loading_block() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let tx_hash = prepare_storage(&mut storage, true).await;
+
+    let block = load_block(&mut storage, MiniblockNumber(1), L2ChainId::default())
+        .await
+        .unwrap()
+        .expect("no miniblock #1");
+    assert_eq!(block.number, 1);
+    let header = block.header.as_ref().unwrap();
+    assert_eq!(header.number, 1);
+    assert_eq!(header.hash, block.hash);
+    assert_eq!(header.timestamp.as_ref().unwrap().seconds, 1);
+
+    assert_eq!(block.transaction_traces.len(), 1);
+    let trace = &block.transaction_traces[0];
+    assert_eq!(trace.hash, tx_hash.as_bytes());
+    assert_eq!(trace.status, eth::TransactionTraceStatus::Succeeded as i32);
+    let receipt = trace.receipt.as_ref().unwrap();
+    assert_eq!(receipt.logs.len(), 1);
+    assert_eq!(receipt.logs[0].address, L2_ETH_TOKEN_ADDRESS.as_bytes());
+    assert_eq!(
+        block.detail_level,
+        eth::block::DetailLevel::DetaillevelExtended as i32
+    );
+
+    // The high-level call wraps the stored call trace.
+    assert_eq!(trace.calls.len(), 2);
+    let root_call = &trace.calls[0];
+    assert_eq!((root_call.index, root_call.parent_index), (1, 0));
+    assert_eq!((trace.calls[1].index, trace.calls[1].parent_index), (2, 1));
+    assert!(block.balance_changes.is_empty());
+    assert!(trace.calls[1].balance_changes.is_empty());
+    assert_eq!(root_call.balance_changes.len(), 1);
+    let balance_change = &root_call.balance_changes[0];
+    assert_eq!(balance_change.address, RECIPIENT.as_bytes());
+    assert!(balance_change.old_value.as_ref().unwrap().bytes.is_empty());
+    assert_eq!(balance_change.new_value.as_ref().unwrap().bytes, [100]);
+
+    // Ordinals must follow the execution order, with the root call enclosing logs and balance changes.
+    assert_eq!(trace.begin_ordinal, 1);
+    assert_eq!((root_call.begin_ordinal, root_call.end_ordinal), (2, 7));
+    let subcall = &trace.calls[1];
+    assert_eq!((subcall.begin_ordinal, subcall.end_ordinal), (3, 4));
+    assert_eq!(receipt.logs[0].ordinal, 5);
+    assert_eq!(balance_change.ordinal, 6);
+    assert_eq!(trace.end_ordinal, 8);
+
+    let missing_block = load_block(&mut storage, MiniblockNumber(2), L2ChainId::default())
+        .await
+        .unwrap();
+    assert!(missing_block.is_none());
+}
+
+#[tokio::test]
+async fn loading_block_without_call_traces() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, false).await;
+
+    let block = load_block(&mut storage, MiniblockNumber(1), L2ChainId::default())
+        .await
+        .unwrap()
+        .expect("no miniblock #1");
+    assert_eq!(
+        block.detail_level,
+        eth::block::DetailLevel::DetaillevelBase as i32
+    );
+    assert!(block.balance_changes.is_empty());
+    let trace = &block.transaction_traces[0];
+    assert!(trace.calls.is_empty());
+    let receipt = trace.receipt.as_ref().unwrap();
+    assert_eq!(
+        (
+            trace.begin_ordinal,
+            receipt.logs[0].ordinal,
+            trace.end_ordinal
+        ),
+        (1, 2, 3)
+    );
+}
+
+#[tokio::test]
+async fn fetching_single_block() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, true).await;
+    drop(storage);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let server = FirehoseServer::new(pool, L2ChainId::default(), &test_config(), stop_receiver);
+
+    let request = fh::SingleBlockRequest {
+        reference: Some(Reference::BlockNumber(
+            fh::single_block_request::BlockNumber { num: 1 },
+        )),
+        transforms: vec![],
+    };
+    let response = server.block(Request::new(request)).await.unwrap();
+    let block_any = response.into_inner().block.unwrap();
+    let block = eth::Block::decode(block_any.value.as_slice()).unwrap();
+    assert_eq!(block.number, 1);
+    let block_hash = H256::from_slice(&block.hash);
+
+    let request = fh::SingleBlockRequest {
+        reference: Some(Reference::BlockHashAndNumber(
+            fh::single_block_request::BlockHashAndNumber {
+                num: 1,
+                hash: format!("{block_hash:?}"),
+            },
+        )),
+        transforms: vec![],
+    };
+    server.block(Request::new(request)).await.unwrap();
+
+    let request = fh::SingleBlockRequest {
+        reference: Some(Reference::BlockHashAndNumber(
+            fh::single_block_request::BlockHashAndNumber {
+                num: 1,
+                hash: format!("{:?}", H256::zero()),
+            },
+        )),
+        transforms: vec![],
+    };
+    let err = server.block(Request::new(request)).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::NotFound);
+
+    let request = fh::SingleBlockRequest {
+        reference: Some(Reference::BlockNumber(
+            fh::single_block_request::BlockNumber { num: 2 },
+        )),
+        transforms: vec![],
+    };
+    let err = server.block(Request::new(request)).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn streaming_blocks() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, true).await;
+    drop(storage);
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let server = FirehoseServer::new(pool, L2ChainId::default(), &test_config(), stop_receiver);
+
+    let request = fh::Request {
+        start_block_num: 0,
+        stop_block_num: 1,
+        ..fh::Request::default()
+    };
+    let stream = server.blocks(Request::new(request)).await.unwrap();
+    let responses: Vec<_> = stream.into_inner().collect().await;
+    assert_eq!(responses.len(), 2);
+    let responses: Vec<_> = responses.into_iter().map(Result::unwrap).collect();
+    let blocks: Vec<_> = responses.iter().map(decode_block).collect();
+    assert_eq!(blocks[0].number, 0);
+    assert_eq!(blocks[1].number, 1);
+    assert_eq!(
+        blocks[1].header.as_ref().unwrap().parent_hash,
+        blocks[0].hash
+    );
+    assert!(responses
+        .iter()
+        .all(|response| response.step == fh::ForkStep::StepNew as i32));
+    // The stream permit must be released once the stream is dropped.
+    assert_eq!(server.stream_permits.available_permits(), 1);
+
+    // Resume the stream from the cursor of the genesis block. Relative start block must be ignored.
+    let request = fh::Request {
+        start_block_num: -100,
+        cursor: responses[0].cursor.clone(),
+        ..fh::Request::default()
+    };
+    let mut stream = server
+        .blocks(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    let response = stream.next().await.unwrap().unwrap();
+    assert_eq!(response.cursor, responses[1].cursor);
+
+    // Only a single stream is allowed by the config.
+    let err = server
+        .blocks(Request::new(fh::Request::default()))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+    // The stream waits for the next block and terminates on the stop signal.
+    let next_response = tokio::spawn(async move { stream.next().await });
+    stop_sender.send_replace(true);
+    let next_response = next_response.await.unwrap();
+    assert!(next_response.is_none());
+
+    let invalid_cursor = Cursor {
+        number: MiniblockNumber(1),
+        hash: H256::zero(),
+    };
+    let request = fh::Request {
+        cursor: invalid_cursor.encode(),
+        ..fh::Request::default()
+    };
+    let err = server.blocks(Request::new(request)).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn streaming_final_blocks_only() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, true).await;
+    drop(storage);
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let server = FirehoseServer::new(pool, L2ChainId::default(), &test_config(), stop_receiver);
+
+    // No L1 batches are executed, so the stream must wait.
+    let request = fh::Request {
+        start_block_num: -1,
+        final_blocks_only: true,
+        ..fh::Request::default()
+    };
+    let mut stream = server
+        .blocks(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    let next_response = tokio::spawn(async move { stream.next().await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!next_response.is_finished());
+    stop_sender.send_replace(true);
+    assert_matches!(next_response.await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn stream_terminates_when_stop_sender_is_dropped() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, true).await;
+    drop(storage);
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let server = FirehoseServer::new(pool, L2ChainId::default(), &test_config(), stop_receiver);
+
+    let request = fh::Request {
+        start_block_num: 2,
+        ..fh::Request::default()
+    };
+    let mut stream = server
+        .blocks(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    drop(stop_sender);
+    let next_response = tokio::time::timeout(Duration::from_secs(5), stream.next())
+        .await
+        .expect("stream doesn't terminate");
+    assert_matches!(next_response, None);
+}
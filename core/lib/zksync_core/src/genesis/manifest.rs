@@ -0,0 +1,580 @@
+//! Genesis manifest customizing the genesis state of a chain.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    block::DeployedContract,
+    get_code_key,
+    utils::{storage_key_for_eth_balance, storage_key_for_eth_total_supply},
+    web3::signing::keccak256,
+    AccountTreeId, Address, Bytes, L2ChainId, StorageKey, StorageLog, H256, U256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode},
+    u256_to_h256,
+};
+
+/// Base token metadata. The base token is still bridged from L1 as Ether; the metadata only changes
+/// how the token is presented by the node (e.g., in the Web3 API and in the block explorer).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BaseTokenParams {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Fee parameters of the genesis miniblock and L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisFeeParams {
+    pub l1_gas_price: u64,
+    pub minimal_l2_gas_price: u64,
+}
+
+/// Storage layout of a predeployed ERC-20 token, which allows setting initial balances of the token.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Erc20Layout {
+    /// Storage slot of the `mapping(address => uint256)` with token balances.
+    pub balances_slot: u64,
+    /// Storage slot of the total token supply.
+    pub total_supply_slot: u64,
+}
+
+impl Erc20Layout {
+    fn balance_key(&self, token: Address, account: Address) -> StorageKey {
+        let mut bytes = [0_u8; 64];
+        bytes[12..32].copy_from_slice(account.as_bytes());
+        bytes[32..].copy_from_slice(H256::from_low_u64_be(self.balances_slot).as_bytes());
+        StorageKey::new(AccountTreeId::new(token), H256(keccak256(&bytes)))
+    }
+
+    fn total_supply_key(&self, token: Address) -> StorageKey {
+        let slot = H256::from_low_u64_be(self.total_supply_slot);
+        StorageKey::new(AccountTreeId::new(token), slot)
+    }
+}
+
+/// Contract deployed in the genesis state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Predeploy {
+    pub address: Address,
+    pub bytecode: Bytes,
+    /// Initial values of contract storage slots.
+    #[serde(default)]
+    pub storage: BTreeMap<H256, H256>,
+    /// Storage layout if the contract is an ERC-20 token with initial balances.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub erc20: Option<Erc20Layout>,
+}
+
+/// Initial balance of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InitialBalance {
+    /// Address of an ERC-20 token predeployed by the manifest. If not specified, the balance is in the base token;
+    /// such balances are only allowed for chains not settling on L1 (see [`GenesisManifest::validate()`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<Address>,
+    pub address: Address,
+    pub amount: U256,
+}
+
+/// Manifest customizing the genesis state of a chain. The manifest is loaded from a JSON file of the following form:
+///
+/// ```json
+/// {
+///   "chain_id": 270,
+///   "base_token": { "name": "Idexo", "symbol": "IDO", "decimals": 18 },
+///   "fee_params": { "l1_gas_price": 1000000000, "minimal_l2_gas_price": 100000000 },
+///   "predeploys": [{
+///     "address": "0x…",
+///     "bytecode": "0x…",
+///     "storage": { "0x…": "0x…" },
+///     "erc20": { "balances_slot": 0, "total_supply_slot": 2 }
+///   }],
+///   "balances": [{ "token": "0x…", "address": "0x…", "amount": "0xde0b6b3a7640000" }]
+/// }
+/// ```
+///
+/// All fields except for `chain_id` are optional. The manifest hash is written to a reserved storage slot
+/// (see [`Self::hash_key()`]), so the entire manifest is committed to by the genesis root hash and, consequently,
+/// by the genesis L1 batch commitment. Thus, all nodes of a chain must use the same manifest during genesis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisManifest {
+    /// L2 chain ID; must match the chain ID configured for the node.
+    pub chain_id: u64,
+    #[serde(default)]
+    pub base_token: Option<BaseTokenParams>,
+    #[serde(default)]
+    pub fee_params: Option<GenesisFeeParams>,
+    #[serde(default)]
+    pub predeploys: Vec<Predeploy>,
+    #[serde(default)]
+    pub balances: Vec<InitialBalance>,
+}
+
+impl GenesisManifest {
+    /// Upper bound (inclusive) of the kernel space, i.e. addresses reserved for system contracts.
+    const MAX_KERNEL_SPACE_ADDRESS: u64 = 0xffff;
+
+    #[cfg(test)]
+    pub(crate) fn mock() -> Self {
+        Self {
+            chain_id: 270,
+            base_token: Some(BaseTokenParams {
+                name: "Idexo".to_owned(),
+                symbol: "IDO".to_owned(),
+                decimals: 18,
+            }),
+            fee_params: Some(GenesisFeeParams {
+                l1_gas_price: 1_000_000_000,
+                minimal_l2_gas_price: 100_000_000,
+            }),
+            predeploys: vec![Predeploy {
+                address: Address::repeat_byte(0x23),
+                // Bytecode must consist of an odd number of 32-byte words.
+                bytecode: Bytes(vec![1; 32 * 3]),
+                storage: BTreeMap::from([(H256::zero(), H256::repeat_byte(0xff))]),
+                erc20: Some(Erc20Layout {
+                    balances_slot: 1,
+                    total_supply_slot: 2,
+                }),
+            }],
+            balances: vec![
+                InitialBalance {
+                    token: Some(Address::repeat_byte(0x23)),
+                    address: Address::repeat_byte(0x01),
+                    amount: U256::from(1_000),
+                },
+                InitialBalance {
+                    token: Some(Address::repeat_byte(0x23)),
+                    address: Address::repeat_byte(0x02),
+                    amount: U256::from(500),
+                },
+            ],
+        }
+    }
+
+    /// Loads a manifest from the specified JSON file.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let raw = std::fs::read(path).with_context(|| {
+            format!("failed reading genesis manifest from `{}`", path.display())
+        })?;
+        serde_json::from_slice(&raw)
+            .with_context(|| format!("failed parsing genesis manifest at `{}`", path.display()))
+    }
+
+    /// Returns the hash of this manifest. The hash covers all manifest fields and doesn't depend
+    /// on the formatting of the manifest file.
+    pub fn hash(&self) -> H256 {
+        let serialized = serde_json::to_vec(self).expect("failed serializing genesis manifest");
+        H256(keccak256(&serialized))
+    }
+
+    /// Returns the storage key at which the manifest hash is stored in the genesis state.
+    pub fn hash_key() -> StorageKey {
+        let slot = H256(keccak256(b"zksync.genesis.manifest_hash"));
+        StorageKey::new(AccountTreeId::new(Address::zero()), slot)
+    }
+
+    /// Checks that the manifest is consistent with the chain ID and system contracts used during genesis.
+    ///
+    /// Base token balances are rejected: the base token is bridged from L1, so minting it in the genesis state
+    /// would create supply not backed by funds locked in the L1 bridge. Initial balances can only be set
+    /// for ERC-20 tokens predeployed by the manifest.
+    pub fn validate(
+        &self,
+        chain_id: L2ChainId,
+        system_contracts: &[DeployedContract],
+    ) -> anyhow::Result<()> {
+        if let Some(balance) = self.balances.iter().find(|balance| balance.token.is_none()) {
+            anyhow::bail!(
+                "initial base token balance of {:?} is not backed by L1 deposits; initial balances \
+                 can only be set for ERC-20 tokens predeployed by the manifest",
+                balance.address
+            );
+        }
+        self.validate_with_base_token_balances(chain_id, system_contracts)
+    }
+
+    /// Same as [`Self::validate()`], but allows base token balances. Must only be used for chains
+    /// not settling on L1 (i.e., the in-memory node), for which the base token supply doesn't need to be backed.
+    pub(crate) fn validate_with_base_token_balances(
+        &self,
+        chain_id: L2ChainId,
+        system_contracts: &[DeployedContract],
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.chain_id == chain_id.as_u64(),
+            "manifest chain ID ({}) doesn't match the configured L2 chain ID ({})",
+            self.chain_id,
+            chain_id.as_u64()
+        );
+        if let Some(base_token) = &self.base_token {
+            anyhow::ensure!(
+                !base_token.name.is_empty() && !base_token.symbol.is_empty(),
+                "base token name and symbol must not be empty"
+            );
+        }
+        if let Some(fee_params) = &self.fee_params {
+            anyhow::ensure!(
+                fee_params.minimal_l2_gas_price > 0,
+                "minimal L2 gas price must be positive"
+            );
+        }
+
+        let max_kernel_space_address = Address::from_low_u64_be(Self::MAX_KERNEL_SPACE_ADDRESS);
+        let system_addresses: HashSet<_> = system_contracts
+            .iter()
+            .map(|contract| *contract.account_id.address())
+            .collect();
+        let mut predeploy_addresses = HashSet::with_capacity(self.predeploys.len());
+        for predeploy in &self.predeploys {
+            let address = predeploy.address;
+            anyhow::ensure!(
+                address > max_kernel_space_address,
+                "predeploy address {address:?} is in the kernel space reserved for system contracts"
+            );
+            anyhow::ensure!(
+                !system_addresses.contains(&address),
+                "predeploy address {address:?} collides with a system contract"
+            );
+            anyhow::ensure!(
+                predeploy_addresses.insert(address),
+                "predeploy address {address:?} is specified multiple times"
+            );
+            validate_bytecode(&predeploy.bytecode.0)
+                .with_context(|| format!("invalid bytecode of predeploy {address:?}"))?;
+        }
+
+        let mut balance_keys = HashSet::with_capacity(self.balances.len());
+        for balance in &self.balances {
+            let address = balance.address;
+            if let Some(token) = balance.token {
+                let predeploy = self
+                    .predeploys
+                    .iter()
+                    .find(|predeploy| predeploy.address == token)
+                    .with_context(|| {
+                        format!(
+                            "token {token:?} of initial balance of {address:?} is not predeployed"
+                        )
+                    })?;
+                anyhow::ensure!(
+                    predeploy.erc20.is_some(),
+                    "predeploy {token:?} has no ERC-20 storage layout"
+                );
+            }
+            anyhow::ensure!(
+                balance_keys.insert((balance.token, address)),
+                "initial balance of {address:?} in token {:?} is specified multiple times",
+                balance.token
+            );
+            anyhow::ensure!(
+                !balance.amount.is_zero(),
+                "initial balance of {address:?} is zero"
+            );
+        }
+        let total_supplies = self
+            .total_supplies()
+            .context("total amount of initial balances overflows")?;
+
+        for predeploy in &self.predeploys {
+            let (token, Some(layout)) = (predeploy.address, predeploy.erc20) else {
+                continue;
+            };
+            // Initial balances and the total supply must not be overwritten by explicitly specified storage.
+            let balance_keys = self
+                .balances
+                .iter()
+                .filter(|balance| balance.token == Some(token))
+                .map(|balance| layout.balance_key(token, balance.address));
+            let mut keys = balance_keys.chain(
+                total_supplies
+                    .contains_key(&Some(token))
+                    .then(|| layout.total_supply_key(token)),
+            );
+            anyhow::ensure!(
+                !keys.any(|key| predeploy.storage.contains_key(key.key())),
+                "storage of predeploy {token:?} overlaps with its initial balances or total supply"
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the total supply of each token with initial balances; the base token is denoted by `None`.
+    fn total_supplies(&self) -> Option<BTreeMap<Option<Address>, U256>> {
+        let mut total_supplies = BTreeMap::<_, U256>::new();
+        for balance in &self.balances {
+            let total_supply = total_supplies.entry(balance.token).or_default();
+            *total_supply = total_supply.checked_add(balance.amount)?;
+        }
+        Some(total_supplies)
+    }
+
+    fn erc20_layout(&self, token: Address) -> Erc20Layout {
+        self.predeploys
+            .iter()
+            .find(|predeploy| predeploy.address == token)
+            .and_then(|predeploy| predeploy.erc20)
+            .expect("initial balance refers to a token without ERC-20 layout")
+    }
+
+    /// Returns the storage key of an initial balance. Expects the manifest to be validated.
+    pub(crate) fn balance_key(&self, balance: &InitialBalance) -> StorageKey {
+        match balance.token {
+            Some(token) => self.erc20_layout(token).balance_key(token, balance.address),
+            None => storage_key_for_eth_balance(&balance.address),
+        }
+    }
+
+    /// Returns storage logs for the genesis state. Expects the manifest to be validated.
+    pub(crate) fn storage_logs(&self) -> Vec<StorageLog> {
+        let mut logs = vec![];
+        for predeploy in &self.predeploys {
+            let bytecode_hash = hash_bytecode(&predeploy.bytecode.0);
+            logs.push(StorageLog::new_write_log(
+                get_code_key(&predeploy.address),
+                bytecode_hash,
+            ));
+            let account = AccountTreeId::new(predeploy.address);
+            logs.extend(predeploy.storage.iter().map(|(&key, &value)| {
+                StorageLog::new_write_log(StorageKey::new(account, key), value)
+            }));
+        }
+
+        for balance in &self.balances {
+            logs.push(StorageLog::new_write_log(
+                self.balance_key(balance),
+                u256_to_h256(balance.amount),
+            ));
+        }
+        let total_supplies = self.total_supplies().expect("total supply overflow");
+        for (token, total_supply) in total_supplies {
+            let total_supply_key = match token {
+                Some(token) => self.erc20_layout(token).total_supply_key(token),
+                None => storage_key_for_eth_total_supply(),
+            };
+            logs.push(StorageLog::new_write_log(
+                total_supply_key,
+                u256_to_h256(total_supply),
+            ));
+        }
+
+        logs.push(StorageLog::new_write_log(Self::hash_key(), self.hash()));
+        logs
+    }
+
+    /// Returns bytecodes of predeployed contracts keyed by their hashes.
+    pub(crate) fn factory_deps(&self) -> HashMap<H256, Vec<u8>> {
+        self.predeploys
+            .iter()
+            .map(|predeploy| {
+                let bytecode = predeploy.bytecode.0.clone();
+                (hash_bytecode(&bytecode), bytecode)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{system_contracts::get_system_smart_contracts, L2_ETH_TOKEN_ADDRESS};
+
+    use super::*;
+
+    fn mock_bytecode() -> Bytes {
+        Bytes(vec![1; 32 * 3])
+    }
+
+    #[test]
+    fn parsing_manifest() {
+        let json = serde_json::json!({
+            "chain_id": 270,
+            "base_token": { "name": "Idexo", "symbol": "IDO", "decimals": 18 },
+            "fee_params": { "l1_gas_price": 1_000_000_000_u64, "minimal_l2_gas_price": 100_000_000 },
+            "predeploys": [{
+                "address": "0x2323232323232323232323232323232323232323",
+                "bytecode": format!("0x{}", hex::encode(mock_bytecode().0)),
+                "storage": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000000":
+                        "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                },
+                "erc20": { "balances_slot": 1, "total_supply_slot": 2 },
+            }],
+            "balances": [
+                {
+                    "token": "0x2323232323232323232323232323232323232323",
+                    "address": "0x0101010101010101010101010101010101010101",
+                    "amount": "0x3e8",
+                },
+                {
+                    "token": "0x2323232323232323232323232323232323232323",
+                    "address": "0x0202020202020202020202020202020202020202",
+                    "amount": "0x1f4",
+                },
+            ],
+        });
+        let manifest: GenesisManifest = serde_json::from_value(json).unwrap();
+        assert_eq!(manifest, GenesisManifest::mock());
+
+        let minimal_manifest: GenesisManifest =
+            serde_json::from_value(serde_json::json!({ "chain_id": 270 })).unwrap();
+        assert!(minimal_manifest.predeploys.is_empty());
+        assert!(minimal_manifest.balances.is_empty());
+
+        let err = serde_json::from_value::<GenesisManifest>(serde_json::json!({
+            "chain_id": 270,
+            "unknown": 1,
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown"), "{err}");
+    }
+
+    #[test]
+    fn manifest_hash_depends_on_all_fields() {
+        let manifest = GenesisManifest::mock();
+        assert_eq!(manifest.hash(), GenesisManifest::mock().hash());
+
+        let mut modified_manifest = manifest.clone();
+        modified_manifest.balances[1].amount += U256::one();
+        assert_ne!(modified_manifest.hash(), manifest.hash());
+
+        let mut modified_manifest = manifest.clone();
+        modified_manifest.predeploys[0]
+            .storage
+            .insert(H256::repeat_byte(1), H256::repeat_byte(2));
+        assert_ne!(modified_manifest.hash(), manifest.hash());
+
+        let mut modified_manifest = manifest.clone();
+        modified_manifest.base_token = None;
+        assert_ne!(modified_manifest.hash(), manifest.hash());
+    }
+
+    #[test]
+    fn validating_manifest() {
+        let system_contracts = get_system_smart_contracts();
+        let chain_id = L2ChainId::from(270);
+        GenesisManifest::mock()
+            .validate(chain_id, &system_contracts)
+            .unwrap();
+
+        let err = GenesisManifest::mock()
+            .validate(L2ChainId::from(271), &system_contracts)
+            .unwrap_err();
+        assert!(err.to_string().contains("chain ID"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        manifest.predeploys[0].address = Address::from_low_u64_be(0x8001);
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("kernel space"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        let predeploy = manifest.predeploys[0].clone();
+        manifest.predeploys.push(predeploy);
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("multiple times"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        manifest.predeploys[0].bytecode = Bytes(vec![1; 64]);
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("invalid bytecode"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        manifest.balances[1].amount = U256::zero();
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("is zero"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        manifest.balances[1].amount = U256::MAX;
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("overflows"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        manifest.balances[1].address = manifest.balances[0].address;
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("multiple times"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        manifest.balances[1].token = Some(Address::repeat_byte(0x24));
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("not predeployed"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        manifest.predeploys[0].erc20 = None;
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("ERC-20 storage layout"), "{err}");
+
+        let mut manifest = GenesisManifest::mock();
+        manifest.predeploys[0]
+            .storage
+            .insert(H256::from_low_u64_be(2), H256::repeat_byte(1));
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("overlaps"), "{err}");
+    }
+
+    #[test]
+    fn base_token_balances_are_only_allowed_without_l1() {
+        let system_contracts = get_system_smart_contracts();
+        let chain_id = L2ChainId::from(270);
+        let mut manifest = GenesisManifest::mock();
+        manifest.balances[1].token = None;
+
+        let err = manifest.validate(chain_id, &system_contracts).unwrap_err();
+        assert!(err.to_string().contains("not backed"), "{err}");
+        manifest
+            .validate_with_base_token_balances(chain_id, &system_contracts)
+            .unwrap();
+
+        let logs = manifest.storage_logs();
+        let logs: HashMap<_, _> = logs.into_iter().map(|log| (log.key, log.value)).collect();
+        let balance_key = storage_key_for_eth_balance(&Address::repeat_byte(0x02));
+        assert_eq!(logs[&balance_key], u256_to_h256(500.into()));
+        let total_supply_key = StorageKey::new(
+            AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
+            H256::from_low_u64_be(1),
+        );
+        assert_eq!(logs[&total_supply_key], u256_to_h256(500.into()));
+    }
+
+    #[test]
+    fn manifest_storage_logs() {
+        let manifest = GenesisManifest::mock();
+        let logs = manifest.storage_logs();
+        let logs: HashMap<_, _> = logs.into_iter().map(|log| (log.key, log.value)).collect();
+
+        let predeploy_address = Address::repeat_byte(0x23);
+        assert_eq!(
+            logs[&get_code_key(&predeploy_address)],
+            hash_bytecode(&mock_bytecode().0)
+        );
+        let slot_key = StorageKey::new(AccountTreeId::new(predeploy_address), H256::zero());
+        assert_eq!(logs[&slot_key], H256::repeat_byte(0xff));
+
+        let mut key_preimage = [0_u8; 64];
+        key_preimage[12..32].copy_from_slice(&[0x01; 20]);
+        key_preimage[63] = 1; // `balances_slot`
+        let balance_key = StorageKey::new(
+            AccountTreeId::new(predeploy_address),
+            H256(keccak256(&key_preimage)),
+        );
+        assert_eq!(logs[&balance_key], u256_to_h256(1_000.into()));
+        let total_supply_key = StorageKey::new(
+            AccountTreeId::new(predeploy_address),
+            H256::from_low_u64_be(2),
+        );
+        assert_eq!(logs[&total_supply_key], u256_to_h256(1_500.into()));
+        let base_total_supply_key = storage_key_for_eth_total_supply();
+        assert!(!logs.contains_key(&base_total_supply_key));
+        assert_eq!(logs[&GenesisManifest::hash_key()], manifest.hash());
+    }
+}
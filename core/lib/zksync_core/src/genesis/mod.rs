@@ -2,6 +2,8 @@
 //! It initializes the Merkle tree with the basic setup (such as fields of special service accounts),
 //! setups the required databases, and outputs the data required to initialize a smart contract.
 
+use std::collections::HashMap;
+
 use anyhow::Context as _;
 use multivm::{
     utils::get_max_gas_per_pubdata_byte,
@@ -30,8 +32,13 @@ use zksync_types::{
 };
 use zksync_utils::{be_words_to_bytes, bytecode::hash_bytecode, h256_to_u256, u256_to_h256};
 
+pub use self::manifest::{
+    BaseTokenParams, Erc20Layout, GenesisFeeParams, GenesisManifest, InitialBalance, Predeploy,
+};
 use crate::metadata_calculator::L1BatchWithLogs;
 
+mod manifest;
+
 #[derive(Debug, Clone)]
 pub struct GenesisParams {
     pub first_validator: Address,
@@ -40,6 +47,8 @@ pub struct GenesisParams {
     pub system_contracts: Vec<DeployedContract>,
     pub first_verifier_address: Address,
     pub first_l1_verifier_config: L1VerifierConfig,
+    /// Manifest customizing the genesis state. If not set, the default genesis state is created.
+    pub manifest: Option<GenesisManifest>,
 }
 
 impl GenesisParams {
@@ -54,6 +63,7 @@ impl GenesisParams {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::zero(),
+            manifest: None,
         }
     }
 }
@@ -84,7 +94,13 @@ pub async fn ensure_genesis_state(
         system_contracts,
         first_verifier_address,
         first_l1_verifier_config,
+        manifest,
     } = genesis_params;
+    if let Some(manifest) = manifest {
+        manifest
+            .validate(zksync_chain_id, system_contracts)
+            .context("invalid genesis manifest")?;
+    }
 
     let base_system_contracts_hashes = base_system_contracts.hashes();
 
@@ -97,6 +113,7 @@ pub async fn ensure_genesis_state(
         system_contracts,
         *first_l1_verifier_config,
         *first_verifier_address,
+        manifest.as_ref(),
     )
     .await?;
     tracing::info!("chain_schema_genesis is complete");
@@ -146,6 +163,9 @@ pub async fn ensure_genesis_state(
         "CHAIN_STATE_KEEPER_DEFAULT_AA_HASH={:?}",
         base_system_contracts_hashes.default_aa
     );
+    if let Some(manifest) = manifest {
+        println!("CONTRACTS_GENESIS_MANIFEST_HASH={:?}", manifest.hash());
+    }
 
     Ok(genesis_root_hash)
 }
@@ -176,8 +196,10 @@ async fn insert_system_contracts(
     storage: &mut StorageProcessor<'_>,
     contracts: &[DeployedContract],
     chain_id: L2ChainId,
+    manifest: Option<&GenesisManifest>,
 ) -> anyhow::Result<()> {
     let system_context_init_logs = (H256::default(), get_system_context_init_logs(chain_id));
+    let manifest_logs = manifest.map(|manifest| (H256::default(), manifest.storage_logs()));
 
     let storage_logs: Vec<_> = contracts
         .iter()
@@ -190,6 +212,7 @@ async fn insert_system_contracts(
             )
         })
         .chain(Some(system_context_init_logs))
+        .chain(manifest_logs)
         .collect();
 
     let mut transaction = storage.start_transaction().await?;
@@ -272,10 +295,13 @@ async fn insert_system_contracts(
         .apply_storage_logs(&storage_logs)
         .await;
 
-    let factory_deps = contracts
+    let mut factory_deps: HashMap<_, _> = contracts
         .iter()
         .map(|c| (hash_bytecode(&c.bytecode), c.bytecode.clone()))
         .collect();
+    if let Some(manifest) = manifest {
+        factory_deps.extend(manifest.factory_deps());
+    }
     transaction
         .factory_deps_dal()
         .insert_factory_deps(MiniblockNumber(0), &factory_deps)
@@ -296,6 +322,7 @@ pub(crate) async fn create_genesis_l1_batch(
     system_contracts: &[DeployedContract],
     l1_verifier_config: L1VerifierConfig,
     verifier_address: Address,
+    manifest: Option<&GenesisManifest>,
 ) -> anyhow::Result<()> {
     let version = ProtocolVersion {
         id: protocol_version,
@@ -313,6 +340,13 @@ pub(crate) async fn create_genesis_l1_batch(
         protocol_version,
     );
 
+    let fee_params = manifest.and_then(|manifest| manifest.fee_params);
+    let (base_fee_per_gas, batch_fee_input) =
+        fee_params.map_or((0, BatchFeeInput::l1_pegged(0, 0)), |params| {
+            let batch_fee_input =
+                BatchFeeInput::l1_pegged(params.l1_gas_price, params.minimal_l2_gas_price);
+            (params.minimal_l2_gas_price, batch_fee_input)
+        });
     let genesis_miniblock_header = MiniblockHeader {
         number: MiniblockNumber(0),
         timestamp: 0,
@@ -320,9 +354,9 @@ pub(crate) async fn create_genesis_l1_batch(
         l1_tx_count: 0,
         l2_tx_count: 0,
         fee_account_address: first_validator_address,
-        base_fee_per_gas: 0,
+        base_fee_per_gas,
         gas_per_pubdata_limit: get_max_gas_per_pubdata_byte(protocol_version.into()),
-        batch_fee_input: BatchFeeInput::l1_pegged(0, 0),
+        batch_fee_input,
         base_system_contracts_hashes: base_system_contracts.hashes(),
         protocol_version: Some(protocol_version),
         virtual_blocks: 0,
@@ -358,25 +392,37 @@ pub(crate) async fn create_genesis_l1_batch(
         .context("failed assigning genesis miniblock to L1 batch")?;
 
     insert_base_system_contracts_to_factory_deps(&mut transaction, base_system_contracts).await?;
-    insert_system_contracts(&mut transaction, system_contracts, chain_id)
+    insert_system_contracts(&mut transaction, system_contracts, chain_id, manifest)
         .await
         .context("cannot insert system contracts")?;
-    add_eth_token(&mut transaction).await?;
+    let base_token = manifest.and_then(|manifest| manifest.base_token.as_ref());
+    add_eth_token(&mut transaction, base_token).await?;
 
     transaction.commit().await?;
     Ok(())
 }
 
-async fn add_eth_token(transaction: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+async fn add_eth_token(
+    transaction: &mut StorageProcessor<'_>,
+    base_token: Option<&BaseTokenParams>,
+) -> anyhow::Result<()> {
     assert!(transaction.in_transaction()); // sanity check
-    let eth_token = TokenInfo {
-        l1_address: ETHEREUM_ADDRESS,
-        l2_address: ETHEREUM_ADDRESS,
-        metadata: TokenMetadata {
+    let metadata = base_token.map_or_else(
+        || TokenMetadata {
             name: "Ether".to_string(),
             symbol: "ETH".to_string(),
             decimals: 18,
         },
+        |params| TokenMetadata {
+            name: params.name.clone(),
+            symbol: params.symbol.clone(),
+            decimals: params.decimals,
+        },
+    );
+    let eth_token = TokenInfo {
+        l1_address: ETHEREUM_ADDRESS,
+        l2_address: ETHEREUM_ADDRESS,
+        metadata,
     };
 
     transaction
@@ -479,6 +525,7 @@ mod tests {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::random(),
+            manifest: None,
         };
         ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
             .await
@@ -512,6 +559,7 @@ mod tests {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::random(),
+            manifest: None,
         };
         ensure_genesis_state(&mut conn, L2ChainId::max(), &params)
             .await
@@ -540,4 +588,82 @@ mod tests {
             .unwrap();
         assert!(!conn.blocks_dal().is_genesis_needed().await.unwrap());
     }
+
+    #[tokio::test]
+    async fn running_genesis_with_manifest() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let default_root_hash =
+            ensure_genesis_state(&mut conn, L2ChainId::from(270), &GenesisParams::mock())
+                .await
+                .unwrap();
+        drop(conn);
+
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let manifest = GenesisManifest::mock();
+        let params = GenesisParams {
+            manifest: Some(manifest.clone()),
+            ..GenesisParams::mock()
+        };
+        let root_hash = ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
+            .await
+            .unwrap();
+        assert_ne!(root_hash, default_root_hash);
+
+        let predeploy = &manifest.predeploys[0];
+        let code_key = get_code_key(&predeploy.address);
+        let bytecode_hash = conn.storage_web3_dal().get_value(&code_key).await.unwrap();
+        assert_eq!(bytecode_hash, hash_bytecode(&predeploy.bytecode.0));
+        let bytecode = conn.factory_deps_dal().get_factory_dep(bytecode_hash).await;
+        assert_eq!(bytecode.unwrap(), predeploy.bytecode.0);
+
+        for balance in &manifest.balances {
+            let balance_key = manifest.balance_key(balance);
+            let value = conn
+                .storage_web3_dal()
+                .get_value(&balance_key)
+                .await
+                .unwrap();
+            assert_eq!(h256_to_u256(value), balance.amount);
+        }
+        let manifest_hash = conn
+            .storage_web3_dal()
+            .get_value(&GenesisManifest::hash_key())
+            .await
+            .unwrap();
+        assert_eq!(manifest_hash, manifest.hash());
+
+        let genesis_miniblock = conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(genesis_miniblock.base_fee_per_gas, 100_000_000);
+        let tokens = conn
+            .tokens_web3_dal()
+            .get_well_known_tokens()
+            .await
+            .unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].metadata.symbol, "IDO");
+    }
+
+    #[tokio::test]
+    async fn running_genesis_with_invalid_manifest() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.blocks_dal().delete_genesis().await.unwrap();
+
+        let params = GenesisParams {
+            manifest: Some(GenesisManifest::mock()),
+            ..GenesisParams::mock()
+        };
+        let err = ensure_genesis_state(&mut conn, L2ChainId::from(271), &params)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("chain ID"), "{err:#}");
+        assert!(conn.blocks_dal().is_genesis_needed().await.unwrap());
+    }
 }
@@ -0,0 +1,171 @@
+//! VM invocation for the in-memory node. All functions in this module are blocking.
+
+use std::collections::HashMap;
+
+use multivm::{
+    interface::{
+        ExecutionResult, Halt, L1BatchEnv, L2BlockEnv, SystemEnv, TxExecutionMode,
+        VmExecutionResultAndLogs, VmInterface,
+    },
+    vm_latest::{constants::BLOCK_GAS_LIMIT, HistoryDisabled},
+    VmInstance,
+};
+use zksync_contracts::BaseSystemContracts;
+use zksync_state::{ReadStorage, StorageView, WriteStorage};
+use zksync_system_constants::{PUBLISH_BYTECODE_OVERHEAD, ZKPORTER_IS_AVAILABLE};
+use zksync_types::{
+    fee_model::BatchFeeInput,
+    get_nonce_key,
+    utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
+    Address, L2ChainId, Nonce, ProtocolVersionId, StorageKey, StorageValue, Transaction, H256,
+    U256,
+};
+use zksync_utils::{
+    bytecode::{compress_bytecode, hash_bytecode},
+    h256_to_u256, u256_to_h256,
+};
+
+use super::state::{BlockEnv, StorageAtBlock};
+
+/// Parameters shared by all VM invocations.
+#[derive(Debug, Clone)]
+pub(super) struct VmParams {
+    pub chain_id: L2ChainId,
+    pub protocol_version: ProtocolVersionId,
+    pub fee_account: Address,
+}
+
+/// Arguments of a single transaction execution.
+#[derive(Debug, Clone)]
+pub(super) struct ExecutionArgs {
+    pub execution_mode: TxExecutionMode,
+    pub base_system_contracts: BaseSystemContracts,
+    pub fee_input: BatchFeeInput,
+    pub enforced_base_fee: Option<u64>,
+    pub enforced_nonce: Option<Nonce>,
+    pub added_balance: U256,
+    /// Whether to execute the block tip after the transaction, so that all changes made by the sealed L1 batch
+    /// are reflected in [`ExecutionOutput::storage_changes`].
+    pub finish_batch: bool,
+}
+
+impl ExecutionArgs {
+    pub fn new(
+        execution_mode: TxExecutionMode,
+        base_system_contracts: BaseSystemContracts,
+        fee_input: BatchFeeInput,
+    ) -> Self {
+        Self {
+            execution_mode,
+            base_system_contracts,
+            fee_input,
+            enforced_base_fee: None,
+            enforced_nonce: None,
+            added_balance: U256::zero(),
+            finish_batch: false,
+        }
+    }
+}
+
+/// Output of a transaction execution.
+#[derive(Debug)]
+pub(super) struct ExecutionOutput {
+    pub result: VmExecutionResultAndLogs,
+    pub storage_changes: HashMap<StorageKey, StorageValue>,
+}
+
+/// Executes a single transaction in a new L1 batch consisting of a single L2 block with the specified environment.
+pub(super) fn execute_tx(
+    storage: StorageAtBlock<'_>,
+    params: &VmParams,
+    block_env: BlockEnv,
+    args: &ExecutionArgs,
+    tx: Transaction,
+) -> ExecutionOutput {
+    let mut storage_view = StorageView::new(storage);
+    if let Some(nonce) = args.enforced_nonce {
+        let nonce_key = get_nonce_key(&tx.initiator_account());
+        let full_nonce = storage_view.read_value(&nonce_key);
+        let (_, deployment_nonce) = decompose_full_nonce(h256_to_u256(full_nonce));
+        let enforced_full_nonce = nonces_to_full_nonce(U256::from(nonce.0), deployment_nonce);
+        storage_view.set_value(nonce_key, u256_to_h256(enforced_full_nonce));
+    }
+    if !args.added_balance.is_zero() {
+        let balance_key = storage_key_for_eth_balance(&tx.payer());
+        let balance = h256_to_u256(storage_view.read_value(&balance_key));
+        storage_view.set_value(balance_key, u256_to_h256(balance + args.added_balance));
+    }
+
+    let system_env = SystemEnv {
+        zk_porter_available: ZKPORTER_IS_AVAILABLE,
+        version: params.protocol_version,
+        base_system_smart_contracts: args.base_system_contracts.clone(),
+        gas_limit: BLOCK_GAS_LIMIT,
+        execution_mode: args.execution_mode,
+        default_validation_computational_gas_limit: BLOCK_GAS_LIMIT,
+        chain_id: params.chain_id,
+    };
+    let l1_batch_env = L1BatchEnv {
+        // Since each block is sealed into its own L1 batch, we can use the previous block hash as the batch hash.
+        previous_batch_hash: Some(block_env.prev_block_hash),
+        number: block_env.l1_batch_number,
+        timestamp: block_env.timestamp,
+        fee_input: args.fee_input,
+        fee_account: params.fee_account,
+        enforced_base_fee: args.enforced_base_fee,
+        first_l2_block: L2BlockEnv {
+            number: block_env.number.0,
+            timestamp: block_env.timestamp,
+            prev_block_hash: block_env.prev_block_hash,
+            max_virtual_blocks_to_create: 1,
+        },
+    };
+
+    let storage_view = storage_view.to_rc_ptr();
+    let mut vm: VmInstance<_, HistoryDisabled> =
+        VmInstance::new(l1_batch_env, system_env, storage_view.clone());
+    let (published_bytecodes, mut result) =
+        vm.execute_transaction_with_bytecode_compression(tx, true);
+    if published_bytecodes.is_err() {
+        // Same as in the state keeper: the transaction is rejected, so that the initiator doesn't pay the fee.
+        result.result = ExecutionResult::Halt {
+            reason: Halt::FailedToPublishCompressedBytecodes,
+        };
+    }
+    let is_halted = matches!(result.result, ExecutionResult::Halt { .. });
+    if args.finish_batch && !is_halted {
+        vm.finish_batch();
+    }
+    drop(vm);
+
+    let storage_changes = storage_view.borrow().modified_storage_keys().clone();
+    ExecutionOutput {
+        result,
+        storage_changes,
+    }
+}
+
+/// Returns the amount of pubdata that the transaction will spend on publishing its factory deps.
+pub(super) fn pubdata_for_factory_deps(
+    storage: &mut StorageAtBlock<'_>,
+    factory_deps: &[Vec<u8>],
+) -> u32 {
+    let effective_lengths = factory_deps.iter().map(|bytecode| {
+        if storage.is_bytecode_known(&hash_bytecode(bytecode)) {
+            return 0;
+        }
+        let length =
+            compress_bytecode(bytecode).map_or(bytecode.len(), |compressed| compressed.len());
+        length as u32 + PUBLISH_BYTECODE_OVERHEAD
+    });
+    effective_lengths.sum()
+}
+
+/// Returns hashes and bytecodes of the factory deps of a transaction.
+pub(super) fn factory_deps(tx: &Transaction) -> Vec<(H256, Vec<u8>)> {
+    let factory_deps = tx.execute.factory_deps.as_deref().unwrap_or_default();
+    factory_deps
+        .iter()
+        .map(|bytecode| (hash_bytecode(bytecode), bytecode.clone()))
+        .collect()
+}
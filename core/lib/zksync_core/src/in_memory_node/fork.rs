@@ -0,0 +1,313 @@
+//! Forking mode of the in-memory node: the state absent locally is lazily fetched from a remote node
+//! at a pinned block.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::runtime::Handle;
+use zksync_contracts::{BaseSystemContracts, SystemContractCode};
+use zksync_types::{
+    api::{self, BlockDetails, BlockIdVariant, BlockNumber, TransactionVariant},
+    L2ChainId, MiniblockNumber, ProtocolVersionId, StorageKey, StorageValue, H256,
+};
+use zksync_utils::{bytecode::hash_bytecode, bytes_to_be_words, h256_to_u256};
+use zksync_web3_decl::{
+    error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
+    jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+};
+
+use super::state::StoredBlock;
+
+/// Source of the remote state for the forking mode.
+#[async_trait]
+pub trait ForkSource: 'static + Send + Sync + fmt::Debug {
+    async fn fetch_chain_id(&self) -> EnrichedClientResult<L2ChainId>;
+
+    async fn fetch_block_number(&self) -> EnrichedClientResult<MiniblockNumber>;
+
+    async fn fetch_block(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<api::Block<TransactionVariant>>>;
+
+    async fn fetch_block_details(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<BlockDetails>>;
+
+    async fn fetch_storage_value(
+        &self,
+        key: &StorageKey,
+        block: MiniblockNumber,
+    ) -> EnrichedClientResult<StorageValue>;
+
+    async fn fetch_bytecode(&self, hash: H256) -> EnrichedClientResult<Option<Vec<u8>>>;
+}
+
+impl dyn ForkSource {
+    /// Creates a fork source based on JSON-RPC.
+    pub fn json_rpc(url: &str) -> anyhow::Result<HttpClient> {
+        HttpClientBuilder::default().build(url).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl ForkSource for HttpClient {
+    async fn fetch_chain_id(&self) -> EnrichedClientResult<L2ChainId> {
+        let chain_id = self.chain_id().rpc_context("chain_id").await?;
+        L2ChainId::try_from(chain_id.as_u64())
+            .map_err(|err| EnrichedClientError::custom(err, "L2ChainId::try_from"))
+    }
+
+    async fn fetch_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        let number = self
+            .get_block_number()
+            .rpc_context("get_block_number")
+            .await?;
+        let number = u32::try_from(number)
+            .map_err(|err| EnrichedClientError::custom(err, "u32::try_from"))?;
+        Ok(MiniblockNumber(number))
+    }
+
+    async fn fetch_block(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<api::Block<TransactionVariant>>> {
+        self.get_block_by_number(BlockNumber::Number(number.0.into()), false)
+            .rpc_context("get_block_by_number")
+            .with_arg("number", &number)
+            .await
+    }
+
+    async fn fetch_block_details(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<BlockDetails>> {
+        self.get_block_details(number)
+            .rpc_context("get_block_details")
+            .with_arg("number", &number)
+            .await
+    }
+
+    async fn fetch_storage_value(
+        &self,
+        key: &StorageKey,
+        block: MiniblockNumber,
+    ) -> EnrichedClientResult<StorageValue> {
+        let block_id = BlockIdVariant::BlockNumber(BlockNumber::Number(block.0.into()));
+        self.get_storage_at(*key.address(), h256_to_u256(*key.key()), Some(block_id))
+            .rpc_context("get_storage_at")
+            .with_arg("key", key)
+            .with_arg("block", &block)
+            .await
+    }
+
+    async fn fetch_bytecode(&self, hash: H256) -> EnrichedClientResult<Option<Vec<u8>>> {
+        let bytecode = self
+            .get_bytecode_by_hash(hash)
+            .rpc_context("get_bytecode_by_hash")
+            .with_arg("hash", &hash)
+            .await?;
+        if let Some(bytecode) = &bytecode {
+            let actual_bytecode_hash = hash_bytecode(bytecode);
+            if actual_bytecode_hash != hash {
+                return Err(EnrichedClientError::custom(
+                    "Got invalid bytecode from fork source",
+                    "get_bytecode_by_hash",
+                )
+                .with_arg("hash", &hash)
+                .with_arg("actual_bytecode_hash", &actual_bytecode_hash));
+            }
+        }
+        Ok(bytecode)
+    }
+}
+
+/// Remote state at the fork block together with the chain params the forked node should use.
+#[derive(Debug)]
+pub(super) struct ForkedChain {
+    pub chain_id: L2ChainId,
+    pub protocol_version: ProtocolVersionId,
+    pub base_system_contracts: BaseSystemContracts,
+    /// Fork block; it becomes the root block of the local chain.
+    pub root_block: StoredBlock,
+    pub storage: ForkStorage,
+}
+
+impl ForkedChain {
+    /// Loads the fork block and chain params from the source. If `block_number` is not specified,
+    /// the latest block of the source is used.
+    pub async fn load(
+        source: Arc<dyn ForkSource>,
+        block_number: Option<MiniblockNumber>,
+    ) -> anyhow::Result<Self> {
+        let chain_id = source.fetch_chain_id().await?;
+        let block_number = match block_number {
+            Some(number) => number,
+            None => source.fetch_block_number().await?,
+        };
+        let block = source
+            .fetch_block(block_number)
+            .await?
+            .with_context(|| format!("block #{block_number} is missing on the fork source"))?;
+        let details = source
+            .fetch_block_details(block_number)
+            .await?
+            .with_context(|| {
+                format!("details for block #{block_number} are missing on the fork source")
+            })?;
+        let protocol_version = details
+            .protocol_version
+            .with_context(|| format!("block #{block_number} has unknown protocol version"))?;
+
+        let contract_hashes = details.base.base_system_contracts_hashes;
+        let bootloader_bytecode = source
+            .fetch_bytecode(contract_hashes.bootloader)
+            .await?
+            .context("bootloader bytecode is missing on the fork source")?;
+        let default_aa_bytecode = source
+            .fetch_bytecode(contract_hashes.default_aa)
+            .await?
+            .context("default AA bytecode is missing on the fork source")?;
+        let base_system_contracts = BaseSystemContracts {
+            bootloader: SystemContractCode {
+                code: bytes_to_be_words(bootloader_bytecode),
+                hash: contract_hashes.bootloader,
+            },
+            default_aa: SystemContractCode {
+                code: bytes_to_be_words(default_aa_bytecode),
+                hash: contract_hashes.default_aa,
+            },
+        };
+
+        // The local chain starts a new L1 batch after the batch the fork block belongs to, regardless
+        // of whether this batch is sealed on the source.
+        let root_block = StoredBlock::new(
+            block_number,
+            details.l1_batch_number,
+            block.hash,
+            block.parent_hash,
+            block.timestamp.as_u64(),
+            block.base_fee_per_gas.as_u64(),
+        );
+        tracing::info!(
+            "Forked chain with L2 chain ID {} at block #{block_number} (L1 batch #{}, protocol version {protocol_version:?})",
+            chain_id.as_u64(),
+            details.l1_batch_number
+        );
+        Ok(Self {
+            chain_id,
+            protocol_version,
+            base_system_contracts,
+            root_block,
+            storage: ForkStorage::new(source, block_number, Handle::current()),
+        })
+    }
+}
+
+/// Remote storage pinned at the fork block. Fetched storage slots and bytecodes are cached; since
+/// the fork block never changes, cached values never become stale.
+///
+/// Must be used from blocking threads only, since it blocks on the remote requests.
+pub(super) struct ForkStorage {
+    source: Arc<dyn ForkSource>,
+    block_number: MiniblockNumber,
+    rt_handle: Handle,
+    values: Mutex<HashMap<StorageKey, StorageValue>>,
+    factory_deps: Mutex<HashMap<H256, Option<Vec<u8>>>>,
+}
+
+impl fmt::Debug for ForkStorage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ForkStorage")
+            .field("source", &self.source)
+            .field("block_number", &self.block_number)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ForkStorage {
+    pub fn new(
+        source: Arc<dyn ForkSource>,
+        block_number: MiniblockNumber,
+        rt_handle: Handle,
+    ) -> Self {
+        Self {
+            source,
+            block_number,
+            rt_handle,
+            values: Mutex::default(),
+            factory_deps: Mutex::default(),
+        }
+    }
+
+    pub fn block_number(&self) -> MiniblockNumber {
+        self.block_number
+    }
+
+    /// Returns the value of the storage slot at the fork block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value cannot be fetched from the source, similarly to how Postgres-backed storage
+    /// panics on DB errors.
+    pub fn read_value(&self, key: &StorageKey) -> StorageValue {
+        let cached_value = self
+            .values
+            .lock()
+            .expect("fork cache is poisoned")
+            .get(key)
+            .copied();
+        if let Some(value) = cached_value {
+            return value;
+        }
+
+        let value = self
+            .rt_handle
+            .block_on(self.source.fetch_storage_value(key, self.block_number))
+            .unwrap_or_else(|err| {
+                panic!("failed fetching storage slot {key:?} from fork source: {err}")
+            });
+        self.values
+            .lock()
+            .expect("fork cache is poisoned")
+            .insert(*key, value);
+        value
+    }
+
+    /// Returns the bytecode with the specified hash known to the source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bytecode cannot be fetched from the source.
+    pub fn load_factory_dep(&self, hash: H256) -> Option<Vec<u8>> {
+        let cached_bytecode = self
+            .factory_deps
+            .lock()
+            .expect("fork cache is poisoned")
+            .get(&hash)
+            .cloned();
+        if let Some(bytecode) = cached_bytecode {
+            return bytecode;
+        }
+
+        let bytecode = self
+            .rt_handle
+            .block_on(self.source.fetch_bytecode(hash))
+            .unwrap_or_else(|err| {
+                panic!("failed fetching bytecode {hash:?} from fork source: {err}")
+            });
+        self.factory_deps
+            .lock()
+            .expect("fork cache is poisoned")
+            .insert(hash, bytecode.clone());
+        bytecode
+    }
+}
@@ -0,0 +1,651 @@
+//! In-memory node: a single-process node without Postgres intended for local development and CI testing
+//! of applications. The node runs the VM, a simple block producer and the Web3 JSON-RPC API (`eth`, `zks`,
+//! `net` and `web3` namespaces).
+//!
+//! # Block production
+//!
+//! Each submitted transaction is executed and sealed immediately, in its own miniblock. Each miniblock is sealed
+//! into its own L1 batch, so the API never returns pending transactions or blocks.
+//! Transactions failing validation are rejected and are not included into a block; reverted transactions
+//! are included with a failed receipt, same as on the real chain.
+//!
+//! # Forking mode
+//!
+//! A node created with [`InMemoryNode::fork()`] starts on top of a block of a remote chain (e.g., the live L3)
+//! rather than on top of the genesis block. Storage slots and bytecodes not modified locally are lazily fetched
+//! from the remote node at the fork block and cached, so transactions and calls can be simulated against
+//! the remote state. The chain ID, protocol version and base system contracts are taken from the remote chain
+//! as well. Blocks and transactions preceding the fork block are not available via the API.
+//!
+//! # Limitations
+//!
+//! - The state is stored in memory only and is lost when the node is stopped.
+//! - There is no L1 integration: L1 batches are never committed, proven or executed, L1->L2 transactions
+//!   are not supported, and L2->L1 log proofs are not available.
+//! - Calls and gas estimation are executed in a new block on top of the requested block. Block-specific
+//!   values (e.g., the block timestamp) observed by contracts may thus differ from the values seen
+//!   by transactions in the requested block.
+//!
+//! The node loads system contracts from `$ZKSYNC_HOME`, so `ZKSYNC_HOME` must point to the repository root
+//! with compiled contracts. In the forking mode, contracts for `eth_call` and gas estimation are still loaded
+//! from `$ZKSYNC_HOME`.
+
+use std::{
+    cmp,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use anyhow::Context as _;
+use chrono::Utc;
+use multivm::{
+    interface::{ExecutionResult, TxExecutionMode},
+    utils::{adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead},
+    vm_latest::constants::ETH_CALL_GAS_LIMIT,
+};
+use tokio::sync::watch;
+use zksync_contracts::BaseSystemContracts;
+use zksync_state::{InMemoryStorage, ReadStorage};
+use zksync_system_constants::{
+    CONTRACT_DEPLOYER_ADDRESS, DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE, MAX_ENCODED_TX_SIZE,
+};
+use zksync_types::{
+    api,
+    block::MiniblockHasher,
+    event::DEPLOY_EVENT_SIGNATURE,
+    fee::Fee,
+    fee_model::BatchFeeInput,
+    get_code_key, get_nonce_key,
+    l2::{error::TxCheckError, L2Tx},
+    system_contracts::get_system_smart_contracts,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
+    Address, ExecuteTransactionCommon, L1ChainId, L2ChainId, MiniblockNumber, PackedEthSignature,
+    ProtocolVersionId, Transaction, H256, MAX_L2_TX_GAS_LIMIT, U256, U64,
+};
+use zksync_utils::{bytecode::hash_bytecode, h256_to_account_address, h256_to_u256};
+use zksync_web3_decl::{
+    jsonrpsee::{server::ServerBuilder, RpcModule},
+    namespaces::{EthNamespaceServer, NetNamespaceServer, Web3NamespaceServer, ZksNamespaceServer},
+};
+
+pub use self::fork::ForkSource;
+use self::{
+    executor::{ExecutionArgs, VmParams},
+    fork::ForkedChain,
+    rpc::InstalledFilters,
+    state::{NodeState, StoredBlock, StoredTransaction},
+};
+use crate::{
+    api_server::{
+        tx_sender::{ApiCallResult, ApiContracts, SubmitTxError},
+        web3::namespaces::{NetNamespace, Web3Namespace},
+    },
+    genesis::GenesisManifest,
+};
+
+mod executor;
+mod fork;
+mod rpc;
+mod state;
+#[cfg(test)]
+mod tests;
+
+/// Configuration of the [`InMemoryNode`].
+#[derive(Debug, Clone)]
+pub struct InMemoryNodeConfig {
+    /// L2 chain ID of the node. Ignored in the forking mode; the chain ID of the remote chain is used instead.
+    pub chain_id: L2ChainId,
+    /// L1 chain ID reported by the `zks_L1ChainId` method. The node doesn't interact with L1.
+    pub l1_chain_id: L1ChainId,
+    /// Address to serve the HTTP JSON-RPC API on.
+    pub http_addr: SocketAddr,
+    /// L1 gas price (in wei) used to compute fees for all blocks.
+    pub l1_gas_price: u64,
+    /// Minimal L2 gas price (in wei) used to compute fees for all blocks.
+    pub minimal_l2_gas_price: u64,
+    /// Operator address receiving fees.
+    pub fee_account: Address,
+    /// Maximum size of an accepted transaction in bytes.
+    pub max_tx_size: usize,
+    /// Factor by which the gas limit found by binary search in gas estimation is multiplied.
+    pub estimate_gas_scale_factor: f64,
+    /// Acceptable overestimation for the binary search in gas estimation.
+    pub estimate_gas_acceptable_overestimation: u32,
+    /// Genesis manifest with predeploys and initial balances. If it specifies fee params, they override
+    /// `l1_gas_price` and `minimal_l2_gas_price`. Cannot be used in the forking mode.
+    pub genesis: Option<GenesisManifest>,
+}
+
+impl Default for InMemoryNodeConfig {
+    fn default() -> Self {
+        Self {
+            chain_id: L2ChainId::default(),
+            l1_chain_id: L1ChainId(9),
+            http_addr: ([127, 0, 0, 1], 8011).into(),
+            l1_gas_price: 1_000_000_000,
+            minimal_l2_gas_price: 250_000_000,
+            fee_account: Address::repeat_byte(0xfe),
+            max_tx_size: MAX_ENCODED_TX_SIZE,
+            estimate_gas_scale_factor: 1.3,
+            estimate_gas_acceptable_overestimation: 1_000,
+            genesis: None,
+        }
+    }
+}
+
+/// Base system contracts used by the node.
+#[derive(Debug)]
+struct NodeContracts {
+    execution: BaseSystemContracts,
+    eth_call: BaseSystemContracts,
+    estimate_gas: BaseSystemContracts,
+}
+
+#[derive(Debug)]
+struct NodeInner {
+    config: InMemoryNodeConfig,
+    vm_params: VmParams,
+    fee_input: BatchFeeInput,
+    base_fee: u64,
+    contracts: NodeContracts,
+    state: Mutex<NodeState>,
+    filters: Mutex<InstalledFilters>,
+}
+
+/// In-memory node. See the module-level docs for details.
+#[derive(Debug, Clone)]
+pub struct InMemoryNode(Arc<NodeInner>);
+
+impl InMemoryNode {
+    /// Creates a node on top of the genesis block.
+    pub fn new(config: InMemoryNodeConfig) -> anyhow::Result<Self> {
+        let protocol_version = ProtocolVersionId::latest();
+        let mut storage =
+            InMemoryStorage::with_system_contracts_and_chain_id(config.chain_id, hash_bytecode);
+        let mut fee_input =
+            BatchFeeInput::l1_pegged(config.l1_gas_price, config.minimal_l2_gas_price);
+        if let Some(manifest) = &config.genesis {
+            // The in-memory node doesn't settle on L1, so base token balances don't need to be backed by deposits.
+            manifest
+                .validate_with_base_token_balances(config.chain_id, &get_system_smart_contracts())
+                .context("invalid genesis manifest")?;
+            for log in manifest.storage_logs() {
+                storage.set_value(log.key, log.value);
+            }
+            for (hash, bytecode) in manifest.factory_deps() {
+                storage.store_factory_dep(hash, bytecode);
+            }
+            if let Some(fee_params) = manifest.fee_params {
+                fee_input = BatchFeeInput::l1_pegged(
+                    fee_params.l1_gas_price,
+                    fee_params.minimal_l2_gas_price,
+                );
+            }
+        }
+
+        let (base_fee, _) = derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+        let state = NodeState::new(storage, base_fee);
+        Ok(Self::from_state(
+            config,
+            protocol_version,
+            BaseSystemContracts::load_from_disk(),
+            fee_input,
+            state,
+        ))
+    }
+
+    /// Creates a node forked from a remote chain at the specified block (or the latest block of the remote chain
+    /// if not specified). See the module-level docs for details.
+    pub async fn fork(
+        mut config: InMemoryNodeConfig,
+        source: Arc<dyn ForkSource>,
+        block_number: Option<MiniblockNumber>,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            config.genesis.is_none(),
+            "genesis manifest cannot be used in the forking mode"
+        );
+        let forked_chain = ForkedChain::load(source, block_number)
+            .await
+            .context("failed loading fork block")?;
+        config.chain_id = forked_chain.chain_id;
+
+        let fee_input = BatchFeeInput::l1_pegged(config.l1_gas_price, config.minimal_l2_gas_price);
+        let state = NodeState::forked(forked_chain.storage, forked_chain.root_block);
+        Ok(Self::from_state(
+            config,
+            forked_chain.protocol_version,
+            forked_chain.base_system_contracts,
+            fee_input,
+            state,
+        ))
+    }
+
+    fn from_state(
+        config: InMemoryNodeConfig,
+        protocol_version: ProtocolVersionId,
+        execution_contracts: BaseSystemContracts,
+        fee_input: BatchFeeInput,
+        state: NodeState,
+    ) -> Self {
+        let (base_fee, _) = derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+        let api_contracts = ApiContracts::load_from_disk();
+        let contracts = NodeContracts {
+            execution: execution_contracts,
+            eth_call: api_contracts
+                .eth_call
+                .get_by_protocol_version(protocol_version),
+            estimate_gas: api_contracts
+                .estimate_gas
+                .get_by_protocol_version(protocol_version),
+        };
+        let vm_params = VmParams {
+            chain_id: config.chain_id,
+            protocol_version,
+            fee_account: config.fee_account,
+        };
+        Self(Arc::new(NodeInner {
+            config,
+            vm_params,
+            fee_input,
+            base_fee,
+            contracts,
+            state: Mutex::new(state),
+            filters: Mutex::default(),
+        }))
+    }
+
+    /// Returns the RPC module with all namespaces supported by the node.
+    pub fn rpc_module(&self) -> RpcModule<()> {
+        let mut rpc = RpcModule::new(());
+        rpc.merge(EthNamespaceServer::into_rpc(self.clone()))
+            .expect("Can't merge eth namespace");
+        rpc.merge(ZksNamespaceServer::into_rpc(self.clone()))
+            .expect("Can't merge zks namespace");
+        rpc.merge(NetNamespace::new(self.0.config.chain_id).into_rpc())
+            .expect("Can't merge net namespace");
+        rpc.merge(Web3Namespace.into_rpc())
+            .expect("Can't merge web3 namespace");
+        rpc
+    }
+
+    /// Serves the HTTP JSON-RPC API until a stop signal is received.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let addr = self.0.config.http_addr;
+        let server = ServerBuilder::default()
+            .http_only()
+            .build(addr)
+            .await
+            .with_context(|| format!("failed building HTTP JSON-RPC server on {addr}"))?;
+        let local_addr = server.local_addr().context("cannot get server address")?;
+        let server_handle = server.start(self.rpc_module());
+        tracing::info!(
+            "In-memory node with L2 chain ID {} is listening on {local_addr}",
+            self.0.config.chain_id.as_u64()
+        );
+
+        let close_handle = server_handle.clone();
+        tokio::spawn(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!(
+                    "Stop signal sender for in-memory node was dropped without sending a signal"
+                );
+            }
+            close_handle.stop().ok();
+        });
+        server_handle.stopped().await;
+        tracing::info!("In-memory node is shut down");
+        Ok(())
+    }
+
+    fn state(&self) -> MutexGuard<'_, NodeState> {
+        // The state can only be poisoned by a panic during VM execution (e.g., if the fork source is unavailable).
+        // The state is not modified during execution, so it's safe to continue using it.
+        self.0.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T, SubmitTxError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self) -> Result<T, SubmitTxError> + Send + 'static,
+    {
+        let this = self.clone();
+        tokio::task::spawn_blocking(move || f(&this))
+            .await
+            .context("in-memory node VM task panicked")?
+    }
+
+    fn expected_nonce(state: &NodeState, address: Address) -> u32 {
+        let latest_block = state.latest_block().number;
+        let full_nonce = state
+            .storage_at(latest_block)
+            .read_value(&get_nonce_key(&address));
+        let (nonce, _) = decompose_full_nonce(h256_to_u256(full_nonce));
+        nonce.as_u32()
+    }
+
+    /// Executes a transaction and seals it in a new block. Returns the transaction hash.
+    pub(crate) async fn submit_tx(&self, tx: L2Tx) -> Result<H256, SubmitTxError> {
+        self.run_blocking(move |this| this.seal_tx(tx)).await
+    }
+
+    fn seal_tx(&self, tx: L2Tx) -> Result<H256, SubmitTxError> {
+        let tx_hash = tx.hash();
+        if tx.common_data.fee.max_fee_per_gas < self.0.base_fee.into() {
+            return Err(SubmitTxError::MaxFeePerGasTooLow);
+        }
+        if tx.common_data.fee.max_priority_fee_per_gas > tx.common_data.fee.max_fee_per_gas {
+            return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee);
+        }
+
+        let mut state = self.state();
+        if state.transaction(&tx_hash).is_some() {
+            return Err(TxCheckError::TxDuplication(tx_hash).into());
+        }
+        let expected_nonce = Self::expected_nonce(&state, tx.initiator_account());
+        let nonce = tx.nonce().0;
+        if nonce < expected_nonce {
+            return Err(SubmitTxError::NonceIsTooLow(
+                expected_nonce,
+                expected_nonce,
+                nonce,
+            ));
+        } else if nonce > expected_nonce {
+            return Err(SubmitTxError::NonceIsTooHigh(
+                expected_nonce,
+                expected_nonce,
+                nonce,
+            ));
+        }
+
+        let parent = state.latest_block();
+        let block_env = state.next_block_env(parent);
+        let mut args = ExecutionArgs::new(
+            TxExecutionMode::VerifyExecute,
+            self.0.contracts.execution.clone(),
+            self.0.fee_input,
+        );
+        args.finish_batch = true;
+        let tx: Transaction = tx.into();
+        let output = executor::execute_tx(
+            state.storage_at(parent.number),
+            &self.0.vm_params,
+            block_env,
+            &args,
+            tx.clone(),
+        );
+        if let ExecutionResult::Halt { .. } = &output.result.result {
+            return Err(output.result.into_api_call_result().unwrap_err());
+        }
+
+        let mut hasher = MiniblockHasher::new(block_env.number, block_env.timestamp, parent.hash);
+        hasher.push_tx_hash(tx_hash);
+        let block_hash = hasher.finalize(self.0.vm_params.protocol_version);
+        let mut block = StoredBlock::new(
+            block_env.number,
+            block_env.l1_batch_number,
+            block_hash,
+            parent.hash,
+            block_env.timestamp,
+            self.0.base_fee,
+        );
+
+        let result = &output.result;
+        let gas_limit = tx.gas_limit();
+        let gas_used = gas_limit - U256::from(result.refunds.gas_refunded);
+        block.gas_used = gas_used;
+        let block_number = U64::from(block_env.number.0);
+        let l1_batch_number = U64::from(block_env.l1_batch_number.0);
+        let logs: Vec<_> = result
+            .logs
+            .events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| api::Log {
+                address: event.address,
+                topics: event.indexed_topics.clone(),
+                data: event.value.clone().into(),
+                block_hash: Some(block_hash),
+                block_number: Some(block_number),
+                l1_batch_number: Some(l1_batch_number),
+                transaction_hash: Some(tx_hash),
+                transaction_index: Some(0.into()),
+                log_index: Some(i.into()),
+                transaction_log_index: Some(i.into()),
+                log_type: None,
+                removed: Some(false),
+            })
+            .collect();
+        let l2_to_l1_logs = result
+            .logs
+            .user_l2_to_l1_logs
+            .iter()
+            .enumerate()
+            .map(|(i, log)| api::L2ToL1Log {
+                block_hash: Some(block_hash),
+                block_number,
+                l1_batch_number: Some(l1_batch_number),
+                log_index: i.into(),
+                transaction_index: 0.into(),
+                transaction_hash: tx_hash,
+                transaction_log_index: i.into(),
+                tx_index_in_l1_batch: Some(0.into()),
+                shard_id: log.0.shard_id.into(),
+                is_service: log.0.is_service,
+                sender: log.0.sender,
+                key: log.0.key,
+                value: log.0.value,
+            })
+            .collect();
+        let contract_address = result.logs.events.iter().find_map(|event| {
+            let is_deploy_event = event.address == CONTRACT_DEPLOYER_ADDRESS
+                && event.indexed_topics.first() == Some(&DEPLOY_EVENT_SIGNATURE);
+            is_deploy_event.then(|| h256_to_account_address(&event.indexed_topics[3]))
+        });
+        let receipt = api::TransactionReceipt {
+            transaction_hash: tx_hash,
+            transaction_index: 0.into(),
+            block_hash,
+            block_number,
+            l1_batch_tx_index: Some(0.into()),
+            l1_batch_number: Some(l1_batch_number),
+            from: tx.initiator_account(),
+            to: Some(tx.recipient_account()),
+            cumulative_gas_used: gas_used,
+            gas_used: Some(gas_used),
+            contract_address,
+            logs,
+            l2_to_l1_logs,
+            status: U64::from(u8::from(!result.result.is_failed())),
+            transaction_type: Some((tx.tx_format() as u32).into()),
+            effective_gas_price: Some(self.0.base_fee.into()),
+            ..api::TransactionReceipt::default()
+        };
+
+        let l2_tx = L2Tx::try_from(tx.clone()).expect("transaction is not an L2 transaction");
+        let gas_per_pubdata = l2_tx.common_data.fee.gas_per_pubdata_limit;
+        let mut api_tx = api::Transaction::from(l2_tx);
+        api_tx.block_hash = Some(block_hash);
+        api_tx.block_number = Some(block_number);
+        api_tx.transaction_index = Some(0.into());
+        api_tx.l1_batch_number = Some(l1_batch_number);
+        api_tx.l1_batch_tx_index = Some(0.into());
+        let factory_deps = executor::factory_deps(&tx);
+        let stored_tx = StoredTransaction {
+            raw: tx,
+            api: api_tx,
+            receipt,
+            gas_per_pubdata,
+            received_at: Utc::now(),
+        };
+
+        tracing::debug!(
+            "Sealed transaction {tx_hash:?} in block #{} (status: {})",
+            block_env.number,
+            if output.result.result.is_failed() {
+                "failed"
+            } else {
+                "success"
+            }
+        );
+        state.push_block(block, output.storage_changes, factory_deps, vec![stored_tx]);
+        Ok(tx_hash)
+    }
+
+    /// Executes a call on top of the specified block.
+    pub(crate) async fn call_tx(
+        &self,
+        tx: L2Tx,
+        block_number: MiniblockNumber,
+    ) -> Result<Vec<u8>, SubmitTxError> {
+        self.run_blocking(move |this| this.execute_call(tx, block_number))
+            .await
+    }
+
+    fn execute_call(
+        &self,
+        mut tx: L2Tx,
+        block_number: MiniblockNumber,
+    ) -> Result<Vec<u8>, SubmitTxError> {
+        let mut args = ExecutionArgs::new(
+            TxExecutionMode::EthCall,
+            self.0.contracts.eth_call.clone(),
+            self.0.fee_input,
+        );
+        args.enforced_base_fee = Some(tx.common_data.fee.max_fee_per_gas.as_u64());
+        if tx.common_data.signature.is_empty() {
+            tx.common_data.signature = PackedEthSignature::default().serialize_packed().into();
+        }
+        tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
+
+        let state = self.state();
+        let block = state
+            .block(block_number)
+            .ok_or_else(|| anyhow::anyhow!("block #{block_number} is not sealed"))?;
+        let block_env = state.next_block_env(block);
+        let output = executor::execute_tx(
+            state.storage_at(block_number),
+            &self.0.vm_params,
+            block_env,
+            &args,
+            tx.into(),
+        );
+        output.result.into_api_call_result()
+    }
+
+    /// Estimates the fee for a transaction on top of the latest block.
+    pub(crate) async fn estimate_tx_fee(&self, tx: L2Tx) -> Result<Fee, SubmitTxError> {
+        self.run_blocking(move |this| this.estimate_tx_fee_blocking(tx))
+            .await
+    }
+
+    fn estimate_tx_fee_blocking(&self, tx: L2Tx) -> Result<Fee, SubmitTxError> {
+        let protocol_version = self.0.vm_params.protocol_version;
+        let mut tx: Transaction = tx.into();
+        let fee_input = adjust_pubdata_price_for_tx(
+            self.0.fee_input,
+            tx.gas_per_pubdata_byte_limit(),
+            protocol_version.into(),
+        );
+        let (base_fee, gas_per_pubdata_byte) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+        if let ExecuteTransactionCommon::L2(common_data) = &mut tx.common_data {
+            common_data.fee.max_fee_per_gas = base_fee.into();
+            common_data.fee.max_priority_fee_per_gas = base_fee.into();
+            if common_data.signature.is_empty() {
+                common_data.signature = PackedEthSignature::default().serialize_packed().into();
+            }
+            common_data.fee.gas_per_pubdata_limit = U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
+        }
+
+        let state = self.state();
+        let parent = state.latest_block();
+        let block_env = state.next_block_env(parent);
+        let mut storage = state.storage_at(parent.number);
+        let initiator = tx.initiator_account();
+        // If the account doesn't have enough funds for transferring `tx.value`, there is no sense to estimate the fee.
+        let has_code = storage.read_value(&get_code_key(&initiator)) != H256::zero();
+        let balance = h256_to_u256(storage.read_value(&storage_key_for_eth_balance(&initiator)));
+        if !has_code && tx.execute.value > balance {
+            return Err(SubmitTxError::InsufficientFundsForTransfer);
+        }
+
+        let factory_deps = tx.execute.factory_deps.as_deref().unwrap_or_default();
+        let gas_for_bytecodes_pubdata =
+            executor::pubdata_for_factory_deps(&mut storage, factory_deps)
+                * gas_per_pubdata_byte as u32;
+
+        let estimate_gas_step = |tx_gas_limit: u32| {
+            let mut tx = tx.clone();
+            let gas_limit_with_overhead = tx_gas_limit
+                + derive_overhead(
+                    tx_gas_limit,
+                    gas_per_pubdata_byte as u32,
+                    tx.encoding_len(),
+                    tx.tx_format() as u8,
+                    protocol_version.into(),
+                );
+            if let ExecuteTransactionCommon::L2(common_data) = &mut tx.common_data {
+                common_data.fee.gas_limit = gas_limit_with_overhead.into();
+            }
+            let mut args = ExecutionArgs::new(
+                TxExecutionMode::EstimateFee,
+                self.0.contracts.estimate_gas.clone(),
+                fee_input,
+            );
+            args.enforced_base_fee = Some(base_fee);
+            args.enforced_nonce = tx.nonce();
+            args.added_balance = tx.gas_limit() * tx.max_fee_per_gas();
+            let output = executor::execute_tx(
+                state.storage_at(parent.number),
+                &self.0.vm_params,
+                block_env,
+                &args,
+                tx,
+            );
+            output.result
+        };
+
+        let mut lower_bound = 0;
+        let mut upper_bound = MAX_L2_TX_GAS_LIMIT as u32;
+        let acceptable_overestimation = self.0.config.estimate_gas_acceptable_overestimation;
+        while lower_bound + acceptable_overestimation < upper_bound {
+            let mid = (lower_bound + upper_bound) / 2;
+            // There is no way to distinct between errors due to out of gas
+            // or normal execution errors, so we just hope that increasing the
+            // gas limit will make the transaction successful.
+            let result = estimate_gas_step(gas_for_bytecodes_pubdata + mid);
+            if result.result.is_failed() {
+                lower_bound = mid + 1;
+            } else {
+                upper_bound = mid;
+            }
+        }
+
+        let tx_body_gas_limit = cmp::min(
+            MAX_L2_TX_GAS_LIMIT as u32,
+            ((upper_bound as f64) * self.0.config.estimate_gas_scale_factor) as u32,
+        );
+        let suggested_gas_limit = tx_body_gas_limit + gas_for_bytecodes_pubdata;
+        estimate_gas_step(suggested_gas_limit).into_api_call_result()?;
+
+        let overhead = derive_overhead(
+            suggested_gas_limit,
+            gas_per_pubdata_byte as u32,
+            tx.encoding_len(),
+            tx.tx_format() as u8,
+            protocol_version.into(),
+        );
+        let full_gas_limit = tx_body_gas_limit
+            .checked_add(gas_for_bytecodes_pubdata + overhead)
+            .ok_or_else(|| {
+                SubmitTxError::ExecutionReverted("exceeds block gas limit".to_owned(), vec![])
+            })?;
+        Ok(Fee {
+            max_fee_per_gas: base_fee.into(),
+            max_priority_fee_per_gas: 0u32.into(),
+            gas_limit: full_gas_limit.into(),
+            gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+        })
+    }
+}
@@ -0,0 +1,809 @@
+//! Implementation of `eth` and `zks` Web3 namespaces for the in-memory node.
+
+use std::collections::HashMap;
+
+use multivm::vm_latest::constants::BLOCK_GAS_LIMIT;
+use zksync_state::ReadStorage;
+use zksync_system_constants::{DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE, EMPTY_UNCLES_HASH};
+use zksync_types::{
+    api::{
+        self, BlockDetails, BlockDetailsBase, BlockId, BlockIdVariant, BlockNumber, BlockStatus,
+        BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
+        TransactionDetails, TransactionStatus, TransactionVariant,
+    },
+    contract_verification_api::VerificationInfo,
+    fee::Fee,
+    fee_model::{FeeModelConfigV1, FeeParams, FeeParamsV1},
+    get_code_key, get_nonce_key,
+    l2::{L2Tx, TransactionType},
+    transaction_request::CallRequest,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
+    web3::types::{FeeHistory, Index, SyncState},
+    AccountTreeId, Address, Bytes, L1BatchNumber, MiniblockNumber, StorageKey, H256, U256, U64,
+};
+use zksync_utils::{h256_to_u256, u256_to_h256};
+use zksync_web3_decl::{
+    error::Web3Error,
+    jsonrpsee::core::{async_trait, RpcResult},
+    namespaces::{EthNamespaceServer, ZksNamespaceServer},
+    types::{Filter, FilterChanges, PubSubFilter, Token},
+};
+
+use super::{
+    state::{NodeState, StoredBlock},
+    InMemoryNode,
+};
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::PROTOCOL_VERSION};
+
+/// Filter installed via `eth_new*Filter` methods.
+#[derive(Debug, Clone)]
+enum InstalledFilter {
+    Blocks {
+        last_seen_block: MiniblockNumber,
+    },
+    Logs {
+        filter: Filter,
+        last_seen_block: MiniblockNumber,
+    },
+    PendingTransactions,
+}
+
+/// Filters installed on the node.
+#[derive(Debug, Default)]
+pub(super) struct InstalledFilters {
+    next_id: U256,
+    filters: HashMap<U256, InstalledFilter>,
+}
+
+impl InstalledFilters {
+    fn insert(&mut self, filter: InstalledFilter) -> U256 {
+        self.next_id += U256::one();
+        self.filters.insert(self.next_id, filter);
+        self.next_id
+    }
+}
+
+fn resolve_block(state: &NodeState, block_id: BlockId) -> Result<&StoredBlock, Web3Error> {
+    state.resolve_block(block_id).ok_or(Web3Error::NoBlock)
+}
+
+fn resolve_block_variant(
+    state: &NodeState,
+    block: Option<BlockIdVariant>,
+) -> Result<MiniblockNumber, Web3Error> {
+    let block_id = block.map_or(BlockId::Number(BlockNumber::Latest), Into::into);
+    Ok(resolve_block(state, block_id)?.number)
+}
+
+fn api_block(
+    state: &NodeState,
+    block: &StoredBlock,
+    full_transactions: bool,
+) -> api::Block<TransactionVariant> {
+    let transactions = block.tx_hashes.iter().map(|hash| {
+        if full_transactions {
+            let tx = state
+                .transaction(hash)
+                .expect("transaction from a sealed block is missing");
+            TransactionVariant::Full(tx.api.clone())
+        } else {
+            TransactionVariant::Hash(*hash)
+        }
+    });
+    api::Block {
+        hash: block.hash,
+        parent_hash: block.parent_hash,
+        uncles_hash: EMPTY_UNCLES_HASH,
+        number: block.number.0.into(),
+        l1_batch_number: Some(block.l1_batch_number.0.into()),
+        gas_used: block.gas_used,
+        gas_limit: BLOCK_GAS_LIMIT.into(),
+        base_fee_per_gas: block.base_fee_per_gas.into(),
+        timestamp: block.timestamp.into(),
+        l1_batch_timestamp: Some(block.timestamp.into()),
+        transactions: transactions.collect(),
+        ..api::Block::default()
+    }
+}
+
+/// Returns logs from the specified block range matching the filter.
+fn filter_logs(
+    state: &NodeState,
+    filter: &Filter,
+    from_block: MiniblockNumber,
+    to_block: MiniblockNumber,
+) -> Vec<api::Log> {
+    let pub_sub_filter = PubSubFilter {
+        address: filter.address.clone(),
+        topics: filter.topics.clone(),
+    };
+    let logs = state
+        .blocks(from_block, to_block)
+        .iter()
+        .flat_map(|block| &block.tx_hashes)
+        .flat_map(|hash| {
+            let tx = state
+                .transaction(hash)
+                .expect("transaction from a sealed block is missing");
+            &tx.receipt.logs
+        });
+    logs.filter(|log| pub_sub_filter.matches(log))
+        .cloned()
+        .collect()
+}
+
+/// Returns the block range for a log filter, or `None` if the range is empty.
+fn log_filter_range(
+    state: &NodeState,
+    filter: &Filter,
+) -> Result<Option<(MiniblockNumber, MiniblockNumber)>, Web3Error> {
+    if let Some(block_hash) = filter.block_hash {
+        if filter.from_block.is_some() || filter.to_block.is_some() {
+            return Err(Web3Error::InvalidFilterBlockHash);
+        }
+        let block = resolve_block(state, BlockId::Hash(block_hash))?;
+        return Ok(Some((block.number, block.number)));
+    }
+
+    let latest_block = state.latest_block().number;
+    let resolve = |number: Option<BlockNumber>| {
+        let number = number.unwrap_or(BlockNumber::Latest);
+        // Block numbers exceeding the latest block are clamped for the range end.
+        state
+            .resolve_block(BlockId::Number(number))
+            .map_or(MiniblockNumber(u32::MAX), |block| block.number)
+    };
+    let from_block = resolve(filter.from_block);
+    let to_block = resolve(filter.to_block).min(latest_block);
+    Ok((from_block <= to_block).then_some((from_block, to_block)))
+}
+
+impl InMemoryNode {
+    /// Reads the node state on a blocking thread. In the forking mode, reading storage may block on requests
+    /// to the fork source, so it must not be performed on async threads.
+    async fn read_state<T, F>(&self, f: F) -> Result<T, Web3Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&NodeState) -> Result<T, Web3Error> + Send + 'static,
+    {
+        let this = self.clone();
+        let output = tokio::task::spawn_blocking(move || f(&this.state())).await;
+        output.map_err(|err| {
+            tracing::error!("In-memory node state task panicked: {err}");
+            Web3Error::InternalError
+        })?
+    }
+
+    /// Prepares a transaction for gas estimation the same way as the main node API does.
+    async fn prepare_tx_for_estimation(&self, mut request: CallRequest) -> Result<L2Tx, Web3Error> {
+        if request.nonce.is_none() {
+            let nonce_key = get_nonce_key(&request.from.unwrap_or_default());
+            let full_nonce = self.read_storage(nonce_key, None).await?;
+            let (nonce, _) = decompose_full_nonce(h256_to_u256(full_nonce));
+            request.nonce = Some(nonce);
+        }
+        if let Some(eip712_meta) = &mut request.eip712_meta {
+            if eip712_meta.gas_per_pubdata == U256::zero() {
+                eip712_meta.gas_per_pubdata = DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE.into();
+            }
+        }
+        let is_eip712 = request.eip712_meta.is_some();
+
+        let mut tx = L2Tx::from_request(request.into(), self.0.config.max_tx_size)?;
+        // The user may not include the proper transaction type during the estimation of
+        // the gas fee. However, it is needed for the bootloader checks to pass properly.
+        if is_eip712 {
+            tx.common_data.transaction_type = TransactionType::EIP712Transaction;
+        }
+        // When we're estimating fee, we are trying to deduce values related to fee, so we should
+        // not consider provided ones.
+        tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
+        tx.common_data.fee.gas_per_pubdata_limit = DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE.into();
+        Ok(tx)
+    }
+
+    async fn read_storage(
+        &self,
+        key: StorageKey,
+        block: Option<BlockIdVariant>,
+    ) -> Result<H256, Web3Error> {
+        self.read_state(move |state| {
+            let block_number = resolve_block_variant(state, block)?;
+            Ok(state.storage_at(block_number).read_value(&key))
+        })
+        .await
+    }
+
+    fn block_details(&self, number: MiniblockNumber) -> Option<BlockDetails> {
+        let state = self.state();
+        let block = state.block(number)?;
+        let fee_input = self.0.fee_input;
+        Some(BlockDetails {
+            number,
+            l1_batch_number: block.l1_batch_number,
+            base: BlockDetailsBase {
+                timestamp: block.timestamp,
+                l1_tx_count: 0,
+                l2_tx_count: block.tx_hashes.len(),
+                root_hash: None,
+                status: BlockStatus::Sealed,
+                commit_tx_hash: None,
+                committed_at: None,
+                prove_tx_hash: None,
+                proven_at: None,
+                execute_tx_hash: None,
+                executed_at: None,
+                l1_gas_price: fee_input.l1_gas_price(),
+                l2_fair_gas_price: fee_input.fair_l2_gas_price(),
+                base_system_contracts_hashes: self.0.contracts.execution.hashes(),
+            },
+            operator_address: self.0.config.fee_account,
+            protocol_version: Some(self.0.vm_params.protocol_version),
+        })
+    }
+
+    fn filter_changes(&self, filter_id: U256) -> Result<FilterChanges, Web3Error> {
+        let state = self.state();
+        let mut filters = self.0.filters.lock().expect("filters are poisoned");
+        let filter = filters
+            .filters
+            .get_mut(&filter_id)
+            .ok_or(Web3Error::FilterNotFound)?;
+        let latest_block = state.latest_block().number;
+        Ok(match filter {
+            InstalledFilter::Blocks { last_seen_block } => {
+                let new_blocks = state.blocks(*last_seen_block + 1, latest_block);
+                *last_seen_block = latest_block;
+                FilterChanges::Hashes(new_blocks.iter().map(|block| block.hash).collect())
+            }
+            InstalledFilter::Logs {
+                filter,
+                last_seen_block,
+            } => {
+                // Unlike for `eth_getLogs`, the default start of the range is the last polled block.
+                let mut range_filter = filter.clone();
+                if range_filter.block_hash.is_none() {
+                    range_filter.from_block.get_or_insert(BlockNumber::Earliest);
+                }
+                let logs = match log_filter_range(&state, &range_filter)? {
+                    Some((from_block, to_block)) => {
+                        let from_block = from_block.max(*last_seen_block + 1);
+                        filter_logs(&state, filter, from_block, to_block)
+                    }
+                    None => vec![],
+                };
+                *last_seen_block = latest_block;
+                FilterChanges::Logs(logs)
+            }
+            // Transactions are never pending since they are sealed immediately.
+            InstalledFilter::PendingTransactions => FilterChanges::Hashes(vec![]),
+        })
+    }
+}
+
+#[async_trait]
+impl EthNamespaceServer for InMemoryNode {
+    async fn get_block_number(&self) -> RpcResult<U64> {
+        Ok(self.state().latest_block().number.0.into())
+    }
+
+    async fn chain_id(&self) -> RpcResult<U64> {
+        Ok(self.0.config.chain_id.as_u64().into())
+    }
+
+    async fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> RpcResult<Bytes> {
+        const METHOD_NAME: &str = "call";
+
+        let block_number = resolve_block_variant(&self.state(), block).map_err(into_jsrpc_error)?;
+        let tx = L2Tx::from_request(req.into(), self.0.config.max_tx_size)
+            .map_err(|err| into_jsrpc_error(err.into()))?;
+        let output = self.call_tx(tx, block_number).await;
+        let output = output.map_err(|err| into_jsrpc_error(err.into_web3_error(METHOD_NAME)))?;
+        Ok(output.into())
+    }
+
+    async fn estimate_gas(&self, req: CallRequest, _block: Option<BlockNumber>) -> RpcResult<U256> {
+        const METHOD_NAME: &str = "estimate_gas";
+
+        let tx = self
+            .prepare_tx_for_estimation(req)
+            .await
+            .map_err(into_jsrpc_error)?;
+        let fee = self.estimate_tx_fee(tx).await;
+        let fee = fee.map_err(|err| into_jsrpc_error(err.into_web3_error(METHOD_NAME)))?;
+        Ok(fee.gas_limit)
+    }
+
+    async fn gas_price(&self) -> RpcResult<U256> {
+        Ok(self.0.base_fee.into())
+    }
+
+    async fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
+        if let Some(topics) = &filter.topics {
+            if topics.len() > 4 {
+                return Err(into_jsrpc_error(Web3Error::TooManyTopics));
+            }
+        }
+        let last_seen_block = self.state().latest_block().number;
+        let mut filters = self.0.filters.lock().expect("filters are poisoned");
+        Ok(filters.insert(InstalledFilter::Logs {
+            filter,
+            last_seen_block,
+        }))
+    }
+
+    async fn new_block_filter(&self) -> RpcResult<U256> {
+        let last_seen_block = self.state().latest_block().number;
+        let mut filters = self.0.filters.lock().expect("filters are poisoned");
+        Ok(filters.insert(InstalledFilter::Blocks { last_seen_block }))
+    }
+
+    async fn uninstall_filter(&self, idx: U256) -> RpcResult<bool> {
+        let mut filters = self.0.filters.lock().expect("filters are poisoned");
+        Ok(filters.filters.remove(&idx).is_some())
+    }
+
+    async fn new_pending_transaction_filter(&self) -> RpcResult<U256> {
+        let mut filters = self.0.filters.lock().expect("filters are poisoned");
+        Ok(filters.insert(InstalledFilter::PendingTransactions))
+    }
+
+    async fn get_logs(&self, filter: Filter) -> RpcResult<Vec<api::Log>> {
+        let state = self.state();
+        let range = log_filter_range(&state, &filter).map_err(into_jsrpc_error)?;
+        Ok(range.map_or_else(Vec::new, |(from_block, to_block)| {
+            filter_logs(&state, &filter, from_block, to_block)
+        }))
+    }
+
+    async fn get_filter_logs(&self, filter_index: U256) -> RpcResult<FilterChanges> {
+        let filter = self
+            .0
+            .filters
+            .lock()
+            .expect("filters are poisoned")
+            .filters
+            .get(&filter_index)
+            .cloned();
+        let Some(InstalledFilter::Logs { filter, .. }) = filter else {
+            return Err(into_jsrpc_error(Web3Error::FilterNotFound));
+        };
+        let logs = self.get_logs(filter).await?;
+        Ok(FilterChanges::Logs(logs))
+    }
+
+    async fn get_filter_changes(&self, filter_index: U256) -> RpcResult<FilterChanges> {
+        self.filter_changes(filter_index).map_err(into_jsrpc_error)
+    }
+
+    async fn get_balance(
+        &self,
+        address: Address,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<U256> {
+        let balance = self
+            .read_storage(storage_key_for_eth_balance(&address), block)
+            .await
+            .map_err(into_jsrpc_error)?;
+        Ok(h256_to_u256(balance))
+    }
+
+    async fn get_block_by_number(
+        &self,
+        block_number: BlockNumber,
+        full_transactions: bool,
+    ) -> RpcResult<Option<api::Block<TransactionVariant>>> {
+        let state = self.state();
+        let block = state.resolve_block(BlockId::Number(block_number));
+        Ok(block.map(|block| api_block(&state, block, full_transactions)))
+    }
+
+    async fn get_block_by_hash(
+        &self,
+        hash: H256,
+        full_transactions: bool,
+    ) -> RpcResult<Option<api::Block<TransactionVariant>>> {
+        let state = self.state();
+        let block = state.resolve_block(BlockId::Hash(hash));
+        Ok(block.map(|block| api_block(&state, block, full_transactions)))
+    }
+
+    async fn get_block_transaction_count_by_number(
+        &self,
+        block_number: BlockNumber,
+    ) -> RpcResult<Option<U256>> {
+        let state = self.state();
+        let block = state.resolve_block(BlockId::Number(block_number));
+        Ok(block.map(|block| block.tx_hashes.len().into()))
+    }
+
+    async fn get_block_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<Vec<api::TransactionReceipt>> {
+        let state = self.state();
+        let Some(block) = state.resolve_block(block_id) else {
+            return Ok(vec![]);
+        };
+        let receipts = block.tx_hashes.iter().map(|hash| {
+            let tx = state
+                .transaction(hash)
+                .expect("transaction from a sealed block is missing");
+            tx.receipt.clone()
+        });
+        Ok(receipts.collect())
+    }
+
+    async fn get_block_transaction_count_by_hash(
+        &self,
+        block_hash: H256,
+    ) -> RpcResult<Option<U256>> {
+        let state = self.state();
+        let block = state.resolve_block(BlockId::Hash(block_hash));
+        Ok(block.map(|block| block.tx_hashes.len().into()))
+    }
+
+    async fn get_code(&self, address: Address, block: Option<BlockIdVariant>) -> RpcResult<Bytes> {
+        let code = self.read_state(move |state| {
+            let block_number = resolve_block_variant(state, block)?;
+            let mut storage = state.storage_at(block_number);
+            let code_hash = storage.read_value(&get_code_key(&address));
+            if code_hash == H256::zero() {
+                return Ok(vec![]);
+            }
+            Ok(storage.load_factory_dep(code_hash).unwrap_or_default())
+        });
+        Ok(code.await.map_err(into_jsrpc_error)?.into())
+    }
+
+    async fn get_storage_at(
+        &self,
+        address: Address,
+        idx: U256,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<H256> {
+        let key = StorageKey::new(AccountTreeId::new(address), u256_to_h256(idx));
+        self.read_storage(key, block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_transaction_count(
+        &self,
+        address: Address,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<U256> {
+        let full_nonce = self
+            .read_storage(get_nonce_key(&address), block)
+            .await
+            .map_err(into_jsrpc_error)?;
+        let (nonce, _) = decompose_full_nonce(h256_to_u256(full_nonce));
+        Ok(nonce)
+    }
+
+    async fn get_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<api::Transaction>> {
+        let state = self.state();
+        Ok(state.transaction(&hash).map(|tx| tx.api.clone()))
+    }
+
+    async fn get_transaction_by_block_hash_and_index(
+        &self,
+        block_hash: H256,
+        index: Index,
+    ) -> RpcResult<Option<api::Transaction>> {
+        let state = self.state();
+        let Some(block) = state.resolve_block(BlockId::Hash(block_hash)) else {
+            return Ok(None);
+        };
+        let tx_hash = block.tx_hashes.get(index.as_usize());
+        Ok(tx_hash.and_then(|hash| Some(state.transaction(hash)?.api.clone())))
+    }
+
+    async fn get_transaction_by_block_number_and_index(
+        &self,
+        block_number: BlockNumber,
+        index: Index,
+    ) -> RpcResult<Option<api::Transaction>> {
+        let state = self.state();
+        let Some(block) = state.resolve_block(BlockId::Number(block_number)) else {
+            return Ok(None);
+        };
+        let tx_hash = block.tx_hashes.get(index.as_usize());
+        Ok(tx_hash.and_then(|hash| Some(state.transaction(hash)?.api.clone())))
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        hash: H256,
+    ) -> RpcResult<Option<api::TransactionReceipt>> {
+        let state = self.state();
+        Ok(state.transaction(&hash).map(|tx| tx.receipt.clone()))
+    }
+
+    async fn protocol_version(&self) -> RpcResult<String> {
+        Ok(PROTOCOL_VERSION.to_owned())
+    }
+
+    async fn send_raw_transaction(&self, tx_bytes: Bytes) -> RpcResult<H256> {
+        const METHOD_NAME: &str = "send_raw_transaction";
+
+        let (tx_request, hash) =
+            api::TransactionRequest::from_bytes(&tx_bytes.0, self.0.config.chain_id)
+                .map_err(|err| into_jsrpc_error(err.into()))?;
+        let mut tx = L2Tx::from_request(tx_request, self.0.config.max_tx_size)
+            .map_err(|err| into_jsrpc_error(err.into()))?;
+        tx.set_input(tx_bytes.0, hash);
+
+        self.submit_tx(tx).await.map_err(|err| {
+            tracing::debug!("Send raw transaction error: {err}");
+            into_jsrpc_error(err.into_web3_error(METHOD_NAME))
+        })
+    }
+
+    async fn syncing(&self) -> RpcResult<SyncState> {
+        Ok(SyncState::NotSyncing)
+    }
+
+    async fn accounts(&self) -> RpcResult<Vec<Address>> {
+        Ok(vec![])
+    }
+
+    async fn coinbase(&self) -> RpcResult<Address> {
+        Ok(self.0.config.fee_account)
+    }
+
+    async fn compilers(&self) -> RpcResult<Vec<String>> {
+        Ok(vec![])
+    }
+
+    async fn hashrate(&self) -> RpcResult<U256> {
+        Ok(U256::zero())
+    }
+
+    async fn get_uncle_count_by_block_hash(&self, hash: H256) -> RpcResult<Option<U256>> {
+        let state = self.state();
+        Ok(state.resolve_block(BlockId::Hash(hash)).map(|_| 0.into()))
+    }
+
+    async fn get_uncle_count_by_block_number(
+        &self,
+        number: BlockNumber,
+    ) -> RpcResult<Option<U256>> {
+        let state = self.state();
+        Ok(state
+            .resolve_block(BlockId::Number(number))
+            .map(|_| 0.into()))
+    }
+
+    async fn mining(&self) -> RpcResult<bool> {
+        Ok(false)
+    }
+
+    async fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Vec<f32>,
+    ) -> RpcResult<FeeHistory> {
+        let state = self.state();
+        let newest_block = resolve_block(&state, BlockId::Number(newest_block))
+            .map_err(into_jsrpc_error)?
+            .number;
+        // Blocks preceding the root block are not available in the forking mode.
+        let available_block_count = newest_block.0 - state.root_block().number.0 + 1;
+        let block_count = block_count.as_u64().clamp(1, available_block_count.into()) as u32;
+        let oldest_block = newest_block.0 + 1 - block_count;
+        let blocks = state.blocks(MiniblockNumber(oldest_block), newest_block);
+
+        let mut base_fee_per_gas: Vec<U256> = blocks
+            .iter()
+            .map(|block| block.base_fee_per_gas.into())
+            .collect();
+        // We do not store gas used ratio for blocks, returns array of zeroes as a placeholder.
+        let gas_used_ratio = vec![0.0; base_fee_per_gas.len()];
+        // Effective priority gas price is currently 0.
+        let reward = Some(vec![
+            vec![U256::zero(); reward_percentiles.len()];
+            base_fee_per_gas.len()
+        ]);
+        // Base fee is constant, so the fee for the next block is the same as for the newest one.
+        base_fee_per_gas.push(self.0.base_fee.into());
+
+        Ok(FeeHistory {
+            oldest_block: zksync_types::web3::types::BlockNumber::Number(oldest_block.into()),
+            base_fee_per_gas,
+            gas_used_ratio,
+            reward,
+        })
+    }
+}
+
+#[async_trait]
+impl ZksNamespaceServer for InMemoryNode {
+    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee> {
+        const METHOD_NAME: &str = "estimate_fee";
+
+        let tx = self
+            .prepare_tx_for_estimation(req)
+            .await
+            .map_err(into_jsrpc_error)?;
+        let fee = self.estimate_tx_fee(tx).await;
+        fee.map_err(|err| into_jsrpc_error(err.into_web3_error(METHOD_NAME)))
+    }
+
+    async fn estimate_gas_l1_to_l2(&self, _req: CallRequest) -> RpcResult<U256> {
+        Err(into_jsrpc_error(Web3Error::NotImplemented))
+    }
+
+    async fn get_bridgehub_contract(&self) -> RpcResult<Option<Address>> {
+        Ok(None)
+    }
+
+    async fn get_main_contract(&self) -> RpcResult<Address> {
+        Err(into_jsrpc_error(Web3Error::NotImplemented))
+    }
+
+    async fn get_testnet_paymaster(&self) -> RpcResult<Option<Address>> {
+        Ok(None)
+    }
+
+    async fn get_bridge_contracts(&self) -> RpcResult<BridgeAddresses> {
+        Err(into_jsrpc_error(Web3Error::NotImplemented))
+    }
+
+    async fn l1_chain_id(&self) -> RpcResult<U64> {
+        Ok(self.0.config.l1_chain_id.0.into())
+    }
+
+    async fn get_confirmed_tokens(&self, _from: u32, _limit: u8) -> RpcResult<Vec<Token>> {
+        Ok(vec![])
+    }
+
+    async fn get_all_account_balances(
+        &self,
+        address: Address,
+    ) -> RpcResult<HashMap<Address, U256>> {
+        let balance = self.get_balance(address, None).await?;
+        let balances = (!balance.is_zero()).then_some((Address::zero(), balance));
+        Ok(balances.into_iter().collect())
+    }
+
+    async fn get_l2_to_l1_msg_proof(
+        &self,
+        _block: MiniblockNumber,
+        _sender: Address,
+        _msg: H256,
+        _l2_log_position: Option<usize>,
+    ) -> RpcResult<Option<L2ToL1LogProof>> {
+        Err(into_jsrpc_error(Web3Error::NotImplemented))
+    }
+
+    async fn get_l2_to_l1_log_proof(
+        &self,
+        _tx_hash: H256,
+        _index: Option<usize>,
+    ) -> RpcResult<Option<L2ToL1LogProof>> {
+        Err(into_jsrpc_error(Web3Error::NotImplemented))
+    }
+
+    async fn get_l1_batch_number(&self) -> RpcResult<U64> {
+        Ok(self.state().latest_block().l1_batch_number.0.into())
+    }
+
+    async fn get_miniblock_range(&self, batch: L1BatchNumber) -> RpcResult<Option<(U64, U64)>> {
+        let state = self.state();
+        let block = state.block_by_l1_batch(batch);
+        Ok(block.map(|block| (block.number.0.into(), block.number.0.into())))
+    }
+
+    async fn get_block_details(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<BlockDetails>> {
+        Ok(self.block_details(block_number))
+    }
+
+    async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>> {
+        let state = self.state();
+        let Some(tx) = state.transaction(&hash) else {
+            return Ok(None);
+        };
+        let gas_used = tx.receipt.gas_used.unwrap_or_default();
+        let status = if tx.receipt.status.is_zero() {
+            TransactionStatus::Failed
+        } else {
+            TransactionStatus::Included
+        };
+        Ok(Some(TransactionDetails {
+            is_l1_originated: false,
+            status,
+            fee: gas_used * tx.receipt.effective_gas_price.unwrap_or_default(),
+            gas_per_pubdata: tx.gas_per_pubdata,
+            initiator_address: tx.raw.initiator_account(),
+            received_at: tx.received_at,
+            eth_commit_tx_hash: None,
+            eth_prove_tx_hash: None,
+            eth_execute_tx_hash: None,
+        }))
+    }
+
+    async fn get_raw_block_transactions(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Vec<zksync_types::Transaction>> {
+        let state = self.state();
+        let Some(block) = state.block(block_number) else {
+            return Ok(vec![]);
+        };
+        let transactions = block.tx_hashes.iter().filter_map(|hash| {
+            let tx = state.transaction(hash)?;
+            Some(tx.raw.clone())
+        });
+        Ok(transactions.collect())
+    }
+
+    async fn get_l1_batch_details(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchDetails>> {
+        let block_number = self
+            .state()
+            .block_by_l1_batch(batch)
+            .map(|block| block.number);
+        let details = block_number.and_then(|number| self.block_details(number));
+        Ok(details.map(|details| L1BatchDetails {
+            number: batch,
+            base: details.base,
+        }))
+    }
+
+    async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>> {
+        let bytecode = self.read_state(move |state| {
+            let latest_block = state.latest_block().number;
+            Ok(state.storage_at(latest_block).load_factory_dep(hash))
+        });
+        bytecode.await.map_err(into_jsrpc_error)
+    }
+
+    async fn get_l1_gas_price(&self) -> RpcResult<U64> {
+        Ok(self.0.fee_input.l1_gas_price().into())
+    }
+
+    async fn get_fee_params(&self) -> RpcResult<FeeParams> {
+        Ok(FeeParams::V1(FeeParamsV1 {
+            config: FeeModelConfigV1 {
+                minimal_l2_gas_price: self.0.fee_input.fair_l2_gas_price(),
+            },
+            l1_gas_price: self.0.fee_input.l1_gas_price(),
+        }))
+    }
+
+    async fn get_protocol_version(
+        &self,
+        _version_id: Option<u16>,
+    ) -> RpcResult<Option<ProtocolVersion>> {
+        Err(into_jsrpc_error(Web3Error::NotImplemented))
+    }
+
+    async fn get_proof(
+        &self,
+        _address: Address,
+        _keys: Vec<H256>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Proof> {
+        Err(into_jsrpc_error(Web3Error::NotImplemented))
+    }
+
+    async fn get_contract_verification_info(
+        &self,
+        _address: Address,
+    ) -> RpcResult<Option<VerificationInfo>> {
+        // The in-memory node doesn't run the contract verifier.
+        Ok(None)
+    }
+
+    async fn get_tree_lag(&self) -> RpcResult<api::idexo::TreeLag> {
+        // The in-memory node doesn't maintain the Merkle tree.
+        Err(into_jsrpc_error(Web3Error::NotImplemented))
+    }
+}
@@ -0,0 +1,324 @@
+//! Chain state of the in-memory node: VM storage, sealed blocks and executed transactions.
+
+use std::{collections::HashMap, fmt};
+
+use chrono::{DateTime, Utc};
+use zksync_state::{InMemoryStorage, ReadStorage};
+use zksync_types::{
+    api, block::MiniblockHasher, L1BatchNumber, MiniblockNumber, StorageKey, StorageValue,
+    Transaction, H256, U256,
+};
+use zksync_utils::time::seconds_since_epoch;
+
+use super::fork::ForkStorage;
+
+/// Block sealed by the in-memory node. Each block (except for the root one, i.e. the genesis or fork block)
+/// contains a single transaction and is sealed into its own L1 batch.
+#[derive(Debug, Clone)]
+pub(super) struct StoredBlock {
+    pub number: MiniblockNumber,
+    pub l1_batch_number: L1BatchNumber,
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub timestamp: u64,
+    pub base_fee_per_gas: u64,
+    pub gas_used: U256,
+    pub tx_hashes: Vec<H256>,
+    /// Values of storage slots modified in this block before the block was applied; `None` means
+    /// that the slot was not initialized.
+    storage_undo_log: HashMap<StorageKey, Option<StorageValue>>,
+}
+
+impl StoredBlock {
+    pub fn new(
+        number: MiniblockNumber,
+        l1_batch_number: L1BatchNumber,
+        hash: H256,
+        parent_hash: H256,
+        timestamp: u64,
+        base_fee_per_gas: u64,
+    ) -> Self {
+        Self {
+            number,
+            l1_batch_number,
+            hash,
+            parent_hash,
+            timestamp,
+            base_fee_per_gas,
+            gas_used: U256::zero(),
+            tx_hashes: vec![],
+            storage_undo_log: HashMap::new(),
+        }
+    }
+}
+
+/// Transaction included into a block together with its execution results.
+#[derive(Debug, Clone)]
+pub(super) struct StoredTransaction {
+    pub raw: Transaction,
+    pub api: api::Transaction,
+    pub receipt: api::TransactionReceipt,
+    pub gas_per_pubdata: U256,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Environment of a block in which transactions are executed.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct BlockEnv {
+    pub number: MiniblockNumber,
+    pub l1_batch_number: L1BatchNumber,
+    pub timestamp: u64,
+    pub prev_block_hash: H256,
+}
+
+/// Chain state of the in-memory node.
+#[derive(Debug)]
+pub(super) struct NodeState {
+    storage: InMemoryStorage,
+    /// Remote storage used for the slots and bytecodes absent in `storage` in the forking mode.
+    fork: Option<ForkStorage>,
+    /// Sealed blocks starting from the root block.
+    blocks: Vec<StoredBlock>,
+    block_numbers_by_hash: HashMap<H256, MiniblockNumber>,
+    transactions: HashMap<H256, StoredTransaction>,
+}
+
+impl NodeState {
+    /// Creates a state with the genesis block on top of the provided genesis storage.
+    pub fn new(storage: InMemoryStorage, base_fee_per_gas: u64) -> Self {
+        // The genesis block has zero timestamp, which is consistent with the initial state of the `SystemContext` contract.
+        let genesis_hash = MiniblockHasher::legacy_hash(MiniblockNumber(0));
+        let genesis_block = StoredBlock::new(
+            MiniblockNumber(0),
+            L1BatchNumber(0),
+            genesis_hash,
+            H256::zero(),
+            0,
+            base_fee_per_gas,
+        );
+        Self::with_root_block(storage, None, genesis_block)
+    }
+
+    /// Creates a state forked from a remote chain. The fork block becomes the root block of the local chain.
+    pub fn forked(fork: ForkStorage, fork_block: StoredBlock) -> Self {
+        assert_eq!(fork.block_number(), fork_block.number);
+        Self::with_root_block(InMemoryStorage::default(), Some(fork), fork_block)
+    }
+
+    fn with_root_block(
+        storage: InMemoryStorage,
+        fork: Option<ForkStorage>,
+        root_block: StoredBlock,
+    ) -> Self {
+        Self {
+            storage,
+            fork,
+            block_numbers_by_hash: HashMap::from([(root_block.hash, root_block.number)]),
+            blocks: vec![root_block],
+            transactions: HashMap::new(),
+        }
+    }
+
+    pub fn is_forked(&self) -> bool {
+        self.fork.is_some()
+    }
+
+    pub fn root_block(&self) -> &StoredBlock {
+        self.blocks.first().expect("no root block")
+    }
+
+    pub fn latest_block(&self) -> &StoredBlock {
+        self.blocks.last().expect("no root block")
+    }
+
+    fn block_index(&self, number: MiniblockNumber) -> Option<usize> {
+        let index = number.0.checked_sub(self.root_block().number.0)?;
+        Some(index as usize)
+    }
+
+    /// Returns a sealed block. Blocks preceding the root block are not available.
+    pub fn block(&self, number: MiniblockNumber) -> Option<&StoredBlock> {
+        self.blocks.get(self.block_index(number)?)
+    }
+
+    /// Returns the block sealed into the specified L1 batch. In the forking mode, the L1 batch of the fork block
+    /// is not available since it may contain other blocks unknown to the node.
+    pub fn block_by_l1_batch(&self, l1_batch_number: L1BatchNumber) -> Option<&StoredBlock> {
+        let root_block = self.root_block();
+        let index = l1_batch_number
+            .0
+            .checked_sub(root_block.l1_batch_number.0)?;
+        if index == 0 && self.is_forked() {
+            return None;
+        }
+        self.blocks.get(index as usize)
+    }
+
+    /// Resolves a block ID. Since blocks are sealed immediately, pending, latest and finalized blocks
+    /// are all resolved to the latest sealed block.
+    pub fn resolve_block(&self, block_id: api::BlockId) -> Option<&StoredBlock> {
+        match block_id {
+            api::BlockId::Hash(hash) => {
+                let number = self.block_numbers_by_hash.get(&hash)?;
+                self.block(*number)
+            }
+            api::BlockId::Number(api::BlockNumber::Number(number)) => {
+                let number = u32::try_from(number.as_u64()).ok()?;
+                self.block(MiniblockNumber(number))
+            }
+            api::BlockId::Number(api::BlockNumber::Earliest) => Some(self.root_block()),
+            api::BlockId::Number(
+                api::BlockNumber::Committed
+                | api::BlockNumber::Finalized
+                | api::BlockNumber::Merkleized
+                | api::BlockNumber::Latest
+                | api::BlockNumber::Pending,
+            ) => Some(self.latest_block()),
+        }
+    }
+
+    pub fn transaction(&self, hash: &H256) -> Option<&StoredTransaction> {
+        self.transactions.get(hash)
+    }
+
+    /// Returns sealed blocks in the specified inclusive range. The range is clamped to the available blocks.
+    pub fn blocks(&self, from: MiniblockNumber, to: MiniblockNumber) -> &[StoredBlock] {
+        let from = self.block_index(from).unwrap_or(0);
+        let Some(to) = self.block_index(to) else {
+            return &[];
+        };
+        let to = to.min(self.blocks.len() - 1);
+        self.blocks.get(from..=to).unwrap_or_default()
+    }
+
+    /// Returns the environment for a block following the specified one.
+    pub fn next_block_env(&self, parent: &StoredBlock) -> BlockEnv {
+        BlockEnv {
+            number: parent.number + 1,
+            l1_batch_number: parent.l1_batch_number + 1,
+            timestamp: seconds_since_epoch().max(parent.timestamp + 1),
+            prev_block_hash: parent.hash,
+        }
+    }
+
+    /// Returns a read-only view of the storage as of the end of the specified block.
+    pub fn storage_at(&self, number: MiniblockNumber) -> StorageAtBlock<'_> {
+        StorageAtBlock {
+            state: self,
+            number,
+        }
+    }
+
+    /// Seals a new block on top of the latest block.
+    pub fn push_block(
+        &mut self,
+        mut block: StoredBlock,
+        storage_changes: HashMap<StorageKey, StorageValue>,
+        factory_deps: Vec<(H256, Vec<u8>)>,
+        transactions: Vec<StoredTransaction>,
+    ) {
+        assert_eq!(block.number, self.latest_block().number + 1);
+        assert_eq!(block.parent_hash, self.latest_block().hash);
+
+        let mut storage = &self.storage;
+        block.storage_undo_log = storage_changes
+            .keys()
+            .map(|key| {
+                let prev_value = (!storage.is_write_initial(key)).then(|| storage.read_value(key));
+                (*key, prev_value)
+            })
+            .collect();
+        for (key, value) in storage_changes {
+            self.storage.set_value(key, value);
+        }
+        for (hash, bytecode) in factory_deps {
+            self.storage.store_factory_dep(hash, bytecode);
+        }
+
+        block.tx_hashes = transactions
+            .iter()
+            .map(|tx| tx.receipt.transaction_hash)
+            .collect();
+        for tx in transactions {
+            self.transactions.insert(tx.receipt.transaction_hash, tx);
+        }
+        self.block_numbers_by_hash.insert(block.hash, block.number);
+        self.blocks.push(block);
+    }
+}
+
+/// Read-only view of the node storage as of the end of a certain block. Newer state is reverted using
+/// storage undo logs of the following blocks. In the forking mode, slots and bytecodes absent locally
+/// are read from the fork.
+///
+/// The fork only reports storage values, so a slot absent locally is considered initial iff its remote value is zero.
+/// This may overestimate pubdata for slots that were reset to zero on the remote chain.
+pub(super) struct StorageAtBlock<'a> {
+    state: &'a NodeState,
+    number: MiniblockNumber,
+}
+
+impl fmt::Debug for StorageAtBlock<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("StorageAtBlock")
+            .field("number", &self.number)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StorageAtBlock<'_> {
+    /// Returns the value of the slot as of this block if it was modified in one of the following blocks.
+    fn reverted_value(&self, key: &StorageKey) -> Option<Option<StorageValue>> {
+        let next_block_idx = self.state.block_index(self.number)? + 1;
+        let newer_blocks = self.state.blocks.get(next_block_idx..).unwrap_or_default();
+        newer_blocks
+            .iter()
+            .find_map(|block| block.storage_undo_log.get(key).copied())
+    }
+
+    /// Returns the value of the slot as of this block if it is known locally, i.e. without querying the fork.
+    fn local_value(&self, key: &StorageKey) -> Option<StorageValue> {
+        match self.reverted_value(key) {
+            Some(value) => value,
+            None => {
+                let mut storage = &self.state.storage;
+                (!storage.is_write_initial(key)).then(|| storage.read_value(key))
+            }
+        }
+    }
+}
+
+impl ReadStorage for StorageAtBlock<'_> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        self.local_value(key)
+            .unwrap_or_else(|| match &self.state.fork {
+                Some(fork) => fork.read_value(key),
+                None => StorageValue::zero(),
+            })
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        if self.local_value(key).is_some() {
+            return false;
+        }
+        match &self.state.fork {
+            Some(fork) => fork.read_value(key).is_zero(),
+            None => true,
+        }
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        let local_bytecode = (&self.state.storage).load_factory_dep(hash);
+        local_bytecode.or_else(|| self.state.fork.as_ref()?.load_factory_dep(hash))
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        // Enumeration indices of remote slots are unknown.
+        if self.local_value(key).is_some() {
+            (&self.state.storage).get_enumeration_index(key)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,461 @@
+//! Tests for the in-memory node.
+
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use async_trait::async_trait;
+use zksync_system_constants::L2_ETH_TOKEN_ADDRESS;
+use zksync_test_account::Account;
+use zksync_types::{
+    api::{
+        BlockDetails, BlockDetailsBase, BlockIdVariant, BlockNumber, BlockStatus,
+        TransactionVariant,
+    },
+    ethabi::{self, ParamType, Token},
+    transaction_request::CallRequest,
+    Execute, L1BatchNumber, Nonce, StorageKey, StorageValue,
+};
+use zksync_utils::{address_to_u256, be_words_to_bytes, u256_to_h256};
+use zksync_web3_decl::{
+    error::EnrichedClientResult,
+    types::{Filter, FilterChanges},
+};
+
+use super::*;
+use crate::genesis::InitialBalance;
+
+const INITIAL_BALANCE: u128 = 100_000_000_000_000_000_000;
+
+fn create_node(rich_account: &Account) -> InMemoryNode {
+    let genesis = GenesisManifest {
+        balances: vec![InitialBalance {
+            token: None,
+            address: rich_account.address,
+            amount: INITIAL_BALANCE.into(),
+        }],
+        ..GenesisManifest::mock()
+    };
+    let config = InMemoryNodeConfig {
+        genesis: Some(genesis),
+        ..InMemoryNodeConfig::default()
+    };
+    InMemoryNode::new(config).unwrap()
+}
+
+fn create_transfer(account: &mut Account, recipient: Address, value: U256) -> L2Tx {
+    let execute = Execute {
+        contract_address: recipient,
+        calldata: vec![],
+        value,
+        factory_deps: None,
+    };
+    let tx = account.get_l2_tx_for_execute(execute, None);
+    tx.try_into().unwrap()
+}
+
+fn block_id(number: u32) -> Option<BlockIdVariant> {
+    Some(BlockIdVariant::BlockNumber(BlockNumber::Number(
+        number.into(),
+    )))
+}
+
+#[tokio::test]
+async fn sealing_transfer() {
+    let mut alice = Account::random();
+    let bob = Address::repeat_byte(0xb0);
+    let node = create_node(&alice);
+    assert_eq!(
+        EthNamespaceServer::get_block_number(&node).await.unwrap(),
+        0.into()
+    );
+
+    let value = U256::from(1_000_000);
+    let tx_hash = node
+        .submit_tx(create_transfer(&mut alice, bob, value))
+        .await
+        .unwrap();
+    assert_eq!(
+        EthNamespaceServer::get_block_number(&node).await.unwrap(),
+        1.into()
+    );
+
+    let receipt = node
+        .get_transaction_receipt(tx_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(receipt.status, 1.into());
+    assert_eq!(receipt.block_number, 1.into());
+    assert_eq!(receipt.from, alice.address);
+    assert_eq!(receipt.to, Some(bob));
+    assert!(!receipt.logs.is_empty());
+    let gas_used = receipt.gas_used.unwrap();
+    assert!(gas_used > U256::zero());
+
+    let block = node
+        .get_block_by_hash(receipt.block_hash, false)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(block.number, 1.into());
+    assert_eq!(block.gas_used, gas_used);
+    assert_eq!(block.transactions, [TransactionVariant::Hash(tx_hash)]);
+    let genesis_block = node
+        .get_block_by_number(BlockNumber::Earliest, false)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(block.parent_hash, genesis_block.hash);
+    assert!(block.timestamp > genesis_block.timestamp);
+
+    let tx = node
+        .get_transaction_by_hash(tx_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tx.block_hash, Some(receipt.block_hash));
+    assert_eq!(tx.value, value);
+
+    assert_eq!(node.get_balance(bob, None).await.unwrap(), value);
+    assert_eq!(node.get_balance(bob, block_id(0)).await.unwrap(), 0.into());
+    let alice_balance = node.get_balance(alice.address, None).await.unwrap();
+    let fee = gas_used * receipt.effective_gas_price.unwrap();
+    assert_eq!(alice_balance, U256::from(INITIAL_BALANCE) - value - fee);
+    let alice_nonce = node
+        .get_transaction_count(alice.address, None)
+        .await
+        .unwrap();
+    assert_eq!(alice_nonce, 1.into());
+    let alice_nonce = node
+        .get_transaction_count(alice.address, block_id(0))
+        .await
+        .unwrap();
+    assert_eq!(alice_nonce, 0.into());
+}
+
+#[tokio::test]
+async fn rejecting_transactions_with_invalid_nonce() {
+    let mut alice = Account::random();
+    let node = create_node(&alice);
+    let tx = create_transfer(&mut alice, Address::repeat_byte(0xb0), 1.into());
+    node.submit_tx(tx.clone()).await.unwrap();
+
+    let err = node.submit_tx(tx).await.unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::IncorrectTx(TxCheckError::TxDuplication(_))
+    );
+
+    alice.nonce = Nonce(0);
+    let tx = create_transfer(&mut alice, Address::repeat_byte(0xb1), 1.into());
+    let err = node.submit_tx(tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::NonceIsTooLow(1, 1, 0));
+
+    alice.nonce = Nonce(5);
+    let tx = create_transfer(&mut alice, Address::repeat_byte(0xb1), 1.into());
+    let err = node.submit_tx(tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::NonceIsTooHigh(1, 1, 5));
+    assert_eq!(
+        EthNamespaceServer::get_block_number(&node).await.unwrap(),
+        1.into()
+    );
+}
+
+#[tokio::test]
+async fn calling_contract_at_different_blocks() {
+    let mut alice = Account::random();
+    let bob = Address::repeat_byte(0xb0);
+    let node = create_node(&alice);
+    let value = U256::from(1_000_000);
+    node.submit_tx(create_transfer(&mut alice, bob, value))
+        .await
+        .unwrap();
+
+    let mut calldata = ethabi::short_signature("balanceOf", &[ParamType::Uint(256)]).to_vec();
+    calldata.extend(ethabi::encode(&[Token::Uint(address_to_u256(&bob))]));
+    let request = CallRequest::builder()
+        .to(L2_ETH_TOKEN_ADDRESS)
+        .data(calldata.into())
+        .build();
+
+    let output = EthNamespaceServer::call(&node, request.clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(U256::from_big_endian(&output.0), value);
+    let output = EthNamespaceServer::call(&node, request, block_id(0))
+        .await
+        .unwrap();
+    assert_eq!(U256::from_big_endian(&output.0), U256::zero());
+}
+
+#[tokio::test]
+async fn estimating_fee_for_transfer() {
+    let mut alice = Account::random();
+    let bob = Address::repeat_byte(0xb0);
+    let node = create_node(&alice);
+    let request = CallRequest::builder()
+        .from(alice.address)
+        .to(bob)
+        .value(1_000.into())
+        .build();
+
+    let fee = ZksNamespaceServer::estimate_fee(&node, request.clone())
+        .await
+        .unwrap();
+    assert_eq!(fee.max_fee_per_gas, node.0.base_fee.into());
+    assert!(fee.gas_limit > U256::zero());
+    assert!(fee.gas_limit < MAX_L2_TX_GAS_LIMIT.into());
+    let gas = EthNamespaceServer::estimate_gas(&node, request, None)
+        .await
+        .unwrap();
+    assert_eq!(gas, fee.gas_limit);
+
+    // The estimated fee should be sufficient to execute the transaction.
+    let execute = Execute {
+        contract_address: bob,
+        calldata: vec![],
+        value: 1_000.into(),
+        factory_deps: None,
+    };
+    let tx = alice.get_l2_tx_for_execute(execute, Some(fee));
+    let tx_hash = node.submit_tx(tx.try_into().unwrap()).await.unwrap();
+    let receipt = node
+        .get_transaction_receipt(tx_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(receipt.status, 1.into());
+}
+
+#[tokio::test]
+async fn polling_filter_changes() {
+    let mut alice = Account::random();
+    let node = create_node(&alice);
+    let block_filter = node.new_block_filter().await.unwrap();
+    let log_filter = node
+        .new_filter(Filter {
+            address: Some(L2_ETH_TOKEN_ADDRESS.into()),
+            ..Filter::default()
+        })
+        .await
+        .unwrap();
+    let changes = node.get_filter_changes(block_filter).await.unwrap();
+    assert_matches!(changes, FilterChanges::Hashes(hashes) if hashes.is_empty());
+    let changes = node.get_filter_changes(log_filter).await.unwrap();
+    assert_matches!(changes, FilterChanges::Logs(logs) if logs.is_empty());
+
+    let tx_hash = node
+        .submit_tx(create_transfer(
+            &mut alice,
+            Address::repeat_byte(0xb0),
+            1.into(),
+        ))
+        .await
+        .unwrap();
+    let block = node
+        .get_block_by_number(BlockNumber::Latest, false)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let changes = node.get_filter_changes(block_filter).await.unwrap();
+    assert_matches!(changes, FilterChanges::Hashes(hashes) if hashes == [block.hash]);
+    let changes = node.get_filter_changes(log_filter).await.unwrap();
+    let FilterChanges::Logs(logs) = changes else {
+        panic!("unexpected filter changes: {changes:?}");
+    };
+    assert!(!logs.is_empty());
+    assert!(logs
+        .iter()
+        .all(|log| log.transaction_hash == Some(tx_hash) && log.address == L2_ETH_TOKEN_ADDRESS));
+
+    let changes = node.get_filter_changes(log_filter).await.unwrap();
+    assert_matches!(changes, FilterChanges::Logs(logs) if logs.is_empty());
+    let logs = node
+        .get_logs(Filter {
+            block_hash: Some(block.hash),
+            ..Filter::default()
+        })
+        .await
+        .unwrap();
+    assert!(!logs.is_empty());
+    let logs = node
+        .get_logs(Filter {
+            from_block: Some(BlockNumber::Earliest),
+            to_block: Some(BlockNumber::Earliest),
+            ..Filter::default()
+        })
+        .await
+        .unwrap();
+    assert!(logs.is_empty());
+
+    assert!(node.uninstall_filter(log_filter).await.unwrap());
+    node.get_filter_changes(log_filter).await.unwrap_err();
+}
+
+/// Fork source serving the genesis state of a chain.
+#[derive(Debug)]
+struct MockForkSource {
+    chain_id: L2ChainId,
+    storage: InMemoryStorage,
+    base_system_contracts: BaseSystemContracts,
+    storage_requests: Mutex<HashMap<StorageKey, usize>>,
+}
+
+impl MockForkSource {
+    fn new(chain_id: L2ChainId, rich_account: &Account) -> Self {
+        let mut storage =
+            InMemoryStorage::with_system_contracts_and_chain_id(chain_id, hash_bytecode);
+        let balance_key = storage_key_for_eth_balance(&rich_account.address);
+        storage.set_value(balance_key, u256_to_h256(INITIAL_BALANCE.into()));
+        Self {
+            chain_id,
+            storage,
+            base_system_contracts: BaseSystemContracts::load_from_disk(),
+            storage_requests: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl ForkSource for MockForkSource {
+    async fn fetch_chain_id(&self) -> EnrichedClientResult<L2ChainId> {
+        Ok(self.chain_id)
+    }
+
+    async fn fetch_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        Ok(MiniblockNumber(0))
+    }
+
+    async fn fetch_block(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<api::Block<TransactionVariant>>> {
+        Ok((number == MiniblockNumber(0)).then(|| api::Block {
+            hash: MiniblockHasher::legacy_hash(number),
+            number: 0.into(),
+            base_fee_per_gas: 250_000_000_u64.into(),
+            ..api::Block::default()
+        }))
+    }
+
+    async fn fetch_block_details(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<BlockDetails>> {
+        let details = BlockDetails {
+            number,
+            l1_batch_number: L1BatchNumber(0),
+            base: BlockDetailsBase {
+                timestamp: 0,
+                l1_tx_count: 0,
+                l2_tx_count: 0,
+                root_hash: None,
+                status: BlockStatus::Verified,
+                commit_tx_hash: None,
+                committed_at: None,
+                prove_tx_hash: None,
+                proven_at: None,
+                execute_tx_hash: None,
+                executed_at: None,
+                l1_gas_price: 0,
+                l2_fair_gas_price: 0,
+                base_system_contracts_hashes: self.base_system_contracts.hashes(),
+            },
+            operator_address: Address::zero(),
+            protocol_version: Some(ProtocolVersionId::latest()),
+        };
+        Ok((number == MiniblockNumber(0)).then_some(details))
+    }
+
+    async fn fetch_storage_value(
+        &self,
+        key: &StorageKey,
+        block: MiniblockNumber,
+    ) -> EnrichedClientResult<StorageValue> {
+        assert_eq!(block, MiniblockNumber(0));
+        *self
+            .storage_requests
+            .lock()
+            .unwrap()
+            .entry(*key)
+            .or_default() += 1;
+        Ok((&self.storage).read_value(key))
+    }
+
+    async fn fetch_bytecode(&self, hash: H256) -> EnrichedClientResult<Option<Vec<u8>>> {
+        let contracts = &self.base_system_contracts;
+        let system_contract = [&contracts.bootloader, &contracts.default_aa]
+            .into_iter()
+            .find(|contract| contract.hash == hash);
+        if let Some(contract) = system_contract {
+            return Ok(Some(be_words_to_bytes(&contract.code)));
+        }
+        Ok((&self.storage).load_factory_dep(hash))
+    }
+}
+
+#[tokio::test]
+async fn forking_remote_chain() {
+    let mut alice = Account::random();
+    let bob = Address::repeat_byte(0xb0);
+    let chain_id = L2ChainId::from(123);
+    let source = Arc::new(MockForkSource::new(chain_id, &alice));
+    let node = InMemoryNode::fork(InMemoryNodeConfig::default(), source.clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        EthNamespaceServer::chain_id(&node).await.unwrap(),
+        chain_id.as_u64().into()
+    );
+    assert_eq!(
+        EthNamespaceServer::get_block_number(&node).await.unwrap(),
+        0.into()
+    );
+    assert_eq!(
+        node.get_balance(alice.address, None).await.unwrap(),
+        INITIAL_BALANCE.into()
+    );
+
+    let value = U256::from(1_000_000);
+    let tx_hash = node
+        .submit_tx(create_transfer(&mut alice, bob, value))
+        .await
+        .unwrap();
+    let receipt = node
+        .get_transaction_receipt(tx_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(receipt.status, 1.into());
+    assert_eq!(receipt.block_number, 1.into());
+    assert_eq!(receipt.l1_batch_number, Some(1.into()));
+    assert_eq!(node.get_balance(bob, None).await.unwrap(), value);
+    assert_eq!(node.get_balance(bob, block_id(0)).await.unwrap(), 0.into());
+
+    // The second transaction should be executed on top of the local state.
+    node.submit_tx(create_transfer(&mut alice, bob, value))
+        .await
+        .unwrap();
+    assert_eq!(node.get_balance(bob, None).await.unwrap(), value * 2);
+
+    let storage_requests = source.storage_requests.lock().unwrap();
+    let balance_key = storage_key_for_eth_balance(&alice.address);
+    assert_eq!(storage_requests[&balance_key], 1);
+    assert!(
+        storage_requests.values().all(|&count| count == 1),
+        "{storage_requests:?}"
+    );
+}
+
+#[tokio::test]
+async fn forking_is_incompatible_with_genesis_manifest() {
+    let alice = Account::random();
+    let source = Arc::new(MockForkSource::new(L2ChainId::default(), &alice));
+    let config = InMemoryNodeConfig {
+        genesis: Some(GenesisManifest::mock()),
+        ..InMemoryNodeConfig::default()
+    };
+    let err = InMemoryNode::fork(config, source, None).await.unwrap_err();
+    assert!(err.to_string().contains("genesis manifest"), "{err:#}");
+}
@@ -0,0 +1,17 @@
+//! DA cost oracle metrics.
+
+use vise::{Counter, Gauge, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_da_cost_oracle")]
+pub(super) struct DACostOracleMetrics {
+    /// Last observed price of publishing a pubdata byte on the DA layer, in wei.
+    pub current_price_per_byte: Gauge<u64>,
+    /// Median price of publishing a pubdata byte, in wei.
+    pub median_price_per_byte: Gauge<u64>,
+    /// Number of failed price updates.
+    pub failed_updates: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DACostOracleMetrics> = vise::Global::new();
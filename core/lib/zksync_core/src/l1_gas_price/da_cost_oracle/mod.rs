@@ -0,0 +1,120 @@
+//! Oracle tracking the cost of publishing pubdata on an external data availability (DA) layer.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use zksync_config::configs::{
+    chain::DataAvailabilityMode,
+    eth_sender::{DACostOracleConfig, DADispatcherConfig},
+};
+use zksync_eth_client::EthInterface;
+
+use self::metrics::METRICS;
+use super::gas_adjuster::GasStatistics;
+use crate::da_dispatcher::{
+    create_da_client, DAPubdataPrice, DataAvailabilityClient, EigenDAClient,
+};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Oracle keeping track of the median price of publishing a pubdata byte on the DA layer of the chain.
+/// The price is queried from the DA layer client, converted to wei and used as the pubdata price
+/// by [`GasAdjuster`](super::GasAdjuster).
+#[derive(Debug)]
+pub struct DACostOracle {
+    client: Box<dyn DataAvailabilityClient>,
+    config: DACostOracleConfig,
+    statistics: GasStatistics,
+}
+
+impl DACostOracle {
+    pub fn new(client: Box<dyn DataAvailabilityClient>, config: DACostOracleConfig) -> Self {
+        Self {
+            client,
+            statistics: GasStatistics::new(config.max_price_samples.max(1), 0, &[]),
+            config,
+        }
+    }
+
+    /// Creates an oracle for the specified DA mode. Returns `Ok(None)` for rollup chains, for which pubdata
+    /// is priced by the `GasAdjuster` itself.
+    pub fn from_config(
+        mode: DataAvailabilityMode,
+        da_dispatcher_config: &DADispatcherConfig,
+        config: DACostOracleConfig,
+        l1_client: Arc<dyn EthInterface>,
+    ) -> anyhow::Result<Option<Self>> {
+        let client: Box<dyn DataAvailabilityClient> = match mode {
+            DataAvailabilityMode::Rollup => return Ok(None),
+            DataAvailabilityMode::EigenDA => {
+                let url = da_dispatcher_config
+                    .eigenda_disperser_url
+                    .clone()
+                    .context("`eigenda_disperser_url` must be set for the EigenDA mode")?;
+                let payment_vault = config.eigenda_payment_vault_address.context(
+                    "`eigenda_payment_vault_address` must be set to price pubdata for the EigenDA mode",
+                )?;
+                Box::new(EigenDAClient::new(url).with_payment_vault(l1_client, payment_vault))
+            }
+            _ => create_da_client(mode, da_dispatcher_config)?,
+        };
+        Ok(Some(Self::new(client, config)))
+    }
+
+    /// Queries the current price from the DA layer and adds it to the statistics.
+    /// This method is intended to be invoked periodically.
+    pub async fn keep_updated(&self) -> anyhow::Result<()> {
+        let result = self.update_price().await;
+        if result.is_err() {
+            METRICS.failed_updates.inc();
+        }
+        result
+    }
+
+    async fn update_price(&self) -> anyhow::Result<()> {
+        let price = self.client.pubdata_price().await?.with_context(|| {
+            format!(
+                "{} DA layer does not expose pubdata prices",
+                self.client.name()
+            )
+        })?;
+        let price = self.price_in_wei(price)?;
+        METRICS.current_price_per_byte.set(price);
+        self.statistics.add_samples(&[price]);
+        Ok(())
+    }
+
+    pub(super) fn price_in_wei(&self, price: DAPubdataPrice) -> anyhow::Result<u64> {
+        let price = match price {
+            DAPubdataPrice::Wei(price) => price,
+            DAPubdataPrice::NativeToken(price) => {
+                let token_price = self.config.native_token_price_in_wei.with_context(|| {
+                    format!(
+                        "`native_token_price_in_wei` must be set to price pubdata on {} DA layer",
+                        self.client.name()
+                    )
+                })?;
+                price * token_price
+            }
+        };
+        anyhow::ensure!(
+            price.is_finite() && price >= 0.0,
+            "invalid pubdata price: {price}"
+        );
+        // Float-to-int conversion saturates, so large prices are capped by `max_price_per_byte()`.
+        let price = (price * self.config.price_scalar).round() as u64;
+        Ok(price.min(self.config.max_price_per_byte()))
+    }
+
+    /// Returns the median price of publishing a pubdata byte in wei, or `None` if the price wasn't observed yet.
+    pub(crate) fn estimate_price_per_byte(&self) -> Option<u64> {
+        if self.statistics.is_empty() {
+            return None;
+        }
+        let median = self.statistics.median();
+        METRICS.median_price_per_byte.set(median);
+        Some(median)
+    }
+}
@@ -0,0 +1,191 @@
+//! Tests for the DA cost oracle.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use zksync_types::{Address, L1BatchNumber};
+
+use super::*;
+use crate::da_dispatcher::{DispatchResponse, InclusionData};
+
+/// DA client with a configurable price. Dispatched blobs are included immediately.
+#[derive(Debug, Default)]
+struct MockDAClient {
+    price: Mutex<Option<DAPubdataPrice>>,
+}
+
+impl MockDAClient {
+    fn set_price(&self, price: Option<DAPubdataPrice>) {
+        *self.price.lock().unwrap() = price;
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for Arc<MockDAClient> {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        _data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        Ok(DispatchResponse {
+            blob_id: format!("blob_{l1_batch_number}"),
+            reference: None,
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<Option<InclusionData>> {
+        Ok(Some(InclusionData {
+            data: blob_id.as_bytes().to_vec(),
+        }))
+    }
+
+    async fn pubdata_price(&self) -> anyhow::Result<Option<DAPubdataPrice>> {
+        Ok(*self.price.lock().unwrap())
+    }
+}
+
+fn config() -> DACostOracleConfig {
+    DACostOracleConfig {
+        max_price_samples: 3,
+        native_token_price_in_wei: None,
+        price_scalar: 1.0,
+        max_price_per_byte: None,
+        eigenda_payment_vault_address: None,
+    }
+}
+
+#[test]
+fn converting_prices_to_wei() {
+    let client = Arc::<MockDAClient>::default();
+    let oracle = DACostOracle::new(Box::new(client.clone()), config());
+    assert_eq!(
+        oracle.price_in_wei(DAPubdataPrice::Wei(100.5)).unwrap(),
+        101
+    );
+    let err = oracle
+        .price_in_wei(DAPubdataPrice::NativeToken(0.02))
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("native_token_price_in_wei"),
+        "{err}"
+    );
+    oracle.price_in_wei(DAPubdataPrice::Wei(-1.0)).unwrap_err();
+    oracle
+        .price_in_wei(DAPubdataPrice::Wei(f64::NAN))
+        .unwrap_err();
+
+    let config = DACostOracleConfig {
+        native_token_price_in_wei: Some(2_000_000.0),
+        price_scalar: 1.5,
+        max_price_per_byte: Some(100_000),
+        ..config()
+    };
+    let oracle = DACostOracle::new(Box::new(client), config);
+    // 0.02 utia * 2_000_000 wei * 1.5, rounded
+    assert_eq!(
+        oracle
+            .price_in_wei(DAPubdataPrice::NativeToken(0.02))
+            .unwrap(),
+        60_000
+    );
+    assert_eq!(
+        oracle.price_in_wei(DAPubdataPrice::Wei(1e30)).unwrap(),
+        100_000
+    );
+}
+
+#[tokio::test]
+async fn oracle_tracks_median_price() {
+    let client = Arc::<MockDAClient>::default();
+    let oracle = DACostOracle::new(Box::new(client.clone()), config());
+    assert_eq!(oracle.estimate_price_per_byte(), None);
+
+    let err = oracle.keep_updated().await.unwrap_err();
+    assert!(err.to_string().contains("does not expose"), "{err}");
+    assert_eq!(oracle.estimate_price_per_byte(), None);
+
+    for price in [10.0, 30.0, 20.0] {
+        client.set_price(Some(DAPubdataPrice::Wei(price)));
+        oracle.keep_updated().await.unwrap();
+    }
+    assert_eq!(oracle.estimate_price_per_byte(), Some(20));
+
+    // Only the last `max_price_samples` observations are taken into account.
+    for _ in 0..2 {
+        client.set_price(Some(DAPubdataPrice::Wei(50.0)));
+        oracle.keep_updated().await.unwrap();
+    }
+    assert_eq!(oracle.estimate_price_per_byte(), Some(50));
+
+    // Failed updates don't affect the price.
+    client.set_price(None);
+    oracle.keep_updated().await.unwrap_err();
+    assert_eq!(oracle.estimate_price_per_byte(), Some(50));
+}
+
+#[test]
+fn creating_oracles() {
+    let da_dispatcher_config = DADispatcherConfig {
+        polling_interval_ms: 5_000,
+        max_batches_to_dispatch: 10,
+        external_da_url: None,
+        celestia_node_url: None,
+        celestia_namespace: None,
+        eigenda_disperser_url: Some("http://127.0.0.1:3074".to_owned()),
+        failover_timeout_ms: None,
+        compression_protocol_version: None,
+        compression_level: None,
+        compression_dictionary_path: None,
+    };
+    let l1_client: Arc<dyn EthInterface> =
+        Arc::new(zksync_eth_client::clients::MockEthereum::default());
+
+    let oracle = DACostOracle::from_config(
+        DataAvailabilityMode::Rollup,
+        &da_dispatcher_config,
+        config(),
+        l1_client.clone(),
+    )
+    .unwrap();
+    assert!(oracle.is_none());
+
+    let oracle = DACostOracle::from_config(
+        DataAvailabilityMode::Validium,
+        &da_dispatcher_config,
+        config(),
+        l1_client.clone(),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(oracle.client.name(), "no_da");
+
+    let err = DACostOracle::from_config(
+        DataAvailabilityMode::EigenDA,
+        &da_dispatcher_config,
+        config(),
+        l1_client.clone(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("eigenda_payment_vault_address"),
+        "{err}"
+    );
+
+    let oracle_config = DACostOracleConfig {
+        eigenda_payment_vault_address: Some(Address::repeat_byte(1)),
+        ..config()
+    };
+    let oracle = DACostOracle::from_config(
+        DataAvailabilityMode::EigenDA,
+        &da_dispatcher_config,
+        oracle_config,
+        l1_client,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(oracle.client.name(), "eigenda");
+}
@@ -7,6 +7,14 @@ use vise::{Gauge, Metrics};
 pub(super) struct GasAdjusterMetrics {
     pub current_base_fee_per_gas: Gauge<u64>,
     pub median_base_fee_per_gas: Gauge<u64>,
+    pub current_blob_base_fee_per_gas: Gauge<u64>,
+    pub median_blob_base_fee_per_gas: Gauge<u64>,
+    /// Base fee on L1 underlying the settlement layer; only reported if the settlement layer is an L2 rollup.
+    pub current_l1_base_fee_per_gas: Gauge<u64>,
+    /// Blob base fee on L1 underlying the settlement layer; only reported if the settlement layer is an L2 rollup.
+    pub current_l1_blob_base_fee_per_gas: Gauge<u64>,
+    /// L1 component of the pubdata price (per byte) if the settlement layer is an L2 rollup.
+    pub l1_pubdata_price_per_byte: Gauge<u64>,
 }
 
 #[vise::register]
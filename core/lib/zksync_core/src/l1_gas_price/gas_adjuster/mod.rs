@@ -11,7 +11,7 @@ use zksync_eth_client::{Error, EthInterface};
 use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
 
 use self::metrics::METRICS;
-use super::L1TxParamsProvider;
+use super::{DACostOracle, L1TxParamsProvider};
 use crate::state_keeper::metrics::KEEPER_METRICS;
 
 mod metrics;
@@ -20,11 +20,32 @@ mod tests;
 
 /// This component keeps track of the median base_fee from the last `max_base_fee_samples` blocks.
 /// It is used to adjust the base_fee of transactions sent to L1.
+///
+/// If L1 supports EIP-4844, the component additionally keeps track of the median blob base fee
+/// from the last `num_samples_for_blob_base_fee_estimate` observations.
+///
+/// If the settlement layer is itself an L2 rollup, the same statistics are additionally collected
+/// for the underlying L1, since L1 prices determine the cost of publishing pubdata on the settlement layer.
+///
+/// If the chain publishes pubdata on an external DA layer, the pubdata price is provided by the [`DACostOracle`]
+/// updated together with the statistics.
 #[derive(Debug)]
 pub struct GasAdjuster {
     pub(super) statistics: GasStatistics,
+    pub(super) blob_base_fee_statistics: GasStatistics,
     pub(super) config: GasAdjusterConfig,
     eth_client: Arc<dyn EthInterface>,
+    pub(super) underlying_l1: Option<UnderlyingL1>,
+    blob_pubdata: bool,
+    da_cost_oracle: Option<DACostOracle>,
+}
+
+/// Fee statistics for the L1 underlying an L2 settlement layer.
+#[derive(Debug)]
+pub(super) struct UnderlyingL1 {
+    pub(super) statistics: GasStatistics,
+    pub(super) blob_base_fee_statistics: GasStatistics,
+    eth_client: Arc<dyn EthInterface>,
 }
 
 impl GasAdjuster {
@@ -32,6 +53,52 @@ impl GasAdjuster {
         eth_client: Arc<dyn EthInterface>,
         config: GasAdjusterConfig,
     ) -> Result<Self, Error> {
+        let (statistics, blob_base_fee_statistics) =
+            Self::init_statistics(eth_client.as_ref(), &config).await?;
+        Ok(Self {
+            statistics,
+            blob_base_fee_statistics,
+            eth_client,
+            config,
+            underlying_l1: None,
+            blob_pubdata: false,
+            da_cost_oracle: None,
+        })
+    }
+
+    /// Makes the adjuster price pubdata taking into account that it may be published in EIP-4844 blobs
+    /// on the settlement layer (i.e., the Ethereum sender uses the blobs pubdata sending mode).
+    pub fn with_blob_pubdata(mut self) -> Self {
+        self.blob_pubdata = true;
+        self
+    }
+
+    /// Makes the adjuster use the price provided by the oracle as the pubdata price.
+    pub fn with_da_cost_oracle(mut self, oracle: DACostOracle) -> Self {
+        self.da_cost_oracle = Some(oracle);
+        self
+    }
+
+    /// Makes the adjuster additionally track prices on L1 underlying the settlement layer, which should be
+    /// used if the settlement layer is an L2 rollup. L1 prices are included into the estimated pubdata price.
+    pub async fn with_underlying_l1(
+        mut self,
+        l1_eth_client: Arc<dyn EthInterface>,
+    ) -> Result<Self, Error> {
+        let (statistics, blob_base_fee_statistics) =
+            Self::init_statistics(l1_eth_client.as_ref(), &self.config).await?;
+        self.underlying_l1 = Some(UnderlyingL1 {
+            statistics,
+            blob_base_fee_statistics,
+            eth_client: l1_eth_client,
+        });
+        Ok(self)
+    }
+
+    async fn init_statistics(
+        eth_client: &dyn EthInterface,
+        config: &GasAdjusterConfig,
+    ) -> Result<(GasStatistics, GasStatistics), Error> {
         // Subtracting 1 from the "latest" block number to prevent errors in case
         // the info about the latest block is not yet present on the node.
         // This sometimes happens on Infura.
@@ -43,45 +110,97 @@ impl GasAdjuster {
         let history = eth_client
             .base_fee_history(current_block, config.max_base_fee_samples, "gas_adjuster")
             .await?;
-        Ok(Self {
-            statistics: GasStatistics::new(config.max_base_fee_samples, current_block, &history),
-            eth_client,
-            config,
-        })
+        let blob_base_fee = Self::fetch_blob_base_fee(eth_client).await;
+        let blob_base_fee_statistics = GasStatistics::new(
+            config.num_samples_for_blob_base_fee_estimate,
+            current_block,
+            blob_base_fee.as_slice(),
+        );
+        let statistics = GasStatistics::new(config.max_base_fee_samples, current_block, &history);
+        Ok((statistics, blob_base_fee_statistics))
+    }
+
+    /// Fetches the blob base fee for the next L1 block. Returns `None` if L1 doesn't support EIP-4844
+    /// or the fee cannot be fetched; in this case, pubdata is published using calldata.
+    async fn fetch_blob_base_fee(eth_client: &dyn EthInterface) -> Option<u64> {
+        match eth_client.get_blob_base_fee("gas_adjuster").await {
+            Ok(fee) => fee.map(|fee| fee.min(u64::MAX.into()).as_u64()),
+            Err(err) => {
+                tracing::warn!("Cannot fetch blob base fee: {err}");
+                None
+            }
+        }
     }
 
     /// Performs an actualization routine for `GasAdjuster`.
     /// This method is intended to be invoked periodically.
     pub async fn keep_updated(&self) -> Result<(), Error> {
+        let (base_fee, blob_base_fee) = Self::update_statistics(
+            self.eth_client.as_ref(),
+            &self.statistics,
+            &self.blob_base_fee_statistics,
+        )
+        .await?;
+        if let Some(base_fee) = base_fee {
+            METRICS.current_base_fee_per_gas.set(base_fee);
+        }
+        if let Some(blob_base_fee) = blob_base_fee {
+            METRICS.current_blob_base_fee_per_gas.set(blob_base_fee);
+        }
+
+        if let Some(l1) = &self.underlying_l1 {
+            let (base_fee, blob_base_fee) = Self::update_statistics(
+                l1.eth_client.as_ref(),
+                &l1.statistics,
+                &l1.blob_base_fee_statistics,
+            )
+            .await?;
+            if let Some(base_fee) = base_fee {
+                METRICS.current_l1_base_fee_per_gas.set(base_fee);
+            }
+            if let Some(blob_base_fee) = blob_base_fee {
+                METRICS.current_l1_blob_base_fee_per_gas.set(blob_base_fee);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds samples for new blocks to the statistics. Returns the newly observed base fee and blob base fee
+    /// (if there are new blocks).
+    async fn update_statistics(
+        eth_client: &dyn EthInterface,
+        statistics: &GasStatistics,
+        blob_base_fee_statistics: &GasStatistics,
+    ) -> Result<(Option<u64>, Option<u64>), Error> {
         // Subtracting 1 from the "latest" block number to prevent errors in case
         // the info about the latest block is not yet present on the node.
         // This sometimes happens on Infura.
-        let current_block = self
-            .eth_client
+        let current_block = eth_client
             .block_number("gas_adjuster")
             .await?
             .as_usize()
             .saturating_sub(1);
 
-        let last_processed_block = self.statistics.last_processed_block();
+        let last_processed_block = statistics.last_processed_block();
+        if current_block <= last_processed_block {
+            return Ok((None, None));
+        }
 
-        if current_block > last_processed_block {
-            // Report the current price to be gathered by the statistics module.
-            let history = self
-                .eth_client
-                .base_fee_history(
-                    current_block,
-                    current_block - last_processed_block,
-                    "gas_adjuster",
-                )
-                .await?;
+        // Report the current price to be gathered by the statistics module.
+        let history = eth_client
+            .base_fee_history(
+                current_block,
+                current_block - last_processed_block,
+                "gas_adjuster",
+            )
+            .await?;
+        statistics.add_samples(&history);
 
-            METRICS
-                .current_base_fee_per_gas
-                .set(*history.last().unwrap());
-            self.statistics.add_samples(&history);
+        let blob_base_fee = Self::fetch_blob_base_fee(eth_client).await;
+        if let Some(blob_base_fee) = blob_base_fee {
+            blob_base_fee_statistics.add_samples(&[blob_base_fee]);
         }
-        Ok(())
+        Ok((history.last().copied(), blob_base_fee))
     }
 
     fn bound_gas_price(&self, gas_price: u64) -> u64 {
@@ -107,6 +226,11 @@ impl GasAdjuster {
             if let Err(err) = self.keep_updated().await {
                 tracing::warn!("Cannot add the base fee to gas statistics: {}", err);
             }
+            if let Some(oracle) = &self.da_cost_oracle {
+                if let Err(err) = oracle.keep_updated().await {
+                    tracing::warn!("Cannot update the DA layer pubdata price: {err:#}");
+                }
+            }
 
             tokio::time::sleep(self.config.poll_period()).await;
         }
@@ -129,9 +253,65 @@ impl GasAdjuster {
         self.bound_gas_price(calculated_price)
     }
 
+    fn fee_scale_factor(&self, time_in_mempool: u32) -> f64 {
+        let a = self.config.pricing_formula_parameter_a;
+        let b = self.config.pricing_formula_parameter_b;
+
+        // Currently we use an exponential formula.
+        // The alternative is a linear one:
+        // `let scale_factor = a + b * time_in_mempool as f64;`
+        a * b.powf(time_in_mempool as f64)
+    }
+
     pub(crate) fn estimate_effective_pubdata_price(&self) -> u64 {
-        // For now, pubdata is only sent via calldata, so its price is pegged to the L1 gas price.
-        self.estimate_effective_gas_price() * L1_GAS_PER_PUBDATA_BYTE as u64
+        if let Some(oracle) = &self.da_cost_oracle {
+            // Until the DA layer price is observed, pubdata is priced as if it was published on the settlement layer.
+            if let Some(price) = oracle.estimate_price_per_byte() {
+                return price;
+            }
+        }
+
+        let mut settlement_price =
+            self.estimate_effective_gas_price() * L1_GAS_PER_PUBDATA_BYTE as u64;
+        if self.blob_pubdata {
+            if let Some(blob_price) = self.estimate_effective_blob_pubdata_price() {
+                // The Ethereum sender falls back to calldata if blobs are more expensive.
+                settlement_price = settlement_price.min(blob_price);
+            }
+        }
+        let Some(l1) = &self.underlying_l1 else {
+            return settlement_price;
+        };
+
+        // If the settlement layer is an L2 rollup, it additionally charges for publishing calldata on L1.
+        let l1_price =
+            (l1.pubdata_price_per_byte() as f64 * self.config.l1_pubdata_price_scalar) as u64;
+        METRICS.l1_pubdata_price_per_byte.set(l1_price);
+        settlement_price.saturating_add(l1_price)
+    }
+
+    /// Returns the price of publishing a pubdata byte in EIP-4844 blobs on the settlement layer, or `None`
+    /// if the settlement layer doesn't support blobs.
+    fn estimate_effective_blob_pubdata_price(&self) -> Option<u64> {
+        if self.blob_base_fee_statistics.is_empty() {
+            return None;
+        }
+        // Each blob byte consumes 1 unit of blob gas.
+        let blob_base_fee = self.blob_base_fee_statistics.median();
+        Some((self.config.internal_l1_pricing_multiplier * blob_base_fee as f64) as u64)
+    }
+}
+
+impl UnderlyingL1 {
+    /// Returns the median L1 price of publishing a byte of data. Blobs are assumed to be used by the settlement layer
+    /// if L1 supports them.
+    fn pubdata_price_per_byte(&self) -> u64 {
+        if self.blob_base_fee_statistics.is_empty() {
+            self.statistics.median() * L1_GAS_PER_PUBDATA_BYTE as u64
+        } else {
+            // Each blob byte consumes 1 unit of blob gas.
+            self.blob_base_fee_statistics.median()
+        }
     }
 }
 
@@ -143,17 +323,18 @@ impl L1TxParamsProvider for GasAdjuster {
     // In other words, in order to pay less fees, we are ready to wait longer.
     // But the longer we wait, the more we are ready to pay.
     fn get_base_fee(&self, time_in_mempool: u32) -> u64 {
-        let a = self.config.pricing_formula_parameter_a;
-        let b = self.config.pricing_formula_parameter_b;
-
-        // Currently we use an exponential formula.
-        // The alternative is a linear one:
-        // `let scale_factor = a + b * time_in_mempool as f64;`
-        let scale_factor = a * b.powf(time_in_mempool as f64);
         let median = self.statistics.median();
         METRICS.median_base_fee_per_gas.set(median);
-        let new_fee = median as f64 * scale_factor;
-        new_fee as u64
+        (median as f64 * self.fee_scale_factor(time_in_mempool)) as u64
+    }
+
+    fn get_blob_base_fee(&self, time_in_mempool: u32) -> Option<u64> {
+        if self.blob_base_fee_statistics.is_empty() {
+            return None;
+        }
+        let median = self.blob_base_fee_statistics.median();
+        METRICS.median_blob_base_fee_per_gas.set(median);
+        Some((median as f64 * self.fee_scale_factor(time_in_mempool)) as u64)
     }
 
     fn get_next_block_minimal_base_fee(&self) -> u64 {
@@ -210,7 +391,14 @@ impl GasStatisticsInner {
         self.samples.back().copied().unwrap_or(self.median_cached)
     }
 
+    fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
     fn add_samples(&mut self, fees: &[u64]) {
+        if fees.is_empty() {
+            return;
+        }
         self.samples.extend(fees);
         self.last_processed_block += fees.len();
 
@@ -244,6 +432,10 @@ impl GasStatistics {
         self.0.read().unwrap().last_added_value()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.read().unwrap().is_empty()
+    }
+
     pub fn add_samples(&self, fees: &[u64]) {
         self.0.write().unwrap().add_samples(fees)
     }
@@ -1,9 +1,13 @@
 use std::{collections::VecDeque, sync::Arc};
 
-use zksync_config::GasAdjusterConfig;
+use zksync_config::{configs::eth_sender::DACostOracleConfig, GasAdjusterConfig};
 use zksync_eth_client::clients::MockEthereum;
 
 use super::{GasAdjuster, GasStatisticsInner};
+use crate::{
+    da_dispatcher::NoDAClient,
+    l1_gas_price::{DACostOracle, L1TxParamsProvider},
+};
 
 /// Check that we compute the median correctly
 #[test]
@@ -44,6 +48,8 @@ async fn kept_updated() {
             internal_enforced_l1_gas_price: None,
             poll_period: 5,
             max_l1_gas_price: None,
+            num_samples_for_blob_base_fee_estimate: 3,
+            l1_pubdata_price_scalar: 1.0,
         },
     )
     .await
@@ -58,3 +64,155 @@ async fn kept_updated() {
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
     assert_eq!(adjuster.statistics.0.read().unwrap().median(), 7);
 }
+
+/// Check that the blob base fee is tracked only if L1 supports EIP-4844
+#[tokio::test]
+async fn blob_base_fee_is_tracked() {
+    let config = GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.5,
+        pricing_formula_parameter_b: 1.0005,
+        internal_l1_pricing_multiplier: 0.8,
+        internal_enforced_l1_gas_price: None,
+        poll_period: 5,
+        max_l1_gas_price: None,
+        num_samples_for_blob_base_fee_estimate: 3,
+        l1_pubdata_price_scalar: 1.0,
+    };
+    let fee_history = vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9];
+
+    let eth_client = Arc::new(MockEthereum::default().with_fee_history(fee_history.clone()));
+    eth_client.advance_block_number(5);
+    let adjuster = GasAdjuster::new(eth_client, config).await.unwrap();
+    assert_eq!(adjuster.get_blob_base_fee(0), None);
+
+    let eth_client = Arc::new(
+        MockEthereum::default()
+            .with_fee_history(fee_history)
+            .with_blob_base_fee(Some(2.into())),
+    );
+    eth_client.advance_block_number(5);
+    let adjuster = GasAdjuster::new(eth_client.clone(), config).await.unwrap();
+    assert_eq!(adjuster.blob_base_fee_statistics.median(), 2);
+    assert_eq!(adjuster.get_blob_base_fee(0), Some(3));
+
+    eth_client.advance_block_number(1);
+    adjuster.keep_updated().await.unwrap();
+    assert_eq!(
+        adjuster.blob_base_fee_statistics.0.read().unwrap().samples,
+        VecDeque::from([2, 2])
+    );
+}
+
+/// Check that L1 prices are included into the pubdata price if the settlement layer is an L2 rollup
+#[tokio::test]
+async fn underlying_l1_prices_are_included_into_pubdata_price() {
+    let config = GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.5,
+        pricing_formula_parameter_b: 1.0005,
+        internal_l1_pricing_multiplier: 0.8,
+        internal_enforced_l1_gas_price: Some(10),
+        poll_period: 5,
+        max_l1_gas_price: None,
+        num_samples_for_blob_base_fee_estimate: 3,
+        l1_pubdata_price_scalar: 0.5,
+    };
+    let fee_history = vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9];
+
+    let settlement_client = Arc::new(MockEthereum::default().with_fee_history(fee_history.clone()));
+    settlement_client.advance_block_number(5);
+    let adjuster = GasAdjuster::new(settlement_client.clone(), config)
+        .await
+        .unwrap();
+    // Settlement layer gas price (10) * `L1_GAS_PER_PUBDATA_BYTE` (17)
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), 170);
+
+    let l1_client = Arc::new(MockEthereum::default().with_fee_history(fee_history.clone()));
+    l1_client.advance_block_number(5);
+    let adjuster = adjuster
+        .with_underlying_l1(l1_client.clone())
+        .await
+        .unwrap();
+    // 170 + L1 median base fee (6) * 17 * scalar (0.5)
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), 221);
+
+    settlement_client.advance_block_number(3);
+    l1_client.advance_block_number(3);
+    adjuster.keep_updated().await.unwrap();
+    let l1 = adjuster.underlying_l1.as_ref().unwrap();
+    assert_eq!(l1.statistics.median(), 7);
+    // 170 + 7 * 17 * 0.5, rounded down
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), 229);
+
+    // If L1 supports blobs, the blob base fee is used instead.
+    let l1_client = Arc::new(
+        MockEthereum::default()
+            .with_fee_history(fee_history)
+            .with_blob_base_fee(Some(20.into())),
+    );
+    l1_client.advance_block_number(5);
+    let adjuster = GasAdjuster::new(settlement_client, config)
+        .await
+        .unwrap()
+        .with_underlying_l1(l1_client)
+        .await
+        .unwrap();
+    // 170 + blob base fee (20) * 0.5
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), 180);
+}
+
+/// Check that pubdata is priced according to the DA layer cost if configured
+#[tokio::test]
+async fn pubdata_price_follows_da_layer_cost() {
+    let config = GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.5,
+        pricing_formula_parameter_b: 1.0005,
+        internal_l1_pricing_multiplier: 0.8,
+        internal_enforced_l1_gas_price: Some(10),
+        poll_period: 5,
+        max_l1_gas_price: None,
+        num_samples_for_blob_base_fee_estimate: 3,
+        l1_pubdata_price_scalar: 1.0,
+    };
+    let eth_client = Arc::new(
+        MockEthereum::default()
+            .with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9])
+            .with_blob_base_fee(Some(20.into())),
+    );
+    eth_client.advance_block_number(5);
+
+    // Blob base fee (20) * multiplier (0.8) is cheaper than calldata (10 * 17).
+    let adjuster = GasAdjuster::new(eth_client.clone(), config)
+        .await
+        .unwrap()
+        .with_blob_pubdata();
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), 16);
+
+    let oracle_config = DACostOracleConfig {
+        max_price_samples: 3,
+        native_token_price_in_wei: None,
+        price_scalar: 1.0,
+        max_price_per_byte: None,
+        eigenda_payment_vault_address: None,
+    };
+    let oracle = DACostOracle::new(Box::new(NoDAClient), oracle_config);
+    let adjuster = GasAdjuster::new(eth_client, config)
+        .await
+        .unwrap()
+        .with_da_cost_oracle(oracle);
+    // Until the DA layer price is observed, pubdata is priced as calldata.
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), 170);
+    adjuster
+        .da_cost_oracle
+        .as_ref()
+        .unwrap()
+        .keep_updated()
+        .await
+        .unwrap();
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), 0);
+}
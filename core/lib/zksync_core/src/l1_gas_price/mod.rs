@@ -2,10 +2,12 @@
 
 use std::fmt;
 
+pub use da_cost_oracle::DACostOracle;
 pub use gas_adjuster::GasAdjuster;
 pub use main_node_fetcher::MainNodeFeeParamsFetcher;
-pub use singleton::GasAdjusterSingleton;
+pub use singleton::{DAPricingParams, GasAdjusterSingleton};
 
+mod da_cost_oracle;
 mod gas_adjuster;
 mod main_node_fetcher;
 pub mod singleton;
@@ -18,6 +20,9 @@ pub trait L1TxParamsProvider: fmt::Debug + 'static + Send + Sync {
     /// Returns the recommended `max_fee_per_gas` value (EIP1559).
     fn get_base_fee(&self, time_in_mempool: u32) -> u64;
 
+    /// Returns the recommended `max_fee_per_blob_gas` value (EIP4844), or `None` if L1 doesn't support blobs.
+    fn get_blob_base_fee(&self, time_in_mempool: u32) -> Option<u64>;
+
     /// Returns the recommended `max_priority_fee_per_gas` value (EIP1559).
     fn get_priority_fee(&self) -> u64;
 
@@ -5,17 +5,35 @@ use tokio::{
     sync::{watch, OnceCell},
     task::JoinHandle,
 };
-use zksync_config::GasAdjusterConfig;
-use zksync_eth_client::clients::QueryClient;
+use zksync_config::{
+    configs::{
+        chain::DataAvailabilityMode,
+        eth_sender::{DACostOracleConfig, DADispatcherConfig, PubdataSendingMode},
+    },
+    GasAdjusterConfig,
+};
+use zksync_eth_client::{clients::QueryClient, EthInterface};
+
+use crate::l1_gas_price::{DACostOracle, GasAdjuster};
 
-use crate::l1_gas_price::GasAdjuster;
+/// Parameters of pricing pubdata based on the actual cost of the data availability layer of the chain.
+#[derive(Debug, Clone)]
+pub struct DAPricingParams {
+    pub mode: DataAvailabilityMode,
+    pub pubdata_sending_mode: PubdataSendingMode,
+    pub oracle_config: DACostOracleConfig,
+    /// Required for non-rollup DA modes.
+    pub da_dispatcher_config: Option<DADispatcherConfig>,
+}
 
 /// Special struct for creating a singleton of `GasAdjuster`.
 /// This is needed only for running the server.
 #[derive(Debug)]
 pub struct GasAdjusterSingleton {
     web3_url: String,
+    l1_web3_url: Option<String>,
     gas_adjuster_config: GasAdjusterConfig,
+    da_pricing: Option<DAPricingParams>,
     singleton: OnceCell<Result<Arc<GasAdjuster>, Error>>,
 }
 
@@ -30,24 +48,69 @@ impl From<anyhow::Error> for Error {
 }
 
 impl GasAdjusterSingleton {
-    pub fn new(web3_url: String, gas_adjuster_config: GasAdjusterConfig) -> Self {
+    pub fn new(
+        web3_url: String,
+        l1_web3_url: Option<String>,
+        gas_adjuster_config: GasAdjusterConfig,
+    ) -> Self {
         Self {
             web3_url,
+            l1_web3_url,
             gas_adjuster_config,
+            da_pricing: None,
             singleton: OnceCell::new(),
         }
     }
 
+    /// Enables pricing of pubdata based on the actual DA layer cost.
+    pub fn with_da_pricing(mut self, params: DAPricingParams) -> Self {
+        self.da_pricing = Some(params);
+        self
+    }
+
     pub async fn get_or_init(&mut self) -> Result<Arc<GasAdjuster>, Error> {
         let adjuster = self
             .singleton
             .get_or_init(|| async {
                 let query_client =
                     QueryClient::new(&self.web3_url).context("QueryClient::new()")?;
-                let adjuster =
-                    GasAdjuster::new(Arc::new(query_client.clone()), self.gas_adjuster_config)
+                let query_client: Arc<dyn EthInterface> = Arc::new(query_client);
+                let mut adjuster = GasAdjuster::new(query_client.clone(), self.gas_adjuster_config)
+                    .await
+                    .context("GasAdjuster::new()")?;
+                let mut l1_client = query_client;
+                if let Some(l1_web3_url) = &self.l1_web3_url {
+                    let l1_query_client =
+                        QueryClient::new(l1_web3_url).context("QueryClient::new() for L1")?;
+                    l1_client = Arc::new(l1_query_client);
+                    adjuster = adjuster
+                        .with_underlying_l1(l1_client.clone())
                         .await
-                        .context("GasAdjuster::new()")?;
+                        .context("GasAdjuster::with_underlying_l1()")?;
+                }
+
+                if let Some(params) = &self.da_pricing {
+                    if params.mode == DataAvailabilityMode::Rollup {
+                        if params.pubdata_sending_mode == PubdataSendingMode::Blobs {
+                            adjuster = adjuster.with_blob_pubdata();
+                        }
+                    } else {
+                        let da_dispatcher_config = params
+                            .da_dispatcher_config
+                            .as_ref()
+                            .context("da_dispatcher config is required for non-rollup DA modes")?;
+                        let oracle = DACostOracle::from_config(
+                            params.mode,
+                            da_dispatcher_config,
+                            params.oracle_config.clone(),
+                            l1_client,
+                        )
+                        .context("DACostOracle::from_config()")?;
+                        if let Some(oracle) = oracle {
+                            adjuster = adjuster.with_da_cost_oracle(oracle);
+                        }
+                    }
+                }
                 Ok(Arc::new(adjuster))
             })
             .await;
@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+use vise::{Buckets, Gauge, Histogram, Metrics, Unit};
+
+/// Metrics for the leader election between sequencer instances.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_leader_election")]
+pub(super) struct LeaderElectionMetrics {
+    /// 1 if this instance is the sequencer leader, 0 if it's a standby.
+    pub is_leader: Gauge<u64>,
+    /// Latency of catching up the state keeper cache by a standby instance.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub standby_catchup_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<LeaderElectionMetrics> = vise::Global::new();
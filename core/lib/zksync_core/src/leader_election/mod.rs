@@ -0,0 +1,496 @@
+//! Leader election between sequencer instances running in the active / standby mode.
+//!
+//! Instances compete for a session-level Postgres advisory lock (see [`LeaderLockDal`]); the instance holding the lock
+//! is the leader and is the only one running singleton writers, i.e. the state keeper, the Ethereum sender and other
+//! components sending settlement layer transactions (see [`LeaderTasks`]). Standby instances running the state keeper
+//! periodically catch up their state keeper cache with Postgres, so that on takeover the state keeper doesn't need
+//! to process a large backlog of L1 batches.
+//! The unsealed L1 batch of the previous leader is re-executed by the new leader from its persisted miniblocks,
+//! the same way as on a sequencer restart.
+//!
+//! [`LeaderLockDal`]: zksync_dal::leader_lock_dal::LeaderLockDal
+//!
+//! The leader checks that it still holds the lock with the configured interval and stops block production as soon as
+//! the lock is lost (e.g., if its Postgres session is terminated), or if the check doesn't complete within the
+//! interval. After acquiring the lock, a new leader waits for two check intervals before starting block production, so
+//! that the previous leader has time to notice the loss of the lock. Unique constraints on miniblock and L1 batch
+//! numbers in Postgres act as the last line of defense against double sealing.
+//!
+//! Since the lock is bound to a Postgres session, instances must connect to Postgres directly or via a pooler
+//! in the session mode; a transaction-mode pooler breaks the lock semantics.
+
+use std::{
+    fmt,
+    future::Future,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use futures::future::{self, BoxFuture, FutureExt};
+use serde::Serialize;
+use tokio::{sync::watch, task::JoinHandle};
+use zksync_config::LeaderElectionConfig;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_state::RocksdbStorage;
+
+use self::metrics::METRICS;
+
+mod metrics;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Role {
+    Standby,
+    Leader,
+}
+
+/// Health details reported by [`LeaderElection`].
+#[derive(Debug, Serialize)]
+struct LeaderElectionHealth {
+    lock_id: i64,
+    role: Role,
+}
+
+/// Result of running leader tasks.
+#[derive(Debug)]
+enum LeaderOutcome {
+    /// A leader task has completed.
+    TaskCompleted(anyhow::Result<()>),
+    /// The stop signal was received.
+    Stopped,
+    /// The leader lock was lost, or it cannot be checked.
+    LockLost(anyhow::Error),
+}
+
+type StartLeaderTasks = Box<
+    dyn FnOnce(
+            watch::Receiver<bool>,
+        ) -> BoxFuture<'static, anyhow::Result<Vec<JoinHandle<anyhow::Result<()>>>>>
+        + Send,
+>;
+
+/// Tasks that must be executed by a single sequencer instance, collected to be started by [`LeaderElection`].
+#[derive(Default)]
+pub struct LeaderTasks {
+    starters: Vec<StartLeaderTasks>,
+}
+
+impl fmt::Debug for LeaderTasks {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("LeaderTasks")
+            .field("starters", &self.starters.len())
+            .finish()
+    }
+}
+
+impl LeaderTasks {
+    /// Adds a task to be spawned once this instance becomes the leader.
+    pub fn push<F, Fut>(&mut self, task: F)
+    where
+        F: FnOnce(watch::Receiver<bool>) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.starters.push(Box::new(|stop_receiver| {
+            future::ready(Ok(vec![tokio::spawn(task(stop_receiver))])).boxed()
+        }));
+    }
+
+    /// Adds a group of tasks started asynchronously (e.g., the state keeper together with its auxiliary tasks)
+    /// once this instance becomes the leader.
+    pub fn push_group<F, Fut>(&mut self, start_tasks: F)
+    where
+        F: FnOnce(watch::Receiver<bool>) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<Vec<JoinHandle<anyhow::Result<()>>>>> + Send + 'static,
+    {
+        self.starters
+            .push(Box::new(|stop_receiver| start_tasks(stop_receiver).boxed()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.starters.is_empty()
+    }
+
+    /// Starts all tasks, which will receive the provided stop signal.
+    pub async fn start(
+        self,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Vec<JoinHandle<anyhow::Result<()>>>> {
+        let mut tasks = vec![];
+        for start_tasks in self.starters {
+            tasks.extend(start_tasks(stop_receiver.clone()).await?);
+        }
+        Ok(tasks)
+    }
+}
+
+/// Runs tasks that must be executed by a single sequencer instance (e.g., the state keeper and its auxiliary tasks)
+/// only while this instance is the leader.
+///
+/// The provided connection pool must allow at least 2 connections: one is dedicated to holding the lock,
+/// and another one is used to catch up the state keeper cache while in standby (if enabled).
+#[derive(Debug)]
+pub struct LeaderElection {
+    pool: ConnectionPool,
+    config: LeaderElectionConfig,
+    state_keeper_db_path: Option<PathBuf>,
+    health_updater: HealthUpdater,
+}
+
+impl LeaderElection {
+    pub fn new(pool: ConnectionPool, config: LeaderElectionConfig) -> Self {
+        Self {
+            pool,
+            config,
+            state_keeper_db_path: None,
+            health_updater: ReactiveHealthCheck::new("leader_election").1,
+        }
+    }
+
+    /// Enables catching up the state keeper cache at the specified path while in standby.
+    #[must_use]
+    pub fn with_state_keeper_cache(mut self, state_keeper_db_path: impl Into<PathBuf>) -> Self {
+        self.state_keeper_db_path = Some(state_keeper_db_path.into());
+        self
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    fn update_role(&self, role: Role) {
+        METRICS.is_leader.set((role == Role::Leader).into());
+        let details = LeaderElectionHealth {
+            lock_id: self.config.lock_id,
+            role,
+        };
+        let health = Health::from(HealthStatus::Ready).with_details(details);
+        self.health_updater.update(health);
+    }
+
+    /// Catches up the state keeper cache with Postgres, so that the state keeper started on takeover
+    /// only needs to process L1 batches sealed since the last catch-up.
+    async fn catch_up_state_keeper_cache(
+        &self,
+        state_keeper_db_path: &Path,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let latency = METRICS.standby_catchup_latency.start();
+        let mut storage = self.pool.access_storage_tagged("leader_election").await?;
+        let builder = RocksdbStorage::builder(state_keeper_db_path)
+            .await
+            .context("failed opening state keeper cache")?;
+        let synced = builder
+            .synchronize(&mut storage, stop_receiver)
+            .await
+            .context("failed synchronizing state keeper cache")?;
+        if synced.is_some() {
+            let latency = latency.observe();
+            tracing::debug!("Caught up state keeper cache with Postgres in {latency:?}");
+        }
+        Ok(())
+    }
+
+    /// Waits until the leader lock is acquired on `conn`. Returns `false` if interrupted by the stop signal.
+    async fn wait_for_leadership(
+        &self,
+        conn: &mut StorageProcessor<'_>,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<bool> {
+        let mut last_catchup: Option<Instant> = None;
+        loop {
+            if *stop_receiver.borrow() {
+                return Ok(false);
+            }
+            let acquired = conn
+                .leader_lock_dal()
+                .try_acquire(self.config.lock_id)
+                .await
+                .context("failed acquiring leader lock")?;
+            if acquired {
+                return Ok(true);
+            }
+
+            if let Some(state_keeper_db_path) = &self.state_keeper_db_path {
+                let catchup_interval = self.config.standby_catchup_interval();
+                let is_catchup_due =
+                    last_catchup.map_or(true, |at| at.elapsed() >= catchup_interval);
+                if is_catchup_due {
+                    // A stale cache only slows down takeover, so errors are not fatal.
+                    let result = self
+                        .catch_up_state_keeper_cache(state_keeper_db_path, stop_receiver)
+                        .await;
+                    if let Err(err) = result {
+                        tracing::warn!("Failed catching up state keeper cache: {err:#}");
+                    }
+                    last_catchup = Some(Instant::now());
+                }
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.config.check_interval(), stop_receiver.changed())
+                .await
+                .ok();
+        }
+    }
+
+    /// Runs the leader election. Once this instance becomes the leader, starts tasks using `start_leader_tasks`
+    /// and runs them until either of them completes, the leadership is lost, or the stop signal is received.
+    /// Leader tasks receive a separate stop signal, which is sent in all these cases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the leadership is lost; the node is expected to shut down in this case and restart
+    /// as a standby.
+    pub async fn run<F, Fut>(
+        self,
+        start_leader_tasks: F,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()>
+    where
+        F: FnOnce(watch::Receiver<bool>) -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<JoinHandle<anyhow::Result<()>>>>>,
+    {
+        let lock_id = self.config.lock_id;
+        let mut conn = self.pool.access_storage_tagged("leader_election").await?;
+        self.update_role(Role::Standby);
+        tracing::info!("Waiting to acquire sequencer leader lock {lock_id}");
+        if !self
+            .wait_for_leadership(&mut conn, &mut stop_receiver)
+            .await?
+        {
+            tracing::info!("Stop signal received, leader election is shutting down");
+            return Ok(());
+        }
+
+        let check_interval = self.config.check_interval();
+        let takeover_delay = check_interval * 2;
+        tracing::info!(
+            "Acquired sequencer leader lock {lock_id}; starting block production in {takeover_delay:?}"
+        );
+        if tokio::time::timeout(takeover_delay, stop_receiver.changed())
+            .await
+            .is_ok()
+        {
+            tracing::info!("Stop signal received, leader election is shutting down");
+            Self::release_lock(&mut conn, lock_id).await;
+            return Ok(());
+        }
+
+        self.update_role(Role::Leader);
+        let (leader_stop_sender, leader_stop_receiver) = watch::channel(false);
+        let tasks = start_leader_tasks(leader_stop_receiver)
+            .await
+            .context("failed starting leader tasks")?;
+        anyhow::ensure!(!tasks.is_empty(), "no leader tasks were started");
+        tracing::info!("Started {} leader tasks", tasks.len());
+
+        let mut tasks = future::select_all(tasks);
+        let (outcome, remaining_tasks) = loop {
+            tokio::select! {
+                (result, _, remaining_tasks) = &mut tasks => {
+                    let result = result
+                        .context("leader task panicked")
+                        .and_then(|result| result);
+                    break (LeaderOutcome::TaskCompleted(result), remaining_tasks);
+                }
+                _ = stop_receiver.changed() => {
+                    break (LeaderOutcome::Stopped, tasks.into_inner());
+                }
+                () = tokio::time::sleep(check_interval) => {
+                    // A new leader starts block production 2 check intervals after acquiring the lock, so the check
+                    // must complete within an interval. A hanging check may mean that the lock session is broken.
+                    let is_held =
+                        tokio::time::timeout(check_interval, conn.leader_lock_dal().is_held(lock_id)).await;
+                    match is_held {
+                        Ok(Ok(true)) => { /* continue running */ }
+                        Ok(Ok(false)) => {
+                            let err = anyhow::anyhow!("sequencer leader lock {lock_id} is lost");
+                            break (LeaderOutcome::LockLost(err), tasks.into_inner());
+                        }
+                        Ok(Err(err)) => {
+                            // If the lock session is broken, the lock is released by Postgres, so we must assume
+                            // that it's lost.
+                            let err = anyhow::Error::new(err)
+                                .context(format!("failed checking sequencer leader lock {lock_id}"));
+                            break (LeaderOutcome::LockLost(err), tasks.into_inner());
+                        }
+                        Err(_) => {
+                            let err = anyhow::anyhow!(
+                                "timed out checking sequencer leader lock {lock_id} after {check_interval:?}"
+                            );
+                            break (LeaderOutcome::LockLost(err), tasks.into_inner());
+                        }
+                    }
+                }
+            }
+        };
+
+        leader_stop_sender.send_replace(true);
+        METRICS.is_leader.set(0);
+        match &outcome {
+            LeaderOutcome::TaskCompleted(_) => {
+                tracing::info!("Leader task completed; stopping other leader tasks");
+            }
+            LeaderOutcome::Stopped => {
+                tracing::info!("Stop signal received, stopping leader tasks");
+            }
+            LeaderOutcome::LockLost(err) => {
+                tracing::error!("Stopping block production: {err:#}");
+            }
+        }
+        // Leader tasks must stop before the lock is released; otherwise, the standby instance could start
+        // producing blocks concurrently.
+        for result in future::join_all(remaining_tasks).await {
+            match result {
+                Ok(Ok(())) => { /* task stopped normally */ }
+                Ok(Err(err)) => tracing::warn!("Leader task failed while stopping: {err:#}"),
+                Err(err) => tracing::warn!("Leader task panicked while stopping: {err}"),
+            }
+        }
+
+        match outcome {
+            LeaderOutcome::TaskCompleted(result) => {
+                Self::release_lock(&mut conn, lock_id).await;
+                result
+            }
+            LeaderOutcome::Stopped => {
+                Self::release_lock(&mut conn, lock_id).await;
+                Ok(())
+            }
+            LeaderOutcome::LockLost(err) => Err(err),
+        }
+    }
+
+    async fn release_lock(conn: &mut StorageProcessor<'_>, lock_id: i64) {
+        // The lock is released on disconnect anyway, so errors are not critical.
+        match conn.leader_lock_dal().release(lock_id).await {
+            Ok(_) => tracing::info!("Released sequencer leader lock {lock_id}"),
+            Err(err) => tracing::warn!("Failed releasing sequencer leader lock {lock_id}: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+    use zksync_health_check::CheckHealth;
+
+    use super::*;
+
+    const LOCK_ID: i64 = -156;
+
+    fn test_config() -> LeaderElectionConfig {
+        LeaderElectionConfig {
+            lock_id: LOCK_ID,
+            check_interval_ms: 10,
+            standby_catchup_interval_ms: 10,
+        }
+    }
+
+    /// Starts a leader task reporting its start to `started_sender` and running until the stop signal.
+    async fn start_test_task(
+        name: &'static str,
+        started_sender: mpsc::UnboundedSender<&'static str>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Vec<JoinHandle<anyhow::Result<()>>>> {
+        let task = tokio::spawn(async move {
+            started_sender.send(name).ok();
+            while !*stop_receiver.borrow() {
+                if stop_receiver.changed().await.is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        });
+        Ok(vec![task])
+    }
+
+    #[tokio::test]
+    async fn standby_takes_over_after_leader_stops() {
+        let pool = ConnectionPool::constrained_test_pool(4).await;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (started_sender, mut started_receiver) = mpsc::unbounded_channel();
+
+        let first_election = LeaderElection::new(pool.clone(), test_config())
+            .with_state_keeper_cache(temp_dir.path().join("first"));
+        let (first_stop_sender, first_stop_receiver) = watch::channel(false);
+        let first_sender = started_sender.clone();
+        let first_handle = tokio::spawn(first_election.run(
+            move |stop_receiver| start_test_task("first", first_sender, stop_receiver),
+            first_stop_receiver,
+        ));
+        assert_eq!(started_receiver.recv().await, Some("first"));
+
+        let second_election = LeaderElection::new(pool, test_config())
+            .with_state_keeper_cache(temp_dir.path().join("second"));
+        let second_health = second_election.health_check();
+        let (second_stop_sender, second_stop_receiver) = watch::channel(false);
+        let second_handle = tokio::spawn(second_election.run(
+            move |stop_receiver| start_test_task("second", started_sender, stop_receiver),
+            second_stop_receiver,
+        ));
+        while second_health.check_health().await.status() != HealthStatus::Ready {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // The standby must not start leader tasks while the leader holds the lock.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(started_receiver.try_recv().is_err());
+
+        first_stop_sender.send_replace(true);
+        first_handle.await.unwrap().unwrap();
+        assert_eq!(started_receiver.recv().await, Some("second"));
+
+        second_stop_sender.send_replace(true);
+        second_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn leader_starts_all_singleton_tasks() {
+        let pool = ConnectionPool::constrained_test_pool(2).await;
+        let (started_sender, mut started_receiver) = mpsc::unbounded_channel();
+
+        let mut leader_tasks = LeaderTasks::default();
+        let group_sender = started_sender.clone();
+        leader_tasks.push_group(move |stop_receiver| {
+            start_test_task("state_keeper", group_sender, stop_receiver)
+        });
+        let mut task_stop_receivers = vec![];
+        for name in ["eth_tx_aggregator", "eth_tx_manager"] {
+            let started_sender = started_sender.clone();
+            let (task_stop_sender, task_stop_receiver) = mpsc::unbounded_channel();
+            task_stop_receivers.push(task_stop_receiver);
+            leader_tasks.push(move |mut stop_receiver| async move {
+                started_sender.send(name).ok();
+                stop_receiver.changed().await.ok();
+                task_stop_sender.send(name).ok();
+                Ok(())
+            });
+        }
+        assert!(!leader_tasks.is_empty());
+
+        let election = LeaderElection::new(pool, test_config());
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let handle = tokio::spawn(election.run(
+            move |stop_receiver| leader_tasks.start(stop_receiver),
+            stop_receiver,
+        ));
+        let mut started_tasks = vec![];
+        for _ in 0..3 {
+            started_tasks.push(started_receiver.recv().await.unwrap());
+        }
+        started_tasks.sort_unstable();
+        assert_eq!(
+            started_tasks,
+            ["eth_tx_aggregator", "eth_tx_manager", "state_keeper"]
+        );
+
+        stop_sender.send_replace(true);
+        handle.await.unwrap().unwrap();
+        // All leader tasks must be stopped before the election task completes.
+        for mut task_stop_receiver in task_stop_receivers {
+            assert!(task_stop_receiver.try_recv().is_ok());
+        }
+    }
+}
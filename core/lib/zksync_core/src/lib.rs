@@ -1,9 +1,12 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
-use std::{net::Ipv4Addr, str::FromStr, sync::Arc, time::Instant};
+use std::{future::Future, net::Ipv4Addr, str::FromStr, sync::Arc, time::Instant};
 
 use anyhow::Context as _;
-use fee_model::{ApiFeeInputProvider, BatchFeeModelInputProvider, MainNodeFeeInputProvider};
+use fee_model::{
+    ApiFeeInputProvider, BatchFeeModelInputProvider, CongestionMultiplier,
+    CongestionMultiplierUpdater, MainNodeFeeInputProvider,
+};
 use futures::channel::oneshot;
 use prometheus_exporter::PrometheusExporterConfig;
 use temp_config_store::TempConfigStore;
@@ -17,45 +20,75 @@ use zksync_config::{
     configs::{
         api::{MerkleTreeApiConfig, Web3JsonRpcConfig},
         chain::{
-            CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
-            StateKeeperConfig,
+            CircuitBreakerConfig, DataAvailabilityMode, MempoolConfig, NetworkConfig,
+            OperationsManagerConfig, StateKeeperConfig,
         },
         contracts::ProverAtGenesis,
         database::{MerkleTreeConfig, MerkleTreeMode},
+        eth_sender::{
+            OperatorBalanceMonitorConfig, OperatorSignerConfig, OperatorSignerMode,
+            ProofSendingMode,
+        },
+        proof_data_handler::ProofGenerationMode,
     },
-    ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, PostgresConfig,
+    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, PostgresConfig,
+    SharedSequencerConfig, StableGasPriceConfig,
 };
 use zksync_contracts::{governance_contract, BaseSystemContracts};
-use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
+use zksync_dal::{
+    connection::{WorkloadClass, WorkloadQuotas},
+    healthcheck::ConnectionPoolHealthCheck,
+    ConnectionPool,
+};
 use zksync_eth_client::{
-    clients::{PKSigningClient, QueryClient},
-    CallFunctionArgs, EthInterface,
+    clients::{PKSigningClient, QueryClient, RemoteSigningClient},
+    BoundEthInterface, CallFunctionArgs, EthInterface,
+};
+use zksync_eth_signer::{
+    remote_signer::{AwsKmsBackend, GcpKmsBackend, Web3SignerBackend},
+    RemoteSigner,
 };
 use zksync_health_check::{CheckHealth, HealthStatus, ReactiveHealthCheck};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     fee_model::FeeModelConfig,
+    network::Network,
     protocol_version::{L1VerifierConfig, VerifierParams},
     system_contracts::get_system_smart_contracts,
     web3::contract::tokens::Detokenize,
-    L2ChainId, PackedEthSignature, ProtocolVersionId,
+    Address, L2ChainId, PackedEthSignature, ProtocolVersionId,
 };
 
 use crate::{
     api_server::{
         contract_verification,
-        execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
+        execution_sandbox::{
+            ApiStateCache, SandboxWarmPool, VmConcurrencyBarrier, VmConcurrencyLimiter,
+        },
         healthcheck::HealthCheckHandle,
-        tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
+        rosetta::RosettaApi,
+        tx_sender::{
+            ApiContracts, BatchCapacity, BatchFillForecaster, ReplayProtection, TxIntakePolicy,
+            TxSender, TxSenderBuilder, TxSenderConfig, WithdrawalLimiter,
+        },
         web3,
-        web3::{state::InternalApiConfig, ApiServerHandles, Namespace},
+        web3::{namespaces::AdminHandles, state::InternalApiConfig, ApiServerHandles, Namespace},
     },
+    audit_log::{self, AuditLogExporter},
     basic_witness_input_producer::BasicWitnessInputProducer,
+    cdc_publisher::{create_broker, CdcPublisher},
     commitment_generator::CommitmentGenerator,
-    eth_sender::{Aggregator, EthTxAggregator, EthTxManager},
-    eth_watch::start_eth_watch,
+    config_reload::{ConfigReloader, ReloadableConfig},
+    da_dispatcher::{create_da_client, DataAvailabilityDispatcher, PubdataCompression},
+    eth_sender::{
+        Aggregator, EthSenderHealthCheck, EthTxAggregator, EthTxManager, FundingContractTopUpHook,
+        OperatorAccounts, OperatorBalanceMonitor, RemoteSignerHealthCheck, WebhookTopUpHook,
+    },
+    eth_watch::{start_bridge_watcher, start_eth_watch},
+    fee_distributor::FeeDistributor,
     house_keeper::{
         blocks_state_reporter::L1BatchMetricsReporter,
         fri_proof_compressor_job_retry_manager::FriProofCompressorJobRetryManager,
@@ -68,35 +101,70 @@ use crate::{
         periodic_job::PeriodicJob,
         waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
     },
-    l1_gas_price::GasAdjusterSingleton,
-    metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
+    l1_gas_price::{DAPricingParams, GasAdjusterSingleton},
+    leader_election::{LeaderElection, LeaderTasks},
+    message_relay::{EthRelayClient, MessageRelay},
+    metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig, TreeLagHealthCheck},
     metrics::{InitStage, APP_METRICS},
+    reexecution_watchdog::ReexecutionWatchdog,
+    shared_sequencer::{self, shared_sequencer_feed},
+    stable_gas_price::{create_price_feed, StableGasPrice, StableGasPriceUpdater},
     state_keeper::{
-        create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer, SequencerSealer,
+        create_state_keeper, ForcedInclusionHealthCheck, MempoolFetcher, MempoolGuard,
+        MiniblockSealer, ScheduledTxsInjector, SequencerSealer, StateKeeperControl,
+        StateKeeperHealthCheck,
     },
+    supervisor::{RestartPolicy, SupervisedTask},
+    supply_checker::{EthLockedFundsClient, LockedFunds, SupplyChecker},
+    utils::contracts_validation::validate_contracts_config,
+    webhooks::{BatchWebhookDispatcher, HttpWebhookClient},
+    withdrawal_finalizer::{EthFinalizerClient, WithdrawalFinalizer},
 };
 
 pub mod api_server;
+pub mod audit_log;
 pub mod basic_witness_input_producer;
+pub mod batch_archive;
 pub mod block_reverter;
+pub mod cdc_publisher;
 pub mod commitment_generator;
+pub mod config_reload;
 pub mod consensus;
 pub mod consistency_checker;
+pub mod da_dispatcher;
+pub mod da_verifier;
+pub mod db_partition_manager;
+pub mod db_pruner;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod fee_distributor;
 pub mod fee_model;
+#[cfg(feature = "firehose")]
+pub mod firehose;
 pub mod gas_tracker;
 pub mod genesis;
 pub mod house_keeper;
+#[cfg(feature = "in-memory-node")]
+pub mod in_memory_node;
 pub mod l1_gas_price;
+pub mod leader_election;
+pub mod message_relay;
 pub mod metadata_calculator;
 mod metrics;
 pub mod proof_data_handler;
+pub mod proof_verifier;
+pub mod reexecution_watchdog;
 pub mod reorg_detector;
+pub mod shared_sequencer;
+pub mod stable_gas_price;
 pub mod state_keeper;
+pub mod supervisor;
+pub mod supply_checker;
 pub mod sync_layer;
 pub mod temp_config_store;
 mod utils;
+pub mod webhooks;
+pub mod withdrawal_finalizer;
 
 /// Inserts the initial information about zkSync tokens into the database.
 pub async fn genesis_init(
@@ -163,6 +231,11 @@ pub async fn genesis_init(
             }
         };
 
+    let manifest = contracts_config
+        .genesis_manifest_path
+        .as_ref()
+        .map(|path| genesis::GenesisManifest::load(path.as_ref()))
+        .transpose()?;
     genesis::ensure_genesis_state(
         &mut storage,
         network_config.zksync_network_id,
@@ -174,6 +247,7 @@ pub async fn genesis_init(
             system_contracts: get_system_smart_contracts(),
             first_verifier_address: contracts_config.verifier_addr,
             first_l1_verifier_config,
+            manifest,
         },
     )
     .await?;
@@ -252,6 +326,29 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// Component publishing sealed miniblocks, receipts and L1 batch status changes to a message broker.
+    CdcPublisher,
+    /// Component tracking deposits to the chain on the settlement layer, including non-finalized ones.
+    BridgeWatcher,
+    /// Component finalizing withdrawals from the chain on the settlement layer on behalf of users.
+    WithdrawalFinalizer,
+    /// Component relaying arbitrary messages between the chain and the settlement layer.
+    MessageRelay,
+    /// Component notifying external services about L1 batch status changes via webhooks.
+    BatchWebhooks,
+    /// Component exporting the audit log of sensitive node actions to a file.
+    AuditLogExporter,
+    /// Component sweeping fees collected by the fee account to the treasury, sequencer and burn destinations.
+    FeeDistributor,
+    /// Component reconciling the base token supply on the chain with funds locked on the settlement layer.
+    SupplyChecker,
+    /// Component re-executing a random sample of recent L1 batches and alerting on divergences
+    /// from the persisted execution outcome.
+    ReexecutionWatchdog,
+    /// Rosetta (Mesh) API used by exchanges to integrate with the chain.
+    RosettaApi,
+    /// Firehose-compatible gRPC block stream used by The Graph and Substreams indexers.
+    Firehose,
 }
 
 #[derive(Debug)]
@@ -288,6 +385,17 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "cdc_publisher" => Ok(Components(vec![Component::CdcPublisher])),
+            "bridge_watcher" => Ok(Components(vec![Component::BridgeWatcher])),
+            "withdrawal_finalizer" => Ok(Components(vec![Component::WithdrawalFinalizer])),
+            "message_relay" => Ok(Components(vec![Component::MessageRelay])),
+            "webhooks" => Ok(Components(vec![Component::BatchWebhooks])),
+            "audit_log_exporter" => Ok(Components(vec![Component::AuditLogExporter])),
+            "fee_distributor" => Ok(Components(vec![Component::FeeDistributor])),
+            "supply_checker" => Ok(Components(vec![Component::SupplyChecker])),
+            "reexecution_watchdog" => Ok(Components(vec![Component::ReexecutionWatchdog])),
+            "rosetta_api" => Ok(Components(vec![Component::RosettaApi])),
+            "firehose" => Ok(Components(vec![Component::Firehose])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -314,7 +422,9 @@ pub async fn initialize_components(
     }
 
     let pool_size = postgres_config.max_connections()?;
+    let workload_quotas = database_workload_quotas(&postgres_config);
     let connection_pool = ConnectionPool::builder(postgres_config.master_url()?, pool_size)
+        .set_workload_quotas(workload_quotas.clone())
         .build()
         .await
         .context("failed to build connection_pool")?;
@@ -324,11 +434,20 @@ pub async fn initialize_components(
         ConnectionPool::builder(postgres_config.replica_url()?, pool_size)
             .set_acquire_timeout(postgres_config.acquire_timeout())
             .set_statement_timeout(postgres_config.statement_timeout())
+            .set_workload_quotas(workload_quotas)
             .build()
             .await
             .context("failed to build replica_connection_pool")?;
+    // Components sharing the main and replica pools are subject to per-workload connection quotas. Other components
+    // (e.g., the state keeper and the Ethereum sender) use dedicated pools.
+    let api_pool = connection_pool.for_workload(WorkloadClass::Api);
+    let api_replica_pool = replica_connection_pool.for_workload(WorkloadClass::Api);
+    let connection_pool = connection_pool.for_workload(WorkloadClass::Background);
+    let replica_connection_pool = replica_connection_pool.for_workload(WorkloadClass::Background);
 
     let mut healthchecks: Vec<Box<dyn CheckHealth>> = Vec::new();
+    // Components are registered in the handles once initialized, i.e., after the API servers are started.
+    let admin_handles = AdminHandles::default();
     let contracts_config = configs
         .contracts_config
         .clone()
@@ -353,9 +472,40 @@ pub async fn initialize_components(
     });
 
     let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
+    if let Some(network_config) = &configs.network_config {
+        let mut storage = connection_pool
+            .access_storage()
+            .await
+            .context("access_storage()")?;
+        validate_contracts_config(
+            &query_client,
+            &mut storage,
+            &contracts_config,
+            network_config,
+        )
+        .await
+        .context("validate_contracts_config()")?;
+    }
     let gas_adjuster_config = configs.gas_adjuster_config.context("gas_adjuster_config")?;
-    let mut gas_adjuster =
-        GasAdjusterSingleton::new(eth_client_config.web3_url.clone(), gas_adjuster_config);
+    let mut gas_adjuster = GasAdjusterSingleton::new(
+        eth_client_config.web3_url.clone(),
+        eth_client_config.l1_web3_url.clone(),
+        gas_adjuster_config,
+    );
+    if let Some(eth_sender) = &configs.eth_sender_config {
+        if let Some(oracle_config) = &eth_sender.da_cost_oracle {
+            let state_keeper_config = configs
+                .state_keeper_config
+                .as_ref()
+                .context("state_keeper_config")?;
+            gas_adjuster = gas_adjuster.with_da_pricing(DAPricingParams {
+                mode: state_keeper_config.data_availability_mode,
+                pubdata_sending_mode: eth_sender.sender.pubdata_sending_mode,
+                oracle_config: oracle_config.clone(),
+                da_dispatcher_config: eth_sender.da_dispatcher.clone(),
+            });
+        }
+    }
 
     let (stop_sender, stop_receiver) = watch::channel(false);
     let (cb_sender, cb_receiver) = oneshot::channel();
@@ -383,6 +533,44 @@ pub async fn initialize_components(
         tokio::spawn(circuit_breaker_checker.run(cb_sender, stop_receiver.clone())),
     ];
 
+    // The congestion multiplier is shared among all fee input providers, so that they return consistent prices.
+    let congestion_multiplier = if components.contains(&Component::HttpApi)
+        || components.contains(&Component::WsApi)
+        || components.contains(&Component::StateKeeper)
+        || components.contains(&Component::FeeDistributor)
+    {
+        let state_keeper_config = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?;
+        build_congestion_multiplier(
+            state_keeper_config,
+            &connection_pool,
+            &stop_receiver,
+            &mut task_futures,
+        )
+    } else {
+        CongestionMultiplier::default()
+    };
+    // The stable gas price is shared for the same reason.
+    let stable_gas_price = if components.contains(&Component::HttpApi)
+        || components.contains(&Component::WsApi)
+        || components.contains(&Component::StateKeeper)
+        || components.contains(&Component::FeeDistributor)
+    {
+        build_stable_gas_price(
+            configs.stable_gas_price_config.as_ref(),
+            &eth_client_config,
+            &stop_receiver,
+            &mut task_futures,
+        )?
+    } else {
+        StableGasPrice::default()
+    };
+    let reloadable_config =
+        build_reloadable_config(configs, &connection_pool, &stop_receiver, &mut task_futures);
+    let restart_policy = RestartPolicy::for_restartable_task(configs.supervisor_config.as_ref());
+
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
         || components.contains(&Component::ContractVerificationApi)
@@ -402,6 +590,7 @@ pub async fn initialize_components(
             &network_config,
             &api_config.web3_json_rpc,
             &contracts_config,
+            &state_keeper_config,
         );
 
         // Lazily initialize storage caches only when they are needed (e.g., skip their initialization
@@ -409,10 +598,41 @@ pub async fn initialize_components(
         // terminate immediately if storage caches are dropped, which will lead to the (unexpected)
         // program termination.
         let mut storage_caches = None;
+        let api_state_cache =
+            if components.contains(&Component::HttpApi) || components.contains(&Component::WsApi) {
+                build_api_state_cache(
+                    &db_config,
+                    &api_replica_pool,
+                    &stop_receiver,
+                    &mut task_futures,
+                )
+            } else {
+                None
+            };
+        let sandbox_warm_pool = if (components.contains(&Component::HttpApi)
+            || components.contains(&Component::WsApi))
+            && api_config.web3_json_rpc.sandbox_warm_pool_enabled
+        {
+            Some(build_sandbox_warm_pool(
+                api_state_cache.clone(),
+                &api_replica_pool,
+                &stop_receiver,
+                &mut task_futures,
+            ))
+        } else {
+            None
+        };
+
+        if components.contains(&Component::HttpApi) || components.contains(&Component::WsApi) {
+            let operator_auth_token = api_config.web3_json_rpc.operator_auth_token.as_deref();
+            audit_log::record_operator_auth_token(&connection_pool, operator_auth_token)
+                .await
+                .context("record_operator_auth_token()")?;
+        }
 
         if components.contains(&Component::HttpApi) {
             storage_caches = Some(
-                build_storage_caches(configs, &replica_connection_pool, &mut task_futures)
+                build_storage_caches(configs, &api_replica_pool, &mut task_futures)
                     .context("build_storage_caches()")?,
             );
 
@@ -422,22 +642,32 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
+            let batch_fee_input_provider = Arc::new(
+                MainNodeFeeInputProvider::new(
+                    bounded_gas_adjuster,
+                    FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+                )
+                .with_congestion_multiplier(congestion_multiplier.clone())
+                .with_stable_gas_price(stable_gas_price.clone())
+                .with_reloadable_config(reloadable_config.clone()),
+            );
             let server_handles = run_http_api(
                 &postgres_config,
                 &tx_sender_config,
                 &state_keeper_config,
                 &internal_api_config,
                 &api_config,
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
+                api_pool.clone(),
+                api_replica_pool.clone(),
                 stop_receiver.clone(),
                 batch_fee_input_provider,
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
+                api_state_cache.clone(),
+                sandbox_warm_pool.clone(),
+                admin_handles.clone(),
+                reloadable_config.clone(),
+                restart_policy.clone(),
             )
             .await
             .context("run_http_api")?;
@@ -455,7 +685,7 @@ pub async fn initialize_components(
         if components.contains(&Component::WsApi) {
             let storage_caches = match storage_caches {
                 Some(storage_caches) => storage_caches,
-                None => build_storage_caches(configs, &replica_connection_pool, &mut task_futures)
+                None => build_storage_caches(configs, &api_replica_pool, &mut task_futures)
                     .context("build_storage_caches()")?,
             };
 
@@ -465,10 +695,15 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
+            let batch_fee_input_provider = Arc::new(
+                MainNodeFeeInputProvider::new(
+                    bounded_gas_adjuster,
+                    FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+                )
+                .with_congestion_multiplier(congestion_multiplier.clone())
+                .with_stable_gas_price(stable_gas_price.clone())
+                .with_reloadable_config(reloadable_config.clone()),
+            );
             let server_handles = run_ws_api(
                 &postgres_config,
                 &tx_sender_config,
@@ -476,10 +711,15 @@ pub async fn initialize_components(
                 &internal_api_config,
                 &api_config,
                 batch_fee_input_provider,
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
+                api_pool.clone(),
+                api_replica_pool.clone(),
                 stop_receiver.clone(),
                 storage_caches,
+                api_state_cache,
+                sandbox_warm_pool,
+                admin_handles.clone(),
+                reloadable_config.clone(),
+                restart_policy.clone(),
             )
             .await
             .context("run_ws_api")?;
@@ -498,8 +738,8 @@ pub async fn initialize_components(
             let started_at = Instant::now();
             tracing::info!("initializing contract verification REST API");
             task_futures.push(contract_verification::start_server_thread_detached(
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
+                api_pool.clone(),
+                api_replica_pool.clone(),
                 api_config.contract_verification.clone(),
                 stop_receiver.clone(),
             ));
@@ -514,6 +754,11 @@ pub async fn initialize_components(
         .clone()
         .context("object_store_config")?;
     let store_factory = ObjectStoreFactory::new(object_store_config);
+    // Singleton writers that must only run on the leader instance if the leader election is enabled.
+    let mut leader_tasks = configs
+        .leader_election_config
+        .is_some()
+        .then(LeaderTasks::default);
 
     if components.contains(&Component::StateKeeper) {
         let started_at = Instant::now();
@@ -526,24 +771,122 @@ pub async fn initialize_components(
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
-        let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-            bounded_gas_adjuster,
-            FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-        ));
-        add_state_keeper_to_task_futures(
-            &mut task_futures,
-            &postgres_config,
-            &contracts_config,
-            state_keeper_config,
-            &configs.network_config.clone().context("network_config")?,
-            &db_config,
-            &configs.mempool_config.clone().context("mempool_config")?,
-            batch_fee_input_provider,
-            store_factory.create_store().await,
-            stop_receiver.clone(),
-        )
-        .await
-        .context("add_state_keeper_to_task_futures()")?;
+        let batch_fee_input_provider = Arc::new(
+            MainNodeFeeInputProvider::new(
+                bounded_gas_adjuster,
+                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+            )
+            .with_congestion_multiplier(congestion_multiplier.clone())
+            .with_stable_gas_price(stable_gas_price.clone())
+            .with_reloadable_config(reloadable_config.clone()),
+        );
+        let network_config = configs.network_config.clone().context("network_config")?;
+        let mempool_config = configs.mempool_config.clone().context("mempool_config")?;
+        let object_store = store_factory.create_store().await;
+        // Transactions streamed by the shared sequencer are subject to the same replay protection as ones
+        // submitted via the API; if the API config is not provided, unprotected transactions are rejected.
+        let mut replay_protection = ReplayProtection::new(network_config.zksync_network_id);
+        if let Some(api_config) = &configs.api_config {
+            let web3_config = &api_config.web3_json_rpc;
+            replay_protection = replay_protection.with_pre_eip155_txs(
+                web3_config.pre_eip155_txs_allowed,
+                web3_config.pre_eip155_tx_senders.clone(),
+            );
+        }
+
+        // Scheduled transactions are validated via a dedicated transaction sender, so that they are validated
+        // in the same way as transactions submitted via the API.
+        let scheduled_txs_injector = match state_keeper_config.scheduled_txs_private_key() {
+            Some(private_key) => {
+                let api_config = configs.api_config.as_ref().context("api_config")?;
+                let tx_sender_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                    .build()
+                    .await
+                    .context("failed to build scheduled_txs_tx_sender_pool")?;
+                let tx_sender_config = TxSenderConfig::new(
+                    &state_keeper_config,
+                    &api_config.web3_json_rpc,
+                    network_config.zksync_network_id,
+                );
+                let storage_caches =
+                    build_storage_caches(configs, &api_replica_pool, &mut task_futures)
+                        .context("build_storage_caches()")?;
+                let (tx_sender, _vm_barrier) = build_tx_sender(
+                    &tx_sender_config,
+                    &api_config.web3_json_rpc,
+                    &state_keeper_config,
+                    api_replica_pool.clone(),
+                    tx_sender_pool,
+                    batch_fee_input_provider.clone(),
+                    storage_caches,
+                    None,
+                    None,
+                    contracts_config.l2_erc20_bridge_addr,
+                )
+                .await;
+                let injector = ScheduledTxsInjector::new(
+                    private_key,
+                    network_config.zksync_network_id,
+                    tx_sender,
+                )
+                .context("failed initializing scheduled transactions injector")?;
+                Some(injector)
+            }
+            None => None,
+        };
+
+        if let Some(leader_tasks) = &mut leader_tasks {
+            let postgres_config = postgres_config.clone();
+            let contracts_config = contracts_config.clone();
+            let db_config = db_config.clone();
+            let admin_handles = admin_handles.clone();
+            let shared_sequencer_config = configs.shared_sequencer_config.clone();
+            let reloadable_config = reloadable_config.clone();
+            let start_state_keeper = move |stop_receiver| async move {
+                let mut state_keeper_tasks = vec![];
+                add_state_keeper_to_task_futures(
+                    &mut state_keeper_tasks,
+                    &postgres_config,
+                    &contracts_config,
+                    state_keeper_config,
+                    &network_config,
+                    &db_config,
+                    &mempool_config,
+                    batch_fee_input_provider,
+                    object_store,
+                    &admin_handles,
+                    shared_sequencer_config.as_ref(),
+                    replay_protection,
+                    scheduled_txs_injector,
+                    reloadable_config,
+                    stop_receiver,
+                )
+                .await
+                .context("add_state_keeper_to_task_futures()")?;
+                Ok(state_keeper_tasks)
+            };
+            leader_tasks.push_group(start_state_keeper);
+        } else {
+            add_state_keeper_to_task_futures(
+                &mut task_futures,
+                &postgres_config,
+                &contracts_config,
+                state_keeper_config,
+                &network_config,
+                &db_config,
+                &mempool_config,
+                batch_fee_input_provider,
+                object_store,
+                &admin_handles,
+                configs.shared_sequencer_config.as_ref(),
+                replay_protection,
+                scheduled_txs_injector,
+                reloadable_config.clone(),
+                stop_receiver.clone(),
+            )
+            .await
+            .context("add_state_keeper_to_task_futures()")?;
+        }
 
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::StateKeeper].set(elapsed);
@@ -602,8 +945,10 @@ pub async fn initialize_components(
                 eth_watch_config,
                 eth_watch_pool,
                 Arc::new(query_client.clone()),
+                &eth_client_config.web3_url,
                 main_zksync_contract_address,
                 governance,
+                restart_policy.clone(),
                 stop_receiver.clone(),
             )
             .await
@@ -614,6 +959,29 @@ pub async fn initialize_components(
         tracing::info!("initialized ETH-Watcher in {elapsed:?}");
     }
 
+    if components.contains(&Component::BridgeWatcher) {
+        let bridge_watcher_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build bridge_watcher_pool")?;
+        let eth_watch_config = configs
+            .eth_watch_config
+            .clone()
+            .context("eth_watch_config")?;
+        task_futures.push(
+            start_bridge_watcher(
+                eth_watch_config,
+                bridge_watcher_pool,
+                Arc::new(query_client.clone()),
+                &eth_client_config.web3_url,
+                main_zksync_contract_address,
+                stop_receiver.clone(),
+            )
+            .await
+            .context("start_bridge_watcher()")?,
+        );
+    }
+
     if components.contains(&Component::EthTxAggregator) {
         let started_at = Instant::now();
         tracing::info!("initializing ETH-TxAggregator");
@@ -626,28 +994,99 @@ pub async fn initialize_components(
             .eth_sender_config
             .clone()
             .context("eth_sender_config")?;
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_tx_aggregator_actor = EthTxAggregator::new(
+        let network_config = configs.network_config.as_ref().context("network_config")?;
+        if eth_sender.sender.proof_sending_mode == ProofSendingMode::OnlyMockProofs {
+            anyhow::ensure!(
+                network_config.network != Network::Mainnet,
+                "mock proofs cannot be sent to Ethereum mainnet"
+            );
+        }
+        let remote_signer = eth_sender
+            .operator_signer
+            .as_ref()
+            .map(build_remote_signer)
+            .transpose()?;
+        let operator_accounts = build_operator_accounts(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+            remote_signer,
+        );
+        let gas_adjuster = gas_adjuster
+            .get_or_init()
+            .await
+            .context("gas_adjuster.get_or_init()")?;
+        let data_availability_mode = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?
+            .data_availability_mode;
+        let mut da_failover_timeout = None;
+        if data_availability_mode == DataAvailabilityMode::Rollup {
+            anyhow::ensure!(
+                eth_sender.da_dispatcher.is_none(),
+                "da_dispatcher config must not be set for rollup chains, which publish pubdata on L1"
+            );
+        } else {
+            let da_dispatcher_config = eth_sender
+                .da_dispatcher
+                .clone()
+                .context("da_dispatcher config is required for non-rollup DA modes")?;
+            da_failover_timeout = da_dispatcher_config.failover_timeout();
+            let da_client = create_da_client(data_availability_mode, &da_dispatcher_config)?;
+            let da_dispatcher_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build da_dispatcher_pool")?;
+            let compression = PubdataCompression::from_config(&da_dispatcher_config)
+                .context("failed initializing pubdata compression")?;
+            let mut da_dispatcher = DataAvailabilityDispatcher::new(
+                da_client,
+                da_dispatcher_pool,
+                da_dispatcher_config,
+            );
+            if let Some(compression) = compression {
+                da_dispatcher = da_dispatcher.with_compression(compression);
+            }
+            spawn_singleton_task(
+                &mut task_futures,
+                leader_tasks.as_mut(),
+                &stop_receiver,
+                |stop_receiver| da_dispatcher.run(stop_receiver),
+            );
+        }
+        let mut eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender.sender.clone(),
             Aggregator::new(
                 eth_sender.sender.clone(),
                 store_factory.create_store().await,
-            ),
-            Arc::new(eth_client),
+                gas_adjuster.clone(),
+            )
+            .with_data_availability_mode(data_availability_mode)
+            .with_da_failover_timeout(da_failover_timeout),
+            operator_accounts,
+            gas_adjuster,
             contracts_config.validator_timelock_addr,
             contracts_config.l1_multicall3_addr,
             main_zksync_contract_address,
-            configs
-                .network_config
-                .as_ref()
-                .context("network_config")?
-                .zksync_network_id,
+            network_config.zksync_network_id,
         )
         .await;
-        task_futures.push(tokio::spawn(
-            eth_tx_aggregator_actor.run(eth_sender_pool, stop_receiver.clone()),
-        ));
+        if let Some(emergency_mode_config) = &eth_sender.emergency_mode {
+            tracing::warn!(
+                "ETH-TxAggregator runs in the emergency mode: L1 batches won't be committed, and L1 batches \
+                 committed by the operator will be proved and executed once it's inactive for {:?}",
+                emergency_mode_config.inactivity_threshold()
+            );
+            eth_tx_aggregator_actor =
+                eth_tx_aggregator_actor.with_emergency_mode(emergency_mode_config);
+        }
+        spawn_singleton_task(
+            &mut task_futures,
+            leader_tasks.as_mut(),
+            &stop_receiver,
+            |stop_receiver| eth_tx_aggregator_actor.run(eth_sender_pool, stop_receiver),
+        );
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::EthTxAggregator].set(elapsed);
         tracing::info!("initialized ETH-TxAggregator in {elapsed:?}");
@@ -664,19 +1103,46 @@ pub async fn initialize_components(
             .eth_sender_config
             .clone()
             .context("eth_sender_config")?;
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
+        let remote_signer = eth_sender
+            .operator_signer
+            .as_ref()
+            .map(build_remote_signer)
+            .transpose()?;
+        if let Some(remote_signer) = &remote_signer {
+            healthchecks.push(Box::new(RemoteSignerHealthCheck::new(
+                remote_signer.clone(),
+            )));
+        }
+        let operator_accounts = build_operator_accounts(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+            remote_signer,
+        );
+        if let Some(balance_monitor_config) = &eth_sender.balance_monitor {
+            let balance_monitor = build_balance_monitor(
+                balance_monitor_config,
+                &eth_sender,
+                &contracts_config,
+                &eth_client_config,
+                operator_accounts.clone(),
+            )?;
+            task_futures.push(tokio::spawn(balance_monitor.run(stop_receiver.clone())));
+        }
         let eth_tx_manager_actor = EthTxManager::new(
             eth_sender.sender,
             gas_adjuster
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?,
-            Arc::new(eth_client),
+            operator_accounts,
+        );
+        spawn_singleton_task(
+            &mut task_futures,
+            leader_tasks.as_mut(),
+            &stop_receiver,
+            |stop_receiver| eth_tx_manager_actor.run(eth_manager_pool, stop_receiver),
         );
-        task_futures.extend([tokio::spawn(
-            eth_tx_manager_actor.run(eth_manager_pool, stop_receiver.clone()),
-        )]);
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::EthTxManager].set(elapsed);
         tracing::info!("initialized ETH-TxManager in {elapsed:?}");
@@ -717,11 +1183,20 @@ pub async fn initialize_components(
     }
 
     if components.contains(&Component::ProofDataHandler) {
+        let proof_data_handler_config = configs
+            .proof_data_handler_config
+            .clone()
+            .context("proof_data_handler_config")?;
+        if proof_data_handler_config.proof_generation_mode == ProofGenerationMode::Mock {
+            let mock_prover = proof_data_handler::MockProver::new(
+                &proof_data_handler_config,
+                connection_pool.clone(),
+                store_factory.create_store().await,
+            );
+            task_futures.push(tokio::spawn(mock_prover.run(stop_receiver.clone())));
+        }
         task_futures.push(tokio::spawn(proof_data_handler::run_server(
-            configs
-                .proof_data_handler_config
-                .clone()
-                .context("proof_data_handler_config")?,
+            proof_data_handler_config,
             configs
                 .contracts_config
                 .clone()
@@ -744,15 +1219,389 @@ pub async fn initialize_components(
         ));
     }
 
-    // Run healthcheck server for all components.
-    healthchecks.push(Box::new(ConnectionPoolHealthCheck::new(
-        replica_connection_pool,
-    )));
+    if components.contains(&Component::CdcPublisher) {
+        let cdc_publisher_config = configs
+            .cdc_publisher_config
+            .clone()
+            .context("cdc_publisher_config")?;
+        let broker = create_broker(&cdc_publisher_config)
+            .await
+            .context("failed to create CDC broker client")?;
+        let cdc_publisher_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build cdc_publisher_pool")?;
+        let cdc_publisher = CdcPublisher::new(broker, cdc_publisher_pool, cdc_publisher_config);
+        task_futures.push(tokio::spawn(cdc_publisher.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::BatchWebhooks) {
+        let webhooks_config = configs.webhooks_config.clone().context("webhooks_config")?;
+        // Check that the client can be created before spawning the dispatcher.
+        HttpWebhookClient::new(webhooks_config.request_timeout())?;
+        let webhooks_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build webhooks_pool")?;
+        let create_dispatcher = move |stop_receiver| {
+            let client = HttpWebhookClient::new(webhooks_config.request_timeout());
+            let webhooks_pool = webhooks_pool.clone();
+            let webhooks_config = webhooks_config.clone();
+            async move {
+                let dispatcher =
+                    BatchWebhookDispatcher::new(Box::new(client?), webhooks_pool, webhooks_config);
+                dispatcher.run(stop_receiver).await
+            }
+        };
+        let dispatcher =
+            SupervisedTask::new("batch_webhooks", restart_policy.clone(), create_dispatcher);
+        task_futures.push(tokio::spawn(dispatcher.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::AuditLogExporter) {
+        let audit_log_config = configs
+            .audit_log_config
+            .as_ref()
+            .context("audit_log_config")?;
+        let audit_log_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build audit_log_pool")?;
+        let exporter = AuditLogExporter::new(audit_log_pool, audit_log_config);
+        task_futures.push(tokio::spawn(exporter.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::WithdrawalFinalizer) {
+        let withdrawal_finalizer_config = configs
+            .withdrawal_finalizer_config
+            .clone()
+            .context("withdrawal_finalizer_config")?;
+        let eth_sender = configs
+            .eth_sender_config
+            .clone()
+            .context("eth_sender_config")?;
+        let private_key = withdrawal_finalizer_config
+            .private_key()
+            .context("withdrawal finalizer private key is not set")?;
+        let finalizer_client = PKSigningClient::from_config_with_key(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+            private_key,
+        );
+        tracing::info!(
+            "Withdrawals will be finalized from account {:?}",
+            finalizer_client.sender_account()
+        );
+        let finalizer_client = EthFinalizerClient::new(
+            Arc::new(finalizer_client),
+            contracts_config.diamond_proxy_addr,
+            contracts_config.l1_erc20_bridge_proxy_addr,
+            contracts_config.l1_multicall3_addr,
+        );
+        let withdrawal_finalizer_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build withdrawal_finalizer_pool")?;
+        let withdrawal_finalizer = WithdrawalFinalizer::new(
+            Box::new(finalizer_client),
+            withdrawal_finalizer_pool,
+            withdrawal_finalizer_config,
+            contracts_config.l2_erc20_bridge_addr,
+        )
+        .await
+        .context("failed initializing withdrawal finalizer")?;
+        spawn_singleton_task(
+            &mut task_futures,
+            leader_tasks.as_mut(),
+            &stop_receiver,
+            |stop_receiver| withdrawal_finalizer.run(stop_receiver),
+        );
+    }
+
+    if components.contains(&Component::MessageRelay) {
+        let message_relay_config = configs
+            .message_relay_config
+            .clone()
+            .context("message_relay_config")?;
+        let eth_sender = configs
+            .eth_sender_config
+            .clone()
+            .context("eth_sender_config")?;
+        let private_key = message_relay_config
+            .private_key()
+            .context("message relay private key is not set")?;
+        let relay_client = PKSigningClient::from_config_with_key(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+            private_key,
+        );
+        tracing::info!(
+            "Messages will be relayed from account {:?}",
+            relay_client.sender_account()
+        );
+        let relay_client = EthRelayClient::new(
+            Arc::new(relay_client),
+            message_relay_config.inbox_addr,
+            message_relay_config.outbox_addr,
+            contracts_config.l1_multicall3_addr,
+        )?;
+        let message_relay_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build message_relay_pool")?;
+        let message_relay = MessageRelay::new(
+            Box::new(relay_client),
+            message_relay_pool,
+            message_relay_config,
+        )
+        .await
+        .context("failed initializing message relay")?;
+        spawn_singleton_task(
+            &mut task_futures,
+            leader_tasks.as_mut(),
+            &stop_receiver,
+            |stop_receiver| message_relay.run(stop_receiver),
+        );
+    }
+
+    if components.contains(&Component::FeeDistributor) {
+        let fee_distributor_config = configs
+            .fee_distributor_config
+            .clone()
+            .context("fee_distributor_config")?;
+        let state_keeper_config = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?;
+        let network_config = configs.network_config.as_ref().context("network_config")?;
+        let api_config = configs.api_config.as_ref().context("api_config")?;
+        let private_key = fee_distributor_config
+            .private_key()
+            .context("invalid fee distributor config")?
+            .context("fee distributor private key is not set")?;
+        let fee_distributor_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build fee_distributor_pool")?;
+
+        // Transfers are submitted via a dedicated transaction sender, so that they are validated
+        // in the same way as transactions submitted via the API.
+        let tx_sender_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build fee_distributor_tx_sender_pool")?;
+        let tx_sender_config = TxSenderConfig::new(
+            state_keeper_config,
+            &api_config.web3_json_rpc,
+            network_config.zksync_network_id,
+        );
+        let storage_caches = build_storage_caches(configs, &api_replica_pool, &mut task_futures)
+            .context("build_storage_caches()")?;
+        let bounded_gas_adjuster = gas_adjuster
+            .get_or_init()
+            .await
+            .context("gas_adjuster.get_or_init()")?;
+        let batch_fee_input_provider = Arc::new(
+            MainNodeFeeInputProvider::new(
+                bounded_gas_adjuster,
+                FeeModelConfig::from_state_keeper_config(state_keeper_config),
+            )
+            .with_congestion_multiplier(congestion_multiplier.clone())
+            .with_stable_gas_price(stable_gas_price.clone())
+            .with_reloadable_config(reloadable_config.clone()),
+        );
+        let (tx_sender, _vm_barrier) = build_tx_sender(
+            &tx_sender_config,
+            &api_config.web3_json_rpc,
+            state_keeper_config,
+            api_replica_pool.clone(),
+            tx_sender_pool,
+            batch_fee_input_provider,
+            storage_caches,
+            None,
+            None,
+            contracts_config.l2_erc20_bridge_addr,
+        )
+        .await;
+
+        let fee_distributor = FeeDistributor::new(
+            fee_distributor_pool,
+            tx_sender,
+            fee_distributor_config,
+            private_key,
+            state_keeper_config.fee_account_addr,
+            network_config.zksync_network_id,
+        )
+        .context("failed initializing fee distributor")?
+        .with_reloadable_config(reloadable_config.clone());
+        spawn_singleton_task(
+            &mut task_futures,
+            leader_tasks.as_mut(),
+            &stop_receiver,
+            |stop_receiver| fee_distributor.run(stop_receiver),
+        );
+    }
 
+    if components.contains(&Component::SupplyChecker) {
+        let supply_checker_config = configs
+            .supply_checker_config
+            .clone()
+            .context("supply_checker_config")?;
+        let network_config = configs.network_config.as_ref().context("network_config")?;
+        let locked_funds = match (
+            supply_checker_config.locked_funds_addr,
+            contracts_config.l1_shared_bridge_proxy_addr,
+        ) {
+            (Some(locked_funds_addr), _) => LockedFunds::Contract(locked_funds_addr),
+            (None, Some(bridge_addr)) => LockedFunds::SharedBridge {
+                bridge_addr,
+                chain_id: network_config.zksync_network_id,
+            },
+            (None, None) => LockedFunds::Contract(contracts_config.diamond_proxy_addr),
+        };
+        tracing::info!("Base token supply will be checked against locked funds {locked_funds:?}");
+        let locked_funds_client = EthLockedFundsClient::new(
+            Box::new(QueryClient::new(&eth_client_config.web3_url)?),
+            supply_checker_config.base_token_l1_addr,
+            locked_funds,
+        );
+        let supply_checker_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build supply_checker_pool")?;
+        let supply_checker = SupplyChecker::new(
+            Box::new(locked_funds_client),
+            supply_checker_pool,
+            supply_checker_config,
+        );
+        healthchecks.push(Box::new(supply_checker.health_check()));
+        task_futures.push(tokio::spawn(supply_checker.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::ReexecutionWatchdog) {
+        let reexecution_watchdog_config = configs
+            .reexecution_watchdog_config
+            .clone()
+            .context("reexecution_watchdog_config")?;
+        let network_config = configs.network_config.as_ref().context("network_config")?;
+        let reexecution_watchdog_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build reexecution_watchdog_pool")?;
+        let reexecution_watchdog = ReexecutionWatchdog::new(
+            reexecution_watchdog_pool,
+            reexecution_watchdog_config,
+            network_config.zksync_network_id,
+        );
+        healthchecks.push(Box::new(reexecution_watchdog.health_check()));
+        task_futures.push(tokio::spawn(
+            reexecution_watchdog.run(stop_receiver.clone()),
+        ));
+    }
+
+    if components.contains(&Component::RosettaApi) {
+        let rosetta_api_config = configs
+            .rosetta_api_config
+            .clone()
+            .context("rosetta_api_config")?;
+        let network_config = configs.network_config.as_ref().context("network_config")?;
+        let bind_address = rosetta_api_config.bind_address();
+        let rosetta_api = RosettaApi::new(
+            api_replica_pool.clone(),
+            network_config.zksync_network_id,
+            &rosetta_api_config,
+        );
+        task_futures.push(tokio::spawn(
+            rosetta_api.run(bind_address, stop_receiver.clone()),
+        ));
+    }
+
+    if components.contains(&Component::Firehose) {
+        #[cfg(feature = "firehose")]
+        {
+            let firehose_config = configs.firehose_config.clone().context("firehose_config")?;
+            let network_config = configs.network_config.as_ref().context("network_config")?;
+            let firehose_server = firehose::FirehoseServer::new(
+                api_replica_pool.clone(),
+                network_config.zksync_network_id,
+                &firehose_config,
+                stop_receiver.clone(),
+            );
+            task_futures.push(tokio::spawn(
+                firehose_server.run(firehose_config.bind_address()),
+            ));
+        }
+        #[cfg(not(feature = "firehose"))]
+        anyhow::bail!(
+            "Firehose component is enabled, but the node is built without the `firehose` feature"
+        );
+    }
+
+    if let (Some(leader_election_config), Some(leader_tasks)) =
+        (configs.leader_election_config.clone(), leader_tasks)
+    {
+        if !leader_tasks.is_empty() {
+            tracing::info!(
+                "Singleton writers will only run while this instance is the sequencer leader"
+            );
+            // One connection holds the leader lock, and another one is used to catch up the state keeper cache.
+            let leader_election_pool = ConnectionPool::builder(postgres_config.master_url()?, 2)
+                .build()
+                .await
+                .context("failed to build leader_election_pool")?;
+            let mut leader_election =
+                LeaderElection::new(leader_election_pool, leader_election_config);
+            if components.contains(&Component::StateKeeper) {
+                leader_election =
+                    leader_election.with_state_keeper_cache(&db_config.state_keeper_db_path);
+            }
+            healthchecks.push(Box::new(leader_election.health_check()));
+            task_futures.push(tokio::spawn(leader_election.run(
+                move |stop_receiver| leader_tasks.start(stop_receiver),
+                stop_receiver.clone(),
+            )));
+        }
+    }
+
+    // Run healthcheck server for all components.
     let healtcheck_api_config = configs
         .health_check_config
         .clone()
         .context("health_check_config")?;
+    if components.contains(&Component::StateKeeper) {
+        healthchecks.push(Box::new(StateKeeperHealthCheck::new(
+            replica_connection_pool.clone(),
+            healtcheck_api_config.state_keeper_max_lag(),
+        )));
+        let state_keeper_config = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?;
+        healthchecks.push(Box::new(ForcedInclusionHealthCheck::new(
+            replica_connection_pool.clone(),
+            state_keeper_config.forced_inclusion_deadline(),
+        )));
+    }
+    if components.contains(&Component::Tree) {
+        healthchecks.push(Box::new(TreeLagHealthCheck::new(
+            replica_connection_pool.clone(),
+            healtcheck_api_config.tree_max_lag,
+        )));
+    }
+    if components.contains(&Component::EthTxManager) {
+        healthchecks.push(Box::new(EthSenderHealthCheck::new(
+            replica_connection_pool.clone(),
+            healtcheck_api_config.eth_sender_max_inflight_txs,
+        )));
+    }
+    healthchecks.push(Box::new(ConnectionPoolHealthCheck::new(
+        replica_connection_pool,
+    )));
+    let healthchecks: Vec<Arc<dyn CheckHealth>> = healthchecks.into_iter().map(Arc::from).collect();
+    admin_handles.set_health_checks(healthchecks.clone());
+
     let health_check_handle =
         HealthCheckHandle::spawn_server(healtcheck_api_config.bind_addr(), healthchecks);
 
@@ -762,6 +1611,41 @@ pub async fn initialize_components(
     Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
 }
 
+/// Returns connection quotas for workloads sharing the main and replica connection pools.
+fn database_workload_quotas(postgres_config: &PostgresConfig) -> WorkloadQuotas {
+    let mut quotas = WorkloadQuotas::default();
+    let limits = [
+        (WorkloadClass::Api, postgres_config.api_max_connections),
+        (
+            WorkloadClass::Background,
+            postgres_config.background_max_connections,
+        ),
+    ];
+    for (class, limit) in limits {
+        if let Some(limit) = limit {
+            quotas.set_limit(class, limit);
+        }
+    }
+    quotas
+}
+
+/// Spawns a singleton writer task. If the leader election is enabled, the task is only started once this instance
+/// becomes the sequencer leader.
+fn spawn_singleton_task<F, Fut>(
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    leader_tasks: Option<&mut LeaderTasks>,
+    stop_receiver: &watch::Receiver<bool>,
+    task: F,
+) where
+    F: FnOnce(watch::Receiver<bool>) -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    match leader_tasks {
+        Some(leader_tasks) => leader_tasks.push(task),
+        None => task_futures.push(tokio::spawn(task(stop_receiver.clone()))),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn add_state_keeper_to_task_futures(
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
@@ -773,6 +1657,11 @@ async fn add_state_keeper_to_task_futures(
     mempool_config: &MempoolConfig,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     object_store: Arc<dyn ObjectStore>,
+    admin_handles: &AdminHandles,
+    shared_sequencer_config: Option<&SharedSequencerConfig>,
+    replay_protection: ReplayProtection,
+    scheduled_txs_injector: Option<ScheduledTxsInjector>,
+    reloadable_config: watch::Receiver<ReloadableConfig>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let pool_builder = ConnectionPool::singleton(postgres_config.master_url()?);
@@ -789,6 +1678,8 @@ async fn add_state_keeper_to_task_futures(
         mempool.register_metrics();
         mempool
     };
+    let state_keeper_control = StateKeeperControl::new(mempool.clone());
+    admin_handles.set_state_keeper(state_keeper_control.clone());
 
     let miniblock_sealer_pool = pool_builder
         .build()
@@ -800,6 +1691,35 @@ async fn add_state_keeper_to_task_futures(
     );
     task_futures.push(tokio::spawn(miniblock_sealer.run()));
 
+    let shared_sequencer_source = if let Some(config) = shared_sequencer_config {
+        tracing::info!(
+            "Using shared sequencer at {} for ordering L2 transactions",
+            config.url
+        );
+        // One connection is used to deduplicate received transactions, and another one to check persisted miniblocks.
+        let shared_sequencer_pool = ConnectionPool::builder(postgres_config.master_url()?, 2)
+            .build()
+            .await
+            .context("failed to build shared_sequencer_pool")?;
+        let client = shared_sequencer::grpc_client(&config.url, network_config.zksync_network_id)?;
+        // Streamed transactions are subject to the same intake policies as transactions submitted via the API.
+        let intake_policy = TxIntakePolicy::new(
+            shared_sequencer_pool.clone(),
+            batch_fee_input_provider.clone(),
+        );
+        let (feed, source) = shared_sequencer_feed(
+            config,
+            client,
+            shared_sequencer_pool,
+            replay_protection,
+            intake_policy,
+        );
+        task_futures.push(tokio::spawn(feed.run(stop_receiver.clone())));
+        Some(source)
+    } else {
+        None
+    };
+
     let state_keeper = create_state_keeper(
         contracts_config,
         state_keeper_config,
@@ -808,9 +1728,13 @@ async fn add_state_keeper_to_task_futures(
         mempool_config,
         state_keeper_pool.clone(),
         mempool.clone(),
+        state_keeper_control,
         batch_fee_input_provider.clone(),
         miniblock_sealer_handle,
         object_store,
+        shared_sequencer_source,
+        scheduled_txs_injector,
+        reloadable_config.clone(),
         stop_receiver.clone(),
     )
     .await;
@@ -829,7 +1753,8 @@ async fn add_state_keeper_to_task_futures(
         batch_fee_input_provider,
         mempool_config,
         mempool_fetcher_pool,
-    );
+    )
+    .with_reloadable_config(reloadable_config);
     let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(stop_receiver));
     task_futures.push(mempool_fetcher_handle);
     Ok(())
@@ -922,6 +1847,9 @@ async fn run_tree(
 
     let tree_health_check = metadata_calculator.tree_health_check();
     healthchecks.push(Box::new(tree_health_check));
+    if let Some(integrity_health_check) = metadata_calculator.tree_integrity_health_check() {
+        healthchecks.push(Box::new(integrity_health_check));
+    }
     let pool = ConnectionPool::singleton(postgres_config.master_url()?)
         .build()
         .await
@@ -1066,6 +1994,139 @@ async fn add_house_keeper_to_task_futures(
     Ok(())
 }
 
+fn build_operator_accounts(
+    eth_sender: &ETHSenderConfig,
+    contracts_config: &ContractsConfig,
+    eth_client_config: &ETHClientConfig,
+    remote_signer: Option<RemoteSigner>,
+) -> OperatorAccounts {
+    let main_client: Arc<dyn BoundEthInterface> = if let Some(remote_signer) = remote_signer {
+        tracing::info!(
+            "Signing transactions for the main operator account using {} signer",
+            remote_signer.kind()
+        );
+        Arc::new(RemoteSigningClient::from_config_with_signer(
+            eth_sender,
+            contracts_config,
+            eth_client_config,
+            remote_signer.address(),
+            remote_signer,
+        ))
+    } else {
+        Arc::new(PKSigningClient::from_config(
+            eth_sender,
+            contracts_config,
+            eth_client_config,
+        ))
+    };
+    let mut accounts = OperatorAccounts::new(main_client);
+    let dedicated_keys = [
+        (
+            AggregatedActionType::Commit,
+            eth_sender.sender.commit_private_key(),
+        ),
+        (
+            AggregatedActionType::PublishProofOnchain,
+            eth_sender.sender.prove_private_key(),
+        ),
+        (
+            AggregatedActionType::Execute,
+            eth_sender.sender.execute_private_key(),
+        ),
+    ];
+    for (op, private_key) in dedicated_keys {
+        let Some(private_key) = private_key else {
+            continue;
+        };
+        let client = PKSigningClient::from_config_with_key(
+            eth_sender,
+            contracts_config,
+            eth_client_config,
+            private_key,
+        );
+        tracing::info!(
+            "Using dedicated operator account {:?} for {op} operations",
+            client.sender_account()
+        );
+        accounts = accounts.with_dedicated_account(op, Arc::new(client));
+    }
+    for private_key in eth_sender.sender.retired_private_keys() {
+        let client = PKSigningClient::from_config_with_key(
+            eth_sender,
+            contracts_config,
+            eth_client_config,
+            private_key,
+        );
+        tracing::info!(
+            "Using retired operator account {:?} to finalize in-flight transactions",
+            client.sender_account()
+        );
+        accounts = accounts.with_retired_account(Arc::new(client));
+    }
+    accounts
+}
+
+fn build_balance_monitor(
+    config: &OperatorBalanceMonitorConfig,
+    eth_sender: &ETHSenderConfig,
+    contracts_config: &ContractsConfig,
+    eth_client_config: &ETHClientConfig,
+    operator_accounts: OperatorAccounts,
+) -> anyhow::Result<OperatorBalanceMonitor> {
+    let mut monitor = OperatorBalanceMonitor::new(config.clone(), operator_accounts);
+    if let Some(url) = &config.top_up_webhook_url {
+        monitor = monitor.with_top_up_hook(Box::new(WebhookTopUpHook::new(url.clone())));
+    }
+    if let Some(contract_address) = config.funding_contract_address {
+        let funder_private_key = config
+            .funder_private_key()
+            .context("funder private key is required to call the funding contract")?;
+        let funder = PKSigningClient::from_config_with_key(
+            eth_sender,
+            contracts_config,
+            eth_client_config,
+            funder_private_key,
+        );
+        tracing::info!(
+            "Operator accounts will be topped up via funding contract {contract_address:?} \
+             from account {:?}",
+            funder.sender_account()
+        );
+        monitor = monitor.with_top_up_hook(Box::new(FundingContractTopUpHook::new(
+            Arc::new(funder),
+            contract_address,
+        )));
+    }
+    Ok(monitor)
+}
+
+fn build_remote_signer(config: &OperatorSignerConfig) -> anyhow::Result<RemoteSigner> {
+    let timeout = config.request_timeout();
+    let signer = match &config.mode {
+        OperatorSignerMode::Web3Signer {
+            web3_signer_url,
+            web3_signer_key_id,
+        } => RemoteSigner::new(
+            Web3SignerBackend::new(web3_signer_url, web3_signer_key_id, timeout)?,
+            config.operator_address,
+        ),
+        OperatorSignerMode::AwsKms {
+            aws_kms_region,
+            aws_kms_key_id,
+        } => RemoteSigner::new(
+            AwsKmsBackend::new(aws_kms_region, aws_kms_key_id, timeout)?,
+            config.operator_address,
+        ),
+        OperatorSignerMode::GcpKms {
+            gcp_kms_key_version_name,
+        } => RemoteSigner::new(
+            GcpKmsBackend::new(gcp_kms_key_version_name, timeout)?,
+            config.operator_address,
+        ),
+    };
+    Ok(signer)
+}
+
 fn build_storage_caches(
     configs: &TempConfigStore,
     replica_connection_pool: &ConnectionPool,
@@ -1092,6 +2153,107 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+/// Spawns the congestion multiplier updater if the `V3` fee model is used.
+fn build_congestion_multiplier(
+    state_keeper_config: &StateKeeperConfig,
+    connection_pool: &ConnectionPool,
+    stop_receiver: &watch::Receiver<bool>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+) -> CongestionMultiplier {
+    let FeeModelConfig::V3(config) = FeeModelConfig::from_state_keeper_config(state_keeper_config)
+    else {
+        return CongestionMultiplier::default();
+    };
+    let updater = CongestionMultiplierUpdater::new(connection_pool.clone(), config);
+    let multiplier = updater.multiplier();
+    task_futures.push(tokio::spawn(updater.run(stop_receiver.clone())));
+    multiplier
+}
+
+/// Spawns the stable gas price updater if the stable pricing mode is configured.
+fn build_stable_gas_price(
+    config: Option<&StableGasPriceConfig>,
+    eth_client_config: &ETHClientConfig,
+    stop_receiver: &watch::Receiver<bool>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+) -> anyhow::Result<StableGasPrice> {
+    let Some(config) = config else {
+        return Ok(StableGasPrice::default());
+    };
+    let feed = create_price_feed(config, &eth_client_config.web3_url)
+        .context("failed creating price feed")?;
+    tracing::info!(
+        "Stable gas pricing is enabled with target transaction cost {} and feed {feed:?}",
+        config.target_tx_cost
+    );
+    let updater = StableGasPriceUpdater::new(feed, config.clone())
+        .context("failed initializing stable gas price updater")?;
+    let price = updater.price();
+    task_futures.push(tokio::spawn(updater.run(stop_receiver.clone())));
+    Ok(price)
+}
+
+/// Spawns the config reloader if it's configured. Otherwise, the returned config never changes.
+fn build_reloadable_config(
+    configs: &TempConfigStore,
+    pool: &ConnectionPool,
+    stop_receiver: &watch::Receiver<bool>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+) -> watch::Receiver<ReloadableConfig> {
+    let reloadable_config = ReloadableConfig::new(
+        configs
+            .api_config
+            .as_ref()
+            .map(|config| &config.web3_json_rpc),
+        configs.mempool_config.as_ref(),
+        configs.state_keeper_config.as_ref(),
+        configs.fee_distributor_config.as_ref(),
+    );
+    let Some(config) = configs.config_reload_config.clone() else {
+        return watch::channel(reloadable_config).1;
+    };
+    tracing::info!("Config reloading is enabled with {config:?}");
+    let reloader = ConfigReloader::new(config, reloadable_config).with_audit_log(pool.clone());
+    let receiver = reloader.subscribe();
+    task_futures.push(tokio::spawn(reloader.run(stop_receiver.clone())));
+    receiver
+}
+
+fn build_api_state_cache(
+    db_config: &DBConfig,
+    replica_connection_pool: &ConnectionPool,
+    stop_receiver: &watch::Receiver<bool>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+) -> Option<ApiStateCache> {
+    let db_path = db_config.api_state_cache_path.as_ref()?;
+    let (state_cache, updater) = ApiStateCache::new(
+        db_path.into(),
+        db_config.state_keeper_db_block_cache_size(),
+        &db_config.state_keeper_rocksdb,
+    );
+    task_futures.push(tokio::spawn(
+        updater.run(replica_connection_pool.clone(), stop_receiver.clone()),
+    ));
+    Some(state_cache)
+}
+
+fn build_sandbox_warm_pool(
+    state_cache: Option<ApiStateCache>,
+    replica_connection_pool: &ConnectionPool,
+    stop_receiver: &watch::Receiver<bool>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+) -> SandboxWarmPool {
+    let (warm_pool, mut updater) = SandboxWarmPool::new();
+    if let Some(state_cache) = state_cache {
+        updater = updater.with_state_cache(state_cache);
+    }
+    task_futures.push(tokio::spawn(
+        updater.run(replica_connection_pool.clone(), stop_receiver.clone()),
+    ));
+    warm_pool
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn build_tx_sender(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
@@ -1100,11 +2262,36 @@ async fn build_tx_sender(
     master_pool: ConnectionPool,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     storage_caches: PostgresStorageCaches,
+    state_cache: Option<ApiStateCache>,
+    warm_pool: Option<SandboxWarmPool>,
+    l2_erc20_bridge_addr: Address,
 ) -> (TxSender, VmConcurrencyBarrier) {
     let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
-    let tx_sender_builder = TxSenderBuilder::new(tx_sender_config.clone(), replica_pool.clone())
-        .with_main_connection_pool(master_pool)
-        .with_sealer(Arc::new(sequencer_sealer));
+    let mut tx_sender_builder =
+        TxSenderBuilder::new(tx_sender_config.clone(), replica_pool.clone())
+            .with_main_connection_pool(master_pool)
+            .with_sealer(Arc::new(sequencer_sealer))
+            .with_withdrawal_limiter(WithdrawalLimiter::new(l2_erc20_bridge_addr));
+    if let Some(horizon_batches) = web3_json_config.inclusion_horizon_batches {
+        let capacity = BatchCapacity::new(state_keeper_config);
+        tx_sender_builder = tx_sender_builder
+            .with_batch_fill_forecaster(BatchFillForecaster::new(capacity, horizon_batches));
+    }
+    if let Some(state_cache) = state_cache {
+        tx_sender_builder = tx_sender_builder.with_state_cache(state_cache);
+    }
+    if let Some(warm_pool) = warm_pool {
+        tx_sender_builder = tx_sender_builder.with_warm_pool(warm_pool);
+    }
+    if web3_json_config.archive_calls_enabled {
+        if let Some(tree_api_url) = &web3_json_config.tree_api_url {
+            tx_sender_builder = tx_sender_builder.with_archive_tree_api(tree_api_url);
+        } else {
+            tracing::warn!(
+                "`archive_calls_enabled` is set without `tree_api_url`; calls for pruned blocks are disabled"
+            );
+        }
+    }
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
@@ -1136,6 +2323,11 @@ async fn run_http_api(
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
+    state_cache: Option<ApiStateCache>,
+    warm_pool: Option<SandboxWarmPool>,
+    admin_handles: AdminHandles,
+    reloadable_config: watch::Receiver<ReloadableConfig>,
+    restart_policy: RestartPolicy,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1145,6 +2337,9 @@ async fn run_http_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        state_cache,
+        warm_pool,
+        internal_api.bridge_addresses.l2_erc20_default_bridge,
     )
     .await;
 
@@ -1153,6 +2348,12 @@ async fn run_http_api(
         namespaces.push(Namespace::Debug)
     }
     namespaces.push(Namespace::Snapshots);
+    if api_config.web3_json_rpc.operator_namespace_enabled {
+        namespaces.push(Namespace::Operator);
+    }
+    if api_config.web3_json_rpc.admin_namespace_enabled {
+        namespaces.push(Namespace::Admin);
+    }
 
     let updaters_pool = ConnectionPool::builder(postgres_config.replica_url()?, 2)
         .build()
@@ -1165,8 +2366,13 @@ async fn run_http_api(
             .with_updaters_pool(updaters_pool)
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
+            .with_operator_auth_token(api_config.web3_json_rpc.operator_auth_token.clone())
+            .with_admin_handles(admin_handles)
+            .with_sequencer_signing_key(api_config.web3_json_rpc.sequencer_signing_key())
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
+            .with_reloadable_config(reloadable_config)
+            .with_restart_policy(restart_policy)
             .with_tx_sender(tx_sender, vm_barrier)
             .enable_api_namespaces(namespaces);
     api_builder.build(stop_receiver).await
@@ -1184,6 +2390,11 @@ async fn run_ws_api(
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
+    state_cache: Option<ApiStateCache>,
+    warm_pool: Option<SandboxWarmPool>,
+    admin_handles: AdminHandles,
+    reloadable_config: watch::Receiver<ReloadableConfig>,
+    restart_policy: RestartPolicy,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1193,6 +2404,9 @@ async fn run_ws_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        state_cache,
+        warm_pool,
+        internal_api.bridge_addresses.l2_erc20_default_bridge,
     )
     .await;
     let last_miniblock_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
@@ -1202,6 +2416,12 @@ async fn run_ws_api(
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
     namespaces.push(Namespace::Snapshots);
+    if api_config.web3_json_rpc.operator_namespace_enabled {
+        namespaces.push(Namespace::Operator);
+    }
+    if api_config.web3_json_rpc.admin_namespace_enabled {
+        namespaces.push(Namespace::Admin);
+    }
 
     let api_builder =
         web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
@@ -1209,6 +2429,10 @@ async fn run_ws_api(
             .with_updaters_pool(last_miniblock_pool)
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
+            .with_pubsub_lag_thresholds(
+                api_config.web3_json_rpc.pubsub_lag_warning_threshold(),
+                api_config.web3_json_rpc.pubsub_lag_close_threshold(),
+            )
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
             .with_websocket_requests_per_minute_limit(
@@ -1218,6 +2442,11 @@ async fn run_ws_api(
             )
             .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
+            .with_operator_auth_token(api_config.web3_json_rpc.operator_auth_token.clone())
+            .with_admin_handles(admin_handles)
+            .with_sequencer_signing_key(api_config.web3_json_rpc.sequencer_signing_key())
+            .with_reloadable_config(reloadable_config)
+            .with_restart_policy(restart_policy)
             .with_tx_sender(tx_sender, vm_barrier)
             .enable_api_namespaces(namespaces);
 
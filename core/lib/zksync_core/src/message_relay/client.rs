@@ -0,0 +1,281 @@
+//! Settlement layer client used by the message relay.
+
+use std::{fmt, sync::Arc};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_contracts::{message_inbox_contract, message_outbox_contract, multicall_contract};
+use zksync_dal::relayed_messages_dal::{MessageOrigin, RelayedMessage};
+use zksync_eth_client::{BoundEthInterface, EthInterface};
+use zksync_types::{
+    ethabi::{Event, Function, RawLog, Token},
+    web3::types::{BlockId, BlockNumber, Bytes, CallRequest, FilterBuilder},
+    Address,
+};
+
+use super::inbound_message_id;
+use crate::utils::settlement_txs::{SettlementTxClient, SettlementTxRequest};
+
+const COMPONENT: &str = "message_relay";
+
+/// Settlement layer operations necessary for the message relay.
+#[async_trait]
+pub trait RelayClient: fmt::Debug + Send + Sync {
+    /// Returns the number of the last finalized settlement layer block.
+    async fn finalized_block_number(&self) -> anyhow::Result<u64>;
+
+    /// Returns inbound messages sent via the outbox contract in the specified inclusive block range.
+    async fn get_inbound_messages(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> anyhow::Result<Vec<RelayedMessage>>;
+
+    /// Checks whether the message is delivered (potentially, by a third party).
+    async fn is_message_delivered(&self, message: &RelayedMessage) -> anyhow::Result<bool>;
+
+    /// Builds a transaction delivering the specified messages.
+    fn delivery_tx(
+        &self,
+        messages: &[RelayedMessage],
+        gas_limit: u64,
+    ) -> anyhow::Result<SettlementTxRequest>;
+
+    /// Returns the client used to send delivery transactions.
+    fn tx_client(&self) -> &dyn SettlementTxClient;
+}
+
+/// Functions and events of the settlement layer contracts used by [`EthRelayClient`].
+#[derive(Debug)]
+struct RelayContracts {
+    deliver_message: Function,
+    is_message_delivered: Function,
+    relay_message: Function,
+    is_message_relayed: Function,
+    message_sent: Event,
+    aggregate3: Function,
+}
+
+impl RelayContracts {
+    fn load() -> anyhow::Result<Self> {
+        let inbox = message_inbox_contract();
+        let outbox = message_outbox_contract();
+        let multicall = multicall_contract();
+        Ok(Self {
+            deliver_message: inbox.function("deliverMessage")?.clone(),
+            is_message_delivered: inbox.function("isMessageDelivered")?.clone(),
+            relay_message: outbox.function("relayMessage")?.clone(),
+            is_message_relayed: outbox.function("isMessageRelayed")?.clone(),
+            message_sent: outbox.event("MessageSent")?.clone(),
+            aggregate3: multicall.function("aggregate3")?.clone(),
+        })
+    }
+}
+
+/// [`RelayClient`] implementation calling settlement layer contracts. Outbound messages are delivered
+/// via the inbox contract, and inbound ones via the outbox contract. Several messages are delivered
+/// in a single transaction via Multicall3; failure of an individual delivery doesn't revert the transaction.
+#[derive(Debug)]
+pub struct EthRelayClient {
+    client: Arc<dyn BoundEthInterface>,
+    contracts: RelayContracts,
+    inbox_addr: Option<Address>,
+    outbox_addr: Option<Address>,
+    multicall3_addr: Address,
+}
+
+impl EthRelayClient {
+    pub fn new(
+        client: Arc<dyn BoundEthInterface>,
+        inbox_addr: Option<Address>,
+        outbox_addr: Option<Address>,
+        multicall3_addr: Address,
+    ) -> anyhow::Result<Self> {
+        let contracts = RelayContracts::load().context("failed loading relay contract ABIs")?;
+        Ok(Self {
+            client,
+            contracts,
+            inbox_addr,
+            outbox_addr,
+            multicall3_addr,
+        })
+    }
+
+    fn target_contract(&self, message: &RelayedMessage) -> anyhow::Result<Address> {
+        match &message.origin {
+            MessageOrigin::L1Batch { .. } => self.inbox_addr.context("inbox is not configured"),
+            MessageOrigin::SettlementLayer { .. } => {
+                self.outbox_addr.context("outbox is not configured")
+            }
+        }
+    }
+
+    fn encode_delivery_call(&self, message: &RelayedMessage) -> anyhow::Result<Vec<u8>> {
+        let data = match &message.origin {
+            MessageOrigin::L1Batch {
+                l1_batch_number,
+                l2_message_index,
+                tx_number_in_batch,
+                merkle_proof,
+            } => {
+                let merkle_proof = merkle_proof
+                    .iter()
+                    .map(|hash| Token::FixedBytes(hash.as_bytes().to_vec()))
+                    .collect();
+                self.contracts.deliver_message.encode_input(&[
+                    Token::Uint(l1_batch_number.0.into()),
+                    Token::Uint((*l2_message_index).into()),
+                    Token::Tuple(vec![
+                        Token::Uint((*tx_number_in_batch).into()),
+                        Token::Address(message.sender),
+                        Token::Bytes(message.data.clone()),
+                    ]),
+                    Token::Array(merkle_proof),
+                ])?
+            }
+            MessageOrigin::SettlementLayer { nonce, .. } => self
+                .contracts
+                .relay_message
+                .encode_input(&[Token::Uint((*nonce).into())])?,
+        };
+        Ok(data)
+    }
+
+    fn encode_multicall(&self, messages: &[RelayedMessage]) -> anyhow::Result<Vec<u8>> {
+        let calls = messages
+            .iter()
+            .map(|message| {
+                Ok(Token::Tuple(vec![
+                    Token::Address(self.target_contract(message)?),
+                    Token::Bool(true), // `allowFailure`
+                    Token::Bytes(self.encode_delivery_call(message)?),
+                ]))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(self
+            .contracts
+            .aggregate3
+            .encode_input(&[Token::Array(calls)])?)
+    }
+}
+
+#[async_trait]
+impl RelayClient for EthRelayClient {
+    async fn finalized_block_number(&self) -> anyhow::Result<u64> {
+        let block = self
+            .client
+            .block(BlockId::Number(BlockNumber::Finalized), COMPONENT)
+            .await?
+            .context("finalized block is missing")?;
+        let number = block.number.context("finalized block has no number")?;
+        Ok(number.as_u64())
+    }
+
+    async fn get_inbound_messages(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> anyhow::Result<Vec<RelayedMessage>> {
+        let outbox_addr = self.outbox_addr.context("outbox is not configured")?;
+        let message_sent = &self.contracts.message_sent;
+        let filter = FilterBuilder::default()
+            .address(vec![outbox_addr])
+            .from_block(from_block.into())
+            .to_block(to_block.into())
+            .topics(Some(vec![message_sent.signature()]), None, None, None)
+            .build();
+        let logs = self.client.logs(filter, COMPONENT).await?;
+
+        let mut messages = Vec::with_capacity(logs.len());
+        for log in logs {
+            let (Some(block_number), Some(tx_hash)) = (log.block_number, log.transaction_hash)
+            else {
+                continue; // Pending log; shouldn't happen for finalized blocks
+            };
+            let raw_log = RawLog {
+                topics: log.topics,
+                data: log.data.0,
+            };
+            let parsed_log = message_sent
+                .parse_log(raw_log)
+                .context("failed decoding MessageSent event")?;
+            // Event params are `(uint256 indexed nonce, address indexed sender, address target, bytes data)`.
+            let mut params = parsed_log.params.into_iter().map(|param| param.value);
+            let (Some(nonce), Some(sender), Some(target), Some(data)) = (
+                params.next().and_then(Token::into_uint),
+                params.next().and_then(Token::into_address),
+                params.next().and_then(Token::into_address),
+                params.next().and_then(Token::into_bytes),
+            ) else {
+                anyhow::bail!("unexpected MessageSent event params");
+            };
+            let nonce = nonce.as_u64();
+            messages.push(RelayedMessage {
+                id: inbound_message_id(nonce),
+                source_tx_hash: tx_hash,
+                sender,
+                data,
+                origin: MessageOrigin::SettlementLayer {
+                    block_number: block_number.as_u64(),
+                    nonce,
+                    target,
+                },
+            });
+        }
+        Ok(messages)
+    }
+
+    async fn is_message_delivered(&self, message: &RelayedMessage) -> anyhow::Result<bool> {
+        let (function, args) = match &message.origin {
+            MessageOrigin::L1Batch {
+                l1_batch_number,
+                l2_message_index,
+                ..
+            } => {
+                let args = vec![
+                    Token::Uint(l1_batch_number.0.into()),
+                    Token::Uint((*l2_message_index).into()),
+                ];
+                (&self.contracts.is_message_delivered, args)
+            }
+            MessageOrigin::SettlementLayer { nonce, .. } => {
+                let args = vec![Token::Uint((*nonce).into())];
+                (&self.contracts.is_message_relayed, args)
+            }
+        };
+        let request = CallRequest {
+            to: Some(self.target_contract(message)?),
+            data: Some(Bytes(function.encode_input(&args)?)),
+            ..CallRequest::default()
+        };
+        let output = self.client.call(request, None, COMPONENT).await?;
+        let tokens = function
+            .decode_output(&output.0)
+            .with_context(|| format!("failed decoding `{}` output", function.name))?;
+        Ok(tokens.into_iter().next().and_then(Token::into_bool) == Some(true))
+    }
+
+    fn delivery_tx(
+        &self,
+        messages: &[RelayedMessage],
+        gas_limit: u64,
+    ) -> anyhow::Result<SettlementTxRequest> {
+        let (calldata, contract_address) = match messages {
+            [] => anyhow::bail!("no messages to deliver"),
+            [message] => (
+                self.encode_delivery_call(message)?,
+                self.target_contract(message)?,
+            ),
+            _ => (self.encode_multicall(messages)?, self.multicall3_addr),
+        };
+        Ok(SettlementTxRequest {
+            contract_address,
+            calldata,
+            gas_limit,
+        })
+    }
+
+    fn tx_client(&self) -> &dyn SettlementTxClient {
+        &self.client
+    }
+}
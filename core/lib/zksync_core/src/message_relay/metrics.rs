@@ -0,0 +1,42 @@
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "direction", rename_all = "snake_case")]
+pub(super) enum Direction {
+    /// Message from the chain to the settlement layer.
+    Outbound,
+    /// Message from the settlement layer to the chain.
+    Inbound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum DeliveryOutcome {
+    /// Message was delivered by the relay.
+    Delivered,
+    /// Message was delivered by a third party.
+    DeliveredExternally,
+    /// Delivery attempt failed; the message will be retried.
+    Retried,
+    /// Message has reached the maximum number of attempts.
+    Failed,
+}
+
+/// Metrics for the message relay.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_message_relay")]
+pub(super) struct MessageRelayMetrics {
+    /// Number of observed messages split by the direction.
+    pub observed_messages: Family<Direction, Counter>,
+    /// Number of sent delivery transactions.
+    pub sent_transactions: Counter,
+    /// Number of processed messages split by the outcome.
+    pub processed_messages: Family<DeliveryOutcome, Counter>,
+    /// Number of the last L1 batch scanned for outbound messages.
+    pub last_scanned_l1_batch: Gauge<u64>,
+    /// Number of the last settlement layer block scanned for inbound messages.
+    pub last_scanned_settlement_block: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<MessageRelayMetrics> = vise::Global::new();
@@ -0,0 +1,369 @@
+//! Message relay: delivers arbitrary messages between the chain and the settlement layer.
+//!
+//! Outbound messages are sent by the configured chain contracts via the L1 messenger. The relay scans L1 batches
+//! executed on the settlement layer for such messages, and delivers them together with their Merkle proofs
+//! via the inbox contract (`IMessageInbox.deliverMessage`). Inbound messages are emitted by the outbox contract
+//! on the settlement layer as `IMessageOutbox.MessageSent` events; the relay scans finalized blocks for these events
+//! and delivers them via `IMessageOutbox.relayMessage`, which enqueues a priority operation calling the target
+//! contract on the chain. Contract ABIs are loaded from the compiled contracts via `zksync_contracts`.
+//!
+//! Each message is identified by a unique ID derived from its position (the L1 batch number and message index
+//! for outbound messages, or the outbox nonce for inbound ones), so rescanning the same L1 batches or blocks
+//! doesn't lead to duplicate deliveries. Several messages are delivered in a single transaction via Multicall3,
+//! and only a single delivery transaction is in flight at a time. Delivery transactions are persisted before they are
+//! sent and are resent with bumped fees if they are not mined in time (see [`SettlementTxManager`]).
+//! After a delivery transaction is mined,
+//! the status of each included message is checked on the settlement layer; undelivered messages are retried until
+//! they reach the configured maximum number of attempts. Receipts of delivery transactions are recorded in Postgres.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::MessageRelayConfig;
+use zksync_dal::{
+    relayed_messages_dal::{MessageOrigin, MessageStatus, RelayedMessage},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_types::{
+    api::idexo::MessageDeliveryReceipt,
+    ethabi::{self, Token},
+    web3::signing::keccak256,
+    L1BatchNumber, H256,
+};
+
+pub use self::client::{EthRelayClient, RelayClient};
+use self::metrics::{DeliveryOutcome, Direction, METRICS};
+use crate::utils::{
+    l2_to_l1_messages::load_proven_messages,
+    settlement_txs::{SettlementTxManager, SettlementTxOutcome},
+};
+
+mod client;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of L1 batches scanned for outbound messages in a single iteration.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 100;
+/// Maximum number of settlement layer blocks scanned for inbound messages in a single iteration.
+const MAX_BLOCKS_PER_ITERATION: u64 = 1_000;
+
+/// Computes the ID of an outbound message.
+fn outbound_message_id(l1_batch_number: L1BatchNumber, l2_message_index: u32) -> H256 {
+    let encoded = ethabi::encode(&[
+        Token::Uint(l1_batch_number.0.into()),
+        Token::Uint(l2_message_index.into()),
+    ]);
+    H256(keccak256(&encoded))
+}
+
+/// Computes the ID of an inbound message. The ID cannot collide with outbound message IDs since it's a hash
+/// of a differently sized input.
+fn inbound_message_id(nonce: u64) -> H256 {
+    H256(keccak256(&ethabi::encode(&[Token::Uint(nonce.into())])))
+}
+
+/// Task relaying messages. See the module-level docs for details.
+#[derive(Debug)]
+pub struct MessageRelay {
+    client: Box<dyn RelayClient>,
+    pool: ConnectionPool,
+    config: MessageRelayConfig,
+    /// Next L1 batch to scan for outbound messages.
+    next_l1_batch: L1BatchNumber,
+    /// Next settlement layer block to scan for inbound messages; `None` if inbound messages are not relayed.
+    next_settlement_block: Option<u64>,
+    tx_manager: SettlementTxManager,
+}
+
+impl MessageRelay {
+    pub async fn new(
+        client: Box<dyn RelayClient>,
+        pool: ConnectionPool,
+        config: MessageRelayConfig,
+    ) -> anyhow::Result<Self> {
+        let relays_outbound = config.inbox_addr.is_some() && !config.outbound_senders.is_empty();
+        let relays_inbound = config.outbox_addr.is_some();
+        anyhow::ensure!(
+            relays_outbound || relays_inbound,
+            "message relay must have the inbox with outbound senders, or the outbox configured"
+        );
+
+        let mut storage = pool.access_storage_tagged("message_relay").await?;
+        let mut dal = storage.relayed_messages_dal();
+        let last_l1_batch = dal.get_last_l1_batch_with_messages().await?;
+        let last_settlement_block = dal.get_last_settlement_block_with_messages().await?;
+        drop(storage);
+
+        // Messages of an L1 batch or a block range are persisted atomically, so the last L1 batch / block
+        // with messages doesn't need to be rescanned. The genesis L1 batch cannot contain messages.
+        let next_l1_batch = last_l1_batch.map_or(L1BatchNumber(1), |number| number + 1);
+        let next_settlement_block = if relays_inbound {
+            Some(match last_settlement_block {
+                Some(number) => number + 1,
+                // Messages sent before the relay was launched are not relayed; they can be delivered manually.
+                None => client
+                    .finalized_block_number()
+                    .await
+                    .context("cannot get finalized block number")?,
+            })
+        } else {
+            None
+        };
+        tracing::info!(
+            "Initialized message relay starting from L1 batch #{next_l1_batch} (outbound: {relays_outbound}) \
+             and settlement layer block {next_settlement_block:?}"
+        );
+
+        let tx_manager = SettlementTxManager::new("message_relay", config.tx_resend_interval());
+        Ok(Self {
+            client,
+            pool,
+            config,
+            next_l1_batch,
+            next_settlement_block,
+            tx_manager,
+        })
+    }
+
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, message relay is shutting down");
+                break;
+            }
+
+            if let Err(err) = self.loop_iteration().await {
+                tracing::warn!("Failed relaying messages: {err:#}");
+            }
+            tokio::time::sleep(self.config.polling_interval()).await;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn loop_iteration(&mut self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("message_relay").await?;
+        if self.config.inbox_addr.is_some() && !self.config.outbound_senders.is_empty() {
+            self.scan_executed_l1_batches(&mut storage).await?;
+        }
+        if self.next_settlement_block.is_some() {
+            self.scan_settlement_layer_blocks(&mut storage).await?;
+        }
+        let has_pending_txs = self.process_sent_messages(&mut storage).await?;
+        if !has_pending_txs {
+            self.send_pending_messages(&mut storage).await?;
+        }
+        Ok(())
+    }
+
+    async fn scan_executed_l1_batches(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let Some(last_executed_l1_batch) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?
+        else {
+            return Ok(());
+        };
+        let last_l1_batch_to_scan =
+            last_executed_l1_batch.min(self.next_l1_batch + (MAX_L1_BATCHES_PER_ITERATION - 1));
+
+        for number in self.next_l1_batch.0..=last_l1_batch_to_scan.0 {
+            let l1_batch_number = L1BatchNumber(number);
+            let messages =
+                load_proven_messages(storage, l1_batch_number, &self.config.outbound_senders)
+                    .await
+                    .with_context(|| format!("failed loading messages from L1 batch #{number}"))?;
+            if !messages.is_empty() {
+                tracing::info!(
+                    "Observed {} outbound messages in L1 batch #{number}",
+                    messages.len()
+                );
+                let messages: Vec<_> = messages
+                    .into_iter()
+                    .map(|message| RelayedMessage {
+                        id: outbound_message_id(l1_batch_number, message.l2_message_index),
+                        source_tx_hash: message.tx_hash,
+                        sender: message.sender,
+                        data: message.data,
+                        origin: MessageOrigin::L1Batch {
+                            l1_batch_number,
+                            l2_message_index: message.l2_message_index,
+                            tx_number_in_batch: message.tx_number_in_batch,
+                            merkle_proof: message.merkle_proof,
+                        },
+                    })
+                    .collect();
+                storage
+                    .relayed_messages_dal()
+                    .insert_messages(&messages)
+                    .await?;
+                METRICS.observed_messages[&Direction::Outbound].inc_by(messages.len() as u64);
+            }
+            self.next_l1_batch = l1_batch_number + 1;
+            METRICS.last_scanned_l1_batch.set(number.into());
+        }
+        Ok(())
+    }
+
+    async fn scan_settlement_layer_blocks(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let Some(next_block) = self.next_settlement_block else {
+            return Ok(());
+        };
+        let finalized_block = self.client.finalized_block_number().await?;
+        if finalized_block < next_block {
+            return Ok(());
+        }
+        let last_block_to_scan = finalized_block.min(next_block + (MAX_BLOCKS_PER_ITERATION - 1));
+
+        let messages = self
+            .client
+            .get_inbound_messages(next_block, last_block_to_scan)
+            .await
+            .with_context(|| {
+                format!("failed loading messages from blocks {next_block}..={last_block_to_scan}")
+            })?;
+        if !messages.is_empty() {
+            tracing::info!(
+                "Observed {} inbound messages in settlement layer blocks {next_block}..={last_block_to_scan}",
+                messages.len()
+            );
+            storage
+                .relayed_messages_dal()
+                .insert_messages(&messages)
+                .await?;
+            METRICS.observed_messages[&Direction::Inbound].inc_by(messages.len() as u64);
+        }
+        self.next_settlement_block = Some(last_block_to_scan + 1);
+        METRICS
+            .last_scanned_settlement_block
+            .set(last_block_to_scan);
+        Ok(())
+    }
+
+    /// Processes messages with mined delivery transactions. Returns `true` if there are delivery
+    /// transactions that are not mined yet.
+    async fn process_sent_messages(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<bool> {
+        let sent_messages = storage.relayed_messages_dal().get_sent_messages().await?;
+        let mut messages_by_tx = BTreeMap::<_, Vec<_>>::new();
+        for (tx_id, message) in sent_messages {
+            messages_by_tx.entry(tx_id).or_default().push(message);
+        }
+
+        let mut has_pending_txs = false;
+        for (tx_id, messages) in messages_by_tx {
+            let tx_client = self.client.tx_client();
+            let Some(outcome) = self.tx_manager.check_tx(storage, tx_client, tx_id).await? else {
+                has_pending_txs = true;
+                continue;
+            };
+            let receipt = match outcome {
+                SettlementTxOutcome::Mined { tx_hash, status } => {
+                    if !status.success {
+                        tracing::warn!("Delivery transaction {tx_hash:?} has failed");
+                    }
+                    Some(MessageDeliveryReceipt {
+                        tx_hash,
+                        block_number: status.block_number,
+                    })
+                }
+                SettlementTxOutcome::NonceUsed => None,
+            };
+
+            // Individual deliveries may fail even if the transaction has succeeded.
+            for message in &messages {
+                if self.client.is_message_delivered(message).await? {
+                    storage
+                        .relayed_messages_dal()
+                        .mark_message_as_delivered(message.id, receipt)
+                        .await?;
+                    METRICS.processed_messages[&DeliveryOutcome::Delivered].inc();
+                    continue;
+                }
+
+                let status = storage
+                    .relayed_messages_dal()
+                    .reset_message(message.id, self.config.max_attempts)
+                    .await?;
+                if status == MessageStatus::Failed {
+                    tracing::error!(
+                        "Message {:?} (source transaction {:?}) was not delivered after {} attempts",
+                        message.id,
+                        message.source_tx_hash,
+                        self.config.max_attempts
+                    );
+                    METRICS.processed_messages[&DeliveryOutcome::Failed].inc();
+                } else {
+                    tracing::warn!(
+                        "Message {:?} was not delivered by transaction #{tx_id} ({receipt:?}); it will be retried",
+                        message.id
+                    );
+                    METRICS.processed_messages[&DeliveryOutcome::Retried].inc();
+                }
+            }
+        }
+        Ok(has_pending_txs)
+    }
+
+    async fn send_pending_messages(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let pending_messages = storage
+            .relayed_messages_dal()
+            .get_pending_messages(self.config.max_messages_per_tx as usize)
+            .await?;
+        let mut messages = Vec::with_capacity(pending_messages.len());
+        for message in pending_messages {
+            if self.client.is_message_delivered(&message).await? {
+                storage
+                    .relayed_messages_dal()
+                    .mark_message_as_delivered(message.id, None)
+                    .await?;
+                METRICS.processed_messages[&DeliveryOutcome::DeliveredExternally].inc();
+            } else {
+                messages.push(message);
+            }
+        }
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let gas_limit = self.config.gas_limit_per_message * messages.len() as u64;
+        let request = self.client.delivery_tx(&messages, gas_limit)?;
+        let tx_client = self.client.tx_client();
+        let tx = self
+            .tx_manager
+            .sign_new_tx(tx_client, request)
+            .await
+            .context("failed signing delivery transaction")?;
+
+        // The transaction is persisted before it's sent, so that it cannot be sent twice.
+        let ids: Vec<_> = messages.iter().map(|message| message.id).collect();
+        let mut transaction = storage.start_transaction().await?;
+        let tx_id = self.tx_manager.save_tx(&mut transaction, &tx).await?;
+        transaction
+            .relayed_messages_dal()
+            .mark_messages_as_sent(&ids, tx_id)
+            .await?;
+        transaction.commit().await?;
+
+        self.tx_manager.send_tx(tx_client, &tx).await;
+        tracing::info!(
+            "Sent transaction #{tx_id} ({:?}) delivering {} messages",
+            tx.tx_hash(),
+            messages.len()
+        );
+        METRICS.sent_transactions.inc();
+        Ok(())
+    }
+}
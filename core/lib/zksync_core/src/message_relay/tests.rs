@@ -0,0 +1,555 @@
+//! Tests for the message relay.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use zksync_dal::settlement_txs_dal::SignedSettlementTx;
+use zksync_types::{
+    api::idexo::{MessageDeliveryStatus, MessageDirection},
+    Address, L2ChainId, U256,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::{
+        settlement_txs::{MinedTxStatus, SettlementTxClient, SettlementTxRequest, TxFees},
+        testonly::store_executed_l1_batch_with_messages,
+    },
+};
+
+const OUTBOUND_SENDER: Address = Address::repeat_byte(0x51);
+
+/// Signed version of a delivery transaction.
+#[derive(Debug, Clone)]
+struct FakeSignedTx {
+    /// 0-based index of the delivery transaction in `FakeClientState.sent_txs`.
+    index: usize,
+    hash: H256,
+    nonce: u64,
+    fees: TxFees,
+}
+
+#[derive(Debug, Default)]
+struct FakeClientState {
+    finalized_block_number: u64,
+    inbound_messages: Vec<RelayedMessage>,
+    delivered: HashSet<H256>,
+    /// Messages included into delivery transactions.
+    sent_txs: Vec<Vec<RelayedMessage>>,
+    signed_txs: Vec<FakeSignedTx>,
+    broadcast_txs: Vec<H256>,
+    fail_broadcasts: bool,
+    mined_txs: HashMap<H256, MinedTxStatus>,
+    nonce: u64,
+}
+
+/// Client emulating message delivery on the settlement layer.
+#[derive(Debug, Default)]
+struct FakeRelayClient {
+    state: Mutex<FakeClientState>,
+}
+
+impl FakeRelayClient {
+    fn set_finalized_block_number(&self, number: u64) {
+        self.state.lock().unwrap().finalized_block_number = number;
+    }
+
+    fn send_inbound_message(&self, message: RelayedMessage) {
+        self.state.lock().unwrap().inbound_messages.push(message);
+    }
+
+    fn deliver_externally(&self, message: &RelayedMessage) {
+        self.state.lock().unwrap().delivered.insert(message.id);
+    }
+
+    fn sent_txs(&self) -> Vec<Vec<RelayedMessage>> {
+        self.state.lock().unwrap().sent_txs.clone()
+    }
+
+    fn signed_txs(&self) -> Vec<FakeSignedTx> {
+        self.state.lock().unwrap().signed_txs.clone()
+    }
+
+    fn broadcast_txs(&self) -> Vec<H256> {
+        self.state.lock().unwrap().broadcast_txs.clone()
+    }
+
+    fn set_fail_broadcasts(&self, fail: bool) {
+        self.state.lock().unwrap().fail_broadcasts = fail;
+    }
+
+    /// Emulates a transaction from the relay account not tracked by the relay.
+    fn use_nonce(&self) {
+        self.state.lock().unwrap().nonce += 1;
+    }
+
+    /// Mines the last signed version of the delivery transaction with the specified 1-based index
+    /// in block `100 + index`. If `deliver` is not set, the transaction succeeds, but doesn't deliver any messages.
+    fn mine_tx(&self, index: usize, deliver: bool) -> H256 {
+        let mut state = self.state.lock().unwrap();
+        let messages = state.sent_txs[index - 1].clone();
+        let signed_tx = state
+            .signed_txs
+            .iter()
+            .rev()
+            .find(|tx| tx.index == index - 1)
+            .expect("transaction is not signed")
+            .clone();
+        assert_eq!(signed_tx.nonce, state.nonce);
+        state.nonce += 1;
+        let status = MinedTxStatus {
+            success: true,
+            block_number: 100 + index as u64,
+        };
+        state.mined_txs.insert(signed_tx.hash, status);
+        if deliver {
+            state
+                .delivered
+                .extend(messages.iter().map(|message| message.id));
+        }
+        signed_tx.hash
+    }
+}
+
+#[async_trait]
+impl RelayClient for Arc<FakeRelayClient> {
+    async fn finalized_block_number(&self) -> anyhow::Result<u64> {
+        Ok(self.state.lock().unwrap().finalized_block_number)
+    }
+
+    async fn get_inbound_messages(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> anyhow::Result<Vec<RelayedMessage>> {
+        let state = self.state.lock().unwrap();
+        let messages = state.inbound_messages.iter().filter(|message| {
+            let MessageOrigin::SettlementLayer { block_number, .. } = &message.origin else {
+                unreachable!();
+            };
+            (from_block..=to_block).contains(block_number)
+        });
+        Ok(messages.cloned().collect())
+    }
+
+    async fn is_message_delivered(&self, message: &RelayedMessage) -> anyhow::Result<bool> {
+        Ok(self.state.lock().unwrap().delivered.contains(&message.id))
+    }
+
+    fn delivery_tx(
+        &self,
+        messages: &[RelayedMessage],
+        gas_limit: u64,
+    ) -> anyhow::Result<SettlementTxRequest> {
+        let mut state = self.state.lock().unwrap();
+        state.sent_txs.push(messages.to_vec());
+        let index = state.sent_txs.len() as u64 - 1;
+        Ok(SettlementTxRequest {
+            contract_address: Address::zero(),
+            calldata: index.to_be_bytes().to_vec(),
+            gas_limit,
+        })
+    }
+
+    fn tx_client(&self) -> &dyn SettlementTxClient {
+        self
+    }
+}
+
+#[async_trait]
+impl SettlementTxClient for Arc<FakeRelayClient> {
+    async fn mined_nonce(&self) -> anyhow::Result<u64> {
+        Ok(self.state.lock().unwrap().nonce)
+    }
+
+    async fn base_fee_per_gas(&self) -> anyhow::Result<U256> {
+        Ok(100.into())
+    }
+
+    async fn sign_tx(
+        &self,
+        request: &SettlementTxRequest,
+        nonce: u64,
+        fees: Option<TxFees>,
+    ) -> anyhow::Result<SignedSettlementTx> {
+        let mut state = self.state.lock().unwrap();
+        let index = u64::from_be_bytes(request.calldata.as_slice().try_into()?) as usize;
+        let fees = fees.unwrap_or(TxFees {
+            max_fee_per_gas: 201.into(),
+            max_priority_fee_per_gas: 1.into(),
+        });
+        let hash = H256::from_low_u64_be(state.signed_txs.len() as u64 + 1);
+        state.signed_txs.push(FakeSignedTx {
+            index,
+            hash,
+            nonce,
+            fees,
+        });
+        Ok(SignedSettlementTx {
+            tx_hash: hash,
+            raw_tx: hash.as_bytes().to_vec(),
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+        })
+    }
+
+    async fn send_raw_tx(&self, raw_tx: &[u8]) -> anyhow::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        anyhow::ensure!(!state.fail_broadcasts, "emulated broadcast failure");
+        state.broadcast_txs.push(H256::from_slice(raw_tx));
+        Ok(())
+    }
+
+    async fn tx_status(&self, tx_hash: H256) -> anyhow::Result<Option<MinedTxStatus>> {
+        Ok(self.state.lock().unwrap().mined_txs.get(&tx_hash).copied())
+    }
+}
+
+fn outbound_config() -> MessageRelayConfig {
+    MessageRelayConfig {
+        polling_interval_ms: 10,
+        outbound_senders: vec![OUTBOUND_SENDER],
+        inbox_addr: Some(Address::repeat_byte(0x01)),
+        outbox_addr: None,
+        max_messages_per_tx: 10,
+        gas_limit_per_message: 300_000,
+        max_attempts: 2,
+        tx_resend_interval_sec: 3_600,
+    }
+}
+
+fn inbound_config() -> MessageRelayConfig {
+    MessageRelayConfig {
+        outbound_senders: vec![],
+        inbox_addr: None,
+        outbox_addr: Some(Address::repeat_byte(0x02)),
+        ..outbound_config()
+    }
+}
+
+async fn create_relay(
+    pool: &ConnectionPool,
+    config: MessageRelayConfig,
+    client: &Arc<FakeRelayClient>,
+) -> MessageRelay {
+    MessageRelay::new(Box::new(client.clone()), pool.clone(), config)
+        .await
+        .unwrap()
+}
+
+fn inbound_message(block_number: u64, nonce: u64) -> RelayedMessage {
+    RelayedMessage {
+        id: inbound_message_id(nonce),
+        source_tx_hash: H256::from_low_u64_be(1_000 + nonce),
+        sender: Address::repeat_byte(0x61),
+        data: vec![0x62; 36],
+        origin: MessageOrigin::SettlementLayer {
+            block_number,
+            nonce,
+            target: Address::repeat_byte(0x63),
+        },
+    }
+}
+
+#[tokio::test]
+async fn relay_requires_configured_direction() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = Arc::<FakeRelayClient>::default();
+    let config = MessageRelayConfig {
+        outbound_senders: vec![],
+        ..outbound_config()
+    };
+    let err = MessageRelay::new(Box::new(client), pool, config)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("must have"), "{err}");
+}
+
+#[tokio::test]
+async fn relaying_outbound_messages() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let messages_by_tx = [
+        vec![
+            (OUTBOUND_SENDER, b"hello".to_vec()),
+            (Address::repeat_byte(0x33), b"not relayed".to_vec()),
+        ],
+        vec![(OUTBOUND_SENDER, b"world".to_vec())],
+    ];
+    let tx_hashes = store_executed_l1_batch_with_messages(&mut storage, 1, &messages_by_tx).await;
+    drop(storage);
+
+    let client = Arc::<FakeRelayClient>::default();
+    let mut relay = create_relay(&pool, outbound_config(), &client).await;
+    assert_eq!(relay.next_l1_batch, L1BatchNumber(1));
+    assert_eq!(relay.next_settlement_block, None);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(relay.next_l1_batch, L1BatchNumber(2));
+
+    let sent_txs = client.sent_txs();
+    assert_eq!(sent_txs.len(), 1);
+    let sent_messages = &sent_txs[0];
+    assert_eq!(sent_messages.len(), 2);
+    let expected_messages = [(0, tx_hashes[0], b"hello"), (2, tx_hashes[1], b"world")];
+    for (message, (index, tx_hash, data)) in sent_messages.iter().zip(expected_messages) {
+        assert_eq!(message.id, outbound_message_id(L1BatchNumber(1), index));
+        assert_eq!(message.source_tx_hash, tx_hash);
+        assert_eq!(message.sender, OUTBOUND_SENDER);
+        assert_eq!(message.data, data);
+        let MessageOrigin::L1Batch {
+            l1_batch_number,
+            l2_message_index,
+            merkle_proof,
+            ..
+        } = &message.origin
+        else {
+            panic!("unexpected message origin: {:?}", message.origin);
+        };
+        assert_eq!(*l1_batch_number, L1BatchNumber(1));
+        assert_eq!(*l2_message_index, index);
+        assert!(!merkle_proof.is_empty());
+    }
+
+    // No new transactions should be sent while the previous one is not mined.
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    client.mine_tx(1, true);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let statuses = storage
+        .relayed_messages_dal()
+        .get_messages_by_source_tx(tx_hashes[0])
+        .await
+        .unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].direction, MessageDirection::Outbound);
+    assert_eq!(statuses[0].status, MessageDeliveryStatus::Delivered);
+    assert_eq!(statuses[0].attempts, 1);
+    assert_eq!(
+        statuses[0].receipt,
+        Some(MessageDeliveryReceipt {
+            tx_hash: H256::from_low_u64_be(1),
+            block_number: 101,
+        })
+    );
+    drop(storage);
+
+    // The restarted relay should not rescan the L1 batch.
+    let relay = create_relay(&pool, outbound_config(), &client).await;
+    assert_eq!(relay.next_l1_batch, L1BatchNumber(2));
+}
+
+#[tokio::test]
+async fn relaying_inbound_messages() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = Arc::<FakeRelayClient>::default();
+    client.set_finalized_block_number(10);
+    let mut relay = create_relay(&pool, inbound_config(), &client).await;
+    assert_eq!(relay.next_settlement_block, Some(10));
+
+    let messages = [inbound_message(12, 0), inbound_message(15, 1)];
+    for message in &messages {
+        client.send_inbound_message(message.clone());
+    }
+    client.set_finalized_block_number(13);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(relay.next_settlement_block, Some(14));
+    assert_eq!(client.sent_txs(), [[messages[0].clone()]]);
+
+    // The second message is observed, but not sent while the first transaction is pending.
+    client.set_finalized_block_number(20);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(relay.next_settlement_block, Some(21));
+    assert_eq!(client.sent_txs().len(), 1);
+
+    // Rescanning blocks after a restart must not duplicate messages.
+    let mut relay = create_relay(&pool, inbound_config(), &client).await;
+    assert_eq!(relay.next_settlement_block, Some(16));
+    relay.next_settlement_block = Some(10);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    client.mine_tx(1, true);
+    client.deliver_externally(&messages[1]);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    for (i, message) in messages.iter().enumerate() {
+        let statuses = storage
+            .relayed_messages_dal()
+            .get_messages_by_source_tx(message.source_tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(statuses.len(), 1);
+        let status = &statuses[0];
+        assert_eq!(status.id, message.id);
+        assert_eq!(status.direction, MessageDirection::Inbound);
+        assert_eq!(status.target, Some(Address::repeat_byte(0x63)));
+        assert_eq!(status.status, MessageDeliveryStatus::Delivered);
+        let expected_receipt = (i == 0).then_some(MessageDeliveryReceipt {
+            tx_hash: H256::from_low_u64_be(1),
+            block_number: 101,
+        });
+        assert_eq!(status.receipt, expected_receipt);
+    }
+}
+
+#[tokio::test]
+async fn retrying_failed_deliveries() {
+    let pool = ConnectionPool::test_pool().await;
+    let message = inbound_message(5, 0);
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .relayed_messages_dal()
+        .insert_messages(&[message.clone()])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let client = Arc::<FakeRelayClient>::default();
+    client.set_finalized_block_number(5);
+    let mut relay = create_relay(&pool, inbound_config(), &client).await;
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    // The transaction is mined, but the message is not delivered; it should be retried.
+    client.mine_tx(1, false);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs(), [[message.clone()], [message.clone()]]);
+
+    // After the second failure, the message should be marked as failed.
+    client.mine_tx(2, false);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 2);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let statuses = storage
+        .relayed_messages_dal()
+        .get_messages_by_source_tx(message.source_tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].status, MessageDeliveryStatus::Failed);
+    assert_eq!(statuses[0].attempts, 2);
+    assert_eq!(statuses[0].receipt, None);
+}
+
+async fn create_relay_with_pending_message(
+    pool: &ConnectionPool,
+    config: MessageRelayConfig,
+    client: &Arc<FakeRelayClient>,
+) -> (MessageRelay, RelayedMessage) {
+    let message = inbound_message(5, 0);
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .relayed_messages_dal()
+        .insert_messages(&[message.clone()])
+        .await
+        .unwrap();
+    drop(storage);
+
+    client.set_finalized_block_number(5);
+    let relay = create_relay(pool, config, client).await;
+    (relay, message)
+}
+
+async fn assert_message_delivered(pool: &ConnectionPool, message: &RelayedMessage, tx_hash: H256) {
+    let mut storage = pool.access_storage().await.unwrap();
+    let statuses = storage
+        .relayed_messages_dal()
+        .get_messages_by_source_tx(message.source_tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].status, MessageDeliveryStatus::Delivered);
+    assert_eq!(statuses[0].receipt.unwrap().tx_hash, tx_hash);
+}
+
+#[tokio::test]
+async fn delivery_tx_is_persisted_before_sending() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = Arc::<FakeRelayClient>::default();
+    let (mut relay, message) =
+        create_relay_with_pending_message(&pool, inbound_config(), &client).await;
+    client.set_fail_broadcasts(true);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs(), [[message.clone()]]);
+    assert_eq!(client.broadcast_txs(), []);
+
+    // The persisted transaction should be rebroadcast rather than replaced with a new one.
+    client.set_fail_broadcasts(false);
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+    let signed_txs = client.signed_txs();
+    assert_eq!(signed_txs.len(), 1);
+    assert_eq!(client.broadcast_txs(), [signed_txs[0].hash]);
+
+    let tx_hash = client.mine_tx(1, true);
+    relay.loop_iteration().await.unwrap();
+    assert_message_delivered(&pool, &message, tx_hash).await;
+}
+
+#[tokio::test]
+async fn replacing_delivery_tx_with_bumped_fees() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = Arc::<FakeRelayClient>::default();
+    let config = MessageRelayConfig {
+        tx_resend_interval_sec: 0,
+        ..inbound_config()
+    };
+    let (mut relay, message) = create_relay_with_pending_message(&pool, config, &client).await;
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.signed_txs().len(), 1);
+
+    // The transaction is not mined, so it should be replaced.
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs(), [[message.clone()]]);
+    let signed_txs = client.signed_txs();
+    assert_eq!(signed_txs.len(), 2);
+    let (original_tx, replacement_tx) = (&signed_txs[0], &signed_txs[1]);
+    assert_eq!(replacement_tx.index, original_tx.index);
+    assert_eq!(replacement_tx.nonce, original_tx.nonce);
+    assert!(
+        replacement_tx.fees.max_fee_per_gas * 10 >= original_tx.fees.max_fee_per_gas * 11,
+        "{signed_txs:?}"
+    );
+    assert!(
+        replacement_tx.fees.max_priority_fee_per_gas * 10
+            >= original_tx.fees.max_priority_fee_per_gas * 11,
+        "{signed_txs:?}"
+    );
+
+    let tx_hash = client.mine_tx(1, true);
+    assert_eq!(tx_hash, replacement_tx.hash);
+    relay.loop_iteration().await.unwrap();
+    assert_message_delivered(&pool, &message, tx_hash).await;
+}
+
+#[tokio::test]
+async fn processing_delivery_tx_with_used_nonce() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = Arc::<FakeRelayClient>::default();
+    let (mut relay, message) =
+        create_relay_with_pending_message(&pool, inbound_config(), &client).await;
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    // The nonce is used by another transaction that doesn't deliver the message; it should be retried.
+    client.use_nonce();
+    relay.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs(), [[message.clone()], [message]]);
+    let signed_txs = client.signed_txs();
+    assert_eq!(signed_txs.len(), 2);
+    assert_eq!(signed_txs[1].nonce, 1);
+}
@@ -0,0 +1,149 @@
+//! Health checks for the Merkle tree lag and for nodes without a tree.
+
+use serde::Serialize;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
+use zksync_types::L1BatchNumber;
+
+use super::helpers::MerkleTreeHealth;
+
+#[derive(Debug, Serialize)]
+struct TreeLagHealthDetails {
+    sealed_l1_batch: Option<L1BatchNumber>,
+    last_l1_batch_with_metadata: Option<L1BatchNumber>,
+    /// Number of sealed L1 batches without metadata computed by the tree; `None` if no L1 batches
+    /// have metadata yet (e.g., right after snapshot recovery).
+    lag: Option<u32>,
+    /// Lag after which the tree is reported as affected.
+    max_lag: Option<u32>,
+}
+
+/// Health check reporting how many sealed L1 batches the Merkle tree hasn't processed yet. Unlike
+/// the `tree` health check, this one is based on Postgres data, so it is accurate regardless of the tree state.
+#[derive(Debug)]
+pub struct TreeLagHealthCheck {
+    pool: ConnectionPool,
+    max_lag: Option<u32>,
+}
+
+impl TreeLagHealthCheck {
+    pub fn new(pool: ConnectionPool, max_lag: Option<u32>) -> Self {
+        Self { pool, max_lag }
+    }
+
+    async fn details(&self) -> anyhow::Result<TreeLagHealthDetails> {
+        let mut storage = self.pool.access_storage_tagged("healthcheck").await?;
+        let sealed_l1_batch = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+        let last_l1_batch_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await?;
+        let lag = sealed_l1_batch
+            .zip(last_l1_batch_with_metadata)
+            .map(|(sealed, with_metadata)| sealed.0.saturating_sub(with_metadata.0));
+        Ok(TreeLagHealthDetails {
+            sealed_l1_batch,
+            last_l1_batch_with_metadata,
+            lag,
+            max_lag: self.max_lag,
+        })
+    }
+}
+
+#[async_trait]
+impl CheckHealth for TreeLagHealthCheck {
+    fn name(&self) -> &'static str {
+        "tree_lag"
+    }
+
+    async fn check_health(&self) -> Health {
+        match self.details().await {
+            Ok(details) => {
+                let is_lagging = details
+                    .lag
+                    .zip(details.max_lag)
+                    .is_some_and(|(lag, max_lag)| lag > max_lag);
+                let status = if is_lagging {
+                    HealthStatus::Affected
+                } else {
+                    HealthStatus::Ready
+                };
+                Health::from(status).with_details(details)
+            }
+            Err(err) => {
+                tracing::warn!("Failed checking Merkle tree lag: {err:#}");
+                let details = serde_json::json!({
+                    "error": format!("{err:#}"),
+                });
+                Health::from(HealthStatus::NotReady).with_details(details)
+            }
+        }
+    }
+}
+
+/// `tree` health check for nodes not running the Merkle tree (e.g., external nodes taking L1 batch root hashes
+/// from the main node). Always ready; allows to distinguish such nodes from nodes with a stalled tree.
+#[derive(Debug)]
+pub struct DisabledTreeHealthCheck;
+
+#[async_trait]
+impl CheckHealth for DisabledTreeHealthCheck {
+    fn name(&self) -> &'static str {
+        "tree"
+    }
+
+    async fn check_health(&self) -> Health {
+        MerkleTreeHealth::Disabled.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_types::L2ChainId;
+
+    use super::*;
+    use crate::{
+        genesis::{ensure_genesis_state, GenesisParams},
+        utils::testonly::create_l1_batch,
+    };
+
+    #[tokio::test]
+    async fn checking_tree_lag() {
+        let pool = ConnectionPool::test_pool().await;
+        let health_check = TreeLagHealthCheck::new(pool.clone(), Some(1));
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(1))
+            .await
+            .unwrap();
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(2))
+            .await
+            .unwrap();
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Affected);
+    }
+
+    #[tokio::test]
+    async fn checking_disabled_tree() {
+        let health = DisabledTreeHealthCheck.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+        let health = serde_json::to_value(health).unwrap();
+        assert_eq!(
+            health["details"],
+            serde_json::json!({ "stage": "disabled" })
+        );
+    }
+}
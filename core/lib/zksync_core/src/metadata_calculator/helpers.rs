@@ -2,7 +2,9 @@
 
 use std::{
     collections::BTreeMap,
+    fs,
     future::Future,
+    io,
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -11,18 +13,20 @@ use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
 #[cfg(test)]
 use tokio::sync::mpsc;
-use zksync_config::configs::database::MerkleTreeMode;
+use zksync_config::configs::database::{MerkleTreeMode, RocksdbProfile};
 use zksync_dal::StorageProcessor;
 use zksync_health_check::{Health, HealthStatus};
 use zksync_merkle_tree::{
     domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
     recovery::MerkleTreeRecovery,
-    Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction,
+    ConsistencyError, Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof,
+    TreeInstruction,
 };
 use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries};
 use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
 
 use super::metrics::{LoadChangesStage, TreeUpdateStage, METRICS};
+use crate::utils::rocksdb_options;
 
 /// General information about the Merkle tree.
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,6 +47,8 @@ pub(super) enum MerkleTreeHealth {
         recovered_chunk_count: u64,
     },
     MainLoop(MerkleTreeInfo),
+    /// The node doesn't run the tree at all.
+    Disabled,
 }
 
 impl From<MerkleTreeHealth> for Health {
@@ -64,6 +70,7 @@ pub(super) async fn create_db(
     memtable_capacity: usize,
     stalled_writes_timeout: Duration,
     multi_get_chunk_size: usize,
+    rocksdb_profile: RocksdbProfile,
 ) -> anyhow::Result<RocksDBWrapper> {
     tokio::task::spawn_blocking(move || {
         create_db_sync(
@@ -72,6 +79,7 @@ pub(super) async fn create_db(
             memtable_capacity,
             stalled_writes_timeout,
             multi_get_chunk_size,
+            &rocksdb_profile,
         )
     })
     .await
@@ -84,11 +92,12 @@ fn create_db_sync(
     memtable_capacity: usize,
     stalled_writes_timeout: Duration,
     multi_get_chunk_size: usize,
+    rocksdb_profile: &RocksdbProfile,
 ) -> anyhow::Result<RocksDBWrapper> {
     tracing::info!(
         "Initializing Merkle tree database at `{path}` with {multi_get_chunk_size} multi-get chunk size, \
          {block_cache_capacity}B block cache, {memtable_capacity}B memtable capacity, \
-         {stalled_writes_timeout:?} stalled writes timeout",
+         {stalled_writes_timeout:?} stalled writes timeout, {rocksdb_profile:?}",
         path = path.display()
     );
 
@@ -98,6 +107,7 @@ fn create_db_sync(
             block_cache_capacity: Some(block_cache_capacity),
             large_memtable_capacity: Some(memtable_capacity),
             stalled_writes_retries: StalledWritesRetries::new(stalled_writes_timeout),
+            ..rocksdb_options(rocksdb_profile)
         },
     )?;
     if cfg!(test) {
@@ -110,6 +120,21 @@ fn create_db_sync(
     Ok(db)
 }
 
+/// Computes the total size of all files in the specified directory, recursing into subdirectories.
+pub(super) fn directory_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            directory_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
 /// Wrapper around the "main" tree implementation used by [`MetadataCalculator`].
 ///
 /// Async methods provided by this wrapper are not cancel-safe! This is probably not an issue;
@@ -198,6 +223,12 @@ impl AsyncTree {
     pub fn revert_logs(&mut self, last_l1_batch_to_keep: L1BatchNumber) {
         self.as_mut().revert_logs(last_l1_batch_to_keep);
     }
+
+    /// Makes the tree hash levels using a dedicated `rayon` thread pool with the specified number of threads
+    /// (0 means the number of logical CPUs).
+    pub fn use_dedicated_thread_pool(&mut self, thread_count: usize) {
+        self.as_mut().use_dedicated_thread_pool(thread_count);
+    }
 }
 
 /// Async version of [`ZkSyncTreeReader`].
@@ -219,6 +250,16 @@ impl AsyncTreeReader {
         .unwrap()
     }
 
+    pub async fn entries(
+        self,
+        l1_batch_number: L1BatchNumber,
+        keys: Vec<Key>,
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        tokio::task::spawn_blocking(move || self.inner.entries(l1_batch_number, &keys))
+            .await
+            .unwrap()
+    }
+
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
@@ -228,6 +269,29 @@ impl AsyncTreeReader {
             .await
             .unwrap()
     }
+
+    pub async fn root_hash_for_l1_batch(self, l1_batch_number: L1BatchNumber) -> Option<H256> {
+        tokio::task::spawn_blocking(move || self.inner.root_hash_for_l1_batch(l1_batch_number))
+            .await
+            .unwrap()
+    }
+
+    /// Verifies consistency of subtrees on paths to the specified `keys`, stopping on the first error.
+    pub async fn verify_subtrees_consistency(
+        self,
+        l1_batch_number: L1BatchNumber,
+        keys: Vec<Key>,
+        depth: usize,
+    ) -> Result<(), ConsistencyError> {
+        tokio::task::spawn_blocking(move || {
+            keys.iter().try_for_each(|key| {
+                self.inner
+                    .verify_subtree_consistency(l1_batch_number, key, depth)
+            })
+        })
+        .await
+        .unwrap()
+    }
 }
 
 /// Async wrapper for [`MerkleTreeRecovery`].
@@ -587,6 +651,7 @@ mod tests {
             16 << 20,       // 16 MiB,
             Duration::ZERO, // writes should never be stalled in tests
             500,
+            RocksdbProfile::default(),
         )
         .await
         .unwrap();
@@ -0,0 +1,213 @@
+//! Background verification of the Merkle tree integrity.
+
+use std::time::Instant;
+
+use rand::Rng;
+use serde::Serialize;
+use tokio::sync::{mpsc, watch};
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater};
+use zksync_merkle_tree::{ConsistencyError, Key};
+use zksync_types::{L1BatchNumber, U256};
+
+use super::{helpers::AsyncTreeReader, metrics::METRICS, MerkleTreeIntegrityCheckConfig};
+
+/// Depth (in nibbles) of the roots of subtrees checked by [`TreeIntegrityVerifier`]. Each subtree
+/// contains approximately `16^-SUBTREE_DEPTH` of all tree leaves.
+const SUBTREE_DEPTH: usize = 3;
+
+/// Corruption of the Merkle tree detected by [`TreeIntegrityVerifier`].
+#[derive(Debug, Clone, Serialize)]
+struct TreeCorruption {
+    /// Earliest L1 batch affected by the corruption, or `None` if it cannot be determined.
+    first_l1_batch: Option<L1BatchNumber>,
+    description: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct TreeIntegrityHealthDetails {
+    last_checked_l1_batch: Option<L1BatchNumber>,
+    /// Total number of subtrees checked without detecting corruption.
+    checked_subtrees: u64,
+    corruption: Option<TreeCorruption>,
+    /// Earliest L1 batch requested to be rebuilt in the tree from Postgres data.
+    repair_requested_from: Option<L1BatchNumber>,
+}
+
+/// Periodically re-hashes random subtrees and the root of the latest tree version, and compares
+/// them with hashes stored in the tree and in Postgres. If auto-repair is enabled, requests
+/// [`TreeUpdater`](super::updater::TreeUpdater) to rebuild corrupted L1 batches from Postgres.
+#[derive(Debug)]
+pub(super) struct TreeIntegrityVerifier {
+    config: MerkleTreeIntegrityCheckConfig,
+    pool: ConnectionPool,
+    tree_reader: AsyncTreeReader,
+    repair_sender: mpsc::UnboundedSender<L1BatchNumber>,
+    health_updater: HealthUpdater,
+    details: TreeIntegrityHealthDetails,
+}
+
+impl TreeIntegrityVerifier {
+    pub fn new(
+        config: MerkleTreeIntegrityCheckConfig,
+        pool: ConnectionPool,
+        tree_reader: AsyncTreeReader,
+        repair_sender: mpsc::UnboundedSender<L1BatchNumber>,
+        health_updater: HealthUpdater,
+    ) -> Self {
+        Self {
+            config,
+            pool,
+            tree_reader,
+            repair_sender,
+            health_updater,
+            details: TreeIntegrityHealthDetails::default(),
+        }
+    }
+
+    fn health(&self) -> Health {
+        let status = if self.details.corruption.is_some() {
+            HealthStatus::Affected
+        } else {
+            HealthStatus::Ready
+        };
+        Health::from(status).with_details(&self.details)
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater.update(self.health());
+        while !*stop_receiver.borrow_and_update() {
+            let started_at = Instant::now();
+            match self.check().await {
+                Ok(Some((l1_batch_number, corruption))) => {
+                    self.update_state(l1_batch_number, corruption);
+                    self.health_updater.update(self.health());
+                }
+                Ok(None) => { /* No tree versions to check */ }
+                Err(err) => tracing::warn!("Failed checking Merkle tree integrity: {err:#}"),
+            }
+            METRICS
+                .integrity_check_latency
+                .observe(started_at.elapsed());
+
+            if tokio::time::timeout(self.config.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, Merkle tree integrity verifier is shutting down");
+        Ok(())
+    }
+
+    /// Checks the latest tree version. Returns the checked L1 batch together with the detected corruption,
+    /// or `None` if the tree is empty or was reverted during the check.
+    async fn check(&self) -> anyhow::Result<Option<(L1BatchNumber, Option<TreeCorruption>)>> {
+        let tree_info = self.tree_reader.clone().info().await;
+        let Some(l1_batch_number) = tree_info.next_l1_batch_number.0.checked_sub(1) else {
+            return Ok(None);
+        };
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let Some(root_hash) = self
+            .tree_reader
+            .clone()
+            .root_hash_for_l1_batch(l1_batch_number)
+            .await
+        else {
+            return Ok(None);
+        };
+
+        let mut storage = self
+            .pool
+            .access_storage_tagged("metadata_calculator")
+            .await?;
+        let postgres_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await?;
+        drop(storage);
+        if let Some(postgres_root_hash) = postgres_root_hash {
+            if postgres_root_hash != root_hash {
+                let corruption = TreeCorruption {
+                    first_l1_batch: Some(l1_batch_number),
+                    description: format!(
+                        "tree root hash {root_hash:?} differs from the one stored in Postgres: {postgres_root_hash:?}"
+                    ),
+                };
+                return Ok(Some((l1_batch_number, Some(corruption))));
+            }
+        }
+
+        let keys: Vec<Key> = {
+            let mut rng = rand::thread_rng();
+            (0..self.config.subtree_count)
+                .map(|_| U256(rng.gen()))
+                .collect()
+        };
+        let result = self
+            .tree_reader
+            .clone()
+            .verify_subtrees_consistency(l1_batch_number, keys, SUBTREE_DEPTH)
+            .await;
+        let corruption = match result {
+            Ok(()) => None,
+            Err(ConsistencyError::MissingVersion(_) | ConsistencyError::MissingRoot(_)) => {
+                // The tree was reverted during the check.
+                return Ok(None);
+            }
+            Err(err) => Some(TreeCorruption {
+                first_l1_batch: err
+                    .corrupted_version()
+                    .and_then(|version| u32::try_from(version).ok())
+                    .map(L1BatchNumber),
+                description: err.to_string(),
+            }),
+        };
+        Ok(Some((l1_batch_number, corruption)))
+    }
+
+    fn update_state(&mut self, l1_batch_number: L1BatchNumber, corruption: Option<TreeCorruption>) {
+        self.details.last_checked_l1_batch = Some(l1_batch_number);
+        let Some(corruption) = corruption else {
+            self.details.checked_subtrees += self.config.subtree_count as u64;
+            self.details.corruption = None;
+            return;
+        };
+
+        METRICS.integrity_errors.inc();
+        tracing::error!(
+            "Detected Merkle tree corruption when checking L1 batch #{l1_batch_number}: {}",
+            corruption.description
+        );
+        if self.config.auto_repair {
+            self.request_repair(&corruption);
+        }
+        self.details.corruption = Some(corruption);
+    }
+
+    fn request_repair(&mut self, corruption: &TreeCorruption) {
+        let Some(first_l1_batch) = corruption.first_l1_batch else {
+            tracing::error!("Cannot determine L1 batches affected by Merkle tree corruption; the tree must be recreated");
+            return;
+        };
+        if first_l1_batch == L1BatchNumber(0) {
+            tracing::error!("Genesis L1 batch is affected by Merkle tree corruption; the tree must be recreated");
+            return;
+        }
+        if self.details.repair_requested_from == Some(first_l1_batch) {
+            tracing::error!(
+                "Merkle tree corruption persists after rebuilding L1 batches starting from #{first_l1_batch}; \
+                 the tree must be recreated"
+            );
+            return;
+        }
+
+        tracing::warn!(
+            "Requesting to rebuild Merkle tree starting from L1 batch #{first_l1_batch}"
+        );
+        if self.repair_sender.send(first_l1_batch).is_ok() {
+            self.details.repair_requested_from = Some(first_l1_batch);
+        }
+    }
+}
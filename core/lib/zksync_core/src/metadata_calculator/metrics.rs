@@ -3,8 +3,8 @@
 use std::time::{Duration, Instant};
 
 use vise::{
-    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver, Metrics,
-    Unit,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
+    Metrics, Unit,
 };
 use zksync_types::block::L1BatchHeader;
 use zksync_utils::time::seconds_since_epoch;
@@ -85,6 +85,9 @@ pub(super) struct MetadataCalculatorMetrics {
     /// The lag can only be positive if Postgres was restored from a backup truncating some
     /// of the batches already processed by the tree.
     pub backup_lag: Gauge<u64>,
+    /// Total size of the Merkle tree RocksDB directory on disk.
+    #[metrics(unit = Unit::Bytes)]
+    pub disk_usage: Gauge<u64>,
     /// Number of zero values that need to be checked for L1 batch of the initial write in the process
     /// of updating the Merkle tree.
     #[metrics(buckets = COUNTS_BUCKETS)]
@@ -101,6 +104,16 @@ pub(super) struct MetadataCalculatorMetrics {
     /// Number of L1 batches applied to the Merkle tree in a single iteration.
     #[metrics(buckets = Buckets::linear(1.0..=20.0, 1.0))]
     blocks_batch: Histogram<usize>,
+    /// Number of L1 batches which data was loaded from Postgres in parallel with flushing
+    /// the previous tree updates to RocksDB.
+    pub prefetched_l1_batches: Counter,
+    /// Latency of a single Merkle tree integrity check.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub integrity_check_latency: Histogram<Duration>,
+    /// Number of Merkle tree integrity checks that have detected corruption.
+    pub integrity_errors: Counter,
+    /// Number of L1 batches reverted in the Merkle tree in order to rebuild them after detecting corruption.
+    pub integrity_rebuilt_l1_batches: Counter,
     /// Latency of updating the Merkle tree per stage.
     #[metrics(buckets = Buckets::LATENCIES)]
     update_tree_latency_stage: Family<TreeUpdateStage, Histogram<Duration>>,
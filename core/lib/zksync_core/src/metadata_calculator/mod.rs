@@ -3,27 +3,35 @@
 
 use std::{
     future::{self, Future},
+    num::NonZeroU64,
+    path::PathBuf,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use anyhow::Context as _;
-use tokio::sync::watch;
+use tokio::sync::{mpsc, watch};
 use zksync_config::configs::{
     chain::OperationsManagerConfig,
-    database::{MerkleTreeConfig, MerkleTreeMode},
+    database::{MerkleTreeConfig, MerkleTreeMode, RocksdbProfile},
 };
 use zksync_dal::ConnectionPool;
 use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
+use zksync_merkle_tree::{MerkleTreePruner, RocksDBWrapper};
 use zksync_object_store::ObjectStore;
 
+pub use self::health::{DisabledTreeHealthCheck, TreeLagHealthCheck};
 pub(crate) use self::helpers::{AsyncTreeReader, L1BatchWithLogs, MerkleTreeInfo};
 use self::{
-    helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth},
+    helpers::{create_db, directory_size, Delayer, GenericAsyncTree, MerkleTreeHealth},
+    integrity::TreeIntegrityVerifier,
+    metrics::METRICS,
     updater::TreeUpdater,
 };
 
+mod health;
 mod helpers;
+mod integrity;
 mod metrics;
 mod recovery;
 #[cfg(test)]
@@ -51,6 +59,39 @@ pub struct MetadataCalculatorConfig {
     pub memtable_capacity: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// Number of threads in a dedicated `rayon` thread pool used to hash tree levels in parallel when updating
+    /// the tree. 0 means the number of logical CPUs. If not set, the global `rayon` thread pool is used.
+    pub thread_pool_size: Option<usize>,
+    /// RocksDB tuning profile (compaction style, WAL settings) for the tree database.
+    pub rocksdb_profile: RocksdbProfile,
+    /// Configuration of the Merkle tree pruning. If not set, the tree retains all its versions.
+    pub pruning: Option<MerkleTreePruningConfig>,
+    /// Configuration of background Merkle tree integrity checks. If not set, integrity is not checked.
+    pub integrity_check: Option<MerkleTreeIntegrityCheckConfig>,
+}
+
+/// Configuration of the Merkle tree pruning performed by [`MetadataCalculator`].
+#[derive(Debug, Clone)]
+pub struct MerkleTreePruningConfig {
+    /// Number of the latest tree versions (= L1 batches) to retain. Merkle proofs can only be served
+    /// for the retained L1 batches, and the tree cannot be reverted beyond them.
+    pub retained_versions: NonZeroU64,
+    /// Interval between checks whether there are tree versions to prune.
+    pub poll_interval: Duration,
+    /// Minimum interval between RocksDB compactions triggered after pruning. If not set, the disk space
+    /// occupied by pruned nodes is only reclaimed by background RocksDB compactions.
+    pub compaction_interval: Option<Duration>,
+}
+
+/// Configuration of the background Merkle tree integrity checks performed by [`MetadataCalculator`].
+#[derive(Debug, Clone)]
+pub struct MerkleTreeIntegrityCheckConfig {
+    /// Interval between integrity checks.
+    pub interval: Duration,
+    /// Number of random subtrees re-hashed on each check.
+    pub subtree_count: usize,
+    /// Whether to rebuild tree versions affected by detected corruption from Postgres data.
+    pub auto_repair: bool,
 }
 
 impl MetadataCalculatorConfig {
@@ -67,6 +108,16 @@ impl MetadataCalculatorConfig {
             block_cache_capacity: merkle_tree_config.block_cache_size(),
             memtable_capacity: merkle_tree_config.memtable_capacity(),
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            thread_pool_size: merkle_tree_config.thread_pool_size,
+            rocksdb_profile: merkle_tree_config.rocksdb.clone(),
+            pruning: None,
+            integrity_check: merkle_tree_config
+                .integrity_check_interval()
+                .map(|interval| MerkleTreeIntegrityCheckConfig {
+                    interval,
+                    subtree_count: merkle_tree_config.integrity_check_subtree_count,
+                    auto_repair: merkle_tree_config.integrity_auto_repair,
+                }),
         }
     }
 }
@@ -78,6 +129,7 @@ pub struct MetadataCalculator {
     object_store: Option<Arc<dyn ObjectStore>>,
     delayer: Delayer,
     health_updater: HealthUpdater,
+    integrity_health_updater: Option<HealthUpdater>,
     max_l1_batches_per_iter: usize,
 }
 
@@ -93,11 +145,16 @@ impl MetadataCalculator {
         );
 
         let (_, health_updater) = ReactiveHealthCheck::new("tree");
+        let integrity_health_updater = config
+            .integrity_check
+            .as_ref()
+            .map(|_| ReactiveHealthCheck::new("tree_integrity").1);
         Ok(Self {
             tree_reader: watch::channel(None).0,
             object_store,
             delayer: Delayer::new(config.delay_interval),
             health_updater,
+            integrity_health_updater,
             max_l1_batches_per_iter: config.max_l1_batches_per_iter,
             config,
         })
@@ -108,6 +165,13 @@ impl MetadataCalculator {
         self.health_updater.subscribe()
     }
 
+    /// Returns a health check for background tree integrity checks, or `None` if they are disabled.
+    pub fn tree_integrity_health_check(&self) -> Option<ReactiveHealthCheck> {
+        self.integrity_health_updater
+            .as_ref()
+            .map(HealthUpdater::subscribe)
+    }
+
     /// Returns a reference to the tree reader.
     pub(crate) fn tree_reader(&self) -> impl Future<Output = AsyncTreeReader> {
         let mut receiver = self.tree_reader.subscribe();
@@ -124,7 +188,10 @@ impl MetadataCalculator {
         }
     }
 
-    async fn create_tree(&self) -> anyhow::Result<GenericAsyncTree> {
+    /// Interval between reporting disk usage of the Merkle tree RocksDB.
+    const DISK_USAGE_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+    async fn create_db(&self) -> anyhow::Result<RocksDBWrapper> {
         self.health_updater
             .update(MerkleTreeHealth::Initialization.into());
 
@@ -135,6 +202,7 @@ impl MetadataCalculator {
             self.config.memtable_capacity,
             self.config.stalled_writes_timeout,
             self.config.multi_get_chunk_size,
+            self.config.rocksdb_profile.clone(),
         )
         .await
         .with_context(|| {
@@ -148,32 +216,113 @@ impl MetadataCalculator {
             self.config,
             started_at.elapsed()
         );
+        Ok(db)
+    }
 
+    async fn create_tree(&self) -> anyhow::Result<GenericAsyncTree> {
+        let db = self.create_db().await?;
         Ok(GenericAsyncTree::new(db, self.config.mode).await)
     }
 
+    /// Periodically reports the total size of the Merkle tree RocksDB directory on disk.
+    async fn report_disk_usage(
+        db_path: PathBuf,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow() {
+            let path = db_path.clone();
+            let disk_usage = tokio::task::spawn_blocking(move || directory_size(&path))
+                .await
+                .context("panicked computing Merkle tree disk usage")?;
+            match disk_usage {
+                Ok(size) => METRICS.disk_usage.set(size),
+                Err(err) => tracing::warn!(
+                    "Failed computing disk usage of Merkle tree RocksDB at `{}`: {err}",
+                    db_path.display()
+                ),
+            }
+
+            if tokio::time::timeout(Self::DISK_USAGE_REPORT_INTERVAL, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     pub async fn run(
         self,
         pool: ConnectionPool,
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
-        let tree = self.create_tree().await?;
+        let db = self.create_db().await?;
+        let tree = GenericAsyncTree::new(db.clone(), self.config.mode).await;
         let tree = tree
             .ensure_ready(&pool, &stop_receiver, &self.health_updater)
             .await?;
-        let Some(tree) = tree else {
+        let Some(mut tree) = tree else {
             return Ok(()); // recovery was aborted because a stop signal was received
         };
+        if let Some(thread_count) = self.config.thread_pool_size {
+            tracing::info!(
+                "Using dedicated thread pool with {thread_count} threads for Merkle tree updates"
+            );
+            tree.use_dedicated_thread_pool(thread_count);
+        }
         let tree_reader = tree.reader();
         tracing::info!(
             "Merkle tree is initialized and ready to process L1 batches: {:?}",
             tree_reader.clone().info().await
         );
-        self.tree_reader.send_replace(Some(tree_reader));
+        self.tree_reader.send_replace(Some(tree_reader.clone()));
 
-        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
-        updater
-            .loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater)
-            .await
+        // The pruner is only started after the tree is ready, so that it doesn't interfere with recovery.
+        let pruner = self.config.pruning.as_ref().map(|config| {
+            let past_versions_to_keep = config.retained_versions.get() - 1;
+            let (mut pruner, pruner_handle) = MerkleTreePruner::new(db, past_versions_to_keep);
+            pruner.set_poll_interval(config.poll_interval);
+            if let Some(compaction_interval) = config.compaction_interval {
+                pruner.set_compaction_interval(compaction_interval);
+            }
+            let pruner_task = tokio::task::spawn_blocking(|| pruner.run());
+            (pruner_handle, pruner_task)
+        });
+
+        let disk_usage_task =
+            Self::report_disk_usage(self.config.db_path.clone().into(), stop_receiver.clone());
+        let mut updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
+        let integrity_task = self
+            .config
+            .integrity_check
+            .zip(self.integrity_health_updater)
+            .map(|(config, health_updater)| {
+                let (repair_sender, repair_receiver) = mpsc::unbounded_channel();
+                updater.set_repair_receiver(repair_receiver);
+                let verifier = TreeIntegrityVerifier::new(
+                    config,
+                    pool.clone(),
+                    tree_reader,
+                    repair_sender,
+                    health_updater,
+                );
+                verifier.run(stop_receiver.clone())
+            });
+        let integrity_task = async {
+            match integrity_task {
+                Some(task) => task.await,
+                None => Ok(()),
+            }
+        };
+        let updater_task =
+            updater.loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater);
+        let result = tokio::try_join!(updater_task, disk_usage_task, integrity_task).map(drop);
+
+        if let Some((pruner_handle, pruner_task)) = pruner {
+            pruner_handle.abort();
+            pruner_task.await.context("Merkle tree pruner panicked")?;
+        }
+        result
     }
 }
@@ -8,7 +8,7 @@ use test_casing::test_casing;
 use tokio::sync::mpsc;
 use zksync_config::configs::{
     chain::OperationsManagerConfig,
-    database::{MerkleTreeConfig, MerkleTreeMode},
+    database::{MerkleTreeConfig, MerkleTreeMode, RocksdbProfile},
 };
 use zksync_health_check::{CheckHealth, HealthStatus, ReactiveHealthCheck};
 use zksync_merkle_tree::{domain::ZkSyncTree, TreeInstruction};
@@ -51,6 +51,7 @@ async fn create_tree_recovery(path: PathBuf, l1_batch: L1BatchNumber) -> AsyncTr
         16 << 20,       // 16 MiB,
         Duration::ZERO, // writes should never be stalled in tests
         500,
+        RocksdbProfile::default(),
     )
     .await
     .unwrap();
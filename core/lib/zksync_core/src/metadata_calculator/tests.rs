@@ -1,6 +1,6 @@
 //! Tests for the metadata calculator component life cycle.
 
-use std::{future::Future, ops, panic, path::Path, sync::Arc, time::Duration};
+use std::{future::Future, num::NonZeroU64, ops, panic, path::Path, sync::Arc, time::Duration};
 
 use assert_matches::assert_matches;
 use itertools::Itertools;
@@ -11,17 +11,21 @@ use zksync_config::configs::{
     database::{MerkleTreeConfig, MerkleTreeMode},
 };
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_health_check::{CheckHealth, HealthStatus};
+use zksync_health_check::{CheckHealth, HealthStatus, ReactiveHealthCheck};
 use zksync_merkle_tree::domain::ZkSyncTree;
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_prover_interface::inputs::PrepareBasicCircuitsJob;
 use zksync_types::{
-    block::L1BatchHeader, AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
-    StorageKey, StorageLog, H256,
+    block::{L1BatchHeader, L1BatchTreeData},
+    AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber, StorageKey, StorageLog,
+    H256, U256,
 };
 use zksync_utils::u32_to_h256;
 
-use super::{GenericAsyncTree, L1BatchWithLogs, MetadataCalculator, MetadataCalculatorConfig};
+use super::{
+    GenericAsyncTree, L1BatchWithLogs, MerkleTreeIntegrityCheckConfig, MerkleTreePruningConfig,
+    MetadataCalculator, MetadataCalculatorConfig,
+};
 use crate::{
     genesis::{ensure_genesis_state, GenesisParams},
     utils::testonly::{create_l1_batch, create_miniblock},
@@ -186,6 +190,24 @@ async fn multi_l1_batch_workflow() {
     }
 }
 
+#[tokio::test]
+async fn multi_l1_batch_workflow_with_dedicated_thread_pool() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Full);
+    merkle_tree_config.thread_pool_size = Some(2);
+    // Process L1 batches in several iterations, so that data for the next iteration is prefetched.
+    merkle_tree_config.max_l1_batches_per_iter = 3;
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    reset_db_state(&pool, 10).await;
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+
+    let expected_tree_hash = expected_tree_hash(&pool).await;
+    assert_eq!(root_hash, expected_tree_hash);
+}
+
 #[tokio::test]
 async fn running_metadata_calculator_with_additional_blocks() {
     let pool = ConnectionPool::test_pool().await;
@@ -236,6 +258,138 @@ async fn running_metadata_calculator_with_additional_blocks() {
     assert_eq!(root_hash_for_full_tree, updated_root_hash);
 }
 
+#[tokio::test]
+async fn pruning_old_tree_versions() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Full);
+    let calculator_config = MetadataCalculatorConfig {
+        pruning: Some(MerkleTreePruningConfig {
+            retained_versions: NonZeroU64::new(2).unwrap(),
+            poll_interval: Duration::from_millis(10),
+            compaction_interval: Some(Duration::ZERO),
+        }),
+        ..MetadataCalculatorConfig::for_main_node(&merkle_tree_config, &operation_config)
+    };
+    let calculator = setup_calculator_with_config(calculator_config, &pool, None).await;
+    reset_db_state(&pool, 5).await;
+
+    let tree_reader = calculator.tree_reader();
+    let (stop_sx, stop_rx) = watch::channel(false);
+    let calculator_task = tokio::spawn(calculator.run(pool, stop_rx));
+    let tree_reader = run_with_timeout(RUN_TIMEOUT, tree_reader).await;
+
+    let keys = vec![U256::zero()];
+    run_with_timeout(RUN_TIMEOUT, async {
+        loop {
+            // Tree info must be obtained first, so that a missing version isn't confused with a pruned one.
+            let next_l1_batch_number = tree_reader.clone().info().await.next_l1_batch_number;
+            let pruning_result = tree_reader
+                .clone()
+                .entries_with_proofs(L1BatchNumber(3), keys.clone())
+                .await;
+            if next_l1_batch_number == L1BatchNumber(6) && pruning_result.is_err() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+
+    // The 2 latest tree versions must be retained.
+    for l1_batch_number in [4, 5] {
+        tree_reader
+            .clone()
+            .entries_with_proofs(L1BatchNumber(l1_batch_number), keys.clone())
+            .await
+            .unwrap();
+    }
+
+    stop_sx.send_replace(true);
+    run_with_timeout(RUN_TIMEOUT, calculator_task)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn detecting_tree_corruption() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Full);
+    let calculator_config = MetadataCalculatorConfig {
+        integrity_check: Some(MerkleTreeIntegrityCheckConfig {
+            interval: Duration::from_millis(10),
+            subtree_count: 16,
+            auto_repair: false,
+        }),
+        ..MetadataCalculatorConfig::for_main_node(&merkle_tree_config, &operation_config)
+    };
+    let calculator = setup_calculator_with_config(calculator_config, &pool, None).await;
+    let integrity_health_check = calculator.tree_integrity_health_check().unwrap();
+    reset_db_state(&pool, 5).await;
+
+    let (stop_sx, stop_rx) = watch::channel(false);
+    let calculator_task = tokio::spawn(calculator.run(pool.clone(), stop_rx));
+    let health = wait_for_integrity_health(&integrity_health_check, |health| {
+        health["details"]["last_checked_l1_batch"] == 5
+    })
+    .await;
+    assert_eq!(health["status"], "ready");
+    assert!(health["details"]["checked_subtrees"].as_u64().unwrap() > 0);
+
+    // Replace the last L1 batch in Postgres, so that its root hash doesn't match the tree.
+    let mut storage = pool.access_storage().await.unwrap();
+    remove_l1_batches(&mut storage, L1BatchNumber(4)).await;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(5))
+        .await
+        .unwrap();
+    let tree_data = L1BatchTreeData {
+        hash: H256::repeat_byte(1),
+        rollup_last_leaf_index: 1,
+    };
+    storage
+        .blocks_dal()
+        .save_l1_batch_tree_data(L1BatchNumber(5), &tree_data)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let health = wait_for_integrity_health(&integrity_health_check, |health| {
+        health["details"]["corruption"].is_object()
+    })
+    .await;
+    assert_eq!(health["status"], "affected");
+    assert_eq!(health["details"]["corruption"]["first_l1_batch"], 5);
+    assert!(health["details"]["repair_requested_from"].is_null());
+
+    stop_sx.send_replace(true);
+    run_with_timeout(RUN_TIMEOUT, calculator_task)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+async fn wait_for_integrity_health(
+    health_check: &ReactiveHealthCheck,
+    mut condition: impl FnMut(&serde_json::Value) -> bool,
+) -> serde_json::Value {
+    let wait = async {
+        loop {
+            let health = serde_json::to_value(health_check.check_health().await).unwrap();
+            if condition(&health) {
+                break health;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    run_with_timeout(RUN_TIMEOUT, wait).await
+}
+
 #[tokio::test]
 async fn shutting_down_calculator() {
     let pool = ConnectionPool::test_pool().await;
@@ -394,6 +548,14 @@ async fn setup_calculator_with_options(
 ) -> MetadataCalculator {
     let calculator_config =
         MetadataCalculatorConfig::for_main_node(merkle_tree_config, operation_config);
+    setup_calculator_with_config(calculator_config, pool, object_store).await
+}
+
+async fn setup_calculator_with_config(
+    calculator_config: MetadataCalculatorConfig,
+    pool: &ConnectionPool,
+    object_store: Option<Arc<dyn ObjectStore>>,
+) -> MetadataCalculator {
     let metadata_calculator = MetadataCalculator::new(calculator_config, object_store)
         .await
         .unwrap();
@@ -4,7 +4,7 @@ use std::{ops, sync::Arc, time::Instant};
 
 use anyhow::Context as _;
 use futures::{future, FutureExt};
-use tokio::sync::watch;
+use tokio::sync::{mpsc, watch};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_health_check::HealthUpdater;
 use zksync_merkle_tree::domain::TreeMetadata;
@@ -27,6 +27,10 @@ pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
     object_store: Option<Arc<dyn ObjectStore>>,
+    /// Data for the first L1 batch of the next iteration loaded while flushing tree updates to RocksDB.
+    prefetched_l1_batch: Option<L1BatchWithLogs>,
+    /// Receives numbers of the earliest corrupted L1 batches found by the integrity verifier.
+    repair_receiver: Option<mpsc::UnboundedReceiver<L1BatchNumber>>,
 }
 
 impl TreeUpdater {
@@ -39,9 +43,15 @@ impl TreeUpdater {
             tree,
             max_l1_batches_per_iter,
             object_store,
+            prefetched_l1_batch: None,
+            repair_receiver: None,
         }
     }
 
+    pub fn set_repair_receiver(&mut self, receiver: mpsc::UnboundedReceiver<L1BatchNumber>) {
+        self.repair_receiver = Some(receiver);
+    }
+
     async fn process_l1_batch(
         &mut self,
         l1_batch: L1BatchWithLogs,
@@ -84,6 +94,9 @@ impl TreeUpdater {
     /// the first L1 batch data beforehand.) This allows saving some time if we actually process
     /// multiple L1 batches at once (e.g., during the initial tree syncing), and if loading data from Postgres
     /// is slow for whatever reason.
+    ///
+    /// Similarly, the first L1 batch for the next iteration is loaded in parallel with flushing
+    /// the tree updates to RocksDB, so that the tree doesn't idle on Postgres I/O during sustained load.
     async fn process_multiple_batches(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -93,7 +106,12 @@ impl TreeUpdater {
         tracing::info!("Processing L1 batches #{l1_batch_numbers:?}");
         let first_l1_batch_number = L1BatchNumber(*l1_batch_numbers.start());
         let last_l1_batch_number = L1BatchNumber(*l1_batch_numbers.end());
-        let mut l1_batch_data = L1BatchWithLogs::new(storage, first_l1_batch_number).await;
+        let mut l1_batch_data = self
+            .take_prefetched_l1_batch(storage, first_l1_batch_number)
+            .await;
+        if l1_batch_data.is_none() {
+            l1_batch_data = L1BatchWithLogs::new(storage, first_l1_batch_number).await;
+        }
 
         let mut total_logs = 0;
         let mut updated_headers = vec![];
@@ -157,14 +175,103 @@ impl TreeUpdater {
             l1_batch_data = next_l1_batch_data;
         }
 
-        let save_rocksdb_latency = METRICS.start_stage(TreeUpdateStage::SaveRocksdb);
-        self.tree.save().await;
-        save_rocksdb_latency.observe();
+        let tree = &mut self.tree;
+        let save_rocksdb_task = async {
+            let save_rocksdb_latency = METRICS.start_stage(TreeUpdateStage::SaveRocksdb);
+            tree.save().await;
+            save_rocksdb_latency.observe();
+        };
+        let prefetch_task = L1BatchWithLogs::new(storage, last_l1_batch_number + 1);
+        let ((), prefetched_l1_batch) = future::join(save_rocksdb_task, prefetch_task).await;
+        self.prefetched_l1_batch = prefetched_l1_batch;
         MetadataCalculator::update_metrics(&updated_headers, total_logs, start);
 
         last_l1_batch_number + 1
     }
 
+    /// Returns the prefetched data for the specified L1 batch if it's still up to date. The L1 batch
+    /// may have been rolled back and re-sealed since it was prefetched, so we compare its header
+    /// with the one currently in Postgres.
+    async fn take_prefetched_l1_batch(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Option<L1BatchWithLogs> {
+        let prefetched = self.prefetched_l1_batch.take()?;
+        if prefetched.header.number != l1_batch_number {
+            return None;
+        }
+        let current_header = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await
+            .unwrap();
+        if current_header.as_ref() == Some(&prefetched.header) {
+            METRICS.prefetched_l1_batches.inc();
+            Some(prefetched)
+        } else {
+            tracing::info!(
+                "Prefetched data for L1 batch #{l1_batch_number} is outdated; reloading it"
+            );
+            None
+        }
+    }
+
+    /// Reverts the tree so that L1 batches found corrupted by the integrity verifier are rebuilt
+    /// from Postgres data on the following iterations. Returns `true` if the tree was reverted.
+    async fn repair_if_requested(&mut self, next_l1_batch_to_seal: &mut L1BatchNumber) -> bool {
+        let Some(receiver) = &mut self.repair_receiver else {
+            return false;
+        };
+        let mut first_corrupted_l1_batch = None::<L1BatchNumber>;
+        while let Ok(l1_batch_number) = receiver.try_recv() {
+            first_corrupted_l1_batch = Some(
+                first_corrupted_l1_batch.map_or(l1_batch_number, |prev| prev.min(l1_batch_number)),
+            );
+        }
+        let Some(first_corrupted_l1_batch) = first_corrupted_l1_batch else {
+            return false;
+        };
+        if first_corrupted_l1_batch >= *next_l1_batch_to_seal {
+            tracing::info!(
+                "L1 batch #{first_corrupted_l1_batch} requested to be rebuilt is not in the tree; skipping"
+            );
+            return false;
+        }
+        let Some(last_l1_batch_to_keep) = first_corrupted_l1_batch.0.checked_sub(1) else {
+            tracing::error!(
+                "Cannot rebuild genesis L1 batch in the Merkle tree; the tree must be recreated"
+            );
+            return false;
+        };
+        let last_l1_batch_to_keep = L1BatchNumber(last_l1_batch_to_keep);
+        let retained_root_hash = self
+            .tree
+            .reader()
+            .root_hash_for_l1_batch(last_l1_batch_to_keep)
+            .await;
+        if retained_root_hash.is_none() {
+            tracing::error!(
+                "Cannot revert Merkle tree to L1 batch #{last_l1_batch_to_keep} since it's pruned; the tree must be recreated"
+            );
+            return false;
+        }
+
+        tracing::warn!(
+            "Reverting Merkle tree to L1 batch #{last_l1_batch_to_keep} in order to rebuild corrupted L1 batches \
+             #{first_corrupted_l1_batch}..{next_l1_batch_to_seal} from Postgres"
+        );
+        self.tree.revert_logs(last_l1_batch_to_keep);
+        self.tree.save().await;
+        self.prefetched_l1_batch = None;
+        let prev_next_l1_batch = *next_l1_batch_to_seal;
+        *next_l1_batch_to_seal = self.tree.next_l1_batch_number();
+        METRICS
+            .integrity_rebuilt_l1_batches
+            .inc_by((prev_next_l1_batch.0 - next_l1_batch_to_seal.0).into());
+        true
+    }
+
     async fn step(
         &mut self,
         mut storage: StorageProcessor<'_>,
@@ -277,6 +384,10 @@ impl TreeUpdater {
                 tracing::info!("Stop signal received, metadata_calculator is shutting down");
                 break;
             }
+            if self.repair_if_requested(&mut next_l1_batch_to_seal).await {
+                let tree_info = self.tree.reader().info().await;
+                health_updater.update(tree_info.into());
+            }
             let storage = pool.access_storage_tagged("metadata_calculator").await?;
 
             let snapshot = *next_l1_batch_to_seal;
@@ -339,3 +450,153 @@ impl TreeUpdater {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tempfile::TempDir;
+    use zksync_config::configs::database::{MerkleTreeMode, RocksdbProfile};
+
+    use zksync_merkle_tree::domain::ZkSyncTree;
+    use zksync_types::L2ChainId;
+
+    use super::*;
+    use crate::{
+        genesis::{ensure_genesis_state, GenesisParams},
+        metadata_calculator::{helpers::create_db, tests::reset_db_state},
+    };
+
+    async fn create_updater(temp_dir: &TempDir, storage: &mut StorageProcessor<'_>) -> TreeUpdater {
+        let db = create_db(
+            temp_dir.path().to_owned(),
+            0,
+            16 << 20,       // 16 MiB,
+            Duration::ZERO, // writes should never be stalled in tests
+            500,
+            RocksdbProfile::default(),
+        )
+        .await
+        .unwrap();
+        let mut tree = AsyncTree::new(db, MerkleTreeMode::Full);
+        tree.use_dedicated_thread_pool(2);
+        let genesis_logs = L1BatchWithLogs::new(storage, L1BatchNumber(0))
+            .await
+            .unwrap();
+        tree.process_l1_batch(genesis_logs.storage_logs).await;
+        TreeUpdater::new(tree, 2, None)
+    }
+
+    #[tokio::test]
+    async fn prefetching_next_l1_batch() {
+        let pool = ConnectionPool::test_pool().await;
+        ensure_genesis_state(
+            &mut pool.access_storage().await.unwrap(),
+            L2ChainId::from(270),
+            &GenesisParams::mock(),
+        )
+        .await
+        .unwrap();
+        reset_db_state(&pool, 5).await;
+        let mut storage = pool.access_storage().await.unwrap();
+
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let mut updater = create_updater(&temp_dir, &mut storage).await;
+        let next_l1_batch = updater.process_multiple_batches(&mut storage, 1..=2).await;
+        assert_eq!(next_l1_batch, L1BatchNumber(3));
+        let prefetched = updater.prefetched_l1_batch.as_ref().unwrap();
+        assert_eq!(prefetched.header.number, L1BatchNumber(3));
+
+        let next_l1_batch = updater.process_multiple_batches(&mut storage, 3..=4).await;
+        assert_eq!(next_l1_batch, L1BatchNumber(5));
+        let prefetched = updater.prefetched_l1_batch.as_ref().unwrap();
+        assert_eq!(prefetched.header.number, L1BatchNumber(5));
+
+        // Roll back the prefetched L1 batch; its data must not be used.
+        storage
+            .blocks_dal()
+            .delete_l1_batches(L1BatchNumber(4))
+            .await
+            .unwrap();
+        let prefetched = updater
+            .take_prefetched_l1_batch(&mut storage, L1BatchNumber(5))
+            .await;
+        assert!(prefetched.is_none());
+        assert!(updater.prefetched_l1_batch.is_none());
+    }
+
+    #[tokio::test]
+    async fn prefetched_l1_batch_is_not_used_for_other_batch() {
+        let pool = ConnectionPool::test_pool().await;
+        ensure_genesis_state(
+            &mut pool.access_storage().await.unwrap(),
+            L2ChainId::from(270),
+            &GenesisParams::mock(),
+        )
+        .await
+        .unwrap();
+        reset_db_state(&pool, 5).await;
+        let mut storage = pool.access_storage().await.unwrap();
+
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let mut updater = create_updater(&temp_dir, &mut storage).await;
+        updater.process_multiple_batches(&mut storage, 1..=2).await;
+        let prefetched = updater
+            .take_prefetched_l1_batch(&mut storage, L1BatchNumber(4))
+            .await;
+        assert!(prefetched.is_none());
+
+        // The tree must still process the remaining L1 batches correctly.
+        let next_l1_batch = updater.process_multiple_batches(&mut storage, 3..=5).await;
+        assert_eq!(next_l1_batch, L1BatchNumber(6));
+        let mut all_logs = vec![];
+        for number in 0..=5 {
+            let l1_batch = L1BatchWithLogs::new(&mut storage, L1BatchNumber(number))
+                .await
+                .unwrap();
+            all_logs.extend(l1_batch.storage_logs);
+        }
+        let expected_root_hash = ZkSyncTree::process_genesis_batch(&all_logs).root_hash;
+        assert_eq!(updater.tree.root_hash(), expected_root_hash);
+    }
+
+    #[tokio::test]
+    async fn rebuilding_l1_batches_on_repair_request() {
+        let pool = ConnectionPool::test_pool().await;
+        ensure_genesis_state(
+            &mut pool.access_storage().await.unwrap(),
+            L2ChainId::from(270),
+            &GenesisParams::mock(),
+        )
+        .await
+        .unwrap();
+        reset_db_state(&pool, 5).await;
+        let mut storage = pool.access_storage().await.unwrap();
+
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let mut updater = create_updater(&temp_dir, &mut storage).await;
+        let mut next_l1_batch = updater.process_multiple_batches(&mut storage, 1..=5).await;
+        assert_eq!(next_l1_batch, L1BatchNumber(6));
+        let expected_root_hash = updater.tree.root_hash();
+
+        let (repair_sender, repair_receiver) = mpsc::unbounded_channel();
+        updater.set_repair_receiver(repair_receiver);
+        assert!(!updater.repair_if_requested(&mut next_l1_batch).await);
+        // Requests for L1 batches not in the tree and for the genesis batch must be ignored.
+        repair_sender.send(L1BatchNumber(6)).unwrap();
+        assert!(!updater.repair_if_requested(&mut next_l1_batch).await);
+        repair_sender.send(L1BatchNumber(0)).unwrap();
+        assert!(!updater.repair_if_requested(&mut next_l1_batch).await);
+        assert_eq!(next_l1_batch, L1BatchNumber(6));
+
+        repair_sender.send(L1BatchNumber(4)).unwrap();
+        repair_sender.send(L1BatchNumber(3)).unwrap();
+        assert!(updater.repair_if_requested(&mut next_l1_batch).await);
+        assert_eq!(next_l1_batch, L1BatchNumber(3));
+        assert_eq!(updater.tree.next_l1_batch_number(), L1BatchNumber(3));
+
+        let next_l1_batch = updater.process_multiple_batches(&mut storage, 3..=5).await;
+        assert_eq!(next_l1_batch, L1BatchNumber(6));
+        assert_eq!(updater.tree.root_hash(), expected_root_hash);
+    }
+}
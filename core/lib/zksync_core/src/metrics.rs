@@ -161,6 +161,8 @@ pub(crate) enum InteractionType {
 pub(crate) enum CheckerComponent {
     ConsistencyChecker,
     ReorgDetector,
+    DataAvailabilityVerifier,
+    ProofVerifier,
 }
 
 /// General-purpose external node metrics.
@@ -175,10 +177,18 @@ pub(crate) struct ExternalNodeMetrics {
     pub synced: Gauge<u64>,
     /// Current sync lag of the external node.
     pub sync_lag: Gauge<u64>,
-    /// Number of the last L1 batch checked by the re-org detector or consistency checker.
+    /// Number of the last L1 batch checked by the re-org detector, consistency checker, DA or proof verifier.
     pub last_correct_batch: Family<CheckerComponent, Gauge<u64>>,
     /// Number of the last miniblock checked by the re-org detector or consistency checker.
     pub last_correct_miniblock: Family<CheckerComponent, Gauge<u64>>,
+    /// Number of L1 batches with pubdata not matching the data published on the DA layer.
+    pub da_mismatches: Counter,
+    /// Number of L1 batches with locally computed commitments not matching the ones committed on L1.
+    pub l1_batch_inconsistencies: Counter,
+    /// Number of L1 batches with proofs submitted to L1 failing local verification.
+    pub invalid_proofs: Counter,
+    /// Number of L1 batches proven on L1 with mock proofs, which cannot be verified.
+    pub mock_proofs: Counter,
 }
 
 #[vise::register]
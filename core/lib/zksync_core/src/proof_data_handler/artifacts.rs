@@ -0,0 +1,498 @@
+//! Raw proof generation artifacts served to external provers. Artifacts can be large, so they are streamed
+//! in chunks (Merkle paths are read from the object store chunk by chunk) and support HTTP range requests;
+//! checksums allow clients to validate resumed downloads.
+
+use std::{
+    num::NonZeroUsize,
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::StreamBody,
+    extract::Path,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures::stream;
+use hyper::body::Bytes;
+use lru::LruCache;
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, StoredObject};
+use zksync_prover_interface::inputs::PrepareBasicCircuitsJob;
+use zksync_types::L1BatchNumber;
+
+/// Size of chunks in which artifacts are streamed.
+const CHUNK_SIZE: u64 = 1 << 20;
+/// Header with the keccak256 checksum of the returned chunk of an artifact.
+const CHUNK_CHECKSUM_HEADER: &str = "x-chunk-keccak256";
+/// Header with the keccak256 checksum of the entire artifact. The same checksum is used as the entity tag.
+const ARTIFACT_CHECKSUM_HEADER: &str = "x-artifact-keccak256";
+/// Number of cached artifact checksums. Artifacts are immutable, so checksums are cached to not re-read artifacts
+/// on resumed downloads.
+const CHECKSUM_CACHE_CAPACITY: usize = 128;
+
+/// Type of proof generation artifacts for an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ProofGenerationArtifact {
+    /// Serialized Merkle paths for storage accesses in the batch (the basic circuits witness input).
+    MerklePaths,
+    /// Pubdata input of the batch.
+    Pubdata,
+}
+
+#[derive(Debug)]
+pub(crate) enum ArtifactError {
+    NotFound(String),
+    RangeNotSatisfiable { artifact_len: u64 },
+    Internal(anyhow::Error),
+}
+
+impl IntoResponse for ArtifactError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message).into_response(),
+            Self::RangeNotSatisfiable { artifact_len } => {
+                let content_range = format!("bytes */{artifact_len}");
+                let headers = [(header::CONTENT_RANGE, content_range)];
+                (StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response()
+            }
+            Self::Internal(err) => {
+                tracing::error!("Failed loading proof generation artifact: {err:#}");
+                (
+                    StatusCode::BAD_GATEWAY,
+                    "Failed loading proof generation artifact".to_owned(),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+/// Parses a single-range `Range` header value as per RFC 7233 into a byte range of an artifact
+/// with the specified length. Returns `Ok(None)` for unsupported ranges (e.g., multi-ranges),
+/// in which case the entire artifact should be returned.
+fn parse_range(header_value: &str, artifact_len: u64) -> Result<Option<Range<u64>>, ArtifactError> {
+    let Some(spec) = header_value.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+    let unsatisfiable = ArtifactError::RangeNotSatisfiable { artifact_len };
+
+    let range = if start.is_empty() {
+        // Suffix range, e.g. `bytes=-500`
+        let Ok(suffix_len) = end.parse::<u64>() else {
+            return Ok(None);
+        };
+        if suffix_len == 0 || artifact_len == 0 {
+            return Err(unsatisfiable);
+        }
+        artifact_len.saturating_sub(suffix_len)..artifact_len
+    } else {
+        let Ok(start) = start.parse::<u64>() else {
+            return Ok(None);
+        };
+        let end = if end.is_empty() {
+            artifact_len
+        } else {
+            let Ok(end) = end.parse::<u64>() else {
+                return Ok(None);
+            };
+            if end < start {
+                return Ok(None);
+            }
+            end.saturating_add(1).min(artifact_len)
+        };
+        if start >= artifact_len {
+            return Err(unsatisfiable);
+        }
+        start..end
+    };
+    Ok(Some(range))
+}
+
+fn header_value(value: String) -> HeaderValue {
+    HeaderValue::try_from(value).expect("invalid header value")
+}
+
+/// Location of an artifact.
+#[derive(Debug, Clone)]
+enum ArtifactSource {
+    /// Artifact in the object store. It is read in chunks using range requests.
+    Blob {
+        bucket: Bucket,
+        key: String,
+        len: u64,
+    },
+    /// Artifact loaded from Postgres. Pubdata is bounded by the pubdata limit of a batch, so it's loaded entirely.
+    Inline(Bytes),
+}
+
+impl ArtifactSource {
+    fn len(&self) -> u64 {
+        match self {
+            Self::Blob { len, .. } => *len,
+            Self::Inline(data) => data.len() as u64,
+        }
+    }
+
+    async fn read(
+        &self,
+        blob_store: &dyn ObjectStore,
+        range: Range<u64>,
+    ) -> Result<Bytes, ObjectStoreError> {
+        match self {
+            Self::Blob { bucket, key, .. } => {
+                let expected_len = range.end - range.start;
+                let chunk = blob_store.get_range_raw(*bucket, key, range).await?;
+                if chunk.len() as u64 != expected_len {
+                    return Err(ObjectStoreError::Other(
+                        "artifact was modified while being read".into(),
+                    ));
+                }
+                Ok(chunk.into())
+            }
+            Self::Inline(data) => Ok(data.slice(range.start as usize..range.end as usize)),
+        }
+    }
+
+    /// Streams the specified range of the artifact in chunks of [`CHUNK_SIZE`].
+    fn stream(
+        self,
+        blob_store: Arc<dyn ObjectStore>,
+        range: Range<u64>,
+    ) -> impl stream::Stream<Item = Result<Bytes, ObjectStoreError>> + Send {
+        stream::try_unfold(range.start, move |offset| {
+            let source = self.clone();
+            let blob_store = blob_store.clone();
+            async move {
+                if offset >= range.end {
+                    return Ok(None);
+                }
+                let chunk_end = (offset + CHUNK_SIZE).min(range.end);
+                let chunk = source.read(blob_store.as_ref(), offset..chunk_end).await?;
+                Ok(Some((chunk, chunk_end)))
+            }
+        })
+    }
+
+    /// Computes the keccak256 checksum of the specified range of the artifact, reading it in chunks.
+    async fn checksum(
+        &self,
+        blob_store: &dyn ObjectStore,
+        range: Range<u64>,
+    ) -> Result<String, ObjectStoreError> {
+        let mut hasher = Keccak256::new();
+        let mut offset = range.start;
+        while offset < range.end {
+            let chunk_end = (offset + CHUNK_SIZE).min(range.end);
+            hasher.update(self.read(blob_store, offset..chunk_end).await?);
+            offset = chunk_end;
+        }
+        Ok(format!("0x{}", hex::encode(hasher.finalize())))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ArtifactsHandler {
+    blob_store: Arc<dyn ObjectStore>,
+    pool: ConnectionPool,
+    checksums: Arc<Mutex<LruCache<(L1BatchNumber, ProofGenerationArtifact), String>>>,
+}
+
+impl ArtifactsHandler {
+    pub(crate) fn new(blob_store: Arc<dyn ObjectStore>, pool: ConnectionPool) -> Self {
+        let capacity = NonZeroUsize::new(CHECKSUM_CACHE_CAPACITY).unwrap();
+        Self {
+            blob_store,
+            pool,
+            checksums: Arc::new(Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    async fn load_source(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        artifact: ProofGenerationArtifact,
+    ) -> Result<ArtifactSource, ArtifactError> {
+        match artifact {
+            ProofGenerationArtifact::MerklePaths => {
+                let bucket = PrepareBasicCircuitsJob::BUCKET;
+                let key = PrepareBasicCircuitsJob::encode_key(l1_batch_number);
+                let len =
+                    self.blob_store
+                        .get_size_raw(bucket, &key)
+                        .await
+                        .map_err(|err| match err {
+                            ObjectStoreError::KeyNotFound(_) => ArtifactError::NotFound(format!(
+                                "Merkle paths for L1 batch #{l1_batch_number} are not found"
+                            )),
+                            err => ArtifactError::Internal(err.into()),
+                        })?;
+                Ok(ArtifactSource::Blob { bucket, key, len })
+            }
+            ProofGenerationArtifact::Pubdata => {
+                let mut storage = self
+                    .pool
+                    .access_storage()
+                    .await
+                    .map_err(ArtifactError::Internal)?;
+                let header = storage
+                    .blocks_dal()
+                    .get_l1_batch_header(l1_batch_number)
+                    .await
+                    .map_err(|err| ArtifactError::Internal(err.into()))?
+                    .ok_or_else(|| {
+                        ArtifactError::NotFound(format!("L1 batch #{l1_batch_number} is not found"))
+                    })?;
+                let pubdata = header.pubdata_input.ok_or_else(|| {
+                    ArtifactError::NotFound(format!(
+                        "pubdata input for L1 batch #{l1_batch_number} is not found"
+                    ))
+                })?;
+                Ok(ArtifactSource::Inline(pubdata.into()))
+            }
+        }
+    }
+
+    async fn artifact_checksum(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        artifact: ProofGenerationArtifact,
+        source: &ArtifactSource,
+    ) -> Result<String, ArtifactError> {
+        let cache_key = (l1_batch_number, artifact);
+        if let Some(checksum) = self.checksums.lock().unwrap().get(&cache_key) {
+            return Ok(checksum.clone());
+        }
+        let checksum = source
+            .checksum(self.blob_store.as_ref(), 0..source.len())
+            .await
+            .map_err(|err| ArtifactError::Internal(err.into()))?;
+        self.checksums
+            .lock()
+            .unwrap()
+            .put(cache_key, checksum.clone());
+        Ok(checksum)
+    }
+
+    /// Returns an artifact or its part if a `Range` header is specified. If an `If-Range` header is specified
+    /// and doesn't match the artifact checksum, the entire artifact is returned.
+    pub(crate) async fn get_artifact(
+        &self,
+        Path((l1_batch_number, artifact)): Path<(u32, ProofGenerationArtifact)>,
+        request_headers: HeaderMap,
+    ) -> Result<Response, ArtifactError> {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        tracing::debug!("Received request for {artifact:?} of L1 batch #{l1_batch_number}");
+        let source = self.load_source(l1_batch_number, artifact).await?;
+        let artifact_len = source.len();
+        let artifact_checksum = self
+            .artifact_checksum(l1_batch_number, artifact, &source)
+            .await?;
+        let etag = header_value(format!("\"{artifact_checksum}\""));
+
+        let if_range_matches = request_headers
+            .get(header::IF_RANGE)
+            .map_or(true, |value| *value == etag);
+        let range = match request_headers.get(header::RANGE) {
+            Some(range) if if_range_matches => {
+                let range = range.to_str().unwrap_or_default();
+                parse_range(range, artifact_len)?
+            }
+            _ => None,
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        headers.insert(header::ETAG, etag);
+        headers.insert(
+            ARTIFACT_CHECKSUM_HEADER,
+            header_value(artifact_checksum.clone()),
+        );
+
+        let (status, range, chunk_checksum) = if let Some(range) = range {
+            let content_range = format!("bytes {}-{}/{artifact_len}", range.start, range.end - 1);
+            headers.insert(header::CONTENT_RANGE, header_value(content_range));
+            let chunk_checksum = source
+                .checksum(self.blob_store.as_ref(), range.clone())
+                .await
+                .map_err(|err| ArtifactError::Internal(err.into()))?;
+            (StatusCode::PARTIAL_CONTENT, range, chunk_checksum)
+        } else {
+            (StatusCode::OK, 0..artifact_len, artifact_checksum)
+        };
+        headers.insert(CHUNK_CHECKSUM_HEADER, header_value(chunk_checksum));
+        headers.insert(
+            header::CONTENT_LENGTH,
+            HeaderValue::from(range.end - range.start),
+        );
+
+        let body = StreamBody::new(source.stream(self.blob_store.clone(), range));
+        Ok((status, headers, body).into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::web3::signing::keccak256;
+
+    use super::*;
+
+    fn checksum(data: &[u8]) -> String {
+        format!("0x{}", hex::encode(keccak256(data)))
+    }
+
+    /// Store that only allows reading values in chunks.
+    #[derive(Debug)]
+    struct ChunkedStore(Arc<dyn ObjectStore>);
+
+    #[async_trait]
+    impl ObjectStore for ChunkedStore {
+        async fn get_raw(&self, _bucket: Bucket, _key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+            panic!("artifacts must not be loaded entirely");
+        }
+
+        async fn get_size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+            let value = self.0.get_raw(bucket, key).await?;
+            Ok(value.len() as u64)
+        }
+
+        async fn get_range_raw(
+            &self,
+            bucket: Bucket,
+            key: &str,
+            range: Range<u64>,
+        ) -> Result<Vec<u8>, ObjectStoreError> {
+            assert!(range.end - range.start <= CHUNK_SIZE, "{range:?}");
+            self.0.get_range_raw(bucket, key, range).await
+        }
+
+        async fn put_raw(
+            &self,
+            bucket: Bucket,
+            key: &str,
+            value: Vec<u8>,
+        ) -> Result<(), ObjectStoreError> {
+            self.0.put_raw(bucket, key, value).await
+        }
+
+        async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+            self.0.remove_raw(bucket, key).await
+        }
+
+        fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+            self.0.storage_prefix_raw(bucket)
+        }
+    }
+
+    #[test]
+    fn parsing_ranges() {
+        let parse = |value| parse_range(value, 100).unwrap();
+        assert_eq!(parse("bytes=0-9"), Some(0..10));
+        assert_eq!(parse("bytes=90-"), Some(90..100));
+        assert_eq!(parse("bytes=90-1000"), Some(90..100));
+        assert_eq!(parse("bytes=-10"), Some(90..100));
+        assert_eq!(parse("bytes=-1000"), Some(0..100));
+        assert_eq!(parse("bytes=0-9, 20-29"), None);
+        assert_eq!(parse("bytes=9-0"), None);
+        assert_eq!(parse("items=0-9"), None);
+        assert_eq!(parse("bytes=a-b"), None);
+
+        assert!(matches!(
+            parse_range("bytes=100-", 100),
+            Err(ArtifactError::RangeNotSatisfiable { artifact_len: 100 })
+        ));
+        assert!(matches!(
+            parse_range("bytes=-0", 100),
+            Err(ArtifactError::RangeNotSatisfiable { .. })
+        ));
+    }
+
+    async fn response_body(response: Response) -> Vec<u8> {
+        hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap()
+            .to_vec()
+    }
+
+    #[tokio::test]
+    async fn getting_artifacts() {
+        let pool = ConnectionPool::test_pool().await;
+        let blob_store: Arc<dyn ObjectStore> = Arc::new(ChunkedStore(
+            ObjectStoreFactory::mock().create_store().await,
+        ));
+        let data: Vec<u8> = (0..=255)
+            .cycle()
+            .take(CHUNK_SIZE as usize * 2 + 10)
+            .collect();
+        let key = PrepareBasicCircuitsJob::encode_key(L1BatchNumber(1));
+        blob_store
+            .put_raw(PrepareBasicCircuitsJob::BUCKET, &key, data.clone())
+            .await
+            .unwrap();
+        let handler = ArtifactsHandler::new(blob_store, pool);
+        let path = || Path((1, ProofGenerationArtifact::MerklePaths));
+
+        let response = handler
+            .get_artifact(path(), HeaderMap::new())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].clone();
+        assert_eq!(
+            response.headers()[ARTIFACT_CHECKSUM_HEADER],
+            checksum(&data)
+        );
+        assert_eq!(response_body(response).await, data);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, HeaderValue::from_static("bytes=1048570-"));
+        headers.insert(header::IF_RANGE, etag);
+        let response = handler.get_artifact(path(), headers.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        let expected_range = format!("bytes 1048570-{}/{}", data.len() - 1, data.len());
+        assert_eq!(response.headers()[header::CONTENT_RANGE], expected_range);
+        let chunk = &data[1_048_570..];
+        assert_eq!(response.headers()[CHUNK_CHECKSUM_HEADER], checksum(chunk));
+        assert_eq!(response_body(response).await, chunk);
+
+        // Mismatched `If-Range` leads to returning the entire artifact.
+        headers.insert(header::IF_RANGE, HeaderValue::from_static("\"0x01\""));
+        let response = handler.get_artifact(path(), headers).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_body(response).await, data);
+
+        let err = handler
+            .get_artifact(
+                Path((2, ProofGenerationArtifact::MerklePaths)),
+                HeaderMap::new(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ArtifactError::NotFound(_)), "{err:?}");
+        let err = handler
+            .get_artifact(
+                Path((1, ProofGenerationArtifact::Pubdata)),
+                HeaderMap::new(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ArtifactError::NotFound(_)), "{err:?}");
+    }
+}
@@ -0,0 +1,217 @@
+//! Mock prover used in deployments without provers, e.g. devnet and staging L3s.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::ProofDataHandlerConfig;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::ObjectStore;
+use zksync_prover_interface::outputs::L1BatchProofForL1;
+use zksync_system_constants::STATE_DIFF_HASH_KEY;
+use zksync_types::{
+    commitment::{serialize_commitments, L1BatchWithMetadata},
+    web3::signing::keccak256,
+    L1BatchNumber, H256,
+};
+use zksync_utils::u256_to_h256;
+
+/// Generates mock proofs for L1 batches ready to be proven instead of provers. Mock proofs are stored
+/// in the same way as real ones, so the rest of the L1 batch lifecycle is unaffected; they should be sent
+/// to the settlement layer with the `OnlyMockProofs` proof sending mode.
+#[derive(Debug)]
+pub(crate) struct MockProver {
+    pool: ConnectionPool,
+    blob_store: Arc<dyn ObjectStore>,
+    proof_generation_timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl MockProver {
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub(crate) fn new(
+        config: &ProofDataHandlerConfig,
+        pool: ConnectionPool,
+        blob_store: Arc<dyn ObjectStore>,
+    ) -> Self {
+        Self {
+            pool,
+            blob_store,
+            proof_generation_timeout: config.proof_generation_timeout(),
+            poll_interval: Self::POLL_INTERVAL,
+        }
+    }
+
+    /// Builds a mock proof with aggregation result coordinates matching L1 batch commitments,
+    /// so that it passes the same checks as real proofs.
+    fn mock_proof(l1_batch: &L1BatchWithMetadata) -> L1BatchProofForL1 {
+        let system_logs = serialize_commitments(&l1_batch.header.system_logs);
+        let system_logs_hash = H256(keccak256(&system_logs));
+        let state_diff_hash = l1_batch
+            .header
+            .system_logs
+            .iter()
+            .find(|log| log.0.key == u256_to_h256(STATE_DIFF_HASH_KEY.into()))
+            .map(|log| log.0.value)
+            .unwrap_or_default();
+        let bootloader_heap_initial_content = l1_batch
+            .metadata
+            .bootloader_initial_content_commitment
+            .unwrap_or_default();
+        let events_queue_state = l1_batch
+            .metadata
+            .events_queue_commitment
+            .unwrap_or_default();
+
+        L1BatchProofForL1::mock([
+            system_logs_hash.0,
+            state_diff_hash.0,
+            bootloader_heap_initial_content.0,
+            events_queue_state.0,
+        ])
+    }
+
+    /// Generates a mock proof for the next L1 batch ready to be proven, if any.
+    async fn prove_next_l1_batch(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = self.pool.access_storage().await?;
+        let Some(l1_batch_number) = storage
+            .proof_generation_dal()
+            .get_next_block_to_be_proven(self.proof_generation_timeout)
+            .await
+        else {
+            return Ok(None);
+        };
+
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await
+            .with_context(|| format!("get_l1_batch_metadata({l1_batch_number})"))?
+            .with_context(|| {
+                format!("L1 batch #{l1_batch_number} ready to be proven has no metadata")
+            })?;
+        let proof = Self::mock_proof(&l1_batch);
+        let blob_url = self
+            .blob_store
+            .put(l1_batch_number, &proof)
+            .await
+            .with_context(|| format!("failed saving mock proof for L1 batch #{l1_batch_number}"))?;
+        storage
+            .proof_generation_dal()
+            .save_proof_artifacts_metadata(l1_batch_number, &blob_url)
+            .await
+            .with_context(|| format!("save_proof_artifacts_metadata({l1_batch_number})"))?;
+        tracing::info!("Generated mock proof for L1 batch #{l1_batch_number}");
+        Ok(Some(l1_batch_number))
+    }
+
+    pub(crate) async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::warn!(
+            "Running mock prover; generated proofs are only accepted by the testnet verifier"
+        );
+        while !*stop_receiver.borrow_and_update() {
+            if self.prove_next_l1_batch().await?.is_some() {
+                continue;
+            }
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, mock prover is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::proof_data_handler::{
+        ProofGenerationMode, ProtocolVersionLoadingMode,
+    };
+    use zksync_dal::StorageProcessor;
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion};
+
+    use super::*;
+    use crate::utils::testonly::{
+        create_l1_batch, create_l1_batch_metadata, l1_batch_metadata_to_commitment_artifacts,
+    };
+
+    async fn insert_l1_batch_ready_to_be_proven(
+        storage: &mut StorageProcessor<'_>,
+        number: u32,
+    ) -> L1BatchHeader {
+        let header = create_l1_batch(number);
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        let metadata = create_l1_batch_metadata(number);
+        storage
+            .blocks_dal()
+            .save_l1_batch_tree_data(header.number, &metadata.tree_data())
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .save_l1_batch_commitment_artifacts(
+                header.number,
+                &l1_batch_metadata_to_commitment_artifacts(&metadata),
+            )
+            .await
+            .unwrap();
+        storage
+            .proof_generation_dal()
+            .insert_proof_generation_details(header.number, "proof_gen_data")
+            .await;
+        header
+    }
+
+    #[tokio::test]
+    async fn generating_mock_proofs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        insert_l1_batch_ready_to_be_proven(&mut storage, 1).await;
+
+        let blob_store = ObjectStoreFactory::mock().create_store().await;
+        let config = ProofDataHandlerConfig {
+            http_port: 3320,
+            proof_generation_timeout_in_secs: 60,
+            protocol_version_loading_mode: ProtocolVersionLoadingMode::FromEnvVar,
+            fri_protocol_version_id: 2,
+            proof_generation_mode: ProofGenerationMode::Mock,
+        };
+        let prover = MockProver::new(&config, pool.clone(), blob_store.clone());
+
+        let proven_l1_batch = prover.prove_next_l1_batch().await.unwrap();
+        assert_eq!(proven_l1_batch, Some(L1BatchNumber(1)));
+        let proof: L1BatchProofForL1 = blob_store.get(L1BatchNumber(1)).await.unwrap();
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            proof.aggregation_result_coords,
+            MockProver::mock_proof(&l1_batch).aggregation_result_coords
+        );
+        assert_eq!(
+            proof.aggregation_result_coords[2],
+            l1_batch
+                .metadata
+                .bootloader_initial_content_commitment
+                .unwrap_or_default()
+                .0
+        );
+
+        // There are no more L1 batches to prove.
+        let proven_l1_batch = prover.prove_next_l1_batch().await.unwrap();
+        assert_eq!(proven_l1_batch, None);
+    }
+}
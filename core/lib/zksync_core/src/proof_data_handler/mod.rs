@@ -1,7 +1,12 @@
 use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::Context as _;
-use axum::{extract::Path, routing::post, Json, Router};
+use axum::{
+    extract::Path,
+    http::HeaderMap,
+    routing::{get, post},
+    Json, Router,
+};
 use tokio::sync::watch;
 use zksync_config::{
     configs::{proof_data_handler::ProtocolVersionLoadingMode, ProofDataHandlerConfig},
@@ -15,8 +20,12 @@ use zksync_types::{
     H256,
 };
 
+use self::artifacts::{ArtifactsHandler, ProofGenerationArtifact};
+pub(crate) use self::mock_prover::MockProver;
 use crate::proof_data_handler::request_processor::RequestProcessor;
 
+mod artifacts;
+mod mock_prover;
 mod request_processor;
 
 fn fri_l1_verifier_config(contracts_config: &ContractsConfig) -> L1VerifierConfig {
@@ -44,6 +53,7 @@ pub(crate) async fn run_server(
         ProtocolVersionLoadingMode::FromDb => None,
         ProtocolVersionLoadingMode::FromEnvVar => Some(fri_l1_verifier_config(&contracts_config)),
     };
+    let artifacts_handler = ArtifactsHandler::new(blob_store.clone(), pool.clone());
     let get_proof_gen_processor =
         RequestProcessor::new(blob_store, pool, config, l1_verifier_config);
     let submit_proof_processor = get_proof_gen_processor.clone();
@@ -69,6 +79,14 @@ pub(crate) async fn run_server(
                         .await
                 },
             ),
+        )
+        .route(
+            "/proof_generation_data/:l1_batch_number/:artifact",
+            get(
+                move |path: Path<(u32, ProofGenerationArtifact)>, headers: HeaderMap| async move {
+                    artifacts_handler.get_artifact(path, headers).await
+                },
+            ),
         );
 
     axum::Server::bind(&bind_address)
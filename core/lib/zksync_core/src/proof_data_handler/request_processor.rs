@@ -7,7 +7,8 @@ use axum::{
     Json,
 };
 use zksync_config::configs::{
-    proof_data_handler::ProtocolVersionLoadingMode, ProofDataHandlerConfig,
+    proof_data_handler::{ProofGenerationMode, ProtocolVersionLoadingMode},
+    ProofDataHandlerConfig,
 };
 use zksync_dal::{ConnectionPool, SqlxError};
 use zksync_object_store::{ObjectStore, ObjectStoreError};
@@ -83,6 +84,10 @@ impl RequestProcessor {
         request: Json<ProofGenerationDataRequest>,
     ) -> Result<Json<ProofGenerationDataResponse>, RequestProcessorError> {
         tracing::info!("Received request for proof generation data: {:?}", request);
+        if self.config.proof_generation_mode == ProofGenerationMode::Mock {
+            // Proofs are generated by the mock prover; no jobs are handed out to provers.
+            return Ok(Json(ProofGenerationDataResponse::Success(None)));
+        }
 
         let l1_batch_number_result = self
             .pool
@@ -0,0 +1,574 @@
+//! Verification of L1 batch proofs for external nodes. For each L1 batch proven on L1, the verifier
+//! retrieves the proof from the `proveBatches` transaction, checks that it proves the locally known batch,
+//! and verifies it using the verifier contract referenced by the diamond proxy and the locally known
+//! verification key hash.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_eth_client::{clients::QueryClient, Error as L1ClientError, EthInterface};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
+use zksync_types::{
+    api::idexo::ProofVerificationStatus,
+    commitment::L1BatchWithMetadata,
+    ethabi::{self, ParamType, Token},
+    web3::{
+        self,
+        contract::tokens::Tokenizable as _,
+        signing::keccak256,
+        types::{BlockId, BlockNumber, CallRequest},
+    },
+    Address, L1BatchNumber, H256, U256,
+};
+
+use crate::{
+    metrics::{CheckerComponent, EN_METRICS},
+    utils::wait_for_l1_batch_with_metadata,
+};
+
+#[cfg(test)]
+mod tests;
+
+const COMPONENT: &str = "proof_verifier";
+/// Public inputs of the proof are truncated to fit into the scalar field used by the verifier.
+const PUBLIC_INPUT_SHIFT: usize = 32;
+/// Location of the commitment in `StoredBatchInfo`.
+const COMMITMENT_INDEX: usize = 7;
+
+#[derive(Debug, thiserror::Error)]
+enum VerifyError {
+    #[error("Web3 error communicating with L1")]
+    Web3(#[from] L1ClientError),
+    #[error("Internal error")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<zksync_dal::SqlxError> for VerifyError {
+    fn from(err: zksync_dal::SqlxError) -> Self {
+        Self::Internal(err.into())
+    }
+}
+
+/// Outcome of the proof verification for a single L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+enum Verification {
+    /// Proof is valid for the local batch commitment.
+    Verified,
+    /// Batch is proven on L1 without a proof; this is only accepted by the testnet verifier.
+    MockProof,
+    /// Proof doesn't prove the local batch, or is rejected by the verifier.
+    Invalid(String),
+}
+
+impl Verification {
+    fn status(&self) -> (ProofVerificationStatus, Option<&str>) {
+        match self {
+            Self::Verified => (ProofVerificationStatus::Verified, None),
+            Self::MockProof => (ProofVerificationStatus::MockProof, None),
+            Self::Invalid(reason) => (ProofVerificationStatus::Invalid, Some(reason)),
+        }
+    }
+}
+
+/// Computes the public input of the proof for a batch from the commitments of this and the previous batch,
+/// in the same way as the executor facet of the diamond proxy.
+fn proof_public_input(prev_commitment: H256, commitment: H256) -> U256 {
+    let hash = keccak256(&[prev_commitment.as_bytes(), commitment.as_bytes()].concat());
+    U256::from_big_endian(&hash) >> PUBLIC_INPUT_SHIFT
+}
+
+fn stored_batch_number(batch: &Token) -> anyhow::Result<U256> {
+    let Token::Tuple(fields) = batch else {
+        anyhow::bail!("Unexpected signature for L1 prove function");
+    };
+    fields
+        .first()
+        .cloned()
+        .and_then(Token::into_uint)
+        .context("Unexpected signature for L1 prove function")
+}
+
+fn stored_batch_commitment(batch: &Token) -> anyhow::Result<H256> {
+    let Token::Tuple(fields) = batch else {
+        anyhow::bail!("Unexpected signature for L1 prove function");
+    };
+    let commitment = fields
+        .get(COMMITMENT_INDEX)
+        .cloned()
+        .and_then(Token::into_fixed_bytes)
+        .filter(|bytes| bytes.len() == 32)
+        .context("Unexpected signature for L1 prove function")?;
+    Ok(H256::from_slice(&commitment))
+}
+
+fn into_uint_array(token: Token) -> anyhow::Result<Vec<U256>> {
+    token
+        .into_array()
+        .context("Unexpected signature for L1 prove function")?
+        .into_iter()
+        .map(|token| {
+            token
+                .into_uint()
+                .context("Unexpected signature for L1 prove function")
+        })
+        .collect()
+}
+
+/// Proof submitted to L1 for an L1 batch, extracted from the `proveBatches` calldata.
+#[derive(Debug, Clone, PartialEq)]
+struct SubmittedProof {
+    /// `StoredBatchInfo` of the batch preceding the proven batch.
+    prev_batch: Token,
+    /// `StoredBatchInfo` of the proven batch.
+    batch: Token,
+    /// Public inputs for all batches proven by the transaction.
+    public_inputs: Vec<U256>,
+    serialized_proof: Vec<U256>,
+    recursive_aggregation_input: Vec<U256>,
+}
+
+impl SubmittedProof {
+    fn extract(
+        prove_tx_input_data: &[u8],
+        prove_function: &ethabi::Function,
+        batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            prove_tx_input_data.get(..4) == Some(&prove_function.short_signature()[..]),
+            "Prove transaction calls an unexpected function"
+        );
+        let tokens = prove_function
+            .decode_input(&prove_tx_input_data[4..])
+            .context("Failed decoding calldata for L1 prove function")?;
+        let [prev_batch, batches, proof_input] = <[Token; 3]>::try_from(tokens)
+            .map_err(|_| anyhow::anyhow!("Unexpected signature for L1 prove function"))?;
+        let batches = batches
+            .into_array()
+            .context("Unexpected signature for L1 prove function")?;
+        let Token::Tuple(proof_input) = proof_input else {
+            anyhow::bail!("Unexpected signature for L1 prove function");
+        };
+        let [recursive_aggregation_input, serialized_proof] =
+            <[Token; 2]>::try_from(proof_input)
+                .map_err(|_| anyhow::anyhow!("Unexpected signature for L1 prove function"))?;
+
+        let mut prev_commitment = stored_batch_commitment(&prev_batch)?;
+        let mut public_inputs = Vec::with_capacity(batches.len());
+        let mut position = None;
+        for (i, batch) in batches.iter().enumerate() {
+            let commitment = stored_batch_commitment(batch)?;
+            public_inputs.push(proof_public_input(prev_commitment, commitment));
+            prev_commitment = commitment;
+            if stored_batch_number(batch)? == U256::from(batch_number.0) {
+                position = Some(i);
+            }
+        }
+        let position = position
+            .with_context(|| format!("Prove transaction doesn't prove L1 batch #{batch_number}"))?;
+        let prev_batch = if position == 0 {
+            prev_batch
+        } else {
+            batches[position - 1].clone()
+        };
+
+        Ok(Self {
+            prev_batch,
+            batch: batches[position].clone(),
+            public_inputs,
+            serialized_proof: into_uint_array(serialized_proof)?,
+            recursive_aggregation_input: into_uint_array(recursive_aggregation_input)?,
+        })
+    }
+}
+
+/// L1 proof data loaded from Postgres.
+#[derive(Debug)]
+struct LocalL1BatchProofData {
+    l1_batch: L1BatchWithMetadata,
+    prev_l1_batch: L1BatchWithMetadata,
+    prove_tx_hash: H256,
+}
+
+impl LocalL1BatchProofData {
+    /// Returns `Ok(None)` if Postgres doesn't contain all data necessary to verify the proof
+    /// for the specified batch.
+    async fn new(
+        storage: &mut StorageProcessor<'_>,
+        batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(storage_l1_batch) = storage
+            .blocks_dal()
+            .get_storage_l1_batch(batch_number)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(prove_tx_id) = storage_l1_batch.eth_prove_tx_id else {
+            return Ok(None);
+        };
+        let prove_tx_hash = storage
+            .eth_sender_dal()
+            .get_confirmed_tx_hash_by_eth_tx_id(prove_tx_id as u32)
+            .await?
+            .with_context(|| {
+                format!("Prove tx hash not found in the database for tx id {prove_tx_id}")
+            })?;
+
+        let Some(l1_batch) = storage
+            .blocks_dal()
+            .get_l1_batch_with_metadata(storage_l1_batch)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(prev_l1_batch) = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(batch_number - 1)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            l1_batch,
+            prev_l1_batch,
+            prove_tx_hash,
+        }))
+    }
+}
+
+/// Health details reported by [`ProofVerifier`].
+#[derive(Debug, Default, Serialize)]
+struct ProofVerifierDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_checked_batch: Option<L1BatchNumber>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    invalid_batches: Vec<L1BatchNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_mock_proof_batch: Option<L1BatchNumber>,
+}
+
+impl ProofVerifierDetails {
+    fn health(&self) -> Health {
+        let status = if !self.invalid_batches.is_empty() {
+            HealthStatus::NotReady
+        } else if self.last_mock_proof_batch.is_some() {
+            HealthStatus::Affected
+        } else {
+            HealthStatus::Ready
+        };
+        Health::from(status).with_details(self)
+    }
+}
+
+/// External node component verifying proofs of L1 batches submitted to L1. Verification results are persisted
+/// in Postgres and exposed via the `idexo_getL1BatchProofStatus` API method. Invalid proofs are logged as errors,
+/// counted in metrics and make the component unhealthy ([`HealthStatus::NotReady`]). Batches proven with mock proofs
+/// are not verified at all, so they make the component health [`HealthStatus::Affected`].
+#[derive(Debug)]
+pub struct ProofVerifier {
+    /// ABI of the zkSync contract
+    contract: ethabi::Contract,
+    diamond_proxy_addr: Address,
+    /// How many past batches to check when starting
+    max_batches_to_recheck: u32,
+    sleep_interval: Duration,
+    l1_client: Box<dyn EthInterface>,
+    pool: ConnectionPool,
+    health_updater: HealthUpdater,
+    health_check: ReactiveHealthCheck,
+    details: ProofVerifierDetails,
+}
+
+impl ProofVerifier {
+    const DEFAULT_SLEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(
+        web3_url: &str,
+        diamond_proxy_addr: Address,
+        max_batches_to_recheck: u32,
+        pool: ConnectionPool,
+    ) -> Self {
+        let web3 = QueryClient::new(web3_url).unwrap();
+        let (health_check, health_updater) = ReactiveHealthCheck::new("proof_verifier");
+        Self {
+            contract: zksync_contracts::zksync_contract(),
+            diamond_proxy_addr,
+            max_batches_to_recheck,
+            sleep_interval: Self::DEFAULT_SLEEP_INTERVAL,
+            l1_client: Box::new(web3),
+            pool,
+            health_updater,
+            health_check,
+            details: ProofVerifierDetails::default(),
+        }
+    }
+
+    /// Returns health check associated with this verifier.
+    pub fn health_check(&self) -> &ReactiveHealthCheck {
+        &self.health_check
+    }
+
+    /// Calls the contract at the specified L1 block, so that the contract state is the one used to verify the proof.
+    async fn call(
+        &self,
+        contract: Address,
+        data: Vec<u8>,
+        block: BlockId,
+    ) -> Result<Vec<u8>, L1ClientError> {
+        let request = CallRequest {
+            to: Some(contract),
+            data: Some(data.into()),
+            ..CallRequest::default()
+        };
+        Ok(self
+            .l1_client
+            .call(request, Some(block), COMPONENT)
+            .await?
+            .0)
+    }
+
+    async fn verifier_address(&self, block: BlockId) -> Result<Address, VerifyError> {
+        let data = ethabi::short_signature("getVerifier", &[]).to_vec();
+        let output = self.call(self.diamond_proxy_addr, data, block).await?;
+        let tokens = ethabi::decode(&[ParamType::Address], &output)
+            .context("Failed decoding `getVerifier` output")?;
+        let address = tokens.into_iter().next().and_then(Token::into_address);
+        Ok(address.context("Unexpected `getVerifier` output")?)
+    }
+
+    async fn verification_key_hash(
+        &self,
+        verifier: Address,
+        block: BlockId,
+    ) -> Result<H256, VerifyError> {
+        let data = ethabi::short_signature("verificationKeyHash", &[]).to_vec();
+        let output = self.call(verifier, data, block).await?;
+        let tokens = ethabi::decode(&[ParamType::FixedBytes(32)], &output)
+            .context("Failed decoding `verificationKeyHash` output")?;
+        let hash = tokens.into_iter().next().and_then(Token::into_fixed_bytes);
+        let hash = hash.context("Unexpected `verificationKeyHash` output")?;
+        Ok(H256::from_slice(&hash))
+    }
+
+    async fn verify_batch(
+        &self,
+        batch_number: L1BatchNumber,
+        local: &LocalL1BatchProofData,
+    ) -> Result<Verification, VerifyError> {
+        let prove_tx_hash = local.prove_tx_hash;
+        let prove_tx = self
+            .l1_client
+            .get_tx(prove_tx_hash, COMPONENT)
+            .await?
+            .with_context(|| format!("Prove tx {prove_tx_hash:?} not found on L1"))?;
+        let prove_function = self
+            .contract
+            .function("proveBatches")
+            .context("L1 contract does not have `proveBatches` function")?;
+        let proof = match SubmittedProof::extract(&prove_tx.input.0, prove_function, batch_number) {
+            Ok(proof) => proof,
+            Err(err) => return Ok(Verification::Invalid(format!("{err:#}"))),
+        };
+
+        if proof.batch != StoredBatchInfo(&local.l1_batch).into_token() {
+            return Ok(Verification::Invalid(
+                "L1 batch proven on L1 differs from the local one".to_owned(),
+            ));
+        }
+        if proof.prev_batch != StoredBatchInfo(&local.prev_l1_batch).into_token() {
+            return Ok(Verification::Invalid(
+                "L1 batch preceding the proven one differs from the local one".to_owned(),
+            ));
+        }
+        if proof.serialized_proof.is_empty() {
+            return Ok(Verification::MockProof);
+        }
+
+        // The verifier referenced by the diamond proxy may change after the batch is proven (e.g., during an upgrade),
+        // so the verifier and its key are taken as of the block the prove tx was executed in.
+        let prove_tx_status = self
+            .l1_client
+            .get_tx_status(prove_tx_hash, COMPONENT)
+            .await?
+            .with_context(|| format!("Prove tx {prove_tx_hash:?} is not executed on L1"))?;
+        let block_number = prove_tx_status
+            .receipt
+            .block_number
+            .with_context(|| format!("Prove tx {prove_tx_hash:?} has no block number"))?;
+        let block = BlockId::Number(BlockNumber::Number(block_number));
+        let verifier = self.verifier_address(block).await?;
+        let protocol_version = local.l1_batch.header.protocol_version;
+        let local_verifier_config = if let Some(version) = protocol_version {
+            let mut storage = self.pool.access_storage_tagged(COMPONENT).await?;
+            storage
+                .protocol_versions_dal()
+                .l1_verifier_config_for_version(version)
+                .await
+        } else {
+            None
+        };
+        if let Some(config) = local_verifier_config {
+            let vk_hash = self.verification_key_hash(verifier, block).await?;
+            let expected_vk_hash = config.recursion_scheduler_level_vk_hash;
+            if vk_hash != expected_vk_hash {
+                return Ok(Verification::Invalid(format!(
+                    "verification key hash of verifier {verifier:?} ({vk_hash:?}) differs from the local one \
+                     ({expected_vk_hash:?})"
+                )));
+            }
+        } else {
+            tracing::debug!(
+                "No local verifier config for protocol version {protocol_version:?}; \
+                 skipping verification key check for L1 batch #{batch_number}"
+            );
+        }
+
+        let uint_array =
+            |values: &[U256]| Token::Array(values.iter().copied().map(Token::Uint).collect());
+        let array_type = ParamType::Array(Box::new(ParamType::Uint(256)));
+        let mut data = ethabi::short_signature(
+            "verify",
+            &[array_type.clone(), array_type.clone(), array_type],
+        )
+        .to_vec();
+        data.extend(ethabi::encode(&[
+            uint_array(&proof.public_inputs),
+            uint_array(&proof.serialized_proof),
+            uint_array(&proof.recursive_aggregation_input),
+        ]));
+        let output = match self.call(verifier, data, block).await {
+            Ok(output) => output,
+            Err(L1ClientError::EthereumGateway(web3::Error::Rpc(err))) => {
+                return Ok(Verification::Invalid(format!(
+                    "verifier {verifier:?} rejected proof: {err}"
+                )));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let is_valid = ethabi::decode(&[ParamType::Bool], &output)
+            .ok()
+            .and_then(|tokens| tokens.into_iter().next())
+            .and_then(Token::into_bool);
+        Ok(match is_valid {
+            Some(true) => Verification::Verified,
+            Some(false) => Verification::Invalid(format!("verifier {verifier:?} rejected proof")),
+            None => Verification::Invalid(format!(
+                "verifier {verifier:?} returned unexpected output 0x{}",
+                hex::encode(output)
+            )),
+        })
+    }
+
+    async fn report(
+        &mut self,
+        batch_number: L1BatchNumber,
+        prove_tx_hash: H256,
+        verification: Verification,
+    ) -> anyhow::Result<()> {
+        let (status, details) = verification.status();
+        let mut storage = self.pool.access_storage_tagged(COMPONENT).await?;
+        storage
+            .proof_verifications_dal()
+            .save_verification(batch_number, prove_tx_hash, status, details)
+            .await?;
+        drop(storage);
+
+        match &verification {
+            Verification::Verified => {
+                tracing::info!("Proof of L1 batch #{batch_number} is valid");
+                EN_METRICS.last_correct_batch[&CheckerComponent::ProofVerifier]
+                    .set(batch_number.0.into());
+            }
+            Verification::MockProof => {
+                tracing::warn!("L1 batch #{batch_number} is proven on L1 with a mock proof");
+                EN_METRICS.mock_proofs.inc();
+                self.details.last_mock_proof_batch = Some(batch_number);
+            }
+            Verification::Invalid(reason) => {
+                tracing::error!("Proof of L1 batch #{batch_number} is invalid: {reason}");
+                EN_METRICS.invalid_proofs.inc();
+                self.details.invalid_batches.push(batch_number);
+            }
+        }
+        self.details.last_checked_batch = Some(batch_number);
+        self.health_updater.update(self.details.health());
+        Ok(())
+    }
+
+    async fn first_batch_to_check(
+        &self,
+        earliest_l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let mut storage = self.pool.access_storage_tagged(COMPONENT).await?;
+        let last_verified_batch = storage
+            .proof_verifications_dal()
+            .get_last_verified_l1_batch()
+            .await?;
+        let first_batch_to_check = if let Some(last_verified_batch) = last_verified_batch {
+            last_verified_batch + 1
+        } else {
+            let last_proven_batch = storage
+                .blocks_dal()
+                .get_number_of_last_l1_batch_proven_on_eth()
+                .await?
+                .unwrap_or(earliest_l1_batch_number);
+            last_proven_batch
+                .0
+                .saturating_sub(self.max_batches_to_recheck)
+                .into()
+        };
+        // The genesis batch is not proven on L1, and the batch preceding the checked one must be present.
+        Ok(first_batch_to_check
+            .max(earliest_l1_batch_number + 1)
+            .max(L1BatchNumber(1)))
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater.update(self.details.health());
+
+        let earliest_l1_batch_number =
+            wait_for_l1_batch_with_metadata(&self.pool, self.sleep_interval, &mut stop_receiver)
+                .await?;
+        let Some(earliest_l1_batch_number) = earliest_l1_batch_number else {
+            return Ok(()); // Stop signal received
+        };
+        let mut batch_number = self.first_batch_to_check(earliest_l1_batch_number).await?;
+        tracing::info!("Starting proof verification from L1 batch #{batch_number}");
+
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, proof verifier is shutting down");
+                break;
+            }
+
+            let mut storage = self.pool.access_storage_tagged(COMPONENT).await?;
+            let Some(local) = LocalL1BatchProofData::new(&mut storage, batch_number).await? else {
+                drop(storage);
+                tokio::time::sleep(self.sleep_interval).await;
+                continue;
+            };
+            drop(storage);
+
+            match self.verify_batch(batch_number, &local).await {
+                Ok(verification) => {
+                    self.report(batch_number, local.prove_tx_hash, verification)
+                        .await?;
+                    batch_number += 1;
+                }
+                Err(VerifyError::Web3(err)) => {
+                    tracing::warn!("Error accessing L1; will retry after a delay: {err}");
+                    tokio::time::sleep(self.sleep_interval).await;
+                }
+                Err(VerifyError::Internal(err)) => {
+                    let context = format!("Failed verifying proof for L1 batch #{batch_number}");
+                    return Err(err.context(context));
+                }
+            }
+        }
+        Ok(())
+    }
+}
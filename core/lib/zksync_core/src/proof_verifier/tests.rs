@@ -0,0 +1,341 @@
+//! Tests for the proof verifier.
+
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use zksync_eth_client::{clients::MockEthereum, BoundEthInterface};
+use zksync_types::{
+    web3::contract::{tokens::Tokenizable, Options},
+    ProtocolVersion,
+};
+
+use super::*;
+use crate::utils::testonly::{create_l1_batch, create_l1_batch_metadata};
+
+const DIAMOND_PROXY: Address = Address::repeat_byte(0x11);
+const VERIFIER: Address = Address::repeat_byte(0x12);
+
+fn create_l1_batch_with_metadata(number: u32) -> L1BatchWithMetadata {
+    L1BatchWithMetadata {
+        header: create_l1_batch(number),
+        metadata: create_l1_batch_metadata(number),
+        raw_published_factory_deps: vec![],
+    }
+}
+
+fn build_prove_tx_input_data(
+    prev_l1_batch: &L1BatchWithMetadata,
+    l1_batches: &[L1BatchWithMetadata],
+    serialized_proof: &[U256],
+) -> Vec<u8> {
+    let contract = zksync_contracts::zksync_contract();
+    let prove_function = contract.function("proveBatches").unwrap();
+    let batches = l1_batches
+        .iter()
+        .map(|batch| StoredBatchInfo(batch).into_token())
+        .collect();
+    let proof = serialized_proof.iter().copied().map(Token::Uint).collect();
+    prove_function
+        .encode_input(&[
+            StoredBatchInfo(prev_l1_batch).into_token(),
+            Token::Array(batches),
+            Token::Tuple(vec![Token::Array(vec![]), Token::Array(proof)]),
+        ])
+        .unwrap()
+}
+
+fn verify_call_data(public_inputs: &[U256], serialized_proof: &[U256]) -> Vec<u8> {
+    let array_type = ParamType::Array(Box::new(ParamType::Uint(256)));
+    let mut data = ethabi::short_signature(
+        "verify",
+        &[array_type.clone(), array_type.clone(), array_type],
+    )
+    .to_vec();
+    let uint_array =
+        |values: &[U256]| Token::Array(values.iter().copied().map(Token::Uint).collect());
+    data.extend(ethabi::encode(&[
+        uint_array(public_inputs),
+        uint_array(serialized_proof),
+        uint_array(&[]),
+    ]));
+    data
+}
+
+async fn send_prove_tx(client: &MockEthereum, input_data: Vec<u8>) -> H256 {
+    let signed_tx = client
+        .sign_prepared_tx_for_addr(
+            input_data,
+            DIAMOND_PROXY,
+            Options {
+                nonce: Some(0.into()),
+                ..Options::default()
+            },
+            "test",
+        )
+        .await
+        .unwrap();
+    client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    client.execute_tx(signed_tx.hash, true, 1);
+    signed_tx.hash
+}
+
+fn create_mock_verifier(client: Arc<MockEthereum>, pool: ConnectionPool) -> ProofVerifier {
+    let (health_check, health_updater) = ReactiveHealthCheck::new("proof_verifier");
+    client.set_call_response(
+        DIAMOND_PROXY,
+        ethabi::short_signature("getVerifier", &[]).to_vec(),
+        ethabi::encode(&[Token::Address(VERIFIER)]),
+    );
+    ProofVerifier {
+        contract: zksync_contracts::zksync_contract(),
+        diamond_proxy_addr: DIAMOND_PROXY,
+        max_batches_to_recheck: 10,
+        sleep_interval: Duration::from_millis(10),
+        l1_client: Box::new(client),
+        pool,
+        health_updater,
+        health_check,
+        details: ProofVerifierDetails::default(),
+    }
+}
+
+#[test]
+fn extracting_submitted_proofs() {
+    let contract = zksync_contracts::zksync_contract();
+    let prove_function = contract.function("proveBatches").unwrap();
+    let batches: Vec<_> = (0..=2).map(create_l1_batch_with_metadata).collect();
+    let proof = [U256::from(1), U256::from(2)];
+    let input_data = build_prove_tx_input_data(&batches[0], &batches[1..], &proof);
+
+    let expected_public_inputs = [
+        proof_public_input(
+            batches[0].metadata.commitment,
+            batches[1].metadata.commitment,
+        ),
+        proof_public_input(
+            batches[1].metadata.commitment,
+            batches[2].metadata.commitment,
+        ),
+    ];
+    for number in [1, 2] {
+        let submitted =
+            SubmittedProof::extract(&input_data, prove_function, L1BatchNumber(number)).unwrap();
+        let number = number as usize;
+        assert_eq!(
+            submitted.prev_batch,
+            StoredBatchInfo(&batches[number - 1]).into_token()
+        );
+        assert_eq!(
+            submitted.batch,
+            StoredBatchInfo(&batches[number]).into_token()
+        );
+        assert_eq!(submitted.public_inputs, expected_public_inputs);
+        assert_eq!(submitted.serialized_proof, proof);
+        assert!(submitted.recursive_aggregation_input.is_empty());
+    }
+
+    let err = SubmittedProof::extract(&input_data, prove_function, L1BatchNumber(3)).unwrap_err();
+    assert!(
+        err.to_string().contains("doesn't prove L1 batch #3"),
+        "{err}"
+    );
+    let mut bogus_input_data = input_data;
+    bogus_input_data[..4].copy_from_slice(b"fake");
+    let err =
+        SubmittedProof::extract(&bogus_input_data, prove_function, L1BatchNumber(1)).unwrap_err();
+    assert!(err.to_string().contains("unexpected function"), "{err}");
+}
+
+#[test]
+fn computing_proof_public_input() {
+    let public_input = proof_public_input(H256::zero(), H256::zero());
+    let hash = keccak256(&[0; 64]);
+    assert_eq!(public_input, U256::from_big_endian(&hash) >> 32);
+    assert!(public_input.leading_zeros() >= 32);
+}
+
+#[tokio::test]
+async fn verifying_proofs() {
+    let pool = ConnectionPool::test_pool().await;
+    pool.access_storage()
+        .await
+        .unwrap()
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    let local = LocalL1BatchProofData {
+        l1_batch: create_l1_batch_with_metadata(1),
+        prev_l1_batch: create_l1_batch_with_metadata(0),
+        prove_tx_hash: H256::zero(),
+    };
+    let proof = [U256::from(1), U256::from(2)];
+    let public_input = proof_public_input(
+        local.prev_l1_batch.metadata.commitment,
+        local.l1_batch.metadata.commitment,
+    );
+
+    let client = Arc::new(MockEthereum::default());
+    let input_data = build_prove_tx_input_data(
+        &local.prev_l1_batch,
+        std::slice::from_ref(&local.l1_batch),
+        &proof,
+    );
+    let prove_tx_hash = send_prove_tx(&client, input_data).await;
+    client.set_call_response(
+        VERIFIER,
+        ethabi::short_signature("verificationKeyHash", &[]).to_vec(),
+        ethabi::encode(&[Token::FixedBytes(vec![0; 32])]),
+    );
+    client.set_call_response(
+        VERIFIER,
+        verify_call_data(&[public_input], &proof),
+        ethabi::encode(&[Token::Bool(true)]),
+    );
+    let verifier = create_mock_verifier(client.clone(), pool.clone());
+    let local = LocalL1BatchProofData {
+        prove_tx_hash,
+        ..local
+    };
+    let verification = verifier
+        .verify_batch(L1BatchNumber(1), &local)
+        .await
+        .unwrap();
+    assert_eq!(verification, Verification::Verified);
+    let verify_calls = client
+        .calls()
+        .into_iter()
+        .filter(|call| call.to == Some(VERIFIER));
+    assert_eq!(verify_calls.count(), 2);
+
+    // The verifier rejects the proof.
+    client.set_call_response(
+        VERIFIER,
+        verify_call_data(&[public_input], &proof),
+        ethabi::encode(&[Token::Bool(false)]),
+    );
+    let verification = verifier
+        .verify_batch(L1BatchNumber(1), &local)
+        .await
+        .unwrap();
+    assert_matches!(verification, Verification::Invalid(reason) if reason.contains("rejected proof"));
+
+    // The verifier uses another verification key.
+    client.set_call_response(
+        VERIFIER,
+        ethabi::short_signature("verificationKeyHash", &[]).to_vec(),
+        ethabi::encode(&[Token::FixedBytes(vec![1; 32])]),
+    );
+    let verification = verifier
+        .verify_batch(L1BatchNumber(1), &local)
+        .await
+        .unwrap();
+    assert_matches!(verification, Verification::Invalid(reason) if reason.contains("verification key hash"));
+}
+
+#[tokio::test]
+async fn verifying_mock_and_mismatched_proofs() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = Arc::new(MockEthereum::default());
+    let l1_batch = create_l1_batch_with_metadata(1);
+    let prev_l1_batch = create_l1_batch_with_metadata(0);
+    let input_data =
+        build_prove_tx_input_data(&prev_l1_batch, std::slice::from_ref(&l1_batch), &[]);
+    let prove_tx_hash = send_prove_tx(&client, input_data).await;
+    let verifier = create_mock_verifier(client.clone(), pool);
+
+    let mut local = LocalL1BatchProofData {
+        l1_batch,
+        prev_l1_batch,
+        prove_tx_hash,
+    };
+    let verification = verifier
+        .verify_batch(L1BatchNumber(1), &local)
+        .await
+        .unwrap();
+    assert_eq!(verification, Verification::MockProof);
+    // Mock proofs aren't sent to the verifier.
+    assert!(client.calls().is_empty());
+
+    local.l1_batch.header.timestamp += 1;
+    let verification = verifier
+        .verify_batch(L1BatchNumber(1), &local)
+        .await
+        .unwrap();
+    assert_matches!(verification, Verification::Invalid(reason) if reason.contains("differs from the local one"));
+}
+
+#[tokio::test]
+async fn reporting_mock_proofs() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(1))
+        .await
+        .unwrap();
+    drop(storage);
+
+    let mut verifier = create_mock_verifier(Arc::default(), pool);
+    assert_matches!(verifier.details.health().status(), HealthStatus::Ready);
+    verifier
+        .report(
+            L1BatchNumber(1),
+            H256::repeat_byte(1),
+            Verification::MockProof,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        verifier.details.last_mock_proof_batch,
+        Some(L1BatchNumber(1))
+    );
+    let health = verifier.details.health();
+    assert_matches!(health.status(), HealthStatus::Affected);
+    assert!(health.status().is_healthy());
+}
+
+#[tokio::test]
+async fn reporting_verification_results() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(1))
+        .await
+        .unwrap();
+    drop(storage);
+
+    let mut verifier = create_mock_verifier(Arc::default(), pool.clone());
+    let prove_tx_hash = H256::repeat_byte(1);
+    verifier
+        .report(
+            L1BatchNumber(1),
+            prove_tx_hash,
+            Verification::Invalid("bogus proof".to_owned()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(verifier.details.invalid_batches, [L1BatchNumber(1)]);
+    assert_matches!(verifier.details.health().status(), HealthStatus::NotReady);
+
+    let status = pool
+        .access_storage()
+        .await
+        .unwrap()
+        .proof_verifications_dal()
+        .get_verification(L1BatchNumber(1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(status.prove_tx_hash, prove_tx_hash);
+    assert_eq!(status.status, ProofVerificationStatus::Invalid);
+    assert_eq!(status.details.as_deref(), Some("bogus proof"));
+}
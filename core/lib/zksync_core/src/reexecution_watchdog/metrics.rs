@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Family, Gauge, Histogram, Metrics, Unit};
+
+use super::MismatchKind;
+
+/// Metrics for the re-execution watchdog.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_reexecution_watchdog")]
+pub(super) struct ReexecutionWatchdogMetrics {
+    /// Number of re-executed L1 batches.
+    pub checked_batches: Counter,
+    /// Number of re-executions that failed with an error (as opposed to producing a mismatched outcome).
+    pub failed_checks: Counter,
+    /// Number of mismatches between the re-executed and the persisted outcome split by kind.
+    pub mismatches: Family<MismatchKind, Counter>,
+    /// Number of the latest re-executed L1 batch.
+    pub last_checked_batch: Gauge<u64>,
+    /// Latency of re-executing a single L1 batch.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub reexecution_latency: Histogram<Duration>,
+    /// Set to 1 if a mismatch was detected since the watchdog start.
+    pub alert: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<ReexecutionWatchdogMetrics> = vise::Global::new();
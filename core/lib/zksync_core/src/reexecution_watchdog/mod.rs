@@ -0,0 +1,531 @@
+//! Re-execution watchdog: re-executes a random sample of recent L1 batches and compares the outcome
+//! with the data persisted by the state keeper.
+//!
+//! Batch execution is expected to be fully deterministic: re-executing a sealed L1 batch with the same inputs
+//! must produce the same transaction outcomes, events, L2-to-L1 logs and storage writes. A divergence indicates
+//! a nondeterminism bug in the VM or the state keeper, which would otherwise surface only when the prover fails
+//! to prove the batch (or not at all, if the bug is in the data that isn't proven). For each sampled batch,
+//! the watchdog compares:
+//!
+//! - Content hashes of miniblocks, which cover executed transactions with their status and refunded gas,
+//!   emitted events, user L2-to-L1 logs and storage writes (see [`MiniblockContentHasher`]).
+//! - The Merkle root of user L2-to-L1 logs and the hash of system logs of the batch.
+//! - The hash of final values of storage slots written in the batch. These writes determine the state root
+//!   computed by the Merkle tree, so the tree itself is not required.
+//!
+//! Re-execution is CPU-heavy, so the watchdog is intended to be run as a separate process
+//! (`--components=reexecution_watchdog`) connected to a Postgres replica. A mismatch is reported via the health check,
+//! logs and metrics. Since a mismatch is never expected to resolve by itself, the alert is only cleared
+//! on the watchdog restart.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use multivm::interface::{L2BlockEnv, VmInterface};
+use rand::Rng;
+use serde::Serialize;
+use tokio::{runtime::Handle, sync::watch};
+use vise::{EncodeLabelSet, EncodeLabelValue};
+use vm_utils::{create_vm, execute_tx};
+use zksync_config::ReexecutionWatchdogConfig;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_types::{
+    block::MiniblockContentHasher,
+    l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log, UserL2ToL1Log},
+    storage_writes_deduplicator::StorageWritesDeduplicator,
+    web3::signing::keccak256,
+    L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, StorageKey, StorageLogQuery,
+    H256,
+};
+use zksync_utils::u256_to_h256;
+
+use self::metrics::METRICS;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Kind of data mismatched between the persisted and the re-executed outcome of an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, EncodeLabelValue, EncodeLabelSet)]
+#[serde(rename_all = "snake_case")]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(crate) enum MismatchKind {
+    /// Transactions in a miniblock with their execution status and refunded gas, or events, user L2-to-L1 logs
+    /// or storage writes produced in it.
+    MiniblockContent,
+    /// User L2-to-L1 logs of the L1 batch.
+    UserL2ToL1Logs,
+    /// System L2-to-L1 logs of the L1 batch.
+    SystemLogs,
+    /// Final values of storage slots written in the L1 batch.
+    StorageWrites,
+}
+
+/// Mismatch between the persisted and the re-executed outcome of an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Mismatch {
+    pub kind: MismatchKind,
+    /// Mismatched miniblock; only set for [`MismatchKind::MiniblockContent`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub miniblock_number: Option<MiniblockNumber>,
+    /// Hash (or Merkle root) of the persisted data.
+    pub persisted: H256,
+    /// Hash (or Merkle root) of the re-executed data.
+    pub reexecuted: H256,
+}
+
+/// Outcome of an L1 batch, either persisted by the state keeper or obtained by re-executing the batch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct BatchOutcome {
+    /// Content hashes of miniblocks in the batch.
+    pub miniblock_content_hashes: BTreeMap<MiniblockNumber, H256>,
+    pub user_l2_to_l1_logs: Vec<UserL2ToL1Log>,
+    pub system_logs: Vec<SystemL2ToL1Log>,
+    /// Final values of storage slots written in the batch.
+    pub storage_writes: HashMap<StorageKey, H256>,
+}
+
+impl BatchOutcome {
+    /// Loads the outcome persisted by the state keeper. Returns `None` if the batch is not sealed.
+    async fn load(
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<(Self, Option<ProtocolVersionId>)>> {
+        let Some(header) = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let (first_miniblock, last_miniblock) = storage
+            .blocks_web3_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("no miniblocks persisted for L1 batch #{l1_batch_number}"))?;
+
+        let mut miniblock_content_hashes = BTreeMap::new();
+        for number in first_miniblock.0..=last_miniblock.0 {
+            let number = MiniblockNumber(number);
+            let mut content_hashes = storage.content_hashes_dal();
+            // Miniblocks sealed before content hashes were introduced don't have a persisted hash.
+            let content_hash = match content_hashes.get_content_hash(number).await? {
+                Some(hash) => hash,
+                None => content_hashes.compute_content_hash(number).await?,
+            };
+            miniblock_content_hashes.insert(number, content_hash);
+        }
+
+        let storage_writes = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_l1_batch(l1_batch_number)
+            .await?;
+        let outcome = Self {
+            miniblock_content_hashes,
+            user_l2_to_l1_logs: header.l2_to_l1_logs,
+            system_logs: header.system_logs,
+            storage_writes,
+        };
+        Ok(Some((outcome, header.protocol_version)))
+    }
+
+    /// Re-executes the L1 batch using the inputs persisted in Postgres.
+    fn reexecute(
+        rt_handle: Handle,
+        pool: &ConnectionPool,
+        l1_batch_number: L1BatchNumber,
+        l2_chain_id: L2ChainId,
+    ) -> anyhow::Result<Self> {
+        let mut connection = rt_handle
+            .block_on(pool.access_storage_tagged("reexecution_watchdog"))
+            .context("failed to get connection for re-execution")?;
+        let miniblocks_execution_data = rt_handle.block_on(
+            connection
+                .transactions_dal()
+                .get_miniblocks_to_execute_for_l1_batch(l1_batch_number),
+        )?;
+        let (_, fictive_miniblock_number) = rt_handle
+            .block_on(
+                connection
+                    .blocks_web3_dal()
+                    .get_miniblock_range_of_l1_batch(l1_batch_number),
+            )?
+            .with_context(|| format!("no miniblocks persisted for L1 batch #{l1_batch_number}"))?;
+        let last_executed_miniblock = miniblocks_execution_data.last().map(|data| data.number);
+        anyhow::ensure!(
+            last_executed_miniblock < Some(fictive_miniblock_number),
+            "L1 batch #{l1_batch_number} has no fictive miniblock"
+        );
+
+        let (mut vm, _) = create_vm(rt_handle, l1_batch_number, connection, l2_chain_id)
+            .context("failed to create VM for re-execution")?;
+
+        let mut outcome = Self::default();
+        let next_miniblocks_data = miniblocks_execution_data
+            .iter()
+            .skip(1)
+            .map(Some)
+            .chain([None]);
+        let miniblocks_data = miniblocks_execution_data.iter().zip(next_miniblocks_data);
+        for (miniblock_data, next_miniblock_data) in miniblocks_data {
+            let mut hasher = MiniblockContentHasher::new(miniblock_data.number);
+            let mut storage_logs = vec![];
+            for tx in &miniblock_data.txs {
+                let tx_hash = tx.hash();
+                let result = execute_tx(tx, &mut vm)
+                    .with_context(|| format!("failed to re-execute transaction {tx_hash:?}"))?;
+                hasher.push_tx(
+                    tx_hash,
+                    !result.result.is_failed(),
+                    result.refunds.gas_refunded.into(),
+                );
+                for event in &result.logs.events {
+                    hasher.push_event(tx_hash, event.address, &event.indexed_topics, &event.value);
+                }
+                for log in &result.logs.user_l2_to_l1_logs {
+                    hasher.push_l2_to_l1_log(tx_hash, &log.0);
+                }
+                storage_logs.extend(result.logs.storage_logs);
+            }
+            outcome.apply_miniblock_storage_logs(&storage_logs, &mut hasher);
+            outcome
+                .miniblock_content_hashes
+                .insert(miniblock_data.number, hasher.finalize());
+
+            if let Some(next_miniblock_data) = next_miniblock_data {
+                vm.start_new_l2_block(L2BlockEnv::from_miniblock_data(next_miniblock_data));
+            }
+        }
+
+        let finished_batch = vm.finish_batch();
+        // The state keeper attributes events, logs and storage writes of the batch tip to the fictive miniblock
+        // with the zero transaction hash.
+        let block_tip_logs = finished_batch.block_tip_execution_result.logs;
+        let mut hasher = MiniblockContentHasher::new(fictive_miniblock_number);
+        for event in &block_tip_logs.events {
+            hasher.push_event(
+                H256::zero(),
+                event.address,
+                &event.indexed_topics,
+                &event.value,
+            );
+        }
+        for log in &block_tip_logs.user_l2_to_l1_logs {
+            hasher.push_l2_to_l1_log(H256::zero(), &log.0);
+        }
+        outcome.apply_miniblock_storage_logs(&block_tip_logs.storage_logs, &mut hasher);
+        outcome
+            .miniblock_content_hashes
+            .insert(fictive_miniblock_number, hasher.finalize());
+
+        let execution_state = finished_batch.final_execution_state;
+        outcome.user_l2_to_l1_logs = execution_state.user_l2_to_l1_logs;
+        outcome.system_logs = execution_state.system_logs;
+        Ok(outcome)
+    }
+
+    /// Applies storage logs produced in a single miniblock. Writes are deduplicated in the same way
+    /// as by the state keeper, i.e., a slot is considered written only if its value at the end of the miniblock
+    /// differs from the value at its start. Deduplicated writes are also pushed to the miniblock content `hasher`.
+    fn apply_miniblock_storage_logs(
+        &mut self,
+        logs: &[StorageLogQuery],
+        hasher: &mut MiniblockContentHasher,
+    ) {
+        let mut deduplicator = StorageWritesDeduplicator::new();
+        deduplicator.apply(logs);
+        for (key, slot) in deduplicator.into_modified_key_values() {
+            let value = u256_to_h256(slot.value);
+            hasher.push_storage_write(key.hashed_key(), value);
+            self.storage_writes.insert(key, value);
+        }
+    }
+
+    /// Returns the Merkle root of user L2-to-L1 logs, which is committed to the settlement layer.
+    fn user_l2_to_l1_logs_root(&self, protocol_version: Option<ProtocolVersionId>) -> H256 {
+        let min_tree_size = if protocol_version.map_or(true, |version| version.is_pre_boojum()) {
+            L2ToL1Log::PRE_BOOJUM_MIN_L2_L1_LOGS_TREE_SIZE
+        } else {
+            L2ToL1Log::MIN_L2_L1_LOGS_TREE_SIZE
+        };
+        let leaves = self.user_l2_to_l1_logs.iter().map(|log| log.0.to_bytes());
+        MiniMerkleTree::new(leaves, Some(min_tree_size)).merkle_root()
+    }
+
+    fn system_logs_hash(&self) -> H256 {
+        let bytes: Vec<u8> = self
+            .system_logs
+            .iter()
+            .flat_map(|log| log.0.to_bytes())
+            .collect();
+        H256(keccak256(&bytes))
+    }
+
+    /// Hashes storage writes ordered by the hashed key, so that the hash doesn't depend on the order of writes.
+    fn storage_writes_hash(&self) -> H256 {
+        let mut writes: Vec<_> = self
+            .storage_writes
+            .iter()
+            .map(|(key, value)| (key.hashed_key(), *value))
+            .collect();
+        writes.sort_unstable();
+        let bytes: Vec<u8> = writes
+            .iter()
+            .flat_map(|(key, value)| [key.0, value.0])
+            .flatten()
+            .collect();
+        H256(keccak256(&bytes))
+    }
+
+    /// Compares this persisted outcome with the re-executed one. Only miniblocks present in the re-executed outcome
+    /// are compared; miniblocks without transactions (other than the fictive one) are not re-executed.
+    pub(crate) fn compare(
+        &self,
+        reexecuted: &Self,
+        protocol_version: Option<ProtocolVersionId>,
+    ) -> Vec<Mismatch> {
+        let mut mismatches = vec![];
+        for (&miniblock_number, &reexecuted_hash) in &reexecuted.miniblock_content_hashes {
+            let persisted_hash = self
+                .miniblock_content_hashes
+                .get(&miniblock_number)
+                .copied()
+                .unwrap_or_default();
+            if persisted_hash != reexecuted_hash {
+                mismatches.push(Mismatch {
+                    kind: MismatchKind::MiniblockContent,
+                    miniblock_number: Some(miniblock_number),
+                    persisted: persisted_hash,
+                    reexecuted: reexecuted_hash,
+                });
+            }
+        }
+
+        let batch_hashes = [
+            (
+                MismatchKind::UserL2ToL1Logs,
+                self.user_l2_to_l1_logs_root(protocol_version),
+                reexecuted.user_l2_to_l1_logs_root(protocol_version),
+            ),
+            (
+                MismatchKind::SystemLogs,
+                self.system_logs_hash(),
+                reexecuted.system_logs_hash(),
+            ),
+            (
+                MismatchKind::StorageWrites,
+                self.storage_writes_hash(),
+                reexecuted.storage_writes_hash(),
+            ),
+        ];
+        for (kind, persisted, reexecuted) in batch_hashes {
+            if persisted != reexecuted {
+                mismatches.push(Mismatch {
+                    kind,
+                    miniblock_number: None,
+                    persisted,
+                    reexecuted,
+                });
+            }
+        }
+        mismatches
+    }
+}
+
+/// Returns a random L1 batch among the `window` latest sealed batches. The genesis batch is never returned
+/// since it's not executed by the state keeper.
+fn sample_l1_batch(
+    last_sealed_batch: L1BatchNumber,
+    window: u32,
+    rng: &mut impl Rng,
+) -> Option<L1BatchNumber> {
+    if last_sealed_batch.0 == 0 {
+        return None;
+    }
+    let first_batch = last_sealed_batch.0.saturating_sub(window.max(1) - 1).max(1);
+    Some(L1BatchNumber(
+        rng.gen_range(first_batch..=last_sealed_batch.0),
+    ))
+}
+
+/// Mismatches detected in a single L1 batch.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchMismatches {
+    l1_batch_number: L1BatchNumber,
+    mismatches: Vec<Mismatch>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReexecutionWatchdogHealthDetails {
+    last_checked_batch: Option<L1BatchNumber>,
+    checked_batches: u64,
+    /// Number of L1 batches with a mismatch detected since the watchdog start.
+    mismatched_batches: u64,
+    /// Mismatches in the latest mismatched L1 batch.
+    last_mismatch: Option<BatchMismatches>,
+}
+
+/// Task re-executing recent L1 batches. See the module-level docs for details.
+#[derive(Debug)]
+pub struct ReexecutionWatchdog {
+    pool: ConnectionPool,
+    config: ReexecutionWatchdogConfig,
+    l2_chain_id: L2ChainId,
+    health_updater: HealthUpdater,
+    last_checked_batch: Option<L1BatchNumber>,
+    checked_batches: u64,
+    mismatched_batches: u64,
+    last_mismatch: Option<BatchMismatches>,
+}
+
+impl ReexecutionWatchdog {
+    pub fn new(
+        pool: ConnectionPool,
+        config: ReexecutionWatchdogConfig,
+        l2_chain_id: L2ChainId,
+    ) -> Self {
+        Self {
+            pool,
+            config,
+            l2_chain_id,
+            health_updater: ReactiveHealthCheck::new("reexecution_watchdog").1,
+            last_checked_batch: None,
+            checked_batches: 0,
+            mismatched_batches: 0,
+            last_mismatch: None,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.update_health();
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+
+            if let Err(err) = self.check().await {
+                METRICS.failed_checks.inc();
+                tracing::warn!("Failed re-executing L1 batch: {err:#}");
+            }
+
+            if tokio::time::timeout(self.config.check_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, re-execution watchdog is shutting down");
+        Ok(())
+    }
+
+    /// Re-executes a random recent L1 batch. Returns detected mismatches, or `None` if there are no batches to check.
+    pub(crate) async fn check(&mut self) -> anyhow::Result<Option<Vec<Mismatch>>> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("reexecution_watchdog")
+            .await?;
+        let last_sealed_batch = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+        drop(storage);
+
+        let Some(last_sealed_batch) = last_sealed_batch else {
+            return Ok(None);
+        };
+        let l1_batch_number = sample_l1_batch(
+            last_sealed_batch,
+            self.config.sample_window_batches,
+            &mut rand::thread_rng(),
+        );
+        let Some(l1_batch_number) = l1_batch_number else {
+            return Ok(None);
+        };
+        self.check_batch(l1_batch_number).await.map(Some)
+    }
+
+    /// Re-executes the specified L1 batch and compares the outcome with the persisted one.
+    pub(crate) async fn check_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Vec<Mismatch>> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("reexecution_watchdog")
+            .await?;
+        let (persisted, protocol_version) = BatchOutcome::load(&mut storage, l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not sealed"))?;
+        drop(storage);
+
+        tracing::info!("Started re-executing L1 batch #{l1_batch_number}");
+        let started_at = Instant::now();
+        let pool = self.pool.clone();
+        let l2_chain_id = self.l2_chain_id;
+        let reexecuted = tokio::task::spawn_blocking(move || {
+            BatchOutcome::reexecute(Handle::current(), &pool, l1_batch_number, l2_chain_id)
+        })
+        .await
+        .context("re-execution panicked")?
+        .with_context(|| format!("failed re-executing L1 batch #{l1_batch_number}"))?;
+        let elapsed = started_at.elapsed();
+        METRICS.reexecution_latency.observe(elapsed);
+        tracing::info!("Finished re-executing L1 batch #{l1_batch_number} in {elapsed:?}");
+
+        let mismatches = persisted.compare(&reexecuted, protocol_version);
+        self.record_check(l1_batch_number, &mismatches);
+        Ok(mismatches)
+    }
+
+    fn record_check(&mut self, l1_batch_number: L1BatchNumber, mismatches: &[Mismatch]) {
+        METRICS.checked_batches.inc();
+        METRICS.last_checked_batch.set(l1_batch_number.0.into());
+        self.last_checked_batch = Some(l1_batch_number);
+        self.checked_batches += 1;
+
+        if mismatches.is_empty() {
+            tracing::debug!("Re-executed L1 batch #{l1_batch_number} matches the persisted data");
+        } else {
+            tracing::error!(
+                "Re-executed L1 batch #{l1_batch_number} diverges from the persisted data, \
+                 which indicates nondeterministic execution: {mismatches:?}"
+            );
+            for mismatch in mismatches {
+                METRICS.mismatches[&mismatch.kind].inc();
+            }
+            METRICS.alert.set(1);
+            self.mismatched_batches += 1;
+            self.last_mismatch = Some(BatchMismatches {
+                l1_batch_number,
+                mismatches: mismatches.to_vec(),
+            });
+        }
+        self.update_health();
+    }
+
+    fn update_health(&self) {
+        let status = if self.last_mismatch.is_some() {
+            HealthStatus::Affected
+        } else {
+            HealthStatus::Ready
+        };
+        let details = ReexecutionWatchdogHealthDetails {
+            last_checked_batch: self.last_checked_batch,
+            checked_batches: self.checked_batches,
+            mismatched_batches: self.mismatched_batches,
+            last_mismatch: self.last_mismatch.clone(),
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
+    }
+}
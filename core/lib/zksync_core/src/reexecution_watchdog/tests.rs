@@ -0,0 +1,258 @@
+//! Tests for the re-execution watchdog.
+
+use rand::{rngs::StdRng, SeedableRng};
+use zksync_types::{
+    l2_to_l1_log::L2ToL1Log,
+    zk_evm_types::{LogQuery, Timestamp},
+    AccountTreeId, Address, StorageLogQueryType,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::store_executed_l1_batch_with_messages,
+};
+
+fn test_config() -> ReexecutionWatchdogConfig {
+    ReexecutionWatchdogConfig {
+        check_interval_ms: 10,
+        sample_window_batches: 10,
+    }
+}
+
+fn write_log_query(key: u64, read_value: u64, written_value: u64) -> StorageLogQuery {
+    StorageLogQuery {
+        log_query: LogQuery {
+            timestamp: Timestamp(0),
+            tx_number_in_block: 0,
+            aux_byte: 0,
+            shard_id: 0,
+            address: Address::repeat_byte(1),
+            key: key.into(),
+            read_value: read_value.into(),
+            written_value: written_value.into(),
+            rw_flag: true,
+            rollback: false,
+            is_service: false,
+        },
+        log_type: StorageLogQueryType::RepeatedWrite,
+    }
+}
+
+fn storage_key(key: u64) -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(1)),
+        u256_to_h256(key.into()),
+    )
+}
+
+fn mock_outcome() -> BatchOutcome {
+    BatchOutcome {
+        miniblock_content_hashes: BTreeMap::from([
+            (MiniblockNumber(1), H256::repeat_byte(1)),
+            (MiniblockNumber(2), H256::repeat_byte(2)),
+        ]),
+        user_l2_to_l1_logs: vec![UserL2ToL1Log(L2ToL1Log {
+            value: H256::repeat_byte(0x11),
+            ..L2ToL1Log::default()
+        })],
+        system_logs: vec![SystemL2ToL1Log(L2ToL1Log::default())],
+        storage_writes: HashMap::from([(storage_key(1), H256::repeat_byte(0x22))]),
+    }
+}
+
+#[test]
+fn sampling_l1_batches() {
+    let rng = &mut StdRng::seed_from_u64(123);
+    assert_eq!(sample_l1_batch(L1BatchNumber(0), 10, rng), None);
+    assert_eq!(
+        sample_l1_batch(L1BatchNumber(1), 10, rng),
+        Some(L1BatchNumber(1))
+    );
+    assert_eq!(
+        sample_l1_batch(L1BatchNumber(100), 0, rng),
+        Some(L1BatchNumber(100))
+    );
+
+    for _ in 0..100 {
+        let sampled = sample_l1_batch(L1BatchNumber(5), 10, rng).unwrap();
+        assert!((1..=5).contains(&sampled.0), "{sampled:?}");
+        let sampled = sample_l1_batch(L1BatchNumber(100), 10, rng).unwrap();
+        assert!((91..=100).contains(&sampled.0), "{sampled:?}");
+    }
+}
+
+#[test]
+fn storage_writes_are_deduplicated_per_miniblock() {
+    let mut outcome = BatchOutcome::default();
+    outcome.apply_miniblock_storage_logs(&[
+        write_log_query(1, 0, 1),
+        write_log_query(1, 1, 0),
+        write_log_query(2, 0, 5),
+    ]);
+    // The first slot is reverted to its initial value within the miniblock, so it's not considered written.
+    assert_eq!(
+        outcome.storage_writes,
+        HashMap::from([(storage_key(2), u256_to_h256(5.into()))])
+    );
+
+    outcome.apply_miniblock_storage_logs(&[write_log_query(1, 0, 3), write_log_query(2, 5, 0)]);
+    assert_eq!(
+        outcome.storage_writes,
+        HashMap::from([
+            (storage_key(1), u256_to_h256(3.into())),
+            (storage_key(2), H256::zero()),
+        ])
+    );
+}
+
+#[test]
+fn comparing_batch_outcomes() {
+    let persisted = mock_outcome();
+    let protocol_version = Some(ProtocolVersionId::latest());
+    assert!(persisted.compare(&persisted, protocol_version).is_empty());
+
+    // Miniblocks not re-executed are not compared.
+    let mut reexecuted = mock_outcome();
+    reexecuted
+        .miniblock_content_hashes
+        .remove(&MiniblockNumber(1));
+    assert!(persisted.compare(&reexecuted, protocol_version).is_empty());
+
+    reexecuted
+        .miniblock_content_hashes
+        .insert(MiniblockNumber(2), H256::repeat_byte(0xff));
+    reexecuted
+        .storage_writes
+        .insert(storage_key(2), H256::zero());
+    let mismatches = persisted.compare(&reexecuted, protocol_version);
+    assert_eq!(mismatches.len(), 2, "{mismatches:?}");
+    assert_eq!(
+        mismatches[0],
+        Mismatch {
+            kind: MismatchKind::MiniblockContent,
+            miniblock_number: Some(MiniblockNumber(2)),
+            persisted: H256::repeat_byte(2),
+            reexecuted: H256::repeat_byte(0xff),
+        }
+    );
+    assert_eq!(mismatches[1].kind, MismatchKind::StorageWrites);
+    assert_eq!(mismatches[1].persisted, persisted.storage_writes_hash());
+
+    let mut reexecuted = mock_outcome();
+    reexecuted.user_l2_to_l1_logs[0].0.value = H256::repeat_byte(0x12);
+    reexecuted.system_logs.clear();
+    let mismatches = persisted.compare(&reexecuted, protocol_version);
+    let mismatch_kinds: Vec<_> = mismatches.iter().map(|mismatch| mismatch.kind).collect();
+    assert_eq!(
+        mismatch_kinds,
+        [MismatchKind::UserL2ToL1Logs, MismatchKind::SystemLogs]
+    );
+    assert_eq!(
+        mismatches[0].persisted,
+        persisted.user_l2_to_l1_logs_root(protocol_version)
+    );
+}
+
+#[test]
+fn storage_writes_hash_does_not_depend_on_order() {
+    let mut outcome = mock_outcome();
+    let mut other_outcome = mock_outcome();
+    let hash = outcome.storage_writes_hash();
+    for key in 2..20 {
+        outcome
+            .storage_writes
+            .insert(storage_key(key), H256::zero());
+    }
+    for key in (2..20).rev() {
+        other_outcome
+            .storage_writes
+            .insert(storage_key(key), H256::zero());
+    }
+    assert_eq!(
+        outcome.storage_writes_hash(),
+        other_outcome.storage_writes_hash()
+    );
+    assert_ne!(outcome.storage_writes_hash(), hash);
+}
+
+#[tokio::test]
+async fn no_checks_without_executed_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut watchdog = ReexecutionWatchdog::new(pool.clone(), test_config(), L2ChainId::default());
+    assert_eq!(watchdog.check().await.unwrap(), None);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    assert_eq!(watchdog.check().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn loading_persisted_outcome() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let messages = [vec![(Address::repeat_byte(1), b"message".to_vec())]];
+    store_executed_l1_batch_with_messages(&mut storage, 1, &messages).await;
+
+    let (outcome, protocol_version) = BatchOutcome::load(&mut storage, L1BatchNumber(1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(protocol_version, Some(ProtocolVersionId::latest()));
+    let expected_content_hash = storage
+        .content_hashes_dal()
+        .compute_content_hash(MiniblockNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(
+        outcome.miniblock_content_hashes,
+        BTreeMap::from([(MiniblockNumber(1), expected_content_hash)])
+    );
+    assert_eq!(outcome.user_l2_to_l1_logs.len(), 1);
+    assert!(outcome.compare(&outcome, protocol_version).is_empty());
+
+    let missing = BatchOutcome::load(&mut storage, L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+}
+
+#[tokio::test]
+async fn mismatch_is_alerted_via_health_check() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut watchdog = ReexecutionWatchdog::new(pool, test_config(), L2ChainId::default());
+    let health_check = watchdog.health_check();
+
+    watchdog.record_check(L1BatchNumber(1), &[]);
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+
+    let mismatch = Mismatch {
+        kind: MismatchKind::StorageWrites,
+        miniblock_number: None,
+        persisted: H256::repeat_byte(1),
+        reexecuted: H256::repeat_byte(2),
+    };
+    watchdog.record_check(L1BatchNumber(2), &[mismatch]);
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Affected
+    );
+
+    // The alert is not cleared by subsequent successful checks.
+    watchdog.record_check(L1BatchNumber(3), &[]);
+    let health = serde_json::to_value(health_check.check_health().await).unwrap();
+    assert_eq!(health["status"], "affected");
+    let details = &health["details"];
+    assert_eq!(details["lastCheckedBatch"], 3);
+    assert_eq!(details["checkedBatches"], 3);
+    assert_eq!(details["mismatchedBatches"], 1);
+    assert_eq!(details["lastMismatch"]["l1BatchNumber"], 2);
+}
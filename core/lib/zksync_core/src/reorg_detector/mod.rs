@@ -289,6 +289,11 @@ impl ReorgDetector {
     }
 
     /// Compares root hashes of the latest local batch and of the same batch from the main node.
+    ///
+    /// If the local root hash was obtained from the main node (e.g., if the node runs without a Merkle tree),
+    /// comparing it is meaningless; the hash of the last miniblock in the batch is compared instead.
+    /// Unlike the root hash, the miniblock hash doesn't cover the state, so a state divergence that doesn't affect
+    /// executed transactions cannot be detected for such batches.
     async fn root_hashes_match(
         &self,
         l1_batch_number: L1BatchNumber,
@@ -301,6 +306,21 @@ impl ReorgDetector {
             .with_context(|| {
                 format!("Root hash does not exist for local batch #{l1_batch_number}")
             })?;
+        let is_hash_from_main_node = storage
+            .blocks_dal()
+            .is_l1_batch_root_hash_from_main_node(l1_batch_number)
+            .await?;
+        if is_hash_from_main_node {
+            let (_, last_miniblock) = storage
+                .blocks_dal()
+                .get_miniblock_range_of_l1_batch(l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!("Miniblocks do not exist for local batch #{l1_batch_number}")
+                })?;
+            drop(storage);
+            return self.miniblock_hashes_match(last_miniblock).await;
+        }
         drop(storage);
 
         let Some(remote_hash) = self.client.l1_batch_root_hash(l1_batch_number).await? else {
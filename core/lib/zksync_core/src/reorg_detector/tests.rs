@@ -470,6 +470,66 @@ async fn reorg_is_detected_on_historic_batch_hash_mismatch(
     );
 }
 
+#[test_casing(4, [2, 3, 5, 8])]
+#[tokio::test]
+async fn reorg_is_detected_for_l1_batches_with_hashes_from_main_node(last_correct_batch: u32) {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    store_miniblock(&mut storage, 0, H256::zero()).await;
+    seal_l1_batch(&mut storage, 0, H256::zero()).await;
+
+    let mut client = MockMainNodeClient::default();
+    client
+        .miniblock_hash_responses
+        .insert(MiniblockNumber(0), H256::zero());
+    client
+        .l1_batch_root_hash_responses
+        .insert(L1BatchNumber(0), H256::zero());
+    for number in 1_u32..=10 {
+        let mut miniblock_hash = H256::from_low_u64_be(number.into());
+        client
+            .miniblock_hash_responses
+            .insert(MiniblockNumber(number), miniblock_hash);
+        // Root hashes are taken from the main node, so they always match.
+        let l1_batch_hash = H256::repeat_byte(number as u8);
+        client
+            .l1_batch_root_hash_responses
+            .insert(L1BatchNumber(number), l1_batch_hash);
+
+        if number > last_correct_batch {
+            miniblock_hash = H256::zero();
+        }
+        store_miniblock(&mut storage, number, miniblock_hash).await;
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number))
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .save_l1_batch_root_hash_from_main_node(L1BatchNumber(number), l1_batch_hash)
+            .await
+            .unwrap();
+    }
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let detector = create_mock_detector(client, pool.clone());
+    let last_correct_l1_batch = detector.run(stop_receiver).await.unwrap();
+    assert_eq!(
+        last_correct_l1_batch,
+        Some(L1BatchNumber(last_correct_batch))
+    );
+}
+
 #[tokio::test]
 async fn stopping_reorg_detector_while_waiting_for_l1_batch() {
     let pool = ConnectionPool::test_pool().await;
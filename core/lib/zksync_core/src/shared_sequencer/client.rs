@@ -0,0 +1,94 @@
+//! gRPC client for the shared sequencer.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt};
+use tonic::transport::{Channel, Endpoint};
+use zksync_types::L2ChainId;
+
+use super::{
+    proto::{self, shared_sequencer_client::SharedSequencerClient as GrpcClient},
+    OrderedTx, OrderedTxsAck, SharedSequencerClient,
+};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// [`SharedSequencerClient`] implementation connecting to the shared sequencer via gRPC.
+#[derive(Debug, Clone)]
+pub struct GrpcSharedSequencerClient {
+    channel: Channel,
+    chain_id: L2ChainId,
+}
+
+impl GrpcSharedSequencerClient {
+    /// Creates a new client. The connection is established lazily, on the first request.
+    pub fn new(url: &str, chain_id: L2ChainId) -> anyhow::Result<Self> {
+        let channel = Endpoint::from_shared(url.to_owned())
+            .with_context(|| format!("invalid shared sequencer URL `{url}`"))?
+            .connect_timeout(CONNECT_TIMEOUT)
+            .connect_lazy();
+        Ok(Self { channel, chain_id })
+    }
+
+    fn client(&self) -> GrpcClient<Channel> {
+        GrpcClient::new(self.channel.clone())
+    }
+}
+
+impl TryFrom<proto::OrderedTransaction> for OrderedTx {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::OrderedTransaction) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence: value.sequence.context("missing sequence")?,
+            raw_tx: value.raw_tx.context("missing raw_tx")?,
+        })
+    }
+}
+
+#[async_trait]
+impl SharedSequencerClient for GrpcSharedSequencerClient {
+    async fn subscribe(
+        &self,
+        after_sequence: Option<u64>,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<OrderedTx>>> {
+        let request = proto::SubscribeRequest {
+            chain_id: Some(self.chain_id.as_u64()),
+            after_sequence,
+        };
+        let stream = self
+            .client()
+            .subscribe(request)
+            .await
+            .context("Subscribe")?
+            .into_inner();
+        let stream = stream.map(|ordered_tx| -> anyhow::Result<OrderedTx> {
+            ordered_tx
+                .context("failed receiving transaction")?
+                .try_into()
+        });
+        Ok(stream.boxed())
+    }
+
+    async fn ack(&self, ack: OrderedTxsAck) -> anyhow::Result<()> {
+        let rejected = ack
+            .rejected
+            .into_iter()
+            .map(|(sequence, reason)| proto::RejectedTransaction {
+                sequence: Some(sequence),
+                reason: Some(reason),
+            })
+            .collect();
+        let mut request = tonic::Request::new(proto::AckRequest {
+            chain_id: Some(self.chain_id.as_u64()),
+            sequence: Some(ack.sequence),
+            rejected,
+        });
+        request.set_timeout(REQUEST_TIMEOUT);
+        self.client().ack(request).await.context("Ack")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use vise::{Counter, Gauge, LabeledFamily, Metrics};
+
+/// Metrics for the shared sequencer integration.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_shared_sequencer")]
+pub(super) struct SharedSequencerMetrics {
+    /// 1 if the shared sequencer stream is connected, 0 otherwise.
+    pub connected: Gauge<u64>,
+    /// 1 if the state keeper takes L2 transactions from the local mempool because the shared sequencer
+    /// is unavailable, 0 otherwise.
+    pub fallback: Gauge<u64>,
+    /// Number of attempts to reconnect to the shared sequencer.
+    pub reconnects: Counter,
+    /// Number of new transactions received from the shared sequencer.
+    pub received_txs: Counter,
+    /// Number of received transactions that could not be parsed.
+    pub invalid_txs: Counter,
+    /// Number of received transactions rejected by the transaction intake policies (e.g., because
+    /// the intake is paused or the fee is below the floor), labeled by the rejection reason.
+    #[metrics(labels = ["reason"])]
+    pub rejected_txs: LabeledFamily<&'static str, Counter>,
+    /// Sequence number of the last received transaction.
+    pub last_received_sequence: Gauge<u64>,
+    /// Sequence number of the last transaction acknowledged to the shared sequencer.
+    pub last_acked_sequence: Gauge<u64>,
+    /// Number of errors acknowledging transactions.
+    pub ack_errors: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<SharedSequencerMetrics> = vise::Global::new();
@@ -0,0 +1,547 @@
+//! Integration with an external shared sequencer.
+//!
+//! If enabled, the state keeper executes L2 transactions in the order streamed by the shared sequencer
+//! instead of taking them from the local mempool. L1 transactions are still taken from the local mempool.
+//! Transactions are received by [`SharedSequencerFeed`] and handed over to the state keeper I/O via
+//! [`SharedSequencerSource`]. Once a miniblock with streamed transactions is persisted, the feed acknowledges
+//! the transactions to the shared sequencer; on reconnection or restart, the stream is resumed
+//! after the last received or acknowledged transaction, respectively. Streamed transactions are subject
+//! to the same replay protection, intake policies and per-account caps as the transactions submitted via the API.
+//! The gRPC client for the shared sequencer requires the `shared-sequencer` crate feature.
+//!
+//! If the shared sequencer is unavailable for longer than the configured fallback timeout, the state keeper
+//! falls back to the local mempool until the connection is restored.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt};
+use tokio::sync::{mpsc, watch};
+use zksync_config::SharedSequencerConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{l2::L2Tx, L2ChainId, MiniblockNumber, Transaction, H256};
+
+#[cfg(feature = "shared-sequencer")]
+pub use self::client::GrpcSharedSequencerClient;
+use self::metrics::METRICS;
+use crate::api_server::tx_sender::{ReplayProtection, SubmitTxError, TxIntakePolicy};
+
+#[cfg(feature = "shared-sequencer")]
+mod client;
+mod metrics;
+#[cfg(feature = "shared-sequencer")]
+pub mod proto;
+#[cfg(test)]
+mod tests;
+
+/// Maximum size of a raw transaction accepted from the shared sequencer. Matches the default limit
+/// for transactions submitted via the API.
+const MAX_TX_SIZE: usize = 1_000_000;
+
+/// Transaction ordered by the shared sequencer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderedTx {
+    /// Position of the transaction in the stream. Sequence numbers are strictly increasing,
+    /// but not necessarily consecutive.
+    pub sequence: u64,
+    /// Transaction in the format accepted by `eth_sendRawTransaction`.
+    pub raw_tx: Vec<u8>,
+}
+
+/// Acknowledgement of all streamed transactions up to and including the specified sequence number.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderedTxsAck {
+    pub sequence: u64,
+    /// Sequence numbers of acknowledged transactions not included into the chain, together with
+    /// the rejection reasons.
+    pub rejected: Vec<(u64, String)>,
+}
+
+impl OrderedTxsAck {
+    fn merge(&mut self, other: Self) {
+        self.sequence = self.sequence.max(other.sequence);
+        self.rejected.extend(other.rejected);
+    }
+}
+
+/// Client for the shared sequencer API.
+#[async_trait]
+pub trait SharedSequencerClient: 'static + fmt::Debug + Send + Sync {
+    /// Subscribes to transactions ordered after `after_sequence`. If `after_sequence` is not specified,
+    /// the stream starts after the last acknowledged transaction.
+    async fn subscribe(
+        &self,
+        after_sequence: Option<u64>,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<OrderedTx>>>;
+
+    /// Acknowledges processing of streamed transactions.
+    async fn ack(&self, ack: OrderedTxsAck) -> anyhow::Result<()>;
+}
+
+#[derive(Debug)]
+enum ReceivedTxKind {
+    New(Transaction),
+    /// The transaction is already included into a miniblock, e.g. because it was executed before
+    /// a restart but wasn't acknowledged.
+    AlreadyExecuted,
+    Invalid(String),
+}
+
+#[derive(Debug)]
+struct ReceivedTx {
+    sequence: u64,
+    kind: ReceivedTxKind,
+}
+
+/// Streamed transactions processed in a certain miniblock.
+#[derive(Debug)]
+struct MiniblockAck {
+    miniblock: MiniblockNumber,
+    ack: OrderedTxsAck,
+}
+
+/// Creates a gRPC client for the shared sequencer at the specified URL.
+#[cfg(feature = "shared-sequencer")]
+pub fn grpc_client(
+    url: &str,
+    chain_id: L2ChainId,
+) -> anyhow::Result<Arc<dyn SharedSequencerClient>> {
+    Ok(Arc::new(GrpcSharedSequencerClient::new(url, chain_id)?))
+}
+
+/// Creates a gRPC client for the shared sequencer at the specified URL. Always fails since the gRPC client
+/// is not built without the `shared-sequencer` feature.
+#[cfg(not(feature = "shared-sequencer"))]
+pub fn grpc_client(
+    url: &str,
+    _chain_id: L2ChainId,
+) -> anyhow::Result<Arc<dyn SharedSequencerClient>> {
+    anyhow::bail!(
+        "Shared sequencer at {url} is configured, but the node is built without the `shared-sequencer` feature"
+    )
+}
+
+/// Creates a feed receiving transactions from the shared sequencer and the source using them
+/// in the state keeper I/O. The feed must be run for the source to receive transactions.
+pub fn shared_sequencer_feed(
+    config: &SharedSequencerConfig,
+    client: Arc<dyn SharedSequencerClient>,
+    pool: ConnectionPool,
+    replay_protection: ReplayProtection,
+    intake_policy: TxIntakePolicy,
+) -> (SharedSequencerFeed, SharedSequencerSource) {
+    let (txs_sender, txs_receiver) = mpsc::channel(config.max_buffered_txs.max(1) as usize);
+    let (acks_sender, acks_receiver) = mpsc::unbounded_channel();
+    let (disconnected_since_sender, disconnected_since) = watch::channel(Some(Instant::now()));
+    let feed = SharedSequencerFeed {
+        client,
+        pool,
+        replay_protection,
+        intake_policy,
+        reconnect_interval: config.reconnect_interval(),
+        ack_interval: config.ack_interval(),
+        txs_sender,
+        acks_receiver,
+        disconnected_since: disconnected_since_sender,
+    };
+    let source = SharedSequencerSource {
+        txs_receiver,
+        acks_sender,
+        disconnected_since,
+        fallback_timeout: config.fallback_timeout(),
+        returned_txs: VecDeque::new(),
+        executed_txs: HashMap::new(),
+        processed_sequences: vec![],
+        rejected_txs: vec![],
+    };
+    (feed, source)
+}
+
+/// Task receiving transactions from the shared sequencer and acknowledging processed transactions.
+#[derive(Debug)]
+pub struct SharedSequencerFeed {
+    client: Arc<dyn SharedSequencerClient>,
+    pool: ConnectionPool,
+    replay_protection: ReplayProtection,
+    intake_policy: TxIntakePolicy,
+    reconnect_interval: Duration,
+    ack_interval: Duration,
+    txs_sender: mpsc::Sender<ReceivedTx>,
+    acks_receiver: mpsc::UnboundedReceiver<MiniblockAck>,
+    /// `None` if the feed is connected to the shared sequencer.
+    disconnected_since: watch::Sender<Option<Instant>>,
+}
+
+impl SharedSequencerFeed {
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let Self {
+            client,
+            pool,
+            replay_protection,
+            intake_policy,
+            reconnect_interval,
+            ack_interval,
+            txs_sender,
+            acks_receiver,
+            disconnected_since,
+        } = self;
+        let receiver = TxsReceiver {
+            client: client.as_ref(),
+            pool: &pool,
+            replay_protection: &replay_protection,
+            intake_policy: &intake_policy,
+            reconnect_interval,
+            txs_sender,
+            disconnected_since,
+        };
+        let acker = TxsAcker {
+            client: client.as_ref(),
+            pool: &pool,
+            ack_interval,
+            acks_receiver,
+        };
+        tokio::try_join!(
+            receiver.run(stop_receiver.clone()),
+            acker.run(stop_receiver)
+        )?;
+        tracing::info!("Stop signal received, shared sequencer feed is shutting down");
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct TxsReceiver<'a> {
+    client: &'a dyn SharedSequencerClient,
+    pool: &'a ConnectionPool,
+    replay_protection: &'a ReplayProtection,
+    intake_policy: &'a TxIntakePolicy,
+    reconnect_interval: Duration,
+    txs_sender: mpsc::Sender<ReceivedTx>,
+    disconnected_since: watch::Sender<Option<Instant>>,
+}
+
+impl TxsReceiver<'_> {
+    async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        // Not initialized on startup, so that the stream resumes after the last acknowledged transaction.
+        let mut last_sequence = None;
+        while !*stop_receiver.borrow() {
+            match self.client.subscribe(last_sequence).await {
+                Ok(stream) => {
+                    tracing::info!(
+                        "Subscribed to shared sequencer after sequence number {last_sequence:?}"
+                    );
+                    self.disconnected_since.send_replace(None);
+                    METRICS.connected.set(1);
+                    let result = self
+                        .process_stream(stream, &mut last_sequence, &mut stop_receiver)
+                        .await;
+                    self.disconnected_since.send_replace(Some(Instant::now()));
+                    METRICS.connected.set(0);
+                    match result {
+                        Ok(()) => {}
+                        Err(ProcessingError::Stream(err)) => {
+                            tracing::warn!("Shared sequencer stream was interrupted: {err:#}");
+                        }
+                        Err(ProcessingError::Fatal(err)) => return Err(err),
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Failed subscribing to shared sequencer: {err:#}");
+                }
+            }
+
+            if *stop_receiver.borrow() {
+                break;
+            }
+            METRICS.reconnects.inc();
+            tokio::time::timeout(self.reconnect_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+
+    async fn process_stream(
+        &self,
+        mut stream: BoxStream<'static, anyhow::Result<OrderedTx>>,
+        last_sequence: &mut Option<u64>,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> Result<(), ProcessingError> {
+        loop {
+            let ordered_tx = tokio::select! {
+                ordered_tx = stream.next() => ordered_tx,
+                _ = stop_receiver.changed() => return Ok(()),
+            };
+            let ordered_tx = ordered_tx
+                .context("stream ended")
+                .and_then(|res| res)
+                .map_err(ProcessingError::Stream)?;
+            if last_sequence.map_or(false, |last| ordered_tx.sequence <= last) {
+                tracing::debug!(
+                    "Skipping transaction with sequence number {} received out of order",
+                    ordered_tx.sequence
+                );
+                continue;
+            }
+            *last_sequence = Some(ordered_tx.sequence);
+            METRICS.last_received_sequence.set(ordered_tx.sequence);
+
+            let received_tx = self.process_tx(ordered_tx).await?;
+            if self.txs_sender.send(received_tx).await.is_err() {
+                return Err(ProcessingError::Fatal(anyhow::anyhow!(
+                    "state keeper I/O dropped the shared sequencer source"
+                )));
+            }
+        }
+    }
+
+    async fn process_tx(&self, ordered_tx: OrderedTx) -> Result<ReceivedTx, ProcessingError> {
+        let OrderedTx { sequence, raw_tx } = ordered_tx;
+        // Transactions are subject to the same replay protection as ones submitted via the API.
+        let parsed = self
+            .replay_protection
+            .parse_raw_tx(&raw_tx)
+            .and_then(|(request, hash)| Ok((L2Tx::from_request(request, MAX_TX_SIZE)?, hash)));
+        let (mut tx, hash) = match parsed {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                tracing::info!(
+                    "Received invalid transaction with sequence number {sequence}: {err}"
+                );
+                METRICS.invalid_txs.inc();
+                let kind = ReceivedTxKind::Invalid(format!("invalid transaction: {err}"));
+                return Ok(ReceivedTx { sequence, kind });
+            }
+        };
+        tx.set_input(raw_tx, hash);
+
+        let mut storage = self
+            .pool
+            .access_storage_tagged("shared_sequencer")
+            .await
+            .map_err(ProcessingError::Fatal)?;
+        let is_executed = storage
+            .transactions_dal()
+            .is_tx_executed(hash)
+            .await
+            .context("is_tx_executed()")
+            .map_err(ProcessingError::Fatal)?;
+        drop(storage);
+
+        if is_executed {
+            tracing::info!(
+                "Transaction {hash:?} with sequence number {sequence} is already executed"
+            );
+            let kind = ReceivedTxKind::AlreadyExecuted;
+            return Ok(ReceivedTx { sequence, kind });
+        }
+
+        let kind = match self.check_intake_policy(&tx).await {
+            Ok(()) => {
+                METRICS.received_txs.inc();
+                ReceivedTxKind::New(tx.into())
+            }
+            Err(SubmitTxError::Internal(err)) => return Err(ProcessingError::Fatal(err)),
+            Err(err) => {
+                tracing::info!(
+                    "Rejected transaction {hash:?} with sequence number {sequence}: {err}"
+                );
+                METRICS.rejected_txs[&err.prom_error_code()].inc();
+                ReceivedTxKind::Invalid(err.to_string())
+            }
+        };
+        Ok(ReceivedTx { sequence, kind })
+    }
+
+    /// Applies the same intake policies as for transactions submitted via the API.
+    async fn check_intake_policy(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        self.intake_policy.ensure_not_paused().await?;
+        self.intake_policy.ensure_fee_above_floor(tx).await
+    }
+}
+
+#[derive(Debug)]
+enum ProcessingError {
+    /// Error receiving transactions from the shared sequencer; the feed should reconnect.
+    Stream(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+#[derive(Debug)]
+struct TxsAcker<'a> {
+    client: &'a dyn SharedSequencerClient,
+    pool: &'a ConnectionPool,
+    ack_interval: Duration,
+    acks_receiver: mpsc::UnboundedReceiver<MiniblockAck>,
+}
+
+impl TxsAcker<'_> {
+    async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut pending_acks = VecDeque::new();
+        // Acknowledgement that wasn't sent to the shared sequencer yet.
+        let mut unsent_ack: Option<OrderedTxsAck> = None;
+        while !*stop_receiver.borrow() {
+            while let Ok(ack) = self.acks_receiver.try_recv() {
+                pending_acks.push_back(ack);
+            }
+            if let Some(ack) = self.take_persisted_acks(&mut pending_acks).await? {
+                match &mut unsent_ack {
+                    Some(unsent_ack) => unsent_ack.merge(ack),
+                    None => unsent_ack = Some(ack),
+                }
+            }
+
+            if let Some(ack) = unsent_ack.take() {
+                let sequence = ack.sequence;
+                match self.client.ack(ack.clone()).await {
+                    Ok(()) => {
+                        tracing::debug!("Acknowledged shared sequencer transactions up to sequence number {sequence}");
+                        METRICS.last_acked_sequence.set(sequence);
+                    }
+                    Err(err) => {
+                        tracing::warn!("Failed acknowledging shared sequencer transactions up to sequence number {sequence}: {err:#}");
+                        METRICS.ack_errors.inc();
+                        unsent_ack = Some(ack);
+                    }
+                }
+            }
+
+            tokio::time::timeout(self.ack_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// Merges acknowledgements for miniblocks already persisted to Postgres.
+    async fn take_persisted_acks(
+        &self,
+        pending_acks: &mut VecDeque<MiniblockAck>,
+    ) -> anyhow::Result<Option<OrderedTxsAck>> {
+        if pending_acks.is_empty() {
+            return Ok(None);
+        }
+        let mut storage = self.pool.access_storage_tagged("shared_sequencer").await?;
+        let sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("get_sealed_miniblock_number()")?;
+        drop(storage);
+        let Some(sealed_miniblock) = sealed_miniblock else {
+            return Ok(None);
+        };
+
+        let mut merged_ack: Option<OrderedTxsAck> = None;
+        while let Some(pending) = pending_acks.front() {
+            if pending.miniblock > sealed_miniblock {
+                break;
+            }
+            let ack = pending_acks.pop_front().unwrap().ack;
+            match &mut merged_ack {
+                Some(merged_ack) => merged_ack.merge(ack),
+                None => merged_ack = Some(ack),
+            }
+        }
+        Ok(merged_ack)
+    }
+}
+
+/// Source of transactions received from the shared sequencer used by the state keeper I/O.
+#[derive(Debug)]
+pub struct SharedSequencerSource {
+    txs_receiver: mpsc::Receiver<ReceivedTx>,
+    acks_sender: mpsc::UnboundedSender<MiniblockAck>,
+    disconnected_since: watch::Receiver<Option<Instant>>,
+    fallback_timeout: Duration,
+    /// Transactions rolled back by the state keeper, which should be executed before any newly received ones.
+    returned_txs: VecDeque<(u64, Transaction)>,
+    /// Sequence numbers of transactions executed in the current miniblock keyed by the transaction hash.
+    executed_txs: HashMap<H256, u64>,
+    /// Sequence numbers of all transactions processed in the current miniblock, in the processing order.
+    processed_sequences: Vec<u64>,
+    rejected_txs: Vec<(u64, String)>,
+}
+
+impl SharedSequencerSource {
+    /// Returns the next transaction received from the shared sequencer, if any.
+    pub(crate) fn next_tx(&mut self) -> Option<Transaction> {
+        if let Some((sequence, tx)) = self.returned_txs.pop_front() {
+            return Some(self.start_executing(sequence, tx));
+        }
+
+        while let Ok(received_tx) = self.txs_receiver.try_recv() {
+            let sequence = received_tx.sequence;
+            match received_tx.kind {
+                ReceivedTxKind::New(tx) => return Some(self.start_executing(sequence, tx)),
+                ReceivedTxKind::AlreadyExecuted => {
+                    self.processed_sequences.push(sequence);
+                }
+                ReceivedTxKind::Invalid(reason) => {
+                    self.processed_sequences.push(sequence);
+                    self.rejected_txs.push((sequence, reason));
+                }
+            }
+        }
+        None
+    }
+
+    fn start_executing(&mut self, sequence: u64, tx: Transaction) -> Transaction {
+        self.executed_txs.insert(tx.hash(), sequence);
+        self.processed_sequences.push(sequence);
+        tx
+    }
+
+    /// Checks whether the state keeper should take L2 transactions from the local mempool.
+    pub(crate) fn should_fall_back(&self) -> bool {
+        let is_fallback = self
+            .disconnected_since
+            .borrow()
+            .map_or(false, |since| since.elapsed() >= self.fallback_timeout);
+        METRICS.fallback.set(is_fallback.into());
+        is_fallback
+    }
+
+    /// Returns the rolled back transaction to the source so that it's executed again. Returns the transaction back
+    /// if it wasn't received from the shared sequencer.
+    pub(crate) fn rollback(&mut self, tx: Transaction) -> Option<Transaction> {
+        let Some(sequence) = self.executed_txs.remove(&tx.hash()) else {
+            return Some(tx);
+        };
+        self.processed_sequences
+            .retain(|&processed| processed != sequence);
+        self.returned_txs.push_front((sequence, tx));
+        None
+    }
+
+    /// Records a rejected transaction. Returns `false` if the transaction wasn't received from the shared sequencer.
+    pub(crate) fn reject(&mut self, tx_hash: H256, reason: &str) -> bool {
+        let Some(sequence) = self.executed_txs.remove(&tx_hash) else {
+            return false;
+        };
+        self.rejected_txs.push((sequence, reason.to_owned()));
+        true
+    }
+
+    /// Schedules acknowledging transactions processed in the sealed miniblock once it's persisted.
+    pub(crate) fn seal_miniblock(&mut self, miniblock: MiniblockNumber) {
+        self.executed_txs.clear();
+        let Some(&sequence) = self.processed_sequences.iter().max() else {
+            return;
+        };
+        self.processed_sequences.clear();
+        let ack = OrderedTxsAck {
+            sequence,
+            rejected: std::mem::take(&mut self.rejected_txs),
+        };
+        // The feed may have stopped; in this case, the acknowledgement will be dropped, and the transactions
+        // will be re-streamed on restart.
+        self.acks_sender.send(MiniblockAck { miniblock, ack }).ok();
+    }
+}
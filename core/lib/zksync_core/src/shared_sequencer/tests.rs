@@ -0,0 +1,571 @@
+//! Tests for the shared sequencer integration.
+
+use std::sync::Mutex;
+
+use futures::stream;
+use test_casing::test_casing;
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    api,
+    fee_model::{FeeModelConfigV1, FeeParams, FeeParamsV1},
+    Address, L2ChainId, PackedEthSignature,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, execute_l2_transaction, MockBatchFeeParamsProvider,
+    },
+};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+fn test_config() -> SharedSequencerConfig {
+    SharedSequencerConfig {
+        url: String::new(),
+        fallback_timeout_ms: 60_000,
+        reconnect_interval_ms: 10,
+        ack_interval_ms: 10,
+        max_buffered_txs: 10,
+    }
+}
+
+fn test_replay_protection() -> ReplayProtection {
+    ReplayProtection::new(L2ChainId::default())
+}
+
+fn test_intake_policy(pool: &ConnectionPool) -> TxIntakePolicy {
+    let fee_input_provider = Arc::new(MockBatchFeeParamsProvider::default());
+    TxIntakePolicy::new(pool.clone(), fee_input_provider)
+}
+
+/// Creates a signed legacy L2 transaction as accepted by `eth_sendRawTransaction`.
+fn raw_transaction(nonce: u32) -> (Vec<u8>, H256) {
+    signed_legacy_transaction(nonce, L2ChainId::default().as_u64())
+}
+
+/// Signs a legacy transaction with the specified chain ID; 0 means no replay protection.
+fn signed_legacy_transaction(nonce: u32, chain_id: u64) -> (Vec<u8>, H256) {
+    let private_key = H256::repeat_byte(11);
+    let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+    let tx_request = api::TransactionRequest {
+        nonce: nonce.into(),
+        chain_id: (chain_id != 0).then_some(chain_id),
+        from: Some(address),
+        to: Some(Address::repeat_byte(2)),
+        value: 123_456.into(),
+        gas: 1_000_000.into(),
+        gas_price: 250_000_000.into(),
+        ..api::TransactionRequest::default()
+    };
+    let mut rlp = Default::default();
+    tx_request.rlp(&mut rlp, chain_id, None);
+    let signed_message = PackedEthSignature::message_to_signed_bytes(&rlp.out());
+    let signature = PackedEthSignature::sign_raw(&private_key, &signed_message).unwrap();
+
+    let mut rlp = Default::default();
+    tx_request.rlp(&mut rlp, chain_id, Some(&signature));
+    let data = rlp.out().to_vec();
+    let (_, hash) = api::TransactionRequest::from_bytes(&data, L2ChainId::default()).unwrap();
+    (data, hash)
+}
+
+#[derive(Debug, Default)]
+struct MockSharedSequencerClient {
+    txs: Vec<OrderedTx>,
+    /// If set, the first stream is interrupted after the specified number of transactions.
+    interrupt_after: Option<usize>,
+    subscriptions: Mutex<Vec<Option<u64>>>,
+    acks: Mutex<Vec<OrderedTxsAck>>,
+}
+
+impl MockSharedSequencerClient {
+    fn new(txs: Vec<OrderedTx>) -> Self {
+        Self {
+            txs,
+            ..Self::default()
+        }
+    }
+
+    fn acks(&self) -> Vec<OrderedTxsAck> {
+        self.acks.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl SharedSequencerClient for MockSharedSequencerClient {
+    async fn subscribe(
+        &self,
+        after_sequence: Option<u64>,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<OrderedTx>>> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let is_first_subscription = subscriptions.is_empty();
+        subscriptions.push(after_sequence);
+
+        let after_sequence = after_sequence.or_else(|| self.acks().last().map(|ack| ack.sequence));
+        let txs = self
+            .txs
+            .iter()
+            .filter(|tx| after_sequence.map_or(true, |after| tx.sequence > after))
+            .cloned();
+        Ok(match self.interrupt_after {
+            Some(count) if is_first_subscription => {
+                let interruption = anyhow::anyhow!("connection reset");
+                let items = txs.take(count).map(Ok).chain([Err(interruption)]);
+                stream::iter(items.collect::<Vec<_>>()).boxed()
+            }
+            _ => {
+                let items: Vec<_> = txs.map(Ok).collect();
+                stream::iter(items).chain(stream::pending()).boxed()
+            }
+        })
+    }
+
+    async fn ack(&self, ack: OrderedTxsAck) -> anyhow::Result<()> {
+        self.acks.lock().unwrap().push(ack);
+        Ok(())
+    }
+}
+
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+}
+
+async fn store_miniblock(storage: &mut StorageProcessor<'_>, number: u32, txs: &[Transaction]) {
+    let miniblock_number = MiniblockNumber(number);
+    let tx_results: Vec<_> = txs
+        .iter()
+        .map(|tx| execute_l2_transaction(tx.clone().try_into().unwrap()))
+        .collect();
+    for result in &tx_results {
+        let l2_tx = result.transaction.clone().try_into().unwrap();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(l2_tx, Default::default())
+            .await;
+    }
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(miniblock_number, &tx_results, 1.into())
+        .await;
+}
+
+async fn wait_for_txs(source: &mut SharedSequencerSource, count: usize) -> Vec<Transaction> {
+    let started_at = Instant::now();
+    let mut txs = vec![];
+    while txs.len() < count {
+        assert!(
+            started_at.elapsed() < TEST_TIMEOUT,
+            "Timed out waiting for transactions"
+        );
+        match source.next_tx() {
+            Some(tx) => txs.push(tx),
+            None => tokio::time::sleep(POLL_INTERVAL).await,
+        }
+    }
+    txs
+}
+
+async fn wait_for_acks(client: &MockSharedSequencerClient, count: usize) -> Vec<OrderedTxsAck> {
+    let started_at = Instant::now();
+    loop {
+        let acks = client.acks();
+        if acks.len() >= count {
+            return acks;
+        }
+        assert!(
+            started_at.elapsed() < TEST_TIMEOUT,
+            "Timed out waiting for acknowledgements"
+        );
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[tokio::test]
+async fn feed_delivers_txs_and_acks_persisted_miniblocks() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+
+    let (first_tx, first_hash) = raw_transaction(0);
+    let (second_tx, second_hash) = raw_transaction(1);
+    let txs = vec![
+        OrderedTx {
+            sequence: 1,
+            raw_tx: first_tx,
+        },
+        OrderedTx {
+            sequence: 3,
+            raw_tx: vec![1, 2, 3],
+        },
+        OrderedTx {
+            sequence: 5,
+            raw_tx: second_tx,
+        },
+    ];
+    let client = Arc::new(MockSharedSequencerClient::new(txs));
+    let (feed, mut source) = shared_sequencer_feed(
+        &test_config(),
+        client.clone(),
+        pool.clone(),
+        test_replay_protection(),
+        test_intake_policy(&pool),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let feed_task = tokio::spawn(feed.run(stop_receiver));
+
+    let txs = wait_for_txs(&mut source, 2).await;
+    let tx_hashes: Vec<_> = txs.iter().map(Transaction::hash).collect();
+    assert_eq!(tx_hashes, [first_hash, second_hash]);
+    assert!(!source.should_fall_back());
+
+    source.seal_miniblock(MiniblockNumber(1));
+    tokio::time::sleep(POLL_INTERVAL * 5).await;
+    // The miniblock is not persisted yet.
+    assert!(client.acks().is_empty());
+
+    let mut storage = pool.access_storage().await.unwrap();
+    store_miniblock(&mut storage, 1, &txs).await;
+    let acks = wait_for_acks(&client, 1).await;
+    assert_eq!(acks.len(), 1);
+    assert_eq!(acks[0].sequence, 5);
+    let rejected_sequences: Vec<_> = acks[0].rejected.iter().map(|(seq, _)| *seq).collect();
+    assert_eq!(rejected_sequences, [3]);
+
+    stop_sender.send_replace(true);
+    feed_task.await.unwrap().unwrap();
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn feed_applies_replay_protection(allow_unprotected_txs: bool) {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+
+    let (unprotected_tx, unprotected_hash) = signed_legacy_transaction(0, 0);
+    let (protected_tx, protected_hash) = raw_transaction(1);
+    let txs = vec![
+        OrderedTx {
+            sequence: 1,
+            raw_tx: unprotected_tx,
+        },
+        OrderedTx {
+            sequence: 2,
+            raw_tx: protected_tx,
+        },
+    ];
+    let client = Arc::new(MockSharedSequencerClient::new(txs));
+    let replay_protection =
+        test_replay_protection().with_pre_eip155_txs(allow_unprotected_txs, vec![]);
+    let (feed, mut source) = shared_sequencer_feed(
+        &test_config(),
+        client.clone(),
+        pool.clone(),
+        replay_protection,
+        test_intake_policy(&pool),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let feed_task = tokio::spawn(feed.run(stop_receiver));
+
+    let expected_hashes = if allow_unprotected_txs {
+        vec![unprotected_hash, protected_hash]
+    } else {
+        vec![protected_hash]
+    };
+    let txs = wait_for_txs(&mut source, expected_hashes.len()).await;
+    let tx_hashes: Vec<_> = txs.iter().map(Transaction::hash).collect();
+    assert_eq!(tx_hashes, expected_hashes);
+
+    source.seal_miniblock(MiniblockNumber(1));
+    let mut storage = pool.access_storage().await.unwrap();
+    store_miniblock(&mut storage, 1, &txs).await;
+    let acks = wait_for_acks(&client, 1).await;
+    assert_eq!(acks[0].sequence, 2);
+    let rejected_sequences: Vec<_> = acks[0].rejected.iter().map(|(seq, _)| *seq).collect();
+    if allow_unprotected_txs {
+        assert!(rejected_sequences.is_empty());
+    } else {
+        assert_eq!(rejected_sequences, [1]);
+    }
+
+    stop_sender.send_replace(true);
+    feed_task.await.unwrap().unwrap();
+}
+
+#[derive(Debug, Clone, Copy)]
+enum IntakeRejection {
+    Paused,
+    LowFee,
+}
+
+#[test_casing(2, [IntakeRejection::Paused, IntakeRejection::LowFee])]
+#[tokio::test]
+async fn feed_applies_intake_policies(rejection: IntakeRejection) {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut intake_policy = test_intake_policy(&pool);
+    match rejection {
+        IntakeRejection::Paused => {
+            let mut storage = pool.access_storage().await.unwrap();
+            storage
+                .tx_intake_dal()
+                .pause_intake("maintenance")
+                .await
+                .unwrap();
+        }
+        IntakeRejection::LowFee => {
+            // The fee floor is above the gas price of test transactions.
+            let fee_params = FeeParams::V1(FeeParamsV1 {
+                config: FeeModelConfigV1 {
+                    minimal_l2_gas_price: 1_000_000_000,
+                },
+                l1_gas_price: 1_000_000_000,
+            });
+            let fee_input_provider = Arc::new(MockBatchFeeParamsProvider(fee_params));
+            intake_policy = TxIntakePolicy::new(pool.clone(), fee_input_provider);
+        }
+    }
+
+    let txs = (0..2)
+        .map(|nonce| OrderedTx {
+            sequence: u64::from(nonce) + 1,
+            raw_tx: raw_transaction(nonce).0,
+        })
+        .collect();
+    let client = Arc::new(MockSharedSequencerClient::new(txs));
+    let (feed, mut source) = shared_sequencer_feed(
+        &test_config(),
+        client.clone(),
+        pool.clone(),
+        test_replay_protection(),
+        intake_policy,
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let feed_task = tokio::spawn(feed.run(stop_receiver));
+
+    // Rejected transactions are acknowledged in the next miniblock without being executed.
+    let started_at = Instant::now();
+    while source.processed_sequences.len() < 2 {
+        assert!(
+            started_at.elapsed() < TEST_TIMEOUT,
+            "Timed out waiting for transactions"
+        );
+        assert!(source.next_tx().is_none());
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    source.seal_miniblock(MiniblockNumber(1));
+    let mut storage = pool.access_storage().await.unwrap();
+    store_miniblock(&mut storage, 1, &[]).await;
+    let acks = wait_for_acks(&client, 1).await;
+    assert_eq!(acks[0].sequence, 2);
+    let rejected_sequences: Vec<_> = acks[0].rejected.iter().map(|(seq, _)| *seq).collect();
+    assert_eq!(rejected_sequences, [1, 2]);
+
+    stop_sender.send_replace(true);
+    feed_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn feed_resumes_interrupted_stream() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+
+    let raw_txs: Vec<_> = (0..3).map(raw_transaction).collect();
+    let txs = raw_txs
+        .iter()
+        .zip(1..)
+        .map(|((raw_tx, _), sequence)| OrderedTx {
+            sequence,
+            raw_tx: raw_tx.clone(),
+        })
+        .collect();
+    let client = Arc::new(MockSharedSequencerClient {
+        interrupt_after: Some(2),
+        ..MockSharedSequencerClient::new(txs)
+    });
+    let (feed, mut source) = shared_sequencer_feed(
+        &test_config(),
+        client.clone(),
+        pool.clone(),
+        test_replay_protection(),
+        test_intake_policy(&pool),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let feed_task = tokio::spawn(feed.run(stop_receiver));
+
+    let txs = wait_for_txs(&mut source, 3).await;
+    let tx_hashes: Vec<_> = txs.iter().map(Transaction::hash).collect();
+    let expected_hashes: Vec<_> = raw_txs.iter().map(|(_, hash)| *hash).collect();
+    assert_eq!(tx_hashes, expected_hashes);
+    // Transactions received before the interruption must not be re-streamed.
+    let subscriptions = client.subscriptions.lock().unwrap().clone();
+    assert_eq!(subscriptions, [None, Some(2)]);
+    tokio::time::sleep(POLL_INTERVAL * 5).await;
+    assert!(source.next_tx().is_none());
+
+    stop_sender.send_replace(true);
+    feed_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn feed_skips_already_executed_txs() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+
+    let (raw_tx, _) = raw_transaction(0);
+    let txs = vec![OrderedTx {
+        sequence: 1,
+        raw_tx: raw_tx.clone(),
+    }];
+    let client = Arc::new(MockSharedSequencerClient::new(txs.clone()));
+    let (feed, mut source) = shared_sequencer_feed(
+        &test_config(),
+        client.clone(),
+        pool.clone(),
+        test_replay_protection(),
+        test_intake_policy(&pool),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let feed_task = tokio::spawn(feed.run(stop_receiver));
+    let executed_txs = wait_for_txs(&mut source, 1).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    store_miniblock(&mut storage, 1, &executed_txs).await;
+    stop_sender.send_replace(true);
+    feed_task.await.unwrap().unwrap();
+
+    // Emulate a restart before the transaction was acknowledged.
+    let client = Arc::new(MockSharedSequencerClient::new(txs));
+    let (feed, mut source) = shared_sequencer_feed(
+        &test_config(),
+        client.clone(),
+        pool.clone(),
+        test_replay_protection(),
+        test_intake_policy(&pool),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let feed_task = tokio::spawn(feed.run(stop_receiver));
+    let started_at = Instant::now();
+    while source.processed_sequences.is_empty() {
+        assert!(
+            started_at.elapsed() < TEST_TIMEOUT,
+            "Timed out waiting for transactions"
+        );
+        assert!(source.next_tx().is_none());
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    source.seal_miniblock(MiniblockNumber(1));
+    let acks = wait_for_acks(&client, 1).await;
+    assert_eq!(
+        acks[0],
+        OrderedTxsAck {
+            sequence: 1,
+            rejected: vec![],
+        }
+    );
+
+    stop_sender.send_replace(true);
+    feed_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn source_handles_rollbacks_and_rejections() {
+    let pool = ConnectionPool::test_pool().await;
+    let (mut feed, mut source) = shared_sequencer_feed(
+        &test_config(),
+        Arc::new(MockSharedSequencerClient::default()),
+        pool.clone(),
+        test_replay_protection(),
+        test_intake_policy(&pool),
+    );
+    let txs: Vec<Transaction> = (0..3)
+        .map(|nonce| {
+            let (raw_tx, _) = raw_transaction(nonce);
+            let (request, hash) =
+                api::TransactionRequest::from_bytes(&raw_tx, L2ChainId::default()).unwrap();
+            let mut tx = L2Tx::from_request(request, MAX_TX_SIZE).unwrap();
+            tx.set_input(raw_tx, hash);
+            tx.into()
+        })
+        .collect();
+    for (tx, sequence) in txs.iter().zip([2, 4, 6]) {
+        let received_tx = ReceivedTx {
+            sequence,
+            kind: ReceivedTxKind::New(tx.clone()),
+        };
+        feed.txs_sender.send(received_tx).await.unwrap();
+    }
+
+    let first_tx = source.next_tx().unwrap();
+    assert_eq!(first_tx.hash(), txs[0].hash());
+    let second_tx = source.next_tx().unwrap();
+    assert_eq!(second_tx.hash(), txs[1].hash());
+    // Transactions not received from the shared sequencer are returned back.
+    let foreign_tx: Transaction = create_l2_transaction(10, 100).into();
+    assert!(source.rollback(foreign_tx.clone()).is_some());
+    assert!(!source.reject(foreign_tx.hash(), "error"));
+
+    assert!(source.rollback(second_tx).is_none());
+    source.seal_miniblock(MiniblockNumber(1));
+    let MiniblockAck { miniblock, ack } = feed.acks_receiver.try_recv().unwrap();
+    assert_eq!(miniblock, MiniblockNumber(1));
+    assert_eq!(
+        ack,
+        OrderedTxsAck {
+            sequence: 2,
+            rejected: vec![],
+        }
+    );
+
+    // The rolled back transaction must be returned first.
+    let second_tx = source.next_tx().unwrap();
+    assert_eq!(second_tx.hash(), txs[1].hash());
+    assert!(source.reject(second_tx.hash(), "rejected: oops"));
+    let third_tx = source.next_tx().unwrap();
+    assert_eq!(third_tx.hash(), txs[2].hash());
+    assert!(source.next_tx().is_none());
+    source.seal_miniblock(MiniblockNumber(2));
+    let MiniblockAck { miniblock, ack } = feed.acks_receiver.try_recv().unwrap();
+    assert_eq!(miniblock, MiniblockNumber(2));
+    assert_eq!(
+        ack,
+        OrderedTxsAck {
+            sequence: 6,
+            rejected: vec![(4, "rejected: oops".to_owned())],
+        }
+    );
+
+    // Empty miniblocks are not acknowledged.
+    source.seal_miniblock(MiniblockNumber(3));
+    assert!(feed.acks_receiver.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn source_falls_back_to_mempool_on_disconnection() {
+    let pool = ConnectionPool::test_pool().await;
+    let config = SharedSequencerConfig {
+        fallback_timeout_ms: 0,
+        ..test_config()
+    };
+    let (feed, source) = shared_sequencer_feed(
+        &config,
+        Arc::new(MockSharedSequencerClient::default()),
+        pool.clone(),
+        test_replay_protection(),
+        test_intake_policy(&pool),
+    );
+    assert!(source.should_fall_back());
+    feed.disconnected_since.send_replace(None);
+    assert!(!source.should_fall_back());
+    feed.disconnected_since.send_replace(Some(Instant::now()));
+    assert!(source.should_fall_back());
+}
@@ -0,0 +1,231 @@
+//! Price feeds used by the stable gas price updater.
+
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_config::{configs::stable_gas_price::PriceFeedSource, StableGasPriceConfig};
+use zksync_eth_client::{clients::QueryClient, EthInterface};
+use zksync_types::{
+    ethabi::{self, ParamType, Token},
+    web3::types::{Bytes, CallRequest},
+    Address, U256,
+};
+
+const COMPONENT: &str = "stable_gas_price";
+
+/// Source of the base token price.
+#[async_trait]
+pub trait PriceFeed: fmt::Debug + Send + Sync {
+    /// Returns the price of a single base token (i.e., 10^18 of its smallest units) in the fiat currency.
+    async fn price(&self) -> anyhow::Result<f64>;
+}
+
+/// Creates a price feed for the specified config.
+pub fn create_price_feed(
+    config: &StableGasPriceConfig,
+    web3_url: &str,
+) -> anyhow::Result<Box<dyn PriceFeed>> {
+    Ok(match config.source {
+        PriceFeedSource::Http => {
+            let url = config
+                .feed_url
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("`feed_url` must be set for the HTTP price feed"))?;
+            Box::new(HttpPriceFeed::new(url, config.feed_json_pointer.clone()))
+        }
+        PriceFeedSource::OnChain => {
+            let contract_addr = config.feed_contract_addr.ok_or_else(|| {
+                anyhow::anyhow!("`feed_contract_addr` must be set for the on-chain price feed")
+            })?;
+            let client = QueryClient::new(web3_url)?;
+            Box::new(OnChainPriceFeed::new(
+                Box::new(client),
+                contract_addr,
+                config.max_price_age(),
+            ))
+        }
+    })
+}
+
+/// Price feed querying an HTTP oracle. The oracle must respond to `GET` requests with a JSON document containing
+/// the price at the configured JSON pointer, e.g. `{ "price": 3000.5 }` for the default `/price` pointer.
+#[derive(Debug)]
+pub struct HttpPriceFeed {
+    client: reqwest::Client,
+    url: String,
+    json_pointer: String,
+}
+
+impl HttpPriceFeed {
+    pub fn new(url: String, json_pointer: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            json_pointer,
+        }
+    }
+}
+
+/// Extracts the price from the oracle response.
+fn parse_http_price(response: &serde_json::Value, json_pointer: &str) -> anyhow::Result<f64> {
+    let value = response
+        .pointer(json_pointer)
+        .with_context(|| format!("no value at `{json_pointer}` in the price feed response"))?;
+    let price = match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    price.with_context(|| format!("unexpected price in the price feed response: {value}"))
+}
+
+#[async_trait]
+impl PriceFeed for HttpPriceFeed {
+    async fn price(&self) -> anyhow::Result<f64> {
+        let response: serde_json::Value = self
+            .client
+            .get(&self.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        parse_http_price(&response, &self.json_pointer)
+    }
+}
+
+/// Price feed reading the latest answer of a Chainlink-compatible aggregator contract on the settlement layer.
+#[derive(Debug)]
+pub struct OnChainPriceFeed {
+    client: Box<dyn EthInterface>,
+    contract_addr: Address,
+    max_price_age: Duration,
+}
+
+impl OnChainPriceFeed {
+    pub fn new(
+        client: Box<dyn EthInterface>,
+        contract_addr: Address,
+        max_price_age: Duration,
+    ) -> Self {
+        Self {
+            client,
+            contract_addr,
+            max_price_age,
+        }
+    }
+
+    async fn call(&self, function: &str, output: &[ParamType]) -> anyhow::Result<Vec<Token>> {
+        let request = CallRequest {
+            to: Some(self.contract_addr),
+            data: Some(Bytes(ethabi::short_signature(function, &[]).to_vec())),
+            ..CallRequest::default()
+        };
+        let response = self.client.call(request, None, COMPONENT).await?;
+        ethabi::decode(output, &response.0)
+            .with_context(|| format!("failed decoding `{function}` output"))
+    }
+}
+
+/// Converts the `latestRoundData` answer and update timestamp to the price, checking its freshness.
+fn parse_round_data(
+    answer: U256,
+    updated_at: U256,
+    decimals: u8,
+    max_price_age: Duration,
+) -> anyhow::Result<f64> {
+    // `answer` is a signed integer; negative answers have the most significant bit set.
+    anyhow::ensure!(
+        !answer.is_zero() && !answer.bit(255),
+        "aggregator returned a non-positive answer"
+    );
+    anyhow::ensure!(
+        updated_at <= U256::from(u64::MAX),
+        "aggregator returned an invalid update timestamp"
+    );
+    let updated_at = UNIX_EPOCH + Duration::from_secs(updated_at.as_u64());
+    let age = SystemTime::now()
+        .duration_since(updated_at)
+        .unwrap_or_default();
+    anyhow::ensure!(
+        age <= max_price_age,
+        "aggregator answer is stale: updated {age:?} ago, while the max age is {max_price_age:?}"
+    );
+
+    // `answer` is positive, so its low 128 bits contain it fully for any sensible price.
+    let answer = answer.low_u128() as f64;
+    Ok(answer / 10_f64.powi(decimals.into()))
+}
+
+#[async_trait]
+impl PriceFeed for OnChainPriceFeed {
+    async fn price(&self) -> anyhow::Result<f64> {
+        let decimals = self.call("decimals", &[ParamType::Uint(8)]).await?;
+        let decimals = decimals
+            .into_iter()
+            .next()
+            .and_then(Token::into_uint)
+            .context("unexpected `decimals` output")?;
+        let decimals = u8::try_from(decimals).map_err(|_| anyhow::anyhow!("invalid decimals"))?;
+
+        let round_data = self
+            .call(
+                "latestRoundData",
+                &[
+                    ParamType::Uint(80),
+                    ParamType::Int(256),
+                    ParamType::Uint(256),
+                    ParamType::Uint(256),
+                    ParamType::Uint(80),
+                ],
+            )
+            .await?;
+        let [_, Token::Int(answer), _, Token::Uint(updated_at), _] = round_data.as_slice() else {
+            anyhow::bail!("unexpected `latestRoundData` output: {round_data:?}");
+        };
+        parse_round_data(*answer, *updated_at, decimals, self.max_price_age)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_http_price() {
+        let response = serde_json::json!({ "price": 3000.5 });
+        assert_eq!(parse_http_price(&response, "/price").unwrap(), 3000.5);
+        let response = serde_json::json!({ "ethereum": { "usd": "2999.25" } });
+        assert_eq!(
+            parse_http_price(&response, "/ethereum/usd").unwrap(),
+            2999.25
+        );
+
+        let err = parse_http_price(&response, "/price").unwrap_err();
+        assert!(err.to_string().contains("no value"), "{err}");
+        let response = serde_json::json!({ "price": null });
+        parse_http_price(&response, "/price").unwrap_err();
+    }
+
+    #[test]
+    fn parsing_round_data() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let max_age = Duration::from_secs(3_600);
+        let price = parse_round_data(300_050_000_000_u64.into(), now.into(), 8, max_age).unwrap();
+        assert_eq!(price, 3000.5);
+
+        let err = parse_round_data(U256::zero(), now.into(), 8, max_age).unwrap_err();
+        assert!(err.to_string().contains("non-positive"), "{err}");
+        let negative_answer = U256::MAX;
+        parse_round_data(negative_answer, now.into(), 8, max_age).unwrap_err();
+        let err = parse_round_data(1.into(), (now - 7_200).into(), 8, max_age).unwrap_err();
+        assert!(err.to_string().contains("stale"), "{err}");
+    }
+}
@@ -0,0 +1,18 @@
+use vise::{Counter, Gauge, Metrics};
+
+/// Metrics for the stable gas price updater.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_stable_gas_price")]
+pub(super) struct StableGasPriceMetrics {
+    /// Latest price of the base token in the fiat currency reported by the price feed.
+    pub base_token_price: Gauge<f64>,
+    /// L2 gas price in wei at which the reference transaction costs exactly the target.
+    pub target_l2_gas_price: Gauge<u64>,
+    /// L2 gas price in wei applied by the fee model after smoothing and bounding.
+    pub l2_gas_price: Gauge<u64>,
+    /// Number of failed price feed queries.
+    pub feed_errors: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<StableGasPriceMetrics> = vise::Global::new();
@@ -0,0 +1,155 @@
+//! Stable gas pricing mode: adjusts the L2 gas price so that the cost of a reference transaction stays near
+//! the target in a fiat currency.
+//!
+//! The updater periodically queries the price of the base token from the configured price feed, and computes
+//! the target L2 gas price at which the reference transaction costs exactly `target_tx_cost`. To not pass feed noise
+//! to users and to avoid oscillation, the applied price is the exponential moving average of target prices, its change
+//! per update is limited, and it is clamped to the configured bounds. If the feed is unavailable, the last applied price
+//! is retained; before the first successful update, fee input providers use the price computed by the fee model.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use tokio::sync::watch;
+use zksync_config::StableGasPriceConfig;
+
+pub use self::feed::{create_price_feed, HttpPriceFeed, OnChainPriceFeed, PriceFeed};
+use self::metrics::METRICS;
+
+mod feed;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Number of the smallest base token units in a single token.
+const BASE_TOKEN_UNITS: f64 = 1e18;
+
+/// L2 gas price set by the [`StableGasPriceUpdater`]. Cloned instances share the value, so that it can be used
+/// by multiple fee input providers.
+#[derive(Debug, Clone, Default)]
+pub struct StableGasPrice(Arc<AtomicU64>);
+
+impl StableGasPrice {
+    /// Returns the applied L2 gas price in wei, or `None` if the stable pricing mode is disabled
+    /// or the price is not computed yet.
+    pub fn get(&self) -> Option<u64> {
+        let price = self.0.load(Ordering::Relaxed);
+        (price != 0).then_some(price)
+    }
+
+    fn set(&self, price: u64) {
+        self.0.store(price, Ordering::Relaxed);
+    }
+}
+
+/// Periodically updates the [`StableGasPrice`] based on the base token price reported by the price feed.
+#[derive(Debug)]
+pub struct StableGasPriceUpdater {
+    feed: Box<dyn PriceFeed>,
+    config: StableGasPriceConfig,
+    price: StableGasPrice,
+}
+
+impl StableGasPriceUpdater {
+    pub fn new(feed: Box<dyn PriceFeed>, config: StableGasPriceConfig) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            config.target_tx_cost > 0.0,
+            "target transaction cost must be positive"
+        );
+        anyhow::ensure!(
+            config.reference_tx_gas > 0,
+            "reference transaction gas must be positive"
+        );
+        anyhow::ensure!(
+            0 < config.min_l2_gas_price && config.min_l2_gas_price <= config.max_l2_gas_price,
+            "L2 gas price bounds must be positive and ordered"
+        );
+        anyhow::ensure!(
+            config.smoothing_factor > 0.0 && config.smoothing_factor <= 1.0,
+            "smoothing factor must be in (0, 1]"
+        );
+        anyhow::ensure!(
+            config.max_change_per_update > 0.0,
+            "max L2 gas price change per update must be positive"
+        );
+
+        Ok(Self {
+            feed,
+            config,
+            price: StableGasPrice::default(),
+        })
+    }
+
+    /// Returns the price updated by this updater.
+    pub fn price(&self) -> StableGasPrice {
+        self.price.clone()
+    }
+
+    /// Computes the L2 gas price at which the reference transaction costs exactly the target.
+    fn target_gas_price(&self, base_token_price: f64) -> anyhow::Result<f64> {
+        anyhow::ensure!(
+            base_token_price.is_finite() && base_token_price > 0.0,
+            "invalid base token price: {base_token_price}"
+        );
+        let tx_cost_in_tokens = self.config.target_tx_cost / base_token_price;
+        Ok(tx_cost_in_tokens * BASE_TOKEN_UNITS / self.config.reference_tx_gas as f64)
+    }
+
+    /// Computes the next applied gas price from the previous one and the target.
+    fn next_gas_price(&self, prev_price: Option<u64>, target_price: f64) -> u64 {
+        let min_price = self.config.min_l2_gas_price as f64;
+        let max_price = self.config.max_l2_gas_price as f64;
+        let target_price = target_price.clamp(min_price, max_price);
+        let Some(prev_price) = prev_price else {
+            return target_price.round() as u64;
+        };
+
+        let prev_price = prev_price as f64;
+        let smoothed_price =
+            prev_price + self.config.smoothing_factor * (target_price - prev_price);
+        let max_change = prev_price * self.config.max_change_per_update;
+        let next_price = smoothed_price
+            .clamp(prev_price - max_change, prev_price + max_change)
+            .clamp(min_price, max_price);
+        next_price.round() as u64
+    }
+
+    /// Returns the updated gas price.
+    pub(crate) async fn update(&self) -> anyhow::Result<u64> {
+        let base_token_price = self.feed.price().await?;
+        let target_price = self.target_gas_price(base_token_price)?;
+        let next_price = self.next_gas_price(self.price.get(), target_price);
+        tracing::debug!(
+            "Base token price: {base_token_price}; target L2 gas price: {target_price:.0} wei, \
+             applied L2 gas price: {next_price} wei"
+        );
+
+        METRICS.base_token_price.set(base_token_price);
+        METRICS.target_l2_gas_price.set(target_price as u64);
+        METRICS.l2_gas_price.set(next_price);
+        self.price.set(next_price);
+        Ok(next_price)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            if let Err(err) = self.update().await {
+                METRICS.feed_errors.inc();
+                tracing::warn!(
+                    "Failed updating stable gas price, retaining {:?}: {err:#}",
+                    self.price.get()
+                );
+            }
+            if tokio::time::timeout(self.config.update_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, stable gas price updater is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+//! Tests for the stable gas price updater.
+
+use std::{sync::Mutex, time::Duration};
+
+use async_trait::async_trait;
+use zksync_config::configs::stable_gas_price::PriceFeedSource;
+
+use super::*;
+
+/// Mock price feed. `None` price is reported as a feed error.
+#[derive(Debug, Clone, Default)]
+struct MockPriceFeed(Arc<Mutex<Option<f64>>>);
+
+impl MockPriceFeed {
+    fn set_price(&self, price: Option<f64>) {
+        *self.0.lock().unwrap() = price;
+    }
+}
+
+#[async_trait]
+impl PriceFeed for MockPriceFeed {
+    async fn price(&self) -> anyhow::Result<f64> {
+        self.0
+            .lock()
+            .unwrap()
+            .ok_or_else(|| anyhow::anyhow!("feed is down"))
+    }
+}
+
+fn test_config() -> StableGasPriceConfig {
+    StableGasPriceConfig {
+        source: PriceFeedSource::Http,
+        feed_url: None,
+        feed_json_pointer: "/price".to_owned(),
+        feed_contract_addr: None,
+        max_price_age_sec: 3_600,
+        update_interval_ms: 10,
+        // With the base token price of 2,000, the target L2 gas price is 0.01 / 2_000 * 1e18 / 500_000 = 10^7 wei.
+        target_tx_cost: 0.01,
+        reference_tx_gas: 500_000,
+        min_l2_gas_price: 1_000_000,
+        max_l2_gas_price: 100_000_000,
+        smoothing_factor: 0.5,
+        max_change_per_update: 0.2,
+    }
+}
+
+fn create_updater(config: StableGasPriceConfig) -> (StableGasPriceUpdater, MockPriceFeed) {
+    let feed = MockPriceFeed::default();
+    let updater = StableGasPriceUpdater::new(Box::new(feed.clone()), config).unwrap();
+    (updater, feed)
+}
+
+#[test]
+fn config_is_validated() {
+    let invalid_configs = [
+        StableGasPriceConfig {
+            target_tx_cost: 0.0,
+            ..test_config()
+        },
+        StableGasPriceConfig {
+            min_l2_gas_price: 200_000_000,
+            ..test_config()
+        },
+        StableGasPriceConfig {
+            smoothing_factor: 1.5,
+            ..test_config()
+        },
+        StableGasPriceConfig {
+            max_change_per_update: 0.0,
+            ..test_config()
+        },
+    ];
+    for config in invalid_configs {
+        let feed = Box::new(MockPriceFeed::default());
+        StableGasPriceUpdater::new(feed, config).unwrap_err();
+    }
+}
+
+#[test]
+fn computing_target_gas_price() {
+    let (updater, _) = create_updater(test_config());
+    let price = updater.target_gas_price(2_000.0).unwrap();
+    assert!((price - 10_000_000.0).abs() < 1e-3, "{price}");
+    // The target price is inversely proportional to the base token price.
+    let price = updater.target_gas_price(4_000.0).unwrap();
+    assert!((price - 5_000_000.0).abs() < 1e-3, "{price}");
+
+    updater.target_gas_price(0.0).unwrap_err();
+    updater.target_gas_price(f64::NAN).unwrap_err();
+}
+
+#[test]
+fn smoothing_and_bounding_gas_price() {
+    let (updater, _) = create_updater(test_config());
+    // The first price is applied as-is, but is bounded.
+    assert_eq!(updater.next_gas_price(None, 10_000_000.0), 10_000_000);
+    assert_eq!(updater.next_gas_price(None, 1e12), 100_000_000);
+    assert_eq!(updater.next_gas_price(None, 1.0), 1_000_000);
+
+    // Moving average with the factor 0.5.
+    assert_eq!(
+        updater.next_gas_price(Some(10_000_000), 11_000_000.0),
+        10_500_000
+    );
+    // The change is limited to 20%.
+    assert_eq!(
+        updater.next_gas_price(Some(10_000_000), 20_000_000.0),
+        12_000_000
+    );
+    assert_eq!(updater.next_gas_price(Some(10_000_000), 0.0), 8_000_000);
+    // The target price is bounded before smoothing.
+    assert_eq!(updater.next_gas_price(Some(90_000_000), 1e12), 95_000_000);
+}
+
+#[tokio::test]
+async fn updating_gas_price() {
+    let (updater, feed) = create_updater(test_config());
+    let price = updater.price();
+    assert_eq!(price.get(), None);
+
+    feed.set_price(Some(2_000.0));
+    assert_eq!(updater.update().await.unwrap(), 10_000_000);
+    assert_eq!(price.get(), Some(10_000_000));
+
+    // The base token price halves, so the target L2 gas price doubles.
+    feed.set_price(Some(1_000.0));
+    assert_eq!(updater.update().await.unwrap(), 12_000_000);
+    assert_eq!(updater.update().await.unwrap(), 14_400_000);
+    assert_eq!(price.get(), Some(14_400_000));
+
+    // The price is retained on feed errors.
+    feed.set_price(None);
+    updater.update().await.unwrap_err();
+    assert_eq!(price.get(), Some(14_400_000));
+}
+
+#[tokio::test]
+async fn updater_is_resilient_to_feed_errors() {
+    let (updater, feed) = create_updater(test_config());
+    let price = updater.price();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let updater_task = tokio::spawn(updater.run(stop_receiver));
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(price.get(), None);
+    feed.set_price(Some(2_000.0));
+    while price.get().is_none() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(price.get(), Some(10_000_000));
+
+    stop_sender.send_replace(true);
+    updater_task.await.unwrap().unwrap();
+}
@@ -0,0 +1,281 @@
+//! Health check for the state keeper.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Serialize;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
+use zksync_types::{api::idexo::PriorityOpInfo, L1BatchNumber, MiniblockNumber};
+use zksync_utils::time::seconds_since_epoch;
+
+#[derive(Debug, Serialize)]
+struct StateKeeperHealthDetails {
+    sealed_miniblock: MiniblockNumber,
+    sealed_l1_batch: Option<L1BatchNumber>,
+    /// Seconds elapsed since the timestamp of the last sealed miniblock.
+    lag_sec: u64,
+}
+
+/// Health check reporting how far the last sealed miniblock lags behind the wall clock. The check is based
+/// on Postgres data, so it works both for the main node and external nodes.
+///
+/// Miniblocks without transactions are not sealed, so the lag grows on an idle chain as well; `max_lag`
+/// should be chosen with this in mind.
+#[derive(Debug)]
+pub struct StateKeeperHealthCheck {
+    pool: ConnectionPool,
+    max_lag: Option<Duration>,
+}
+
+impl StateKeeperHealthCheck {
+    pub fn new(pool: ConnectionPool, max_lag: Option<Duration>) -> Self {
+        Self { pool, max_lag }
+    }
+
+    async fn details(&self) -> anyhow::Result<Option<StateKeeperHealthDetails>> {
+        let mut storage = self.pool.access_storage_tagged("healthcheck").await?;
+        let Some(header) = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await?
+        else {
+            return Ok(None);
+        };
+        let sealed_l1_batch = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+        Ok(Some(StateKeeperHealthDetails {
+            sealed_miniblock: header.number,
+            sealed_l1_batch,
+            lag_sec: seconds_since_epoch().saturating_sub(header.timestamp),
+        }))
+    }
+}
+
+#[async_trait]
+impl CheckHealth for StateKeeperHealthCheck {
+    fn name(&self) -> &'static str {
+        "state_keeper"
+    }
+
+    async fn check_health(&self) -> Health {
+        match self.details().await {
+            Ok(Some(details)) => {
+                let is_lagging = self
+                    .max_lag
+                    .is_some_and(|max_lag| details.lag_sec > max_lag.as_secs());
+                let status = if is_lagging {
+                    HealthStatus::Affected
+                } else {
+                    HealthStatus::Ready
+                };
+                Health::from(status).with_details(details)
+            }
+            Ok(None) => HealthStatus::NotReady.into(),
+            Err(err) => {
+                tracing::warn!("Failed checking state keeper health: {err:#}");
+                let details = serde_json::json!({
+                    "error": format!("{err:#}"),
+                });
+                Health::from(HealthStatus::NotReady).with_details(details)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ForcedInclusionHealthDetails {
+    deadline_sec: u64,
+    last_executed_l1_batch: Option<L1BatchNumber>,
+    overdue_count: u64,
+    oldest_unexecuted_op: Option<PriorityOpInfo>,
+}
+
+/// Health check failing while there are priority operations not executed on the settlement layer within
+/// the forced inclusion deadline. Unlike [`StateKeeperHealthCheck`], missing the deadline marks the node as not ready,
+/// since priority operations must not be censored by the operator.
+#[derive(Debug)]
+pub struct ForcedInclusionHealthCheck {
+    pool: ConnectionPool,
+    deadline: Duration,
+}
+
+impl ForcedInclusionHealthCheck {
+    pub fn new(pool: ConnectionPool, deadline: Duration) -> Self {
+        Self { pool, deadline }
+    }
+
+    async fn details(&self) -> anyhow::Result<ForcedInclusionHealthDetails> {
+        let mut storage = self.pool.access_storage_tagged("healthcheck").await?;
+        let last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?;
+        let overdue_threshold = Utc::now() - chrono::Duration::from_std(self.deadline)?;
+        let mut transactions_dal = storage.transactions_web3_dal();
+        let (_, overdue_count) = transactions_dal
+            .get_unexecuted_priority_ops_count(last_executed_l1_batch, overdue_threshold)
+            .await?;
+        let oldest_unexecuted_op = transactions_dal
+            .get_oldest_unexecuted_priority_op(last_executed_l1_batch)
+            .await?;
+        Ok(ForcedInclusionHealthDetails {
+            deadline_sec: self.deadline.as_secs(),
+            last_executed_l1_batch,
+            overdue_count,
+            oldest_unexecuted_op,
+        })
+    }
+}
+
+#[async_trait]
+impl CheckHealth for ForcedInclusionHealthCheck {
+    fn name(&self) -> &'static str {
+        "forced_inclusion"
+    }
+
+    async fn check_health(&self) -> Health {
+        match self.details().await {
+            Ok(details) => {
+                let status = if details.overdue_count > 0 {
+                    HealthStatus::NotReady
+                } else {
+                    HealthStatus::Ready
+                };
+                Health::from(status).with_details(details)
+            }
+            Err(err) => {
+                tracing::warn!("Failed checking forced inclusion health: {err:#}");
+                let details = serde_json::json!({
+                    "error": format!("{err:#}"),
+                });
+                Health::from(HealthStatus::NotReady).with_details(details)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType,
+        l1::{L1Tx, L1TxCommonData},
+        tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
+        Execute, L1BlockNumber, L2ChainId, PriorityOpId, H256,
+    };
+
+    use super::*;
+    use crate::{
+        genesis::{ensure_genesis_state, GenesisParams},
+        utils::testonly::{create_l1_batch, create_miniblock},
+    };
+
+    #[tokio::test]
+    async fn checking_state_keeper_lag() {
+        let pool = ConnectionPool::test_pool().await;
+        let health_check = StateKeeperHealthCheck::new(pool.clone(), Some(Duration::from_secs(60)));
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::NotReady);
+
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        let mut miniblock = create_miniblock(1);
+        miniblock.timestamp = seconds_since_epoch() - 120;
+        storage
+            .blocks_dal()
+            .insert_miniblock(&miniblock)
+            .await
+            .unwrap();
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Affected);
+
+        let mut miniblock = create_miniblock(2);
+        miniblock.timestamp = seconds_since_epoch();
+        storage
+            .blocks_dal()
+            .insert_miniblock(&miniblock)
+            .await
+            .unwrap();
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+    }
+
+    #[tokio::test]
+    async fn checking_forced_inclusion() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        let health_check = ForcedInclusionHealthCheck::new(pool.clone(), Duration::from_secs(60));
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+
+        // The operation is received long ago, so it's overdue.
+        let l1_tx = L1Tx {
+            execute: Execute::default(),
+            common_data: L1TxCommonData {
+                serial_id: PriorityOpId(0),
+                gas_limit: 100_000.into(),
+                canonical_tx_hash: H256::from_low_u64_be(1),
+                ..L1TxCommonData::default()
+            },
+            received_timestamp_ms: 0,
+        };
+        storage
+            .transactions_dal()
+            .insert_transaction_l1(l1_tx.clone(), L1BlockNumber(10))
+            .await;
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::NotReady);
+
+        // Including the operation into an L1 batch isn't enough; the batch must be executed on the settlement layer.
+        let executed_tx = TransactionExecutionResult {
+            hash: l1_tx.hash(),
+            transaction: l1_tx.into(),
+            execution_info: ExecutionMetrics::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            compressed_bytecodes: vec![],
+            call_traces: vec![],
+            revert_reason: None,
+            executed_at: None,
+        };
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(1))
+            .await
+            .unwrap();
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[executed_tx.clone()], 1.into())
+            .await;
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(1))
+            .await
+            .unwrap();
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &[executed_tx])
+            .await;
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::NotReady);
+
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Execute,
+                H256::from_low_u64_be(1),
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+    }
+}
@@ -0,0 +1,171 @@
+//! Fairness policy limiting the number of L2 transactions from a single account per miniblock / L1 batch.
+
+use std::collections::{HashMap, HashSet};
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::{Address, Transaction};
+
+use crate::state_keeper::metrics::KEEPER_METRICS;
+
+/// Tracks the number of L2 transactions included into the current miniblock and L1 batch per initiator account,
+/// and determines accounts that have reached the configured caps. Transactions of such accounts are left
+/// in the mempool until the next miniblock / batch, so that a single account cannot monopolize the blockspace.
+/// Since transactions streamed by the shared sequencer must be executed in order, the stream is paused instead
+/// once it reaches a transaction from a capped account.
+#[derive(Debug)]
+pub(crate) struct AccountTxCaps {
+    max_txs_in_miniblock: Option<u32>,
+    max_txs_in_batch: Option<u32>,
+    miniblock_tx_counts: HashMap<Address, u32>,
+    batch_tx_counts: HashMap<Address, u32>,
+    capped_accounts: HashSet<Address>,
+}
+
+impl AccountTxCaps {
+    /// Returns `None` if caps are not configured.
+    pub fn new(config: &StateKeeperConfig) -> Option<Self> {
+        Self::with_limits(
+            config.max_txs_per_account_in_miniblock,
+            config.max_txs_per_account_in_batch,
+        )
+    }
+
+    /// Returns `None` if both limits are not set.
+    pub fn with_limits(
+        max_txs_in_miniblock: Option<u32>,
+        max_txs_in_batch: Option<u32>,
+    ) -> Option<Self> {
+        if max_txs_in_miniblock.is_none() && max_txs_in_batch.is_none() {
+            return None;
+        }
+        Some(Self {
+            max_txs_in_miniblock,
+            max_txs_in_batch,
+            miniblock_tx_counts: HashMap::new(),
+            batch_tx_counts: HashMap::new(),
+            capped_accounts: HashSet::new(),
+        })
+    }
+
+    /// Accounts whose transactions must not be included into the current miniblock.
+    pub fn capped_accounts(&self) -> &HashSet<Address> {
+        &self.capped_accounts
+    }
+
+    /// Accounts for a transaction taken for execution. L1 transactions are not capped.
+    pub fn register(&mut self, tx: &Transaction) {
+        if tx.is_l1() {
+            return;
+        }
+        let account = tx.initiator_account();
+        *self.miniblock_tx_counts.entry(account).or_default() += 1;
+        *self.batch_tx_counts.entry(account).or_default() += 1;
+        if self.is_capped(account) && self.capped_accounts.insert(account) {
+            tracing::debug!("Account {account:?} has reached the transaction cap");
+            KEEPER_METRICS.capped_accounts.inc();
+        }
+    }
+
+    /// Reverts [`Self::register()`] for a transaction that was rolled back or rejected.
+    pub fn unregister(&mut self, tx: &Transaction) {
+        if tx.is_l1() {
+            return;
+        }
+        let account = tx.initiator_account();
+        for counts in [&mut self.miniblock_tx_counts, &mut self.batch_tx_counts] {
+            if let Some(count) = counts.get_mut(&account) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        if !self.is_capped(account) {
+            self.capped_accounts.remove(&account);
+        }
+    }
+
+    /// Resets per-miniblock counts. Should be called once a miniblock is sealed.
+    pub fn start_miniblock(&mut self) {
+        self.miniblock_tx_counts.clear();
+        self.capped_accounts.retain(|&account| {
+            Self::exceeds(self.max_txs_in_batch, &self.batch_tx_counts, account)
+        });
+    }
+
+    /// Resets all counts. Should be called once an L1 batch is sealed.
+    pub fn start_batch(&mut self) {
+        self.miniblock_tx_counts.clear();
+        self.batch_tx_counts.clear();
+        self.capped_accounts.clear();
+    }
+
+    fn is_capped(&self, account: Address) -> bool {
+        Self::exceeds(
+            self.max_txs_in_miniblock,
+            &self.miniblock_tx_counts,
+            account,
+        ) || Self::exceeds(self.max_txs_in_batch, &self.batch_tx_counts, account)
+    }
+
+    fn exceeds(cap: Option<u32>, counts: &HashMap<Address, u32>, account: Address) -> bool {
+        cap.is_some_and(|cap| counts.get(&account).copied().unwrap_or(0) >= cap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{l2::L2Tx, Nonce, H256};
+
+    use super::*;
+
+    fn l2_tx(account: Address, nonce: u32) -> Transaction {
+        let mut tx = L2Tx::new(
+            Address::default(),
+            vec![],
+            Nonce(nonce),
+            Default::default(),
+            account,
+            0.into(),
+            None,
+            Default::default(),
+        );
+        tx.set_input(vec![], H256::random());
+        tx.into()
+    }
+
+    #[test]
+    fn caps_are_not_created_if_not_configured() {
+        assert!(AccountTxCaps::new(&StateKeeperConfig::default()).is_none());
+    }
+
+    #[test]
+    fn capping_accounts() {
+        let config = StateKeeperConfig {
+            max_txs_per_account_in_miniblock: Some(2),
+            max_txs_per_account_in_batch: Some(3),
+            ..StateKeeperConfig::default()
+        };
+        let mut caps = AccountTxCaps::new(&config).unwrap();
+        let account = Address::repeat_byte(1);
+        let other_account = Address::repeat_byte(2);
+
+        caps.register(&l2_tx(account, 0));
+        caps.register(&l2_tx(other_account, 0));
+        assert!(caps.capped_accounts().is_empty());
+        let tx = l2_tx(account, 1);
+        caps.register(&tx);
+        assert_eq!(*caps.capped_accounts(), HashSet::from([account]));
+        caps.unregister(&tx);
+        assert!(caps.capped_accounts().is_empty());
+        caps.register(&tx);
+
+        caps.start_miniblock();
+        assert!(caps.capped_accounts().is_empty());
+        caps.register(&l2_tx(account, 2));
+        // The per-batch cap is reached.
+        assert_eq!(*caps.capped_accounts(), HashSet::from([account]));
+        caps.start_miniblock();
+        assert_eq!(*caps.capped_accounts(), HashSet::from([account]));
+
+        caps.start_batch();
+        assert!(caps.capped_accounts().is_empty());
+    }
+}
@@ -11,6 +11,7 @@ use multivm::{
     interface::{FinishedL1Batch, L1BatchEnv, SystemEnv},
     utils::derive_base_fee_and_gas_per_pubdata,
 };
+use tokio::sync::watch;
 use vm_utils::storage::{l1_batch_params, L1BatchParamsProvider};
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_dal::ConnectionPool;
@@ -18,23 +19,30 @@ use zksync_mempool::L2TxFilter;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
     protocol_version::ProtocolUpgradeTx, witness_block_state::WitnessBlockState, Address,
-    L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+    ExecuteTransactionCommon, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId,
+    Transaction, VmEvent, H256,
 };
 // TODO (SMA-1206): use seconds instead of milliseconds.
 use zksync_utils::time::millis_since_epoch;
 
 use crate::{
+    api_server::tx_sender::{SubmitTxError, WithdrawalLimiter},
+    config_reload::ReloadableConfig,
     fee_model::BatchFeeModelInputProvider,
+    shared_sequencer::SharedSequencerSource,
     state_keeper::{
         extractors,
         io::{
+            account_caps::AccountTxCaps,
             common::{load_pending_batch, poll_iters, IoCursor},
-            fee_address_migration, MiniblockParams, MiniblockSealerHandle, PendingBatchData,
-            StateKeeperIO,
+            fee_address_migration,
+            scheduled_txs::ScheduledTxsInjector,
+            MiniblockParams, MiniblockSealerHandle, PendingBatchData, StateKeeperIO,
         },
         mempool_actor::l2_tx_filter,
-        metrics::KEEPER_METRICS,
+        metrics::{AGGREGATION_METRICS, KEEPER_METRICS},
         seal_criteria::{IoSealCriteria, TimeoutSealer},
+        types::StateKeeperControl,
         updates::{MiniblockUpdates, UpdatesManager},
         MempoolGuard,
     },
@@ -67,10 +75,36 @@ pub struct MempoolIO {
 
     virtual_blocks_interval: u32,
     virtual_blocks_per_miniblock: u32,
+    scheduled_txs_injector: Option<ScheduledTxsInjector>,
+    control: Option<StateKeeperControl>,
+    shared_sequencer: Option<SharedSequencerSource>,
+    withdrawal_limiter: Option<WithdrawalLimiter>,
+    account_caps: Option<AccountTxCaps>,
+    reloadable_config: Option<watch::Receiver<ReloadableConfig>>,
+    forced_inclusion_deadline: Duration,
 }
 
 impl IoSealCriteria for MempoolIO {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        const RULE_NAME: &str = "operator_request";
+
+        // The request is only consumed for non-empty batches, since empty batches are never sealed.
+        let is_seal_requested = manager.pending_executed_transactions_len() > 0
+            && self
+                .control
+                .as_ref()
+                .is_some_and(StateKeeperControl::take_l1_batch_seal_request);
+        if is_seal_requested {
+            AGGREGATION_METRICS.inc_criterion(RULE_NAME);
+            tracing::info!(
+                "Sealing L1 batch #{} as requested by the operator",
+                self.current_l1_batch_number
+            );
+            return true;
+        }
+        if self.should_seal_for_forced_inclusion(manager) {
+            return true;
+        }
         self.timeout_sealer
             .should_seal_l1_batch_unconditionally(manager)
     }
@@ -148,6 +182,15 @@ impl StateKeeperIO for MempoolIO {
             fee_per_gas: base_fee,
             gas_per_pubdata: gas_per_pubdata as u32,
         };
+        // Account for transactions in the pending batch, so that per-batch caps hold after the restart.
+        if let Some(caps) = &mut self.account_caps {
+            for miniblock in &pending_miniblocks {
+                for tx in &miniblock.txs {
+                    caps.register(tx);
+                }
+            }
+            caps.start_miniblock();
+        }
 
         Some(PendingBatchData {
             l1_batch_env,
@@ -159,7 +202,7 @@ impl StateKeeperIO for MempoolIO {
     async fn wait_for_new_batch_params(
         &mut self,
         max_wait: Duration,
-    ) -> Option<(SystemEnv, L1BatchEnv)> {
+    ) -> anyhow::Result<Option<(SystemEnv, L1BatchEnv)>> {
         let deadline = Instant::now() + max_wait;
 
         // Block until at least one transaction in the mempool can match the filter (or timeout happens).
@@ -172,18 +215,16 @@ impl StateKeeperIO for MempoolIO {
                 deadline.into(),
                 sleep_past(self.prev_miniblock_timestamp, self.current_miniblock_number),
             );
-            let current_timestamp = current_timestamp.await.ok()?;
+            let Ok(current_timestamp) = current_timestamp.await else {
+                return Ok(None);
+            };
 
             tracing::trace!(
                 "Fee input for L1 batch #{} is {:#?}",
                 self.current_l1_batch_number.0,
                 self.filter.fee_input
             );
-            let mut storage = self
-                .pool
-                .access_storage_tagged("state_keeper")
-                .await
-                .unwrap();
+            let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
             let (base_system_contracts, protocol_version) = storage
                 .protocol_versions_dal()
                 .base_system_contracts_by_timestamp(current_timestamp)
@@ -197,14 +238,15 @@ impl StateKeeperIO for MempoolIO {
                 protocol_version.into(),
             )
             .await;
+            self.inject_scheduled_txs().await;
             if !self.mempool.has_next(&self.filter) {
                 tokio::time::sleep(self.delay_interval).await;
                 continue;
             }
 
             // We only need to get the root hash when we're certain that we have a new transaction.
-            let prev_l1_batch_hash = self.wait_for_previous_l1_batch_hash().await;
-            return Some(l1_batch_params(
+            let prev_l1_batch_hash = self.wait_for_previous_l1_batch_hash().await?;
+            return Ok(Some(l1_batch_params(
                 self.current_l1_batch_number,
                 self.fee_account,
                 current_timestamp,
@@ -217,9 +259,9 @@ impl StateKeeperIO for MempoolIO {
                 protocol_version,
                 self.get_virtual_blocks_count(true, self.current_miniblock_number.0),
                 self.chain_id,
-            ));
+            )));
         }
-        None
+        Ok(None)
     }
 
     // Returns the pair of timestamp and the number of virtual blocks to be produced in this miniblock
@@ -235,6 +277,7 @@ impl StateKeeperIO for MempoolIO {
         )
         .await
         .ok()?;
+        self.inject_scheduled_txs().await;
 
         let virtual_blocks = self.get_virtual_blocks_count(false, self.current_miniblock_number.0);
 
@@ -246,8 +289,34 @@ impl StateKeeperIO for MempoolIO {
 
     async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
         for _ in 0..poll_iters(self.delay_interval, max_wait) {
+            // Priority operations are forcibly included: they are taken before any mempool policies
+            // (the shared sequencer ordering, per-account caps, the fee filter) are applied.
+            if let Some(tx) = self.mempool.next_l1_transaction() {
+                return Some(tx);
+            }
+            if self.shared_sequencer.is_some() {
+                if let Some(tx) = self.next_shared_sequencer_tx() {
+                    return Some(tx);
+                }
+                if !self.shared_sequencer.as_ref().unwrap().should_fall_back() {
+                    tokio::time::sleep(self.delay_interval).await;
+                    continue;
+                }
+            }
+
             let get_latency = KEEPER_METRICS.get_tx_from_mempool.start();
-            let res = self.mempool.next_transaction(&self.filter);
+            let res = match &mut self.account_caps {
+                Some(caps) => {
+                    let res = self
+                        .mempool
+                        .next_transaction_excluding(&self.filter, caps.capped_accounts());
+                    if let Some(tx) = &res {
+                        caps.register(tx);
+                    }
+                    res
+                }
+                None => self.mempool.next_transaction(&self.filter),
+            };
             get_latency.observe();
             if let Some(res) = res {
                 return Some(res);
@@ -260,6 +329,16 @@ impl StateKeeperIO for MempoolIO {
     }
 
     async fn rollback(&mut self, tx: Transaction) {
+        if let Some(caps) = &mut self.account_caps {
+            caps.unregister(&tx);
+        }
+        let tx = match &mut self.shared_sequencer {
+            Some(shared_sequencer) => match shared_sequencer.rollback(tx) {
+                Some(tx) => tx,
+                None => return,
+            },
+            None => tx,
+        };
         // Reset nonces in the mempool.
         self.mempool.rollback(&tx);
         // Insert the transaction back.
@@ -273,8 +352,17 @@ impl StateKeeperIO for MempoolIO {
             error
         );
 
-        // Reset the nonces in the mempool, but don't insert the transaction back.
-        self.mempool.rollback(rejected);
+        if let Some(caps) = &mut self.account_caps {
+            caps.unregister(rejected);
+        }
+        let is_from_shared_sequencer = self
+            .shared_sequencer
+            .as_mut()
+            .map_or(false, |source| source.reject(rejected.hash(), error));
+        if !is_from_shared_sequencer {
+            // Reset the nonces in the mempool, but don't insert the transaction back.
+            self.mempool.rollback(rejected);
+        }
 
         // Mark tx as rejected in the storage.
         let mut storage = self
@@ -292,16 +380,69 @@ impl StateKeeperIO for MempoolIO {
             .transactions_dal()
             .mark_tx_as_rejected(rejected.hash(), &format!("rejected: {}", error))
             .await;
+        if self.withdrawal_limiter.is_some() {
+            storage
+                .withdrawal_limits_dal()
+                .release_usage(&[rejected.hash()])
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn check_executed_tx(&mut self, tx: &Transaction, events: &[VmEvent]) -> Option<String> {
+        let limiter = self.withdrawal_limiter.as_ref()?;
+        if tx.is_l1() {
+            return None;
+        }
+        // Withdrawals may differ from the dry run of the transaction, so the limits are re-checked based on
+        // the actual execution. Usage reserved by the transaction itself is not taken into account.
+        let withdrawals = limiter.extract_withdrawals(events);
+        if withdrawals.is_empty() {
+            return None;
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage_tagged("state_keeper")
+            .await
+            .unwrap();
+        match limiter
+            .check(
+                &mut storage,
+                tx.hash(),
+                tx.initiator_account(),
+                &withdrawals,
+            )
+            .await
+        {
+            Ok(_) => None,
+            Err(SubmitTxError::Internal(err)) => {
+                panic!(
+                    "Failed checking withdrawal limits for transaction {:?}: {err:#}",
+                    tx.hash()
+                )
+            }
+            Err(err) => Some(err.to_string()),
+        }
     }
 
     async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        // Transactions received from the shared sequencer are not stored in Postgres before execution.
+        let pre_insert_txs = self.shared_sequencer.is_some();
         let command = updates_manager.seal_miniblock_command(
             self.current_l1_batch_number,
             self.current_miniblock_number,
             self.l2_erc20_bridge_addr,
-            false,
+            pre_insert_txs,
         );
         self.miniblock_sealer_handle.submit(command).await;
+        if let Some(shared_sequencer) = &mut self.shared_sequencer {
+            shared_sequencer.seal_miniblock(self.current_miniblock_number);
+        }
+        if let Some(caps) = &mut self.account_caps {
+            caps.start_miniblock();
+        }
+        self.report_forced_inclusion(&updates_manager.miniblock);
         self.update_miniblock_fields(&updates_manager.miniblock);
     }
 
@@ -353,6 +494,10 @@ impl StateKeeperIO for MempoolIO {
             .await;
         self.update_miniblock_fields(&fictive_miniblock);
         self.current_l1_batch_number += 1;
+        if let Some(caps) = &mut self.account_caps {
+            caps.start_batch();
+        }
+        self.update_account_caps();
         Ok(())
     }
 
@@ -489,9 +634,123 @@ impl MempoolIO {
             chain_id,
             virtual_blocks_interval: config.virtual_blocks_interval,
             virtual_blocks_per_miniblock: config.virtual_blocks_per_miniblock,
+            scheduled_txs_injector: None,
+            control: None,
+            shared_sequencer: None,
+            withdrawal_limiter: None,
+            account_caps: AccountTxCaps::new(config),
+            reloadable_config: None,
+            forced_inclusion_deadline: config.forced_inclusion_deadline(),
         })
     }
 
+    /// Allows controlling this I/O via the provided handle, e.g. requesting to seal the current L1 batch.
+    pub(crate) fn with_control(mut self, control: StateKeeperControl) -> Self {
+        self.control = Some(control);
+        self
+    }
+
+    /// Takes L2 transactions in the order received from the shared sequencer instead of the local mempool.
+    /// L1 transactions are still taken from the local mempool.
+    pub(crate) fn with_shared_sequencer(mut self, source: SharedSequencerSource) -> Self {
+        self.shared_sequencer = Some(source);
+        self
+    }
+
+    /// Submits transactions for due operator-scheduled transactions.
+    pub(crate) fn with_scheduled_txs_injector(mut self, injector: ScheduledTxsInjector) -> Self {
+        self.scheduled_txs_injector = Some(injector);
+        self
+    }
+
+    /// Re-checks withdrawal limits for executed transactions before including them into a miniblock, and releases
+    /// usage of the limits reserved by rejected transactions.
+    pub(crate) fn with_withdrawal_limiter(mut self, limiter: WithdrawalLimiter) -> Self {
+        self.withdrawal_limiter = Some(limiter);
+        self
+    }
+
+    /// Applies per-account transaction caps changed at runtime. Changes are applied at the start of the next L1 batch.
+    pub(crate) fn with_reloadable_config(
+        mut self,
+        config: watch::Receiver<ReloadableConfig>,
+    ) -> Self {
+        self.reloadable_config = Some(config);
+        self
+    }
+
+    fn update_account_caps(&mut self) {
+        let Some(config) = &mut self.reloadable_config else {
+            return;
+        };
+        if !config.has_changed().unwrap_or(false) {
+            return;
+        }
+        if let Some(params) = &config.borrow_and_update().state_keeper {
+            self.account_caps = AccountTxCaps::with_limits(
+                params.max_txs_per_account_in_miniblock,
+                params.max_txs_per_account_in_batch,
+            );
+        }
+    }
+
+    fn next_shared_sequencer_tx(&mut self) -> Option<Transaction> {
+        let source = self.shared_sequencer.as_mut()?;
+        let tx = source.next_tx()?;
+        if let Some(caps) = &mut self.account_caps {
+            if caps.capped_accounts().contains(&tx.initiator_account()) {
+                // Streamed transactions must be executed in the order set by the shared sequencer, so the stream
+                // is paused until the caps are reset in the next miniblock / L1 batch.
+                source.rollback(tx);
+                return None;
+            }
+            caps.register(&tx);
+        }
+        // The transaction may have also been submitted to the local mempool; it must not be executed twice.
+        if let ExecuteTransactionCommon::L2(data) = &tx.common_data {
+            self.mempool
+                .remove_l2_transaction(data.initiator_address, data.nonce, tx.hash());
+        }
+        Some(tx)
+    }
+
+    /// Checks whether the L1 batch should be sealed so that its priority operations can be executed on the settlement
+    /// layer within the forced inclusion deadline. The batch is sealed once the oldest operation in it has waited
+    /// for half of the deadline; the other half is left for committing, proving and executing the batch.
+    fn should_seal_for_forced_inclusion(&self, manager: &UpdatesManager) -> bool {
+        const RULE_NAME: &str = "forced_inclusion";
+
+        let Some(received_at_ms) = manager.oldest_priority_op_received_at_ms() else {
+            return false;
+        };
+        let waited =
+            Duration::from_millis((millis_since_epoch() as u64).saturating_sub(received_at_ms));
+        if waited < self.forced_inclusion_deadline / 2 {
+            return false;
+        }
+        AGGREGATION_METRICS.inc_criterion(RULE_NAME);
+        tracing::debug!(
+            "Sealing L1 batch #{} since its oldest priority operation has waited for {waited:?}, \
+             which is at least half of the forced inclusion deadline {:?}",
+            self.current_l1_batch_number,
+            self.forced_inclusion_deadline
+        );
+        true
+    }
+
+    fn report_forced_inclusion(&self, miniblock: &MiniblockUpdates) {
+        let now_ms = millis_since_epoch() as u64;
+        let priority_txs = miniblock
+            .executed_transactions
+            .iter()
+            .filter(|tx| tx.transaction.is_l1());
+        for tx in priority_txs {
+            let received_at_ms = tx.transaction.received_timestamp_ms;
+            let latency = Duration::from_millis(now_ms.saturating_sub(received_at_ms));
+            KEEPER_METRICS.forced_inclusion_latency.observe(latency);
+        }
+    }
+
     fn update_miniblock_fields(&mut self, miniblock: &MiniblockUpdates) {
         assert_eq!(
             miniblock.number, self.current_miniblock_number.0,
@@ -502,18 +761,37 @@ impl MempoolIO {
         self.prev_miniblock_timestamp = miniblock.timestamp;
     }
 
-    async fn wait_for_previous_l1_batch_hash(&self) -> H256 {
+    /// Submits due operator-scheduled transactions. Submitted transactions reach the mempool
+    /// via the mempool fetcher, like any other L2 transaction.
+    async fn inject_scheduled_txs(&mut self) {
+        let Some(injector) = &mut self.scheduled_txs_injector else {
+            return;
+        };
+        if !injector.should_check() {
+            return;
+        }
+        let mut storage = self
+            .pool
+            .access_storage_tagged("state_keeper")
+            .await
+            .unwrap();
+        // Failing to submit a scheduled transaction must not stop block production.
+        if let Err(err) = injector.inject_due_txs(&mut storage, &self.filter).await {
+            tracing::error!("Failed injecting scheduled transactions: {err:#}");
+        }
+    }
+
+    /// Unlike on external nodes, the async tree mode cannot be used here: the previous L1 batch root hash
+    /// is an input of the bootloader and is checked on L1, so it must be computed locally before a new L1 batch
+    /// is opened. The main node only relies on the Merkle tree keeping up with the state keeper.
+    async fn wait_for_previous_l1_batch_hash(&self) -> anyhow::Result<H256> {
         tracing::trace!(
             "Getting previous L1 batch hash for L1 batch #{}",
             self.current_l1_batch_number
         );
         let wait_latency = KEEPER_METRICS.wait_for_prev_hash_time.start();
 
-        let mut storage = self
-            .pool
-            .access_storage_tagged("state_keeper")
-            .await
-            .unwrap();
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
         let prev_l1_batch_number = self.current_l1_batch_number - 1;
         let (batch_hash, _) = self
             .l1_batch_params_provider
@@ -521,15 +799,14 @@ impl MempoolIO {
             .await
             .with_context(|| {
                 format!("error waiting for params for L1 batch #{prev_l1_batch_number}")
-            })
-            .unwrap();
+            })?;
 
         wait_latency.observe();
         tracing::trace!(
             "Got previous L1 batch hash: {batch_hash:?} for L1 batch #{}",
             self.current_l1_batch_number
         );
-        batch_hash
+        Ok(batch_hash)
     }
 
     /// "virtual_blocks_per_miniblock" will be created either if the miniblock_number % virtual_blocks_interval == 0 or
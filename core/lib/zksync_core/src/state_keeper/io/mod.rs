@@ -10,7 +10,7 @@ use zksync_dal::ConnectionPool;
 use zksync_types::{
     block::MiniblockExecutionData, protocol_version::ProtocolUpgradeTx,
     witness_block_state::WitnessBlockState, L1BatchNumber, MiniblockNumber, ProtocolVersionId,
-    Transaction,
+    Transaction, VmEvent,
 };
 
 use super::{
@@ -19,9 +19,11 @@ use super::{
     updates::{MiniblockSealCommand, UpdatesManager},
 };
 
+pub(crate) mod account_caps;
 pub(crate) mod common;
 pub(crate) mod fee_address_migration;
 pub(crate) mod mempool;
+pub(crate) mod scheduled_txs;
 pub(crate) mod seal_logic;
 #[cfg(test)]
 mod tests;
@@ -76,7 +78,7 @@ pub trait StateKeeperIO: 'static + Send + IoSealCriteria {
     async fn wait_for_new_batch_params(
         &mut self,
         max_wait: Duration,
-    ) -> Option<(SystemEnv, L1BatchEnv)>;
+    ) -> anyhow::Result<Option<(SystemEnv, L1BatchEnv)>>;
     /// Blocks for up to `max_wait` until the parameters for the next miniblock are available.
     async fn wait_for_new_miniblock_params(
         &mut self,
@@ -89,6 +91,15 @@ pub trait StateKeeperIO: 'static + Send + IoSealCriteria {
     async fn rollback(&mut self, tx: Transaction);
     /// Marks the transaction as "rejected", e.g. one that is not correct and can't be executed.
     async fn reject(&mut self, tx: &Transaction, error: &str);
+    /// Checks a successfully executed transaction before it's included into the miniblock. Returns the reason
+    /// to reject the transaction, if any. By default, all executed transactions are included.
+    async fn check_executed_tx(
+        &mut self,
+        _tx: &Transaction,
+        _events: &[VmEvent],
+    ) -> Option<String> {
+        None
+    }
     /// Marks the miniblock (aka L2 block) as sealed.
     /// Returns the timestamp for the next miniblock.
     async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager);
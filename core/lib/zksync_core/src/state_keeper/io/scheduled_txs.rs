@@ -0,0 +1,300 @@
+//! Injection of operator-scheduled recurring L2 transactions (e.g., oracle pushes or fee sweeps).
+//! Schedules are managed via the `admin_registerScheduledTx`, `admin_disableScheduledTx` and `admin_getScheduledTxs`
+//! methods.
+//!
+//! Scheduled transactions are validated by a dedicated transaction sender in the same way as transactions submitted
+//! via the API (nonce, balance, fee floor, and whether the transaction fits into a batch based on its dry run)
+//! before they are inserted into the mempool. Transactions that fail validation are not submitted; the schedule
+//! moves to the next run and the failure counts towards its consecutive failures.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use zksync_dal::{
+    scheduled_txs_dal::{NewScheduledTx, ScheduledTxOutcome},
+    transactions_dal::L2TxSubmissionResult,
+    StorageProcessor,
+};
+use zksync_mempool::L2TxFilter;
+use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
+use zksync_types::{
+    api, fee::Fee, l2::L2Tx, transaction_request::PaymasterParams, Address, L2ChainId, Nonce,
+    PackedEthSignature, H256, U256,
+};
+
+use crate::{
+    api_server::tx_sender::{SubmitTxError, TxSender},
+    state_keeper::metrics::{ScheduledTxEvent, SCHEDULED_TXS_METRICS},
+};
+
+/// Minimum interval between consecutive checks of the scheduled transactions.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Submits due scheduled transactions on behalf of a dedicated operator account and tracks their outcomes.
+///
+/// The injector is the only sender of transactions for the operator account, so it caches the next nonce
+/// and only reloads it from the storage on startup or after a transaction has failed.
+#[derive(Debug)]
+pub(crate) struct ScheduledTxsInjector {
+    private_key: H256,
+    address: Address,
+    chain_id: L2ChainId,
+    tx_sender: TxSender,
+    next_nonce: Option<Nonce>,
+    last_checked_at: Option<Instant>,
+}
+
+impl ScheduledTxsInjector {
+    pub fn new(
+        private_key: H256,
+        chain_id: L2ChainId,
+        tx_sender: TxSender,
+    ) -> anyhow::Result<Self> {
+        let address = PackedEthSignature::address_from_private_key(&private_key)
+            .context("invalid private key for scheduled transactions")?;
+        Ok(Self {
+            private_key,
+            address,
+            chain_id,
+            tx_sender,
+            next_nonce: None,
+            last_checked_at: None,
+        })
+    }
+
+    /// Checks whether scheduled transactions should be checked now. Used to not query the storage
+    /// on each state keeper I/O poll.
+    pub fn should_check(&mut self) -> bool {
+        let now = Instant::now();
+        if let Some(last_checked_at) = self.last_checked_at {
+            if now.duration_since(last_checked_at) < CHECK_INTERVAL {
+                return false;
+            }
+        }
+        self.last_checked_at = Some(now);
+        true
+    }
+
+    /// Processes outcomes of previously submitted transactions and submits transactions for all due schedules.
+    /// Returns the number of submitted transactions.
+    pub async fn inject_due_txs(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        filter: &L2TxFilter,
+    ) -> anyhow::Result<usize> {
+        let scheduled_txs = storage
+            .scheduled_txs_dal()
+            .get_enabled_scheduled_txs()
+            .await
+            .context("failed loading scheduled transactions")?;
+
+        let mut submitted_count = 0;
+        let mut max_consecutive_failures = 0;
+        for scheduled_tx in scheduled_txs {
+            let name = &scheduled_tx.tx.name;
+            let success = match &scheduled_tx.last_outcome {
+                ScheduledTxOutcome::Idle => None,
+                ScheduledTxOutcome::Pending(_) => {
+                    max_consecutive_failures =
+                        max_consecutive_failures.max(scheduled_tx.consecutive_failures);
+                    continue;
+                }
+                ScheduledTxOutcome::Succeeded(tx_hash, miniblock_number) => {
+                    tracing::debug!(
+                        "Scheduled transaction `{name}` ({tx_hash:?}) was included in miniblock #{miniblock_number}"
+                    );
+                    SCHEDULED_TXS_METRICS.events[&ScheduledTxEvent::Succeeded].inc();
+                    Some(true)
+                }
+                ScheduledTxOutcome::Failed(tx_hash, error) => {
+                    tracing::error!(
+                        "Scheduled transaction `{name}` ({tx_hash:?}) failed: {error}; consecutive failures: {}",
+                        scheduled_tx.consecutive_failures + 1
+                    );
+                    SCHEDULED_TXS_METRICS.events[&ScheduledTxEvent::Failed].inc();
+                    Some(false)
+                }
+                ScheduledTxOutcome::Dropped(tx_hash) => {
+                    tracing::error!(
+                        "Scheduled transaction `{name}` ({tx_hash:?}) was dropped from the mempool; consecutive failures: {}",
+                        scheduled_tx.consecutive_failures + 1
+                    );
+                    SCHEDULED_TXS_METRICS.events[&ScheduledTxEvent::Dropped].inc();
+                    Some(false)
+                }
+            };
+
+            let consecutive_failures = match success {
+                None => scheduled_tx.consecutive_failures,
+                Some(true) => 0,
+                Some(false) => scheduled_tx.consecutive_failures + 1,
+            };
+            if let Some(success) = success {
+                if !success {
+                    // The failed transaction may have left a gap in nonces.
+                    self.next_nonce = None;
+                }
+                storage
+                    .scheduled_txs_dal()
+                    .mark_scheduled_tx_processed(scheduled_tx.id, success)
+                    .await
+                    .with_context(|| format!("failed updating scheduled transaction `{name}`"))?;
+            }
+            max_consecutive_failures = max_consecutive_failures.max(consecutive_failures);
+
+            if !scheduled_tx.is_due {
+                continue;
+            }
+            let tx = self
+                .sign_tx(storage, &scheduled_tx.tx, filter)
+                .await
+                .with_context(|| format!("failed signing scheduled transaction `{name}`"))?;
+            let (tx_hash, nonce) = (tx.hash(), tx.nonce());
+            let metrics = match self.tx_sender.dry_run_tx(&tx).await {
+                Ok(metrics) => metrics,
+                Err(SubmitTxError::Internal(err)) => {
+                    return Err(
+                        err.context(format!("failed validating scheduled transaction `{name}`"))
+                    );
+                }
+                Err(err) => {
+                    self.reject_tx(storage, scheduled_tx.id, name, tx_hash, &err)
+                        .await?;
+                    max_consecutive_failures =
+                        max_consecutive_failures.max(consecutive_failures + 1);
+                    continue;
+                }
+            };
+
+            // The transaction and the schedule are updated atomically, so that the schedule cannot lose track
+            // of the submitted transaction.
+            let mut transaction = storage
+                .start_transaction()
+                .await
+                .context("failed starting DB transaction")?;
+            let submission_result = transaction
+                .transactions_dal()
+                .insert_transaction_l2(tx, metrics)
+                .await;
+            // A transaction with the same nonce may be replaced if it has failed, but not a duplicate one.
+            if !matches!(
+                submission_result,
+                L2TxSubmissionResult::Added | L2TxSubmissionResult::Replaced
+            ) {
+                drop(transaction);
+                self.reject_tx(storage, scheduled_tx.id, name, tx_hash, &submission_result)
+                    .await?;
+                max_consecutive_failures = max_consecutive_failures.max(consecutive_failures + 1);
+                continue;
+            }
+            transaction
+                .scheduled_txs_dal()
+                .mark_scheduled_tx_submitted(scheduled_tx.id, tx_hash)
+                .await
+                .with_context(|| format!("failed updating scheduled transaction `{name}`"))?;
+            transaction
+                .commit()
+                .await
+                .context("failed committing DB transaction")?;
+            self.next_nonce = Some(nonce + 1);
+
+            tracing::info!("Submitted scheduled transaction `{name}` with hash {tx_hash:?}");
+            SCHEDULED_TXS_METRICS.events[&ScheduledTxEvent::Submitted].inc();
+            submitted_count += 1;
+        }
+
+        SCHEDULED_TXS_METRICS
+            .max_consecutive_failures
+            .set(max_consecutive_failures.into());
+        Ok(submitted_count)
+    }
+
+    /// Records that a transaction for the schedule was rejected before submission.
+    async fn reject_tx(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        id: i64,
+        name: &str,
+        tx_hash: H256,
+        reason: &dyn fmt::Display,
+    ) -> anyhow::Result<()> {
+        tracing::error!("Scheduled transaction `{name}` ({tx_hash:?}) was rejected: {reason}");
+        SCHEDULED_TXS_METRICS.events[&ScheduledTxEvent::Rejected].inc();
+        // The nonce may be invalid, e.g. if a transaction was sent from the account externally.
+        self.next_nonce = None;
+        storage
+            .scheduled_txs_dal()
+            .mark_scheduled_tx_rejected(id)
+            .await
+            .with_context(|| format!("failed updating scheduled transaction `{name}`"))
+    }
+
+    /// Signs a transaction for the schedule with the next nonce of the operator account.
+    async fn sign_tx(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        scheduled_tx: &NewScheduledTx,
+        filter: &L2TxFilter,
+    ) -> anyhow::Result<L2Tx> {
+        let nonce = match self.next_nonce {
+            Some(nonce) => nonce,
+            None => {
+                let nonce = self.load_next_nonce(storage).await?;
+                self.next_nonce = Some(nonce);
+                nonce
+            }
+        };
+        // Overpay the current fee to not get stuck in the mempool if the fee grows slightly.
+        let fee = Fee {
+            gas_limit: scheduled_tx.gas_limit.into(),
+            max_fee_per_gas: (filter.fee_per_gas + filter.fee_per_gas / 2).into(),
+            max_priority_fee_per_gas: U256::zero(),
+            gas_per_pubdata_limit: DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE
+                .max(filter.gas_per_pubdata.into())
+                .into(),
+        };
+        let mut tx = L2Tx::new_signed(
+            scheduled_tx.contract_address,
+            scheduled_tx.calldata.clone(),
+            nonce,
+            fee,
+            scheduled_tx.value,
+            self.chain_id,
+            &self.private_key,
+            None,
+            PaymasterParams::default(),
+        )
+        .context("failed signing transaction")?;
+
+        let request = api::TransactionRequest::from(tx.clone());
+        let signature = PackedEthSignature::deserialize_packed(&tx.common_data.signature)
+            .context("invalid transaction signature")?;
+        let raw_bytes = request.get_signed_bytes(&signature, self.chain_id);
+        let tx_hash = request
+            .get_tx_hash(self.chain_id)
+            .context("failed computing transaction hash")?;
+        tx.set_input(raw_bytes, tx_hash);
+        Ok(tx)
+    }
+
+    async fn load_next_nonce(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<Nonce> {
+        let stored_nonces = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&[self.address])
+            .await
+            .context("failed loading stored nonce for scheduled transactions account")?;
+        let stored_nonce = stored_nonces
+            .get(&self.address)
+            .copied()
+            .unwrap_or(Nonce(0));
+        let next_nonce = storage
+            .transactions_web3_dal()
+            .next_nonce_by_initiator_account(self.address, stored_nonce.0.into())
+            .await
+            .context("failed loading pending nonce for scheduled transactions account")?;
+        Ok(Nonce(next_nonce.as_u32()))
+    }
+}
@@ -1,7 +1,10 @@
 //! This module is a source-of-truth on what is expected to be done when sealing a block.
 //! It contains the logic of the block sealing, which is used by both the mempool-based and external node IO.
 
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use chrono::Utc;
 use itertools::Itertools;
@@ -11,7 +14,7 @@ use multivm::{
 };
 use zksync_dal::StorageProcessor;
 use zksync_types::{
-    block::{unpack_block_info, L1BatchHeader, MiniblockHeader},
+    block::{unpack_block_info, L1BatchHeader, MiniblockContentHasher, MiniblockHeader},
     event::{extract_added_tokens, extract_long_l2_to_l1_messages},
     l1::L1Tx,
     l2::L2Tx,
@@ -19,18 +22,19 @@ use zksync_types::{
     protocol_version::ProtocolUpgradeTx,
     storage_writes_deduplicator::{ModifiedSlot, StorageWritesDeduplicator},
     tx::{
-        tx_execution_info::DeduplicatedWritesMetrics, IncludedTxLocation,
-        TransactionExecutionResult,
+        tx_execution_info::{DeduplicatedWritesMetrics, TxExecutionStatus},
+        IncludedTxLocation, TransactionExecutionResult,
     },
     zk_evm_types::LogQuery,
     AccountTreeId, Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber,
     MiniblockNumber, ProtocolVersionId, StorageKey, StorageLog, StorageLogQuery, Transaction,
-    VmEvent, CURRENT_VIRTUAL_BLOCK_INFO_POSITION, H256, SYSTEM_CONTEXT_ADDRESS,
+    VmEvent, CURRENT_VIRTUAL_BLOCK_INFO_POSITION, H256, SYSTEM_CONTEXT_ADDRESS, U256,
 };
 // TODO (SMA-1206): use seconds instead of milliseconds.
 use zksync_utils::{h256_to_u256, time::millis_since_epoch, u256_to_h256};
 
 use crate::{
+    api_server::tx_sender::extract_withdrawals,
     metrics::{BlockStage, MiniblockStage, APP_METRICS},
     state_keeper::{
         metrics::{
@@ -369,6 +373,31 @@ impl MiniblockSealCommand {
             .await;
         progress.observe(self.miniblock.executed_transactions.len());
 
+        let progress =
+            MINIBLOCK_METRICS.start(MiniblockSealStage::InsertWithdrawalLimitUsage, is_fictive);
+        // Usage reserved by the transactions when they were accepted to the mempool is replaced with the actual usage.
+        let l2_tx_hashes: Vec<_> = self
+            .miniblock
+            .executed_transactions
+            .iter()
+            .filter(|tx| matches!(tx.transaction.common_data, ExecuteTransactionCommon::L2(_)))
+            .map(|tx| tx.hash)
+            .collect();
+        transaction
+            .withdrawal_limits_dal()
+            .release_usage(&l2_tx_hashes)
+            .await
+            .unwrap();
+        let withdrawal_limit_usage = self.extract_withdrawal_limit_usage(&mut transaction).await;
+        for (tx_hash, initiator, withdrawals) in &withdrawal_limit_usage {
+            transaction
+                .withdrawal_limits_dal()
+                .insert_usage(*tx_hash, *initiator, withdrawals)
+                .await
+                .unwrap();
+        }
+        progress.observe(withdrawal_limit_usage.len());
+
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertStorageLogs, is_fictive);
         let write_logs = self.extract_deduplicated_write_logs(is_fictive);
         let write_log_count: usize = write_logs.iter().map(|(_, logs)| logs.len()).sum();
@@ -453,6 +482,15 @@ impl MiniblockSealCommand {
             .await;
         progress.observe(user_l2_to_l1_log_count);
 
+        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertContentHash, is_fictive);
+        let content_hash = self.content_hash(&miniblock_events, &user_l2_to_l1_logs, &write_logs);
+        transaction
+            .content_hashes_dal()
+            .set_content_hash(miniblock_number, content_hash)
+            .await
+            .unwrap();
+        progress.observe(None);
+
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::CommitMiniblock, is_fictive);
         let current_l2_virtual_block_info = transaction
             .storage_web3_dal()
@@ -512,6 +550,68 @@ impl MiniblockSealCommand {
         }
     }
 
+    /// Extracts withdrawals initiated by successfully executed L2 transactions that count towards
+    /// withdrawal limits, i.e., withdrawals of tokens with configured limits. Returns `(tx_hash, initiator,
+    /// withdrawals)` tuples, where withdrawals are `(l1_token, amount)` pairs.
+    async fn extract_withdrawal_limit_usage(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Vec<(H256, Address, Vec<(Address, U256)>)> {
+        let first_tx_index = self.first_tx_index;
+        let withdrawals_by_tx: Vec<_> = self
+            .miniblock
+            .executed_transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| {
+                matches!(tx.transaction.common_data, ExecuteTransactionCommon::L2(_))
+                    && tx.execution_status == TxExecutionStatus::Success
+            })
+            .filter_map(|(i, tx)| {
+                let tx_index = (first_tx_index + i) as u32;
+                let tx_events = self
+                    .miniblock
+                    .events
+                    .iter()
+                    .filter(|event| event.location.1 == tx_index);
+                let withdrawals = extract_withdrawals(self.l2_erc20_bridge_addr, tx_events);
+                (!withdrawals.is_empty()).then_some((tx, withdrawals))
+            })
+            .collect();
+        if withdrawals_by_tx.is_empty() {
+            return vec![];
+        }
+
+        let l1_tokens: Vec<_> = withdrawals_by_tx
+            .iter()
+            .flat_map(|(_, withdrawals)| withdrawals.iter().map(|withdrawal| withdrawal.l1_token))
+            .unique()
+            .collect();
+        let limits = storage
+            .withdrawal_limits_dal()
+            .get_limits_for_tokens(&l1_tokens)
+            .await
+            .unwrap();
+        if limits.is_empty() {
+            return vec![];
+        }
+
+        withdrawals_by_tx
+            .into_iter()
+            .filter_map(|(tx, withdrawals)| {
+                let mut amounts = HashMap::<_, U256>::new();
+                for withdrawal in withdrawals {
+                    if limits.contains_key(&withdrawal.l1_token) {
+                        let amount = amounts.entry(withdrawal.l1_token).or_default();
+                        *amount = amount.saturating_add(withdrawal.amount);
+                    }
+                }
+                let initiator = tx.transaction.initiator_account();
+                (!amounts.is_empty()).then(|| (tx.hash, initiator, amounts.into_iter().collect()))
+            })
+            .collect()
+    }
+
     fn extract_deduplicated_write_logs(&self, is_fictive: bool) -> Vec<(H256, Vec<StorageLog>)> {
         let mut storage_writes_deduplicator = StorageWritesDeduplicator::new();
         storage_writes_deduplicator.apply(
@@ -559,6 +659,40 @@ impl MiniblockSealCommand {
         &tx_result.transaction
     }
 
+    /// Computes the content hash of the miniblock. The hash must match the one computed from the persisted
+    /// miniblock data by `ContentHashesDal`.
+    fn content_hash(
+        &self,
+        miniblock_events: &[(IncludedTxLocation, Vec<&VmEvent>)],
+        user_l2_to_l1_logs: &[(IncludedTxLocation, Vec<&UserL2ToL1Log>)],
+        write_logs: &[(H256, Vec<StorageLog>)],
+    ) -> H256 {
+        let mut hasher = MiniblockContentHasher::new(self.miniblock_number);
+        for tx in &self.miniblock.executed_transactions {
+            let is_successful = tx.execution_status == TxExecutionStatus::Success;
+            hasher.push_tx(tx.hash, is_successful, tx.refunded_gas.into());
+        }
+        for (location, events) in miniblock_events {
+            for event in events {
+                hasher.push_event(
+                    location.tx_hash,
+                    event.address,
+                    &event.indexed_topics,
+                    &event.value,
+                );
+            }
+        }
+        for (location, logs) in user_l2_to_l1_logs {
+            for log in logs {
+                hasher.push_l2_to_l1_log(location.tx_hash, &log.0);
+            }
+        }
+        for log in write_logs.iter().flat_map(|(_, logs)| logs) {
+            hasher.push_storage_write(log.key.hashed_key(), log.value);
+        }
+        hasher.finalize()
+    }
+
     fn extract_events(&self, is_fictive: bool) -> Vec<(IncludedTxLocation, Vec<&VmEvent>)> {
         self.group_by_tx_location(&self.miniblock.events, is_fictive, |event| event.location.1)
     }
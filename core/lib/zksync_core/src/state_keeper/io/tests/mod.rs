@@ -1,32 +1,58 @@
 use std::time::Duration;
 
+use assert_matches::assert_matches;
 use futures::FutureExt;
-use multivm::utils::derive_base_fee_and_gas_per_pubdata;
+use multivm::{interface::ExecutionResult, utils::derive_base_fee_and_gas_per_pubdata};
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::ConnectionPool;
+use zksync_dal::{
+    content_hashes_dal::ContentHashCheck,
+    fee_discounts_dal::UnpaidFeeRebate,
+    scheduled_txs_dal::{NewScheduledTx, ScheduledTxOutcome},
+    ConnectionPool,
+};
 use zksync_mempool::L2TxFilter;
 use zksync_types::{
+    api,
     block::{BlockGasCount, MiniblockHasher},
+    ethabi,
+    event::L1_MESSAGE_EVENT_SIGNATURE,
     fee::TransactionExecutionMetrics,
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
+    l1::{L1Tx, L1TxCommonData},
     tx::ExecutionMetrics,
-    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, VmEvent,
-    H256, U256,
+    utils::storage_key_for_eth_balance,
+    AccountTreeId, Address, Execute, L1BatchNumber, L2ChainId, MiniblockNumber, PackedEthSignature,
+    PriorityOpId, ProtocolVersionId, StorageKey, StorageLog, Transaction, VmEvent, H256,
+    L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, U256,
+};
+use zksync_utils::{
+    address_to_h256,
+    time::{millis_since_epoch, seconds_since_epoch},
+    u256_to_h256,
 };
-use zksync_utils::time::seconds_since_epoch;
 
 use self::tester::Tester;
 use crate::{
+    api_server::{
+        execution_sandbox::testonly::MockTransactionExecutor,
+        tx_sender::tests::create_test_tx_sender,
+    },
     state_keeper::{
-        io::{MiniblockParams, MiniblockSealer, StateKeeperIO},
+        io::{
+            scheduled_txs::ScheduledTxsInjector, MiniblockParams, MiniblockSealer, StateKeeperIO,
+        },
         mempool_actor::l2_tx_filter,
+        seal_criteria::IoSealCriteria,
         tests::{
             create_execution_result, create_transaction, create_updates_manager,
             default_l1_batch_env, default_system_env, default_vm_block_result, Query,
         },
         updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
+        StateKeeperControl,
     },
     utils::testonly::prepare_recovery_snapshot,
+    withdrawal_finalizer::tests::eth_withdrawal_message,
 };
 
 mod tester;
@@ -129,6 +155,7 @@ async fn test_filter_with_no_pending_batch() {
     mempool
         .wait_for_new_batch_params(Duration::from_secs(10))
         .await
+        .unwrap()
         .expect("No batch params in the test mempool");
     assert_eq!(mempool.filter(), &want_filter);
 }
@@ -164,6 +191,7 @@ async fn test_timestamps_are_distinct(
     let batch_params = mempool
         .wait_for_new_batch_params(Duration::from_secs(10))
         .await
+        .unwrap()
         .expect("No batch params in the test mempool");
     assert!(batch_params.1.timestamp > prev_miniblock_timestamp);
 }
@@ -357,6 +385,216 @@ async fn processing_events_when_sealing_miniblock() {
     }
 }
 
+#[tokio::test]
+async fn persisting_content_hash_when_sealing_miniblock() {
+    let pool = ConnectionPool::constrained_test_pool(1).await;
+    let l1_batch_number = L1BatchNumber(2);
+    let mut miniblock = MiniblockUpdates::new(0, 1, H256::zero(), 1, ProtocolVersionId::latest());
+    for i in 0_u8..2 {
+        let tx = create_transaction(10, 100);
+        let mut execution_result = create_execution_result(i.into(), []);
+        execution_result.logs.events = vec![VmEvent {
+            location: (l1_batch_number, i.into()),
+            address: Address::repeat_byte(i),
+            indexed_topics: vec![H256::repeat_byte(i)],
+            value: vec![i],
+        }];
+        miniblock.extend_from_executed_transaction(
+            tx,
+            execution_result,
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![],
+            vec![],
+        );
+    }
+
+    let miniblock_number = MiniblockNumber(3);
+    let seal_command = MiniblockSealCommand {
+        l1_batch_number,
+        miniblock_number,
+        miniblock,
+        first_tx_index: 0,
+        fee_account_address: Address::repeat_byte(0x23),
+        fee_input: BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+            l1_gas_price: 100,
+            fair_l2_gas_price: 100,
+            fair_pubdata_price: 100,
+        }),
+        base_fee_per_gas: 10,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: Some(ProtocolVersionId::latest()),
+        l2_erc20_bridge_addr: Address::default(),
+        pre_insert_txs: true,
+    };
+    let mut conn = pool.access_storage().await.unwrap();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+    seal_command.seal(&mut conn).await;
+
+    let check = conn
+        .content_hashes_dal()
+        .check_content_hash(miniblock_number)
+        .await
+        .unwrap();
+    assert_matches!(check, ContentHashCheck::Valid(_));
+
+    // Emulate data corruption by removing the miniblock events.
+    conn.events_dal()
+        .rollback_events(miniblock_number - 1)
+        .await;
+    let check = conn
+        .content_hashes_dal()
+        .check_content_hash(miniblock_number)
+        .await
+        .unwrap();
+    assert_matches!(check, ContentHashCheck::Mismatch { .. });
+}
+
+#[tokio::test]
+async fn computing_fee_rebates_for_sealed_transactions() {
+    let pool = ConnectionPool::constrained_test_pool(1).await;
+    let mut miniblock = MiniblockUpdates::new(0, 1, H256::zero(), 1, ProtocolVersionId::latest());
+    let discounted_tx = create_transaction(10, 100);
+    let discounted_account = discounted_tx.initiator_account();
+    let discounted_tx_hash = discounted_tx.hash();
+    for (i, tx) in [discounted_tx, create_transaction(10, 100)]
+        .into_iter()
+        .enumerate()
+    {
+        miniblock.extend_from_executed_transaction(
+            tx,
+            create_execution_result(i as u16, []),
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![],
+            vec![],
+        );
+    }
+
+    let miniblock_number = MiniblockNumber(3);
+    let seal_command = MiniblockSealCommand {
+        l1_batch_number: L1BatchNumber(2),
+        miniblock_number,
+        miniblock,
+        first_tx_index: 0,
+        fee_account_address: Address::repeat_byte(0x23),
+        fee_input: BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+            l1_gas_price: 100,
+            fair_l2_gas_price: 100,
+            fair_pubdata_price: 100,
+        }),
+        base_fee_per_gas: 10,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: Some(ProtocolVersionId::latest()),
+        l2_erc20_bridge_addr: Address::default(),
+        pre_insert_txs: true,
+    };
+    let mut conn = pool.access_storage().await.unwrap();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+    // The discount is recorded by the API server when the transaction is accepted.
+    conn.fee_discounts_dal()
+        .insert_discounted_tx(discounted_tx_hash, discounted_account, 5_000)
+        .await
+        .unwrap();
+    seal_command.seal(&mut conn).await;
+
+    // Transactions don't get refunds, so the fee paid is `gas_limit * base_fee_per_gas`.
+    let unpaid_rebates = conn
+        .fee_discounts_dal()
+        .get_unpaid_rebates(miniblock_number)
+        .await
+        .unwrap();
+    assert_eq!(
+        unpaid_rebates,
+        [UnpaidFeeRebate {
+            account: discounted_account,
+            amount: 5_000.into(),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn recording_withdrawal_limit_usage_when_sealing_miniblock() {
+    let pool = ConnectionPool::constrained_test_pool(1).await;
+    let mut miniblock = MiniblockUpdates::new(0, 1, H256::zero(), 1, ProtocolVersionId::latest());
+    let withdrawing_tx = create_transaction(10, 100);
+    let initiator = withdrawing_tx.initiator_account();
+    let withdrawal_event = VmEvent {
+        location: (L1BatchNumber(2), 0),
+        address: L1_MESSENGER_ADDRESS,
+        indexed_topics: vec![
+            *L1_MESSAGE_EVENT_SIGNATURE,
+            address_to_h256(&L2_ETH_TOKEN_ADDRESS),
+            H256::zero(), // message hash; not checked
+        ],
+        value: ethabi::encode(&[ethabi::Token::Bytes(eth_withdrawal_message(
+            Address::repeat_byte(1),
+            100.into(),
+        ))]),
+    };
+    let non_withdrawing_tx = create_transaction(10, 100);
+    let non_withdrawing_tx_hash = non_withdrawing_tx.hash();
+    for (i, tx) in [withdrawing_tx, non_withdrawing_tx].into_iter().enumerate() {
+        let mut execution_result = create_execution_result(i as u16, []);
+        if i == 0 {
+            execution_result.logs.events = vec![withdrawal_event.clone()];
+        }
+        miniblock.extend_from_executed_transaction(
+            tx,
+            execution_result,
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![],
+            vec![],
+        );
+    }
+
+    let seal_command = MiniblockSealCommand {
+        l1_batch_number: L1BatchNumber(2),
+        miniblock_number: MiniblockNumber(3),
+        miniblock,
+        first_tx_index: 0,
+        fee_account_address: Address::repeat_byte(0x23),
+        fee_input: BatchFeeInput::l1_pegged(100, 100),
+        base_fee_per_gas: 10,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: Some(ProtocolVersionId::latest()),
+        l2_erc20_bridge_addr: Address::default(),
+        pre_insert_txs: true,
+    };
+    let mut conn = pool.access_storage().await.unwrap();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+    conn.withdrawal_limits_dal()
+        .set_limit(Address::zero(), Some(1_000.into()), None, 3_600)
+        .await
+        .unwrap();
+    // Emulate a reservation made based on the dry run of the transaction, which didn't withdraw anything
+    // when executed by the state keeper.
+    conn.withdrawal_limits_dal()
+        .insert_usage(
+            non_withdrawing_tx_hash,
+            initiator,
+            &[(Address::zero(), 500.into())],
+        )
+        .await
+        .unwrap();
+    seal_command.seal(&mut conn).await;
+
+    let withdrawn = conn
+        .withdrawal_limits_dal()
+        .get_withdrawn_amounts(Address::zero(), initiator, Duration::from_secs(3_600), None)
+        .await
+        .unwrap();
+    assert_eq!(withdrawn.total, 100.into());
+    assert_eq!(withdrawn.account, 100.into());
+}
+
 async fn test_miniblock_and_l1_batch_processing(
     pool: ConnectionPool,
     miniblock_sealer_capacity: usize,
@@ -472,6 +710,7 @@ async fn miniblock_processing_after_snapshot_recovery() {
     let (system_env, l1_batch_env) = mempool
         .wait_for_new_batch_params(Duration::from_secs(10))
         .await
+        .unwrap()
         .unwrap();
     assert_eq!(l1_batch_env.number, snapshot_recovery.l1_batch_number + 1);
     assert_eq!(
@@ -668,3 +907,223 @@ async fn different_timestamp_for_miniblocks_in_same_batch() {
         .unwrap();
     assert!(miniblock_params.timestamp > current_timestamp);
 }
+
+#[tokio::test]
+async fn requesting_l1_batch_seal() {
+    let connection_pool = ConnectionPool::constrained_test_pool(1).await;
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+    let (mempool, guard) = tester.create_test_mempool_io(connection_pool, 1).await;
+    let control = StateKeeperControl::new(guard);
+    let mut mempool = mempool.with_control(control.clone());
+
+    let l1_batch_env = default_l1_batch_env(1, seconds_since_epoch(), Address::default());
+    let mut updates_manager = UpdatesManager::new(&l1_batch_env, &default_system_env());
+    assert!(!mempool.should_seal_l1_batch_unconditionally(&updates_manager));
+    control.request_l1_batch_seal();
+    // Empty L1 batches are never sealed, so the request should be retained.
+    assert!(!mempool.should_seal_l1_batch_unconditionally(&updates_manager));
+
+    updates_manager.extend_from_executed_transaction(
+        create_transaction(10, 100),
+        create_execution_result(0, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    assert!(mempool.should_seal_l1_batch_unconditionally(&updates_manager));
+    // The request should be consumed.
+    assert!(!mempool.should_seal_l1_batch_unconditionally(&updates_manager));
+}
+
+fn create_priority_transaction(serial_id: u64, received_timestamp_ms: u64) -> Transaction {
+    let l1_tx = L1Tx {
+        execute: Execute::default(),
+        common_data: L1TxCommonData {
+            serial_id: PriorityOpId(serial_id),
+            gas_limit: 100_000.into(),
+            canonical_tx_hash: H256::from_low_u64_be(serial_id + 1),
+            ..L1TxCommonData::default()
+        },
+        received_timestamp_ms,
+    };
+    l1_tx.into()
+}
+
+#[tokio::test]
+async fn sealing_l1_batch_for_forced_inclusion() {
+    let connection_pool = ConnectionPool::constrained_test_pool(1).await;
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+    let (mut mempool, _) = tester.create_test_mempool_io(connection_pool, 1).await;
+    let deadline = StateKeeperConfig::default().forced_inclusion_deadline();
+
+    let l1_batch_env = default_l1_batch_env(1, seconds_since_epoch(), Address::default());
+    let mut updates_manager = UpdatesManager::new(&l1_batch_env, &default_system_env());
+    let now_ms = millis_since_epoch() as u64;
+    updates_manager.extend_from_executed_transaction(
+        create_priority_transaction(0, now_ms),
+        create_execution_result(0, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    // The priority operation has just been received, so there's enough time to execute it.
+    assert!(!mempool.should_seal_l1_batch_unconditionally(&updates_manager));
+
+    // An operation that has waited for half of the deadline should lead to sealing the batch, even if it's included
+    // into a sealed miniblock.
+    let l1_batch_env = default_l1_batch_env(2, seconds_since_epoch(), Address::default());
+    let mut updates_manager = UpdatesManager::new(&l1_batch_env, &default_system_env());
+    let overdue_received_at_ms = now_ms - (deadline / 2).as_millis() as u64 - 1_000;
+    updates_manager.extend_from_executed_transaction(
+        create_priority_transaction(1, overdue_received_at_ms),
+        create_execution_result(0, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    updates_manager.push_miniblock(MiniblockParams {
+        timestamp: l1_batch_env.timestamp + 1,
+        virtual_blocks: 1,
+    });
+    updates_manager.extend_from_executed_transaction(
+        create_priority_transaction(2, now_ms),
+        create_execution_result(1, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    assert!(mempool.should_seal_l1_batch_unconditionally(&updates_manager));
+}
+
+#[tokio::test]
+async fn injecting_scheduled_txs() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+    let mut storage = connection_pool.access_storage().await.unwrap();
+
+    let scheduled_tx = NewScheduledTx {
+        name: "oracle_push".to_owned(),
+        contract_address: Address::repeat_byte(0x23),
+        calldata: vec![1, 2, 3],
+        value: U256::zero(),
+        gas_limit: 1_000_000,
+        interval_sec: 0,
+    };
+    let id = storage
+        .scheduled_txs_dal()
+        .register_scheduled_tx(&scheduled_tx)
+        .await
+        .unwrap();
+
+    let private_key = H256::repeat_byte(0x11);
+    let chain_id = L2ChainId::from(270);
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_tx_responses(|_, _| ExecutionResult::Success { output: vec![] });
+    let (tx_sender, _) =
+        create_test_tx_sender(connection_pool.clone(), chain_id, tx_executor.into()).await;
+    let mut injector = ScheduledTxsInjector::new(private_key, chain_id, tx_sender).unwrap();
+    let mut filter = L2TxFilter {
+        fee_input: BatchFeeInput::l1_pegged(1_000_000_000, 100_000_000),
+        fee_per_gas: 100_000_000,
+        gas_per_pubdata: 100,
+    };
+
+    // The operator account has no funds, so the transaction should be rejected before it's submitted.
+    let submitted = injector
+        .inject_due_txs(&mut storage, &filter)
+        .await
+        .unwrap();
+    assert_eq!(submitted, 0);
+    let scheduled_txs = storage
+        .scheduled_txs_dal()
+        .get_enabled_scheduled_txs()
+        .await
+        .unwrap();
+    assert_eq!(scheduled_txs[0].consecutive_failures, 1);
+    assert_matches!(scheduled_txs[0].last_outcome, ScheduledTxOutcome::Idle);
+
+    let operator_address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+    tester
+        .insert_miniblock(&connection_pool, 1, 100_000_000, filter.fee_input)
+        .await;
+    let balance_log = StorageLog::new_write_log(
+        storage_key_for_eth_balance(&operator_address),
+        u256_to_h256(U256::exp10(18)),
+    );
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), vec![balance_log])])
+        .await
+        .unwrap();
+
+    let submitted = injector
+        .inject_due_txs(&mut storage, &filter)
+        .await
+        .unwrap();
+    assert_eq!(submitted, 1);
+
+    let scheduled_txs = storage
+        .scheduled_txs_dal()
+        .get_enabled_scheduled_txs()
+        .await
+        .unwrap();
+    assert_eq!(scheduled_txs.len(), 1);
+    assert_eq!(scheduled_txs[0].id, id);
+    assert_eq!(scheduled_txs[0].tx, scheduled_tx);
+    let ScheduledTxOutcome::Pending(tx_hash) = scheduled_txs[0].last_outcome else {
+        panic!("Unexpected outcome: {:?}", scheduled_txs[0].last_outcome);
+    };
+    let tx = storage
+        .transactions_web3_dal()
+        .get_transaction(api::TransactionId::Hash(tx_hash), chain_id)
+        .await
+        .unwrap()
+        .expect("scheduled transaction is not persisted");
+    assert_eq!(tx.nonce, U256::zero());
+    assert_eq!(tx.to, Some(scheduled_tx.contract_address));
+
+    // No new transactions should be submitted while the previous one is pending.
+    let submitted = injector
+        .inject_due_txs(&mut storage, &filter)
+        .await
+        .unwrap();
+    assert_eq!(submitted, 0);
+
+    storage
+        .transactions_dal()
+        .mark_tx_as_rejected(tx_hash, "rejected: test")
+        .await;
+    // Change the fee, so that the new transaction has a different hash.
+    filter.fee_per_gas *= 2;
+    let submitted = injector
+        .inject_due_txs(&mut storage, &filter)
+        .await
+        .unwrap();
+    assert_eq!(submitted, 1);
+
+    let scheduled_txs = storage
+        .scheduled_txs_dal()
+        .get_enabled_scheduled_txs()
+        .await
+        .unwrap();
+    assert_eq!(scheduled_txs[0].consecutive_failures, 2);
+    let ScheduledTxOutcome::Pending(new_tx_hash) = scheduled_txs[0].last_outcome else {
+        panic!("Unexpected outcome: {:?}", scheduled_txs[0].last_outcome);
+    };
+    assert_ne!(new_tx_hash, tx_hash);
+    // The nonce of the rejected transaction should be reused.
+    let tx = storage
+        .transactions_web3_dal()
+        .get_transaction(api::TransactionId::Hash(new_tx_hash), chain_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tx.nonce, U256::zero());
+}
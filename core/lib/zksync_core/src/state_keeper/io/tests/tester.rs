@@ -57,6 +57,8 @@ impl Tester {
             internal_enforced_l1_gas_price: None,
             poll_period: 10,
             max_l1_gas_price: None,
+            num_samples_for_blob_base_fee_estimate: 10,
+            l1_pubdata_price_scalar: 1.0,
         };
 
         GasAdjuster::new(Arc::new(eth_client), gas_adjuster_config)
@@ -140,6 +142,7 @@ impl Tester {
                 &get_system_smart_contracts(),
                 L1VerifierConfig::default(),
                 Address::zero(),
+                None,
             )
             .await
             .unwrap();
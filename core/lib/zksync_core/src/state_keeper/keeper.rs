@@ -293,7 +293,11 @@ impl ZkSyncStateKeeper {
 
     async fn wait_for_new_batch_params(&mut self) -> Result<(SystemEnv, L1BatchEnv), Error> {
         while !self.is_canceled() {
-            if let Some(params) = self.io.wait_for_new_batch_params(POLL_WAIT_DURATION).await {
+            if let Some(params) = self
+                .io
+                .wait_for_new_batch_params(POLL_WAIT_DURATION)
+                .await?
+            {
                 return Ok(params);
             }
         }
@@ -620,7 +624,7 @@ impl ZkSyncStateKeeper {
         // Otherwise, `ExcludeAndSeal` resolution is returned, i.e. batch will be sealed and transaction will be included in the next L1 batch.
 
         let is_first_tx = updates_manager.pending_executed_transactions_len() == 0;
-        let resolution = match &exec_result {
+        let mut resolution = match &exec_result {
             TxExecutionResult::BootloaderOutOfGasForTx
             | TxExecutionResult::BootloaderOutOfGasForBlockTip
             | TxExecutionResult::RejectedByVm {
@@ -734,6 +738,18 @@ impl ZkSyncStateKeeper {
                 )
             }
         };
+
+        if let TxExecutionResult::Success { tx_result, .. } = &exec_result {
+            if matches!(
+                resolution,
+                SealResolution::NoSeal | SealResolution::IncludeAndSeal
+            ) {
+                let events = &tx_result.logs.events;
+                if let Some(reason) = self.io.check_executed_tx(&tx, events).await {
+                    resolution = SealResolution::Unexecutable(reason);
+                }
+            }
+        }
         (resolution, exec_result)
     }
 }
@@ -12,8 +12,14 @@ use zksync_mempool::L2TxFilter;
 use zksync_types::H256;
 use zksync_types::{get_nonce_key, Address, Nonce, Transaction, VmVersion};
 
-use super::{metrics::KEEPER_METRICS, types::MempoolGuard};
-use crate::{fee_model::BatchFeeModelInputProvider, utils::pending_protocol_version};
+use super::{
+    metrics::{MempoolEvictionReason, KEEPER_METRICS},
+    types::MempoolGuard,
+};
+use crate::{
+    config_reload::ReloadableConfig, fee_model::BatchFeeModelInputProvider,
+    utils::pending_protocol_version,
+};
 
 /// Creates a mempool filter for L2 transactions based on the current L1 gas price.
 /// The filter is used to filter out transactions from the mempool that do not cover expenses
@@ -40,6 +46,7 @@ pub struct MempoolFetcher {
     sync_interval: Duration,
     sync_batch_size: usize,
     stuck_tx_timeout: Option<Duration>,
+    reloadable_config: Option<watch::Receiver<ReloadableConfig>>,
     #[cfg(test)]
     transaction_hashes_sender: mpsc::UnboundedSender<Vec<H256>>,
 }
@@ -58,11 +65,30 @@ impl MempoolFetcher {
             sync_interval: config.sync_interval(),
             sync_batch_size: config.sync_batch_size,
             stuck_tx_timeout: config.remove_stuck_txs.then(|| config.stuck_tx_timeout()),
+            reloadable_config: None,
             #[cfg(test)]
             transaction_hashes_sender: mpsc::unbounded_channel().0,
         }
     }
 
+    /// Applies the mempool capacity changed at runtime.
+    pub fn with_reloadable_config(mut self, config: watch::Receiver<ReloadableConfig>) -> Self {
+        self.reloadable_config = Some(config);
+        self
+    }
+
+    fn update_capacity(&mut self) {
+        let Some(config) = &mut self.reloadable_config else {
+            return;
+        };
+        if !config.has_changed().unwrap_or(false) {
+            return;
+        }
+        if let Some(params) = &config.borrow_and_update().mempool {
+            self.mempool.set_capacity(params.capacity);
+        }
+    }
+
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
         if let Some(stuck_tx_timeout) = self.stuck_tx_timeout {
@@ -72,6 +98,9 @@ impl MempoolFetcher {
                 .await
                 .context("failed removing stuck transactions")?;
             tracing::info!("Number of stuck txs was removed: {removed_txs}");
+            KEEPER_METRICS.mempool_evicted_transactions[&MempoolEvictionReason::Stuck]
+                .inc_by(removed_txs as u64);
+            release_withdrawal_limit_usage(&mut storage).await?;
         }
         storage
             .transactions_dal()
@@ -85,9 +114,14 @@ impl MempoolFetcher {
                 tracing::info!("Stop signal received, mempool is shutting down");
                 break;
             }
+            self.update_capacity();
             let latency = KEEPER_METRICS.mempool_sync.start();
             let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
             let mempool_info = self.mempool.get_mempool_info();
+            KEEPER_METRICS.mempool_evicted_transactions[&MempoolEvictionReason::LowFee]
+                .inc_by(mempool_info.stashed_transaction_count as u64);
+            KEEPER_METRICS.mempool_evicted_transactions[&MempoolEvictionReason::Capacity]
+                .inc_by(mempool_info.purged_transaction_count as u64);
             let protocol_version = pending_protocol_version(&mut storage)
                 .await
                 .context("failed getting pending protocol version")?;
@@ -110,6 +144,9 @@ impl MempoolFetcher {
                 .await
                 .context("failed syncing mempool")?;
             let nonces = get_transaction_nonces(&mut storage, &transactions).await?;
+            if !mempool_info.purged_accounts.is_empty() {
+                release_withdrawal_limit_usage(&mut storage).await?;
+            }
             drop(storage);
 
             #[cfg(test)]
@@ -129,6 +166,21 @@ impl MempoolFetcher {
     }
 }
 
+/// Releases withdrawal limit usage reserved by transactions evicted from the mempool.
+async fn release_withdrawal_limit_usage(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    let released = storage
+        .withdrawal_limits_dal()
+        .release_orphaned_usage()
+        .await
+        .context("failed releasing withdrawal limit usage of evicted transactions")?;
+    if released > 0 {
+        tracing::info!(
+            "Released {released} withdrawal limit usage records of evicted transactions"
+        );
+    }
+    Ok(())
+}
+
 /// Loads nonces for all distinct `transactions` initiators from the storage.
 async fn get_transaction_nonces(
     storage: &mut StorageProcessor<'_>,
@@ -6,10 +6,11 @@ use std::{
 };
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    LatencyObserver, Metrics,
 };
 use zksync_mempool::MempoolStore;
+use zksync_types::{helpers::unix_timestamp_ms, U256};
 
 use super::seal_criteria::SealResolution;
 use crate::metrics::InteractionType;
@@ -47,11 +48,28 @@ impl TxExecutionType {
     }
 }
 
+/// Reason of removing L2 transactions from the mempool without executing them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(crate) enum MempoolEvictionReason {
+    /// Transactions of an account were stashed because their fee is too low for the current conditions.
+    /// Stashed transactions remain in Postgres and can be loaded to the mempool again.
+    LowFee,
+    /// Transactions with non-executable nonces were purged because the mempool is full.
+    Capacity,
+    /// Transactions were removed on startup because they are stuck for too long.
+    Stuck,
+}
+
 const INCLUSION_DELAY_BUCKETS: Buckets = Buckets::values(&[
     0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1, 1.2, 1.3, 1.4, 1.5, 1.6, 1.7, 1.8, 1.9,
     2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 20.0, 30.0, 60.0, 120.0, 240.0,
 ]);
 
+const FORCED_INCLUSION_LATENCY_BUCKETS: Buckets = Buckets::values(&[
+    1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1_200.0, 1_800.0, 3_600.0, 7_200.0,
+]);
+
 /// General-purpose state keeper metrics.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_state_keeper")]
@@ -81,11 +99,31 @@ pub(crate) struct StateKeeperMetrics {
     pub tx_execution_time: Family<TxExecutionStage, Histogram<Duration>>,
     /// Number of times gas price was reported as too high.
     pub gas_price_too_high: Counter,
+    /// Number of L2 transactions removed from the mempool without execution.
+    pub mempool_evicted_transactions: Family<MempoolEvictionReason, Counter>,
+    /// Number of times an account has reached the per-miniblock or per-batch transaction cap.
+    pub capped_accounts: Counter,
+    /// Time between a priority operation being picked up from the settlement layer and its inclusion
+    /// into a sealed miniblock.
+    #[metrics(buckets = FORCED_INCLUSION_LATENCY_BUCKETS)]
+    pub forced_inclusion_latency: Histogram<Duration>,
 }
 
 #[vise::register]
 pub(crate) static KEEPER_METRICS: vise::Global<StateKeeperMetrics> = vise::Global::new();
 
+/// Upper bounds (inclusive) of `max_fee_per_gas` buckets used to split the L2 priority queue,
+/// together with their labels in gwei.
+const FEE_PER_GAS_BUCKETS: [(&str, u64); 7] = [
+    ("0.05", 50_000_000),
+    ("0.1", 100_000_000),
+    ("0.25", 250_000_000),
+    ("0.5", 500_000_000),
+    ("1", 1_000_000_000),
+    ("2.5", 2_500_000_000),
+    ("10", 10_000_000_000),
+];
+
 /// State keeper-related gauges exposed via a collector.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_state_keeper")]
@@ -96,6 +134,15 @@ pub(super) struct StateKeeperGauges {
     mempool_l2_size: Gauge<u64>,
     /// Current size of the L2 priority queue.
     l2_priority_queue_size: Gauge<usize>,
+    /// Current size of the L2 priority queue split by `max_fee_per_gas` of transactions. The label value
+    /// is the inclusive upper bound of the bucket in gwei; buckets are not cumulative.
+    #[metrics(labels = ["max_fee_per_gas_gwei"])]
+    l2_priority_queue_size_by_fee: LabeledFamily<&'static str, Gauge<usize>>,
+    /// Age of the oldest L1 transaction and of the oldest L2 transaction in the priority queue.
+    /// Zero if there are no transactions of the corresponding type.
+    mempool_oldest_tx_age: Family<TxExecutionType, Gauge<Duration>>,
+    /// Number of pending L2 transactions of the account with the most transactions in the mempool.
+    mempool_largest_account_size: Gauge<usize>,
 }
 
 impl StateKeeperGauges {
@@ -105,7 +152,13 @@ impl StateKeeperGauges {
 
         let res = COLLECTOR.before_scrape(move || {
             pool_ref.upgrade().map(|pool| {
-                let stats = pool.lock().expect("failed to acquire mempool lock").stats();
+                let fee_upper_bounds = FEE_PER_GAS_BUCKETS.map(|(_, bound)| U256::from(bound));
+                let (stats, fee_distribution) = {
+                    let mempool = pool.lock().expect("failed to acquire mempool lock");
+                    let distribution =
+                        mempool.l2_priority_queue_fee_distribution(&fee_upper_bounds);
+                    (mempool.stats(), distribution)
+                };
                 drop(pool); // Don't prevent the pool to be dropped
 
                 let gauges = StateKeeperGauges::default();
@@ -114,6 +167,38 @@ impl StateKeeperGauges {
                 gauges
                     .l2_priority_queue_size
                     .set(stats.l2_priority_queue_size);
+
+                let fee_labels = FEE_PER_GAS_BUCKETS
+                    .iter()
+                    .map(|&(label, _)| label)
+                    .chain(["+Inf"]);
+                for (label, tx_count) in fee_labels.zip(fee_distribution) {
+                    gauges.l2_priority_queue_size_by_fee[&label].set(tx_count);
+                }
+
+                let now_ms = unix_timestamp_ms();
+                let oldest_txs = [
+                    (
+                        TxExecutionType::L1,
+                        stats.oldest_l1_transaction_received_at_ms,
+                    ),
+                    (
+                        TxExecutionType::L2,
+                        stats.oldest_l2_transaction_received_at_ms,
+                    ),
+                ];
+                for (tx_type, received_at_ms) in oldest_txs {
+                    let age = received_at_ms.map_or(Duration::ZERO, |received_at_ms| {
+                        Duration::from_millis(now_ms.saturating_sub(received_at_ms))
+                    });
+                    gauges.mempool_oldest_tx_age[&tx_type].set(age);
+                }
+
+                let largest_account_size =
+                    stats.largest_account.map_or(0, |(_, tx_count)| tx_count);
+                gauges
+                    .mempool_largest_account_size
+                    .set(largest_account_size);
                 gauges
             })
         });
@@ -262,6 +347,7 @@ pub(super) enum MiniblockSealStage {
     PreInsertTxs,
     InsertMiniblockHeader,
     MarkTransactionsInMiniblock,
+    InsertWithdrawalLimitUsage,
     InsertStorageLogs,
     ApplyStorageLogs,
     InsertFactoryDeps,
@@ -271,6 +357,7 @@ pub(super) enum MiniblockSealStage {
     InsertEvents,
     ExtractL2ToL1Logs,
     InsertL2ToL1Logs,
+    InsertContentHash,
     CommitMiniblock,
     ReportTxMetrics,
 }
@@ -395,3 +482,26 @@ pub(super) struct ExecutorMetrics {
 
 #[vise::register]
 pub(super) static EXECUTOR_METRICS: vise::Global<ExecutorMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "event", rename_all = "snake_case")]
+pub(super) enum ScheduledTxEvent {
+    Submitted,
+    Rejected,
+    Succeeded,
+    Failed,
+    Dropped,
+}
+
+/// Metrics for operator-scheduled L2 transactions.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "state_keeper_scheduled_txs")]
+pub(super) struct ScheduledTxsMetrics {
+    /// Number of scheduled transactions that went through a certain lifecycle event.
+    pub events: Family<ScheduledTxEvent, Counter>,
+    /// Maximum number of consecutive failures among all enabled schedules.
+    pub max_consecutive_failures: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static SCHEDULED_TXS_METRICS: vise::Global<ScheduledTxsMetrics> = vise::Global::new();
@@ -8,18 +8,24 @@ use zksync_config::{
 use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStore;
 
+pub(crate) use self::io::scheduled_txs::ScheduledTxsInjector;
 pub use self::{
     batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
+    health::{ForcedInclusionHealthCheck, StateKeeperHealthCheck},
     io::{mempool::MempoolIO, MiniblockSealer, MiniblockSealerHandle, StateKeeperIO},
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
     seal_criteria::SequencerSealer,
-    types::MempoolGuard,
+    types::{MempoolGuard, StateKeeperControl},
+};
+use crate::{
+    api_server::tx_sender::WithdrawalLimiter, config_reload::ReloadableConfig,
+    fee_model::BatchFeeModelInputProvider, shared_sequencer::SharedSequencerSource,
 };
-use crate::fee_model::BatchFeeModelInputProvider;
 
 mod batch_executor;
 pub(crate) mod extractors;
+mod health;
 pub(crate) mod io;
 mod keeper;
 mod mempool_actor;
@@ -39,9 +45,13 @@ pub(crate) async fn create_state_keeper(
     mempool_config: &MempoolConfig,
     pool: ConnectionPool,
     mempool: MempoolGuard,
+    control: StateKeeperControl,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     miniblock_sealer_handle: MiniblockSealerHandle,
     object_store: Arc<dyn ObjectStore>,
+    shared_sequencer: Option<SharedSequencerSource>,
+    scheduled_txs_injector: Option<ScheduledTxsInjector>,
+    reloadable_config: watch::Receiver<ReloadableConfig>,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper {
     let batch_executor_base = MainBatchExecutor::new(
@@ -52,9 +62,13 @@ pub(crate) async fn create_state_keeper(
         state_keeper_config.upload_witness_inputs_to_gcs,
         state_keeper_config.enum_index_migration_chunk_size(),
         false,
+    )
+    .with_state_keeper_db_tuning(
+        db_config.state_keeper_db_block_cache_size(),
+        &db_config.state_keeper_rocksdb,
     );
 
-    let io = MempoolIO::new(
+    let mut io = MempoolIO::new(
         mempool,
         object_store,
         miniblock_sealer_handle,
@@ -67,7 +81,18 @@ pub(crate) async fn create_state_keeper(
         network_config.zksync_network_id,
     )
     .await
-    .expect("Failed initializing main node I/O for state keeper");
+    .expect("Failed initializing main node I/O for state keeper")
+    .with_control(control)
+    .with_reloadable_config(reloadable_config)
+    .with_withdrawal_limiter(WithdrawalLimiter::new(
+        contracts_config.l2_erc20_bridge_addr,
+    ));
+    if let Some(shared_sequencer) = shared_sequencer {
+        io = io.with_shared_sequencer(shared_sequencer);
+    }
+    if let Some(injector) = scheduled_txs_injector {
+        io = io.with_scheduled_txs_injector(injector);
+    }
 
     let sealer = SequencerSealer::new(state_keeper_config);
     ZkSyncStateKeeper::new(
@@ -1,13 +1,16 @@
 use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 use multivm::interface::VmExecutionResultAndLogs;
 use zksync_dal::StorageProcessor;
 use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction, H256,
 };
 
 use super::metrics::StateKeeperGauges;
@@ -51,6 +54,24 @@ impl MempoolGuard {
             .next_transaction(filter)
     }
 
+    pub fn next_transaction_excluding(
+        &mut self,
+        filter: &L2TxFilter,
+        excluded_accounts: &HashSet<Address>,
+    ) -> Option<Transaction> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .next_transaction_excluding(filter, excluded_accounts)
+    }
+
+    pub fn next_l1_transaction(&mut self) -> Option<Transaction> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .next_l1_transaction()
+    }
+
     pub fn rollback(&mut self, rejected: &Transaction) {
         self.0
             .lock()
@@ -58,6 +79,20 @@ impl MempoolGuard {
             .rollback(rejected);
     }
 
+    pub fn remove_l2_transaction(&mut self, account: Address, nonce: Nonce, hash: H256) -> bool {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .remove_l2_transaction(account, nonce, hash)
+    }
+
+    pub fn set_capacity(&mut self, capacity: u64) {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .set_capacity(capacity);
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
         self.0
             .lock()
@@ -78,6 +113,42 @@ impl MempoolGuard {
     }
 }
 
+/// Handle allowing other node components (e.g., the `admin` API namespace) to control the state keeper
+/// running in the same process.
+#[derive(Debug, Clone)]
+pub struct StateKeeperControl {
+    mempool: MempoolGuard,
+    l1_batch_seal_requested: Arc<AtomicBool>,
+}
+
+impl StateKeeperControl {
+    pub fn new(mempool: MempoolGuard) -> Self {
+        Self {
+            mempool,
+            l1_batch_seal_requested: Arc::default(),
+        }
+    }
+
+    /// Requests the state keeper to seal the current L1 batch. Since empty L1 batches are never sealed,
+    /// the request takes effect once the batch contains at least one transaction.
+    pub fn request_l1_batch_seal(&self) {
+        self.l1_batch_seal_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether sealing the current L1 batch was requested and resets the request.
+    pub(super) fn take_l1_batch_seal_request(&self) -> bool {
+        self.l1_batch_seal_requested.swap(false, Ordering::Relaxed)
+    }
+
+    /// Removes a pending L2 transaction from the in-memory mempool. Returns `false` if the transaction
+    /// is not in the mempool; e.g., it may be executed by the state keeper at the moment.
+    pub fn remove_mempool_transaction(&self, account: Address, nonce: Nonce, hash: H256) -> bool {
+        self.mempool
+            .clone()
+            .remove_l2_transaction(account, nonce, hash)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ExecutionMetricsForCriteria {
     pub l1_gas: BlockGasCount,
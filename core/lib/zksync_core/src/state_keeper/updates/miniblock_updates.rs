@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use chrono::Utc;
 use multivm::{
     interface::{ExecutionResult, L2BlockEnv, VmExecutionResultAndLogs},
     vm_latest::TransactionVmExt,
@@ -143,6 +144,7 @@ impl MiniblockUpdates {
             compressed_bytecodes,
             call_traces,
             revert_reason,
+            executed_at: Some(Utc::now()),
         });
     }
 
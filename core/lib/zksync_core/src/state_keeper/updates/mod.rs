@@ -155,6 +155,20 @@ impl UpdatesManager {
     pub(crate) fn pending_txs_encoding_size(&self) -> usize {
         self.l1_batch.txs_encoding_size + self.miniblock.txs_encoding_size
     }
+
+    /// Returns the time (in ms since the Unix epoch) the oldest priority operation in the pending L1 batch
+    /// was received at. Priority operations are executed in the order they were received, so it's the first one.
+    pub(crate) fn oldest_priority_op_received_at_ms(&self) -> Option<u64> {
+        let sealed_txs = if self.l1_batch.priority_ops_onchain_data.is_empty() {
+            [].iter()
+        } else {
+            self.l1_batch.executed_transactions.iter()
+        };
+        let tx = sealed_txs
+            .chain(&self.miniblock.executed_transactions)
+            .find(|tx| tx.transaction.is_l1())?;
+        Some(tx.transaction.received_timestamp_ms)
+    }
 }
 
 /// Command to seal a miniblock containing all necessary data for it.
@@ -0,0 +1,27 @@
+use vise::{Counter, EncodeLabelValue, LabeledFamily, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum FailureKind {
+    Error,
+    Panic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum FailureOutcome {
+    Restarted,
+    Escalated,
+}
+
+/// Metrics for supervised background tasks.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_supervisor")]
+pub(super) struct SupervisorMetrics {
+    /// Number of failures of supervised tasks split by the task, failure kind and whether the task was restarted.
+    #[metrics(labels = ["task", "kind", "outcome"])]
+    pub failures: LabeledFamily<(&'static str, FailureKind, FailureOutcome), Counter, 3>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<SupervisorMetrics> = vise::Global::new();
@@ -0,0 +1,139 @@
+//! Supervision of background tasks.
+//!
+//! By default, a failure (an error or a panic) of any server task shuts down the entire server. This is the only
+//! safe option for tasks like the state keeper or the Ethereum sender, which hold state that cannot be safely
+//! recovered in-process. Auxiliary tasks that re-derive their state from Postgres on start (e.g., the Ethereum
+//! watcher, pub-sub notifiers, webhooks and the account nonce sweeper) can instead be restarted after a transient
+//! failure. [`SupervisedTask`] restarts such tasks with exponential backoff according to a [`RestartPolicy`],
+//! and escalates the failure (i.e., returns an error shutting down the server) if the task fails too often.
+
+use std::{collections::VecDeque, fmt, future::Future, time::Instant};
+
+use tokio::sync::watch;
+use zksync_config::SupervisorConfig;
+use zksync_utils::panic_extractor::try_extract_panic_message;
+
+use self::metrics::{FailureKind, FailureOutcome, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Policy applied to a failed [`SupervisedTask`].
+#[derive(Debug, Clone, Default)]
+pub enum RestartPolicy {
+    /// Escalate the failure, i.e., shut down the server.
+    #[default]
+    Escalate,
+    /// Restart the task with exponential backoff; escalate the failure if the task is restarted too often.
+    Restart(SupervisorConfig),
+}
+
+impl RestartPolicy {
+    /// Returns the policy for a task that is safe to restart. Such a task is restarted only if supervision
+    /// is configured.
+    pub fn for_restartable_task(config: Option<&SupervisorConfig>) -> Self {
+        config.map_or(Self::Escalate, |config| Self::Restart(config.clone()))
+    }
+}
+
+/// Background task supervised according to a [`RestartPolicy`]. The task is (re)created by a closure
+/// accepting the stop signal receiver.
+pub struct SupervisedTask<F> {
+    name: &'static str,
+    policy: RestartPolicy,
+    create_task: F,
+}
+
+impl<F> fmt::Debug for SupervisedTask<F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SupervisedTask")
+            .field("name", &self.name)
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, Fut> SupervisedTask<F>
+where
+    F: FnMut(watch::Receiver<bool>) -> Fut + Send,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    pub fn new(name: &'static str, policy: RestartPolicy, create_task: F) -> Self {
+        Self {
+            name,
+            policy,
+            create_task,
+        }
+    }
+
+    /// Runs the task until it completes successfully, the stop signal is received, or a failure is escalated.
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut restarts = VecDeque::new();
+        let mut next_backoff = None;
+        loop {
+            let started_at = Instant::now();
+            let task = tokio::spawn((self.create_task)(stop_receiver.clone()));
+            let (err, kind) = match task.await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(err)) => (err, FailureKind::Error),
+                Err(err) => {
+                    let panic_message = try_extract_panic_message(err);
+                    let err = anyhow::anyhow!("task panicked: {panic_message}");
+                    (err, FailureKind::Panic)
+                }
+            };
+            if *stop_receiver.borrow() {
+                // Failures during shutdown are not retried or reported in metrics.
+                return Err(err);
+            }
+
+            let RestartPolicy::Restart(config) = &self.policy else {
+                return Err(self.escalate(err, kind));
+            };
+            let now = Instant::now();
+            while restarts.front().map_or(false, |&restarted_at| {
+                now - restarted_at > config.restart_window()
+            }) {
+                restarts.pop_front();
+            }
+            if restarts.len() >= config.max_restarts as usize {
+                return Err(self.escalate(err, kind));
+            }
+
+            // Reset backoff if the task has been running for long enough.
+            let backoff = match next_backoff {
+                Some(backoff) if started_at.elapsed() <= config.restart_window() => backoff,
+                _ => config.initial_backoff(),
+            };
+            next_backoff = Some((backoff * 2).min(config.max_backoff()));
+
+            tracing::warn!(
+                "Task `{}` failed, restarting it in {backoff:?}: {err:#}",
+                self.name
+            );
+            METRICS.failures[&(self.name, kind, FailureOutcome::Restarted)].inc();
+            if tokio::time::timeout(backoff, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                tracing::info!(
+                    "Stop signal received, task `{}` is not restarted",
+                    self.name
+                );
+                return Ok(());
+            }
+            restarts.push_back(Instant::now());
+        }
+    }
+
+    fn escalate(&self, err: anyhow::Error, kind: FailureKind) -> anyhow::Error {
+        tracing::error!(
+            "Task `{}` failed, shutting down the server: {err:#}",
+            self.name
+        );
+        METRICS.failures[&(self.name, kind, FailureOutcome::Escalated)].inc();
+        err.context(format!("task `{}` failed", self.name))
+    }
+}
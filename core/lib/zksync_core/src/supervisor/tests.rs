@@ -0,0 +1,115 @@
+//! Tests for task supervision.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use super::*;
+
+fn mock_config(max_restarts: u32) -> SupervisorConfig {
+    SupervisorConfig {
+        max_restarts,
+        restart_window_sec: 600,
+        initial_backoff_ms: 1,
+        max_backoff_ms: 10,
+    }
+}
+
+/// Creates a task that fails on the first `failures` attempts, alternating between panics and errors.
+fn flaky_task(
+    failures: usize,
+) -> (
+    Arc<AtomicUsize>,
+    impl FnMut(watch::Receiver<bool>) -> futures::future::BoxFuture<'static, anyhow::Result<()>>,
+) {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_for_task = attempts.clone();
+    let create_task = move |_stop_receiver: watch::Receiver<bool>| {
+        let attempt = attempts_for_task.fetch_add(1, Ordering::SeqCst);
+        let future = async move {
+            if attempt >= failures {
+                Ok(())
+            } else if attempt % 2 == 0 {
+                panic!("panic #{attempt}")
+            } else {
+                anyhow::bail!("error #{attempt}")
+            }
+        };
+        Box::pin(future) as futures::future::BoxFuture<'static, _>
+    };
+    (attempts, create_task)
+}
+
+#[tokio::test]
+async fn restarting_failed_task() {
+    let (attempts, create_task) = flaky_task(3);
+    let policy = RestartPolicy::Restart(mock_config(5));
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+
+    SupervisedTask::new("test", policy, create_task)
+        .run(stop_receiver)
+        .await
+        .unwrap();
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn escalating_failure_after_max_restarts() {
+    let (attempts, create_task) = flaky_task(usize::MAX);
+    let policy = RestartPolicy::Restart(mock_config(2));
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+
+    let err = SupervisedTask::new("test", policy, create_task)
+        .run(stop_receiver)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("task `test` failed"), "{err}");
+    assert!(err.contains("task panicked: panic #2"), "{err}");
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn escalating_failure_without_restarts() {
+    let (attempts, create_task) = flaky_task(usize::MAX);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = SupervisedTask::new("test", RestartPolicy::Escalate, create_task)
+        .run(stop_receiver)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("task panicked: panic #0"), "{err}");
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+    let policy = RestartPolicy::for_restartable_task(None);
+    assert_matches::assert_matches!(policy, RestartPolicy::Escalate);
+}
+
+#[tokio::test]
+async fn stopping_task_during_backoff() {
+    let (attempts, create_task) = flaky_task(usize::MAX);
+    let config = SupervisorConfig {
+        initial_backoff_ms: 3_600_000,
+        max_backoff_ms: 3_600_000,
+        ..mock_config(5)
+    };
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let task = tokio::spawn(
+        SupervisedTask::new("test", RestartPolicy::Restart(config), create_task).run(stop_receiver),
+    );
+
+    while attempts.load(Ordering::SeqCst) == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    stop_sender.send_replace(true);
+    tokio::time::timeout(Duration::from_secs(5), task)
+        .await
+        .expect("task hasn't stopped")
+        .unwrap()
+        .unwrap();
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
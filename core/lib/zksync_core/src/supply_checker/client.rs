@@ -0,0 +1,124 @@
+//! Settlement layer client used by the supply checker.
+
+use std::fmt;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_eth_client::EthInterface;
+use zksync_types::{
+    ethabi::{self, ParamType, Token},
+    web3::types::{Bytes, CallRequest},
+    Address, L2ChainId, H160, U256,
+};
+
+const COMPONENT: &str = "supply_checker";
+
+/// Settlement layer operations necessary for the supply checker.
+#[async_trait]
+pub trait LockedFundsClient: fmt::Debug + Send + Sync {
+    /// Returns the amount of the base token locked on the settlement layer.
+    async fn locked_amount(&self) -> anyhow::Result<U256>;
+}
+
+/// Address used by the shared bridge to denote ETH.
+const SHARED_BRIDGE_ETH_TOKEN_ADDRESS: Address =
+    H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+/// Settlement layer source of the locked base token amount.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockedFunds {
+    /// Balance of the chain tracked by the shared bridge. The shared bridge holds funds of all chains
+    /// sharing it, so its own token balance cannot be used.
+    SharedBridge {
+        bridge_addr: Address,
+        chain_id: L2ChainId,
+    },
+    /// Token balance of a contract holding funds of this chain only (e.g., the diamond proxy of a chain
+    /// without the shared bridge).
+    Contract(Address),
+}
+
+/// [`LockedFundsClient`] implementation reading the locked base token amount from the settlement layer.
+#[derive(Debug)]
+pub struct EthLockedFundsClient {
+    client: Box<dyn EthInterface>,
+    /// Settlement layer address of the base token; `None` for ETH.
+    base_token_l1_addr: Option<Address>,
+    locked_funds: LockedFunds,
+}
+
+impl EthLockedFundsClient {
+    pub fn new(
+        client: Box<dyn EthInterface>,
+        base_token_l1_addr: Option<Address>,
+        locked_funds: LockedFunds,
+    ) -> Self {
+        Self {
+            client,
+            base_token_l1_addr,
+            locked_funds,
+        }
+    }
+
+    async fn call_uint(
+        &self,
+        contract_addr: Address,
+        signature: &str,
+        params: &[ParamType],
+        args: &[Token],
+    ) -> anyhow::Result<U256> {
+        let mut data = ethabi::short_signature(signature, params).to_vec();
+        data.extend(ethabi::encode(args));
+        let request = CallRequest {
+            to: Some(contract_addr),
+            data: Some(Bytes(data)),
+            ..CallRequest::default()
+        };
+        let output = self.client.call(request, None, COMPONENT).await?;
+        let tokens = ethabi::decode(&[ParamType::Uint(256)], &output.0)
+            .with_context(|| format!("failed decoding `{signature}` output"))?;
+        tokens
+            .into_iter()
+            .next()
+            .and_then(Token::into_uint)
+            .with_context(|| format!("unexpected `{signature}` output"))
+    }
+}
+
+#[async_trait]
+impl LockedFundsClient for EthLockedFundsClient {
+    async fn locked_amount(&self) -> anyhow::Result<U256> {
+        match (self.locked_funds, self.base_token_l1_addr) {
+            (
+                LockedFunds::SharedBridge {
+                    bridge_addr,
+                    chain_id,
+                },
+                base_token_l1_addr,
+            ) => {
+                let token = base_token_l1_addr.unwrap_or(SHARED_BRIDGE_ETH_TOKEN_ADDRESS);
+                let args = [Token::Uint(chain_id.as_u64().into()), Token::Address(token)];
+                self.call_uint(
+                    bridge_addr,
+                    "chainBalance",
+                    &[ParamType::Uint(256), ParamType::Address],
+                    &args,
+                )
+                .await
+            }
+            (LockedFunds::Contract(contract_addr), None) => {
+                Ok(self.client.eth_balance(contract_addr, COMPONENT).await?)
+            }
+            (LockedFunds::Contract(contract_addr), Some(base_token_l1_addr)) => {
+                let args = [Token::Address(contract_addr)];
+                self.call_uint(
+                    base_token_l1_addr,
+                    "balanceOf",
+                    &[ParamType::Address],
+                    &args,
+                )
+                .await
+            }
+        }
+    }
+}
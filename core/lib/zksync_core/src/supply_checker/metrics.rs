@@ -0,0 +1,22 @@
+use vise::{Gauge, Metrics};
+
+/// Metrics for the supply checker. Amounts are measured in gwei.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_supply_checker")]
+pub(super) struct SupplyCheckerMetrics {
+    /// Total supply of the base token on the chain.
+    pub total_supply_gwei: Gauge<u64>,
+    /// Base token withdrawals burned on the chain, but not finalized on the settlement layer.
+    pub unfinalized_withdrawals_gwei: Gauge<u64>,
+    /// Amount of the base token locked on the settlement layer.
+    pub locked_amount_gwei: Gauge<u64>,
+    /// Excess of the chain liabilities (the supply and unfinalized withdrawals) over the backing amount.
+    pub deficit_gwei: Gauge<u64>,
+    /// Excess of the backing amount over the chain liabilities.
+    pub surplus_gwei: Gauge<u64>,
+    /// Set to 1 if the supply divergence alert is raised.
+    pub alert: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<SupplyCheckerMetrics> = vise::Global::new();
@@ -0,0 +1,247 @@
+//! Supply checker: reconciles the total supply of the base token on the chain with the amount locked
+//! on the settlement layer.
+//!
+//! Each unit of the base token on the chain must be backed by a unit locked in the bridge on the settlement layer.
+//! The checker compares the chain *liabilities* (the total supply, and withdrawals burned on the chain, but not yet
+//! finalized on the settlement layer) with the *backing* (the locked amount, and the configured supply minted without
+//! backing, such as genesis balances).
+//!
+//! The liabilities exceeding the backing by more than the configured epsilon indicate minting unbacked tokens and
+//! are always alerted on. The backing is expected to exceed the liabilities by the amount of in-flight deposits
+//! and withdrawals not yet tracked by the withdrawal finalizer; thus, the surplus is only alerted on if a maximum
+//! surplus is configured. To filter out transient divergences (e.g., caused by a withdrawal finalized by a third party
+//! and not yet marked as such by the withdrawal finalizer), an alert is only raised after the divergence is observed
+//! for the configured number of consecutive checks. The alert is reported via the health check, logs and metrics.
+
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_config::SupplyCheckerConfig;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{utils::storage_key_for_eth_total_supply, Address, MiniblockNumber, U256};
+use zksync_utils::h256_to_u256;
+
+pub use self::client::{EthLockedFundsClient, LockedFunds, LockedFundsClient};
+use self::metrics::METRICS;
+
+mod client;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+const GWEI: u64 = 1_000_000_000;
+
+fn to_gwei(amount: U256) -> u64 {
+    let gwei = amount / GWEI;
+    if gwei > U256::from(u64::MAX) {
+        u64::MAX
+    } else {
+        gwei.as_u64()
+    }
+}
+
+/// Divergence between the chain liabilities and the backing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "amount")]
+pub(crate) enum Divergence {
+    /// Liabilities exceed the backing by the specified amount.
+    Deficit(U256),
+    /// Backing exceeds the liabilities by the specified amount.
+    Surplus(U256),
+}
+
+/// Base token accounting snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SupplySnapshot {
+    /// Miniblock at which the total supply was read.
+    pub miniblock_number: MiniblockNumber,
+    /// Total supply of the base token on the chain.
+    pub total_supply: U256,
+    /// Base token withdrawals burned on the chain, but not finalized on the settlement layer.
+    pub unfinalized_withdrawals: U256,
+    /// Amount of the base token locked on the settlement layer.
+    pub locked_amount: U256,
+    /// Supply minted on the chain without backing.
+    pub unbacked_supply: U256,
+}
+
+impl SupplySnapshot {
+    fn liabilities(&self) -> U256 {
+        self.total_supply
+            .saturating_add(self.unfinalized_withdrawals)
+    }
+
+    fn backing(&self) -> U256 {
+        self.locked_amount.saturating_add(self.unbacked_supply)
+    }
+
+    fn divergence(&self) -> Option<Divergence> {
+        let (liabilities, backing) = (self.liabilities(), self.backing());
+        if liabilities > backing {
+            Some(Divergence::Deficit(liabilities - backing))
+        } else if backing > liabilities {
+            Some(Divergence::Surplus(backing - liabilities))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SupplyCheckerHealthDetails {
+    #[serde(flatten)]
+    snapshot: SupplySnapshot,
+    divergence: Option<Divergence>,
+    /// Number of consecutive checks with the divergence beyond the threshold.
+    consecutive_divergences: u32,
+}
+
+/// Task checking the base token supply. See the module-level docs for details.
+#[derive(Debug)]
+pub struct SupplyChecker {
+    client: Box<dyn LockedFundsClient>,
+    pool: ConnectionPool,
+    config: SupplyCheckerConfig,
+    health_updater: HealthUpdater,
+    consecutive_divergences: u32,
+}
+
+impl SupplyChecker {
+    pub fn new(
+        client: Box<dyn LockedFundsClient>,
+        pool: ConnectionPool,
+        config: SupplyCheckerConfig,
+    ) -> Self {
+        Self {
+            client,
+            pool,
+            config,
+            health_updater: ReactiveHealthCheck::new("supply_checker").1,
+            consecutive_divergences: 0,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, supply checker is shutting down");
+                break;
+            }
+
+            if let Err(err) = self.check().await {
+                tracing::warn!("Failed checking base token supply: {err:#}");
+            }
+            tokio::time::sleep(self.config.check_interval()).await;
+        }
+        Ok(())
+    }
+
+    async fn take_snapshot(&self) -> anyhow::Result<Option<SupplySnapshot>> {
+        // The locked amount is read first, so that withdrawals finalized after reading it are still accounted
+        // for as unfinalized. Otherwise, such withdrawals would lead to a transient deficit.
+        let locked_amount = self.client.locked_amount().await?;
+
+        let mut storage = self.pool.access_storage_tagged("supply_checker").await?;
+        let Some(miniblock_number) = storage.blocks_dal().get_sealed_miniblock_number().await?
+        else {
+            return Ok(None);
+        };
+        let total_supply = storage
+            .storage_web3_dal()
+            .get_historical_value_unchecked(&storage_key_for_eth_total_supply(), miniblock_number)
+            .await?;
+        // The withdrawal finalizer records the base token with the zero address.
+        let unfinalized_withdrawals = storage
+            .withdrawals_dal()
+            .get_unfinalized_withdrawals_amount(Address::zero())
+            .await?;
+
+        Ok(Some(SupplySnapshot {
+            miniblock_number,
+            total_supply: h256_to_u256(total_supply),
+            unfinalized_withdrawals,
+            locked_amount,
+            unbacked_supply: U256::from(self.config.unbacked_supply_gwei) * GWEI,
+        }))
+    }
+
+    /// Returns the divergence if it exceeds the configured thresholds.
+    fn excessive_divergence(&self, snapshot: &SupplySnapshot) -> Option<Divergence> {
+        let divergence = snapshot.divergence()?;
+        let is_excessive = match divergence {
+            Divergence::Deficit(amount) => amount > U256::from(self.config.epsilon_gwei) * GWEI,
+            Divergence::Surplus(amount) => self
+                .config
+                .max_surplus_gwei
+                .map_or(false, |max_surplus| amount > U256::from(max_surplus) * GWEI),
+        };
+        is_excessive.then_some(divergence)
+    }
+
+    /// Performs a single check. Returns `true` if the alert is raised.
+    pub(crate) async fn check(&mut self) -> anyhow::Result<bool> {
+        let Some(snapshot) = self.take_snapshot().await? else {
+            return Ok(false);
+        };
+        METRICS
+            .total_supply_gwei
+            .set(to_gwei(snapshot.total_supply));
+        METRICS
+            .unfinalized_withdrawals_gwei
+            .set(to_gwei(snapshot.unfinalized_withdrawals));
+        METRICS
+            .locked_amount_gwei
+            .set(to_gwei(snapshot.locked_amount));
+        let divergence = snapshot.divergence();
+        let (deficit, surplus) = match divergence {
+            Some(Divergence::Deficit(amount)) => (amount, U256::zero()),
+            Some(Divergence::Surplus(amount)) => (U256::zero(), amount),
+            None => (U256::zero(), U256::zero()),
+        };
+        METRICS.deficit_gwei.set(to_gwei(deficit));
+        METRICS.surplus_gwei.set(to_gwei(surplus));
+
+        let excessive_divergence = self.excessive_divergence(&snapshot);
+        if excessive_divergence.is_some() {
+            self.consecutive_divergences += 1;
+        } else {
+            self.consecutive_divergences = 0;
+        }
+        let is_alerted = self.consecutive_divergences >= self.config.alert_after_checks.max(1);
+
+        if is_alerted {
+            tracing::error!(
+                "Base token supply diverges from the amount locked on the settlement layer for {} consecutive checks: \
+                 {excessive_divergence:?}; {snapshot:?}",
+                self.consecutive_divergences
+            );
+        } else if let Some(divergence) = excessive_divergence {
+            tracing::warn!(
+                "Base token supply diverges from the amount locked on the settlement layer: {divergence:?}; {snapshot:?}"
+            );
+        } else {
+            tracing::debug!("Base token supply is consistent: {snapshot:?}");
+        }
+        METRICS.alert.set(is_alerted.into());
+
+        let status = if is_alerted {
+            HealthStatus::Affected
+        } else {
+            HealthStatus::Ready
+        };
+        let details = SupplyCheckerHealthDetails {
+            snapshot,
+            divergence,
+            consecutive_divergences: self.consecutive_divergences,
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
+        Ok(is_alerted)
+    }
+}
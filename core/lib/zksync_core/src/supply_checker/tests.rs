@@ -0,0 +1,252 @@
+//! Tests for the supply checker.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use zksync_dal::{withdrawals_dal::Withdrawal, StorageProcessor};
+use zksync_eth_client::clients::MockEthereum;
+use zksync_types::{ethabi, L1BatchNumber, L2ChainId, StorageLog, H256, L2_ETH_TOKEN_ADDRESS};
+use zksync_utils::u256_to_h256;
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::create_miniblock,
+};
+
+#[derive(Debug, Clone, Default)]
+struct MockLockedFundsClient(Arc<Mutex<U256>>);
+
+impl MockLockedFundsClient {
+    fn set_locked_amount(&self, amount: U256) {
+        *self.0.lock().unwrap() = amount;
+    }
+}
+
+#[async_trait]
+impl LockedFundsClient for MockLockedFundsClient {
+    async fn locked_amount(&self) -> anyhow::Result<U256> {
+        Ok(*self.0.lock().unwrap())
+    }
+}
+
+fn gwei(amount: u64) -> U256 {
+    U256::from(amount) * GWEI
+}
+
+fn test_config() -> SupplyCheckerConfig {
+    SupplyCheckerConfig {
+        check_interval_ms: 10,
+        base_token_l1_addr: None,
+        locked_funds_addr: None,
+        unbacked_supply_gwei: 0,
+        epsilon_gwei: 10,
+        max_surplus_gwei: None,
+        alert_after_checks: 2,
+    }
+}
+
+async fn prepare_storage(storage: &mut StorageProcessor<'_>, total_supply: U256) {
+    ensure_genesis_state(storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(1))
+        .await
+        .unwrap();
+    let supply_log = StorageLog::new_write_log(
+        storage_key_for_eth_total_supply(),
+        u256_to_h256(total_supply),
+    );
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), vec![supply_log])])
+        .await
+        .unwrap();
+}
+
+fn create_checker(
+    pool: ConnectionPool,
+    config: SupplyCheckerConfig,
+    locked_amount: U256,
+) -> (SupplyChecker, MockLockedFundsClient) {
+    let client = MockLockedFundsClient::default();
+    client.set_locked_amount(locked_amount);
+    let checker = SupplyChecker::new(Box::new(client.clone()), pool, config);
+    (checker, client)
+}
+
+fn mock_withdrawal(amount: U256) -> Withdrawal {
+    Withdrawal {
+        l1_batch_number: L1BatchNumber(1),
+        l2_message_index: 0,
+        tx_number_in_batch: 0,
+        tx_hash: H256::repeat_byte(1),
+        sender: L2_ETH_TOKEN_ADDRESS,
+        l1_token: Address::zero(),
+        l1_receiver: Address::repeat_byte(2),
+        amount,
+        message: vec![],
+        merkle_proof: vec![],
+    }
+}
+
+#[tokio::test]
+async fn no_checks_without_miniblocks() {
+    let pool = ConnectionPool::test_pool().await;
+    let (mut checker, _) = create_checker(pool, test_config(), gwei(1_000));
+    assert_eq!(checker.take_snapshot().await.unwrap(), None);
+    assert!(!checker.check().await.unwrap());
+}
+
+#[tokio::test]
+async fn balanced_supply() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, gwei(1_000)).await;
+
+    let config = SupplyCheckerConfig {
+        unbacked_supply_gwei: 100,
+        ..test_config()
+    };
+    let (mut checker, _) = create_checker(pool.clone(), config, gwei(900));
+    let snapshot = checker.take_snapshot().await.unwrap().unwrap();
+    assert_eq!(
+        snapshot,
+        SupplySnapshot {
+            miniblock_number: MiniblockNumber(1),
+            total_supply: gwei(1_000),
+            unfinalized_withdrawals: U256::zero(),
+            locked_amount: gwei(900),
+            unbacked_supply: gwei(100),
+        }
+    );
+    assert_eq!(snapshot.divergence(), None);
+
+    let health_check = checker.health_check();
+    assert!(!checker.check().await.unwrap());
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+#[tokio::test]
+async fn deficit_is_alerted_after_consecutive_checks() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, gwei(1_000)).await;
+
+    let (mut checker, client) = create_checker(pool.clone(), test_config(), gwei(995));
+    let health_check = checker.health_check();
+    // The deficit is within epsilon.
+    assert!(!checker.check().await.unwrap());
+    assert_eq!(checker.consecutive_divergences, 0);
+
+    client.set_locked_amount(gwei(900));
+    let snapshot = checker.take_snapshot().await.unwrap().unwrap();
+    assert_eq!(snapshot.divergence(), Some(Divergence::Deficit(gwei(100))));
+    assert!(!checker.check().await.unwrap());
+    assert_eq!(checker.consecutive_divergences, 1);
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+    assert!(checker.check().await.unwrap());
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Affected
+    );
+
+    // The alert is cleared once the supply is consistent again.
+    client.set_locked_amount(gwei(1_000));
+    assert!(!checker.check().await.unwrap());
+    assert_eq!(checker.consecutive_divergences, 0);
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+#[tokio::test]
+async fn unfinalized_withdrawals_are_liabilities() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, gwei(1_000)).await;
+
+    let config = SupplyCheckerConfig {
+        alert_after_checks: 1,
+        ..test_config()
+    };
+    let (mut checker, _) = create_checker(pool.clone(), config, gwei(1_000));
+    assert!(!checker.check().await.unwrap());
+
+    storage
+        .withdrawals_dal()
+        .insert_withdrawals(&[mock_withdrawal(gwei(500))])
+        .await
+        .unwrap();
+    let snapshot = checker.take_snapshot().await.unwrap().unwrap();
+    assert_eq!(snapshot.unfinalized_withdrawals, gwei(500));
+    assert_eq!(snapshot.divergence(), Some(Divergence::Deficit(gwei(500))));
+    assert!(checker.check().await.unwrap());
+}
+
+#[tokio::test]
+async fn surplus_is_only_alerted_if_configured() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    prepare_storage(&mut storage, gwei(1_000)).await;
+
+    let config = SupplyCheckerConfig {
+        alert_after_checks: 1,
+        ..test_config()
+    };
+    let (mut checker, _) = create_checker(pool.clone(), config.clone(), gwei(2_000));
+    let snapshot = checker.take_snapshot().await.unwrap().unwrap();
+    assert_eq!(
+        snapshot.divergence(),
+        Some(Divergence::Surplus(gwei(1_000)))
+    );
+    assert!(!checker.check().await.unwrap());
+
+    let config = SupplyCheckerConfig {
+        max_surplus_gwei: Some(1_000),
+        ..config
+    };
+    let (mut checker, client) = create_checker(pool.clone(), config, gwei(2_000));
+    assert!(!checker.check().await.unwrap());
+    client.set_locked_amount(gwei(2_001));
+    assert!(checker.check().await.unwrap());
+}
+
+#[tokio::test]
+async fn reading_chain_balance_from_shared_bridge() {
+    let bridge_addr = Address::repeat_byte(0x11);
+    let base_token_l1_addr = Address::repeat_byte(0x22);
+    let chain_id = L2ChainId::from(270);
+    let mut calldata = ethabi::short_signature(
+        "chainBalance",
+        &[ethabi::ParamType::Uint(256), ethabi::ParamType::Address],
+    )
+    .to_vec();
+    calldata.extend(ethabi::encode(&[
+        ethabi::Token::Uint(270.into()),
+        ethabi::Token::Address(base_token_l1_addr),
+    ]));
+    let eth_client = MockEthereum::default();
+    eth_client.set_call_response(
+        bridge_addr,
+        calldata,
+        ethabi::encode(&[ethabi::Token::Uint(gwei(1_000))]),
+    );
+
+    let locked_funds = LockedFunds::SharedBridge {
+        bridge_addr,
+        chain_id,
+    };
+    let client =
+        EthLockedFundsClient::new(Box::new(eth_client), Some(base_token_l1_addr), locked_funds);
+    assert_eq!(client.locked_amount().await.unwrap(), gwei(1_000));
+}
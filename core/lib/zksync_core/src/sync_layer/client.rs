@@ -1,11 +1,19 @@
 //! Client abstractions for syncing between the external node and the main node.
 
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::HashMap,
+    fmt,
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use zksync_system_constants::ACCOUNT_CODE_STORAGE_ADDRESS;
 use zksync_types::{
-    api::{self, en::SyncBlock},
+    api::{
+        self,
+        en::{SyncBlock, SyncL1BatchRootHash},
+    },
     get_code_key, Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256, U64,
 };
 use zksync_web3_decl::{
@@ -16,9 +24,6 @@ use zksync_web3_decl::{
 
 use super::metrics::{CachedMethod, FETCHER_METRICS};
 
-/// Maximum number of concurrent requests to the main node.
-const MAX_CONCURRENT_REQUESTS: usize = 100;
-
 /// Client abstracting connection to the main node.
 #[async_trait]
 pub trait MainNodeClient: 'static + Send + Sync + fmt::Debug {
@@ -46,6 +51,13 @@ pub trait MainNodeClient: 'static + Send + Sync + fmt::Debug {
         number: MiniblockNumber,
         with_transactions: bool,
     ) -> EnrichedClientResult<Option<SyncBlock>>;
+
+    /// Fetches the root hash of the specified L1 batch. Returns `Ok(None)` if the L1 batch is unknown
+    /// to the main node or is not processed by its Merkle tree yet.
+    async fn fetch_l1_batch_root_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SyncL1BatchRootHash>>;
 }
 
 impl dyn MainNodeClient {
@@ -150,6 +162,70 @@ impl MainNodeClient for HttpClient {
             .with_arg("with_transactions", &with_transactions)
             .await
     }
+
+    async fn fetch_l1_batch_root_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SyncL1BatchRootHash>> {
+        self.sync_l1_batch_root_hash(number)
+            .rpc_context("sync_l1_batch_root_hash")
+            .with_arg("number", &number)
+            .await
+    }
+}
+
+/// Controller of the number of concurrent requests to the main node, which is adjusted based on
+/// the main node latency in the additive increase / multiplicative decrease (AIMD) fashion.
+/// If all requests in a batch succeed and the batch latency is within the target, concurrency is increased;
+/// otherwise, it is halved so that a lagging main node is not overloaded.
+#[derive(Debug)]
+pub(super) struct AdaptiveConcurrency {
+    current: usize,
+    max: usize,
+    target_latency: Duration,
+}
+
+impl AdaptiveConcurrency {
+    /// Default maximum number of concurrent requests to the main node.
+    pub const DEFAULT_MAX: usize = 100;
+    /// Default target latency of a batch of concurrent requests.
+    pub const DEFAULT_TARGET_LATENCY: Duration = Duration::from_secs(1);
+    /// Concurrency never drops below this value.
+    const MIN: usize = 1;
+    /// Concurrency is increased by this value after each successful batch.
+    const ADDITIVE_INCREASE: usize = 10;
+
+    pub fn new(max: NonZeroUsize, target_latency: Duration) -> Self {
+        let max = max.get();
+        Self {
+            // Start conservatively; concurrency will quickly ramp up if the main node keeps up.
+            current: max.min(Self::ADDITIVE_INCREASE),
+            max,
+            target_latency,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Adjusts concurrency based on the outcome of a batch of concurrent requests. `saturated` signals whether
+    /// the batch used all available concurrency; if it didn't, concurrency is not the bottleneck and is not increased.
+    pub fn observe(&mut self, latency: Duration, had_errors: bool, saturated: bool) {
+        if had_errors || latency > self.target_latency {
+            self.current = (self.current / 2).max(Self::MIN);
+        } else if saturated {
+            self.current = (self.current + Self::ADDITIVE_INCREASE).min(self.max);
+        }
+        FETCHER_METRICS.concurrency.set(self.current);
+    }
+}
+
+impl Default for AdaptiveConcurrency {
+    fn default() -> Self {
+        let max = NonZeroUsize::new(Self::DEFAULT_MAX).unwrap();
+        Self::new(max, Self::DEFAULT_TARGET_LATENCY)
+    }
 }
 
 /// This is a temporary implementation of a cache layer for the main node HTTP requests.
@@ -170,6 +246,7 @@ pub(super) struct CachingMainNodeClient {
     /// Earliest miniblock number that is not yet cached. Used as a marker to refill the cache.
     next_refill_at: MiniblockNumber,
     blocks: HashMap<MiniblockNumber, SyncBlock>,
+    concurrency: AdaptiveConcurrency,
 }
 
 impl CachingMainNodeClient {
@@ -178,9 +255,14 @@ impl CachingMainNodeClient {
             client,
             next_refill_at: MiniblockNumber(0),
             blocks: Default::default(),
+            concurrency: AdaptiveConcurrency::default(),
         }
     }
 
+    pub fn set_concurrency(&mut self, concurrency: AdaptiveConcurrency) {
+        self.concurrency = concurrency;
+    }
+
     /// Cached version of [`HttpClient::sync_l2_block`].
     pub async fn fetch_l2_block(
         &mut self,
@@ -208,6 +290,9 @@ impl CachingMainNodeClient {
         self.blocks.remove(&miniblock);
     }
 
+    /// Concurrently fetches miniblocks starting from `current_miniblock` up to `last_miniblock` (inclusive)
+    /// into the cache. The number of fetched miniblocks is limited by the current concurrency, which is adjusted
+    /// based on the latency of the main node responses.
     pub async fn populate_miniblocks_cache(
         &mut self,
         current_miniblock: MiniblockNumber,
@@ -216,13 +301,14 @@ impl CachingMainNodeClient {
         // This method may be invoked frequently, but in order to take advantage of the concurrent fetching,
         // we only need to do it once in a while. If we'll do it too often, we'll end up adding 1 element to
         // the cache at a time, which eliminates the cache's purpose.
-        if current_miniblock < self.next_refill_at {
+        if current_miniblock < self.next_refill_at || current_miniblock > last_miniblock {
             return;
         }
         let populate_latency = FETCHER_METRICS.cache_populate.start();
+        let concurrency = self.concurrency.current();
         let last_miniblock_to_fetch =
-            last_miniblock.min(current_miniblock + MAX_CONCURRENT_REQUESTS as u32);
-        let task_futures = (current_miniblock.0..last_miniblock_to_fetch.0)
+            last_miniblock.min(current_miniblock + (concurrency - 1) as u32);
+        let task_futures = (current_miniblock.0..=last_miniblock_to_fetch.0)
             .map(MiniblockNumber)
             .filter(|&miniblock| {
                 // If the miniblock is already in the cache, we don't need to fetch it.
@@ -230,7 +316,11 @@ impl CachingMainNodeClient {
             })
             .map(|block_number| self.client.fetch_l2_block(block_number, true));
 
+        let started_at = Instant::now();
         let results = futures::future::join_all(task_futures).await;
+        let latency = started_at.elapsed();
+        let saturated = results.len() >= concurrency;
+        let mut had_errors = false;
         for result in results {
             if let Ok(Some(block)) = result {
                 self.next_refill_at = self.next_refill_at.max(block.number + 1);
@@ -240,12 +330,14 @@ impl CachingMainNodeClient {
                 // The entry won't be included into the cache, and whoever uses the cache, will have to process
                 // a cache miss as they will.
                 FETCHER_METRICS.cache_errors.inc();
+                had_errors = true;
             }
         }
+        self.concurrency.observe(latency, had_errors, saturated);
         populate_latency.observe();
     }
 
-    fn has_miniblock(&self, miniblock: MiniblockNumber) -> bool {
+    pub fn has_miniblock(&self, miniblock: MiniblockNumber) -> bool {
         self.blocks.contains_key(&miniblock)
     }
 }
@@ -0,0 +1,190 @@
+//! Verification of content hashes for miniblocks sealed by the external node.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{content_hashes_dal::ContentHashCheck, ConnectionPool};
+use zksync_types::MiniblockNumber;
+use zksync_web3_decl::error::EnrichedClientError;
+
+use super::{
+    client::MainNodeClient,
+    metrics::{ContentHashMismatch, FETCHER_METRICS},
+};
+
+#[derive(Debug, thiserror::Error)]
+enum CheckerError {
+    #[error("JSON-RPC error communicating with main node")]
+    Web3(#[from] EnrichedClientError),
+    #[error("Internal error")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Component verifying content hashes of miniblocks sealed by the external node. For each sealed miniblock,
+/// the checker recomputes the content hash from Postgres and compares it with:
+///
+/// - The hash persisted when sealing the miniblock. A mismatch means that the local data was silently corrupted;
+///   the checker returns an error in this case, so that the corrupted data isn't served to users.
+/// - The hash reported by the main node. A mismatch means that execution results diverge from the main node;
+///   since continuing to sync on top of diverged state would only compound the problem, the checker returns
+///   an error in this case as well, which stops the node.
+///
+/// Miniblocks without a content hash (e.g., sealed before content hashes were introduced) are skipped.
+/// Only miniblocks sealed after the checker has started are checked.
+#[derive(Debug)]
+pub struct ContentHashChecker {
+    client: Box<dyn MainNodeClient>,
+    pool: ConnectionPool,
+    sleep_interval: Duration,
+}
+
+impl ContentHashChecker {
+    const DEFAULT_SLEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(client: Box<dyn MainNodeClient>, pool: ConnectionPool) -> Self {
+        Self {
+            client,
+            pool,
+            sleep_interval: Self::DEFAULT_SLEEP_INTERVAL,
+        }
+    }
+
+    async fn check_miniblock(&self, number: MiniblockNumber) -> Result<(), CheckerError> {
+        let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
+        let check = storage
+            .content_hashes_dal()
+            .check_content_hash(number)
+            .await
+            .with_context(|| format!("failed checking content hash for miniblock #{number}"))?;
+        drop(storage);
+
+        let local_hash = match check {
+            ContentHashCheck::Missing => return Ok(()),
+            ContentHashCheck::Valid(hash) => hash,
+            ContentHashCheck::Mismatch {
+                persisted,
+                computed,
+            } => {
+                FETCHER_METRICS.content_hash_mismatches[&ContentHashMismatch::Local].inc();
+                let err = anyhow::anyhow!(
+                    "Miniblock #{number} data in Postgres is corrupted: persisted content hash {persisted:?}, \
+                     hash computed from the current data {computed:?}"
+                );
+                return Err(err.into());
+            }
+        };
+
+        let main_node_block = self.client.fetch_l2_block(number, false).await?;
+        let Some(main_node_hash) = main_node_block.and_then(|block| block.content_hash) else {
+            return Ok(());
+        };
+        if main_node_hash != local_hash {
+            FETCHER_METRICS.content_hash_mismatches[&ContentHashMismatch::MainNode].inc();
+            let err = anyhow::anyhow!(
+                "Content hash of miniblock #{number} diverges from the main node: local {local_hash:?}, \
+                 main node {main_node_hash:?}"
+            );
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut next_miniblock = None;
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, content hash checker is shutting down");
+                break;
+            }
+
+            let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
+            let sealed_miniblock = storage.blocks_dal().get_sealed_miniblock_number().await?;
+            drop(storage);
+
+            if let Some(sealed_miniblock) = sealed_miniblock {
+                let next_miniblock = next_miniblock.get_or_insert(sealed_miniblock);
+                while *next_miniblock <= sealed_miniblock {
+                    match self.check_miniblock(*next_miniblock).await {
+                        Ok(()) => *next_miniblock += 1,
+                        Err(CheckerError::Web3(err)) => {
+                            tracing::warn!(
+                                "Failed fetching miniblock #{next_miniblock} from the main node: {err}"
+                            );
+                            break;
+                        }
+                        Err(CheckerError::Internal(err)) => return Err(err),
+                    }
+                }
+            }
+
+            if tokio::time::timeout(self.sleep_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                tracing::info!("Stop signal received, content hash checker is shutting down");
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_types::{L2ChainId, H256};
+
+    use super::*;
+    use crate::{
+        consensus::testonly::MockMainNodeClient,
+        genesis::{ensure_genesis_state, GenesisParams},
+    };
+
+    #[tokio::test]
+    async fn checking_content_hashes() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        let content_hash = storage
+            .content_hashes_dal()
+            .compute_content_hash(MiniblockNumber(0))
+            .await
+            .unwrap();
+        storage
+            .content_hashes_dal()
+            .set_content_hash(MiniblockNumber(0), content_hash)
+            .await
+            .unwrap();
+
+        let mut client = MockMainNodeClient::default();
+        client.push_l1_batch(0);
+        client.set_content_hash(MiniblockNumber(0), content_hash);
+        let checker = ContentHashChecker::new(Box::new(client.clone()), pool.clone());
+        checker.check_miniblock(MiniblockNumber(0)).await.unwrap();
+        // Missing miniblocks are skipped.
+        checker.check_miniblock(MiniblockNumber(1)).await.unwrap();
+
+        let mut diverged_client = client.clone();
+        diverged_client.set_content_hash(MiniblockNumber(0), H256::repeat_byte(1));
+        let diverged_checker = ContentHashChecker::new(Box::new(diverged_client), pool.clone());
+        let err = diverged_checker
+            .check_miniblock(MiniblockNumber(0))
+            .await
+            .unwrap_err();
+        assert_matches!(err, CheckerError::Internal(_));
+
+        storage
+            .content_hashes_dal()
+            .set_content_hash(MiniblockNumber(0), H256::repeat_byte(2))
+            .await
+            .unwrap();
+        let err = checker
+            .check_miniblock(MiniblockNumber(0))
+            .await
+            .unwrap_err();
+        assert_matches!(err, CheckerError::Internal(_));
+    }
+}
@@ -1,17 +1,21 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use multivm::interface::{FinishedL1Batch, L1BatchEnv, SystemEnv};
 use vm_utils::storage::{l1_batch_params, L1BatchParamsProvider};
 use zksync_contracts::{BaseSystemContracts, SystemContractCode};
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     ethabi::Address, fee_model::BatchFeeInput, protocol_version::ProtocolUpgradeTx,
     witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId, MiniblockNumber,
     ProtocolVersionId, Transaction, H256,
 };
 use zksync_utils::bytes_to_be_words;
+use zksync_web3_decl::jsonrpsee::core::ClientError as RpcError;
 
 use super::{
     client::MainNodeClient,
@@ -35,6 +39,16 @@ use crate::{
 /// The interval between the action queue polling attempts for the new actions.
 const POLL_INTERVAL: Duration = Duration::from_millis(100);
 
+/// Availability of the previous L1 batch hash used to open a new L1 batch.
+#[derive(Debug)]
+enum PrevL1BatchHash {
+    /// The hash is not available yet.
+    Pending,
+    Ready(H256),
+    /// The hash should be computed by the local Merkle tree.
+    LocalTree,
+}
+
 /// ExternalIO is the IO abstraction for the state keeper that is used in the external node.
 /// It receives a sequence of actions from the fetcher via the action queue and propagates it
 /// into the state keeper.
@@ -53,6 +67,14 @@ pub struct ExternalIO {
     actions: ActionQueue,
     sync_state: SyncState,
     main_node_client: Box<dyn MainNodeClient>,
+    /// If set, the node doesn't wait for its Merkle tree to process the previous L1 batch before opening a new one,
+    /// as long as the tree lags behind by at most this number of L1 batches.
+    async_tree_max_lag: Option<u32>,
+    /// If unset, the node runs without a Merkle tree; L1 batch root hashes are fetched from the main node
+    /// and persisted.
+    has_local_tree: bool,
+    /// Time when the state keeper started waiting for the previous L1 batch hash for the current L1 batch.
+    prev_l1_batch_hash_wait_started_at: Option<Instant>,
 
     /// Required to extract newly added tokens.
     l2_erc20_bridge_addr: Address,
@@ -103,12 +125,37 @@ impl ExternalIO {
             actions,
             sync_state,
             main_node_client,
+            async_tree_max_lag: None,
+            has_local_tree: true,
+            prev_l1_batch_hash_wait_started_at: None,
             l2_erc20_bridge_addr,
             validation_computational_gas_limit,
             chain_id,
         })
     }
 
+    /// Enables the async tree mode. In this mode, if the local Merkle tree hasn't processed the previous L1 batch yet,
+    /// its root hash is fetched from the main node instead, so that batch execution only waits for the tree
+    /// if it lags behind by more than `max_lag` L1 batches. Root hashes fetched from the main node are not persisted;
+    /// they are overwritten by the local tree once it catches up.
+    ///
+    /// Pending L1 batches loaded on node restart still wait for the local tree.
+    #[must_use]
+    pub fn with_async_tree(mut self, max_lag: u32) -> Self {
+        self.async_tree_max_lag = Some(max_lag);
+        self
+    }
+
+    /// Configures the I/O for a node running without a Merkle tree. In this mode, the root hash of the previous
+    /// L1 batch is always fetched from the main node and persisted locally (marked as obtained from the main node),
+    /// so that pending L1 batches can be loaded on node restart. Since such hashes aren't computed locally,
+    /// the reorg detector compares miniblock hashes rather than root hashes for the corresponding L1 batches.
+    #[must_use]
+    pub fn without_tree(mut self) -> Self {
+        self.has_local_tree = false;
+        self
+    }
+
     fn update_miniblock_fields(&mut self, miniblock: &MiniblockUpdates) {
         assert_eq!(
             miniblock.number, self.current_miniblock_number.0,
@@ -122,24 +169,118 @@ impl ExternalIO {
         self.prev_miniblock_hash = miniblock.get_miniblock_hash();
     }
 
-    async fn wait_for_previous_l1_batch_hash(&self) -> H256 {
+    /// Returns the hash of the previous L1 batch, or `None` if it isn't available yet. Hashes from the main node
+    /// are not waited for; instead, the caller polls this method, so that the state keeper can be stopped
+    /// in the meantime.
+    async fn load_previous_l1_batch_hash(&self) -> anyhow::Result<Option<H256>> {
+        let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
+        let prev_l1_batch_number = self.current_l1_batch_number - 1;
+        let hash = if !self.has_local_tree {
+            self.load_and_persist_main_node_l1_batch_hash(&mut storage, prev_l1_batch_number)
+                .await
+        } else if let Some(max_lag) = self.async_tree_max_lag {
+            self.load_main_node_l1_batch_hash(&mut storage, prev_l1_batch_number, max_lag)
+                .await
+        } else {
+            Ok(PrevL1BatchHash::LocalTree)
+        };
+        let hash = hash.with_context(|| {
+            format!("error getting hash for L1 batch #{prev_l1_batch_number} from main node")
+        })?;
+
+        match hash {
+            PrevL1BatchHash::Pending => Ok(None),
+            PrevL1BatchHash::Ready(hash) => Ok(Some(hash)),
+            PrevL1BatchHash::LocalTree => {
+                let (hash, _) = self
+                    .l1_batch_params_provider
+                    .wait_for_l1_batch_params(&mut storage, prev_l1_batch_number)
+                    .await
+                    .with_context(|| {
+                        format!("error waiting for params for L1 batch #{prev_l1_batch_number}")
+                    })?;
+                Ok(Some(hash))
+            }
+        }
+    }
+
+    /// Checks whether the root hash of the specified L1 batch should be taken from the main node, i.e., the local
+    /// Merkle tree hasn't processed the L1 batch yet, but lags behind by at most `max_lag` L1 batches.
+    async fn load_main_node_l1_batch_hash(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        number: L1BatchNumber,
+        max_lag: u32,
+    ) -> anyhow::Result<PrevL1BatchHash> {
+        let local_hash = storage.blocks_dal().get_l1_batch_state_root(number).await?;
+        if local_hash.is_some() {
+            return Ok(PrevL1BatchHash::LocalTree);
+        }
+        let Some(last_processed_l1_batch) = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await?
+        else {
+            // The tree didn't process any L1 batches yet (e.g., right after snapshot recovery);
+            // the lag cannot be determined, so we wait for the local tree.
+            return Ok(PrevL1BatchHash::LocalTree);
+        };
+
+        let lag = number.0.saturating_sub(last_processed_l1_batch.0);
+        if lag > max_lag {
+            return Ok(PrevL1BatchHash::Pending);
+        }
+        let Some(hash) = self.fetch_main_node_l1_batch_hash(number).await? else {
+            return Ok(PrevL1BatchHash::Pending);
+        };
         tracing::info!(
-            "Getting previous L1 batch hash for L1 batch #{}",
-            self.current_l1_batch_number
+            "Using hash {hash:?} for L1 batch #{number} from main node; local tree lags by {lag} L1 batches"
         );
-        let mut storage = self.pool.access_storage_tagged("sync_layer").await.unwrap();
-        let wait_latency = KEEPER_METRICS.wait_for_prev_hash_time.start();
-        let prev_l1_batch_number = self.current_l1_batch_number - 1;
-        let (hash, _) = self
-            .l1_batch_params_provider
-            .wait_for_l1_batch_params(&mut storage, prev_l1_batch_number)
-            .await
-            .with_context(|| {
-                format!("error waiting for params for L1 batch #{prev_l1_batch_number}")
-            })
-            .unwrap();
-        wait_latency.observe();
-        hash
+        Ok(PrevL1BatchHash::Ready(hash))
+    }
+
+    /// Fetches the root hash of the specified L1 batch from the main node. Returns `Ok(None)` if the main node
+    /// hasn't computed the hash yet or on a transient network error. Other errors (e.g., a hash failing sequencer
+    /// signature verification) are returned, since the hash is an input for executing the next L1 batch.
+    async fn fetch_main_node_l1_batch_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        match self.main_node_client.fetch_l1_batch_root_hash(number).await {
+            Ok(response) => Ok(response.map(|response| response.root_hash)),
+            Err(err)
+                if matches!(
+                    err.as_ref(),
+                    RpcError::Transport(_) | RpcError::RequestTimeout
+                ) =>
+            {
+                tracing::warn!("Failed fetching hash for L1 batch #{number} from main node: {err}");
+                Ok(None)
+            }
+            Err(err) => Err(anyhow::Error::new(err).context("failed fetching L1 batch hash")),
+        }
+    }
+
+    /// Returns the locally persisted root hash for the specified L1 batch, or fetches it from the main node
+    /// and persists it locally. Used if the node runs without a Merkle tree.
+    async fn load_and_persist_main_node_l1_batch_hash(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<PrevL1BatchHash> {
+        if let Some(local_hash) = storage.blocks_dal().get_l1_batch_state_root(number).await? {
+            return Ok(PrevL1BatchHash::Ready(local_hash));
+        }
+        let Some(hash) = self.fetch_main_node_l1_batch_hash(number).await? else {
+            return Ok(PrevL1BatchHash::Pending);
+        };
+        // The L1 batch may be missing locally if it is the snapshot L1 batch; this is fine.
+        storage
+            .blocks_dal()
+            .save_l1_batch_root_hash_from_main_node(number, hash)
+            .await?;
+        tracing::info!("Persisted hash {hash:?} for L1 batch #{number} from main node");
+        Ok(PrevL1BatchHash::Ready(hash))
     }
 
     async fn load_base_system_contracts_by_version_id(
@@ -329,10 +470,11 @@ impl StateKeeperIO for ExternalIO {
     async fn wait_for_new_batch_params(
         &mut self,
         max_wait: Duration,
-    ) -> Option<(SystemEnv, L1BatchEnv)> {
+    ) -> anyhow::Result<Option<(SystemEnv, L1BatchEnv)>> {
         tracing::debug!("Waiting for the new batch params");
         for _ in 0..poll_iters(POLL_INTERVAL, max_wait) {
-            match self.actions.pop_action() {
+            // The action is only popped once the previous L1 batch hash is available.
+            match self.actions.peek_action() {
                 Some(SyncAction::OpenBatch {
                     number,
                     timestamp,
@@ -347,7 +489,19 @@ impl StateKeeperIO for ExternalIO {
                         number, self.current_l1_batch_number,
                         "Batch number mismatch"
                     );
-                    let previous_l1_batch_hash = self.wait_for_previous_l1_batch_hash().await;
+                    let wait_started_at = *self
+                        .prev_l1_batch_hash_wait_started_at
+                        .get_or_insert_with(Instant::now);
+                    let Some(previous_l1_batch_hash) = self.load_previous_l1_batch_hash().await?
+                    else {
+                        tokio::time::sleep(POLL_INTERVAL).await;
+                        continue;
+                    };
+                    KEEPER_METRICS
+                        .wait_for_prev_hash_time
+                        .observe(wait_started_at.elapsed());
+                    self.prev_l1_batch_hash_wait_started_at = None;
+                    self.actions.pop_action();
                     tracing::info!(
                         "Previous L1 batch hash: {previous_l1_batch_hash:?}, previous miniblock hash: {:?}",
                         self.prev_miniblock_hash
@@ -356,7 +510,7 @@ impl StateKeeperIO for ExternalIO {
                     let base_system_contracts = self
                         .load_base_system_contracts_by_version_id(protocol_version)
                         .await;
-                    return Some(l1_batch_params(
+                    return Ok(Some(l1_batch_params(
                         number,
                         operator_address,
                         timestamp,
@@ -374,7 +528,7 @@ impl StateKeeperIO for ExternalIO {
                         protocol_version,
                         virtual_blocks,
                         self.chain_id,
-                    ));
+                    )));
                 }
                 Some(other) => {
                     panic!("Unexpected action in the action queue: {other:?}");
@@ -384,7 +538,7 @@ impl StateKeeperIO for ExternalIO {
                 }
             }
         }
-        None
+        Ok(None)
     }
 
     async fn wait_for_new_miniblock_params(
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{num::NonZeroUsize, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
@@ -10,7 +10,7 @@ use zksync_types::{
 use zksync_web3_decl::{error::EnrichedClientError, jsonrpsee::core::ClientError as RpcError};
 
 use super::{
-    client::{CachingMainNodeClient, MainNodeClient},
+    client::{AdaptiveConcurrency, CachingMainNodeClient, MainNodeClient},
     metrics::{FetchStage, L1BatchStage, FETCHER_METRICS},
     sync_action::{ActionQueueSender, SyncAction},
     SyncState,
@@ -90,19 +90,22 @@ impl IoCursor {
         Ok(this)
     }
 
-    pub(crate) fn advance(&mut self, block: FetchedBlock) -> Vec<SyncAction> {
+    /// Converts the block into sync actions. Returns an error if the miniblock hash computed from the received
+    /// transactions doesn't match the reference hash returned by the main node; such a block must not be applied.
+    pub(crate) fn advance(&mut self, block: FetchedBlock) -> anyhow::Result<Vec<SyncAction>> {
         assert_eq!(block.number, self.next_miniblock);
         let local_block_hash = block.compute_hash(self.prev_miniblock_hash);
         if let Some(reference_hash) = block.reference_hash {
-            if local_block_hash != reference_hash {
-                // This is a warning, not an assertion because hash mismatch may occur after a reorg.
-                // Indeed, `self.prev_miniblock_hash` may differ from the hash of the updated previous miniblock.
-                tracing::warn!(
-                    "Mismatch between the locally computed and received miniblock hash for {block:?}; \
-                     local_block_hash = {local_block_hash:?}, prev_miniblock_hash = {:?}",
-                    self.prev_miniblock_hash
-                );
-            }
+            // After a reorg, `self.prev_miniblock_hash` may differ from the hash of the updated previous miniblock.
+            // This is still an error: the node reverts and restarts on reorgs, which reloads the cursor from storage.
+            anyhow::ensure!(
+                local_block_hash == reference_hash,
+                "Mismatch between the locally computed and received miniblock hash for miniblock #{}; \
+                 local_block_hash = {local_block_hash:?}, reference_hash = {reference_hash:?}, \
+                 prev_miniblock_hash = {:?}",
+                block.number,
+                self.prev_miniblock_hash
+            );
         }
 
         let mut new_actions = Vec::new();
@@ -161,7 +164,7 @@ impl IoCursor {
         self.next_miniblock += 1;
         self.prev_miniblock_hash = local_block_hash;
 
-        new_actions
+        Ok(new_actions)
     }
 }
 
@@ -219,6 +222,18 @@ impl MainNodeFetcher {
         })
     }
 
+    /// Sets the maximum number of concurrent requests to the main node and the target latency of a batch
+    /// of such requests. The actual concurrency is adjusted between 1 and the maximum based on the main node latency.
+    pub fn with_concurrency(
+        mut self,
+        max_concurrency: NonZeroUsize,
+        target_latency: Duration,
+    ) -> Self {
+        self.client
+            .set_concurrency(AdaptiveConcurrency::new(max_concurrency, target_latency));
+        self
+    }
+
     pub async fn run(mut self) -> anyhow::Result<()> {
         tracing::info!(
             "Starting the fetcher routine. Initial miniblock: {}, initial l1 batch: {}",
@@ -252,16 +267,17 @@ impl MainNodeFetcher {
                 return Ok(());
             }
 
-            let mut progressed = false;
             let last_main_node_block = self.client.fetch_l2_block_number().await?;
             self.sync_state.set_main_node_block(last_main_node_block);
 
-            self.client
-                .populate_miniblocks_cache(self.cursor.next_miniblock, last_main_node_block)
-                .await;
+            // Don't fetch new miniblocks if the state keeper doesn't keep up with the already fetched ones.
             let has_action_capacity = self.actions.has_action_capacity();
+            let mut progressed = false;
             if has_action_capacity {
-                progressed |= self.fetch_next_miniblock().await?;
+                self.client
+                    .populate_miniblocks_cache(self.cursor.next_miniblock, last_main_node_block)
+                    .await;
+                progressed = self.apply_fetched_miniblocks().await?;
             }
 
             if !progressed {
@@ -277,6 +293,22 @@ impl MainNodeFetcher {
         }
     }
 
+    /// Inserts miniblocks to the sync queue in order, starting from the next miniblock, for as long as miniblocks
+    /// are cached and the queue has capacity. Returns `true` if at least one miniblock was processed.
+    async fn apply_fetched_miniblocks(&mut self) -> Result<bool, FetcherError> {
+        if !self.fetch_next_miniblock().await? {
+            return Ok(false);
+        }
+        while self.actions.has_action_capacity()
+            && self.client.has_miniblock(self.cursor.next_miniblock)
+        {
+            if self.check_if_cancelled() || !self.fetch_next_miniblock().await? {
+                break;
+            }
+        }
+        Ok(true)
+    }
+
     /// Tries to fetch the next miniblock and insert it to the sync queue.
     /// Returns `true` if a miniblock was processed and `false` otherwise.
     async fn fetch_next_miniblock(&mut self) -> Result<bool, FetcherError> {
@@ -292,7 +324,7 @@ impl MainNodeFetcher {
         request_latency.observe();
 
         let block_number = block.number;
-        let new_actions = self.cursor.advance(block.try_into()?);
+        let new_actions = self.cursor.advance(block.try_into()?)?;
 
         tracing::info!(
             "New miniblock: {block_number} / {}",
@@ -8,18 +8,21 @@ use zksync_types::{
 };
 
 use super::client::MainNodeClient;
-use crate::genesis::{ensure_genesis_state, GenesisParams};
+use crate::genesis::{ensure_genesis_state, GenesisManifest, GenesisParams};
 
+/// Performs genesis if it wasn't performed yet and checks that the genesis state matches the main node.
+/// If the main node used a genesis manifest, the same manifest must be provided.
 pub async fn perform_genesis_if_needed(
     storage: &mut StorageProcessor<'_>,
     zksync_chain_id: L2ChainId,
     client: &dyn MainNodeClient,
+    manifest: Option<GenesisManifest>,
 ) -> anyhow::Result<()> {
     let mut transaction = storage.start_transaction().await?;
     // We want to check whether the genesis is needed before we create genesis params to not
     // make the node startup slower.
     let genesis_block_hash = if transaction.blocks_dal().is_genesis_needed().await? {
-        let genesis_params = create_genesis_params(client).await?;
+        let genesis_params = create_genesis_params(client, manifest).await?;
         ensure_genesis_state(&mut transaction, zksync_chain_id, &genesis_params)
             .await
             .context("ensure_genesis_state")?
@@ -36,7 +39,10 @@ pub async fn perform_genesis_if_needed(
     Ok(())
 }
 
-async fn create_genesis_params(client: &dyn MainNodeClient) -> anyhow::Result<GenesisParams> {
+async fn create_genesis_params(
+    client: &dyn MainNodeClient,
+    manifest: Option<GenesisManifest>,
+) -> anyhow::Result<GenesisParams> {
     let genesis_miniblock = client
         .fetch_l2_block(zksync_types::MiniblockNumber(0), false)
         .await?
@@ -95,6 +101,7 @@ async fn create_genesis_params(client: &dyn MainNodeClient) -> anyhow::Result<Ge
         first_validator,
         first_l1_verifier_config,
         first_verifier_address,
+        manifest,
     })
 }
 
@@ -131,7 +138,8 @@ async fn validate_genesis_state(
     let genesis_l1_batch_hash = client.fetch_genesis_l1_batch_hash().await?;
     anyhow::ensure!(
         genesis_l1_batch_hash == root_hash,
-        "Genesis L1 batch root hash mismatch with main node: expected {root_hash}, got {genesis_l1_batch_hash}"
+        "Genesis L1 batch root hash mismatch with main node: expected {root_hash}, got {genesis_l1_batch_hash}. \
+         Check that the node uses the same genesis manifest as the main node (if any)"
     );
     Ok(())
 }
@@ -11,6 +11,7 @@ pub(super) enum FetchStage {
     GetMiniblockRange,
     GetBlockDetails,
     SyncL2Block,
+    SyncL2BlockOutputs,
 }
 
 #[derive(
@@ -40,6 +41,15 @@ pub(super) enum CachedMethod {
     SyncL2Block,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(super) enum ContentHashMismatch {
+    /// Miniblock data in the local Postgres doesn't match the persisted content hash.
+    Local,
+    /// Content hash of a miniblock differs from the one reported by the main node.
+    MainNode,
+}
+
 /// Metrics for the fetcher.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "external_node_fetcher")]
@@ -50,6 +60,16 @@ pub(super) struct FetcherMetrics {
     pub miniblock: Gauge<u64>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub fetch_next_miniblock: Histogram<Duration>,
+    /// Current number of concurrent requests used to fetch miniblocks from the main node.
+    pub concurrency: Gauge<usize>,
+    /// Number of miniblocks returned by main node witness endpoints that diverge from the primary endpoint.
+    pub quorum_mismatches: Counter,
+    /// Number of errors fetching miniblocks from main node witness endpoints.
+    pub witness_errors: Counter,
+    /// Number of miniblocks (or miniblock outputs) returned by the main node without a valid sequencer signature.
+    pub invalid_signatures: Counter,
+    /// Number of miniblocks failing content hash verification.
+    pub content_hash_mismatches: Family<ContentHashMismatch, Counter>,
 
     // Cache-related metrics.
     pub cache_total: Family<CachedMethod, Counter>,
@@ -70,3 +90,21 @@ pub(super) struct ActionQueueMetrics {
 
 #[vise::register]
 pub(super) static QUEUE_METRICS: vise::Global<ActionQueueMetrics> = vise::Global::new();
+
+/// Metrics for the read replica syncer.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_replica")]
+pub(super) struct ReplicaMetrics {
+    /// Number of the last miniblock persisted by the replica.
+    pub miniblock: Gauge<u64>,
+    /// Number of the last L1 batch sealed by the replica.
+    pub l1_batch: Gauge<u64>,
+    /// Latency of persisting a miniblock together with its execution outputs.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub persist_miniblock: Histogram<Duration>,
+    /// Number of miniblocks with execution outputs inconsistent with the miniblock returned by the main node.
+    pub invalid_outputs: Counter,
+}
+
+#[vise::register]
+pub(super) static REPLICA_METRICS: vise::Global<ReplicaMetrics> = vise::Global::new();
@@ -1,15 +1,24 @@
 pub mod batch_status_updater;
 mod client;
+mod content_hash_checker;
 pub mod external_io;
 pub mod fetcher;
 pub mod genesis;
 mod metrics;
+mod quorum;
+pub mod replica;
+mod signature;
 pub(crate) mod sync_action;
 mod sync_state;
 #[cfg(test)]
 mod tests;
 
 pub use self::{
-    client::MainNodeClient, external_io::ExternalIO, sync_action::ActionQueue,
+    client::MainNodeClient,
+    content_hash_checker::ContentHashChecker,
+    external_io::ExternalIO,
+    quorum::{QuorumMainNodeClient, QuorumPolicy},
+    signature::{SignatureVerifyingMainNodeClient, SignatureVerifyingOutputsClient},
+    sync_action::ActionQueue,
     sync_state::SyncState,
 };
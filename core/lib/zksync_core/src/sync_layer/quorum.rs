@@ -0,0 +1,206 @@
+//! Cross-checking of miniblocks fetched from the main node across several endpoints.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use zksync_types::{
+    api::{
+        self,
+        en::{SyncBlock, SyncL1BatchRootHash},
+    },
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256,
+};
+use zksync_web3_decl::error::{EnrichedClientError, EnrichedClientResult};
+
+use super::{client::MainNodeClient, metrics::FETCHER_METRICS};
+
+/// Policy determining how many main node endpoints must return matching miniblocks for a miniblock to be applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuorumPolicy {
+    /// All endpoints must return matching miniblocks.
+    All,
+    /// More than a half of the endpoints (including the primary one) must return matching miniblocks.
+    #[default]
+    Majority,
+}
+
+impl QuorumPolicy {
+    fn required_confirmations(self, endpoint_count: usize) -> usize {
+        match self {
+            Self::All => endpoint_count,
+            Self::Majority => endpoint_count / 2 + 1,
+        }
+    }
+}
+
+/// [`MainNodeClient`] fetching miniblocks from the primary main node endpoint and cross-checking them
+/// with witness endpoints according to the [`QuorumPolicy`]. Protects the external node from a single
+/// compromised or buggy upstream.
+///
+/// Miniblocks are cross-checked in full: if transactions are requested, they are fetched from all endpoints
+/// and compared by their serialized contents, so that a compromised endpoint cannot substitute transaction
+/// contents while keeping transaction hashes. All other requests are served by the primary endpoint.
+#[derive(Debug)]
+pub struct QuorumMainNodeClient {
+    primary: Box<dyn MainNodeClient>,
+    witnesses: Vec<Box<dyn MainNodeClient>>,
+    policy: QuorumPolicy,
+}
+
+impl QuorumMainNodeClient {
+    pub fn new(
+        primary: Box<dyn MainNodeClient>,
+        witnesses: Vec<Box<dyn MainNodeClient>>,
+        policy: QuorumPolicy,
+    ) -> Self {
+        Self {
+            primary,
+            witnesses,
+            policy,
+        }
+    }
+
+    fn blocks_match(block: &SyncBlock, witness_block: &SyncBlock) -> bool {
+        block.number == witness_block.number
+            && block.l1_batch_number == witness_block.l1_batch_number
+            && block.last_in_batch == witness_block.last_in_batch
+            && block.timestamp == witness_block.timestamp
+            && block.l1_gas_price == witness_block.l1_gas_price
+            && block.l2_fair_gas_price == witness_block.l2_fair_gas_price
+            && block.fair_pubdata_price == witness_block.fair_pubdata_price
+            && block.base_system_contracts_hashes == witness_block.base_system_contracts_hashes
+            && block.operator_address == witness_block.operator_address
+            && block.virtual_blocks == witness_block.virtual_blocks
+            && block.hash == witness_block.hash
+            && block.protocol_version == witness_block.protocol_version
+            && Self::transactions_match(block, witness_block)
+    }
+
+    fn transactions_match(block: &SyncBlock, witness_block: &SyncBlock) -> bool {
+        let (Some(transactions), Some(witness_transactions)) =
+            (&block.transactions, &witness_block.transactions)
+        else {
+            return block.transactions.is_none() && witness_block.transactions.is_none();
+        };
+        transactions.len() == witness_transactions.len()
+            && transactions
+                .iter()
+                .zip(witness_transactions)
+                .all(|(tx, witness_tx)| {
+                    // `Transaction` doesn't implement `PartialEq`, so we compare serialized transactions instead.
+                    let tx = serde_json::to_value(tx).expect("failed serializing transaction");
+                    let witness_tx =
+                        serde_json::to_value(witness_tx).expect("failed serializing transaction");
+                    tx == witness_tx
+                })
+    }
+}
+
+#[async_trait]
+impl MainNodeClient for QuorumMainNodeClient {
+    async fn fetch_system_contract_by_hash(
+        &self,
+        hash: H256,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.primary.fetch_system_contract_by_hash(hash).await
+    }
+
+    async fn fetch_genesis_contract_bytecode(
+        &self,
+        address: Address,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.primary.fetch_genesis_contract_bytecode(address).await
+    }
+
+    async fn fetch_protocol_version(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        self.primary.fetch_protocol_version(protocol_version).await
+    }
+
+    async fn fetch_genesis_l1_batch_hash(&self) -> EnrichedClientResult<H256> {
+        self.primary.fetch_genesis_l1_batch_hash().await
+    }
+
+    async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        self.primary.fetch_l2_block_number().await
+    }
+
+    async fn fetch_l1_batch_root_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SyncL1BatchRootHash>> {
+        self.primary.fetch_l1_batch_root_hash(number).await
+    }
+
+    /// Returns `Ok(None)` if the quorum is not reached yet (e.g., because some witnesses lag behind
+    /// the primary endpoint), and an error if the quorum cannot be reached because of diverging miniblocks.
+    async fn fetch_l2_block(
+        &self,
+        number: MiniblockNumber,
+        with_transactions: bool,
+    ) -> EnrichedClientResult<Option<SyncBlock>> {
+        let Some(block) = self
+            .primary
+            .fetch_l2_block(number, with_transactions)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let witness_responses = self
+            .witnesses
+            .iter()
+            .map(|witness| witness.fetch_l2_block(number, with_transactions));
+        let witness_responses = futures::future::join_all(witness_responses).await;
+
+        let mut confirmations = 1; // The primary endpoint
+        let mut mismatches = 0;
+        for (i, response) in witness_responses.into_iter().enumerate() {
+            match response {
+                Ok(Some(witness_block)) if Self::blocks_match(&block, &witness_block) => {
+                    confirmations += 1;
+                }
+                Ok(Some(witness_block)) => {
+                    tracing::error!(
+                        "Miniblock #{number} returned by main node witness #{i} diverges from the one returned \
+                         by the primary endpoint; primary: {block:?}, witness: {witness_block:?}"
+                    );
+                    FETCHER_METRICS.quorum_mismatches.inc();
+                    mismatches += 1;
+                }
+                Ok(None) => {
+                    tracing::debug!("Main node witness #{i} doesn't have miniblock #{number} yet");
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed fetching miniblock #{number} from main node witness #{i}: {err}"
+                    );
+                    FETCHER_METRICS.witness_errors.inc();
+                }
+            }
+        }
+
+        let endpoint_count = self.witnesses.len() + 1;
+        let required_confirmations = self.policy.required_confirmations(endpoint_count);
+        if confirmations >= required_confirmations {
+            Ok(Some(block))
+        } else if endpoint_count - mismatches < required_confirmations {
+            let err = EnrichedClientError::custom(
+                "miniblock diverges between main node endpoints",
+                "fetch_l2_block",
+            );
+            Err(err
+                .with_arg("number", &number)
+                .with_arg("confirmations", &confirmations)
+                .with_arg("mismatches", &mismatches))
+        } else {
+            tracing::info!(
+                "Miniblock #{number} is confirmed by {confirmations}/{required_confirmations} required \
+                 main node endpoints; waiting for other endpoints"
+            );
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,724 @@
+//! Read replica mode of the external node.
+//!
+//! A read replica doesn't execute transactions. Instead, it fetches miniblocks together with their execution outputs
+//! (transaction results, events, L2-to-L1 logs, storage writes and published bytecodes; for the last miniblock
+//! in an L1 batch, also the batch header and initial writes) from the main node, checks the outputs against
+//! the miniblock data and persists both to Postgres. Thus, a replica can serve the read-only part of the Web3 API
+//! (including VM invocations like `eth_call`) without running the state keeper or the Merkle tree.
+//!
+//! The replica uses the same Postgres schema as other nodes. Data only needed for execution or proving
+//! (e.g., bootloader memory, events queues and call traces) is not populated.
+
+use std::{collections::HashMap, fmt, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use itertools::Itertools;
+use multivm::utils::{derive_base_fee_and_gas_per_pubdata, get_max_gas_per_pubdata_byte};
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    api::en::{SyncBlock, SyncBlockOutputs},
+    block::{L1BatchHeader, MiniblockContentHasher, MiniblockHasher, MiniblockHeader},
+    event::extract_added_tokens,
+    fee_model::BatchFeeInput,
+    l1::L1Tx,
+    l2::L2Tx,
+    protocol_version::ProtocolUpgradeTx,
+    tx::{tx_execution_info::TxExecutionStatus, IncludedTxLocation, TransactionExecutionResult},
+    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, H256,
+};
+use zksync_utils::bytecode::hash_bytecode;
+use zksync_web3_decl::{
+    error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
+    jsonrpsee::{core::ClientError as RpcError, http_client::HttpClient},
+    namespaces::EnNamespaceClient,
+};
+
+use super::{
+    client::MainNodeClient,
+    metrics::{FetchStage, FETCHER_METRICS, REPLICA_METRICS},
+    SyncState,
+};
+use crate::state_keeper::io::common::IoCursor;
+
+#[cfg(test)]
+mod tests;
+
+const RETRY_DELAY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Client fetching miniblock execution outputs from the main node.
+#[async_trait]
+pub trait BlockOutputsClient: 'static + Send + Sync + fmt::Debug {
+    async fn fetch_l2_block_outputs(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<SyncBlockOutputs>>;
+}
+
+#[async_trait]
+impl BlockOutputsClient for HttpClient {
+    async fn fetch_l2_block_outputs(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<SyncBlockOutputs>> {
+        let request_latency = FETCHER_METRICS.requests[&FetchStage::SyncL2BlockOutputs].start();
+        let outputs = self
+            .sync_l2_block_outputs(number)
+            .rpc_context("fetch_l2_block_outputs")
+            .with_arg("number", &number)
+            .await?;
+        request_latency.observe();
+        Ok(outputs)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum SyncerError {
+    #[error("JSON-RPC error communicating with main node")]
+    Web3(#[from] EnrichedClientError),
+    #[error("Internal error")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl SyncerError {
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Web3(err) => {
+                matches!(
+                    err.as_ref(),
+                    RpcError::Transport(_) | RpcError::RequestTimeout
+                )
+            }
+            Self::Internal(_) => false,
+        }
+    }
+}
+
+/// Miniblock together with its execution outputs that were checked to be consistent with each other.
+#[derive(Debug)]
+struct ReplicatedMiniblock {
+    block: SyncBlock,
+    hash: H256,
+    content_hash: H256,
+    transactions: Vec<TransactionExecutionResult>,
+    outputs: SyncBlockOutputs,
+}
+
+impl ReplicatedMiniblock {
+    fn new(
+        mut block: SyncBlock,
+        outputs: SyncBlockOutputs,
+        prev_miniblock_hash: H256,
+    ) -> anyhow::Result<Self> {
+        let number = block.number;
+        anyhow::ensure!(
+            outputs.number == number,
+            "Main node returned outputs for miniblock #{} instead of #{number}",
+            outputs.number
+        );
+        let transactions = block
+            .transactions
+            .take()
+            .context("Transactions are always requested")?;
+        anyhow::ensure!(
+            transactions.len() == outputs.transactions.len(),
+            "Miniblock #{number} has {} transactions, but outputs for {} transactions",
+            transactions.len(),
+            outputs.transactions.len()
+        );
+
+        let transactions: Vec<_> = transactions
+            .into_iter()
+            .zip(&outputs.transactions)
+            .map(|(transaction, output)| {
+                let hash = transaction.hash();
+                anyhow::ensure!(
+                    hash == output.hash,
+                    "Output for transaction {:?} in miniblock #{number} doesn't match transaction {hash:?}",
+                    output.hash
+                );
+                Ok(TransactionExecutionResult {
+                    transaction,
+                    hash,
+                    execution_info: output.execution_info,
+                    execution_status: if output.is_successful {
+                        TxExecutionStatus::Success
+                    } else {
+                        TxExecutionStatus::Failure
+                    },
+                    refunded_gas: output.refunded_gas,
+                    operator_suggested_refund: 0,
+                    compressed_bytecodes: vec![],
+                    call_traces: vec![],
+                    revert_reason: None,
+                    executed_at: None,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        // The replica doesn't run the reorg detector, so a miniblock not extending the local chain is fatal.
+        let mut hasher = MiniblockHasher::new(number, block.timestamp, prev_miniblock_hash);
+        for tx in &transactions {
+            hasher.push_tx_hash(tx.hash);
+        }
+        let hash = hasher.finalize(block.protocol_version);
+        if let Some(reference_hash) = block.hash {
+            anyhow::ensure!(
+                reference_hash == hash,
+                "Miniblock #{number} returned by the main node has hash {reference_hash:?}, while the hash \
+                 computed from local data is {hash:?}; the replica has diverged from the main node"
+            );
+        }
+
+        let mut this = Self {
+            block,
+            hash,
+            content_hash: H256::zero(),
+            transactions,
+            outputs,
+        };
+        this.validate_tx_indices()?;
+        this.validate_l1_batch_data()?;
+        this.validate_factory_deps()?;
+        this.content_hash = this.compute_content_hash();
+        if let Some(reference_hash) = this.block.content_hash {
+            anyhow::ensure!(
+                reference_hash == this.content_hash,
+                "Content hash of miniblock #{number} returned by the main node ({reference_hash:?}) \
+                 doesn't match the hash computed from its outputs ({:?})",
+                this.content_hash
+            );
+        }
+        Ok(this)
+    }
+
+    /// Checks that outputs reference only transactions in this miniblock. Outputs of a fictive miniblock
+    /// (i.e., one without transactions) are produced by the bootloader and have zero transaction hash.
+    fn validate_tx_indices(&self) -> anyhow::Result<()> {
+        let number = self.block.number;
+        let tx_count = self.transactions.len();
+        let is_valid_index = |index: u32| tx_count == 0 || (index as usize) < tx_count;
+
+        for event in &self.outputs.events {
+            anyhow::ensure!(
+                is_valid_index(event.location.1),
+                "Event in miniblock #{number} references transaction #{}",
+                event.location.1
+            );
+        }
+        for (tx_index, _) in &self.outputs.l2_to_l1_logs {
+            anyhow::ensure!(
+                is_valid_index(*tx_index),
+                "L2-to-L1 log in miniblock #{number} references transaction #{tx_index}"
+            );
+        }
+        for (tx_hash, _) in &self.outputs.storage_logs {
+            let is_known =
+                tx_hash.is_zero() || self.transactions.iter().any(|tx| tx.hash == *tx_hash);
+            anyhow::ensure!(
+                is_known,
+                "Storage logs in miniblock #{number} reference unknown transaction {tx_hash:?}"
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that the L1 batch header and initial writes are provided exactly for the last miniblock in the batch.
+    fn validate_l1_batch_data(&self) -> anyhow::Result<()> {
+        let number = self.block.number;
+        let l1_batch_number = self.block.l1_batch_number;
+        if !self.block.last_in_batch {
+            anyhow::ensure!(
+                self.outputs.l1_batch_header.is_none() && self.outputs.initial_writes.is_empty(),
+                "Outputs of miniblock #{number}, which isn't the last one in L1 batch #{l1_batch_number}, \
+                 contain L1 batch data"
+            );
+            return Ok(());
+        }
+
+        let header = self.outputs.l1_batch_header.as_ref().with_context(|| {
+            format!("Outputs of miniblock #{number} lack the header of L1 batch #{l1_batch_number}")
+        })?;
+        anyhow::ensure!(
+            header.number == l1_batch_number,
+            "Outputs of miniblock #{number} contain header of L1 batch #{} instead of #{l1_batch_number}",
+            header.number
+        );
+        anyhow::ensure!(
+            header.base_system_contracts_hashes == self.block.base_system_contracts_hashes
+                && header.protocol_version == Some(self.block.protocol_version),
+            "Header of L1 batch #{l1_batch_number} doesn't match miniblock #{number}"
+        );
+        Ok(())
+    }
+
+    /// Checks that published bytecodes match their hashes.
+    fn validate_factory_deps(&self) -> anyhow::Result<()> {
+        for (hash, bytecode) in &self.outputs.factory_deps {
+            let actual_hash = hash_bytecode(&bytecode.0);
+            anyhow::ensure!(
+                actual_hash == *hash,
+                "Bytecode published in miniblock #{} has hash {actual_hash:?}, while the main node reports {hash:?}",
+                self.block.number
+            );
+        }
+        Ok(())
+    }
+
+    fn tx_location(&self, tx_index: u32) -> IncludedTxLocation {
+        let (tx_hash, tx_initiator_address) = match self.transactions.get(tx_index as usize) {
+            Some(tx) => (tx.hash, tx.transaction.initiator_account()),
+            None => (H256::zero(), Address::zero()),
+        };
+        IncludedTxLocation {
+            tx_hash,
+            tx_index_in_miniblock: tx_index,
+            tx_initiator_address,
+        }
+    }
+
+    fn compute_content_hash(&self) -> H256 {
+        let mut hasher = MiniblockContentHasher::new(self.block.number);
+        for output in &self.outputs.transactions {
+            hasher.push_tx(
+                output.hash,
+                output.is_successful,
+                output.refunded_gas.into(),
+            );
+        }
+        for event in &self.outputs.events {
+            let tx_hash = self.tx_location(event.location.1).tx_hash;
+            hasher.push_event(tx_hash, event.address, &event.indexed_topics, &event.value);
+        }
+        for (tx_index, log) in &self.outputs.l2_to_l1_logs {
+            let tx_hash = self.tx_location(*tx_index).tx_hash;
+            hasher.push_l2_to_l1_log(tx_hash, &log.0);
+        }
+        for log in self.outputs.storage_logs.iter().flat_map(|(_, logs)| logs) {
+            hasher.push_storage_write(log.key.hashed_key(), log.value);
+        }
+        hasher.finalize()
+    }
+
+    /// Persists the miniblock and its outputs. If the miniblock is the last one in its L1 batch, also inserts
+    /// the L1 batch header and initial writes. Only data necessary to serve the Web3 API is persisted; e.g.,
+    /// call traces are not.
+    async fn persist(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l2_erc20_bridge_addr: Address,
+    ) -> anyhow::Result<()> {
+        let block = &self.block;
+        let number = block.number;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .context("failed starting DB transaction")?;
+
+        for tx in &self.transactions {
+            if let Ok(l1_tx) = L1Tx::try_from(tx.transaction.clone()) {
+                let l1_block_number = L1BlockNumber(l1_tx.common_data.eth_block as u32);
+                transaction
+                    .transactions_dal()
+                    .insert_transaction_l1(l1_tx, l1_block_number)
+                    .await;
+            } else if let Ok(l2_tx) = L2Tx::try_from(tx.transaction.clone()) {
+                transaction
+                    .transactions_dal()
+                    .insert_transaction_l2(l2_tx, Default::default())
+                    .await;
+            } else if let Ok(upgrade_tx) = ProtocolUpgradeTx::try_from(tx.transaction.clone()) {
+                transaction
+                    .transactions_dal()
+                    .insert_system_transaction(upgrade_tx)
+                    .await;
+            } else {
+                anyhow::bail!("Transaction {:?} is neither L1 nor L2", tx.hash);
+            }
+        }
+
+        let protocol_version = block.protocol_version;
+        let fee_input = BatchFeeInput::for_protocol_version(
+            protocol_version,
+            block.l2_fair_gas_price,
+            block.fair_pubdata_price,
+            block.l1_gas_price,
+        );
+        let base_fee_per_gas =
+            derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into()).0;
+        let l1_tx_count = self
+            .transactions
+            .iter()
+            .filter(|tx| matches!(tx.transaction.common_data, ExecuteTransactionCommon::L1(_)))
+            .count();
+        let l2_tx_count = self.transactions.len() - l1_tx_count;
+
+        let miniblock_header = MiniblockHeader {
+            number,
+            timestamp: block.timestamp,
+            hash: self.hash,
+            l1_tx_count: l1_tx_count as u16,
+            l2_tx_count: l2_tx_count as u16,
+            fee_account_address: block.operator_address,
+            base_fee_per_gas,
+            batch_fee_input: fee_input,
+            base_system_contracts_hashes: block.base_system_contracts_hashes,
+            protocol_version: Some(protocol_version),
+            gas_per_pubdata_limit: get_max_gas_per_pubdata_byte(protocol_version.into()),
+            virtual_blocks: block.virtual_blocks.unwrap_or(0),
+        };
+        transaction
+            .blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .with_context(|| format!("failed inserting miniblock #{number}"))?;
+        transaction
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(number, &self.transactions, base_fee_per_gas.into())
+            .await;
+
+        let storage_logs = &self.outputs.storage_logs;
+        transaction
+            .storage_logs_dal()
+            .insert_storage_logs(number, storage_logs)
+            .await
+            .with_context(|| format!("failed inserting storage logs for miniblock #{number}"))?;
+        #[allow(deprecated)] // Will be removed shortly
+        {
+            transaction
+                .storage_dal()
+                .apply_storage_logs(storage_logs)
+                .await;
+        }
+
+        if !self.outputs.factory_deps.is_empty() {
+            let factory_deps: HashMap<_, _> = self
+                .outputs
+                .factory_deps
+                .iter()
+                .map(|(hash, bytecode)| (*hash, bytecode.0.clone()))
+                .collect();
+            transaction
+                .factory_deps_dal()
+                .insert_factory_deps(number, &factory_deps)
+                .await
+                .with_context(|| {
+                    format!("failed inserting factory deps for miniblock #{number}")
+                })?;
+        }
+
+        let added_tokens = extract_added_tokens(l2_erc20_bridge_addr, &self.outputs.events);
+        if !added_tokens.is_empty() {
+            transaction
+                .tokens_dal()
+                .add_tokens(&added_tokens)
+                .await
+                .context("failed adding tokens")?;
+        }
+
+        let events = self
+            .outputs
+            .events
+            .iter()
+            .group_by(|event| event.location.1);
+        let events: Vec<_> = events
+            .into_iter()
+            .map(|(tx_index, events)| (self.tx_location(tx_index), events.collect()))
+            .collect();
+        transaction.events_dal().save_events(number, &events).await;
+
+        let l2_to_l1_logs = self
+            .outputs
+            .l2_to_l1_logs
+            .iter()
+            .group_by(|(tx_index, _)| *tx_index);
+        let l2_to_l1_logs: Vec<_> = l2_to_l1_logs
+            .into_iter()
+            .map(|(tx_index, logs)| {
+                (
+                    self.tx_location(tx_index),
+                    logs.map(|(_, log)| log).collect(),
+                )
+            })
+            .collect();
+        transaction
+            .events_dal()
+            .save_user_l2_to_l1_logs(number, &l2_to_l1_logs)
+            .await;
+
+        transaction
+            .content_hashes_dal()
+            .set_content_hash(number, self.content_hash)
+            .await
+            .with_context(|| format!("failed setting content hash for miniblock #{number}"))?;
+
+        if let Some(header) = &self.outputs.l1_batch_header {
+            Self::seal_l1_batch(
+                &mut transaction,
+                block,
+                header,
+                &self.outputs.initial_writes,
+            )
+            .await?;
+        }
+        transaction
+            .commit()
+            .await
+            .context("failed committing DB transaction")
+    }
+
+    /// Inserts the header and initial writes of the L1 batch ending with the specified miniblock. Bootloader memory
+    /// and other data only needed for proving the batch is not persisted. The root hash of the batch is not known
+    /// at this point; it's fetched from the main node separately (see [`ReplicaSyncer::sync_root_hashes()`]).
+    async fn seal_l1_batch(
+        storage: &mut StorageProcessor<'_>,
+        last_miniblock: &SyncBlock,
+        header: &L1BatchHeader,
+        initial_writes: &[(H256, u64)],
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = last_miniblock.l1_batch_number;
+        let resolved_l1_batch = storage
+            .storage_web3_dal()
+            .resolve_l1_batch_number_of_miniblock(last_miniblock.number)
+            .await
+            .context("failed resolving pending L1 batch")?;
+        anyhow::ensure!(
+            resolved_l1_batch.pending_l1_batch == l1_batch_number,
+            "Unexpected pending L1 batch: expected #{l1_batch_number}, got #{}",
+            resolved_l1_batch.pending_l1_batch
+        );
+        let timestamp = storage
+            .blocks_web3_dal()
+            .get_expected_l1_batch_timestamp(&resolved_l1_batch)
+            .await
+            .context("failed getting L1 batch timestamp")?
+            .with_context(|| format!("L1 batch #{l1_batch_number} has no miniblocks"))?;
+        anyhow::ensure!(
+            header.timestamp == timestamp,
+            "L1 batch #{l1_batch_number} returned by the main node has timestamp {}, while its first miniblock \
+             has timestamp {timestamp}",
+            header.timestamp
+        );
+
+        storage
+            .blocks_dal()
+            .insert_l1_batch(
+                header,
+                &[],
+                Default::default(),
+                &[],
+                &[],
+                Default::default(),
+            )
+            .await
+            .with_context(|| format!("failed inserting L1 batch #{l1_batch_number}"))?;
+        storage
+            .storage_logs_dedup_dal()
+            .insert_initial_writes_with_indices(l1_batch_number, initial_writes)
+            .await
+            .with_context(|| {
+                format!("failed inserting initial writes for L1 batch #{l1_batch_number}")
+            })?;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(l1_batch_number)
+            .await
+            .context("failed marking miniblocks as executed")?;
+        storage
+            .transactions_dal()
+            .mark_miniblock_txs_as_executed_in_l1_batch(l1_batch_number)
+            .await
+            .context("failed marking transactions as executed")?;
+        Ok(())
+    }
+}
+
+/// Component synchronizing a read replica with the main node. For each miniblock, the syncer fetches the miniblock
+/// with transactions and its execution outputs, checks them for consistency and persists them to Postgres.
+///
+/// Miniblocks are trusted to be correctly executed by the main node (sequencer signatures of miniblocks
+/// and their outputs are checked by the provided [`MainNodeClient`] and [`BlockOutputsClient`], respectively);
+/// the syncer only checks that miniblocks extend the local chain and that outputs match the miniblock
+/// content hash. Unlike the fetcher, the syncer doesn't handle reorgs; a miniblock diverging from the local chain
+/// stops the syncer with an error.
+///
+/// Root hashes of sealed L1 batches are fetched from the main node once it computes them. They are not
+/// authenticated; the replica doesn't use them other than to serve L1 batch details via the API.
+#[derive(Debug)]
+pub struct ReplicaSyncer {
+    client: Box<dyn MainNodeClient>,
+    outputs_client: Box<dyn BlockOutputsClient>,
+    pool: ConnectionPool,
+    sync_state: SyncState,
+    l2_erc20_bridge_addr: Address,
+    poll_interval: Duration,
+}
+
+impl ReplicaSyncer {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn new(
+        client: Box<dyn MainNodeClient>,
+        outputs_client: Box<dyn BlockOutputsClient>,
+        pool: ConnectionPool,
+        sync_state: SyncState,
+        l2_erc20_bridge_addr: Address,
+    ) -> Self {
+        Self {
+            client,
+            outputs_client,
+            pool,
+            sync_state,
+            l2_erc20_bridge_addr,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
+        let mut cursor = IoCursor::new(&mut storage)
+            .await
+            .context("failed getting I/O cursor from Postgres")?;
+        let last_l1_batch_with_root_hash = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await?;
+        drop(storage);
+        let mut next_root_hash_l1_batch =
+            last_l1_batch_with_root_hash.map_or(cursor.l1_batch, |number| number + 1);
+        tracing::info!(
+            "Starting replica syncer. Initial miniblock: {}, initial L1 batch: {}, \
+             initial L1 batch without root hash: {next_root_hash_l1_batch}",
+            cursor.next_miniblock,
+            cursor.l1_batch
+        );
+
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            let result = match self.sync_miniblocks(&mut cursor, &stop_receiver).await {
+                Ok(progressed) => self
+                    .sync_root_hashes(&mut next_root_hash_l1_batch, cursor.l1_batch)
+                    .await
+                    .map(|()| progressed),
+                Err(err) => Err(err),
+            };
+            let delay = match result {
+                Ok(true) => continue,
+                Ok(false) => self.poll_interval,
+                Err(err) if err.is_transient() => {
+                    tracing::warn!("Following transport error occurred: {err}");
+                    tracing::info!("Trying again after a delay: {RETRY_DELAY_INTERVAL:?}");
+                    RETRY_DELAY_INTERVAL
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if tokio::time::timeout(delay, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, replica syncer is shutting down");
+        Ok(())
+    }
+
+    /// Syncs miniblocks up to the latest one on the main node. Returns `true` if at least one miniblock was persisted.
+    async fn sync_miniblocks(
+        &self,
+        cursor: &mut IoCursor,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> Result<bool, SyncerError> {
+        let last_main_node_block = self.client.fetch_l2_block_number().await?;
+        self.sync_state.set_main_node_block(last_main_node_block);
+
+        let mut progressed = false;
+        while cursor.next_miniblock <= last_main_node_block && !*stop_receiver.borrow() {
+            if !self.sync_miniblock(cursor).await? {
+                break;
+            }
+            progressed = true;
+        }
+        Ok(progressed)
+    }
+
+    /// Fetches and persists root hashes for sealed L1 batches starting from `next_l1_batch` and until either
+    /// `pending_l1_batch` is reached or the main node doesn't have the root hash yet.
+    async fn sync_root_hashes(
+        &self,
+        next_l1_batch: &mut L1BatchNumber,
+        pending_l1_batch: L1BatchNumber,
+    ) -> Result<(), SyncerError> {
+        while *next_l1_batch < pending_l1_batch {
+            let number = *next_l1_batch;
+            let Some(response) = self.client.fetch_l1_batch_root_hash(number).await? else {
+                break;
+            };
+            let root_hash = response.root_hash;
+            let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
+            storage
+                .blocks_dal()
+                .save_l1_batch_root_hash_from_main_node(number, root_hash)
+                .await
+                .with_context(|| format!("failed saving root hash for L1 batch #{number}"))?;
+            drop(storage);
+            tracing::debug!("Persisted root hash {root_hash:?} for L1 batch #{number}");
+            *next_l1_batch += 1;
+        }
+        Ok(())
+    }
+
+    /// Fetches, checks and persists the next miniblock. Returns `false` if the main node doesn't have it yet.
+    async fn sync_miniblock(&self, cursor: &mut IoCursor) -> Result<bool, SyncerError> {
+        let number = cursor.next_miniblock;
+        let Some(block) = self.client.fetch_l2_block(number, true).await? else {
+            return Ok(false);
+        };
+        let Some(outputs) = self.outputs_client.fetch_l2_block_outputs(number).await? else {
+            return Ok(false);
+        };
+        if block.l1_batch_number != cursor.l1_batch {
+            let err = anyhow::anyhow!(
+                "Miniblock #{number} belongs to L1 batch #{}, while the pending L1 batch is #{}",
+                block.l1_batch_number,
+                cursor.l1_batch
+            );
+            return Err(err.into());
+        }
+
+        let miniblock = match ReplicatedMiniblock::new(block, outputs, cursor.prev_miniblock_hash) {
+            Ok(miniblock) => miniblock,
+            Err(err) => {
+                REPLICA_METRICS.invalid_outputs.inc();
+                return Err(err.into());
+            }
+        };
+        let latency = REPLICA_METRICS.persist_miniblock.start();
+        let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
+        miniblock
+            .persist(&mut storage, self.l2_erc20_bridge_addr)
+            .await?;
+        drop(storage);
+        latency.observe();
+
+        tracing::info!(
+            "Persisted miniblock #{number} with {} transactions / {}",
+            miniblock.transactions.len(),
+            self.sync_state.get_main_node_block().max(number)
+        );
+        cursor.next_miniblock += 1;
+        cursor.prev_miniblock_hash = miniblock.hash;
+        cursor.prev_miniblock_timestamp = miniblock.block.timestamp;
+        REPLICA_METRICS.miniblock.set(number.0.into());
+        if miniblock.block.last_in_batch {
+            tracing::info!("Sealed L1 batch #{}", cursor.l1_batch);
+            REPLICA_METRICS.l1_batch.set(cursor.l1_batch.0.into());
+            cursor.l1_batch += 1;
+        }
+        self.sync_state.set_local_block(number);
+        Ok(true)
+    }
+}
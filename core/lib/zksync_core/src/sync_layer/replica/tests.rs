@@ -0,0 +1,314 @@
+//! Tests for the read replica syncer.
+
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use zksync_dal::content_hashes_dal::ContentHashCheck;
+use zksync_types::{
+    api::en::SyncTransactionOutput, tx::ExecutionMetrics, L2ChainId, PackedEthSignature,
+};
+
+use super::*;
+use crate::{
+    consensus::testonly::MockMainNodeClient,
+    genesis::{ensure_genesis_state, GenesisParams},
+    sync_layer::SignatureVerifyingOutputsClient,
+};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Default)]
+struct MockOutputsClient(HashMap<MiniblockNumber, SyncBlockOutputs>);
+
+impl MockOutputsClient {
+    fn insert(&mut self, block: &SyncBlock, l1_batch_timestamp: u64) {
+        let transactions = block.transactions.as_ref().unwrap();
+        let (l1_batch_header, initial_writes) = if block.last_in_batch {
+            let header = L1BatchHeader::new(
+                block.l1_batch_number,
+                l1_batch_timestamp,
+                block.base_system_contracts_hashes,
+                block.protocol_version,
+            );
+            let initial_write = (
+                H256::from_low_u64_be(block.l1_batch_number.0.into()),
+                1_000 + u64::from(block.l1_batch_number.0),
+            );
+            (Some(header), vec![initial_write])
+        } else {
+            (None, vec![])
+        };
+        let outputs = SyncBlockOutputs {
+            number: block.number,
+            transactions: transactions
+                .iter()
+                .map(|tx| SyncTransactionOutput {
+                    hash: tx.hash(),
+                    is_successful: true,
+                    refunded_gas: 0,
+                    execution_info: ExecutionMetrics::default(),
+                })
+                .collect(),
+            events: vec![],
+            l2_to_l1_logs: vec![],
+            storage_logs: vec![],
+            factory_deps: vec![],
+            l1_batch_header,
+            initial_writes,
+            sequencer_signature: None,
+        };
+        self.0.insert(block.number, outputs);
+    }
+
+    fn sign(&mut self, signing_key: &H256, chain_id: L2ChainId) {
+        for outputs in self.0.values_mut() {
+            let digest = outputs.signed_digest(chain_id);
+            let signature = PackedEthSignature::sign_raw(signing_key, &digest).unwrap();
+            outputs.sequencer_signature = Some(signature);
+        }
+    }
+}
+
+#[async_trait]
+impl BlockOutputsClient for MockOutputsClient {
+    async fn fetch_l2_block_outputs(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<SyncBlockOutputs>> {
+        Ok(self.0.get(&number).cloned())
+    }
+}
+
+async fn prepare_clients(l1_batch_sizes: &[u32]) -> (MockMainNodeClient, MockOutputsClient) {
+    let mut client = MockMainNodeClient::default();
+    client.push_l1_batch(0);
+    for &size in l1_batch_sizes {
+        client.push_l1_batch(size);
+    }
+    let mut outputs_client = MockOutputsClient::default();
+    let last_block = client.fetch_l2_block_number().await.unwrap();
+    let mut l1_batch_timestamps = HashMap::new();
+    for number in 1..=last_block.0 {
+        let block = client
+            .fetch_l2_block(MiniblockNumber(number), true)
+            .await
+            .unwrap()
+            .unwrap();
+        // The L1 batch timestamp is equal to the timestamp of its first miniblock.
+        let l1_batch_timestamp = *l1_batch_timestamps
+            .entry(block.l1_batch_number)
+            .or_insert(block.timestamp);
+        outputs_client.insert(&block, l1_batch_timestamp);
+    }
+    (client, outputs_client)
+}
+
+#[tokio::test]
+async fn validating_replicated_miniblocks() {
+    let (client, outputs_client) = prepare_clients(&[2]).await;
+    let block = client
+        .fetch_l2_block(MiniblockNumber(1), true)
+        .await
+        .unwrap()
+        .unwrap();
+    let outputs = outputs_client.0[&MiniblockNumber(1)].clone();
+    let genesis_hash = MiniblockHasher::legacy_hash(MiniblockNumber(0));
+
+    let miniblock = ReplicatedMiniblock::new(block.clone(), outputs.clone(), genesis_hash).unwrap();
+    assert_eq!(miniblock.hash, block.hash.unwrap());
+    assert_eq!(miniblock.transactions.len(), 1);
+
+    let err = ReplicatedMiniblock::new(block.clone(), outputs.clone(), H256::repeat_byte(1))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("diverged"), "{err}");
+
+    let mut invalid_outputs = outputs.clone();
+    invalid_outputs.transactions[0].hash = H256::repeat_byte(2);
+    ReplicatedMiniblock::new(block.clone(), invalid_outputs, genesis_hash).unwrap_err();
+
+    let mut invalid_outputs = outputs.clone();
+    invalid_outputs.transactions.clear();
+    ReplicatedMiniblock::new(block.clone(), invalid_outputs, genesis_hash).unwrap_err();
+
+    let mut invalid_outputs = outputs.clone();
+    invalid_outputs.factory_deps = vec![(H256::repeat_byte(3), vec![0; 32].into())];
+    let err = ReplicatedMiniblock::new(block.clone(), invalid_outputs, genesis_hash)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Bytecode"), "{err}");
+
+    // L1 batch data must be provided only for the last miniblock in the batch.
+    let mut invalid_outputs = outputs.clone();
+    invalid_outputs.initial_writes = vec![(H256::repeat_byte(4), 1_000)];
+    ReplicatedMiniblock::new(block.clone(), invalid_outputs, genesis_hash).unwrap_err();
+
+    let prev_block = client
+        .fetch_l2_block(MiniblockNumber(2), false)
+        .await
+        .unwrap()
+        .unwrap();
+    let fictive_block = client
+        .fetch_l2_block(MiniblockNumber(3), true)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(fictive_block.last_in_batch);
+    let mut fictive_outputs = outputs_client.0[&MiniblockNumber(3)].clone();
+    fictive_outputs.l1_batch_header = None;
+    let err = ReplicatedMiniblock::new(fictive_block, fictive_outputs, prev_block.hash.unwrap())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("lack the header"), "{err}");
+
+    let mut block_with_content_hash = block.clone();
+    block_with_content_hash.content_hash = Some(miniblock.content_hash);
+    ReplicatedMiniblock::new(
+        block_with_content_hash.clone(),
+        outputs.clone(),
+        genesis_hash,
+    )
+    .unwrap();
+    let mut invalid_outputs = outputs;
+    invalid_outputs.transactions[0].is_successful = false;
+    let err = ReplicatedMiniblock::new(block_with_content_hash, invalid_outputs, genesis_hash)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Content hash"), "{err}");
+}
+
+#[tokio::test]
+async fn syncing_miniblocks() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    // Two L1 batches with 2 and 1 miniblocks, plus a fictive miniblock in each batch.
+    let (mut client, outputs_client) = prepare_clients(&[2, 1]).await;
+    // Only the first L1 batch has its root hash computed by the main node.
+    client.insert_l1_batch_root_hash(L1BatchNumber(1), H256::repeat_byte(0x11));
+    let sync_state = SyncState::default();
+    let mut syncer = ReplicaSyncer::new(
+        Box::new(client),
+        Box::new(outputs_client),
+        pool.clone(),
+        sync_state.clone(),
+        Address::repeat_byte(1),
+    );
+    syncer.poll_interval = POLL_INTERVAL;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let syncer_task = tokio::spawn(syncer.run(stop_receiver));
+
+    let started_at = tokio::time::Instant::now();
+    while sync_state.get_local_block() < MiniblockNumber(5) {
+        assert!(
+            started_at.elapsed() < TEST_TIMEOUT,
+            "timed out syncing miniblocks"
+        );
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    stop_sender.send_replace(true);
+    syncer_task.await.unwrap().unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let sealed_l1_batch = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_l1_batch, Some(L1BatchNumber(2)));
+    let l1_batch_header = storage
+        .blocks_dal()
+        .get_l1_batch_header(L1BatchNumber(1))
+        .await
+        .unwrap()
+        .unwrap();
+    // The L1 batch timestamp is equal to the timestamp of its first miniblock.
+    assert_eq!(l1_batch_header.timestamp, 1);
+    let initial_writes = storage
+        .storage_logs_dedup_dal()
+        .initial_writes_for_batch(L1BatchNumber(1))
+        .await;
+    assert_eq!(initial_writes, [(H256::from_low_u64_be(1), 1_001)]);
+
+    let root_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(root_hash, Some(H256::repeat_byte(0x11)));
+    let root_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert_eq!(root_hash, None);
+
+    for number in 1..=5 {
+        let check = storage
+            .content_hashes_dal()
+            .check_content_hash(MiniblockNumber(number))
+            .await
+            .unwrap();
+        assert_matches!(check, ContentHashCheck::Valid(_));
+    }
+
+    // Restarting the syncer should continue from the last persisted miniblock.
+    let cursor = IoCursor::new(&mut storage).await.unwrap();
+    assert_eq!(cursor.next_miniblock, MiniblockNumber(6));
+    assert_eq!(cursor.l1_batch, L1BatchNumber(3));
+}
+
+#[tokio::test]
+async fn signature_verifying_outputs_client() {
+    let chain_id = L2ChainId::default();
+    let signing_key = H256::repeat_byte(0x11);
+    let sequencer_address = PackedEthSignature::address_from_private_key(&signing_key).unwrap();
+    let (_, mut outputs_client) = prepare_clients(&[1]).await;
+    let unsigned_outputs: HashMap<_, _> = outputs_client.0.clone();
+    outputs_client.sign(&signing_key, chain_id);
+
+    let mut tampered_outputs = outputs_client.0.clone();
+    let outputs = tampered_outputs.get_mut(&MiniblockNumber(2)).unwrap();
+    outputs.initial_writes[0].1 += 1;
+
+    let client =
+        SignatureVerifyingOutputsClient::new(Box::new(outputs_client), sequencer_address, chain_id);
+    for number in 1..=2 {
+        let outputs = client
+            .fetch_l2_block_outputs(MiniblockNumber(number))
+            .await
+            .unwrap();
+        assert!(outputs.is_some());
+    }
+    let missing_outputs = client.fetch_l2_block_outputs(MiniblockNumber(3)).await;
+    assert!(missing_outputs.unwrap().is_none());
+
+    let client = SignatureVerifyingOutputsClient::new(
+        Box::new(MockOutputsClient(tampered_outputs)),
+        sequencer_address,
+        chain_id,
+    );
+    client
+        .fetch_l2_block_outputs(MiniblockNumber(1))
+        .await
+        .unwrap();
+    client
+        .fetch_l2_block_outputs(MiniblockNumber(2))
+        .await
+        .unwrap_err();
+
+    let client = SignatureVerifyingOutputsClient::new(
+        Box::new(MockOutputsClient(unsigned_outputs)),
+        sequencer_address,
+        chain_id,
+    );
+    client
+        .fetch_l2_block_outputs(MiniblockNumber(1))
+        .await
+        .unwrap_err();
+}
@@ -0,0 +1,231 @@
+//! Verification of sequencer signatures for miniblocks (and their outputs) fetched from the main node.
+
+use async_trait::async_trait;
+use zksync_types::{
+    api::{
+        self,
+        en::{SyncBlock, SyncBlockOutputs, SyncL1BatchRootHash},
+    },
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, PackedEthSignature, ProtocolVersionId,
+    H256,
+};
+use zksync_web3_decl::error::{EnrichedClientError, EnrichedClientResult};
+
+use super::{client::MainNodeClient, metrics::FETCHER_METRICS, replica::BlockOutputsClient};
+
+/// Checks that `digest` is signed by `expected_signer`.
+fn verify_signature(
+    signature: Option<&PackedEthSignature>,
+    digest: &H256,
+    expected_signer: Address,
+) -> Result<(), &'static str> {
+    let signature = signature.ok_or("data is not signed by the sequencer")?;
+    let signer = signature
+        .signature_recover_signer(digest)
+        .map_err(|_| "sequencer signature is malformed")?;
+    if signer == expected_signer {
+        Ok(())
+    } else {
+        Err("data is signed by an unexpected key")
+    }
+}
+
+/// [`MainNodeClient`] verifying that miniblocks are signed by the expected sequencer address. Allows the external node
+/// operator to detect a spoofed or compromised upstream (e.g., a proxy in front of the main node): miniblocks without
+/// a valid signature result in a non-transient error. For miniblocks fetched with transactions, the signed digest
+/// is recomputed from the received transaction contents (see [`SyncBlock::signed_digest()`]), so substituting
+/// a transaction body while keeping its hash invalidates the signature. Miniblocks fetched without transactions
+/// (e.g., during genesis or by the content hash checker) must have a valid header signature
+/// (see [`SyncBlock::header_signed_digest()`]).
+///
+/// Root hashes of L1 batches (used by nodes that don't wait for their own Merkle tree) must be signed as well.
+/// All other requests are passed through without verification.
+#[derive(Debug)]
+pub struct SignatureVerifyingMainNodeClient {
+    inner: Box<dyn MainNodeClient>,
+    sequencer_address: Address,
+    chain_id: L2ChainId,
+}
+
+impl SignatureVerifyingMainNodeClient {
+    pub fn new(
+        inner: Box<dyn MainNodeClient>,
+        sequencer_address: Address,
+        chain_id: L2ChainId,
+    ) -> Self {
+        Self {
+            inner,
+            sequencer_address,
+            chain_id,
+        }
+    }
+
+    fn verify(&self, block: &SyncBlock, with_transactions: bool) -> Result<(), &'static str> {
+        if with_transactions {
+            let digest = block
+                .signed_digest(self.chain_id)
+                .ok_or("miniblock lacks the hash or transactions")?;
+            verify_signature(
+                block.sequencer_signature.as_ref(),
+                &digest,
+                self.sequencer_address,
+            )
+        } else {
+            let digest = block
+                .header_signed_digest(self.chain_id)
+                .ok_or("miniblock lacks the hash")?;
+            verify_signature(
+                block.sequencer_header_signature.as_ref(),
+                &digest,
+                self.sequencer_address,
+            )
+        }
+    }
+}
+
+#[async_trait]
+impl MainNodeClient for SignatureVerifyingMainNodeClient {
+    async fn fetch_system_contract_by_hash(
+        &self,
+        hash: H256,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.inner.fetch_system_contract_by_hash(hash).await
+    }
+
+    async fn fetch_genesis_contract_bytecode(
+        &self,
+        address: Address,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.inner.fetch_genesis_contract_bytecode(address).await
+    }
+
+    async fn fetch_protocol_version(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        self.inner.fetch_protocol_version(protocol_version).await
+    }
+
+    async fn fetch_genesis_l1_batch_hash(&self) -> EnrichedClientResult<H256> {
+        self.inner.fetch_genesis_l1_batch_hash().await
+    }
+
+    async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        self.inner.fetch_l2_block_number().await
+    }
+
+    async fn fetch_l1_batch_root_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SyncL1BatchRootHash>> {
+        let Some(root_hash) = self.inner.fetch_l1_batch_root_hash(number).await? else {
+            return Ok(None);
+        };
+        let digest = root_hash.signed_digest(self.chain_id);
+        let mut verification = verify_signature(
+            root_hash.sequencer_signature.as_ref(),
+            &digest,
+            self.sequencer_address,
+        );
+        if root_hash.number != number {
+            verification = Err("root hash is returned for an unexpected L1 batch");
+        }
+        if let Err(reason) = verification {
+            tracing::error!(
+                "Root hash of L1 batch #{number} fails sequencer signature verification ({reason}); \
+                 expected signer: {:?}, root hash: {root_hash:?}",
+                self.sequencer_address
+            );
+            FETCHER_METRICS.invalid_signatures.inc();
+            let err = EnrichedClientError::custom(reason, "fetch_l1_batch_root_hash");
+            return Err(err
+                .with_arg("number", &number)
+                .with_arg("sequencer_address", &self.sequencer_address));
+        }
+        Ok(Some(root_hash))
+    }
+
+    async fn fetch_l2_block(
+        &self,
+        number: MiniblockNumber,
+        with_transactions: bool,
+    ) -> EnrichedClientResult<Option<SyncBlock>> {
+        let block = self.inner.fetch_l2_block(number, with_transactions).await?;
+        let Some(block) = block else {
+            return Ok(None);
+        };
+        let mut verification = self.verify(&block, with_transactions);
+        if block.number != number {
+            verification = Err("miniblock is returned for an unexpected number");
+        }
+        if let Err(reason) = verification {
+            tracing::error!(
+                "Miniblock #{number} fails sequencer signature verification ({reason}); \
+                 expected signer: {:?}, block: {block:?}",
+                self.sequencer_address
+            );
+            FETCHER_METRICS.invalid_signatures.inc();
+            let err = EnrichedClientError::custom(reason, "fetch_l2_block");
+            return Err(err
+                .with_arg("number", &number)
+                .with_arg("sequencer_address", &self.sequencer_address));
+        }
+        Ok(Some(block))
+    }
+}
+
+/// [`BlockOutputsClient`] verifying that miniblock outputs are signed by the expected sequencer address.
+/// Read replicas persist outputs without re-executing transactions, so unauthenticated outputs would allow
+/// a spoofed upstream to inject arbitrary state into the replica. Outputs without a valid signature result
+/// in a non-transient error stopping the replica syncer.
+#[derive(Debug)]
+pub struct SignatureVerifyingOutputsClient {
+    inner: Box<dyn BlockOutputsClient>,
+    sequencer_address: Address,
+    chain_id: L2ChainId,
+}
+
+impl SignatureVerifyingOutputsClient {
+    pub fn new(
+        inner: Box<dyn BlockOutputsClient>,
+        sequencer_address: Address,
+        chain_id: L2ChainId,
+    ) -> Self {
+        Self {
+            inner,
+            sequencer_address,
+            chain_id,
+        }
+    }
+}
+
+#[async_trait]
+impl BlockOutputsClient for SignatureVerifyingOutputsClient {
+    async fn fetch_l2_block_outputs(
+        &self,
+        number: MiniblockNumber,
+    ) -> EnrichedClientResult<Option<SyncBlockOutputs>> {
+        let Some(outputs) = self.inner.fetch_l2_block_outputs(number).await? else {
+            return Ok(None);
+        };
+        let digest = outputs.signed_digest(self.chain_id);
+        let verification = verify_signature(
+            outputs.sequencer_signature.as_ref(),
+            &digest,
+            self.sequencer_address,
+        );
+        if let Err(reason) = verification {
+            tracing::error!(
+                "Outputs of miniblock #{number} fail sequencer signature verification ({reason}); \
+                 expected signer: {:?}",
+                self.sequencer_address
+            );
+            FETCHER_METRICS.invalid_signatures.inc();
+            let err = EnrichedClientError::custom(reason, "fetch_l2_block_outputs");
+            return Err(err
+                .with_arg("number", &number)
+                .with_arg("sequencer_address", &self.sequencer_address));
+        }
+        Ok(Some(outputs))
+    }
+}
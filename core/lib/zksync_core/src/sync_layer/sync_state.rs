@@ -51,6 +51,17 @@ impl SyncState {
         inner.update_sync_metric();
     }
 
+    /// Updates the main node block only if `block` is greater than the currently known one. Used for sources
+    /// that can lag behind the main node, such as the gossip network. The main node block can only be moved back
+    /// (e.g., after a revert on the main node) by [`Self::set_main_node_block()`].
+    pub(crate) fn observe_main_node_block(&self, block: MiniblockNumber) {
+        let mut inner = self.inner.write().unwrap();
+        if inner.main_node_block.map_or(true, |known| known < block) {
+            inner.main_node_block = Some(block);
+            inner.update_sync_metric();
+        }
+    }
+
     pub(super) fn set_local_block(&self, block: MiniblockNumber) {
         let mut inner = self.inner.write().unwrap();
         if let Some(main_node_block) = inner.main_node_block {
@@ -180,6 +191,25 @@ mod tests {
         assert!(!sync_state.is_synced());
     }
 
+    #[test]
+    fn observing_main_node_block() {
+        let sync_state = SyncState::default();
+        sync_state.observe_main_node_block(MiniblockNumber(5));
+        assert_eq!(sync_state.get_main_node_block(), MiniblockNumber(5));
+        // Observing an older block doesn't move the main node block back.
+        sync_state.observe_main_node_block(MiniblockNumber(3));
+        assert_eq!(sync_state.get_main_node_block(), MiniblockNumber(5));
+        sync_state.observe_main_node_block(MiniblockNumber(7));
+        assert_eq!(sync_state.get_main_node_block(), MiniblockNumber(7));
+
+        // The main node has reverted blocks.
+        sync_state.set_local_block(MiniblockNumber(7));
+        sync_state.set_main_node_block(MiniblockNumber(4));
+        assert_eq!(sync_state.get_main_node_block(), MiniblockNumber(4));
+        sync_state.observe_main_node_block(MiniblockNumber(5));
+        assert_eq!(sync_state.get_main_node_block(), MiniblockNumber(5));
+    }
+
     #[test]
     fn test_sync_state_doesnt_panic_on_local_block() {
         let sync_state = SyncState::default();
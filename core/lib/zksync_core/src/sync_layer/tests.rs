@@ -3,10 +3,12 @@
 use std::{
     collections::{HashMap, VecDeque},
     iter,
+    num::NonZeroUsize,
     sync::Arc,
     time::{Duration, Instant},
 };
 
+use assert_matches::assert_matches;
 use test_casing::test_casing;
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_config::configs::chain::NetworkConfig;
@@ -17,7 +19,8 @@ use zksync_types::{
     block::MiniblockHasher,
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
     snapshots::SnapshotRecoveryStatus,
-    Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, PackedEthSignature, ProtocolVersionId,
+    Transaction, H256, U256,
 };
 
 use super::{sync_action::SyncAction, *};
@@ -29,7 +32,10 @@ use crate::{
         io::common::IoCursor, seal_criteria::NoopSealer, tests::TestBatchExecutorBuilder,
         MiniblockSealer, ZkSyncStateKeeper,
     },
-    sync_layer::{client::CachingMainNodeClient, fetcher::MainNodeFetcher},
+    sync_layer::{
+        client::{AdaptiveConcurrency, CachingMainNodeClient},
+        fetcher::MainNodeFetcher,
+    },
     utils::testonly::{create_l1_batch_metadata, create_l2_transaction, prepare_recovery_snapshot},
 };
 
@@ -64,6 +70,16 @@ impl StateKeeperHandles {
         main_node_client: MockMainNodeClient,
         actions: ActionQueue,
         tx_hashes: &[&[H256]],
+    ) -> Self {
+        Self::with_io_config(pool, main_node_client, actions, tx_hashes, |io| io).await
+    }
+
+    async fn with_io_config(
+        pool: ConnectionPool,
+        main_node_client: MockMainNodeClient,
+        actions: ActionQueue,
+        tx_hashes: &[&[H256]],
+        configure_io: impl FnOnce(ExternalIO) -> ExternalIO,
     ) -> Self {
         assert!(!tx_hashes.is_empty());
         assert!(tx_hashes.iter().all(|tx_hashes| !tx_hashes.is_empty()));
@@ -83,6 +99,7 @@ impl StateKeeperHandles {
         )
         .await
         .unwrap();
+        let io = configure_io(io);
 
         let (stop_sender, stop_receiver) = watch::channel(false);
         let mut batch_executor_base = TestBatchExecutorBuilder::default();
@@ -587,6 +604,130 @@ async fn external_io_with_multiple_l1_batches() {
     assert_eq!(fictive_miniblock.l2_tx_count, 0);
 }
 
+#[tokio::test]
+async fn external_io_with_async_tree() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis(&mut storage).await;
+    drop(storage);
+
+    let first_tx = create_l2_transaction(10, 100);
+    let first_tx_hash = first_tx.hash();
+    let first_l1_batch_actions = vec![
+        open_l1_batch(1, 1, 1),
+        SyncAction::Tx(Box::new(first_tx.into())),
+        SyncAction::SealMiniblock,
+        SyncAction::Miniblock {
+            number: MiniblockNumber(2),
+            timestamp: 2,
+            virtual_blocks: 0,
+        },
+        SyncAction::SealBatch { virtual_blocks: 0 },
+    ];
+    let second_tx = create_l2_transaction(10, 100);
+    let second_tx_hash = second_tx.hash();
+    let second_l1_batch_actions = vec![
+        open_l1_batch(2, 3, 3),
+        SyncAction::Tx(Box::new(second_tx.into())),
+        SyncAction::SealMiniblock,
+    ];
+
+    let mut client = MockMainNodeClient::default();
+    let main_node_hash = H256::repeat_byte(0x42);
+    client.insert_l1_batch_root_hash(L1BatchNumber(1), main_node_hash);
+    let (actions_sender, action_queue) = ActionQueue::new();
+    let state_keeper = StateKeeperHandles::with_io_config(
+        pool.clone(),
+        client,
+        action_queue,
+        &[&[first_tx_hash], &[second_tx_hash]],
+        |io| io.with_async_tree(1),
+    )
+    .await;
+    actions_sender.push_actions(first_l1_batch_actions).await;
+    actions_sender.push_actions(second_l1_batch_actions).await;
+    // The second L1 batch must be executed without the local tree processing the first one.
+    state_keeper
+        .wait(|state| state.get_local_block() == MiniblockNumber(3))
+        .await;
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let local_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(local_hash, None);
+    let last_l1_batch_with_metadata = storage
+        .blocks_dal()
+        .get_last_l1_batch_number_with_metadata()
+        .await
+        .unwrap();
+    assert_eq!(last_l1_batch_with_metadata, Some(L1BatchNumber(0)));
+}
+
+#[tokio::test]
+async fn external_io_without_tree() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis(&mut storage).await;
+    drop(storage);
+
+    let first_tx = create_l2_transaction(10, 100);
+    let first_tx_hash = first_tx.hash();
+    let first_l1_batch_actions = vec![
+        open_l1_batch(1, 1, 1),
+        SyncAction::Tx(Box::new(first_tx.into())),
+        SyncAction::SealMiniblock,
+        SyncAction::Miniblock {
+            number: MiniblockNumber(2),
+            timestamp: 2,
+            virtual_blocks: 0,
+        },
+        SyncAction::SealBatch { virtual_blocks: 0 },
+    ];
+    let second_tx = create_l2_transaction(10, 100);
+    let second_tx_hash = second_tx.hash();
+    let second_l1_batch_actions = vec![
+        open_l1_batch(2, 3, 3),
+        SyncAction::Tx(Box::new(second_tx.into())),
+        SyncAction::SealMiniblock,
+    ];
+
+    let mut client = MockMainNodeClient::default();
+    let main_node_hash = H256::repeat_byte(0x42);
+    client.insert_l1_batch_root_hash(L1BatchNumber(1), main_node_hash);
+    let (actions_sender, action_queue) = ActionQueue::new();
+    let state_keeper = StateKeeperHandles::with_io_config(
+        pool.clone(),
+        client,
+        action_queue,
+        &[&[first_tx_hash], &[second_tx_hash]],
+        ExternalIO::without_tree,
+    )
+    .await;
+    actions_sender.push_actions(first_l1_batch_actions).await;
+    actions_sender.push_actions(second_l1_batch_actions).await;
+    state_keeper
+        .wait(|state| state.get_local_block() == MiniblockNumber(3))
+        .await;
+
+    // Unlike with the async tree, the hash from the main node must be persisted.
+    let mut storage = pool.access_storage().await.unwrap();
+    let local_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(local_hash, Some(main_node_hash));
+    let tree_data = storage
+        .blocks_dal()
+        .get_l1_batch_tree_data(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert!(tree_data.is_none(), "{tree_data:?}");
+}
+
 #[tokio::test]
 async fn fetcher_basics() {
     let pool = ConnectionPool::test_pool().await;
@@ -665,6 +806,403 @@ async fn fetcher_basics() {
     fetcher_task.await.unwrap().unwrap();
 }
 
+#[test]
+fn adaptive_concurrency_adjustments() {
+    const TARGET_LATENCY: Duration = Duration::from_millis(100);
+
+    let max = NonZeroUsize::new(25).unwrap();
+    let mut concurrency = AdaptiveConcurrency::new(max, TARGET_LATENCY);
+    assert_eq!(concurrency.current(), 10);
+
+    // Concurrency is not increased if it's not a bottleneck.
+    concurrency.observe(Duration::from_millis(10), false, false);
+    assert_eq!(concurrency.current(), 10);
+    concurrency.observe(Duration::from_millis(10), false, true);
+    assert_eq!(concurrency.current(), 20);
+    concurrency.observe(Duration::from_millis(10), false, true);
+    assert_eq!(concurrency.current(), 25);
+    concurrency.observe(Duration::from_millis(10), false, true);
+    assert_eq!(concurrency.current(), 25);
+
+    concurrency.observe(Duration::from_millis(200), false, true);
+    assert_eq!(concurrency.current(), 12);
+    concurrency.observe(Duration::from_millis(10), true, true);
+    assert_eq!(concurrency.current(), 6);
+    for _ in 0..5 {
+        concurrency.observe(Duration::from_millis(200), false, true);
+    }
+    assert_eq!(concurrency.current(), 1);
+}
+
+#[tokio::test]
+async fn fetcher_with_many_miniblocks() {
+    const MINIBLOCK_COUNT: u32 = 100;
+
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis(&mut storage).await;
+
+    let mut mock_client = MockMainNodeClient::default();
+    mock_client.push_l1_batch(0);
+    let mut tx_hashes = VecDeque::from(mock_client.push_l1_batch(MINIBLOCK_COUNT));
+
+    let (actions_sender, mut actions) = ActionQueue::new();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let fetcher = MainNodeFetcher::new(
+        &mut storage,
+        Box::new(mock_client),
+        actions_sender,
+        SyncState::default(),
+        stop_receiver,
+    )
+    .await
+    .unwrap()
+    .with_concurrency(NonZeroUsize::new(16).unwrap(), TEST_TIMEOUT);
+    drop(storage);
+    let fetcher_task = tokio::spawn(fetcher.run());
+
+    // Check that miniblocks are applied in order despite being fetched concurrently.
+    let mut current_miniblock_number = MiniblockNumber(0);
+    let deadline = Instant::now() + TEST_TIMEOUT;
+    loop {
+        let action = tokio::time::timeout_at(deadline.into(), actions.recv_action())
+            .await
+            .unwrap();
+        match action {
+            SyncAction::OpenBatch {
+                first_miniblock_info,
+                ..
+            } => {
+                current_miniblock_number += 1;
+                assert_eq!(first_miniblock_info.0, current_miniblock_number);
+            }
+            SyncAction::Miniblock { number, .. } => {
+                current_miniblock_number += 1;
+                assert_eq!(number, current_miniblock_number);
+            }
+            SyncAction::Tx(tx) => {
+                assert_eq!(tx.hash(), tx_hashes.pop_front().unwrap());
+            }
+            SyncAction::SealMiniblock => {}
+            SyncAction::SealBatch { .. } => break,
+        }
+    }
+    assert_eq!(
+        current_miniblock_number,
+        MiniblockNumber(MINIBLOCK_COUNT + 1)
+    );
+    assert!(tx_hashes.is_empty());
+
+    stop_sender.send_replace(true);
+    fetcher_task.await.unwrap().unwrap();
+}
+
+#[test_casing(2, [QuorumPolicy::All, QuorumPolicy::Majority])]
+#[tokio::test]
+async fn quorum_client_with_matching_witnesses(policy: QuorumPolicy) {
+    let mut mock_client = MockMainNodeClient::default();
+    mock_client.push_l1_batch(0);
+    mock_client.push_l1_batch(2);
+    let witnesses: Vec<Box<dyn MainNodeClient>> =
+        vec![Box::new(mock_client.clone()), Box::new(mock_client.clone())];
+    let client = QuorumMainNodeClient::new(Box::new(mock_client), witnesses, policy);
+
+    assert_eq!(
+        client.fetch_l2_block_number().await.unwrap(),
+        MiniblockNumber(3)
+    );
+    for number in 0..=3 {
+        let block = client
+            .fetch_l2_block(MiniblockNumber(number), true)
+            .await
+            .unwrap()
+            .expect("no miniblock");
+        assert_eq!(block.number, MiniblockNumber(number));
+        assert!(block.transactions.is_some());
+    }
+    let missing_block = client.fetch_l2_block(MiniblockNumber(4), true).await;
+    assert!(missing_block.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn quorum_client_with_lagging_witness() {
+    let mut mock_client = MockMainNodeClient::default();
+    mock_client.push_l1_batch(0);
+    let lagging_witness = mock_client.clone();
+    mock_client.push_l1_batch(1);
+    let witnesses = || -> Vec<Box<dyn MainNodeClient>> {
+        vec![
+            Box::new(mock_client.clone()),
+            Box::new(lagging_witness.clone()),
+        ]
+    };
+
+    let client = QuorumMainNodeClient::new(
+        Box::new(mock_client.clone()),
+        witnesses(),
+        QuorumPolicy::All,
+    );
+    let block = client.fetch_l2_block(MiniblockNumber(0), true).await;
+    assert!(block.unwrap().is_some());
+    // The lagging witness doesn't have the miniblock yet, so the client should wait.
+    let block = client.fetch_l2_block(MiniblockNumber(1), true).await;
+    assert!(block.unwrap().is_none());
+
+    let client = QuorumMainNodeClient::new(
+        Box::new(mock_client.clone()),
+        witnesses(),
+        QuorumPolicy::Majority,
+    );
+    let block = client.fetch_l2_block(MiniblockNumber(1), true).await;
+    assert!(block.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn quorum_client_with_diverging_witness() {
+    let mut mock_client = MockMainNodeClient::default();
+    mock_client.push_l1_batch(0);
+    let mut diverging_witness = mock_client.clone();
+    mock_client.push_l1_batch(1);
+    // Generated transactions are random, so the miniblock hashes will differ.
+    diverging_witness.push_l1_batch(1);
+
+    let witnesses: Vec<Box<dyn MainNodeClient>> = vec![Box::new(diverging_witness.clone())];
+    let client =
+        QuorumMainNodeClient::new(Box::new(mock_client.clone()), witnesses, QuorumPolicy::All);
+    let block = client.fetch_l2_block(MiniblockNumber(0), true).await;
+    assert!(block.unwrap().is_some());
+    client
+        .fetch_l2_block(MiniblockNumber(1), true)
+        .await
+        .unwrap_err();
+
+    // The diverging witness is outvoted.
+    let witnesses: Vec<Box<dyn MainNodeClient>> =
+        vec![Box::new(mock_client.clone()), Box::new(diverging_witness)];
+    let client = QuorumMainNodeClient::new(
+        Box::new(mock_client.clone()),
+        witnesses,
+        QuorumPolicy::Majority,
+    );
+    let block = client.fetch_l2_block(MiniblockNumber(1), true).await;
+    assert!(block.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn quorum_client_with_witness_diverging_in_transactions() {
+    let mut mock_client = MockMainNodeClient::default();
+    mock_client.push_l1_batch(0);
+    mock_client.push_l1_batch(1);
+    // Tamper with transaction contents without changing the transaction hash and the miniblock header.
+    let mut diverging_witness = mock_client.clone();
+    let tx = diverging_witness.l2_blocks_mut()[1]
+        .transactions
+        .as_mut()
+        .unwrap()
+        .first_mut()
+        .unwrap();
+    tx.execute.calldata = vec![1, 2, 3];
+
+    let witnesses: Vec<Box<dyn MainNodeClient>> = vec![Box::new(diverging_witness)];
+    let client =
+        QuorumMainNodeClient::new(Box::new(mock_client.clone()), witnesses, QuorumPolicy::All);
+    client
+        .fetch_l2_block(MiniblockNumber(1), true)
+        .await
+        .unwrap_err();
+    // Miniblock headers still match.
+    let block = client.fetch_l2_block(MiniblockNumber(1), false).await;
+    assert!(block.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn cursor_rejects_miniblock_with_mismatched_hash() {
+    let mut mock_client = MockMainNodeClient::default();
+    mock_client.push_l1_batch(0);
+    mock_client.push_l1_batch(1);
+    let genesis_block = mock_client
+        .fetch_l2_block(MiniblockNumber(0), false)
+        .await
+        .unwrap()
+        .unwrap();
+    let block = mock_client
+        .fetch_l2_block(MiniblockNumber(1), true)
+        .await
+        .unwrap()
+        .unwrap();
+    let cursor = || IoCursor {
+        next_miniblock: MiniblockNumber(1),
+        prev_miniblock_hash: genesis_block.hash.unwrap(),
+        prev_miniblock_timestamp: genesis_block.timestamp,
+        l1_batch: L1BatchNumber(0),
+    };
+
+    let mut tampered_block = block.clone();
+    tampered_block.hash = Some(H256::repeat_byte(1));
+    let err = cursor()
+        .advance(tampered_block.try_into().unwrap())
+        .unwrap_err();
+    assert!(err.to_string().contains("Mismatch"), "{err}");
+
+    let actions = cursor().advance(block.try_into().unwrap()).unwrap();
+    assert_matches!(actions[0], SyncAction::OpenBatch { .. });
+}
+
+#[tokio::test]
+async fn signature_verifying_client() {
+    let chain_id = L2ChainId::default();
+    let signing_key = H256::repeat_byte(0x11);
+    let sequencer_address = PackedEthSignature::address_from_private_key(&signing_key).unwrap();
+    let mut mock_client = MockMainNodeClient::default();
+    mock_client.push_l1_batch(0);
+    mock_client.push_l1_batch(1);
+    let unsigned_client = mock_client.clone();
+    mock_client.sign_l2_blocks(&signing_key, chain_id);
+
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(mock_client.clone()),
+        sequencer_address,
+        chain_id,
+    );
+    for number in 0..=2 {
+        let block = client
+            .fetch_l2_block(MiniblockNumber(number), true)
+            .await
+            .unwrap()
+            .expect("no miniblock");
+        assert!(block.sequencer_signature.is_some());
+        // Headers are verified as well.
+        let block = client
+            .fetch_l2_block(MiniblockNumber(number), false)
+            .await
+            .unwrap()
+            .expect("no miniblock");
+        assert!(block.transactions.is_none());
+        assert!(block.sequencer_header_signature.is_some());
+    }
+    let missing_block = client.fetch_l2_block(MiniblockNumber(3), true).await;
+    assert!(missing_block.unwrap().is_none());
+
+    // Signatures are bound to the chain ID.
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(mock_client.clone()),
+        sequencer_address,
+        L2ChainId::from(1),
+    );
+    client
+        .fetch_l2_block(MiniblockNumber(1), true)
+        .await
+        .unwrap_err();
+
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(mock_client.clone()),
+        Address::repeat_byte(0x22),
+        chain_id,
+    );
+    client
+        .fetch_l2_block(MiniblockNumber(1), true)
+        .await
+        .unwrap_err();
+
+    // Substituting a transaction body while retaining its hash invalidates the signature.
+    let mut tampered_client = mock_client.clone();
+    tampered_client.update_l2_block(MiniblockNumber(1), |block| {
+        let tx = &mut block.transactions.as_mut().unwrap()[0];
+        let original_hash = tx.hash();
+        tx.execute.value += U256::one();
+        assert_eq!(tx.hash(), original_hash);
+    });
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(tampered_client),
+        sequencer_address,
+        chain_id,
+    );
+    client
+        .fetch_l2_block(MiniblockNumber(1), true)
+        .await
+        .unwrap_err();
+    // The header signature doesn't cover transactions, but covers the content hash.
+    client
+        .fetch_l2_block(MiniblockNumber(1), false)
+        .await
+        .unwrap()
+        .expect("no miniblock");
+    let mut tampered_client = mock_client;
+    tampered_client.set_content_hash(MiniblockNumber(1), H256::repeat_byte(0xcc));
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(tampered_client),
+        sequencer_address,
+        chain_id,
+    );
+    client
+        .fetch_l2_block(MiniblockNumber(1), false)
+        .await
+        .unwrap_err();
+
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(unsigned_client),
+        sequencer_address,
+        chain_id,
+    );
+    client
+        .fetch_l2_block(MiniblockNumber(1), true)
+        .await
+        .unwrap_err();
+    client
+        .fetch_l2_block(MiniblockNumber(1), false)
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn signature_verifying_client_for_l1_batch_root_hashes() {
+    let chain_id = L2ChainId::default();
+    let signing_key = H256::repeat_byte(0x11);
+    let sequencer_address = PackedEthSignature::address_from_private_key(&signing_key).unwrap();
+    let root_hash = H256::repeat_byte(0xaa);
+    let mut mock_client = MockMainNodeClient::default();
+    mock_client.insert_l1_batch_root_hash(L1BatchNumber(1), root_hash);
+    let unsigned_client = mock_client.clone();
+    mock_client.sign_l1_batch_root_hashes(&signing_key, chain_id);
+
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(mock_client.clone()),
+        sequencer_address,
+        chain_id,
+    );
+    let response = client
+        .fetch_l1_batch_root_hash(L1BatchNumber(1))
+        .await
+        .unwrap()
+        .expect("no root hash");
+    assert_eq!(response.root_hash, root_hash);
+    let missing_response = client.fetch_l1_batch_root_hash(L1BatchNumber(2)).await;
+    assert!(missing_response.unwrap().is_none());
+
+    // Substituting the root hash invalidates the signature.
+    let mut tampered_client = mock_client;
+    tampered_client.insert_l1_batch_root_hash(L1BatchNumber(1), H256::repeat_byte(0xbb));
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(tampered_client),
+        sequencer_address,
+        chain_id,
+    );
+    client
+        .fetch_l1_batch_root_hash(L1BatchNumber(1))
+        .await
+        .unwrap_err();
+
+    let client = SignatureVerifyingMainNodeClient::new(
+        Box::new(unsigned_client),
+        sequencer_address,
+        chain_id,
+    );
+    client
+        .fetch_l1_batch_root_hash(L1BatchNumber(1))
+        .await
+        .unwrap_err();
+}
+
 #[test_casing(2, [false, true])]
 #[tokio::test]
 async fn fetcher_with_real_server(snapshot_recovery: bool) {
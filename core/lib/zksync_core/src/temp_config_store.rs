@@ -10,8 +10,11 @@ use zksync_config::{
         FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig,
         ProofDataHandlerConfig, WitnessGeneratorConfig,
     },
-    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
-    GasAdjusterConfig, ObjectStoreConfig, PostgresConfig,
+    ApiConfig, AuditLogConfig, CdcPublisherConfig, ConfigReloadConfig, ContractsConfig, DBConfig,
+    ETHClientConfig, ETHSenderConfig, ETHWatchConfig, FeeDistributorConfig, FirehoseConfig,
+    GasAdjusterConfig, LeaderElectionConfig, MessageRelayConfig, ObjectStoreConfig, PostgresConfig,
+    ReexecutionWatchdogConfig, RosettaApiConfig, SharedSequencerConfig, StableGasPriceConfig,
+    SupervisorConfig, SupplyCheckerConfig, WebhooksConfig, WithdrawalFinalizerConfig,
 };
 
 use crate::consensus;
@@ -47,4 +50,19 @@ pub struct TempConfigStore {
     pub gas_adjuster_config: Option<GasAdjusterConfig>,
     pub object_store_config: Option<ObjectStoreConfig>,
     pub consensus_config: Option<consensus::MainNodeConfig>,
+    pub cdc_publisher_config: Option<CdcPublisherConfig>,
+    pub withdrawal_finalizer_config: Option<WithdrawalFinalizerConfig>,
+    pub message_relay_config: Option<MessageRelayConfig>,
+    pub fee_distributor_config: Option<FeeDistributorConfig>,
+    pub supply_checker_config: Option<SupplyCheckerConfig>,
+    pub reexecution_watchdog_config: Option<ReexecutionWatchdogConfig>,
+    pub stable_gas_price_config: Option<StableGasPriceConfig>,
+    pub webhooks_config: Option<WebhooksConfig>,
+    pub audit_log_config: Option<AuditLogConfig>,
+    pub leader_election_config: Option<LeaderElectionConfig>,
+    pub shared_sequencer_config: Option<SharedSequencerConfig>,
+    pub rosetta_api_config: Option<RosettaApiConfig>,
+    pub firehose_config: Option<FirehoseConfig>,
+    pub config_reload_config: Option<ConfigReloadConfig>,
+    pub supervisor_config: Option<SupervisorConfig>,
 }
@@ -0,0 +1,309 @@
+//! Startup validation of custom bridge and hyperchain contract addresses from the contracts config.
+
+use anyhow::Context as _;
+use zksync_config::{configs::chain::NetworkConfig, ContractsConfig};
+use zksync_dal::StorageProcessor;
+use zksync_eth_client::EthInterface;
+use zksync_types::{
+    ethabi::{self, ParamType, Token},
+    get_code_key,
+    web3::{
+        signing::keccak256,
+        types::{Bytes, CallRequest},
+    },
+    Address, H256, U256,
+};
+
+const COMPONENT: &str = "contracts_validation";
+
+/// Zero addresses are used as placeholders in the default config, so they are treated as not set.
+fn configured(address: Option<Address>) -> Option<Address> {
+    address.filter(|address| !address.is_zero())
+}
+
+/// Validates custom contract addresses against the settlement layer and the chain state:
+///
+/// - The settlement layer chain ID must match the configured one.
+/// - Contracts must be deployed at the configured addresses, with the expected code hashes if they are specified.
+/// - The bridgehub must point to the configured diamond proxy for the chain ID of the chain,
+///   and to the configured shared bridge.
+///
+/// Does nothing if no custom contracts are configured.
+pub(crate) async fn validate_contracts_config(
+    eth_client: &dyn EthInterface,
+    storage: &mut StorageProcessor<'_>,
+    contracts: &ContractsConfig,
+    network: &NetworkConfig,
+) -> anyhow::Result<()> {
+    let l1_shared_bridge = configured(contracts.l1_shared_bridge_proxy_addr);
+    let l2_shared_bridge = configured(contracts.l2_shared_bridge_addr);
+    let bridgehub = configured(contracts.bridgehub_proxy_addr);
+    if l1_shared_bridge.is_none() && l2_shared_bridge.is_none() && bridgehub.is_none() {
+        return Ok(());
+    }
+
+    let expected_l1_chain_id = network.network.chain_id();
+    let l1_chain_id = eth_client.fetch_chain_id(COMPONENT).await?;
+    anyhow::ensure!(
+        l1_chain_id == expected_l1_chain_id,
+        "settlement layer chain ID mismatch: configured {expected_l1_chain_id}, actual {l1_chain_id}"
+    );
+
+    if let Some(address) = l1_shared_bridge {
+        let expected_hash = contracts.l1_shared_bridge_code_hash;
+        check_l1_code(eth_client, "L1 shared bridge", address, expected_hash).await?;
+    }
+    if let Some(address) = l2_shared_bridge {
+        let expected_hash = contracts.l2_shared_bridge_code_hash;
+        check_l2_code(storage, "L2 shared bridge", address, expected_hash).await?;
+    }
+
+    if let Some(bridgehub) = bridgehub {
+        check_l1_code(eth_client, "bridgehub", bridgehub, None).await?;
+        let chain_id = network.zksync_network_id;
+        let hyperchain = call_address_getter(
+            eth_client,
+            bridgehub,
+            "getHyperchain",
+            Some(chain_id.as_u64().into()),
+        )
+        .await?;
+        anyhow::ensure!(
+            hyperchain == contracts.diamond_proxy_addr,
+            "bridgehub {bridgehub:?} points to hyperchain {hyperchain:?} for chain ID {}, \
+             while diamond proxy {:?} is configured",
+            chain_id.as_u64(),
+            contracts.diamond_proxy_addr
+        );
+        if let Some(l1_shared_bridge) = l1_shared_bridge {
+            let shared_bridge =
+                call_address_getter(eth_client, bridgehub, "sharedBridge", None).await?;
+            anyhow::ensure!(
+                shared_bridge == l1_shared_bridge,
+                "bridgehub {bridgehub:?} points to shared bridge {shared_bridge:?}, \
+                 while {l1_shared_bridge:?} is configured"
+            );
+        }
+    }
+    tracing::info!("Validated custom contract addresses against the settlement layer");
+    Ok(())
+}
+
+async fn check_l1_code(
+    eth_client: &dyn EthInterface,
+    name: &str,
+    address: Address,
+    expected_hash: Option<H256>,
+) -> anyhow::Result<()> {
+    let code = eth_client
+        .get_code(address, COMPONENT)
+        .await
+        .with_context(|| format!("failed getting code of {name} {address:?}"))?;
+    anyhow::ensure!(
+        !code.0.is_empty(),
+        "{name} {address:?} is not deployed on the settlement layer"
+    );
+    if let Some(expected_hash) = expected_hash {
+        let code_hash = H256(keccak256(&code.0));
+        anyhow::ensure!(
+            code_hash == expected_hash,
+            "code hash of {name} {address:?} ({code_hash:?}) differs from the expected one ({expected_hash:?})"
+        );
+    }
+    Ok(())
+}
+
+async fn check_l2_code(
+    storage: &mut StorageProcessor<'_>,
+    name: &str,
+    address: Address,
+    expected_hash: Option<H256>,
+) -> anyhow::Result<()> {
+    let bytecode_hash = storage
+        .storage_web3_dal()
+        .get_value(&get_code_key(&address))
+        .await?;
+    anyhow::ensure!(
+        !bytecode_hash.is_zero(),
+        "{name} {address:?} is not deployed on the chain"
+    );
+    if let Some(expected_hash) = expected_hash {
+        anyhow::ensure!(
+            bytecode_hash == expected_hash,
+            "bytecode hash of {name} {address:?} ({bytecode_hash:?}) differs from the expected one ({expected_hash:?})"
+        );
+    }
+    Ok(())
+}
+
+fn encode_getter_call(name: &str, arg: Option<U256>) -> Vec<u8> {
+    match arg {
+        Some(arg) => {
+            let mut data = ethabi::short_signature(name, &[ParamType::Uint(256)]).to_vec();
+            data.extend(ethabi::encode(&[Token::Uint(arg)]));
+            data
+        }
+        None => ethabi::short_signature(name, &[]).to_vec(),
+    }
+}
+
+/// Calls a getter with an optional `uint256` argument returning an address.
+async fn call_address_getter(
+    eth_client: &dyn EthInterface,
+    contract: Address,
+    name: &str,
+    arg: Option<U256>,
+) -> anyhow::Result<Address> {
+    let data = encode_getter_call(name, arg);
+    let request = CallRequest {
+        to: Some(contract),
+        data: Some(Bytes(data)),
+        ..CallRequest::default()
+    };
+    let output = eth_client
+        .call(request, None, COMPONENT)
+        .await
+        .with_context(|| format!("failed calling `{name}` on {contract:?}"))?;
+    let tokens = ethabi::decode(&[ParamType::Address], &output.0)
+        .with_context(|| format!("failed decoding `{name}` output"))?;
+    tokens
+        .into_iter()
+        .next()
+        .and_then(Token::into_address)
+        .with_context(|| format!("unexpected `{name}` output"))
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::ConnectionPool;
+    use zksync_eth_client::clients::MockEthereum;
+    use zksync_types::{L1ChainId, MiniblockNumber, StorageLog};
+
+    use super::*;
+
+    const L1_SHARED_BRIDGE: Address = Address::repeat_byte(0x21);
+    const L2_SHARED_BRIDGE: Address = Address::repeat_byte(0x22);
+    const BRIDGEHUB: Address = Address::repeat_byte(0x23);
+
+    fn contracts_config() -> ContractsConfig {
+        ContractsConfig {
+            l1_shared_bridge_proxy_addr: Some(L1_SHARED_BRIDGE),
+            l2_shared_bridge_addr: Some(L2_SHARED_BRIDGE),
+            bridgehub_proxy_addr: Some(BRIDGEHUB),
+            ..ContractsConfig::for_tests()
+        }
+    }
+
+    fn mock_settlement_layer(contracts: &ContractsConfig, network: &NetworkConfig) -> MockEthereum {
+        let client = MockEthereum::default().with_network_chain_id(network.network.chain_id());
+        client.set_code(L1_SHARED_BRIDGE, vec![1, 2, 3]);
+        client.set_code(BRIDGEHUB, vec![4, 5, 6]);
+        let chain_id = network.zksync_network_id.as_u64().into();
+        client.set_call_response(
+            BRIDGEHUB,
+            encode_getter_call("getHyperchain", Some(chain_id)),
+            ethabi::encode(&[Token::Address(contracts.diamond_proxy_addr)]),
+        );
+        client.set_call_response(
+            BRIDGEHUB,
+            encode_getter_call("sharedBridge", None),
+            ethabi::encode(&[Token::Address(L1_SHARED_BRIDGE)]),
+        );
+        client
+    }
+
+    async fn deploy_l2_contract(storage: &mut StorageProcessor<'_>, bytecode_hash: H256) {
+        let log = StorageLog::new_write_log(get_code_key(&L2_SHARED_BRIDGE), bytecode_hash);
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(0), &[(H256::zero(), vec![log])])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn validating_contracts_config() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let network = NetworkConfig::for_tests();
+        let mut contracts = contracts_config();
+        let client = mock_settlement_layer(&contracts, &network);
+
+        let err = validate_contracts_config(&client, &mut storage, &contracts, &network)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("is not deployed on the chain"),
+            "{err}"
+        );
+
+        let bytecode_hash = H256::repeat_byte(0x01);
+        deploy_l2_contract(&mut storage, bytecode_hash).await;
+        validate_contracts_config(&client, &mut storage, &contracts, &network)
+            .await
+            .unwrap();
+
+        contracts.l1_shared_bridge_code_hash = Some(H256(keccak256(&[1, 2, 3])));
+        contracts.l2_shared_bridge_code_hash = Some(bytecode_hash);
+        validate_contracts_config(&client, &mut storage, &contracts, &network)
+            .await
+            .unwrap();
+
+        contracts.l1_shared_bridge_code_hash = Some(H256::repeat_byte(0xff));
+        let err = validate_contracts_config(&client, &mut storage, &contracts, &network)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("differs from the expected one"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn validating_invalid_contracts_config() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let network = NetworkConfig::for_tests();
+        let contracts = contracts_config();
+        let client =
+            mock_settlement_layer(&contracts, &network).with_network_chain_id(L1ChainId(1));
+
+        let err = validate_contracts_config(&client, &mut storage, &contracts, &network)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("chain ID mismatch"), "{err}");
+
+        // The bridgehub points to another hyperchain.
+        let contracts = ContractsConfig {
+            diamond_proxy_addr: Address::repeat_byte(0xff),
+            ..contracts
+        };
+        let client = mock_settlement_layer(&contracts_config(), &network);
+        deploy_l2_contract(&mut storage, H256::repeat_byte(0x01)).await;
+        let err = validate_contracts_config(&client, &mut storage, &contracts, &network)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("points to hyperchain"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn placeholder_addresses_are_not_validated() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let contracts = ContractsConfig {
+            l1_shared_bridge_proxy_addr: None,
+            l2_shared_bridge_addr: None,
+            bridgehub_proxy_addr: Some(Address::zero()),
+            ..ContractsConfig::for_tests()
+        };
+        let client = MockEthereum::default().with_network_chain_id(L1ChainId(1));
+        validate_contracts_config(
+            &client,
+            &mut storage,
+            &contracts,
+            &NetworkConfig::for_tests(),
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+//! Loading L2-to-L1 messages sent via the L1 messenger together with their inclusion proofs.
+
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::Context as _;
+use zksync_dal::StorageProcessor;
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_types::{
+    api::GetLogsFilter,
+    ethabi::{self, ParamType},
+    event::L1_MESSAGE_EVENT_SIGNATURE,
+    l2_to_l1_log::L2ToL1Log,
+    Address, L1BatchNumber, H256, L1_MESSENGER_ADDRESS,
+};
+use zksync_utils::{address_to_h256, h256_to_account_address};
+
+/// L2-to-L1 message included into an L1 batch, together with the data necessary to prove its inclusion
+/// on the settlement layer.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ProvenMessage {
+    /// Index of the L2-to-L1 log with the message in the L1 batch.
+    pub l2_message_index: u32,
+    pub tx_number_in_batch: u16,
+    /// Hash of the transaction that has sent the message.
+    pub tx_hash: H256,
+    pub sender: Address,
+    pub data: Vec<u8>,
+    pub merkle_proof: Vec<H256>,
+}
+
+/// Loads message contents from `L1MessageSent` events, keyed by the message hash. Each message is accompanied
+/// by the hash of the sending transaction. Messages with the same hash are ordered in the same way
+/// as the corresponding L2-to-L1 logs.
+async fn load_message_contents(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+    senders: &[Address],
+) -> anyhow::Result<HashMap<H256, VecDeque<(H256, Vec<u8>)>>> {
+    let (from_miniblock, to_miniblock) = storage
+        .blocks_web3_dal()
+        .get_miniblock_range_of_l1_batch(l1_batch_number)
+        .await?
+        .context("L1 batch has no miniblocks")?;
+    let filter = GetLogsFilter {
+        from_block: from_miniblock,
+        to_block: to_miniblock,
+        addresses: vec![L1_MESSENGER_ADDRESS],
+        topics: vec![
+            (1, vec![*L1_MESSAGE_EVENT_SIGNATURE]),
+            (2, senders.iter().map(address_to_h256).collect()),
+        ],
+    };
+    let events = storage
+        .events_web3_dal()
+        .get_logs(filter, i32::MAX as usize)
+        .await?;
+
+    let mut messages = HashMap::<_, VecDeque<_>>::new();
+    for event in events {
+        let (Some(&message_hash), Some(tx_hash)) = (event.topics.get(2), event.transaction_hash)
+        else {
+            continue;
+        };
+        let tokens = ethabi::decode(&[ParamType::Bytes], &event.data.0)
+            .context("failed decoding L1MessageSent event")?;
+        let message = tokens
+            .into_iter()
+            .next()
+            .and_then(ethabi::Token::into_bytes)
+            .context("unexpected L1MessageSent event data")?;
+        messages
+            .entry(message_hash)
+            .or_default()
+            .push_back((tx_hash, message));
+    }
+    Ok(messages)
+}
+
+/// Loads messages sent in the specified L1 batch by any of `senders`, ordered by their index in the L1 batch.
+pub(crate) async fn load_proven_messages(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+    senders: &[Address],
+) -> anyhow::Result<Vec<ProvenMessage>> {
+    let logs = storage
+        .blocks_web3_dal()
+        .get_l2_to_l1_logs(l1_batch_number)
+        .await?;
+    let is_matching_log = |log: &L2ToL1Log| {
+        log.sender == L1_MESSENGER_ADDRESS && senders.contains(&h256_to_account_address(&log.key))
+    };
+    if !logs.iter().any(is_matching_log) {
+        return Ok(vec![]);
+    }
+
+    let header = storage
+        .blocks_dal()
+        .get_l1_batch_header(l1_batch_number)
+        .await?
+        .context("L1 batch header is missing")?;
+    let min_tree_size = if header
+        .protocol_version
+        .map(|v| v.is_pre_boojum())
+        .unwrap_or(true)
+    {
+        Some(L2ToL1Log::PRE_BOOJUM_MIN_L2_L1_LOGS_TREE_SIZE)
+    } else {
+        Some(L2ToL1Log::MIN_L2_L1_LOGS_TREE_SIZE)
+    };
+    let merkle_tree_leaves: Vec<_> = logs.iter().map(L2ToL1Log::to_bytes).collect();
+    let mut contents = load_message_contents(storage, l1_batch_number, senders).await?;
+
+    let mut messages = vec![];
+    for (index, log) in logs.iter().enumerate() {
+        if !is_matching_log(log) {
+            continue;
+        }
+        let (tx_hash, data) = contents
+            .get_mut(&log.value)
+            .and_then(VecDeque::pop_front)
+            .with_context(|| format!("message for L2-to-L1 log #{index} is missing"))?;
+        let (_, merkle_proof) =
+            MiniMerkleTree::new(merkle_tree_leaves.iter().cloned(), min_tree_size)
+                .merkle_root_and_path(index);
+        messages.push(ProvenMessage {
+            l2_message_index: index as u32,
+            tx_number_in_batch: log.tx_number_in_block,
+            tx_hash,
+            sender: h256_to_account_address(&log.key),
+            data,
+            merkle_proof,
+        });
+    }
+    Ok(messages)
+}
@@ -9,9 +9,20 @@ use std::{
 use anyhow::Context as _;
 use async_trait::async_trait;
 use tokio::sync::watch;
+use zksync_config::configs::database::{RocksdbCompactionStyle, RocksdbProfile};
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_types::{L1BatchNumber, ProtocolVersionId};
+use zksync_storage::{RocksDBCompactionStyle, RocksDBOptions};
+use zksync_system_constants::ZKPORTER_IS_AVAILABLE;
+use zksync_types::{
+    commitment::{
+        AuxCommitments, L1BatchCommitmentArtifacts, L1BatchCommitmentHash, L1BatchMetadata,
+    },
+    L1BatchNumber, ProtocolVersionId,
+};
 
+pub(crate) mod contracts_validation;
+pub(crate) mod l2_to_l1_messages;
+pub(crate) mod settlement_txs;
 #[cfg(test)]
 pub(crate) mod testonly;
 
@@ -163,6 +174,52 @@ pub(crate) async fn pending_protocol_version(
     Ok(snapshot_recovery.protocol_version)
 }
 
+/// Converts a RocksDB tuning profile to RocksDB options. Options not covered by the profile are set
+/// to their default values.
+pub(crate) fn rocksdb_options(profile: &RocksdbProfile) -> RocksDBOptions {
+    let compaction_style = match profile.compaction_style {
+        RocksdbCompactionStyle::Level => RocksDBCompactionStyle::Level,
+        RocksdbCompactionStyle::Universal => RocksDBCompactionStyle::Universal,
+    };
+    RocksDBOptions {
+        compaction_style,
+        max_total_wal_size: profile.max_total_wal_size(),
+        wal_bytes_per_sync: profile.wal_bytes_per_sync(),
+        ..RocksDBOptions::default()
+    }
+}
+
+/// Converts L1 batch metadata to commitment artifacts persisted by the commitment generator.
+pub(crate) fn l1_batch_metadata_to_commitment_artifacts(
+    metadata: &L1BatchMetadata,
+) -> L1BatchCommitmentArtifacts {
+    L1BatchCommitmentArtifacts {
+        commitment_hash: L1BatchCommitmentHash {
+            pass_through_data: metadata.pass_through_data_hash,
+            aux_output: metadata.aux_data_hash,
+            meta_parameters: metadata.meta_parameters_hash,
+            commitment: metadata.commitment,
+        },
+        l2_l1_merkle_root: metadata.l2_l1_merkle_root,
+        compressed_state_diffs: Some(metadata.state_diffs_compressed.clone()),
+        compressed_initial_writes: metadata.initial_writes_compressed.clone(),
+        compressed_repeated_writes: metadata.repeated_writes_compressed.clone(),
+        zkporter_is_available: ZKPORTER_IS_AVAILABLE,
+        aux_commitments: match (
+            metadata.bootloader_initial_content_commitment,
+            metadata.events_queue_commitment,
+        ) {
+            (Some(bootloader_initial_content_commitment), Some(events_queue_commitment)) => {
+                Some(AuxCommitments {
+                    bootloader_initial_content_commitment,
+                    events_queue_commitment,
+                })
+            }
+            _ => None,
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_types::L2ChainId;
@@ -0,0 +1,304 @@
+//! Sending settlement layer transactions from auxiliary components (the withdrawal finalizer and the message relay).
+//!
+//! A transaction is persisted in Postgres before it's sent, so that a crash between sending the transaction and
+//! recording it cannot lead to sending a duplicate. While the transaction is not mined, it's rebroadcast on each
+//! check (which also covers transactions that weren't sent before a restart, or were dropped from the mempool).
+//! If the transaction isn't mined in time, it's replaced with a transaction with the same nonce and bumped fees.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::Utc;
+use zksync_dal::{
+    settlement_txs_dal::{SettlementTx, SignedSettlementTx},
+    StorageProcessor,
+};
+use zksync_eth_client::{BoundEthInterface, EthInterface, RawTransactionBytes};
+use zksync_types::{web3::contract::Options, Address, H256, U256};
+
+/// Minimum fee increase for a replacement transaction. Nodes require at least 10% to accept a replacement.
+const FEE_BUMP_PERCENT: u64 = 20;
+
+/// Settlement layer transaction to be signed and sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettlementTxRequest {
+    pub contract_address: Address,
+    pub calldata: Vec<u8>,
+    pub gas_limit: u64,
+}
+
+/// Fees of an EIP-1559 transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TxFees {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+/// Status of a mined settlement layer transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinedTxStatus {
+    pub success: bool,
+    pub block_number: u64,
+}
+
+/// Settlement layer operations necessary to send transactions.
+#[async_trait]
+pub trait SettlementTxClient: fmt::Debug + Send + Sync {
+    /// Returns the nonce of the next transaction from the sender account, not accounting for pending transactions.
+    async fn mined_nonce(&self) -> anyhow::Result<u64>;
+
+    /// Returns the base fee per gas of the pending block.
+    async fn base_fee_per_gas(&self) -> anyhow::Result<U256>;
+
+    /// Signs a transaction. If `fees` are not specified, the default fees for the current base fee are used.
+    async fn sign_tx(
+        &self,
+        request: &SettlementTxRequest,
+        nonce: u64,
+        fees: Option<TxFees>,
+    ) -> anyhow::Result<SignedSettlementTx>;
+
+    async fn send_raw_tx(&self, raw_tx: &[u8]) -> anyhow::Result<()>;
+
+    /// Returns the status of a transaction, or `None` if it's not mined yet.
+    async fn tx_status(&self, tx_hash: H256) -> anyhow::Result<Option<MinedTxStatus>>;
+}
+
+#[async_trait]
+impl SettlementTxClient for Arc<dyn BoundEthInterface> {
+    async fn mined_nonce(&self) -> anyhow::Result<u64> {
+        Ok(self
+            .current_nonce(SettlementTxManager::COMPONENT)
+            .await?
+            .as_u64())
+    }
+
+    async fn base_fee_per_gas(&self) -> anyhow::Result<U256> {
+        let component = SettlementTxManager::COMPONENT;
+        Ok(self.get_pending_block_base_fee_per_gas(component).await?)
+    }
+
+    async fn sign_tx(
+        &self,
+        request: &SettlementTxRequest,
+        nonce: u64,
+        fees: Option<TxFees>,
+    ) -> anyhow::Result<SignedSettlementTx> {
+        let options = Options {
+            nonce: Some(nonce.into()),
+            gas: Some(request.gas_limit.into()),
+            max_fee_per_gas: fees.map(|fees| fees.max_fee_per_gas),
+            max_priority_fee_per_gas: fees.map(|fees| fees.max_priority_fee_per_gas),
+            ..Options::default()
+        };
+        let signed_tx = self
+            .sign_prepared_tx_for_addr(
+                request.calldata.clone(),
+                request.contract_address,
+                options,
+                SettlementTxManager::COMPONENT,
+            )
+            .await?;
+        Ok(SignedSettlementTx {
+            tx_hash: signed_tx.hash,
+            raw_tx: signed_tx.raw_tx.as_ref().to_vec(),
+            max_fee_per_gas: signed_tx.max_fee_per_gas,
+            max_priority_fee_per_gas: signed_tx.max_priority_fee_per_gas,
+        })
+    }
+
+    async fn send_raw_tx(&self, raw_tx: &[u8]) -> anyhow::Result<()> {
+        let raw_tx = RawTransactionBytes::new_unchecked(raw_tx.to_vec());
+        EthInterface::send_raw_tx(self.as_ref(), raw_tx).await?;
+        Ok(())
+    }
+
+    async fn tx_status(&self, tx_hash: H256) -> anyhow::Result<Option<MinedTxStatus>> {
+        let status = self
+            .get_tx_status(tx_hash, SettlementTxManager::COMPONENT)
+            .await?;
+        let Some(status) = status else {
+            return Ok(None);
+        };
+        let block_number = status
+            .receipt
+            .block_number
+            .context("receipt of a mined transaction doesn't have a block number")?;
+        Ok(Some(MinedTxStatus {
+            success: status.success,
+            block_number: block_number.as_u64(),
+        }))
+    }
+}
+
+/// Transaction signed, but not yet persisted or sent.
+#[derive(Debug)]
+pub struct NewSettlementTx {
+    tx: SettlementTx,
+    signed_tx: SignedSettlementTx,
+}
+
+impl NewSettlementTx {
+    pub fn tx_hash(&self) -> H256 {
+        self.signed_tx.tx_hash
+    }
+}
+
+/// Outcome of a persisted transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettlementTxOutcome {
+    /// One of the versions of the transaction is mined.
+    Mined {
+        tx_hash: H256,
+        status: MinedTxStatus,
+    },
+    /// The transaction nonce was used by a transaction not tracked by the component (e.g., sent manually).
+    /// The effects of the transaction must be checked on the settlement layer.
+    NonceUsed,
+}
+
+/// Persists, sends and tracks settlement layer transactions of a component. See the module-level docs for details.
+#[derive(Debug)]
+pub struct SettlementTxManager {
+    component: &'static str,
+    resend_interval: Duration,
+}
+
+impl SettlementTxManager {
+    const COMPONENT: &'static str = "settlement_tx_manager";
+
+    pub fn new(component: &'static str, resend_interval: Duration) -> Self {
+        Self {
+            component,
+            resend_interval,
+        }
+    }
+
+    /// Signs a new transaction. The transaction must be persisted with [`Self::save_tx()`] before it's sent.
+    /// There must be no other pending transactions from the sender account.
+    pub async fn sign_new_tx(
+        &self,
+        client: &dyn SettlementTxClient,
+        request: SettlementTxRequest,
+    ) -> anyhow::Result<NewSettlementTx> {
+        let nonce = client.mined_nonce().await?;
+        let signed_tx = client.sign_tx(&request, nonce, None).await?;
+        let tx = SettlementTx {
+            id: 0,
+            nonce,
+            contract_address: request.contract_address,
+            calldata: request.calldata,
+            gas_limit: request.gas_limit,
+        };
+        Ok(NewSettlementTx { tx, signed_tx })
+    }
+
+    /// Persists a new transaction. Returns the ID of the transaction. This should be performed in the same
+    /// DB transaction as recording the effects of the transaction.
+    pub async fn save_tx(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx: &NewSettlementTx,
+    ) -> anyhow::Result<u32> {
+        let tx_id = storage
+            .settlement_txs_dal()
+            .insert_tx(self.component, &tx.tx, &tx.signed_tx)
+            .await?;
+        Ok(tx_id)
+    }
+
+    /// Sends a persisted transaction. Errors are logged rather than returned, since the transaction
+    /// is rebroadcast by [`Self::check_tx()`].
+    pub async fn send_tx(&self, client: &dyn SettlementTxClient, tx: &NewSettlementTx) {
+        self.broadcast(client, &tx.signed_tx).await;
+    }
+
+    async fn broadcast(&self, client: &dyn SettlementTxClient, signed_tx: &SignedSettlementTx) {
+        if let Err(err) = client.send_raw_tx(&signed_tx.raw_tx).await {
+            // The error may be benign, e.g. if the transaction is already in the mempool.
+            tracing::debug!(
+                "Failed broadcasting {} transaction {:?}: {err:#}",
+                self.component,
+                signed_tx.tx_hash
+            );
+        }
+    }
+
+    /// Checks the status of a persisted transaction. Returns `None` if the transaction is not mined yet;
+    /// in this case, the transaction is rebroadcast, or replaced with bumped fees if it's not mined in time.
+    pub async fn check_tx(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        client: &dyn SettlementTxClient,
+        tx_id: u32,
+    ) -> anyhow::Result<Option<SettlementTxOutcome>> {
+        let mut dal = storage.settlement_txs_dal();
+        let tx = dal
+            .get_tx(tx_id)
+            .await?
+            .with_context(|| format!("transaction #{tx_id} is not persisted"))?;
+        let attempts = dal.get_attempts(tx_id).await?;
+        // Newer versions are more likely to be mined, so they are checked first.
+        for attempt in attempts.iter().rev() {
+            let tx_hash = attempt.tx.tx_hash;
+            if let Some(status) = client.tx_status(tx_hash).await? {
+                return Ok(Some(SettlementTxOutcome::Mined { tx_hash, status }));
+            }
+        }
+        if client.mined_nonce().await? > tx.nonce {
+            tracing::warn!(
+                "Nonce {} of {} transaction #{tx_id} was used by an unknown transaction",
+                tx.nonce,
+                self.component
+            );
+            return Ok(Some(SettlementTxOutcome::NonceUsed));
+        }
+
+        let last_attempt = attempts
+            .last()
+            .with_context(|| format!("transaction #{tx_id} has no signed versions"))?;
+        let age = (Utc::now().naive_utc() - last_attempt.created_at)
+            .to_std()
+            .unwrap_or_default();
+        if age < self.resend_interval {
+            self.broadcast(client, &last_attempt.tx).await;
+            return Ok(None);
+        }
+
+        let fees = Self::bumped_fees(client, &last_attempt.tx).await?;
+        let request = SettlementTxRequest {
+            contract_address: tx.contract_address,
+            calldata: tx.calldata,
+            gas_limit: tx.gas_limit,
+        };
+        let signed_tx = client.sign_tx(&request, tx.nonce, Some(fees)).await?;
+        storage
+            .settlement_txs_dal()
+            .insert_attempt(tx_id, &signed_tx)
+            .await?;
+        tracing::info!(
+            "Replacing {} transaction {:?} not mined for {age:?} with {:?} ({fees:?})",
+            self.component,
+            last_attempt.tx.tx_hash,
+            signed_tx.tx_hash
+        );
+        self.broadcast(client, &signed_tx).await;
+        Ok(None)
+    }
+
+    async fn bumped_fees(
+        client: &dyn SettlementTxClient,
+        prev_tx: &SignedSettlementTx,
+    ) -> anyhow::Result<TxFees> {
+        let bump = |fee: U256| fee + fee * FEE_BUMP_PERCENT / 100 + 1;
+        let max_priority_fee_per_gas = bump(prev_tx.max_priority_fee_per_gas);
+        let base_fee_per_gas = client.base_fee_per_gas().await?;
+        let max_fee_per_gas =
+            bump(prev_tx.max_fee_per_gas).max(base_fee_per_gas * 2 + max_priority_fee_per_gas);
+        Ok(TxFees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+}
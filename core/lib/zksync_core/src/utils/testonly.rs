@@ -2,27 +2,35 @@
 
 use std::collections::HashMap;
 
+use chrono::Utc;
 use multivm::utils::get_max_gas_per_pubdata_byte;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::StorageProcessor;
 use zksync_merkle_tree::{domain::ZkSyncTree, TreeInstruction};
 use zksync_system_constants::ZKPORTER_IS_AVAILABLE;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     block::{L1BatchHeader, MiniblockHeader},
-    commitment::{
-        AuxCommitments, L1BatchCommitmentArtifacts, L1BatchCommitmentHash, L1BatchMetaParameters,
-        L1BatchMetadata,
-    },
+    commitment::{L1BatchMetaParameters, L1BatchMetadata},
+    ethabi,
+    event::L1_MESSAGE_EVENT_SIGNATURE,
     fee::Fee,
     fee_model::{BatchFeeInput, FeeParams},
     l2::L2Tx,
+    l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
     snapshots::SnapshotRecoveryStatus,
     transaction_request::PaymasterParams,
-    tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
+    tx::{
+        tx_execution_info::TxExecutionStatus, ExecutionMetrics, IncludedTxLocation,
+        TransactionExecutionResult,
+    },
+    web3::signing::keccak256,
     Address, L1BatchNumber, L2ChainId, MiniblockNumber, Nonce, ProtocolVersion, ProtocolVersionId,
-    StorageLog, H256, U256,
+    StorageLog, VmEvent, H256, L1_MESSENGER_ADDRESS, U256,
 };
+use zksync_utils::address_to_h256;
 
+pub(crate) use super::l1_batch_metadata_to_commitment_artifacts;
 use crate::{fee_model::BatchFeeModelInputProvider, genesis::GenesisParams};
 
 /// Creates a miniblock header with the specified number and deterministic contents.
@@ -77,36 +85,6 @@ pub(crate) fn create_l1_batch_metadata(number: u32) -> L1BatchMetadata {
     }
 }
 
-pub(crate) fn l1_batch_metadata_to_commitment_artifacts(
-    metadata: &L1BatchMetadata,
-) -> L1BatchCommitmentArtifacts {
-    L1BatchCommitmentArtifacts {
-        commitment_hash: L1BatchCommitmentHash {
-            pass_through_data: metadata.pass_through_data_hash,
-            aux_output: metadata.aux_data_hash,
-            meta_parameters: metadata.meta_parameters_hash,
-            commitment: metadata.commitment,
-        },
-        l2_l1_merkle_root: metadata.l2_l1_merkle_root,
-        compressed_state_diffs: Some(metadata.state_diffs_compressed.clone()),
-        compressed_initial_writes: metadata.initial_writes_compressed.clone(),
-        compressed_repeated_writes: metadata.repeated_writes_compressed.clone(),
-        zkporter_is_available: ZKPORTER_IS_AVAILABLE,
-        aux_commitments: match (
-            metadata.bootloader_initial_content_commitment,
-            metadata.events_queue_commitment,
-        ) {
-            (Some(bootloader_initial_content_commitment), Some(events_queue_commitment)) => {
-                Some(AuxCommitments {
-                    bootloader_initial_content_commitment,
-                    events_queue_commitment,
-                })
-            }
-            _ => None,
-        },
-    }
-}
-
 /// Creates an L2 transaction with randomized parameters.
 pub(crate) fn create_l2_transaction(fee_per_gas: u64, gas_per_pubdata: u64) -> L2Tx {
     let fee = Fee {
@@ -145,6 +123,7 @@ pub(crate) fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionR
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        executed_at: None,
     }
 }
 
@@ -252,3 +231,101 @@ impl BatchFeeModelInputProvider for MockBatchFeeParamsProvider {
         self.0
     }
 }
+
+/// Stores an executed L1 batch with a single miniblock, in which each transaction sends the specified messages
+/// via the L1 messenger. Returns hashes of the stored transactions.
+pub(crate) async fn store_executed_l1_batch_with_messages(
+    storage: &mut StorageProcessor<'_>,
+    number: u32,
+    messages_by_tx: &[Vec<(Address, Vec<u8>)>],
+) -> Vec<H256> {
+    let transaction_results: Vec<_> = messages_by_tx
+        .iter()
+        .map(|_| execute_l2_transaction(create_l2_transaction(10, 100)))
+        .collect();
+    for result in &transaction_results {
+        let l2_tx = result.transaction.clone().try_into().unwrap();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(l2_tx, Default::default())
+            .await;
+    }
+    let miniblock_number = MiniblockNumber(number);
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(miniblock_number, &transaction_results, 1.into())
+        .await;
+
+    let mut l2_to_l1_logs = vec![];
+    let mut events_by_tx = vec![];
+    for (i, (result, messages)) in transaction_results.iter().zip(messages_by_tx).enumerate() {
+        let mut events = vec![];
+        for (sender, message) in messages {
+            let message_hash = H256(keccak256(message));
+            l2_to_l1_logs.push(UserL2ToL1Log(L2ToL1Log {
+                tx_number_in_block: i as u16,
+                sender: L1_MESSENGER_ADDRESS,
+                key: address_to_h256(sender),
+                value: message_hash,
+                ..L2ToL1Log::default()
+            }));
+            events.push(VmEvent {
+                location: (L1BatchNumber(number), i as u32),
+                address: L1_MESSENGER_ADDRESS,
+                indexed_topics: vec![
+                    *L1_MESSAGE_EVENT_SIGNATURE,
+                    address_to_h256(sender),
+                    message_hash,
+                ],
+                value: ethabi::encode(&[ethabi::Token::Bytes(message.clone())]),
+            });
+        }
+        let location = IncludedTxLocation {
+            tx_hash: result.hash,
+            tx_index_in_miniblock: i as u32,
+            tx_initiator_address: result.transaction.initiator_account(),
+        };
+        events_by_tx.push((location, events));
+    }
+    let events_by_tx: Vec<_> = events_by_tx
+        .iter()
+        .map(|(location, events)| (*location, events.iter().collect()))
+        .collect();
+    storage
+        .events_dal()
+        .save_events(miniblock_number, &events_by_tx)
+        .await;
+
+    let mut header = create_l1_batch(number);
+    header.l2_to_l1_logs = l2_to_l1_logs;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&header)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+        .await
+        .unwrap();
+    storage
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            L1BatchNumber(number),
+            AggregatedActionType::Execute,
+            H256::from_low_u64_be(number.into()),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+    transaction_results
+        .iter()
+        .map(|result| result.hash)
+        .collect()
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics, Unit};
+
+use super::WebhookBatchStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum DeliveryResult {
+    Delivered,
+    /// Failed attempt that will be retried.
+    Failed,
+    /// Failed attempt after which the notification will no longer be retried.
+    Abandoned,
+}
+
+/// Metrics for the webhook dispatcher.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_webhooks")]
+pub(super) struct WebhooksMetrics {
+    /// Number of enqueued notifications split by L1 batch status.
+    pub enqueued_notifications: Family<WebhookBatchStatus, Counter>,
+    /// Number of delivery attempts split by result.
+    pub deliveries: Family<DeliveryResult, Counter>,
+    /// Latency of a single delivery request.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub delivery_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<WebhooksMetrics> = vise::Global::new();
@@ -0,0 +1,431 @@
+//! Batch status webhooks: POSTs JSON notifications to configured URLs when L1 batches are committed, proven
+//! and executed on the settlement layer. This allows external services (e.g., exchanges and bridges) to react
+//! to L1 batch status changes without polling the Web3 API.
+//!
+//! # Delivery guarantees
+//!
+//! Notifications are enqueued in the chain order for each status and are persisted in Postgres together with
+//! the dispatcher progress, so no notifications are lost if the server restarts. Delivery is at-least-once;
+//! a notification may be delivered several times (e.g., if a response is lost), and retried notifications
+//! may be delivered out of order. Receivers should deduplicate notifications using the `X-Idexo-Delivery` header
+//! and order them using the L1 batch number. Failed deliveries are retried with exponential backoff; notifications
+//! that are not delivered after the configured number of attempts are kept in the delivery log. Notifications
+//! are delivered to each URL independently, so a slow or unavailable receiver doesn't delay other receivers.
+//!
+//! On the first start, the dispatcher starts from the current state of the node storage; notifications
+//! about historical L1 batches are not sent.
+//!
+//! # Signatures
+//!
+//! If `WEBHOOKS_SIGNING_SECRET` is set, each request has the `X-Idexo-Signature` header with the value
+//! `sha256=<hex>`, where `<hex>` is the hex-encoded HMAC-SHA256 of `{timestamp}.{body}` keyed by the secret,
+//! `timestamp` is the value of the `X-Idexo-Timestamp` header (Unix timestamp in seconds), and `body`
+//! is the raw request body. Receivers should reject requests with stale timestamps to prevent replays.
+
+use std::{collections::HashMap, fmt, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::watch;
+use vise::{EncodeLabelSet, EncodeLabelValue};
+use zksync_config::WebhooksConfig;
+use zksync_dal::{webhooks_dal::PendingWebhookDelivery, ConnectionPool, StorageProcessor};
+use zksync_types::{L1BatchNumber, MiniblockNumber, H256};
+
+use self::metrics::{DeliveryResult, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of L1 batches processed for each status in a single iteration.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 100;
+/// Maximum number of deliveries to a single URL attempted in a single iteration.
+const MAX_DELIVERIES_PER_URL: usize = 100;
+/// Upper bound for the delay between delivery attempts.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(3_600);
+
+/// L1 batch status notified about by webhooks. Notifications for each L1 batch are enqueued in the order
+/// statuses are listed.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    EncodeLabelValue,
+    EncodeLabelSet,
+)]
+#[serde(rename_all = "snake_case")]
+#[metrics(label = "status", rename_all = "snake_case")]
+pub enum WebhookBatchStatus {
+    Committed,
+    Proven,
+    Executed,
+}
+
+impl WebhookBatchStatus {
+    pub const ALL: [Self; 3] = [Self::Committed, Self::Proven, Self::Executed];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Committed => "committed",
+            Self::Proven => "proven",
+            Self::Executed => "executed",
+        }
+    }
+}
+
+/// JSON body of a webhook notification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStatusNotification {
+    pub l1_batch_number: L1BatchNumber,
+    pub status: WebhookBatchStatus,
+    pub first_miniblock: MiniblockNumber,
+    pub last_miniblock: MiniblockNumber,
+    /// Hash of the L1 transaction that has changed the status.
+    pub l1_tx_hash: Option<H256>,
+    pub root_hash: Option<H256>,
+    /// Timestamp of the L1 batch.
+    pub timestamp: u64,
+    /// Time when the L1 transaction that has changed the status was confirmed.
+    pub status_changed_at: Option<DateTime<Utc>>,
+}
+
+impl BatchStatusNotification {
+    /// Loads the notification from the storage. Returns `None` if the L1 batch is missing (e.g., after
+    /// snapshot recovery).
+    async fn load(
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+        status: WebhookBatchStatus,
+    ) -> anyhow::Result<Option<Self>> {
+        let details = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(l1_batch_number)
+            .await?;
+        let miniblocks = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await?;
+        let (Some(details), Some((first_miniblock, last_miniblock))) = (details, miniblocks) else {
+            return Ok(None);
+        };
+        let base = details.base;
+        let (l1_tx_hash, status_changed_at) = match status {
+            WebhookBatchStatus::Committed => (base.commit_tx_hash, base.committed_at),
+            WebhookBatchStatus::Proven => (base.prove_tx_hash, base.proven_at),
+            WebhookBatchStatus::Executed => (base.execute_tx_hash, base.executed_at),
+        };
+        Ok(Some(Self {
+            l1_batch_number,
+            status,
+            first_miniblock,
+            last_miniblock,
+            l1_tx_hash,
+            root_hash: base.root_hash,
+            timestamp: base.timestamp,
+            status_changed_at,
+        }))
+    }
+}
+
+/// Client sending webhook requests.
+#[async_trait]
+pub trait WebhookClient: fmt::Debug + Send + Sync {
+    /// POSTs `body` to the specified URL and returns the HTTP status of the response.
+    async fn post(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: Vec<u8>,
+    ) -> anyhow::Result<u16>;
+}
+
+/// [`WebhookClient`] implementation based on `reqwest`.
+#[derive(Debug)]
+pub struct HttpWebhookClient {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookClient {
+    pub fn new(request_timeout: Duration) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(request_timeout)
+            .build()
+            .context("failed building HTTP client")?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl WebhookClient for HttpWebhookClient {
+    async fn post(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: Vec<u8>,
+    ) -> anyhow::Result<u16> {
+        let mut request = self.client.post(url).body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = request.send().await?;
+        Ok(response.status().as_u16())
+    }
+}
+
+/// Computes the value of the `X-Idexo-Signature` header. See the module-level docs for details.
+fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Task enqueueing and delivering webhook notifications. See the module-level docs for details.
+#[derive(Debug)]
+pub struct BatchWebhookDispatcher {
+    client: Box<dyn WebhookClient>,
+    pool: ConnectionPool,
+    signing_secret: Option<String>,
+    config: WebhooksConfig,
+}
+
+impl BatchWebhookDispatcher {
+    pub fn new(
+        client: Box<dyn WebhookClient>,
+        pool: ConnectionPool,
+        config: WebhooksConfig,
+    ) -> Self {
+        Self {
+            client,
+            pool,
+            signing_secret: config.signing_secret(),
+            config,
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        if self.signing_secret.is_none() {
+            tracing::warn!("Webhook signing secret is not set; notifications will not be signed");
+        }
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, webhook dispatcher is shutting down");
+                break;
+            }
+
+            if let Err(err) = self.enqueue_notifications().await {
+                tracing::warn!("Failed enqueueing webhook notifications: {err:#}");
+            }
+            if let Err(err) = self.deliver_pending().await {
+                tracing::warn!("Failed delivering webhook notifications: {err:#}");
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.config.polling_interval(), stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// Enqueues notifications for L1 batch status changes since the previous call.
+    pub(crate) async fn enqueue_notifications(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("webhooks").await?;
+        let cursors = storage.webhooks_dal().get_cursors().await?;
+        for status in WebhookBatchStatus::ALL {
+            let cursor = cursors.get(status.name()).map(|number| number.0);
+            self.enqueue_status_changes(&mut storage, status, cursor)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn enqueue_status_changes(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        status: WebhookBatchStatus,
+        cursor: Option<u32>,
+    ) -> anyhow::Result<()> {
+        let mut blocks_dal = storage.blocks_dal();
+        let head = match status {
+            WebhookBatchStatus::Committed => {
+                blocks_dal
+                    .get_number_of_last_l1_batch_committed_on_eth()
+                    .await?
+            }
+            WebhookBatchStatus::Proven => {
+                blocks_dal
+                    .get_number_of_last_l1_batch_proven_on_eth()
+                    .await?
+            }
+            WebhookBatchStatus::Executed => {
+                blocks_dal
+                    .get_number_of_last_l1_batch_executed_on_eth()
+                    .await?
+            }
+        };
+        let head = head.map(|number| number.0);
+        // The genesis L1 batch is never committed, proven or executed.
+        let next_head = head.map_or(1, |head| head + 1);
+        let name = status.name();
+
+        let Some(cursor) = cursor else {
+            tracing::info!("Initializing webhook cursor `{name}` at L1 batch #{next_head}");
+            storage
+                .webhooks_dal()
+                .set_cursor(name, L1BatchNumber(next_head))
+                .await?;
+            return Ok(());
+        };
+        if cursor > next_head {
+            // Can happen if the node storage was rolled back (e.g., by the block reverter).
+            tracing::warn!(
+                "Webhook cursor `{name}` (L1 batch #{cursor}) is ahead of the node storage (L1 batch #{next_head}); \
+                 resetting it. Delivered notifications for rolled back L1 batches will not be retracted"
+            );
+            storage
+                .webhooks_dal()
+                .set_cursor(name, L1BatchNumber(next_head))
+                .await?;
+            return Ok(());
+        }
+        let Some(head) = head.filter(|&head| cursor <= head) else {
+            return Ok(());
+        };
+        let last_l1_batch = head.min(cursor + MAX_L1_BATCHES_PER_ITERATION - 1);
+
+        let mut transaction = storage.start_transaction().await?;
+        for number in cursor..=last_l1_batch {
+            let l1_batch_number = L1BatchNumber(number);
+            let notification =
+                BatchStatusNotification::load(&mut transaction, l1_batch_number, status).await?;
+            let Some(notification) = notification else {
+                continue;
+            };
+            let payload = serde_json::to_value(&notification)
+                .context("failed serializing webhook notification")?;
+            for url in &self.config.urls {
+                transaction
+                    .webhooks_dal()
+                    .insert_delivery(url, l1_batch_number, name, &payload)
+                    .await?;
+            }
+            METRICS.enqueued_notifications[&status].inc_by(self.config.urls.len() as u64);
+        }
+        transaction
+            .webhooks_dal()
+            .set_cursor(name, L1BatchNumber(last_l1_batch + 1))
+            .await?;
+        transaction.commit().await?;
+
+        tracing::debug!(
+            "Enqueued webhook notifications for L1 batches #{cursor}..=#{last_l1_batch} with status `{name}`"
+        );
+        Ok(())
+    }
+
+    /// Attempts to deliver notifications that are due for delivery. Notifications are delivered to each URL
+    /// sequentially in the order they were enqueued, while different URLs are served concurrently, so that
+    /// a slow or unresponsive receiver doesn't delay notifications to other receivers.
+    pub(crate) async fn deliver_pending(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("webhooks").await?;
+        let pending = storage
+            .webhooks_dal()
+            .get_pending_deliveries(self.config.max_attempts, MAX_DELIVERIES_PER_URL)
+            .await?;
+        drop(storage);
+
+        let mut pending_by_url = HashMap::<_, Vec<_>>::new();
+        for delivery in pending {
+            pending_by_url
+                .entry(delivery.url.clone())
+                .or_default()
+                .push(delivery);
+        }
+        let deliveries = pending_by_url.into_values().map(|pending| async move {
+            for delivery in pending {
+                self.deliver(delivery).await?;
+            }
+            anyhow::Ok(())
+        });
+        // Deliveries to all URLs are awaited even if some of them fail, so that they are not interrupted
+        // between sending a request and recording its result.
+        future::join_all(deliveries).await.into_iter().collect()
+    }
+
+    async fn deliver(&self, delivery: PendingWebhookDelivery) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(&delivery.payload)?;
+        let timestamp = Utc::now().timestamp();
+        let mut headers = vec![
+            ("Content-Type", "application/json".to_owned()),
+            ("X-Idexo-Delivery", delivery.id.to_string()),
+            ("X-Idexo-Event", format!("l1_batch.{}", delivery.status)),
+            ("X-Idexo-Timestamp", timestamp.to_string()),
+        ];
+        if let Some(secret) = &self.signing_secret {
+            headers.push(("X-Idexo-Signature", sign_payload(secret, timestamp, &body)));
+        }
+
+        let latency = METRICS.delivery_latency.start();
+        let response = self.client.post(&delivery.url, headers, body).await;
+        latency.observe();
+
+        let mut storage = self.pool.access_storage_tagged("webhooks").await?;
+        let (response_status, error) = match response {
+            Ok(status) if (200..300).contains(&status) => {
+                storage
+                    .webhooks_dal()
+                    .mark_delivered(delivery.id, status)
+                    .await?;
+                METRICS.deliveries[&DeliveryResult::Delivered].inc();
+                return Ok(());
+            }
+            Ok(status) => (Some(status), format!("unexpected HTTP status {status}")),
+            Err(err) => (None, format!("{err:#}")),
+        };
+
+        let retry_after = self
+            .config
+            .retry_backoff()
+            .saturating_mul(2_u32.saturating_pow(delivery.attempts))
+            .min(MAX_RETRY_BACKOFF);
+        storage
+            .webhooks_dal()
+            .record_failed_attempt(delivery.id, response_status, &error, retry_after)
+            .await?;
+
+        let attempts = delivery.attempts + 1;
+        if attempts >= self.config.max_attempts {
+            tracing::error!(
+                "Abandoned delivering webhook notification #{} (L1 batch #{}, status `{}`) to {} \
+                 after {attempts} attempts: {error}",
+                delivery.id,
+                delivery.l1_batch_number,
+                delivery.status,
+                delivery.url
+            );
+            METRICS.deliveries[&DeliveryResult::Abandoned].inc();
+        } else {
+            tracing::warn!(
+                "Failed delivering webhook notification #{} to {} (attempt {attempts}), retrying in {retry_after:?}: {error}",
+                delivery.id,
+                delivery.url
+            );
+            METRICS.deliveries[&DeliveryResult::Failed].inc();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,417 @@
+//! Tests for the webhook dispatcher.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::Notify;
+use zksync_types::{aggregated_operations::AggregatedActionType, L2ChainId};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l1_batch, create_miniblock},
+};
+
+const URL: &str = "http://webhook.test/";
+
+#[derive(Debug)]
+struct RecordedRequest {
+    url: String,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find_map(|(header, value)| (*header == name).then_some(value.as_str()))
+    }
+
+    fn notification(&self) -> BatchStatusNotification {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+}
+
+/// Client recording requests and responding with a configurable HTTP status. Requests to the stalled URL
+/// (if any) don't complete until the URL is unstalled.
+#[derive(Debug)]
+struct MockClient {
+    requests: Mutex<Vec<RecordedRequest>>,
+    response_status: Mutex<u16>,
+    stalled_url: Mutex<Option<String>>,
+    unstalled: Notify,
+}
+
+impl Default for MockClient {
+    fn default() -> Self {
+        Self {
+            requests: Mutex::default(),
+            response_status: Mutex::new(200),
+            stalled_url: Mutex::default(),
+            unstalled: Notify::new(),
+        }
+    }
+}
+
+impl MockClient {
+    fn take_requests(&self) -> Vec<RecordedRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+
+    fn set_response_status(&self, status: u16) {
+        *self.response_status.lock().unwrap() = status;
+    }
+
+    fn stall_url(&self, url: &str) {
+        *self.stalled_url.lock().unwrap() = Some(url.to_owned());
+    }
+
+    fn unstall(&self) {
+        *self.stalled_url.lock().unwrap() = None;
+        self.unstalled.notify_one();
+    }
+}
+
+#[async_trait]
+impl WebhookClient for Arc<MockClient> {
+    async fn post(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: Vec<u8>,
+    ) -> anyhow::Result<u16> {
+        let is_stalled = self.stalled_url.lock().unwrap().as_deref() == Some(url);
+        if is_stalled {
+            self.unstalled.notified().await;
+        }
+        self.requests.lock().unwrap().push(RecordedRequest {
+            url: url.to_owned(),
+            headers,
+            body,
+        });
+        Ok(*self.response_status.lock().unwrap())
+    }
+}
+
+fn config() -> WebhooksConfig {
+    WebhooksConfig {
+        urls: vec![URL.to_owned()],
+        polling_interval_ms: 10,
+        request_timeout_ms: 1_000,
+        max_attempts: 2,
+        retry_backoff_ms: 0,
+    }
+}
+
+fn create_dispatcher(client: &Arc<MockClient>, pool: &ConnectionPool) -> BatchWebhookDispatcher {
+    let mut dispatcher =
+        BatchWebhookDispatcher::new(Box::new(client.clone()), pool.clone(), config());
+    dispatcher.signing_secret = Some("secret".to_owned());
+    dispatcher
+}
+
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+}
+
+async fn seal_l1_batch(storage: &mut StorageProcessor<'_>, number: u32) {
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(number))
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+        .await
+        .unwrap();
+}
+
+async fn commit_l1_batch(pool: &ConnectionPool, number: u32, tx_hash: H256) {
+    pool.access_storage()
+        .await
+        .unwrap()
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            L1BatchNumber(number),
+            AggregatedActionType::Commit,
+            tx_hash,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+}
+
+#[test]
+fn signing_payload() {
+    let signature = sign_payload("secret", 1_700_000_000, br#"{"l1BatchNumber":1}"#);
+    assert_eq!(
+        signature,
+        "sha256=9549297df50d21664d24fd9a46fa0d50557b35203779e333d5e6b508d68ed296"
+    );
+}
+
+#[tokio::test]
+async fn cursors_are_initialized_at_storage_head() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let client = Arc::new(MockClient::default());
+    let dispatcher = create_dispatcher(&client, &pool);
+
+    dispatcher.enqueue_notifications().await.unwrap();
+    dispatcher.deliver_pending().await.unwrap();
+    assert!(client.take_requests().is_empty());
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let cursors = storage.webhooks_dal().get_cursors().await.unwrap();
+    let expected_cursors = HashMap::from(
+        WebhookBatchStatus::ALL.map(|status| (status.name().to_owned(), L1BatchNumber(1))),
+    );
+    assert_eq!(cursors, expected_cursors);
+}
+
+#[tokio::test]
+async fn delivering_signed_notifications() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let client = Arc::new(MockClient::default());
+    let dispatcher = create_dispatcher(&client, &pool);
+    dispatcher.enqueue_notifications().await.unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    seal_l1_batch(&mut storage, 1).await;
+    drop(storage);
+    // Sealed L1 batches are not notified about.
+    dispatcher.enqueue_notifications().await.unwrap();
+    dispatcher.deliver_pending().await.unwrap();
+    assert!(client.take_requests().is_empty());
+
+    let commit_tx_hash = H256::repeat_byte(0xc0);
+    commit_l1_batch(&pool, 1, commit_tx_hash).await;
+    dispatcher.enqueue_notifications().await.unwrap();
+    dispatcher.deliver_pending().await.unwrap();
+
+    let requests = client.take_requests();
+    assert_eq!(requests.len(), 1, "{requests:?}");
+    let request = &requests[0];
+    assert_eq!(request.url, URL);
+    assert_eq!(request.header("Content-Type"), Some("application/json"));
+    assert_eq!(request.header("X-Idexo-Event"), Some("l1_batch.committed"));
+    let timestamp: i64 = request
+        .header("X-Idexo-Timestamp")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let expected_signature = sign_payload("secret", timestamp, &request.body);
+    assert_eq!(
+        request.header("X-Idexo-Signature"),
+        Some(expected_signature.as_str())
+    );
+
+    let notification = request.notification();
+    assert_eq!(notification.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(notification.status, WebhookBatchStatus::Committed);
+    assert_eq!(notification.first_miniblock, MiniblockNumber(1));
+    assert_eq!(notification.last_miniblock, MiniblockNumber(1));
+    assert_eq!(notification.l1_tx_hash, Some(commit_tx_hash));
+    assert!(notification.status_changed_at.is_some());
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let deliveries = storage
+        .webhooks_dal()
+        .get_deliveries(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].status, "committed");
+    assert_eq!(deliveries[0].attempts, 1);
+    assert_eq!(deliveries[0].last_response_status, Some(200));
+    assert!(deliveries[0].delivered_at.is_some());
+    drop(storage);
+
+    // Delivered notifications are not re-sent.
+    dispatcher.enqueue_notifications().await.unwrap();
+    dispatcher.deliver_pending().await.unwrap();
+    assert!(client.take_requests().is_empty());
+}
+
+#[tokio::test]
+async fn retrying_failed_deliveries() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let client = Arc::new(MockClient::default());
+    let dispatcher = create_dispatcher(&client, &pool);
+    dispatcher.enqueue_notifications().await.unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    seal_l1_batch(&mut storage, 1).await;
+    seal_l1_batch(&mut storage, 2).await;
+    drop(storage);
+    commit_l1_batch(&pool, 1, H256::repeat_byte(1)).await;
+    commit_l1_batch(&pool, 2, H256::repeat_byte(2)).await;
+    dispatcher.enqueue_notifications().await.unwrap();
+
+    client.set_response_status(503);
+    dispatcher.deliver_pending().await.unwrap();
+    let requests = client.take_requests();
+    let l1_batch_numbers: Vec<_> = requests
+        .iter()
+        .map(|request| request.notification().l1_batch_number)
+        .collect();
+    assert_eq!(l1_batch_numbers, [L1BatchNumber(1), L1BatchNumber(2)]);
+    let delivery_ids: Vec<_> = requests
+        .iter()
+        .map(|request| request.header("X-Idexo-Delivery").unwrap().to_owned())
+        .collect();
+
+    // The retried notification has the same delivery ID.
+    client.set_response_status(204);
+    dispatcher.deliver_pending().await.unwrap();
+    let retried_ids: Vec<_> = client
+        .take_requests()
+        .iter()
+        .map(|request| request.header("X-Idexo-Delivery").unwrap().to_owned())
+        .collect();
+    assert_eq!(retried_ids, delivery_ids);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let deliveries = storage
+        .webhooks_dal()
+        .get_deliveries(L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert_eq!(deliveries[0].attempts, 2);
+    assert_eq!(deliveries[0].last_response_status, Some(204));
+    assert_eq!(deliveries[0].last_error, None);
+    assert!(deliveries[0].delivered_at.is_some());
+}
+
+#[tokio::test]
+async fn slow_url_does_not_block_other_urls() {
+    const SLOW_URL: &str = "http://slow-webhook.test/";
+
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let client = Arc::new(MockClient::default());
+    let mut dispatcher = create_dispatcher(&client, &pool);
+    dispatcher.config.urls.push(SLOW_URL.to_owned());
+    dispatcher.enqueue_notifications().await.unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    seal_l1_batch(&mut storage, 1).await;
+    seal_l1_batch(&mut storage, 2).await;
+    drop(storage);
+    commit_l1_batch(&pool, 1, H256::repeat_byte(1)).await;
+    commit_l1_batch(&pool, 2, H256::repeat_byte(2)).await;
+    dispatcher.enqueue_notifications().await.unwrap();
+
+    client.stall_url(SLOW_URL);
+    let delivery = dispatcher.deliver_pending();
+    tokio::pin!(delivery);
+    let fast_url_requests = async {
+        while client.requests.lock().unwrap().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    tokio::select! {
+        res = &mut delivery => panic!("delivery completed while the URL is stalled: {res:?}"),
+        () = fast_url_requests => {}
+    }
+    let requests = client.take_requests();
+    assert!(
+        requests.iter().all(|request| request.url == URL),
+        "{requests:?}"
+    );
+
+    client.unstall();
+    delivery.await.unwrap();
+    let requests = client.take_requests();
+    assert!(
+        requests.iter().all(|request| request.url == SLOW_URL),
+        "{requests:?}"
+    );
+    // Notifications to the same URL are still delivered in order.
+    let l1_batch_numbers: Vec<_> = requests
+        .iter()
+        .map(|request| request.notification().l1_batch_number)
+        .collect();
+    assert_eq!(l1_batch_numbers, [L1BatchNumber(1), L1BatchNumber(2)]);
+}
+
+#[tokio::test]
+async fn abandoning_deliveries_after_max_attempts() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let client = Arc::new(MockClient::default());
+    let dispatcher = create_dispatcher(&client, &pool);
+    dispatcher.enqueue_notifications().await.unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    seal_l1_batch(&mut storage, 1).await;
+    drop(storage);
+    commit_l1_batch(&pool, 1, H256::repeat_byte(1)).await;
+    dispatcher.enqueue_notifications().await.unwrap();
+
+    client.set_response_status(500);
+    for _ in 0..3 {
+        dispatcher.deliver_pending().await.unwrap();
+    }
+    // `max_attempts` is 2, so the 3rd iteration doesn't send any requests.
+    assert_eq!(client.take_requests().len(), 2);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let deliveries = storage
+        .webhooks_dal()
+        .get_deliveries(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(deliveries[0].attempts, 2);
+    assert_eq!(deliveries[0].last_response_status, Some(500));
+    assert_eq!(
+        deliveries[0].last_error.as_deref(),
+        Some("unexpected HTTP status 500")
+    );
+    assert!(deliveries[0].delivered_at.is_none());
+}
+
+#[tokio::test]
+async fn cursor_is_reset_after_storage_rollback() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    for status in WebhookBatchStatus::ALL {
+        storage
+            .webhooks_dal()
+            .set_cursor(status.name(), L1BatchNumber(10))
+            .await
+            .unwrap();
+    }
+    drop(storage);
+
+    let client = Arc::new(MockClient::default());
+    let dispatcher = create_dispatcher(&client, &pool);
+    dispatcher.enqueue_notifications().await.unwrap();
+    let mut storage = pool.access_storage().await.unwrap();
+    let cursors = storage.webhooks_dal().get_cursors().await.unwrap();
+    assert_eq!(cursors["committed"], L1BatchNumber(1));
+
+    seal_l1_batch(&mut storage, 1).await;
+    drop(storage);
+    commit_l1_batch(&pool, 1, H256::repeat_byte(1)).await;
+    dispatcher.enqueue_notifications().await.unwrap();
+    dispatcher.deliver_pending().await.unwrap();
+    let requests = client.take_requests();
+    assert_eq!(requests.len(), 1, "{requests:?}");
+    assert_eq!(requests[0].notification().l1_batch_number, L1BatchNumber(1));
+}
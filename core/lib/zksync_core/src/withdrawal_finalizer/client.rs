@@ -0,0 +1,188 @@
+//! Settlement layer client used by the withdrawal finalizer.
+
+use std::{fmt, sync::Arc};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_dal::withdrawals_dal::Withdrawal;
+use zksync_eth_client::{BoundEthInterface, EthInterface};
+use zksync_types::{
+    ethabi::{self, ParamType, Token},
+    web3::types::{Bytes, CallRequest},
+    Address, U256,
+};
+
+use crate::utils::settlement_txs::{SettlementTxClient, SettlementTxRequest};
+
+const COMPONENT: &str = "withdrawal_finalizer";
+
+/// Parameters of `finalizeEthWithdrawal` and `finalizeWithdrawal` methods (the methods have the same signature).
+fn finalize_params() -> [ParamType; 5] {
+    [
+        ParamType::Uint(256),
+        ParamType::Uint(256),
+        ParamType::Uint(16),
+        ParamType::Bytes,
+        ParamType::Array(Box::new(ParamType::FixedBytes(32))),
+    ]
+}
+
+/// Returns the selector of the finalization method for the base token (`is_base_token == true`) or ERC-20 tokens.
+/// Withdrawal messages start with the same selector.
+pub(super) fn finalize_method_selector(is_base_token: bool) -> [u8; 4] {
+    let name = if is_base_token {
+        "finalizeEthWithdrawal"
+    } else {
+        "finalizeWithdrawal"
+    };
+    ethabi::short_signature(name, &finalize_params())
+}
+
+/// Settlement layer operations necessary for the withdrawal finalizer.
+#[async_trait]
+pub trait FinalizerClient: fmt::Debug + Send + Sync {
+    /// Returns the current gas price on the settlement layer in wei.
+    async fn gas_price(&self) -> anyhow::Result<U256>;
+
+    /// Checks whether the withdrawal is finalized (potentially, by the user).
+    async fn is_withdrawal_finalized(&self, withdrawal: &Withdrawal) -> anyhow::Result<bool>;
+
+    /// Builds a transaction finalizing the specified withdrawals.
+    fn finalization_tx(
+        &self,
+        withdrawals: &[Withdrawal],
+        gas_limit: u64,
+    ) -> anyhow::Result<SettlementTxRequest>;
+
+    /// Returns the client used to send finalization transactions.
+    fn tx_client(&self) -> &dyn SettlementTxClient;
+}
+
+/// [`FinalizerClient`] implementation calling settlement layer contracts. Withdrawals of the base token
+/// are finalized via the diamond proxy, and ERC-20 withdrawals via the L1 ERC-20 bridge. Several withdrawals
+/// are finalized in a single transaction via Multicall3; failure of an individual finalization doesn't
+/// revert the transaction.
+#[derive(Debug)]
+pub struct EthFinalizerClient {
+    client: Arc<dyn BoundEthInterface>,
+    diamond_proxy_addr: Address,
+    l1_erc20_bridge_addr: Address,
+    multicall3_addr: Address,
+}
+
+impl EthFinalizerClient {
+    pub fn new(
+        client: Arc<dyn BoundEthInterface>,
+        diamond_proxy_addr: Address,
+        l1_erc20_bridge_addr: Address,
+        multicall3_addr: Address,
+    ) -> Self {
+        Self {
+            client,
+            diamond_proxy_addr,
+            l1_erc20_bridge_addr,
+            multicall3_addr,
+        }
+    }
+
+    fn target_contract(&self, withdrawal: &Withdrawal) -> Address {
+        if withdrawal.l1_token == Address::zero() {
+            self.diamond_proxy_addr
+        } else {
+            self.l1_erc20_bridge_addr
+        }
+    }
+
+    fn encode_finalize_call(withdrawal: &Withdrawal) -> Vec<u8> {
+        let is_base_token = withdrawal.l1_token == Address::zero();
+        let mut data = finalize_method_selector(is_base_token).to_vec();
+        let merkle_proof = withdrawal
+            .merkle_proof
+            .iter()
+            .map(|hash| Token::FixedBytes(hash.as_bytes().to_vec()))
+            .collect();
+        data.extend(ethabi::encode(&[
+            Token::Uint(withdrawal.l1_batch_number.0.into()),
+            Token::Uint(withdrawal.l2_message_index.into()),
+            Token::Uint(withdrawal.tx_number_in_batch.into()),
+            Token::Bytes(withdrawal.message.clone()),
+            Token::Array(merkle_proof),
+        ]));
+        data
+    }
+
+    fn encode_multicall(&self, withdrawals: &[Withdrawal]) -> Vec<u8> {
+        let call_param =
+            ParamType::Tuple(vec![ParamType::Address, ParamType::Bool, ParamType::Bytes]);
+        let mut data =
+            ethabi::short_signature("aggregate3", &[ParamType::Array(Box::new(call_param))])
+                .to_vec();
+        let calls = withdrawals
+            .iter()
+            .map(|withdrawal| {
+                Token::Tuple(vec![
+                    Token::Address(self.target_contract(withdrawal)),
+                    Token::Bool(true), // `allowFailure`
+                    Token::Bytes(Self::encode_finalize_call(withdrawal)),
+                ])
+            })
+            .collect();
+        data.extend(ethabi::encode(&[Token::Array(calls)]));
+        data
+    }
+}
+
+#[async_trait]
+impl FinalizerClient for EthFinalizerClient {
+    async fn gas_price(&self) -> anyhow::Result<U256> {
+        Ok(self.client.get_gas_price(COMPONENT).await?)
+    }
+
+    async fn is_withdrawal_finalized(&self, withdrawal: &Withdrawal) -> anyhow::Result<bool> {
+        let is_base_token = withdrawal.l1_token == Address::zero();
+        let name = if is_base_token {
+            "isEthWithdrawalFinalized"
+        } else {
+            "isWithdrawalFinalized"
+        };
+        let mut data =
+            ethabi::short_signature(name, &[ParamType::Uint(256), ParamType::Uint(256)]).to_vec();
+        data.extend(ethabi::encode(&[
+            Token::Uint(withdrawal.l1_batch_number.0.into()),
+            Token::Uint(withdrawal.l2_message_index.into()),
+        ]));
+        let request = CallRequest {
+            to: Some(self.target_contract(withdrawal)),
+            data: Some(Bytes(data)),
+            ..CallRequest::default()
+        };
+        let output = self.client.call(request, None, COMPONENT).await?;
+        let tokens = ethabi::decode(&[ParamType::Bool], &output.0)
+            .with_context(|| format!("failed decoding `{name}` output"))?;
+        Ok(tokens.into_iter().next().and_then(Token::into_bool) == Some(true))
+    }
+
+    fn finalization_tx(
+        &self,
+        withdrawals: &[Withdrawal],
+        gas_limit: u64,
+    ) -> anyhow::Result<SettlementTxRequest> {
+        let (calldata, contract_address) = match withdrawals {
+            [] => anyhow::bail!("no withdrawals to finalize"),
+            [withdrawal] => (
+                Self::encode_finalize_call(withdrawal),
+                self.target_contract(withdrawal),
+            ),
+            _ => (self.encode_multicall(withdrawals), self.multicall3_addr),
+        };
+        Ok(SettlementTxRequest {
+            contract_address,
+            calldata,
+            gas_limit,
+        })
+    }
+
+    fn tx_client(&self) -> &dyn SettlementTxClient {
+        &self.client
+    }
+}
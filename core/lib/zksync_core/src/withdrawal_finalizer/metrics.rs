@@ -0,0 +1,33 @@
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum FinalizationOutcome {
+    /// Withdrawal was finalized by the finalizer.
+    Finalized,
+    /// Withdrawal was finalized by a third party (e.g., the user).
+    FinalizedExternally,
+    /// Finalization attempt failed; the withdrawal will be retried.
+    Retried,
+    /// Withdrawal has reached the maximum number of attempts.
+    Failed,
+}
+
+/// Metrics for the withdrawal finalizer.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_withdrawal_finalizer")]
+pub(super) struct WithdrawalFinalizerMetrics {
+    /// Number of withdrawals extracted from executed L1 batches.
+    pub observed_withdrawals: Counter,
+    /// Number of sent finalization transactions.
+    pub sent_transactions: Counter,
+    /// Number of processed withdrawals split by the outcome.
+    pub processed_withdrawals: Family<FinalizationOutcome, Counter>,
+    /// Number of the last L1 batch scanned for withdrawals.
+    pub last_scanned_l1_batch: Gauge<u64>,
+    /// Whether finalization is postponed because of a high gas price.
+    pub gas_price_too_high: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<WithdrawalFinalizerMetrics> = vise::Global::new();
@@ -0,0 +1,364 @@
+//! Withdrawal finalizer: finalizes withdrawals from the chain on the settlement layer on behalf of users.
+//!
+//! The finalizer scans L1 batches executed on the settlement layer for withdrawal messages sent by the base token
+//! and the L2 ERC-20 bridge, and persists them together with their Merkle proofs. Pending withdrawals of allowlisted
+//! tokens are then finalized in batches via Multicall3. Only a single finalization transaction is in flight
+//! at a time, so the finalizer account nonce is always derived from the settlement layer. Finalization transactions
+//! are persisted before they are sent, and are rebroadcast or replaced with bumped fees until they are mined
+//! (see [`SettlementTxManager`]).
+//!
+//! After a finalization transaction is mined (or its nonce is used by another transaction), the finalization status
+//! of each included withdrawal is checked on the settlement layer. Withdrawals that are not finalized are retried
+//! until they reach the configured maximum number of attempts; after that, they must be finalized by users.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::WithdrawalFinalizerConfig;
+use zksync_dal::{
+    withdrawals_dal::{Withdrawal, WithdrawalStatus},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_types::{Address, L1BatchNumber, L2_ETH_TOKEN_ADDRESS, U256};
+
+pub use self::client::{EthFinalizerClient, FinalizerClient};
+use self::metrics::{FinalizationOutcome, METRICS};
+use crate::utils::{
+    l2_to_l1_messages::load_proven_messages,
+    settlement_txs::{SettlementTxManager, SettlementTxOutcome},
+};
+
+mod client;
+mod metrics;
+#[cfg(test)]
+pub(crate) mod tests;
+
+const GWEI: u64 = 1_000_000_000;
+/// Maximum number of L1 batches scanned for withdrawals in a single iteration.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 100;
+
+/// Information parsed from a withdrawal message.
+#[derive(Debug, PartialEq)]
+pub(crate) struct WithdrawalMessage {
+    /// Address of the withdrawn token on the settlement layer; the zero address for the base token.
+    pub l1_token: Address,
+    pub l1_receiver: Address,
+    pub amount: U256,
+}
+
+/// Parses a message sent to the settlement layer by `sender`. Returns `None` if the message is not a withdrawal
+/// initiated via the base token contract or the L2 ERC-20 bridge.
+pub(crate) fn parse_withdrawal_message(
+    l2_erc20_bridge_addr: Address,
+    sender: Address,
+    message: &[u8],
+) -> Option<WithdrawalMessage> {
+    if sender == L2_ETH_TOKEN_ADDRESS {
+        // `abi.encodePacked(finalizeEthWithdrawal.selector, l1Receiver, amount)`
+        if message.len() != 56 || message[..4] != client::finalize_method_selector(true) {
+            return None;
+        }
+        Some(WithdrawalMessage {
+            l1_token: Address::zero(),
+            l1_receiver: Address::from_slice(&message[4..24]),
+            amount: U256::from_big_endian(&message[24..56]),
+        })
+    } else if sender == l2_erc20_bridge_addr {
+        // `abi.encodePacked(finalizeWithdrawal.selector, l1Receiver, l1Token, amount)`
+        if message.len() != 76 || message[..4] != client::finalize_method_selector(false) {
+            return None;
+        }
+        Some(WithdrawalMessage {
+            l1_receiver: Address::from_slice(&message[4..24]),
+            l1_token: Address::from_slice(&message[24..44]),
+            amount: U256::from_big_endian(&message[44..76]),
+        })
+    } else {
+        None
+    }
+}
+
+/// Task finalizing withdrawals. See the module-level docs for details.
+#[derive(Debug)]
+pub struct WithdrawalFinalizer {
+    client: Box<dyn FinalizerClient>,
+    pool: ConnectionPool,
+    config: WithdrawalFinalizerConfig,
+    l2_erc20_bridge_addr: Address,
+    tx_manager: SettlementTxManager,
+    /// Next L1 batch to scan for withdrawals.
+    next_l1_batch: L1BatchNumber,
+}
+
+impl WithdrawalFinalizer {
+    pub async fn new(
+        client: Box<dyn FinalizerClient>,
+        pool: ConnectionPool,
+        config: WithdrawalFinalizerConfig,
+        l2_erc20_bridge_addr: Address,
+    ) -> anyhow::Result<Self> {
+        let mut storage = pool.access_storage_tagged("withdrawal_finalizer").await?;
+        let last_l1_batch = storage
+            .withdrawals_dal()
+            .get_last_l1_batch_with_withdrawals()
+            .await?;
+        drop(storage);
+        // Withdrawals of an L1 batch are persisted atomically, so the last L1 batch with withdrawals doesn't need
+        // to be rescanned. The genesis L1 batch cannot contain withdrawals.
+        let next_l1_batch = last_l1_batch.map_or(L1BatchNumber(1), |number| number + 1);
+        tracing::info!("Initialized withdrawal finalizer starting from L1 batch #{next_l1_batch}");
+
+        let tx_manager =
+            SettlementTxManager::new("withdrawal_finalizer", config.tx_resend_interval());
+        Ok(Self {
+            client,
+            pool,
+            config,
+            l2_erc20_bridge_addr,
+            tx_manager,
+            next_l1_batch,
+        })
+    }
+
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, withdrawal finalizer is shutting down");
+                break;
+            }
+
+            if let Err(err) = self.loop_iteration().await {
+                tracing::warn!("Failed finalizing withdrawals: {err:#}");
+            }
+            tokio::time::sleep(self.config.polling_interval()).await;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn loop_iteration(&mut self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("withdrawal_finalizer")
+            .await?;
+        self.scan_executed_l1_batches(&mut storage).await?;
+        let has_pending_txs = self.process_sent_withdrawals(&mut storage).await?;
+        if !has_pending_txs {
+            self.send_pending_withdrawals(&mut storage).await?;
+        }
+        Ok(())
+    }
+
+    async fn scan_executed_l1_batches(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let Some(last_executed_l1_batch) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?
+        else {
+            return Ok(());
+        };
+        let last_l1_batch_to_scan =
+            last_executed_l1_batch.min(self.next_l1_batch + (MAX_L1_BATCHES_PER_ITERATION - 1));
+
+        for number in self.next_l1_batch.0..=last_l1_batch_to_scan.0 {
+            let l1_batch_number = L1BatchNumber(number);
+            let withdrawals = self
+                .extract_withdrawals(storage, l1_batch_number)
+                .await
+                .with_context(|| {
+                    format!("failed extracting withdrawals from L1 batch #{number}")
+                })?;
+            if !withdrawals.is_empty() {
+                tracing::info!(
+                    "Observed {} withdrawals in L1 batch #{number}",
+                    withdrawals.len()
+                );
+                storage
+                    .withdrawals_dal()
+                    .insert_withdrawals(&withdrawals)
+                    .await?;
+                METRICS
+                    .observed_withdrawals
+                    .inc_by(withdrawals.len() as u64);
+            }
+            self.next_l1_batch = l1_batch_number + 1;
+            METRICS.last_scanned_l1_batch.set(number.into());
+        }
+        Ok(())
+    }
+
+    async fn extract_withdrawals(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Vec<Withdrawal>> {
+        let senders = [L2_ETH_TOKEN_ADDRESS, self.l2_erc20_bridge_addr];
+        let messages = load_proven_messages(storage, l1_batch_number, &senders).await?;
+
+        let mut withdrawals = Vec::with_capacity(messages.len());
+        for message in messages {
+            let Some(parsed) =
+                parse_withdrawal_message(self.l2_erc20_bridge_addr, message.sender, &message.data)
+            else {
+                tracing::debug!(
+                    "Message #{} in L1 batch #{l1_batch_number} sent by {:?} is not a withdrawal",
+                    message.l2_message_index,
+                    message.sender
+                );
+                continue;
+            };
+            withdrawals.push(Withdrawal {
+                l1_batch_number,
+                l2_message_index: message.l2_message_index,
+                tx_number_in_batch: message.tx_number_in_batch,
+                tx_hash: message.tx_hash,
+                sender: message.sender,
+                l1_token: parsed.l1_token,
+                l1_receiver: parsed.l1_receiver,
+                amount: parsed.amount,
+                message: message.data,
+                merkle_proof: message.merkle_proof,
+            });
+        }
+        Ok(withdrawals)
+    }
+
+    /// Processes withdrawals with mined finalization transactions. Returns `true` if there are finalization
+    /// transactions that are not mined yet.
+    async fn process_sent_withdrawals(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<bool> {
+        let sent_withdrawals = storage.withdrawals_dal().get_sent_withdrawals().await?;
+        let mut withdrawals_by_tx = BTreeMap::<_, Vec<_>>::new();
+        for (tx_id, withdrawal) in sent_withdrawals {
+            withdrawals_by_tx.entry(tx_id).or_default().push(withdrawal);
+        }
+
+        let mut has_pending_txs = false;
+        for (tx_id, withdrawals) in withdrawals_by_tx {
+            let tx_client = self.client.tx_client();
+            let Some(outcome) = self.tx_manager.check_tx(storage, tx_client, tx_id).await? else {
+                has_pending_txs = true;
+                continue;
+            };
+            let mined_tx_hash = match outcome {
+                SettlementTxOutcome::Mined { tx_hash, status } => {
+                    if !status.success {
+                        tracing::warn!("Finalization transaction {tx_hash:?} has failed");
+                    }
+                    Some(tx_hash)
+                }
+                SettlementTxOutcome::NonceUsed => None,
+            };
+
+            // Individual finalizations may fail even if the transaction has succeeded.
+            for withdrawal in &withdrawals {
+                if self.client.is_withdrawal_finalized(withdrawal).await? {
+                    storage
+                        .withdrawals_dal()
+                        .mark_withdrawal_as_finalized(withdrawal, mined_tx_hash)
+                        .await?;
+                    METRICS.processed_withdrawals[&FinalizationOutcome::Finalized].inc();
+                    continue;
+                }
+
+                let status = storage
+                    .withdrawals_dal()
+                    .reset_withdrawal(withdrawal, self.config.max_attempts)
+                    .await?;
+                if status == WithdrawalStatus::Failed {
+                    tracing::error!(
+                        "Withdrawal #{} in L1 batch #{} (transaction {:?}) was not finalized \
+                         after {} attempts; it must be finalized by the user",
+                        withdrawal.l2_message_index,
+                        withdrawal.l1_batch_number,
+                        withdrawal.tx_hash,
+                        self.config.max_attempts
+                    );
+                    METRICS.processed_withdrawals[&FinalizationOutcome::Failed].inc();
+                } else {
+                    tracing::warn!(
+                        "Withdrawal #{} in L1 batch #{} was not finalized by transaction #{tx_id} \
+                         ({mined_tx_hash:?}); it will be retried",
+                        withdrawal.l2_message_index,
+                        withdrawal.l1_batch_number
+                    );
+                    METRICS.processed_withdrawals[&FinalizationOutcome::Retried].inc();
+                }
+            }
+        }
+        Ok(has_pending_txs)
+    }
+
+    async fn send_pending_withdrawals(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        if let Some(max_gas_price_gwei) = self.config.max_gas_price_gwei {
+            let gas_price = self.client.gas_price().await?;
+            let max_gas_price = U256::from(max_gas_price_gwei) * GWEI;
+            let is_too_high = gas_price > max_gas_price;
+            METRICS.gas_price_too_high.set(is_too_high.into());
+            if is_too_high {
+                tracing::info!(
+                    "Gas price {gas_price} wei exceeds the limit of {max_gas_price} wei; \
+                     postponing finalization"
+                );
+                return Ok(());
+            }
+        }
+
+        let pending_withdrawals = storage
+            .withdrawals_dal()
+            .get_pending_withdrawals(
+                &self.config.token_allowlist,
+                self.config.max_withdrawals_per_tx as usize,
+            )
+            .await?;
+        let mut withdrawals = Vec::with_capacity(pending_withdrawals.len());
+        for withdrawal in pending_withdrawals {
+            if self.client.is_withdrawal_finalized(&withdrawal).await? {
+                storage
+                    .withdrawals_dal()
+                    .mark_withdrawal_as_finalized(&withdrawal, None)
+                    .await?;
+                METRICS.processed_withdrawals[&FinalizationOutcome::FinalizedExternally].inc();
+            } else {
+                withdrawals.push(withdrawal);
+            }
+        }
+        if withdrawals.is_empty() {
+            return Ok(());
+        }
+
+        let gas_limit = self.config.gas_limit_per_withdrawal * withdrawals.len() as u64;
+        let request = self.client.finalization_tx(&withdrawals, gas_limit)?;
+        let tx_client = self.client.tx_client();
+        let tx = self
+            .tx_manager
+            .sign_new_tx(tx_client, request)
+            .await
+            .context("failed signing finalization transaction")?;
+        // The transaction is persisted before it's sent, so that it cannot be sent twice.
+        let mut transaction = storage.start_transaction().await?;
+        let tx_id = self.tx_manager.save_tx(&mut transaction, &tx).await?;
+        transaction
+            .withdrawals_dal()
+            .mark_withdrawals_as_sent(&withdrawals, tx_id)
+            .await?;
+        transaction.commit().await?;
+
+        self.tx_manager.send_tx(tx_client, &tx).await;
+        tracing::info!(
+            "Sent transaction #{tx_id} ({:?}) finalizing {} withdrawals",
+            tx.tx_hash(),
+            withdrawals.len()
+        );
+        METRICS.sent_transactions.inc();
+        Ok(())
+    }
+}
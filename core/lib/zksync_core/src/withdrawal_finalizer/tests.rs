@@ -0,0 +1,588 @@
+//! Tests for the withdrawal finalizer.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use zksync_dal::settlement_txs_dal::SignedSettlementTx;
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_types::{l2_to_l1_log::L2ToL1Log, L2ChainId, H256};
+use zksync_utils::u256_to_bytes_be;
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::{
+        settlement_txs::{MinedTxStatus, SettlementTxClient, SettlementTxRequest, TxFees},
+        testonly::store_executed_l1_batch_with_messages,
+    },
+};
+
+const L2_ERC20_BRIDGE_ADDR: Address = Address::repeat_byte(0xb2);
+
+/// Signed version of a finalization transaction.
+#[derive(Debug, Clone)]
+struct FakeSignedTx {
+    /// 0-based index of the finalization transaction in `FakeClientState.sent_txs`.
+    index: usize,
+    hash: H256,
+    nonce: u64,
+    fees: TxFees,
+}
+
+#[derive(Debug, Default)]
+struct FakeClientState {
+    gas_price: U256,
+    finalized: HashSet<(L1BatchNumber, u32)>,
+    /// Withdrawals included into finalization transactions.
+    sent_txs: Vec<Vec<Withdrawal>>,
+    signed_txs: Vec<FakeSignedTx>,
+    broadcast_txs: Vec<H256>,
+    fail_broadcasts: bool,
+    mined_txs: HashMap<H256, MinedTxStatus>,
+    nonce: u64,
+}
+
+/// Client emulating finalization of withdrawals on the settlement layer.
+#[derive(Debug, Default)]
+struct FakeFinalizerClient {
+    state: Mutex<FakeClientState>,
+}
+
+impl FakeFinalizerClient {
+    fn set_gas_price(&self, gas_price: U256) {
+        self.state.lock().unwrap().gas_price = gas_price;
+    }
+
+    fn finalize_externally(&self, withdrawal: &Withdrawal) {
+        let key = (withdrawal.l1_batch_number, withdrawal.l2_message_index);
+        self.state.lock().unwrap().finalized.insert(key);
+    }
+
+    fn sent_txs(&self) -> Vec<Vec<Withdrawal>> {
+        self.state.lock().unwrap().sent_txs.clone()
+    }
+
+    fn signed_txs(&self) -> Vec<FakeSignedTx> {
+        self.state.lock().unwrap().signed_txs.clone()
+    }
+
+    fn broadcast_txs(&self) -> Vec<H256> {
+        self.state.lock().unwrap().broadcast_txs.clone()
+    }
+
+    fn set_fail_broadcasts(&self, fail: bool) {
+        self.state.lock().unwrap().fail_broadcasts = fail;
+    }
+
+    /// Emulates a transaction from the finalizer account not tracked by the finalizer.
+    fn use_nonce(&self) {
+        self.state.lock().unwrap().nonce += 1;
+    }
+
+    /// Mines the last signed version of the finalization transaction with the specified 1-based index.
+    /// If `finalize` is not set, the transaction succeeds, but doesn't finalize any withdrawals.
+    fn mine_tx(&self, index: usize, finalize: bool) -> H256 {
+        let mut state = self.state.lock().unwrap();
+        let withdrawals = state.sent_txs[index - 1].clone();
+        let signed_tx = state
+            .signed_txs
+            .iter()
+            .rev()
+            .find(|tx| tx.index == index - 1)
+            .expect("transaction is not signed")
+            .clone();
+        assert_eq!(signed_tx.nonce, state.nonce);
+        state.nonce += 1;
+        let status = MinedTxStatus {
+            success: true,
+            block_number: state.nonce,
+        };
+        state.mined_txs.insert(signed_tx.hash, status);
+        if finalize {
+            for withdrawal in &withdrawals {
+                let key = (withdrawal.l1_batch_number, withdrawal.l2_message_index);
+                state.finalized.insert(key);
+            }
+        }
+        signed_tx.hash
+    }
+}
+
+#[async_trait]
+impl FinalizerClient for Arc<FakeFinalizerClient> {
+    async fn gas_price(&self) -> anyhow::Result<U256> {
+        Ok(self.state.lock().unwrap().gas_price)
+    }
+
+    async fn is_withdrawal_finalized(&self, withdrawal: &Withdrawal) -> anyhow::Result<bool> {
+        let key = (withdrawal.l1_batch_number, withdrawal.l2_message_index);
+        Ok(self.state.lock().unwrap().finalized.contains(&key))
+    }
+
+    fn finalization_tx(
+        &self,
+        withdrawals: &[Withdrawal],
+        gas_limit: u64,
+    ) -> anyhow::Result<SettlementTxRequest> {
+        let mut state = self.state.lock().unwrap();
+        state.sent_txs.push(withdrawals.to_vec());
+        let index = state.sent_txs.len() as u64 - 1;
+        Ok(SettlementTxRequest {
+            contract_address: Address::zero(),
+            calldata: index.to_be_bytes().to_vec(),
+            gas_limit,
+        })
+    }
+
+    fn tx_client(&self) -> &dyn SettlementTxClient {
+        self
+    }
+}
+
+#[async_trait]
+impl SettlementTxClient for Arc<FakeFinalizerClient> {
+    async fn mined_nonce(&self) -> anyhow::Result<u64> {
+        Ok(self.state.lock().unwrap().nonce)
+    }
+
+    async fn base_fee_per_gas(&self) -> anyhow::Result<U256> {
+        Ok(self.state.lock().unwrap().gas_price)
+    }
+
+    async fn sign_tx(
+        &self,
+        request: &SettlementTxRequest,
+        nonce: u64,
+        fees: Option<TxFees>,
+    ) -> anyhow::Result<SignedSettlementTx> {
+        let mut state = self.state.lock().unwrap();
+        let index = u64::from_be_bytes(request.calldata.as_slice().try_into()?) as usize;
+        let fees = fees.unwrap_or(TxFees {
+            max_fee_per_gas: state.gas_price + 1,
+            max_priority_fee_per_gas: 1.into(),
+        });
+        let hash = H256::from_low_u64_be(state.signed_txs.len() as u64 + 1);
+        state.signed_txs.push(FakeSignedTx {
+            index,
+            hash,
+            nonce,
+            fees,
+        });
+        Ok(SignedSettlementTx {
+            tx_hash: hash,
+            raw_tx: hash.as_bytes().to_vec(),
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+        })
+    }
+
+    async fn send_raw_tx(&self, raw_tx: &[u8]) -> anyhow::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        anyhow::ensure!(!state.fail_broadcasts, "emulated broadcast failure");
+        state.broadcast_txs.push(H256::from_slice(raw_tx));
+        Ok(())
+    }
+
+    async fn tx_status(&self, tx_hash: H256) -> anyhow::Result<Option<MinedTxStatus>> {
+        Ok(self.state.lock().unwrap().mined_txs.get(&tx_hash).copied())
+    }
+}
+
+fn config() -> WithdrawalFinalizerConfig {
+    WithdrawalFinalizerConfig {
+        polling_interval_ms: 10,
+        token_allowlist: vec![Address::zero()],
+        max_withdrawals_per_tx: 2,
+        gas_limit_per_withdrawal: 200_000,
+        max_gas_price_gwei: None,
+        max_attempts: 2,
+        tx_resend_interval_sec: 3_600,
+    }
+}
+
+async fn create_finalizer(
+    pool: &ConnectionPool,
+    config: WithdrawalFinalizerConfig,
+) -> (WithdrawalFinalizer, Arc<FakeFinalizerClient>) {
+    let client = Arc::<FakeFinalizerClient>::default();
+    let finalizer = WithdrawalFinalizer::new(
+        Box::new(client.clone()),
+        pool.clone(),
+        config,
+        L2_ERC20_BRIDGE_ADDR,
+    )
+    .await
+    .unwrap();
+    (finalizer, client)
+}
+
+pub(crate) fn eth_withdrawal_message(receiver: Address, amount: U256) -> Vec<u8> {
+    let mut message = client::finalize_method_selector(true).to_vec();
+    message.extend_from_slice(receiver.as_bytes());
+    message.extend_from_slice(&u256_to_bytes_be(&amount));
+    message
+}
+
+pub(crate) fn erc20_withdrawal_message(
+    receiver: Address,
+    l1_token: Address,
+    amount: U256,
+) -> Vec<u8> {
+    let mut message = client::finalize_method_selector(false).to_vec();
+    message.extend_from_slice(receiver.as_bytes());
+    message.extend_from_slice(l1_token.as_bytes());
+    message.extend_from_slice(&u256_to_bytes_be(&amount));
+    message
+}
+
+fn mock_withdrawal(l1_batch_number: u32, l2_message_index: u32, l1_token: Address) -> Withdrawal {
+    let receiver = Address::repeat_byte(0x01);
+    let amount = U256::from(1_000);
+    let (sender, message) = if l1_token == Address::zero() {
+        (
+            L2_ETH_TOKEN_ADDRESS,
+            eth_withdrawal_message(receiver, amount),
+        )
+    } else {
+        let message = erc20_withdrawal_message(receiver, l1_token, amount);
+        (L2_ERC20_BRIDGE_ADDR, message)
+    };
+    Withdrawal {
+        l1_batch_number: L1BatchNumber(l1_batch_number),
+        l2_message_index,
+        tx_number_in_batch: 0,
+        tx_hash: H256::from_low_u64_be(l2_message_index.into()),
+        sender,
+        l1_token,
+        l1_receiver: receiver,
+        amount,
+        message,
+        merkle_proof: vec![H256::repeat_byte(0xaa); 11],
+    }
+}
+
+#[tokio::test]
+async fn extracting_withdrawals_from_executed_l1_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let receiver = Address::repeat_byte(0x01);
+    let l1_token = Address::repeat_byte(0x10);
+    let eth_message = eth_withdrawal_message(receiver, 1_000.into());
+    let erc20_message = erc20_withdrawal_message(receiver, l1_token, 2_000.into());
+    let messages_by_tx = [
+        vec![(L2_ETH_TOKEN_ADDRESS, eth_message.clone())],
+        vec![
+            (Address::repeat_byte(0x33), b"not a withdrawal".to_vec()),
+            (L2_ERC20_BRIDGE_ADDR, erc20_message.clone()),
+        ],
+    ];
+    let tx_hashes = store_executed_l1_batch_with_messages(&mut storage, 1, &messages_by_tx).await;
+    drop(storage);
+
+    let (mut finalizer, _) = create_finalizer(&pool, config()).await;
+    assert_eq!(finalizer.next_l1_batch, L1BatchNumber(1));
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(finalizer.next_l1_batch, L1BatchNumber(2));
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let header = storage
+        .blocks_dal()
+        .get_l1_batch_header(L1BatchNumber(1))
+        .await
+        .unwrap()
+        .unwrap();
+    let leaves: Vec<_> = header
+        .l2_to_l1_logs
+        .iter()
+        .map(|log| log.0.to_bytes())
+        .collect();
+    let tree_size = Some(L2ToL1Log::MIN_L2_L1_LOGS_TREE_SIZE);
+    let (_, eth_proof) =
+        MiniMerkleTree::new(leaves.iter().cloned(), tree_size).merkle_root_and_path(0);
+    let (_, erc20_proof) =
+        MiniMerkleTree::new(leaves.iter().cloned(), tree_size).merkle_root_and_path(2);
+
+    // The base token withdrawal is sent, and the ERC-20 withdrawal is pending since the token isn't allowlisted.
+    let sent_withdrawals = storage
+        .withdrawals_dal()
+        .get_sent_withdrawals()
+        .await
+        .unwrap();
+    assert_eq!(sent_withdrawals.len(), 1);
+    let eth_withdrawal = &sent_withdrawals[0].1;
+    assert_eq!(
+        *eth_withdrawal,
+        Withdrawal {
+            l1_batch_number: L1BatchNumber(1),
+            l2_message_index: 0,
+            tx_number_in_batch: 0,
+            tx_hash: tx_hashes[0],
+            sender: L2_ETH_TOKEN_ADDRESS,
+            l1_token: Address::zero(),
+            l1_receiver: receiver,
+            amount: 1_000.into(),
+            message: eth_message,
+            merkle_proof: eth_proof,
+        }
+    );
+
+    let pending_withdrawals = storage
+        .withdrawals_dal()
+        .get_pending_withdrawals(&[l1_token], 10)
+        .await
+        .unwrap();
+    assert_eq!(
+        pending_withdrawals,
+        [Withdrawal {
+            l1_batch_number: L1BatchNumber(1),
+            l2_message_index: 2,
+            tx_number_in_batch: 1,
+            tx_hash: tx_hashes[1],
+            sender: L2_ERC20_BRIDGE_ADDR,
+            l1_token,
+            l1_receiver: receiver,
+            amount: 2_000.into(),
+            message: erc20_message,
+            merkle_proof: erc20_proof,
+        }]
+    );
+    drop(storage);
+
+    // The restarted finalizer should not rescan the L1 batch.
+    let (finalizer, _) = create_finalizer(&pool, config()).await;
+    assert_eq!(finalizer.next_l1_batch, L1BatchNumber(2));
+}
+
+#[tokio::test]
+async fn finalizing_withdrawals_in_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    let withdrawals = [
+        mock_withdrawal(1, 0, Address::zero()),
+        mock_withdrawal(1, 1, Address::repeat_byte(0x10)),
+        mock_withdrawal(1, 2, Address::zero()),
+        mock_withdrawal(2, 0, Address::zero()),
+    ];
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .withdrawals_dal()
+        .insert_withdrawals(&withdrawals)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let (mut finalizer, client) = create_finalizer(&pool, config()).await;
+    finalizer.loop_iteration().await.unwrap();
+    let expected_tx = vec![withdrawals[0].clone(), withdrawals[2].clone()];
+    assert_eq!(client.sent_txs(), [expected_tx.clone()]);
+
+    // No new transactions should be sent while the previous one is not mined.
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    // The remaining base token withdrawal is finalized by the user.
+    client.mine_tx(1, true);
+    client.finalize_externally(&withdrawals[3]);
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let sent_withdrawals = storage
+        .withdrawals_dal()
+        .get_sent_withdrawals()
+        .await
+        .unwrap();
+    assert_eq!(sent_withdrawals, []);
+    let all_tokens = [Address::zero(), Address::repeat_byte(0x10)];
+    let pending_withdrawals = storage
+        .withdrawals_dal()
+        .get_pending_withdrawals(&all_tokens, 10)
+        .await
+        .unwrap();
+    assert_eq!(pending_withdrawals, [withdrawals[1].clone()]);
+}
+
+#[tokio::test]
+async fn retrying_failed_finalizations() {
+    let pool = ConnectionPool::test_pool().await;
+    let withdrawal = mock_withdrawal(1, 0, Address::zero());
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .withdrawals_dal()
+        .insert_withdrawals(&[withdrawal.clone()])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let (mut finalizer, client) = create_finalizer(&pool, config()).await;
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    // The transaction is mined, but the withdrawal is not finalized; it should be retried.
+    client.mine_tx(1, false);
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(
+        client.sent_txs(),
+        [[withdrawal.clone()], [withdrawal.clone()]]
+    );
+
+    // After the second failure, the withdrawal should be marked as failed.
+    client.mine_tx(2, false);
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 2);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let sent_withdrawals = storage
+        .withdrawals_dal()
+        .get_sent_withdrawals()
+        .await
+        .unwrap();
+    assert_eq!(sent_withdrawals, []);
+    let pending_withdrawals = storage
+        .withdrawals_dal()
+        .get_pending_withdrawals(&[Address::zero()], 10)
+        .await
+        .unwrap();
+    assert_eq!(pending_withdrawals, []);
+}
+
+#[tokio::test]
+async fn postponing_finalization_on_high_gas_price() {
+    let pool = ConnectionPool::test_pool().await;
+    let withdrawal = mock_withdrawal(1, 0, Address::zero());
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .withdrawals_dal()
+        .insert_withdrawals(&[withdrawal.clone()])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let config = WithdrawalFinalizerConfig {
+        max_gas_price_gwei: Some(10),
+        ..config()
+    };
+    let (mut finalizer, client) = create_finalizer(&pool, config).await;
+    client.set_gas_price(U256::from(20) * GWEI);
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 0);
+
+    client.set_gas_price(U256::from(5) * GWEI);
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs(), [[withdrawal]]);
+}
+
+async fn insert_single_withdrawal(pool: &ConnectionPool) -> Withdrawal {
+    let withdrawal = mock_withdrawal(1, 0, Address::zero());
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .withdrawals_dal()
+        .insert_withdrawals(&[withdrawal.clone()])
+        .await
+        .unwrap();
+    withdrawal
+}
+
+#[tokio::test]
+async fn finalization_tx_is_persisted_before_sending() {
+    let pool = ConnectionPool::test_pool().await;
+    let withdrawal = insert_single_withdrawal(&pool).await;
+    let (mut finalizer, client) = create_finalizer(&pool, config()).await;
+    client.set_fail_broadcasts(true);
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs(), [[withdrawal.clone()]]);
+    assert_eq!(client.broadcast_txs(), []);
+
+    // The persisted transaction should be rebroadcast rather than replaced with a new one.
+    client.set_fail_broadcasts(false);
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+    let signed_txs = client.signed_txs();
+    assert_eq!(signed_txs.len(), 1);
+    assert_eq!(client.broadcast_txs(), [signed_txs[0].hash]);
+
+    let tx_hash = client.mine_tx(1, true);
+    finalizer.loop_iteration().await.unwrap();
+    let mut storage = pool.access_storage().await.unwrap();
+    let sent_withdrawals = storage
+        .withdrawals_dal()
+        .get_sent_withdrawals()
+        .await
+        .unwrap();
+    assert_eq!(sent_withdrawals, []);
+    assert_eq!(tx_hash, signed_txs[0].hash);
+}
+
+#[tokio::test]
+async fn replacing_finalization_tx_with_bumped_fees() {
+    let pool = ConnectionPool::test_pool().await;
+    let withdrawal = insert_single_withdrawal(&pool).await;
+    let config = WithdrawalFinalizerConfig {
+        tx_resend_interval_sec: 0,
+        ..config()
+    };
+    let (mut finalizer, client) = create_finalizer(&pool, config).await;
+    client.set_gas_price(100.into());
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.signed_txs().len(), 1);
+
+    // The transaction is not mined, so it should be replaced.
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs(), [[withdrawal]]);
+    let signed_txs = client.signed_txs();
+    assert_eq!(signed_txs.len(), 2);
+    let (original_tx, replacement_tx) = (&signed_txs[0], &signed_txs[1]);
+    assert_eq!(replacement_tx.index, original_tx.index);
+    assert_eq!(replacement_tx.nonce, original_tx.nonce);
+    assert!(
+        replacement_tx.fees.max_fee_per_gas * 10 >= original_tx.fees.max_fee_per_gas * 11,
+        "{signed_txs:?}"
+    );
+    assert!(
+        replacement_tx.fees.max_priority_fee_per_gas * 10
+            >= original_tx.fees.max_priority_fee_per_gas * 11,
+        "{signed_txs:?}"
+    );
+    assert_eq!(
+        client.broadcast_txs(),
+        [original_tx.hash, replacement_tx.hash]
+    );
+
+    let tx_hash = client.mine_tx(1, true);
+    assert_eq!(tx_hash, replacement_tx.hash);
+    finalizer.loop_iteration().await.unwrap();
+    let mut storage = pool.access_storage().await.unwrap();
+    let sent_withdrawals = storage
+        .withdrawals_dal()
+        .get_sent_withdrawals()
+        .await
+        .unwrap();
+    assert_eq!(sent_withdrawals, []);
+    assert_eq!(client.sent_txs().len(), 1);
+}
+
+#[tokio::test]
+async fn processing_finalization_tx_with_used_nonce() {
+    let pool = ConnectionPool::test_pool().await;
+    let withdrawal = insert_single_withdrawal(&pool).await;
+    let (mut finalizer, client) = create_finalizer(&pool, config()).await;
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(client.sent_txs().len(), 1);
+
+    // The nonce is used by another transaction that doesn't finalize the withdrawal; it should be retried.
+    client.use_nonce();
+    finalizer.loop_iteration().await.unwrap();
+    assert_eq!(
+        client.sent_txs(),
+        [[withdrawal.clone()], [withdrawal.clone()]]
+    );
+    let signed_txs = client.signed_txs();
+    assert_eq!(signed_txs.len(), 2);
+    assert_eq!(signed_txs[1].nonce, 1);
+}
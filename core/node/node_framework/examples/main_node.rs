@@ -2,6 +2,8 @@
 //! This example defines a `ResourceProvider` that works using the main node env config, and
 //! initializes a single task with a health check server.
 
+use std::time::Duration;
+
 use anyhow::Context;
 use zksync_config::{
     configs::{
@@ -108,6 +110,16 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_task_dependencies(mut self) -> anyhow::Result<Self> {
+        // New L1 batches must not be produced until the Merkle tree is initialized; otherwise, the tree
+        // would lag behind from the start.
+        let tree_startup_deadline = Duration::from_secs(3_600);
+        self.node
+            .add_task_dependency("state_keeper", "metadata_calculator")
+            .set_startup_deadline("metadata_calculator", tree_startup_deadline);
+        Ok(self)
+    }
+
     fn build(self) -> ZkStackService {
         self.node
     }
@@ -132,6 +144,7 @@ fn main() -> anyhow::Result<()> {
         .add_metadata_calculator_layer()?
         .add_state_keeper_layer()?
         .add_healthcheck_layer()?
+        .add_task_dependencies()?
         .build()
         .run()?;
 
@@ -2,12 +2,15 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use zksync_config::{configs::chain::StateKeeperConfig, GasAdjusterConfig};
-use zksync_core::{fee_model::MainNodeFeeInputProvider, l1_gas_price::GasAdjuster};
+use zksync_core::{
+    fee_model::{CongestionMultiplierUpdater, MainNodeFeeInputProvider},
+    l1_gas_price::GasAdjuster,
+};
 use zksync_types::fee_model::FeeModelConfig;
 
 use crate::{
     implementations::resources::{
-        eth_interface::EthInterfaceResource, fee_input::FeeInputResource,
+        eth_interface::EthInterfaceResource, fee_input::FeeInputResource, pools::MasterPoolResource,
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -45,11 +48,17 @@ impl WiringLayer for SequencerFeeInputLayer {
             .context("GasAdjuster::new()")?;
         let gas_adjuster = Arc::new(adjuster);
 
-        let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-            gas_adjuster.clone(),
-            FeeModelConfig::from_state_keeper_config(&self.state_keeper_config),
-        ));
-        context.insert_resource(FeeInputResource(batch_fee_input_provider))?;
+        let fee_model_config = FeeModelConfig::from_state_keeper_config(&self.state_keeper_config);
+        let mut batch_fee_input_provider =
+            MainNodeFeeInputProvider::new(gas_adjuster.clone(), fee_model_config);
+        if let FeeModelConfig::V3(config) = fee_model_config {
+            let pool = context.get_resource::<MasterPoolResource>().await?;
+            let updater = CongestionMultiplierUpdater::new(pool.get_singleton().await?, config);
+            batch_fee_input_provider =
+                batch_fee_input_provider.with_congestion_multiplier(updater.multiplier());
+            context.add_task(Box::new(CongestionMultiplierTask { updater }));
+        }
+        context.insert_resource(FeeInputResource(Arc::new(batch_fee_input_provider)))?;
 
         context.add_task(Box::new(GasAdjusterTask { gas_adjuster }));
         Ok(())
@@ -71,3 +80,19 @@ impl Task for GasAdjusterTask {
         self.gas_adjuster.run(stop_receiver.0).await
     }
 }
+
+#[derive(Debug)]
+struct CongestionMultiplierTask {
+    updater: CongestionMultiplierUpdater,
+}
+
+#[async_trait::async_trait]
+impl Task for CongestionMultiplierTask {
+    fn name(&self) -> &'static str {
+        "congestion_multiplier_updater"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.updater.run(stop_receiver.0).await
+    }
+}
@@ -17,9 +17,11 @@ use crate::{
 /// corresponding resource collection and spawns an HTTP server exposing them.
 ///
 /// This layer expects other tasks to add health checks to the `ResourceCollection<HealthCheckResource>`.
+/// Besides these checks, the server exposes the `task_graph` health check containing the task graph of the node.
 ///
 /// ## Effects
 ///
+/// - Adds `task_graph` health check to the `ResourceCollection<HealthCheckResource>`.
 /// - Resolves `ResourceCollection<HealthCheckResource>`.
 /// - Adds `healthcheck_server` to the node.
 #[derive(Debug)]
@@ -35,6 +37,9 @@ impl WiringLayer for HealthCheckLayer {
         let healthchecks = node
             .get_resource_or_default::<ResourceCollection<HealthCheckResource>>()
             .await;
+        healthchecks
+            .push(HealthCheckResource::new(node.task_graph_health_check()))
+            .expect("Wiring stage");
 
         let task = HealthCheckTask {
             config: self.0,
@@ -1,5 +1,6 @@
 use zksync_core::metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig};
 use zksync_dal::ConnectionPool;
+use zksync_health_check::CheckHealth;
 use zksync_storage::RocksDB;
 
 use crate::{
@@ -87,4 +88,8 @@ impl Task for MetadataCalculatorTask {
 
         result
     }
+
+    fn readiness_check(&self) -> Option<Box<dyn CheckHealth>> {
+        Some(Box::new(self.metadata_calculator.tree_health_check()))
+    }
 }
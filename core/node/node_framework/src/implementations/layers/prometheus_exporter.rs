@@ -1,5 +1,5 @@
 use prometheus_exporter::PrometheusExporterConfig;
-use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_health_check::{CheckHealth, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 
 use crate::{
     implementations::resources::healthcheck::HealthCheckResource,
@@ -65,4 +65,8 @@ impl Task for PrometheusExporterTask {
         drop(self.prometheus_health_updater);
         res
     }
+
+    fn readiness_check(&self) -> Option<Box<dyn CheckHealth>> {
+        Some(Box::new(self.prometheus_health_updater.subscribe()))
+    }
 }
@@ -40,6 +40,10 @@ impl WiringLayer for MainBatchExecutorLayer {
             self.state_keeper_config.upload_witness_inputs_to_gcs,
             self.state_keeper_config.enum_index_migration_chunk_size(),
             false,
+        )
+        .with_state_keeper_db_tuning(
+            self.db_config.state_keeper_db_block_cache_size(),
+            &self.db_config.state_keeper_rocksdb,
         );
 
         context.insert_resource(BatchExecutorResource(Unique::new(Box::new(builder))))?;
@@ -1,3 +1,5 @@
+use zksync_health_check::ReactiveHealthCheck;
+
 use crate::{
     resource::{Resource, StoredResource},
     service::ZkStackService,
@@ -32,6 +34,11 @@ impl<'a> ServiceContext<'a> {
         self.service.runtime.handle()
     }
 
+    /// Returns a health check exposing the task graph of the service, e.g. to be served by a health check server.
+    pub fn task_graph_health_check(&self) -> ReactiveHealthCheck {
+        self.service.task_graph_health_check()
+    }
+
     /// Adds a task to the service.
     /// Added tasks will be launched after the wiring process will be finished.
     pub fn add_task(&mut self, task: Box<dyn Task>) -> &mut Self {
@@ -1,9 +1,19 @@
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
 
-use futures::{future::BoxFuture, FutureExt};
-use tokio::{runtime::Runtime, sync::watch};
+use anyhow::Context as _;
+use futures::future::BoxFuture;
+use tokio::{runtime::Runtime, sync::watch, task::JoinHandle};
+use zksync_health_check::{CheckHealth, Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 
-pub use self::{context::ServiceContext, stop_receiver::StopReceiver};
+pub use self::{
+    context::ServiceContext,
+    stop_receiver::StopReceiver,
+    task_graph::{TaskGraph, TaskGraphError, TaskNode, TaskState},
+};
 use crate::{
     resource::{ResourceId, StoredResource},
     task::Task,
@@ -12,6 +22,7 @@ use crate::{
 
 mod context;
 mod stop_receiver;
+mod task_graph;
 
 /// "Manager" class for a set of tasks. Collects all the resources and tasks,
 /// then runs tasks until completion.
@@ -23,9 +34,14 @@ mod stop_receiver;
 ///   - invokes a `wire` method on each added wiring layer. If any of the layers fails,
 ///     the service will return an error. If no layers have added a task, the service will
 ///     also return an error.
+///   - builds the task graph from dependencies declared via
+///     [`add_task_dependency`](ZkStackService::add_task_dependency). If the graph is invalid (e.g., contains a cycle), the service will return an error.
+///   - spawns tasks in the graph order; each task is spawned only after all its dependencies are ready
+///     according to their [readiness checks](Task::readiness_check). If a task doesn't become ready
+///     before its [startup deadline](ZkStackService::set_startup_deadline), the service is shut down.
 ///   - waits for any of the tasks to finish.
-///   - sends stop signal to all the tasks.
-///   - waits for the remaining tasks to finish.
+///   - sends stop signal to the remaining tasks in the reverse graph order, waiting for each task to finish
+///     before stopping the next one.
 ///   - calls `after_node_shutdown` hook for every task that has provided it.
 ///   - returns the result of the task that has finished.
 pub struct ZkStackService {
@@ -35,9 +51,13 @@ pub struct ZkStackService {
     layers: Vec<Box<dyn WiringLayer>>,
     /// Tasks added to the service.
     tasks: Vec<Box<dyn Task>>,
+    /// Dependencies between tasks as `(task, dependency)` pairs.
+    task_dependencies: Vec<(&'static str, &'static str)>,
+    /// Maximum durations for tasks to become ready after they are spawned.
+    startup_deadlines: HashMap<&'static str, Duration>,
+    /// Updater for the health check exposing the task graph.
+    task_graph_health: HealthUpdater,
 
-    /// Sender used to stop the tasks.
-    stop_sender: watch::Sender<bool>,
     /// Tokio runtime used to spawn tasks.
     runtime: Runtime,
 }
@@ -60,12 +80,14 @@ impl ZkStackService {
             .build()
             .unwrap();
 
-        let (stop_sender, _stop_receiver) = watch::channel(false);
+        let (_, task_graph_health) = ReactiveHealthCheck::new("task_graph");
         let self_ = Self {
             resources: HashMap::default(),
             layers: Vec::new(),
             tasks: Vec::new(),
-            stop_sender,
+            task_dependencies: Vec::new(),
+            startup_deadlines: HashMap::new(),
+            task_graph_health,
             runtime,
         };
 
@@ -80,6 +102,31 @@ impl ZkStackService {
         self
     }
 
+    /// Declares that `task` depends on `dependency`: it will be spawned only after `dependency` is ready,
+    /// and will be stopped before `dependency`. Tasks are referenced by their [names](Task::name).
+    /// Both tasks must be added to the service by wiring layers; otherwise, the service will fail to start.
+    pub fn add_task_dependency(
+        &mut self,
+        task: &'static str,
+        dependency: &'static str,
+    ) -> &mut Self {
+        self.task_dependencies.push((task, dependency));
+        self
+    }
+
+    /// Sets the maximum duration for the task to become ready after it's spawned. If the task isn't ready
+    /// by then according to its [readiness check](Task::readiness_check), the service will shut down with an error.
+    /// By default, the service waits for tasks to become ready indefinitely.
+    pub fn set_startup_deadline(&mut self, task: &'static str, deadline: Duration) -> &mut Self {
+        self.startup_deadlines.insert(task, deadline);
+        self
+    }
+
+    /// Returns a health check exposing the task graph and the current state of each task in its details.
+    pub fn task_graph_health_check(&self) -> ReactiveHealthCheck {
+        self.task_graph_health.subscribe()
+    }
+
     /// Runs the system.
     pub fn run(mut self) -> anyhow::Result<()> {
         // Initialize tasks.
@@ -109,92 +156,284 @@ impl ZkStackService {
             anyhow::bail!("One or more task weren't able to start");
         }
 
-        let mut tasks = Vec::new();
-        for task in std::mem::take(&mut self.tasks) {
-            let name = task.name().to_string();
-            let after_node_shutdown = task.after_node_shutdown();
-            let task_future = Box::pin(task.run(self.stop_receiver()));
-            let task_repr = TaskRepr {
-                name,
-                task: Some(task_future),
-                after_node_shutdown,
-            };
-            tasks.push(task_repr);
-        }
+        let tasks = std::mem::take(&mut self.tasks);
         if tasks.is_empty() {
             anyhow::bail!("No tasks to run");
         }
+        let task_names: Vec<_> = tasks.iter().map(|task| task.name()).collect();
+        let graph = TaskGraph::new(
+            &task_names,
+            &self.task_dependencies,
+            &self.startup_deadlines,
+        )
+        .context("Task graph is invalid")?;
+        tracing::info!(
+            "Tasks will be started in the following order: {:?}",
+            graph
+                .tasks()
+                .iter()
+                .map(|task| task.name)
+                .collect::<Vec<_>>()
+        );
+        tracing::debug!("Task graph:\n{}", graph.to_dot());
+
+        let mut tasks_by_name: HashMap<_, _> =
+            tasks.into_iter().map(|task| (task.name(), task)).collect();
+        let tasks = graph
+            .tasks()
+            .iter()
+            .map(|node| {
+                let task = tasks_by_name
+                    .remove(node.name)
+                    .expect("task names are validated when building graph");
+                TaskRepr::new(task)
+            })
+            .collect();
 
         // Wiring is now complete.
         for resource in self.resources.values_mut() {
             resource.stored_resource_wired();
         }
 
-        // Prepare tasks for running.
-        let rt_handle = self.runtime.handle().clone();
-        let join_handles: Vec<_> = tasks
-            .iter_mut()
-            .map(|task| {
-                let task = task.task.take().expect(
-                    "Tasks are created by the node and must be Some prior to calling this method",
-                );
-                rt_handle.spawn(task).fuse()
-            })
-            .collect();
-
-        // Run the tasks until one of them exits.
+        let mut tasks = ServiceTasks {
+            tasks,
+            graph,
+            health_updater: &self.task_graph_health,
+        };
+        // Start the tasks and run them until one of them exits.
         // TODO (QIT-24): wrap every task into a timeout to prevent hanging.
-        let (resolved, idx, remaining) = self
-            .runtime
-            .block_on(futures::future::select_all(join_handles));
-        let task_name = tasks[idx].name.clone();
-        let failure = match resolved {
-            Ok(Ok(())) => {
-                tracing::info!("Task {task_name} completed");
-                false
-            }
-            Ok(Err(err)) => {
-                tracing::error!("Task {task_name} exited with an error: {err}");
-                true
+        let failure = self.runtime.block_on(async {
+            let outcome = tasks.start().await;
+            if let StartupOutcome::DeadlineExceeded { task, deadline } = outcome {
+                tracing::error!("Task {task} hasn't become ready in {deadline:?}");
+                return Some(format!("Task {task} hasn't become ready in {deadline:?}"));
             }
-            Err(_) => {
-                tracing::error!("Task {task_name} panicked");
-                true
-            }
-        };
+            tasks.wait_for_any_task().await
+        });
 
         // Send stop signal to remaining tasks and wait for them to finish.
         // Given that we are shutting down, we do not really care about returned values.
-        self.stop_sender.send(true).ok();
-        self.runtime.block_on(futures::future::join_all(remaining));
+        self.runtime.block_on(tasks.stop());
 
         // Call after_node_shutdown hooks.
         let local_set = tokio::task::LocalSet::new();
-        let join_handles = tasks.iter_mut().filter_map(|task| {
+        let join_handles = tasks.tasks.iter_mut().filter_map(|task| {
             task.after_node_shutdown
                 .take()
                 .map(|task| local_set.spawn_local(task))
         });
         local_set.block_on(&self.runtime, futures::future::join_all(join_handles));
 
-        if failure {
-            anyhow::bail!("Task {task_name} failed");
+        if let Some(failure) = failure {
+            anyhow::bail!(failure);
         } else {
             Ok(())
         }
     }
+}
+
+/// Outcome of starting the service tasks.
+#[derive(Debug)]
+enum StartupOutcome {
+    /// All tasks are started and ready.
+    Started,
+    /// One of the spawned tasks has finished before all tasks were started.
+    TaskFinished,
+    /// A task hasn't become ready before its startup deadline.
+    DeadlineExceeded {
+        task: &'static str,
+        deadline: Duration,
+    },
+}
+
+/// Tasks of the service in the startup order together with their graph.
+struct ServiceTasks<'a> {
+    tasks: Vec<TaskRepr>,
+    graph: TaskGraph,
+    health_updater: &'a HealthUpdater,
+}
+
+impl ServiceTasks<'_> {
+    /// Interval between readiness checks of a starting task.
+    const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    fn set_state(&mut self, idx: usize, state: TaskState) {
+        self.graph.set_state(idx, state);
+        self.update_health();
+    }
 
-    pub(crate) fn stop_receiver(&self) -> StopReceiver {
-        StopReceiver(self.stop_sender.subscribe())
+    fn update_health(&self) {
+        let tasks = self.graph.tasks();
+        let status = if tasks.iter().all(|task| task.state == TaskState::Ready) {
+            HealthStatus::Ready
+        } else if tasks.iter().any(|task| task.state == TaskState::Finished) {
+            HealthStatus::ShutDown
+        } else {
+            HealthStatus::NotReady
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(&self.graph));
+    }
+
+    /// Spawns tasks in the startup order, waiting for dependencies of each task to become ready. Afterwards,
+    /// waits for all tasks to become ready.
+    async fn start(&mut self) -> StartupOutcome {
+        self.update_health();
+        for idx in 0..self.tasks.len() {
+            let dependencies: Vec<_> = self.graph.dependency_indices(idx).collect();
+            for dependency_idx in dependencies {
+                let outcome = self.wait_until_ready(dependency_idx).await;
+                if !matches!(outcome, StartupOutcome::Started) {
+                    return outcome;
+                }
+            }
+            self.spawn(idx);
+        }
+
+        for idx in 0..self.tasks.len() {
+            let outcome = self.wait_until_ready(idx).await;
+            if !matches!(outcome, StartupOutcome::Started) {
+                return outcome;
+            }
+        }
+        tracing::info!("All tasks are started and ready");
+        StartupOutcome::Started
+    }
+
+    fn spawn(&mut self, idx: usize) {
+        let task = &mut self.tasks[idx];
+        let future = task
+            .task
+            .take()
+            .expect("Tasks are created by the node and must be Some prior to calling this method");
+        task.join_handle = Some(tokio::spawn(future));
+        task.started_at = Some(Instant::now());
+        tracing::info!("Spawned task {}", task.name);
+
+        let state = if task.readiness_check.is_some() {
+            TaskState::Starting
+        } else {
+            TaskState::Ready
+        };
+        self.set_state(idx, state);
+    }
+
+    /// Waits until a spawned task is ready according to its readiness check.
+    async fn wait_until_ready(&mut self, idx: usize) -> StartupOutcome {
+        let node = &self.graph.tasks()[idx];
+        if node.state == TaskState::Ready {
+            return StartupOutcome::Started;
+        }
+        let deadline = node.startup_deadline;
+        let task = &self.tasks[idx];
+        let started_at = task.started_at.expect("task is not spawned");
+        let readiness_check = task
+            .readiness_check
+            .as_ref()
+            .expect("task without readiness check is ready once spawned");
+
+        tracing::info!("Waiting for task {} to become ready", task.name);
+        while !readiness_check.check_health().await.status().is_healthy() {
+            if self.has_finished_tasks() {
+                return StartupOutcome::TaskFinished;
+            }
+            if let Some(deadline) = deadline {
+                if started_at.elapsed() > deadline {
+                    return StartupOutcome::DeadlineExceeded {
+                        task: task.name,
+                        deadline,
+                    };
+                }
+            }
+            tokio::time::sleep(Self::READINESS_POLL_INTERVAL).await;
+        }
+        tracing::info!(
+            "Task {} has become ready in {:?}",
+            task.name,
+            started_at.elapsed()
+        );
+        self.set_state(idx, TaskState::Ready);
+        StartupOutcome::Started
+    }
+
+    fn has_finished_tasks(&self) -> bool {
+        self.tasks.iter().any(|task| {
+            task.join_handle
+                .as_ref()
+                .map_or(false, JoinHandle::is_finished)
+        })
+    }
+
+    /// Waits for any of the spawned tasks to finish. Returns the failure message if the task has failed.
+    async fn wait_for_any_task(&mut self) -> Option<String> {
+        let (indices, join_handles): (Vec<_>, Vec<_>) = self
+            .tasks
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(idx, task)| Some((idx, task.join_handle.as_mut()?)))
+            .unzip();
+        let (resolved, i, _) = futures::future::select_all(join_handles).await;
+        let idx = indices[i];
+        self.tasks[idx].join_handle = None;
+        self.set_state(idx, TaskState::Finished);
+
+        let task_name = self.tasks[idx].name;
+        match resolved {
+            Ok(Ok(())) => {
+                tracing::info!("Task {task_name} completed");
+                None
+            }
+            Ok(Err(err)) => {
+                tracing::error!("Task {task_name} exited with an error: {err}");
+                Some(format!("Task {task_name} failed"))
+            }
+            Err(_) => {
+                tracing::error!("Task {task_name} panicked");
+                Some(format!("Task {task_name} failed"))
+            }
+        }
+    }
+
+    /// Stops spawned tasks in the reverse startup order, so that each task is stopped after all tasks
+    /// depending on it.
+    async fn stop(&mut self) {
+        for idx in (0..self.tasks.len()).rev() {
+            let Some(join_handle) = self.tasks[idx].join_handle.take() else {
+                continue;
+            };
+            tracing::info!("Stopping task {}", self.tasks[idx].name);
+            self.set_state(idx, TaskState::Stopping);
+            self.tasks[idx].stop_sender.send_replace(true);
+            join_handle.await.ok();
+            self.set_state(idx, TaskState::Finished);
+        }
     }
 }
 
 struct TaskRepr {
-    name: String,
+    name: &'static str,
     task: Option<BoxFuture<'static, anyhow::Result<()>>>,
+    readiness_check: Option<Box<dyn CheckHealth>>,
     after_node_shutdown: Option<BoxFuture<'static, ()>>,
+    stop_sender: watch::Sender<bool>,
+    join_handle: Option<JoinHandle<anyhow::Result<()>>>,
+    started_at: Option<Instant>,
 }
 
+impl TaskRepr {
+    fn new(task: Box<dyn Task>) -> Self {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        Self {
+            name: task.name(),
+            readiness_check: task.readiness_check(),
+            after_node_shutdown: task.after_node_shutdown(),
+            task: Some(Box::pin(task.run(StopReceiver(stop_receiver)))),
+            stop_sender,
+            join_handle: None,
+            started_at: None,
+        }
+    }
+}
 impl fmt::Debug for TaskRepr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TaskRepr")
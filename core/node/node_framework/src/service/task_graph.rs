@@ -0,0 +1,274 @@
+//! Task dependency graph used to determine the startup and shutdown order of tasks.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write as _,
+    time::Duration,
+};
+
+use serde::Serialize;
+
+/// An error that can occur when building a [`TaskGraph`].
+#[derive(Debug, thiserror::Error, PartialEq)]
+#[non_exhaustive]
+pub enum TaskGraphError {
+    #[error("Task {0} is added to the service more than once")]
+    DuplicateTask(&'static str),
+    #[error("Task {task} depends on task {dependency}, which is not added to the service")]
+    UnknownDependency {
+        task: &'static str,
+        dependency: &'static str,
+    },
+    #[error("Startup deadline is set for task {0}, which is not added to the service")]
+    UnknownDeadlineTask(&'static str),
+    #[error("Task dependencies contain a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<&'static str>),
+}
+
+/// State of a task tracked by the service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    /// Task waits for its dependencies to become ready.
+    Pending,
+    /// Task is spawned, but its readiness check doesn't report it as healthy yet.
+    Starting,
+    /// Task is ready; dependent tasks can be started.
+    Ready,
+    /// Task has received the stop signal and is shutting down.
+    Stopping,
+    /// Task has finished.
+    Finished,
+}
+
+/// Node of a [`TaskGraph`].
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskNode {
+    pub name: &'static str,
+    /// Tasks that must be ready before this task is started.
+    pub dependencies: Vec<&'static str>,
+    /// Maximum duration for the task to become ready after it's spawned.
+    #[serde(
+        rename = "startup_deadline_ms",
+        serialize_with = "serialize_deadline",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub startup_deadline: Option<Duration>,
+    pub state: TaskState,
+}
+
+fn serialize_deadline<S: serde::Serializer>(
+    deadline: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let millis = deadline.map(|deadline| deadline.as_millis() as u64);
+    millis.serialize(serializer)
+}
+
+/// Directed acyclic graph of tasks with nodes in the startup order.
+///
+/// A task is started only after all its dependencies are ready, and is stopped before any of its dependencies.
+/// Independent tasks retain the order in which they were added to the service, so the startup order
+/// is deterministic.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskGraph {
+    tasks: Vec<TaskNode>,
+}
+
+impl TaskGraph {
+    /// Builds a graph for tasks with the specified names (in the order they were added to the service).
+    pub fn new(
+        task_names: &[&'static str],
+        dependencies: &[(&'static str, &'static str)],
+        startup_deadlines: &HashMap<&'static str, Duration>,
+    ) -> Result<Self, TaskGraphError> {
+        let mut indices = HashMap::with_capacity(task_names.len());
+        for (i, &name) in task_names.iter().enumerate() {
+            if indices.insert(name, i).is_some() {
+                return Err(TaskGraphError::DuplicateTask(name));
+            }
+        }
+        if let Some(&task) = startup_deadlines
+            .keys()
+            .find(|task| !indices.contains_key(*task))
+        {
+            return Err(TaskGraphError::UnknownDeadlineTask(task));
+        }
+
+        let mut task_dependencies = vec![vec![]; task_names.len()];
+        for &(task, dependency) in dependencies {
+            let unknown_dependency = || TaskGraphError::UnknownDependency { task, dependency };
+            let task_idx = *indices.get(task).ok_or_else(unknown_dependency)?;
+            let dependency_idx = *indices.get(dependency).ok_or_else(unknown_dependency)?;
+            if !task_dependencies[task_idx].contains(&dependency_idx) {
+                task_dependencies[task_idx].push(dependency_idx);
+            }
+        }
+
+        let order = Self::sort_topologically(&task_dependencies).map_err(|cycle| {
+            TaskGraphError::Cycle(cycle.into_iter().map(|idx| task_names[idx]).collect())
+        })?;
+        let tasks = order
+            .into_iter()
+            .map(|idx| {
+                let name = task_names[idx];
+                TaskNode {
+                    name,
+                    dependencies: task_dependencies[idx]
+                        .iter()
+                        .map(|&dependency_idx| task_names[dependency_idx])
+                        .collect(),
+                    startup_deadline: startup_deadlines.get(name).copied(),
+                    state: TaskState::Pending,
+                }
+            })
+            .collect();
+        Ok(Self { tasks })
+    }
+
+    /// Returns task indices in the startup order, or a dependency cycle if there is one.
+    fn sort_topologically(dependencies: &[Vec<usize>]) -> Result<Vec<usize>, Vec<usize>> {
+        let mut order = Vec::with_capacity(dependencies.len());
+        let mut is_sorted = vec![false; dependencies.len()];
+        // Each iteration picks the first task (in the insertion order) with all dependencies sorted,
+        // which makes the order deterministic.
+        while order.len() < dependencies.len() {
+            let next_idx = (0..dependencies.len()).find(|&idx| {
+                !is_sorted[idx] && dependencies[idx].iter().all(|&dep_idx| is_sorted[dep_idx])
+            });
+            let Some(next_idx) = next_idx else {
+                return Err(Self::find_cycle(dependencies, &is_sorted));
+            };
+            is_sorted[next_idx] = true;
+            order.push(next_idx);
+        }
+        Ok(order)
+    }
+
+    /// Finds a cycle among unsorted tasks. Each of these tasks has at least one unsorted dependency,
+    /// so following such dependencies is guaranteed to eventually revisit a task.
+    fn find_cycle(dependencies: &[Vec<usize>], is_sorted: &[bool]) -> Vec<usize> {
+        let mut idx = is_sorted.iter().position(|&sorted| !sorted).unwrap();
+        let mut path = vec![];
+        let mut visited = HashSet::new();
+        while visited.insert(idx) {
+            path.push(idx);
+            idx = *dependencies[idx]
+                .iter()
+                .find(|&&dep_idx| !is_sorted[dep_idx])
+                .unwrap();
+        }
+        let cycle_start = path.iter().position(|&path_idx| path_idx == idx).unwrap();
+        let mut cycle = path.split_off(cycle_start);
+        cycle.push(idx);
+        cycle
+    }
+
+    /// Returns tasks in the startup order.
+    pub fn tasks(&self) -> &[TaskNode] {
+        &self.tasks
+    }
+
+    pub(super) fn set_state(&mut self, idx: usize, state: TaskState) {
+        self.tasks[idx].state = state;
+    }
+
+    /// Returns indices of the dependencies of the specified task.
+    pub(super) fn dependency_indices(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        self.tasks[idx].dependencies.iter().map(|&dependency| {
+            self.tasks
+                .iter()
+                .position(|task| task.name == dependency)
+                .expect("dependencies are validated when building graph")
+        })
+    }
+
+    /// Renders this graph in the Graphviz DOT format. Edges point from a task to its dependencies.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph tasks {\n");
+        for task in &self.tasks {
+            writeln!(
+                dot,
+                "    \"{}\" [label=\"{} ({:?})\"];",
+                task.name, task.name, task.state
+            )
+            .unwrap();
+            for dependency in &task.dependencies {
+                writeln!(dot, "    \"{}\" -> \"{dependency}\";", task.name).unwrap();
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_names(graph: &TaskGraph) -> Vec<&'static str> {
+        graph.tasks().iter().map(|task| task.name).collect()
+    }
+
+    #[test]
+    fn graph_without_dependencies_retains_insertion_order() {
+        let graph = TaskGraph::new(&["c", "a", "b"], &[], &HashMap::new()).unwrap();
+        assert_eq!(task_names(&graph), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn graph_with_dependencies() {
+        let dependencies = [("api", "tree"), ("api", "genesis"), ("tree", "genesis")];
+        let deadlines = HashMap::from([("tree", Duration::from_secs(10))]);
+        let graph = TaskGraph::new(
+            &["api", "prometheus", "tree", "genesis"],
+            &dependencies,
+            &deadlines,
+        )
+        .unwrap();
+        assert_eq!(task_names(&graph), ["prometheus", "genesis", "tree", "api"]);
+
+        let api_dependencies: Vec<_> = graph.dependency_indices(3).collect();
+        assert_eq!(api_dependencies, [2, 1]);
+        assert_eq!(
+            graph.tasks()[2].startup_deadline,
+            Some(Duration::from_secs(10))
+        );
+        assert!(graph
+            .tasks()
+            .iter()
+            .all(|task| task.state == TaskState::Pending));
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"api\" -> \"tree\";"), "{dot}");
+        assert!(dot.contains("\"tree\" -> \"genesis\";"), "{dot}");
+    }
+
+    #[test]
+    fn graph_errors() {
+        let err = TaskGraph::new(&["a", "b", "a"], &[], &HashMap::new()).unwrap_err();
+        assert_eq!(err, TaskGraphError::DuplicateTask("a"));
+
+        let err = TaskGraph::new(&["a", "b"], &[("a", "c")], &HashMap::new()).unwrap_err();
+        assert_eq!(
+            err,
+            TaskGraphError::UnknownDependency {
+                task: "a",
+                dependency: "c"
+            }
+        );
+
+        let deadlines = HashMap::from([("c", Duration::from_secs(1))]);
+        let err = TaskGraph::new(&["a", "b"], &[], &deadlines).unwrap_err();
+        assert_eq!(err, TaskGraphError::UnknownDeadlineTask("c"));
+
+        let dependencies = [("a", "b"), ("b", "c"), ("c", "b")];
+        let err =
+            TaskGraph::new(&["a", "b", "c", "d"], &dependencies, &HashMap::new()).unwrap_err();
+        assert_eq!(err, TaskGraphError::Cycle(vec!["b", "c", "b"]));
+        assert_eq!(
+            err.to_string(),
+            "Task dependencies contain a cycle: b -> c -> b"
+        );
+    }
+}
@@ -2,6 +2,7 @@
 //! is stopped.
 
 use futures::future::BoxFuture;
+use zksync_health_check::CheckHealth;
 
 use crate::service::StopReceiver;
 
@@ -24,6 +25,15 @@ pub trait Task: 'static + Send {
     /// Each task is expected to perform the required cleanup after receiving the stop signal.
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()>;
 
+    /// Health check used to determine whether the task is ready after it's spawned. Tasks depending on this task
+    /// (see [`ZkStackService::add_task_dependency()`](crate::service::ZkStackService::add_task_dependency()))
+    /// are started only once the check reports a healthy status.
+    ///
+    /// If not provided, the task is considered ready as soon as it's spawned.
+    fn readiness_check(&self) -> Option<Box<dyn CheckHealth>> {
+        None
+    }
+
     /// Asynchronous hook that will be called after *each task* has finished their cleanup.
     /// It is guaranteed that no other task is running at this point, e.g. `ZkStackService` will invoke
     /// this hook sequentially for each task.
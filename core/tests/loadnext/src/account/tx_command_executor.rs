@@ -53,6 +53,7 @@ impl AccountLifespan {
                 self.execute_loadnext_contract(command, ExecutionType::L1)
                     .await
             }
+            TxType::L2Transfer => self.execute_transfer(command).await,
         }
     }
 
@@ -253,6 +254,47 @@ impl AccountLifespan {
         Ok(self.apply_modifier(tx, command.modifier).await)
     }
 
+    async fn execute_transfer(&mut self, command: &TxCommand) -> Result<SubmitResult, ClientError> {
+        let tx = self.build_transfer(command).await?;
+        self.execute_submit(tx, command.modifier).await
+    }
+
+    async fn build_transfer(&self, command: &TxCommand) -> Result<L2Tx, ClientError> {
+        let wallet = self.wallet.wallet.clone();
+
+        let mut builder = wallet
+            .start_transfer()
+            .to(command.to)
+            .amount(command.amount)
+            .token(self.main_l2_token);
+
+        let fee = builder
+            .estimate_fee(Some(get_approval_based_paymaster_input_for_estimation(
+                self.paymaster_address,
+                self.main_l2_token,
+                MIN_ALLOWANCE_FOR_PAYMASTER_ESTIMATE.into(),
+            )))
+            .await?;
+        builder = builder.fee(fee.clone());
+
+        let paymaster_params = get_approval_based_paymaster_input(
+            self.paymaster_address,
+            self.main_l2_token,
+            fee.max_total_fee(),
+            Vec::new(),
+        );
+        builder = builder.fee(fee);
+        builder = builder.paymaster_params(paymaster_params);
+
+        if let Some(nonce) = self.current_nonce {
+            builder = builder.nonce(nonce);
+        }
+
+        let tx = builder.tx().await.map_err(Self::tx_creation_error)?;
+
+        Ok(self.apply_modifier(tx, command.modifier).await)
+    }
+
     async fn execute_deploy_contract(
         &mut self,
         command: &TxCommand,
@@ -84,6 +84,8 @@ pub struct AccountPool {
     pub accounts: VecDeque<TestWallet>,
     /// Pool of addresses of the test accounts.
     pub addresses: AddressPool,
+    /// Hex-encoded master seed used to generate the test accounts and their operations.
+    pub seed: String,
 }
 
 impl AccountPool {
@@ -165,6 +167,7 @@ impl AccountPool {
             master_wallet,
             accounts,
             addresses: AddressPool::new(addresses),
+            seed: rng.seed_hex(),
         })
     }
 }
@@ -0,0 +1,199 @@
+//! Statistics on L1 batches sealed by the server during the loadtest.
+
+use serde::Serialize;
+use zksync::{error::ClientError, ZksNamespaceClient};
+use zksync_types::{L1BatchNumber, U64};
+
+/// Information about a single sealed L1 batch.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchInfo {
+    pub number: L1BatchNumber,
+    pub timestamp: u64,
+    pub tx_count: usize,
+    pub miniblock_count: u32,
+}
+
+/// Minimum, maximum and mean of a set of values.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Distribution {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Distribution {
+    fn new(values: impl Iterator<Item = f64>) -> Option<Self> {
+        let mut count = 0_usize;
+        let mut sum = 0.0;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for value in values {
+            count += 1;
+            sum += value;
+            min = min.min(value);
+            max = max.max(value);
+        }
+        (count > 0).then(|| Self {
+            min,
+            max,
+            mean: sum / count as f64,
+        })
+    }
+}
+
+/// Batch-fill statistics for L1 batches sealed during the loadtest.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchStats {
+    pub first_batch: u32,
+    pub last_batch: u32,
+    pub batch_count: usize,
+    /// Number of transactions (both L1 and L2) per batch.
+    pub txs_per_batch: Distribution,
+    pub miniblocks_per_batch: Distribution,
+    /// Difference between timestamps of consecutive batches in seconds. `None` if there is a single batch.
+    pub batch_interval_secs: Option<Distribution>,
+}
+
+impl BatchStats {
+    /// Computes statistics for the provided batches ordered by their number. Returns `None` if there are no batches.
+    pub fn new(batches: &[BatchInfo]) -> Option<Self> {
+        let first_batch = batches.first()?;
+        let last_batch = batches.last()?;
+        let txs_per_batch = Distribution::new(batches.iter().map(|batch| batch.tx_count as f64))?;
+        let miniblocks_per_batch =
+            Distribution::new(batches.iter().map(|batch| batch.miniblock_count.into()))?;
+        let batch_intervals = batches
+            .windows(2)
+            .map(|window| window[1].timestamp.saturating_sub(window[0].timestamp) as f64);
+
+        Some(Self {
+            first_batch: first_batch.number.0,
+            last_batch: last_batch.number.0,
+            batch_count: batches.len(),
+            txs_per_batch,
+            miniblocks_per_batch,
+            batch_interval_secs: Distribution::new(batch_intervals),
+        })
+    }
+
+    /// Fetches information about batches in the `(from_batch, to_batch]` range from the server
+    /// and computes statistics for them.
+    pub async fn fetch(
+        client: &impl ZksNamespaceClient,
+        from_batch: L1BatchNumber,
+        to_batch: L1BatchNumber,
+    ) -> Result<Option<Self>, ClientError> {
+        let mut batches = vec![];
+        for number in (from_batch.0 + 1)..=to_batch.0 {
+            let number = L1BatchNumber(number);
+            let Some(details) = client.get_l1_batch_details(number).await? else {
+                break;
+            };
+            let Some((first_miniblock, last_miniblock)) =
+                client.get_miniblock_range(number).await?
+            else {
+                break;
+            };
+            batches.push(BatchInfo {
+                number,
+                timestamp: details.base.timestamp,
+                tx_count: details.base.l1_tx_count + details.base.l2_tx_count,
+                miniblock_count: (last_miniblock - first_miniblock + U64::one()).as_u32(),
+            });
+        }
+        Ok(Self::new(&batches))
+    }
+
+    pub fn report(&self) {
+        tracing::info!(
+            "Sealed {} L1 batches (#{}..=#{}) during the test",
+            self.batch_count,
+            self.first_batch,
+            self.last_batch
+        );
+        tracing::info!("Transactions per batch: {:?}", self.txs_per_batch);
+        tracing::info!("Miniblocks per batch: {:?}", self.miniblocks_per_batch);
+        if let Some(intervals) = &self.batch_interval_secs {
+            tracing::info!("Interval between batches (sec): {intervals:?}");
+        }
+    }
+}
+
+/// Returns the number of the latest sealed L1 batch.
+pub async fn latest_batch_number(
+    client: &impl ZksNamespaceClient,
+) -> Result<L1BatchNumber, ClientError> {
+    let number = client.get_l1_batch_number().await?;
+    Ok(L1BatchNumber(number.as_u32()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch(number: u32, timestamp: u64, tx_count: usize, miniblock_count: u32) -> BatchInfo {
+        BatchInfo {
+            number: L1BatchNumber(number),
+            timestamp,
+            tx_count,
+            miniblock_count,
+        }
+    }
+
+    #[test]
+    fn no_batch_stats_for_empty_range() {
+        assert_eq!(BatchStats::new(&[]), None);
+    }
+
+    #[test]
+    fn single_batch_stats() {
+        let stats = BatchStats::new(&[batch(5, 100, 10, 2)]).unwrap();
+        assert_eq!(stats.first_batch, 5);
+        assert_eq!(stats.last_batch, 5);
+        assert_eq!(stats.batch_count, 1);
+        let expected = Distribution {
+            min: 10.0,
+            max: 10.0,
+            mean: 10.0,
+        };
+        assert_eq!(stats.txs_per_batch, expected);
+        assert_eq!(stats.batch_interval_secs, None);
+    }
+
+    #[test]
+    fn multiple_batch_stats() {
+        let batches = [
+            batch(1, 100, 10, 2),
+            batch(2, 103, 20, 4),
+            batch(3, 110, 30, 3),
+        ];
+        let stats = BatchStats::new(&batches).unwrap();
+        assert_eq!(stats.first_batch, 1);
+        assert_eq!(stats.last_batch, 3);
+        assert_eq!(stats.batch_count, 3);
+        assert_eq!(
+            stats.txs_per_batch,
+            Distribution {
+                min: 10.0,
+                max: 30.0,
+                mean: 20.0,
+            }
+        );
+        assert_eq!(
+            stats.miniblocks_per_batch,
+            Distribution {
+                min: 2.0,
+                max: 4.0,
+                mean: 3.0,
+            }
+        );
+        assert_eq!(
+            stats.batch_interval_secs,
+            Some(Distribution {
+                min: 3.0,
+                max: 7.0,
+                mean: 5.0,
+            })
+        );
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     rng::{LoadtestRng, WeightedRandom},
 };
 
-static WEIGHTS: OnceCell<[(TxType, f32); 5]> = OnceCell::new();
+static WEIGHTS: OnceCell<[(TxType, f32); 6]> = OnceCell::new();
 
 /// Type of transaction. It doesn't copy the zkSync operation list, because
 /// it divides some transactions in subcategories (e.g. to new account / to existing account; to self / to other; etc)/
@@ -22,6 +22,8 @@ pub enum TxType {
     DeployContract,
     L1Execute,
     L2Execute,
+    /// ERC-20 transfer of the main token on L2.
+    L2Transfer,
 }
 
 impl TxType {
@@ -31,6 +33,7 @@ impl TxType {
                 (TxType::Deposit, transaction_weights.deposit),
                 (TxType::L2Execute, transaction_weights.l2_transactions),
                 (TxType::L1Execute, transaction_weights.l1_transactions),
+                (TxType::L2Transfer, transaction_weights.l2_transfers),
                 (TxType::WithdrawToSelf, transaction_weights.withdrawal / 2.0),
                 (
                     TxType::WithdrawToOther,
@@ -61,6 +64,7 @@ impl TxType {
             Self::WithdrawToOther,
             Self::L1Execute,
             Self::L2Execute,
+            Self::L2Transfer,
         ]
     }
 
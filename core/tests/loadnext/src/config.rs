@@ -136,6 +136,11 @@ pub struct LoadtestConfig {
     /// in an eventual test failure anyway (e.g., a failure processing transactions).
     #[serde(default)]
     pub fail_fast: bool,
+
+    /// Path to write the machine-readable JSON summary of the test to (TPS, latencies, batch-fill statistics, etc.).
+    /// Summaries of different runs can be compared to detect performance regressions.
+    #[serde(default)]
+    pub summary_report_path: Option<PathBuf>,
 }
 
 fn default_max_inflight_txs() -> usize {
@@ -301,6 +306,10 @@ pub struct TransactionWeights {
     pub withdrawal: f32,
     pub l1_transactions: f32,
     pub l2_transactions: f32,
+    /// Weight of L2 transfers of the main token. Defaults to 0 if weights are loaded from env
+    /// for backward compatibility.
+    #[serde(default)]
+    pub l2_transfers: f32,
 }
 
 impl TransactionWeights {
@@ -316,6 +325,7 @@ impl Default for TransactionWeights {
             withdrawal: 0.5,
             l1_transactions: 0.05,
             l2_transactions: 1.0,
+            l2_transfers: 0.5,
         }
     }
 }
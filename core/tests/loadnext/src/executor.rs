@@ -14,17 +14,18 @@ use zksync_eth_client::{BoundEthInterface, EthInterface};
 use zksync_eth_signer::PrivateKeySigner;
 use zksync_system_constants::MAX_L1_TRANSACTION_GAS_LIMIT;
 use zksync_types::{
-    api::BlockNumber, tokens::ETHEREUM_ADDRESS, Address, Nonce,
+    api::BlockNumber, tokens::ETHEREUM_ADDRESS, Address, L1BatchNumber, Nonce,
     REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
 
 use crate::{
     account::AccountLifespan,
     account_pool::AccountPool,
+    batch_stats::{self, BatchStats},
     config::{ExecutionConfig, LoadtestConfig, RequestLimiters},
     constants::*,
     report::ReportBuilder,
-    report_collector::{LoadtestResult, ReportCollector},
+    report_collector::{LoadtestResult, LoadtestSummary, ReportCollector},
     utils::format_eth,
 };
 
@@ -583,6 +584,8 @@ impl Executor {
             account_tasks.extend(new_account_futures);
         }
 
+        let provider = &self.pool.master_wallet.provider;
+        let start_batch = batch_stats::latest_batch_number(provider).await?;
         report_sender
             .send(ReportBuilder::build_init_complete_report())
             .await
@@ -600,7 +603,47 @@ impl Executor {
         future::try_join_all(account_tasks).await?;
         tracing::info!("All the spawned tasks are completed");
 
-        Ok(report_collector_future.await?)
+        let collected_report = report_collector_future.await?;
+        if let Some(mut summary) = collected_report.summary {
+            summary.seed = Some(self.pool.seed.clone());
+            summary.batches = self.batch_stats(start_batch).await;
+            self.write_summary(&summary).await?;
+        }
+        Ok(collected_report.result)
+    }
+
+    /// Fetches statistics for L1 batches sealed after `start_batch`. Errors are logged and ignored,
+    /// since they don't influence the test outcome.
+    async fn batch_stats(&self, start_batch: L1BatchNumber) -> Option<BatchStats> {
+        let provider = &self.pool.master_wallet.provider;
+        let stats = async {
+            let end_batch = batch_stats::latest_batch_number(provider).await?;
+            BatchStats::fetch(provider, start_batch, end_batch).await
+        };
+        match stats.await {
+            Ok(Some(stats)) => {
+                stats.report();
+                Some(stats)
+            }
+            Ok(None) => {
+                tracing::info!("No L1 batches were sealed during the test");
+                None
+            }
+            Err(err) => {
+                tracing::warn!("Failed fetching L1 batch statistics: {err}");
+                None
+            }
+        }
+    }
+
+    async fn write_summary(&self, summary: &LoadtestSummary) -> anyhow::Result<()> {
+        let Some(path) = &self.config.summary_report_path else {
+            return Ok(());
+        };
+        let summary = serde_json::to_string_pretty(summary)?;
+        tokio::fs::write(path, summary).await?;
+        tracing::info!("Test summary was written to {}", path.display());
+        Ok(())
     }
 
     /// Calculates amount of ETH to be distributed per account in order to make them
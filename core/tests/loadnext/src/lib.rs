@@ -3,6 +3,7 @@
 pub mod account;
 pub mod account_pool;
 pub mod all;
+pub mod batch_stats;
 pub mod command;
 pub mod config;
 pub mod constants;
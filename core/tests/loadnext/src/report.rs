@@ -123,6 +123,7 @@ pub enum TxActionType {
     Deposit,
     DeployContract,
     Execute(ExecutionType),
+    Transfer,
 }
 
 impl All for TxActionType {
@@ -133,6 +134,7 @@ impl All for TxActionType {
             TxActionType::DeployContract,
             TxActionType::Execute(ExecutionType::L2),
             TxActionType::Execute(ExecutionType::L1),
+            TxActionType::Transfer,
         ];
 
         ALL
@@ -147,6 +149,7 @@ impl From<TxType> for TxActionType {
             TxType::L2Execute => Self::Execute(ExecutionType::L2),
             TxType::L1Execute => Self::Execute(ExecutionType::L1),
             TxType::DeployContract => Self::DeployContract,
+            TxType::L2Transfer => Self::Transfer,
         }
     }
 }
@@ -3,6 +3,8 @@ use std::{
     time::Duration,
 };
 
+use serde::Serialize;
+
 use crate::report::ActionType;
 
 #[derive(Debug, Clone)]
@@ -102,6 +104,26 @@ impl TimeHistogram {
     }
 }
 
+/// Latency percentiles for a certain kind of action. Each value is the lower bound of the histogram window
+/// the percentile falls into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LatencyPercentiles {
+    pub p10_ms: u64,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+}
+
+impl LatencyPercentiles {
+    fn new(histogram: &TimeHistogram) -> Self {
+        let lower_bound_ms = |percentile| histogram.percentile(percentile).0.as_millis() as u64;
+        Self {
+            p10_ms: lower_bound_ms(10),
+            p50_ms: lower_bound_ms(50),
+            p90_ms: lower_bound_ms(90),
+        }
+    }
+}
+
 /// Collector for the execution time metrics.
 ///
 /// It builds a distribution histogram for each type of action, thus reported results are represented
@@ -131,18 +153,24 @@ impl MetricsCollector {
 
     pub fn report(&self) {
         tracing::info!("Action: [10 percentile, 50 percentile, 90 percentile]");
-        for (action, histogram) in &self.action_stats {
-            // Only report data that was actually gathered.
-            if !histogram.is_empty() {
-                tracing::info!(
-                    "{action:?}: [>{}ms >{}ms >{}ms]",
-                    histogram.percentile(10).0.as_millis(),
-                    histogram.percentile(50).0.as_millis(),
-                    histogram.percentile(90).0.as_millis(),
-                );
-            }
+        for (action, percentiles) in self.latencies() {
+            tracing::info!(
+                "{action}: [>{}ms >{}ms >{}ms]",
+                percentiles.p10_ms,
+                percentiles.p50_ms,
+                percentiles.p90_ms,
+            );
         }
     }
+
+    /// Returns latency percentiles for each kind of action that was actually performed.
+    pub fn latencies(&self) -> BTreeMap<String, LatencyPercentiles> {
+        self.action_stats
+            .iter()
+            .filter(|(_, histogram)| !histogram.is_empty())
+            .map(|(action, histogram)| (format!("{action:?}"), LatencyPercentiles::new(histogram)))
+            .collect()
+    }
 }
 
 #[cfg(test)]
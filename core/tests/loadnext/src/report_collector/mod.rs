@@ -1,9 +1,17 @@
-use std::time::{Duration, Instant};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
 
 use futures::{channel::mpsc::Receiver, StreamExt};
 use operation_results_collector::OperationResultsCollector;
+use serde::Serialize;
 
+pub use self::{
+    metrics_collector::LatencyPercentiles, operation_results_collector::ResultCollector,
+};
 use crate::{
+    batch_stats::BatchStats,
     report::{ActionType, Report, ReportLabel},
     report_collector::metrics_collector::MetricsCollector,
 };
@@ -18,6 +26,32 @@ pub enum LoadtestResult {
     TestFailed,
 }
 
+/// Machine-readable summary of the loadtest, which can be compared among runs to detect regressions.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadtestSummary {
+    /// Hex-encoded master seed of the test. Re-using it reproduces the sequence of operations.
+    pub seed: Option<String>,
+    pub duration_secs: f64,
+    /// TPS measured over the actual test duration.
+    pub tps: f64,
+    /// TPS measured over the requested test duration.
+    pub nominal_tps: f64,
+    pub txs: ResultCollector,
+    pub api_requests: ResultCollector,
+    pub subscriptions: ResultCollector,
+    pub latencies: BTreeMap<String, LatencyPercentiles>,
+    /// Statistics on L1 batches sealed during the test.
+    pub batches: Option<BatchStats>,
+}
+
+/// Outcome of the loadtest produced by the [`ReportCollector`].
+#[derive(Debug)]
+pub struct CollectedReport {
+    pub result: LoadtestResult,
+    /// Summary of the test; `None` if the test failed before initialization was completed.
+    pub summary: Option<LoadtestSummary>,
+}
+
 #[derive(Debug)]
 struct Collectors {
     start: Instant,
@@ -49,6 +83,22 @@ impl Collectors {
         self.operation_results.report(actual_duration);
     }
 
+    fn summary(&self) -> LoadtestSummary {
+        let actual_duration = self.start.elapsed();
+        let operation_results = &self.operation_results;
+        LoadtestSummary {
+            seed: None,
+            duration_secs: actual_duration.as_secs_f64(),
+            tps: operation_results.tps(actual_duration),
+            nominal_tps: operation_results.nominal_tps(),
+            txs: operation_results.tx_results.clone(),
+            api_requests: operation_results.api_requests_results.clone(),
+            subscriptions: operation_results.subscriptions_results.clone(),
+            latencies: self.metrics.latencies(),
+            batches: None,
+        }
+    }
+
     fn final_resolution(&self, expected_tx_count: Option<usize>) -> LoadtestResult {
         let is_tx_count_acceptable = expected_tx_count.map_or(true, |expected_count| {
             const MIN_ACCEPTABLE_DELTA: f64 = -10.0;
@@ -122,7 +172,7 @@ impl ReportCollector {
         }
     }
 
-    pub async fn run(mut self) -> LoadtestResult {
+    pub async fn run(mut self) -> CollectedReport {
         let mut collectors = None;
         let mut start = Instant::now();
 
@@ -178,10 +228,16 @@ impl ReportCollector {
         // Now we can output the statistics.
         if let Some(collectors) = collectors {
             collectors.report(self.prometheus_label);
-            collectors.final_resolution(self.expected_tx_count)
+            CollectedReport {
+                result: collectors.final_resolution(self.expected_tx_count),
+                summary: Some(collectors.summary()),
+            }
         } else {
             tracing::error!("Test failed before initialization was completed");
-            LoadtestResult::TestFailed
+            CollectedReport {
+                result: LoadtestResult::TestFailed,
+                summary: None,
+            }
         }
     }
 }
@@ -1,5 +1,7 @@
 use std::{fmt, time::Duration};
 
+use serde::Serialize;
+
 use crate::report::{ActionType, ReportLabel};
 
 /// Collector that analyzes the outcomes of the performed operations.
@@ -8,13 +10,13 @@ use crate::report::{ActionType, ReportLabel};
 #[derive(Debug, Default)]
 pub struct OperationResultsCollector {
     pub(super) tx_results: ResultCollector,
-    api_requests_results: ResultCollector,
-    subscriptions_results: ResultCollector,
+    pub(super) api_requests_results: ResultCollector,
+    pub(super) subscriptions_results: ResultCollector,
     loadtest_duration: Duration,
 }
 
-#[derive(Debug, Default)]
-pub(super) struct ResultCollector {
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ResultCollector {
     successes: u64,
     skipped: u64,
     failures: u64,
@@ -28,6 +28,18 @@ function parseSuggestedValues(suggestedValuesString: string) {
     return { lastL1BatchNumber, nonce, priorityFee };
 }
 
+// Runs a block reverter command in the dry-run mode and returns the confirmation token from its report.
+async function getConfirmationToken(command: string): Promise<string> {
+    const executedProcess = await utils.exec(
+        `cd $ZKSYNC_HOME && RUST_LOG=off cargo run --bin block_reverter --release -- ${command} --dry-run`
+    );
+    const match = executedProcess.stdout.match(/--confirm ([0-9a-f]+)/);
+    if (!match) {
+        throw new Error(`Dry run has failed: ${executedProcess.stdout}`);
+    }
+    return match[1];
+}
+
 async function killServerAndWaitForShutdown(tester: Tester) {
     await utils.exec('pkill -9 zksync_server');
     // Wait until it's really stopped.
@@ -160,13 +172,17 @@ describe('Block reverting test', function () {
         );
 
         console.log('Sending ETH transaction..');
+        const sendCommand = `send-eth-transaction --l1-batch-number ${lastL1BatchNumber} --nonce ${nonce} --priority-fee-per-gas ${priorityFee}`;
+        let token = await getConfirmationToken(sendCommand);
         await utils.spawn(
-            `cd $ZKSYNC_HOME && cargo run --bin block_reverter --release -- send-eth-transaction --l1-batch-number ${lastL1BatchNumber} --nonce ${nonce} --priority-fee-per-gas ${priorityFee}`
+            `cd $ZKSYNC_HOME && cargo run --bin block_reverter --release -- ${sendCommand} --confirm ${token}`
         );
 
         console.log('Rolling back DB..');
+        const rollbackCommand = `rollback-db --l1-batch-number ${lastL1BatchNumber} --rollback-postgres --rollback-tree --rollback-sk-cache`;
+        token = await getConfirmationToken(rollbackCommand);
         await utils.spawn(
-            `cd $ZKSYNC_HOME && cargo run --bin block_reverter --release -- rollback-db --l1-batch-number ${lastL1BatchNumber} --rollback-postgres --rollback-tree --rollback-sk-cache`
+            `cd $ZKSYNC_HOME && cargo run --bin block_reverter --release -- ${rollbackCommand} --confirm ${token}`
         );
 
         let blocksCommitted = await mainContract.getTotalBlocksCommitted();
@@ -107,6 +107,19 @@ export async function snapshots_creator() {
     await utils.spawn('cargo run  --release --bin snapshots_creator');
 }
 
+export async function chain_exporter() {
+    process.chdir(process.env.ZKSYNC_HOME ?? '.');
+    await utils.spawn('cargo run --release --bin chain_exporter');
+}
+
+export async function emergency_artifacts_exporter(fromL1Batch: string, toL1Batch: string, output?: string) {
+    process.chdir(process.env.ZKSYNC_HOME ?? '.');
+    const outputArg = output ? ` --output ${output}` : '';
+    await utils.spawn(
+        `cargo run --release --bin emergency_artifacts_exporter -- --from-l1-batch ${fromL1Batch} --to-l1-batch ${toL1Batch}${outputArg}`
+    );
+}
+
 export const command = new Command('run').description('run miscellaneous applications');
 
 command.command('test-accounts').description('print ethereum test accounts').action(testAccounts);
@@ -155,3 +168,8 @@ command
     });
 
 command.command('snapshots-creator').action(snapshots_creator);
+command.command('chain-exporter').action(chain_exporter);
+command
+    .command('emergency-artifacts-exporter <from_l1_batch> <to_l1_batch> [output]')
+    .description('export calldata for proving and executing committed L1 batches in the settlement layer emergency mode')
+    .action(emergency_artifacts_exporter);